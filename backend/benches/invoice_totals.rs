@@ -0,0 +1,35 @@
+//! Invoice money math, run on every invoice create and update
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use flashbill_api::domain::models::{CurrencyRounding, InvoiceTotals, LineInput, RoundingPolicy};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn lines(count: usize) -> Vec<LineInput> {
+    (0..count)
+        .map(|i| LineInput {
+            quantity: Decimal::ONE + Decimal::from(i % 7) * dec!(0.25),
+            unit_price: dec!(19.99) + Decimal::from(i % 13) * dec!(7.5),
+            tax_rate: [dec!(0), dec!(0.05), dec!(0.0725), dec!(0.2)][i % 4],
+        })
+        .collect()
+}
+
+fn bench_invoice_totals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("invoice_totals");
+    for count in [10, 100, 1000] {
+        let lines = lines(count);
+        for (name, rounding) in [("per_line", RoundingPolicy::PerLine), ("per_invoice", RoundingPolicy::PerInvoice)] {
+            group.bench_with_input(BenchmarkId::new(name, count), &lines, |b, lines| {
+                b.iter(|| InvoiceTotals::calculate(black_box(lines), dec!(25), false, rounding, CurrencyRounding::CENTS))
+            });
+        }
+        group.bench_with_input(BenchmarkId::new("tax_included", count), &lines, |b, lines| {
+            b.iter(|| InvoiceTotals::calculate(black_box(lines), dec!(25), true, RoundingPolicy::PerLine, CurrencyRounding::CENTS))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_invoice_totals);
+criterion_main!(benches);
@@ -0,0 +1,61 @@
+//! Invoice PDF rendering, behind the download, email and guest endpoints
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use flashbill_api::domain::services::{InvoiceItemPdf, PdfBranding, PdfService, PdfTaxLine, PdfTemplate, PdfWatermark};
+
+fn items(count: usize) -> Vec<InvoiceItemPdf> {
+    (0..count)
+        .map(|i| InvoiceItemPdf {
+            description: format!("Consulting services, week {}", i + 1),
+            quantity: 8.0,
+            unit_price: 125.0,
+            total: 1000.0,
+        })
+        .collect()
+}
+
+fn bench_invoice_pdf(c: &mut Criterion) {
+    let pdf = PdfService::new();
+    let mut group = c.benchmark_group("pdf");
+    for (template, count) in [(PdfTemplate::Classic, 5), (PdfTemplate::Classic, 50), (PdfTemplate::Modern, 50)] {
+        let items = items(count);
+        let subtotal = 1000.0 * count as f64;
+        let branding = PdfBranding { template, ..Default::default() };
+        let id = match template {
+            PdfTemplate::Classic => BenchmarkId::new("invoice", count),
+            _ => BenchmarkId::new(format!("invoice_{}", template.as_str()), count),
+        };
+        group.bench_with_input(id, &items, |b, items| {
+            b.iter(|| {
+                pdf.generate_invoice_pdf(
+                    "INV-2026-0042",
+                    Some("Bench Studio"),
+                    Some("1 Market Street, Springfield"),
+                    "Acme Corporation",
+                    Some("ap@acme.test"),
+                    Some("500 Industrial Way, Shelbyville"),
+                    "2026-01-15",
+                    "2026-02-14",
+                    black_box(items),
+                    subtotal,
+                    subtotal * 0.1,
+                    0.0,
+                    subtotal * 1.1,
+                    Some("Thank you for your business."),
+                    Some("Payment due within 30 days."),
+                    &[PdfTaxLine { label: "VAT".to_string(), amount: subtotal * 0.1 }],
+                    &[],
+                    None,
+                    None,
+                    Some(PdfWatermark::Draft),
+                    &branding,
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_invoice_pdf);
+criterion_main!(benches);
@@ -0,0 +1,129 @@
+//! Report SQL against a seeded database.
+//!
+//! Needs a migrated-or-empty Postgres in `BENCH_DATABASE_URL` (falls back to
+//! `DATABASE_URL`); skipped when neither is set. Seeds one user with a year of
+//! invoices and expenses, and deletes them again afterwards.
+
+use chrono::{Datelike, NaiveDate, Utc};
+use criterion::{criterion_group, criterion_main, Criterion};
+use flashbill_api::domain::repositories::report_repository::{ClientReportFilter, ReportRepository};
+use flashbill_api::infrastructure::repositories::ReportRepositoryImpl;
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const CLIENTS: i32 = 50;
+const INVOICES: i32 = 5_000;
+const EXPENSES: i32 = 1_000;
+
+async fn seed(db: &PgPool) -> Result<Uuid, sqlx::Error> {
+    let user_id = Uuid::new_v4();
+    let tag = user_id.simple().to_string();
+
+    sqlx::query("INSERT INTO users (id, email, password_hash) VALUES ($1, $2, 'bench')")
+        .bind(user_id)
+        .bind(format!("bench_{}@example.test", tag))
+        .execute(db)
+        .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO clients (user_id, name, email)
+        SELECT $1, 'Client ' || g, 'client' || g || '@example.test'
+        FROM generate_series(1, $2) g
+        "#,
+    )
+    .bind(user_id)
+    .bind(CLIENTS)
+    .execute(db)
+    .await?;
+
+    // A year of invoices spread over every status the reports distinguish
+    sqlx::query(
+        r#"
+        WITH c AS (SELECT id, ROW_NUMBER() OVER (ORDER BY id) - 1 AS n FROM clients WHERE user_id = $1)
+        INSERT INTO invoices (
+            user_id, client_id, invoice_number, status, issue_date, due_date, sent_at, paid_at,
+            subtotal, tax_amount, total_amount, amount_paid, items
+        )
+        SELECT $1, c.id, 'BENCH-' || $3 || '-' || g,
+               (ARRAY['draft', 'sent', 'paid', 'paid', 'partial', 'overdue'])[g % 6 + 1],
+               CURRENT_DATE - (g % 365),
+               CURRENT_DATE - (g % 365) + 30,
+               NOW() - make_interval(days => g % 365),
+               CASE WHEN g % 6 IN (2, 3) THEN NOW() - make_interval(days => g % 365 - 12) END,
+               1000 + g % 500, (1000 + g % 500) * 0.1, (1000 + g % 500) * 1.1,
+               CASE WHEN g % 6 IN (2, 3) THEN (1000 + g % 500) * 1.1 WHEN g % 6 = 4 THEN 500 ELSE 0 END,
+               '[]'::jsonb
+        FROM generate_series(1, $2) g
+        JOIN c ON c.n = g % $4
+        "#,
+    )
+    .bind(user_id)
+    .bind(INVOICES)
+    .bind(&tag)
+    .bind(CLIENTS as i64)
+    .execute(db)
+    .await?;
+
+    // The tax report nets reclaimable input tax from these
+    sqlx::query(
+        r#"
+        INSERT INTO expenses (user_id, amount, category, date_incurred)
+        SELECT $1, 50 + g % 200, (ARRAY['software', 'travel', 'office', 'marketing'])[g % 4 + 1], CURRENT_DATE - (g % 365)
+        FROM generate_series(1, $2) g
+        "#,
+    )
+    .bind(user_id)
+    .bind(EXPENSES)
+    .execute(db)
+    .await?;
+
+    sqlx::query("ANALYZE").execute(db).await?;
+    Ok(user_id)
+}
+
+fn bench_reports(c: &mut Criterion) {
+    let Some(url) = std::env::var("BENCH_DATABASE_URL").or_else(|_| std::env::var("DATABASE_URL")).ok() else {
+        eprintln!("Skipping report benchmarks: set BENCH_DATABASE_URL to a test database");
+        return;
+    };
+
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let db = rt.block_on(async {
+        let db = PgPoolOptions::new().max_connections(4).connect(&url).await.expect("connect to bench database");
+        sqlx::migrate!("./migrations").run(&db).await.expect("run migrations");
+        db
+    });
+    let user_id = rt.block_on(seed(&db)).expect("seed bench data");
+
+    let reports = ReportRepositoryImpl::new(db.clone());
+    let all_clients = ClientReportFilter::default();
+    let today = Utc::now().date_naive();
+    let year_start = NaiveDate::from_ymd_opt(today.year() - 1, today.month(), 1).unwrap();
+
+    let mut group = c.benchmark_group("reports");
+    group.bench_function("overview", |b| {
+        b.to_async(&rt).iter(|| async { reports.get_overview_stats(user_id).await.unwrap() })
+    });
+    group.bench_function("income_year", |b| {
+        b.to_async(&rt)
+            .iter(|| async { reports.get_income_report(user_id, year_start, today, &all_clients).await.unwrap() })
+    });
+    group.bench_function("tax_year", |b| {
+        b.to_async(&rt).iter(|| async { reports.get_tax_report(user_id, year_start, today).await.unwrap() })
+    });
+    group.bench_function("aging", |b| {
+        b.to_async(&rt).iter(|| async { reports.get_aging_report(user_id, &all_clients).await.unwrap() })
+    });
+    group.finish();
+
+    rt.block_on(async {
+        if let Err(e) = sqlx::query("DELETE FROM users WHERE id = $1").bind(user_id).execute(&db).await {
+            eprintln!("Failed to remove bench user {}: {}", user_id, e);
+        }
+    });
+}
+
+criterion_group!(benches, bench_reports);
+criterion_main!(benches);
@@ -0,0 +1,96 @@
+//! JSON encoding of the largest list responses: invoices and clients
+
+use chrono::{Duration, NaiveDate, Utc};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use flashbill_api::application::dto::invoice_dto::InvoiceSummaryDto;
+use flashbill_api::domain::models::{BillingContact, Client, InvoiceStatus};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+fn invoices(count: usize) -> Vec<InvoiceSummaryDto> {
+    let issued = NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+    (0..count)
+        .map(|i| InvoiceSummaryDto {
+            id: Uuid::new_v4(),
+            invoice_number: format!("INV-2026-{:05}", i),
+            status: [InvoiceStatus::Draft, InvoiceStatus::Sent, InvoiceStatus::Paid, InvoiceStatus::Overdue][i % 4].clone(),
+            client_name: format!("Client {}", i % 50),
+            client_email: Some(format!("client{}@example.test", i % 50)),
+            issue_date: issued + Duration::days((i % 365) as i64),
+            due_date: issued + Duration::days((i % 365) as i64 + 30),
+            total_amount: Decimal::new(125_050 + i as i64 * 100, 2),
+            balance_due: if i % 4 == 2 { Decimal::ZERO } else { Decimal::new(125_050 + i as i64 * 100, 2) },
+            days_until_due: 30 - (i % 60) as i32,
+            is_overdue: i % 4 == 3,
+            label: (i % 10 == 0).then(|| "Priority".to_string()),
+            created_at: Utc::now(),
+        })
+        .collect()
+}
+
+fn clients(count: usize) -> Vec<Client> {
+    let user_id = Uuid::new_v4();
+    (0..count)
+        .map(|i| Client {
+            id: Uuid::new_v4(),
+            user_id,
+            name: format!("Client {}", i),
+            email: Some(format!("client{}@example.test", i)),
+            phone: Some("+6281234567890".to_string()),
+            company_name: Some(format!("Client {} Ltd", i)),
+            billing_address: Some(serde_json::json!({
+                "street": "1 Market Street",
+                "city": "Springfield",
+                "postal_code": "12345",
+                "country": "US",
+            })),
+            payment_terms: 30,
+            tax_exempt: false,
+            tax_exempt_certificate: None,
+            notes: None,
+            total_invoiced: Decimal::from(15_000),
+            total_paid: Decimal::from(12_500),
+            average_payment_days: Some(24),
+            parent_client_id: None,
+            statement_opt_out: false,
+            billing_contacts: vec![BillingContact {
+                name: Some("Accounts".to_string()),
+                email: format!("ap{}@example.test", i),
+            }],
+            locale: None,
+            default_tax_id: None,
+            default_currency: None,
+            default_discount_percent: None,
+            hourly_rate: None,
+            vat_number: None,
+            vat_status: None,
+            vat_registered_name: None,
+            vat_checked_at: None,
+            archived_at: None,
+            deleted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+        .collect()
+}
+
+fn bench_list_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json");
+    for count in [100, 1000] {
+        group.throughput(Throughput::Elements(count as u64));
+
+        let invoices = invoices(count);
+        group.bench_with_input(BenchmarkId::new("invoice_list", count), &invoices, |b, invoices| {
+            b.iter(|| serde_json::to_vec(black_box(invoices)).unwrap())
+        });
+
+        let clients = clients(count);
+        group.bench_with_input(BenchmarkId::new("client_list", count), &clients, |b, clients| {
+            b.iter(|| serde_json::to_vec(black_box(clients)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_list_serialization);
+criterion_main!(benches);
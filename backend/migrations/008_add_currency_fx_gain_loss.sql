@@ -0,0 +1,39 @@
+-- Multi-currency invoices and realized FX gain/loss tracking
+
+-- Invoice currency and the exchange rate (base currency per 1 unit of invoice currency) at issuance
+ALTER TABLE invoices
+ADD COLUMN IF NOT EXISTS currency VARCHAR(3) DEFAULT 'USD';
+
+ALTER TABLE invoices
+ADD COLUMN IF NOT EXISTS exchange_rate DECIMAL(18,8) DEFAULT 1.0;
+
+UPDATE invoices
+SET currency = 'USD'
+WHERE currency IS NULL;
+
+UPDATE invoices
+SET exchange_rate = 1.0
+WHERE exchange_rate IS NULL;
+
+-- Realized gain/loss booked when a foreign-currency invoice is settled at a different rate
+CREATE TABLE fx_gain_loss_entries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    payment_id UUID REFERENCES payments(id) ON DELETE SET NULL,
+
+    currency VARCHAR(3) NOT NULL,
+    foreign_amount DECIMAL(15,2) NOT NULL,
+    issue_rate DECIMAL(18,8) NOT NULL,
+    settlement_rate DECIMAL(18,8) NOT NULL,
+    gain_loss_amount DECIMAL(15,2) NOT NULL,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE INDEX idx_fx_gain_loss_user ON fx_gain_loss_entries(user_id);
+CREATE INDEX idx_fx_gain_loss_invoice ON fx_gain_loss_entries(invoice_id);
+CREATE INDEX idx_fx_gain_loss_created ON fx_gain_loss_entries(created_at);
+
+COMMENT ON TABLE fx_gain_loss_entries IS 'Realized foreign exchange gains (positive) and losses (negative) in the base currency';
+COMMENT ON COLUMN fx_gain_loss_entries.gain_loss_amount IS 'foreign_amount * (settlement_rate - issue_rate), in base currency';
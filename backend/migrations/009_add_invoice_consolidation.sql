@@ -0,0 +1,9 @@
+-- Consolidated invoices: several draft invoices for one client combined into one
+
+-- Originals are cancelled and point at the invoice that replaced them
+ALTER TABLE invoices
+ADD COLUMN IF NOT EXISTS consolidated_into_id UUID REFERENCES invoices(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_consolidated_into ON invoices(consolidated_into_id);
+
+COMMENT ON COLUMN invoices.consolidated_into_id IS 'Consolidated invoice that replaced this (cancelled) invoice';
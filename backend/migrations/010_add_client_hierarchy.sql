@@ -0,0 +1,11 @@
+-- Parent/child client relationships (subsidiaries rolled up into a parent)
+
+ALTER TABLE clients
+ADD COLUMN IF NOT EXISTS parent_client_id UUID REFERENCES clients(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_clients_parent ON clients(parent_client_id);
+
+ALTER TABLE clients
+ADD CONSTRAINT chk_clients_parent_not_self CHECK (parent_client_id IS NULL OR parent_client_id <> id);
+
+COMMENT ON COLUMN clients.parent_client_id IS 'Parent company; invoices roll up into the parent statement';
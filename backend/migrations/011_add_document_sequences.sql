@@ -0,0 +1,34 @@
+-- Per-user, per-document-type numbering sequences (invoices, quotes, credit notes, pro-formas)
+
+-- Configurable number format per document type
+CREATE TABLE document_number_formats (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    doc_type VARCHAR(20) NOT NULL,
+    prefix VARCHAR(20) NOT NULL,
+    padding INTEGER NOT NULL DEFAULT 4 CHECK (padding BETWEEN 1 AND 10),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (user_id, doc_type)
+);
+
+-- Last issued number, restarting every year
+CREATE TABLE document_sequences (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    doc_type VARCHAR(20) NOT NULL,
+    year INTEGER NOT NULL,
+    last_value BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    PRIMARY KEY (user_id, doc_type, year)
+);
+
+-- Continue invoice numbering from existing invoices
+INSERT INTO document_sequences (user_id, doc_type, year, last_value)
+SELECT user_id, 'invoice', EXTRACT(YEAR FROM created_at)::int, COUNT(*)
+FROM invoices
+GROUP BY user_id, EXTRACT(YEAR FROM created_at)::int
+ON CONFLICT DO NOTHING;
+
+-- Numbers are unique per user, not globally
+ALTER TABLE invoices DROP CONSTRAINT IF EXISTS invoices_invoice_number_key;
+ALTER TABLE invoices ADD CONSTRAINT invoices_user_invoice_number_key UNIQUE (user_id, invoice_number);
+
+COMMENT ON TABLE document_sequences IS 'Per-user numbering sequence keyed by (user, doc_type, year)';
@@ -0,0 +1,20 @@
+-- Failures from background automation that need the user's attention
+
+CREATE TABLE automation_issues (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(40) NOT NULL,
+    invoice_id UUID REFERENCES invoices(id) ON DELETE SET NULL,
+    summary VARCHAR(255) NOT NULL,
+    detail TEXT,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    digested_at TIMESTAMP WITH TIME ZONE,
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX idx_automation_issues_user ON automation_issues(user_id, created_at DESC);
+CREATE INDEX idx_automation_issues_undigested ON automation_issues(user_id) WHERE digested_at IS NULL AND resolved_at IS NULL;
+
+COMMENT ON TABLE automation_issues IS 'Failed background automation (email bounces, skipped recurring invoices, dead-lettered webhooks) surfaced in the daily needs-attention digest';
+COMMENT ON COLUMN automation_issues.digested_at IS 'When the issue was included in a needs-attention email';
@@ -0,0 +1,20 @@
+-- Automatic monthly client statements
+
+ALTER TABLE clients
+ADD COLUMN IF NOT EXISTS statement_opt_out BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN clients.statement_opt_out IS 'Client does not receive automatic monthly statements';
+
+-- One row per client and statement month, so a statement is never sent twice
+CREATE TABLE IF NOT EXISTS statement_deliveries (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    period_end DATE NOT NULL,
+    sent_to VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (client_id, period_start)
+);
+
+CREATE INDEX IF NOT EXISTS idx_statement_deliveries_user ON statement_deliveries(user_id, period_start);
@@ -0,0 +1,23 @@
+-- Annual budgets: planned income and expenses by month and category
+
+CREATE TABLE IF NOT EXISTS budgets (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    year INTEGER NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, year)
+);
+
+CREATE TABLE IF NOT EXISTS budget_lines (
+    id UUID PRIMARY KEY,
+    budget_id UUID NOT NULL REFERENCES budgets(id) ON DELETE CASCADE,
+    kind VARCHAR(10) NOT NULL CHECK (kind IN ('income', 'expense')),
+    -- Expense category; NULL for income lines
+    category VARCHAR(50),
+    month SMALLINT NOT NULL CHECK (month BETWEEN 1 AND 12),
+    amount DECIMAL(15,2) NOT NULL CHECK (amount >= 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_lines_budget ON budget_lines(budget_id);
@@ -0,0 +1,22 @@
+-- Gateway payouts: batches of payments, minus fees, transferred to the bank
+CREATE TABLE IF NOT EXISTS payouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    gateway VARCHAR(50) NOT NULL,
+    gateway_payout_id VARCHAR(255) NOT NULL,
+    amount DECIMAL(15,2) NOT NULL,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    arrival_date DATE,
+    unmatched_references TEXT[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, gateway, gateway_payout_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payouts_user_arrival ON payouts(user_id, arrival_date);
+
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS payout_id UUID REFERENCES payouts(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_payments_payout ON payments(payout_id);
+CREATE INDEX IF NOT EXISTS idx_payments_gateway_payment ON payments(gateway, gateway_payment_id);
@@ -0,0 +1,15 @@
+-- Additional businesses under one login.
+-- Each business is backed by its own users row (the business account), so every
+-- user-scoped table -- clients, invoices, expenses, document sequences, tax and
+-- invoice settings -- is kept separate per business. The login account itself is
+-- the primary business and has no row here.
+CREATE TABLE IF NOT EXISTS businesses (
+    id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    owner_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (id <> owner_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_businesses_owner ON businesses(owner_id);
@@ -0,0 +1,12 @@
+-- Client lifecycle: archived clients are hidden from pickers but keep working;
+-- deleted clients go to the trash and can be restored with their draft invoices
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS archived_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+
+-- Drafts cancelled because their client was trashed; matches the client's deleted_at
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS trashed_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_clients_user_deleted ON clients(user_id, deleted_at);
+
+COMMENT ON COLUMN clients.deleted_at IS 'Soft delete; clients with outstanding invoices cannot be deleted';
+COMMENT ON COLUMN invoices.trashed_at IS 'Set on drafts cancelled by a client deletion so a restore can bring them back';
@@ -0,0 +1,10 @@
+-- Corrected invoices: an issued invoice with an error is not edited in place. A new
+-- invoice is issued that references it, and the original is marked 'superseded'.
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS supersedes_id UUID REFERENCES invoices(id) ON DELETE SET NULL;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS superseded_by_id UUID REFERENCES invoices(id) ON DELETE SET NULL;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS correction_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_supersedes ON invoices(supersedes_id);
+
+COMMENT ON COLUMN invoices.supersedes_id IS 'Original invoice this corrected invoice replaces';
+COMMENT ON COLUMN invoices.superseded_by_id IS 'Corrected invoice that replaced this (superseded) invoice';
@@ -0,0 +1,17 @@
+-- Read-only access for an external accountant.
+-- A grant is bound to one business account. The accountant exchanges the access
+-- code for a short-lived token limited to reports, exports and invoice PDFs. The
+-- grant stops working once it expires or the owner revokes it.
+CREATE TABLE IF NOT EXISTS accountant_access (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    name VARCHAR(255),
+    access_code VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_accountant_access_user ON accountant_access(user_id);
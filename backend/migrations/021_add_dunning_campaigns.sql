@@ -0,0 +1,40 @@
+-- Dunning campaigns: outstanding-balance emails to a segment of clients.
+-- The audience is snapshotted when the campaign is created; each recipient gets a
+-- send_after slot spaced by the campaign's throttle interval. Balances are
+-- re-checked at send time, so clients who paid in the meantime are skipped.
+CREATE TABLE IF NOT EXISTS dunning_campaigns (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    template VARCHAR(20) NOT NULL,
+    subject_template TEXT NOT NULL,
+    body_template TEXT NOT NULL,
+    segment JSONB NOT NULL DEFAULT '{}'::jsonb,
+    send_interval_seconds INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'scheduled',
+    start_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dunning_campaigns_user ON dunning_campaigns(user_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS dunning_campaign_recipients (
+    id UUID PRIMARY KEY,
+    campaign_id UUID NOT NULL REFERENCES dunning_campaigns(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    client_name VARCHAR(255) NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    -- Balance when the campaign was created, and when the email went out
+    targeted_balance DECIMAL(15,2) NOT NULL,
+    sent_balance DECIMAL(15,2),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    error TEXT,
+    send_after TIMESTAMPTZ NOT NULL,
+    sent_at TIMESTAMPTZ,
+    UNIQUE (campaign_id, client_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_dunning_recipients_due
+    ON dunning_campaign_recipients(send_after) WHERE status = 'pending';
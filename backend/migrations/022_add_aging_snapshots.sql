@@ -0,0 +1,13 @@
+-- Nightly receivables aging per user, so aging can be trended over time
+CREATE TABLE IF NOT EXISTS aging_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    snapshot_date DATE NOT NULL,
+    current_amount DECIMAL(15,2) NOT NULL DEFAULT 0,
+    one_to_thirty_days DECIMAL(15,2) NOT NULL DEFAULT 0,
+    thirty_one_to_sixty_days DECIMAL(15,2) NOT NULL DEFAULT 0,
+    sixty_one_to_ninety_days DECIMAL(15,2) NOT NULL DEFAULT 0,
+    over_ninety_days DECIMAL(15,2) NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, snapshot_date)
+);
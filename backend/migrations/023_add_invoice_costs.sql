@@ -0,0 +1,23 @@
+-- Direct costs linked to an invoice, for per-invoice and per-client margins.
+-- A cost is either an existing expense (its amount is read live from the expense)
+-- or a block of time valued at an hourly cost. An expense counts against at most
+-- one invoice.
+CREATE TABLE IF NOT EXISTS invoice_costs (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('expense', 'time')),
+    expense_id UUID UNIQUE REFERENCES expenses(id) ON DELETE CASCADE,
+    description TEXT,
+    hours DECIMAL(10,2),
+    hourly_cost DECIMAL(15,2),
+    work_date DATE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (
+        (kind = 'expense' AND expense_id IS NOT NULL)
+        OR (kind = 'time' AND hours IS NOT NULL AND hourly_cost IS NOT NULL)
+    )
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_costs_invoice ON invoice_costs(invoice_id);
+CREATE INDEX IF NOT EXISTS idx_invoice_costs_user ON invoice_costs(user_id);
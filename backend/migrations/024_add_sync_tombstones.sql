@@ -0,0 +1,39 @@
+-- Incremental sync for offline clients: changes are read from each table's
+-- updated_at, and hard deletions leave a tombstone so clients can drop their copy.
+-- Tombstones are pruned after the cursor retention period. There is no foreign key
+-- to users: deleting an account cascades into these triggers.
+CREATE TABLE IF NOT EXISTS sync_tombstones (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL,
+    entity_type VARCHAR(20) NOT NULL CHECK (entity_type IN ('invoice', 'client', 'payment')),
+    entity_id UUID NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_tombstones_user_deleted ON sync_tombstones(user_id, deleted_at);
+
+CREATE OR REPLACE FUNCTION record_sync_tombstone()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO sync_tombstones (user_id, entity_type, entity_id)
+    VALUES (OLD.user_id, TG_ARGV[0], OLD.id);
+    RETURN OLD;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS sync_tombstone_invoices ON invoices;
+CREATE TRIGGER sync_tombstone_invoices AFTER DELETE ON invoices
+    FOR EACH ROW EXECUTE FUNCTION record_sync_tombstone('invoice');
+
+DROP TRIGGER IF EXISTS sync_tombstone_clients ON clients;
+CREATE TRIGGER sync_tombstone_clients AFTER DELETE ON clients
+    FOR EACH ROW EXECUTE FUNCTION record_sync_tombstone('client');
+
+DROP TRIGGER IF EXISTS sync_tombstone_payments ON payments;
+CREATE TRIGGER sync_tombstone_payments AFTER DELETE ON payments
+    FOR EACH ROW EXECUTE FUNCTION record_sync_tombstone('payment');
+
+-- The change feed walks each table by (user_id, updated_at)
+CREATE INDEX IF NOT EXISTS idx_invoices_user_updated ON invoices(user_id, updated_at);
+CREATE INDEX IF NOT EXISTS idx_clients_user_updated ON clients(user_id, updated_at);
+CREATE INDEX IF NOT EXISTS idx_payments_user_updated ON payments(user_id, updated_at);
@@ -0,0 +1,6 @@
+-- Links a manual resend to the failed send attempt it retries
+ALTER TABLE invoice_notifications
+    ADD COLUMN IF NOT EXISTS resent_from_id UUID REFERENCES invoice_notifications(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_invoice_notifications_channel
+    ON invoice_notifications(invoice_id, channel, created_at DESC);
@@ -0,0 +1,20 @@
+-- User-defined pipeline labels ("awaiting PO", "in review") layered on top of the
+-- core invoice status. A label can be limited to some core statuses; once an
+-- invoice moves past them the label no longer applies.
+CREATE TABLE IF NOT EXISTS invoice_labels (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    color VARCHAR(7),
+    statuses TEXT[] NOT NULL DEFAULT '{}',
+    show_on_pdf BOOLEAN NOT NULL DEFAULT FALSE,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_invoice_labels_user_name ON invoice_labels(user_id, LOWER(name));
+
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS label_id UUID REFERENCES invoice_labels(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_label ON invoices(label_id) WHERE label_id IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Input tax (VAT/GST paid on purchases), reclaimable against output tax on invoices.
+-- tax_rate is a fraction (0.21 for 21%); tax_amount is the tax portion of the gross amount.
+ALTER TABLE expenses
+    ADD COLUMN IF NOT EXISTS tax_rate DECIMAL(6,4),
+    ADD COLUMN IF NOT EXISTS tax_amount DECIMAL(15,2) NOT NULL DEFAULT 0;
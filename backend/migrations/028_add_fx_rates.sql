@@ -0,0 +1,26 @@
+-- Exchange rate history: daily provider rates (shared) and per-user manual overrides.
+-- rate is base currency per 1 unit of currency, matching invoices.exchange_rate.
+CREATE TABLE IF NOT EXISTS fx_rates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+
+    base_currency VARCHAR(3) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    rate DECIMAL(18,8) NOT NULL CHECK (rate > 0),
+    rate_date DATE NOT NULL,
+    source VARCHAR(30) NOT NULL,
+
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+-- One provider rate per pair and day, one override per user, pair and day
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fx_rates_provider
+    ON fx_rates(source, base_currency, currency, rate_date) WHERE user_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_fx_rates_override
+    ON fx_rates(user_id, base_currency, currency, rate_date) WHERE user_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_fx_rates_lookup
+    ON fx_rates(base_currency, currency, rate_date DESC);
+
+CREATE TRIGGER update_fx_rates_updated_at BEFORE UPDATE ON fx_rates
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,19 @@
+-- Signatures appended to invoice and reminder emails. The row without a member_id
+-- is the business-wide default; a row with member_id is that member's override
+-- within the business. Members are logins; today that is only the owner.
+CREATE TABLE IF NOT EXISTS email_signatures (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    member_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100),
+    title VARCHAR(100),
+    phone VARCHAR(50),
+    links JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_signatures_default
+    ON email_signatures(user_id) WHERE member_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email_signatures_member
+    ON email_signatures(user_id, member_id) WHERE member_id IS NOT NULL;
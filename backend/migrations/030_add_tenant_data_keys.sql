@@ -0,0 +1,14 @@
+-- Per-tenant data keys for uploaded file encryption. Keys are only stored
+-- wrapped (encrypted) under the server's master key named by master_key_id.
+-- Each tenant has one active key; retired keys stay to decrypt older files.
+CREATE TABLE IF NOT EXISTS tenant_data_keys (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    master_key_id VARCHAR(50) NOT NULL,
+    wrapped_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retired_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tenant_data_keys_active
+    ON tenant_data_keys(user_id) WHERE retired_at IS NULL;
@@ -0,0 +1,29 @@
+-- Inbound forwarding addresses. Mail sent to bills+{token}@<inbound domain> is
+-- posted by the mail provider to /inbound/u/{token}.
+CREATE TABLE IF NOT EXISTS inbound_addresses (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Client details parsed from forwarded emails and vCards, waiting in the
+-- notification inbox. client_id is the existing client an import would update,
+-- and after confirmation the client that was created or updated.
+CREATE TABLE IF NOT EXISTS client_imports (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    source VARCHAR(20) NOT NULL,
+    sender VARCHAR(255) NOT NULL,
+    subject VARCHAR(255),
+    name VARCHAR(255) NOT NULL,
+    email VARCHAR(255),
+    phone VARCHAR(50),
+    company_name VARCHAR(255),
+    billing_address JSONB,
+    client_id UUID REFERENCES clients(id) ON DELETE SET NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_imports_user ON client_imports(user_id, created_at DESC);
@@ -0,0 +1,12 @@
+-- Offer expiry: an invoice can be valid only until a date ("offer valid 14 days").
+-- A follow-up goes out shortly before expires_at; once it passes, an unpaid invoice
+-- is marked 'expired' and can no longer be paid through its guest link.
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS expires_at DATE;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS expiry_reminder_sent_at TIMESTAMPTZ;
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS expired_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_expires_at ON invoices(expires_at)
+    WHERE expires_at IS NOT NULL AND status IN ('sent', 'viewed', 'overdue');
+
+COMMENT ON COLUMN invoices.expires_at IS 'Last day the offer can be accepted or paid';
+COMMENT ON COLUMN invoices.expired_at IS 'When the scheduler marked the invoice expired';
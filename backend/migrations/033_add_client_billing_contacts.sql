@@ -0,0 +1,3 @@
+-- Secondary billing contacts, in the order they're tried when an invoice email
+-- to the client's primary address hard-bounces: [{"name": ..., "email": ...}]
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS billing_contacts JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,10 @@
+-- Background reminders: sent invoices past their due date are flipped to 'overdue'
+-- and clients are reminded on the days past due listed in the user's notification
+-- settings. reminder_sent_count/last_reminder_sent track the overdue reminders;
+-- unviewed_reminder_sent_at marks the one-off nudge for a sent invoice nobody opened.
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS unviewed_reminder_sent_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_open_due_date ON invoices(due_date)
+    WHERE status IN ('sent', 'viewed', 'partial', 'overdue');
+
+COMMENT ON COLUMN invoices.unviewed_reminder_sent_at IS 'When the client was nudged about a sent invoice they had not opened';
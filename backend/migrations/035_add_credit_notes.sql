@@ -0,0 +1,31 @@
+-- Credit notes issued against an invoice for returns or over-billing.
+-- applied_amount is the part that settled the invoice's open balance; the rest
+-- of total_amount is owed back to the client.
+CREATE TABLE IF NOT EXISTS credit_notes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    credit_note_number VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'issued',
+    reason TEXT,
+    issue_date DATE NOT NULL,
+    items JSONB NOT NULL DEFAULT '[]',
+    subtotal DECIMAL(15,2) NOT NULL,
+    tax_amount DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    discount_amount DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    total_amount DECIMAL(15,2) NOT NULL,
+    applied_amount DECIMAL(15,2) NOT NULL DEFAULT 0.00,
+    currency VARCHAR(3) NOT NULL DEFAULT 'USD',
+    voided_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, credit_note_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_credit_notes_user ON credit_notes(user_id, issue_date DESC);
+CREATE INDEX IF NOT EXISTS idx_credit_notes_invoice ON credit_notes(invoice_id);
+
+-- Sum of issued (non-void) credit notes. Applied credit is also counted in
+-- amount_paid so balances settle; reports net it back out of income and totals.
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS credited_amount DECIMAL(15,2) NOT NULL DEFAULT 0.00;
@@ -0,0 +1,3 @@
+-- Whether numbering restarts every year (INV-2026-0001) or runs continuously
+-- (INV-0001). Continuous sequences are kept in document_sequences under year 0.
+ALTER TABLE document_number_formats ADD COLUMN IF NOT EXISTS yearly_reset BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,16 @@
+-- Signed guest links. The token itself carries the invoice, token id and expiry
+-- under an HMAC; this table records which ids were issued and whether they were
+-- revoked. invoices.guest_payment_token holds the invoice's current link.
+CREATE TABLE IF NOT EXISTS guest_tokens (
+    id UUID PRIMARY KEY,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_guest_tokens_invoice ON guest_tokens(invoice_id) WHERE revoked_at IS NULL;
+
+-- Unsigned guest_{invoice_id}_{n} links can be guessed from the invoice id; drop
+-- them. Owners issue new links with POST /invoices/{id}/guest-link.
+UPDATE invoices SET guest_payment_token = NULL WHERE guest_payment_token IS NOT NULL;
@@ -0,0 +1,19 @@
+-- Files attached to an invoice or an expense: contracts, receipts, timesheets.
+-- The file lives in the owner's upload directory as file_name; file_size counts
+-- toward the owner's attachment storage limit.
+CREATE TABLE IF NOT EXISTS attachments (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invoice_id UUID REFERENCES invoices(id) ON DELETE CASCADE,
+    expense_id UUID REFERENCES expenses(id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    original_name VARCHAR(255) NOT NULL,
+    mime_type VARCHAR(255) NOT NULL,
+    file_size BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((invoice_id IS NULL) <> (expense_id IS NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_user ON attachments(user_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_invoice ON attachments(invoice_id) WHERE invoice_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_attachments_expense ON attachments(expense_id) WHERE expense_id IS NOT NULL;
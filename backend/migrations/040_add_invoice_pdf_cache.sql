@@ -0,0 +1,4 @@
+-- pdf_url points at the last rendered PDF, stored with the owner's files.
+-- pdf_fingerprint digests what it was rendered from; the stored copy is only
+-- served while the invoice still renders to the same fingerprint.
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS pdf_fingerprint VARCHAR(64);
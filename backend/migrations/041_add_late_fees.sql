@@ -0,0 +1,30 @@
+-- Late fee rules, one per user: a flat amount or a percentage of the open
+-- balance, charged once the grace period after the due date has passed and
+-- optionally again every week or month, up to max_applications times.
+CREATE TABLE IF NOT EXISTS late_fee_policies (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    fee_type VARCHAR(20) NOT NULL CHECK (fee_type IN ('flat', 'percentage')),
+    amount NUMERIC(15,2) NOT NULL CHECK (amount > 0),
+    grace_days INTEGER NOT NULL DEFAULT 0 CHECK (grace_days >= 0),
+    recurrence VARCHAR(20) NOT NULL DEFAULT 'once' CHECK (recurrence IN ('once', 'weekly', 'monthly')),
+    max_applications INTEGER CHECK (max_applications > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Each fee added to an invoice. sequence numbers the fees per invoice, so a fee
+-- can't be charged twice by overlapping runs.
+CREATE TABLE IF NOT EXISTS invoice_late_fees (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    item_id UUID NOT NULL,
+    sequence INTEGER NOT NULL,
+    amount NUMERIC(15,2) NOT NULL,
+    days_overdue INTEGER NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (invoice_id, sequence)
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_late_fees_user ON invoice_late_fees(user_id);
@@ -0,0 +1,6 @@
+-- audit_logs.user_id is the account the change was made in; actor_id is the login
+-- that made it (differs for business members), NULL for background jobs.
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS actor_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_audit_user_created ON audit_logs(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_audit_actor ON audit_logs(actor_id);
@@ -0,0 +1,120 @@
+-- Outbound webhooks: endpoints users register, an outbox of events written by
+-- triggers (so every path that creates, sends or settles a record is covered)
+-- and one delivery per endpoint and event, retried with backoff.
+CREATE TABLE IF NOT EXISTS webhook_endpoints (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret VARCHAR(100) NOT NULL,
+    events TEXT[] NOT NULL,
+    description VARCHAR(255),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_endpoints_user ON webhook_endpoints(user_id);
+
+CREATE TABLE IF NOT EXISTS webhook_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    entity_id UUID NOT NULL,
+    data JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    dispatched_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_events_pending ON webhook_events(created_at) WHERE dispatched_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY,
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES webhook_events(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ,
+    last_status_code INTEGER,
+    last_error TEXT,
+    delivered_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (endpoint_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_endpoint ON webhook_deliveries(endpoint_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
+
+-- Only users with an enabled endpoint for the event get an outbox row
+CREATE OR REPLACE FUNCTION queue_webhook_event(p_user_id UUID, p_event VARCHAR, p_entity_id UUID, p_data JSONB)
+RETURNS VOID AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM webhook_endpoints
+        WHERE user_id = p_user_id AND enabled AND p_event = ANY(events)
+    ) THEN
+        INSERT INTO webhook_events (user_id, event_type, entity_id, data)
+        VALUES (p_user_id, p_event, p_entity_id, p_data);
+    END IF;
+END;
+$$ language 'plpgsql';
+
+CREATE OR REPLACE FUNCTION invoice_webhook_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        PERFORM queue_webhook_event(NEW.user_id, 'invoice.created', NEW.id, to_jsonb(NEW));
+        IF NEW.sent_at IS NOT NULL THEN
+            PERFORM queue_webhook_event(NEW.user_id, 'invoice.sent', NEW.id, to_jsonb(NEW));
+        END IF;
+        IF NEW.status = 'paid' THEN
+            PERFORM queue_webhook_event(NEW.user_id, 'invoice.paid', NEW.id, to_jsonb(NEW));
+        END IF;
+        RETURN NEW;
+    END IF;
+
+    -- Every send (and resend) stamps sent_at
+    IF NEW.sent_at IS NOT NULL AND NEW.sent_at IS DISTINCT FROM OLD.sent_at THEN
+        PERFORM queue_webhook_event(NEW.user_id, 'invoice.sent', NEW.id, to_jsonb(NEW));
+    END IF;
+    IF NEW.viewed_at IS NOT NULL AND OLD.viewed_at IS NULL THEN
+        PERFORM queue_webhook_event(NEW.user_id, 'invoice.viewed', NEW.id, to_jsonb(NEW));
+    END IF;
+    IF NEW.status = 'paid' AND OLD.status IS DISTINCT FROM 'paid' THEN
+        PERFORM queue_webhook_event(NEW.user_id, 'invoice.paid', NEW.id, to_jsonb(NEW));
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS webhook_events_invoices ON invoices;
+CREATE TRIGGER webhook_events_invoices AFTER INSERT OR UPDATE ON invoices
+    FOR EACH ROW EXECUTE FUNCTION invoice_webhook_events();
+
+CREATE OR REPLACE FUNCTION payment_webhook_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status = 'refunded' THEN
+        PERFORM queue_webhook_event(NEW.user_id, 'payment.refunded', NEW.id, to_jsonb(NEW));
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS webhook_events_payments ON payments;
+CREATE TRIGGER webhook_events_payments AFTER INSERT ON payments
+    FOR EACH ROW EXECUTE FUNCTION payment_webhook_events();
+
+CREATE OR REPLACE FUNCTION client_webhook_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM queue_webhook_event(NEW.user_id, 'client.created', NEW.id, to_jsonb(NEW));
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS webhook_events_clients ON clients;
+CREATE TRIGGER webhook_events_clients AFTER INSERT ON clients
+    FOR EACH ROW EXECUTE FUNCTION client_webhook_events();
@@ -0,0 +1,26 @@
+-- Background CSV client imports. `rows` holds the validated rows still to be
+-- created and is cleared when the job finishes; `processed_rows` of them are done.
+CREATE TABLE IF NOT EXISTS client_import_jobs (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    file_name VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'queued' CHECK (status IN ('queued', 'running', 'completed', 'failed')),
+    rows JSONB,
+    total_rows INTEGER NOT NULL,
+    processed_rows INTEGER NOT NULL DEFAULT 0,
+    created_count INTEGER NOT NULL DEFAULT 0,
+    skipped_count INTEGER NOT NULL DEFAULT 0,
+    failed_count INTEGER NOT NULL DEFAULT 0,
+    -- Rows rejected when the file was validated
+    errors JSONB NOT NULL DEFAULT '[]',
+    -- Rows skipped because a client with the same email exists
+    duplicates JSONB NOT NULL DEFAULT '[]',
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_import_jobs_user ON client_import_jobs(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_client_import_jobs_pending ON client_import_jobs(created_at) WHERE status IN ('queued', 'running');
@@ -0,0 +1,6 @@
+-- List endpoints page newest first by (created_at, id), by page number or by cursor.
+-- These let a page start from its cursor without reading the rows before it.
+CREATE INDEX IF NOT EXISTS idx_invoices_user_created_id ON invoices(user_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_clients_user_created_id ON clients(user_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_payments_user_created_id ON payments(user_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_expenses_user_created_id ON expenses(user_id, created_at DESC, id DESC);
@@ -0,0 +1,30 @@
+-- Full-text search (GET /api/v1/search). The 'simple' configuration keeps words as
+-- written rather than stemming them for one language, so names, invoice numbers and
+-- text in any language match as typed. Weights rank identifying fields above notes.
+
+-- Punctuation splits words, the way search queries are split: INV-2026-0042 is
+-- indexed as inv, 2026 and 0042, and ana@acme.co as ana, acme and co
+CREATE OR REPLACE FUNCTION search_words(value TEXT) RETURNS TEXT
+    LANGUAGE sql IMMUTABLE PARALLEL SAFE
+    AS $$ SELECT regexp_replace(coalesce(value, ''), '[[:punct:][:space:]]+', ' ', 'g') $$;
+
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', search_words(invoice_number)), 'A') ||
+    setweight(to_tsvector('simple', search_words(jsonb_path_query_array(items, '$[*].description')::text)), 'B') ||
+    setweight(to_tsvector('simple', search_words(notes) || ' ' || search_words(terms)), 'C')
+) STORED;
+
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', search_words(name) || ' ' || search_words(company_name)), 'A') ||
+    setweight(to_tsvector('simple', search_words(email)), 'B') ||
+    setweight(to_tsvector('simple', search_words(notes)), 'C')
+) STORED;
+
+ALTER TABLE expenses ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('simple', search_words(vendor)), 'A') ||
+    setweight(to_tsvector('simple', search_words(description)), 'B')
+) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_search ON invoices USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_clients_search ON clients USING GIN (search_vector);
+CREATE INDEX IF NOT EXISTS idx_expenses_search ON expenses USING GIN (search_vector);
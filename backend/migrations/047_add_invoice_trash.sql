@@ -0,0 +1,7 @@
+-- Invoice trash: deleting a draft moves it to the trash, where it can be restored
+-- until the purge job removes it for good along with clients trashed as long ago
+ALTER TABLE invoices ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_invoices_user_deleted ON invoices(user_id, deleted_at) WHERE deleted_at IS NOT NULL;
+
+COMMENT ON COLUMN invoices.deleted_at IS 'Soft delete; only drafts can be deleted, and they are purged after TRASH_RETENTION_DAYS';
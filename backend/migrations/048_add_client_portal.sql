@@ -0,0 +1,28 @@
+-- Client portal: buyers sign in with their email to see the invoices of every business
+-- that bills them. An account is created the first time a sign-in link sent to the
+-- email is used, which proves the buyer owns it; a password can be set afterwards.
+CREATE TABLE IF NOT EXISTS client_accounts (
+    id UUID PRIMARY KEY,
+    email VARCHAR(255) NOT NULL,
+    password_hash VARCHAR(255),
+    last_sign_in_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_client_accounts_email ON client_accounts(LOWER(email));
+
+-- One-time sign-in links; only a hash of the token is kept
+CREATE TABLE IF NOT EXISTS client_sign_in_links (
+    id UUID PRIMARY KEY,
+    email VARCHAR(255) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_sign_in_links_email ON client_sign_in_links(LOWER(email), created_at);
+
+-- Portal accounts find their invoices through the client records with their email
+CREATE INDEX IF NOT EXISTS idx_clients_email_lower ON clients(LOWER(email)) WHERE deleted_at IS NULL;
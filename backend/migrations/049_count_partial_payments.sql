@@ -0,0 +1,5 @@
+-- partial_payment_count counts each payment that leaves a balance. It's kept
+-- where payments are recorded; the trigger only counted the first one, and
+-- also fired when a credit note left an invoice part-settled.
+DROP TRIGGER IF EXISTS trigger_update_partial_count ON invoices;
+DROP FUNCTION IF EXISTS update_partial_count();
@@ -0,0 +1,11 @@
+-- Bank transfer and ACH payments stay pending until the seller confirms the money
+-- arrived, recording the bank's reference and the day it was received
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS bank_reference VARCHAR(100);
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS received_on DATE;
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS reconciled_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_payments_pending_bank ON payments(user_id, created_at)
+    WHERE status = 'pending' AND payment_method IN ('bank_transfer', 'ach_debit');
+
+COMMENT ON COLUMN payments.bank_reference IS 'Reference on the bank statement line, set when a bank transfer or ACH payment is confirmed';
+COMMENT ON COLUMN payments.received_on IS 'Day the bank transfer or ACH payment was received';
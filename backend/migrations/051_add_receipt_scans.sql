@@ -0,0 +1,23 @@
+-- Receipt photos read by OCR into draft expenses. The scan is kept with what was
+-- extracted and how confident the OCR was, so a confirmed expense can be audited
+-- against the receipt it came from.
+CREATE TABLE IF NOT EXISTS receipt_scans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expense_id UUID REFERENCES expenses(id) ON DELETE SET NULL,
+    file_name VARCHAR(255) NOT NULL,
+    receipt_image_url TEXT NOT NULL,
+    backend VARCHAR(50) NOT NULL,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('completed', 'failed')),
+    confidence DOUBLE PRECISION,
+    raw_text TEXT,
+    extracted JSONB NOT NULL DEFAULT '{}',
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    confirmed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_receipt_scans_user ON receipt_scans(user_id, created_at DESC);
+
+COMMENT ON COLUMN receipt_scans.confidence IS 'Mean OCR confidence over the recognized lines, 0 to 1';
+COMMENT ON COLUMN receipt_scans.extracted IS 'Vendor, date, amount and tax read from the receipt, each with its confidence';
@@ -0,0 +1,29 @@
+-- Spending limits per expense category for each month or quarter, and the alerts
+-- raised when spending in a period crosses 80% and 100% of a limit
+
+CREATE TABLE IF NOT EXISTS budget_limits (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category VARCHAR(50) NOT NULL,
+    period VARCHAR(10) NOT NULL CHECK (period IN ('monthly', 'quarterly')),
+    amount DECIMAL(15,2) NOT NULL CHECK (amount > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, category, period)
+);
+
+-- One alert per limit, period and threshold, so a crossing is only reported once
+CREATE TABLE IF NOT EXISTS budget_alerts (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    limit_id UUID NOT NULL REFERENCES budget_limits(id) ON DELETE CASCADE,
+    period_start DATE NOT NULL,
+    threshold SMALLINT NOT NULL CHECK (threshold IN (80, 100)),
+    spent DECIMAL(15,2) NOT NULL,
+    amount DECIMAL(15,2) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    read_at TIMESTAMPTZ,
+    UNIQUE (limit_id, period_start, threshold)
+);
+
+CREATE INDEX IF NOT EXISTS idx_budget_alerts_user ON budget_alerts(user_id, created_at DESC);
@@ -0,0 +1,24 @@
+-- Brute-force protection for sign-in: failed attempts per account lock it for a
+-- while, longer after each lockout, and failed attempts per IP throttle it
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS locked_until TIMESTAMPTZ,
+    -- Consecutive lockouts without a successful sign-in, for the backoff
+    ADD COLUMN IF NOT EXISTS lockout_count INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS login_attempts (
+    id UUID PRIMARY KEY,
+    email VARCHAR(255) NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ip_address INET,
+    succeeded BOOLEAN NOT NULL,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_attempts_ip ON login_attempts(ip_address, attempted_at DESC) WHERE NOT succeeded;
+CREATE INDEX IF NOT EXISTS idx_login_attempts_email ON login_attempts(email, attempted_at DESC);
+
+-- Password resets also unlock accounts. The expiry was the one users timestamp
+-- without a time zone, so users with a pending reset failed to load at all.
+ALTER TABLE users ALTER COLUMN reset_token_expires TYPE TIMESTAMPTZ;
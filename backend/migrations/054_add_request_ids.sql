@@ -0,0 +1,21 @@
+-- X-Request-Id of the request behind an audit entry or webhook event, to
+-- correlate them with logs and error reports. Connections carry it as the
+-- app.request_id setting, which is empty outside a request.
+
+ALTER TABLE audit_logs ADD COLUMN IF NOT EXISTS request_id VARCHAR(128);
+ALTER TABLE webhook_events ADD COLUMN IF NOT EXISTS request_id VARCHAR(128);
+
+CREATE INDEX IF NOT EXISTS idx_audit_request ON audit_logs(request_id) WHERE request_id IS NOT NULL;
+
+CREATE OR REPLACE FUNCTION queue_webhook_event(p_user_id UUID, p_event VARCHAR, p_entity_id UUID, p_data JSONB)
+RETURNS VOID AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM webhook_endpoints
+        WHERE user_id = p_user_id AND enabled AND p_event = ANY(events)
+    ) THEN
+        INSERT INTO webhook_events (user_id, event_type, entity_id, data, request_id)
+        VALUES (p_user_id, p_event, p_entity_id, p_data, NULLIF(current_setting('app.request_id', true), ''));
+    END IF;
+END;
+$$ language 'plpgsql';
@@ -0,0 +1,12 @@
+-- A user's own subject and/or body for the emails they send clients (invoice,
+-- payment_reminder, payment_confirmation). A NULL part uses the built-in one.
+CREATE TABLE IF NOT EXISTS email_templates (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(40) NOT NULL,
+    subject VARCHAR(200),
+    body TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, kind)
+);
@@ -0,0 +1,4 @@
+-- Language and formatting for invoices, PDFs and emails (en, id, es, de). A
+-- client's locale overrides its user's; NULL uses the user's.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS locale VARCHAR(8) NOT NULL DEFAULT 'en';
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS locale VARCHAR(8);
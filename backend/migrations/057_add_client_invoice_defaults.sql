@@ -0,0 +1,7 @@
+-- What new invoices for a client start with, besides payment_terms and locale.
+-- NULL falls back to the user's default tax, base currency and no discount.
+ALTER TABLE clients
+    ADD COLUMN IF NOT EXISTS default_tax_id UUID REFERENCES tax_settings(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS default_currency VARCHAR(3),
+    ADD COLUMN IF NOT EXISTS default_discount_percent DECIMAL(5,2)
+        CHECK (default_discount_percent > 0 AND default_discount_percent <= 100);
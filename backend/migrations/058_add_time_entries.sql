@@ -0,0 +1,23 @@
+-- Time tracking. An entry with no ended_at is a running timer; each user has at
+-- most one. Entries are billed by rolling them up into an invoice, which sets
+-- invoice_id; purging that invoice makes them billable again.
+ALTER TABLE clients ADD COLUMN IF NOT EXISTS hourly_rate DECIMAL(15,2) CHECK (hourly_rate > 0);
+
+CREATE TABLE IF NOT EXISTS time_entries (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    description TEXT NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ CHECK (ended_at > started_at),
+    hourly_rate DECIMAL(15,2) CHECK (hourly_rate > 0),
+    billable BOOLEAN NOT NULL DEFAULT TRUE,
+    invoice_id UUID REFERENCES invoices(id) ON DELETE SET NULL,
+    billed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_time_entries_user_started ON time_entries(user_id, started_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_time_entries_unbilled ON time_entries(client_id) WHERE invoice_id IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_time_entries_running ON time_entries(user_id) WHERE ended_at IS NULL;
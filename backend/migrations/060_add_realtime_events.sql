@@ -0,0 +1,83 @@
+-- Live dashboard updates: triggers write an outbox of events (so every path that
+-- changes an invoice, records a payment or posts a message is covered), the API
+-- publishes them over Redis to every replica and streams them to the user. Rows
+-- are kept for a while so a reconnecting client can catch up.
+CREATE TABLE IF NOT EXISTS realtime_events (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    invoice_id UUID NOT NULL,
+    data JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_realtime_events_pending ON realtime_events(id) WHERE published_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_realtime_events_user ON realtime_events(user_id, id);
+CREATE INDEX IF NOT EXISTS idx_realtime_events_created ON realtime_events(created_at);
+
+CREATE OR REPLACE FUNCTION invoice_realtime_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF NEW.status IS DISTINCT FROM OLD.status THEN
+        INSERT INTO realtime_events (user_id, event_type, invoice_id, data)
+        VALUES (NEW.user_id, 'invoice.status_changed', NEW.id, jsonb_build_object(
+            'invoice_number', NEW.invoice_number,
+            'status', NEW.status,
+            'previous_status', OLD.status,
+            'amount_paid', NEW.amount_paid,
+            'total_amount', NEW.total_amount
+        ));
+    END IF;
+    IF NEW.viewed_at IS NOT NULL AND OLD.viewed_at IS NULL THEN
+        INSERT INTO realtime_events (user_id, event_type, invoice_id, data)
+        VALUES (NEW.user_id, 'invoice.viewed', NEW.id, jsonb_build_object(
+            'invoice_number', NEW.invoice_number,
+            'viewed_at', NEW.viewed_at
+        ));
+    END IF;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS realtime_events_invoices ON invoices;
+CREATE TRIGGER realtime_events_invoices AFTER UPDATE ON invoices
+    FOR EACH ROW EXECUTE FUNCTION invoice_realtime_events();
+
+CREATE OR REPLACE FUNCTION payment_realtime_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO realtime_events (user_id, event_type, invoice_id, data)
+    VALUES (NEW.user_id, 'payment.created', NEW.invoice_id, jsonb_build_object(
+        'payment_id', NEW.id,
+        'amount', NEW.amount,
+        'currency', NEW.currency,
+        'payment_method', NEW.payment_method,
+        'status', NEW.status
+    ));
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS realtime_events_payments ON payments;
+CREATE TRIGGER realtime_events_payments AFTER INSERT ON payments
+    FOR EACH ROW EXECUTE FUNCTION payment_realtime_events();
+
+CREATE OR REPLACE FUNCTION discussion_realtime_events()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO realtime_events (user_id, event_type, invoice_id, data)
+    SELECT i.user_id, 'discussion.message', NEW.invoice_id, jsonb_build_object(
+        'message_id', NEW.id,
+        'sender_type', NEW.sender_type,
+        'message', NEW.message
+    )
+    FROM invoices i
+    WHERE i.id = NEW.invoice_id;
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS realtime_events_discussions ON invoice_discussions;
+CREATE TRIGGER realtime_events_discussions AFTER INSERT ON invoice_discussions
+    FOR EACH ROW EXECUTE FUNCTION discussion_realtime_events();
@@ -0,0 +1,13 @@
+-- Read state of each side of an invoice discussion: messages from the other side
+-- after last_read_at are unread, and last_notified_at throttles the email/WhatsApp
+-- notifications of new messages
+CREATE TABLE IF NOT EXISTS invoice_discussion_participants (
+    invoice_id UUID NOT NULL REFERENCES invoices(id) ON DELETE CASCADE,
+    participant VARCHAR(10) NOT NULL CHECK (participant IN ('seller', 'buyer')),
+    last_read_at TIMESTAMPTZ,
+    last_notified_at TIMESTAMPTZ,
+    PRIMARY KEY (invoice_id, participant)
+);
+
+CREATE INDEX IF NOT EXISTS idx_invoice_discussions_invoice_created
+    ON invoice_discussions(invoice_id, created_at);
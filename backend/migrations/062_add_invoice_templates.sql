@@ -0,0 +1,18 @@
+-- Named, reusable invoice content. A template isn't tied to a client; instantiating
+-- it with one creates a draft invoice.
+CREATE TABLE IF NOT EXISTS invoice_templates (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    items JSONB NOT NULL DEFAULT '[]',
+    notes TEXT,
+    terms TEXT,
+    discount_amount DECIMAL(15,2) CHECK (discount_amount >= 0),
+    tax_included BOOLEAN NOT NULL DEFAULT FALSE,
+    currency VARCHAR(3),
+    due_days INTEGER CHECK (due_days >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_invoice_templates_user_name ON invoice_templates(user_id, LOWER(name));
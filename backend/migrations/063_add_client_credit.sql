@@ -0,0 +1,18 @@
+-- Credit a client holds with the user, such as a prepaid retainer. Deposits add
+-- to it, refunds and credit applied to invoices draw it down (negative amounts).
+-- The balance in a currency is the sum of its entries and never goes below zero.
+CREATE TABLE IF NOT EXISTS client_credit_entries (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    client_id UUID NOT NULL REFERENCES clients(id) ON DELETE CASCADE,
+    invoice_id UUID REFERENCES invoices(id) ON DELETE SET NULL,
+    kind VARCHAR(20) NOT NULL,
+    amount DECIMAL(15,2) NOT NULL,
+    currency VARCHAR(3) NOT NULL,
+    description TEXT,
+    entry_date DATE NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_client_credit_entries_client ON client_credit_entries(client_id, entry_date, created_at);
+CREATE INDEX IF NOT EXISTS idx_client_credit_entries_invoice ON client_credit_entries(invoice_id) WHERE invoice_id IS NOT NULL;
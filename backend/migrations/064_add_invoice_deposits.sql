@@ -0,0 +1,13 @@
+-- Deposit the client pays upfront, before work begins: a fixed amount or a
+-- percentage of the total (deposit_percent is kept so edits can recompute it).
+-- deposit_paid_at is set once payments first cover the deposit.
+ALTER TABLE invoices
+ADD COLUMN IF NOT EXISTS deposit_amount DECIMAL(15,2),
+ADD COLUMN IF NOT EXISTS deposit_percent DECIMAL(5,2),
+ADD COLUMN IF NOT EXISTS deposit_due_date DATE,
+ADD COLUMN IF NOT EXISTS deposit_paid_at TIMESTAMPTZ;
+
+-- Reminder runs look for deposits still outstanding
+CREATE INDEX IF NOT EXISTS idx_invoices_deposit_due
+ON invoices(deposit_due_date)
+WHERE deposit_amount IS NOT NULL AND deposit_paid_at IS NULL;
@@ -0,0 +1,18 @@
+-- EU VAT: a client's VAT number and the outcome of its last VIES check
+-- (valid, invalid, or unverified when VIES couldn't be reached).
+ALTER TABLE clients
+ADD COLUMN IF NOT EXISTS vat_number VARCHAR(20),
+ADD COLUMN IF NOT EXISTS vat_status VARCHAR(20),
+ADD COLUMN IF NOT EXISTS vat_registered_name VARCHAR(255),
+ADD COLUMN IF NOT EXISTS vat_checked_at TIMESTAMPTZ;
+
+-- Invoices to VAT-registered businesses in another member state are reverse
+-- charged: no VAT is charged and the buyer accounts for it. vat_country is the
+-- member state the sale is reported under in the VAT summary.
+ALTER TABLE invoices
+ADD COLUMN IF NOT EXISTS reverse_charge BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN IF NOT EXISTS vat_country VARCHAR(2);
+
+CREATE INDEX IF NOT EXISTS idx_invoices_vat_country
+ON invoices(user_id, issue_date)
+WHERE vat_country IS NOT NULL;
@@ -0,0 +1,91 @@
+#!/bin/bash
+
+# FlashBill Benchmark Runner
+#
+# Runs the criterion benchmarks in benches/ and compares each mean time with
+# benches/baseline.json. Fails when a benchmark is slower than its baseline by
+# more than the tolerance, so hot-path regressions are caught before release.
+#
+# Usage:
+#   ./run_benchmarks.sh              # run and compare
+#   ./run_benchmarks.sh --save       # run and record the results as the new baseline
+#   ./run_benchmarks.sh pdf          # only benchmarks matching "pdf" (other criterion options work too)
+#
+# Report benchmarks need BENCH_DATABASE_URL (or DATABASE_URL) pointing at a test
+# database; they are skipped otherwise. Baselines are machine-specific: record
+# them on the machine that runs the comparison.
+
+set -e
+
+RED='\033[0;31m'
+GREEN='\033[0;32m'
+YELLOW='\033[1;33m'
+NC='\033[0m' # No Color
+
+SAVE=false
+if [ "$1" = "--save" ]; then
+    SAVE=true
+    shift
+fi
+
+echo -e "${YELLOW}=== FlashBill Benchmarks ===${NC}"
+echo ""
+
+STARTED_AT=$(date +%s)
+cargo bench --bench invoice_totals --bench pdf --bench reports --bench serialization -- "$@"
+
+echo ""
+set +e
+python3 - "$SAVE" "$STARTED_AT" <<'PY'
+import json, os, pathlib, sys
+
+save = sys.argv[1] == "true"
+started_at = float(sys.argv[2])
+criterion_dir = pathlib.Path("target/criterion")
+baseline_path = pathlib.Path("benches/baseline.json")
+baseline = json.loads(baseline_path.read_text())
+tolerance = float(os.environ.get("BENCH_TOLERANCE", baseline["tolerance"]))
+
+# Mean time in nanoseconds of every benchmark run just now; older results
+# left in target/ by previous runs are ignored
+results = {}
+for estimates in criterion_dir.glob("**/new/estimates.json"):
+    if estimates.stat().st_mtime < started_at:
+        continue
+    name = str(estimates.parent.parent.relative_to(criterion_dir))
+    results[name] = json.loads(estimates.read_text())["mean"]["point_estimate"]
+
+if save:
+    baseline["benchmarks"].update({name: round(mean) for name, mean in results.items()})
+    baseline["benchmarks"] = dict(sorted(baseline["benchmarks"].items()))
+    baseline_path.write_text(json.dumps(baseline, indent=2) + "\n")
+    print(f"Saved {len(results)} results to {baseline_path}")
+    sys.exit(0)
+
+regressions = 0
+for name, mean in sorted(results.items()):
+    expected = baseline["benchmarks"].get(name)
+    if expected is None:
+        print(f"  new         {name}: {mean:,.0f} ns (no baseline; record one with --save)")
+        continue
+    change = mean / expected - 1
+    if change > tolerance:
+        regressions += 1
+        print(f"  REGRESSED   {name}: {mean:,.0f} ns vs {expected:,} ns (+{change:.0%})")
+    else:
+        print(f"  ok          {name}: {mean:,.0f} ns vs {expected:,} ns ({change:+.0%})")
+
+if regressions:
+    print(f"\n{regressions} benchmark(s) slower than baseline by more than {tolerance:.0%}")
+    sys.exit(1)
+PY
+RESULT=$?
+
+echo ""
+if [ $RESULT -eq 0 ]; then
+    echo -e "${GREEN}=== Benchmarks within baseline ===${NC}"
+else
+    echo -e "${RED}=== Performance regressions found ===${NC}"
+fi
+
+exit $RESULT
@@ -55,8 +55,10 @@ else
         cargo build
     fi
 
-    # Start API in background
-    cargo run --bin flashbill-api &
+    # Start API in background. Every test signs in from localhost, far past the
+    # per-IP sign-in limits, so limits are only reported and failed sign-ins
+    # don't throttle the IP
+    RATE_LIMIT_ENFORCE=false LOGIN_MAX_FAILURES_PER_IP=0 cargo run --bin flashbill-api &
     API_PID=$!
     API_STARTED_BY_SCRIPT=true
 
@@ -1,12 +1,19 @@
 #![allow(dead_code)]
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Serialize;
 use serde_json::json;
+use std::collections::BTreeMap;
 use thiserror::Error;
+use utoipa::openapi::{ContentBuilder, RefOr, Response as OpenApiResponse, ResponseBuilder, ResponsesBuilder};
+use utoipa::ToSchema;
+
+use crate::api::middleware::locale::current_locale;
+use crate::domain::i18n::translate;
 
 #[derive(Debug, Error)]
 pub enum ApiError {
@@ -36,37 +43,290 @@ pub enum ApiError {
 
     #[error("Invalid input: {0}")]
     BadRequest(String),
+
+    /// The request clashes with one that is still being processed
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// An external provider (email, WhatsApp, ...) rejected the request
+    #[error("Upstream error: {0}")]
+    Upstream(String),
+
+    /// Shed while the instance is unhealthy
+    #[error("Service temporarily unavailable")]
+    ServiceUnavailable,
+
+    /// Too many wrong passwords; the account opens again after `retry_after` seconds
+    #[error("Account locked")]
+    AccountLocked { retry_after: i64 },
+
+    /// Too many failed sign-ins from the caller's IP
+    #[error("Too many failed sign-in attempts")]
+    TooManyLoginAttempts { retry_after: i64 },
+
+    /// A service error with its own code, see `ErrorCode`
+    #[error("{message}")]
+    Coded {
+        code: ErrorCode,
+        message: String,
+        details: Option<serde_json::Value>,
+    },
+}
+
+/// Machine-readable error codes, shared by every endpoint. Clients branch on the
+/// code; the message is for people and is translated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    ValidationError,
+    BadRequest,
+    Unauthorized,
+    InvalidCredentials,
+    InvalidToken,
+    TokenExpired,
+    AccessRevoked,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    RequestTimeout,
+    Conflict,
+    PayloadTooLarge,
+    UnsupportedMediaType,
+    AccountLocked,
+    RateLimit,
+    TooManyLoginAttempts,
+    DatabaseError,
+    InternalError,
+    UpstreamError,
+    ServiceUnavailable,
+    /// The invoice's client doesn't exist
+    ClientNotFound,
+    /// The referenced invoice doesn't exist
+    InvoiceNotFound,
+    InvalidStatus,
+    /// The invoice's status doesn't allow the change; details name the status and action
+    InvalidStatusTransition,
+    /// No channel accepted the invoice
+    DeliveryFailed,
+    /// Details carry `active_invoices`
+    ClientHasActiveInvoices,
+    AlreadyExists,
+    /// The receipt or bank transfer was already confirmed or dismissed
+    AlreadyProcessed,
+    /// Details carry `max_bytes`
+    FileTooLarge,
+    /// Details carry `used` and `limit` in bytes
+    StorageLimitExceeded,
+    InvalidFileType,
+    InvalidCursor,
+    /// Sync again without a cursor
+    CursorExpired,
+    /// A webhook's signature didn't verify
+    InvalidSignature,
+    /// The integration isn't set up for this account
+    NotConfigured,
+    /// The payment provider declined or failed the request
+    PaymentFailed,
+    /// No exchange rate for the currency pair and date
+    RateUnavailable,
+}
+
+impl ErrorCode {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::ValidationError
+            | ErrorCode::BadRequest
+            | ErrorCode::ClientNotFound
+            | ErrorCode::InvoiceNotFound
+            | ErrorCode::InvalidStatus
+            | ErrorCode::ClientHasActiveInvoices
+            | ErrorCode::AlreadyExists
+            | ErrorCode::FileTooLarge
+            | ErrorCode::StorageLimitExceeded
+            | ErrorCode::InvalidFileType
+            | ErrorCode::InvalidCursor
+            | ErrorCode::CursorExpired
+            | ErrorCode::NotConfigured
+            | ErrorCode::PaymentFailed
+            | ErrorCode::RateUnavailable => StatusCode::BAD_REQUEST,
+            ErrorCode::Unauthorized
+            | ErrorCode::InvalidCredentials
+            | ErrorCode::InvalidToken
+            | ErrorCode::TokenExpired
+            | ErrorCode::AccessRevoked
+            | ErrorCode::InvalidSignature => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::RequestTimeout => StatusCode::REQUEST_TIMEOUT,
+            ErrorCode::Conflict | ErrorCode::InvalidStatusTransition | ErrorCode::AlreadyProcessed => StatusCode::CONFLICT,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::UnsupportedMediaType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ErrorCode::AccountLocked => StatusCode::LOCKED,
+            ErrorCode::RateLimit | ErrorCode::TooManyLoginAttempts => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::DatabaseError | ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::UpstreamError | ErrorCode::DeliveryFailed => StatusCode::BAD_GATEWAY,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    /// Generic code for an error status raised outside the handlers, e.g. by an
+    /// extractor rejection or an unmatched route
+    pub fn for_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+            StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+            StatusCode::NOT_FOUND => ErrorCode::NotFound,
+            StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+            StatusCode::REQUEST_TIMEOUT => ErrorCode::RequestTimeout,
+            StatusCode::CONFLICT => ErrorCode::Conflict,
+            StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+            StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::ValidationError,
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimit,
+            StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT => ErrorCode::UpstreamError,
+            StatusCode::SERVICE_UNAVAILABLE => ErrorCode::ServiceUnavailable,
+            status if status.is_server_error() => ErrorCode::InternalError,
+            _ => ErrorCode::BadRequest,
+        }
+    }
+}
+
+/// Body of every error response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub error: ErrorDetail,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorDetail {
+    pub code: ErrorCode,
+    /// In the language of the request's Accept-Language
+    pub message: String,
+    /// Structured context for some codes, e.g. the failing fields of a
+    /// VALIDATION_ERROR or `retry_after` of ACCOUNT_LOCKED
+    pub details: Option<serde_json::Value>,
+    /// Same as the X-Request-Id response header; quote it when reporting a problem
+    pub request_id: Option<String>,
+    /// RFC 3339
+    pub timestamp: String,
+}
+
+/// The error envelope, with the message translated to the request's language
+pub fn error_response(status: StatusCode, code: ErrorCode, message: &str, details: Option<serde_json::Value>) -> Response {
+    let body = ErrorResponse {
+        error: ErrorDetail {
+            code,
+            message: translate(current_locale(), message).into_owned(),
+            details,
+            request_id: crate::domain::request_id::current(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        },
+    };
+    (status, Json(body)).into_response()
+}
+
+/// Documents the error statuses handlers share, all with an `ErrorResponse` body
+impl utoipa::IntoResponses for ApiError {
+    fn responses() -> BTreeMap<String, RefOr<OpenApiResponse>> {
+        let error = |description: &str| {
+            ResponseBuilder::new()
+                .description(description)
+                .content("application/json", ContentBuilder::new().schema(Some(ErrorResponse::schema_ref())).build())
+                .build()
+        };
+        ResponsesBuilder::new()
+            .response("400", error("Invalid input"))
+            .response("401", error("Missing or invalid bearer token"))
+            .response("403", error("The token doesn't grant this action"))
+            .response("404", error("Not found"))
+            .response("409", error("The resource's state doesn't allow the change"))
+            .response("429", error("Rate limit exceeded; see Retry-After"))
+            .response("500", error("Internal server error"))
+            .build()
+            .into()
+    }
+}
+
+impl ErrorResponse {
+    fn schema_ref() -> RefOr<utoipa::openapi::schema::Schema> {
+        RefOr::Ref(utoipa::openapi::Ref::from_schema_name("ErrorResponse"))
+    }
+}
+
+impl ApiError {
+    /// An error with a specific code; the status follows from it
+    pub fn coded(code: ErrorCode, message: impl Into<String>) -> Self {
+        ApiError::Coded { code, message: message.into(), details: None }
+    }
+
+    pub fn with_details(code: ErrorCode, message: impl Into<String>, details: serde_json::Value) -> Self {
+        ApiError::Coded { code, message: message.into(), details: Some(details) }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ApiError::Validation(_) => ErrorCode::ValidationError,
+            ApiError::Unauthorized => ErrorCode::Unauthorized,
+            ApiError::InvalidCredentials => ErrorCode::InvalidCredentials,
+            ApiError::NotFound => ErrorCode::NotFound,
+            ApiError::Forbidden => ErrorCode::Forbidden,
+            ApiError::Database(_) => ErrorCode::DatabaseError,
+            ApiError::Internal => ErrorCode::InternalError,
+            ApiError::RateLimit => ErrorCode::RateLimit,
+            ApiError::BadRequest(_) => ErrorCode::BadRequest,
+            ApiError::Conflict(_) => ErrorCode::Conflict,
+            ApiError::Upstream(_) => ErrorCode::UpstreamError,
+            ApiError::ServiceUnavailable => ErrorCode::ServiceUnavailable,
+            ApiError::AccountLocked { .. } => ErrorCode::AccountLocked,
+            ApiError::TooManyLoginAttempts { .. } => ErrorCode::TooManyLoginAttempts,
+            ApiError::Coded { code, .. } => *code,
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, message, code) = match self {
-            ApiError::Validation(msg) => (StatusCode::BAD_REQUEST, msg, "VALIDATION_ERROR"),
-            ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string(), "UNAUTHORIZED"),
-            ApiError::InvalidCredentials => (StatusCode::UNAUTHORIZED, "Invalid credentials".to_string(), "INVALID_CREDENTIALS"),
-            ApiError::NotFound => (StatusCode::NOT_FOUND, "Not found".to_string(), "NOT_FOUND"),
-            ApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string(), "FORBIDDEN"),
+        let code = self.code();
+        let retry_after = match self {
+            ApiError::AccountLocked { retry_after } | ApiError::TooManyLoginAttempts { retry_after } => Some(retry_after),
+            _ => None,
+        };
+        let (message, details) = match self {
+            ApiError::Validation(msg)
+            | ApiError::BadRequest(msg)
+            | ApiError::Conflict(msg)
+            | ApiError::Upstream(msg) => (msg, None),
+            ApiError::Unauthorized => ("Unauthorized".to_string(), None),
+            ApiError::InvalidCredentials => ("Invalid credentials".to_string(), None),
+            ApiError::NotFound => ("Not found".to_string(), None),
+            ApiError::Forbidden => ("Forbidden".to_string(), None),
             ApiError::Database(msg) => {
                 tracing::error!("Database error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string(), "DATABASE_ERROR")
+                ("Database error".to_string(), None)
             }
             ApiError::Internal => {
                 tracing::error!("Internal server error");
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string(), "INTERNAL_ERROR")
+                ("Internal server error".to_string(), None)
             }
-            ApiError::RateLimit => (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string(), "RATE_LIMIT"),
-            ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg, "BAD_REQUEST"),
+            ApiError::RateLimit => ("Rate limit exceeded".to_string(), None),
+            ApiError::ServiceUnavailable => ("Service is under heavy load; please try again shortly".to_string(), None),
+            ApiError::AccountLocked { retry_after } => (
+                "Account temporarily locked after too many failed sign-in attempts; try again later or reset your password".to_string(),
+                Some(json!({ "retry_after": retry_after })),
+            ),
+            ApiError::TooManyLoginAttempts { retry_after } => (
+                "Too many failed sign-in attempts; please try again later".to_string(),
+                Some(json!({ "retry_after": retry_after })),
+            ),
+            ApiError::Coded { message, details, .. } => (message, details),
         };
 
-        let body = json!({
-            "error": {
-                "code": code,
-                "message": message,
-                "timestamp": chrono::Utc::now().to_rfc3339()
-            }
-        });
-
-        (status, Json(body)).into_response()
+        let mut response = error_response(code.status(), code, &message, details);
+        if let Some(retry_after) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
     }
 }
 
@@ -78,7 +338,28 @@ impl From<sqlx::Error> for ApiError {
 
 impl From<validator::ValidationErrors> for ApiError {
     fn from(err: validator::ValidationErrors) -> Self {
-        ApiError::Validation(err.to_string())
+        // The rule and its limits per field, without echoing the submitted value (it may be a password)
+        let fields: serde_json::Map<String, serde_json::Value> = err
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let errors = errors
+                    .iter()
+                    .map(|error| {
+                        let params: serde_json::Map<String, serde_json::Value> = error
+                            .params
+                            .iter()
+                            .filter(|(name, _)| *name != "value")
+                            .map(|(name, value)| (name.to_string(), value.clone()))
+                            .collect();
+                        json!({ "code": error.code, "message": error.message, "params": params })
+                    })
+                    .collect();
+                (field.to_string(), serde_json::Value::Array(errors))
+            })
+            .collect();
+        let message = format!("Invalid fields: {}", fields.keys().cloned().collect::<Vec<_>>().join(", "));
+        ApiError::with_details(ErrorCode::ValidationError, message, json!({ "fields": fields }))
     }
 }
 
@@ -86,12 +367,16 @@ impl From<crate::domain::services::AuthError> for ApiError {
     fn from(err: crate::domain::services::AuthError) -> Self {
         match err {
             crate::domain::services::AuthError::InvalidCredentials => ApiError::InvalidCredentials,
-            crate::domain::services::AuthError::TokenExpired => ApiError::Unauthorized,
-            crate::domain::services::AuthError::InvalidToken => ApiError::Unauthorized,
+            crate::domain::services::AuthError::TokenExpired => ApiError::coded(ErrorCode::TokenExpired, "Token expired"),
+            crate::domain::services::AuthError::InvalidToken => ApiError::coded(ErrorCode::InvalidToken, "Invalid token"),
             crate::domain::services::AuthError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::AuthError::InvalidFields(errors) => errors.into(),
+            crate::domain::services::AuthError::EmailTaken => ApiError::coded(ErrorCode::AlreadyExists, "Email already registered"),
             crate::domain::services::AuthError::DatabaseError(msg) => ApiError::Database(msg),
             crate::domain::services::AuthError::UserNotFound => ApiError::NotFound,
             crate::domain::services::AuthError::HashingFailed => ApiError::Internal,
+            crate::domain::services::AuthError::AccountLocked { retry_after } => ApiError::AccountLocked { retry_after },
+            crate::domain::services::AuthError::TooManyLoginAttempts { retry_after } => ApiError::TooManyLoginAttempts { retry_after },
         }
     }
 }
@@ -100,8 +385,8 @@ impl From<crate::domain::services::InvoiceError> for ApiError {
     fn from(err: crate::domain::services::InvoiceError) -> Self {
         match err {
             crate::domain::services::InvoiceError::NotFound => ApiError::NotFound,
-            crate::domain::services::InvoiceError::ClientNotFound => ApiError::BadRequest("Client not found".to_string()),
-            crate::domain::services::InvoiceError::InvalidStatus(msg) => ApiError::BadRequest(msg),
+            crate::domain::services::InvoiceError::ClientNotFound => ApiError::coded(ErrorCode::ClientNotFound, "Client not found"),
+            crate::domain::services::InvoiceError::InvalidStatus(msg) => ApiError::coded(ErrorCode::InvalidStatus, msg),
             crate::domain::services::InvoiceError::DatabaseError(msg) => ApiError::Database(msg),
             crate::domain::services::InvoiceError::Validation(msg) => ApiError::Validation(msg),
             crate::domain::services::InvoiceError::PdfGenerationError(msg) => {
@@ -120,6 +405,13 @@ impl From<crate::domain::services::InvoiceError> for ApiError {
                 tracing::error!("Notification error: {}", msg);
                 ApiError::Internal
             }
+            crate::domain::services::InvoiceError::DeliveryFailed(msg) => {
+                ApiError::coded(ErrorCode::DeliveryFailed, format!("Invoice could not be delivered: {}", msg))
+            }
+            crate::domain::services::InvoiceError::InvalidTransition { ref invoice_number, ref from, action } => {
+                let details = json!({ "invoice_number": invoice_number, "status": from, "action": action.to_string() });
+                ApiError::with_details(ErrorCode::InvalidStatusTransition, err.to_string(), details)
+            }
         }
     }
 }
@@ -140,7 +432,13 @@ impl From<crate::application::use_cases::SettingsError> for ApiError {
     fn from(err: crate::application::use_cases::SettingsError) -> Self {
         match err {
             crate::application::use_cases::SettingsError::UserNotFound => ApiError::NotFound,
+            crate::application::use_cases::SettingsError::InvalidLogo(msg) => ApiError::Validation(msg),
+            crate::application::use_cases::SettingsError::Storage(msg) => {
+                tracing::error!("Settings storage error: {}", msg);
+                ApiError::Internal
+            }
             crate::application::use_cases::SettingsError::DatabaseError(msg) => ApiError::Database(msg),
+            crate::application::use_cases::SettingsError::Validation(msg) => ApiError::Validation(msg),
         }
     }
 }
@@ -149,6 +447,10 @@ impl From<crate::application::use_cases::ClientError> for ApiError {
     fn from(err: crate::application::use_cases::ClientError) -> Self {
         match err {
             crate::application::use_cases::ClientError::NotFound => ApiError::NotFound,
+            crate::application::use_cases::ClientError::Validation(msg) => ApiError::Validation(msg),
+            crate::application::use_cases::ClientError::HasActiveInvoices(count) => {
+                ApiError::with_details(ErrorCode::ClientHasActiveInvoices, err.to_string(), json!({ "active_invoices": count }))
+            }
             crate::application::use_cases::ClientError::DatabaseError(msg) => ApiError::Database(msg),
         }
     }
@@ -167,6 +469,7 @@ impl From<crate::application::use_cases::ExpenseError> for ApiError {
     fn from(err: crate::application::use_cases::ExpenseError) -> Self {
         match err {
             crate::application::use_cases::ExpenseError::NotFound => ApiError::NotFound,
+            crate::application::use_cases::ExpenseError::Validation(msg) => ApiError::BadRequest(msg),
             crate::application::use_cases::ExpenseError::DatabaseError(msg) => ApiError::Database(msg),
         }
     }
@@ -177,24 +480,548 @@ impl From<crate::domain::services::TaxError> for ApiError {
         match err {
             crate::domain::services::TaxError::InvalidRate(msg) => ApiError::BadRequest(msg),
             crate::domain::services::TaxError::NotFound => ApiError::NotFound,
-            crate::domain::services::TaxError::AlreadyExists => ApiError::BadRequest("Tax setting already exists".to_string()),
-            crate::domain::services::TaxError::DefaultAlreadyExists => ApiError::BadRequest("Default tax already exists".to_string()),
+            crate::domain::services::TaxError::AlreadyExists => ApiError::coded(ErrorCode::AlreadyExists, "Tax setting already exists"),
+            crate::domain::services::TaxError::DefaultAlreadyExists => ApiError::coded(ErrorCode::AlreadyExists, "Default tax already exists"),
             crate::domain::services::TaxError::DatabaseError(msg) => ApiError::Database(msg),
             crate::domain::services::TaxError::Validation(msg) => ApiError::Validation(msg),
         }
     }
 }
 
+impl From<crate::domain::services::DocumentNumberError> for ApiError {
+    fn from(err: crate::domain::services::DocumentNumberError) -> Self {
+        match err {
+            crate::domain::services::DocumentNumberError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::DocumentNumberError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::GuestTokenError> for ApiError {
+    fn from(err: crate::domain::services::GuestTokenError) -> Self {
+        match err {
+            crate::domain::services::GuestTokenError::Malformed(msg) => ApiError::BadRequest(msg),
+            crate::domain::services::GuestTokenError::Invalid => ApiError::NotFound,
+            crate::domain::services::GuestTokenError::InvoiceNotFound => ApiError::NotFound,
+            crate::domain::services::GuestTokenError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::AttachmentError> for ApiError {
+    fn from(err: crate::domain::services::AttachmentError) -> Self {
+        match err {
+            crate::domain::services::AttachmentError::NotFound
+            | crate::domain::services::AttachmentError::InvoiceNotFound
+            | crate::domain::services::AttachmentError::ExpenseNotFound => ApiError::NotFound,
+            crate::domain::services::AttachmentError::FileTooLarge(max_bytes) => {
+                ApiError::with_details(ErrorCode::FileTooLarge, err.to_string(), json!({ "max_bytes": max_bytes }))
+            }
+            crate::domain::services::AttachmentError::StorageLimitExceeded { used, limit } => {
+                ApiError::with_details(ErrorCode::StorageLimitExceeded, err.to_string(), json!({ "used": used, "limit": limit }))
+            }
+            crate::domain::services::AttachmentError::InvalidFileType(_) => ApiError::coded(ErrorCode::InvalidFileType, err.to_string()),
+            crate::domain::services::AttachmentError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::AttachmentError::Storage(msg) => {
+                tracing::error!("Attachment storage error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::AttachmentError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::LateFeeError> for ApiError {
+    fn from(err: crate::domain::services::LateFeeError) -> Self {
+        match err {
+            crate::domain::services::LateFeeError::InvoiceNotFound => ApiError::NotFound,
+            crate::domain::services::LateFeeError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::LateFeeError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::AuditError> for ApiError {
+    fn from(err: crate::domain::services::AuditError) -> Self {
+        match err {
+            crate::domain::services::AuditError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::AuditError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::AutomationIssueError> for ApiError {
+    fn from(err: crate::domain::services::AutomationIssueError) -> Self {
+        match err {
+            crate::domain::services::AutomationIssueError::NotFound => ApiError::NotFound,
+            crate::domain::services::AutomationIssueError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::BudgetError> for ApiError {
+    fn from(err: crate::domain::services::BudgetError) -> Self {
+        match err {
+            crate::domain::services::BudgetError::NotFound => ApiError::NotFound,
+            crate::domain::services::BudgetError::AlreadyExists(_) => ApiError::coded(ErrorCode::AlreadyExists, err.to_string()),
+            crate::domain::services::BudgetError::LimitExists(..) => ApiError::Conflict(err.to_string()),
+            crate::domain::services::BudgetError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::BudgetError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::BusinessError> for ApiError {
+    fn from(err: crate::domain::services::BusinessError) -> Self {
+        match err {
+            crate::domain::services::BusinessError::NotFound => ApiError::NotFound,
+            crate::domain::services::BusinessError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::BusinessError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::AccountantError> for ApiError {
+    fn from(err: crate::domain::services::AccountantError) -> Self {
+        match err {
+            crate::domain::services::AccountantError::NotFound => ApiError::NotFound,
+            crate::domain::services::AccountantError::InvalidAccessCode => ApiError::InvalidCredentials,
+            crate::domain::services::AccountantError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::AccountantError::Token(_) => ApiError::Internal,
+            crate::domain::services::AccountantError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::CampaignError> for ApiError {
+    fn from(err: crate::domain::services::CampaignError) -> Self {
+        match err {
+            crate::domain::services::CampaignError::NotFound => ApiError::NotFound,
+            crate::domain::services::CampaignError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::CampaignError::InvalidStatus(msg) => ApiError::coded(ErrorCode::InvalidStatus, msg),
+            crate::domain::services::CampaignError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ProfitabilityError> for ApiError {
+    fn from(err: crate::domain::services::ProfitabilityError) -> Self {
+        match err {
+            crate::domain::services::ProfitabilityError::InvoiceNotFound
+            | crate::domain::services::ProfitabilityError::ExpenseNotFound
+            | crate::domain::services::ProfitabilityError::CostNotFound => ApiError::NotFound,
+            crate::domain::services::ProfitabilityError::ExpenseAlreadyLinked => ApiError::coded(ErrorCode::AlreadyExists, err.to_string()),
+            crate::domain::services::ProfitabilityError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ProfitabilityError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::SyncError> for ApiError {
+    fn from(err: crate::domain::services::SyncError) -> Self {
+        match err {
+            crate::domain::services::SyncError::InvalidCursor(_) => ApiError::coded(ErrorCode::InvalidCursor, err.to_string()),
+            crate::domain::services::SyncError::CursorExpired(max_age_days) => {
+                ApiError::with_details(ErrorCode::CursorExpired, err.to_string(), json!({ "max_age_days": max_age_days }))
+            }
+            crate::domain::services::SyncError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::SyncError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::InvoiceLabelError> for ApiError {
+    fn from(err: crate::domain::services::InvoiceLabelError) -> Self {
+        match err {
+            crate::domain::services::InvoiceLabelError::NotFound => ApiError::NotFound,
+            crate::domain::services::InvoiceLabelError::InvoiceNotFound => ApiError::NotFound,
+            crate::domain::services::InvoiceLabelError::AlreadyExists(_) => ApiError::coded(ErrorCode::AlreadyExists, err.to_string()),
+            crate::domain::services::InvoiceLabelError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::InvoiceLabelError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::TimeEntryError> for ApiError {
+    fn from(err: crate::domain::services::TimeEntryError) -> Self {
+        match err {
+            crate::domain::services::TimeEntryError::NotFound => ApiError::NotFound,
+            crate::domain::services::TimeEntryError::ClientNotFound => ApiError::coded(ErrorCode::ClientNotFound, "Client not found"),
+            crate::domain::services::TimeEntryError::AlreadyBilled => ApiError::coded(ErrorCode::AlreadyProcessed, err.to_string()),
+            crate::domain::services::TimeEntryError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::TimeEntryError::Invoice(err) => err.into(),
+            crate::domain::services::TimeEntryError::Project(err) => err.into(),
+            crate::domain::services::TimeEntryError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ProjectError> for ApiError {
+    fn from(err: crate::domain::services::ProjectError) -> Self {
+        match err {
+            crate::domain::services::ProjectError::NotFound => ApiError::NotFound,
+            crate::domain::services::ProjectError::ClientNotFound => ApiError::coded(ErrorCode::ClientNotFound, "Client not found"),
+            crate::domain::services::ProjectError::InvoiceNotFound => ApiError::NotFound,
+            crate::domain::services::ProjectError::ExpenseNotFound => ApiError::NotFound,
+            crate::domain::services::ProjectError::TimeEntryNotFound => ApiError::NotFound,
+            crate::domain::services::ProjectError::AlreadyBilled => ApiError::coded(ErrorCode::AlreadyProcessed, err.to_string()),
+            crate::domain::services::ProjectError::AlreadyExists(_) => ApiError::coded(ErrorCode::AlreadyExists, err.to_string()),
+            crate::domain::services::ProjectError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ProjectError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::InvoiceTemplateError> for ApiError {
+    fn from(err: crate::domain::services::InvoiceTemplateError) -> Self {
+        match err {
+            crate::domain::services::InvoiceTemplateError::NotFound => ApiError::NotFound,
+            crate::domain::services::InvoiceTemplateError::AlreadyExists(_) => ApiError::coded(ErrorCode::AlreadyExists, err.to_string()),
+            crate::domain::services::InvoiceTemplateError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::InvoiceTemplateError::Invoice(err) => err.into(),
+            crate::domain::services::InvoiceTemplateError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ClientCreditError> for ApiError {
+    fn from(err: crate::domain::services::ClientCreditError) -> Self {
+        match err {
+            crate::domain::services::ClientCreditError::ClientNotFound => ApiError::coded(ErrorCode::ClientNotFound, "Client not found"),
+            crate::domain::services::ClientCreditError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ClientCreditError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::RealtimeError> for ApiError {
+    fn from(err: crate::domain::services::RealtimeError) -> Self {
+        match err {
+            crate::domain::services::RealtimeError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::FxError> for ApiError {
+    fn from(err: crate::domain::services::FxError) -> Self {
+        match err {
+            crate::domain::services::FxError::NotFound => ApiError::NotFound,
+            crate::domain::services::FxError::RateUnavailable(_) => ApiError::coded(ErrorCode::RateUnavailable, err.to_string()),
+            crate::domain::services::FxError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::FxError::Provider(msg) => {
+                tracing::error!("Exchange rate provider error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::FxError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::EmailSignatureError> for ApiError {
+    fn from(err: crate::domain::services::EmailSignatureError) -> Self {
+        match err {
+            crate::domain::services::EmailSignatureError::NotFound => ApiError::NotFound,
+            crate::domain::services::EmailSignatureError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::EmailSignatureError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::EmailTemplateError> for ApiError {
+    fn from(err: crate::domain::services::EmailTemplateError) -> Self {
+        match err {
+            crate::domain::services::EmailTemplateError::NotFound => ApiError::NotFound,
+            crate::domain::services::EmailTemplateError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::EmailTemplateError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::CustomReportError> for ApiError {
+    fn from(err: crate::domain::services::CustomReportError) -> Self {
+        match err {
+            crate::domain::services::CustomReportError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::CustomReportError::DatabaseError(msg) => ApiError::Database(msg),
+            crate::domain::services::CustomReportError::Csv(msg) => {
+                tracing::error!("Custom report CSV error: {}", msg);
+                ApiError::Internal
+            }
+        }
+    }
+}
+
+impl From<crate::domain::services::TemplateBundleError> for ApiError {
+    fn from(err: crate::domain::services::TemplateBundleError) -> Self {
+        match err {
+            crate::domain::services::TemplateBundleError::UserNotFound => ApiError::NotFound,
+            crate::domain::services::TemplateBundleError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::TemplateBundleError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ClientImportError> for ApiError {
+    fn from(err: crate::domain::services::ClientImportError) -> Self {
+        match err {
+            crate::domain::services::ClientImportError::NotFound => ApiError::NotFound,
+            crate::domain::services::ClientImportError::AlreadyResolved => {
+                ApiError::BadRequest("Import was already confirmed or dismissed".to_string())
+            }
+            crate::domain::services::ClientImportError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ClientImportError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::CreditNoteError> for ApiError {
+    fn from(err: crate::domain::services::CreditNoteError) -> Self {
+        match err {
+            crate::domain::services::CreditNoteError::NotFound => ApiError::NotFound,
+            crate::domain::services::CreditNoteError::InvoiceNotFound => ApiError::coded(ErrorCode::InvoiceNotFound, "Invoice not found"),
+            crate::domain::services::CreditNoteError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::CreditNoteError::InvalidFields(errors) => errors.into(),
+            crate::domain::services::CreditNoteError::PdfError(msg) => {
+                tracing::error!("Credit note PDF generation error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::CreditNoteError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::PayoutError> for ApiError {
+    fn from(err: crate::domain::services::PayoutError) -> Self {
+        match err {
+            crate::domain::services::PayoutError::NotFound => ApiError::NotFound,
+            crate::domain::services::PayoutError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::PayoutError::InvalidSignature => ApiError::coded(ErrorCode::InvalidSignature, err.to_string()),
+            crate::domain::services::PayoutError::NotConfigured(msg) => ApiError::coded(ErrorCode::NotConfigured, msg),
+            crate::domain::services::PayoutError::Gateway(msg) => {
+                tracing::error!("Payout gateway error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::PayoutError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::PayPalWebhookError> for ApiError {
+    fn from(err: crate::domain::services::PayPalWebhookError) -> Self {
+        match err {
+            crate::domain::services::PayPalWebhookError::InvalidSignature => ApiError::coded(ErrorCode::InvalidSignature, err.to_string()),
+            crate::domain::services::PayPalWebhookError::NotConfigured(msg) => ApiError::coded(ErrorCode::NotConfigured, msg),
+            crate::domain::services::PayPalWebhookError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::PayPalWebhookError::Gateway(msg) => {
+                tracing::error!("PayPal webhook gateway error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::PayPalWebhookError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
 impl From<crate::domain::services::payment_gateway_service::PaymentGatewayError> for ApiError {
     fn from(err: crate::domain::services::payment_gateway_service::PaymentGatewayError) -> Self {
         match err {
-            crate::domain::services::payment_gateway_service::PaymentGatewayError::Stripe(msg) => ApiError::BadRequest(msg),
-            crate::domain::services::payment_gateway_service::PaymentGatewayError::PayPal(msg) => ApiError::BadRequest(msg),
-            crate::domain::services::payment_gateway_service::PaymentGatewayError::Ach(msg) => ApiError::BadRequest(msg),
+            crate::domain::services::payment_gateway_service::PaymentGatewayError::Stripe(msg)
+            | crate::domain::services::payment_gateway_service::PaymentGatewayError::PayPal(msg)
+            | crate::domain::services::payment_gateway_service::PaymentGatewayError::Ach(msg) => ApiError::coded(ErrorCode::PaymentFailed, msg),
             crate::domain::services::payment_gateway_service::PaymentGatewayError::Config(_msg) => ApiError::Internal,
             crate::domain::services::payment_gateway_service::PaymentGatewayError::InvalidAmount => ApiError::BadRequest("Invalid amount".to_string()),
-            crate::domain::services::payment_gateway_service::PaymentGatewayError::Failed(msg) => ApiError::BadRequest(msg),
+            crate::domain::services::payment_gateway_service::PaymentGatewayError::Failed(msg) => ApiError::coded(ErrorCode::PaymentFailed, msg),
             crate::domain::services::payment_gateway_service::PaymentGatewayError::Http(_msg) => ApiError::Internal,
         }
     }
 }
+
+impl From<crate::domain::services::WebhookError> for ApiError {
+    fn from(err: crate::domain::services::WebhookError) -> Self {
+        match err {
+            crate::domain::services::WebhookError::EndpointNotFound => ApiError::NotFound,
+            crate::domain::services::WebhookError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::WebhookError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ClientCsvImportError> for ApiError {
+    fn from(err: crate::domain::services::ClientCsvImportError) -> Self {
+        match err {
+            crate::domain::services::ClientCsvImportError::JobNotFound => ApiError::NotFound,
+            crate::domain::services::ClientCsvImportError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ClientCsvImportError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::InvoiceCsvImportError> for ApiError {
+    fn from(err: crate::domain::services::InvoiceCsvImportError) -> Self {
+        match err {
+            crate::domain::services::InvoiceCsvImportError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::InvoiceCsvImportError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::SearchError> for ApiError {
+    fn from(err: crate::domain::services::SearchError) -> Self {
+        match err {
+            crate::domain::services::SearchError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::SearchError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::TrashError> for ApiError {
+    fn from(err: crate::domain::services::TrashError) -> Self {
+        match err {
+            crate::domain::services::TrashError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ClientAuthError> for ApiError {
+    fn from(err: crate::domain::services::ClientAuthError) -> Self {
+        match err {
+            crate::domain::services::ClientAuthError::NotFound => ApiError::NotFound,
+            crate::domain::services::ClientAuthError::InvalidCredentials => ApiError::InvalidCredentials,
+            crate::domain::services::ClientAuthError::InvalidLink => ApiError::Unauthorized,
+            crate::domain::services::ClientAuthError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ClientAuthError::Token(_) => ApiError::Internal,
+            crate::domain::services::ClientAuthError::Invoice(err) => err.into(),
+            crate::domain::services::ClientAuthError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::StripeCheckoutError> for ApiError {
+    fn from(err: crate::domain::services::StripeCheckoutError) -> Self {
+        match err {
+            crate::domain::services::StripeCheckoutError::NotFound => ApiError::NotFound,
+            crate::domain::services::StripeCheckoutError::InvalidSignature => ApiError::coded(ErrorCode::InvalidSignature, err.to_string()),
+            crate::domain::services::StripeCheckoutError::NotConfigured(msg) => ApiError::coded(ErrorCode::NotConfigured, msg),
+            crate::domain::services::StripeCheckoutError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::StripeCheckoutError::Gateway(msg) => {
+                tracing::error!("Stripe checkout gateway error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::StripeCheckoutError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::BankReconciliationError> for ApiError {
+    fn from(err: crate::domain::services::BankReconciliationError) -> Self {
+        match err {
+            crate::domain::services::BankReconciliationError::NotFound => ApiError::NotFound,
+            err @ crate::domain::services::BankReconciliationError::NotPending(_) => ApiError::coded(ErrorCode::AlreadyProcessed, err.to_string()),
+            crate::domain::services::BankReconciliationError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::BankReconciliationError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ReceiptScanError> for ApiError {
+    fn from(err: crate::domain::services::ReceiptScanError) -> Self {
+        match err {
+            crate::domain::services::ReceiptScanError::NotFound => ApiError::NotFound,
+            err @ crate::domain::services::ReceiptScanError::NotConfigured => ApiError::coded(ErrorCode::NotConfigured, err.to_string()),
+            err @ crate::domain::services::ReceiptScanError::AlreadyConfirmed => ApiError::coded(ErrorCode::AlreadyProcessed, err.to_string()),
+            crate::domain::services::ReceiptScanError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ReceiptScanError::Ocr(msg) => ApiError::Upstream(msg),
+            crate::domain::services::ReceiptScanError::Storage(msg) => {
+                tracing::error!("Receipt storage error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::ReceiptScanError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::ClientStatementError> for ApiError {
+    fn from(err: crate::domain::services::ClientStatementError) -> Self {
+        match err {
+            crate::domain::services::ClientStatementError::NotFound => ApiError::NotFound,
+            crate::domain::services::ClientStatementError::Validation(msg) => ApiError::Validation(msg),
+            crate::domain::services::ClientStatementError::Export(msg) => {
+                tracing::error!("Statement export error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::ClientStatementError::Email(msg) => {
+                tracing::error!("Statement email error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::ClientStatementError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::EInvoiceError> for ApiError {
+    fn from(err: crate::domain::services::EInvoiceError) -> Self {
+        match err {
+            crate::domain::services::EInvoiceError::InvalidStatus(msg) => ApiError::coded(ErrorCode::InvalidStatus, msg),
+            crate::domain::services::EInvoiceError::NotExportable { ref problems, .. } => {
+                let details = serde_json::json!({ "missing": problems });
+                ApiError::with_details(ErrorCode::ValidationError, err.to_string(), details)
+            }
+            crate::domain::services::EInvoiceError::Invoice(err) => err.into(),
+            crate::domain::services::EInvoiceError::Export(msg) => {
+                tracing::error!("E-invoice export error: {}", msg);
+                ApiError::Internal
+            }
+            crate::domain::services::EInvoiceError::DatabaseError(msg) => ApiError::Database(msg),
+        }
+    }
+}
+
+impl From<crate::domain::services::EmailQueueError> for ApiError {
+    fn from(err: crate::domain::services::EmailQueueError) -> Self {
+        tracing::error!("Email queue error: {}", err);
+        match err {
+            crate::domain::services::EmailQueueError::RedisError(_) => {
+                ApiError::coded(ErrorCode::ServiceUnavailable, "The email queue's Redis store is unavailable")
+            }
+            _ => ApiError::Internal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_service_errors_keep_their_code_and_details() {
+        let mut errors = validator::ValidationErrors::new();
+        let mut error = validator::ValidationError::new("length");
+        error.add_param("min".into(), &8);
+        error.add_param("value".into(), &"hunter2");
+        errors.add("password", error);
+        let response = ApiError::from(errors).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error = &body(response).await["error"];
+        assert_eq!(error["code"], "VALIDATION_ERROR");
+        assert_eq!(error["message"], "Invalid fields: password");
+        let field = &error["details"]["fields"]["password"][0];
+        assert_eq!(field["code"], "length");
+        assert_eq!(field["params"]["min"], 8);
+        assert!(field["params"]["value"].is_null());
+
+        let response = ApiError::from(crate::domain::services::SyncError::CursorExpired(30)).into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let error = &body(response).await["error"];
+        assert_eq!(error["code"], "CURSOR_EXPIRED");
+        assert_eq!(error["details"]["max_age_days"], 30);
+
+        let response = ApiError::NotFound.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let error = &body(response).await["error"];
+        assert_eq!(error["code"], "NOT_FOUND");
+        assert!(error["details"].is_null());
+    }
+}
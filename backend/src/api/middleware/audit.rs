@@ -0,0 +1,222 @@
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{header, request::Parts, Method, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::middleware::client_ip::client_ip;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{AuditAction, AuditEntityType};
+use crate::domain::services::{AuditEvent, AuditOrigin, AuditService};
+use crate::domain::request_id;
+
+/// Create responses are read back for the new record's id
+const MAX_CREATE_RESPONSE_BYTES: usize = 1024 * 1024;
+const MAX_USER_AGENT_LENGTH: usize = 512;
+
+/// What an audited request does to which record
+#[derive(Debug, PartialEq)]
+struct AuditedRoute {
+    action: AuditAction,
+    entity_type: AuditEntityType,
+    /// From the path; creates take it from the response
+    entity_id: Option<Uuid>,
+    /// Settings section, e.g. "invoice" for PUT /settings/invoice
+    section: Option<String>,
+}
+
+impl AuditedRoute {
+    fn new(action: AuditAction, entity_type: AuditEntityType, id: Option<&str>) -> Option<Self> {
+        let entity_id = match id {
+            Some(id) => Some(Uuid::parse_str(id).ok()?),
+            None => None,
+        };
+        Some(Self { action, entity_type, entity_id, section: None })
+    }
+}
+
+/// Changes to invoices, clients, payments, expenses and settings that go in the
+/// audit trail
+fn audited_route(method: &Method, path: &str) -> Option<AuditedRoute> {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (&Method::POST, ["invoices"]) | (&Method::POST, ["invoices", "consolidate"]) => {
+            AuditedRoute::new(AuditAction::Create, AuditEntityType::Invoice, None)
+        }
+        (&Method::PUT, ["invoices", id]) => AuditedRoute::new(AuditAction::Update, AuditEntityType::Invoice, Some(id)),
+        (&Method::DELETE, ["invoices", id]) => AuditedRoute::new(AuditAction::Delete, AuditEntityType::Invoice, Some(id)),
+        (&Method::POST, ["invoices", id, "send" | "send-whatsapp" | "remind" | "send-confirmation"]) => {
+            AuditedRoute::new(AuditAction::Send, AuditEntityType::Invoice, Some(id))
+        }
+        (&Method::POST, ["invoices", id, "pay"]) => AuditedRoute::new(AuditAction::Payment, AuditEntityType::Invoice, Some(id)),
+        (&Method::POST, ["invoices", id, "correct" | "view" | "cancel" | "restore"]) => AuditedRoute::new(AuditAction::Update, AuditEntityType::Invoice, Some(id)),
+
+        (&Method::POST, ["clients"]) => AuditedRoute::new(AuditAction::Create, AuditEntityType::Client, None),
+        (&Method::PUT, ["clients", id]) => AuditedRoute::new(AuditAction::Update, AuditEntityType::Client, Some(id)),
+        (&Method::DELETE, ["clients", id]) => AuditedRoute::new(AuditAction::Delete, AuditEntityType::Client, Some(id)),
+        (&Method::PUT, ["clients", id, "parent"])
+        | (&Method::POST | &Method::DELETE, ["clients", id, "archive"])
+        | (&Method::POST, ["clients", id, "restore"]) => AuditedRoute::new(AuditAction::Update, AuditEntityType::Client, Some(id)),
+
+        (&Method::POST, ["payments"]) => AuditedRoute::new(AuditAction::Create, AuditEntityType::Payment, None),
+        (&Method::POST, ["payments", id, "refund"]) => AuditedRoute::new(AuditAction::Refund, AuditEntityType::Payment, Some(id)),
+
+        (&Method::POST, ["expenses"]) => AuditedRoute::new(AuditAction::Create, AuditEntityType::Expense, None),
+        (&Method::PUT, ["expenses", id]) => AuditedRoute::new(AuditAction::Update, AuditEntityType::Expense, Some(id)),
+        (&Method::DELETE, ["expenses", id]) => AuditedRoute::new(AuditAction::Delete, AuditEntityType::Expense, Some(id)),
+
+        (&Method::PUT | &Method::POST | &Method::DELETE, ["settings", section @ ..]) if !section.is_empty() => {
+            Some(AuditedRoute {
+                action: AuditAction::Update,
+                entity_type: AuditEntityType::Settings,
+                entity_id: None,
+                section: Some(section.join("/")),
+            })
+        }
+        _ => None,
+    }
+}
+
+fn user_agent(parts: &Parts) -> Option<String> {
+    parts
+        .headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT_LENGTH).collect())
+}
+
+/// Records successful changes to invoices, clients, payments, expenses and
+/// settings in the audit trail, with the record as it was before and after.
+/// Runs inside idempotency so replayed responses aren't recorded twice.
+pub async fn audit_middleware(
+    State(audit): State<Arc<AuditService>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(route) = audited_route(req.method(), req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let (mut parts, body) = req.into_parts();
+    // Unauthenticated requests are rejected by the handler; nothing to record
+    let Ok(auth_user) = AuthUser::from_request_parts(&mut parts, &()).await else {
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+    let origin = AuditOrigin {
+        user_id: auth_user.user_id,
+        actor_id: Some(auth_user.owner_id),
+        ip_address: client_ip(&parts.headers, &parts.extensions).map(|ip| ip.to_string()),
+        user_agent: user_agent(&parts),
+        request_id: request_id::current(),
+    };
+
+    let entity_id = match route.entity_type {
+        AuditEntityType::Settings => Some(auth_user.user_id),
+        _ => route.entity_id,
+    };
+    let before = match entity_id {
+        Some(id) => snapshot(&audit, auth_user.user_id, route.entity_type, id).await,
+        None => None,
+    };
+
+    let response = next.run(Request::from_parts(parts, body)).await;
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let (response, entity_id) = match entity_id {
+        Some(id) => (response, Some(id)),
+        None => {
+            let (parts, body) = response.into_parts();
+            let Ok(body) = axum::body::to_bytes(body, MAX_CREATE_RESPONSE_BYTES).await else {
+                tracing::error!("Failed to read create response for the audit trail");
+                return Response::from_parts(parts, Body::empty());
+            };
+            let id = serde_json::from_slice::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| json.get("id")?.as_str().and_then(|id| Uuid::parse_str(id).ok()));
+            (Response::from_parts(parts, Body::from(body)), id)
+        }
+    };
+
+    let after = match entity_id {
+        Some(id) => snapshot(&audit, auth_user.user_id, route.entity_type, id).await,
+        None => None,
+    };
+    let event = AuditEvent {
+        action: route.action,
+        entity_type: route.entity_type,
+        entity_id,
+        before,
+        after,
+        context: route.section.map(|section| serde_json::json!({ "section": section })),
+    };
+    audit.record(&origin, event).await;
+
+    response
+}
+
+async fn snapshot(
+    audit: &AuditService,
+    user_id: Uuid,
+    entity_type: AuditEntityType,
+    entity_id: Uuid,
+) -> Option<serde_json::Value> {
+    match audit.snapshot(user_id, entity_type, entity_id).await {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            tracing::error!(entity_id = %entity_id, "Audit snapshot failed: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(method: Method, path: &str) -> Option<(AuditAction, AuditEntityType)> {
+        audited_route(&method, path).map(|route| (route.action, route.entity_type))
+    }
+
+    #[test]
+    fn test_changes_are_audited() {
+        let id = Uuid::new_v4();
+        assert_eq!(route(Method::POST, "/api/v1/invoices"), Some((AuditAction::Create, AuditEntityType::Invoice)));
+        assert_eq!(
+            route(Method::PUT, &format!("/api/v1/invoices/{}", id)),
+            Some((AuditAction::Update, AuditEntityType::Invoice))
+        );
+        assert_eq!(
+            route(Method::POST, &format!("/api/v1/invoices/{}/send", id)),
+            Some((AuditAction::Send, AuditEntityType::Invoice))
+        );
+        assert_eq!(
+            route(Method::POST, &format!("/api/v1/payments/{}/refund", id)),
+            Some((AuditAction::Refund, AuditEntityType::Payment))
+        );
+        assert_eq!(
+            route(Method::DELETE, &format!("/api/v1/expenses/{}/", id)),
+            Some((AuditAction::Delete, AuditEntityType::Expense))
+        );
+
+        let settings = audited_route(&Method::POST, "/api/v1/settings/invoice/logo").unwrap();
+        assert_eq!(settings.entity_type, AuditEntityType::Settings);
+        assert_eq!(settings.section.as_deref(), Some("invoice/logo"));
+    }
+
+    #[test]
+    fn test_reads_and_unknown_ids_are_not_audited() {
+        assert_eq!(route(Method::GET, "/api/v1/invoices"), None);
+        assert_eq!(route(Method::GET, "/api/v1/settings/invoice"), None);
+        assert_eq!(route(Method::PUT, "/api/v1/settings"), None);
+        assert_eq!(route(Method::PUT, "/api/v1/invoices/not-a-uuid"), None);
+        assert_eq!(route(Method::POST, "/api/v1/auth/login"), None);
+    }
+}
@@ -1,8 +1,8 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::FromRequestParts,
-    http::{request::Parts, StatusCode},
+    extract::{FromRequestParts, OriginalUri, Query},
+    http::{request::Parts, Method},
     response::IntoResponse,
     RequestPartsExt,
 };
@@ -11,11 +11,14 @@ use axum_extra::{
     TypedHeader,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
-use crate::domain::services::AuthService;
+use crate::api::error::{error_response, ErrorCode};
+use crate::domain::models::{AccessRole, Permission, BUSINESS_HEADER, BUSINESS_QUERY_PARAM};
+use crate::domain::services::{AccountantService, AuthService};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct _AuthClaims {
@@ -39,34 +42,42 @@ pub enum AuthExtractorError {
 
     #[error("Unauthorized")]
     Unauthorized,
+
+    #[error("Business not found")]
+    BusinessNotFound,
+
+    #[error("Access revoked")]
+    AccessRevoked,
+
+    #[error("Forbidden")]
+    Forbidden,
 }
 
 impl IntoResponse for AuthExtractorError {
     fn into_response(self) -> axum::response::Response {
-        let (status, message) = match self {
-            AuthExtractorError::MissingAuth => (StatusCode::UNAUTHORIZED, "Missing authorization header"),
-            AuthExtractorError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid token"),
-            AuthExtractorError::TokenExpired => (StatusCode::UNAUTHORIZED, "Token expired"),
-            AuthExtractorError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
+        let (code, message) = match self {
+            AuthExtractorError::MissingAuth => (ErrorCode::Unauthorized, "Missing authorization header"),
+            AuthExtractorError::InvalidToken => (ErrorCode::InvalidToken, "Invalid token"),
+            AuthExtractorError::TokenExpired => (ErrorCode::TokenExpired, "Token expired"),
+            AuthExtractorError::Unauthorized => (ErrorCode::Unauthorized, "Unauthorized"),
+            AuthExtractorError::BusinessNotFound => (ErrorCode::Forbidden, "Business not found"),
+            AuthExtractorError::AccessRevoked => (ErrorCode::AccessRevoked, "Access has expired or was revoked"),
+            AuthExtractorError::Forbidden => (ErrorCode::Forbidden, "Your access doesn't include this action"),
         };
 
-        let body = serde_json::json!({
-            "error": {
-                "code": "AUTH_ERROR",
-                "message": message,
-                "timestamp": chrono::Utc::now().to_rfc3339()
-            }
-        });
-
-        (status, axum::Json(body)).into_response()
+        error_response(code.status(), code, message, None)
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct AuthUser {
+    /// Account the request acts on: the selected business, or the login account
     pub user_id: Uuid,
+    /// The login account that owns the token
+    pub owner_id: Uuid,
     pub _email: String,
     pub _tier: String,
+    pub role: AccessRole,
 }
 
 impl<S> FromRequestParts<S> for AuthUser
@@ -95,13 +106,181 @@ where
             .verify_token(bearer.token())
             .map_err(|_| AuthExtractorError::InvalidToken)?;
 
-        let user_id = Uuid::parse_str(&claims.sub)
+        // Portal tokens belong to buyers, not to a seller account
+        if claims.role == AccessRole::Client {
+            return Err(AuthExtractorError::Forbidden);
+        }
+
+        let owner_id = Uuid::parse_str(&claims.sub)
             .map_err(|_| AuthExtractorError::InvalidToken)?;
 
+        // Scoped roles: the grant must still be live and cover this route
+        if claims.role != AccessRole::Owner {
+            let grant_id = claims.grant.ok_or(AuthExtractorError::InvalidToken)?;
+            let accountants = parts
+                .extensions
+                .get::<Arc<AccountantService>>()
+                .ok_or(AuthExtractorError::Unauthorized)?;
+            let active = accountants
+                .is_active(owner_id, grant_id)
+                .await
+                .map_err(|_| AuthExtractorError::Unauthorized)?;
+            if !active {
+                return Err(AuthExtractorError::AccessRevoked);
+            }
+
+            let path = parts
+                .extensions
+                .get::<OriginalUri>()
+                .map(|uri| uri.path().to_string())
+                .unwrap_or_else(|| parts.uri.path().to_string());
+            if !claims.role.allows(required_permission(&parts.method, &path)) {
+                return Err(AuthExtractorError::Forbidden);
+            }
+
+            // Grants are per business, so the business switcher doesn't apply
+            return Ok(AuthUser {
+                user_id: owner_id,
+                owner_id,
+                _email: claims.email,
+                _tier: claims.tier,
+                role: claims.role,
+            });
+        }
+
+        // Business switcher: scope the request to one of the owner's businesses
+        let user_id = match requested_business(parts)? {
+            Some(business_id) if business_id != owner_id => {
+                let owned = auth_service
+                    .owns_business(owner_id, business_id)
+                    .await
+                    .map_err(|_| AuthExtractorError::Unauthorized)?;
+                if !owned {
+                    return Err(AuthExtractorError::BusinessNotFound);
+                }
+                business_id
+            }
+            _ => owner_id,
+        };
+
         Ok(AuthUser {
             user_id,
+            owner_id,
             _email: claims.email,
             _tier: claims.tier,
+            role: claims.role,
         })
     }
 }
+
+/// Buyer signed in to the client portal
+#[derive(Debug, Clone)]
+pub struct PortalUser {
+    pub account_id: Uuid,
+    pub email: String,
+}
+
+impl<S> FromRequestParts<S> for PortalUser
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthExtractorError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let TypedHeader(Authorization(bearer)) = parts
+            .extract::<TypedHeader<Authorization<Bearer>>>()
+            .await
+            .map_err(|_| AuthExtractorError::MissingAuth)?;
+
+        let auth_service = parts
+            .extensions
+            .get::<Arc<AuthService>>()
+            .ok_or(AuthExtractorError::Unauthorized)?;
+
+        let claims = auth_service
+            .verify_token(bearer.token())
+            .map_err(|_| AuthExtractorError::InvalidToken)?;
+
+        // Seller and accountant tokens don't open the portal
+        if claims.role != AccessRole::Client {
+            return Err(AuthExtractorError::Forbidden);
+        }
+
+        let account_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| AuthExtractorError::InvalidToken)?;
+
+        Ok(PortalUser {
+            account_id,
+            email: claims.email,
+        })
+    }
+}
+
+/// Permission a request needs. Only the read-only routes open to accountants are
+/// listed; everything else requires full access to the account.
+fn required_permission(method: &Method, path: &str) -> Permission {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (method, segments.as_slice()) {
+        (&Method::POST, ["reports", "export"]) => Permission::ExportReports,
+        (&Method::POST, ["reports", "custom"]) => Permission::ExportReports,
+        (&Method::GET, ["reports", ..]) => Permission::ViewReports,
+        (&Method::GET, ["invoices"]) => Permission::ListInvoices,
+        (&Method::GET, ["invoices", _, "pdf"]) => Permission::DownloadInvoicePdf,
+        _ => Permission::ManageAccount,
+    }
+}
+
+/// Business selected by the `X-Business-Id` header, falling back to the `business_id` query parameter
+fn requested_business(parts: &Parts) -> Result<Option<Uuid>, AuthExtractorError> {
+    let from_header = parts
+        .headers
+        .get(BUSINESS_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let from_query = || {
+        Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .ok()
+            .and_then(|Query(mut params)| params.remove(BUSINESS_QUERY_PARAM))
+    };
+
+    match from_header.or_else(from_query) {
+        Some(raw) if !raw.trim().is_empty() => Uuid::parse_str(raw.trim())
+            .map(Some)
+            .map_err(|_| AuthExtractorError::BusinessNotFound),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_permission_maps_read_only_routes() {
+        assert_eq!(required_permission(&Method::GET, "/api/v1/reports/income"), Permission::ViewReports);
+        assert_eq!(required_permission(&Method::POST, "/api/v1/reports/export"), Permission::ExportReports);
+        assert_eq!(required_permission(&Method::POST, "/api/v1/reports/custom"), Permission::ExportReports);
+        assert_eq!(required_permission(&Method::GET, "/api/v1/invoices"), Permission::ListInvoices);
+        assert_eq!(
+            required_permission(&Method::GET, "/api/v1/invoices/6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f/pdf"),
+            Permission::DownloadInvoicePdf
+        );
+    }
+
+    #[test]
+    fn test_required_permission_defaults_to_full_access() {
+        assert_eq!(required_permission(&Method::GET, "/api/v1/clients"), Permission::ManageAccount);
+        assert_eq!(required_permission(&Method::POST, "/api/v1/invoices"), Permission::ManageAccount);
+        assert_eq!(
+            required_permission(&Method::POST, "/api/v1/invoices/6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f/send"),
+            Permission::ManageAccount
+        );
+        assert_eq!(required_permission(&Method::POST, "/api/v1/reports/income"), Permission::ManageAccount);
+    }
+}
@@ -0,0 +1,143 @@
+use axum::{
+    extract::ConnectInfo,
+    http::{request::Parts, Extensions, HeaderMap},
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use crate::api::middleware::scrape_auth::{parse_ranges, IpRange};
+
+/// Reverse proxies allowed to report the client's address.
+///
+/// `TRUSTED_PROXIES` is a comma-separated list of addresses or CIDR ranges, e.g.
+/// `10.0.0.0/8,127.0.0.1`. X-Forwarded-For is only read when the connecting peer
+/// is one of them; otherwise the peer itself is the client. Unset trusts nobody.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies {
+    ranges: Vec<IpRange>,
+}
+
+impl TrustedProxies {
+    pub fn from_env() -> Self {
+        std::env::var("TRUSTED_PROXIES")
+            .map(|list| Self::parse(&list))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(list: &str) -> Self {
+        Self { ranges: parse_ranges("TRUSTED_PROXIES", list) }
+    }
+
+    fn trusts(&self, ip: IpAddr) -> bool {
+        self.ranges.iter().any(|range| range.contains(ip))
+    }
+
+    /// The client behind `peer`. X-Forwarded-For is walked from the right, since
+    /// each proxy appends the address it saw; the first hop that isn't a trusted
+    /// proxy is the client. Anything left of it is client-controlled.
+    pub fn resolve(&self, headers: &HeaderMap, peer: IpAddr) -> IpAddr {
+        let mut client = peer.to_canonical();
+        if !self.trusts(client) {
+            return client;
+        }
+
+        let hops: Vec<&str> = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .collect();
+        for hop in hops.into_iter().rev() {
+            match hop.trim().parse::<IpAddr>() {
+                Ok(ip) => client = ip.to_canonical(),
+                Err(_) => break,
+            }
+            if !self.trusts(client) {
+                break;
+            }
+        }
+        client
+    }
+}
+
+/// The client's address: the connecting peer, or who it forwarded for when it's
+/// a trusted proxy. None without connection info, e.g. in tests.
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<IpAddr> {
+    let peer = extensions.get::<ConnectInfo<SocketAddr>>()?.0.ip();
+    Some(match extensions.get::<Arc<TrustedProxies>>() {
+        Some(proxies) => proxies.resolve(headers, peer),
+        None => peer.to_canonical(),
+    })
+}
+
+/// The client's address for handlers, as the audit trail records it
+pub struct ClientIp(pub Option<String>);
+
+impl<S> axum::extract::FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(client_ip(&parts.headers, &parts.extensions).map(|ip| ip.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn forwarded(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn test_spoofed_forwarded_for_from_untrusted_peer_is_ignored() {
+        let proxies = TrustedProxies::parse("10.0.0.0/8");
+        let headers = forwarded("1.2.3.4");
+
+        assert_eq!(proxies.resolve(&headers, ip("203.0.113.7")), ip("203.0.113.7"));
+        assert_eq!(TrustedProxies::default().resolve(&headers, ip("10.0.0.1")), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_trusted_proxy_reports_the_client() {
+        let proxies = TrustedProxies::parse("10.0.0.0/8, 127.0.0.1");
+
+        assert_eq!(proxies.resolve(&forwarded("203.0.113.7"), ip("10.0.0.1")), ip("203.0.113.7"));
+        // Whatever the client sent is left of its real address and never reached
+        assert_eq!(
+            proxies.resolve(&forwarded("1.2.3.4, 203.0.113.7, 10.0.0.2"), ip("127.0.0.1")),
+            ip("203.0.113.7")
+        );
+        // IPv4-mapped peers match IPv4 ranges
+        assert_eq!(proxies.resolve(&forwarded("203.0.113.7"), ip("::ffff:10.0.0.1")), ip("203.0.113.7"));
+    }
+
+    #[test]
+    fn test_unusable_forwarded_for_falls_back_to_the_nearest_hop() {
+        let proxies = TrustedProxies::parse("10.0.0.0/8");
+
+        assert_eq!(proxies.resolve(&HeaderMap::new(), ip("10.0.0.1")), ip("10.0.0.1"));
+        assert_eq!(proxies.resolve(&forwarded("1.2.3.4, garbage"), ip("10.0.0.1")), ip("10.0.0.1"));
+        assert_eq!(proxies.resolve(&forwarded("10.0.0.3"), ip("10.0.0.1")), ip("10.0.0.3"));
+    }
+
+    #[test]
+    fn test_client_ip_needs_connection_info() {
+        let mut extensions = Extensions::new();
+        let headers = forwarded("1.2.3.4");
+        assert_eq!(client_ip(&headers, &extensions), None);
+
+        extensions.insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 443))));
+        assert_eq!(client_ip(&headers, &extensions), Some(ip("203.0.113.7")));
+    }
+}
@@ -0,0 +1,84 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::error::{error_response, ErrorCode};
+
+/// Longest plain-text error body carried over as the message
+const MAX_MESSAGE_BYTES: usize = 16 * 1024;
+
+/// Puts error responses that weren't built from `ApiError` — extractor
+/// rejections, unmatched routes, body limits, timeouts — in the same envelope,
+/// so every error a client sees has a code. The status and headers are kept.
+pub async fn error_envelope_middleware(req: Request<Body>, next: Next) -> Response {
+    let response = next.run(req).await;
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let message = match to_bytes(body, MAX_MESSAGE_BYTES).await {
+        Ok(bytes) if !bytes.is_empty() => String::from_utf8_lossy(&bytes).trim().to_string(),
+        _ => status.canonical_reason().unwrap_or("Error").to_string(),
+    };
+
+    let (envelope, body) = error_response(status, ErrorCode::for_status(status), &message, None).into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    if let Some(content_type) = envelope.headers.get(header::CONTENT_TYPE) {
+        parts.headers.insert(header::CONTENT_TYPE, content_type.clone());
+    }
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::post, Json, Router};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::api::error::ApiError;
+
+    async fn send(app: &Router, uri: &str, body: &str) -> (StatusCode, Value) {
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_rejections_and_unmatched_routes_get_the_envelope() {
+        let app = Router::new()
+            .route("/items", post(|Json(item): Json<Value>| async move { Json(item) }))
+            .route("/conflict", post(|| async { Err::<(), _>(ApiError::Conflict("Taken".to_string())) }))
+            .layer(axum::middleware::from_fn(error_envelope_middleware));
+
+        let (status, body) = send(&app, "/items", "{not json").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"]["code"], "BAD_REQUEST");
+        assert!(body["error"]["message"].as_str().unwrap().contains("JSON"));
+
+        let (status, body) = send(&app, "/missing", "{}").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+
+        // ApiError bodies pass through untouched
+        let (status, body) = send(&app, "/conflict", "{}").await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"]["code"], "CONFLICT");
+        assert_eq!(body["error"]["message"], "Taken");
+    }
+}
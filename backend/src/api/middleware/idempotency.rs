@@ -0,0 +1,247 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::rate_limit::caller_key;
+use crate::domain::services::{SharedClock, Shutdown};
+use crate::infrastructure::repositories::{IdempotencyClaim, IdempotencyRepository, StoredResponse};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses replayed from an earlier request with the same key
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+const MAX_KEY_LENGTH: usize = 255;
+/// Matches the global request body limit
+const MAX_REQUEST_BODY_BYTES: usize = 10 * 1024 * 1024;
+const MAX_STORED_RESPONSE_BYTES: usize = 1024 * 1024;
+/// How long a key keeps replaying its response
+const KEY_TTL_HOURS: i64 = 24;
+/// An in-flight claim older than this belongs to a request that never finished
+const IN_FLIGHT_TIMEOUT_SECS: i64 = 60;
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Create endpoints that honour the Idempotency-Key header
+fn is_idempotent_route(path: &str) -> bool {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    matches!(
+        segments.as_slice(),
+        ["invoices"] | ["payments"] | ["guest", "pay", _] | ["guest", "v1", "pay", _] | ["portal", "invoices", _, "pay"]
+    )
+}
+
+fn parse_key(value: &HeaderValue) -> Result<String, String> {
+    let key = value
+        .to_str()
+        .map_err(|_| "Idempotency-Key must be visible ASCII".to_string())?
+        .trim();
+
+    if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+        return Err(format!("Idempotency-Key must be 1-{} characters", MAX_KEY_LENGTH));
+    }
+    Ok(key.to_string())
+}
+
+/// Keys are per user, or per payment link for guests, and per endpoint
+fn scope(req: &Request<Body>) -> String {
+    let caller = caller_key(req).filter(|caller| caller.starts_with("user:"));
+    format!("{} {}", caller.as_deref().unwrap_or("guest"), req.uri().path().trim_end_matches('/'))
+}
+
+fn request_hash(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status_code).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored.content_type.and_then(|v| HeaderValue::from_str(&v).ok()) {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+#[derive(Clone)]
+pub struct IdempotencyMiddleware {
+    repo: Arc<IdempotencyRepository>,
+    clock: SharedClock,
+}
+
+impl IdempotencyMiddleware {
+    pub fn new(repo: Arc<IdempotencyRepository>, clock: SharedClock) -> Self {
+        Self { repo, clock }
+    }
+
+    /// Periodically drop keys past their TTL
+    pub fn start_purge_worker(self, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                let expired_before = self.clock.now() - chrono::Duration::hours(KEY_TTL_HOURS);
+                match self.repo.purge_expired(expired_before).await {
+                    Ok(0) => {}
+                    Ok(purged) => tracing::info!("Purged {} expired idempotency key(s)", purged),
+                    Err(e) => tracing::error!("Idempotency key purge failed: {}", e),
+                }
+            }
+        });
+    }
+
+    async fn claim(&self, scope: &str, key: &str, request_hash: &str) -> Result<IdempotencyClaim, sqlx::Error> {
+        let now = self.clock.now();
+        self.repo
+            .claim(
+                scope,
+                key,
+                request_hash,
+                now,
+                now - chrono::Duration::hours(KEY_TTL_HOURS),
+                now - chrono::Duration::seconds(IN_FLIGHT_TIMEOUT_SECS),
+            )
+            .await
+    }
+
+    async fn release(&self, scope: &str, key: &str) {
+        if let Err(e) = self.repo.release(scope, key).await {
+            tracing::error!(scope = %scope, "Failed to release idempotency key: {}", e);
+        }
+    }
+}
+
+/// Replays the stored response when POST /invoices, POST /payments or a guest
+/// payment is retried with the same Idempotency-Key. Only successful responses
+/// are kept; a failed request frees the key so the client can try again.
+pub async fn idempotency_middleware(
+    State(idempotency): State<IdempotencyMiddleware>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST || !is_idempotent_route(req.uri().path()) {
+        return next.run(req).await;
+    }
+    let key = match req.headers().get(IDEMPOTENCY_KEY_HEADER).map(parse_key) {
+        None => return next.run(req).await,
+        Some(Ok(key)) => key,
+        Some(Err(msg)) => return ApiError::BadRequest(msg).into_response(),
+    };
+    let scope = scope(&req);
+
+    let (parts, body) = req.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_REQUEST_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return ApiError::BadRequest("Could not read request body".to_string()).into_response(),
+    };
+
+    match idempotency.claim(&scope, &key, &request_hash(&body)).await {
+        Ok(IdempotencyClaim::Claimed) => {}
+        Ok(IdempotencyClaim::Completed(stored)) => return replay(stored),
+        Ok(IdempotencyClaim::InFlight) => {
+            let mut response = ApiError::Conflict(
+                "A request with this Idempotency-Key is still being processed".to_string(),
+            )
+            .into_response();
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(1));
+            return response;
+        }
+        Ok(IdempotencyClaim::Mismatch) => {
+            return ApiError::BadRequest(
+                "Idempotency-Key was already used with a different request body".to_string(),
+            )
+            .into_response();
+        }
+        Err(e) => return ApiError::from(e).into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if !response.status().is_success() {
+        idempotency.release(&scope, &key).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_STORED_RESPONSE_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for idempotency key: {}", e);
+            idempotency.release(&scope, &key).await;
+            return ApiError::Internal.into_response();
+        }
+    };
+
+    let stored = StoredResponse {
+        status_code: parts.status.as_u16(),
+        content_type: parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        body: body.to_vec(),
+    };
+    if let Err(e) = idempotency.repo.complete(&scope, &key, &stored).await {
+        tracing::error!(scope = %scope, "Failed to store idempotent response: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_create_endpoints_are_idempotent() {
+        assert!(is_idempotent_route("/api/v1/invoices"));
+        assert!(is_idempotent_route("/api/v1/invoices/"));
+        assert!(is_idempotent_route("/api/v1/payments"));
+        assert!(is_idempotent_route("/api/v1/guest/pay/guest_abc_123"));
+        assert!(is_idempotent_route("/api/v1/guest/v1/pay/guest_abc_123"));
+        assert!(is_idempotent_route("/api/v1/portal/invoices/6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f/pay"));
+
+        assert!(!is_idempotent_route("/api/v1/invoices/consolidate"));
+        assert!(!is_idempotent_route("/api/v1/payments/refund"));
+        assert!(!is_idempotent_route("/api/v1/guest/invoice/guest_abc_123"));
+        assert!(!is_idempotent_route("/api/v1/portal/invoices"));
+        assert!(!is_idempotent_route("/api/v1/clients"));
+    }
+
+    #[test]
+    fn test_key_must_be_short_and_non_empty() {
+        assert_eq!(parse_key(&HeaderValue::from_static(" retry-1 ")), Ok("retry-1".to_string()));
+        assert!(parse_key(&HeaderValue::from_static("   ")).is_err());
+
+        let long = HeaderValue::from_str(&"k".repeat(MAX_KEY_LENGTH + 1)).unwrap();
+        assert!(parse_key(&long).is_err());
+    }
+
+    #[test]
+    fn test_request_hash_tracks_body() {
+        assert_eq!(request_hash(b"{\"amount\":10}"), request_hash(b"{\"amount\":10}"));
+        assert_ne!(request_hash(b"{\"amount\":10}"), request_hash(b"{\"amount\":11}"));
+    }
+
+    #[test]
+    fn test_replay_restores_response_and_marks_it() {
+        let response = replay(StoredResponse {
+            status_code: 201,
+            content_type: Some("application/json".to_string()),
+            body: b"{}".to_vec(),
+        });
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+    }
+}
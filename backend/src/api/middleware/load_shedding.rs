@@ -0,0 +1,107 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::api::error::ApiError;
+use crate::domain::services::{HealthStatus, MonitoringService};
+
+/// Suggested wait before retrying a shed request; matches the health check interval
+const RETRY_AFTER_SECS: u32 = 15;
+
+/// Reports, exports, PDF generation and invoice batches: the endpoints turned away while the
+/// instance is unhealthy. Everything else keeps being served.
+fn is_expensive(path: &str) -> bool {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    matches!(
+        segments.as_slice(),
+        ["reports", ..]
+            | ["invoices", _, "pdf"]
+            | ["invoices", "batch"]
+            | ["clients", _, "statement"]
+            | ["budgets", _, "report"]
+            | ["payouts", "report"]
+            | ["support", "invoices", _, "anonymized"]
+    )
+}
+
+/// Rejects expensive requests with 503 while the last health check found the
+/// instance unhealthy, so a struggling instance still serves lightweight reads
+pub async fn load_shedding_middleware(
+    State(monitoring): State<Arc<MonitoringService>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if is_expensive(req.uri().path()) {
+        if let HealthStatus::Unhealthy(reason) = monitoring.get_health_status().await {
+            tracing::warn!(path = %req.uri().path(), reason = %reason, "Shedding expensive request");
+            let mut response = ApiError::ServiceUnavailable.into_response();
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(RETRY_AFTER_SECS));
+            return response;
+        }
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(monitoring: Arc<MonitoringService>) -> Router {
+        Router::new()
+            .route("/api/v1/reports/income", get(|| async { "report" }))
+            .route("/api/v1/invoices/{id}", get(|| async { "invoice" }))
+            .layer(axum::middleware::from_fn_with_state(monitoring, load_shedding_middleware))
+    }
+
+    async fn status(app: &Router, path: &str) -> StatusCode {
+        let request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_expensive_endpoints() {
+        assert!(is_expensive("/api/v1/reports/income"));
+        assert!(is_expensive("/api/v1/reports/export"));
+        assert!(is_expensive("/api/v1/invoices/6f1c/pdf"));
+        assert!(is_expensive("/api/v1/invoices/batch"));
+        assert!(is_expensive("/api/v1/clients/6f1c/statement/"));
+        assert!(is_expensive("/api/v1/support/invoices/6f1c/anonymized"));
+
+        assert!(!is_expensive("/api/v1/invoices"));
+        assert!(!is_expensive("/api/v1/invoices/6f1c"));
+        assert!(!is_expensive("/api/v1/clients/6f1c"));
+        assert!(!is_expensive("/metrics/health"));
+    }
+
+    #[tokio::test]
+    async fn test_sheds_only_expensive_requests_while_unhealthy() {
+        let monitoring = Arc::new(MonitoringService::new());
+        let app = app(monitoring.clone());
+
+        assert_eq!(status(&app, "/api/v1/reports/income").await, StatusCode::OK);
+
+        // Degraded instances keep serving everything
+        monitoring.set_health_status(HealthStatus::Degraded("Slow".to_string())).await;
+        assert_eq!(status(&app, "/api/v1/reports/income").await, StatusCode::OK);
+
+        monitoring.set_health_status(HealthStatus::Unhealthy("Database down".to_string())).await;
+        let request = Request::builder().uri("/api/v1/reports/income").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "15");
+        assert_eq!(status(&app, "/api/v1/invoices/6f1c").await, StatusCode::OK);
+
+        monitoring.set_health_status(HealthStatus::Healthy).await;
+        assert_eq!(status(&app, "/api/v1/reports/income").await, StatusCode::OK);
+    }
+}
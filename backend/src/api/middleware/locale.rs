@@ -0,0 +1,34 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::domain::i18n::Locale;
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// Negotiates the locale from `Accept-Language` for the rest of the request, so
+/// error responses can be translated where they are built
+pub async fn locale_middleware(request: Request<Body>, next: Next) -> Response {
+    let locale = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(Locale::negotiate)
+        .unwrap_or_default();
+
+    let mut response = REQUEST_LOCALE.scope(locale, next.run(request)).await;
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+/// Locale of the request being handled; English outside a request
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE.try_with(|locale| *locale).unwrap_or_default()
+}
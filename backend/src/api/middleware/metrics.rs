@@ -1,8 +1,7 @@
-#![allow(dead_code)]
-
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    extract::{MatchedPath, State},
+    http::{Method, Request},
     middleware::Next,
     response::Response,
 };
@@ -11,41 +10,49 @@ use std::time::Instant;
 
 use crate::domain::services::MetricsService;
 
-/// Metrics middleware that tracks HTTP request metrics
+/// Records each request's latency and status under its matched route pattern,
+/// so `/invoices/{id}` is one series however many invoices there are
 pub async fn metrics_middleware(
-    metrics: Arc<MetricsService>,
+    State(metrics): State<Arc<MetricsService>>,
     req: Request<Body>,
     next: Next,
-) -> Result<Response, StatusCode> {
-    let start_time = Instant::now();
-    let method = req.method().to_string();
-    let uri = req.uri().path().to_string();
-
-    // Increment active requests
-    metrics.http_requests_active.inc();
-
-    // Process the request
+) -> Response {
+    let method = match *req.method() {
+        Method::GET | Method::POST | Method::PUT | Method::PATCH | Method::DELETE | Method::HEAD | Method::OPTIONS => {
+            req.method().to_string()
+        }
+        _ => "OTHER".to_string(),
+    };
+    let route = req.extensions().get::<MatchedPath>().map(|path| path.as_str().to_string());
+
+    let _active = metrics.start_request();
+    let started = Instant::now();
     let response = next.run(req).await;
+    metrics.observe_http_request(&method, route.as_deref(), response.status().as_u16(), started.elapsed());
 
-    // Record metrics
-    let duration = start_time.elapsed().as_secs_f64();
-    metrics.http_requests_duration.observe(duration);
-    metrics.http_requests_total.inc();
-    metrics.http_requests_active.dec();
+    response
+}
 
-    // Record errors
-    if response.status().is_server_error() || response.status().is_client_error() {
-        metrics.record_error();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_requests_are_labeled_by_route_pattern() {
+        let metrics = Arc::new(MetricsService::new().unwrap());
+        let app = Router::new()
+            .nest("/api", Router::new().route("/items/{id}", get(|| async { "ok" })))
+            .layer(axum::middleware::from_fn_with_state(metrics.clone(), metrics_middleware));
+
+        for uri in ["/api/items/1", "/api/items/2", "/nowhere"] {
+            app.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        }
+
+        let text = metrics.render().await.unwrap();
+        assert!(text.contains(r#"http_requests_total{method="GET",route="/api/items/{id}",status="200"} 2"#));
+        assert!(text.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#));
+        assert!(text.contains(r#"http_request_duration_seconds_count{method="GET",route="/api/items/{id}",status="200"} 2"#));
     }
-
-    // Log the request
-    tracing::info!(
-        method = %method,
-        uri = %uri,
-        status = %response.status(),
-        duration = ?duration,
-        "HTTP request completed"
-    );
-
-    Ok(response)
 }
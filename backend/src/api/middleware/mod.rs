@@ -2,5 +2,15 @@ pub mod auth;
 pub mod logging;
 pub mod rate_limit;
 pub mod metrics;
+pub mod scrape_auth;
+pub mod sparse_fields;
+pub mod locale;
+pub mod load_shedding;
+pub mod idempotency;
+pub mod audit;
+pub mod client_ip;
+pub mod report_cache;
+pub mod error_envelope;
 
 pub use auth::*;
+pub mod request_id;
@@ -2,85 +2,547 @@
 
 use axum::{
     body::Body,
-    http::{Request, StatusCode},
+    extract::State,
+    http::{HeaderMap, HeaderValue, Method, Request},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use governor::{
-    clock::DefaultClock,
-    state::{keyed::DefaultKeyedStateStore},
-    Quota, RateLimiter,
-};
-use nonzero_ext::nonzero;
-use std::sync::Arc;
+use serde::Serialize;
+use utoipa::ToSchema;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::api::middleware::client_ip;
+use crate::domain::services::{AuthService, RedisService, SharedClock};
+
+/// Entries kept in memory before expired windows are pruned
+const MAX_TRACKED_WINDOWS: usize = 10_000;
+
+/// The longest window; counters older than two of them no longer matter
+const LONGEST_WINDOW_SECONDS: i64 = 3600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimits {
+    pub per_minute: u32,
+    pub per_hour: u32,
+}
+
+impl RateLimits {
+    fn from_env(prefix: &str, per_minute: u32, per_hour: u32) -> Self {
+        let var = |name: &str, default: u32| {
+            std::env::var(format!("{}_{}", prefix, name))
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            per_minute: var("PER_MINUTE", per_minute),
+            per_hour: var("PER_HOUR", per_hour),
+        }
+    }
+
+    fn scaled(self, factor: u32) -> Self {
+        Self {
+            per_minute: self.per_minute.saturating_mul(factor),
+            per_hour: self.per_hour.saturating_mul(factor),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Reads, per user (RATE_LIMIT_PER_MINUTE / RATE_LIMIT_PER_HOUR)
+    pub read: RateLimits,
+    /// Creates, updates and deletes, per user (RATE_LIMIT_WRITE_PER_MINUTE / _PER_HOUR)
+    pub write: RateLimits,
+    /// Sign-in, registration and password resets, per IP (RATE_LIMIT_AUTH_PER_MINUTE / _PER_HOUR)
+    pub auth: RateLimits,
+    /// How many users' worth of reads and writes one IP may send, for offices
+    /// behind one address (RATE_LIMIT_IP_FACTOR)
+    pub ip_factor: u32,
+    /// When false, limits are only reported through headers (soft limiting)
+    pub enforce: bool,
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        Self {
+            read: RateLimits::from_env("RATE_LIMIT", 100, 2000),
+            write: RateLimits::from_env("RATE_LIMIT_WRITE", 60, 1000),
+            auth: RateLimits::from_env("RATE_LIMIT_AUTH", 10, 50),
+            ip_factor: std::env::var("RATE_LIMIT_IP_FACTOR")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|factor| *factor > 0)
+                .unwrap_or(5),
+            enforce: std::env::var("RATE_LIMIT_ENFORCE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+        }
+    }
 
-use crate::domain::services::RedisService;
+    fn limits(&self, category: RateLimitCategory, caller: &RateLimitCaller) -> RateLimits {
+        match (category, caller) {
+            (RateLimitCategory::Auth, _) => self.auth,
+            (RateLimitCategory::Write, RateLimitCaller::User(_)) => self.write,
+            (RateLimitCategory::Write, RateLimitCaller::Ip(_)) => self.write.scaled(self.ip_factor),
+            (RateLimitCategory::Read, RateLimitCaller::User(_)) => self.read,
+            (RateLimitCategory::Read, RateLimitCaller::Ip(_)) => self.read.scaled(self.ip_factor),
+        }
+    }
+
+    fn windows(&self, category: RateLimitCategory, caller: &RateLimitCaller) -> [(&'static str, u32, i64); 2] {
+        let limits = self.limits(category, caller);
+        [("minute", limits.per_minute, 60), ("hour", limits.per_hour, LONGEST_WINDOW_SECONDS)]
+    }
+}
+
+/// Each category has its own limits and counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitCategory {
+    /// Sign-in, registration and password resets: guessable, so much stricter
+    Auth,
+    Write,
+    Read,
+}
+
+impl RateLimitCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitCategory::Auth => "auth",
+            RateLimitCategory::Write => "write",
+            RateLimitCategory::Read => "read",
+        }
+    }
+
+    pub fn for_request(method: &Method, path: &str) -> Self {
+        let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match (method, segments.as_slice()) {
+            (&Method::POST, ["auth", "login" | "register" | "forgot-password" | "reset-password"])
+            | (&Method::POST, ["portal", "sign-in", ..]) => RateLimitCategory::Auth,
+            (&Method::GET | &Method::HEAD | &Method::OPTIONS, _) => RateLimitCategory::Read,
+            _ => RateLimitCategory::Write,
+        }
+    }
+}
+
+/// Who a request is counted against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitCaller {
+    User(String),
+    Ip(String),
+}
+
+impl RateLimitCaller {
+    pub fn key(&self) -> String {
+        match self {
+            RateLimitCaller::User(user_id) => format!("user:{}", user_id),
+            RateLimitCaller::Ip(ip) => format!("ip:{}", ip),
+        }
+    }
+}
+
+/// Current state of one sliding-window bucket for a caller
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RateLimitBucket {
+    pub window: &'static str,
+    pub limit: u32,
+    /// Requests in the sliding window ending now
+    pub used: u32,
+    pub remaining: u32,
+    /// Seconds until the current window ends
+    pub reset: i64,
+    /// Seconds until another request fits, once the limit is exceeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<i64>,
+}
+
+impl RateLimitBucket {
+    fn is_exceeded(&self) -> bool {
+        self.used > self.limit
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RateLimitStatus {
+    pub caller: String,
+    pub category: RateLimitCategory,
+    pub enforced: bool,
+    pub buckets: Vec<RateLimitBucket>,
+}
+
+/// (category:caller, window, window start) -> requests counted in that window
+type WindowCounters = HashMap<(String, &'static str, i64), u32>;
+
+/// Requests in the sliding window ending `elapsed` seconds into the current
+/// fixed window: all of the current window plus the part of the previous one
+/// it still overlaps, rounded up
+fn sliding_count(previous: u32, current: u32, elapsed: i64, seconds: i64) -> u32 {
+    let overlap = (seconds - elapsed).clamp(0, seconds);
+    ((previous as i64 * overlap + seconds - 1) / seconds) as u32 + current
+}
+
+/// Seconds until the sliding count drops below `limit`, so one more request fits
+fn retry_after(previous: u32, current: u32, elapsed: i64, seconds: i64, limit: u32) -> i64 {
+    let room = limit.saturating_sub(1) as f64;
+    let wait = if current as f64 <= room && previous > 0 {
+        // The previous window's share fades out before this window ends
+        seconds as f64 - elapsed as f64 - (room - current as f64) * seconds as f64 / previous as f64
+    } else {
+        // This window has to become the previous one and fade in turn
+        2.0 * seconds as f64 - elapsed as f64 - room * seconds as f64 / current.max(1) as f64
+    };
+    (wait.ceil() as i64).clamp(1, 2 * seconds)
+}
 
 #[derive(Clone)]
 pub struct RateLimitMiddleware {
-    limiter: Arc<RateLimiter<String, DefaultKeyedStateStore<String>, DefaultClock>>,
+    config: RateLimitConfig,
+    counters: Arc<Mutex<WindowCounters>>,
     redis: Option<Arc<RedisService>>,
+    clock: SharedClock,
 }
 
 impl RateLimitMiddleware {
-    pub fn new(redis: Option<Arc<RedisService>>) -> Self {
-        // 100 requests per minute per IP
-        let quota = Quota::per_minute(nonzero!(100u32));
-        let limiter = Arc::new(RateLimiter::<
-            String,
-            DefaultKeyedStateStore<String>,
-            DefaultClock,
-        >::keyed(quota));
-
-        Self { limiter, redis }
-    }
-
-    pub async fn check_rate_limit(&self, key: &str) -> bool {
-        // First check in-memory limiter
-        if !self.limiter.check_key(&key.to_string()).is_ok() {
-            return false;
-        }
-
-        // If Redis is available, also track in Redis for distributed rate limiting
-        if let Some(redis) = &self.redis {
-            let redis_key = format!("rate_limit:{}", key);
-            if let Ok(count) = redis.increment(&redis_key).await {
-                if count == 1 {
-                    // Set 60 second expiration on first request
-                    let _ = redis.expire(&redis_key, 60).await;
-                }
-                // Allow up to 150 requests per minute in Redis
-                return count <= 150;
-            }
+    pub fn new(redis: Option<Arc<RedisService>>, clock: SharedClock) -> Self {
+        Self::with_config(RateLimitConfig::from_env(), redis, clock)
+    }
+
+    pub fn with_config(config: RateLimitConfig, redis: Option<Arc<RedisService>>, clock: SharedClock) -> Self {
+        Self {
+            config,
+            counters: Arc::new(Mutex::new(HashMap::new())),
+            redis,
+            clock,
+        }
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        self.config.enforce
+    }
+
+    /// Count a request against every window and return the resulting buckets
+    pub async fn hit(&self, category: RateLimitCategory, caller: &RateLimitCaller) -> Vec<RateLimitBucket> {
+        self.buckets(category, caller, true).await
+    }
+
+    /// Current buckets without counting a request
+    pub async fn status(&self, category: RateLimitCategory, caller: &RateLimitCaller) -> RateLimitStatus {
+        RateLimitStatus {
+            caller: caller.key(),
+            category,
+            enforced: self.config.enforce,
+            buckets: self.buckets(category, caller, false).await,
+        }
+    }
+
+    pub async fn check_rate_limit(&self, category: RateLimitCategory, caller: &RateLimitCaller) -> bool {
+        !self.hit(category, caller).await.iter().any(RateLimitBucket::is_exceeded)
+    }
+
+    async fn buckets(&self, category: RateLimitCategory, caller: &RateLimitCaller, count: bool) -> Vec<RateLimitBucket> {
+        let now = self.clock.now().timestamp();
+        let key = format!("{}:{}", category.as_str(), caller.key());
+        let mut buckets = Vec::new();
+
+        for (window, limit, seconds) in self.config.windows(category, caller) {
+            let elapsed = now.rem_euclid(seconds);
+            let window_start = now - elapsed;
+
+            let (previous, current) = match self.redis_count(&key, window, window_start, seconds, count).await {
+                Some(counts) => counts,
+                None => self.local_count(&key, window, window_start, seconds, now, count),
+            };
+
+            let used = sliding_count(previous, current, elapsed, seconds);
+            buckets.push(RateLimitBucket {
+                window,
+                limit,
+                used,
+                remaining: limit.saturating_sub(used),
+                reset: seconds - elapsed,
+                retry_after: (used > limit).then(|| retry_after(previous, current, elapsed, seconds, limit)),
+            });
         }
 
-        true
+        buckets
+    }
+
+    /// (previous window, current window) counts
+    fn local_count(
+        &self,
+        key: &str,
+        window: &'static str,
+        window_start: i64,
+        seconds: i64,
+        now: i64,
+        count: bool,
+    ) -> (u32, u32) {
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+
+        if counters.len() > MAX_TRACKED_WINDOWS {
+            counters.retain(|(_, _, start), _| now - start < 2 * LONGEST_WINDOW_SECONDS);
+        }
+
+        let previous = counters
+            .get(&(key.to_string(), window, window_start - seconds))
+            .copied()
+            .unwrap_or(0);
+        let entry = counters.entry((key.to_string(), window, window_start)).or_insert(0);
+        if count {
+            *entry += 1;
+        }
+        (previous, *entry)
+    }
+
+    /// Shared counters for distributed deployments; None falls back to in-memory
+    async fn redis_count(
+        &self,
+        key: &str,
+        window: &str,
+        window_start: i64,
+        seconds: i64,
+        count: bool,
+    ) -> Option<(u32, u32)> {
+        let redis = self.redis.as_ref()?;
+        let redis_key = |start: i64| format!("rate_limit:{}:{}:{}", key, window, start);
+        let current_key = redis_key(window_start);
+
+        let current = if count {
+            let used = redis.increment(&current_key).await.ok()?;
+            if used == 1 {
+                // Kept through the next window, which weighs it in
+                let _ = redis.expire(&current_key, (2 * seconds) as u64).await;
+            }
+            used
+        } else {
+            redis.get::<i64>(&current_key).await.ok()?.unwrap_or(0)
+        };
+        let previous = redis.get::<i64>(&redis_key(window_start - seconds)).await.ok()?.unwrap_or(0);
+
+        Some((previous.max(0) as u32, current.max(0) as u32))
     }
 }
 
-pub async fn rate_limit_middleware(
-    req: Request<Body>,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    // Extract IP address from request
-    let ip = req
-        .headers()
-        .get("x-forwarded-for")
+/// The authenticated user's ID when a valid bearer token is present
+fn user_id(req: &Request<Body>) -> Option<String> {
+    req.headers()
+        .get(axum::http::header::AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .map(|s| s.to_string())
-        .or_else(|| {
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| {
             req.extensions()
-                .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
-                .map(|ci| ci.0.ip().to_string())
+                .get::<Arc<AuthService>>()
+                .and_then(|auth| auth.verify_token(token).ok())
         })
-        .unwrap_or_else(|| "unknown".to_string());
+        .map(|claims| claims.sub)
+}
+
+/// The caller's address, as resolved through trusted proxies. None only without
+/// connection info; such requests aren't lumped into one shared IP bucket.
+fn client_ip(req: &Request<Body>) -> Option<String> {
+    client_ip::client_ip(req.headers(), req.extensions()).map(|ip| ip.to_string())
+}
+
+/// Identify the caller: authenticated user when a valid token is present, otherwise client IP
+pub fn caller_key(req: &Request<Body>) -> Option<String> {
+    match user_id(req) {
+        Some(user_id) => Some(RateLimitCaller::User(user_id).key()),
+        None => client_ip(req).map(|ip| RateLimitCaller::Ip(ip).key()),
+    }
+}
+
+/// Everyone a request counts against: its IP, and its user outside of
+/// sign-in, where there is none yet
+fn callers(req: &Request<Body>, category: RateLimitCategory) -> Vec<RateLimitCaller> {
+    let mut callers = Vec::with_capacity(2);
+    if category != RateLimitCategory::Auth {
+        if let Some(user_id) = user_id(req) {
+            callers.push(RateLimitCaller::User(user_id));
+        }
+    }
+    if let Some(ip) = client_ip(req) {
+        callers.push(RateLimitCaller::Ip(ip));
+    }
+    callers
+}
+
+/// Report the most constrained bucket via X-RateLimit-* headers
+fn apply_headers(headers: &mut HeaderMap, buckets: &[RateLimitBucket]) {
+    let tightest = match buckets.iter().min_by_key(|b| (b.remaining, b.reset)) {
+        Some(bucket) => bucket,
+        None => return,
+    };
+
+    headers.insert("x-ratelimit-limit", HeaderValue::from(tightest.limit));
+    headers.insert("x-ratelimit-remaining", HeaderValue::from(tightest.remaining));
+    headers.insert("x-ratelimit-reset", HeaderValue::from(tightest.reset));
+}
+
+pub async fn rate_limit_middleware(
+    State(rate_limiter): State<RateLimitMiddleware>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let category = RateLimitCategory::for_request(req.method(), req.uri().path());
+    let mut buckets = Vec::new();
+    for caller in callers(&req, category) {
+        buckets.extend(rate_limiter.hit(category, &caller).await);
+    }
+
+    // Wait for every exceeded bucket, not just the first
+    let retry_after = buckets.iter().filter_map(|b| b.retry_after).max();
+    if let Some(retry_after) = retry_after {
+        if rate_limiter.is_enforced() {
+            let mut response = crate::api::error::ApiError::RateLimit.into_response();
+            apply_headers(response.headers_mut(), &buckets);
+            response
+                .headers_mut()
+                .insert(axum::http::header::RETRY_AFTER, HeaderValue::from(retry_after));
+            return response;
+        }
 
-    // Check rate limit
-    let rate_limiter = RateLimitMiddleware::new(None);
-    if !rate_limiter.check_rate_limit(&ip).await {
-        return Err(StatusCode::TOO_MANY_REQUESTS);
+        tracing::debug!(category = category.as_str(), retry_after, "Soft rate limit exceeded");
     }
 
-    Ok(next.run(req).await)
+    let mut response = next.run(req).await;
+    apply_headers(response.headers_mut(), &buckets);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::clock::MockClock;
+
+    fn limiter(clock: Arc<MockClock>) -> RateLimitMiddleware {
+        RateLimitMiddleware::with_config(
+            RateLimitConfig {
+                read: RateLimits { per_minute: 3, per_hour: 5 },
+                write: RateLimits { per_minute: 2, per_hour: 5 },
+                auth: RateLimits { per_minute: 1, per_hour: 3 },
+                ip_factor: 2,
+                enforce: true,
+            },
+            None,
+            clock,
+        )
+    }
+
+    fn ip(addr: &str) -> RateLimitCaller {
+        RateLimitCaller::Ip(addr.to_string())
+    }
+
+    fn user(id: &str) -> RateLimitCaller {
+        RateLimitCaller::User(id.to_string())
+    }
+
+    #[test]
+    fn test_sign_in_is_its_own_category() {
+        assert_eq!(RateLimitCategory::for_request(&Method::POST, "/api/v1/auth/login"), RateLimitCategory::Auth);
+        assert_eq!(RateLimitCategory::for_request(&Method::POST, "/api/v1/auth/register/"), RateLimitCategory::Auth);
+        assert_eq!(
+            RateLimitCategory::for_request(&Method::POST, "/api/v1/portal/sign-in/link"),
+            RateLimitCategory::Auth
+        );
+        assert_eq!(RateLimitCategory::for_request(&Method::POST, "/api/v1/auth/refresh"), RateLimitCategory::Write);
+        assert_eq!(RateLimitCategory::for_request(&Method::DELETE, "/api/v1/clients/1"), RateLimitCategory::Write);
+        assert_eq!(RateLimitCategory::for_request(&Method::GET, "/api/v1/auth/me"), RateLimitCategory::Read);
+    }
+
+    #[tokio::test]
+    async fn test_window_slides_instead_of_resetting() {
+        let clock = MockClock::default_start();
+        let limiter = limiter(clock.clone());
+
+        for _ in 0..3 {
+            assert!(limiter.check_rate_limit(RateLimitCategory::Read, &user("a")).await);
+        }
+        assert!(!limiter.check_rate_limit(RateLimitCategory::Read, &user("a")).await);
+
+        // Other callers and categories have their own buckets
+        assert!(limiter.check_rate_limit(RateLimitCategory::Read, &user("b")).await);
+        assert!(limiter.check_rate_limit(RateLimitCategory::Write, &user("a")).await);
+
+        // Half a minute into the next window, half of the last one still counts
+        clock.advance(chrono::Duration::seconds(90));
+        let status = limiter.status(RateLimitCategory::Read, &user("a")).await;
+        let minute = &status.buckets[0];
+        assert_eq!(minute.used, 2);
+        assert_eq!(minute.remaining, 1);
+        assert_eq!(minute.reset, 30);
+
+        // The hourly bucket still remembers every request
+        let hour = &status.buckets[1];
+        assert_eq!(hour.used, 4);
+        assert_eq!(hour.remaining, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_when_the_previous_window_fades() {
+        let clock = MockClock::default_start();
+        let limiter = limiter(clock.clone());
+
+        for _ in 0..2 {
+            limiter.hit(RateLimitCategory::Write, &user("a")).await;
+        }
+        // 09:01:15: three quarters of the first minute still overlap, rounded up to both requests
+        clock.advance(chrono::Duration::seconds(75));
+        let buckets = limiter.hit(RateLimitCategory::Write, &user("a")).await;
+        assert_eq!(buckets[0].used, 3);
+        // Room again at 09:02:00, once the first minute has slid out
+        assert_eq!(buckets[0].retry_after, Some(45));
+
+        clock.advance(chrono::Duration::seconds(45));
+        assert!(limiter.check_rate_limit(RateLimitCategory::Write, &user("a")).await);
+    }
+
+    #[tokio::test]
+    async fn test_ip_carries_several_users_but_sign_in_is_strict() {
+        let clock = MockClock::default_start();
+        let limiter = limiter(clock.clone());
+
+        // Reads per IP allow twice the per-user limit
+        for _ in 0..6 {
+            assert!(limiter.check_rate_limit(RateLimitCategory::Read, &ip("1.2.3.4")).await);
+        }
+        assert!(!limiter.check_rate_limit(RateLimitCategory::Read, &ip("1.2.3.4")).await);
+
+        assert!(limiter.check_rate_limit(RateLimitCategory::Auth, &ip("1.2.3.4")).await);
+        let buckets = limiter.hit(RateLimitCategory::Auth, &ip("1.2.3.4")).await;
+        assert!(buckets[0].is_exceeded());
+        // Two requests this minute: both must fade, well into the next one
+        assert_eq!(buckets[0].retry_after, Some(120));
+    }
+
+    fn sign_in(peer: [u8; 4], forwarded_for: &str, proxies: &str) -> Request<Body> {
+        let mut req = Request::builder()
+            .method(Method::POST)
+            .uri("/api/v1/auth/login")
+            .header("x-forwarded-for", forwarded_for)
+            .body(Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(axum::extract::ConnectInfo(std::net::SocketAddr::from((peer, 443))));
+        req.extensions_mut().insert(Arc::new(client_ip::TrustedProxies::parse(proxies)));
+        req
+    }
+
+    #[test]
+    fn test_sign_in_is_limited_by_the_peer_not_a_spoofed_header() {
+        let req = sign_in([203, 0, 113, 7], "1.2.3.4", "10.0.0.0/8");
+        assert_eq!(callers(&req, RateLimitCategory::Auth), vec![ip("203.0.113.7")]);
+
+        let req = sign_in([10, 0, 0, 1], "1.2.3.4", "10.0.0.0/8");
+        assert_eq!(callers(&req, RateLimitCategory::Auth), vec![ip("1.2.3.4")]);
+    }
+
+    #[test]
+    fn test_unknown_addresses_share_no_bucket() {
+        let req = Request::builder().uri("/api/v1/auth/login").body(Body::empty()).unwrap();
+        assert!(callers(&req, RateLimitCategory::Auth).is_empty());
+        assert_eq!(caller_key(&req), None);
+    }
 }
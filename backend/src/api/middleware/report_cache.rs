@@ -0,0 +1,62 @@
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, State},
+    http::{Method, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::middleware::AuthUser;
+use crate::domain::services::ReportCache;
+
+/// Writes that can change what reports show: invoices and everything hanging off
+/// them (payments, credit, late fees, imports), payments, credit notes, expenses
+/// and scanned receipts, clients, trash restores and offline sync
+fn changes_report_data(method: &Method, path: &str) -> bool {
+    if !matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE) {
+        return false;
+    }
+    let path = path.strip_prefix("/api/v1").unwrap_or(path);
+    matches!(
+        path.split('/').find(|segment| !segment.is_empty()),
+        Some("invoices" | "payments" | "credit-notes" | "expenses" | "clients" | "trash" | "sync")
+    )
+}
+
+/// Invalidates the caller's cached reports after a successful write to report
+/// data, so dashboards reflect it on the next request
+pub async fn report_cache_middleware(
+    State(reports): State<ReportCache>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if !changes_report_data(req.method(), req.uri().path()) {
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let auth_user = AuthUser::from_request_parts(&mut parts, &()).await.ok();
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    if let Some(auth_user) = auth_user.filter(|_| response.status().is_success()) {
+        reports.invalidate_user(auth_user.user_id).await;
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_to_report_data_invalidate() {
+        assert!(changes_report_data(&Method::POST, "/api/v1/payments"));
+        assert!(changes_report_data(&Method::POST, "/api/v1/invoices/6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f/send"));
+        assert!(changes_report_data(&Method::DELETE, "/api/v1/expenses/6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f"));
+        assert!(changes_report_data(&Method::POST, "/api/v1/sync/apply"));
+
+        assert!(!changes_report_data(&Method::GET, "/api/v1/invoices"));
+        assert!(!changes_report_data(&Method::POST, "/api/v1/reports/export"));
+        assert!(!changes_report_data(&Method::PUT, "/api/v1/settings/invoice"));
+    }
+}
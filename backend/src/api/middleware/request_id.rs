@@ -0,0 +1,70 @@
+use axum::{
+    body::Body,
+    http::{HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::Instrument;
+
+use crate::domain::request_id::{self, REQUEST_ID_HEADER};
+
+/// Takes the caller's `X-Request-Id`, or makes one up, and keeps it for the
+/// rest of the request: every log line is in a span carrying it, error bodies
+/// and audit entries record it, and emails and webhook events it queues keep
+/// it. The ID is echoed back in the response header.
+pub async fn request_id_middleware(mut request: Request<Body>, next: Next) -> Response {
+    let id = request_id::accept_or_generate(
+        request.headers().get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok()),
+    );
+    let header = HeaderValue::from_str(&id).expect("request IDs are visible ASCII");
+    request.headers_mut().insert(REQUEST_ID_HEADER, header.clone());
+
+    let span = tracing::info_span!("request", request_id = %id);
+    let mut response = request_id::scope(id, next.run(request)).instrument(span).await;
+    response.headers_mut().insert(REQUEST_ID_HEADER, header);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::to_bytes, http::StatusCode, routing::get, Router};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use crate::api::error::ApiError;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/id", get(|| async { request_id::current().unwrap_or_default() }))
+            .route("/missing", get(|| async { Err::<(), _>(ApiError::NotFound) }))
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+
+    async fn send(uri: &str, request_id: Option<&str>) -> Response {
+        let mut request = Request::get(uri);
+        if let Some(id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, id);
+        }
+        app().oneshot(request.body(Body::empty()).unwrap()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_echoed_and_reaches_handlers_and_errors() {
+        let response = send("/id", Some("client-123")).await;
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "client-123");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"client-123");
+
+        let response = send("/missing", None).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let generated = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&generated).is_ok());
+        let body: Value = serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await.unwrap()).unwrap();
+        assert_eq!(body["error"]["request_id"], generated.as_str());
+
+        // Unsafe IDs are replaced rather than logged
+        let response = send("/id", Some("bad id")).await;
+        assert_ne!(response.headers()[REQUEST_ID_HEADER], "bad id");
+    }
+}
@@ -0,0 +1,243 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, State},
+    http::{header, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Access control for the metrics and monitoring endpoints.
+///
+/// `METRICS_TOKEN` requires `Authorization: Bearer <token>` (Prometheus `bearer_token`).
+/// `METRICS_ALLOWED_IPS` is a comma-separated list of addresses or CIDR ranges matched
+/// against the connecting peer, e.g. `10.0.0.0/8,127.0.0.1`. When both are set, either
+/// one is enough. When neither is set the endpoints stay public, and the admin
+/// endpoints are turned off.
+#[derive(Debug, Clone, Default)]
+pub struct ScrapeAuthConfig {
+    token: Option<String>,
+    allowed: Vec<IpRange>,
+}
+
+/// An address or CIDR range, e.g. `10.0.0.0/8`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct IpRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    pub(crate) fn parse(raw: &str) -> Option<Self> {
+        let (addr, prefix) = match raw.split_once('/') {
+            Some((addr, prefix)) => (addr.trim(), Some(prefix.trim().parse::<u8>().ok()?)),
+            None => (raw.trim(), None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = prefix.unwrap_or(max);
+        (prefix <= max).then_some(Self { network, prefix })
+    }
+
+    pub(crate) fn contains(&self, ip: IpAddr) -> bool {
+        // Match IPv4 peers that arrive as IPv4-mapped IPv6 addresses
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ScrapeAuthConfig {
+    pub fn from_env() -> Self {
+        let token = std::env::var("METRICS_TOKEN").ok().filter(|t| !t.trim().is_empty());
+        let allowed = std::env::var("METRICS_ALLOWED_IPS")
+            .map(|list| parse_ranges("METRICS_ALLOWED_IPS", &list))
+            .unwrap_or_default();
+
+        Self { token, allowed }
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.token.is_none() && self.allowed.is_empty()
+    }
+
+    fn allows(&self, bearer: Option<&str>, peer: Option<IpAddr>) -> bool {
+        if self.is_public() {
+            return true;
+        }
+
+        let token_ok = match (&self.token, bearer) {
+            (Some(expected), Some(given)) => constant_time_eq(expected.as_bytes(), given.as_bytes()),
+            _ => false,
+        };
+        let ip_ok = peer.is_some_and(|ip| self.allowed.iter().any(|range| range.contains(ip)));
+
+        token_ok || ip_ok
+    }
+}
+
+/// A comma-separated list of addresses and CIDR ranges from the `var` setting,
+/// skipping (and warning about) entries that don't parse
+pub(crate) fn parse_ranges(var: &str, list: &str) -> Vec<IpRange> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let range = IpRange::parse(entry);
+            if range.is_none() {
+                tracing::warn!("Ignoring invalid {} entry: {}", var, entry);
+            }
+            range
+        })
+        .collect()
+}
+
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Bearer token and connecting peer of a request. Only the peer is used;
+/// forwarded headers are client-controlled.
+fn credentials(req: &Request<Body>) -> (Option<&str>, Option<IpAddr>) {
+    let bearer = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ci| ci.0.ip());
+    (bearer, peer)
+}
+
+fn unauthorized() -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        "Unauthorized",
+    )
+        .into_response()
+}
+
+pub async fn scrape_auth_middleware(
+    State(config): State<Arc<ScrapeAuthConfig>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let (bearer, peer) = credentials(&req);
+    if !config.allows(bearer, peer) {
+        return unauthorized();
+    }
+
+    next.run(req).await
+}
+
+/// Like `scrape_auth_middleware`, but for operator endpoints that change state:
+/// closed rather than public when neither setting is configured.
+pub async fn admin_auth_middleware(
+    State(config): State<Arc<ScrapeAuthConfig>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if config.is_public() {
+        return (
+            StatusCode::FORBIDDEN,
+            "Admin endpoints are disabled; set METRICS_TOKEN or METRICS_ALLOWED_IPS",
+        )
+            .into_response();
+    }
+    let (bearer, peer) = credentials(&req);
+    if !config.allows(bearer, peer) {
+        return unauthorized();
+    }
+
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(token: Option<&str>, ips: &str) -> ScrapeAuthConfig {
+        ScrapeAuthConfig {
+            token: token.map(str::to_string),
+            allowed: parse_ranges("METRICS_ALLOWED_IPS", ips),
+        }
+    }
+
+    #[test]
+    fn test_public_without_configuration() {
+        let open = config(None, "");
+        assert!(open.is_public());
+        assert!(open.allows(None, None));
+    }
+
+    #[test]
+    fn test_bearer_token_required() {
+        let protected = config(Some("s3cret"), "");
+        assert!(protected.allows(Some("s3cret"), None));
+        assert!(!protected.allows(Some("wrong"), None));
+        assert!(!protected.allows(None, Some("127.0.0.1".parse().unwrap())));
+    }
+
+    #[test]
+    fn test_ip_allowlist_with_cidr_ranges() {
+        let protected = config(None, "10.0.0.0/8, 192.168.1.5, fd00::/8, bogus");
+        assert_eq!(protected.allowed.len(), 3);
+        assert!(protected.allows(None, Some("10.42.0.7".parse().unwrap())));
+        assert!(protected.allows(None, Some("192.168.1.5".parse().unwrap())));
+        assert!(protected.allows(None, Some("::ffff:10.1.2.3".parse().unwrap())));
+        assert!(protected.allows(None, Some("fd12::1".parse().unwrap())));
+        assert!(!protected.allows(None, Some("192.168.1.6".parse().unwrap())));
+        assert!(!protected.allows(None, None));
+    }
+
+    #[test]
+    fn test_token_or_ip_is_enough_when_both_configured() {
+        let protected = config(Some("s3cret"), "127.0.0.1");
+        assert!(protected.allows(None, Some("127.0.0.1".parse().unwrap())));
+        assert!(protected.allows(Some("s3cret"), Some("8.8.8.8".parse().unwrap())));
+        assert!(!protected.allows(Some("nope"), Some("8.8.8.8".parse().unwrap())));
+    }
+
+    #[tokio::test]
+    async fn test_admin_endpoints_are_closed_until_configured() {
+        use axum::{routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = |config: ScrapeAuthConfig| {
+            Router::new()
+                .route("/admin", get(|| async { "ok" }))
+                .layer(axum::middleware::from_fn_with_state(Arc::new(config), admin_auth_middleware))
+        };
+        let request = |token: Option<&str>| {
+            let builder = Request::get("/admin");
+            let builder = match token {
+                Some(token) => builder.header(header::AUTHORIZATION, format!("Bearer {}", token)),
+                None => builder,
+            };
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let open = app(config(None, "")).oneshot(request(None)).await.unwrap();
+        assert_eq!(open.status(), StatusCode::FORBIDDEN);
+        let protected = app(config(Some("s3cret"), ""));
+        assert_eq!(protected.clone().oneshot(request(None)).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(protected.oneshot(request(Some("s3cret"))).await.unwrap().status(), StatusCode::OK);
+    }
+}
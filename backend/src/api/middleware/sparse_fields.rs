@@ -0,0 +1,274 @@
+use axum::{
+    body::Body,
+    extract::Query,
+    http::{header, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+
+/// Largest response body that will be buffered for trimming
+const MAX_SPARSE_BODY_BYTES: usize = 32 * 1024 * 1024;
+const MAX_SELECTED_FIELDS: usize = 50;
+
+const COMPACT_INVOICE: &[&str] = &["id", "invoice_number", "client_name", "status", "balance_due", "due_date"];
+const COMPACT_CLIENT: &[&str] = &["id", "name", "email", "company_name", "outstanding_balance"];
+const COMPACT_PAYMENT: &[&str] = &["id", "invoice_number", "amount", "currency", "status", "created_at"];
+const COMPACT_EXPENSE: &[&str] = &["id", "amount", "currency", "category", "vendor", "date_incurred"];
+
+/// Fields kept from a JSON response, as a tree of dotted paths. A field with no
+/// children is kept whole.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FieldSelection {
+    fields: BTreeMap<String, FieldSelection>,
+}
+
+impl FieldSelection {
+    /// Parse `id,client.name,items.description`
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let paths: Vec<&str> = spec.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+        if paths.is_empty() {
+            return Err("fields must name at least one field".to_string());
+        }
+        if paths.len() > MAX_SELECTED_FIELDS {
+            return Err(format!("fields can name at most {} fields", MAX_SELECTED_FIELDS));
+        }
+
+        let mut selection = Self::default();
+        for path in paths {
+            let valid = path
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+            if !valid {
+                return Err(format!("Invalid field: {}", path));
+            }
+            selection.insert(&path.split('.').collect::<Vec<_>>());
+        }
+        Ok(selection)
+    }
+
+    fn from_names(names: &[&str]) -> Self {
+        let mut selection = Self::default();
+        for name in names {
+            selection.insert(&[name]);
+        }
+        selection
+    }
+
+    fn insert(&mut self, path: &[&str]) {
+        let Some((head, rest)) = path.split_first() else { return };
+        match self.fields.get_mut(*head) {
+            // Already kept whole
+            Some(child) if child.fields.is_empty() => {}
+            Some(child) if rest.is_empty() => child.fields.clear(),
+            Some(child) => child.insert(rest),
+            None => {
+                let mut child = Self::default();
+                child.insert(rest);
+                self.fields.insert(head.to_string(), child);
+            }
+        }
+    }
+
+    /// Trim an object, or each object in an array, to the selected fields.
+    /// Unknown field names are ignored.
+    pub fn apply(&self, value: &mut Value) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply(item)),
+            Value::Object(map) => {
+                map.retain(|key, _| self.fields.contains_key(key));
+                for (key, child) in &self.fields {
+                    if child.fields.is_empty() {
+                        continue;
+                    }
+                    if let Some(nested) = map.get_mut(key) {
+                        child.apply(nested);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Batch lookups (`?ids=`) wrap their results with the IDs not found, and list pages
+/// with the paging fields; the selection applies to each item rather than the wrapper.
+fn apply_to_body(selection: &FieldSelection, body: &mut Value) {
+    if let Value::Object(map) = body {
+        let is_batch = map.len() == 2 && map.contains_key("not_found");
+        let is_page = map.len() == 5 && map.contains_key("next_cursor") && map.contains_key("total");
+        if is_batch || is_page {
+            if let Some(items) = map.get_mut("items") {
+                selection.apply(items);
+                return;
+            }
+        }
+    }
+    selection.apply(body);
+}
+
+/// Fields of the `view=compact` response for a GET path, if the endpoint has one
+fn compact_view(path: &str) -> Option<&'static [&'static str]> {
+    let path = path.strip_prefix("/api/v1").unwrap_or(path).trim_end_matches('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let is_id = |segment: &str| Uuid::parse_str(segment).is_ok();
+
+    match segments.as_slice() {
+        ["invoices"] => Some(COMPACT_INVOICE),
+        ["invoices", id] if is_id(id) => Some(COMPACT_INVOICE),
+        ["clients", id, "invoices"] if is_id(id) => Some(COMPACT_INVOICE),
+        ["clients"] => Some(COMPACT_CLIENT),
+        ["clients", id] if is_id(id) => Some(COMPACT_CLIENT),
+        ["payments"] => Some(COMPACT_PAYMENT),
+        ["payments", id] if is_id(id) => Some(COMPACT_PAYMENT),
+        ["expenses"] => Some(COMPACT_EXPENSE),
+        ["expenses", id] if is_id(id) => Some(COMPACT_EXPENSE),
+        _ => None,
+    }
+}
+
+/// Selection requested by `fields=` or `view=` on a GET request; None for a full response
+fn requested_selection(req: &Request<Body>) -> Result<Option<FieldSelection>, ApiError> {
+    if req.method() != Method::GET {
+        return Ok(None);
+    }
+    let Ok(Query(params)) = Query::<HashMap<String, String>>::try_from_uri(req.uri()) else {
+        return Ok(None);
+    };
+
+    match (params.get("fields"), params.get("view").map(String::as_str)) {
+        (Some(_), Some(_)) => Err(ApiError::BadRequest("Use either fields or view, not both".to_string())),
+        (Some(spec), None) => FieldSelection::parse(spec).map(Some).map_err(ApiError::BadRequest),
+        (None, Some("full")) | (None, None) => Ok(None),
+        (None, Some("compact")) => compact_view(req.uri().path())
+            .map(|names| Some(FieldSelection::from_names(names)))
+            .ok_or_else(|| ApiError::BadRequest("This endpoint has no compact view".to_string())),
+        (None, Some(other)) => Err(ApiError::BadRequest(format!("Unknown view: {} (expected compact or full)", other))),
+    }
+}
+
+/// Sparse responses for GET endpoints. `?fields=id,status,client.name` keeps only the
+/// named fields of the JSON body (of each element, for lists); `?view=compact` applies
+/// the endpoint's predefined compact field set. Other requests pass through untouched.
+pub async fn sparse_fields_middleware(req: Request<Body>, next: Next) -> Response {
+    let selection = match requested_selection(&req) {
+        Ok(Some(selection)) => selection,
+        Ok(None) => return next.run(req).await,
+        Err(e) => return e.into_response(),
+    };
+
+    let response = next.run(req).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_SPARSE_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for field selection: {}", e);
+            return ApiError::Internal.into_response();
+        }
+    };
+
+    let mut value: Value = match serde_json::from_slice(&bytes) {
+        Ok(value) => value,
+        Err(_) => return Response::from_parts(parts, Body::from(bytes)),
+    };
+    apply_to_body(&selection, &mut value);
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    match serde_json::to_vec(&value) {
+        Ok(trimmed) => Response::from_parts(parts, Body::from(trimmed)),
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn selects_fields_of_each_list_element() {
+        let selection = FieldSelection::parse("id, status,client.name").unwrap();
+        let mut value = json!([
+            { "id": 1, "status": "sent", "notes": "x", "client": { "name": "Acme", "email": "a@b.c" } },
+            { "id": 2, "status": "paid", "items": [] },
+        ]);
+
+        selection.apply(&mut value);
+
+        assert_eq!(
+            value,
+            json!([
+                { "id": 1, "status": "sent", "client": { "name": "Acme" } },
+                { "id": 2, "status": "paid" },
+            ])
+        );
+    }
+
+    #[test]
+    fn whole_field_wins_over_nested_selection() {
+        let mut value = json!({ "client": { "name": "Acme", "email": "a@b.c" }, "id": 1 });
+        FieldSelection::parse("client.name,client").unwrap().apply(&mut value);
+        assert_eq!(value, json!({ "client": { "name": "Acme", "email": "a@b.c" } }));
+
+        let mut value = json!({ "client": { "name": "Acme", "email": "a@b.c" }, "id": 1 });
+        FieldSelection::parse("client,client.name").unwrap().apply(&mut value);
+        assert_eq!(value, json!({ "client": { "name": "Acme", "email": "a@b.c" } }));
+    }
+
+    #[test]
+    fn selection_applies_inside_batch_results() {
+        let selection = FieldSelection::parse("id").unwrap();
+        let mut body = json!({ "items": [{ "id": 1, "status": "sent" }], "not_found": ["x"] });
+
+        apply_to_body(&selection, &mut body);
+
+        assert_eq!(body, json!({ "items": [{ "id": 1 }], "not_found": ["x"] }));
+    }
+
+    #[test]
+    fn selection_applies_inside_list_pages() {
+        let selection = FieldSelection::parse("id").unwrap();
+        let mut body = json!({
+            "items": [{ "id": 1, "status": "sent" }],
+            "total": 1,
+            "page": 1,
+            "per_page": 50,
+            "next_cursor": null,
+        });
+
+        apply_to_body(&selection, &mut body);
+
+        assert_eq!(body["items"], json!([{ "id": 1 }]));
+        assert_eq!(body["total"], 1);
+    }
+
+    #[test]
+    fn rejects_malformed_fields() {
+        assert!(FieldSelection::parse(" , ").is_err());
+        assert!(FieldSelection::parse("client..name").is_err());
+        assert!(FieldSelection::parse("id;drop").is_err());
+    }
+
+    #[test]
+    fn compact_views_by_path() {
+        let id = Uuid::new_v4();
+        assert_eq!(compact_view("/api/v1/invoices"), Some(COMPACT_INVOICE));
+        assert_eq!(compact_view(&format!("/api/v1/invoices/{}", id)), Some(COMPACT_INVOICE));
+        assert_eq!(compact_view(&format!("/api/v1/clients/{}/invoices", id)), Some(COMPACT_INVOICE));
+        assert_eq!(compact_view("/api/v1/payments/stats"), None);
+        assert_eq!(compact_view("/api/v1/reports/aging"), None);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod middleware;
 pub mod routes;
 pub mod error;
+pub mod openapi;
@@ -0,0 +1,119 @@
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::api::error::ErrorResponse;
+use crate::api::routes::*;
+
+/// Generated from the `#[utoipa::path]` annotations on the handlers; each route
+/// module lists its handlers in its own `ApiDoc`
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "FlashBill API", description = "Invoicing, payments, expenses and reports"),
+    components(schemas(ErrorResponse)),
+    modifiers(&BearerAuth)
+)]
+struct ApiDoc;
+
+/// Multipart upload body; the first part with a file name is taken, whatever
+/// its field name
+#[derive(ToSchema)]
+pub struct FileUpload {
+    #[schema(content_media_type = "application/octet-stream")]
+    pub file: Vec<u8>,
+}
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+            );
+        }
+    }
+}
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    for module in [
+        auth::ApiDoc::openapi(),
+        invoices::ApiDoc::openapi(),
+        invoice_transfers::ApiDoc::openapi(),
+        invoice_labels::ApiDoc::openapi(),
+        attachments::ApiDoc::openapi(),
+        late_fees::ApiDoc::openapi(),
+        profitability::ApiDoc::openapi(),
+        clients::ApiDoc::openapi(),
+        client_credit::ApiDoc::openapi(),
+        time_entries::ApiDoc::openapi(),
+        projects::ApiDoc::openapi(),
+        invoice_templates::ApiDoc::openapi(),
+        events::ApiDoc::openapi(),
+        client_imports::ApiDoc::openapi(),
+        payments::ApiDoc::openapi(),
+        bank_transfers::ApiDoc::openapi(),
+        credit_notes::ApiDoc::openapi(),
+        expenses::ApiDoc::openapi(),
+        receipts::ApiDoc::openapi(),
+        reports::ApiDoc::openapi(),
+        budgets::ApiDoc::openapi(),
+        settings::ApiDoc::openapi(),
+        tax::ApiDoc::openapi(),
+        document_numbers::ApiDoc::openapi(),
+        email_signatures::ApiDoc::openapi(),
+        email_templates::ApiDoc::openapi(),
+        template_bundles::ApiDoc::openapi(),
+        rate_limits::ApiDoc::openapi(),
+        files::ApiDoc::openapi(),
+        fx::ApiDoc::openapi(),
+        search::ApiDoc::openapi(),
+        trash::ApiDoc::openapi(),
+        audit_logs::ApiDoc::openapi(),
+        notifications::ApiDoc::openapi(),
+        webhook_endpoints::ApiDoc::openapi(),
+        sync::ApiDoc::openapi(),
+        businesses::ApiDoc::openapi(),
+        accountants::ApiDoc::openapi(),
+        campaigns::ApiDoc::openapi(),
+        payouts::ApiDoc::openapi(),
+        paypal::ApiDoc::openapi(),
+        stripe_checkout::ApiDoc::openapi(),
+        guest::ApiDoc::openapi(),
+        portal::ApiDoc::openapi(),
+        support::ApiDoc::openapi(),
+        metrics::ApiDoc::openapi(),
+        admin::ApiDoc::openapi(),
+    ] {
+        openapi.merge(module);
+    }
+    openapi
+}
+
+/// `GET /api/v1/openapi.json` and Swagger UI at `/api/v1/docs`
+pub fn create_router() -> Router {
+    Router::new().merge(SwaggerUi::new("/api/v1/docs").url("/api/v1/openapi.json", openapi()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_routes_and_resolves_schemas() {
+        let spec = openapi();
+        for path in ["/api/v1/auth/login", "/api/v1/invoices/{id}", "/api/v1/reports/timeseries"] {
+            assert!(spec.paths.paths.contains_key(path), "{} is not documented", path);
+        }
+
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        let json = spec.to_json().unwrap();
+        for reference in json.split("\"#/components/schemas/").skip(1) {
+            let name = &reference[..reference.find('"').unwrap()];
+            assert!(schemas.contains_key(name), "{} is referenced but not defined", name);
+        }
+    }
+}
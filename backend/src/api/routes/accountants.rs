@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    AccountantAccess, AccountantInvite, AccountantLogin, AccountantSession, CreateAccountantAccess,
+};
+use crate::domain::services::AccountantService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_access, invite_accountant, revoke_access, sign_in,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct AccountantState {
+    accountants: Arc<AccountantService>,
+}
+
+/// Read-only access for external accountants. Owners grant and revoke access per
+/// business; accountants exchange their access code at `/sign-in` for a token
+/// limited to reports, exports and invoice PDFs.
+pub fn create_router(accountants: Arc<AccountantService>) -> Router {
+    let state = AccountantState { accountants };
+
+    Router::new()
+        .route("/", get(list_access).post(invite_accountant))
+        .route("/{id}", delete(revoke_access))
+        .route("/sign-in", post(sign_in))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/accountants",
+    tag = "accountants",
+    responses((status = 200, body = Vec<AccountantAccess>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_access(
+    auth_user: AuthUser,
+    State(state): State<AccountantState>,
+) -> Result<Json<Vec<AccountantAccess>>, ApiError> {
+    let access = state.accountants.list(auth_user.user_id).await?;
+    Ok(Json(access))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/accountants",
+    tag = "accountants",
+    request_body = CreateAccountantAccess,
+    responses((status = 201, body = AccountantInvite), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn invite_accountant(
+    auth_user: AuthUser,
+    State(state): State<AccountantState>,
+    Json(payload): Json<CreateAccountantAccess>,
+) -> Result<(StatusCode, Json<AccountantInvite>), ApiError> {
+    let invite = state.accountants.invite(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(invite)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/accountants/{id}",
+    tag = "accountants",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = AccountantAccess), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn revoke_access(
+    auth_user: AuthUser,
+    State(state): State<AccountantState>,
+    Path(access_id): Path<Uuid>,
+) -> Result<Json<AccountantAccess>, ApiError> {
+    let access = state.accountants.revoke(auth_user.user_id, access_id).await?;
+    Ok(Json(access))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/accountants/sign-in",
+    tag = "accountants",
+    request_body = AccountantLogin,
+    responses((status = 200, body = AccountantSession), ApiError)
+)]
+async fn sign_in(
+    State(state): State<AccountantState>,
+    Json(payload): Json<AccountantLogin>,
+) -> Result<Json<AccountantSession>, ApiError> {
+    let session = state.accountants.sign_in(payload).await?;
+    Ok(Json(session))
+}
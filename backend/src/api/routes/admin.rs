@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::api::error::ApiError;
+use crate::api::middleware::scrape_auth::{admin_auth_middleware, ScrapeAuthConfig};
+use crate::domain::services::{DeadLetter, EmailQueueService, QueueStats};
+
+/// Most dead letters one request lists
+const MAX_DEAD_LETTERS: usize = 500;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(get_email_queue, list_dead_letters, retry_dead_letter, clear_dead_letters),
+    components(schemas(QueueStats, DeadLetter))
+)]
+pub struct ApiDoc;
+
+/// Operator endpoints, nested under /admin. They take the metrics credentials
+/// (METRICS_TOKEN or METRICS_ALLOWED_IPS) and are off when neither is set.
+pub fn create_router(email_queue: Arc<EmailQueueService>, scrape_auth: Arc<ScrapeAuthConfig>) -> Router {
+    Router::new()
+        .route("/email-queue", get(get_email_queue))
+        .route("/email-queue/dead-letters", get(list_dead_letters))
+        .route("/email-queue/dead-letters", delete(clear_dead_letters))
+        .route("/email-queue/dead-letters/{id}/retry", post(retry_dead_letter))
+        .route_layer(axum::middleware::from_fn_with_state(scrape_auth, admin_auth_middleware))
+        .with_state(email_queue)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DeadLetterQuery {
+    /// Default 50, at most 500
+    limit: Option<usize>,
+}
+
+/// Jobs waiting to be sent, and jobs that gave up
+#[utoipa::path(
+    get,
+    path = "/admin/email-queue",
+    tag = "admin",
+    responses((status = 200, body = QueueStats), ApiError)
+)]
+async fn get_email_queue(State(email_queue): State<Arc<EmailQueueService>>) -> Result<Json<QueueStats>, ApiError> {
+    Ok(Json(email_queue.get_stats().await?))
+}
+
+/// Emails that failed for good, most recent first. Bodies aren't shown, as
+/// they can hold sign-in links.
+#[utoipa::path(
+    get,
+    path = "/admin/email-queue/dead-letters",
+    tag = "admin",
+    params(DeadLetterQuery),
+    responses((status = 200, body = Vec<DeadLetter>), ApiError)
+)]
+async fn list_dead_letters(
+    State(email_queue): State<Arc<EmailQueueService>>,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<Vec<DeadLetter>>, ApiError> {
+    let limit = query.limit.unwrap_or(50).clamp(1, MAX_DEAD_LETTERS);
+    Ok(Json(email_queue.dead_letters(limit).await?))
+}
+
+/// Queue a dead-lettered email again with a fresh set of retries
+#[utoipa::path(
+    post,
+    path = "/admin/email-queue/dead-letters/{id}/retry",
+    tag = "admin",
+    params(("id" = String, Path)),
+    responses((status = 202, description = "Queued again"), ApiError)
+)]
+async fn retry_dead_letter(
+    State(email_queue): State<Arc<EmailQueueService>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if !email_queue.retry_dead_letter(&id).await? {
+        return Err(ApiError::NotFound);
+    }
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/email-queue/dead-letters",
+    tag = "admin",
+    responses((status = 204), ApiError)
+)]
+async fn clear_dead_letters(State(email_queue): State<Arc<EmailQueueService>>) -> Result<StatusCode, ApiError> {
+    email_queue.clear_dead_letters().await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,256 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use axum_extra::extract::Multipart;
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{Attachment, AttachmentParent};
+use crate::domain::services::AttachmentService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        upload_invoice_attachment, list_invoice_attachments, download_invoice_attachment,
+        delete_invoice_attachment, upload_expense_attachment, list_expense_attachments,
+        download_expense_attachment, delete_expense_attachment,
+    )
+)]
+pub struct ApiDoc;
+
+/// Attachment routes, merged into the invoices router
+pub fn create_invoice_router(attachments: Arc<AttachmentService>) -> Router {
+    Router::new()
+        .route(
+            "/{id}/attachments",
+            get(list_invoice_attachments)
+                .post(upload_invoice_attachment)
+                // Bounded by the global request limit and the per-file size check
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}",
+            get(download_invoice_attachment).delete(delete_invoice_attachment),
+        )
+        .with_state(attachments)
+}
+
+/// Attachment routes, merged into the expenses router
+pub fn create_expense_router(attachments: Arc<AttachmentService>) -> Router {
+    Router::new()
+        .route(
+            "/{id}/attachments",
+            get(list_expense_attachments)
+                .post(upload_expense_attachment)
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/{id}/attachments/{attachment_id}",
+            get(download_expense_attachment).delete(delete_expense_attachment),
+        )
+        .with_state(attachments)
+}
+
+/// The file as a download named after the uploaded file
+pub(crate) fn file_response(attachment: &Attachment, content: Vec<u8>) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(&attachment.mime_type)
+            .unwrap_or_else(|_| HeaderValue::from_static("application/octet-stream")),
+    );
+    // Header values must be visible ASCII
+    let file_name: String = attachment
+        .file_name
+        .chars()
+        .map(|c| if c == ' ' || (c.is_ascii_graphic() && !matches!(c, '"' | '\\')) { c } else { '_' })
+        .collect();
+    if let Ok(disposition) = HeaderValue::from_str(&format!("attachment; filename=\"{}\"", file_name)) {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+
+    (headers, content).into_response()
+}
+
+async fn upload(
+    attachments: &AttachmentService,
+    user_id: Uuid,
+    parent: AttachmentParent,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<Attachment>), ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let content_type = field.content_type().map(str::to_string);
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+        let attachment = attachments
+            .upload(user_id, parent, &file_name, content_type.as_deref(), &data)
+            .await?;
+        return Ok((StatusCode::CREATED, Json(attachment)));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/attachments",
+    tag = "attachments",
+    params(("id" = Uuid, Path)),
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 201, body = Attachment), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn upload_invoice_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path(invoice_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Attachment>), ApiError> {
+    upload(&attachments, auth_user.user_id, AttachmentParent::Invoice(invoice_id), multipart).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/attachments",
+    tag = "attachments",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<Attachment>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_invoice_attachments(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>, ApiError> {
+    let list = attachments.list(auth_user.user_id, AttachmentParent::Invoice(invoice_id)).await?;
+    Ok(Json(list))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    params(("id" = Uuid, Path), ("attachment_id" = Uuid, Path)),
+    responses((status = 200, description = "The attachment, with its stored content type", content_type = "application/octet-stream", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn download_invoice_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path((invoice_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let (attachment, content) = attachments
+        .download(auth_user.user_id, AttachmentParent::Invoice(invoice_id), attachment_id)
+        .await?;
+    Ok(file_response(&attachment, content))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invoices/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    params(("id" = Uuid, Path), ("attachment_id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_invoice_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path((invoice_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    attachments
+        .delete(auth_user.user_id, AttachmentParent::Invoice(invoice_id), attachment_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/expenses/{id}/attachments",
+    tag = "attachments",
+    params(("id" = Uuid, Path)),
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 201, body = Attachment), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn upload_expense_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path(expense_id): Path<Uuid>,
+    multipart: Multipart,
+) -> Result<(StatusCode, Json<Attachment>), ApiError> {
+    upload(&attachments, auth_user.user_id, AttachmentParent::Expense(expense_id), multipart).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses/{id}/attachments",
+    tag = "attachments",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<Attachment>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_expense_attachments(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path(expense_id): Path<Uuid>,
+) -> Result<Json<Vec<Attachment>>, ApiError> {
+    let list = attachments.list(auth_user.user_id, AttachmentParent::Expense(expense_id)).await?;
+    Ok(Json(list))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    params(("id" = Uuid, Path), ("attachment_id" = Uuid, Path)),
+    responses((status = 200, description = "The attachment, with its stored content type", content_type = "application/octet-stream", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn download_expense_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path((expense_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<Response, ApiError> {
+    let (attachment, content) = attachments
+        .download(auth_user.user_id, AttachmentParent::Expense(expense_id), attachment_id)
+        .await?;
+    Ok(file_response(&attachment, content))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/expenses/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    params(("id" = Uuid, Path), ("attachment_id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_expense_attachment(
+    auth_user: AuthUser,
+    State(attachments): State<Arc<AttachmentService>>,
+    Path((expense_id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    attachments
+        .delete(auth_user.user_id, AttachmentParent::Expense(expense_id), attachment_id)
+        .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{AuditListFilter, AuditLog};
+use crate::domain::services::AuditService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_audit_logs,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(audit: Arc<AuditService>) -> Router {
+    Router::new()
+        .route("/", get(list_audit_logs))
+        .with_state(audit)
+}
+
+/// The account's audit trail, newest first. Filters: `entity_type`, `entity_id`,
+/// `actor_id`, `action`, `date_from`/`date_to` (inclusive), `limit`, `offset`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit-logs",
+    tag = "audit-logs",
+    params(AuditListFilter),
+    responses((status = 200, body = Vec<AuditLog>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_audit_logs(
+    auth_user: AuthUser,
+    State(audit): State<Arc<AuditService>>,
+    Query(filter): Query<AuditListFilter>,
+) -> Result<Json<Vec<AuditLog>>, ApiError> {
+    let logs = audit.list(auth_user.user_id, &filter).await?;
+    Ok(Json(logs))
+}
@@ -7,13 +7,24 @@ use axum::{
     Json, Router,
 };
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
 use std::sync::Arc;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
+use crate::api::middleware::client_ip::ClientIp;
 use crate::application::dto::auth_dto::*;
 use crate::application::use_cases::*;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        register, login, refresh_token, logout, forgot_password, reset_password, verify_email,
+        get_current_user, update_profile,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct AuthState {
     register_uc: Arc<RegisterUserUseCase>,
@@ -60,6 +71,13 @@ pub fn create_router(
         .with_state(state)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterUserCommand,
+    responses((status = 201, body = AuthResultDto), ApiError)
+)]
 async fn register(
     State(state): State<AuthState>,
     Json(payload): Json<RegisterUserCommand>,
@@ -68,14 +86,29 @@ async fn register(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginUserCommand,
+    responses((status = 200, body = AuthResultDto), ApiError)
+)]
 async fn login(
     State(state): State<AuthState>,
+    ClientIp(ip_address): ClientIp,
     Json(payload): Json<LoginUserCommand>,
 ) -> Result<Json<AuthResultDto>, ApiError> {
-    let response = state.login_uc.execute(payload).await?;
+    let response = state.login_uc.execute(payload, ip_address).await?;
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenCommand,
+    responses((status = 200, body = AuthResultDto), ApiError)
+)]
 async fn refresh_token(
     State(state): State<AuthState>,
     Json(payload): Json<RefreshTokenCommand>,
@@ -84,12 +117,26 @@ async fn refresh_token(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses((status = 200), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn logout(
     _auth_user: AuthUser,
 ) -> Result<StatusCode, ApiError> {
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "auth",
+    request_body = ForgotPasswordCommand,
+    responses((status = 200), ApiError)
+)]
 async fn forgot_password(
     state: State<AuthState>,
     Json(payload): Json<ForgotPasswordCommand>,
@@ -98,6 +145,13 @@ async fn forgot_password(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordCommand,
+    responses((status = 200), ApiError)
+)]
 async fn reset_password(
     state: State<AuthState>,
     Json(payload): Json<ResetPasswordCommand>,
@@ -106,6 +160,13 @@ async fn reset_password(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/verify-email",
+    tag = "auth",
+    params(VerifyEmailParams),
+    responses((status = 200, body = VerificationResultDto), ApiError)
+)]
 async fn verify_email(
     state: State<AuthState>,
     Query(params): Query<VerifyEmailParams>,
@@ -114,20 +175,35 @@ async fn verify_email(
     Ok(Json(result))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    responses((status = 200, body = UserDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_current_user(
     auth_user: AuthUser,
     state: State<AuthState>,
 ) -> Result<Json<UserDto>, ApiError> {
-    let user = state.get_current_user_uc.execute(auth_user.user_id).await?;
+    let user = state.get_current_user_uc.execute(auth_user.owner_id).await?;
     Ok(Json(user))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    request_body = UpdateProfileCommand,
+    responses((status = 200, body = UserDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_profile(
     auth_user: AuthUser,
     state: State<AuthState>,
     Json(payload): Json<UpdateProfileCommand>,
 ) -> Result<Json<UserDto>, ApiError> {
-    let user = state.update_profile_uc.execute(auth_user.user_id, payload).await?;
+    let user = state.update_profile_uc.execute(auth_user.owner_id, payload).await?;
     Ok(Json(user))
 }
 
@@ -136,7 +212,8 @@ struct RefreshTokenRequest {
     refresh_token: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct VerifyEmailParams {
     token: String,
 }
@@ -0,0 +1,127 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use axum_extra::extract::Multipart;
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    BankTransferFilter, BankTransferPayment, ConfirmBankPayment, RejectBankPayment, StatementMatchReport,
+};
+use crate::domain::services::BankReconciliationService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_bank_transfers, confirm_bank_transfer, reject_bank_transfer, match_statement,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(reconciliation: Arc<BankReconciliationService>) -> Router {
+    Router::new()
+        .route("/", get(list_bank_transfers))
+        .route("/{id}/confirm", post(confirm_bank_transfer))
+        .route("/{id}/reject", post(reject_bank_transfer))
+        .route(
+            "/statement",
+            // Statement exports can be larger than the default body limit
+            post(match_statement).layer(DefaultBodyLimit::disable()),
+        )
+        .with_state(reconciliation)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/bank-transfers",
+    tag = "bank-transfers",
+    params(BankTransferFilter),
+    responses((status = 200, body = Vec<BankTransferPayment>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_bank_transfers(
+    auth_user: AuthUser,
+    State(reconciliation): State<Arc<BankReconciliationService>>,
+    Query(filter): Query<BankTransferFilter>,
+) -> Result<Json<Vec<BankTransferPayment>>, ApiError> {
+    let payments = reconciliation.list(auth_user.user_id, filter.status).await?;
+    Ok(Json(payments))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/bank-transfers/{id}/confirm",
+    tag = "bank-transfers",
+    params(("id" = Uuid, Path)),
+    request_body = ConfirmBankPayment,
+    responses((status = 200, body = BankTransferPayment), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn confirm_bank_transfer(
+    auth_user: AuthUser,
+    State(reconciliation): State<Arc<BankReconciliationService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ConfirmBankPayment>,
+) -> Result<Json<BankTransferPayment>, ApiError> {
+    let payment = reconciliation.confirm(auth_user.user_id, id, payload).await?;
+    Ok(Json(payment))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/bank-transfers/{id}/reject",
+    tag = "bank-transfers",
+    params(("id" = Uuid, Path)),
+    request_body = RejectBankPayment,
+    responses((status = 200, body = BankTransferPayment), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn reject_bank_transfer(
+    auth_user: AuthUser,
+    State(reconciliation): State<Arc<BankReconciliationService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RejectBankPayment>,
+) -> Result<Json<BankTransferPayment>, ApiError> {
+    let payment = reconciliation.reject(auth_user.user_id, id, payload).await?;
+    Ok(Json(payment))
+}
+
+/// Suggests which pending transfer each credit on an uploaded statement CSV is
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/bank-transfers/statement",
+    tag = "bank-transfers",
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 200, body = StatementMatchReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn match_statement(
+    auth_user: AuthUser,
+    State(reconciliation): State<Arc<BankReconciliationService>>,
+    mut multipart: Multipart,
+) -> Result<Json<StatementMatchReport>, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+        let report = reconciliation.match_statement(auth_user.user_id, &file_name, &data).await?;
+        return Ok(Json(report));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
@@ -0,0 +1,270 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    Budget, BudgetAlert, BudgetAlertFilter, BudgetLimit, BudgetReport, BudgetUtilization, CreateBudget,
+    CreateBudgetLimit, UpdateBudget, UpdateBudgetLimit, UtilizationQuery,
+};
+use crate::domain::services::BudgetService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_budgets, create_budget, get_budget, update_budget, delete_budget, get_budget_report,
+        list_limits, create_limit, update_limit, delete_limit, get_utilization, list_alerts,
+        mark_alert_read,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct BudgetState {
+    budgets: Arc<BudgetService>,
+}
+
+pub fn create_router(budgets: Arc<BudgetService>) -> Router {
+    let state = BudgetState { budgets };
+
+    Router::new()
+        .route("/", get(list_budgets).post(create_budget))
+        .route("/{id}", get(get_budget).put(update_budget).delete(delete_budget))
+        .route("/{id}/report", get(get_budget_report))
+        .route("/limits", get(list_limits).post(create_limit))
+        .route("/limits/{id}", put(update_limit).delete(delete_limit))
+        .route("/utilization", get(get_utilization))
+        .route("/alerts", get(list_alerts))
+        .route("/alerts/{id}/read", post(mark_alert_read))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    responses((status = 200, body = Vec<Budget>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_budgets(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+) -> Result<Json<Vec<Budget>>, ApiError> {
+    let budgets = state.budgets.list_budgets(auth_user.user_id).await?;
+    Ok(Json(budgets))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets",
+    tag = "budgets",
+    request_body = CreateBudget,
+    responses((status = 201, body = Budget), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_budget(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Json(payload): Json<CreateBudget>,
+) -> Result<(StatusCode, Json<Budget>), ApiError> {
+    let budget = state.budgets.create_budget(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(budget)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Budget), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_budget(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<Budget>, ApiError> {
+    let budget = state.budgets.get_budget(auth_user.user_id, budget_id).await?;
+    Ok(Json(budget))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateBudget,
+    responses((status = 200, body = Budget), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_budget(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(budget_id): Path<Uuid>,
+    Json(payload): Json<UpdateBudget>,
+) -> Result<Json<Budget>, ApiError> {
+    let budget = state.budgets.update_budget(auth_user.user_id, budget_id, payload).await?;
+    Ok(Json(budget))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/budgets/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_budget(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.budgets.delete_budget(auth_user.user_id, budget_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Budget vs actuals with variance percentages
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/{id}/report",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = BudgetReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_budget_report(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(budget_id): Path<Uuid>,
+) -> Result<Json<BudgetReport>, ApiError> {
+    let report = state.budgets.get_report(auth_user.user_id, budget_id).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/limits",
+    tag = "budgets",
+    responses((status = 200, body = Vec<BudgetLimit>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_limits(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+) -> Result<Json<Vec<BudgetLimit>>, ApiError> {
+    let limits = state.budgets.list_limits(auth_user.user_id).await?;
+    Ok(Json(limits))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/limits",
+    tag = "budgets",
+    request_body = CreateBudgetLimit,
+    responses((status = 201, body = BudgetLimit), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_limit(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Json(payload): Json<CreateBudgetLimit>,
+) -> Result<(StatusCode, Json<BudgetLimit>), ApiError> {
+    let limit = state.budgets.create_limit(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(limit)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/budgets/limits/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateBudgetLimit,
+    responses((status = 200, body = BudgetLimit), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_limit(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(limit_id): Path<Uuid>,
+    Json(payload): Json<UpdateBudgetLimit>,
+) -> Result<Json<BudgetLimit>, ApiError> {
+    let limit = state.budgets.update_limit(auth_user.user_id, limit_id, payload).await?;
+    Ok(Json(limit))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/budgets/limits/{id}",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_limit(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(limit_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.budgets.delete_limit(auth_user.user_id, limit_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Spending against each limit this month or quarter
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/utilization",
+    tag = "budgets",
+    params(UtilizationQuery),
+    responses((status = 200, body = Vec<BudgetUtilization>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_utilization(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Query(query): Query<UtilizationQuery>,
+) -> Result<Json<Vec<BudgetUtilization>>, ApiError> {
+    let utilization = state.budgets.utilization(auth_user.user_id, query.date).await?;
+    Ok(Json(utilization))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/budgets/alerts",
+    tag = "budgets",
+    params(BudgetAlertFilter),
+    responses((status = 200, body = Vec<BudgetAlert>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_alerts(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Query(filter): Query<BudgetAlertFilter>,
+) -> Result<Json<Vec<BudgetAlert>>, ApiError> {
+    let alerts = state.budgets.list_alerts(auth_user.user_id, filter.unread).await?;
+    Ok(Json(alerts))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/budgets/alerts/{id}/read",
+    tag = "budgets",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn mark_alert_read(
+    auth_user: AuthUser,
+    State(state): State<BudgetState>,
+    Path(alert_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.budgets.mark_alert_read(auth_user.user_id, alert_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
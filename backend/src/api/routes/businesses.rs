@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{Business, CreateBusiness, UpdateBusiness};
+use crate::domain::services::BusinessService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_businesses, create_business, update_business, delete_business,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct BusinessState {
+    businesses: Arc<BusinessService>,
+}
+
+/// Businesses under the login account. Other routes act on one of them when the
+/// request carries an `X-Business-Id` header or `business_id` query parameter.
+pub fn create_router(businesses: Arc<BusinessService>) -> Router {
+    let state = BusinessState { businesses };
+
+    Router::new()
+        .route("/", get(list_businesses).post(create_business))
+        .route("/{id}", put(update_business).delete(delete_business))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/businesses",
+    tag = "businesses",
+    responses((status = 200, body = Vec<Business>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_businesses(
+    auth_user: AuthUser,
+    State(state): State<BusinessState>,
+) -> Result<Json<Vec<Business>>, ApiError> {
+    let businesses = state.businesses.list(auth_user.owner_id).await?;
+    Ok(Json(businesses))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/businesses",
+    tag = "businesses",
+    request_body = CreateBusiness,
+    responses((status = 201, body = Business), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_business(
+    auth_user: AuthUser,
+    State(state): State<BusinessState>,
+    Json(payload): Json<CreateBusiness>,
+) -> Result<(StatusCode, Json<Business>), ApiError> {
+    let business = state.businesses.create(auth_user.owner_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(business)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/businesses/{id}",
+    tag = "businesses",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateBusiness,
+    responses((status = 200, body = Business), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_business(
+    auth_user: AuthUser,
+    State(state): State<BusinessState>,
+    Path(business_id): Path<Uuid>,
+    Json(payload): Json<UpdateBusiness>,
+) -> Result<Json<Business>, ApiError> {
+    let business = state.businesses.rename(auth_user.owner_id, business_id, payload).await?;
+    Ok(Json(business))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/businesses/{id}",
+    tag = "businesses",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_business(
+    auth_user: AuthUser,
+    State(state): State<BusinessState>,
+    Path(business_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.businesses.delete(auth_user.owner_id, business_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,164 @@
+use utoipa::{OpenApi, ToSchema};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    Campaign, CampaignPreview, CampaignReport, CampaignTemplate, CreateCampaign, PreviewCampaign, CAMPAIGN_VARIABLES,
+};
+use crate::domain::services::CampaignService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_campaigns, list_templates, preview_campaign, create_campaign, get_campaign_report,
+        cancel_campaign,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct CampaignState {
+    campaigns: Arc<CampaignService>,
+}
+
+/// Dunning campaigns: preview the audience for a segment and template, then send
+/// personalized balance emails on a throttled schedule and follow the outcomes.
+pub fn create_router(campaigns: Arc<CampaignService>) -> Router {
+    let state = CampaignState { campaigns };
+
+    Router::new()
+        .route("/", get(list_campaigns).post(create_campaign))
+        .route("/templates", get(list_templates))
+        .route("/preview", post(preview_campaign))
+        .route("/{id}", get(get_campaign_report))
+        .route("/{id}/cancel", post(cancel_campaign))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/campaigns",
+    tag = "campaigns",
+    responses((status = 200, body = Vec<Campaign>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_campaigns(
+    auth_user: AuthUser,
+    State(state): State<CampaignState>,
+) -> Result<Json<Vec<Campaign>>, ApiError> {
+    let campaigns = state.campaigns.list(auth_user.user_id).await?;
+    Ok(Json(campaigns))
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct CampaignTemplateResponse {
+    template: CampaignTemplate,
+    subject: &'static str,
+    body: &'static str,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+struct CampaignTemplatesResponse {
+    templates: Vec<CampaignTemplateResponse>,
+    /// Placeholders resolved per client when each email is sent
+    variables: Vec<&'static str>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/campaigns/templates",
+    tag = "campaigns",
+    responses((status = 200, body = CampaignTemplatesResponse)),
+    security(("bearer_auth" = []))
+)]
+async fn list_templates(_auth_user: AuthUser) -> Json<CampaignTemplatesResponse> {
+    let templates = [CampaignTemplate::Friendly, CampaignTemplate::Firm, CampaignTemplate::FinalNotice]
+        .into_iter()
+        .map(|template| CampaignTemplateResponse {
+            template,
+            subject: template.subject(),
+            body: template.body(),
+        })
+        .collect();
+
+    Json(CampaignTemplatesResponse {
+        templates,
+        variables: CAMPAIGN_VARIABLES.to_vec(),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/preview",
+    tag = "campaigns",
+    request_body = PreviewCampaign,
+    responses((status = 200, body = CampaignPreview), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn preview_campaign(
+    auth_user: AuthUser,
+    State(state): State<CampaignState>,
+    Json(payload): Json<PreviewCampaign>,
+) -> Result<Json<CampaignPreview>, ApiError> {
+    let preview = state.campaigns.preview(auth_user.user_id, payload).await?;
+    Ok(Json(preview))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns",
+    tag = "campaigns",
+    request_body = CreateCampaign,
+    responses((status = 201, body = CampaignReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_campaign(
+    auth_user: AuthUser,
+    State(state): State<CampaignState>,
+    Json(payload): Json<CreateCampaign>,
+) -> Result<(StatusCode, Json<CampaignReport>), ApiError> {
+    let report = state.campaigns.create(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/campaigns/{id}",
+    tag = "campaigns",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CampaignReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_campaign_report(
+    auth_user: AuthUser,
+    State(state): State<CampaignState>,
+    Path(campaign_id): Path<Uuid>,
+) -> Result<Json<CampaignReport>, ApiError> {
+    let report = state.campaigns.report(auth_user.user_id, campaign_id).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/campaigns/{id}/cancel",
+    tag = "campaigns",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CampaignReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_campaign(
+    auth_user: AuthUser,
+    State(state): State<CampaignState>,
+    Path(campaign_id): Path<Uuid>,
+) -> Result<Json<CampaignReport>, ApiError> {
+    let report = state.campaigns.cancel(auth_user.user_id, campaign_id).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{ClientCreditEntry, ClientCreditLedger, CreateClientCredit, CreditApplied};
+use crate::domain::services::{ClientCreditService, InvoiceService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_client_credit, deposit_client_credit, refund_client_credit, apply_client_credit,
+    )
+)]
+pub struct ApiDoc;
+
+/// A client's credit ledger, merged into the clients router
+pub fn create_router(client_credit: Arc<ClientCreditService>) -> Router {
+    Router::new()
+        .route("/{id}/credit", get(get_client_credit))
+        .route("/{id}/credit/deposits", post(deposit_client_credit))
+        .route("/{id}/credit/refunds", post(refund_client_credit))
+        .with_state(client_credit)
+}
+
+/// Applying client credit by hand, merged into the invoices router
+pub fn create_invoice_router(invoice_service: Arc<InvoiceService>) -> Router {
+    Router::new()
+        .route("/{id}/apply-credit", post(apply_client_credit))
+        .with_state(invoice_service)
+}
+
+/// Credit available in each currency and every deposit, application and refund
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}/credit",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ClientCreditLedger), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_client_credit(
+    auth_user: AuthUser,
+    State(client_credit): State<Arc<ClientCreditService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ClientCreditLedger>, ApiError> {
+    let ledger = client_credit.ledger(auth_user.user_id, id).await?;
+    Ok(Json(ledger))
+}
+
+/// Records a retainer or prepayment the client has paid in
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/credit/deposits",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    request_body = CreateClientCredit,
+    responses((status = 201, body = ClientCreditEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn deposit_client_credit(
+    auth_user: AuthUser,
+    State(client_credit): State<Arc<ClientCreditService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateClientCredit>,
+) -> Result<(StatusCode, Json<ClientCreditEntry>), ApiError> {
+    let entry = client_credit.deposit(auth_user.user_id, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Records unused credit paid back to the client. Can't exceed what's available.
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/credit/refunds",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    request_body = CreateClientCredit,
+    responses((status = 201, body = ClientCreditEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn refund_client_credit(
+    auth_user: AuthUser,
+    State(client_credit): State<Arc<ClientCreditService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateClientCredit>,
+) -> Result<(StatusCode, Json<ClientCreditEntry>), ApiError> {
+    let entry = client_credit.refund(auth_user.user_id, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Settles what the client's credit covers of the invoice's balance
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/apply-credit",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CreditApplied), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn apply_client_credit(
+    auth_user: AuthUser,
+    State(invoice_service): State<Arc<InvoiceService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<CreditApplied>, ApiError> {
+    let applied = invoice_service.apply_client_credit(auth_user.user_id, id).await?;
+    Ok(Json(applied))
+}
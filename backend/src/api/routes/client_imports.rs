@@ -0,0 +1,251 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use axum_extra::extract::Multipart;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
+use crate::api::middleware::scrape_auth::constant_time_eq;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    Client, ClientImport, ClientImportFilter, ClientImportJob, ClientImportQuery, InboundAddress, InboundEmail,
+};
+use crate::domain::services::{ClientCsvImportService, ClientImportOutcome, ClientImportService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_address, rotate_address, list_imports, confirm_import, dismiss_import, receive_email,
+        import_csv, get_import_job,
+    )
+)]
+pub struct ApiDoc;
+
+/// Header the mail provider sends when `INBOUND_EMAIL_SECRET` is configured
+const INBOUND_SECRET_HEADER: &str = "X-Inbound-Secret";
+
+#[derive(Clone)]
+struct InboundState {
+    imports: Arc<ClientImportService>,
+    secret: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct InboundAck {
+    received: bool,
+    import_id: Uuid,
+}
+
+/// The caller's forwarding address, under `/settings/inbound-address`
+pub fn create_settings_router(imports: Arc<ClientImportService>) -> Router {
+    Router::new()
+        .route("/", get(get_address))
+        .route("/rotate", post(rotate_address))
+        .with_state(imports)
+}
+
+/// Imports awaiting confirmation, merged into `/notifications`
+pub fn create_inbox_router(imports: Arc<ClientImportService>) -> Router {
+    Router::new()
+        .route("/client-imports", get(list_imports))
+        .route("/client-imports/{id}/confirm", post(confirm_import))
+        .route("/client-imports/{id}/dismiss", post(dismiss_import))
+        .with_state(imports)
+}
+
+/// CSV uploads, merged into the clients router
+pub fn create_csv_router(csv_imports: Arc<ClientCsvImportService>) -> Router {
+    Router::new()
+        .route(
+            "/import",
+            // Bounded by the global request limit and the file size check
+            post(import_csv).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/import/{id}", get(get_import_job))
+        .with_state(csv_imports)
+}
+
+/// Public endpoint the mail provider posts forwarded messages to
+pub fn create_inbound_router(imports: Arc<ClientImportService>, secret: Option<String>) -> Router {
+    let state = InboundState { imports, secret };
+
+    Router::new()
+        .route("/u/{token}", post(receive_email))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/inbound-address",
+    tag = "clients",
+    responses((status = 200, body = InboundAddress), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_address(
+    auth_user: AuthUser,
+    State(imports): State<Arc<ClientImportService>>,
+) -> Result<Json<InboundAddress>, ApiError> {
+    let address = imports.address(auth_user.user_id).await?;
+    Ok(Json(address))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/settings/inbound-address/rotate",
+    tag = "clients",
+    responses((status = 200, body = InboundAddress), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn rotate_address(
+    auth_user: AuthUser,
+    State(imports): State<Arc<ClientImportService>>,
+) -> Result<Json<InboundAddress>, ApiError> {
+    let address = imports.rotate_address(auth_user.user_id).await?;
+    Ok(Json(address))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/client-imports",
+    tag = "clients",
+    params(ClientImportFilter),
+    responses((status = 200, body = Vec<ClientImport>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_imports(
+    auth_user: AuthUser,
+    State(imports): State<Arc<ClientImportService>>,
+    Query(filter): Query<ClientImportFilter>,
+) -> Result<Json<Vec<ClientImport>>, ApiError> {
+    let imports = imports.list(auth_user.user_id, filter).await?;
+    Ok(Json(imports))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/client-imports/{id}/confirm",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn confirm_import(
+    auth_user: AuthUser,
+    State(imports): State<Arc<ClientImportService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Client>, ApiError> {
+    let client = imports.confirm(auth_user.user_id, id).await?;
+    Ok(Json(client))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/client-imports/{id}/dismiss",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ClientImport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn dismiss_import(
+    auth_user: AuthUser,
+    State(imports): State<Arc<ClientImportService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ClientImport>, ApiError> {
+    let import = imports.dismiss(auth_user.user_id, id).await?;
+    Ok(Json(import))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/inbound/u/{token}",
+    tag = "clients",
+    params(("token" = String, Path)),
+    request_body = InboundEmail,
+    responses((status = 202, body = InboundAck), ApiError)
+)]
+async fn receive_email(
+    State(state): State<InboundState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<InboundEmail>,
+) -> Result<(StatusCode, Json<InboundAck>), ApiError> {
+    if let Some(expected) = &state.secret {
+        let given = headers
+            .get(INBOUND_SECRET_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        if !constant_time_eq(expected.as_bytes(), given.as_bytes()) {
+            return Err(ApiError::Unauthorized);
+        }
+    }
+
+    let import = state.imports.receive(&token, payload).await.inspect_err(|e| {
+        tracing::warn!("Inbound email not imported: {}", e);
+    })?;
+    Ok((StatusCode::ACCEPTED, Json(InboundAck { received: true, import_id: import.id })))
+}
+
+/// 200 with the report for dry runs and small files, 202 with the job for
+/// large files imported in the background
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/import",
+    tag = "clients",
+    params(ClientImportQuery),
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 200, description = "Dry runs and small files, imported in the request", body = crate::domain::models::ClientImportReport), (status = 202, description = "Large files, queued", body = ClientImportJob), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn import_csv(
+    auth_user: AuthUser,
+    State(csv_imports): State<Arc<ClientCsvImportService>>,
+    Query(query): Query<ClientImportQuery>,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+        let response = match csv_imports.import(auth_user.user_id, &file_name, &data, query.dry_run).await? {
+            ClientImportOutcome::Completed(report) => Json(report).into_response(),
+            ClientImportOutcome::Queued(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        };
+        return Ok(response);
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/import/{id}",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ClientImportJob), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_import_job(
+    auth_user: AuthUser,
+    State(csv_imports): State<Arc<ClientCsvImportService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ClientImportJob>, ApiError> {
+    let job = csv_imports.get_job(auth_user.user_id, id).await?;
+    Ok(Json(job))
+}
@@ -1,21 +1,41 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post, put, delete},
     Json, Router,
 };
+use chrono::Utc;
 use std::sync::Arc;
 use uuid::Uuid;
+use utoipa::OpenApi;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
-use crate::domain::models::{CreateClient, UpdateClient, ClientListFilter};
+use crate::domain::models::{
+    parse_batch_ids, CreateClient, UpdateClient, ClientListFilter, SetClientParent, EmailStatementRequest, StatementEmailed,
+    StatementFormat, StatementQuery, ClientInvoiceDefaults, InvoiceDefaultsQuery,
+};
+use crate::domain::services::ClientStatementService;
 use crate::application::use_cases::{
     CreateClientUseCase, GetClientUseCase, ListClientsUseCase,
     UpdateClientUseCase, DeleteClientUseCase, GetClientInvoicesUseCase,
-    GetClientStatsUseCase,
+    GetClientStatsUseCase, SetClientParentUseCase, GetClientStatementUseCase,
+    ArchiveClientUseCase, RestoreClientUseCase, ListDeletedClientsUseCase,
 };
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_clients, create_client, get_client, update_client, delete_client, get_client_invoice_defaults, check_client_vat,
+        archive_client,
+        unarchive_client, restore_client, list_deleted_clients, get_client_invoices,
+        set_client_parent, get_client_statement, email_client_statement, get_client_stats,
+    ),
+    components(schemas(crate::domain::models::ClientHierarchyStatement))
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct ClientState {
     create_client_uc: Arc<CreateClientUseCase>,
@@ -25,6 +45,11 @@ struct ClientState {
     delete_client_uc: Arc<DeleteClientUseCase>,
     get_client_invoices_uc: Arc<GetClientInvoicesUseCase>,
     get_client_stats_uc: Arc<GetClientStatsUseCase>,
+    set_client_parent_uc: Arc<SetClientParentUseCase>,
+    get_client_statement_uc: Arc<GetClientStatementUseCase>,
+    archive_client_uc: Arc<ArchiveClientUseCase>,
+    restore_client_uc: Arc<RestoreClientUseCase>,
+    list_deleted_clients_uc: Arc<ListDeletedClientsUseCase>,
 }
 
 pub fn create_router(
@@ -35,6 +60,11 @@ pub fn create_router(
     delete_client_uc: Arc<DeleteClientUseCase>,
     get_client_invoices_uc: Arc<GetClientInvoicesUseCase>,
     get_client_stats_uc: Arc<GetClientStatsUseCase>,
+    set_client_parent_uc: Arc<SetClientParentUseCase>,
+    get_client_statement_uc: Arc<GetClientStatementUseCase>,
+    archive_client_uc: Arc<ArchiveClientUseCase>,
+    restore_client_uc: Arc<RestoreClientUseCase>,
+    list_deleted_clients_uc: Arc<ListDeletedClientsUseCase>,
 ) -> Router {
     let state = ClientState {
         create_client_uc,
@@ -44,6 +74,11 @@ pub fn create_router(
         delete_client_uc,
         get_client_invoices_uc,
         get_client_stats_uc,
+        set_client_parent_uc,
+        get_client_statement_uc,
+        archive_client_uc,
+        restore_client_uc,
+        list_deleted_clients_uc,
     };
 
     Router::new()
@@ -53,24 +88,56 @@ pub fn create_router(
         .route("/{id}", put(update_client))
         .route("/{id}", delete(delete_client))
         .route("/{id}/invoices", get(get_client_invoices))
+        .route("/{id}/invoice-defaults", get(get_client_invoice_defaults))
+        .route("/{id}/vat-check", post(check_client_vat))
+        .route("/{id}/parent", put(set_client_parent))
+        .route("/{id}/statement", get(get_client_statement))
+        .route("/{id}/archive", post(archive_client).delete(unarchive_client))
+        .route("/{id}/restore", post(restore_client))
         .route("/stats", get(get_client_stats))
+        .route("/trash", get(list_deleted_clients))
         .with_state(state)
 }
 
+/// Lists clients a page at a time, or with `?ids=a,b,c` fetches those clients as a batch
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients",
+    tag = "clients",
+    params(ClientListFilter),
+    responses((status = 200, description = "A page of clients, or with `ids` a batch of them", body = crate::domain::models::Page<crate::domain::models::ClientResponse>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn list_clients(
     auth_user: AuthUser,
     State(state): State<ClientState>,
     Query(filter): Query<ClientListFilter>,
-) -> Result<Json<Vec<crate::domain::models::ClientResponse>>, ApiError> {
+) -> Result<Response, ApiError> {
+    if let Some(ids) = filter.ids.as_deref() {
+        let ids = parse_batch_ids(ids).map_err(ApiError::BadRequest)?;
+        let batch = state.list_clients_uc.execute_batch(auth_user.user_id, ids).await?;
+        return Ok(Json(batch).into_response());
+    }
+
+    let page = filter.page_request().map_err(ApiError::BadRequest)?;
     let clients = state.list_clients_uc.execute(
         auth_user.user_id,
         filter.search,
-        filter.limit,
-        filter.offset,
+        filter.parent_client_id,
+        filter.include_archived.unwrap_or(false),
+        &page,
     ).await?;
-    Ok(Json(clients))
+    Ok(Json(clients).into_response())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients",
+    tag = "clients",
+    request_body = CreateClient,
+    responses((status = 201, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_client(
     auth_user: AuthUser,
     State(state): State<ClientState>,
@@ -80,6 +147,14 @@ async fn create_client(
     Ok((StatusCode::CREATED, Json(client)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_client(
     auth_user: AuthUser,
     State(state): State<ClientState>,
@@ -89,6 +164,15 @@ async fn get_client(
     Ok(Json(client))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/clients/{id}",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateClient,
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_client(
     auth_user: AuthUser,
     State(state): State<ClientState>,
@@ -99,6 +183,14 @@ async fn update_client(
     Ok(Json(client))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/clients/{id}",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn delete_client(
     auth_user: AuthUser,
     State(state): State<ClientState>,
@@ -108,6 +200,120 @@ async fn delete_client(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Payment terms, due date, currency, tax, discount and language a new invoice for the
+/// client starts with
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}/invoice-defaults",
+    tag = "clients",
+    params(("id" = Uuid, Path), InvoiceDefaultsQuery),
+    responses((status = 200, body = ClientInvoiceDefaults), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_client_invoice_defaults(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+    Query(query): Query<InvoiceDefaultsQuery>,
+) -> Result<Json<ClientInvoiceDefaults>, ApiError> {
+    let issue_date = query.issue_date.unwrap_or_else(|| Utc::now().date_naive());
+    let defaults = state.get_client_uc.invoice_defaults(auth_user.user_id, client_id, issue_date).await?;
+    Ok(Json(defaults))
+}
+
+/// Check the client's VAT number against VIES again, e.g. after VIES was unavailable
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/vat-check",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn check_client_vat(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::Client>, ApiError> {
+    let client = state.update_client_uc.check_vat_number(auth_user.user_id, client_id).await?;
+    Ok(Json(client))
+}
+
+/// Hide the client from the list and block new invoices, keeping its history
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/archive",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn archive_client(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::Client>, ApiError> {
+    let client = state.archive_client_uc.execute(auth_user.user_id, client_id, true).await?;
+    Ok(Json(client))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/clients/{id}/archive",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn unarchive_client(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::Client>, ApiError> {
+    let client = state.archive_client_uc.execute(auth_user.user_id, client_id, false).await?;
+    Ok(Json(client))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/restore",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn restore_client(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::Client>, ApiError> {
+    let client = state.restore_client_uc.execute(auth_user.user_id, client_id).await?;
+    Ok(Json(client))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/trash",
+    tag = "clients",
+    responses((status = 200, body = Vec<crate::domain::models::Client>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_deleted_clients(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+) -> Result<Json<Vec<crate::domain::models::Client>>, ApiError> {
+    let clients = state.list_deleted_clients_uc.execute(auth_user.user_id).await?;
+    Ok(Json(clients))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}/invoices",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<crate::domain::models::InvoiceResponse>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_client_invoices(
     auth_user: AuthUser,
     State(state): State<ClientState>,
@@ -117,6 +323,120 @@ async fn get_client_invoices(
     Ok(Json(invoices))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/clients/{id}/parent",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    request_body = SetClientParent,
+    responses((status = 200, body = crate::domain::models::Client), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_client_parent(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+    Json(payload): Json<SetClientParent>,
+) -> Result<Json<crate::domain::models::Client>, ApiError> {
+    let client = state.set_client_parent_uc.execute(auth_user.user_id, client_id, payload.parent_client_id).await?;
+    Ok(Json(client))
+}
+
+/// Without dates, the balance rolled up over the client's subsidiaries. With
+/// `start_date` and `end_date`, the statement of account for that period as JSON,
+/// or as a file with `format=pdf|csv`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/{id}/statement",
+    tag = "clients",
+    params(("id" = Uuid, Path), StatementQuery),
+    responses((status = 200, description = "The statement of account as JSON, PDF or CSV; without dates, a ClientHierarchyStatement", content((crate::domain::models::AccountStatement = "application/json"), ([u8] = "application/pdf"), (String = "text/csv"))), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_client_statement(
+    auth_user: AuthUser,
+    State(state): State<ClientState>,
+    Path(client_id): Path<Uuid>,
+    Query(query): Query<StatementQuery>,
+) -> Result<Response, ApiError> {
+    let format = match query.format.as_deref() {
+        Some(format) => StatementFormat::parse(format)
+            .ok_or_else(|| ApiError::BadRequest("format must be json, pdf or csv".to_string()))?,
+        None => StatementFormat::Json,
+    };
+
+    let (start_date, end_date) = match (query.start_date, query.end_date) {
+        (Some(start_date), Some(end_date)) => (start_date, end_date),
+        (None, None) if format == StatementFormat::Json => {
+            let statement = state.get_client_statement_uc.execute(auth_user.user_id, client_id).await?;
+            return Ok(Json(statement).into_response());
+        }
+        _ => return Err(ApiError::BadRequest("start_date and end_date are required together".to_string())),
+    };
+
+    if format == StatementFormat::Json {
+        let statement = state
+            .get_client_statement_uc
+            .execute_for_period(auth_user.user_id, client_id, start_date, end_date)
+            .await?;
+        return Ok(Json(statement).into_response());
+    }
+
+    let file = state
+        .get_client_statement_uc
+        .export(auth_user.user_id, client_id, start_date, end_date, format)
+        .await?;
+    let (content_type, extension) = match format {
+        StatementFormat::Csv => ("text/csv", "csv"),
+        _ => ("application/pdf", "pdf"),
+    };
+    let filename = format!("statement_{}_{}_{}.{}", client_id, start_date, end_date, extension);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", filename)),
+        ],
+        file,
+    )
+        .into_response())
+}
+
+/// Emailing statements of account, merged into `/clients`
+pub fn create_statement_router(client_statements: Arc<ClientStatementService>) -> Router {
+    Router::new()
+        .route("/{id}/statement/email", post(email_client_statement))
+        .with_state(client_statements)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/clients/{id}/statement/email",
+    tag = "clients",
+    params(("id" = Uuid, Path)),
+    request_body = EmailStatementRequest,
+    responses((status = 200, body = StatementEmailed), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn email_client_statement(
+    auth_user: AuthUser,
+    State(client_statements): State<Arc<ClientStatementService>>,
+    Path(client_id): Path<Uuid>,
+    Json(payload): Json<EmailStatementRequest>,
+) -> Result<Json<StatementEmailed>, ApiError> {
+    let (sent_to, statement) = client_statements
+        .email(auth_user.user_id, client_id, payload.start_date, payload.end_date, payload.to)
+        .await?;
+    Ok(Json(StatementEmailed { sent_to, closing_balance: statement.closing_balance }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/clients/stats",
+    tag = "clients",
+    responses((status = 200, body = crate::domain::models::ClientStats), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_client_stats(
     auth_user: AuthUser,
     State(state): State<ClientState>,
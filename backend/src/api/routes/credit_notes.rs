@@ -0,0 +1,156 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{CreateCreditNote, CreditNote, CreditNoteListFilter};
+use crate::application::use_cases::{
+    CreateCreditNoteUseCase, GetCreditNoteUseCase, ListCreditNotesUseCase,
+    VoidCreditNoteUseCase, GetCreditNotePdfUseCase,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_credit_notes, create_credit_note, get_credit_note, void_credit_note,
+        get_credit_note_pdf,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct CreditNoteState {
+    create_credit_note_uc: Arc<CreateCreditNoteUseCase>,
+    get_credit_note_uc: Arc<GetCreditNoteUseCase>,
+    list_credit_notes_uc: Arc<ListCreditNotesUseCase>,
+    void_credit_note_uc: Arc<VoidCreditNoteUseCase>,
+    get_credit_note_pdf_uc: Arc<GetCreditNotePdfUseCase>,
+}
+
+pub fn create_router(
+    create_credit_note_uc: Arc<CreateCreditNoteUseCase>,
+    get_credit_note_uc: Arc<GetCreditNoteUseCase>,
+    list_credit_notes_uc: Arc<ListCreditNotesUseCase>,
+    void_credit_note_uc: Arc<VoidCreditNoteUseCase>,
+    get_credit_note_pdf_uc: Arc<GetCreditNotePdfUseCase>,
+) -> Router {
+    let state = CreditNoteState {
+        create_credit_note_uc,
+        get_credit_note_uc,
+        list_credit_notes_uc,
+        void_credit_note_uc,
+        get_credit_note_pdf_uc,
+    };
+
+    Router::new()
+        .route("/", get(list_credit_notes))
+        .route("/", post(create_credit_note))
+        .route("/{id}", get(get_credit_note))
+        .route("/{id}/void", post(void_credit_note))
+        .route("/{id}/pdf", get(get_credit_note_pdf))
+        .with_state(state)
+}
+
+/// Lists credit notes, optionally for one invoice (`?invoice_id=`) or client (`?client_id=`)
+#[utoipa::path(
+    get,
+    path = "/api/v1/credit-notes",
+    tag = "credit-notes",
+    params(CreditNoteListFilter),
+    responses((status = 200, body = Vec<CreditNote>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_credit_notes(
+    auth_user: AuthUser,
+    State(state): State<CreditNoteState>,
+    Query(filter): Query<CreditNoteListFilter>,
+) -> Result<Json<Vec<CreditNote>>, ApiError> {
+    if filter.limit.is_some_and(|limit| !(1..=200).contains(&limit)) {
+        return Err(ApiError::Validation("limit must be between 1 and 200".to_string()));
+    }
+
+    let credit_notes = state.list_credit_notes_uc.execute(auth_user.user_id, filter).await?;
+    Ok(Json(credit_notes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/credit-notes",
+    tag = "credit-notes",
+    request_body = CreateCreditNote,
+    responses((status = 201, body = CreditNote), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_credit_note(
+    auth_user: AuthUser,
+    State(state): State<CreditNoteState>,
+    Json(payload): Json<CreateCreditNote>,
+) -> Result<(StatusCode, Json<CreditNote>), ApiError> {
+    let credit_note = state.create_credit_note_uc.execute(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(credit_note)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/credit-notes/{id}",
+    tag = "credit-notes",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CreditNote), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_credit_note(
+    auth_user: AuthUser,
+    State(state): State<CreditNoteState>,
+    Path(credit_note_id): Path<Uuid>,
+) -> Result<Json<CreditNote>, ApiError> {
+    let credit_note = state.get_credit_note_uc.execute(auth_user.user_id, credit_note_id).await?;
+    Ok(Json(credit_note))
+}
+
+/// Withdraws the credit; the invoice owes whatever it had settled again
+#[utoipa::path(
+    post,
+    path = "/api/v1/credit-notes/{id}/void",
+    tag = "credit-notes",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = CreditNote), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn void_credit_note(
+    auth_user: AuthUser,
+    State(state): State<CreditNoteState>,
+    Path(credit_note_id): Path<Uuid>,
+) -> Result<Json<CreditNote>, ApiError> {
+    let credit_note = state.void_credit_note_uc.execute(auth_user.user_id, credit_note_id).await?;
+    Ok(Json(credit_note))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/credit-notes/{id}/pdf",
+    tag = "credit-notes",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, description = "The credit note PDF", content_type = "application/pdf", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_credit_note_pdf(
+    auth_user: AuthUser,
+    State(state): State<CreditNoteState>,
+    Path(credit_note_id): Path<Uuid>,
+) -> Result<(HeaderMap, Vec<u8>), ApiError> {
+    let pdf = state.get_credit_note_pdf_uc.execute(auth_user.user_id, credit_note_id).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "application/pdf".parse().unwrap(),
+    );
+    Ok((headers, pdf))
+}
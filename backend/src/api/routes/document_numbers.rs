@@ -0,0 +1,96 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{DocumentNumberFormat, DocumentType, UpdateDocumentNumberFormat};
+use crate::domain::services::DocumentNumberService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_formats, get_format, update_format,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct DocumentNumberState {
+    document_numbers: Arc<DocumentNumberService>,
+}
+
+pub fn create_router(document_numbers: Arc<DocumentNumberService>) -> Router {
+    let state = DocumentNumberState { document_numbers };
+
+    Router::new()
+        .route("/", get(list_formats))
+        .route("/{doc_type}", get(get_format))
+        .route("/{doc_type}", put(update_format))
+        .with_state(state)
+}
+
+fn parse_doc_type(value: &str) -> Result<DocumentType, ApiError> {
+    DocumentType::parse(value)
+        .ok_or_else(|| ApiError::BadRequest(format!("Unknown document type: {}", value)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/document-numbers",
+    tag = "settings",
+    responses((status = 200, body = Vec<DocumentNumberFormat>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_formats(
+    auth_user: AuthUser,
+    State(state): State<DocumentNumberState>,
+) -> Result<Json<Vec<DocumentNumberFormat>>, ApiError> {
+    let formats = state.document_numbers.list_formats(auth_user.user_id).await?;
+    Ok(Json(formats))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/document-numbers/{doc_type}",
+    tag = "settings",
+    params(("doc_type" = String, Path)),
+    responses((status = 200, body = DocumentNumberFormat), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_format(
+    auth_user: AuthUser,
+    State(state): State<DocumentNumberState>,
+    Path(doc_type): Path<String>,
+) -> Result<Json<DocumentNumberFormat>, ApiError> {
+    let doc_type = parse_doc_type(&doc_type)?;
+    let format = state.document_numbers.get_format(auth_user.user_id, doc_type).await?;
+    Ok(Json(format))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/document-numbers/{doc_type}",
+    tag = "settings",
+    params(("doc_type" = String, Path)),
+    request_body = UpdateDocumentNumberFormat,
+    responses((status = 200, body = DocumentNumberFormat), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_format(
+    auth_user: AuthUser,
+    State(state): State<DocumentNumberState>,
+    Path(doc_type): Path<String>,
+    Json(payload): Json<UpdateDocumentNumberFormat>,
+) -> Result<Json<DocumentNumberFormat>, ApiError> {
+    let doc_type = parse_doc_type(&doc_type)?;
+    let format = state
+        .document_numbers
+        .update_format(auth_user.user_id, doc_type, payload)
+        .await?;
+    Ok(Json(format))
+}
@@ -0,0 +1,116 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    routing::put,
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{EmailSignature, EmailSignatureSettings};
+use crate::domain::services::EmailSignatureService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_signatures, set_default_signature, delete_default_signature, set_member_signature,
+        delete_member_signature,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct EmailSignatureState {
+    signatures: Arc<EmailSignatureService>,
+}
+
+/// The business default lives at `/`, the caller's own override at `/member`
+pub fn create_router(signatures: Arc<EmailSignatureService>) -> Router {
+    let state = EmailSignatureState { signatures };
+
+    Router::new()
+        .route("/", put(set_default_signature).get(get_signatures).delete(delete_default_signature))
+        .route("/member", put(set_member_signature).delete(delete_member_signature))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/email-signature",
+    tag = "settings",
+    responses((status = 200, body = EmailSignatureSettings), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_signatures(
+    auth_user: AuthUser,
+    State(state): State<EmailSignatureState>,
+) -> Result<Json<EmailSignatureSettings>, ApiError> {
+    let settings = state.signatures.get(auth_user.user_id, auth_user.owner_id).await?;
+    Ok(Json(settings))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/email-signature",
+    tag = "settings",
+    request_body = EmailSignature,
+    responses((status = 200, body = EmailSignature), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_default_signature(
+    auth_user: AuthUser,
+    State(state): State<EmailSignatureState>,
+    Json(payload): Json<EmailSignature>,
+) -> Result<Json<EmailSignature>, ApiError> {
+    let signature = state.signatures.set(auth_user.user_id, None, payload).await?;
+    Ok(Json(signature))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/settings/email-signature",
+    tag = "settings",
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_default_signature(
+    auth_user: AuthUser,
+    State(state): State<EmailSignatureState>,
+) -> Result<StatusCode, ApiError> {
+    state.signatures.delete(auth_user.user_id, None).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/email-signature/member",
+    tag = "settings",
+    request_body = EmailSignature,
+    responses((status = 200, body = EmailSignature), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_member_signature(
+    auth_user: AuthUser,
+    State(state): State<EmailSignatureState>,
+    Json(payload): Json<EmailSignature>,
+) -> Result<Json<EmailSignature>, ApiError> {
+    let signature = state.signatures.set(auth_user.user_id, Some(auth_user.owner_id), payload).await?;
+    Ok(Json(signature))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/settings/email-signature/member",
+    tag = "settings",
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_member_signature(
+    auth_user: AuthUser,
+    State(state): State<EmailSignatureState>,
+) -> Result<StatusCode, ApiError> {
+    state.signatures.delete(auth_user.user_id, Some(auth_user.owner_id)).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,113 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    EmailTemplate, EmailTemplateInfo, EmailTemplateKind, EmailTemplatePreview, PreviewEmailTemplate,
+};
+use crate::domain::services::EmailTemplateService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(list_templates, set_template, delete_template, preview_template),
+    components(schemas(EmailTemplateKind, EmailTemplate, EmailTemplateInfo, PreviewEmailTemplate, EmailTemplatePreview))
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct EmailTemplateState {
+    templates: Arc<EmailTemplateService>,
+}
+
+pub fn create_router(templates: Arc<EmailTemplateService>) -> Router {
+    let state = EmailTemplateState { templates };
+
+    Router::new()
+        .route("/", get(list_templates))
+        .route("/preview", post(preview_template))
+        .route("/{kind}", put(set_template).delete(delete_template))
+        .with_state(state)
+}
+
+fn parse_kind(kind: &str) -> Result<EmailTemplateKind, ApiError> {
+    EmailTemplateKind::parse(kind).ok_or(ApiError::NotFound)
+}
+
+/// Each named email's built-in subject and body, the placeholders it can use
+/// and the caller's override
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/email-templates",
+    tag = "settings",
+    responses((status = 200, body = Vec<EmailTemplateInfo>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_templates(
+    auth_user: AuthUser,
+    State(state): State<EmailTemplateState>,
+) -> Result<Json<Vec<EmailTemplateInfo>>, ApiError> {
+    Ok(Json(state.templates.list(auth_user.user_id).await?))
+}
+
+/// Save an override; a subject or body left out uses the built-in one
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/email-templates/{kind}",
+    tag = "settings",
+    params(("kind" = EmailTemplateKind, Path)),
+    request_body = EmailTemplate,
+    responses((status = 200, body = EmailTemplate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_template(
+    auth_user: AuthUser,
+    State(state): State<EmailTemplateState>,
+    Path(kind): Path<String>,
+    Json(payload): Json<EmailTemplate>,
+) -> Result<Json<EmailTemplate>, ApiError> {
+    let template = state.templates.set(auth_user.user_id, parse_kind(&kind)?, payload).await?;
+    Ok(Json(template))
+}
+
+/// Go back to the built-in template
+#[utoipa::path(
+    delete,
+    path = "/api/v1/settings/email-templates/{kind}",
+    tag = "settings",
+    params(("kind" = EmailTemplateKind, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_template(
+    auth_user: AuthUser,
+    State(state): State<EmailTemplateState>,
+    Path(kind): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state.templates.delete(auth_user.user_id, parse_kind(&kind)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Render a template with example values before saving it. Unknown
+/// placeholders and syntax errors are reported as validation errors.
+#[utoipa::path(
+    post,
+    path = "/api/v1/settings/email-templates/preview",
+    tag = "settings",
+    request_body = PreviewEmailTemplate,
+    responses((status = 200, body = EmailTemplatePreview), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn preview_template(
+    auth_user: AuthUser,
+    State(state): State<EmailTemplateState>,
+    Json(payload): Json<PreviewEmailTemplate>,
+) -> Result<Json<EmailTemplatePreview>, ApiError> {
+    Ok(Json(state.templates.preview(auth_user.user_id, payload).await?))
+}
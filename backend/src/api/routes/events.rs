@@ -0,0 +1,72 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use futures::{Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::services::RealtimeService;
+
+const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+#[derive(OpenApi)]
+#[openapi(paths(stream_events))]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct EventState {
+    realtime: Arc<RealtimeService>,
+}
+
+pub fn create_router(realtime: Arc<RealtimeService>) -> Router {
+    let state = EventState { realtime };
+
+    Router::new()
+        .route("/stream", get(stream_events))
+        .with_state(state)
+}
+
+/// Server-sent events for the user's invoices: `invoice.status_changed`,
+/// `invoice.viewed`, `payment.created` and `discussion.message`. Reconnecting
+/// with `Last-Event-ID` replays what was missed in the last hour.
+#[utoipa::path(
+    get,
+    path = "/api/v1/events/stream",
+    tag = "events",
+    params(("Last-Event-ID" = Option<i64>, Header, description = "Id of the last event received")),
+    responses(
+        (status = 200, description = "Event stream; each event's data is a RealtimeEvent", content_type = "text/event-stream", body = String),
+        ApiError
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn stream_events(
+    auth_user: AuthUser,
+    State(state): State<EventState>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<i64>().ok());
+
+    let events = state.realtime.subscribe(auth_user.user_id, last_event_id).await?;
+    let stream = events.filter_map(|event| async move {
+        match Event::default().id(event.id.to_string()).event(event.event_type.as_str()).json_data(&*event) {
+            Ok(sse_event) => Some(Ok(sse_event)),
+            Err(e) => {
+                tracing::error!(event_id = event.id, "Failed to encode realtime event: {}", e);
+                None
+            }
+        }
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -6,15 +6,25 @@ use axum::{
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use utoipa::OpenApi;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
-use crate::domain::models::{CreateExpense, UpdateExpense, ExpenseListFilter};
+use crate::domain::models::{CreateExpense, UpdateExpense, ExpenseListFilter, Page};
 use crate::application::use_cases::{
     CreateExpenseUseCase, GetExpenseUseCase, ListExpensesUseCase,
     UpdateExpenseUseCase, DeleteExpenseUseCase, GetExpenseStatsUseCase,
 };
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_expenses, create_expense, get_expense, update_expense, delete_expense,
+        get_expense_stats,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct ExpenseState {
     create_expense_uc: Arc<CreateExpenseUseCase>,
@@ -52,11 +62,20 @@ pub fn create_router(
         .with_state(state)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses",
+    tag = "expenses",
+    params(ExpenseListFilter),
+    responses((status = 200, body = Page<crate::domain::models::ExpenseResponse>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn list_expenses(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
     Query(filter): Query<ExpenseListFilter>,
-) -> Result<Json<Vec<crate::domain::models::ExpenseResponse>>, ApiError> {
+) -> Result<Json<Page<crate::domain::models::ExpenseResponse>>, ApiError> {
+    let page = filter.page_request().map_err(ApiError::BadRequest)?;
     let expenses = state.list_expenses_uc.execute(
         auth_user.user_id,
         filter.category,
@@ -64,12 +83,19 @@ async fn list_expenses(
         filter.date_to,
         filter.tax_deductible,
         filter.search,
-        filter.limit,
-        filter.offset,
+        &page,
     ).await?;
     Ok(Json(expenses))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/expenses",
+    tag = "expenses",
+    request_body = CreateExpense,
+    responses((status = 201, body = crate::domain::models::Expense), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_expense(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
@@ -79,6 +105,14 @@ async fn create_expense(
     Ok((StatusCode::CREATED, Json(expense)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::Expense), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_expense(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
@@ -88,6 +122,15 @@ async fn get_expense(
     Ok(Json(expense))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateExpense,
+    responses((status = 200, body = crate::domain::models::Expense), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_expense(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
@@ -98,6 +141,14 @@ async fn update_expense(
     Ok(Json(expense))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/expenses/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn delete_expense(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
@@ -107,6 +158,13 @@ async fn delete_expense(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses/stats",
+    tag = "expenses",
+    responses((status = 200, body = crate::domain::models::ExpenseStats), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_expense_stats(
     auth_user: AuthUser,
     State(state): State<ExpenseState>,
@@ -7,23 +7,33 @@ use axum::{
 };
 use axum_extra::extract::Multipart;
 use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
 use std::sync::Arc;
 
 use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
 use crate::api::middleware::AuthUser;
 use crate::domain::services::{FileService, UploadedFile, FileError};
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        upload_file, list_files, download_file, delete_file,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct FileState {
     file_service: Arc<FileService>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct FileListResponse {
     files: Vec<UploadedFile>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct FileDeleteRequest {
     file_name: String,
 }
@@ -40,8 +50,16 @@ pub fn create_router(file_service: Arc<FileService>) -> Router {
 }
 
 /// Upload a file using multipart form data
+#[utoipa::path(
+    post,
+    path = "/api/v1/files/upload",
+    tag = "files",
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 200, body = UploadedFile), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn upload_file(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<FileState>,
     mut multipart: Multipart,
 ) -> Result<Json<UploadedFile>, ApiError> {
@@ -67,9 +85,12 @@ async fn upload_file(
         // Upload the file
         let result = state
             .file_service
-            .upload_file(&file_data, &file_name, &content_type)
+            .upload_file(auth_user.user_id, &file_data, &file_name, &content_type)
             .await
-            .map_err(|_e| ApiError::Internal)?;
+            .map_err(|e| {
+                tracing::error!("File upload failed: {}", e);
+                ApiError::Internal
+            })?;
 
         uploaded_file = Some(result);
     }
@@ -80,14 +101,21 @@ async fn upload_file(
     }
 }
 
-/// List all uploaded files
+/// List the caller's uploaded files
+#[utoipa::path(
+    get,
+    path = "/api/v1/files/list",
+    tag = "files",
+    responses((status = 200, body = FileListResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn list_files(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<FileState>,
 ) -> Result<Json<FileListResponse>, ApiError> {
     let files = state
         .file_service
-        .list_files()
+        .list_files(auth_user.user_id)
         .await
         .map_err(|_| ApiError::Internal)?;
 
@@ -95,25 +123,37 @@ async fn list_files(
 }
 
 /// Download a file
+#[utoipa::path(
+    get,
+    path = "/api/v1/files/download/{file_name}",
+    tag = "files",
+    params(("file_name" = String, Path)),
+    responses((status = 200, description = "The file, with its stored content type", content_type = "application/octet-stream", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn download_file(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<FileState>,
     axum::extract::Path(file_name): axum::extract::Path<String>,
 ) -> Result<Response, ApiError> {
     let file_data = state
         .file_service
-        .get_file(&file_name)
+        .get_file(auth_user.user_id, &file_name)
         .await
         .map_err(|e| match e {
             FileError::NotFound(_) => {
                 ApiError::BadRequest(format!("File not found: {}", file_name))
             }
-            _ => ApiError::Internal,
+            FileError::InvalidFileName => ApiError::BadRequest("Invalid file name".to_string()),
+            e => {
+                tracing::error!("File download failed: {}", e);
+                ApiError::Internal
+            }
         })?;
 
     let file_info = state
         .file_service
-        .get_file_info(&file_name)
+        .get_file_info(auth_user.user_id, &file_name)
         .await
         .map_err(|_| ApiError::Internal)?;
 
@@ -133,19 +173,28 @@ async fn download_file(
 }
 
 /// Delete a file
+#[utoipa::path(
+    post,
+    path = "/api/v1/files/delete",
+    tag = "files",
+    request_body = FileDeleteRequest,
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn delete_file(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<FileState>,
     Json(payload): Json<FileDeleteRequest>,
 ) -> Result<StatusCode, ApiError> {
     state
         .file_service
-        .delete_file(&payload.file_name)
+        .delete_file(auth_user.user_id, &payload.file_name)
         .await
         .map_err(|e| match e {
             FileError::NotFound(_) => {
                 ApiError::BadRequest(format!("File not found: {}", payload.file_name))
             }
+            FileError::InvalidFileName => ApiError::BadRequest("Invalid file name".to_string()),
             _ => ApiError::Internal,
         })?;
 
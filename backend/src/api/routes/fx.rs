@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{FxRate, FxRateHistoryQuery, FxRateQuery, SetFxRate};
+use crate::domain::services::FxRateService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_rate, get_history, set_override, delete_override,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct FxState {
+    fx_rates: Arc<FxRateService>,
+}
+
+pub fn create_router(fx_rates: Arc<FxRateService>) -> Router {
+    let state = FxState { fx_rates };
+
+    Router::new()
+        .route("/rates", get(get_rate).put(set_override))
+        .route("/rates/history", get(get_history))
+        .route("/rates/{id}", delete(delete_override))
+        .with_state(state)
+}
+
+/// Rate into the user's base currency in effect on a day
+#[utoipa::path(
+    get,
+    path = "/api/v1/fx/rates",
+    tag = "fx",
+    params(FxRateQuery),
+    responses((status = 200, body = FxRate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_rate(
+    auth_user: AuthUser,
+    State(state): State<FxState>,
+    Query(query): Query<FxRateQuery>,
+) -> Result<Json<FxRate>, ApiError> {
+    let rate = state.fx_rates.rate(auth_user.user_id, &query.currency, query.date).await?;
+    Ok(Json(rate))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/fx/rates/history",
+    tag = "fx",
+    params(FxRateHistoryQuery),
+    responses((status = 200, body = Vec<FxRate>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_history(
+    auth_user: AuthUser,
+    State(state): State<FxState>,
+    Query(query): Query<FxRateHistoryQuery>,
+) -> Result<Json<Vec<FxRate>>, ApiError> {
+    let rates = state.fx_rates.history(auth_user.user_id, query).await?;
+    Ok(Json(rates))
+}
+
+/// Override the provider rate for one day
+#[utoipa::path(
+    put,
+    path = "/api/v1/fx/rates",
+    tag = "fx",
+    request_body = SetFxRate,
+    responses((status = 200, body = FxRate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_override(
+    auth_user: AuthUser,
+    State(state): State<FxState>,
+    Json(payload): Json<SetFxRate>,
+) -> Result<Json<FxRate>, ApiError> {
+    let rate = state.fx_rates.set_override(auth_user.user_id, payload).await?;
+    Ok(Json(rate))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/fx/rates/{id}",
+    tag = "fx",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_override(
+    auth_user: AuthUser,
+    State(state): State<FxState>,
+    Path(rate_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.fx_rates.delete_override(auth_user.user_id, rate_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
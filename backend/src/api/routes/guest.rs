@@ -1,25 +1,45 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
+    response::Response,
     routing::{get, post},
     Json, Router,
 };
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::api::error::ApiError;
+use crate::api::routes::attachments::file_response;
 use crate::application::dto::invoice_dto::{AddDiscussionMessageCommand, DiscussionMessageDto, DiscussionResponseDto};
-use crate::domain::models::invoice::{InvoiceDetailResponse, InvoiceStatus};
-use crate::domain::models::payment::{CreatePayment, PaymentMethod};
+use crate::domain::models::attachment::Attachment;
+use crate::domain::models::invoice::{DiscussionUnread, InvoiceAction, InvoiceDetailResponse, InvoiceStatus};
+use crate::domain::models::payment::{CreatePayment, PaymentMethod, PaymentStatus};
+use crate::domain::services::attachment_service::AttachmentService;
+use crate::domain::services::clock::SharedClock;
+use crate::domain::services::guest_token_service::GuestTokenService;
 use crate::domain::services::invoice_service::InvoiceService;
 use crate::domain::services::notification_service_new::EnhancedNotificationService;
-use crate::domain::services::payment_gateway_service::{CreatePaymentIntent, PaymentGatewayService};
+use crate::domain::services::payment_gateway_service::{CreatePaymentIntent, PaymentGatewayService, PaymentIntent};
+use crate::domain::services::stripe_checkout_service::StripeCheckoutService;
 use crate::infrastructure::repositories::invoice_repository::InvoiceRepository;
 use crate::infrastructure::repositories::payment_repository::PaymentRepository;
 use crate::infrastructure::repositories::user_repository::UserRepository;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_invoice_by_token, download_attachment, process_guest_payment, get_guest_payment_history,
+        mark_invoice_viewed, send_guest_payment_link, get_discussion_messages_guest,
+        add_discussion_message_guest, get_discussion_unread_guest, mark_discussion_read_guest,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 pub struct GuestState {
     pub invoice_repo: Arc<InvoiceRepository>,
@@ -28,26 +48,35 @@ pub struct GuestState {
     pub invoice_service: Arc<InvoiceService>,
     pub payment_gateway: Arc<PaymentGatewayService>,
     pub notification_service: Arc<EnhancedNotificationService>,
+    pub guest_tokens: Arc<GuestTokenService>,
+    pub attachments: Arc<AttachmentService>,
+    pub stripe_checkout: Arc<StripeCheckoutService>,
+    pub clock: SharedClock,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestInvoiceResponse {
     pub invoice: InvoiceDetailResponse,
     pub seller: GuestSellerInfo,
     pub payment_methods: Vec<String>,
     pub guest_payment_link: String,
+    /// What to pay now: the rest of the deposit while it's due, otherwise the balance
+    pub amount_due_now: Decimal,
+    /// Files the seller attached, downloadable from /invoice/{token}/attachments/{id}
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestSellerInfo {
     pub company_name: Option<String>,
     pub email: Option<String>,
     pub phone: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestPaymentRequest {
-    pub amount: f64,
+    pub amount: Decimal,
     pub payment_method: PaymentMethod,
     pub customer_email: Option<String>,
     pub customer_phone: Option<String>,
@@ -55,27 +84,29 @@ pub struct GuestPaymentRequest {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestPaymentResponse {
     pub payment_id: Uuid,
     pub status: String,
     pub redirect_url: Option<String>,
+    /// Left to pay on the invoice; a pending payment only counts once the gateway confirms it
+    pub balance_due: Decimal,
     pub message: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestPaymentHistoryRequest {
     pub email: Option<String>,
     pub phone: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestPaymentHistoryResponse {
     pub payments: Vec<GuestPaymentSummary>,
     pub total_count: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GuestPaymentSummary {
     pub invoice_number: String,
     pub amount: f64,
@@ -83,23 +114,34 @@ pub struct GuestPaymentSummary {
     pub status: String,
 }
 
+/// Stale links can still show an expired offer, but not accept or pay it
+fn ensure_not_expired(invoice: &InvoiceDetailResponse, today: NaiveDate) -> Result<(), ApiError> {
+    if invoice.is_expired(today) {
+        let since = invoice
+            .expires_at
+            .map(|date| format!(" on {}", date))
+            .unwrap_or_default();
+        return Err(ApiError::BadRequest(format!(
+            "This offer expired{} and can no longer be paid",
+            since
+        )));
+    }
+    Ok(())
+}
+
 /// Get invoice by token (guest access)
+#[utoipa::path(
+    get,
+    path = "/api/v1/guest/invoice/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200, body = GuestInvoiceResponse), ApiError)
+)]
 async fn get_invoice_by_token(
     State(state): State<GuestState>,
     Path(token): Path<String>,
 ) -> Result<(StatusCode, Json<GuestInvoiceResponse>), ApiError> {
-    // Verify token (simplified - in production use JWT or signed tokens)
-    // For now, we'll decode the token to get invoice_id
-    // Format: "guest_{invoice_id}_{hash}"
-
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
-
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
 
     // Get invoice
     let invoice = state
@@ -116,16 +158,22 @@ async fn get_invoice_by_token(
         .map_err(|_| ApiError::Database("Database error".to_string()))?
         .ok_or(ApiError::NotFound)?;
 
-    // Get available payment methods
-    let payment_methods = state.payment_gateway.get_available_gateways();
+    // Get available payment methods; an expired offer can only be viewed
+    let payment_methods = if invoice.is_expired(state.clock.today()) {
+        Vec::new()
+    } else {
+        state.payment_gateway.get_available_gateways()
+    };
 
     // Generate guest payment link
     let guest_payment_link = format!(
         "https://yourapp.com/guest/pay/{}",
         token
     );
+    let attachments = state.attachments.list_for_invoice(invoice_id).await?;
 
     let response = GuestInvoiceResponse {
+        amount_due_now: invoice.amount_due_now(),
         invoice,
         seller: GuestSellerInfo {
             company_name: seller.company_name,
@@ -134,27 +182,56 @@ async fn get_invoice_by_token(
         },
         payment_methods,
         guest_payment_link,
+        attachments,
     };
 
     Ok((StatusCode::OK, Json(response)))
 }
 
+/// Download a file attached to the invoice (guest access)
+#[utoipa::path(
+    get,
+    path = "/api/v1/guest/invoice/{token}/attachments/{attachment_id}",
+    tag = "guest",
+    params(("token" = String, Path), ("attachment_id" = Uuid, Path)),
+    responses((status = 200, description = "The attachment, with its stored content type", content_type = "application/octet-stream", body = [u8]), ApiError)
+)]
+async fn download_attachment(
+    State(state): State<GuestState>,
+    Path((token, attachment_id)): Path<(String, Uuid)>,
+) -> Result<Response, ApiError> {
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
+    let (attachment, content) = state.attachments.guest_download(invoice_id, attachment_id).await?;
+
+    Ok(file_response(&attachment, content))
+}
+
 /// Process guest payment
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/pay/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    request_body = GuestPaymentRequest,
+    responses((status = 201, body = GuestPaymentResponse), ApiError)
+)]
 async fn process_guest_payment(
     State(state): State<GuestState>,
     Path(token): Path<String>,
     Json(payload): Json<GuestPaymentRequest>,
 ) -> Result<(StatusCode, Json<GuestPaymentResponse>), ApiError> {
-    // Verify token
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
+    let response = initiate_payment(&state, invoice_id, payload).await?;
 
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
 
+/// Start paying an invoice through a gateway; shared by guest links and the client portal
+pub(crate) async fn initiate_payment(
+    state: &GuestState,
+    invoice_id: Uuid,
+    payload: GuestPaymentRequest,
+) -> Result<GuestPaymentResponse, ApiError> {
     // Get invoice
     let invoice = state
         .invoice_repo
@@ -166,38 +243,47 @@ async fn process_guest_payment(
     if invoice.status == InvoiceStatus::Paid {
         return Err(ApiError::BadRequest("Invoice already paid".to_string()));
     }
-
-    // Check if amount matches
-    if payload.amount != invoice.total_amount {
-        return Err(ApiError::BadRequest(
-            "Payment amount does not match invoice total".to_string(),
-        ));
+    ensure_not_expired(&invoice, state.clock.today())?;
+    if !invoice.status.allows(InvoiceAction::RecordPayment) {
+        return Err(ApiError::BadRequest(format!("Invoice is {} and can't be paid", invoice.status)));
     }
 
+    // Part payments follow the invoice's partial payment settings
+    invoice.check_payment_amount(payload.amount).map_err(ApiError::BadRequest)?;
+
     // Process payment based on method
+    let gateway_amount = payload.amount.to_f64().unwrap_or_default();
+    let mut checkout_url = None;
     let payment_result = match payload.payment_method {
         PaymentMethod::PayPal => {
             let intent = CreatePaymentIntent {
-                amount: payload.amount,
-                currency: "USD".to_string(),
+                amount: gateway_amount,
+                currency: invoice.currency.clone(),
                 description: Some(format!("Payment for invoice {}", invoice.invoice_number)),
                 metadata: None,
             };
             state.payment_gateway.create_paypal_order(intent).await
         }
+        // Card payments happen on Stripe's hosted Checkout page
         PaymentMethod::Stripe => {
-            let intent = CreatePaymentIntent {
-                amount: payload.amount,
-                currency: "USD".to_string(),
-                description: Some(format!("Payment for invoice {}", invoice.invoice_number)),
-                metadata: None,
-            };
-            state.payment_gateway.create_stripe_payment_intent(intent).await
+            let session = state
+                .stripe_checkout
+                .create_session(&invoice, payload.amount, payload.customer_email.clone())
+                .await?;
+            checkout_url = session.url.clone();
+            Ok(PaymentIntent {
+                id: session.id,
+                client_secret: None,
+                amount: gateway_amount,
+                currency: invoice.currency.clone(),
+                status: session.status.unwrap_or_else(|| "open".to_string()),
+                payment_method: "stripe".to_string(),
+            })
         }
         PaymentMethod::AchDebit => {
             let intent = CreatePaymentIntent {
-                amount: payload.amount,
-                currency: "USD".to_string(),
+                amount: gateway_amount,
+                currency: invoice.currency.clone(),
                 description: Some(format!("Payment for invoice {}", invoice.invoice_number)),
                 metadata: None,
             };
@@ -205,8 +291,8 @@ async fn process_guest_payment(
         }
         PaymentMethod::BankTransfer => {
             let intent = CreatePaymentIntent {
-                amount: payload.amount,
-                currency: "USD".to_string(),
+                amount: gateway_amount,
+                currency: invoice.currency.clone(),
                 description: Some(format!("Payment for invoice {}", invoice.invoice_number)),
                 metadata: None,
             };
@@ -226,19 +312,24 @@ async fn process_guest_payment(
         payment_method: payload.payment_method.clone(),
         gateway: Some(payment_result.payment_method.clone()),
         gateway_payment_id: Some(payment_result.id.clone()),
-        gateway_fee: Some(0.0),
+        gateway_fee: Some(Decimal::ZERO),
         paid_by: Some(payload.customer_name.clone()),
         notes: payload.notes.clone(),
+        exchange_rate: None,
     };
 
+    // Until the gateway confirms it, the payment is pending and the invoice stays open
+    let completed = payment_result.status == "completed" || payment_result.status == "succeeded";
+    let status = if completed { PaymentStatus::Completed } else { PaymentStatus::Pending };
+
     let payment = state
         .payment_repo
-        .create_payment(create_payment)
+        .create_payment(create_payment, status)
         .await
         .map_err(|e| ApiError::Database(e.to_string()))?;
 
     // Auto-flag as paid if completed
-    if payment_result.status == "completed" || payment_result.status == "succeeded" {
+    let balance_due = if completed {
         // Convert Payment to CreatePayment
         let create_payment = CreatePayment {
             invoice_id,
@@ -249,20 +340,13 @@ async fn process_guest_payment(
             gateway_fee: Some(payment.gateway_fee),
             paid_by: payment.paid_by.clone(),
             notes: payment.notes.clone(),
+            exchange_rate: None,
         };
 
-        let _updated_invoice = state
-            .invoice_repo
-            .record_payment_guest(invoice_id, create_payment)
-            .await
-            .map_err(|e| ApiError::Database(e.to_string()))?;
-
-        // Get full invoice details for notification
         let invoice_detail = state
-            .invoice_repo
-            .get_invoice_by_id(invoice_id)
-            .await
-            .map_err(|e| ApiError::Database(e.to_string()))?;
+            .invoice_service
+            .record_guest_payment(invoice_id, payment.id, create_payment)
+            .await?;
 
         // Send payment confirmation
         let _ = state
@@ -273,28 +357,38 @@ async fn process_guest_payment(
                 payload.customer_phone.clone(),
             )
             .await;
-    }
+
+        invoice_detail.balance_due
+    } else {
+        invoice.balance_due
+    };
 
     // Generate redirect URL based on payment method
     let redirect_url = match payload.payment_method {
         PaymentMethod::PayPal => Some(format!("https://paypal.com/checkout/{}", payment_result.id)),
-        PaymentMethod::Stripe => Some(format!("https://stripe.com/pay/{}", payment_result.id)),
+        PaymentMethod::Stripe => checkout_url,
         PaymentMethod::AchDebit => Some(format!("https://yourapp.com/guest/ach/status/{}", payment.id)),
         PaymentMethod::BankTransfer => Some(format!("https://yourapp.com/guest/bt/status/{}", payment.id)),
         _ => None,
     };
 
-    let response = GuestPaymentResponse {
+    Ok(GuestPaymentResponse {
         payment_id: payment.id,
         status: payment_result.status,
         redirect_url,
+        balance_due,
         message: "Payment initiated successfully".to_string(),
-    };
-
-    Ok((StatusCode::CREATED, Json(response)))
+    })
 }
 
 /// Get guest payment history (by email or phone)
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/history",
+    tag = "guest",
+    request_body = GuestPaymentHistoryRequest,
+    responses((status = 200, body = GuestPaymentHistoryResponse), ApiError)
+)]
 async fn get_guest_payment_history(
     _state: State<GuestState>,
     Json(payload): Json<GuestPaymentHistoryRequest>,
@@ -329,18 +423,18 @@ async fn get_guest_payment_history(
 }
 
 /// Mark invoice as viewed (for tracking)
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/view/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200), ApiError)
+)]
 async fn mark_invoice_viewed(
     State(state): State<GuestState>,
     Path(token): Path<String>,
 ) -> Result<StatusCode, ApiError> {
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
-
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
 
     // Update viewed_at timestamp
     state
@@ -353,18 +447,18 @@ async fn mark_invoice_viewed(
 }
 
 /// Send guest payment link via WhatsApp
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/send-link/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200), ApiError)
+)]
 async fn send_guest_payment_link(
     State(state): State<GuestState>,
     Path(token): Path<String>,
 ) -> Result<StatusCode, ApiError> {
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
-
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
 
     // Get invoice
     let invoice = state
@@ -373,6 +467,8 @@ async fn send_guest_payment_link(
         .await
         .map_err(|_| ApiError::NotFound)?;
 
+    ensure_not_expired(&invoice, state.clock.today())?;
+
     // Get recent payment count (mock - would query database)
     let recent_payments_count = 0;
 
@@ -395,18 +491,18 @@ async fn send_guest_payment_link(
 }
 
 /// Get discussion messages for guest (by token)
+#[utoipa::path(
+    get,
+    path = "/api/v1/guest/discussion/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200, body = DiscussionResponseDto), ApiError)
+)]
 async fn get_discussion_messages_guest(
     State(state): State<GuestState>,
     Path(token): Path<String>,
 ) -> Result<Json<DiscussionResponseDto>, ApiError> {
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
-
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
 
     let messages = state
         .invoice_service
@@ -426,19 +522,20 @@ async fn get_discussion_messages_guest(
 }
 
 /// Add discussion message as guest (buyer)
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/discussion/{token}",
+    tag = "guest",
+    params(("token" = String, Path)),
+    request_body = AddDiscussionMessageCommand,
+    responses((status = 201, body = DiscussionMessageDto), ApiError)
+)]
 async fn add_discussion_message_guest(
     State(state): State<GuestState>,
     Path(token): Path<String>,
     Json(payload): Json<AddDiscussionMessageCommand>,
 ) -> Result<(StatusCode, Json<DiscussionMessageDto>), ApiError> {
-    let parts: Vec<&str> = token.split('_').collect();
-    if parts.len() < 2 {
-        return Err(ApiError::BadRequest("Invalid token format".to_string()));
-    }
-
-    let invoice_id_str = parts[1];
-    let invoice_id = Uuid::parse_str(invoice_id_str)
-        .map_err(|_| ApiError::BadRequest("Invalid invoice ID".to_string()))?;
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
 
     let discussion = state
         .invoice_service
@@ -457,14 +554,58 @@ async fn add_discussion_message_guest(
     Ok((StatusCode::CREATED, Json(dto)))
 }
 
+/// Seller messages the client hasn't read, for badging the guest view
+#[utoipa::path(
+    get,
+    path = "/api/v1/guest/discussion/{token}/unread",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200, body = DiscussionUnread), ApiError)
+)]
+async fn get_discussion_unread_guest(
+    State(state): State<GuestState>,
+    Path(token): Path<String>,
+) -> Result<Json<DiscussionUnread>, ApiError> {
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
+    let unread = state.invoice_service.discussion_unread_guest(invoice_id).await?;
+    Ok(Json(unread))
+}
+
+/// Marks the discussion read by the client, up to now
+#[utoipa::path(
+    post,
+    path = "/api/v1/guest/discussion/{token}/read",
+    tag = "guest",
+    params(("token" = String, Path)),
+    responses((status = 200, body = DiscussionUnread), ApiError)
+)]
+async fn mark_discussion_read_guest(
+    State(state): State<GuestState>,
+    Path(token): Path<String>,
+) -> Result<Json<DiscussionUnread>, ApiError> {
+    let invoice_id = state.guest_tokens.resolve(&token).await?;
+    let unread = state.invoice_service.mark_discussion_read_guest(invoice_id).await?;
+    Ok(Json(unread))
+}
+
+/// Guest API, contract version 1.
+///
+/// These endpoints back the public payment page, which we can't force-upgrade, so the
+/// v1 shapes are pinned by `tests/integration/guest_contract_test.rs`. Adding fields is
+/// fine; renaming or removing fields, changing types or status codes is not. Breaking
+/// changes go in a new router mounted at `/guest/v2`, and v1 stays served at both
+/// `/guest/v1` and the unversioned `/guest` prefix.
 pub fn create_guest_router(state: GuestState) -> Router {
     Router::new()
         .route("/invoice/{token}", get(get_invoice_by_token))
+        .route("/invoice/{token}/attachments/{attachment_id}", get(download_attachment))
         .route("/pay/{token}", post(process_guest_payment))
         .route("/history", post(get_guest_payment_history))
         .route("/view/{token}", post(mark_invoice_viewed))
         .route("/send-link/{token}", post(send_guest_payment_link))
         .route("/discussion/{token}", get(get_discussion_messages_guest))
         .route("/discussion/{token}", post(add_discussion_message_guest))
+        .route("/discussion/{token}/unread", get(get_discussion_unread_guest))
+        .route("/discussion/{token}/read", post(mark_discussion_read_guest))
         .with_state(state)
 }
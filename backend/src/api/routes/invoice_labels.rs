@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{CreateInvoiceLabel, InvoiceLabel, SetInvoiceLabel, UpdateInvoiceLabel};
+use crate::domain::services::InvoiceLabelService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_labels, create_label, update_label, delete_label, set_invoice_label,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct InvoiceLabelState {
+    labels: Arc<InvoiceLabelService>,
+}
+
+pub fn create_router(labels: Arc<InvoiceLabelService>) -> Router {
+    let state = InvoiceLabelState { labels };
+
+    Router::new()
+        .route("/", get(list_labels).post(create_label))
+        .route("/{id}", put(update_label).delete(delete_label))
+        .with_state(state)
+}
+
+/// Label assignment, merged into the invoices router
+pub fn create_invoice_router(labels: Arc<InvoiceLabelService>) -> Router {
+    let state = InvoiceLabelState { labels };
+
+    Router::new()
+        .route("/{id}/label", put(set_invoice_label))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoice-labels",
+    tag = "invoice-labels",
+    responses((status = 200, body = Vec<InvoiceLabel>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_labels(
+    auth_user: AuthUser,
+    State(state): State<InvoiceLabelState>,
+) -> Result<Json<Vec<InvoiceLabel>>, ApiError> {
+    let labels = state.labels.list_labels(auth_user.user_id).await?;
+    Ok(Json(labels))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoice-labels",
+    tag = "invoice-labels",
+    request_body = CreateInvoiceLabel,
+    responses((status = 201, body = InvoiceLabel), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_label(
+    auth_user: AuthUser,
+    State(state): State<InvoiceLabelState>,
+    Json(payload): Json<CreateInvoiceLabel>,
+) -> Result<(StatusCode, Json<InvoiceLabel>), ApiError> {
+    let label = state.labels.create_label(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(label)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/invoice-labels/{id}",
+    tag = "invoice-labels",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateInvoiceLabel,
+    responses((status = 200, body = InvoiceLabel), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_label(
+    auth_user: AuthUser,
+    State(state): State<InvoiceLabelState>,
+    Path(label_id): Path<Uuid>,
+    Json(payload): Json<UpdateInvoiceLabel>,
+) -> Result<Json<InvoiceLabel>, ApiError> {
+    let label = state.labels.update_label(auth_user.user_id, label_id, payload).await?;
+    Ok(Json(label))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invoice-labels/{id}",
+    tag = "invoice-labels",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_label(
+    auth_user: AuthUser,
+    State(state): State<InvoiceLabelState>,
+    Path(label_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.labels.delete_label(auth_user.user_id, label_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Returns the label now on the invoice, or null after clearing it
+#[utoipa::path(
+    put,
+    path = "/api/v1/invoices/{id}/label",
+    tag = "invoice-labels",
+    params(("id" = Uuid, Path)),
+    request_body = SetInvoiceLabel,
+    responses((status = 200, body = Option<InvoiceLabel>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_invoice_label(
+    auth_user: AuthUser,
+    State(state): State<InvoiceLabelState>,
+    Path(invoice_id): Path<Uuid>,
+    Json(payload): Json<SetInvoiceLabel>,
+) -> Result<Json<Option<InvoiceLabel>>, ApiError> {
+    let label = state.labels.set_invoice_label(auth_user.user_id, invoice_id, payload.label_id).await?;
+    Ok(Json(label))
+}
@@ -0,0 +1,187 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::application::dto::invoice_dto::InvoiceCreatedDto;
+use crate::application::use_cases::{CreateInvoiceFromTemplateUseCase, DuplicateInvoiceUseCase};
+use crate::domain::models::{
+    CreateInvoiceTemplate, DuplicateInvoice, InvoiceFromTemplate, InvoiceTemplate, UpdateInvoiceTemplate,
+};
+use crate::domain::services::InvoiceTemplateService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_templates, create_template, get_template, update_template, delete_template,
+        create_invoice_from_template, duplicate_invoice,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct InvoiceTemplateState {
+    templates: Arc<InvoiceTemplateService>,
+    invoice_from_template: Arc<CreateInvoiceFromTemplateUseCase>,
+}
+
+#[derive(Clone)]
+struct DuplicateInvoiceState {
+    duplicate_invoice: Arc<DuplicateInvoiceUseCase>,
+}
+
+pub fn create_router(
+    templates: Arc<InvoiceTemplateService>,
+    invoice_from_template: Arc<CreateInvoiceFromTemplateUseCase>,
+) -> Router {
+    let state = InvoiceTemplateState { templates, invoice_from_template };
+
+    Router::new()
+        .route("/", get(list_templates).post(create_template))
+        .route("/{id}", get(get_template).put(update_template).delete(delete_template))
+        .route("/{id}/invoices", post(create_invoice_from_template))
+        .with_state(state)
+}
+
+/// Invoice duplication, merged into the invoices router
+pub fn create_invoice_router(duplicate_invoice_uc: Arc<DuplicateInvoiceUseCase>) -> Router {
+    let state = DuplicateInvoiceState { duplicate_invoice: duplicate_invoice_uc };
+
+    Router::new()
+        .route("/{id}/duplicate", post(duplicate_invoice))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoice-templates",
+    tag = "invoice-templates",
+    responses((status = 200, body = Vec<InvoiceTemplate>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_templates(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+) -> Result<Json<Vec<InvoiceTemplate>>, ApiError> {
+    let templates = state.templates.list_templates(auth_user.user_id).await?;
+    Ok(Json(templates))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoice-templates",
+    tag = "invoice-templates",
+    request_body = CreateInvoiceTemplate,
+    responses((status = 201, body = InvoiceTemplate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_template(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+    Json(payload): Json<CreateInvoiceTemplate>,
+) -> Result<(StatusCode, Json<InvoiceTemplate>), ApiError> {
+    let template = state.templates.create_template(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(template)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoice-templates/{id}",
+    tag = "invoice-templates",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceTemplate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_template(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+    Path(template_id): Path<Uuid>,
+) -> Result<Json<InvoiceTemplate>, ApiError> {
+    let template = state.templates.get_template(auth_user.user_id, template_id).await?;
+    Ok(Json(template))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/invoice-templates/{id}",
+    tag = "invoice-templates",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateInvoiceTemplate,
+    responses((status = 200, body = InvoiceTemplate), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_template(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+    Path(template_id): Path<Uuid>,
+    Json(payload): Json<UpdateInvoiceTemplate>,
+) -> Result<Json<InvoiceTemplate>, ApiError> {
+    let template = state.templates.update_template(auth_user.user_id, template_id, payload).await?;
+    Ok(Json(template))
+}
+
+/// Invoices created from the template are kept
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invoice-templates/{id}",
+    tag = "invoice-templates",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_template(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+    Path(template_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.templates.delete_template(auth_user.user_id, template_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Creates a draft invoice for the client from the template
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoice-templates/{id}/invoices",
+    tag = "invoice-templates",
+    params(("id" = Uuid, Path)),
+    request_body = InvoiceFromTemplate,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_invoice_from_template(
+    auth_user: AuthUser,
+    State(state): State<InvoiceTemplateState>,
+    Path(template_id): Path<Uuid>,
+    Json(payload): Json<InvoiceFromTemplate>,
+) -> Result<(StatusCode, Json<InvoiceCreatedDto>), ApiError> {
+    let invoice = state.invoice_from_template.execute(auth_user.user_id, template_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(invoice)))
+}
+
+/// Copies the invoice's client, lines, notes and terms into a new draft with its
+/// own number, dated today unless given
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/duplicate",
+    tag = "invoice-templates",
+    params(("id" = Uuid, Path)),
+    request_body = DuplicateInvoice,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn duplicate_invoice(
+    auth_user: AuthUser,
+    State(state): State<DuplicateInvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+    Json(payload): Json<DuplicateInvoice>,
+) -> Result<(StatusCode, Json<InvoiceCreatedDto>), ApiError> {
+    let invoice = state.duplicate_invoice.execute(auth_user.user_id, invoice_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(invoice)))
+}
@@ -0,0 +1,157 @@
+use axum::{
+    body::Body,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use axum_extra::extract::Multipart;
+use std::sync::Arc;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{EInvoiceQuery, InvoiceExportQuery, InvoiceImportQuery, InvoiceImportReport};
+use crate::domain::services::{EInvoiceService, InvoiceCsvImportService, InvoiceExportService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        import_csv, export, export_e_invoice,
+    ),
+    components(schemas(crate::domain::models::EInvoiceFormat))
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct TransferState {
+    imports: Arc<InvoiceCsvImportService>,
+    exports: Arc<InvoiceExportService>,
+    e_invoices: Arc<EInvoiceService>,
+}
+
+/// CSV import, CSV/XLSX export and e-invoices, merged into the invoices router
+pub fn create_invoice_router(
+    imports: Arc<InvoiceCsvImportService>,
+    exports: Arc<InvoiceExportService>,
+    e_invoices: Arc<EInvoiceService>,
+) -> Router {
+    let state = TransferState { imports, exports, e_invoices };
+
+    Router::new()
+        .route(
+            "/import",
+            // Bounded by the global request limit and the file size check
+            post(import_csv).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/export", get(export))
+        .route("/{id}/export", get(export_e_invoice))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/import",
+    tag = "invoices",
+    params(InvoiceImportQuery),
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 200, body = InvoiceImportReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn import_csv(
+    auth_user: AuthUser,
+    State(state): State<TransferState>,
+    Query(query): Query<InvoiceImportQuery>,
+    mut multipart: Multipart,
+) -> Result<Json<InvoiceImportReport>, ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+        let report = state.imports.import(auth_user.user_id, &file_name, &data, query.dry_run).await?;
+        return Ok(Json(report));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+/// Streams the invoices matching the list filters as CSV or XLSX
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/export",
+    tag = "invoices",
+    params(InvoiceExportQuery),
+    responses((status = 200, description = "The invoices as CSV or XLSX", content((String = "text/csv"), ([u8] = "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"))), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn export(
+    auth_user: AuthUser,
+    State(state): State<TransferState>,
+    Query(query): Query<InvoiceExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stream = state.exports.stream(auth_user.user_id, &query);
+
+    let filename = format!(
+        "invoices_{}.{}",
+        chrono::Utc::now().timestamp(),
+        query.format.extension()
+    );
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        query.format.content_type().parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename).parse().unwrap(),
+    );
+
+    Ok((headers, Body::from_stream(stream)))
+}
+
+/// The invoice as an EN 16931 e-invoice: UBL 2.1 XML, or a Factur-X PDF with
+/// the CII XML attached. Lists the missing mandatory fields when it can't be made.
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/export",
+    tag = "invoices",
+    params(("id" = Uuid, Path, description = "Invoice ID"), EInvoiceQuery),
+    responses((status = 200, description = "The e-invoice", content((String = "application/xml"), ([u8] = "application/pdf"))), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn export_e_invoice(
+    auth_user: AuthUser,
+    State(state): State<TransferState>,
+    Path(invoice_id): Path<Uuid>,
+    Query(query): Query<EInvoiceQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let file = state.e_invoices.export(auth_user.user_id, invoice_id, query.format).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(axum::http::header::CONTENT_TYPE, file.content_type.parse().unwrap());
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        content_disposition(&file.file_name).parse().unwrap(),
+    );
+
+    Ok((headers, file.content))
+}
+
+/// Invoice numbers are user-chosen; anything a header can't carry is dropped
+fn content_disposition(file_name: &str) -> String {
+    let file_name: String = file_name.chars().filter(|c| c.is_ascii_graphic() && *c != '"' && *c != '\\').collect();
+    format!("attachment; filename=\"{}\"", file_name)
+}
@@ -1,16 +1,33 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderName, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post, put, delete},
     Json, Router,
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use utoipa::OpenApi;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
 use crate::application::dto::invoice_dto::*;
 use crate::application::use_cases::*;
+use crate::domain::models::{parse_batch_ids, InvoiceBatch, InvoiceBatchAction, BatchActionReport};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_invoices, create_invoice, consolidate_invoices, get_invoice, update_invoice,
+        delete_invoice, restore_invoice, cancel_invoice, send_invoice, get_invoice_notifications,
+        resend_invoice_notification, regenerate_guest_link, send_reminder, get_pdf, correct_invoice,
+        record_payment, send_invoice_whatsapp, mark_invoice_viewed, send_payment_confirmation,
+        add_discussion_message, get_discussion_messages, get_discussion_unread, mark_discussion_read,
+        list_unread_discussions, batch_invoices,
+    ),
+    components(schemas(crate::domain::models::InvoiceExportFormat))
+)]
+pub struct ApiDoc;
 
 #[derive(Clone)]
 struct InvoiceState {
@@ -19,6 +36,10 @@ struct InvoiceState {
     list_invoices_uc: Arc<ListInvoicesUseCase>,
     update_invoice_uc: Arc<UpdateInvoiceUseCase>,
     delete_invoice_uc: Arc<DeleteInvoiceUseCase>,
+    restore_invoice_uc: Arc<RestoreInvoiceUseCase>,
+    cancel_invoice_uc: Arc<CancelInvoiceUseCase>,
+    consolidate_invoices_uc: Arc<ConsolidateInvoicesUseCase>,
+    correct_invoice_uc: Arc<CorrectInvoiceUseCase>,
     record_payment_uc: Arc<RecordPaymentUseCase>,
     send_invoice_uc: Arc<SendInvoiceUseCase>,
     get_pdf_uc: Arc<GetInvoicePdfUseCase>,
@@ -28,6 +49,10 @@ struct InvoiceState {
     send_payment_confirmation_uc: Arc<SendPaymentConfirmationUseCase>,
     add_discussion_message_uc: Arc<AddDiscussionMessageUseCase>,
     get_discussion_messages_uc: Arc<GetDiscussionMessagesUseCase>,
+    get_invoice_notifications_uc: Arc<GetInvoiceNotificationsUseCase>,
+    resend_invoice_notification_uc: Arc<ResendInvoiceNotificationUseCase>,
+    regenerate_guest_link_uc: Arc<RegenerateGuestLinkUseCase>,
+    invoice_batch_uc: Arc<InvoiceBatchUseCase>,
 }
 
 pub fn create_router(
@@ -36,6 +61,10 @@ pub fn create_router(
     list_invoices_uc: Arc<ListInvoicesUseCase>,
     update_invoice_uc: Arc<UpdateInvoiceUseCase>,
     delete_invoice_uc: Arc<DeleteInvoiceUseCase>,
+    restore_invoice_uc: Arc<RestoreInvoiceUseCase>,
+    cancel_invoice_uc: Arc<CancelInvoiceUseCase>,
+    consolidate_invoices_uc: Arc<ConsolidateInvoicesUseCase>,
+    correct_invoice_uc: Arc<CorrectInvoiceUseCase>,
     record_payment_uc: Arc<RecordPaymentUseCase>,
     send_invoice_uc: Arc<SendInvoiceUseCase>,
     get_pdf_uc: Arc<GetInvoicePdfUseCase>,
@@ -45,6 +74,10 @@ pub fn create_router(
     send_payment_confirmation_uc: Arc<SendPaymentConfirmationUseCase>,
     add_discussion_message_uc: Arc<AddDiscussionMessageUseCase>,
     get_discussion_messages_uc: Arc<GetDiscussionMessagesUseCase>,
+    get_invoice_notifications_uc: Arc<GetInvoiceNotificationsUseCase>,
+    resend_invoice_notification_uc: Arc<ResendInvoiceNotificationUseCase>,
+    regenerate_guest_link_uc: Arc<RegenerateGuestLinkUseCase>,
+    invoice_batch_uc: Arc<InvoiceBatchUseCase>,
 ) -> Router {
     let state = InvoiceState {
         create_invoice_uc,
@@ -52,6 +85,10 @@ pub fn create_router(
         list_invoices_uc,
         update_invoice_uc,
         delete_invoice_uc,
+        restore_invoice_uc,
+        cancel_invoice_uc,
+        consolidate_invoices_uc,
+        correct_invoice_uc,
         record_payment_uc,
         send_invoice_uc,
         get_pdf_uc,
@@ -61,39 +98,78 @@ pub fn create_router(
         send_payment_confirmation_uc,
         add_discussion_message_uc,
         get_discussion_messages_uc,
+        get_invoice_notifications_uc,
+        resend_invoice_notification_uc,
+        regenerate_guest_link_uc,
+        invoice_batch_uc,
     };
 
     Router::new()
         .route("/", get(list_invoices))
         .route("/", post(create_invoice))
+        .route("/consolidate", post(consolidate_invoices))
+        .route("/batch", post(batch_invoices))
+        .route("/discussions/unread", get(list_unread_discussions))
         .route("/{id}", get(get_invoice))
         .route("/{id}", put(update_invoice))
         .route("/{id}", delete(delete_invoice))
+        .route("/{id}/restore", post(restore_invoice))
         .route("/{id}/send", post(send_invoice))
+        .route("/{id}/cancel", post(cancel_invoice))
+        .route("/{id}/correct", post(correct_invoice))
         .route("/{id}/send-whatsapp", post(send_invoice_whatsapp))
         .route("/{id}/remind", post(send_reminder))
         .route("/{id}/pdf", get(get_pdf))
         .route("/{id}/pay", post(record_payment))
         .route("/{id}/view", post(mark_invoice_viewed))
         .route("/{id}/send-confirmation", post(send_payment_confirmation))
+        .route("/{id}/notifications", get(get_invoice_notifications))
+        .route("/{id}/notifications/{notification_id}/resend", post(resend_invoice_notification))
         .route("/{id}/discussion", get(get_discussion_messages))
         .route("/{id}/discussion", post(add_discussion_message))
+        .route("/{id}/discussion/unread", get(get_discussion_unread))
+        .route("/{id}/discussion/read", post(mark_discussion_read))
+        .route("/{id}/guest-link", post(regenerate_guest_link))
         .with_state(state)
 }
 
+/// Lists invoices a page at a time, or with `?ids=a,b,c` fetches those invoices as a batch
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices",
+    tag = "invoices",
+    params(InvoiceListQuery),
+    responses((status = 200, description = "A page of invoices, or with `ids` a batch of them", body = crate::domain::models::Page<InvoiceSummaryDto>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn list_invoices(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
     Query(query): Query<InvoiceListQuery>,
-) -> Result<Json<Vec<InvoiceSummaryDto>>, ApiError> {
+) -> Result<Response, ApiError> {
+    if let Some(ids) = query.ids.as_deref() {
+        let ids = parse_batch_ids(ids).map_err(ApiError::BadRequest)?;
+        let batch = state.list_invoices_uc.execute_batch(auth_user.user_id, ids).await?;
+        return Ok(Json(batch).into_response());
+    }
+
+    let page = query.page_request().map_err(ApiError::BadRequest)?;
     let invoices = state
         .list_invoices_uc
-        .execute(auth_user.user_id, query)
+        .execute(auth_user.user_id, query, &page)
         .await?;
 
-    Ok(Json(invoices))
+    Ok(Json(invoices).into_response())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices",
+    tag = "invoices",
+    request_body = CreateInvoiceCommand,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_invoice(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -107,6 +183,35 @@ async fn create_invoice(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/consolidate",
+    tag = "invoices",
+    request_body = ConsolidateInvoicesCommand,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn consolidate_invoices(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Json(payload): Json<ConsolidateInvoicesCommand>,
+) -> Result<(StatusCode, Json<InvoiceCreatedDto>), ApiError> {
+    let response = state
+        .consolidate_invoices_uc
+        .execute(auth_user.user_id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_invoice(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -120,6 +225,15 @@ async fn get_invoice(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/invoices/{id}",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateInvoiceCommand,
+    responses((status = 200, body = InvoiceDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_invoice(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -134,6 +248,14 @@ async fn update_invoice(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invoices/{id}",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn delete_invoice(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -147,6 +269,59 @@ async fn delete_invoice(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Takes a deleted draft out of the trash
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/restore",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn restore_invoice(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<InvoiceDto>, ApiError> {
+    let invoice = state
+        .restore_invoice_uc
+        .execute(auth_user.user_id, invoice_id)
+        .await?;
+
+    Ok(Json(invoice))
+}
+
+/// Paid and part-paid invoices can't be cancelled; they take a credit note
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/cancel",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_invoice(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<InvoiceDto>, ApiError> {
+    let invoice = state
+        .cancel_invoice_uc
+        .execute(auth_user.user_id, invoice_id)
+        .await?;
+
+    Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/send",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    request_body = SendInvoiceCommand,
+    responses((status = 200), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn send_invoice(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -155,12 +330,85 @@ async fn send_invoice(
 ) -> Result<StatusCode, ApiError> {
     state
         .send_invoice_uc
-        .execute(auth_user.user_id, invoice_id, payload)
+        .execute(auth_user.user_id, auth_user.owner_id, invoice_id, payload)
         .await?;
 
     Ok(StatusCode::OK)
 }
 
+/// Every send attempt with the exact recipients, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/notifications",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<crate::domain::models::InvoiceNotification>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_invoice_notifications(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<Vec<crate::domain::models::InvoiceNotification>>, ApiError> {
+    let notifications = state
+        .get_invoice_notifications_uc
+        .execute(auth_user.user_id, invoice_id)
+        .await?;
+
+    Ok(Json(notifications))
+}
+
+/// Retry a failed send; the new attempt is returned whether or not it got through
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/notifications/{notification_id}/resend",
+    tag = "invoices",
+    params(("id" = Uuid, Path), ("notification_id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::InvoiceNotification), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn resend_invoice_notification(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path((invoice_id, notification_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<crate::domain::models::InvoiceNotification>, ApiError> {
+    let attempt = state
+        .resend_invoice_notification_uc
+        .execute(auth_user.user_id, invoice_id, notification_id)
+        .await?;
+
+    Ok(Json(attempt))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/guest-link",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::services::GuestLink), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn regenerate_guest_link(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<crate::domain::services::GuestLink>, ApiError> {
+    let link = state
+        .regenerate_guest_link_uc
+        .execute(auth_user.user_id, invoice_id)
+        .await?;
+
+    Ok(Json(link))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/remind",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn send_reminder(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -168,25 +416,118 @@ async fn send_reminder(
 ) -> Result<StatusCode, ApiError> {
     state
         .send_reminder_uc
-        .execute(auth_user.user_id, invoice_id)
+        .execute(auth_user.user_id, auth_user.owner_id, invoice_id)
         .await?;
 
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/pdf",
+    tag = "invoices",
+    params(("id" = Uuid, Path), InvoicePdfQuery),
+    responses((status = 200, description = "The invoice PDF; X-PDF-Cache tells whether it was cached", content_type = "application/pdf", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_pdf(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
     Path(invoice_id): Path<Uuid>,
-) -> Result<Vec<u8>, ApiError> {
-    let pdf_bytes = state
+    Query(query): Query<InvoicePdfQuery>,
+) -> Result<Response, ApiError> {
+    let pdf = state
         .get_pdf_uc
-        .execute(auth_user.user_id, invoice_id)
+        .execute(auth_user.user_id, invoice_id, query.regenerate)
         .await?;
 
-    Ok(pdf_bytes)
+    let cache = if pdf.cached { "hit" } else { "miss" };
+    Ok((
+        [(header::CONTENT_TYPE, "application/pdf"), (HeaderName::from_static("x-pdf-cache"), cache)],
+        pdf.content,
+    ).into_response())
 }
 
+/// Sends, marks paid, deletes or downloads many invoices at once. Each invoice is
+/// checked as it would be on its own, and one that fails doesn't stop the others.
+/// `download_pdfs` answers with a zip and the number of failures in `X-Batch-Failed`,
+/// or with the report and 422 when no PDF could be rendered.
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/batch",
+    tag = "invoices",
+    request_body = InvoiceBatch,
+    responses(
+        (status = 200, description = "What happened to each invoice, or for `download_pdfs` the zipped PDFs", body = BatchActionReport),
+        (status = 422, description = "None of the PDFs could be rendered", body = BatchActionReport),
+        ApiError
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn batch_invoices(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Json(payload): Json<InvoiceBatch>,
+) -> Result<Response, ApiError> {
+    if payload.action != InvoiceBatchAction::DownloadPdfs {
+        let report = state
+            .invoice_batch_uc
+            .execute(auth_user.user_id, auth_user.owner_id, payload)
+            .await?;
+        return Ok(Json(report).into_response());
+    }
+
+    let batch = state
+        .invoice_batch_uc
+        .download_pdfs(auth_user.user_id, payload.invoice_ids)
+        .await?;
+    let Some(archive) = batch.archive else {
+        return Ok((StatusCode::UNPROCESSABLE_ENTITY, Json(batch.report)).into_response());
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"invoices.zip\"".to_string()),
+            (HeaderName::from_static("x-batch-failed"), batch.report.failed.to_string()),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/correct",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    request_body = CorrectInvoiceCommand,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn correct_invoice(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CorrectInvoiceCommand>,
+) -> Result<(StatusCode, Json<InvoiceCreatedDto>), ApiError> {
+    let response = state
+        .correct_invoice_uc
+        .execute(auth_user.user_id, id, payload)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/pay",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    request_body = RecordPaymentCommand,
+    responses((status = 201, body = PaymentRecordedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn record_payment(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -201,6 +542,14 @@ async fn record_payment(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/send-whatsapp",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn send_invoice_whatsapp(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -214,6 +563,13 @@ async fn send_invoice_whatsapp(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/view",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceDto), ApiError)
+)]
 async fn mark_invoice_viewed(
     State(state): State<InvoiceState>,
     Path(invoice_id): Path<Uuid>,
@@ -226,6 +582,14 @@ async fn mark_invoice_viewed(
     Ok(Json(response))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/send-confirmation",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn send_payment_confirmation(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -239,6 +603,15 @@ async fn send_payment_confirmation(
     Ok(StatusCode::OK)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/discussion",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    request_body = AddDiscussionMessageCommand,
+    responses((status = 201, body = DiscussionMessageDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn add_discussion_message(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -253,6 +626,14 @@ async fn add_discussion_message(
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/discussion",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = DiscussionResponseDto), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_discussion_messages(
     auth_user: AuthUser,
     State(state): State<InvoiceState>,
@@ -265,3 +646,55 @@ async fn get_discussion_messages(
 
     Ok(Json(response))
 }
+
+/// Client messages on the invoice the seller hasn't read
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/discussion/unread",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::DiscussionUnread), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_discussion_unread(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::DiscussionUnread>, ApiError> {
+    let unread = state.get_discussion_messages_uc.unread(auth_user.user_id, invoice_id).await?;
+    Ok(Json(unread))
+}
+
+/// Marks the discussion read by the seller, up to now
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/discussion/read",
+    tag = "invoices",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::DiscussionUnread), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn mark_discussion_read(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<crate::domain::models::DiscussionUnread>, ApiError> {
+    let unread = state.get_discussion_messages_uc.mark_read(auth_user.user_id, invoice_id).await?;
+    Ok(Json(unread))
+}
+
+/// Invoices with client messages the seller hasn't read, latest message first
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/discussions/unread",
+    tag = "invoices",
+    responses((status = 200, body = Vec<crate::domain::models::UnreadDiscussion>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_unread_discussions(
+    auth_user: AuthUser,
+    State(state): State<InvoiceState>,
+) -> Result<Json<Vec<crate::domain::models::UnreadDiscussion>>, ApiError> {
+    let conversations = state.get_discussion_messages_uc.unread_conversations(auth_user.user_id).await?;
+    Ok(Json(conversations))
+}
@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{AppliedLateFee, LateFeePolicy, UpdateLateFeePolicy};
+use crate::domain::services::LateFeeService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_policy, update_policy, list_invoice_late_fees,
+    )
+)]
+pub struct ApiDoc;
+
+/// The user's late fee policy, nested under /settings/late-fees
+pub fn create_router(late_fees: Arc<LateFeeService>) -> Router {
+    Router::new()
+        .route("/", get(get_policy).put(update_policy))
+        .with_state(late_fees)
+}
+
+/// Late fee routes, merged into the invoices router
+pub fn create_invoice_router(late_fees: Arc<LateFeeService>) -> Router {
+    Router::new()
+        .route("/{id}/late-fees", get(list_invoice_late_fees))
+        .with_state(late_fees)
+}
+
+/// Null until the user sets a policy
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/late-fees",
+    tag = "late-fees",
+    responses((status = 200, body = Option<LateFeePolicy>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_policy(
+    auth_user: AuthUser,
+    State(late_fees): State<Arc<LateFeeService>>,
+) -> Result<Json<Option<LateFeePolicy>>, ApiError> {
+    let policy = late_fees.get_policy(auth_user.user_id).await?;
+    Ok(Json(policy))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/late-fees",
+    tag = "late-fees",
+    request_body = UpdateLateFeePolicy,
+    responses((status = 200, body = LateFeePolicy), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_policy(
+    auth_user: AuthUser,
+    State(late_fees): State<Arc<LateFeeService>>,
+    Json(payload): Json<UpdateLateFeePolicy>,
+) -> Result<Json<LateFeePolicy>, ApiError> {
+    let policy = late_fees.update_policy(auth_user.user_id, payload).await?;
+    Ok(Json(policy))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/late-fees",
+    tag = "late-fees",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Vec<AppliedLateFee>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_invoice_late_fees(
+    auth_user: AuthUser,
+    State(late_fees): State<Arc<LateFeeService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<AppliedLateFee>>, ApiError> {
+    let fees = late_fees.list_for_invoice(auth_user.user_id, id).await?;
+    Ok(Json(fees))
+}
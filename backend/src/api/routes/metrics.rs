@@ -7,42 +7,80 @@ use axum::{
 };
 use std::sync::Arc;
 use serde_json::json;
+use utoipa::OpenApi;
 
-use crate::domain::services::{MetricsService, MonitoringService, RedisService};
+use crate::api::middleware::scrape_auth::{scrape_auth_middleware, ScrapeAuthConfig};
+use crate::domain::services::{HealthStatus, MetricsService, MonitoringService, RedisService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_metrics, health_check, readiness_check, get_monitoring_summary, get_active_requests,
+        get_recent_errors,
+    )
+)]
+pub struct ApiDoc;
 
 #[derive(Clone)]
 struct MetricsState {
     metrics: Arc<MetricsService>,
     monitoring: Arc<MonitoringService>,
+}
+
+#[derive(Clone)]
+struct ReadinessState {
+    monitoring: Arc<MonitoringService>,
     redis: Option<Arc<RedisService>>,
     db_pool: Option<sqlx::PgPool>,
 }
 
+/// `/ready`, served both here and at the root for load balancers and orchestrators
+pub fn readiness_router(
+    monitoring: Arc<MonitoringService>,
+    redis: Option<Arc<RedisService>>,
+    db_pool: Option<sqlx::PgPool>,
+) -> Router {
+    Router::new()
+        .route("/ready", get(readiness_check))
+        .with_state(ReadinessState { monitoring, redis, db_pool })
+}
+
 pub fn create_router(
     metrics: Arc<MetricsService>,
     monitoring: Arc<MonitoringService>,
     redis: Option<Arc<RedisService>>,
     db_pool: Option<sqlx::PgPool>,
+    scrape_auth: Arc<ScrapeAuthConfig>,
 ) -> Router {
+    let readiness = readiness_router(monitoring.clone(), redis, db_pool);
     let state = MetricsState {
         metrics,
         monitoring,
-        redis,
-        db_pool,
     };
 
-    Router::new()
+    // Metrics and monitoring expose request counts and DB details; probes stay open
+    let protected = Router::new()
         .route("/metrics", get(get_metrics))
-        .route("/health", get(health_check))
-        .route("/ready", get(readiness_check))
         .route("/monitoring/summary", get(get_monitoring_summary))
         .route("/monitoring/active-requests", get(get_active_requests))
         .route("/monitoring/errors", get(get_recent_errors))
+        .route_layer(axum::middleware::from_fn_with_state(scrape_auth, scrape_auth_middleware));
+
+    Router::new()
+        .route("/health", get(health_check))
+        .merge(protected)
         .with_state(state)
+        .merge(readiness)
 }
 
+#[utoipa::path(
+    get,
+    path = "/metrics/metrics",
+    tag = "metrics",
+    responses((status = 200, description = "Prometheus text exposition format", content_type = "text/plain", body = String))
+)]
 async fn get_metrics(State(state): State<MetricsState>) -> Result<Response, StatusCode> {
-    match state.metrics.get_metrics() {
+    match state.metrics.render().await {
         Ok(metrics) => Ok((
             [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
             metrics,
@@ -53,43 +91,74 @@ async fn get_metrics(State(state): State<MetricsState>) -> Result<Response, Stat
 }
 
 /// Health check endpoint - returns 200 if service is running
+#[utoipa::path(
+    get,
+    path = "/metrics/health",
+    tag = "metrics",
+    responses((status = 200))
+)]
 async fn health_check(State(_state): State<MetricsState>) -> StatusCode {
     // Basic health check - just check if we can respond
     StatusCode::OK
 }
 
-/// Readiness check - performs deeper health checks
-async fn readiness_check(State(state): State<MetricsState>) -> (StatusCode, Json<serde_json::Value>) {
-    let health_status = state.monitoring.perform_health_check(
+/// Readiness check - checks Postgres, Redis and SMTP, each with a short timeout.
+/// 503 if any configured dependency is down, naming it in `dependencies`.
+#[utoipa::path(
+    get,
+    path = "/metrics/ready",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Every configured dependency answered", body = serde_json::Value),
+        (status = 503, description = "A dependency is down or timed out", body = serde_json::Value),
+    )
+)]
+async fn readiness_check(State(state): State<ReadinessState>) -> (StatusCode, Json<serde_json::Value>) {
+    let report = state.monitoring.check_health(
         state.db_pool.as_ref(),
         state.redis.as_ref(),
     ).await;
+    let ready = report.is_ready();
+    let integrations = state.monitoring.integration_statuses();
 
-    match health_status {
-        crate::domain::services::HealthStatus::Healthy => {
-            (StatusCode::OK, Json(json!({
-                "status": "healthy",
-                "message": "All systems operational"
-            })))
-        }
-        crate::domain::services::HealthStatus::Degraded(msg) => {
-            (StatusCode::OK, Json(json!({
-                "status": "degraded",
-                "message": msg,
-                "warning": "Service is operational but with degraded performance"
-            })))
-        }
-        crate::domain::services::HealthStatus::Unhealthy(msg) => {
-            (StatusCode::SERVICE_UNAVAILABLE, Json(json!({
-                "status": "unhealthy",
-                "message": msg,
-                "error": "Service is not healthy"
-            })))
-        }
+    let (status, message) = match &report.status {
+        HealthStatus::Healthy => ("healthy", "All systems operational".to_string()),
+        HealthStatus::Degraded(msg) => ("degraded", msg.clone()),
+        HealthStatus::Unhealthy(msg) => ("unhealthy", msg.clone()),
+    };
+    let down: Vec<&str> = report.dependencies.iter()
+        .filter(|check| check.is_down())
+        .map(|check| check.name)
+        .collect();
+
+    let mut body = json!({
+        "status": status,
+        "ready": ready,
+        "message": message,
+        "dependencies": report.dependencies,
+        "integrations": integrations,
+    });
+    if !down.is_empty() {
+        body["error"] = json!(format!("Unavailable: {}", down.join(", ")));
+    } else if matches!(report.status, HealthStatus::Degraded(_)) {
+        body["warning"] = json!("Service is operational but with degraded performance");
     }
+
+    let code = if ready && !matches!(report.status, HealthStatus::Unhealthy(_)) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(body))
 }
 
 /// Get monitoring summary
+#[utoipa::path(
+    get,
+    path = "/metrics/monitoring/summary",
+    tag = "metrics",
+    responses((status = 200, body = serde_json::Value))
+)]
 async fn get_monitoring_summary(State(state): State<MetricsState>) -> (StatusCode, Json<serde_json::Value>) {
     let summary = state.monitoring.get_performance_summary().await;
     let metrics = state.monitoring.get_metrics();
@@ -110,6 +179,12 @@ async fn get_monitoring_summary(State(state): State<MetricsState>) -> (StatusCod
 }
 
 /// Get active requests
+#[utoipa::path(
+    get,
+    path = "/metrics/monitoring/active-requests",
+    tag = "metrics",
+    responses((status = 200, body = serde_json::Value))
+)]
 async fn get_active_requests(State(state): State<MetricsState>) -> (StatusCode, Json<serde_json::Value>) {
     let active = state.monitoring.get_active_requests().await;
 
@@ -130,6 +205,12 @@ async fn get_active_requests(State(state): State<MetricsState>) -> (StatusCode,
 }
 
 /// Get recent errors
+#[utoipa::path(
+    get,
+    path = "/metrics/monitoring/errors",
+    tag = "metrics",
+    responses((status = 200, body = serde_json::Value))
+)]
 async fn get_recent_errors(State(state): State<MetricsState>) -> (StatusCode, Json<serde_json::Value>) {
     let errors = state.monitoring.get_recent_errors(50).await;
 
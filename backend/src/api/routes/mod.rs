@@ -10,3 +10,38 @@ pub mod files;
 pub mod tax;
 pub mod paypal;
 pub mod guest;
+pub mod document_numbers;
+pub mod notifications;
+pub mod rate_limits;
+pub mod support;
+pub mod budgets;
+pub mod payouts;
+pub mod businesses;
+pub mod accountants;
+pub mod campaigns;
+pub mod profitability;
+pub mod sync;
+pub mod invoice_labels;
+pub mod fx;
+pub mod email_signatures;
+pub mod email_templates;
+pub mod template_bundles;
+pub mod client_imports;
+pub mod credit_notes;
+pub mod attachments;
+pub mod late_fees;
+pub mod audit_logs;
+pub mod webhook_endpoints;
+pub mod invoice_transfers;
+pub mod search;
+pub mod trash;
+pub mod portal;
+pub mod stripe_checkout;
+pub mod bank_transfers;
+pub mod receipts;
+pub mod time_entries;
+pub mod projects;
+pub mod invoice_templates;
+pub mod events;
+pub mod admin;
+pub mod client_credit;
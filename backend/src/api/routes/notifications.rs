@@ -0,0 +1,69 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{AutomationIssue, AutomationIssueFilter};
+use crate::domain::services::AutomationIssueService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_issues, resolve_issue,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct NotificationState {
+    automation_issues: Arc<AutomationIssueService>,
+}
+
+pub fn create_router(automation_issues: Arc<AutomationIssueService>) -> Router {
+    let state = NotificationState { automation_issues };
+
+    Router::new()
+        .route("/issues", get(list_issues))
+        .route("/issues/{id}/resolve", post(resolve_issue))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/issues",
+    tag = "notifications",
+    params(AutomationIssueFilter),
+    responses((status = 200, body = Vec<AutomationIssue>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_issues(
+    auth_user: AuthUser,
+    State(state): State<NotificationState>,
+    Query(filter): Query<AutomationIssueFilter>,
+) -> Result<Json<Vec<AutomationIssue>>, ApiError> {
+    let issues = state.automation_issues.list_issues(auth_user.user_id, filter).await?;
+    Ok(Json(issues))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/issues/{id}/resolve",
+    tag = "notifications",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = AutomationIssue), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn resolve_issue(
+    auth_user: AuthUser,
+    State(state): State<NotificationState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AutomationIssue>, ApiError> {
+    let issue = state.automation_issues.resolve_issue(auth_user.user_id, id).await?;
+    Ok(Json(issue))
+}
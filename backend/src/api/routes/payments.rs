@@ -1,20 +1,31 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
 use std::sync::Arc;
 use uuid::Uuid;
+use utoipa::OpenApi;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
-use crate::domain::models::{CreatePayment, PaymentListFilter, RefundRequest};
+use crate::domain::models::{parse_batch_ids, CreatePayment, PaymentListFilter, RefundRequest};
 use crate::application::use_cases::{
     CreatePaymentUseCase, GetPaymentUseCase, ListPaymentsUseCase,
     RefundPaymentUseCase, GetPaymentStatsUseCase, GetPaymentMethodsUseCase,
 };
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_payments, create_payment, get_payment, refund_payment, get_payment_methods,
+        get_payment_stats,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct PaymentState {
     create_payment_uc: Arc<CreatePaymentUseCase>,
@@ -52,23 +63,46 @@ pub fn create_router(
         .with_state(state)
 }
 
+/// Lists payments a page at a time, or with `?ids=a,b,c` fetches those payments as a batch
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments",
+    tag = "payments",
+    params(PaymentListFilter),
+    responses((status = 200, description = "A page of payments, or with `ids` a batch of them", body = crate::domain::models::Page<crate::domain::models::PaymentResponse>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn list_payments(
     auth_user: AuthUser,
     State(state): State<PaymentState>,
     Query(filter): Query<PaymentListFilter>,
-) -> Result<Json<Vec<crate::domain::models::PaymentResponse>>, ApiError> {
+) -> Result<Response, ApiError> {
+    if let Some(ids) = filter.ids.as_deref() {
+        let ids = parse_batch_ids(ids).map_err(ApiError::BadRequest)?;
+        let batch = state.list_payments_uc.execute_batch(auth_user.user_id, ids).await?;
+        return Ok(Json(batch).into_response());
+    }
+
+    let page = filter.page_request().map_err(ApiError::BadRequest)?;
     let payments = state.list_payments_uc.execute(
         auth_user.user_id,
         filter.status,
         filter.payment_method,
         filter.date_from,
         filter.date_to,
-        filter.limit,
-        filter.offset,
+        &page,
     ).await?;
-    Ok(Json(payments))
+    Ok(Json(payments).into_response())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments",
+    tag = "payments",
+    request_body = CreatePayment,
+    responses((status = 201, body = crate::domain::models::Payment), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_payment(
     auth_user: AuthUser,
     State(state): State<PaymentState>,
@@ -78,6 +112,14 @@ async fn create_payment(
     Ok((StatusCode::CREATED, Json(payment)))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/{id}",
+    tag = "payments",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = crate::domain::models::PaymentResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment(
     auth_user: AuthUser,
     State(state): State<PaymentState>,
@@ -87,6 +129,15 @@ async fn get_payment(
     Ok(Json(payment))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/payments/{id}/refund",
+    tag = "payments",
+    params(("id" = Uuid, Path)),
+    request_body = RefundRequest,
+    responses((status = 200, body = crate::domain::models::Payment), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn refund_payment(
     auth_user: AuthUser,
     State(state): State<PaymentState>,
@@ -97,6 +148,13 @@ async fn refund_payment(
     Ok(Json(refund))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/methods",
+    tag = "payments",
+    responses((status = 200, body = Vec<String>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment_methods(
     _auth_user: AuthUser,
     State(state): State<PaymentState>,
@@ -105,6 +163,13 @@ async fn get_payment_methods(
     Ok(Json(methods))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/payments/stats",
+    tag = "payments",
+    responses((status = 200, body = crate::domain::models::PaymentStats), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment_stats(
     auth_user: AuthUser,
     State(state): State<PaymentState>,
@@ -0,0 +1,184 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{PayoutDetail, PayoutReport, RecordPayout};
+use crate::domain::services::PayoutService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_payouts, record_payout, get_payout, get_payout_report, sync_stripe_payout,
+        stripe_payout_webhook,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct PayoutState {
+    payouts: Arc<PayoutService>,
+}
+
+pub fn create_router(payouts: Arc<PayoutService>) -> Router {
+    let state = PayoutState { payouts };
+
+    Router::new()
+        .route("/", get(list_payouts).post(record_payout))
+        .route("/report", get(get_payout_report))
+        .route("/sync/stripe/{gateway_payout_id}", post(sync_stripe_payout))
+        .route("/{id}", get(get_payout))
+        .with_state(state)
+}
+
+/// Unauthenticated gateway callbacks; requests are verified by signature instead
+pub fn create_webhook_router(payouts: Arc<PayoutService>) -> Router {
+    let state = PayoutState { payouts };
+
+    Router::new()
+        .route("/stripe/payouts", post(stripe_payout_webhook))
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DateRange {
+    start_date: String,
+    end_date: String,
+}
+
+impl DateRange {
+    fn parse(&self) -> Result<(NaiveDate, NaiveDate), ApiError> {
+        let start = NaiveDate::parse_from_str(&self.start_date, "%Y-%m-%d")
+            .map_err(|e| ApiError::BadRequest(format!("Invalid start_date: {}", e)))?;
+        let end = NaiveDate::parse_from_str(&self.end_date, "%Y-%m-%d")
+            .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
+        Ok((start, end))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payouts",
+    tag = "payouts",
+    params(DateRange),
+    responses((status = 200, body = Vec<PayoutDetail>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_payouts(
+    auth_user: AuthUser,
+    State(state): State<PayoutState>,
+    Query(range): Query<DateRange>,
+) -> Result<Json<Vec<PayoutDetail>>, ApiError> {
+    let (start, end) = range.parse()?;
+    let payouts = state.payouts.list_payouts(auth_user.user_id, start, end).await?;
+    Ok(Json(payouts))
+}
+
+/// Record a payout from a gateway settlement report (e.g. a PayPal transfer)
+#[utoipa::path(
+    post,
+    path = "/api/v1/payouts",
+    tag = "payouts",
+    request_body = RecordPayout,
+    responses((status = 201, body = PayoutDetail), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn record_payout(
+    auth_user: AuthUser,
+    State(state): State<PayoutState>,
+    Json(payload): Json<RecordPayout>,
+) -> Result<(StatusCode, Json<PayoutDetail>), ApiError> {
+    let payout = state.payouts.record_payout(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(payout)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/payouts/{id}",
+    tag = "payouts",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = PayoutDetail), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_payout(
+    auth_user: AuthUser,
+    State(state): State<PayoutState>,
+    Path(payout_id): Path<Uuid>,
+) -> Result<Json<PayoutDetail>, ApiError> {
+    let payout = state.payouts.get_payout(auth_user.user_id, payout_id).await?;
+    Ok(Json(payout))
+}
+
+/// Bank deposits traced back to individual payments and invoices
+#[utoipa::path(
+    get,
+    path = "/api/v1/payouts/report",
+    tag = "payouts",
+    params(DateRange),
+    responses((status = 200, body = PayoutReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_payout_report(
+    auth_user: AuthUser,
+    State(state): State<PayoutState>,
+    Query(range): Query<DateRange>,
+) -> Result<Json<PayoutReport>, ApiError> {
+    let (start, end) = range.parse()?;
+    let report = state.payouts.get_report(auth_user.user_id, start, end).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/payouts/sync/stripe/{gateway_payout_id}",
+    tag = "payouts",
+    params(("gateway_payout_id" = String, Path)),
+    responses((status = 200, body = PayoutDetail), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn sync_stripe_payout(
+    auth_user: AuthUser,
+    State(state): State<PayoutState>,
+    Path(gateway_payout_id): Path<String>,
+) -> Result<Json<PayoutDetail>, ApiError> {
+    let payout = state.payouts.sync_stripe_payout(auth_user.user_id, &gateway_payout_id).await?;
+    Ok(Json(payout))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(as = PayoutWebhookAck)]
+struct WebhookAck {
+    received: bool,
+    recorded: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/stripe/payouts",
+    tag = "payouts",
+    request_body(content = String, content_type = "application/json"),
+    responses((status = 200, body = WebhookAck), ApiError)
+)]
+async fn stripe_payout_webhook(
+    State(state): State<PayoutState>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<WebhookAck>, ApiError> {
+    let signature = headers
+        .get("Stripe-Signature")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+
+    let recorded = state.payouts.handle_stripe_webhook(&body, signature).await?;
+    Ok(Json(WebhookAck { received: true, recorded }))
+}
@@ -1,23 +1,41 @@
 use axum::{
     extract::State,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     routing::post,
     Json, Router,
 };
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
-use crate::domain::services::payment_gateway_service::{CreatePaymentIntent, PaymentGatewayService, RefundRequest};
+use crate::domain::models::payment::{CreatePayment, PaymentMethod, PaymentStatus};
+use crate::domain::services::payment_gateway_service::{
+    CreatePaymentIntent, PayPalTransmission, PaymentGatewayService, RefundRequest,
+};
+use crate::domain::services::PayPalWebhookService;
+use crate::infrastructure::repositories::{InvoiceRepository, PaymentRepository};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_paypal_order, refund_paypal_payment, get_paypal_status, paypal_webhook,
+    )
+)]
+pub struct ApiDoc;
 
 #[derive(Clone)]
 pub struct PayPalState {
     payment_gateway_service: Arc<PaymentGatewayService>,
+    payment_repo: Arc<PaymentRepository>,
+    invoice_repo: Arc<InvoiceRepository>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreatePayPalOrderRequest {
     pub amount: f64,
     pub currency: Option<String>,
@@ -25,7 +43,7 @@ pub struct CreatePayPalOrderRequest {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CreatePayPalOrderResponse {
     pub order_id: String,
     pub amount: f64,
@@ -36,14 +54,14 @@ pub struct CreatePayPalOrderResponse {
     pub message: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RefundPayPalRequest {
     pub payment_id: String,
     pub amount: Option<f64>,
     pub reason: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RefundPayPalResponse {
     pub refund_id: String,
     pub amount: f64,
@@ -51,7 +69,7 @@ pub struct RefundPayPalResponse {
     pub message: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PayPalStatusResponse {
     pub configured: bool,
     pub available_gateways: Vec<String>,
@@ -59,9 +77,15 @@ pub struct PayPalStatusResponse {
 }
 
 /// Create PayPal router with all payment gateway endpoints
-pub fn create_paypal_router(payment_gateway_service: Arc<PaymentGatewayService>) -> Router {
+pub fn create_paypal_router(
+    payment_gateway_service: Arc<PaymentGatewayService>,
+    payment_repo: Arc<PaymentRepository>,
+    invoice_repo: Arc<InvoiceRepository>,
+) -> Router {
     let state = PayPalState {
         payment_gateway_service,
+        payment_repo,
+        invoice_repo,
     };
 
     Router::new()
@@ -71,10 +95,26 @@ pub fn create_paypal_router(payment_gateway_service: Arc<PaymentGatewayService>)
         .with_state(state)
 }
 
+/// Unauthenticated PayPal callbacks; deliveries are verified by transmission signature
+pub fn create_webhook_router(webhooks: Arc<PayPalWebhookService>) -> Router {
+    Router::new()
+        .route("/paypal", post(paypal_webhook))
+        .with_state(webhooks)
+}
+
 /// Create a PayPal order for checkout
-/// This creates an order that can be used with PayPal's JavaScript SDK
+/// This creates an order that can be used with PayPal's JavaScript SDK.
+/// Orders for an invoice are tracked as a pending payment until PayPal's webhook confirms them.
+#[utoipa::path(
+    post,
+    path = "/api/v1/paypal/create-order",
+    tag = "paypal",
+    request_body = CreatePayPalOrderRequest,
+    responses((status = 201, body = CreatePayPalOrderResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_paypal_order(
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     State(state): State<PayPalState>,
     Json(payload): Json<CreatePayPalOrderRequest>,
 ) -> Result<(StatusCode, Json<CreatePayPalOrderResponse>), ApiError> {
@@ -99,6 +139,30 @@ async fn create_paypal_order(
         .await
         .map_err(|_e| ApiError::Internal)?;
 
+    // Only orders for one of the user's own invoices are tracked
+    let tracked_invoice = match payload.invoice_id {
+        Some(invoice_id) => match state.invoice_repo.get_by_id(auth_user.user_id, invoice_id).await {
+            Ok(invoice) => Some(invoice.id),
+            Err(sqlx::Error::RowNotFound) => None,
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    if let Some(invoice_id) = tracked_invoice {
+        let pending = CreatePayment {
+            invoice_id,
+            amount: Decimal::from_f64(payment_intent.amount).unwrap_or_default(),
+            payment_method: PaymentMethod::PayPal,
+            gateway: Some("paypal".to_string()),
+            gateway_payment_id: Some(payment_intent.id.clone()),
+            gateway_fee: None,
+            paid_by: None,
+            notes: None,
+            exchange_rate: None,
+        };
+        state.payment_repo.create_payment(pending, PaymentStatus::Pending).await?;
+    }
+
     // In production, you would also generate a PayPal checkout URL here
     // For now, return the order ID for client-side integration
     Ok((StatusCode::CREATED, Json(CreatePayPalOrderResponse {
@@ -113,6 +177,14 @@ async fn create_paypal_order(
 }
 
 /// Refund a PayPal payment
+#[utoipa::path(
+    post,
+    path = "/api/v1/paypal/refund",
+    tag = "paypal",
+    request_body = RefundPayPalRequest,
+    responses((status = 200, body = RefundPayPalResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn refund_paypal_payment(
     _auth_user: AuthUser,
     State(state): State<PayPalState>,
@@ -139,6 +211,13 @@ async fn refund_paypal_payment(
 }
 
 /// Check PayPal gateway status and configuration
+#[utoipa::path(
+    post,
+    path = "/api/v1/paypal/status",
+    tag = "paypal",
+    responses((status = 200, body = PayPalStatusResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_paypal_status(
     _auth_user: AuthUser,
     State(state): State<PayPalState>,
@@ -160,3 +239,41 @@ async fn get_paypal_status(
         client_id,
     }))
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+struct WebhookAck {
+    received: bool,
+    settled: bool,
+}
+
+/// PayPal webhook: settles the invoice behind an approved order or completed capture
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/paypal",
+    tag = "paypal",
+    request_body(content = String, content_type = "application/json"),
+    responses((status = 200, body = WebhookAck), ApiError)
+)]
+async fn paypal_webhook(
+    State(webhooks): State<Arc<PayPalWebhookService>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<WebhookAck>, ApiError> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .ok_or(ApiError::Unauthorized)
+    };
+    let transmission = PayPalTransmission {
+        id: header("PAYPAL-TRANSMISSION-ID")?,
+        time: header("PAYPAL-TRANSMISSION-TIME")?,
+        signature: header("PAYPAL-TRANSMISSION-SIG")?,
+        cert_url: header("PAYPAL-CERT-URL")?,
+        auth_algo: header("PAYPAL-AUTH-ALGO")?,
+    };
+
+    let settled = webhooks.handle(&transmission, &body).await?;
+    Ok(Json(WebhookAck { received: true, settled }))
+}
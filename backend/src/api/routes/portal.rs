@@ -0,0 +1,200 @@
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::PortalUser;
+use crate::api::routes::guest::{initiate_payment, GuestPaymentRequest, GuestPaymentResponse, GuestState};
+use crate::domain::models::{
+    PortalAccount, PortalInvoice, PortalInvoiceDetail, PortalLinkRequest, PortalLinkSignIn, PortalLogin,
+    PortalSession, PortalSetPassword,
+};
+use crate::domain::services::ClientAuthService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        request_link, sign_in_with_link, sign_in, get_account, set_password, list_invoices,
+        get_invoice, get_invoice_pdf, pay_invoice,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+pub struct PortalState {
+    pub client_auth: Arc<ClientAuthService>,
+    /// Portal payments go through the same checkout as guest links
+    pub guest: GuestState,
+}
+
+/// Client portal for buyers. A buyer asks for a sign-in link at `/sign-in/link`,
+/// trades it for a token at `/sign-in/link/verify` (creating their account the first
+/// time) and may then set a password for `/sign-in`. Portal tokens only work here.
+pub fn create_router(state: PortalState) -> Router {
+    Router::new()
+        .route("/sign-in", post(sign_in))
+        .route("/sign-in/link", post(request_link))
+        .route("/sign-in/link/verify", post(sign_in_with_link))
+        .route("/me", get(get_account))
+        .route("/password", put(set_password))
+        .route("/invoices", get(list_invoices))
+        .route("/invoices/{id}", get(get_invoice))
+        .route("/invoices/{id}/pdf", get(get_invoice_pdf))
+        .route("/invoices/{id}/pay", post(pay_invoice))
+        .with_state(state)
+}
+
+/// Always accepted, whether or not the email belongs to a client
+#[utoipa::path(
+    post,
+    path = "/api/v1/portal/sign-in/link",
+    tag = "portal",
+    request_body = PortalLinkRequest,
+    responses((status = 202), ApiError)
+)]
+async fn request_link(
+    State(state): State<PortalState>,
+    Json(payload): Json<PortalLinkRequest>,
+) -> Result<StatusCode, ApiError> {
+    state.client_auth.request_link(payload).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/portal/sign-in/link/verify",
+    tag = "portal",
+    request_body = PortalLinkSignIn,
+    responses((status = 200, body = PortalSession), ApiError)
+)]
+async fn sign_in_with_link(
+    State(state): State<PortalState>,
+    Json(payload): Json<PortalLinkSignIn>,
+) -> Result<Json<PortalSession>, ApiError> {
+    let session = state.client_auth.sign_in_with_link(payload).await?;
+    Ok(Json(session))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/portal/sign-in",
+    tag = "portal",
+    request_body = PortalLogin,
+    responses((status = 200, body = PortalSession), ApiError)
+)]
+async fn sign_in(
+    State(state): State<PortalState>,
+    Json(payload): Json<PortalLogin>,
+) -> Result<Json<PortalSession>, ApiError> {
+    let session = state.client_auth.sign_in(payload).await?;
+    Ok(Json(session))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/portal/me",
+    tag = "portal",
+    responses((status = 200, body = PortalAccount), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_account(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+) -> Result<Json<PortalAccount>, ApiError> {
+    let account = state.client_auth.account(portal_user.account_id).await?;
+    Ok(Json(account))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/portal/password",
+    tag = "portal",
+    request_body = PortalSetPassword,
+    responses((status = 200, body = PortalAccount), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_password(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+    Json(payload): Json<PortalSetPassword>,
+) -> Result<Json<PortalAccount>, ApiError> {
+    let account = state.client_auth.set_password(portal_user.account_id, payload).await?;
+    Ok(Json(account))
+}
+
+/// Issued invoices from every business that bills the buyer
+#[utoipa::path(
+    get,
+    path = "/api/v1/portal/invoices",
+    tag = "portal",
+    responses((status = 200, body = Vec<PortalInvoice>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_invoices(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+) -> Result<Json<Vec<PortalInvoice>>, ApiError> {
+    let invoices = state.client_auth.list_invoices(portal_user.account_id).await?;
+    Ok(Json(invoices))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/portal/invoices/{id}",
+    tag = "portal",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = PortalInvoiceDetail), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_invoice(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<PortalInvoiceDetail>, ApiError> {
+    let invoice = state.client_auth.get_invoice(portal_user.account_id, invoice_id).await?;
+    Ok(Json(invoice))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/portal/invoices/{id}/pdf",
+    tag = "portal",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, description = "The invoice PDF", content_type = "application/pdf", body = [u8]), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_invoice_pdf(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Response, ApiError> {
+    let pdf = state.client_auth.invoice_pdf(portal_user.account_id, invoice_id).await?;
+    Ok(([(header::CONTENT_TYPE, "application/pdf")], pdf.content).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/portal/invoices/{id}/pay",
+    tag = "portal",
+    params(("id" = Uuid, Path)),
+    request_body = GuestPaymentRequest,
+    responses((status = 201, body = GuestPaymentResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn pay_invoice(
+    portal_user: PortalUser,
+    State(state): State<PortalState>,
+    Path(invoice_id): Path<Uuid>,
+    Json(payload): Json<GuestPaymentRequest>,
+) -> Result<(StatusCode, Json<GuestPaymentResponse>), ApiError> {
+    state.client_auth.authorize_invoice(portal_user.account_id, invoice_id).await?;
+    let response = initiate_payment(&state.guest, invoice_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(response)))
+}
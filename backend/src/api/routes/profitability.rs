@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{AddInvoiceCost, ClientMarginReport, InvoiceCost, InvoiceProfitability};
+use crate::domain::services::ProfitabilityService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        add_cost, remove_cost, get_invoice_profitability, get_margin_by_client,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct ProfitabilityState {
+    profitability: Arc<ProfitabilityService>,
+}
+
+/// Cost and profitability routes, merged into the invoices router
+pub fn create_invoice_router(profitability: Arc<ProfitabilityService>) -> Router {
+    let state = ProfitabilityState { profitability };
+
+    Router::new()
+        .route("/{id}/costs", post(add_cost))
+        .route("/{id}/costs/{cost_id}", delete(remove_cost))
+        .route("/{id}/profitability", get(get_invoice_profitability))
+        .with_state(state)
+}
+
+/// Margin report, merged into the reports router
+pub fn create_report_router(profitability: Arc<ProfitabilityService>) -> Router {
+    let state = ProfitabilityState { profitability };
+
+    Router::new()
+        .route("/margin-by-client", get(get_margin_by_client))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/{id}/costs",
+    tag = "profitability",
+    params(("id" = Uuid, Path)),
+    request_body = AddInvoiceCost,
+    responses((status = 201, body = InvoiceCost), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn add_cost(
+    auth_user: AuthUser,
+    State(state): State<ProfitabilityState>,
+    Path(invoice_id): Path<Uuid>,
+    Json(payload): Json<AddInvoiceCost>,
+) -> Result<(StatusCode, Json<InvoiceCost>), ApiError> {
+    let cost = state.profitability.add_cost(auth_user.user_id, invoice_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(cost)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/invoices/{id}/costs/{cost_id}",
+    tag = "profitability",
+    params(("id" = Uuid, Path), ("cost_id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn remove_cost(
+    auth_user: AuthUser,
+    State(state): State<ProfitabilityState>,
+    Path((invoice_id, cost_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    state.profitability.remove_cost(auth_user.user_id, invoice_id, cost_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/invoices/{id}/profitability",
+    tag = "profitability",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = InvoiceProfitability), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_invoice_profitability(
+    auth_user: AuthUser,
+    State(state): State<ProfitabilityState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<InvoiceProfitability>, ApiError> {
+    let report = state.profitability.invoice_profitability(auth_user.user_id, invoice_id).await?;
+    Ok(Json(report))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DateRange {
+    start_date: String,
+    end_date: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/margin-by-client",
+    tag = "profitability",
+    params(DateRange),
+    responses((status = 200, body = ClientMarginReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_margin_by_client(
+    auth_user: AuthUser,
+    State(state): State<ProfitabilityState>,
+    Query(date_range): Query<DateRange>,
+) -> Result<Json<ClientMarginReport>, ApiError> {
+    let start_date = NaiveDate::parse_from_str(&date_range.start_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid start_date: {}", e)))?;
+    let end_date = NaiveDate::parse_from_str(&date_range.end_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
+
+    let report = state.profitability.margin_by_client(auth_user.user_id, start_date, end_date).await?;
+    Ok(Json(report))
+}
@@ -0,0 +1,233 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    CreateProject, Project, ProjectListFilter, ProjectProfitability, SetProject, UpdateProject,
+};
+use crate::domain::services::ProjectService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_projects, create_project, get_project, update_project, delete_project, get_profitability,
+        set_invoice_project, set_expense_project, set_time_entry_project,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct ProjectState {
+    projects: Arc<ProjectService>,
+}
+
+pub fn create_router(projects: Arc<ProjectService>) -> Router {
+    let state = ProjectState { projects };
+
+    Router::new()
+        .route("/", get(list_projects).post(create_project))
+        .route("/{id}", get(get_project).put(update_project).delete(delete_project))
+        .route("/{id}/profitability", get(get_profitability))
+        .with_state(state)
+}
+
+/// Project tagging of invoices, merged into the invoices router
+pub fn create_invoice_router(projects: Arc<ProjectService>) -> Router {
+    let state = ProjectState { projects };
+
+    Router::new()
+        .route("/{id}/project", put(set_invoice_project))
+        .with_state(state)
+}
+
+/// Project tagging of expenses, merged into the expenses router
+pub fn create_expense_router(projects: Arc<ProjectService>) -> Router {
+    let state = ProjectState { projects };
+
+    Router::new()
+        .route("/{id}/project", put(set_expense_project))
+        .with_state(state)
+}
+
+/// Project tagging of time entries, merged into the time entries router
+pub fn create_time_entry_router(projects: Arc<ProjectService>) -> Router {
+    let state = ProjectState { projects };
+
+    Router::new()
+        .route("/{id}/project", put(set_time_entry_project))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects",
+    tag = "projects",
+    params(ProjectListFilter),
+    responses((status = 200, body = Vec<Project>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_projects(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Query(filter): Query<ProjectListFilter>,
+) -> Result<Json<Vec<Project>>, ApiError> {
+    let projects = state.projects.list_projects(auth_user.user_id, filter).await?;
+    Ok(Json(projects))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/projects",
+    tag = "projects",
+    request_body = CreateProject,
+    responses((status = 201, body = Project), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Json(payload): Json<CreateProject>,
+) -> Result<(StatusCode, Json<Project>), ApiError> {
+    let project = state.projects.create_project(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(project)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = Project), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<Project>, ApiError> {
+    let project = state.projects.get_project(auth_user.user_id, project_id).await?;
+    Ok(Json(project))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateProject,
+    responses((status = 200, body = Project), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(project_id): Path<Uuid>,
+    Json(payload): Json<UpdateProject>,
+) -> Result<Json<Project>, ApiError> {
+    let project = state.projects.update_project(auth_user.user_id, project_id, payload).await?;
+    Ok(Json(project))
+}
+
+/// Deletes the project; its invoices, expenses and time entries are untagged
+#[utoipa::path(
+    delete,
+    path = "/api/v1/projects/{id}",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(project_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.projects.delete_project(auth_user.user_id, project_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Billed against costs, tracked time, and how much of the budget is left
+#[utoipa::path(
+    get,
+    path = "/api/v1/projects/{id}/profitability",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ProjectProfitability), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_profitability(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(project_id): Path<Uuid>,
+) -> Result<Json<ProjectProfitability>, ApiError> {
+    let report = state.projects.profitability(auth_user.user_id, project_id).await?;
+    Ok(Json(report))
+}
+
+/// Tags the invoice with one of its client's active projects; null untags it
+#[utoipa::path(
+    put,
+    path = "/api/v1/invoices/{id}/project",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    request_body = SetProject,
+    responses((status = 200, body = Option<Project>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_invoice_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(invoice_id): Path<Uuid>,
+    Json(payload): Json<SetProject>,
+) -> Result<Json<Option<Project>>, ApiError> {
+    let project = state.projects.set_invoice_project(auth_user.user_id, invoice_id, payload.project_id).await?;
+    Ok(Json(project))
+}
+
+/// Tags the expense with an active project; null untags it
+#[utoipa::path(
+    put,
+    path = "/api/v1/expenses/{id}/project",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    request_body = SetProject,
+    responses((status = 200, body = Option<Project>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_expense_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(expense_id): Path<Uuid>,
+    Json(payload): Json<SetProject>,
+) -> Result<Json<Option<Project>>, ApiError> {
+    let project = state.projects.set_expense_project(auth_user.user_id, expense_id, payload.project_id).await?;
+    Ok(Json(project))
+}
+
+/// Tags an unbilled entry with one of its client's active projects; null untags it
+#[utoipa::path(
+    put,
+    path = "/api/v1/time-entries/{id}/project",
+    tag = "projects",
+    params(("id" = Uuid, Path)),
+    request_body = SetProject,
+    responses((status = 200, body = Option<Project>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn set_time_entry_project(
+    auth_user: AuthUser,
+    State(state): State<ProjectState>,
+    Path(entry_id): Path<Uuid>,
+    Json(payload): Json<SetProject>,
+) -> Result<Json<Option<Project>>, ApiError> {
+    let project = state.projects.set_time_entry_project(auth_user.user_id, entry_id, payload.project_id).await?;
+    Ok(Json(project))
+}
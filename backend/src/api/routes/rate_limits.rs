@@ -0,0 +1,36 @@
+use axum::{extract::State, routing::get, Json, Router};
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::api::middleware::rate_limit::{RateLimitCaller, RateLimitCategory, RateLimitMiddleware, RateLimitStatus};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_rate_limits,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(rate_limiter: RateLimitMiddleware) -> Router {
+    Router::new()
+        .route("/", get(get_rate_limits))
+        .with_state(rate_limiter)
+}
+
+/// Current read buckets for the authenticated caller
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/rate-limits",
+    tag = "settings",
+    responses((status = 200, body = RateLimitStatus), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_rate_limits(
+    auth_user: AuthUser,
+    State(rate_limiter): State<RateLimitMiddleware>,
+) -> Result<Json<RateLimitStatus>, ApiError> {
+    let caller = RateLimitCaller::User(auth_user.user_id.to_string());
+    Ok(Json(rate_limiter.status(RateLimitCategory::Read, &caller).await))
+}
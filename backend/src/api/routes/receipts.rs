@@ -0,0 +1,111 @@
+use axum::{
+    extract::{DefaultBodyLimit, Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use axum_extra::extract::Multipart;
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{CreateExpense, Expense, ReceiptScan};
+use crate::domain::services::ReceiptScanService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        scan_receipt, get_receipt_scan, confirm_receipt_scan,
+    )
+)]
+pub struct ApiDoc;
+
+/// Receipt scanning, merged into the `/expenses` router
+pub fn create_router(receipts: Arc<ReceiptScanService>) -> Router {
+    Router::new()
+        .route(
+            "/receipts",
+            // Phone photos can be larger than the default body limit
+            post(scan_receipt).layer(DefaultBodyLimit::disable()),
+        )
+        .route("/receipts/{id}", get(get_receipt_scan))
+        .route("/receipts/{id}/confirm", post(confirm_receipt_scan))
+        .with_state(receipts)
+}
+
+/// Reads an uploaded receipt image into a draft expense for the user to check
+#[utoipa::path(
+    post,
+    path = "/api/v1/expenses/receipts",
+    tag = "expenses",
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 201, body = ReceiptScan), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn scan_receipt(
+    auth_user: AuthUser,
+    State(receipts): State<Arc<ReceiptScanService>>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<ReceiptScan>), ApiError> {
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::BadRequest(format!("Failed to read multipart field: {}", e)))?
+    {
+        let Some(file_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let content_type = field.content_type().map(str::to_string);
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| ApiError::BadRequest(format!("Failed to read file data: {}", e)))?;
+
+        let scan = receipts
+            .scan(auth_user.user_id, &file_name, content_type.as_deref(), &data)
+            .await?;
+        return Ok((StatusCode::CREATED, Json(scan)));
+    }
+
+    Err(ApiError::BadRequest("No file uploaded".to_string()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/expenses/receipts/{id}",
+    tag = "expenses",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = ReceiptScan), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_receipt_scan(
+    auth_user: AuthUser,
+    State(receipts): State<Arc<ReceiptScanService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ReceiptScan>, ApiError> {
+    let scan = receipts.get(auth_user.user_id, id).await?;
+    Ok(Json(scan))
+}
+
+/// Creates the expense from the draft as the user corrected it
+#[utoipa::path(
+    post,
+    path = "/api/v1/expenses/receipts/{id}/confirm",
+    tag = "expenses",
+    params(("id" = Uuid, Path)),
+    request_body = CreateExpense,
+    responses((status = 201, body = Expense), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn confirm_receipt_scan(
+    auth_user: AuthUser,
+    State(receipts): State<Arc<ReceiptScanService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateExpense>,
+) -> Result<(StatusCode, Json<Expense>), ApiError> {
+    let expense = receipts.confirm(auth_user.user_id, id, payload).await?;
+    Ok((StatusCode::CREATED, Json(expense)))
+}
@@ -1,4 +1,5 @@
 use axum::{
+    body::Body,
     extract::{Query, State},
     http::HeaderMap,
     response::IntoResponse,
@@ -8,16 +9,35 @@ use axum::{
 use std::sync::Arc;
 use chrono::NaiveDate;
 use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 use crate::api::error::ApiError;
 use crate::api::middleware::AuthUser;
 use crate::application::use_cases::{
     GetOverviewStatsUseCase, GetIncomeReportUseCase, GetExpensesReportUseCase,
-    GetTaxReportUseCase, GetAgingReportUseCase, ExportReportUseCase,
+    GetTaxReportUseCase, GetVatReportUseCase, GetAgingReportUseCase, GetAgingTrendUseCase, GetSlaReportUseCase, GetCashflowForecastUseCase,
+    GetTimeseriesUseCase, ExportReportUseCase,
 };
 use crate::domain::repositories::report_repository::{
-    OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport,
+    OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport, AgingTrend, ClientReportFilter, SlaReport,
+    VatReport,
 };
+use crate::domain::models::{
+    CashflowForecast, CustomReportRequest, Timeseries, TimeseriesInterval, TimeseriesMetric, TimeseriesQuery,
+    MAX_TIMESERIES_POINTS,
+};
+use crate::domain::services::CustomReportService;
+use crate::domain::services::report_service::{DEFAULT_AGING_TREND_MONTHS, MAX_AGING_TREND_MONTHS};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_overview, get_income_report, get_expenses_report, get_tax_report, get_aging_report,
+        get_vat_report, get_sla_report, get_cashflow_forecast, get_timeseries, get_aging_trend, export_report,
+        custom_report,
+    )
+)]
+pub struct ApiDoc;
 
 #[derive(Clone)]
 struct ReportState {
@@ -26,6 +46,7 @@ struct ReportState {
     get_expenses_report_uc: Arc<GetExpensesReportUseCase>,
     get_tax_report_uc: Arc<GetTaxReportUseCase>,
     get_aging_report_uc: Arc<GetAgingReportUseCase>,
+    get_aging_trend_uc: Arc<GetAgingTrendUseCase>,
     export_report_uc: Arc<ExportReportUseCase>,
 }
 
@@ -35,6 +56,7 @@ pub fn create_router(
     get_expenses_report_uc: Arc<GetExpensesReportUseCase>,
     get_tax_report_uc: Arc<GetTaxReportUseCase>,
     get_aging_report_uc: Arc<GetAgingReportUseCase>,
+    get_aging_trend_uc: Arc<GetAgingTrendUseCase>,
     export_report_uc: Arc<ExportReportUseCase>,
 ) -> Router {
     let state = ReportState {
@@ -43,6 +65,7 @@ pub fn create_router(
         get_expenses_report_uc,
         get_tax_report_uc,
         get_aging_report_uc,
+        get_aging_trend_uc,
         export_report_uc,
     };
 
@@ -52,10 +75,53 @@ pub fn create_router(
         .route("/expenses", get(get_expenses_report))
         .route("/tax", get(get_tax_report))
         .route("/aging", get(get_aging_report))
+        .route("/aging/trend", get(get_aging_trend))
         .route("/export", post(export_report))
         .with_state(state)
 }
 
+/// Custom CSV report builder, merged into `/reports`
+pub fn create_custom_router(custom_reports: Arc<CustomReportService>) -> Router {
+    Router::new()
+        .route("/custom", post(custom_report))
+        .with_state(custom_reports)
+}
+
+/// EU VAT summary, merged into `/reports`
+pub fn create_vat_router(get_vat_report_uc: Arc<GetVatReportUseCase>) -> Router {
+    Router::new()
+        .route("/vat", get(get_vat_report))
+        .with_state(get_vat_report_uc)
+}
+
+/// Response-time SLA report, merged into `/reports`
+pub fn create_sla_router(get_sla_report_uc: Arc<GetSlaReportUseCase>) -> Router {
+    Router::new()
+        .route("/sla", get(get_sla_report))
+        .with_state(get_sla_report_uc)
+}
+
+/// Cash-flow forecast, merged into `/reports`
+pub fn create_cashflow_router(get_cashflow_forecast_uc: Arc<GetCashflowForecastUseCase>) -> Router {
+    Router::new()
+        .route("/cashflow-forecast", get(get_cashflow_forecast))
+        .with_state(get_cashflow_forecast_uc)
+}
+
+/// Dashboard time series, merged into `/reports`
+pub fn create_timeseries_router(get_timeseries_uc: Arc<GetTimeseriesUseCase>) -> Router {
+    Router::new()
+        .route("/timeseries", get(get_timeseries))
+        .with_state(get_timeseries_uc)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/overview",
+    tag = "reports",
+    responses((status = 200, body = OverviewStats), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_overview(
     auth_user: AuthUser,
     State(state): State<ReportState>,
@@ -64,26 +130,44 @@ async fn get_overview(
     Ok(Json(stats))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams, ToSchema)]
+#[into_params(parameter_in = Query)]
 struct DateRange {
     start_date: String,
     end_date: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/income",
+    tag = "reports",
+    params(DateRange, ClientReportFilter),
+    responses((status = 200, body = IncomeReport), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_income_report(
     auth_user: AuthUser,
     State(state): State<ReportState>,
     Query(date_range): Query<DateRange>,
+    Query(client_filter): Query<ClientReportFilter>,
 ) -> Result<Json<IncomeReport>, ApiError> {
     let start_date = NaiveDate::parse_from_str(&date_range.start_date, "%Y-%m-%d")
         .map_err(|e| ApiError::BadRequest(format!("Invalid start_date: {}", e)))?;
     let end_date = NaiveDate::parse_from_str(&date_range.end_date, "%Y-%m-%d")
         .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
 
-    let report = state.get_income_report_uc.execute(auth_user.user_id, start_date, end_date).await?;
+    let report = state.get_income_report_uc.execute(auth_user.user_id, start_date, end_date, client_filter).await?;
     Ok(Json(report))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/expenses",
+    tag = "reports",
+    params(DateRange),
+    responses((status = 200, body = ExpensesReport), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_expenses_report(
     auth_user: AuthUser,
     State(state): State<ReportState>,
@@ -98,6 +182,14 @@ async fn get_expenses_report(
     Ok(Json(report))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/tax",
+    tag = "reports",
+    params(DateRange),
+    responses((status = 200, body = TaxReport), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_tax_report(
     auth_user: AuthUser,
     State(state): State<ReportState>,
@@ -112,21 +204,167 @@ async fn get_tax_report(
     Ok(Json(report))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/aging",
+    tag = "reports",
+    params(ClientReportFilter),
+    responses((status = 200, body = AgingReport), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_aging_report(
     auth_user: AuthUser,
     State(state): State<ReportState>,
+    Query(client_filter): Query<ClientReportFilter>,
 ) -> Result<Json<AgingReport>, ApiError> {
-    let report = state.get_aging_report_uc.execute(auth_user.user_id).await?;
+    let report = state.get_aging_report_uc.execute(auth_user.user_id, client_filter).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/vat",
+    tag = "reports",
+    params(DateRange),
+    responses((status = 200, body = VatReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_vat_report(
+    auth_user: AuthUser,
+    State(get_vat_report_uc): State<Arc<GetVatReportUseCase>>,
+    Query(date_range): Query<DateRange>,
+) -> Result<Json<VatReport>, ApiError> {
+    let start_date = NaiveDate::parse_from_str(&date_range.start_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid start_date: {}", e)))?;
+    let end_date = NaiveDate::parse_from_str(&date_range.end_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
+
+    let report = get_vat_report_uc.execute(auth_user.user_id, start_date, end_date).await?;
+    Ok(Json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/sla",
+    tag = "reports",
+    params(DateRange),
+    responses((status = 200, body = SlaReport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_sla_report(
+    auth_user: AuthUser,
+    State(get_sla_report_uc): State<Arc<GetSlaReportUseCase>>,
+    Query(date_range): Query<DateRange>,
+) -> Result<Json<SlaReport>, ApiError> {
+    let start_date = NaiveDate::parse_from_str(&date_range.start_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid start_date: {}", e)))?;
+    let end_date = NaiveDate::parse_from_str(&date_range.end_date, "%Y-%m-%d")
+        .map_err(|e| ApiError::BadRequest(format!("Invalid end_date: {}", e)))?;
+
+    let report = get_sla_report_uc.execute(auth_user.user_id, start_date, end_date).await?;
     Ok(Json(report))
 }
 
-#[derive(Deserialize)]
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/cashflow-forecast",
+    tag = "reports",
+    responses((status = 200, body = CashflowForecast), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_cashflow_forecast(
+    auth_user: AuthUser,
+    State(get_cashflow_forecast_uc): State<Arc<GetCashflowForecastUseCase>>,
+) -> Result<Json<CashflowForecast>, ApiError> {
+    let forecast = get_cashflow_forecast_uc.execute(auth_user.user_id).await?;
+    Ok(Json(forecast))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/timeseries",
+    tag = "reports",
+    params(TimeseriesQuery),
+    responses((status = 200, body = Timeseries), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_timeseries(
+    auth_user: AuthUser,
+    State(get_timeseries_uc): State<Arc<GetTimeseriesUseCase>>,
+    Query(query): Query<TimeseriesQuery>,
+) -> Result<Json<Timeseries>, ApiError> {
+    let metric = TimeseriesMetric::parse(&query.metric).ok_or_else(|| {
+        ApiError::BadRequest(format!("Invalid metric '{}': use revenue, expenses or outstanding", query.metric))
+    })?;
+    let interval = match query.interval.as_deref() {
+        None => TimeseriesInterval::default(),
+        Some(value) => TimeseriesInterval::parse(value).ok_or_else(|| {
+            ApiError::BadRequest(format!("Invalid interval '{}': use day, week or month", value))
+        })?,
+    };
+    if query.end < query.start {
+        return Err(ApiError::BadRequest("end must not be before start".to_string()));
+    }
+    if interval.bucket_starts(query.start, query.end).len() > MAX_TIMESERIES_POINTS {
+        return Err(ApiError::BadRequest(format!(
+            "Range too long: at most {} {} buckets",
+            MAX_TIMESERIES_POINTS,
+            interval.as_str()
+        )));
+    }
+
+    let series = get_timeseries_uc
+        .execute(auth_user.user_id, metric, interval, query.start, query.end)
+        .await?;
+    Ok(Json(series))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TrendQuery {
+    months: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/aging/trend",
+    tag = "reports",
+    params(TrendQuery),
+    responses((status = 200, body = AgingTrend), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_aging_trend(
+    auth_user: AuthUser,
+    State(state): State<ReportState>,
+    Query(query): Query<TrendQuery>,
+) -> Result<Json<AgingTrend>, ApiError> {
+    let months = query.months.unwrap_or(DEFAULT_AGING_TREND_MONTHS);
+    if months == 0 || months > MAX_AGING_TREND_MONTHS {
+        return Err(ApiError::BadRequest(format!(
+            "months must be between 1 and {}",
+            MAX_AGING_TREND_MONTHS
+        )));
+    }
+
+    let trend = state.get_aging_trend_uc.execute(auth_user.user_id, months).await?;
+    Ok(Json(trend))
+}
+
+#[derive(Deserialize, ToSchema)]
 struct ExportRequest {
     report_type: String,
     format: String, // "pdf" or "csv"
     date_range: DateRange,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports/export",
+    tag = "reports",
+    request_body = ExportRequest,
+    responses((status = 200, description = "The report as a file", content(([u8] = "application/pdf"), (String = "text/csv"))), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn export_report(
     auth_user: AuthUser,
     State(state): State<ReportState>,
@@ -174,3 +412,38 @@ async fn export_report(
 
     Ok((headers, file_data))
 }
+
+/// Streams the CSV; the request is validated before any rows are sent
+#[utoipa::path(
+    post,
+    path = "/api/v1/reports/custom",
+    tag = "reports",
+    request_body = CustomReportRequest,
+    responses((status = 200, description = "The report as CSV", content_type = "text/csv", body = String), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn custom_report(
+    auth_user: AuthUser,
+    State(custom_reports): State<Arc<CustomReportService>>,
+    Json(payload): Json<CustomReportRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    let stream = custom_reports.csv_stream(auth_user.user_id, &payload)?;
+
+    let filename = format!(
+        "{}_custom_{}.csv",
+        payload.entity.as_str(),
+        chrono::Utc::now().timestamp()
+    );
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/csv".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename).parse().unwrap(),
+    );
+
+    Ok((headers, Body::from_stream(stream)))
+}
@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{SearchQuery, SearchResults};
+use crate::domain::services::SearchService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        search_account,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(search: Arc<SearchService>) -> Router {
+    Router::new()
+        .route("/", get(search_account))
+        .with_state(search)
+}
+
+/// Searches invoices, clients and expenses for `q`, each word matched as a prefix.
+/// `types` narrows the groups searched and `limit` sets the results per group.
+#[utoipa::path(
+    get,
+    path = "/api/v1/search",
+    tag = "search",
+    params(SearchQuery),
+    responses((status = 200, body = SearchResults), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn search_account(
+    auth_user: AuthUser,
+    State(search): State<Arc<SearchService>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResults>, ApiError> {
+    let results = search.search(auth_user.user_id, &query).await?;
+    Ok(Json(results))
+}
@@ -1,19 +1,37 @@
+use utoipa::{OpenApi, ToSchema};
 use axum::{
-    routing::{get, put},
+    routing::{get, post, put},
     extract::{State},
     Json, Router,
 };
+use axum_extra::extract::Multipart;
 use std::sync::Arc;
 
 use crate::api::error::ApiError;
+use crate::api::openapi::FileUpload;
 use crate::api::middleware::AuthUser;
-use crate::domain::models::{BusinessAddress, NotificationSettings};
+use crate::domain::i18n::Locale;
+use crate::domain::models::{
+    parse_hex_color, validate_qris, BusinessAddress, CreditAutoApply, DocumentNumberFormat, DocumentType, InvoiceSettings,
+    NotificationSettings, PaymentQrFormat, SepaAccount, UpdateDocumentNumberFormat, MAX_FOOTER_TEXT_LENGTH, MAX_REMINDER_DAYS, TEMPLATE_VARIABLES,
+};
+use crate::domain::services::{DocumentNumberService, PdfTemplate};
 use crate::application::use_cases::{
     GetBusinessSettingsUseCase, UpdateBusinessSettingsUseCase,
     GetNotificationSettingsUseCase, UpdateNotificationSettingsUseCase,
     GetInvoiceSettingsUseCase, UpdateInvoiceSettingsUseCase,
 };
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_business_settings, update_business_settings, get_notification_settings,
+        update_notification_settings, get_invoice_settings, update_invoice_settings,
+        upload_invoice_logo, remove_invoice_logo,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 struct SettingsState {
     get_business_uc: Arc<GetBusinessSettingsUseCase>,
@@ -22,6 +40,7 @@ struct SettingsState {
     update_notification_uc: Arc<UpdateNotificationSettingsUseCase>,
     get_invoice_uc: Arc<GetInvoiceSettingsUseCase>,
     update_invoice_uc: Arc<UpdateInvoiceSettingsUseCase>,
+    document_numbers: Arc<DocumentNumberService>,
 }
 
 pub fn create_router(
@@ -31,6 +50,7 @@ pub fn create_router(
     update_notification_uc: Arc<UpdateNotificationSettingsUseCase>,
     get_invoice_uc: Arc<GetInvoiceSettingsUseCase>,
     update_invoice_uc: Arc<UpdateInvoiceSettingsUseCase>,
+    document_numbers: Arc<DocumentNumberService>,
 ) -> Router {
     let state = SettingsState {
         get_business_uc,
@@ -39,6 +59,7 @@ pub fn create_router(
         update_notification_uc,
         get_invoice_uc,
         update_invoice_uc,
+        document_numbers,
     };
 
     Router::new()
@@ -48,18 +69,30 @@ pub fn create_router(
         .route("/notifications", put(update_notification_settings))
         .route("/invoice", get(get_invoice_settings))
         .route("/invoice", put(update_invoice_settings))
+        .route("/invoice/logo", post(upload_invoice_logo).delete(remove_invoice_logo))
         .with_state(state)
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct BusinessSettingsResponse {
     company_name: Option<String>,
     business_type: Option<String>,
     address: Option<BusinessAddress>,
     phone: Option<String>,
     email: String,
+    /// Language and formats of invoices, PDFs and client emails
+    locale: Locale,
+    /// EU VAT number, printed on reverse-charged invoices and e-invoices
+    vat_number: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/business",
+    tag = "settings",
+    responses((status = 200, body = BusinessSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_business_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
@@ -71,17 +104,30 @@ async fn get_business_settings(
         address: user.business_address,
         phone: user.phone,
         email: user.email,
+        locale: user.locale,
+        vat_number: user.tax_settings.and_then(|tax| tax.tax_id),
     }))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 struct UpdateBusinessRequest {
     company_name: Option<String>,
     business_type: Option<String>,
     address: Option<BusinessAddress>,
     phone: Option<String>,
+    locale: Option<Locale>,
+    /// EU VAT number, e.g. DE136695976; "" removes it
+    vat_number: Option<String>,
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/business",
+    tag = "settings",
+    request_body = UpdateBusinessRequest,
+    responses((status = 200, body = BusinessSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_business_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
@@ -93,6 +139,8 @@ async fn update_business_settings(
         payload.business_type,
         payload.address,
         payload.phone,
+        payload.locale,
+        payload.vat_number,
     ).await?;
 
     Ok(Json(BusinessSettingsResponse {
@@ -101,9 +149,18 @@ async fn update_business_settings(
         address: user.business_address,
         phone: user.phone,
         email: user.email,
+        locale: user.locale,
+        vat_number: user.tax_settings.and_then(|tax| tax.tax_id),
     }))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/notifications",
+    tag = "settings",
+    responses((status = 200, body = NotificationSettings), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_notification_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
@@ -112,65 +169,252 @@ async fn get_notification_settings(
     Ok(Json(user.notification_settings))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/notifications",
+    tag = "settings",
+    request_body = NotificationSettings,
+    responses((status = 200, body = NotificationSettings), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_notification_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
-    Json(payload): Json<NotificationSettings>,
+    Json(mut payload): Json<NotificationSettings>,
 ) -> Result<Json<NotificationSettings>, ApiError> {
-    let user = state.update_notification_uc.execute(auth_user.user_id, payload.clone()).await?;
+    payload.reminder_days.sort_unstable();
+    payload.reminder_days.dedup();
+    if payload.reminder_days.len() > MAX_REMINDER_DAYS {
+        return Err(ApiError::Validation(format!("reminder_days can list at most {} days", MAX_REMINDER_DAYS)));
+    }
+    if payload.reminder_days.iter().any(|days| *days > 365) {
+        return Err(ApiError::Validation("reminder_days must be between 0 and 365".to_string()));
+    }
+
+    let user = state.update_notification_uc.execute(auth_user.user_id, payload).await?;
     Ok(Json(user.notification_settings))
 }
 
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, ToSchema)]
 struct InvoiceSettingsResponse {
     template: String,
     logo_url: Option<String>,
     terms: String,
     notes: String,
+    late_fee_rate: Option<f64>,
+    paid_stamp: bool,
+    sla_view_hours: Option<f64>,
+    sla_payment_days: Option<f64>,
+    accent_color: Option<String>,
+    footer_text: Option<String>,
+    credit_auto_apply: CreditAutoApply,
+    payment_qr: PaymentQrFormat,
+    sepa_account: Option<SepaAccount>,
+    qris_payload: Option<String>,
+    /// PDF layouts `template` can name
+    templates: Vec<&'static str>,
+    /// Invoice numbering; same as /settings/document-numbers/invoice
+    numbering: DocumentNumberFormat,
+    /// Placeholders resolved in terms/notes when an invoice is created
+    variables: Vec<&'static str>,
 }
 
+impl InvoiceSettingsResponse {
+    fn new(settings: InvoiceSettings, numbering: DocumentNumberFormat) -> Self {
+        Self {
+            template: settings.template,
+            logo_url: settings.logo_url,
+            terms: settings.terms,
+            notes: settings.notes,
+            late_fee_rate: settings.late_fee_rate,
+            paid_stamp: settings.paid_stamp,
+            sla_view_hours: settings.sla_view_hours,
+            sla_payment_days: settings.sla_payment_days,
+            accent_color: settings.accent_color,
+            footer_text: settings.footer_text,
+            credit_auto_apply: settings.credit_auto_apply,
+            payment_qr: settings.payment_qr,
+            sepa_account: settings.sepa_account,
+            qris_payload: settings.qris_payload,
+            templates: PdfTemplate::ALL.iter().map(PdfTemplate::as_str).collect(),
+            numbering,
+            variables: TEMPLATE_VARIABLES.to_vec(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/invoice",
+    tag = "settings",
+    responses((status = 200, body = InvoiceSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_invoice_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
 ) -> Result<Json<InvoiceSettingsResponse>, ApiError> {
     let user = state.get_invoice_uc.execute(auth_user.user_id).await?;
-    let invoice_settings = user.invoice_settings.unwrap_or_default();
-
-    Ok(Json(InvoiceSettingsResponse {
-        template: invoice_settings.template,
-        logo_url: invoice_settings.logo_url,
-        terms: invoice_settings.terms,
-        notes: invoice_settings.notes,
-    }))
+    let numbering = state.document_numbers.get_format(auth_user.user_id, DocumentType::Invoice).await?;
+    Ok(Json(InvoiceSettingsResponse::new(user.invoice_settings.unwrap_or_default(), numbering)))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, ToSchema)]
 struct UpdateInvoiceRequest {
     template: String,
     logo_url: Option<String>,
     terms: String,
     notes: String,
+    #[serde(default)]
+    late_fee_rate: Option<f64>,
+    #[serde(default)]
+    paid_stamp: bool,
+    #[serde(default)]
+    sla_view_hours: Option<f64>,
+    #[serde(default)]
+    sla_payment_days: Option<f64>,
+    /// `#RRGGBB`
+    #[serde(default)]
+    accent_color: Option<String>,
+    #[serde(default)]
+    footer_text: Option<String>,
+    /// When client credit is applied to invoices: `on_create`, `on_send` (default) or `manual`
+    #[serde(default)]
+    credit_auto_apply: CreditAutoApply,
+    /// QR code on invoice PDFs: `link` (default, the guest payment link), `sepa`
+    /// (EPC QR on EUR invoices), `qris` (on IDR invoices) or `off`
+    #[serde(default)]
+    payment_qr: PaymentQrFormat,
+    /// Required for `sepa`
+    #[serde(default)]
+    sepa_account: Option<SepaAccount>,
+    /// The static QRIS code to fill the amount into; required for `qris`
+    #[serde(default)]
+    qris_payload: Option<String>,
+    /// Fields left out keep their current value
+    #[serde(default)]
+    numbering: Option<UpdateDocumentNumberFormat>,
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/invoice",
+    tag = "settings",
+    request_body = UpdateInvoiceRequest,
+    responses((status = 200, body = InvoiceSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_invoice_settings(
     auth_user: AuthUser,
     State(state): State<SettingsState>,
     Json(payload): Json<UpdateInvoiceRequest>,
 ) -> Result<Json<InvoiceSettingsResponse>, ApiError> {
+    if payload.late_fee_rate.is_some_and(|rate| !(0.0..=100.0).contains(&rate)) {
+        return Err(ApiError::Validation("late_fee_rate must be between 0 and 100".to_string()));
+    }
+    if payload.sla_view_hours.is_some_and(|hours| !hours.is_finite() || hours <= 0.0) {
+        return Err(ApiError::Validation("sla_view_hours must be greater than 0".to_string()));
+    }
+    if payload.sla_payment_days.is_some_and(|days| !days.is_finite() || days <= 0.0) {
+        return Err(ApiError::Validation("sla_payment_days must be greater than 0".to_string()));
+    }
+    let accent_color = payload.accent_color.filter(|color| !color.trim().is_empty());
+    if accent_color.as_deref().is_some_and(|color| parse_hex_color(color).is_none()) {
+        return Err(ApiError::Validation("accent_color must be a hex colour like #2563EB".to_string()));
+    }
+    let footer_text = payload.footer_text.map(|text| text.trim().to_string()).filter(|text| !text.is_empty());
+    if footer_text.as_ref().is_some_and(|text| text.chars().count() > MAX_FOOTER_TEXT_LENGTH) {
+        return Err(ApiError::Validation(format!("footer_text must be at most {} characters", MAX_FOOTER_TEXT_LENGTH)));
+    }
+    let sepa_account = payload.sepa_account.map(SepaAccount::normalize).transpose().map_err(ApiError::Validation)?;
+    let qris_payload = payload
+        .qris_payload
+        .filter(|qris| !qris.trim().is_empty())
+        .map(|qris| validate_qris(&qris))
+        .transpose()
+        .map_err(ApiError::Validation)?;
+    if payload.payment_qr == PaymentQrFormat::Sepa && sepa_account.is_none() {
+        return Err(ApiError::Validation("payment_qr sepa needs a sepa_account".to_string()));
+    }
+    if payload.payment_qr == PaymentQrFormat::Qris && qris_payload.is_none() {
+        return Err(ApiError::Validation("payment_qr qris needs a qris_payload".to_string()));
+    }
+
+    let numbering = match payload.numbering {
+        Some(update) => state.document_numbers.update_format(auth_user.user_id, DocumentType::Invoice, update).await?,
+        None => state.document_numbers.get_format(auth_user.user_id, DocumentType::Invoice).await?,
+    };
+
     let user = state.update_invoice_uc.execute(
         auth_user.user_id,
-        payload.template.clone(),
-        payload.logo_url.clone(),
-        payload.terms.clone(),
-        payload.notes.clone(),
+        InvoiceSettings {
+            template: payload.template,
+            logo_url: payload.logo_url,
+            terms: payload.terms,
+            notes: payload.notes,
+            late_fee_rate: payload.late_fee_rate,
+            paid_stamp: payload.paid_stamp,
+            sla_view_hours: payload.sla_view_hours,
+            sla_payment_days: payload.sla_payment_days,
+            accent_color,
+            footer_text,
+            credit_auto_apply: payload.credit_auto_apply,
+            payment_qr: payload.payment_qr,
+            sepa_account,
+            qris_payload,
+        },
     ).await?;
 
-    let invoice_settings = user.invoice_settings.unwrap_or_default();
+    Ok(Json(InvoiceSettingsResponse::new(user.invoice_settings.unwrap_or_default(), numbering)))
+}
 
-    Ok(Json(InvoiceSettingsResponse {
-        template: invoice_settings.template,
-        logo_url: invoice_settings.logo_url,
-        terms: invoice_settings.terms,
-        notes: invoice_settings.notes,
-    }))
+/// Upload the invoice logo as multipart field `file` (PNG or JPEG)
+#[utoipa::path(
+    post,
+    path = "/api/v1/settings/invoice/logo",
+    tag = "settings",
+    request_body(content = inline(FileUpload), content_type = "multipart/form-data"),
+    responses((status = 200, body = InvoiceSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn upload_invoice_logo(
+    auth_user: AuthUser,
+    State(state): State<SettingsState>,
+    mut multipart: Multipart,
+) -> Result<Json<InvoiceSettingsResponse>, ApiError> {
+    let mut logo = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        ApiError::BadRequest(format!("Failed to read multipart field: {}", e))
+    })? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = field.file_name().unwrap_or("logo").to_string();
+        let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
+        let data = field.bytes().await.map_err(|e| {
+            ApiError::BadRequest(format!("Failed to read file data: {}", e))
+        })?;
+        logo = Some((file_name, content_type, data));
+    }
+    let (file_name, content_type, data) = logo.ok_or_else(|| ApiError::BadRequest("No file uploaded".to_string()))?;
+
+    let user = state.update_invoice_uc.upload_logo(auth_user.user_id, &data, &file_name, &content_type).await?;
+    let numbering = state.document_numbers.get_format(auth_user.user_id, DocumentType::Invoice).await?;
+    Ok(Json(InvoiceSettingsResponse::new(user.invoice_settings.unwrap_or_default(), numbering)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/settings/invoice/logo",
+    tag = "settings",
+    responses((status = 200, body = InvoiceSettingsResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn remove_invoice_logo(
+    auth_user: AuthUser,
+    State(state): State<SettingsState>,
+) -> Result<Json<InvoiceSettingsResponse>, ApiError> {
+    let user = state.update_invoice_uc.remove_logo(auth_user.user_id).await?;
+    let numbering = state.document_numbers.get_format(auth_user.user_id, DocumentType::Invoice).await?;
+    Ok(Json(InvoiceSettingsResponse::new(user.invoice_settings.unwrap_or_default(), numbering)))
 }
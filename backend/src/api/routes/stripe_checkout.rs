@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Redirect,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use std::sync::Arc;
+
+use crate::api::error::ApiError;
+use crate::domain::services::StripeCheckoutService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        checkout_success, checkout_cancel, stripe_checkout_webhook,
+    )
+)]
+pub struct ApiDoc;
+
+/// Where Stripe's hosted Checkout page sends the payer back to. Both look the
+/// session up with Stripe before acting on it, then redirect to the app.
+pub fn create_router(checkout: Arc<StripeCheckoutService>) -> Router {
+    Router::new()
+        .route("/success", get(checkout_success))
+        .route("/cancel", get(checkout_cancel))
+        .with_state(checkout)
+}
+
+/// Unauthenticated Stripe callbacks; deliveries are verified by signature instead
+pub fn create_webhook_router(checkout: Arc<StripeCheckoutService>) -> Router {
+    Router::new()
+        .route("/stripe/checkout", post(stripe_checkout_webhook))
+        .with_state(checkout)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CheckoutReturn {
+    session_id: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct WebhookAck {
+    received: bool,
+    settled: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/checkout/stripe/success",
+    tag = "stripe-checkout",
+    params(CheckoutReturn),
+    responses((status = 303, description = "Back to the invoice after payment"), ApiError)
+)]
+async fn checkout_success(
+    State(checkout): State<Arc<StripeCheckoutService>>,
+    Query(query): Query<CheckoutReturn>,
+) -> Result<Redirect, ApiError> {
+    let url = checkout.complete(&query.session_id).await?;
+    Ok(Redirect::to(&url))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/checkout/stripe/cancel",
+    tag = "stripe-checkout",
+    params(CheckoutReturn),
+    responses((status = 303, description = "Back to the invoice"), ApiError)
+)]
+async fn checkout_cancel(
+    State(checkout): State<Arc<StripeCheckoutService>>,
+    Query(query): Query<CheckoutReturn>,
+) -> Result<Redirect, ApiError> {
+    let url = checkout.cancel(&query.session_id).await?;
+    Ok(Redirect::to(&url))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/stripe/checkout",
+    tag = "stripe-checkout",
+    request_body(content = String, content_type = "application/json"),
+    responses((status = 200, body = WebhookAck), ApiError)
+)]
+async fn stripe_checkout_webhook(
+    State(checkout): State<Arc<StripeCheckoutService>>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<WebhookAck>, ApiError> {
+    let signature = headers
+        .get("Stripe-Signature")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+
+    let settled = checkout.handle_webhook(&body, signature).await?;
+    Ok(Json(WebhookAck { received: true, settled }))
+}
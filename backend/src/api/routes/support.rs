@@ -0,0 +1,66 @@
+use axum::{
+    extract::{Path, State},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::invoice::InvoiceDetailResponse;
+use crate::domain::services::{InvoiceAnonymizer, InvoiceService};
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        export_anonymized_invoice,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct SupportState {
+    invoice_service: Arc<InvoiceService>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnonymizedInvoiceExport {
+    pub generated_at: DateTime<Utc>,
+    /// Names, contact details, notes and IDs are pseudonymized and amounts are scaled
+    /// by a constant factor; dates, statuses, quantities and tax rates are unchanged
+    pub invoice: InvoiceDetailResponse,
+}
+
+pub fn create_router(invoice_service: Arc<InvoiceService>) -> Router {
+    let state = SupportState { invoice_service };
+
+    Router::new()
+        .route("/invoices/{id}/anonymized", get(export_anonymized_invoice))
+        .with_state(state)
+}
+
+/// Anonymized copy of an invoice that can be shared with support
+#[utoipa::path(
+    get,
+    path = "/api/v1/support/invoices/{id}/anonymized",
+    tag = "support",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = AnonymizedInvoiceExport), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn export_anonymized_invoice(
+    auth_user: AuthUser,
+    State(state): State<SupportState>,
+    Path(invoice_id): Path<Uuid>,
+) -> Result<Json<AnonymizedInvoiceExport>, ApiError> {
+    let invoice = state.invoice_service.get_invoice(auth_user.user_id, invoice_id).await?;
+
+    Ok(Json(AnonymizedInvoiceExport {
+        generated_at: Utc::now(),
+        invoice: InvoiceAnonymizer::new().anonymize_invoice(invoice),
+    }))
+}
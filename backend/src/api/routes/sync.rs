@@ -0,0 +1,84 @@
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use std::sync::Arc;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{ApplySyncMutations, SyncApplyResult, SyncChanges};
+use crate::domain::services::SyncService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        get_changes, apply_mutations,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct SyncState {
+    sync: Arc<SyncService>,
+}
+
+/// Incremental sync for offline clients. `GET /sync` without a cursor returns every
+/// live entity; passing the returned cursor as `since` returns only what changed.
+/// `POST /sync/apply` sends offline edits back, each against the version it was made on.
+pub fn create_router(sync: Arc<SyncService>) -> Router {
+    let state = SyncState { sync };
+
+    Router::new()
+        .route("/", get(get_changes))
+        .route("/apply", post(apply_mutations))
+        .with_state(state)
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SyncQuery {
+    since: Option<String>,
+    limit: Option<i64>,
+    /// Seconds to wait for a change when there is none yet
+    wait: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/sync",
+    tag = "sync",
+    params(SyncQuery),
+    responses((status = 200, body = SyncChanges), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_changes(
+    auth_user: AuthUser,
+    State(state): State<SyncState>,
+    Query(query): Query<SyncQuery>,
+) -> Result<Json<SyncChanges>, ApiError> {
+    let changes = state
+        .sync
+        .changes(auth_user.user_id, query.since.as_deref(), query.limit, query.wait)
+        .await?;
+    Ok(Json(changes))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/sync/apply",
+    tag = "sync",
+    request_body = ApplySyncMutations,
+    responses((status = 200, body = SyncApplyResult), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn apply_mutations(
+    auth_user: AuthUser,
+    State(state): State<SyncState>,
+    Json(payload): Json<ApplySyncMutations>,
+) -> Result<Json<SyncApplyResult>, ApiError> {
+    let result = state.sync.apply(auth_user.user_id, payload).await?;
+    Ok(Json(result))
+}
@@ -5,6 +5,7 @@ use axum::{
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
+use utoipa::{OpenApi, ToSchema};
 use uuid::Uuid;
 
 use crate::api::error::ApiError;
@@ -17,6 +18,15 @@ use crate::application::use_cases::{
 use crate::domain::models::{CreateTaxSetting, UpdateTaxSetting, TaxSetting, TaxCalculation, TaxSummary, InvoiceListFilter};
 use crate::infrastructure::repositories::InvoiceRepository;
 
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        create_tax_setting, get_tax_settings, get_default_tax, update_tax_setting,
+        delete_tax_setting, calculate_tax, get_tax_summary, validate_tax_id,
+    )
+)]
+pub struct ApiDoc;
+
 #[derive(Clone)]
 pub struct TaxState {
     pub create_tax_setting: Arc<CreateTaxSettingUseCase>,
@@ -34,6 +44,14 @@ use std::sync::Arc;
 
 /// POST /api/v1/settings/tax
 /// Create a new tax setting
+#[utoipa::path(
+    post,
+    path = "/api/v1/settings/tax",
+    tag = "tax",
+    request_body = CreateTaxSetting,
+    responses((status = 200, body = TaxSetting), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn create_tax_setting(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -47,6 +65,13 @@ async fn create_tax_setting(
 
 /// GET /api/v1/settings/tax
 /// Get all tax settings for the organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/tax",
+    tag = "tax",
+    responses((status = 200, body = Vec<TaxSetting>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_tax_settings(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -59,6 +84,13 @@ async fn get_tax_settings(
 
 /// GET /api/v1/settings/tax/default
 /// Get the default tax setting
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/tax/default",
+    tag = "tax",
+    responses((status = 200, body = Option<TaxSetting>), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_default_tax(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -71,6 +103,15 @@ async fn get_default_tax(
 
 /// PUT /api/v1/settings/tax/{id}
 /// Update a tax setting
+#[utoipa::path(
+    put,
+    path = "/api/v1/settings/tax/{id}",
+    tag = "tax",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateTaxSetting,
+    responses((status = 200, body = TaxSetting), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn update_tax_setting(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -85,6 +126,14 @@ async fn update_tax_setting(
 
 /// DELETE /api/v1/settings/tax/{id}
 /// Delete a tax setting
+#[utoipa::path(
+    delete,
+    path = "/api/v1/settings/tax/{id}",
+    tag = "tax",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn delete_tax_setting(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -98,17 +147,25 @@ async fn delete_tax_setting(
 
 /// POST /api/v1/tax/calculate
 /// Calculate tax for an amount
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct CalculateTaxRequest {
     amount: f64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct CalculateTaxResponse {
     calculation: TaxCalculation,
     legal_disclaimer: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/tax/calculate",
+    tag = "tax",
+    request_body = CalculateTaxRequest,
+    responses((status = 200, body = CalculateTaxResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn calculate_tax(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -126,12 +183,20 @@ async fn calculate_tax(
 
 /// GET /api/v1/tax/summary
 /// Get tax summary for a period
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct TaxSummaryRequest {
     start_date: String,
     end_date: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/tax/summary",
+    tag = "tax",
+    request_body = TaxSummaryRequest,
+    responses((status = 200, body = TaxSummary), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn get_tax_summary(
     auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -151,8 +216,8 @@ async fn get_tax_summary(
         date_from: Some(start_date),
         date_to: Some(end_date),
         search: None,
-        limit: None,
-        offset: None,
+        label_id: None,
+        ids: None,
     };
 
     // Get invoice IDs first
@@ -183,6 +248,8 @@ async fn get_tax_summary(
             tax_included: detail.tax_included,
             tax_label: detail.tax_label,
             tax_id: detail.tax_id,
+            currency: detail.currency,
+            exchange_rate: detail.exchange_rate,
             pdf_url: detail.pdf_url,
             receipt_image_url: detail.receipt_image_url,
             sent_at: detail.sent_at,
@@ -196,6 +263,10 @@ async fn get_tax_summary(
             allow_partial_payment: detail.allow_partial_payment,
             min_payment_amount: detail.min_payment_amount,
             partial_payment_count: detail.partial_payment_count,
+            deposit_amount: detail.deposit.as_ref().map(|d| d.amount),
+            deposit_percent: detail.deposit.as_ref().and_then(|d| d.percent),
+            deposit_due_date: detail.deposit.as_ref().map(|d| d.due_date),
+            deposit_paid_at: detail.deposit.as_ref().and_then(|d| d.paid_at),
             created_at: detail.created_at,
             updated_at: detail.updated_at,
         };
@@ -217,18 +288,26 @@ async fn get_tax_summary(
 
 /// POST /api/v1/tax/validate
 /// Validate a tax ID
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct ValidateTaxIdRequest {
     tax_id: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct ValidateTaxIdResponse {
     tax_id: String,
     is_valid: bool,
     normalized: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/tax/validate",
+    tag = "tax",
+    request_body = ValidateTaxIdRequest,
+    responses((status = 200, body = ValidateTaxIdResponse), ApiError),
+    security(("bearer_auth" = []))
+)]
 async fn validate_tax_id(
     _auth_user: AuthUser,
     State(state): State<TaxState>,
@@ -0,0 +1,68 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::TemplateBundle;
+use crate::domain::services::TemplateBundleService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        export_bundle, import_bundle,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(bundles: Arc<TemplateBundleService>) -> Router {
+    Router::new()
+        .route("/", get(export_bundle).post(import_bundle))
+        .with_state(bundles)
+}
+
+/// Downloads the bundle as a JSON file ready to share
+#[utoipa::path(
+    get,
+    path = "/api/v1/settings/template-bundle",
+    tag = "settings",
+    responses((status = 200, description = "The bundle as a JSON download", body = TemplateBundle), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn export_bundle(
+    auth_user: AuthUser,
+    State(bundles): State<Arc<TemplateBundleService>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let bundle = bundles.export(auth_user.user_id).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        "attachment; filename=\"invoice-template.json\"".parse().unwrap(),
+    );
+
+    Ok((headers, Json(bundle)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/settings/template-bundle",
+    tag = "settings",
+    request_body = TemplateBundle,
+    responses((status = 200, body = TemplateBundle), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn import_bundle(
+    auth_user: AuthUser,
+    State(bundles): State<Arc<TemplateBundleService>>,
+    Json(payload): Json<TemplateBundle>,
+) -> Result<Json<TemplateBundle>, ApiError> {
+    let bundle = bundles.import(auth_user.user_id, payload).await?;
+    Ok(Json(bundle))
+}
@@ -0,0 +1,179 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::application::dto::invoice_dto::InvoiceCreatedDto;
+use crate::application::use_cases::CreateInvoiceFromTimeUseCase;
+use crate::domain::models::{
+    CreateTimeEntry, InvoiceFromTime, Page, StartTimer, TimeEntry, TimeEntryListFilter, UpdateTimeEntry,
+};
+use crate::domain::services::TimeEntryService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_entries, create_entry, start_timer, stop_timer, update_entry, delete_entry, create_invoice_from_time,
+    )
+)]
+pub struct ApiDoc;
+
+#[derive(Clone)]
+struct TimeEntryState {
+    time_entries: Arc<TimeEntryService>,
+}
+
+#[derive(Clone)]
+struct InvoiceFromTimeState {
+    invoice_from_time: Arc<CreateInvoiceFromTimeUseCase>,
+}
+
+pub fn create_router(time_entries: Arc<TimeEntryService>) -> Router {
+    let state = TimeEntryState { time_entries };
+
+    Router::new()
+        .route("/", get(list_entries).post(create_entry))
+        .route("/start", post(start_timer))
+        .route("/{id}/stop", post(stop_timer))
+        .route("/{id}", put(update_entry).delete(delete_entry))
+        .with_state(state)
+}
+
+/// Billing of tracked time, merged into the invoices router
+pub fn create_invoice_router(invoice_from_time: Arc<CreateInvoiceFromTimeUseCase>) -> Router {
+    let state = InvoiceFromTimeState { invoice_from_time };
+
+    Router::new()
+        .route("/from-time", post(create_invoice_from_time))
+        .with_state(state)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/time-entries",
+    tag = "time-entries",
+    params(TimeEntryListFilter),
+    responses((status = 200, body = Page<TimeEntry>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_entries(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Query(filter): Query<TimeEntryListFilter>,
+) -> Result<Json<Page<TimeEntry>>, ApiError> {
+    let entries = state.time_entries.list_entries(auth_user.user_id, filter).await?;
+    Ok(Json(entries))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/time-entries",
+    tag = "time-entries",
+    request_body = CreateTimeEntry,
+    responses((status = 201, body = TimeEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_entry(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Json(payload): Json<CreateTimeEntry>,
+) -> Result<(StatusCode, Json<TimeEntry>), ApiError> {
+    let entry = state.time_entries.create_entry(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+/// Starts a timer now, stopping the one already running
+#[utoipa::path(
+    post,
+    path = "/api/v1/time-entries/start",
+    tag = "time-entries",
+    request_body = StartTimer,
+    responses((status = 201, body = TimeEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn start_timer(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Json(payload): Json<StartTimer>,
+) -> Result<(StatusCode, Json<TimeEntry>), ApiError> {
+    let entry = state.time_entries.start_timer(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(entry)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/time-entries/{id}/stop",
+    tag = "time-entries",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = TimeEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn stop_timer(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<Json<TimeEntry>, ApiError> {
+    let entry = state.time_entries.stop_timer(auth_user.user_id, entry_id).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/time-entries/{id}",
+    tag = "time-entries",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateTimeEntry,
+    responses((status = 200, body = TimeEntry), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_entry(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Path(entry_id): Path<Uuid>,
+    Json(payload): Json<UpdateTimeEntry>,
+) -> Result<Json<TimeEntry>, ApiError> {
+    let entry = state.time_entries.update_entry(auth_user.user_id, entry_id, payload).await?;
+    Ok(Json(entry))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/time-entries/{id}",
+    tag = "time-entries",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_entry(
+    auth_user: AuthUser,
+    State(state): State<TimeEntryState>,
+    Path(entry_id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.time_entries.delete_entry(auth_user.user_id, entry_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Creates a draft with a line per unbilled entry and marks the entries billed
+#[utoipa::path(
+    post,
+    path = "/api/v1/invoices/from-time",
+    tag = "time-entries",
+    request_body = InvoiceFromTime,
+    responses((status = 201, body = InvoiceCreatedDto), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_invoice_from_time(
+    auth_user: AuthUser,
+    State(state): State<InvoiceFromTimeState>,
+    Json(payload): Json<InvoiceFromTime>,
+) -> Result<(StatusCode, Json<InvoiceCreatedDto>), ApiError> {
+    let invoice = state.invoice_from_time.execute(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(invoice)))
+}
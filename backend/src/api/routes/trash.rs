@@ -0,0 +1,39 @@
+use axum::{extract::State, routing::get, Json, Router};
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::Trash;
+use crate::domain::services::TrashService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_trash,
+    )
+)]
+pub struct ApiDoc;
+
+pub fn create_router(trash: Arc<TrashService>) -> Router {
+    Router::new()
+        .route("/", get(list_trash))
+        .with_state(trash)
+}
+
+/// Deleted draft invoices and clients, with when each will be purged. Restore them
+/// with `POST /invoices/{id}/restore` and `POST /clients/{id}/restore`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/trash",
+    tag = "trash",
+    responses((status = 200, body = Trash), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_trash(
+    auth_user: AuthUser,
+    State(trash): State<Arc<TrashService>>,
+) -> Result<Json<Trash>, ApiError> {
+    let trash = trash.list(auth_user.user_id).await?;
+    Ok(Json(trash))
+}
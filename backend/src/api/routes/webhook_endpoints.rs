@@ -0,0 +1,157 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use utoipa::OpenApi;
+
+use crate::api::error::ApiError;
+use crate::api::middleware::AuthUser;
+use crate::domain::models::{
+    CreateWebhookEndpoint, UpdateWebhookEndpoint, WebhookDelivery, WebhookDeliveryFilter, WebhookEndpoint,
+};
+use crate::domain::services::WebhookService;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        list_endpoints, create_endpoint, get_endpoint, update_endpoint, delete_endpoint,
+        rotate_secret, list_deliveries,
+    )
+)]
+pub struct ApiDoc;
+
+/// Outbound webhook endpoints and their delivery log, nested under /webhook-endpoints
+pub fn create_router(webhooks: Arc<WebhookService>) -> Router {
+    Router::new()
+        .route("/", get(list_endpoints).post(create_endpoint))
+        .route("/{id}", get(get_endpoint).put(update_endpoint).delete(delete_endpoint))
+        .route("/{id}/rotate-secret", post(rotate_secret))
+        .route("/{id}/deliveries", get(list_deliveries))
+        .with_state(webhooks)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-endpoints",
+    tag = "webhook-endpoints",
+    responses((status = 200, body = Vec<WebhookEndpoint>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_endpoints(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+) -> Result<Json<Vec<WebhookEndpoint>>, ApiError> {
+    let endpoints = webhooks.list_endpoints(auth_user.user_id).await?;
+    Ok(Json(endpoints))
+}
+
+/// The response includes the signing secret, which isn't shown again
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhook-endpoints",
+    tag = "webhook-endpoints",
+    request_body = CreateWebhookEndpoint,
+    responses((status = 201, body = WebhookEndpoint), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn create_endpoint(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Json(payload): Json<CreateWebhookEndpoint>,
+) -> Result<(StatusCode, Json<WebhookEndpoint>), ApiError> {
+    let endpoint = webhooks.create_endpoint(auth_user.user_id, payload).await?;
+    Ok((StatusCode::CREATED, Json(endpoint)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-endpoints/{id}",
+    tag = "webhook-endpoints",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = WebhookEndpoint), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn get_endpoint(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookEndpoint>, ApiError> {
+    let endpoint = webhooks.get_endpoint(auth_user.user_id, id).await?;
+    Ok(Json(endpoint))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/webhook-endpoints/{id}",
+    tag = "webhook-endpoints",
+    params(("id" = Uuid, Path)),
+    request_body = UpdateWebhookEndpoint,
+    responses((status = 200, body = WebhookEndpoint), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn update_endpoint(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWebhookEndpoint>,
+) -> Result<Json<WebhookEndpoint>, ApiError> {
+    let endpoint = webhooks.update_endpoint(auth_user.user_id, id, payload).await?;
+    Ok(Json(endpoint))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhook-endpoints/{id}",
+    tag = "webhook-endpoints",
+    params(("id" = Uuid, Path)),
+    responses((status = 204), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn delete_endpoint(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    webhooks.delete_endpoint(auth_user.user_id, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Returns the endpoint with its new secret
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhook-endpoints/{id}/rotate-secret",
+    tag = "webhook-endpoints",
+    params(("id" = Uuid, Path)),
+    responses((status = 200, body = WebhookEndpoint), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn rotate_secret(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookEndpoint>, ApiError> {
+    let endpoint = webhooks.rotate_secret(auth_user.user_id, id).await?;
+    Ok(Json(endpoint))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhook-endpoints/{id}/deliveries",
+    tag = "webhook-endpoints",
+    params(("id" = Uuid, Path), WebhookDeliveryFilter),
+    responses((status = 200, body = Vec<WebhookDelivery>), ApiError),
+    security(("bearer_auth" = []))
+)]
+async fn list_deliveries(
+    auth_user: AuthUser,
+    State(webhooks): State<Arc<WebhookService>>,
+    Path(id): Path<Uuid>,
+    Query(filter): Query<WebhookDeliveryFilter>,
+) -> Result<Json<Vec<WebhookDelivery>>, ApiError> {
+    let deliveries = webhooks.list_deliveries(auth_user.user_id, id, &filter).await?;
+    Ok(Json(deliveries))
+}
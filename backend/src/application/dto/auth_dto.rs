@@ -1,10 +1,11 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::domain::models::{SubscriptionTier, SubscriptionStatus};
 
 // Input DTOs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RegisterUserCommand {
     pub email: String,
     pub password: String,
@@ -13,29 +14,29 @@ pub struct RegisterUserCommand {
     pub business_type: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct LoginUserCommand {
     pub email: String,
     pub password: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RefreshTokenCommand {
     pub refresh_token: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ForgotPasswordCommand {
     pub email: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ResetPasswordCommand {
     pub token: String,
     pub new_password: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateProfileCommand {
     pub phone: Option<String>,
     pub company_name: Option<String>,
@@ -46,7 +47,7 @@ pub struct UpdateProfileCommand {
 }
 
 // Output DTOs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthenticatedUserDto {
     pub id: Uuid,
     pub email: String,
@@ -54,7 +55,7 @@ pub struct AuthenticatedUserDto {
     pub company_name: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthResultDto {
     pub access_token: String,
     pub refresh_token: String,
@@ -63,7 +64,7 @@ pub struct AuthResultDto {
     pub user: AuthenticatedUserDto,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserDto {
     pub id: Uuid,
     pub email: String,
@@ -82,7 +83,7 @@ pub struct UserDto {
     pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct VerificationResultDto {
     pub success: bool,
     pub message: String,
@@ -1,34 +1,58 @@
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::domain::models::{InvoiceStatus, InvoiceItem};
+use crate::domain::models::{DepositTerms, InvoiceDeposit, InvoiceStatus, InvoiceItem, InvoiceLabel, NotificationDelivery, PageRequest, TaxComponent};
 
 // Input DTOs (from API layer)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateInvoiceCommand {
     pub client_id: Uuid,
     pub issue_date: NaiveDate,
-    pub due_date: NaiveDate,
+    /// Defaults to the issue date plus the client's payment terms
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
     pub items: Vec<CreateInvoiceItemCommand>,
     pub notes: Option<String>,
     pub terms: Option<String>,
-    pub discount_amount: Option<f64>,
+    /// Defaults to the client's default discount percent of the line amounts
+    pub discount_amount: Option<Decimal>,
     pub tax_included: bool,
     pub send_immediately: bool,
+    /// Defaults to the client's default currency, then the user's base currency
+    pub currency: Option<String>,
+    pub exchange_rate: Option<f64>,
     pub allow_partial_payment: Option<bool>,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
+    /// Last day the offer can be accepted or paid
+    #[serde(default)]
+    pub expires_at: Option<NaiveDate>,
+    /// One of the client's active projects
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// Deposit to collect before work begins
+    #[serde(default)]
+    pub deposit: Option<DepositTerms>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateInvoiceItemCommand {
     pub description: String,
-    pub quantity: f64,
-    pub unit_price: f64,
-    pub tax_rate: Option<f64>,
+    pub quantity: Decimal,
+    pub unit_price: Decimal,
+    /// Defaults to the client's default tax
+    pub tax_rate: Option<Decimal>,
+    /// Heading the line is grouped under
+    #[serde(default)]
+    pub section: Option<String>,
+    /// Several taxes on the line, such as GST and PST; replaces `tax_rate` when given
+    #[serde(default)]
+    pub taxes: Vec<TaxComponent>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UpdateInvoiceCommand {
     pub client_id: Option<Uuid>,
     pub issue_date: Option<NaiveDate>,
@@ -36,39 +60,97 @@ pub struct UpdateInvoiceCommand {
     pub items: Option<Vec<CreateInvoiceItemCommand>>,
     pub notes: Option<String>,
     pub terms: Option<String>,
-    pub discount_amount: Option<f64>,
+    pub discount_amount: Option<Decimal>,
     pub tax_included: Option<bool>,
     pub allow_partial_payment: Option<bool>,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
+    /// Last day the offer can be accepted or paid
+    #[serde(default)]
+    pub expires_at: Option<NaiveDate>,
+    /// Replaces the deposit; left out keeps the current one
+    #[serde(default)]
+    pub deposit: Option<DepositTerms>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConsolidateInvoicesCommand {
+    pub invoice_ids: Vec<Uuid>,
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CorrectInvoiceCommand {
+    pub reason: String,
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub items: Option<Vec<CreateInvoiceItemCommand>>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub discount_amount: Option<Decimal>,
+    /// Send the corrected invoice to the client right away (defaults to true)
+    pub send: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RecordPaymentCommand {
-    pub amount: f64,
+    pub amount: Decimal,
     pub payment_method: String,
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SendInvoiceCommand {
     pub email: Option<String>,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    #[serde(default)]
+    pub bcc: Vec<String>,
     pub subject: Option<String>,
     pub message: Option<String>,
+    /// Attach a calendar event (.ics) for the due date
+    #[serde(default)]
+    pub attach_calendar: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct InvoiceListQuery {
     pub status: Option<String>,
     pub client_id: Option<Uuid>,
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
     pub search: Option<String>,
+    pub label_id: Option<Uuid>,
+    /// Comma-separated invoice IDs to fetch in one call
+    pub ids: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// `next_cursor` of the previous page, to page by cursor instead of number
+    pub cursor: Option<String>,
+    /// Older names for `per_page` and the page's start
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
+impl InvoiceListQuery {
+    pub fn page_request(&self) -> Result<PageRequest, String> {
+        PageRequest::from_params(self.page, self.per_page, self.cursor.as_deref(), self.limit, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InvoicePdfQuery {
+    /// Render a fresh copy even if a stored one is current
+    #[serde(default)]
+    pub regenerate: bool,
+}
+
 // Output DTOs (to API layer)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceDto {
     pub id: Uuid,
     pub invoice_number: String,
@@ -80,12 +162,12 @@ pub struct InvoiceDto {
     pub client_address: Option<serde_json::Value>,
     pub issue_date: NaiveDate,
     pub due_date: NaiveDate,
-    pub subtotal: f64,
-    pub tax_amount: f64,
-    pub discount_amount: f64,
-    pub total_amount: f64,
-    pub amount_paid: f64,
-    pub balance_due: f64,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+    pub amount_paid: Decimal,
+    pub balance_due: Decimal,
     pub items: Vec<InvoiceItem>,
     pub notes: Option<String>,
     pub terms: Option<String>,
@@ -93,6 +175,8 @@ pub struct InvoiceDto {
     pub tax_included: bool,
     pub tax_label: Option<String>,
     pub tax_id: Option<String>,
+    pub currency: String,
+    pub exchange_rate: f64,
     pub pdf_url: Option<String>,
     pub receipt_image_url: Option<String>,
     pub sent_at: Option<DateTime<Utc>>,
@@ -104,13 +188,30 @@ pub struct InvoiceDto {
     pub whatsapp_sent_at: Option<DateTime<Utc>>,
     pub guest_payment_token: Option<String>,
     pub allow_partial_payment: bool,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
     pub partial_payment_count: i32,
+    pub deposit: Option<InvoiceDeposit>,
+    pub reverse_charge: bool,
+    pub vat_country: Option<String>,
+    pub client_vat_number: Option<String>,
+    pub consolidated_into_id: Option<Uuid>,
+    pub supersedes_id: Option<Uuid>,
+    pub superseded_by_id: Option<Uuid>,
+    pub correction_reason: Option<String>,
+    pub expires_at: Option<NaiveDate>,
+    pub expired_at: Option<DateTime<Utc>>,
+    pub project_id: Option<Uuid>,
+    /// Pipeline label, while it applies to the current status
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<InvoiceLabel>,
+    /// Latest send attempt per channel; a failed one can be resent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub delivery: Vec<NotificationDelivery>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceSummaryDto {
     pub id: Uuid,
     pub invoice_number: String,
@@ -119,31 +220,32 @@ pub struct InvoiceSummaryDto {
     pub client_email: Option<String>,
     pub issue_date: NaiveDate,
     pub due_date: NaiveDate,
-    pub total_amount: f64,
-    pub balance_due: f64,
+    pub total_amount: Decimal,
+    pub balance_due: Decimal,
     pub days_until_due: i32,
     pub is_overdue: bool,
+    pub label: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceCreatedDto {
     pub id: Uuid,
     pub invoice_number: String,
     pub status: InvoiceStatus,
-    pub subtotal: f64,
-    pub tax_amount: f64,
-    pub total_amount: f64,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub total_amount: Decimal,
     pub tax_label: Option<String>,
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaymentRecordedDto {
     pub invoice_id: Uuid,
     pub invoice_number: String,
-    pub amount_paid: f64,
-    pub new_balance: f64,
+    pub amount_paid: Decimal,
+    pub new_balance: Decimal,
     pub status: InvoiceStatus,
     pub message: String,
 }
@@ -176,7 +278,7 @@ pub struct InvoiceReadStatusDto {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GuestInvoiceInfoDto {
     pub invoice_number: String,
-    pub total_amount: f64,
+    pub total_amount: Decimal,
     pub due_date: NaiveDate,
     pub client_name: String,
     pub client_email: Option<String>,
@@ -198,12 +300,12 @@ pub struct InvoiceNotificationDto {
 }
 
 // Discussion DTOs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AddDiscussionMessageCommand {
     pub message: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DiscussionMessageDto {
     pub id: Uuid,
     pub invoice_id: Uuid,
@@ -212,7 +314,7 @@ pub struct DiscussionMessageDto {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DiscussionResponseDto {
     pub messages: Vec<DiscussionMessageDto>,
 }
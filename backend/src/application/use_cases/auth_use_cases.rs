@@ -51,13 +51,13 @@ impl LoginUserUseCase {
         Self { auth_service }
     }
 
-    pub async fn execute(&self, command: LoginUserCommand) -> Result<AuthResultDto, AuthError> {
+    pub async fn execute(&self, command: LoginUserCommand, ip_address: Option<String>) -> Result<AuthResultDto, AuthError> {
         let request = LoginRequest {
             email: command.email,
             password: command.password,
         };
 
-        let response = self.auth_service.login(request).await?;
+        let response = self.auth_service.login(request, ip_address.as_deref()).await?;
 
         Ok(AuthResultDto {
             access_token: response.access_token,
@@ -221,6 +221,7 @@ impl UpdateProfileUseCase {
             tax_settings,
             notification_settings,
             invoice_settings: None,
+            locale: None,
         };
 
         let user = self.auth_service.update_user(user_id, update).await?;
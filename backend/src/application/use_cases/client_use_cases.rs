@@ -2,13 +2,23 @@ use std::sync::Arc;
 use uuid::Uuid;
 use thiserror::Error;
 
-use crate::domain::services::ClientService;
-use crate::domain::models::{Client, ClientResponse, ClientStats, CreateClient, UpdateClient};
+use crate::domain::services::{ClientService, ClientStatementError, ClientStatementService};
+use crate::domain::models::{
+    normalize_billing_contacts, normalize_invoice_defaults, normalize_optional_phone, normalize_vat_number,
+    validate_hourly_rate,
+    AccountStatement, BatchResult, Client, ClientHierarchyStatement, ClientInvoiceDefaults, ClientResponse, ClientStats,
+    CreateClient, Page, PageRequest, StatementFormat, UpdateClient,
+};
+use chrono::NaiveDate;
 
 #[derive(Debug, Error)]
 pub enum ClientError {
     #[error("Client not found")]
     NotFound,
+    #[error("Validation error: {0}")]
+    Validation(String),
+    #[error("Client has {0} active invoice(s); archive it instead, or delete once they are paid or cancelled")]
+    HasActiveInvoices(i64),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -33,7 +43,27 @@ impl CreateClientUseCase {
         Self { client_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, create: CreateClient) -> Result<Client, ClientError> {
+    pub async fn execute(&self, user_id: Uuid, mut create: CreateClient) -> Result<Client, ClientError> {
+        // Client numbers are used for WhatsApp/SMS sends, so they must be valid E.164
+        create.phone = normalize_optional_phone(create.phone).map_err(ClientError::Validation)?;
+        if let Some(contacts) = create.billing_contacts.take() {
+            create.billing_contacts = Some(
+                normalize_billing_contacts(contacts, create.email.as_deref()).map_err(ClientError::Validation)?,
+            );
+        }
+        (create.default_currency, create.default_discount_percent) =
+            normalize_invoice_defaults(create.default_currency, create.default_discount_percent)
+                .map_err(ClientError::Validation)?;
+        ensure_tax_setting(&self.client_service, user_id, create.default_tax_id).await?;
+        validate_hourly_rate(create.hourly_rate).map_err(ClientError::Validation)?;
+        create.vat_number = normalize_vat_number(create.vat_number).map_err(ClientError::Validation)?;
+
+        if let Some(parent_id) = create.parent_client_id {
+            if self.client_service.get_client(user_id, parent_id).await?.is_none() {
+                return Err(ClientError::Validation("Parent client not found".to_string()));
+            }
+        }
+
         Ok(self.client_service.create_client(user_id, create).await?)
     }
 }
@@ -51,7 +81,28 @@ impl GetClientUseCase {
 
     pub async fn execute(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, ClientError> {
         let client = self.client_service.get_client(user_id, client_id).await?;
-        client.ok_or(ClientError::NotFound)
+        client.filter(|c| c.deleted_at.is_none()).ok_or(ClientError::NotFound)
+    }
+
+    /// What a new invoice for the client starts with, to pre-fill the invoice form
+    pub async fn invoice_defaults(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        issue_date: NaiveDate,
+    ) -> Result<ClientInvoiceDefaults, ClientError> {
+        let client = self.execute(user_id, client_id).await?;
+        Ok(self.client_service.invoice_defaults(user_id, &client, issue_date).await?)
+    }
+}
+
+/// A client's default tax must be one of the user's active tax settings
+async fn ensure_tax_setting(client_service: &ClientService, user_id: Uuid, tax_id: Option<Uuid>) -> Result<(), ClientError> {
+    match tax_id {
+        Some(tax_id) if !client_service.has_tax_setting(user_id, tax_id).await => {
+            Err(ClientError::Validation("Default tax setting not found".to_string()))
+        }
+        _ => Ok(()),
     }
 }
 
@@ -70,10 +121,17 @@ impl ListClientsUseCase {
         &self,
         user_id: Uuid,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ClientResponse>, ClientError> {
-        Ok(self.client_service.list_clients(user_id, search, limit, offset).await?)
+        parent_client_id: Option<Uuid>,
+        include_archived: bool,
+        page: &PageRequest,
+    ) -> Result<Page<ClientResponse>, ClientError> {
+        Ok(self.client_service.list_clients(user_id, search, parent_client_id, include_archived, page).await?)
+    }
+
+    /// Fetch specific clients in one call, reporting IDs that weren't found
+    pub async fn execute_batch(&self, user_id: Uuid, ids: Vec<Uuid>) -> Result<BatchResult<ClientResponse>, ClientError> {
+        let clients = self.client_service.get_clients_by_ids(user_id, &ids).await?;
+        Ok(BatchResult::new(&ids, clients, |client| client.id))
     }
 }
 
@@ -92,10 +150,38 @@ impl UpdateClientUseCase {
         &self,
         user_id: Uuid,
         client_id: Uuid,
-        update: UpdateClient,
+        mut update: UpdateClient,
     ) -> Result<Client, ClientError> {
+        update.phone = normalize_optional_phone(update.phone).map_err(ClientError::Validation)?;
+        if let Some(contacts) = update.billing_contacts.take() {
+            // Compare against the primary address the client will have after this update
+            let primary = match update.email.clone() {
+                Some(email) => Some(email),
+                None => self.client_service.get_client(user_id, client_id).await?.and_then(|c| c.email),
+            };
+            update.billing_contacts = Some(
+                normalize_billing_contacts(contacts, primary.as_deref()).map_err(ClientError::Validation)?,
+            );
+        }
+        (update.default_currency, update.default_discount_percent) =
+            normalize_invoice_defaults(update.default_currency, update.default_discount_percent)
+                .map_err(ClientError::Validation)?;
+        ensure_tax_setting(&self.client_service, user_id, update.default_tax_id).await?;
+        validate_hourly_rate(update.hourly_rate).map_err(ClientError::Validation)?;
+        update.vat_number = normalize_vat_number(update.vat_number).map_err(ClientError::Validation)?;
+
         Ok(self.client_service.update_client(user_id, client_id, update).await?)
     }
+
+    /// Checks the client's VAT number against VIES again, e.g. after VIES was unavailable
+    pub async fn check_vat_number(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, ClientError> {
+        let client = self.client_service.get_client(user_id, client_id).await?
+            .filter(|c| c.deleted_at.is_none())
+            .ok_or(ClientError::NotFound)?;
+        let vat_number = client.vat_number
+            .ok_or_else(|| ClientError::Validation("Client has no VAT number".to_string()))?;
+        Ok(self.client_service.set_vat_number(user_id, client_id, Some(&vat_number)).await?)
+    }
 }
 
 // DeleteClientUseCase
@@ -109,11 +195,156 @@ impl DeleteClientUseCase {
         Self { client_service }
     }
 
+    /// Clients with outstanding invoices or subsidiaries can't be deleted; otherwise the
+    /// client moves to the trash and can be brought back with [`RestoreClientUseCase`]
     pub async fn execute(&self, user_id: Uuid, client_id: Uuid) -> Result<(), ClientError> {
+        let client = self.client_service.get_client(user_id, client_id).await?;
+        if client.is_none_or(|c| c.deleted_at.is_some()) {
+            return Err(ClientError::NotFound);
+        }
+
+        let active = self.client_service.count_active_invoices(user_id, client_id).await?;
+        if active > 0 {
+            return Err(ClientError::HasActiveInvoices(active));
+        }
+
+        if self.client_service.count_subsidiaries(user_id, client_id).await? > 0 {
+            return Err(ClientError::Validation(
+                "Client has subsidiaries; reassign or delete them first".to_string(),
+            ));
+        }
+
         Ok(self.client_service.delete_client(user_id, client_id).await?)
     }
 }
 
+// ArchiveClientUseCase
+#[derive(Clone)]
+pub struct ArchiveClientUseCase {
+    client_service: Arc<ClientService>,
+}
+
+impl ArchiveClientUseCase {
+    pub fn new(client_service: Arc<ClientService>) -> Self {
+        Self { client_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, client_id: Uuid, archived: bool) -> Result<Client, ClientError> {
+        Ok(self.client_service.set_archived(user_id, client_id, archived).await?)
+    }
+}
+
+// RestoreClientUseCase
+#[derive(Clone)]
+pub struct RestoreClientUseCase {
+    client_service: Arc<ClientService>,
+}
+
+impl RestoreClientUseCase {
+    pub fn new(client_service: Arc<ClientService>) -> Self {
+        Self { client_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, ClientError> {
+        Ok(self.client_service.restore_client(user_id, client_id).await?)
+    }
+}
+
+// ListDeletedClientsUseCase
+#[derive(Clone)]
+pub struct ListDeletedClientsUseCase {
+    client_service: Arc<ClientService>,
+}
+
+impl ListDeletedClientsUseCase {
+    pub fn new(client_service: Arc<ClientService>) -> Self {
+        Self { client_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid) -> Result<Vec<Client>, ClientError> {
+        Ok(self.client_service.list_deleted_clients(user_id).await?)
+    }
+}
+
+// SetClientParentUseCase
+#[derive(Clone)]
+pub struct SetClientParentUseCase {
+    client_service: Arc<ClientService>,
+}
+
+impl SetClientParentUseCase {
+    pub fn new(client_service: Arc<ClientService>) -> Self {
+        Self { client_service }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        parent_client_id: Option<Uuid>,
+    ) -> Result<Client, ClientError> {
+        if let Some(parent_id) = parent_client_id {
+            if self.client_service.get_client(user_id, parent_id).await?.is_none() {
+                return Err(ClientError::Validation("Parent client not found".to_string()));
+            }
+
+            // The new parent must not be the client itself or one of its subsidiaries
+            let hierarchy = self.client_service.get_hierarchy_ids(user_id, client_id).await?;
+            if hierarchy.contains(&parent_id) {
+                return Err(ClientError::Validation(
+                    "A client cannot be its own parent or a parent of its ancestors".to_string(),
+                ));
+            }
+        }
+
+        Ok(self.client_service.set_parent(user_id, client_id, parent_client_id).await?)
+    }
+}
+
+// GetClientStatementUseCase
+#[derive(Clone)]
+pub struct GetClientStatementUseCase {
+    client_service: Arc<ClientService>,
+    client_statements: Arc<ClientStatementService>,
+}
+
+impl GetClientStatementUseCase {
+    pub fn new(client_service: Arc<ClientService>, client_statements: Arc<ClientStatementService>) -> Self {
+        Self { client_service, client_statements }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, client_id: Uuid) -> Result<ClientHierarchyStatement, ClientError> {
+        let statement = self.client_service.get_hierarchy_statement(user_id, client_id).await?;
+        statement.ok_or(ClientError::NotFound)
+    }
+
+    /// Statement of account for the period
+    pub async fn execute_for_period(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<AccountStatement, ClientStatementError> {
+        self.client_statements.statement(user_id, client_id, start_date, end_date).await
+    }
+
+    /// Statement of account as a PDF or CSV file
+    pub async fn export(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        format: StatementFormat,
+    ) -> Result<Vec<u8>, ClientStatementError> {
+        match format {
+            StatementFormat::Csv => self.client_statements.csv(user_id, client_id, start_date, end_date).await,
+            _ => self.client_statements.pdf(user_id, client_id, start_date, end_date).await,
+        }
+    }
+}
+
 // GetClientInvoicesUseCase
 #[derive(Clone)]
 pub struct GetClientInvoicesUseCase {
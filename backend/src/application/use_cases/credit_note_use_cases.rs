@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::models::{CreateCreditNote, CreditNote, CreditNoteListFilter};
+use crate::domain::services::{CreditNoteError, CreditNoteService};
+
+// CreateCreditNoteUseCase
+#[derive(Clone)]
+pub struct CreateCreditNoteUseCase {
+    credit_note_service: Arc<CreditNoteService>,
+}
+
+impl CreateCreditNoteUseCase {
+    pub fn new(credit_note_service: Arc<CreditNoteService>) -> Self {
+        Self { credit_note_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, create: CreateCreditNote) -> Result<CreditNote, CreditNoteError> {
+        self.credit_note_service.create(user_id, create).await
+    }
+}
+
+// GetCreditNoteUseCase
+#[derive(Clone)]
+pub struct GetCreditNoteUseCase {
+    credit_note_service: Arc<CreditNoteService>,
+}
+
+impl GetCreditNoteUseCase {
+    pub fn new(credit_note_service: Arc<CreditNoteService>) -> Self {
+        Self { credit_note_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<CreditNote, CreditNoteError> {
+        self.credit_note_service.get(user_id, credit_note_id).await
+    }
+}
+
+// ListCreditNotesUseCase
+#[derive(Clone)]
+pub struct ListCreditNotesUseCase {
+    credit_note_service: Arc<CreditNoteService>,
+}
+
+impl ListCreditNotesUseCase {
+    pub fn new(credit_note_service: Arc<CreditNoteService>) -> Self {
+        Self { credit_note_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, filter: CreditNoteListFilter) -> Result<Vec<CreditNote>, CreditNoteError> {
+        self.credit_note_service.list(user_id, filter).await
+    }
+}
+
+// VoidCreditNoteUseCase
+#[derive(Clone)]
+pub struct VoidCreditNoteUseCase {
+    credit_note_service: Arc<CreditNoteService>,
+}
+
+impl VoidCreditNoteUseCase {
+    pub fn new(credit_note_service: Arc<CreditNoteService>) -> Self {
+        Self { credit_note_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<CreditNote, CreditNoteError> {
+        self.credit_note_service.void(user_id, credit_note_id).await
+    }
+}
+
+// GetCreditNotePdfUseCase
+#[derive(Clone)]
+pub struct GetCreditNotePdfUseCase {
+    credit_note_service: Arc<CreditNoteService>,
+}
+
+impl GetCreditNotePdfUseCase {
+    pub fn new(credit_note_service: Arc<CreditNoteService>) -> Self {
+        Self { credit_note_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<Vec<u8>, CreditNoteError> {
+        self.credit_note_service.pdf(user_id, credit_note_id).await
+    }
+}
@@ -4,12 +4,14 @@ use chrono::NaiveDate;
 use thiserror::Error;
 
 use crate::domain::services::ExpenseService;
-use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, CreateExpense, UpdateExpense, ExpenseCategory};
+use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, Page, PageRequest, CreateExpense, UpdateExpense, ExpenseCategory, resolve_input_tax};
 
 #[derive(Debug, Error)]
 pub enum ExpenseError {
     #[error("Expense not found")]
     NotFound,
+    #[error("Validation error: {0}")]
+    Validation(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
@@ -34,7 +36,10 @@ impl CreateExpenseUseCase {
         Self { expense_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, create: CreateExpense) -> Result<Expense, ExpenseError> {
+    pub async fn execute(&self, user_id: Uuid, mut create: CreateExpense) -> Result<Expense, ExpenseError> {
+        let tax_amount = resolve_input_tax(create.amount, create.tax_rate, create.tax_amount, self.expense_service.rounding())
+            .map_err(ExpenseError::Validation)?;
+        create.tax_amount = Some(tax_amount);
         Ok(self.expense_service.create_expense(user_id, create).await?)
     }
 }
@@ -75,11 +80,10 @@ impl ListExpensesUseCase {
         date_to: Option<NaiveDate>,
         tax_deductible: Option<bool>,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ExpenseResponse>, ExpenseError> {
+        page: &PageRequest,
+    ) -> Result<Page<ExpenseResponse>, ExpenseError> {
         Ok(self.expense_service.list_expenses(
-            user_id, category, date_from, date_to, tax_deductible, search, limit, offset
+            user_id, category, date_from, date_to, tax_deductible, search, page
         ).await?)
     }
 }
@@ -99,8 +103,20 @@ impl UpdateExpenseUseCase {
         &self,
         user_id: Uuid,
         expense_id: Uuid,
-        update: UpdateExpense,
+        mut update: UpdateExpense,
     ) -> Result<Expense, ExpenseError> {
+        if update.amount.is_some() || update.tax_rate.is_some() || update.tax_amount.is_some() {
+            let existing = self.expense_service.get_expense(user_id, expense_id).await?
+                .ok_or(ExpenseError::NotFound)?;
+            let amount = update.amount.unwrap_or(existing.amount);
+            let tax_rate = update.tax_rate.or(existing.tax_rate);
+            // With a rate on file the tax follows the amount; a manually entered tax is kept
+            let tax_amount = update.tax_amount
+                .or(if tax_rate.is_some() { None } else { Some(existing.tax_amount) });
+            let tax_amount = resolve_input_tax(amount, tax_rate, tax_amount, self.expense_service.rounding())
+                .map_err(ExpenseError::Validation)?;
+            update.tax_amount = Some(tax_amount);
+        }
         Ok(self.expense_service.update_expense(user_id, expense_id, update).await?)
     }
 }
@@ -2,8 +2,8 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::application::dto::invoice_dto::*;
-use crate::domain::models::{CreateInvoice, UpdateInvoice, CreatePayment, InvoiceListFilter, SenderType};
-use crate::domain::services::{InvoiceService, InvoiceError};
+use crate::domain::models::{CreateInvoice, InvoiceFromTime, UpdateInvoice, ConsolidateInvoices, CorrectInvoice, CreatePayment, InvoiceListFilter, InvoiceStatus, InvoiceResponse, BatchResult, Page, PageRequest, SenderType, InvoiceNotification, InvoiceSendOptions, DiscussionUnread, UnreadDiscussion, DuplicateInvoice, InvoiceFromTemplate, InvoiceBatch, InvoiceBatchAction, BatchActionReport, BatchItemResult, PaymentMethod, normalize_batch_ids, invoice_file_name, DepositTerms, LineTax};
+use crate::domain::services::{ClientService, InvoiceService, InvoiceError, ProjectService, TimeEntryService, TimeEntryError, InvoicePdf, FxRateService, GuestTokenService, GuestTokenError, GuestLink, InvoiceTemplateService, InvoiceTemplateError, ZipArchive};
 
 /// Use case: Create a new invoice
 ///
@@ -13,34 +13,69 @@ use crate::domain::services::{InvoiceService, InvoiceError};
 /// 3. Returns DTO to API layer
 pub struct CreateInvoiceUseCase {
     invoice_service: Arc<InvoiceService>,
+    client_service: Arc<ClientService>,
+    fx_rates: Arc<FxRateService>,
+    projects: Arc<ProjectService>,
 }
 
 impl CreateInvoiceUseCase {
-    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
-        Self { invoice_service }
+    pub fn new(
+        invoice_service: Arc<InvoiceService>,
+        client_service: Arc<ClientService>,
+        fx_rates: Arc<FxRateService>,
+        projects: Arc<ProjectService>,
+    ) -> Self {
+        Self { invoice_service, client_service, fx_rates, projects }
     }
 
-    pub async fn execute(&self, user_id: Uuid, command: CreateInvoiceCommand) -> Result<InvoiceCreatedDto, InvoiceError> {
+    pub async fn execute(&self, user_id: Uuid, mut command: CreateInvoiceCommand) -> Result<InvoiceCreatedDto, InvoiceError> {
+        // Fields left out take the client's defaults
+        let client = self.client_service.get_client(user_id, command.client_id).await?
+            .ok_or(InvoiceError::ClientNotFound)?;
+        let defaults = self.client_service.invoice_defaults(user_id, &client, command.issue_date).await?;
+        command.currency = command.currency.or_else(|| Some(defaults.currency.clone()));
+        if let Some(project_id) = command.project_id {
+            self.projects.taggable_project(user_id, project_id, Some(client.id)).await?;
+        }
+
+        // Foreign-currency invoices without an explicit rate take the rate on the issue date
+        let exchange_rate = match (&command.currency, command.exchange_rate) {
+            (Some(currency), None) => self.fx_rates.rate_if_available(user_id, currency, Some(command.issue_date)).await,
+            _ => command.exchange_rate,
+        };
+
+        let items: Vec<_> = command.items.into_iter().map(|item| crate::domain::models::CreateInvoiceItem {
+            description: item.description,
+            quantity: item.quantity,
+            unit_price: item.unit_price,
+            tax_rate: item.tax_rate.or(Some(defaults.tax_rate)),
+            section: item.section,
+            taxes: item.taxes,
+        }).collect();
+        let discount_amount = command.discount_amount.or_else(|| defaults.discount_for(&items));
+
         // Convert command to domain model
         let create_invoice = CreateInvoice {
             client_id: command.client_id,
             issue_date: command.issue_date,
-            due_date: command.due_date,
-            items: command.items.into_iter().map(|item| crate::domain::models::CreateInvoiceItem {
-                description: item.description,
-                quantity: item.quantity,
-                unit_price: item.unit_price,
-                tax_rate: item.tax_rate,
-            }).collect(),
+            due_date: command.due_date.unwrap_or(defaults.due_date),
+            items,
             notes: command.notes,
             terms: command.terms,
-            discount_amount: command.discount_amount,
+            discount_amount,
             tax_included: command.tax_included,
             send_immediately: command.send_immediately,
-            tax_label: None,  // Will be set by service based on default tax
-            tax_id: None,    // Will be set by service based on default tax
+            tax_label: defaults.tax_label,
+            tax_id: defaults.tax_setting_id.map(|id| id.to_string()),
+            currency: command.currency,
+            exchange_rate,
             allow_partial_payment: command.allow_partial_payment,
             min_payment_amount: command.min_payment_amount,
+            expires_at: command.expires_at,
+            project_id: command.project_id,
+            deposit: command.deposit,
+            reverse_charge: defaults.reverse_charge,
+            vat_country: defaults.vat_country,
         };
 
         // Execute business logic via service
@@ -65,6 +100,214 @@ impl CreateInvoiceUseCase {
     }
 }
 
+/// Use case: Bill a client's tracked time. Each entry becomes a line of its hours
+/// at its rate; the rest of the draft comes from the client's defaults.
+pub struct CreateInvoiceFromTimeUseCase {
+    create_invoice: Arc<CreateInvoiceUseCase>,
+    invoice_service: Arc<InvoiceService>,
+    time_entries: Arc<TimeEntryService>,
+}
+
+impl CreateInvoiceFromTimeUseCase {
+    pub fn new(
+        create_invoice: Arc<CreateInvoiceUseCase>,
+        invoice_service: Arc<InvoiceService>,
+        time_entries: Arc<TimeEntryService>,
+    ) -> Self {
+        Self { create_invoice, invoice_service, time_entries }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, request: InvoiceFromTime) -> Result<InvoiceCreatedDto, TimeEntryError> {
+        let entries = self.time_entries.billable_entries(user_id, &request).await?;
+        let entry_ids: Vec<Uuid> = entries.iter().map(|billable| billable.entry.id).collect();
+        // The project every entry is on, if they share one
+        let project_id = entries[0].entry.project_id.filter(|project_id| {
+            entries.iter().all(|billable| billable.entry.project_id == Some(*project_id))
+        });
+
+        let items = entries
+            .iter()
+            .map(|billable| CreateInvoiceItemCommand {
+                description: format!(
+                    "{} ({})",
+                    billable.entry.description,
+                    billable.entry.started_at.date_naive().format("%Y-%m-%d")
+                ),
+                quantity: billable.entry.billable_hours(),
+                unit_price: billable.hourly_rate,
+                tax_rate: None,
+                section: None,
+                taxes: Vec::new(),
+            })
+            .collect();
+
+        let command = CreateInvoiceCommand {
+            client_id: request.client_id,
+            issue_date: request.issue_date.unwrap_or_else(|| self.time_entries.today()),
+            due_date: request.due_date,
+            items,
+            notes: request.notes,
+            terms: request.terms,
+            discount_amount: None,
+            tax_included: false,
+            send_immediately: false,
+            currency: None,
+            exchange_rate: None,
+            allow_partial_payment: None,
+            min_payment_amount: None,
+            expires_at: None,
+            project_id: request.project_id.or(project_id),
+            deposit: None,
+        };
+        let mut created = self.create_invoice.execute(user_id, command).await?;
+
+        if let Err(err) = self.time_entries.mark_billed(user_id, &entry_ids, created.id).await {
+            // Some of the time was billed by another request meanwhile
+            self.invoice_service.discard_draft(user_id, created.id).await?;
+            return Err(err);
+        }
+
+        created.message = format!("Invoice created as draft from {} time entries", entry_ids.len());
+        Ok(created)
+    }
+}
+
+/// Use case: Copy an invoice's client, lines, notes and terms into a new draft
+/// with its own number and fresh dates
+pub struct DuplicateInvoiceUseCase {
+    create_invoice: Arc<CreateInvoiceUseCase>,
+    invoice_service: Arc<InvoiceService>,
+    projects: Arc<ProjectService>,
+}
+
+impl DuplicateInvoiceUseCase {
+    pub fn new(
+        create_invoice: Arc<CreateInvoiceUseCase>,
+        invoice_service: Arc<InvoiceService>,
+        projects: Arc<ProjectService>,
+    ) -> Self {
+        Self { create_invoice, invoice_service, projects }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid, request: DuplicateInvoice) -> Result<InvoiceCreatedDto, InvoiceError> {
+        let original = self.invoice_service.get_invoice(user_id, invoice_id).await?;
+
+        // Same payment terms and offer validity as the original, from the new issue date
+        let issue_date = request.issue_date.unwrap_or_else(|| self.invoice_service.today());
+        let due_date = request.due_date.unwrap_or(issue_date + (original.due_date - original.issue_date));
+        let expires_at = original.expires_at.map(|expires_at| issue_date + (expires_at - original.issue_date));
+
+        // A project that has since been completed or archived isn't carried over
+        let project_id = match original.project_id {
+            Some(project_id) if self.projects.taggable_project(user_id, project_id, Some(original.client_id)).await.is_ok() => {
+                Some(project_id)
+            }
+            _ => None,
+        };
+
+        let items = original
+            .items
+            .into_iter()
+            .map(|item| CreateInvoiceItemCommand {
+                description: item.description,
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                tax_rate: Some(item.tax_rate),
+                taxes: item.taxes.iter().map(LineTax::component).collect(),
+                section: item.section,
+            })
+            .collect();
+
+        let command = CreateInvoiceCommand {
+            client_id: original.client_id,
+            issue_date,
+            due_date: Some(due_date),
+            items,
+            notes: original.notes,
+            terms: original.terms,
+            discount_amount: Some(original.discount_amount),
+            tax_included: original.tax_included,
+            send_immediately: false,
+            currency: Some(original.currency),
+            // Foreign-currency copies take the rate on the new issue date
+            exchange_rate: None,
+            allow_partial_payment: Some(original.allow_partial_payment),
+            min_payment_amount: original.min_payment_amount,
+            expires_at,
+            project_id,
+            // The copy asks for the same deposit, due on its own issue date
+            deposit: original.deposit.map(|deposit| DepositTerms { due_date: None, ..deposit.terms() }),
+        };
+        let mut created = self.create_invoice.execute(user_id, command).await?;
+
+        created.message = format!("Duplicated {} as a new draft", original.invoice_number);
+        Ok(created)
+    }
+}
+
+/// Use case: Create a draft for a client from a saved template. What the template
+/// leaves out comes from the client's defaults.
+pub struct CreateInvoiceFromTemplateUseCase {
+    create_invoice: Arc<CreateInvoiceUseCase>,
+    templates: Arc<InvoiceTemplateService>,
+}
+
+impl CreateInvoiceFromTemplateUseCase {
+    pub fn new(create_invoice: Arc<CreateInvoiceUseCase>, templates: Arc<InvoiceTemplateService>) -> Self {
+        Self { create_invoice, templates }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        template_id: Uuid,
+        request: InvoiceFromTemplate,
+    ) -> Result<InvoiceCreatedDto, InvoiceTemplateError> {
+        let template = self.templates.get_template(user_id, template_id).await?;
+
+        let issue_date = request.issue_date.unwrap_or_else(|| self.templates.today());
+        let due_date = request
+            .due_date
+            .or_else(|| template.due_days.map(|days| issue_date + chrono::Duration::days(i64::from(days))));
+
+        let items = template
+            .items
+            .into_iter()
+            .map(|item| CreateInvoiceItemCommand {
+                description: item.description,
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                tax_rate: item.tax_rate,
+                section: item.section,
+                taxes: item.taxes,
+            })
+            .collect();
+
+        let command = CreateInvoiceCommand {
+            client_id: request.client_id,
+            issue_date,
+            due_date,
+            items,
+            notes: template.notes,
+            terms: template.terms,
+            discount_amount: template.discount_amount,
+            tax_included: template.tax_included,
+            send_immediately: false,
+            currency: template.currency,
+            exchange_rate: None,
+            allow_partial_payment: None,
+            min_payment_amount: None,
+            expires_at: None,
+            project_id: request.project_id,
+            deposit: None,
+        };
+        let mut created = self.create_invoice.execute(user_id, command).await?;
+
+        created.message = format!("Invoice created as draft from template {}", template.name);
+        Ok(created)
+    }
+}
+
 /// Use case: Get invoice details
 pub struct GetInvoiceUseCase {
     invoice_service: Arc<InvoiceService>,
@@ -77,6 +320,8 @@ impl GetInvoiceUseCase {
 
     pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<InvoiceDto, InvoiceError> {
         let invoice = self.invoice_service.get_invoice(user_id, invoice_id).await?;
+        let delivery = self.invoice_service.delivery_status(user_id, invoice_id).await?;
+        let label = self.invoice_service.active_label(user_id, invoice_id).await?;
 
         Ok(InvoiceDto {
             id: invoice.id,
@@ -102,6 +347,8 @@ impl GetInvoiceUseCase {
             tax_included: invoice.tax_included,
             tax_label: invoice.tax_label,
             tax_id: invoice.tax_id,
+            currency: invoice.currency,
+            exchange_rate: invoice.exchange_rate,
             pdf_url: invoice.pdf_url,
             receipt_image_url: invoice.receipt_image_url,
             sent_at: invoice.sent_at,
@@ -115,6 +362,19 @@ impl GetInvoiceUseCase {
             allow_partial_payment: invoice.allow_partial_payment,
             min_payment_amount: invoice.min_payment_amount,
             partial_payment_count: invoice.partial_payment_count,
+            deposit: invoice.deposit,
+            reverse_charge: invoice.reverse_charge,
+            vat_country: invoice.vat_country,
+            client_vat_number: invoice.client_vat_number,
+            consolidated_into_id: invoice.consolidated_into_id,
+            supersedes_id: invoice.supersedes_id,
+            superseded_by_id: invoice.superseded_by_id,
+            correction_reason: invoice.correction_reason,
+            expires_at: invoice.expires_at,
+            expired_at: invoice.expired_at,
+            project_id: invoice.project_id,
+            label,
+            delivery,
             created_at: invoice.created_at,
             updated_at: invoice.updated_at,
         })
@@ -131,29 +391,42 @@ impl ListInvoicesUseCase {
         Self { invoice_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, query: InvoiceListQuery) -> Result<Vec<InvoiceSummaryDto>, InvoiceError> {
+    pub async fn execute(&self, user_id: Uuid, query: InvoiceListQuery, page: &PageRequest) -> Result<Page<InvoiceSummaryDto>, InvoiceError> {
         let filter = InvoiceListFilter {
-            status: query.status.and_then(|s| match s.as_str() {
-                "draft" => Some(crate::domain::models::InvoiceStatus::Draft),
-                "sent" => Some(crate::domain::models::InvoiceStatus::Sent),
-                "viewed" => Some(crate::domain::models::InvoiceStatus::Viewed),
-                "partial" => Some(crate::domain::models::InvoiceStatus::Partial),
-                "paid" => Some(crate::domain::models::InvoiceStatus::Paid),
-                "overdue" => Some(crate::domain::models::InvoiceStatus::Overdue),
-                "cancelled" => Some(crate::domain::models::InvoiceStatus::Cancelled),
-                _ => None,
-            }),
+            status: query.status.as_deref().and_then(InvoiceStatus::parse),
             client_id: query.client_id,
             date_from: query.date_from,
             date_to: query.date_to,
             search: query.search,
-            limit: query.limit,
-            offset: query.offset,
+            label_id: query.label_id,
+            ids: None,
+        };
+
+        let invoices = self.invoice_service.list_invoices_page(user_id, filter, page).await?;
+
+        Ok(invoices.map(Self::to_summary))
+    }
+
+    /// Fetch specific invoices in one call, reporting IDs that weren't found
+    pub async fn execute_batch(&self, user_id: Uuid, ids: Vec<Uuid>) -> Result<BatchResult<InvoiceSummaryDto>, InvoiceError> {
+        let filter = InvoiceListFilter {
+            status: None,
+            client_id: None,
+            date_from: None,
+            date_to: None,
+            search: None,
+            label_id: None,
+            ids: Some(ids.clone()),
         };
 
         let invoices = self.invoice_service.list_invoices(user_id, filter).await?;
+        let found = invoices.into_iter().map(Self::to_summary).collect();
+
+        Ok(BatchResult::new(&ids, found, |invoice| invoice.id))
+    }
 
-        Ok(invoices.into_iter().map(|inv| InvoiceSummaryDto {
+    fn to_summary(inv: InvoiceResponse) -> InvoiceSummaryDto {
+        InvoiceSummaryDto {
             id: inv.id,
             invoice_number: inv.invoice_number,
             status: inv.status,
@@ -165,8 +438,9 @@ impl ListInvoicesUseCase {
             balance_due: inv.balance_due,
             days_until_due: inv.days_until_due,
             is_overdue: inv.is_overdue,
+            label: inv.label,
             created_at: inv.created_at,
-        }).collect())
+        }
     }
 }
 
@@ -190,6 +464,8 @@ impl UpdateInvoiceUseCase {
                 quantity: item.quantity,
                 unit_price: item.unit_price,
                 tax_rate: item.tax_rate,
+                section: item.section,
+                taxes: item.taxes,
             }).collect()),
             notes: command.notes,
             terms: command.terms,
@@ -197,9 +473,13 @@ impl UpdateInvoiceUseCase {
             tax_included: command.tax_included,
             allow_partial_payment: command.allow_partial_payment,
             min_payment_amount: command.min_payment_amount,
+            expires_at: command.expires_at,
+            deposit: command.deposit,
         };
 
         let invoice = self.invoice_service.update_invoice(user_id, invoice_id, update).await?;
+        let delivery = self.invoice_service.delivery_status(user_id, invoice_id).await?;
+        let label = self.invoice_service.active_label(user_id, invoice_id).await?;
 
         Ok(InvoiceDto {
             id: invoice.id,
@@ -225,6 +505,8 @@ impl UpdateInvoiceUseCase {
             tax_included: invoice.tax_included,
             tax_label: invoice.tax_label,
             tax_id: invoice.tax_id,
+            currency: invoice.currency,
+            exchange_rate: invoice.exchange_rate,
             pdf_url: invoice.pdf_url,
             receipt_image_url: invoice.receipt_image_url,
             sent_at: invoice.sent_at,
@@ -238,6 +520,19 @@ impl UpdateInvoiceUseCase {
             allow_partial_payment: invoice.allow_partial_payment,
             min_payment_amount: invoice.min_payment_amount,
             partial_payment_count: invoice.partial_payment_count,
+            deposit: invoice.deposit,
+            reverse_charge: invoice.reverse_charge,
+            vat_country: invoice.vat_country,
+            client_vat_number: invoice.client_vat_number,
+            consolidated_into_id: invoice.consolidated_into_id,
+            supersedes_id: invoice.supersedes_id,
+            superseded_by_id: invoice.superseded_by_id,
+            correction_reason: invoice.correction_reason,
+            expires_at: invoice.expires_at,
+            expired_at: invoice.expired_at,
+            project_id: invoice.project_id,
+            label,
+            delivery,
             created_at: invoice.created_at,
             updated_at: invoice.updated_at,
         })
@@ -259,6 +554,117 @@ impl DeleteInvoiceUseCase {
     }
 }
 
+/// Use case: Restore a deleted draft from the trash
+pub struct RestoreInvoiceUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl RestoreInvoiceUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<InvoiceDto, InvoiceError> {
+        self.invoice_service.restore_invoice(user_id, invoice_id).await?;
+        GetInvoiceUseCase::new(self.invoice_service.clone()).execute(user_id, invoice_id).await
+    }
+}
+
+/// Use case: Cancel an unpaid invoice
+pub struct CancelInvoiceUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl CancelInvoiceUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<InvoiceDto, InvoiceError> {
+        self.invoice_service.cancel_invoice(user_id, invoice_id).await?;
+        GetInvoiceUseCase::new(self.invoice_service.clone()).execute(user_id, invoice_id).await
+    }
+}
+
+/// Use case: Consolidate draft invoices for one client into a single invoice
+pub struct ConsolidateInvoicesUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl ConsolidateInvoicesUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, command: ConsolidateInvoicesCommand) -> Result<InvoiceCreatedDto, InvoiceError> {
+        let consolidate = ConsolidateInvoices {
+            invoice_ids: command.invoice_ids,
+            issue_date: command.issue_date,
+            due_date: command.due_date,
+            notes: command.notes,
+            terms: command.terms,
+        };
+
+        let source_count = consolidate.invoice_ids.len();
+        let invoice = self.invoice_service.consolidate_invoices(user_id, consolidate).await?;
+
+        Ok(InvoiceCreatedDto {
+            id: invoice.id,
+            invoice_number: invoice.invoice_number,
+            status: invoice.status,
+            subtotal: invoice.subtotal,
+            tax_amount: invoice.tax_amount,
+            total_amount: invoice.total_amount,
+            tax_label: invoice.tax_label,
+            message: format!("Consolidated {} invoices into a new draft", source_count),
+        })
+    }
+}
+
+/// Use case: Issue a corrected invoice that supersedes a sent one
+pub struct CorrectInvoiceUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl CorrectInvoiceUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid, command: CorrectInvoiceCommand) -> Result<InvoiceCreatedDto, InvoiceError> {
+        let correction = CorrectInvoice {
+            reason: command.reason,
+            issue_date: command.issue_date,
+            due_date: command.due_date,
+            items: command.items.map(|items| items.into_iter().map(|item| crate::domain::models::CreateInvoiceItem {
+                description: item.description,
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                tax_rate: item.tax_rate,
+                section: item.section,
+                taxes: item.taxes,
+            }).collect()),
+            notes: command.notes,
+            terms: command.terms,
+            discount_amount: command.discount_amount,
+            send: command.send.unwrap_or(true),
+        };
+
+        let invoice = self.invoice_service.correct_invoice(user_id, invoice_id, correction).await?;
+
+        Ok(InvoiceCreatedDto {
+            id: invoice.id,
+            message: format!("Issued {} as a correction", invoice.invoice_number),
+            invoice_number: invoice.invoice_number,
+            status: invoice.status,
+            subtotal: invoice.subtotal,
+            tax_amount: invoice.tax_amount,
+            total_amount: invoice.total_amount,
+            tax_label: invoice.tax_label,
+        })
+    }
+}
+
 /// Use case: Record payment for invoice
 pub struct RecordPaymentUseCase {
     invoice_service: Arc<InvoiceService>,
@@ -286,6 +692,7 @@ impl RecordPaymentUseCase {
             gateway_fee: None,
             paid_by: None,
             notes: command.notes,
+            exchange_rate: None,
         };
 
         let invoice = self.invoice_service.record_payment(user_id, invoice_id, payment).await?;
@@ -311,8 +718,73 @@ impl SendInvoiceUseCase {
         Self { invoice_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid, command: SendInvoiceCommand) -> Result<(), InvoiceError> {
-        self.invoice_service.send_invoice(user_id, invoice_id, command.email).await
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        sender_id: Uuid,
+        invoice_id: Uuid,
+        command: SendInvoiceCommand,
+    ) -> Result<(), InvoiceError> {
+        let options = InvoiceSendOptions {
+            email: command.email,
+            cc: command.cc,
+            bcc: command.bcc,
+            subject: command.subject,
+            message: command.message,
+            attach_calendar: command.attach_calendar,
+            sender_id: Some(sender_id),
+        };
+        self.invoice_service.send_invoice(user_id, invoice_id, options).await
+    }
+}
+
+/// Use case: List the send attempts of an invoice
+pub struct GetInvoiceNotificationsUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl GetInvoiceNotificationsUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Vec<InvoiceNotification>, InvoiceError> {
+        self.invoice_service.list_notifications(user_id, invoice_id).await
+    }
+}
+
+/// Use case: Retry a failed send attempt
+pub struct ResendInvoiceNotificationUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl ResendInvoiceNotificationUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        notification_id: Uuid,
+    ) -> Result<InvoiceNotification, InvoiceError> {
+        self.invoice_service.resend_notification(user_id, invoice_id, notification_id).await
+    }
+}
+
+/// Use case: Replace an invoice's guest link, revoking the old ones
+pub struct RegenerateGuestLinkUseCase {
+    guest_tokens: Arc<GuestTokenService>,
+}
+
+impl RegenerateGuestLinkUseCase {
+    pub fn new(guest_tokens: Arc<GuestTokenService>) -> Self {
+        Self { guest_tokens }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<GuestLink, GuestTokenError> {
+        self.guest_tokens.regenerate(user_id, invoice_id).await
     }
 }
 
@@ -326,8 +798,108 @@ impl GetInvoicePdfUseCase {
         Self { invoice_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Vec<u8>, InvoiceError> {
-        self.invoice_service.get_invoice_pdf(user_id, invoice_id).await
+    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid, regenerate: bool) -> Result<InvoicePdf, InvoiceError> {
+        self.invoice_service.get_invoice_pdf(user_id, invoice_id, regenerate).await
+    }
+}
+
+/// PDFs of a batch zipped together, with what happened to each invoice
+pub struct InvoicePdfBatch {
+    pub report: BatchActionReport,
+    /// `None` when not one PDF could be rendered
+    pub archive: Option<Vec<u8>>,
+}
+
+/// Use case: Apply one action to many invoices. Each invoice goes through the same
+/// checks as on its own; one that fails is reported and the rest carry on.
+pub struct InvoiceBatchUseCase {
+    invoice_service: Arc<InvoiceService>,
+}
+
+impl InvoiceBatchUseCase {
+    pub fn new(invoice_service: Arc<InvoiceService>) -> Self {
+        Self { invoice_service }
+    }
+
+    /// Sends, marks paid or deletes the invoices. PDFs are downloaded with `download_pdfs`.
+    pub async fn execute(&self, user_id: Uuid, sender_id: Uuid, batch: InvoiceBatch) -> Result<BatchActionReport, InvoiceError> {
+        let ids = normalize_batch_ids(batch.invoice_ids).map_err(InvoiceError::Validation)?;
+        let payment_method = batch.payment_method.unwrap_or(PaymentMethod::BankTransfer);
+
+        let mut results = Vec::with_capacity(ids.len());
+        for id in ids {
+            let outcome = match batch.action {
+                InvoiceBatchAction::Send => {
+                    let options = InvoiceSendOptions { sender_id: Some(sender_id), ..Default::default() };
+                    self.invoice_service.send_invoice(user_id, id, options).await
+                }
+                InvoiceBatchAction::MarkPaid => self.mark_paid(user_id, id, payment_method.clone()).await,
+                InvoiceBatchAction::Delete => self.invoice_service.delete_invoice(user_id, id).await,
+                InvoiceBatchAction::DownloadPdfs => Err(InvoiceError::Validation(
+                    "PDFs are downloaded as a zip, not applied as an action".to_string(),
+                )),
+            };
+            results.push(BatchItemResult::of(id, &outcome));
+        }
+        Ok(BatchActionReport::new(results))
+    }
+
+    /// Zips the invoices' PDFs as `{invoice number}.pdf`. Invoices whose PDF couldn't
+    /// be rendered are reported and listed in an `errors.txt` inside the archive.
+    pub async fn download_pdfs(&self, user_id: Uuid, invoice_ids: Vec<Uuid>) -> Result<InvoicePdfBatch, InvoiceError> {
+        let ids = normalize_batch_ids(invoice_ids).map_err(InvoiceError::Validation)?;
+
+        let mut archive = ZipArchive::new();
+        let mut results = Vec::with_capacity(ids.len());
+        let mut errors = String::new();
+        for id in ids {
+            let outcome = match self.invoice_service.get_invoice(user_id, id).await {
+                Ok(invoice) => match self.invoice_service.get_invoice_pdf(user_id, id, false).await {
+                    Ok(pdf) => archive
+                        .add(&invoice_file_name(&invoice.invoice_number, "pdf"), &pdf.content)
+                        .map_err(|e| InvoiceError::PdfGenerationError(e.to_string())),
+                    Err(e) => Err(e),
+                }
+                .map_err(|e| {
+                    errors.push_str(&format!("{}: {}\n", invoice.invoice_number, e));
+                    e
+                }),
+                Err(e) => {
+                    errors.push_str(&format!("{}: {}\n", id, e));
+                    Err(e)
+                }
+            };
+            results.push(BatchItemResult::of(id, &outcome));
+        }
+
+        let report = BatchActionReport::new(results);
+        if report.succeeded == 0 {
+            return Ok(InvoicePdfBatch { report, archive: None });
+        }
+        if !errors.is_empty() {
+            archive
+                .add("errors.txt", errors.as_bytes())
+                .map_err(|e| InvoiceError::PdfGenerationError(e.to_string()))?;
+        }
+        let archive = archive.finish().map_err(|e| InvoiceError::PdfGenerationError(e.to_string()))?;
+        Ok(InvoicePdfBatch { report, archive: Some(archive) })
+    }
+
+    /// Records a payment of whatever is still due
+    async fn mark_paid(&self, user_id: Uuid, invoice_id: Uuid, payment_method: PaymentMethod) -> Result<(), InvoiceError> {
+        let invoice = self.invoice_service.get_invoice(user_id, invoice_id).await?;
+        let payment = CreatePayment {
+            invoice_id,
+            amount: invoice.balance_due,
+            payment_method,
+            gateway: None,
+            gateway_payment_id: None,
+            gateway_fee: None,
+            paid_by: None,
+            notes: None,
+            exchange_rate: None,
+        };
+        self.invoice_service.record_payment(user_id, invoice_id, payment).await.map(|_| ())
     }
 }
 
@@ -341,8 +913,8 @@ impl SendReminderUseCase {
         Self { invoice_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
-        self.invoice_service.send_reminder(user_id, invoice_id).await
+    pub async fn execute(&self, user_id: Uuid, sender_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
+        self.invoice_service.send_reminder(user_id, invoice_id, Some(sender_id)).await
     }
 }
 
@@ -398,6 +970,8 @@ impl MarkInvoiceViewedUseCase {
             tax_included: invoice.tax_included,
             tax_label: invoice.tax_label,
             tax_id: invoice.tax_id,
+            currency: invoice.currency,
+            exchange_rate: invoice.exchange_rate,
             pdf_url: invoice.pdf_url,
             receipt_image_url: invoice.receipt_image_url,
             sent_at: invoice.sent_at,
@@ -411,6 +985,19 @@ impl MarkInvoiceViewedUseCase {
             allow_partial_payment: invoice.allow_partial_payment,
             min_payment_amount: invoice.min_payment_amount,
             partial_payment_count: invoice.partial_payment_count,
+            deposit: invoice.deposit,
+            reverse_charge: invoice.reverse_charge,
+            vat_country: invoice.vat_country,
+            client_vat_number: invoice.client_vat_number,
+            consolidated_into_id: invoice.consolidated_into_id,
+            supersedes_id: invoice.supersedes_id,
+            superseded_by_id: invoice.superseded_by_id,
+            correction_reason: invoice.correction_reason,
+            expires_at: invoice.expires_at,
+            expired_at: invoice.expired_at,
+            project_id: invoice.project_id,
+            label: None,
+            delivery: Vec::new(),
             created_at: invoice.created_at,
             updated_at: invoice.updated_at,
         })
@@ -532,4 +1119,19 @@ impl GetDiscussionMessagesUseCase {
 
         Ok(DiscussionResponseDto { messages: dtos })
     }
+
+    /// The client's messages the seller hasn't read
+    pub async fn unread(&self, user_id: Uuid, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        self.invoice_service.discussion_unread(user_id, invoice_id).await
+    }
+
+    /// Mark the discussion read by the seller
+    pub async fn mark_read(&self, user_id: Uuid, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        self.invoice_service.mark_discussion_read(user_id, invoice_id).await
+    }
+
+    /// Every invoice with client messages the seller hasn't read
+    pub async fn unread_conversations(&self, user_id: Uuid) -> Result<Vec<UnreadDiscussion>, InvoiceError> {
+        self.invoice_service.unread_discussions(user_id).await
+    }
 }
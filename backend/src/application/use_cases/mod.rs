@@ -6,6 +6,7 @@ pub mod client_use_cases;
 pub mod payment_use_cases;
 pub mod expense_use_cases;
 pub mod tax_use_cases;
+pub mod credit_note_use_cases;
 
 pub use invoice_use_cases::*;
 pub use auth_use_cases::*;
@@ -15,3 +16,4 @@ pub use client_use_cases::*;
 pub use payment_use_cases::*;
 pub use expense_use_cases::*;
 pub use tax_use_cases::*;
+pub use credit_note_use_cases::*;
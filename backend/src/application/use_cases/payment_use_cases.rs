@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use thiserror::Error;
 
 use crate::domain::services::PaymentService;
-use crate::domain::models::{Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod, CreatePayment, RefundRequest};
+use crate::domain::models::{BatchResult, Page, PageRequest, Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod, CreatePayment, RefundRequest};
 
 #[derive(Debug, Error)]
 pub enum PaymentError {
@@ -74,13 +74,18 @@ impl ListPaymentsUseCase {
         payment_method: Option<PaymentMethod>,
         date_from: Option<DateTime<Utc>>,
         date_to: Option<DateTime<Utc>>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<PaymentResponse>, PaymentError> {
+        page: &PageRequest,
+    ) -> Result<Page<PaymentResponse>, PaymentError> {
         Ok(self.payment_service.list_payments(
-            user_id, status, payment_method, date_from, date_to, limit, offset
+            user_id, status, payment_method, date_from, date_to, page
         ).await?)
     }
+
+    /// Fetch specific payments in one call, reporting IDs that weren't found
+    pub async fn execute_batch(&self, user_id: Uuid, ids: Vec<Uuid>) -> Result<BatchResult<PaymentResponse>, PaymentError> {
+        let payments = self.payment_service.get_payments_by_ids(user_id, &ids).await?;
+        Ok(BatchResult::new(&ids, payments, |payment| payment.id))
+    }
 }
 
 // RefundPaymentUseCase
@@ -121,7 +126,7 @@ impl GetPaymentStatsUseCase {
 }
 
 // GetPaymentMethodsUseCase (static list)
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct GetPaymentMethodsUseCase;
 
 impl GetPaymentMethodsUseCase {
@@ -3,10 +3,11 @@ use uuid::Uuid;
 use chrono::NaiveDate;
 use thiserror::Error;
 
-use crate::domain::services::ReportService;
+use crate::domain::services::{ReportService, SettingsService};
 use crate::domain::repositories::report_repository::{
-    OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport,
+    OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport, AgingTrend, ClientReportFilter, SlaReport, VatReport,
 };
+use crate::domain::models::{CashflowForecast, Timeseries, TimeseriesInterval, TimeseriesMetric};
 
 #[derive(Debug, Error)]
 pub enum ReportError {
@@ -22,6 +23,12 @@ impl From<sqlx::Error> for ReportError {
     }
 }
 
+impl From<crate::domain::services::SettingsError> for ReportError {
+    fn from(err: crate::domain::services::SettingsError) -> Self {
+        ReportError::DatabaseError(err.to_string())
+    }
+}
+
 impl From<Box<dyn std::error::Error + Send + Sync>> for ReportError {
     fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
         ReportError::ExportError(err.to_string())
@@ -60,8 +67,9 @@ impl GetIncomeReportUseCase {
         user_id: Uuid,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        filter: ClientReportFilter,
     ) -> Result<IncomeReport, ReportError> {
-        Ok(self.report_service.get_income_report(user_id, start_date, end_date).await?)
+        Ok(self.report_service.get_income_report(user_id, start_date, end_date, &filter).await?)
     }
 }
 
@@ -107,6 +115,27 @@ impl GetTaxReportUseCase {
     }
 }
 
+// GetVatReportUseCase
+#[derive(Clone)]
+pub struct GetVatReportUseCase {
+    report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+}
+
+impl GetVatReportUseCase {
+    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
+        Self { report_service }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<VatReport, ReportError> {
+        Ok(self.report_service.get_vat_report(user_id, start_date, end_date).await?)
+    }
+}
+
 // GetAgingReportUseCase
 #[derive(Clone)]
 pub struct GetAgingReportUseCase {
@@ -118,8 +147,86 @@ impl GetAgingReportUseCase {
         Self { report_service }
     }
 
-    pub async fn execute(&self, user_id: Uuid) -> Result<AgingReport, ReportError> {
-        Ok(self.report_service.get_aging_report(user_id).await?)
+    pub async fn execute(&self, user_id: Uuid, filter: ClientReportFilter) -> Result<AgingReport, ReportError> {
+        Ok(self.report_service.get_aging_report(user_id, &filter).await?)
+    }
+}
+
+// GetAgingTrendUseCase
+#[derive(Clone)]
+pub struct GetAgingTrendUseCase {
+    report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+}
+
+impl GetAgingTrendUseCase {
+    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
+        Self { report_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid, months: u32) -> Result<AgingTrend, ReportError> {
+        let today = chrono::Utc::now().date_naive();
+        Ok(self.report_service.get_aging_trend(user_id, months, today).await?)
+    }
+}
+
+// GetSlaReportUseCase
+#[derive(Clone)]
+pub struct GetSlaReportUseCase {
+    report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+}
+
+impl GetSlaReportUseCase {
+    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
+        Self { report_service }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<SlaReport, ReportError> {
+        Ok(self.report_service.get_sla_report(user_id, start_date, end_date).await?)
+    }
+}
+
+// GetCashflowForecastUseCase
+#[derive(Clone)]
+pub struct GetCashflowForecastUseCase {
+    report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+}
+
+impl GetCashflowForecastUseCase {
+    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
+        Self { report_service }
+    }
+
+    pub async fn execute(&self, user_id: Uuid) -> Result<CashflowForecast, ReportError> {
+        let today = chrono::Utc::now().date_naive();
+        Ok(self.report_service.get_cashflow_forecast(user_id, today).await?)
+    }
+}
+
+// GetTimeseriesUseCase
+#[derive(Clone)]
+pub struct GetTimeseriesUseCase {
+    report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+}
+
+impl GetTimeseriesUseCase {
+    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
+        Self { report_service }
+    }
+
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        metric: TimeseriesMetric,
+        interval: TimeseriesInterval,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Timeseries, ReportError> {
+        Ok(self.report_service.get_timeseries(user_id, metric, interval, start_date, end_date).await?)
     }
 }
 
@@ -127,11 +234,15 @@ impl GetAgingReportUseCase {
 #[derive(Clone)]
 pub struct ExportReportUseCase {
     report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+    settings_service: Arc<SettingsService>,
 }
 
 impl ExportReportUseCase {
-    pub fn new(report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>) -> Self {
-        Self { report_service }
+    pub fn new(
+        report_service: Arc<ReportService<crate::infrastructure::repositories::ReportRepositoryImpl>>,
+        settings_service: Arc<SettingsService>,
+    ) -> Self {
+        Self { report_service, settings_service }
     }
 
     pub async fn execute(
@@ -142,6 +253,11 @@ impl ExportReportUseCase {
         start_date: NaiveDate,
         end_date: NaiveDate,
     ) -> Result<Vec<u8>, ReportError> {
-        Ok(self.report_service.export_report(user_id, &report_type, &format, start_date, end_date).await?)
+        // PDFs use the invoice template and branding
+        let branding = match format.as_str() {
+            "pdf" => self.settings_service.pdf_branding(user_id).await?,
+            _ => Default::default(),
+        };
+        Ok(self.report_service.export_report(user_id, &report_type, &format, start_date, end_date, &branding).await?)
     }
 }
@@ -2,21 +2,30 @@ use std::sync::Arc;
 use uuid::Uuid;
 use thiserror::Error;
 
+use crate::domain::i18n::Locale;
 use crate::domain::services::SettingsService;
-use crate::domain::models::{BusinessAddress, TaxSettings, NotificationSettings, User};
+use crate::domain::models::{normalize_vat_number, BusinessAddress, TaxSettings, NotificationSettings, InvoiceSettings, User};
 
 #[derive(Debug, Error)]
 pub enum SettingsError {
     #[error("User not found")]
     UserNotFound,
+    #[error("Invalid logo: {0}")]
+    InvalidLogo(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
+    #[error("Validation error: {0}")]
+    Validation(String),
 }
 
 impl From<crate::domain::services::SettingsError> for SettingsError {
     fn from(err: crate::domain::services::SettingsError) -> Self {
         match err {
             crate::domain::services::SettingsError::UserNotFound => SettingsError::UserNotFound,
+            crate::domain::services::SettingsError::InvalidLogo(e) => SettingsError::InvalidLogo(e),
+            crate::domain::services::SettingsError::Storage(e) => SettingsError::Storage(e),
             crate::domain::services::SettingsError::DatabaseError(e) => SettingsError::DatabaseError(e),
         }
     }
@@ -56,13 +65,19 @@ impl UpdateBusinessSettingsUseCase {
         business_type: Option<String>,
         business_address: Option<BusinessAddress>,
         phone: Option<String>,
+        locale: Option<Locale>,
+        vat_number: Option<String>,
     ) -> Result<User, SettingsError> {
+        let vat_number = normalize_vat_number(vat_number).map_err(SettingsError::Validation)?;
+
         Ok(self.settings_service.update_business_settings(
             user_id,
             company_name,
             business_type,
             business_address,
             phone,
+            locale,
+            vat_number,
         ).await?)
     }
 }
@@ -170,14 +185,15 @@ impl UpdateInvoiceSettingsUseCase {
         Self { settings_service }
     }
 
-    pub async fn execute(
-        &self,
-        user_id: Uuid,
-        template: String,
-        logo_url: Option<String>,
-        terms: String,
-        notes: String,
-    ) -> Result<User, SettingsError> {
-        Ok(self.settings_service.update_invoice_settings(user_id, template, logo_url, terms, notes).await?)
+    pub async fn execute(&self, user_id: Uuid, invoice_settings: InvoiceSettings) -> Result<User, SettingsError> {
+        Ok(self.settings_service.update_invoice_settings(user_id, invoice_settings).await?)
+    }
+
+    pub async fn upload_logo(&self, user_id: Uuid, file_data: &[u8], file_name: &str, mime_type: &str) -> Result<User, SettingsError> {
+        Ok(self.settings_service.upload_invoice_logo(user_id, file_data, file_name, mime_type).await?)
+    }
+
+    pub async fn remove_logo(&self, user_id: Uuid) -> Result<User, SettingsError> {
+        Ok(self.settings_service.remove_invoice_logo(user_id).await?)
     }
 }
@@ -171,6 +171,15 @@ pub fn validate_env() -> Result<ValidationResult, ConfigError> {
         }
     }
 
+    // Validate file encryption master keys
+    match crate::domain::services::MasterKeyRing::from_env() {
+        Ok(Some(_)) => {}
+        Ok(None) => result.warnings.push(
+            "FILE_ENCRYPTION_KEYS is not set. Uploaded files will be stored unencrypted.".to_string(),
+        ),
+        Err(e) => result.invalid_vars.push(("FILE_ENCRYPTION_KEYS".to_string(), e)),
+    }
+
     Ok(result)
 }
 
@@ -229,6 +238,13 @@ pub fn print_config_summary() {
         println!("❌ JWT Secret: Not set");
     }
 
+    // File encryption
+    if env::var("FILE_ENCRYPTION_KEYS").is_ok() {
+        println!("✅ File Encryption: Configured");
+    } else {
+        println!("⚠️  File Encryption: Not configured (uploads stored unencrypted)");
+    }
+
     println!();
     println!("Server will start on: http://0.0.0.0:{}", env::var("PORT").unwrap_or_else(|_| "3000".to_string()));
     println!("========================================");
@@ -0,0 +1,729 @@
+//! Translations for user-facing text, and locale-aware number, money and date
+//! formatting. Messages are looked up by their English wording; `{}` in a
+//! catalog entry matches any text, which the translation places with `{0}`,
+//! `{1}`, ... Text without an entry stays in English.
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use utoipa::ToSchema;
+
+use crate::domain::models::iso_minor_units;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Id,
+    Es,
+    De,
+}
+
+impl Locale {
+    pub const SUPPORTED: &'static [Locale] = &[Locale::En, Locale::Id, Locale::Es, Locale::De];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Id => "id",
+            Locale::Es => "es",
+            Locale::De => "de",
+        }
+    }
+
+    /// Matches on the primary language subtag, so `es-MX` is Spanish
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        Locale::SUPPORTED.iter().copied().find(|locale| locale.code() == primary)
+    }
+
+    /// Best supported locale for an `Accept-Language` header, by quality value.
+    /// English when nothing matches.
+    pub fn negotiate(accept_language: &str) -> Locale {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map(|q| q.trim().parse::<f32>().unwrap_or(0.0))
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // Stable, so equal weights keep the client's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| Locale::parse(tag))
+            .unwrap_or_default()
+    }
+}
+
+struct Entry {
+    en: &'static str,
+    id: &'static str,
+    es: &'static str,
+    de: &'static str,
+}
+
+impl Entry {
+    fn text(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => self.en,
+            Locale::Id => self.id,
+            Locale::Es => self.es,
+            Locale::De => self.de,
+        }
+    }
+}
+
+const fn entry(en: &'static str, id: &'static str, es: &'static str, de: &'static str) -> Entry {
+    Entry { en, id, es, de }
+}
+
+/// More specific wordings go before general ones that would also match
+const CATALOG: &[Entry] = &[
+    // Generic API errors
+    entry("Unauthorized", "Tidak diizinkan", "No autorizado", "Nicht autorisiert"),
+    entry("Invalid credentials", "Email atau kata sandi salah", "Credenciales no válidas", "Ungültige Anmeldedaten"),
+    entry("Not found", "Tidak ditemukan", "No encontrado", "Nicht gefunden"),
+    entry("Forbidden", "Akses ditolak", "Prohibido", "Zugriff verweigert"),
+    entry("Database error", "Kesalahan basis data", "Error de base de datos", "Datenbankfehler"),
+    entry(
+        "Internal server error",
+        "Terjadi kesalahan pada server",
+        "Error interno del servidor",
+        "Interner Serverfehler",
+    ),
+    entry(
+        "Rate limit exceeded",
+        "Batas permintaan terlampaui",
+        "Límite de solicitudes excedido",
+        "Anfragelimit überschritten",
+    ),
+    entry(
+        "Service is under heavy load; please try again shortly",
+        "Layanan sedang sibuk; silakan coba lagi sebentar lagi",
+        "El servicio está sobrecargado; inténtelo de nuevo en breve",
+        "Der Dienst ist stark ausgelastet; bitte versuchen Sie es gleich noch einmal",
+    ),
+    // Authentication
+    entry(
+        "Missing authorization header",
+        "Header otorisasi tidak ada",
+        "Falta el encabezado de autorización",
+        "Authorization-Header fehlt",
+    ),
+    entry("Invalid token format", "Format token tidak valid", "Formato de token no válido", "Ungültiges Token-Format"),
+    entry("Invalid token", "Token tidak valid", "Token no válido", "Ungültiges Token"),
+    entry("Token expired", "Token sudah kedaluwarsa", "El token ha caducado", "Token abgelaufen"),
+    entry("Business not found", "Bisnis tidak ditemukan", "Empresa no encontrada", "Unternehmen nicht gefunden"),
+    entry(
+        "Access has expired or was revoked",
+        "Akses sudah kedaluwarsa atau dicabut",
+        "El acceso ha caducado o fue revocado",
+        "Der Zugriff ist abgelaufen oder wurde widerrufen",
+    ),
+    entry(
+        "Your access doesn't include this action",
+        "Akses Anda tidak mencakup tindakan ini",
+        "Su acceso no incluye esta acción",
+        "Ihr Zugriff umfasst diese Aktion nicht",
+    ),
+    entry(
+        "Email already registered",
+        "Email sudah terdaftar",
+        "El correo electrónico ya está registrado",
+        "E-Mail-Adresse ist bereits registriert",
+    ),
+    entry("User not found", "Pengguna tidak ditemukan", "Usuario no encontrado", "Benutzer nicht gefunden"),
+    entry(
+        "Account temporarily locked after too many failed sign-in attempts; try again later or reset your password",
+        "Akun dikunci sementara karena terlalu banyak percobaan masuk yang gagal; coba lagi nanti atau atur ulang kata sandi Anda",
+        "Cuenta bloqueada temporalmente por demasiados intentos fallidos; inténtelo más tarde o restablezca su contraseña",
+        "Konto nach zu vielen fehlgeschlagenen Anmeldeversuchen vorübergehend gesperrt; versuchen Sie es später erneut oder setzen Sie Ihr Passwort zurück",
+    ),
+    entry(
+        "Too many failed sign-in attempts; please try again later",
+        "Terlalu banyak percobaan masuk yang gagal; silakan coba lagi nanti",
+        "Demasiados intentos de inicio de sesión fallidos; inténtelo más tarde",
+        "Zu viele fehlgeschlagene Anmeldeversuche; bitte versuchen Sie es später erneut",
+    ),
+    // Request parameters
+    entry(
+        "Invalid start_date: {}",
+        "start_date tidak valid: {0}",
+        "start_date no válida: {0}",
+        "Ungültiges start_date: {0}",
+    ),
+    entry("Invalid end_date: {}", "end_date tidak valid: {0}", "end_date no válida: {0}", "Ungültiges end_date: {0}"),
+    entry(
+        "start_date must not be after end_date",
+        "start_date tidak boleh setelah end_date",
+        "start_date no puede ser posterior a end_date",
+        "start_date darf nicht nach end_date liegen",
+    ),
+    entry("Invalid invoice ID", "ID faktur tidak valid", "ID de factura no válido", "Ungültige Rechnungs-ID"),
+    entry(
+        "Invalid currency code '{}'",
+        "Kode mata uang '{0}' tidak valid",
+        "Código de moneda '{0}' no válido",
+        "Ungültiger Währungscode '{0}'",
+    ),
+    entry("No file uploaded", "Tidak ada file yang diunggah", "No se subió ningún archivo", "Keine Datei hochgeladen"),
+    entry(
+        "Select at least one column",
+        "Pilih setidaknya satu kolom",
+        "Seleccione al menos una columna",
+        "Wählen Sie mindestens eine Spalte aus",
+    ),
+    // Clients
+    entry("Client not found", "Klien tidak ditemukan", "Cliente no encontrado", "Kunde nicht gefunden"),
+    entry(
+        "Client is archived; unarchive it to invoice again",
+        "Klien diarsipkan; batalkan pengarsipan untuk membuat faktur lagi",
+        "El cliente está archivado; desarchívelo para volver a facturarle",
+        "Der Kunde ist archiviert; heben Sie die Archivierung auf, um ihm wieder Rechnungen zu stellen",
+    ),
+    entry("Client is archived", "Klien diarsipkan", "El cliente está archivado", "Der Kunde ist archiviert"),
+    entry(
+        "Client has no phone number",
+        "Klien tidak memiliki nomor telepon",
+        "El cliente no tiene número de teléfono",
+        "Der Kunde hat keine Telefonnummer",
+    ),
+    entry(
+        "Client email required",
+        "Email klien wajib diisi",
+        "Se requiere el correo electrónico del cliente",
+        "E-Mail-Adresse des Kunden erforderlich",
+    ),
+    // Invoices and payments
+    entry(
+        "Due date must be on or after the issue date",
+        "Tanggal jatuh tempo harus sama dengan atau setelah tanggal terbit",
+        "La fecha de vencimiento debe ser igual o posterior a la fecha de emisión",
+        "Das Fälligkeitsdatum darf nicht vor dem Rechnungsdatum liegen",
+    ),
+    entry("Invoice already paid", "Faktur sudah dibayar", "La factura ya está pagada", "Rechnung bereits bezahlt"),
+    entry(
+        "Invoice is not yet due",
+        "Faktur belum jatuh tempo",
+        "La factura aún no ha vencido",
+        "Rechnung ist noch nicht fällig",
+    ),
+    entry(
+        "Amount must be greater than 0",
+        "Jumlah harus lebih dari 0",
+        "El importe debe ser mayor que 0",
+        "Der Betrag muss größer als 0 sein",
+    ),
+    entry("Invalid amount", "Jumlah tidak valid", "Importe no válido", "Ungültiger Betrag"),
+    entry(
+        "Rate must be greater than 0",
+        "Kurs harus lebih dari 0",
+        "El tipo de cambio debe ser mayor que 0",
+        "Der Kurs muss größer als 0 sein",
+    ),
+    entry(
+        "A reason for the correction is required",
+        "Alasan koreksi wajib diisi",
+        "Se requiere un motivo para la corrección",
+        "Für die Korrektur ist ein Grund erforderlich",
+    ),
+    entry(
+        "Client has no email address or phone number to send the invoice to",
+        "Klien tidak memiliki alamat email atau nomor telepon untuk menerima faktur",
+        "El cliente no tiene correo electrónico ni número de teléfono al que enviar la factura",
+        "Der Kunde hat keine E-Mail-Adresse oder Telefonnummer, an die die Rechnung gesendet werden kann",
+    ),
+    entry(
+        "Invoice could not be delivered: {}",
+        "Faktur tidak dapat dikirim: {0}",
+        "No se pudo entregar la factura: {0}",
+        "Rechnung konnte nicht zugestellt werden: {0}",
+    ),
+    entry(
+        "Only failed notifications can be resent",
+        "Hanya notifikasi yang gagal yang dapat dikirim ulang",
+        "Solo se pueden reenviar las notificaciones fallidas",
+        "Nur fehlgeschlagene Benachrichtigungen können erneut gesendet werden",
+    ),
+    entry(
+        "Invalid email address: {}",
+        "Alamat email tidak valid: {0}",
+        "Dirección de correo electrónico no válida: {0}",
+        "Ungültige E-Mail-Adresse: {0}",
+    ),
+    entry(
+        "Invalid {} address: {}",
+        "Alamat {0} tidak valid: {1}",
+        "Dirección {0} no válida: {1}",
+        "Ungültige {0}-Adresse: {1}",
+    ),
+    entry(
+        "At most 10 {} addresses are allowed",
+        "Maksimal 10 alamat {0} diperbolehkan",
+        "Se permiten como máximo 10 direcciones {0}",
+        "Höchstens 10 {0}-Adressen sind erlaubt",
+    ),
+    // Settings
+    entry(
+        "Business name is required",
+        "Nama bisnis wajib diisi",
+        "El nombre de la empresa es obligatorio",
+        "Der Firmenname ist erforderlich",
+    ),
+    entry(
+        "Label name is required",
+        "Nama label wajib diisi",
+        "El nombre de la etiqueta es obligatorio",
+        "Der Name des Labels ist erforderlich",
+    ),
+    entry(
+        "Signature needs a name, title, phone or link",
+        "Tanda tangan memerlukan nama, jabatan, telepon, atau tautan",
+        "La firma necesita un nombre, cargo, teléfono o enlace",
+        "Die Signatur benötigt einen Namen, Titel, eine Telefonnummer oder einen Link",
+    ),
+    // Invoice PDFs
+    entry("INVOICE", "FAKTUR", "FACTURA", "RECHNUNG"),
+    entry("Invoice", "Faktur", "Factura", "Rechnung"),
+    entry("BILL TO:", "TAGIHAN KEPADA:", "FACTURAR A:", "RECHNUNG AN:"),
+    entry("BILL TO", "TAGIHAN KEPADA", "FACTURAR A", "RECHNUNG AN"),
+    entry("Billed to", "Ditagihkan kepada", "Facturado a", "Rechnung an"),
+    entry("Invoice #: {}", "No. Faktur: {0}", "N.º de factura: {0}", "Rechnungsnr.: {0}"),
+    entry("Issue Date: {}", "Tanggal Terbit: {0}", "Fecha de emisión: {0}", "Rechnungsdatum: {0}"),
+    entry("Due Date: {}", "Jatuh Tempo: {0}", "Fecha de vencimiento: {0}", "Fällig am: {0}"),
+    entry("Status: {}", "Status: {0}", "Estado: {0}", "Status: {0}"),
+    entry("Description", "Deskripsi", "Descripción", "Beschreibung"),
+    entry("Qty", "Jml", "Cant.", "Menge"),
+    entry("Unit Price", "Harga Satuan", "Precio unitario", "Einzelpreis"),
+    entry("Total", "Total", "Total", "Gesamt"),
+    entry("Subtotal:", "Subtotal:", "Subtotal:", "Zwischensumme:"),
+    entry("Tax", "Pajak", "Impuesto", "Steuer"),
+    entry("Discount:", "Diskon:", "Descuento:", "Rabatt:"),
+    entry("TOTAL:", "TOTAL:", "TOTAL:", "GESAMT:"),
+    entry("Notes:", "Catatan:", "Notas:", "Anmerkungen:"),
+    entry("Terms:", "Ketentuan:", "Condiciones:", "Bedingungen:"),
+    entry(
+        "Tax information is for informational purposes only. FlashBill does not",
+        "Informasi pajak hanya sebagai keterangan. FlashBill tidak menghitung,",
+        "La información fiscal es solo informativa. FlashBill no calcula,",
+        "Steuerangaben dienen nur zur Information. FlashBill berechnet, prüft",
+    ),
+    entry(
+        "calculate, verify, or file taxes on your behalf.",
+        "memverifikasi, atau melaporkan pajak atas nama Anda.",
+        "verifica ni declara impuestos en su nombre.",
+        "oder meldet keine Steuern in Ihrem Namen.",
+    ),
+    entry(
+        "Generated by FlashBill - Thank you for your business!",
+        "Dibuat dengan FlashBill - Terima kasih atas kepercayaan Anda!",
+        "Generado con FlashBill - ¡Gracias por su confianza!",
+        "Erstellt mit FlashBill - Vielen Dank für Ihren Auftrag!",
+    ),
+    // Emails
+    entry("Invoice #{} - {}", "Faktur #{0} - {1}", "Factura n.º {0} - {1}", "Rechnung Nr. {0} - {1}"),
+    entry("Invoice #{}", "Faktur #{0}", "Factura n.º {0}", "Rechnung Nr. {0}"),
+    entry("Invoice #:", "No. Faktur:", "N.º de factura:", "Rechnungsnr.:"),
+    entry("Hello {},", "Halo {0},", "Hola, {0}:", "Hallo {0},"),
+    entry(
+        "You have received an invoice for <strong>{}</strong>.",
+        "Anda menerima faktur sebesar <strong>{0}</strong>.",
+        "Ha recibido una factura por <strong>{0}</strong>.",
+        "Sie haben eine Rechnung über <strong>{0}</strong> erhalten.",
+    ),
+    entry("Due Date:", "Jatuh Tempo:", "Fecha de vencimiento:", "Fällig am:"),
+    entry(
+        "You can view and download your invoice using the link below:",
+        "Anda dapat melihat dan mengunduh faktur melalui tautan di bawah ini:",
+        "Puede ver y descargar su factura con el siguiente enlace:",
+        "Über den folgenden Link können Sie Ihre Rechnung ansehen und herunterladen:",
+    ),
+    entry("View Invoice", "Lihat Faktur", "Ver factura", "Rechnung ansehen"),
+    entry(
+        "Please find your invoice attached to this email as a PDF.",
+        "Faktur Anda terlampir pada email ini sebagai PDF.",
+        "Encontrará su factura adjunta a este correo en formato PDF.",
+        "Ihre Rechnung finden Sie als PDF im Anhang dieser E-Mail.",
+    ),
+    entry("Attachments:", "Lampiran:", "Archivos adjuntos:", "Anhänge:"),
+    entry("Thank you for your business!", "Terima kasih atas kepercayaan Anda!", "¡Gracias por su confianza!", "Vielen Dank für Ihren Auftrag!"),
+    entry(
+        "This is an automated message from {}",
+        "Ini adalah pesan otomatis dari {0}",
+        "Este es un mensaje automático de {0}",
+        "Dies ist eine automatische Nachricht von {0}",
+    ),
+    entry("Amount Due:", "Jumlah Tagihan:", "Importe pendiente:", "Offener Betrag:"),
+    entry("Days Overdue:", "Hari Terlambat:", "Días de retraso:", "Tage überfällig:"),
+    entry(
+        "Please remit payment at your earliest convenience.",
+        "Mohon segera lakukan pembayaran.",
+        "Le rogamos que realice el pago lo antes posible.",
+        "Bitte begleichen Sie den Betrag zum nächstmöglichen Zeitpunkt.",
+    ),
+    entry("Pay Now", "Bayar Sekarang", "Pagar ahora", "Jetzt bezahlen"),
+    entry(
+        "If you have already paid, please disregard this email.",
+        "Jika Anda sudah membayar, abaikan email ini.",
+        "Si ya ha pagado, ignore este correo.",
+        "Falls Sie bereits bezahlt haben, betrachten Sie diese E-Mail bitte als gegenstandslos.",
+    ),
+    entry(
+        "Friendly Reminder: Invoice Due Today",
+        "Pengingat: Faktur Jatuh Tempo Hari Ini",
+        "Recordatorio: la factura vence hoy",
+        "Freundliche Erinnerung: Rechnung heute fällig",
+    ),
+    entry(
+        "Just a friendly reminder that your invoice is due today.",
+        "Sekadar mengingatkan bahwa faktur Anda jatuh tempo hari ini.",
+        "Le recordamos amablemente que su factura vence hoy.",
+        "Nur eine freundliche Erinnerung, dass Ihre Rechnung heute fällig ist.",
+    ),
+    entry("Payment Reminder", "Pengingat Pembayaran", "Recordatorio de pago", "Zahlungserinnerung"),
+    entry(
+        "This is a reminder that your invoice is overdue.",
+        "Ini adalah pengingat bahwa faktur Anda telah lewat jatuh tempo.",
+        "Le recordamos que su factura está vencida.",
+        "Wir möchten Sie daran erinnern, dass Ihre Rechnung überfällig ist.",
+    ),
+    entry("Urgent: Payment Overdue", "Penting: Pembayaran Terlambat", "Urgente: pago vencido", "Dringend: Zahlung überfällig"),
+    entry(
+        "Your invoice is significantly overdue. Please remit payment immediately.",
+        "Faktur Anda sudah jauh melewati jatuh tempo. Mohon segera lakukan pembayaran.",
+        "Su factura tiene un retraso considerable. Por favor, realice el pago de inmediato.",
+        "Ihre Rechnung ist erheblich überfällig. Bitte begleichen Sie den Betrag umgehend.",
+    ),
+    entry("Final Notice", "Pemberitahuan Terakhir", "Último aviso", "Letzte Mahnung"),
+    entry("Deposit Due Today", "Uang Muka Jatuh Tempo Hari Ini", "El anticipo vence hoy", "Anzahlung heute fällig"),
+    entry(
+        "Just a friendly reminder that the deposit for your invoice is due today.",
+        "Sekadar mengingatkan bahwa uang muka untuk faktur Anda jatuh tempo hari ini.",
+        "Le recordamos amablemente que el anticipo de su factura vence hoy.",
+        "Nur eine freundliche Erinnerung, dass die Anzahlung für Ihre Rechnung heute fällig ist.",
+    ),
+    entry("Deposit Reminder", "Pengingat Uang Muka", "Recordatorio de anticipo", "Erinnerung an die Anzahlung"),
+    entry(
+        "This is a reminder that the deposit for your invoice is overdue. Work begins once it's paid.",
+        "Ini adalah pengingat bahwa uang muka untuk faktur Anda telah lewat jatuh tempo. Pekerjaan dimulai setelah uang muka dibayar.",
+        "Le recordamos que el anticipo de su factura está vencido. El trabajo comenzará una vez que se haya pagado.",
+        "Wir möchten Sie daran erinnern, dass die Anzahlung für Ihre Rechnung überfällig ist. Die Arbeit beginnt nach Zahlungseingang.",
+    ),
+    entry(
+        "This is our final notice. Immediate payment is required to avoid further action.",
+        "Ini adalah pemberitahuan terakhir kami. Pembayaran segera diperlukan untuk menghindari tindakan lebih lanjut.",
+        "Este es nuestro último aviso. Se requiere el pago inmediato para evitar más medidas.",
+        "Dies ist unsere letzte Mahnung. Um weitere Schritte zu vermeiden, ist eine sofortige Zahlung erforderlich.",
+    ),
+    entry(
+        "Payment Received - Invoice #{}",
+        "Pembayaran Diterima - Faktur #{0}",
+        "Pago recibido - Factura n.º {0}",
+        "Zahlung erhalten - Rechnung Nr. {0}",
+    ),
+    entry("Payment Confirmation", "Konfirmasi Pembayaran", "Confirmación de pago", "Zahlungsbestätigung"),
+    entry(
+        "We have received your payment of <strong>{}</strong> for invoice <strong>#{}</strong>.",
+        "Kami telah menerima pembayaran Anda sebesar <strong>{0}</strong> untuk faktur <strong>#{1}</strong>.",
+        "Hemos recibido su pago de <strong>{0}</strong> de la factura <strong>n.º {1}</strong>.",
+        "Wir haben Ihre Zahlung über <strong>{0}</strong> für die Rechnung <strong>Nr. {1}</strong> erhalten.",
+    ),
+    entry("Payment Method:", "Metode Pembayaran:", "Método de pago:", "Zahlungsart:"),
+    entry(
+        "Your invoice has been marked as paid.",
+        "Faktur Anda telah ditandai lunas.",
+        "Su factura se ha marcado como pagada.",
+        "Ihre Rechnung wurde als bezahlt markiert.",
+    ),
+    entry(
+        "Thank you for your prompt payment!",
+        "Terima kasih atas pembayaran Anda yang tepat waktu!",
+        "¡Gracias por su pronto pago!",
+        "Vielen Dank für Ihre prompte Zahlung!",
+    ),
+    entry("{} Payment Confirmation", "Konfirmasi Pembayaran {0}", "Confirmación de pago de {0}", "Zahlungsbestätigung von {0}"),
+    entry("Bank Transfer", "Transfer Bank", "Transferencia bancaria", "Banküberweisung"),
+    entry("Check", "Cek", "Cheque", "Scheck"),
+    entry("Cash", "Tunai", "Efectivo", "Bargeld"),
+    entry("ACH Debit", "Debit ACH", "Débito ACH", "ACH-Lastschrift"),
+    entry("Password Reset Request", "Permintaan Atur Ulang Kata Sandi", "Solicitud de restablecimiento de contraseña", "Anfrage zum Zurücksetzen des Passworts"),
+    entry("Password Reset", "Atur Ulang Kata Sandi", "Restablecimiento de contraseña", "Passwort zurücksetzen"),
+    entry(
+        "You have requested to reset your password.",
+        "Anda telah meminta untuk mengatur ulang kata sandi.",
+        "Ha solicitado restablecer su contraseña.",
+        "Sie haben angefordert, Ihr Passwort zurückzusetzen.",
+    ),
+    entry("Your reset token is:", "Token atur ulang Anda adalah:", "Su token de restablecimiento es:", "Ihr Token zum Zurücksetzen lautet:"),
+    entry("This token will expire in 1 hour.", "Token ini akan kedaluwarsa dalam 1 jam.", "Este token caducará en 1 hora.", "Dieses Token läuft in 1 Stunde ab."),
+    entry("Reset Password", "Atur Ulang Kata Sandi", "Restablecer contraseña", "Passwort zurücksetzen"),
+    entry(
+        "If you did not request this, please ignore this email.",
+        "Jika Anda tidak memintanya, abaikan email ini.",
+        "Si no lo ha solicitado, ignore este correo.",
+        "Falls Sie dies nicht angefordert haben, ignorieren Sie diese E-Mail bitte.",
+    ),
+    entry("FlashBill Security", "Keamanan FlashBill", "Seguridad de FlashBill", "FlashBill-Sicherheit"),
+    entry("Verify Your Email Address", "Verifikasi Alamat Email Anda", "Verifique su dirección de correo electrónico", "Bestätigen Sie Ihre E-Mail-Adresse"),
+    entry("Welcome to FlashBill!", "Selamat datang di FlashBill!", "¡Bienvenido a FlashBill!", "Willkommen bei FlashBill!"),
+    entry(
+        "Please verify your email address by clicking the button below:",
+        "Silakan verifikasi alamat email Anda dengan mengeklik tombol di bawah ini:",
+        "Verifique su dirección de correo electrónico haciendo clic en el botón de abajo:",
+        "Bitte bestätigen Sie Ihre E-Mail-Adresse, indem Sie auf die Schaltfläche unten klicken:",
+    ),
+    entry("Verify Email", "Verifikasi Email", "Verificar correo", "E-Mail bestätigen"),
+    entry(
+        "If the button doesn't work, use this token: {}",
+        "Jika tombol tidak berfungsi, gunakan token ini: {0}",
+        "Si el botón no funciona, use este token: {0}",
+        "Falls die Schaltfläche nicht funktioniert, verwenden Sie dieses Token: {0}",
+    ),
+    entry("FlashBill Team", "Tim FlashBill", "Equipo de FlashBill", "Ihr FlashBill-Team"),
+    entry(
+        "New message about invoice #{}",
+        "Pesan baru tentang faktur #{0}",
+        "Nuevo mensaje sobre la factura #{0}",
+        "Neue Nachricht zu Rechnung #{0}",
+    ),
+    entry("{} wrote:", "{0} menulis:", "{0} escribió:", "{0} schrieb:"),
+    entry("View Conversation", "Lihat Percakapan", "Ver conversación", "Unterhaltung anzeigen"),
+    entry("Scan to pay online", "Pindai untuk membayar online", "Escanee para pagar en línea", "Scannen und online bezahlen"),
+    entry(
+        "Scan to pay by bank transfer",
+        "Pindai untuk membayar lewat transfer bank",
+        "Escanee para pagar por transferencia",
+        "Scannen und per Überweisung bezahlen",
+    ),
+    entry("Scan to pay with QRIS", "Pindai untuk membayar dengan QRIS", "Escanee para pagar con QRIS", "Scannen und mit QRIS bezahlen"),
+    entry("VAT:", "PPN:", "IVA:", "USt.:"),
+    entry(
+        "Supplier VAT number: {}",
+        "Nomor PPN pemasok: {0}",
+        "NIF-IVA del proveedor: {0}",
+        "USt-IdNr. des Leistenden: {0}",
+    ),
+    entry(
+        "Customer VAT number: {}",
+        "Nomor PPN pelanggan: {0}",
+        "NIF-IVA del cliente: {0}",
+        "USt-IdNr. des Leistungsempfängers: {0}",
+    ),
+    entry(
+        "Reverse charge: VAT to be accounted for by the recipient (Article 196, Council Directive 2006/112/EC)",
+        "Pembalikan beban: PPN terutang oleh penerima (Pasal 196, Direktif Dewan 2006/112/EC)",
+        "Inversión del sujeto pasivo: IVA a cargo del destinatario (artículo 196 de la Directiva 2006/112/CE del Consejo)",
+        "Steuerschuldnerschaft des Leistungsempfängers (Artikel 196 der Richtlinie 2006/112/EG des Rates)",
+    ),
+];
+
+/// `message` in `locale`, or unchanged when the catalog has no entry for it
+pub fn translate(locale: Locale, message: &str) -> Cow<'_, str> {
+    if locale == Locale::En {
+        return Cow::Borrowed(message);
+    }
+
+    for entry in CATALOG {
+        if let Some(args) = match_template(entry.en, message) {
+            let mut text = entry.text(locale).to_string();
+            for (i, arg) in args.iter().enumerate() {
+                text = text.replace(&format!("{{{}}}", i), arg);
+            }
+            return Cow::Owned(text);
+        }
+    }
+    Cow::Borrowed(message)
+}
+
+/// A catalog wording in `locale` with its `{}` filled from `args` in order, as
+/// templates ask for "Hello {}," with the client's name. Wordings without an
+/// entry are filled in English.
+pub fn translate_with(locale: Locale, text: &str, args: &[&str]) -> String {
+    match CATALOG.iter().find(|entry| entry.en == text).filter(|_| locale != Locale::En) {
+        Some(entry) => {
+            let mut translated = entry.text(locale).to_string();
+            for (i, arg) in args.iter().enumerate() {
+                translated = translated.replace(&format!("{{{}}}", i), arg);
+            }
+            translated
+        }
+        None => {
+            let mut parts = text.split("{}");
+            let mut filled = parts.next().unwrap_or_default().to_string();
+            for (i, part) in parts.enumerate() {
+                filled.push_str(args.get(i).copied().unwrap_or_default());
+                filled.push_str(part);
+            }
+            filled
+        }
+    }
+}
+
+/// `value` with `decimals` places and the locale's separators: `1,234.56` in
+/// English, `1.234,56` in Indonesian, Spanish and German
+pub fn format_number(locale: Locale, value: f64, decimals: u32) -> String {
+    let (group, decimal) = match locale {
+        Locale::En => (',', '.'),
+        Locale::Id | Locale::Es | Locale::De => ('.', ','),
+    };
+    let fixed = format!("{:.*}", decimals as usize, value.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+    let mut text = String::with_capacity(fixed.len() + whole.len() / 3 + 1);
+    if value < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        text.push('-');
+    }
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            text.push(group);
+        }
+        text.push(digit);
+    }
+    if !fraction.is_empty() {
+        text.push(decimal);
+        text.push_str(fraction);
+    }
+    text
+}
+
+/// `amount` in `currency` with its minor units and symbol where the locale puts
+/// it: `$1,250.00` and `Rp 1.250.000,00` before, `1.250,00 €` after
+pub fn format_money(locale: Locale, amount: f64, currency: &str) -> String {
+    let currency = currency.trim().to_ascii_uppercase();
+    let number = format_number(locale, amount, iso_minor_units(&currency));
+    let symbol = match currency.as_str() {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        "JPY" => "¥",
+        "IDR" => "Rp",
+        code => code,
+    };
+    match locale {
+        Locale::En | Locale::Id if symbol.chars().count() == 1 => format!("{}{}", symbol, number),
+        Locale::En | Locale::Id => format!("{} {}", symbol, number),
+        Locale::Es | Locale::De => format!("{} {}", number, symbol),
+    }
+}
+
+const MONTHS_ID: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "Mei", "Jun", "Jul", "Agu", "Sep", "Okt", "Nov", "Des"];
+
+/// A date as the locale writes it. English keeps ISO 8601 (`2025-04-30`), which
+/// invoices have always shown.
+pub fn format_date(locale: Locale, date: NaiveDate) -> String {
+    match locale {
+        Locale::En => date.format("%Y-%m-%d").to_string(),
+        Locale::Id => format!("{} {} {}", date.day(), MONTHS_ID[date.month0() as usize], date.year()),
+        Locale::Es => date.format("%d/%m/%Y").to_string(),
+        Locale::De => date.format("%d.%m.%Y").to_string(),
+    }
+}
+
+/// The text in each `{}` of `template`, if `message` has that shape
+fn match_template<'m>(template: &str, message: &'m str) -> Option<Vec<&'m str>> {
+    let parts: Vec<&str> = template.split("{}").collect();
+    let (first, rest) = parts.split_first()?;
+    let Some((last, middle)) = rest.split_last() else {
+        return (template == message).then(Vec::new);
+    };
+
+    let mut remaining = message.strip_prefix(first)?.strip_suffix(last)?;
+    let mut args = Vec::with_capacity(parts.len() - 1);
+    for literal in middle {
+        let (arg, tail) = remaining.split_once(literal)?;
+        args.push(arg);
+        remaining = tail;
+    }
+    args.push(remaining);
+    Some(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_by_quality_and_primary_tag() {
+        assert_eq!(Locale::negotiate("id-ID,id;q=0.9,en;q=0.8"), Locale::Id);
+        assert_eq!(Locale::negotiate("fr-FR, es-MX;q=0.7, en;q=0.5"), Locale::Es);
+        assert_eq!(Locale::negotiate("en;q=0.4, es;q=0.9"), Locale::Es);
+        assert_eq!(Locale::negotiate("es;q=0, id;q=0.1"), Locale::Id);
+        assert_eq!(Locale::negotiate("de-AT, fr"), Locale::De);
+        assert_eq!(Locale::negotiate("fr, it"), Locale::En);
+        assert_eq!(Locale::negotiate("*"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn translates_fixed_and_templated_messages() {
+        assert_eq!(translate(Locale::Id, "Not found"), "Tidak ditemukan");
+        assert_eq!(translate(Locale::Es, "Invalid currency code 'XYZ'"), "Código de moneda 'XYZ' no válido");
+        assert_eq!(
+            translate(Locale::Id, "Invalid cc address: bob@"),
+            "Alamat cc tidak valid: bob@"
+        );
+        assert_eq!(
+            translate(Locale::Es, "Invalid email address: bob@"),
+            "Dirección de correo electrónico no válida: bob@"
+        );
+    }
+
+    #[test]
+    fn unknown_messages_and_english_are_unchanged() {
+        assert_eq!(translate(Locale::Es, "Something new went wrong"), "Something new went wrong");
+        assert_eq!(translate(Locale::En, "Not found"), "Not found");
+        assert_eq!(translate(Locale::Id, "Not found at all"), "Not found at all");
+    }
+
+    #[test]
+    fn every_entry_has_its_placeholders() {
+        for entry in CATALOG {
+            let count = entry.en.matches("{}").count();
+            for translation in [entry.id, entry.es, entry.de] {
+                for i in 0..count {
+                    assert!(translation.contains(&format!("{{{}}}", i)), "{} is missing {{{}}}", translation, i);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fills_template_wordings_in_every_locale() {
+        assert_eq!(translate_with(Locale::En, "Hello {},", &["Jane"]), "Hello Jane,");
+        assert_eq!(translate_with(Locale::De, "Hello {},", &["Jane"]), "Hallo Jane,");
+        assert_eq!(
+            translate_with(Locale::Id, "Invoice #{} - {}", &["INV-1", "Rp 5.000,00"]),
+            "Faktur #INV-1 - Rp 5.000,00"
+        );
+        assert_eq!(translate_with(Locale::Es, "Not in the catalog {}", &["x"]), "Not in the catalog x");
+    }
+
+    #[test]
+    fn formats_numbers_money_and_dates_per_locale() {
+        assert_eq!(format_number(Locale::En, 1234567.891, 2), "1,234,567.89");
+        assert_eq!(format_number(Locale::De, 1234567.891, 2), "1.234.567,89");
+        assert_eq!(format_number(Locale::Id, -950.0, 0), "-950");
+        assert_eq!(format_number(Locale::En, -0.001, 2), "0.00");
+
+        assert_eq!(format_money(Locale::En, 150.0, "USD"), "$150.00");
+        assert_eq!(format_money(Locale::En, 1250.0, "usd"), "$1,250.00");
+        assert_eq!(format_money(Locale::Id, 1250000.0, "IDR"), "Rp 1.250.000,00");
+        assert_eq!(format_money(Locale::De, 1250.5, "EUR"), "1.250,50 €");
+        assert_eq!(format_money(Locale::Es, 99.0, "CHF"), "99,00 CHF");
+        assert_eq!(format_money(Locale::De, 5000.0, "JPY"), "5.000 ¥");
+
+        let date = NaiveDate::from_ymd_opt(2025, 8, 3).unwrap();
+        assert_eq!(format_date(Locale::En, date), "2025-08-03");
+        assert_eq!(format_date(Locale::Id, date), "3 Agu 2025");
+        assert_eq!(format_date(Locale::Es, date), "03/08/2025");
+        assert_eq!(format_date(Locale::De, date), "03.08.2025");
+    }
+}
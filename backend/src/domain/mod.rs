@@ -1,4 +1,5 @@
 pub mod models;
 pub mod services;
 pub mod repositories;
-
+pub mod i18n;
+pub mod request_id;
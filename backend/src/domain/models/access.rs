@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Role carried in an access token. Owners have full access to their account;
+/// other roles only get the permissions granted in [`AccessRole::allows`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessRole {
+    #[default]
+    Owner,
+    /// External accountant: read-only reports, exports and invoice PDFs
+    Accountant,
+    /// Buyer signed in to the client portal; only the `/portal` routes accept it
+    Client,
+}
+
+/// What a request needs to be allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    ViewReports,
+    ExportReports,
+    ListInvoices,
+    DownloadInvoicePdf,
+    /// Everything else: editing clients and invoices, sending, settings
+    ManageAccount,
+}
+
+impl AccessRole {
+    pub fn allows(self, permission: Permission) -> bool {
+        match self {
+            AccessRole::Owner => true,
+            AccessRole::Accountant => !matches!(permission, Permission::ManageAccount),
+            AccessRole::Client => false,
+        }
+    }
+}
+
+/// Default and maximum lifetime of an accountant grant
+pub const ACCOUNTANT_ACCESS_DEFAULT_DAYS: i64 = 30;
+pub const ACCOUNTANT_ACCESS_MAX_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountantAccess {
+    pub id: Uuid,
+    pub email: String,
+    pub name: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateAccountantAccess {
+    pub email: String,
+    pub name: Option<String>,
+    pub expires_in_days: Option<i64>,
+}
+
+/// A new grant together with its access code. The code is only shown here and in
+/// the invitation email.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountantInvite {
+    #[serde(flatten)]
+    pub access: AccountantAccess,
+    pub access_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountantLogin {
+    pub access_code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountantSession {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub business_id: Uuid,
+    pub business_name: String,
+    pub access_expires_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accountant_is_limited_to_read_only_permissions() {
+        assert!(AccessRole::Accountant.allows(Permission::ViewReports));
+        assert!(AccessRole::Accountant.allows(Permission::ExportReports));
+        assert!(AccessRole::Accountant.allows(Permission::DownloadInvoicePdf));
+        assert!(!AccessRole::Accountant.allows(Permission::ManageAccount));
+        assert!(AccessRole::Owner.allows(Permission::ManageAccount));
+    }
+
+    #[test]
+    fn test_client_role_has_no_seller_permissions() {
+        for permission in [
+            Permission::ViewReports,
+            Permission::ExportReports,
+            Permission::ListInvoices,
+            Permission::DownloadInvoicePdf,
+            Permission::ManageAccount,
+        ] {
+            assert!(!AccessRole::Client.allows(permission));
+        }
+    }
+}
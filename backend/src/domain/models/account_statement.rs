@@ -0,0 +1,268 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::client_credit::{ClientCreditEntry, CreditBalance};
+use crate::domain::models::invoice_totals::round_money;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementEntryKind {
+    Invoice,
+    Payment,
+    CreditNote,
+    ClientCredit,
+}
+
+impl StatementEntryKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementEntryKind::Invoice => "invoice",
+            StatementEntryKind::Payment => "payment",
+            StatementEntryKind::CreditNote => "credit_note",
+            StatementEntryKind::ClientCredit => "client_credit",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "invoice" => Some(StatementEntryKind::Invoice),
+            "payment" => Some(StatementEntryKind::Payment),
+            "credit_note" => Some(StatementEntryKind::CreditNote),
+            "client_credit" => Some(StatementEntryKind::ClientCredit),
+            _ => None,
+        }
+    }
+}
+
+/// A dated movement on the client's account. Invoices are debits; payments,
+/// applied credit notes and credit drawn from the client's balance are credits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEntry {
+    pub date: NaiveDate,
+    pub kind: StatementEntryKind,
+    pub reference: String,
+    pub description: String,
+    pub invoice_id: Uuid,
+    pub debit: f64,
+    pub credit: f64,
+}
+
+/// A statement entry with the balance owed after it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct StatementLine {
+    pub date: NaiveDate,
+    pub kind: StatementEntryKind,
+    pub reference: String,
+    pub description: String,
+    pub invoice_id: Uuid,
+    pub debit: f64,
+    pub credit: f64,
+    pub balance: f64,
+}
+
+/// Statement of account for one client over a period
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountStatement {
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub client_email: Option<String>,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Owed at the start of `start_date`
+    pub opening_balance: f64,
+    pub total_invoiced: f64,
+    /// Payments and applied credit in the period
+    pub total_received: f64,
+    /// Owed at the end of `end_date`
+    pub closing_balance: f64,
+    pub lines: Vec<StatementLine>,
+    /// Unused credit the client holds at the end of `end_date`, per currency
+    pub credit_balances: Vec<CreditBalance>,
+    /// Credit ledger movements in the period
+    pub credit_entries: Vec<ClientCreditEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatementFormat {
+    #[default]
+    Json,
+    Pdf,
+    Csv,
+}
+
+impl StatementFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(StatementFormat::Json),
+            "pdf" => Some(StatementFormat::Pdf),
+            "csv" => Some(StatementFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatementQuery {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// json (default), pdf or csv
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct EmailStatementRequest {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Defaults to the client's email
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementEmailed {
+    pub sent_to: String,
+    pub closing_balance: f64,
+}
+
+impl AccountStatement {
+    /// Builds the statement from every entry up to `end_date`: earlier entries make
+    /// up the opening balance, the rest are listed oldest first with a running
+    /// balance. On the same day invoices come before what settles them.
+    pub fn build(
+        client_id: Uuid,
+        client_name: String,
+        client_email: Option<String>,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        mut entries: Vec<StatementEntry>,
+    ) -> Self {
+        entries.retain(|entry| entry.date <= end_date);
+        entries.sort_by(|a, b| {
+            a.date
+                .cmp(&b.date)
+                .then(a.kind.cmp(&b.kind))
+                .then_with(|| a.reference.cmp(&b.reference))
+        });
+
+        let opening_balance = round_money(
+            entries
+                .iter()
+                .filter(|entry| entry.date < start_date)
+                .fold(0.0, |balance, entry| balance + entry.debit - entry.credit),
+        );
+
+        let mut balance = opening_balance;
+        let mut total_invoiced = 0.0;
+        let mut total_received = 0.0;
+        let lines: Vec<StatementLine> = entries
+            .into_iter()
+            .filter(|entry| entry.date >= start_date)
+            .map(|entry| {
+                balance = round_money(balance + entry.debit - entry.credit);
+                total_invoiced += entry.debit;
+                total_received += entry.credit;
+                StatementLine {
+                    date: entry.date,
+                    kind: entry.kind,
+                    reference: entry.reference,
+                    description: entry.description,
+                    invoice_id: entry.invoice_id,
+                    debit: entry.debit,
+                    credit: entry.credit,
+                    balance,
+                }
+            })
+            .collect();
+
+        Self {
+            client_id,
+            client_name,
+            client_email,
+            start_date,
+            end_date,
+            opening_balance,
+            total_invoiced: round_money(total_invoiced),
+            total_received: round_money(total_received),
+            closing_balance: balance,
+            lines,
+            credit_balances: Vec::new(),
+            credit_entries: Vec::new(),
+        }
+    }
+
+    /// "March 2025" for a whole calendar month, otherwise the date range
+    pub fn period_label(&self) -> String {
+        let whole_month = self.start_date.day() == 1
+            && (self.start_date.year(), self.start_date.month()) == (self.end_date.year(), self.end_date.month())
+            && self.end_date.succ_opt().is_none_or(|next| next.day() == 1);
+        if whole_month {
+            self.start_date.format("%B %Y").to_string()
+        } else {
+            format!("{} to {}", self.start_date, self.end_date)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn entry(day: &str, kind: StatementEntryKind, reference: &str, debit: f64, credit: f64) -> StatementEntry {
+        StatementEntry {
+            date: date(day),
+            kind,
+            reference: reference.to_string(),
+            description: String::new(),
+            invoice_id: Uuid::new_v4(),
+            debit,
+            credit,
+        }
+    }
+
+    fn statement(start: &str, end: &str, entries: Vec<StatementEntry>) -> AccountStatement {
+        AccountStatement::build(Uuid::new_v4(), "Acme".to_string(), None, date(start), date(end), entries)
+    }
+
+    #[test]
+    fn test_running_balance_from_opening_to_closing() {
+        let result = statement(
+            "2025-03-01",
+            "2025-03-31",
+            vec![
+                entry("2025-03-20", StatementEntryKind::Payment, "PAY-2", 0.0, 300.0),
+                entry("2025-02-10", StatementEntryKind::Invoice, "INV-1", 500.0, 0.0),
+                entry("2025-02-25", StatementEntryKind::Payment, "PAY-1", 0.0, 200.0),
+                entry("2025-03-05", StatementEntryKind::Invoice, "INV-2", 1000.0, 0.0),
+                entry("2025-03-05", StatementEntryKind::CreditNote, "CN-1", 0.0, 100.0),
+                entry("2025-04-02", StatementEntryKind::Invoice, "INV-3", 50.0, 0.0),
+            ],
+        );
+
+        assert_eq!(result.opening_balance, 300.0);
+        let lines: Vec<_> = result.lines.iter().map(|line| (line.reference.as_str(), line.balance)).collect();
+        assert_eq!(lines, vec![("INV-2", 1300.0), ("CN-1", 1200.0), ("PAY-2", 900.0)]);
+        assert_eq!(result.total_invoiced, 1000.0);
+        assert_eq!(result.total_received, 400.0);
+        assert_eq!(result.closing_balance, 900.0);
+    }
+
+    #[test]
+    fn test_quiet_period_carries_the_balance() {
+        let result = statement(
+            "2025-03-01",
+            "2025-03-15",
+            vec![entry("2025-01-10", StatementEntryKind::Invoice, "INV-1", 250.0, 0.0)],
+        );
+        assert!(result.lines.is_empty());
+        assert_eq!((result.opening_balance, result.closing_balance), (250.0, 250.0));
+        assert_eq!(result.period_label(), "2025-03-01 to 2025-03-15");
+
+        assert_eq!(statement("2025-02-01", "2025-02-28", vec![]).period_label(), "February 2025");
+    }
+}
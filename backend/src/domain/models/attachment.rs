@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MAX_ATTACHMENT_NAME_LENGTH: usize = 255;
+
+/// The invoice or expense a file is attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentParent {
+    Invoice(Uuid),
+    Expense(Uuid),
+}
+
+/// A contract, receipt, timesheet or other file attached to an invoice or expense
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Attachment {
+    pub id: Uuid,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub invoice_id: Option<Uuid>,
+    pub expense_id: Option<Uuid>,
+    /// Name the file was uploaded with
+    pub file_name: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    /// Name of the stored file in the owner's upload directory
+    #[serde(skip)]
+    pub stored_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// The display name for an uploaded file: the last path component, without
+/// control characters, cut to MAX_ATTACHMENT_NAME_LENGTH. None when nothing is left.
+pub fn attachment_name(original: &str) -> Option<String> {
+    let base = original.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = base
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_ATTACHMENT_NAME_LENGTH)
+        .collect();
+    let name = name.trim();
+
+    (!name.is_empty() && name != "." && name != "..").then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attachment_name_keeps_only_the_file_name() {
+        assert_eq!(attachment_name("contract.pdf").as_deref(), Some("contract.pdf"));
+        assert_eq!(attachment_name("C:\\Users\\me\\hours.xlsx").as_deref(), Some("hours.xlsx"));
+        assert_eq!(attachment_name("../../etc/passwd").as_deref(), Some("passwd"));
+        assert_eq!(attachment_name(" receipt\n.png ").as_deref(), Some("receipt.png"));
+        assert_eq!(attachment_name("x".repeat(300).as_str()).map(|n| n.len()), Some(MAX_ATTACHMENT_NAME_LENGTH));
+
+        assert_eq!(attachment_name(""), None);
+        assert_eq!(attachment_name("uploads/"), None);
+        assert_eq!(attachment_name(".."), None);
+    }
+}
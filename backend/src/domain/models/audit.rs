@@ -1,21 +1,26 @@
 #![allow(dead_code)]
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use sqlx::Type;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, ToSchema)]
 #[sqlx(type_name = "varchar")]
+#[serde(rename_all = "snake_case")]
 pub enum AuditAction {
     Create,
     Update,
     Delete,
+    /// An update that moved the record to another status
+    StatusChange,
     Login,
     Logout,
     Payment,
     Send,
     Refund,
+    LateFee,
 }
 
 impl std::fmt::Display for AuditAction {
@@ -24,23 +29,45 @@ impl std::fmt::Display for AuditAction {
             AuditAction::Create => write!(f, "create"),
             AuditAction::Update => write!(f, "update"),
             AuditAction::Delete => write!(f, "delete"),
+            AuditAction::StatusChange => write!(f, "status_change"),
             AuditAction::Login => write!(f, "login"),
             AuditAction::Logout => write!(f, "logout"),
             AuditAction::Payment => write!(f, "payment"),
             AuditAction::Send => write!(f, "send"),
             AuditAction::Refund => write!(f, "refund"),
+            AuditAction::LateFee => write!(f, "late_fee"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+impl AuditAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(AuditAction::Create),
+            "update" => Some(AuditAction::Update),
+            "delete" => Some(AuditAction::Delete),
+            "status_change" => Some(AuditAction::StatusChange),
+            "login" => Some(AuditAction::Login),
+            "logout" => Some(AuditAction::Logout),
+            "payment" => Some(AuditAction::Payment),
+            "send" => Some(AuditAction::Send),
+            "refund" => Some(AuditAction::Refund),
+            "late_fee" => Some(AuditAction::LateFee),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type, PartialEq, Eq, ToSchema)]
 #[sqlx(type_name = "varchar")]
+#[serde(rename_all = "snake_case")]
 pub enum AuditEntityType {
     User,
     Invoice,
     Client,
     Payment,
     Expense,
+    Settings,
 }
 
 impl std::fmt::Display for AuditEntityType {
@@ -51,41 +78,174 @@ impl std::fmt::Display for AuditEntityType {
             AuditEntityType::Client => write!(f, "client"),
             AuditEntityType::Payment => write!(f, "payment"),
             AuditEntityType::Expense => write!(f, "expense"),
+            AuditEntityType::Settings => write!(f, "settings"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl AuditEntityType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "user" => Some(AuditEntityType::User),
+            "invoice" => Some(AuditEntityType::Invoice),
+            "client" => Some(AuditEntityType::Client),
+            "payment" => Some(AuditEntityType::Payment),
+            "expense" => Some(AuditEntityType::Expense),
+            "settings" => Some(AuditEntityType::Settings),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuditLog {
     pub id: Uuid,
+    /// Account the change was made in
     pub user_id: Option<Uuid>,
+    /// Login that made the change; None for background jobs
+    pub actor_id: Option<Uuid>,
     pub action: AuditAction,
     pub entity_type: AuditEntityType,
     pub entity_id: Option<Uuid>,
     pub changes: Option<serde_json::Value>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    /// X-Request-Id of the request that made the change
+    pub request_id: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateAuditLog {
     pub user_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
     pub action: AuditAction,
     pub entity_type: AuditEntityType,
     pub entity_id: Option<Uuid>,
     pub changes: Option<serde_json::Value>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
+    pub request_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const DEFAULT_AUDIT_PAGE_SIZE: i64 = 50;
+pub const MAX_AUDIT_PAGE_SIZE: i64 = 200;
+
+/// Query for `GET /audit-logs`; always scoped to the caller's account
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AuditListFilter {
     pub action: Option<AuditAction>,
     pub entity_type: Option<AuditEntityType>,
-    pub user_id: Option<Uuid>,
-    pub date_from: Option<DateTime<Utc>>,
-    pub date_to: Option<DateTime<Utc>>,
+    pub entity_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    /// Entries made by one request, as quoted from an X-Request-Id header or error body
+    pub request_id: Option<String>,
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
+
+/// Never copied into an audit entry
+fn is_redacted(key: &str) -> bool {
+    ["password", "token", "secret"].iter().any(|word| key.contains(word))
+}
+
+/// Bumped on every write, so they'd show up in every diff
+fn is_noise(key: &str) -> bool {
+    matches!(key, "updated_at" | "last_login_at")
+}
+
+/// The record without passwords, tokens or secrets, for anything that leaves the
+/// table it was read from
+pub fn without_secrets(snapshot: &serde_json::Value) -> serde_json::Value {
+    match snapshot {
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .filter(|(key, _)| !is_redacted(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        other => other.clone(),
+    }
+}
+
+/// `{"before": ..., "after": ...}` for an audit entry. Updates keep only the fields
+/// that changed; a create has no `before` and a delete no `after`. None when an
+/// update changed nothing.
+pub fn audit_changes(before: Option<&serde_json::Value>, after: Option<&serde_json::Value>) -> Option<serde_json::Value> {
+    let (before, after) = match (before, after) {
+        (None, None) => return None,
+        (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) => {
+            let keys = old.keys().chain(new.keys().filter(|key| !old.contains_key(*key)));
+            let mut changed_before = serde_json::Map::new();
+            let mut changed_after = serde_json::Map::new();
+            for key in keys.filter(|key| !is_redacted(key) && !is_noise(key)) {
+                let old_value = old.get(key).unwrap_or(&serde_json::Value::Null);
+                let new_value = new.get(key).unwrap_or(&serde_json::Value::Null);
+                if old_value != new_value {
+                    changed_before.insert(key.clone(), old_value.clone());
+                    changed_after.insert(key.clone(), new_value.clone());
+                }
+            }
+            if changed_after.is_empty() {
+                return None;
+            }
+            (serde_json::Value::Object(changed_before), serde_json::Value::Object(changed_after))
+        }
+        (before, after) => (
+            before.map(without_secrets).unwrap_or(serde_json::Value::Null),
+            after.map(without_secrets).unwrap_or(serde_json::Value::Null),
+        ),
+    };
+
+    Some(serde_json::json!({ "before": before, "after": after }))
+}
+
+/// Whether the changes move the record to another status
+pub fn changes_status(changes: Option<&serde_json::Value>) -> bool {
+    changes
+        .and_then(|changes| changes.get("before"))
+        .and_then(|before| before.as_object())
+        .is_some_and(|before| before.contains_key("status"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_update_keeps_changed_fields_only() {
+        let before = json!({ "status": "sent", "notes": "a", "updated_at": "t1", "total_amount": 10 });
+        let after = json!({ "status": "paid", "notes": "a", "updated_at": "t2", "total_amount": 10 });
+
+        let changes = audit_changes(Some(&before), Some(&after)).unwrap();
+        assert_eq!(changes, json!({ "before": { "status": "sent" }, "after": { "status": "paid" } }));
+        assert!(changes_status(Some(&changes)));
+
+        assert_eq!(audit_changes(Some(&before), Some(&before)), None);
+    }
+
+    #[test]
+    fn test_secrets_never_reach_the_trail() {
+        let before = json!({ "company_name": "Old", "password_hash": "x", "guest_payment_token": "t" });
+        let after = json!({ "company_name": "New", "password_hash": "y", "guest_payment_token": "u" });
+
+        let changes = audit_changes(Some(&before), Some(&after)).unwrap();
+        assert_eq!(changes["after"], json!({ "company_name": "New" }));
+
+        let created = audit_changes(None, Some(&after)).unwrap();
+        assert_eq!(created, json!({ "before": null, "after": { "company_name": "New" } }));
+        assert!(!changes_status(Some(&created)));
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for action in [AuditAction::Create, AuditAction::StatusChange, AuditAction::LateFee] {
+            assert_eq!(AuditAction::parse(&action.to_string()), Some(action));
+        }
+        assert_eq!(AuditEntityType::parse("settings"), Some(AuditEntityType::Settings));
+        assert_eq!(serde_json::to_value(AuditAction::StatusChange).unwrap(), "status_change");
+    }
+}
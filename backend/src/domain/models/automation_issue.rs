@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Background automation that can fail without a user waiting on the result
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationIssueKind {
+    EmailBounce,
+    WhatsappFailed,
+    RecurringInvoiceSkipped,
+    WebhookDeadLettered,
+}
+
+impl AutomationIssueKind {
+    pub const ALL: [AutomationIssueKind; 4] = [
+        AutomationIssueKind::EmailBounce,
+        AutomationIssueKind::WhatsappFailed,
+        AutomationIssueKind::RecurringInvoiceSkipped,
+        AutomationIssueKind::WebhookDeadLettered,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AutomationIssueKind::EmailBounce => "email_bounce",
+            AutomationIssueKind::WhatsappFailed => "whatsapp_failed",
+            AutomationIssueKind::RecurringInvoiceSkipped => "recurring_invoice_skipped",
+            AutomationIssueKind::WebhookDeadLettered => "webhook_dead_lettered",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AutomationIssueKind::EmailBounce => "Email not delivered",
+            AutomationIssueKind::WhatsappFailed => "WhatsApp message not delivered",
+            AutomationIssueKind::RecurringInvoiceSkipped => "Recurring invoice skipped",
+            AutomationIssueKind::WebhookDeadLettered => "Webhook delivery abandoned",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AutomationIssue {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: AutomationIssueKind,
+    pub invoice_id: Option<Uuid>,
+    pub summary: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub digested_at: Option<DateTime<Utc>>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CreateAutomationIssue {
+    pub user_id: Uuid,
+    pub kind: AutomationIssueKind,
+    pub invoice_id: Option<Uuid>,
+    pub summary: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AutomationIssueFilter {
+    #[serde(default)]
+    pub include_resolved: bool,
+}
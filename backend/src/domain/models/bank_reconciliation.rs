@@ -0,0 +1,437 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::invoice_csv_import::parse_amount;
+use crate::domain::models::{CsvTable, ImportRowIssue, PaymentMethod, PaymentStatus};
+
+pub const MAX_BANK_STATEMENT_BYTES: usize = 5 * 1024 * 1024;
+pub const MAX_BANK_STATEMENT_ROWS: usize = 10_000;
+const MAX_BANK_REFERENCE_LEN: usize = 100;
+/// Suggestions below this score are noise: an amount or a reference has to match
+const MIN_MATCH_SCORE: u32 = 40;
+const MAX_SUGGESTIONS: usize = 3;
+/// Transfers usually land within a few business days of being started
+const RECEIPT_WINDOW_DAYS: i64 = 14;
+
+/// A bank transfer or ACH payment, as the seller reconciles it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BankTransferPayment {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub client_name: Option<String>,
+    pub amount: Decimal,
+    pub currency: String,
+    pub payment_method: PaymentMethod,
+    pub status: PaymentStatus,
+    /// Reference the payer was given for the transfer
+    pub reference: Option<String>,
+    pub paid_by: Option<String>,
+    /// Reference on the bank statement, once confirmed
+    pub bank_reference: Option<String>,
+    pub received_on: Option<NaiveDate>,
+    pub failure_reason: Option<String>,
+    /// What the invoice still owes
+    pub balance_due: Decimal,
+    pub created_at: DateTime<Utc>,
+    pub reconciled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BankTransferFilter {
+    /// Defaults to pending
+    pub status: Option<PaymentStatus>,
+}
+
+/// The seller saw the money arrive
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfirmBankPayment {
+    /// Reference of the transaction on the bank statement
+    pub reference: String,
+    pub received_on: NaiveDate,
+}
+
+impl ConfirmBankPayment {
+    pub fn validate(&self, today: NaiveDate) -> Result<(), String> {
+        let reference = self.reference.trim();
+        if reference.is_empty() {
+            return Err("reference is required".to_string());
+        }
+        if reference.chars().count() > MAX_BANK_REFERENCE_LEN {
+            return Err(format!("reference can be at most {} characters", MAX_BANK_REFERENCE_LEN));
+        }
+        if self.received_on > today {
+            return Err("received_on can't be in the future".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The money never arrived, or was returned
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct RejectBankPayment {
+    pub reason: Option<String>,
+}
+
+/// A bank statement column in the CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum StatementColumn {
+    Date,
+    /// Signed amount; money out is negative
+    Amount,
+    /// Money in, in a file that splits credits and debits
+    Credit,
+    Description,
+    Reference,
+}
+
+impl StatementColumn {
+    /// The column a header names, normalized as `CsvTable` does
+    fn from_header(header: &str) -> Option<Self> {
+        let column = match header {
+            "date" | "booking_date" | "transaction_date" | "posting_date" | "posted" | "value_date" => {
+                StatementColumn::Date
+            }
+            "amount" | "transaction_amount" => StatementColumn::Amount,
+            "credit" | "credit_amount" | "deposit" | "deposits" | "paid_in" | "money_in" => StatementColumn::Credit,
+            "description" | "details" | "memo" | "narrative" | "payee" | "name" | "counterparty"
+            | "transaction_details" => StatementColumn::Description,
+            "reference" | "ref" | "payment_reference" | "remittance" | "remittance_information" | "end_to_end_id" => {
+                StatementColumn::Reference
+            }
+            _ => return None,
+        };
+        Some(column)
+    }
+}
+
+/// Money received, from one row of the statement. `row` is the line in the
+/// file (the header is row 1).
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BankStatementLine {
+    pub row: u32,
+    pub date: NaiveDate,
+    pub amount: Decimal,
+    pub description: String,
+    pub reference: Option<String>,
+}
+
+/// A statement file checked row by row
+#[derive(Debug, Clone, Default)]
+pub struct ParsedBankStatement {
+    /// Credits, in file order
+    pub lines: Vec<BankStatementLine>,
+    /// Data rows in the file
+    pub total_rows: usize,
+    /// Rows for money going out, which can't be payments
+    pub debits: usize,
+    pub errors: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+/// Reads a bank statement export: a header row, then one transaction per row,
+/// with a date, an amount (or a credit column) and a description. Comma and
+/// semicolon separated files are accepted. Only credits are kept.
+pub fn parse_bank_statement_csv(data: &[u8]) -> Result<ParsedBankStatement, String> {
+    let table = CsvTable::read(data, MAX_BANK_STATEMENT_ROWS, StatementColumn::from_header)?;
+    if !table.has(StatementColumn::Date) {
+        return Err("The CSV needs a date column".to_string());
+    }
+    // A credit column is the money in; a signed amount is the fallback
+    let amount_column = match (table.has(StatementColumn::Credit), table.has(StatementColumn::Amount)) {
+        (true, _) => StatementColumn::Credit,
+        (false, true) => StatementColumn::Amount,
+        (false, false) => return Err("The CSV needs an amount or credit column".to_string()),
+    };
+    if !table.has(StatementColumn::Description) && !table.has(StatementColumn::Reference) {
+        return Err("The CSV needs a description or reference column".to_string());
+    }
+
+    let mut parsed = ParsedBankStatement {
+        total_rows: table.total_rows,
+        errors: table.errors.clone(),
+        ignored_columns: table.ignored_columns.clone(),
+        ..Default::default()
+    };
+    for csv_row in &table.rows {
+        let row = csv_row.row;
+        let value = |column: StatementColumn| table.value(csv_row, column);
+
+        // A split file leaves the credit empty on debit rows
+        let Some(amount) = value(amount_column) else {
+            parsed.debits += 1;
+            continue;
+        };
+        let Some(amount) = parse_amount(&amount) else {
+            parsed.errors.push(ImportRowIssue::new(row, Some("amount"), "Amounts must be numbers"));
+            continue;
+        };
+        if amount <= Decimal::ZERO {
+            parsed.debits += 1;
+            continue;
+        }
+        let date = match value(StatementColumn::Date).as_deref().map(parse_statement_date) {
+            Some(Some(date)) => date,
+            Some(None) => {
+                parsed.errors.push(ImportRowIssue::new(row, Some("date"), "Dates must be YYYY-MM-DD or DD.MM.YYYY"));
+                continue;
+            }
+            None => {
+                parsed.errors.push(ImportRowIssue::new(row, Some("date"), "Date is required"));
+                continue;
+            }
+        };
+
+        parsed.lines.push(BankStatementLine {
+            row,
+            date,
+            amount,
+            description: value(StatementColumn::Description).unwrap_or_default(),
+            reference: value(StatementColumn::Reference),
+        });
+    }
+
+    parsed.errors.sort_by_key(|issue| issue.row);
+    Ok(parsed)
+}
+
+/// ISO dates, and the day-first dotted dates European banks export.
+/// Slashed dates are ambiguous between day and month first, so they're rejected.
+fn parse_statement_date(value: &str) -> Option<NaiveDate> {
+    ["%Y-%m-%d", "%Y/%m/%d", "%d.%m.%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+/// A pending payment that may be the money on a statement line
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MatchSuggestion {
+    pub payment_id: Uuid,
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub amount: Decimal,
+    /// 0-100; higher is a likelier match
+    pub score: u32,
+    /// What matched, e.g. "amount" or "invoice_number"
+    pub reasons: Vec<String>,
+}
+
+/// A statement line with its likeliest payments, best first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementLineMatches {
+    #[serde(flatten)]
+    pub line: BankStatementLine,
+    pub suggestions: Vec<MatchSuggestion>,
+}
+
+/// Statement lines matched against pending bank transfers. Nothing is
+/// confirmed until the seller confirms a payment.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StatementMatchReport {
+    pub total_rows: usize,
+    pub credits: usize,
+    /// Credits with at least one suggestion
+    pub matched: usize,
+    pub lines: Vec<StatementLineMatches>,
+    pub errors: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+impl StatementMatchReport {
+    pub fn new(statement: ParsedBankStatement, pending: &[BankTransferPayment]) -> Self {
+        let lines: Vec<StatementLineMatches> = statement
+            .lines
+            .into_iter()
+            .map(|line| {
+                let suggestions = suggest_matches(&line, pending);
+                StatementLineMatches { line, suggestions }
+            })
+            .collect();
+
+        Self {
+            total_rows: statement.total_rows,
+            credits: lines.len(),
+            matched: lines.iter().filter(|line| !line.suggestions.is_empty()).count(),
+            lines,
+            errors: statement.errors,
+            ignored_columns: statement.ignored_columns,
+        }
+    }
+}
+
+/// The pending payments a statement line most likely pays, best first
+pub fn suggest_matches(line: &BankStatementLine, pending: &[BankTransferPayment]) -> Vec<MatchSuggestion> {
+    let mut suggestions: Vec<MatchSuggestion> = pending
+        .iter()
+        .filter_map(|payment| {
+            let (score, reasons) = score_match(line, payment);
+            (score >= MIN_MATCH_SCORE).then(|| MatchSuggestion {
+                payment_id: payment.id,
+                invoice_id: payment.invoice_id,
+                invoice_number: payment.invoice_number.clone(),
+                amount: payment.amount,
+                score,
+                reasons: reasons.into_iter().map(str::to_string).collect(),
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.score.cmp(&a.score).then(a.invoice_number.cmp(&b.invoice_number)));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Scores how well a statement line fits a payment. Payers type references
+/// loosely ("inv 2026/0042" for INV-2026-0042), so text is compared with
+/// punctuation and case dropped.
+fn score_match(line: &BankStatementLine, payment: &BankTransferPayment) -> (u32, Vec<&'static str>) {
+    let text = normalize(&format!("{} {}", line.description, line.reference.as_deref().unwrap_or_default()));
+    let text_digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    let mut score: i64 = 0;
+    let mut reasons = Vec::new();
+
+    if line.amount == payment.amount {
+        score += 50;
+        reasons.push("amount");
+    } else if (line.amount - payment.amount).abs() * Decimal::ONE_HUNDRED <= payment.amount {
+        // Intermediary banks sometimes take a small fee
+        score += 25;
+        reasons.push("amount_close");
+    }
+
+    let invoice_number = normalize(&payment.invoice_number);
+    let invoice_digits: String = invoice_number.chars().filter(char::is_ascii_digit).collect();
+    if !invoice_number.is_empty() && text.contains(&invoice_number) {
+        score += 40;
+        reasons.push("invoice_number");
+    } else if invoice_digits.len() >= 4 && text_digits.contains(&invoice_digits) {
+        score += 30;
+        reasons.push("invoice_number");
+    }
+
+    if let Some(reference) = payment.reference.as_deref().map(normalize).filter(|r| !r.is_empty()) {
+        if text.contains(&reference) {
+            score += 40;
+            reasons.push("reference");
+        }
+    }
+
+    // Most words of the payer's name show up in the description
+    let name = payment.paid_by.as_deref().or(payment.client_name.as_deref()).unwrap_or_default();
+    let words: Vec<String> = name.split_whitespace().map(normalize).filter(|word| word.len() >= 3).collect();
+    if !words.is_empty() && words.iter().filter(|word| text.contains(word.as_str())).count() * 2 >= words.len() {
+        score += 15;
+        reasons.push("payer_name");
+    }
+
+    let days_after = (line.date - payment.created_at.date_naive()).num_days();
+    if (0..=RECEIPT_WINDOW_DAYS).contains(&days_after) {
+        score += 10;
+        reasons.push("date");
+    } else if days_after < -1 {
+        // Received before the payer started the transfer
+        score -= 30;
+    }
+
+    (score.clamp(0, 100) as u32, reasons)
+}
+
+/// Upper-case letters and digits only
+fn normalize(value: &str) -> String {
+    value.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_uppercase()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn pending(invoice_number: &str, amount: Decimal, paid_by: &str) -> BankTransferPayment {
+        BankTransferPayment {
+            id: Uuid::new_v4(),
+            invoice_id: Uuid::new_v4(),
+            invoice_number: invoice_number.to_string(),
+            client_name: Some("Acme Corp".to_string()),
+            amount,
+            currency: "USD".to_string(),
+            payment_method: PaymentMethod::BankTransfer,
+            status: PaymentStatus::Pending,
+            reference: Some("BT_4f2a9c".to_string()),
+            paid_by: Some(paid_by.to_string()),
+            bank_reference: None,
+            received_on: None,
+            failure_reason: None,
+            balance_due: amount,
+            created_at: Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap(),
+            reconciled_at: None,
+        }
+    }
+
+    fn line(date: &str, amount: Decimal, description: &str) -> BankStatementLine {
+        BankStatementLine {
+            row: 2,
+            date: NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap(),
+            amount,
+            description: description.to_string(),
+            reference: None,
+        }
+    }
+
+    #[test]
+    fn test_statement_keeps_credits_only() {
+        let csv = "Booking Date;Details;Amount;Balance\n\
+                   03.03.2026;SEPA credit ACME CORP inv 2026/0042;1250,50;9000\n\
+                   2026-03-04;Card payment;-45,10;8954.90\n\
+                   2026-03-05;Refund;abc;0\n\
+                   03/05/2026;Transfer;20;0\n";
+        let parsed = parse_bank_statement_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_rows, 4);
+        assert_eq!(parsed.debits, 1);
+        assert_eq!(parsed.ignored_columns, vec!["Balance".to_string()]);
+        let errors: Vec<(u32, Option<&str>)> = parsed.errors.iter().map(|e| (e.row, e.field.as_deref())).collect();
+        assert_eq!(errors, vec![(4, Some("amount")), (5, Some("date"))]);
+        assert_eq!(parsed.lines.len(), 1);
+        assert_eq!(parsed.lines[0].amount, Decimal::new(125050, 2));
+        assert_eq!(parsed.lines[0].date, NaiveDate::from_ymd_opt(2026, 3, 3).unwrap());
+
+        let csv = "date,description,credit,debit\n2026-03-03,ACME CORP INV-2026-0042,1250.50,\n2026-03-04,Rent,,900\n";
+        let parsed = parse_bank_statement_csv(csv.as_bytes()).unwrap();
+        assert_eq!(parsed.debits, 1);
+        assert_eq!(parsed.lines.len(), 1);
+        assert_eq!(parsed.lines[0].description, "ACME CORP INV-2026-0042");
+    }
+
+    #[test]
+    fn test_statement_needs_date_amount_and_description() {
+        assert!(parse_bank_statement_csv(b"amount,description\n10,x\n").is_err());
+        assert!(parse_bank_statement_csv(b"date,description\n2026-03-03,x\n").is_err());
+        assert!(parse_bank_statement_csv(b"date,amount\n2026-03-03,10\n").is_err());
+    }
+
+    #[test]
+    fn test_loosely_typed_invoice_numbers_are_suggested_first() {
+        let amount = Decimal::new(125050, 2);
+        let exact = pending("INV-2026-0042", amount, "Budi Santoso");
+        let same_amount = pending("INV-2026-0043", amount, "Someone Else");
+        let unrelated = pending("INV-2026-0050", Decimal::new(300, 0), "Someone Else");
+        let payments = vec![same_amount.clone(), unrelated, exact.clone()];
+
+        let suggestions = suggest_matches(&line("2026-03-04", amount, "TRF BUDI SANTOSO inv 2026/0042"), &payments);
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggestions[0].payment_id, exact.id);
+        assert_eq!(suggestions[0].score, 100);
+        assert_eq!(suggestions[0].reasons, vec!["amount", "invoice_number", "payer_name", "date"]);
+        assert_eq!(suggestions[1].payment_id, same_amount.id);
+
+        // A small bank fee off the amount, with the transfer reference
+        let suggestions = suggest_matches(&line("2026-03-04", Decimal::new(124550, 2), "ref BT-4F2A9C"), std::slice::from_ref(&exact));
+        assert_eq!(suggestions[0].reasons, vec!["amount_close", "reference", "date"]);
+
+        // Money that arrived before the transfer was started
+        assert!(suggest_matches(&line("2026-02-20", amount, "payment"), &[exact]).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most IDs accepted by one batch lookup (`?ids=a,b,c`)
+pub const MAX_BATCH_IDS: usize = 100;
+
+/// Entities fetched by ID in one request. Items follow the requested order; IDs that
+/// don't exist or belong to another account are listed in `not_found`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult<T> {
+    pub items: Vec<T>,
+    pub not_found: Vec<Uuid>,
+}
+
+impl<T> BatchResult<T> {
+    pub fn new(requested: &[Uuid], found: Vec<T>, id_of: impl Fn(&T) -> Uuid) -> Self {
+        let mut found: Vec<Option<T>> = found.into_iter().map(Some).collect();
+        let mut items = Vec::with_capacity(requested.len());
+        let mut not_found = Vec::new();
+
+        for id in requested {
+            let position = found.iter().position(|item| item.as_ref().is_some_and(|item| id_of(item) == *id));
+            match position.and_then(|index| found[index].take()) {
+                Some(item) => items.push(item),
+                None => not_found.push(*id),
+            }
+        }
+
+        Self { items, not_found }
+    }
+}
+
+/// What an action applied to many entities did to one of them
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchItemResult {
+    pub id: Uuid,
+    pub success: bool,
+    /// Why it failed; the others went ahead regardless
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Per-item results of a batch action, in the requested order
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BatchActionReport {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchItemResult>,
+}
+
+impl BatchActionReport {
+    pub fn new(results: Vec<BatchItemResult>) -> Self {
+        let succeeded = results.iter().filter(|result| result.success).count();
+        Self { succeeded, failed: results.len() - succeeded, results }
+    }
+}
+
+impl BatchItemResult {
+    pub fn of<E: std::fmt::Display>(id: Uuid, outcome: &Result<(), E>) -> Self {
+        match outcome {
+            Ok(()) => Self { id, success: true, error: None },
+            Err(err) => Self { id, success: false, error: Some(err.to_string()) },
+        }
+    }
+}
+
+/// Parse a comma-separated ID list, dropping duplicates
+pub fn parse_batch_ids(raw: &str) -> Result<Vec<Uuid>, String> {
+    let mut ids: Vec<Uuid> = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+        ids.push(Uuid::parse_str(part).map_err(|_| format!("Invalid id: {}", part))?);
+    }
+    normalize_batch_ids(ids)
+}
+
+/// Drop duplicate IDs, keeping the first of each, and check the list isn't empty or too long
+pub fn normalize_batch_ids(ids: Vec<Uuid>) -> Result<Vec<Uuid>, String> {
+    let mut unique: Vec<Uuid> = Vec::with_capacity(ids.len());
+    for id in ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+
+    if unique.is_empty() {
+        return Err("ids must list at least one id".to_string());
+    }
+    if unique.len() > MAX_BATCH_IDS {
+        return Err(format!("At most {} ids per request", MAX_BATCH_IDS));
+    }
+    Ok(unique)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_keeps_requested_order_and_reports_missing() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let result = BatchResult::new(&[a, b, c], vec![c, a], |id| *id);
+
+        assert_eq!(result.items, vec![a, c]);
+        assert_eq!(result.not_found, vec![b]);
+    }
+
+    #[test]
+    fn parses_and_bounds_id_lists() {
+        let a = Uuid::new_v4();
+        assert_eq!(parse_batch_ids(&format!("{a}, {a},")).unwrap(), vec![a]);
+        assert!(parse_batch_ids("").is_err());
+        assert!(parse_batch_ids("not-a-uuid").is_err());
+
+        let too_many: Vec<String> = (0..=MAX_BATCH_IDS).map(|_| Uuid::new_v4().to_string()).collect();
+        assert!(parse_batch_ids(&too_many.join(",")).is_err());
+    }
+
+    #[test]
+    fn report_counts_outcomes() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let report = BatchActionReport::new(vec![
+            BatchItemResult::of::<String>(a, &Ok(())),
+            BatchItemResult::of(b, &Err("Invoice not found")),
+        ]);
+
+        assert_eq!((report.succeeded, report.failed), (1, 1));
+        assert!(report.results[0].success && report.results[0].error.is_none());
+        assert_eq!(report.results[1].error.as_deref(), Some("Invoice not found"));
+    }
+}
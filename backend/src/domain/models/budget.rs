@@ -0,0 +1,494 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::domain::models::expense::ExpenseCategory;
+use crate::domain::models::invoice_totals::round_money;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetLineKind {
+    Income,
+    Expense,
+}
+
+impl BudgetLineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetLineKind::Income => "income",
+            BudgetLineKind::Expense => "expense",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "income" => Some(BudgetLineKind::Income),
+            "expense" => Some(BudgetLineKind::Expense),
+            _ => None,
+        }
+    }
+}
+
+/// Planned amount for one month; expense lines are planned per category
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetLine {
+    pub kind: BudgetLineKind,
+    pub category: Option<ExpenseCategory>,
+    /// 1-12
+    pub month: u32,
+    pub amount: f64,
+}
+
+/// Annual budget of planned income and expenses
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Budget {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub year: i32,
+    pub name: String,
+    pub lines: Vec<BudgetLine>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateBudget {
+    pub year: i32,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub lines: Vec<BudgetLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateBudget {
+    pub name: Option<String>,
+    /// Replaces all lines when present
+    pub lines: Option<Vec<BudgetLine>>,
+}
+
+/// Planned vs actual for one bucket
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct BudgetVariance {
+    pub planned: f64,
+    pub actual: f64,
+    /// actual - planned
+    pub variance: f64,
+    /// Variance as a percentage of planned; None when nothing was planned
+    pub variance_pct: Option<f64>,
+}
+
+impl BudgetVariance {
+    pub fn new(planned: f64, actual: f64) -> Self {
+        let planned = round_money(planned);
+        let actual = round_money(actual);
+        let variance = round_money(actual - planned);
+        let variance_pct = if planned.abs() > f64::EPSILON {
+            Some((variance / planned * 10_000.0).round() / 100.0)
+        } else {
+            None
+        };
+
+        Self { planned, actual, variance, variance_pct }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetMonthReport {
+    pub month: u32,
+    pub income: BudgetVariance,
+    pub expenses: BudgetVariance,
+    /// Income minus expenses
+    pub net: BudgetVariance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetCategoryReport {
+    pub category: String,
+    pub expenses: BudgetVariance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetReport {
+    pub budget_id: Uuid,
+    pub year: i32,
+    pub months: Vec<BudgetMonthReport>,
+    pub categories: Vec<BudgetCategoryReport>,
+    pub income: BudgetVariance,
+    pub expenses: BudgetVariance,
+    pub net: BudgetVariance,
+}
+
+/// Actual amounts recorded in a budget year
+#[derive(Debug, Clone, Default)]
+pub struct BudgetActuals {
+    /// month -> income received
+    pub income_by_month: BTreeMap<u32, f64>,
+    /// (month, category) -> expenses incurred
+    pub expenses: BTreeMap<(u32, String), f64>,
+}
+
+impl BudgetReport {
+    pub fn build(budget: &Budget, actuals: &BudgetActuals) -> Self {
+        let mut planned_income = [0.0; 12];
+        let mut planned_expenses = [0.0; 12];
+        let mut planned_by_category: BTreeMap<String, f64> = BTreeMap::new();
+
+        for line in &budget.lines {
+            let idx = (line.month.clamp(1, 12) - 1) as usize;
+            match line.kind {
+                BudgetLineKind::Income => planned_income[idx] += line.amount,
+                BudgetLineKind::Expense => {
+                    planned_expenses[idx] += line.amount;
+                    let category = line
+                        .category
+                        .as_ref()
+                        .map(|c| c.to_string())
+                        .unwrap_or_else(|| ExpenseCategory::Other.to_string());
+                    *planned_by_category.entry(category).or_default() += line.amount;
+                }
+            }
+        }
+
+        let mut actual_expenses = [0.0; 12];
+        let mut actual_by_category: BTreeMap<String, f64> = BTreeMap::new();
+        for ((month, category), amount) in &actuals.expenses {
+            actual_expenses[((*month).clamp(1, 12) - 1) as usize] += amount;
+            *actual_by_category.entry(category.clone()).or_default() += amount;
+        }
+
+        let months = (1..=12u32)
+            .map(|month| {
+                let idx = (month - 1) as usize;
+                let actual_income = actuals.income_by_month.get(&month).copied().unwrap_or(0.0);
+                BudgetMonthReport {
+                    month,
+                    income: BudgetVariance::new(planned_income[idx], actual_income),
+                    expenses: BudgetVariance::new(planned_expenses[idx], actual_expenses[idx]),
+                    net: BudgetVariance::new(
+                        planned_income[idx] - planned_expenses[idx],
+                        actual_income - actual_expenses[idx],
+                    ),
+                }
+            })
+            .collect();
+
+        let mut category_names: Vec<String> = planned_by_category.keys().cloned().collect();
+        for name in actual_by_category.keys() {
+            if !planned_by_category.contains_key(name) {
+                category_names.push(name.clone());
+            }
+        }
+        category_names.sort();
+
+        let categories = category_names
+            .into_iter()
+            .map(|category| BudgetCategoryReport {
+                expenses: BudgetVariance::new(
+                    planned_by_category.get(&category).copied().unwrap_or(0.0),
+                    actual_by_category.get(&category).copied().unwrap_or(0.0),
+                ),
+                category,
+            })
+            .collect();
+
+        let planned_income_total: f64 = planned_income.iter().sum();
+        let planned_expense_total: f64 = planned_expenses.iter().sum();
+        let actual_income_total: f64 = actuals.income_by_month.values().sum();
+        let actual_expense_total: f64 = actual_expenses.iter().sum();
+
+        Self {
+            budget_id: budget.id,
+            year: budget.year,
+            months,
+            categories,
+            income: BudgetVariance::new(planned_income_total, actual_income_total),
+            expenses: BudgetVariance::new(planned_expense_total, actual_expense_total),
+            net: BudgetVariance::new(
+                planned_income_total - planned_expense_total,
+                actual_income_total - actual_expense_total,
+            ),
+        }
+    }
+}
+
+/// Share of a limit at which spending raises an alert, in percent
+pub const BUDGET_ALERT_THRESHOLDS: [u8; 2] = [80, 100];
+
+/// How long a spending limit runs before it starts over
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Monthly,
+    Quarterly,
+}
+
+impl BudgetPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriod::Monthly => "monthly",
+            BudgetPeriod::Quarterly => "quarterly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "monthly" => Some(BudgetPeriod::Monthly),
+            "quarterly" => Some(BudgetPeriod::Quarterly),
+            _ => None,
+        }
+    }
+
+    /// First and last day of the calendar month or quarter containing `date`
+    pub fn bounds(&self, date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let (first_month, months) = match self {
+            BudgetPeriod::Monthly => (date.month(), 1),
+            BudgetPeriod::Quarterly => ((date.month0() / 3) * 3 + 1, 3),
+        };
+        let start = NaiveDate::from_ymd_opt(date.year(), first_month, 1).unwrap_or(date);
+        let end = start
+            .checked_add_months(chrono::Months::new(months))
+            .and_then(|next| next.pred_opt())
+            .unwrap_or(date);
+        (start, end)
+    }
+}
+
+/// Most that should be spent on an expense category each month or quarter
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetLimit {
+    pub id: Uuid,
+    pub category: ExpenseCategory,
+    pub period: BudgetPeriod,
+    pub amount: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateBudgetLimit {
+    pub category: ExpenseCategory,
+    pub period: BudgetPeriod,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateBudgetLimit {
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UtilizationStatus {
+    OnTrack,
+    /// Past the first alert threshold
+    Warning,
+    Exceeded,
+}
+
+/// Spending against a limit in the period containing the requested day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetUtilization {
+    pub limit_id: Uuid,
+    pub category: ExpenseCategory,
+    pub period: BudgetPeriod,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub amount: f64,
+    pub spent: f64,
+    /// Negative once the limit is exceeded
+    pub remaining: f64,
+    /// Spent as a percentage of the limit
+    pub utilization_pct: f64,
+    pub status: UtilizationStatus,
+}
+
+impl BudgetUtilization {
+    pub fn new(limit: &BudgetLimit, period_start: NaiveDate, period_end: NaiveDate, spent: f64) -> Self {
+        let amount = round_money(limit.amount);
+        let spent = round_money(spent);
+        let utilization_pct = if amount > 0.0 { (spent / amount * 10_000.0).round() / 100.0 } else { 0.0 };
+        // From the amounts, not the rounded percentage: 79.999% hasn't reached 80%
+        let status = if reached(spent, amount, 100) {
+            UtilizationStatus::Exceeded
+        } else if reached(spent, amount, BUDGET_ALERT_THRESHOLDS[0]) {
+            UtilizationStatus::Warning
+        } else {
+            UtilizationStatus::OnTrack
+        };
+
+        Self {
+            limit_id: limit.id,
+            category: limit.category.clone(),
+            period: limit.period,
+            period_start,
+            period_end,
+            amount,
+            spent,
+            remaining: round_money(amount - spent),
+            utilization_pct,
+            status,
+        }
+    }
+
+    /// Alert thresholds the spending has reached
+    pub fn crossed_thresholds(&self) -> Vec<u8> {
+        BUDGET_ALERT_THRESHOLDS
+            .into_iter()
+            .filter(|threshold| reached(self.spent, self.amount, *threshold))
+            .collect()
+    }
+}
+
+/// Whether `spent` is at least `threshold` percent of `amount`
+fn reached(spent: f64, amount: f64, threshold: u8) -> bool {
+    spent * 100.0 >= amount * f64::from(threshold)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UtilizationQuery {
+    /// Day whose month or quarter is reported; today by default
+    pub date: Option<NaiveDate>,
+}
+
+/// Spending in a period reached a threshold of a limit
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BudgetAlert {
+    pub id: Uuid,
+    pub limit_id: Uuid,
+    pub category: ExpenseCategory,
+    pub period: BudgetPeriod,
+    pub period_start: NaiveDate,
+    /// 80 or 100
+    pub threshold: u8,
+    /// Spent when the threshold was reached
+    pub spent: f64,
+    pub amount: f64,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BudgetAlertFilter {
+    #[serde(default)]
+    pub unread: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(kind: BudgetLineKind, category: Option<ExpenseCategory>, month: u32, amount: f64) -> BudgetLine {
+        BudgetLine { kind, category, month, amount }
+    }
+
+    fn budget(lines: Vec<BudgetLine>) -> Budget {
+        Budget {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            year: 2025,
+            name: "2025".to_string(),
+            lines,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_variance_percentages() {
+        let over = BudgetVariance::new(200.0, 250.0);
+        assert_eq!(over.variance, 50.0);
+        assert_eq!(over.variance_pct, Some(25.0));
+
+        let under = BudgetVariance::new(300.0, 200.0);
+        assert_eq!(under.variance, -100.0);
+        assert_eq!(under.variance_pct, Some(-33.33));
+
+        // Unplanned spending has no meaningful percentage
+        let unplanned = BudgetVariance::new(0.0, 40.0);
+        assert_eq!(unplanned.variance_pct, None);
+    }
+
+    #[test]
+    fn test_report_compares_months_and_categories() {
+        let budget = budget(vec![
+            line(BudgetLineKind::Income, None, 1, 1000.0),
+            line(BudgetLineKind::Income, None, 2, 1000.0),
+            line(BudgetLineKind::Expense, Some(ExpenseCategory::Software), 1, 100.0),
+            line(BudgetLineKind::Expense, Some(ExpenseCategory::Travel), 2, 300.0),
+        ]);
+
+        let mut actuals = BudgetActuals::default();
+        actuals.income_by_month.insert(1, 1200.0);
+        actuals.expenses.insert((1, "software".to_string()), 150.0);
+        actuals.expenses.insert((2, "marketing".to_string()), 80.0);
+
+        let report = BudgetReport::build(&budget, &actuals);
+
+        assert_eq!(report.months.len(), 12);
+        let january = &report.months[0];
+        assert_eq!(january.income, BudgetVariance::new(1000.0, 1200.0));
+        assert_eq!(january.expenses.variance_pct, Some(50.0));
+        assert_eq!(january.net, BudgetVariance::new(900.0, 1050.0));
+
+        let february = &report.months[1];
+        assert_eq!(february.income.variance_pct, Some(-100.0));
+        assert_eq!(february.expenses, BudgetVariance::new(300.0, 80.0));
+
+        // Planned and unplanned categories both appear
+        let categories: Vec<&str> = report.categories.iter().map(|c| c.category.as_str()).collect();
+        assert_eq!(categories, ["marketing", "software", "travel"]);
+        assert_eq!(report.categories[0].expenses.variance_pct, None);
+
+        assert_eq!(report.income, BudgetVariance::new(2000.0, 1200.0));
+        assert_eq!(report.expenses, BudgetVariance::new(400.0, 230.0));
+        assert_eq!(report.net, BudgetVariance::new(1600.0, 970.0));
+    }
+
+    fn limit(period: BudgetPeriod, amount: f64) -> BudgetLimit {
+        BudgetLimit {
+            id: Uuid::new_v4(),
+            category: ExpenseCategory::Travel,
+            period,
+            amount,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_period_bounds() {
+        let date = NaiveDate::from_ymd_opt(2025, 8, 14).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+
+        assert_eq!(BudgetPeriod::Monthly.bounds(date), (day(8, 1), day(8, 31)));
+        assert_eq!(BudgetPeriod::Quarterly.bounds(date), (day(7, 1), day(9, 30)));
+        assert_eq!(BudgetPeriod::Quarterly.bounds(day(12, 31)), (day(10, 1), day(12, 31)));
+        assert_eq!(BudgetPeriod::Monthly.bounds(day(2, 3)), (day(2, 1), day(2, 28)));
+    }
+
+    #[test]
+    fn test_utilization_thresholds() {
+        let start = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+        let travel = limit(BudgetPeriod::Quarterly, 500.0);
+
+        let on_track = BudgetUtilization::new(&travel, start, end, 399.99);
+        assert_eq!(on_track.status, UtilizationStatus::OnTrack);
+        assert!(on_track.crossed_thresholds().is_empty());
+
+        let warning = BudgetUtilization::new(&travel, start, end, 400.0);
+        assert_eq!(warning.utilization_pct, 80.0);
+        assert_eq!(warning.status, UtilizationStatus::Warning);
+        assert_eq!(warning.crossed_thresholds(), [80]);
+
+        let exceeded = BudgetUtilization::new(&travel, start, end, 612.5);
+        assert_eq!(exceeded.utilization_pct, 122.5);
+        assert_eq!(exceeded.remaining, -112.5);
+        assert_eq!(exceeded.status, UtilizationStatus::Exceeded);
+        assert_eq!(exceeded.crossed_thresholds(), [80, 100]);
+    }
+}
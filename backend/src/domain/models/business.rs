@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Request header selecting the business a request acts on
+pub const BUSINESS_HEADER: &str = "X-Business-Id";
+
+/// Query parameter alternative to [`BUSINESS_HEADER`], e.g. for download links
+pub const BUSINESS_QUERY_PARAM: &str = "business_id";
+
+/// A business under a login. The login account is the primary business; additional
+/// businesses have their own invoice sequences, branding, tax settings and reports.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Business {
+    pub id: Uuid,
+    pub name: String,
+    pub is_primary: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateBusiness {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateBusiness {
+    pub name: String,
+}
+
+/// Address for a business account: a plus-address of the owner's email, so mail
+/// sent to the business still reaches the owner's inbox
+pub fn business_account_email(owner_email: &str, business_id: Uuid) -> String {
+    let tag = format!("business-{}", business_id.simple());
+    match owner_email.rsplit_once('@') {
+        Some((local, domain)) => format!("{}+{}@{}", local, tag, domain),
+        None => format!("{}+{}", owner_email, tag),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_business_account_email_is_plus_addressed() {
+        let id = Uuid::parse_str("6f1c2a9e-1b2c-4d3e-8f40-5a6b7c8d9e0f").unwrap();
+        assert_eq!(
+            business_account_email("jane@example.com", id),
+            "jane+business-6f1c2a9e1b2c4d3e8f405a6b7c8d9e0f@example.com"
+        );
+        assert_eq!(
+            business_account_email("jane+work@example.com", id),
+            "jane+work+business-6f1c2a9e1b2c4d3e8f405a6b7c8d9e0f@example.com"
+        );
+    }
+}
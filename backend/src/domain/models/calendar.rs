@@ -0,0 +1,180 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// Longest content line allowed before folding (RFC 5545 §3.1), in octets
+const MAX_LINE_OCTETS: usize = 75;
+
+const PRODUCT_ID: &str = "-//FlashBill//Invoices//EN";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarContact {
+    pub name: String,
+    pub email: String,
+}
+
+/// An all-day event, such as an invoice due date
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Stable across updates, so a re-sent event replaces the earlier copy
+    pub uid: String,
+    pub date: NaiveDate,
+    pub summary: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub organizer: Option<CalendarContact>,
+    pub attendee: Option<CalendarContact>,
+    /// Days before the event to show a reminder
+    pub reminder_days_before: Option<u32>,
+}
+
+/// An iCalendar (RFC 5545) document. Email attachments set a method; calendar
+/// feeds leave it out.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Calendar {
+    pub name: Option<String>,
+    pub method: Option<&'static str>,
+    pub events: Vec<CalendarEvent>,
+}
+
+impl Calendar {
+    /// Render with CRLF line endings and folded lines. `stamp` is when the document
+    /// was generated.
+    pub fn render(&self, stamp: DateTime<Utc>) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODUCT_ID),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        if let Some(method) = self.method {
+            lines.push(format!("METHOD:{}", method));
+        }
+        if let Some(name) = &self.name {
+            lines.push(format!("X-WR-CALNAME:{}", escape_text(name)));
+        }
+
+        let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+        for event in &self.events {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}", escape_text(&event.uid)));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART;VALUE=DATE:{}", event.date.format("%Y%m%d")));
+            if let Some(next_day) = event.date.succ_opt() {
+                lines.push(format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")));
+            }
+            lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+            if let Some(description) = &event.description {
+                lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+            }
+            if let Some(url) = &event.url {
+                lines.push(format!("URL:{}", url));
+            }
+            if let Some(organizer) = &event.organizer {
+                lines.push(format!("ORGANIZER;CN={}:mailto:{}", quote_param(&organizer.name), organizer.email));
+            }
+            if let Some(attendee) = &event.attendee {
+                lines.push(format!(
+                    "ATTENDEE;CN={};ROLE=REQ-PARTICIPANT:mailto:{}",
+                    quote_param(&attendee.name),
+                    attendee.email
+                ));
+            }
+            lines.push("TRANSP:TRANSPARENT".to_string());
+            if let Some(days) = event.reminder_days_before {
+                lines.push("BEGIN:VALARM".to_string());
+                lines.push("ACTION:DISPLAY".to_string());
+                lines.push(format!("DESCRIPTION:{}", escape_text(&event.summary)));
+                lines.push(format!("TRIGGER:-P{}D", days));
+                lines.push("END:VALARM".to_string());
+            }
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines.iter().map(|line| fold_line(line)).collect::<Vec<_>>().concat()
+    }
+}
+
+/// Escape a TEXT value: backslash, comma, semicolon and newlines
+fn escape_text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ',' => escaped.push_str("\\,"),
+            ';' => escaped.push_str("\\;"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Quote a parameter value such as CN; double quotes aren't allowed inside
+fn quote_param(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'").replace(['\r', '\n'], " "))
+}
+
+/// Split a content line into 75-octet pieces, continuation lines starting with a
+/// space, without breaking a UTF-8 character
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            // The leading space counts toward the continuation line
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> CalendarEvent {
+        CalendarEvent {
+            uid: "invoice-1@flashbill".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+            summary: "Invoice #INV-1 due: USD 1,200.00".to_string(),
+            description: Some("Pay online; thanks!\nAcme".to_string()),
+            url: None,
+            organizer: Some(CalendarContact { name: "Acme, Inc".to_string(), email: "billing@acme.test".to_string() }),
+            attendee: Some(CalendarContact { name: "Client".to_string(), email: "client@example.com".to_string() }),
+            reminder_days_before: Some(1),
+        }
+    }
+
+    #[test]
+    fn renders_all_day_event_with_escaped_text() {
+        let calendar = Calendar { method: Some("PUBLISH"), events: vec![event()], ..Default::default() };
+        let stamp = DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+        let ics = calendar.render(stamp);
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("METHOD:PUBLISH\r\n"));
+        assert!(ics.contains("DTSTAMP:20260101T000000Z\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260331\r\nDTEND;VALUE=DATE:20260401\r\n"));
+        assert!(ics.contains("SUMMARY:Invoice #INV-1 due: USD 1\\,200.00\r\n"));
+        assert!(ics.contains("DESCRIPTION:Pay online\\; thanks!\\nAcme\r\n"));
+        assert!(ics.contains("ORGANIZER;CN=\"Acme, Inc\":mailto:billing@acme.test\r\n"));
+        assert!(ics.contains("TRIGGER:-P1D\r\n"));
+        assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn folds_long_lines_on_character_boundaries() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_line(&line);
+
+        for piece in folded.trim_end_matches("\r\n").split("\r\n") {
+            assert!(piece.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+    }
+}
@@ -0,0 +1,354 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::invoice_template::render_placeholders;
+
+/// Variables supported in campaign subjects and bodies, e.g. `{{client_name}} owes {{outstanding_balance}}`
+pub const CAMPAIGN_VARIABLES: [&str; 6] = [
+    "client_name",
+    "company_name",
+    "outstanding_balance",
+    "open_invoices",
+    "oldest_due_date",
+    "days_overdue",
+];
+
+/// Default and bounds for the pause between two campaign emails
+pub const CAMPAIGN_DEFAULT_INTERVAL_SECS: i64 = 30;
+pub const CAMPAIGN_MAX_INTERVAL_SECS: i64 = 3600;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignStatus {
+    Scheduled,
+    Sending,
+    Completed,
+    Cancelled,
+}
+
+impl CampaignStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampaignStatus::Scheduled => "scheduled",
+            CampaignStatus::Sending => "sending",
+            CampaignStatus::Completed => "completed",
+            CampaignStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "scheduled" => Some(CampaignStatus::Scheduled),
+            "sending" => Some(CampaignStatus::Sending),
+            "completed" => Some(CampaignStatus::Completed),
+            "cancelled" => Some(CampaignStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignRecipientStatus {
+    Pending,
+    /// Claimed by a sender
+    Sending,
+    Sent,
+    Failed,
+    /// Not sent: the balance was settled first, or the campaign was cancelled
+    Skipped,
+}
+
+impl CampaignRecipientStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampaignRecipientStatus::Pending => "pending",
+            CampaignRecipientStatus::Sending => "sending",
+            CampaignRecipientStatus::Sent => "sent",
+            CampaignRecipientStatus::Failed => "failed",
+            CampaignRecipientStatus::Skipped => "skipped",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(CampaignRecipientStatus::Pending),
+            "sending" => Some(CampaignRecipientStatus::Sending),
+            "sent" => Some(CampaignRecipientStatus::Sent),
+            "failed" => Some(CampaignRecipientStatus::Failed),
+            "skipped" => Some(CampaignRecipientStatus::Skipped),
+            _ => None,
+        }
+    }
+}
+
+/// Built-in wording, from a gentle nudge to a last notice. Subject and body can be
+/// overridden per campaign.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CampaignTemplate {
+    Friendly,
+    Firm,
+    FinalNotice,
+}
+
+impl CampaignTemplate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CampaignTemplate::Friendly => "friendly",
+            CampaignTemplate::Firm => "firm",
+            CampaignTemplate::FinalNotice => "final_notice",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "friendly" => Some(CampaignTemplate::Friendly),
+            "firm" => Some(CampaignTemplate::Firm),
+            "final_notice" => Some(CampaignTemplate::FinalNotice),
+            _ => None,
+        }
+    }
+
+    pub fn subject(&self) -> &'static str {
+        match self {
+            CampaignTemplate::Friendly => "A quick note about your balance with {{company_name}}",
+            CampaignTemplate::Firm => "Outstanding balance of {{outstanding_balance}}",
+            CampaignTemplate::FinalNotice => "Final notice: {{outstanding_balance}} overdue",
+        }
+    }
+
+    pub fn body(&self) -> &'static str {
+        match self {
+            CampaignTemplate::Friendly => {
+                "Hi {{client_name}},\n\nJust a friendly reminder that {{outstanding_balance}} is outstanding across {{open_invoices}} invoice(s). If you've already paid, thank you and please ignore this note.\n\n{{company_name}}"
+            }
+            CampaignTemplate::Firm => {
+                "Hello {{client_name}},\n\nOur records show {{outstanding_balance}} outstanding across {{open_invoices}} invoice(s), the oldest due on {{oldest_due_date}}. Please arrange payment at your earliest convenience.\n\n{{company_name}}"
+            }
+            CampaignTemplate::FinalNotice => {
+                "Hello {{client_name}},\n\nYour balance of {{outstanding_balance}} is now {{days_overdue}} days overdue. Please settle it promptly to avoid further action.\n\n{{company_name}}"
+            }
+        }
+    }
+}
+
+/// Which clients a campaign targets. Only clients with an email address and an
+/// open balance are ever included.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CampaignSegment {
+    /// Minimum outstanding balance
+    pub min_balance: Option<f64>,
+    /// Oldest open invoice at least this many days past due
+    pub min_days_overdue: Option<i64>,
+    pub max_days_overdue: Option<i64>,
+    /// Restrict to these clients
+    pub client_ids: Option<Vec<Uuid>>,
+}
+
+/// A client in a campaign audience, with their current open balance
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct CampaignAudienceMember {
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub email: String,
+    pub outstanding_balance: f64,
+    pub open_invoices: i64,
+    pub oldest_due_date: NaiveDate,
+}
+
+impl CampaignAudienceMember {
+    pub fn days_overdue(&self, today: NaiveDate) -> i64 {
+        (today - self.oldest_due_date).num_days().max(0)
+    }
+
+    /// Render a subject or body for this client
+    pub fn render(&self, template: &str, company_name: &str, today: NaiveDate) -> String {
+        render_placeholders(template, |name| match name {
+            "client_name" => Some(self.client_name.clone()),
+            "company_name" => Some(company_name.to_string()),
+            "outstanding_balance" => Some(format!("{:.2}", self.outstanding_balance)),
+            "open_invoices" => Some(self.open_invoices.to_string()),
+            "oldest_due_date" => Some(self.oldest_due_date.to_string()),
+            "days_overdue" => Some(self.days_overdue(today).to_string()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignMessage {
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PreviewCampaign {
+    #[serde(default)]
+    pub segment: CampaignSegment,
+    pub template: CampaignTemplate,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignPreview {
+    pub recipients: Vec<CampaignAudienceMember>,
+    pub total_outstanding: f64,
+    /// The email the first recipient would get
+    pub sample: Option<CampaignMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateCampaign {
+    pub name: String,
+    #[serde(default)]
+    pub segment: CampaignSegment,
+    pub template: CampaignTemplate,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    /// Pause between two emails, in seconds
+    pub send_interval_seconds: Option<i64>,
+    /// When the first email goes out; now if omitted
+    pub start_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Campaign {
+    pub id: Uuid,
+    pub name: String,
+    pub template: CampaignTemplate,
+    pub subject_template: String,
+    pub body_template: String,
+    pub segment: CampaignSegment,
+    pub send_interval_seconds: i32,
+    pub status: CampaignStatus,
+    pub start_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignRecipient {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub email: String,
+    pub targeted_balance: f64,
+    pub sent_balance: Option<f64>,
+    pub status: CampaignRecipientStatus,
+    pub error: Option<String>,
+    pub send_after: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    /// Payments received from the client since the email went out
+    pub paid_since_sent: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CampaignSummary {
+    pub recipients: usize,
+    pub pending: usize,
+    pub sent: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub targeted_balance: f64,
+    /// Payments received from emailed clients since their email
+    pub collected: f64,
+    pub paying_clients: usize,
+}
+
+impl CampaignSummary {
+    pub fn from_recipients(recipients: &[CampaignRecipient]) -> Self {
+        let mut summary = CampaignSummary {
+            recipients: recipients.len(),
+            ..Default::default()
+        };
+        for recipient in recipients {
+            match recipient.status {
+                CampaignRecipientStatus::Pending | CampaignRecipientStatus::Sending => summary.pending += 1,
+                CampaignRecipientStatus::Sent => summary.sent += 1,
+                CampaignRecipientStatus::Failed => summary.failed += 1,
+                CampaignRecipientStatus::Skipped => summary.skipped += 1,
+            }
+            summary.targeted_balance += recipient.targeted_balance;
+            summary.collected += recipient.paid_since_sent;
+            if recipient.paid_since_sent > 0.0 {
+                summary.paying_clients += 1;
+            }
+        }
+        summary
+    }
+}
+
+/// Campaign with per-recipient delivery and payment outcomes
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CampaignReport {
+    #[serde(flatten)]
+    pub campaign: Campaign,
+    pub summary: CampaignSummary,
+    pub recipients: Vec<CampaignRecipient>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member() -> CampaignAudienceMember {
+        CampaignAudienceMember {
+            client_id: Uuid::new_v4(),
+            client_name: "Acme Corp".to_string(),
+            email: "billing@acme.test".to_string(),
+            outstanding_balance: 1250.5,
+            open_invoices: 3,
+            oldest_due_date: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_campaign_variables() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 2).unwrap();
+        let body = member().render(CampaignTemplate::FinalNotice.body(), "Seller Co", today);
+        assert!(body.starts_with("Hello Acme Corp,"));
+        assert!(body.contains("1250.50 is now 30 days overdue"));
+        assert!(body.ends_with("Seller Co"));
+
+        let subject = member().render(CampaignTemplate::Firm.subject(), "Seller Co", today);
+        assert_eq!(subject, "Outstanding balance of 1250.50");
+    }
+
+    #[test]
+    fn test_days_overdue_is_never_negative() {
+        let early = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(member().days_overdue(early), 0);
+    }
+
+    #[test]
+    fn test_summary_counts_outcomes_and_collections() {
+        let recipient = |status, paid| CampaignRecipient {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            client_name: "Acme".to_string(),
+            email: "a@acme.test".to_string(),
+            targeted_balance: 100.0,
+            sent_balance: None,
+            status,
+            error: None,
+            send_after: Utc::now(),
+            sent_at: None,
+            paid_since_sent: paid,
+        };
+        let summary = CampaignSummary::from_recipients(&[
+            recipient(CampaignRecipientStatus::Sent, 60.0),
+            recipient(CampaignRecipientStatus::Sent, 0.0),
+            recipient(CampaignRecipientStatus::Failed, 0.0),
+            recipient(CampaignRecipientStatus::Pending, 0.0),
+            recipient(CampaignRecipientStatus::Skipped, 0.0),
+        ]);
+        assert_eq!((summary.sent, summary.failed, summary.pending, summary.skipped), (2, 1, 1, 1));
+        assert_eq!(summary.targeted_balance, 500.0);
+        assert_eq!(summary.collected, 60.0);
+        assert_eq!(summary.paying_clients, 1);
+    }
+}
@@ -0,0 +1,436 @@
+use chrono::{Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::domain::models::invoice_totals::round_money;
+
+/// Days ahead each forecast total covers
+pub const CASHFLOW_HORIZONS: [i64; 3] = [30, 60, 90];
+
+/// Months of invoice, payment and expense history the forecast learns from
+pub const CASHFLOW_HISTORY_MONTHS: u32 = 12;
+
+/// Fewest past occurrences before a series counts as recurring
+const MIN_RECURRING_OCCURRENCES: usize = 3;
+
+/// An unpaid balance on a sent invoice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenReceivable {
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub client_id: Uuid,
+    pub due_date: NaiveDate,
+    pub balance: f64,
+}
+
+/// Average days between due date and payment over a client's paid invoices.
+/// Negative means the client usually pays early.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientPaymentDelay {
+    pub client_id: Uuid,
+    pub avg_delay_days: f64,
+    pub paid_invoices: i64,
+}
+
+/// A past invoice or expense. Entries sharing a `series` are checked for a
+/// regular cadence.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashflowHistoryEntry {
+    pub series: String,
+    pub label: String,
+    pub client_id: Option<Uuid>,
+    pub date: NaiveDate,
+    pub amount: f64,
+    /// Days from issue to due date; zero for expenses
+    pub terms_days: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CashflowInputs {
+    pub open_invoices: Vec<OpenReceivable>,
+    pub payment_delays: Vec<ClientPaymentDelay>,
+    pub invoice_history: Vec<CashflowHistoryEntry>,
+    pub expense_history: Vec<CashflowHistoryEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CashflowDirection {
+    In,
+    Out,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CashflowSource {
+    OpenInvoice,
+    RecurringInvoice,
+    RecurringExpense,
+}
+
+/// One expected movement of cash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct CashflowItem {
+    pub date: NaiveDate,
+    pub direction: CashflowDirection,
+    pub source: CashflowSource,
+    pub amount: f64,
+    /// Invoice number, client name or vendor
+    pub label: String,
+    pub invoice_id: Option<Uuid>,
+    pub client_id: Option<Uuid>,
+    /// The client's average payment delay applied to the due date
+    pub delay_days: Option<i64>,
+}
+
+/// Expected totals from today through `end_date`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct CashflowHorizon {
+    pub days: i64,
+    pub end_date: NaiveDate,
+    pub expected_in: f64,
+    pub expected_out: f64,
+    pub net: f64,
+    pub open_invoices: f64,
+    pub recurring_invoices: f64,
+    pub recurring_expenses: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CashflowForecast {
+    pub as_of: NaiveDate,
+    pub horizons: Vec<CashflowHorizon>,
+    /// Every expected movement within the longest horizon, by date
+    pub items: Vec<CashflowItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cadence {
+    /// Calendar months, so monthly bills stay on their day of the month
+    Months(u32),
+    Days(i64),
+}
+
+impl Cadence {
+    /// The `n`th occurrence after `from`
+    pub fn step(&self, from: NaiveDate, n: u32) -> Option<NaiveDate> {
+        match *self {
+            Cadence::Months(months) => from.checked_add_months(Months::new(months * n)),
+            Cadence::Days(days) => from.checked_add_signed(Duration::days(days * i64::from(n))),
+        }
+    }
+
+    fn approx_days(&self) -> i64 {
+        match *self {
+            Cadence::Months(months) => i64::from(months) * 30,
+            Cadence::Days(days) => days,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurringPattern {
+    pub cadence: Cadence,
+    pub last_date: NaiveDate,
+    /// Mean of the last three amounts
+    pub amount: f64,
+}
+
+/// Finds a regular cadence in dated amounts: at least three occurrences, a
+/// typical gap between a week and a quarter, and every gap within a fifth of it
+/// (at least three days). A series that has missed two occurrences by `today` has
+/// lapsed and is not projected.
+pub fn detect_recurrence(occurrences: &[(NaiveDate, f64)], today: NaiveDate) -> Option<RecurringPattern> {
+    if occurrences.len() < MIN_RECURRING_OCCURRENCES {
+        return None;
+    }
+
+    let mut sorted = occurrences.to_vec();
+    sorted.sort_by_key(|(date, _)| *date);
+
+    let mut gaps: Vec<i64> = sorted.windows(2).map(|pair| (pair[1].0 - pair[0].0).num_days()).collect();
+    gaps.sort_unstable();
+    let median = gaps[gaps.len() / 2];
+    if !(7..=92).contains(&median) {
+        return None;
+    }
+
+    let tolerance = (median / 5).max(3);
+    if gaps.iter().any(|gap| (gap - median).abs() > tolerance) {
+        return None;
+    }
+
+    let cadence = match median {
+        27..=33 => Cadence::Months(1),
+        58..=64 => Cadence::Months(2),
+        86..=92 => Cadence::Months(3),
+        days => Cadence::Days(days),
+    };
+
+    let (last_date, _) = *sorted.last()?;
+    if (today - last_date).num_days() > cadence.approx_days() * 2 {
+        return None;
+    }
+
+    let recent: Vec<f64> = sorted.iter().rev().take(3).map(|(_, amount)| *amount).collect();
+    let amount = round_money(recent.iter().sum::<f64>() / recent.len() as f64);
+
+    Some(RecurringPattern { cadence, last_date, amount })
+}
+
+/// Projects open invoices, recurring invoices and recurring expenses over the
+/// next 90 days. Invoices are expected on their due date shifted by the client's
+/// average payment delay; anything expected in the past is expected today.
+pub fn build_cashflow_forecast(inputs: CashflowInputs, today: NaiveDate) -> CashflowForecast {
+    let longest = CASHFLOW_HORIZONS.iter().copied().max().unwrap_or(0);
+    let end = today + Duration::days(longest);
+
+    let delays: HashMap<Uuid, i64> = inputs
+        .payment_delays
+        .iter()
+        .map(|delay| (delay.client_id, delay.avg_delay_days.round() as i64))
+        .collect();
+    let expected = |due: NaiveDate, client_id: Option<Uuid>| {
+        let delay = client_id.and_then(|id| delays.get(&id).copied());
+        let date = (due + Duration::days(delay.unwrap_or(0))).max(today);
+        (date, delay)
+    };
+
+    let mut items = Vec::new();
+
+    for invoice in &inputs.open_invoices {
+        let (date, delay_days) = expected(invoice.due_date, Some(invoice.client_id));
+        items.push(CashflowItem {
+            date,
+            direction: CashflowDirection::In,
+            source: CashflowSource::OpenInvoice,
+            amount: round_money(invoice.balance),
+            label: invoice.invoice_number.clone(),
+            invoice_id: Some(invoice.invoice_id),
+            client_id: Some(invoice.client_id),
+            delay_days,
+        });
+    }
+
+    for (series, pattern) in recurring_series(&inputs.invoice_history, today) {
+        for issue_date in occurrences(&pattern, today, end) {
+            let (date, delay_days) = expected(issue_date + Duration::days(series.terms_days), series.client_id);
+            items.push(CashflowItem {
+                date,
+                direction: CashflowDirection::In,
+                source: CashflowSource::RecurringInvoice,
+                amount: pattern.amount,
+                label: series.label.clone(),
+                invoice_id: None,
+                client_id: series.client_id,
+                delay_days,
+            });
+        }
+    }
+
+    for (series, pattern) in recurring_series(&inputs.expense_history, today) {
+        for date in occurrences(&pattern, today, end) {
+            items.push(CashflowItem {
+                date,
+                direction: CashflowDirection::Out,
+                source: CashflowSource::RecurringExpense,
+                amount: pattern.amount,
+                label: series.label.clone(),
+                invoice_id: None,
+                client_id: None,
+                delay_days: None,
+            });
+        }
+    }
+
+    items.retain(|item| item.date <= end);
+    items.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.label.cmp(&b.label)));
+
+    let horizons = CASHFLOW_HORIZONS
+        .iter()
+        .map(|&days| {
+            let end_date = today + Duration::days(days);
+            let sum = |source: CashflowSource| {
+                round_money(
+                    items
+                        .iter()
+                        .filter(|item| item.source == source && item.date <= end_date)
+                        .fold(0.0, |total, item| total + item.amount),
+                )
+            };
+            let open_invoices = sum(CashflowSource::OpenInvoice);
+            let recurring_invoices = sum(CashflowSource::RecurringInvoice);
+            let recurring_expenses = sum(CashflowSource::RecurringExpense);
+            let expected_in = round_money(open_invoices + recurring_invoices);
+
+            CashflowHorizon {
+                days,
+                end_date,
+                expected_in,
+                expected_out: recurring_expenses,
+                net: round_money(expected_in - recurring_expenses),
+                open_invoices,
+                recurring_invoices,
+                recurring_expenses,
+            }
+        })
+        .collect();
+
+    CashflowForecast { as_of: today, horizons, items }
+}
+
+/// The most recent entry of each recurring series, with its pattern
+fn recurring_series(history: &[CashflowHistoryEntry], today: NaiveDate) -> Vec<(&CashflowHistoryEntry, RecurringPattern)> {
+    let mut by_series: BTreeMap<&str, Vec<&CashflowHistoryEntry>> = BTreeMap::new();
+    for entry in history {
+        by_series.entry(entry.series.as_str()).or_default().push(entry);
+    }
+
+    by_series
+        .into_values()
+        .filter_map(|entries| {
+            let dated: Vec<(NaiveDate, f64)> = entries.iter().map(|entry| (entry.date, entry.amount)).collect();
+            let pattern = detect_recurrence(&dated, today)?;
+            let latest = entries.into_iter().max_by_key(|entry| entry.date)?;
+            Some((latest, pattern))
+        })
+        .collect()
+}
+
+/// Occurrences after the last one, up to `end`. One already missed is still
+/// expected, so it moves to today.
+fn occurrences(pattern: &RecurringPattern, today: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    (1..)
+        .map_while(|n| pattern.cadence.step(pattern.last_date, n))
+        .take_while(|date| *date <= end)
+        .map(|date| date.max(today))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn entry(series: &str, client_id: Option<Uuid>, day: &str, amount: f64, terms_days: i64) -> CashflowHistoryEntry {
+        CashflowHistoryEntry {
+            series: series.to_string(),
+            label: series.to_string(),
+            client_id,
+            date: date(day),
+            amount,
+            terms_days,
+        }
+    }
+
+    #[test]
+    fn test_detects_monthly_and_weekly_cadences() {
+        let today = date("2025-04-20");
+
+        let monthly = [
+            (date("2025-01-31"), 100.0),
+            (date("2025-02-28"), 110.0),
+            (date("2025-03-31"), 120.0),
+        ];
+        let pattern = detect_recurrence(&monthly, today).unwrap();
+        assert_eq!(pattern.cadence, Cadence::Months(1));
+        assert_eq!(pattern.last_date, date("2025-03-31"));
+        assert_eq!(pattern.amount, 110.0);
+
+        let weekly = [
+            (date("2025-04-17"), 50.0),
+            (date("2025-04-03"), 50.0),
+            (date("2025-04-10"), 50.0),
+        ];
+        assert_eq!(detect_recurrence(&weekly, today).unwrap().cadence, Cadence::Days(7));
+
+        // Too few occurrences, irregular gaps, and a series that stopped
+        assert_eq!(detect_recurrence(&monthly[..2], today), None);
+        let irregular = [(date("2025-01-01"), 10.0), (date("2025-01-09"), 10.0), (date("2025-03-20"), 10.0)];
+        assert_eq!(detect_recurrence(&irregular, today), None);
+        assert_eq!(detect_recurrence(&monthly, date("2025-07-01")), None);
+    }
+
+    #[test]
+    fn test_open_invoices_shift_by_client_delay() {
+        let today = date("2025-05-01");
+        let slow = Uuid::new_v4();
+        let unknown = Uuid::new_v4();
+        let receivable = |client_id, due: &str, balance| OpenReceivable {
+            invoice_id: Uuid::new_v4(),
+            invoice_number: format!("INV-{}", due),
+            client_id,
+            due_date: date(due),
+            balance,
+        };
+
+        let forecast = build_cashflow_forecast(
+            CashflowInputs {
+                open_invoices: vec![
+                    receivable(slow, "2025-05-10", 500.0),
+                    receivable(unknown, "2025-04-01", 200.0),
+                    receivable(slow, "2025-07-25", 900.0),
+                ],
+                payment_delays: vec![ClientPaymentDelay { client_id: slow, avg_delay_days: 14.4, paid_invoices: 5 }],
+                ..Default::default()
+            },
+            today,
+        );
+
+        // Overdue with no history is expected today; the slow payer's invoice lands
+        // two weeks after it is due, and the last one falls past 90 days
+        let dates: Vec<_> = forecast.items.iter().map(|item| (item.date, item.delay_days)).collect();
+        assert_eq!(dates, vec![(today, None), (date("2025-05-24"), Some(14))]);
+
+        let totals: Vec<_> = forecast.horizons.iter().map(|h| (h.days, h.expected_in)).collect();
+        assert_eq!(totals, vec![(30, 700.0), (60, 700.0), (90, 700.0)]);
+    }
+
+    #[test]
+    fn test_recurring_series_are_projected() {
+        let today = date("2025-05-01");
+        let client = Uuid::new_v4();
+
+        let forecast = build_cashflow_forecast(
+            CashflowInputs {
+                payment_delays: vec![ClientPaymentDelay { client_id: client, avg_delay_days: 5.0, paid_invoices: 3 }],
+                invoice_history: vec![
+                    entry("retainer", Some(client), "2025-02-15", 1000.0, 14),
+                    entry("retainer", Some(client), "2025-03-15", 1000.0, 14),
+                    entry("retainer", Some(client), "2025-04-15", 1000.0, 14),
+                    entry("one-off", Some(client), "2025-04-02", 5000.0, 14),
+                ],
+                expense_history: vec![
+                    entry("rent", None, "2025-02-01", 800.0, 0),
+                    entry("rent", None, "2025-03-01", 800.0, 0),
+                    entry("rent", None, "2025-04-01", 800.0, 0),
+                ],
+                ..Default::default()
+            },
+            today,
+        );
+
+        // Retainer issued on the 15th, due 14 days later, paid 5 days after that
+        let retainer: Vec<_> = forecast
+            .items
+            .iter()
+            .filter(|item| item.source == CashflowSource::RecurringInvoice)
+            .map(|item| item.date)
+            .collect();
+        assert_eq!(retainer, vec![date("2025-06-03"), date("2025-07-04")]);
+
+        // Rent on the 1st of May, June and July
+        let rent = forecast.items.iter().filter(|item| item.direction == CashflowDirection::Out).count();
+        assert_eq!(rent, 3);
+
+        let thirty = &forecast.horizons[0];
+        assert_eq!((thirty.expected_in, thirty.expected_out, thirty.net), (0.0, 800.0, -800.0));
+        let ninety = &forecast.horizons[2];
+        assert_eq!((ninety.recurring_invoices, ninety.recurring_expenses, ninety.net), (2000.0, 2400.0, -400.0));
+    }
+}
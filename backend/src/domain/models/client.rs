@@ -1,10 +1,20 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
 use sqlx::{FromRow, Row};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidateEmail};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+use crate::domain::i18n::Locale;
+use crate::domain::models::fx::normalize_currency_code;
+use crate::domain::models::{eu_country_code, CreateInvoiceItem, PageRequest, TaxSetting, VatNumber, VatStatus};
+
+/// Most secondary billing contacts a client can have
+pub const MAX_BILLING_CONTACTS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct Client {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -25,15 +35,47 @@ pub struct Client {
     pub tax_exempt_certificate: Option<String>,
     pub notes: Option<String>,
 
-    pub total_invoiced: f64,
-    pub total_paid: f64,
+    pub total_invoiced: Decimal,
+    pub total_paid: Decimal,
     pub average_payment_days: Option<i32>,
 
+    // Parent company for subsidiaries
+    pub parent_client_id: Option<Uuid>,
+
+    // Excluded from automatic monthly statements
+    pub statement_opt_out: bool,
+
+    // Tried in order when an invoice email to `email` hard-bounces
+    pub billing_contacts: Vec<BillingContact>,
+
+    // Language and formats of this client's invoices and emails; None uses the user's
+    pub locale: Option<Locale>,
+
+    // What new invoices start with besides `payment_terms`; None uses the user's
+    // default tax, base currency and no discount
+    pub default_tax_id: Option<Uuid>,
+    pub default_currency: Option<String>,
+    pub default_discount_percent: Option<Decimal>,
+
+    // Rate for tracked time; an entry's own rate wins
+    pub hourly_rate: Option<Decimal>,
+
+    // EU VAT number and the outcome of its last VIES check
+    pub vat_number: Option<String>,
+    pub vat_status: Option<VatStatus>,
+    pub vat_registered_name: Option<String>,
+    pub vat_checked_at: Option<DateTime<Utc>>,
+
+    // Hidden from the client list; no new invoices
+    pub archived_at: Option<DateTime<Utc>>,
+    // In the trash; restorable
+    pub deleted_at: Option<DateTime<Utc>>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateClient {
     #[validate(length(min = 1, max = 255))]
     pub name: String,
@@ -50,9 +92,29 @@ pub struct CreateClient {
     pub tax_exempt: Option<bool>,
     pub tax_exempt_certificate: Option<String>,
     pub notes: Option<String>,
+
+    pub parent_client_id: Option<Uuid>,
+
+    #[serde(default)]
+    pub billing_contacts: Option<Vec<BillingContact>>,
+
+    #[serde(default)]
+    pub locale: Option<Locale>,
+
+    #[serde(default)]
+    pub default_tax_id: Option<Uuid>,
+    #[serde(default)]
+    pub default_currency: Option<String>,
+    #[serde(default)]
+    pub default_discount_percent: Option<Decimal>,
+    #[serde(default)]
+    pub hourly_rate: Option<Decimal>,
+    /// EU VAT number, checked against VIES when saved
+    #[serde(default)]
+    pub vat_number: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateClient {
     pub name: Option<String>,
     pub email: Option<String>,
@@ -63,28 +125,221 @@ pub struct UpdateClient {
     pub tax_exempt: Option<bool>,
     pub tax_exempt_certificate: Option<String>,
     pub notes: Option<String>,
+    pub statement_opt_out: Option<bool>,
+    /// Replaces the whole list; `[]` removes all secondary contacts
+    #[serde(default)]
+    pub billing_contacts: Option<Vec<BillingContact>>,
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// Tax setting new invoices use instead of the user's default
+    #[serde(default)]
+    pub default_tax_id: Option<Uuid>,
+    #[serde(default)]
+    pub default_currency: Option<String>,
+    /// Percent of the subtotal taken off new invoices; `0` removes it
+    #[serde(default)]
+    pub default_discount_percent: Option<Decimal>,
+    /// Rate for tracked time
+    #[serde(default)]
+    pub hourly_rate: Option<Decimal>,
+    /// EU VAT number, checked against VIES when it changes; `""` removes it
+    #[serde(default)]
+    pub vat_number: Option<String>,
+}
+
+impl Client {
+    /// The member state the client is in: their VAT number's, else a billing address
+    /// `country` given as an EU country code
+    pub fn vat_country(&self) -> Option<String> {
+        let from_vat_number = self
+            .vat_number
+            .as_deref()
+            .and_then(|vat_number| VatNumber::parse(vat_number).ok())
+            .map(|vat| vat.country_code().to_string());
+        from_vat_number.or_else(|| {
+            self.billing_address
+                .as_ref()
+                .and_then(|address| address.get("country"))
+                .and_then(|country| country.as_str())
+                .and_then(eu_country_code)
+        })
+    }
+
+    /// Whether sales from `seller_country` to this client are reverse charged: the
+    /// client's VAT number passed VIES and is registered in another member state
+    pub fn is_reverse_charged_from(&self, seller_country: Option<&str>) -> bool {
+        let Some(seller_country) = seller_country.and_then(eu_country_code) else {
+            return false;
+        };
+        self.vat_status == Some(VatStatus::Valid)
+            && self
+                .vat_number
+                .as_deref()
+                .and_then(|vat_number| VatNumber::parse(vat_number).ok())
+                .is_some_and(|vat| vat.country_code() != seller_country)
+    }
+}
+
+/// Secondary person at the client who can receive invoices
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BillingContact {
+    pub name: Option<String>,
+    pub email: String,
+}
+
+/// Trims and validates secondary contacts, dropping duplicates and any that
+/// repeat the primary address
+pub fn normalize_billing_contacts(
+    contacts: Vec<BillingContact>,
+    primary_email: Option<&str>,
+) -> Result<Vec<BillingContact>, String> {
+    let mut normalized: Vec<BillingContact> = Vec::with_capacity(contacts.len());
+    for contact in contacts {
+        let email = contact.email.trim().to_string();
+        if !email.validate_email() {
+            return Err(format!("Billing contact email '{}' is invalid", email));
+        }
+        let duplicate = primary_email.is_some_and(|primary| primary.trim().eq_ignore_ascii_case(&email))
+            || normalized.iter().any(|c| c.email.eq_ignore_ascii_case(&email));
+        if duplicate {
+            continue;
+        }
+        let name = contact.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        normalized.push(BillingContact { name, email });
+    }
+
+    if normalized.len() > MAX_BILLING_CONTACTS {
+        return Err(format!("A client can have at most {} billing contacts", MAX_BILLING_CONTACTS));
+    }
+    Ok(normalized)
+}
+
+/// Uppercases the default currency and checks the default discount is 0-100%
+pub fn normalize_invoice_defaults(
+    currency: Option<String>,
+    discount_percent: Option<Decimal>,
+) -> Result<(Option<String>, Option<Decimal>), String> {
+    let currency = match currency {
+        Some(code) => Some(normalize_currency_code(&code).ok_or_else(|| format!("Invalid currency code '{}'", code))?),
+        None => None,
+    };
+    if discount_percent.is_some_and(|d| d < Decimal::ZERO || d > Decimal::ONE_HUNDRED) {
+        return Err("Default discount must be between 0 and 100 percent".to_string());
+    }
+    Ok((currency, discount_percent))
+}
+
+/// What a new invoice for a client starts with. `POST /invoices` fills fields left out
+/// from these; anything sent with the invoice wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ClientInvoiceDefaults {
+    pub client_id: Uuid,
+    pub payment_terms: i32,
+    /// Issue date plus `payment_terms`
+    pub due_date: NaiveDate,
+    pub currency: String,
+    /// Tax setting new lines use; None when the client is tax exempt or no tax is set up
+    pub tax_setting_id: Option<Uuid>,
+    pub tax_label: Option<String>,
+    /// Rate for lines without one (0.07 for 7%)
+    pub tax_rate: Decimal,
+    pub discount_percent: Option<Decimal>,
+    pub locale: Locale,
+    /// Sold to a VAT-registered business in another member state: lines carry no
+    /// VAT and the PDF notes that the client accounts for it
+    pub reverse_charge: bool,
+    /// Member state the sale is reported under: the client's, else the user's
+    pub vat_country: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ClientInvoiceDefaults {
+    /// Tax-exempt and reverse-charged clients are never taxed by default. Otherwise the
+    /// client's own tax setting applies while it is active, then the user's default.
+    pub fn resolve(
+        client: &Client,
+        issue_date: NaiveDate,
+        base_currency: &str,
+        user_locale: Locale,
+        seller_country: Option<&str>,
+        client_tax: Option<TaxSetting>,
+        user_tax: Option<TaxSetting>,
+    ) -> Self {
+        let reverse_charge = client.is_reverse_charged_from(seller_country);
+        let tax = if client.tax_exempt || reverse_charge {
+            None
+        } else {
+            client_tax.filter(|t| t.is_active).or(user_tax)
+        };
+
+        Self {
+            client_id: client.id,
+            payment_terms: client.payment_terms,
+            due_date: issue_date + Duration::days(client.payment_terms.into()),
+            currency: client.default_currency.clone().unwrap_or_else(|| base_currency.to_string()),
+            tax_setting_id: tax.as_ref().map(|t| t.id),
+            tax_label: tax.as_ref().map(|t| t.label.clone()),
+            tax_rate: tax.and_then(|t| Decimal::from_f64(t.rate)).unwrap_or_default(),
+            discount_percent: client.default_discount_percent,
+            locale: client.locale.unwrap_or(user_locale),
+            reverse_charge,
+            vat_country: client.vat_country().or_else(|| seller_country.and_then(eu_country_code)),
+        }
+    }
+
+    /// The default discount as an amount off these lines
+    pub fn discount_for(&self, items: &[CreateInvoiceItem]) -> Option<Decimal> {
+        let percent = self.discount_percent?;
+        let subtotal: Decimal = items.iter().map(|item| item.quantity * item.unit_price).sum();
+        Some(subtotal * percent / Decimal::ONE_HUNDRED)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InvoiceDefaultsQuery {
+    /// Defaults to today
+    pub issue_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ClientListFilter {
     pub search: Option<String>,
+    pub parent_client_id: Option<Uuid>,
+    pub include_archived: Option<bool>,
+    /// Comma-separated client IDs to fetch in one call
+    pub ids: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// `next_cursor` of the previous page, to page by cursor instead of number
+    pub cursor: Option<String>,
+    /// Older names for `per_page` and the page's start
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ClientListFilter {
+    pub fn page_request(&self) -> Result<PageRequest, String> {
+        PageRequest::from_params(self.page, self.per_page, self.cursor.as_deref(), self.limit, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ClientResponse {
     pub id: Uuid,
     pub name: String,
     pub email: Option<String>,
     pub phone: Option<String>,
     pub company_name: Option<String>,
-    pub total_invoiced: f64,
-    pub total_paid: f64,
-    pub outstanding_balance: f64,
+    pub parent_client_id: Option<Uuid>,
+    pub total_invoiced: Decimal,
+    pub total_paid: Decimal,
+    pub outstanding_balance: Decimal,
     pub average_payment_days: Option<i32>,
     pub last_invoice_date: Option<DateTime<Utc>>,
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl FromRow<'_, sqlx::postgres::PgRow> for ClientResponse {
@@ -95,22 +350,232 @@ impl FromRow<'_, sqlx::postgres::PgRow> for ClientResponse {
             email: row.try_get("email")?,
             phone: row.try_get("phone")?,
             company_name: row.try_get("company_name")?,
+            parent_client_id: row.try_get("parent_client_id")?,
             total_invoiced: row.try_get("total_invoiced")?,
             total_paid: row.try_get("total_paid")?,
             outstanding_balance: row.try_get("outstanding_balance")?,
             average_payment_days: row.try_get("average_payment_days")?,
             last_invoice_date: row.try_get("last_invoice_date")?,
+            archived_at: row.try_get("archived_at")?,
             created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
         })
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ClientStats {
     pub total_clients: i64,
     pub active_clients: i64,
-    pub total_invoiced: f64,
-    pub total_paid: f64,
-    pub outstanding_balance: f64,
+    pub total_invoiced: Decimal,
+    pub total_paid: Decimal,
+    pub outstanding_balance: Decimal,
     pub avg_payment_days: f64,
+    /// Unused credit clients hold on account
+    pub credit_balance: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetClientParent {
+    pub parent_client_id: Option<Uuid>,
+}
+
+/// Balance of one client within a hierarchy statement
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientBalance {
+    pub client_id: Uuid,
+    pub name: String,
+    pub parent_client_id: Option<Uuid>,
+    pub total_invoiced: Decimal,
+    pub total_paid: Decimal,
+    pub outstanding_balance: Decimal,
+}
+
+/// Parent statement rolling up invoices of the client and all its subsidiaries
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientHierarchyStatement {
+    pub client_id: Uuid,
+    pub name: String,
+    pub total_invoiced: Decimal,
+    pub total_paid: Decimal,
+    pub outstanding_balance: Decimal,
+    pub balances: Vec<ClientBalance>,
+    pub invoices: Vec<crate::domain::models::InvoiceResponse>,
+}
+
+/// Client due an automatic monthly statement
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StatementRecipient {
+    pub user_id: Uuid,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub client_email: String,
+    pub seller_name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(email: &str) -> BillingContact {
+        BillingContact { name: Some(" Accounts ".to_string()), email: email.to_string() }
+    }
+
+    #[test]
+    fn test_billing_contacts_are_trimmed_and_deduplicated() {
+        let contacts = vec![
+            contact(" ap@client.com "),
+            contact("AP@client.com"),
+            contact("owner@client.com"),
+            contact("finance@client.com"),
+        ];
+        let normalized = normalize_billing_contacts(contacts, Some("Owner@Client.com")).unwrap();
+
+        let emails: Vec<&str> = normalized.iter().map(|c| c.email.as_str()).collect();
+        assert_eq!(emails, ["ap@client.com", "finance@client.com"]);
+        assert_eq!(normalized[0].name.as_deref(), Some("Accounts"));
+    }
+
+    #[test]
+    fn test_billing_contacts_are_validated() {
+        assert!(normalize_billing_contacts(vec![contact("not-an-email")], None).is_err());
+
+        let too_many = (0..=MAX_BILLING_CONTACTS).map(|i| contact(&format!("ap{}@client.com", i))).collect();
+        assert!(normalize_billing_contacts(too_many, None).is_err());
+    }
+
+    fn client() -> Client {
+        Client {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Acme".to_string(),
+            email: None,
+            phone: None,
+            company_name: None,
+            billing_address: None,
+            payment_terms: 14,
+            tax_exempt: false,
+            tax_exempt_certificate: None,
+            notes: None,
+            total_invoiced: Decimal::ZERO,
+            total_paid: Decimal::ZERO,
+            average_payment_days: None,
+            parent_client_id: None,
+            statement_opt_out: false,
+            billing_contacts: vec![],
+            locale: None,
+            default_tax_id: None,
+            default_currency: None,
+            default_discount_percent: None,
+            hourly_rate: None,
+            vat_number: None,
+            vat_status: None,
+            vat_registered_name: None,
+            vat_checked_at: None,
+            archived_at: None,
+            deleted_at: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn tax(label: &str, rate: f64, is_active: bool) -> TaxSetting {
+        TaxSetting {
+            id: Uuid::new_v4(),
+            organization_id: Uuid::new_v4(),
+            label: label.to_string(),
+            rate,
+            is_default: false,
+            is_active,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn item(quantity: i64, unit_price: i64) -> CreateInvoiceItem {
+        CreateInvoiceItem {
+            description: "Work".to_string(),
+            quantity: Decimal::from(quantity),
+            unit_price: Decimal::from(unit_price),
+            tax_rate: None,
+            section: None,
+            taxes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_invoice_defaults_fall_back_to_the_users() {
+        let issue_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let defaults = ClientInvoiceDefaults::resolve(&client(), issue_date, "USD", Locale::Id, None, None, Some(tax("Sales Tax", 0.07, true)));
+
+        assert_eq!(defaults.due_date, NaiveDate::from_ymd_opt(2025, 3, 15).unwrap());
+        assert_eq!(defaults.currency, "USD");
+        assert_eq!(defaults.tax_label.as_deref(), Some("Sales Tax"));
+        assert_eq!(defaults.tax_rate, Decimal::new(7, 2));
+        assert_eq!(defaults.locale, Locale::Id);
+        assert_eq!(defaults.discount_for(&[item(2, 50)]), None);
+    }
+
+    #[test]
+    fn test_client_invoice_defaults_win() {
+        let mut client = client();
+        client.default_currency = Some("EUR".to_string());
+        client.default_discount_percent = Some(Decimal::new(125, 1));
+        client.locale = Some(Locale::De);
+        let issue_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+
+        let defaults = ClientInvoiceDefaults::resolve(
+            &client, issue_date, "USD", Locale::En, None, Some(tax("VAT", 0.19, true)), Some(tax("Sales Tax", 0.07, true)),
+        );
+        assert_eq!(defaults.currency, "EUR");
+        assert_eq!(defaults.tax_label.as_deref(), Some("VAT"));
+        assert_eq!(defaults.locale, Locale::De);
+        assert_eq!(defaults.discount_for(&[item(2, 50), item(1, 100)]), Some(Decimal::from(25)));
+
+        // A deactivated tax setting gives way to the user's default
+        let defaults = ClientInvoiceDefaults::resolve(
+            &client, issue_date, "USD", Locale::En, None, Some(tax("VAT", 0.19, false)), Some(tax("Sales Tax", 0.07, true)),
+        );
+        assert_eq!(defaults.tax_label.as_deref(), Some("Sales Tax"));
+
+        client.tax_exempt = true;
+        let defaults = ClientInvoiceDefaults::resolve(
+            &client, issue_date, "USD", Locale::En, None, Some(tax("VAT", 0.19, true)), Some(tax("Sales Tax", 0.07, true)),
+        );
+        assert_eq!((defaults.tax_setting_id, defaults.tax_rate), (None, Decimal::ZERO));
+    }
+
+    #[test]
+    fn test_valid_vat_number_in_another_member_state_is_reverse_charged() {
+        let mut client = client();
+        client.vat_number = Some("FR40303265045".to_string());
+        client.vat_status = Some(VatStatus::Valid);
+        let issue_date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let resolve = |client: &Client, seller_country: Option<&str>| {
+            ClientInvoiceDefaults::resolve(client, issue_date, "EUR", Locale::En, seller_country, None, Some(tax("VAT", 0.19, true)))
+        };
+
+        let defaults = resolve(&client, Some("DE"));
+        assert!(defaults.reverse_charge);
+        assert_eq!((defaults.tax_label, defaults.tax_rate), (None, Decimal::ZERO));
+        assert_eq!(defaults.vat_country.as_deref(), Some("FR"));
+
+        // Domestic, unknown seller, and unconfirmed numbers are taxed as usual
+        assert!(!resolve(&client, Some("FR")).reverse_charge);
+        assert!(!resolve(&client, None).reverse_charge);
+        client.vat_status = Some(VatStatus::Unverified);
+        let defaults = resolve(&client, Some("DE"));
+        assert!(!defaults.reverse_charge);
+        assert_eq!(defaults.tax_rate, Decimal::new(19, 2));
+    }
+
+    #[test]
+    fn test_invoice_defaults_are_validated() {
+        let (currency, discount) = normalize_invoice_defaults(Some(" eur ".to_string()), Some(Decimal::from(10))).unwrap();
+        assert_eq!((currency.as_deref(), discount), (Some("EUR"), Some(Decimal::from(10))));
+
+        assert!(normalize_invoice_defaults(Some("EURO".to_string()), None).is_err());
+        assert!(normalize_invoice_defaults(None, Some(Decimal::from(101))).is_err());
+        assert!(normalize_invoice_defaults(None, Some(Decimal::from(-1))).is_err());
+    }
 }
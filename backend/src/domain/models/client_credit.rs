@@ -0,0 +1,155 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::normalize_currency_code;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientCreditKind {
+    /// Money received ahead of invoicing, such as a retainer
+    Deposit,
+    /// Credit used to settle an invoice
+    Applied,
+    /// Unused credit paid back to the client
+    Refund,
+}
+
+impl ClientCreditKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientCreditKind::Deposit => "deposit",
+            ClientCreditKind::Applied => "applied",
+            ClientCreditKind::Refund => "refund",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "deposit" => Some(ClientCreditKind::Deposit),
+            "applied" => Some(ClientCreditKind::Applied),
+            "refund" => Some(ClientCreditKind::Refund),
+            _ => None,
+        }
+    }
+}
+
+/// One movement on a client's credit balance. Deposits are positive; refunds and
+/// applied credit are negative.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientCreditEntry {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub kind: ClientCreditKind,
+    pub amount: Decimal,
+    pub currency: String,
+    /// The invoice applied credit settled
+    pub invoice_id: Option<Uuid>,
+    pub invoice_number: Option<String>,
+    pub description: Option<String>,
+    pub entry_date: NaiveDate,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct CreditBalance {
+    pub currency: String,
+    pub amount: Decimal,
+}
+
+/// A client's credit: what's available in each currency and every movement, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientCreditLedger {
+    pub client_id: Uuid,
+    pub balances: Vec<CreditBalance>,
+    pub entries: Vec<ClientCreditEntry>,
+}
+
+/// A deposit or refund
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateClientCredit {
+    pub amount: Decimal,
+    /// Defaults to the client's default currency, then the user's base currency
+    pub currency: Option<String>,
+    /// Defaults to today
+    pub entry_date: Option<NaiveDate>,
+    pub description: Option<String>,
+}
+
+/// Credit drawn from the client's balance to settle an invoice
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreditApplied {
+    pub invoice_id: Uuid,
+    pub currency: String,
+    /// Zero when there was no credit or nothing left to settle
+    pub applied: Decimal,
+    pub balance_due: Decimal,
+    /// Credit left in the invoice's currency
+    pub remaining_credit: Decimal,
+}
+
+/// When available client credit is applied to an invoice without being asked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditAutoApply {
+    /// As soon as the invoice is created, even as a draft
+    OnCreate,
+    /// When the invoice is sent, so the emailed PDF shows what's left to pay
+    #[default]
+    OnSend,
+    /// Only through `POST /invoices/{id}/apply-credit`
+    Manual,
+}
+
+/// Checks a deposit or refund amount and currency, returning the currency to use
+pub fn normalize_credit(amount: Decimal, currency: Option<&str>, default_currency: &str) -> Result<String, String> {
+    if amount < Decimal::new(1, 2) {
+        return Err("Amount must be at least 0.01".to_string());
+    }
+    if amount.round_dp(2) != amount {
+        return Err("Amount can't have more than two decimal places".to_string());
+    }
+    match currency {
+        Some(code) => normalize_currency_code(code).ok_or_else(|| format!("Invalid currency code '{}'", code)),
+        None => Ok(default_currency.to_string()),
+    }
+}
+
+/// Credit to apply to an invoice: as much of the balance due as the credit covers
+pub fn credit_to_apply(available: Decimal, balance_due: Decimal) -> Decimal {
+    available.min(balance_due).max(Decimal::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_credit_covers_what_it_can() {
+        assert_eq!(credit_to_apply(dec!(500), dec!(120.50)), dec!(120.50));
+        assert_eq!(credit_to_apply(dec!(80), dec!(120.50)), dec!(80));
+        assert_eq!(credit_to_apply(dec!(0), dec!(120.50)), dec!(0));
+        assert_eq!(credit_to_apply(dec!(80), dec!(0)), dec!(0));
+    }
+
+    #[test]
+    fn test_credit_amounts_and_currencies() {
+        assert_eq!(normalize_credit(dec!(1000), None, "USD"), Ok("USD".to_string()));
+        assert_eq!(normalize_credit(dec!(1000), Some(" eur "), "USD"), Ok("EUR".to_string()));
+        assert!(normalize_credit(dec!(0), None, "USD").is_err());
+        assert!(normalize_credit(dec!(-5), None, "USD").is_err());
+        assert!(normalize_credit(dec!(10.005), None, "USD").is_err());
+        assert!(normalize_credit(dec!(10), Some("EURO"), "USD").is_err());
+    }
+
+    #[test]
+    fn test_kinds_round_trip() {
+        for kind in [ClientCreditKind::Deposit, ClientCreditKind::Applied, ClientCreditKind::Refund] {
+            assert_eq!(ClientCreditKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(serde_json::to_value(CreditAutoApply::default()).unwrap(), "on_send");
+    }
+}
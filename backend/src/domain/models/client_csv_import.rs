@@ -0,0 +1,339 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::HashMap;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{normalize_optional_phone, ContactAddress, CreateClient, CsvTable};
+
+pub const MAX_CLIENT_CSV_BYTES: usize = 5 * 1024 * 1024;
+pub const MAX_CLIENT_CSV_ROWS: usize = 10_000;
+/// Files with more rows to create than this are imported by a background job
+pub const INLINE_CLIENT_IMPORT_ROWS: usize = 200;
+const MAX_PAYMENT_TERMS_DAYS: i32 = 365;
+
+/// Rejects files that are too large or are spreadsheets rather than CSV
+pub fn check_csv_upload(file_name: &str, data: &[u8], max_bytes: usize) -> Result<(), String> {
+    if data.len() > max_bytes {
+        return Err(format!("The file must be at most {} MB", max_bytes / (1024 * 1024)));
+    }
+    let lower_name = file_name.to_lowercase();
+    // XLSX files are zip archives; old .xls files are OLE documents
+    if lower_name.ends_with(".xlsx")
+        || lower_name.ends_with(".xls")
+        || data.starts_with(b"PK\x03\x04")
+        || data.starts_with(b"\xD0\xCF\x11\xE0")
+    {
+        return Err("Excel files aren't supported; save the sheet as CSV (UTF-8) and upload that".to_string());
+    }
+    Ok(())
+}
+
+/// A client column in the CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientColumn {
+    Name,
+    Email,
+    Phone,
+    CompanyName,
+    Street,
+    City,
+    State,
+    ZipCode,
+    Country,
+    PaymentTerms,
+    TaxExempt,
+    Notes,
+}
+
+impl ClientColumn {
+    /// The column a header names, normalized as `CsvTable` does
+    fn from_header(header: &str) -> Option<Self> {
+        let column = match header {
+            "name" | "client_name" | "contact_name" | "full_name" => ClientColumn::Name,
+            "email" | "email_address" | "e_mail" => ClientColumn::Email,
+            "phone" | "phone_number" | "mobile" | "telephone" => ClientColumn::Phone,
+            "company" | "company_name" | "organization" | "organisation" | "business_name" => ClientColumn::CompanyName,
+            "street" | "address" | "address_line1" | "address_line_1" | "billing_address" => ClientColumn::Street,
+            "city" | "town" => ClientColumn::City,
+            "state" | "province" | "region" => ClientColumn::State,
+            "zip" | "zip_code" | "postal_code" | "postcode" => ClientColumn::ZipCode,
+            "country" => ClientColumn::Country,
+            "payment_terms" | "terms" | "payment_terms_days" => ClientColumn::PaymentTerms,
+            "tax_exempt" => ClientColumn::TaxExempt,
+            "notes" | "note" => ClientColumn::Notes,
+            _ => return None,
+        };
+        Some(column)
+    }
+}
+
+/// A valid row, ready to create. `row` is the line in the file (the header is row 1).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientCsvRow {
+    pub row: u32,
+    pub client: CreateClient,
+}
+
+/// A row that was rejected or skipped, and why
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowIssue {
+    pub row: u32,
+    /// Column the problem is in, if it's about one column
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ImportRowIssue {
+    pub fn new(row: u32, field: Option<&str>, message: impl Into<String>) -> Self {
+        Self { row, field: field.map(str::to_string), message: message.into() }
+    }
+
+    pub fn duplicate_email(row: u32) -> Self {
+        Self::new(row, Some("email"), "A client with this email already exists")
+    }
+}
+
+/// A CSV file checked row by row
+#[derive(Debug, Clone, Default)]
+pub struct ParsedClientCsv {
+    pub rows: Vec<ClientCsvRow>,
+    /// Data rows in the file
+    pub total_rows: usize,
+    pub errors: Vec<ImportRowIssue>,
+    /// Rows with the same email as an earlier row
+    pub duplicates: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+/// Reads a client CSV: a header row, then one client per row. Comma and
+/// semicolon separated files are accepted. Rows that fail validation are
+/// reported rather than failing the file; a file without a name or company
+/// column, or with too many rows, is rejected.
+pub fn parse_client_csv(data: &[u8]) -> Result<ParsedClientCsv, String> {
+    let table = CsvTable::read(data, MAX_CLIENT_CSV_ROWS, ClientColumn::from_header)?;
+    if !table.has(ClientColumn::Name) && !table.has(ClientColumn::CompanyName) {
+        return Err("The CSV needs a name or company column".to_string());
+    }
+
+    let mut parsed = ParsedClientCsv {
+        total_rows: table.total_rows,
+        errors: table.errors.clone(),
+        ignored_columns: table.ignored_columns.clone(),
+        ..Default::default()
+    };
+    // Lower-cased email -> row it first appeared in
+    let mut seen_emails: HashMap<String, u32> = HashMap::new();
+
+    for csv_row in &table.rows {
+        let row = csv_row.row;
+        let value = |column: ClientColumn| table.value(csv_row, column);
+        let client = match client_from_row(value) {
+            Ok(client) => client,
+            Err((field, message)) => {
+                parsed.errors.push(ImportRowIssue::new(row, Some(field), message));
+                continue;
+            }
+        };
+
+        if let Some(email) = &client.email {
+            if let Some(first) = seen_emails.get(&email.to_lowercase()) {
+                parsed.duplicates.push(ImportRowIssue::new(
+                    row,
+                    Some("email"),
+                    format!("Same email as row {}", first),
+                ));
+                continue;
+            }
+            seen_emails.insert(email.to_lowercase(), row);
+        }
+        parsed.rows.push(ClientCsvRow { row, client });
+    }
+
+    parsed.errors.sort_by_key(|issue| issue.row);
+    Ok(parsed)
+}
+
+/// The client in one row, or the column that's wrong and why
+fn client_from_row(value: impl Fn(ClientColumn) -> Option<String>) -> Result<CreateClient, (&'static str, String)> {
+    let company_name = value(ClientColumn::CompanyName);
+    let Some(name) = value(ClientColumn::Name).or_else(|| company_name.clone()) else {
+        return Err(("name", "Name is required".to_string()));
+    };
+    if name.chars().count() > 255 {
+        return Err(("name", "Name must be at most 255 characters".to_string()));
+    }
+
+    let email = value(ClientColumn::Email);
+    if email.as_ref().is_some_and(|email| !email.validate_email()) {
+        return Err(("email", "Invalid email address".to_string()));
+    }
+    let phone = normalize_optional_phone(value(ClientColumn::Phone)).map_err(|e| ("phone", e))?;
+
+    let payment_terms = match value(ClientColumn::PaymentTerms) {
+        Some(terms) => match terms.parse::<i32>() {
+            Ok(days) if (0..=MAX_PAYMENT_TERMS_DAYS).contains(&days) => Some(days),
+            _ => {
+                return Err((
+                    "payment_terms",
+                    format!("Payment terms must be a number of days from 0 to {}", MAX_PAYMENT_TERMS_DAYS),
+                ))
+            }
+        },
+        None => None,
+    };
+    let tax_exempt = match value(ClientColumn::TaxExempt).map(|v| v.to_lowercase()) {
+        Some(v) if matches!(v.as_str(), "true" | "yes" | "y" | "1") => Some(true),
+        Some(v) if matches!(v.as_str(), "false" | "no" | "n" | "0") => Some(false),
+        Some(_) => return Err(("tax_exempt", "Tax exempt must be yes or no".to_string())),
+        None => None,
+    };
+
+    let address = ContactAddress {
+        street: value(ClientColumn::Street).unwrap_or_default(),
+        city: value(ClientColumn::City).unwrap_or_default(),
+        state: value(ClientColumn::State).unwrap_or_default(),
+        zip_code: value(ClientColumn::ZipCode).unwrap_or_default(),
+        country: value(ClientColumn::Country).unwrap_or_default(),
+    };
+    let billing_address = (address != ContactAddress::default())
+        .then(|| serde_json::to_value(address).unwrap_or_default());
+
+    Ok(CreateClient {
+        name,
+        email,
+        phone,
+        company_name,
+        billing_address,
+        payment_terms,
+        tax_exempt,
+        tax_exempt_certificate: None,
+        notes: value(ClientColumn::Notes),
+        parent_client_id: None,
+        billing_contacts: None,
+        locale: None,
+        default_tax_id: None,
+        default_currency: None,
+        default_discount_percent: None,
+        hourly_rate: None,
+        vat_number: None,
+    })
+}
+
+/// What an import did, or for a dry run would do
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub created: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub errors: Vec<ImportRowIssue>,
+    pub duplicates: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientImportJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ClientImportJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientImportJobStatus::Queued => "queued",
+            ClientImportJobStatus::Running => "running",
+            ClientImportJobStatus::Completed => "completed",
+            ClientImportJobStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queued" => Some(ClientImportJobStatus::Queued),
+            "running" => Some(ClientImportJobStatus::Running),
+            "completed" => Some(ClientImportJobStatus::Completed),
+            "failed" => Some(ClientImportJobStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A large import running in the background. `processed_rows` of the
+/// `total_rows` valid rows have been created or skipped so far.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientImportJob {
+    pub id: Uuid,
+    pub file_name: String,
+    pub status: ClientImportJobStatus,
+    pub total_rows: i32,
+    pub processed_rows: i32,
+    pub created: i32,
+    pub skipped: i32,
+    pub failed: i32,
+    pub errors: Vec<ImportRowIssue>,
+    pub duplicates: Vec<ImportRowIssue>,
+    /// Why the job failed
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClientImportQuery {
+    /// Validate and report without creating clients
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_are_validated_one_by_one() {
+        let csv = "Name,Email,Company,City,Payment Terms,Tax Exempt,Favourite Colour\n\
+                   Ana,ana@example.com,Ana Co,Jakarta,14,yes,blue\n\
+                   ,bad-email,,,,,\n\
+                   Budi,not-an-email,,,,,\n\
+                   ,,Cahya Ltd,,,,\n\
+                   Dewi,dewi@example.com,,,ninety,,\n\
+                   ,,,,,,\n";
+        let parsed = parse_client_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_rows, 5);
+        assert_eq!(parsed.rows.len(), 2);
+        assert_eq!(parsed.rows[0].client.payment_terms, Some(14));
+        assert_eq!(parsed.rows[0].client.tax_exempt, Some(true));
+        assert_eq!(parsed.rows[0].client.billing_address.as_ref().unwrap()["city"], "Jakarta");
+        // Company doubles as the name
+        assert_eq!(parsed.rows[1].client.name, "Cahya Ltd");
+        assert_eq!(parsed.rows[1].row, 5);
+
+        let errors: Vec<(u32, Option<&str>)> =
+            parsed.errors.iter().map(|e| (e.row, e.field.as_deref())).collect();
+        assert_eq!(errors, vec![(3, Some("name")), (4, Some("email")), (6, Some("payment_terms"))]);
+        assert_eq!(parsed.ignored_columns, vec!["Favourite Colour".to_string()]);
+    }
+
+    #[test]
+    fn test_repeated_emails_are_skipped() {
+        let csv = "\u{feff}name;email\nAna;ana@example.com\nAna again;ANA@example.com\n";
+        let parsed = parse_client_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.rows.len(), 1);
+        assert_eq!(parsed.duplicates, vec![ImportRowIssue::new(3, Some("email"), "Same email as row 2")]);
+    }
+
+    #[test]
+    fn test_file_needs_a_name_column() {
+        assert!(parse_client_csv(b"email,phone\nana@example.com,\n").is_err());
+    }
+}
@@ -0,0 +1,412 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// A message delivered to a user's inbound address, as posted by the mail provider
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboundEmail {
+    pub from: String,
+    pub subject: Option<String>,
+    pub text: Option<String>,
+    #[serde(default)]
+    pub attachments: Vec<InboundAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboundAttachment {
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub content: String,
+    /// `base64` when `content` is encoded; plain text otherwise
+    pub encoding: Option<String>,
+}
+
+impl InboundAttachment {
+    fn is_vcard(&self) -> bool {
+        let by_type = self
+            .content_type
+            .as_deref()
+            .is_some_and(|t| matches!(t.split(';').next().unwrap_or("").trim(), "text/vcard" | "text/x-vcard"));
+        let by_name = self
+            .filename
+            .as_deref()
+            .is_some_and(|name| name.to_ascii_lowercase().ends_with(".vcf"));
+        by_type || by_name
+    }
+
+    fn text(&self) -> Option<String> {
+        match self.encoding.as_deref() {
+            Some(encoding) if encoding.eq_ignore_ascii_case("base64") => {
+                let cleaned: String = self.content.split_whitespace().collect();
+                String::from_utf8(STANDARD.decode(cleaned).ok()?).ok()
+            }
+            _ => Some(self.content.clone()),
+        }
+    }
+}
+
+/// The forwarding address shown to the user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InboundAddress {
+    pub address: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientImportSource {
+    Vcard,
+    ForwardedEmail,
+}
+
+impl ClientImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientImportSource::Vcard => "vcard",
+            ClientImportSource::ForwardedEmail => "forwarded_email",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "vcard" => Some(ClientImportSource::Vcard),
+            "forwarded_email" => Some(ClientImportSource::ForwardedEmail),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientImportStatus {
+    Pending,
+    Confirmed,
+    Dismissed,
+}
+
+impl ClientImportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientImportStatus::Pending => "pending",
+            ClientImportStatus::Confirmed => "confirmed",
+            ClientImportStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(ClientImportStatus::Pending),
+            "confirmed" => Some(ClientImportStatus::Confirmed),
+            "dismissed" => Some(ClientImportStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ContactAddress {
+    pub street: String,
+    pub city: String,
+    pub state: String,
+    pub zip_code: String,
+    pub country: String,
+}
+
+/// Client details found in an inbound message
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ParsedContact {
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub company_name: Option<String>,
+    pub billing_address: Option<ContactAddress>,
+}
+
+/// Contact details waiting in the notification inbox. Confirming creates the
+/// client, or updates `client_id` when a client with that email already exists.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientImport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub source: ClientImportSource,
+    /// Who forwarded the message
+    pub sender: String,
+    pub subject: Option<String>,
+    pub contact: ParsedContact,
+    pub client_id: Option<Uuid>,
+    pub status: ClientImportStatus,
+    pub created_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClientImportFilter {
+    #[serde(default)]
+    pub include_resolved: bool,
+}
+
+/// Finds the client in a forwarded message: an attached vCard first, then a
+/// vCard pasted into the body, then the original sender of a forwarded email
+pub fn parse_inbound_email(email: &InboundEmail) -> Result<(ClientImportSource, ParsedContact), String> {
+    let attached = email
+        .attachments
+        .iter()
+        .filter(|attachment| attachment.is_vcard())
+        .filter_map(InboundAttachment::text)
+        .find_map(|text| parse_vcard(&text));
+    if let Some(contact) = attached {
+        return Ok((ClientImportSource::Vcard, contact));
+    }
+
+    let body = email.text.as_deref().unwrap_or("");
+    if let Some(contact) = parse_vcard(body) {
+        return Ok((ClientImportSource::Vcard, contact));
+    }
+
+    // The outer sender is the user who forwarded the message, not the client
+    let forwarder = parse_mailbox(&email.from).map(|(_, address)| address);
+    match parse_forwarded(body) {
+        Some(contact) if contact.email.is_some() && contact.email != forwarder => {
+            Ok((ClientImportSource::ForwardedEmail, contact))
+        }
+        _ => Err("No contact found: forward an email from the client or attach a vCard".to_string()),
+    }
+}
+
+/// Reads the first card in `text`. Handles folded lines, `item1.` groups and
+/// parameters such as `TYPE=work`; the first value of each property wins.
+pub fn parse_vcard(text: &str) -> Option<ParsedContact> {
+    let start = text.find("BEGIN:VCARD")?;
+    let card = &text[start..];
+    let card = &card[..card.find("END:VCARD")?];
+
+    // Unfold: a line starting with a space or tab continues the previous one
+    let mut lines: Vec<String> = Vec::new();
+    for line in card.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(previous)) => previous.push_str(rest),
+            _ => lines.push(line.trim_end_matches('\r').to_string()),
+        }
+    }
+
+    let mut contact = ParsedContact::default();
+    let mut structured_name = None;
+    for line in &lines {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let property = key.split(';').next().unwrap_or("");
+        let property = property.rsplit('.').next().unwrap_or(property).to_ascii_uppercase();
+        let value = unescape_vcard(value.trim());
+        if value.is_empty() {
+            continue;
+        }
+
+        match property.as_str() {
+            "FN" if contact.name.is_empty() => contact.name = value,
+            "N" if structured_name.is_none() => {
+                // Family;Given;Additional;Prefix;Suffix
+                let parts: Vec<&str> = value.split(';').map(str::trim).collect();
+                let given = parts.get(1).copied().unwrap_or("");
+                let family = parts.first().copied().unwrap_or("");
+                let name = format!("{} {}", given, family).trim().to_string();
+                structured_name = (!name.is_empty()).then_some(name);
+            }
+            "EMAIL" if contact.email.is_none() => contact.email = clean_email(&value),
+            "TEL" if contact.phone.is_none() => {
+                contact.phone = Some(value.trim_start_matches("tel:").to_string());
+            }
+            "ORG" if contact.company_name.is_none() => {
+                let org = value.split(';').next().unwrap_or("").trim().to_string();
+                contact.company_name = (!org.is_empty()).then_some(org);
+            }
+            "ADR" if contact.billing_address.is_none() => {
+                // PO box;Extended;Street;City;Region;Postal code;Country
+                let parts: Vec<&str> = value.split(';').map(str::trim).collect();
+                let part = |i: usize| parts.get(i).copied().unwrap_or("").to_string();
+                let street = [part(0), part(1), part(2)]
+                    .into_iter()
+                    .filter(|p| !p.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let address = ContactAddress {
+                    street,
+                    city: part(3),
+                    state: part(4),
+                    zip_code: part(5),
+                    country: part(6),
+                };
+                if address != ContactAddress::default() {
+                    contact.billing_address = Some(address);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if contact.name.is_empty() {
+        contact.name = structured_name
+            .or_else(|| contact.company_name.clone())
+            .or_else(|| contact.email.as_deref().map(name_from_email))?;
+    }
+    Some(contact)
+}
+
+/// The `From:` of the forwarded message inside `body`
+pub fn parse_forwarded(body: &str) -> Option<ParsedContact> {
+    const MARKERS: [&str; 3] = ["Forwarded message", "Original Message", "Begin forwarded message"];
+    let forwarded = MARKERS
+        .iter()
+        .filter_map(|marker| body.find(marker))
+        .min()
+        .map(|at| &body[at..])
+        .unwrap_or(body);
+
+    let from = forwarded.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches('>').trim();
+        let (header, value) = line.split_once(':')?;
+        ["from", "de", "dari", "von"]
+            .contains(&header.trim().to_ascii_lowercase().as_str())
+            .then_some(value.trim())
+    })?;
+
+    let (name, email) = parse_mailbox(from)?;
+    Some(ParsedContact {
+        name: name.unwrap_or_else(|| name_from_email(&email)),
+        email: Some(email),
+        ..Default::default()
+    })
+}
+
+/// `Jane Doe <jane@example.com>`, `"Doe, Jane" <jane@example.com>` or a bare address
+fn parse_mailbox(value: &str) -> Option<(Option<String>, String)> {
+    let value = value.trim();
+    let (name, address) = match (value.rfind('<'), value.rfind('>')) {
+        (Some(open), Some(close)) if open < close => (
+            Some(value[..open].trim().trim_matches('"').trim().to_string()),
+            &value[open + 1..close],
+        ),
+        _ => (None, value),
+    };
+    let email = clean_email(address)?;
+    Some((name.filter(|n| !n.is_empty() && !n.contains('@')), email))
+}
+
+fn clean_email(value: &str) -> Option<String> {
+    let email = value.trim().trim_start_matches("mailto:").trim().to_ascii_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.contains(char::is_whitespace);
+    valid.then_some(email)
+}
+
+/// `jane.doe@example.com` becomes `Jane Doe`
+fn name_from_email(email: &str) -> String {
+    email
+        .split('@')
+        .next()
+        .unwrap_or(email)
+        .split(['.', '_', '-'])
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn unescape_vcard(value: &str) -> String {
+    value.replace("\\n", " ").replace("\\N", " ").replace("\\,", ",").replace("\\:", ":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(text: &str, attachments: Vec<InboundAttachment>) -> InboundEmail {
+        InboundEmail {
+            from: "Owner <owner@mybusiness.com>".to_string(),
+            subject: Some("Fwd: Project".to_string()),
+            text: Some(text.to_string()),
+            attachments,
+        }
+    }
+
+    const CARD: &str = "BEGIN:VCARD\r\nVERSION:3.0\r\nN:Doe;Jane;;;\r\nFN:Jane Doe\r\nORG:Acme Corp;Sales\r\n\
+item1.EMAIL;TYPE=INTERNET,WORK:Jane@Acme.example\r\nTEL;TYPE=CELL:+62 812 3456 7890\r\n\
+ADR;TYPE=WORK:;;Jl. Sudirman 1;Jakarta;DKI;10220;Indonesia\r\nNOTE:Met at the expo\\, \r\n  very keen\r\nEND:VCARD\r\n";
+
+    #[test]
+    fn test_parse_vcard() {
+        let contact = parse_vcard(CARD).unwrap();
+        assert_eq!(contact.name, "Jane Doe");
+        assert_eq!(contact.email.as_deref(), Some("jane@acme.example"));
+        assert_eq!(contact.phone.as_deref(), Some("+62 812 3456 7890"));
+        assert_eq!(contact.company_name.as_deref(), Some("Acme Corp"));
+        let address = contact.billing_address.unwrap();
+        assert_eq!(address.street, "Jl. Sudirman 1");
+        assert_eq!(address.city, "Jakarta");
+        assert_eq!(address.country, "Indonesia");
+    }
+
+    #[test]
+    fn test_vcard_name_fallbacks() {
+        let card = "BEGIN:VCARD\nN:Smith;Bob\nEND:VCARD";
+        assert_eq!(parse_vcard(card).unwrap().name, "Bob Smith");
+
+        let card = "BEGIN:VCARD\nEMAIL:mary.ann_lee@example.com\nEND:VCARD";
+        assert_eq!(parse_vcard(card).unwrap().name, "Mary Ann Lee");
+
+        assert!(parse_vcard("BEGIN:VCARD\nVERSION:4.0\nEND:VCARD").is_none());
+        assert!(parse_vcard("no card here").is_none());
+    }
+
+    #[test]
+    fn test_attached_vcard_wins() {
+        let attachment = InboundAttachment {
+            filename: Some("jane.vcf".to_string()),
+            content_type: None,
+            content: STANDARD.encode(CARD),
+            encoding: Some("base64".to_string()),
+        };
+        let body = "---------- Forwarded message ---------\nFrom: Someone Else <else@example.com>";
+        let (source, contact) = parse_inbound_email(&email(body, vec![attachment])).unwrap();
+        assert_eq!(source, ClientImportSource::Vcard);
+        assert_eq!(contact.name, "Jane Doe");
+    }
+
+    #[test]
+    fn test_forwarded_sender() {
+        let body = "FYI, new client\n\n---------- Forwarded message ---------\n\
+From: \"Doe, Jane\" <Jane@Acme.example>\nDate: Mon, 12 Oct 2026\nSubject: Quote\n\nHi!";
+        let (source, contact) = parse_inbound_email(&email(body, vec![])).unwrap();
+        assert_eq!(source, ClientImportSource::ForwardedEmail);
+        assert_eq!(contact.name, "Doe, Jane");
+        assert_eq!(contact.email.as_deref(), Some("jane@acme.example"));
+
+        // Quoted headers and a bare address
+        let body = "> -----Original Message-----\n> From: billing@globex.example\n> Subject: hi";
+        let (_, contact) = parse_inbound_email(&email(body, vec![])).unwrap();
+        assert_eq!(contact.name, "Billing");
+    }
+
+    #[test]
+    fn test_rejects_messages_without_a_client() {
+        assert!(parse_inbound_email(&email("Just a note to myself", vec![])).is_err());
+
+        // Forwarding your own message doesn't make you a client
+        let body = "---------- Forwarded message ---------\nFrom: Owner <OWNER@mybusiness.com>";
+        assert!(parse_inbound_email(&email(body, vec![])).is_err());
+
+        let body = "---------- Forwarded message ---------\nFrom: Jane <not-an-email>";
+        assert!(parse_inbound_email(&email(body, vec![])).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domain::models::{CreateInvoiceItem, InvoiceItem, InvoiceStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CreditNoteStatus {
+    Issued,
+    /// Withdrawn; its credit no longer counts against the invoice
+    Void,
+}
+
+impl std::fmt::Display for CreditNoteStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CreditNoteStatus::Issued => write!(f, "issued"),
+            CreditNoteStatus::Void => write!(f, "void"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreditNote {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub credit_note_number: String,
+    pub status: CreditNoteStatus,
+    pub reason: Option<String>,
+    pub issue_date: NaiveDate,
+    pub items: Vec<InvoiceItem>,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+    /// Part of the credit that settled the invoice's open balance
+    pub applied_amount: Decimal,
+    /// Part of the credit owed back to the client
+    pub refund_amount: Decimal,
+    pub currency: String,
+    pub voided_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateCreditNote {
+    pub invoice_id: Uuid,
+
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+
+    /// Defaults to today
+    pub issue_date: Option<NaiveDate>,
+
+    /// Lines being credited; omit to credit the whole invoice
+    #[validate(nested)]
+    pub items: Option<Vec<CreateInvoiceItem>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreditNoteListFilter {
+    pub invoice_id: Option<Uuid>,
+    pub client_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// How much of a new credit can still be issued against an invoice
+pub fn creditable_amount(invoice_total: Decimal, credited_amount: Decimal) -> Decimal {
+    (invoice_total - credited_amount).max(Decimal::ZERO)
+}
+
+/// The credit settles the open balance first; anything beyond it is a refund
+pub fn credit_applied_to_balance(balance_due: Decimal, credit: Decimal) -> Decimal {
+    credit.min(balance_due.max(Decimal::ZERO))
+}
+
+/// Invoice status once credits or their voiding change what has been settled.
+/// Fully credited invoices are cancelled; an untouched balance keeps the
+/// sent/viewed/overdue status it had.
+pub fn status_after_credit(
+    current: InvoiceStatus,
+    total_amount: Decimal,
+    amount_paid: Decimal,
+    credited_amount: Decimal,
+    past_due: bool,
+) -> InvoiceStatus {
+    if total_amount > Decimal::ZERO && credited_amount >= total_amount {
+        InvoiceStatus::Cancelled
+    } else if amount_paid >= total_amount {
+        InvoiceStatus::Paid
+    } else if amount_paid > Decimal::ZERO {
+        InvoiceStatus::Partial
+    } else {
+        match current {
+            InvoiceStatus::Sent | InvoiceStatus::Viewed | InvoiceStatus::Overdue => current,
+            _ if past_due => InvoiceStatus::Overdue,
+            _ => InvoiceStatus::Sent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_credit_settles_balance_before_refunding() {
+        assert_eq!(credit_applied_to_balance(dec!(100), dec!(30)), dec!(30));
+        // Paid invoice: all of the credit is owed back
+        assert_eq!(credit_applied_to_balance(dec!(0), dec!(30)), dec!(0));
+        // Partly paid: the remaining 20 is settled, 10 is refunded
+        assert_eq!(credit_applied_to_balance(dec!(20), dec!(30)), dec!(20));
+
+        assert_eq!(creditable_amount(dec!(100), dec!(30)), dec!(70));
+        assert_eq!(creditable_amount(dec!(100), dec!(100)), dec!(0));
+    }
+
+    #[test]
+    fn test_status_follows_settlement() {
+        use InvoiceStatus::*;
+
+        assert_eq!(status_after_credit(Sent, dec!(100), dec!(30), dec!(30), false), Partial);
+        assert_eq!(status_after_credit(Partial, dec!(100), dec!(100), dec!(40), false), Paid);
+        assert_eq!(status_after_credit(Paid, dec!(100), dec!(100), dec!(100), false), Cancelled);
+        // Voiding the only credit on an unpaid invoice
+        assert_eq!(status_after_credit(Partial, dec!(100), dec!(0), dec!(0), true), Overdue);
+        assert_eq!(status_after_credit(Cancelled, dec!(100), dec!(0), dec!(0), false), Sent);
+        assert_eq!(status_after_credit(Viewed, dec!(100), dec!(0), dec!(0), false), Viewed);
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::domain::models::ImportRowIssue;
+
+/// One data row of an uploaded CSV. `row` is the line in the file (the header is row 1).
+#[derive(Debug, Clone)]
+pub struct CsvRow {
+    pub row: u32,
+    record: csv::StringRecord,
+}
+
+/// An uploaded CSV read against the columns one importer knows. Comma and
+/// semicolon separated files are accepted; blank rows are skipped and rows
+/// that can't be read are reported rather than failing the file.
+#[derive(Debug, Clone)]
+pub struct CsvTable<C> {
+    columns: HashMap<C, usize>,
+    /// Headers no column matched
+    pub ignored_columns: Vec<String>,
+    pub rows: Vec<CsvRow>,
+    /// Data rows in the file, unreadable ones included
+    pub total_rows: usize,
+    /// Rows that couldn't be read
+    pub errors: Vec<ImportRowIssue>,
+}
+
+impl<C: Copy + Eq + Hash> CsvTable<C> {
+    /// Reads the header and every row. Headers are matched case-insensitively,
+    /// with spaces and dashes as underscores, and the first header for a
+    /// column wins. A file with more than `max_rows` data rows is rejected.
+    pub fn read(data: &[u8], max_rows: usize, column: impl Fn(&str) -> Option<C>) -> Result<Self, String> {
+        // Excel adds a byte order mark to UTF-8 exports
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        let first_line = data.split(|b| *b == b'\n').next().unwrap_or_default();
+        let commas = first_line.iter().filter(|b| **b == b',').count();
+        let semicolons = first_line.iter().filter(|b| **b == b';').count();
+        let delimiter = if semicolons > commas { b';' } else { b',' };
+
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(delimiter)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(data);
+
+        let headers = reader.headers().map_err(|e| format!("Could not read the CSV header: {}", e))?.clone();
+        let mut table = CsvTable {
+            columns: HashMap::new(),
+            ignored_columns: Vec::new(),
+            rows: Vec::new(),
+            total_rows: 0,
+            errors: Vec::new(),
+        };
+        for (index, header) in headers.iter().enumerate() {
+            match column(&header.trim().to_lowercase().replace([' ', '-'], "_")) {
+                Some(column) => {
+                    table.columns.entry(column).or_insert(index);
+                }
+                None if !header.is_empty() => table.ignored_columns.push(header.to_string()),
+                None => {}
+            }
+        }
+
+        for (index, record) in reader.into_records().enumerate() {
+            // Line numbers stay right when blank lines or quoted line breaks are skipped over
+            let fallback_row = index as u32 + 2;
+            let record = match record {
+                Ok(record) if record.iter().all(str::is_empty) => continue,
+                Ok(record) => record,
+                Err(e) => {
+                    let row = e.position().map_or(fallback_row, |p| p.line() as u32);
+                    table.errors.push(ImportRowIssue::new(row, None, format!("Could not read row: {}", e)));
+                    table.total_rows += 1;
+                    continue;
+                }
+            };
+            table.total_rows += 1;
+            if table.total_rows > max_rows {
+                return Err(format!("The CSV can have at most {} rows", max_rows));
+            }
+            let row = record.position().map_or(fallback_row, |p| p.line() as u32);
+            table.rows.push(CsvRow { row, record });
+        }
+        Ok(table)
+    }
+
+    pub fn has(&self, column: C) -> bool {
+        self.columns.contains_key(&column)
+    }
+
+    /// The row's value in a column; None when the file has no such column or the cell is empty
+    pub fn value(&self, row: &CsvRow, column: C) -> Option<String> {
+        self.columns
+            .get(&column)
+            .and_then(|index| row.record.get(*index))
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(header: &str) -> Option<&'static str> {
+        match header {
+            "name" | "full_name" => Some("name"),
+            "e_mail" => Some("email"),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_headers_and_delimiter_are_recognized() {
+        let csv = "\u{feff}Full Name;E-Mail;Name;Colour\nAna;ana@example.com;Other;blue\n";
+        let table = CsvTable::read(csv.as_bytes(), 10, column).unwrap();
+
+        assert_eq!(table.ignored_columns, vec!["Colour".to_string()]);
+        let row = &table.rows[0];
+        assert_eq!(row.row, 2);
+        assert_eq!(table.value(row, "name").as_deref(), Some("Ana"));
+        assert_eq!(table.value(row, "email").as_deref(), Some("ana@example.com"));
+    }
+
+    #[test]
+    fn test_blank_and_unreadable_rows() {
+        let mut csv = b"name,e_mail\n\"Ana\nMaria\",\nBudi,\n".to_vec();
+        csv.extend_from_slice(b"\xFF\xFE,x\n,\nCahya,c@example.com\n");
+        let table = CsvTable::read(&csv, 10, column).unwrap();
+
+        assert_eq!(table.total_rows, 4);
+        assert_eq!(table.rows.iter().map(|row| row.row).collect::<Vec<_>>(), vec![2, 4, 7]);
+        assert_eq!(table.value(&table.rows[1], "email"), None);
+        assert_eq!(table.errors.iter().map(|issue| issue.row).collect::<Vec<_>>(), vec![5]);
+
+        assert!(CsvTable::read(&csv, 3, column).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Most columns, filters and `in` values one custom report can use
+pub const MAX_CUSTOM_REPORT_COLUMNS: usize = 30;
+pub const MAX_CUSTOM_REPORT_FILTERS: usize = 20;
+pub const MAX_CUSTOM_REPORT_FILTER_VALUES: usize = 500;
+
+/// Row count per group; only valid in a grouped report
+pub const COUNT_COLUMN: &str = "count";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomReportEntity {
+    Invoices,
+    Payments,
+    Expenses,
+}
+
+impl CustomReportEntity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CustomReportEntity::Invoices => "invoices",
+            CustomReportEntity::Payments => "payments",
+            CustomReportEntity::Expenses => "expenses",
+        }
+    }
+
+    /// Columns that can be selected, filtered and grouped on
+    pub fn columns(self) -> &'static [ReportColumn] {
+        match self {
+            CustomReportEntity::Invoices => INVOICE_COLUMNS,
+            CustomReportEntity::Payments => PAYMENT_COLUMNS,
+            CustomReportEntity::Expenses => EXPENSE_COLUMNS,
+        }
+    }
+
+    pub fn column(self, key: &str) -> Option<&'static ReportColumn> {
+        self.columns().iter().find(|column| column.key == key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportColumnKind {
+    Text,
+    Number,
+    Date,
+    Id,
+    Boolean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ReportColumn {
+    pub key: &'static str,
+    pub header: &'static str,
+    pub kind: ReportColumnKind,
+}
+
+const fn column(key: &'static str, header: &'static str, kind: ReportColumnKind) -> ReportColumn {
+    ReportColumn { key, header, kind }
+}
+
+const INVOICE_COLUMNS: &[ReportColumn] = &[
+    column("invoice_number", "Invoice Number", ReportColumnKind::Text),
+    column("status", "Status", ReportColumnKind::Text),
+    column("label", "Label", ReportColumnKind::Text),
+    column("client_id", "Client ID", ReportColumnKind::Id),
+    column("client_name", "Client", ReportColumnKind::Text),
+    column("issue_date", "Issue Date", ReportColumnKind::Date),
+    column("due_date", "Due Date", ReportColumnKind::Date),
+    column("sent_at", "Sent", ReportColumnKind::Date),
+    column("paid_at", "Paid", ReportColumnKind::Date),
+    column("currency", "Currency", ReportColumnKind::Text),
+    column("exchange_rate", "Exchange Rate", ReportColumnKind::Number),
+    column("subtotal", "Subtotal", ReportColumnKind::Number),
+    column("tax_amount", "Tax", ReportColumnKind::Number),
+    column("discount_amount", "Discount", ReportColumnKind::Number),
+    column("total_amount", "Total", ReportColumnKind::Number),
+    column("amount_paid", "Paid Amount", ReportColumnKind::Number),
+    column("balance_due", "Balance Due", ReportColumnKind::Number),
+];
+
+const PAYMENT_COLUMNS: &[ReportColumn] = &[
+    column("payment_date", "Payment Date", ReportColumnKind::Date),
+    column("invoice_number", "Invoice Number", ReportColumnKind::Text),
+    column("client_id", "Client ID", ReportColumnKind::Id),
+    column("client_name", "Client", ReportColumnKind::Text),
+    column("amount", "Amount", ReportColumnKind::Number),
+    column("currency", "Currency", ReportColumnKind::Text),
+    column("payment_method", "Method", ReportColumnKind::Text),
+    column("status", "Status", ReportColumnKind::Text),
+    column("gateway", "Gateway", ReportColumnKind::Text),
+    column("gateway_fee", "Gateway Fee", ReportColumnKind::Number),
+    column("paid_by", "Paid By", ReportColumnKind::Text),
+];
+
+const EXPENSE_COLUMNS: &[ReportColumn] = &[
+    column("date", "Date", ReportColumnKind::Date),
+    column("category", "Category", ReportColumnKind::Text),
+    column("vendor", "Vendor", ReportColumnKind::Text),
+    column("description", "Description", ReportColumnKind::Text),
+    column("amount", "Amount", ReportColumnKind::Number),
+    column("currency", "Currency", ReportColumnKind::Text),
+    column("tax_rate", "Tax Rate", ReportColumnKind::Number),
+    column("tax_amount", "Input Tax", ReportColumnKind::Number),
+    column("tax_deductible", "Tax Deductible", ReportColumnKind::Boolean),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    In,
+    /// Case-insensitive substring match on text columns
+    Contains,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CustomReportFilter {
+    pub column: String,
+    pub op: ReportFilterOp,
+    pub value: serde_json::Value,
+}
+
+/// Body of `POST /reports/custom`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CustomReportRequest {
+    pub entity: CustomReportEntity,
+    pub columns: Vec<String>,
+    #[serde(default)]
+    pub filters: Vec<CustomReportFilter>,
+    /// Group rows by this column; the other columns must be numeric (summed) or `count`
+    pub group_by: Option<String>,
+}
+
+/// A filter value converted to the column's type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportValue {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Id(Uuid),
+    Boolean(bool),
+    List(Vec<ReportValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportFilterSpec {
+    pub column: &'static ReportColumn,
+    pub op: ReportFilterOp,
+    pub value: ReportValue,
+}
+
+/// An output column: a catalogue column, or the row count of a grouped report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportOutput {
+    Column(&'static ReportColumn),
+    Count,
+}
+
+impl ReportOutput {
+    pub fn header(self) -> &'static str {
+        match self {
+            ReportOutput::Column(column) => column.header,
+            ReportOutput::Count => "Count",
+        }
+    }
+}
+
+/// A request checked against the column whitelist
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomReportSpec {
+    pub entity: CustomReportEntity,
+    pub columns: Vec<ReportOutput>,
+    pub filters: Vec<ReportFilterSpec>,
+    pub group_by: Option<&'static ReportColumn>,
+}
+
+impl CustomReportSpec {
+    pub fn headers(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|column| column.header()).collect()
+    }
+}
+
+impl CustomReportRequest {
+    /// Resolves columns and filters against the entity's whitelist, or says what is wrong
+    pub fn validate(&self) -> Result<CustomReportSpec, String> {
+        let entity = self.entity;
+        let unknown = |key: &str| {
+            let allowed: Vec<&str> = entity.columns().iter().map(|column| column.key).collect();
+            format!("Unknown {} column {}; expected one of: {}", entity.as_str(), key, allowed.join(", "))
+        };
+
+        if self.columns.is_empty() {
+            return Err("Select at least one column".to_string());
+        }
+        if self.columns.len() > MAX_CUSTOM_REPORT_COLUMNS {
+            return Err(format!("At most {} columns can be selected", MAX_CUSTOM_REPORT_COLUMNS));
+        }
+        if self.filters.len() > MAX_CUSTOM_REPORT_FILTERS {
+            return Err(format!("At most {} filters are allowed", MAX_CUSTOM_REPORT_FILTERS));
+        }
+
+        let group_by = match self.group_by.as_deref() {
+            Some(key) => {
+                let column = entity.column(key).ok_or_else(|| unknown(key))?;
+                if column.kind == ReportColumnKind::Number {
+                    return Err(format!("Can't group by numeric column {}", key));
+                }
+                Some(column)
+            }
+            None => None,
+        };
+
+        let mut columns = Vec::with_capacity(self.columns.len());
+        for key in &self.columns {
+            let output = if key == COUNT_COLUMN {
+                if group_by.is_none() {
+                    return Err("The count column needs group_by".to_string());
+                }
+                ReportOutput::Count
+            } else {
+                let column = entity.column(key).ok_or_else(|| unknown(key))?;
+                if let Some(group) = group_by {
+                    if column.key != group.key && column.kind != ReportColumnKind::Number {
+                        return Err(format!(
+                            "Column {} can't be shown when grouping by {}; only numeric columns are summed",
+                            key, group.key
+                        ));
+                    }
+                }
+                ReportOutput::Column(column)
+            };
+            if columns.contains(&output) {
+                return Err(format!("Column {} is selected twice", key));
+            }
+            columns.push(output);
+        }
+
+        let filters = self
+            .filters
+            .iter()
+            .map(|filter| {
+                let column = entity.column(&filter.column).ok_or_else(|| unknown(&filter.column))?;
+                let value = filter_value(column, filter.op, &filter.value)?;
+                Ok(ReportFilterSpec { column, op: filter.op, value })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(CustomReportSpec { entity, columns, filters, group_by })
+    }
+}
+
+fn filter_value(column: &ReportColumn, op: ReportFilterOp, value: &serde_json::Value) -> Result<ReportValue, String> {
+    match op {
+        ReportFilterOp::In => {
+            let values = value
+                .as_array()
+                .ok_or_else(|| format!("Filter on {} with op in needs a list of values", column.key))?;
+            if values.is_empty() || values.len() > MAX_CUSTOM_REPORT_FILTER_VALUES {
+                return Err(format!(
+                    "Filter on {} needs between 1 and {} values",
+                    column.key, MAX_CUSTOM_REPORT_FILTER_VALUES
+                ));
+            }
+            let values = values
+                .iter()
+                .map(|value| scalar_value(column, value))
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(ReportValue::List(values))
+        }
+        ReportFilterOp::Contains if column.kind != ReportColumnKind::Text => {
+            Err(format!("Filter op contains only works on text columns, not {}", column.key))
+        }
+        ReportFilterOp::Gt | ReportFilterOp::Gte | ReportFilterOp::Lt | ReportFilterOp::Lte
+            if matches!(column.kind, ReportColumnKind::Id | ReportColumnKind::Boolean) =>
+        {
+            Err(format!("Column {} can only be compared with eq, ne or in", column.key))
+        }
+        _ => scalar_value(column, value),
+    }
+}
+
+fn scalar_value(column: &ReportColumn, value: &serde_json::Value) -> Result<ReportValue, String> {
+    let invalid = || format!("Invalid value {} for column {}", value, column.key);
+    match column.kind {
+        ReportColumnKind::Text => value.as_str().map(|text| ReportValue::Text(text.to_string())).ok_or_else(invalid),
+        ReportColumnKind::Number => value
+            .as_f64()
+            .filter(|number| number.is_finite())
+            .map(ReportValue::Number)
+            .ok_or_else(invalid),
+        ReportColumnKind::Date => value
+            .as_str()
+            .and_then(|text| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok())
+            .map(ReportValue::Date)
+            .ok_or_else(invalid),
+        ReportColumnKind::Id => value
+            .as_str()
+            .and_then(|text| Uuid::parse_str(text).ok())
+            .map(ReportValue::Id)
+            .ok_or_else(invalid),
+        ReportColumnKind::Boolean => value.as_bool().map(ReportValue::Boolean).ok_or_else(invalid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(body: serde_json::Value) -> CustomReportRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn resolves_columns_and_typed_filters() {
+        let spec = request(json!({
+            "entity": "invoices",
+            "columns": ["invoice_number", "client_name", "total_amount"],
+            "filters": [
+                { "column": "issue_date", "op": "gte", "value": "2025-01-01" },
+                { "column": "status", "op": "in", "value": ["sent", "overdue"] },
+            ],
+        }))
+        .validate()
+        .unwrap();
+
+        assert_eq!(spec.headers(), vec!["Invoice Number", "Client", "Total"]);
+        assert_eq!(spec.filters[0].value, ReportValue::Date(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()));
+        assert_eq!(
+            spec.filters[1].value,
+            ReportValue::List(vec![ReportValue::Text("sent".to_string()), ReportValue::Text("overdue".to_string())])
+        );
+    }
+
+    #[test]
+    fn rejects_columns_outside_the_whitelist() {
+        let err = request(json!({ "entity": "expenses", "columns": ["amount", "user_id"] }))
+            .validate()
+            .unwrap_err();
+        assert!(err.contains("Unknown expenses column user_id"));
+
+        assert!(request(json!({ "entity": "payments", "columns": [] })).validate().is_err());
+        assert!(request(json!({ "entity": "payments", "columns": ["amount", "amount"] })).validate().is_err());
+        assert!(request(json!({ "entity": "payments", "columns": ["count"] })).validate().is_err());
+    }
+
+    #[test]
+    fn grouped_reports_only_sum_numeric_columns() {
+        let spec = request(json!({
+            "entity": "expenses",
+            "columns": ["category", "count", "amount"],
+            "group_by": "category",
+        }))
+        .validate()
+        .unwrap();
+        assert_eq!(spec.group_by.unwrap().key, "category");
+        assert_eq!(spec.columns[1], ReportOutput::Count);
+
+        assert!(request(json!({ "entity": "expenses", "columns": ["vendor", "amount"], "group_by": "category" }))
+            .validate()
+            .is_err());
+        assert!(request(json!({ "entity": "expenses", "columns": ["amount"], "group_by": "amount" }))
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn rejects_filter_values_of_the_wrong_type() {
+        let filter = |column: &str, op: &str, value: serde_json::Value| {
+            request(json!({
+                "entity": "invoices",
+                "columns": ["invoice_number"],
+                "filters": [{ "column": column, "op": op, "value": value }],
+            }))
+            .validate()
+        };
+
+        assert!(filter("total_amount", "gt", json!("100")).is_err());
+        assert!(filter("issue_date", "eq", json!("01/02/2025")).is_err());
+        assert!(filter("client_id", "gt", json!(Uuid::new_v4())).is_err());
+        assert!(filter("total_amount", "contains", json!(1)).is_err());
+        assert!(filter("status", "in", json!([])).is_err());
+        assert!(filter("client_name", "contains", json!("acme")).is_ok());
+    }
+}
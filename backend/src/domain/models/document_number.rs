@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Kinds of documents that draw numbers from their own sequence
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    Invoice,
+    Quote,
+    CreditNote,
+    ProForma,
+}
+
+impl DocumentType {
+    pub const ALL: [DocumentType; 4] = [
+        DocumentType::Invoice,
+        DocumentType::Quote,
+        DocumentType::CreditNote,
+        DocumentType::ProForma,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocumentType::Invoice => "invoice",
+            DocumentType::Quote => "quote",
+            DocumentType::CreditNote => "credit_note",
+            DocumentType::ProForma => "pro_forma",
+        }
+    }
+
+    pub fn default_prefix(&self) -> &'static str {
+        match self {
+            DocumentType::Invoice => "INV",
+            DocumentType::Quote => "QUO",
+            DocumentType::CreditNote => "CN",
+            DocumentType::ProForma => "PF",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == value)
+    }
+}
+
+impl std::fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+pub const DEFAULT_DOCUMENT_NUMBER_PADDING: i32 = 4;
+
+/// Sequence year used by numbering that doesn't restart every year
+pub const CONTINUOUS_SEQUENCE_YEAR: i32 = 0;
+
+/// Effective numbering configuration for one document type
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DocumentNumberFormat {
+    pub doc_type: DocumentType,
+    pub prefix: String,
+    pub padding: i32,
+    /// Restart at 1 every year, with the year in the number
+    pub yearly_reset: bool,
+    pub year: i32,
+    pub next_number: i64,
+    pub next_preview: String,
+}
+
+impl DocumentNumberFormat {
+    /// `INV-2026-0042`, or `INV-0042` for continuous numbering (no year)
+    pub fn format_number(prefix: &str, padding: i32, year: Option<i32>, value: i64) -> String {
+        let width = padding.max(1) as usize;
+        match year {
+            Some(year) => format!("{}-{}-{:0width$}", prefix, year, value, width = width),
+            None => format!("{}-{:0width$}", prefix, value, width = width),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateDocumentNumberFormat {
+    pub prefix: Option<String>,
+    pub padding: Option<i32>,
+    pub yearly_reset: Option<bool>,
+    /// Next number to issue; may only move forward
+    pub next_number: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_number_with_and_without_year() {
+        assert_eq!(DocumentNumberFormat::format_number("INV", 4, Some(2026), 42), "INV-2026-0042");
+        assert_eq!(DocumentNumberFormat::format_number("INV", 5, None, 42), "INV-00042");
+        // Padding never truncates
+        assert_eq!(DocumentNumberFormat::format_number("CN", 2, None, 12345), "CN-12345");
+    }
+}
@@ -0,0 +1,454 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::models::{seller_vat_country, Client, InvoiceDetailResponse, User};
+
+/// Profile both formats follow: the core of the European e-invoicing standard
+pub const EN16931_PROFILE: &str = "urn:cen.eu:en16931:2017";
+
+/// Structured e-invoice formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EInvoiceFormat {
+    /// UBL 2.1 XML, as Peppol exchanges it
+    Ubl,
+    /// Factur-X / ZUGFeRD: the invoice PDF with its data attached as CII XML
+    Facturx,
+}
+
+impl EInvoiceFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EInvoiceFormat::Ubl => "UBL",
+            EInvoiceFormat::Facturx => "Factur-X",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            EInvoiceFormat::Ubl => "xml",
+            EInvoiceFormat::Facturx => "pdf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            EInvoiceFormat::Ubl => "application/xml",
+            EInvoiceFormat::Facturx => "application/pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct EInvoiceQuery {
+    pub format: EInvoiceFormat,
+}
+
+/// VAT category of a line (UNTDID 5305)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VatCategory {
+    Standard,
+    ZeroRated,
+    /// Sold to a VAT-registered business in another member state
+    ReverseCharge,
+    /// The seller isn't registered for VAT
+    NotSubjectToVat,
+}
+
+impl VatCategory {
+    /// Lines of a reverse-charged invoice are AE; otherwise a taxed line is
+    /// standard rated and an untaxed one zero rated, or outside VAT altogether
+    /// when the seller has no VAT number
+    pub fn for_line(rate: Decimal, reverse_charge: bool, seller_registered: bool) -> Self {
+        if reverse_charge {
+            VatCategory::ReverseCharge
+        } else if rate > Decimal::ZERO {
+            VatCategory::Standard
+        } else if seller_registered {
+            VatCategory::ZeroRated
+        } else {
+            VatCategory::NotSubjectToVat
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            VatCategory::Standard => "S",
+            VatCategory::ZeroRated => "Z",
+            VatCategory::ReverseCharge => "AE",
+            VatCategory::NotSubjectToVat => "O",
+        }
+    }
+
+    /// Why no VAT is charged: the VATEX code and its text
+    pub fn exemption(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            VatCategory::ReverseCharge => Some(("VATEX-EU-AE", "Reverse charge")),
+            VatCategory::NotSubjectToVat => Some(("VATEX-EU-O", "Not subject to VAT")),
+            VatCategory::Standard | VatCategory::ZeroRated => None,
+        }
+    }
+
+    /// Sales outside VAT state no rate
+    pub fn has_rate(&self) -> bool {
+        *self != VatCategory::NotSubjectToVat
+    }
+}
+
+/// Seller or buyer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EInvoiceParty {
+    pub name: String,
+    pub vat_number: Option<String>,
+    pub email: Option<String>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub postal_code: Option<String>,
+    pub region: Option<String>,
+    /// ISO 3166-1 alpha-2
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EInvoiceLine {
+    pub description: String,
+    pub quantity: Decimal,
+    /// Net price of `base_quantity` units
+    pub price: Decimal,
+    pub base_quantity: Decimal,
+    pub net_amount: Decimal,
+    pub tax_amount: Decimal,
+    pub category: VatCategory,
+    /// 0 to 1
+    pub rate: Decimal,
+    /// Taxes the line carries when it has several
+    pub tax_count: usize,
+}
+
+/// VAT per category and rate
+#[derive(Debug, Clone, PartialEq)]
+pub struct EInvoiceVat {
+    pub category: VatCategory,
+    pub rate: Decimal,
+    pub taxable_amount: Decimal,
+    pub tax_amount: Decimal,
+}
+
+/// An invoice as the e-invoicing standard (EN 16931) sees it, ready to write
+/// as UBL or CII
+#[derive(Debug, Clone, PartialEq)]
+pub struct EInvoice {
+    pub number: String,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub currency: String,
+    pub note: Option<String>,
+    pub payment_terms: Option<String>,
+    pub seller: EInvoiceParty,
+    pub buyer: EInvoiceParty,
+    pub lines: Vec<EInvoiceLine>,
+    /// Discount off the whole invoice
+    pub allowance: Decimal,
+    pub tax_amount: Decimal,
+    pub prepaid: Decimal,
+}
+
+impl EInvoice {
+    pub fn new(detail: &InvoiceDetailResponse, user: &User, client: &Client) -> Self {
+        let seller_vat_number = user
+            .tax_settings
+            .as_ref()
+            .and_then(|tax| tax.tax_id.as_deref())
+            .map(str::trim)
+            .filter(|tax_id| !tax_id.is_empty())
+            .map(str::to_string);
+        let address = user.business_address.as_ref();
+        let seller = EInvoiceParty {
+            name: user.company_name.clone().unwrap_or_default().trim().to_string(),
+            email: Some(user.email.clone()),
+            street: address.and_then(|a| non_empty(&a.street)),
+            city: address.and_then(|a| non_empty(&a.city)),
+            postal_code: address.and_then(|a| non_empty(&a.zip_code)),
+            region: address.and_then(|a| non_empty(&a.state)),
+            country: address.and_then(|a| country_code(&a.country)).or_else(|| seller_vat_country(user)),
+            vat_number: seller_vat_number,
+        };
+
+        let billing = |key: &str| {
+            client.billing_address.as_ref().and_then(|a| a.get(key)).and_then(|v| v.as_str()).and_then(non_empty)
+        };
+        let buyer = EInvoiceParty {
+            name: client.name.clone(),
+            vat_number: client.vat_number.clone(),
+            email: client.email.clone(),
+            street: billing("street"),
+            city: billing("city"),
+            postal_code: billing("zip_code"),
+            region: billing("state"),
+            country: billing("country").as_deref().and_then(country_code).or_else(|| client.vat_country()),
+        };
+
+        let lines = detail
+            .items
+            .iter()
+            .map(|item| {
+                let net_amount = item.total - item.tax_amount;
+                // Gross prices have no exact net unit price, so the line is priced as a whole
+                let (price, base_quantity) = if detail.tax_included {
+                    (net_amount, item.quantity)
+                } else {
+                    (item.unit_price, Decimal::ONE)
+                };
+                EInvoiceLine {
+                    description: item.description.clone(),
+                    quantity: item.quantity,
+                    price,
+                    base_quantity,
+                    net_amount,
+                    tax_amount: item.tax_amount,
+                    category: VatCategory::for_line(item.tax_rate, detail.reverse_charge, seller.vat_number.is_some()),
+                    rate: item.tax_rate,
+                    tax_count: item.taxes.len(),
+                }
+            })
+            .collect();
+
+        Self {
+            number: detail.invoice_number.clone(),
+            issue_date: detail.issue_date,
+            due_date: detail.due_date,
+            currency: detail.currency.clone(),
+            note: detail.notes.clone().filter(|note| !note.trim().is_empty()),
+            payment_terms: detail.terms.clone().filter(|terms| !terms.trim().is_empty()),
+            seller,
+            buyer,
+            lines,
+            allowance: detail.discount_amount,
+            tax_amount: detail.tax_amount,
+            prepaid: detail.amount_paid,
+        }
+    }
+
+    /// What the standard requires that this invoice lacks or can't express, one
+    /// entry per problem
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+
+        if self.seller.name.is_empty() {
+            problems.push("seller name (company_name in business settings)".to_string());
+        }
+        if self.seller.country.is_none() {
+            problems.push("seller country (address.country in business settings, as a two-letter code)".to_string());
+        }
+        let charges_vat = self.lines.iter().any(|line| line.category != VatCategory::NotSubjectToVat);
+        if charges_vat && self.seller.vat_number.is_none() {
+            problems.push("seller VAT number (vat_number in business settings)".to_string());
+        }
+        if self.buyer.country.is_none() {
+            problems.push("buyer country (the client's billing_address.country, as a two-letter code)".to_string());
+        }
+        let reverse_charge = self.lines.iter().any(|line| line.category == VatCategory::ReverseCharge);
+        if reverse_charge && self.buyer.vat_number.is_none() {
+            problems.push("buyer VAT number (the client's vat_number)".to_string());
+        }
+        if self.lines.is_empty() {
+            problems.push("at least one line item".to_string());
+        }
+        for (index, line) in self.lines.iter().enumerate().filter(|(_, line)| line.tax_count > 1) {
+            problems.push(format!("line {}: one VAT rate per line (it carries {} taxes)", index + 1, line.tax_count));
+        }
+        if self.allowance > Decimal::ZERO && self.tax_amount > Decimal::ZERO {
+            problems.push("a discount before VAT (this invoice's discount comes off the total after VAT)".to_string());
+        }
+
+        if problems.is_empty() { Ok(()) } else { Err(problems) }
+    }
+
+    /// Sum of the lines' net amounts
+    pub fn line_total(&self) -> Decimal {
+        self.lines.iter().map(|line| line.net_amount).sum()
+    }
+
+    pub fn tax_exclusive_total(&self) -> Decimal {
+        self.line_total() - self.allowance
+    }
+
+    pub fn tax_inclusive_total(&self) -> Decimal {
+        self.tax_exclusive_total() + self.tax_amount
+    }
+
+    pub fn payable(&self) -> Decimal {
+        self.tax_inclusive_total() - self.prepaid
+    }
+
+    /// Category and rate the discount falls under: that of the lines, which an
+    /// untaxed invoice shares
+    pub fn allowance_category(&self) -> Option<(VatCategory, Decimal)> {
+        self.lines.first().filter(|_| self.allowance > Decimal::ZERO).map(|line| (line.category, line.rate))
+    }
+
+    /// Taxable amount and VAT per category and rate, the discount taken off its category
+    pub fn vat_breakdown(&self) -> Vec<EInvoiceVat> {
+        let mut breakdown: Vec<EInvoiceVat> = Vec::new();
+        for line in &self.lines {
+            match breakdown.iter_mut().find(|vat| vat.category == line.category && vat.rate == line.rate) {
+                Some(vat) => {
+                    vat.taxable_amount += line.net_amount;
+                    vat.tax_amount += line.tax_amount;
+                }
+                None => breakdown.push(EInvoiceVat {
+                    category: line.category,
+                    rate: line.rate,
+                    taxable_amount: line.net_amount,
+                    tax_amount: line.tax_amount,
+                }),
+            }
+        }
+        if let Some((category, rate)) = self.allowance_category() {
+            if let Some(vat) = breakdown.iter_mut().find(|vat| vat.category == category && vat.rate == rate) {
+                vat.taxable_amount -= self.allowance;
+            }
+        }
+        breakdown
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+/// A two-letter country code, upper-cased; names such as "Germany" aren't read
+fn country_code(country: &str) -> Option<String> {
+    let code = country.trim();
+    (code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())).then(|| code.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn party(name: &str, vat_number: Option<&str>, country: &str) -> EInvoiceParty {
+        EInvoiceParty {
+            name: name.to_string(),
+            vat_number: vat_number.map(str::to_string),
+            country: Some(country.to_string()),
+            ..EInvoiceParty::default()
+        }
+    }
+
+    fn line(net_amount: Decimal, rate: Decimal, category: VatCategory) -> EInvoiceLine {
+        EInvoiceLine {
+            description: "Consulting".to_string(),
+            quantity: Decimal::ONE,
+            price: net_amount,
+            base_quantity: Decimal::ONE,
+            net_amount,
+            tax_amount: (net_amount * rate).round_dp(2),
+            category,
+            rate,
+            tax_count: 0,
+        }
+    }
+
+    fn invoice(lines: Vec<EInvoiceLine>) -> EInvoice {
+        let tax_amount = lines.iter().map(|line| line.tax_amount).sum();
+        EInvoice {
+            number: "INV-0001".to_string(),
+            issue_date: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+            due_date: NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+            currency: "EUR".to_string(),
+            note: None,
+            payment_terms: None,
+            seller: party("Berlin GmbH", Some("DE136695976"), "DE"),
+            buyer: party("Munich AG", Some("DE129273398"), "DE"),
+            lines,
+            allowance: Decimal::ZERO,
+            tax_amount,
+            prepaid: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_line_categories() {
+        assert_eq!(VatCategory::for_line(dec!(0.19), false, true), VatCategory::Standard);
+        assert_eq!(VatCategory::for_line(dec!(0), false, true), VatCategory::ZeroRated);
+        assert_eq!(VatCategory::for_line(dec!(0), true, true), VatCategory::ReverseCharge);
+        assert_eq!(VatCategory::for_line(dec!(0), false, false), VatCategory::NotSubjectToVat);
+    }
+
+    #[test]
+    fn test_totals_and_breakdown_per_rate() {
+        let invoice = invoice(vec![
+            line(dec!(100), dec!(0.19), VatCategory::Standard),
+            line(dec!(50), dec!(0.07), VatCategory::Standard),
+            line(dec!(200), dec!(0.19), VatCategory::Standard),
+        ]);
+        assert!(invoice.validate().is_ok());
+        assert_eq!(invoice.line_total(), dec!(350));
+        assert_eq!(invoice.tax_inclusive_total(), dec!(410.50));
+
+        let breakdown = invoice.vat_breakdown();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].taxable_amount, dec!(300));
+        assert_eq!(breakdown[0].tax_amount, dec!(57));
+        assert_eq!(breakdown[1].tax_amount, dec!(3.50));
+    }
+
+    #[test]
+    fn test_missing_mandatory_fields_are_listed() {
+        let mut invoice = invoice(vec![line(dec!(100), dec!(0.19), VatCategory::Standard)]);
+        invoice.seller = EInvoiceParty { name: String::new(), ..EInvoiceParty::default() };
+        invoice.buyer.country = None;
+
+        let problems = invoice.validate().unwrap_err();
+        assert_eq!(problems.len(), 4);
+        assert!(problems[0].starts_with("seller name"));
+        assert!(problems[1].starts_with("seller country"));
+        assert!(problems[2].starts_with("seller VAT number"));
+        assert!(problems[3].starts_with("buyer country"));
+    }
+
+    #[test]
+    fn test_reverse_charge_needs_the_buyer_vat_number() {
+        let mut invoice = invoice(vec![line(dec!(100), dec!(0), VatCategory::ReverseCharge)]);
+        invoice.buyer = party("Paris SARL", None, "FR");
+        assert_eq!(invoice.validate().unwrap_err(), vec!["buyer VAT number (the client's vat_number)".to_string()]);
+
+        invoice.buyer.vat_number = Some("FR40303265045".to_string());
+        assert!(invoice.validate().is_ok());
+    }
+
+    #[test]
+    fn test_unregistered_seller_can_export_untaxed_invoices() {
+        let mut invoice = invoice(vec![line(dec!(100), dec!(0), VatCategory::NotSubjectToVat)]);
+        invoice.seller.vat_number = None;
+        assert!(invoice.validate().is_ok());
+        assert!(!VatCategory::NotSubjectToVat.has_rate());
+    }
+
+    #[test]
+    fn test_discounts_are_allowances_only_on_untaxed_invoices() {
+        let mut untaxed = invoice(vec![line(dec!(100), dec!(0), VatCategory::ZeroRated)]);
+        untaxed.allowance = dec!(10);
+        assert!(untaxed.validate().is_ok());
+        assert_eq!(untaxed.tax_exclusive_total(), dec!(90));
+        assert_eq!(untaxed.vat_breakdown()[0].taxable_amount, dec!(90));
+
+        // FlashBill takes discounts off after tax, which the standard can't express
+        let mut taxed = invoice(vec![line(dec!(100), dec!(0.19), VatCategory::Standard)]);
+        taxed.allowance = dec!(10);
+        assert!(taxed.validate().unwrap_err()[0].starts_with("a discount before VAT"));
+    }
+
+    #[test]
+    fn test_lines_with_several_taxes_are_rejected() {
+        let mut stacked = line(dec!(100), dec!(0.12), VatCategory::Standard);
+        stacked.tax_count = 2;
+        let invoice = invoice(vec![line(dec!(100), dec!(0.19), VatCategory::Standard), stacked]);
+        assert_eq!(invoice.validate().unwrap_err(), vec!["line 2: one VAT rate per line (it carries 2 taxes)".to_string()]);
+    }
+}
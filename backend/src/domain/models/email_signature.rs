@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+pub const MAX_SIGNATURE_FIELD_LENGTH: usize = 100;
+pub const MAX_SIGNATURE_LINKS: usize = 5;
+
+/// Contact block appended to invoice and reminder emails
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EmailSignature {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub links: Vec<SignatureLink>,
+}
+
+/// A labelled link such as "Website" or "LinkedIn"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SignatureLink {
+    pub label: String,
+    pub url: String,
+}
+
+/// Signatures that apply to the caller: the business default, their own override,
+/// and the one their emails actually use
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmailSignatureSettings {
+    pub default: Option<EmailSignature>,
+    pub member: Option<EmailSignature>,
+    pub effective: Option<EmailSignature>,
+}
+
+impl EmailSignatureSettings {
+    pub fn new(default: Option<EmailSignature>, member: Option<EmailSignature>) -> Self {
+        let effective = member.clone().or_else(|| default.clone());
+        Self { default, member, effective }
+    }
+}
+
+impl EmailSignature {
+    /// Trimmed signature with blank fields dropped, or why it can't be used
+    pub fn normalize(self) -> Result<EmailSignature, String> {
+        let name = normalize_field("name", self.name)?;
+        let title = normalize_field("title", self.title)?;
+        let phone = normalize_field("phone", self.phone)?;
+
+        if self.links.len() > MAX_SIGNATURE_LINKS {
+            return Err(format!("A signature can have at most {} links", MAX_SIGNATURE_LINKS));
+        }
+        let links = self
+            .links
+            .into_iter()
+            .map(|link| {
+                let label = normalize_field("link label", Some(link.label))?
+                    .ok_or("Link label is required".to_string())?;
+                let url = link.url.trim().to_string();
+                if !(url.starts_with("https://") || url.starts_with("http://")) || url.contains(char::is_whitespace) {
+                    return Err(format!("Invalid link {}: expected an http(s) URL", url));
+                }
+                Ok(SignatureLink { label, url })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let signature = EmailSignature { name, title, phone, links };
+        if signature.is_empty() {
+            return Err("Signature needs a name, title, phone or link".to_string());
+        }
+        Ok(signature)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.title.is_none() && self.phone.is_none() && self.links.is_empty()
+    }
+}
+
+fn normalize_field(field: &str, value: Option<String>) -> Result<Option<String>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let value = value.trim();
+    if value.chars().count() > MAX_SIGNATURE_FIELD_LENGTH {
+        return Err(format!("Signature {} must be at most {} characters", field, MAX_SIGNATURE_FIELD_LENGTH));
+    }
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(label: &str, url: &str) -> SignatureLink {
+        SignatureLink { label: label.to_string(), url: url.to_string() }
+    }
+
+    #[test]
+    fn normalize_trims_and_drops_blank_fields() {
+        let signature = EmailSignature {
+            name: Some("  Jane Doe ".to_string()),
+            title: Some("   ".to_string()),
+            phone: None,
+            links: vec![link(" Website ", " https://example.com ")],
+        }
+        .normalize()
+        .unwrap();
+
+        assert_eq!(signature.name.as_deref(), Some("Jane Doe"));
+        assert_eq!(signature.title, None);
+        assert_eq!(signature.links, vec![link("Website", "https://example.com")]);
+    }
+
+    #[test]
+    fn normalize_rejects_empty_and_invalid_signatures() {
+        assert!(EmailSignature::default().normalize().is_err());
+        assert!(EmailSignature { name: Some(" ".to_string()), ..Default::default() }.normalize().is_err());
+        assert!(EmailSignature { name: Some("x".repeat(101)), ..Default::default() }.normalize().is_err());
+        assert!(EmailSignature { links: vec![link("Site", "javascript:alert(1)")], ..Default::default() }
+            .normalize()
+            .is_err());
+        assert!(EmailSignature { links: vec![link(" ", "https://example.com")], ..Default::default() }
+            .normalize()
+            .is_err());
+        let links = (0..6).map(|i| link("Site", &format!("https://example.com/{}", i))).collect();
+        assert!(EmailSignature { links, ..Default::default() }.normalize().is_err());
+    }
+
+    #[test]
+    fn member_signature_overrides_the_default() {
+        let default = EmailSignature { name: Some("Acme Billing".to_string()), ..Default::default() };
+        let member = EmailSignature { name: Some("Jane Doe".to_string()), ..Default::default() };
+
+        let settings = EmailSignatureSettings::new(Some(default.clone()), Some(member.clone()));
+        assert_eq!(settings.effective, Some(member));
+
+        let settings = EmailSignatureSettings::new(Some(default.clone()), None);
+        assert_eq!(settings.effective, Some(default));
+    }
+}
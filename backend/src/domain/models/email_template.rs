@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use utoipa::ToSchema;
+
+pub const MAX_TEMPLATE_SUBJECT_LENGTH: usize = 200;
+pub const MAX_TEMPLATE_BODY_LENGTH: usize = 20_000;
+
+/// The emails that have a named template
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplateKind {
+    Invoice,
+    PaymentReminder,
+    PaymentConfirmation,
+    PasswordReset,
+    Verification,
+}
+
+impl EmailTemplateKind {
+    pub const ALL: [EmailTemplateKind; 5] = [
+        EmailTemplateKind::Invoice,
+        EmailTemplateKind::PaymentReminder,
+        EmailTemplateKind::PaymentConfirmation,
+        EmailTemplateKind::PasswordReset,
+        EmailTemplateKind::Verification,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailTemplateKind::Invoice => "invoice",
+            EmailTemplateKind::PaymentReminder => "payment_reminder",
+            EmailTemplateKind::PaymentConfirmation => "payment_confirmation",
+            EmailTemplateKind::PasswordReset => "password_reset",
+            EmailTemplateKind::Verification => "verification",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    /// Emails a business sends its clients can be customized; account emails
+    /// FlashBill sends the user can't
+    pub fn is_customizable(&self) -> bool {
+        matches!(
+            self,
+            EmailTemplateKind::Invoice | EmailTemplateKind::PaymentReminder | EmailTemplateKind::PaymentConfirmation
+        )
+    }
+
+    /// Placeholders a template of this kind can use, e.g. `{{client_name}}`
+    pub fn variables(&self) -> &'static [&'static str] {
+        match self {
+            EmailTemplateKind::Invoice => &[
+                "business_name", "client_name", "invoice_number", "amount", "due_date", "message",
+                "invoice_url", "attachments", "signature", "locale",
+            ],
+            EmailTemplateKind::PaymentReminder => &[
+                "business_name", "client_name", "invoice_number", "amount_due", "due_date", "days_overdue",
+                "headline", "message", "payment_url", "signature", "locale",
+            ],
+            EmailTemplateKind::PaymentConfirmation => &[
+                "business_name", "client_name", "invoice_number", "amount", "payment_method", "locale",
+            ],
+            EmailTemplateKind::PasswordReset => &["name", "reset_token", "reset_url", "locale"],
+            EmailTemplateKind::Verification => &["name", "verification_token", "verify_url", "locale"],
+        }
+    }
+
+    /// Example values for every variable, used for previews and to check
+    /// custom templates only use variables that exist. `locale` is English;
+    /// previews set the user's own.
+    pub fn sample_data(&self, business_name: &str) -> serde_json::Value {
+        let mut data = match self {
+            EmailTemplateKind::Invoice => json!({
+                "business_name": business_name,
+                "client_name": "Jane Client",
+                "invoice_number": "INV-2025-0042",
+                "amount": "$1,250.00",
+                "due_date": "2025-05-31",
+                "message": "Thanks for another great month.",
+                "invoice_url": "https://app.flashbill.com/guest/invoice/example",
+                "attachments": [{"name": "Timesheet.pdf", "url": "https://app.flashbill.com/files/example"}],
+                "signature": "",
+            }),
+            EmailTemplateKind::PaymentReminder => json!({
+                "business_name": business_name,
+                "client_name": "Jane Client",
+                "invoice_number": "INV-2025-0042",
+                "amount_due": "$1,250.00",
+                "due_date": "2025-05-31",
+                "days_overdue": 5,
+                "headline": "Payment Reminder",
+                "message": "This is a reminder that your invoice is overdue.",
+                "payment_url": "https://app.flashbill.com/guest/pay/example",
+                "signature": "",
+            }),
+            EmailTemplateKind::PaymentConfirmation => json!({
+                "business_name": business_name,
+                "client_name": "Jane Client",
+                "invoice_number": "INV-2025-0042",
+                "amount": "$1,250.00",
+                "payment_method": "Bank Transfer",
+            }),
+            EmailTemplateKind::PasswordReset => json!({
+                "name": business_name,
+                "reset_token": "example-token",
+                "reset_url": "https://app.flashbill.com/reset-password?token=example-token",
+            }),
+            EmailTemplateKind::Verification => json!({
+                "name": business_name,
+                "verification_token": "example-token",
+                "verify_url": "https://app.flashbill.com/verify-email?token=example-token",
+            }),
+        };
+        data["locale"] = "en".into();
+        data
+    }
+}
+
+/// A user's own subject and/or HTML body for one kind of email. Either left
+/// out falls back to the built-in one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct EmailTemplate {
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+impl EmailTemplate {
+    /// Trimmed template with blank parts dropped, or why it can't be used.
+    /// Placeholders are checked separately, against the kind's variables.
+    pub fn normalize(self) -> Result<EmailTemplate, String> {
+        let subject = normalize_part("Subject", self.subject, MAX_TEMPLATE_SUBJECT_LENGTH)?;
+        let body = normalize_part("Body", self.body, MAX_TEMPLATE_BODY_LENGTH)?;
+        if subject.as_deref().is_some_and(|s| s.contains(['\r', '\n'])) {
+            return Err("Subject must be a single line".to_string());
+        }
+        if subject.is_none() && body.is_none() {
+            return Err("Template needs a subject or a body".to_string());
+        }
+        Ok(EmailTemplate { subject, body })
+    }
+}
+
+fn normalize_part(part: &str, value: Option<String>, max: usize) -> Result<Option<String>, String> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let value = value.trim();
+    if value.chars().count() > max {
+        return Err(format!("{} must be at most {} characters", part, max));
+    }
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+/// One kind of email as the settings page shows it: the built-in template, the
+/// user's override if any, and the variables either can use
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailTemplateInfo {
+    pub kind: EmailTemplateKind,
+    pub customizable: bool,
+    pub variables: Vec<String>,
+    pub default_subject: String,
+    pub default_body: String,
+    pub custom: Option<EmailTemplate>,
+}
+
+/// Render a template with example values. Subject and body not given are taken
+/// from the saved override, then the built-in template.
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PreviewEmailTemplate {
+    pub kind: EmailTemplateKind,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EmailTemplatePreview {
+    pub subject: String,
+    pub html_body: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kinds_round_trip_and_only_client_emails_are_customizable() {
+        for kind in EmailTemplateKind::ALL {
+            assert_eq!(EmailTemplateKind::parse(kind.as_str()), Some(kind));
+            let sample = kind.sample_data("Acme");
+            for variable in kind.variables() {
+                assert!(sample.get(variable).is_some(), "{} has no sample {}", kind.as_str(), variable);
+            }
+        }
+        assert_eq!(EmailTemplateKind::parse("statement"), None);
+        assert!(EmailTemplateKind::Invoice.is_customizable());
+        assert!(!EmailTemplateKind::PasswordReset.is_customizable());
+    }
+
+    #[test]
+    fn test_normalize_trims_and_rejects_empty_or_oversized_templates() {
+        let template = EmailTemplate { subject: Some("  Invoice {{invoice_number}} ".to_string()), body: Some(" ".to_string()) }
+            .normalize()
+            .unwrap();
+        assert_eq!(template.subject.as_deref(), Some("Invoice {{invoice_number}}"));
+        assert_eq!(template.body, None);
+
+        assert!(EmailTemplate::default().normalize().is_err());
+        assert!(EmailTemplate { subject: Some("a\nb".to_string()), body: None }.normalize().is_err());
+        assert!(EmailTemplate { subject: Some("x".repeat(201)), body: None }.normalize().is_err());
+    }
+}
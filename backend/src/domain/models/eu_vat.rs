@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::User;
+
+/// Member states as VIES prefixes VAT numbers: ISO codes except Greece (EL), plus
+/// Northern Ireland (XI) for goods
+const EU_VAT_PREFIXES: [&str; 28] = [
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "EL", "ES", "FI", "FR", "HR", "HU", "IE", "IT", "LT", "LU",
+    "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK", "XI",
+];
+
+/// Printed on reverse-charged invoices, as Article 226(11a) of the VAT Directive requires
+pub const REVERSE_CHARGE_NOTE: &str =
+    "Reverse charge: VAT to be accounted for by the recipient (Article 196, Council Directive 2006/112/EC)";
+
+/// An EU VAT number split into its member state prefix and the national number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VatNumber {
+    /// VIES prefix, e.g. DE, or EL for Greece
+    pub prefix: String,
+    pub number: String,
+}
+
+impl VatNumber {
+    /// Reads "DE 123.456.789" and the like: spaces, dots and dashes are dropped and
+    /// the prefix must be a member state's
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let compact: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '.' && *c != '-')
+            .collect::<String>()
+            .to_ascii_uppercase();
+        let invalid = || format!("'{}' is not an EU VAT number (expected a country prefix such as DE or FR)", input.trim());
+
+        if compact.len() < 4 || !compact.is_char_boundary(2) {
+            return Err(invalid());
+        }
+        let (prefix, number) = compact.split_at(2);
+        if !EU_VAT_PREFIXES.contains(&prefix) || number.len() > 12 || !number.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid());
+        }
+        Ok(Self { prefix: prefix.to_string(), number: number.to_string() })
+    }
+
+    /// The member state's ISO code: GR for an EL number
+    pub fn country_code(&self) -> &str {
+        if self.prefix == "EL" { "GR" } else { &self.prefix }
+    }
+}
+
+impl std::fmt::Display for VatNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.prefix, self.number)
+    }
+}
+
+/// A VAT number as stored, e.g. "DE123456789". A blank one is kept blank, to clear it.
+pub fn normalize_vat_number(vat_number: Option<String>) -> Result<Option<String>, String> {
+    match vat_number {
+        Some(vat_number) if vat_number.trim().is_empty() => Ok(Some(String::new())),
+        Some(vat_number) => Ok(Some(VatNumber::parse(&vat_number)?.to_string())),
+        None => Ok(None),
+    }
+}
+
+/// The ISO code of a member state given as a code ("de", "EL") or None when it isn't one
+pub fn eu_country_code(country: &str) -> Option<String> {
+    let code = country.trim().to_ascii_uppercase();
+    match code.as_str() {
+        "GR" | "EL" => Some("GR".to_string()),
+        _ if EU_VAT_PREFIXES.contains(&code.as_str()) => Some(code),
+        _ => None,
+    }
+}
+
+/// The member state the user sells from: the prefix of their own VAT number, else
+/// their business address country
+pub fn seller_vat_country(user: &User) -> Option<String> {
+    let from_vat_number = user
+        .tax_settings
+        .as_ref()
+        .and_then(|tax| tax.tax_id.as_deref())
+        .and_then(|tax_id| VatNumber::parse(tax_id).ok())
+        .map(|vat| vat.country_code().to_string());
+    from_vat_number.or_else(|| user.business_address.as_ref().and_then(|address| eu_country_code(&address.country)))
+}
+
+/// Outcome of the last VIES check of a client's VAT number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VatStatus {
+    /// Registered for intra-EU trade
+    Valid,
+    /// Unknown to VIES or not active
+    Invalid,
+    /// VIES or the member state's register couldn't be reached; checked again on request
+    Unverified,
+}
+
+impl VatStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VatStatus::Valid => "valid",
+            VatStatus::Invalid => "invalid",
+            VatStatus::Unverified => "unverified",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "valid" => Some(VatStatus::Valid),
+            "invalid" => Some(VatStatus::Invalid),
+            "unverified" => Some(VatStatus::Unverified),
+            _ => None,
+        }
+    }
+}
+
+/// What a VIES check says about a number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VatCheck {
+    pub status: VatStatus,
+    /// Name the business is registered under, where the member state discloses it
+    pub registered_name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vat_numbers_are_normalized() {
+        let vat = VatNumber::parse(" de 123.456-789 ").unwrap();
+        assert_eq!(vat.to_string(), "DE123456789");
+        assert_eq!(vat.country_code(), "DE");
+
+        let greek = VatNumber::parse("EL094259216").unwrap();
+        assert_eq!(greek.country_code(), "GR");
+    }
+
+    #[test]
+    fn test_non_eu_or_malformed_numbers_are_rejected() {
+        assert!(VatNumber::parse("GB123456789").is_err());
+        assert!(VatNumber::parse("US12").is_err());
+        assert!(VatNumber::parse("DE1").is_err());
+        assert!(VatNumber::parse("FR12345678901234").is_err());
+        assert!(VatNumber::parse("NL12#456").is_err());
+        assert!(VatNumber::parse("").is_err());
+    }
+
+    #[test]
+    fn test_eu_country_codes() {
+        assert_eq!(eu_country_code(" fr ").as_deref(), Some("FR"));
+        assert_eq!(eu_country_code("EL").as_deref(), Some("GR"));
+        assert_eq!(eu_country_code("US"), None);
+        assert_eq!(eu_country_code("Germany"), None);
+    }
+}
@@ -1,10 +1,14 @@
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use sqlx::Type;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+use super::{validate_min_cent, CurrencyRounding, PageRequest};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "varchar")]
 #[serde(rename_all = "snake_case")]
 pub enum ExpenseCategory {
@@ -18,6 +22,22 @@ pub enum ExpenseCategory {
     Other,
 }
 
+impl ExpenseCategory {
+    /// From the stored value; unknown values read as Other
+    pub fn from_stored(value: &str) -> Self {
+        match value {
+            "supplies" => ExpenseCategory::Supplies,
+            "office_supplies" => ExpenseCategory::OfficeSupplies,
+            "travel" => ExpenseCategory::Travel,
+            "equipment" => ExpenseCategory::Equipment,
+            "software" => ExpenseCategory::Software,
+            "marketing" => ExpenseCategory::Marketing,
+            "utilities" => ExpenseCategory::Utilities,
+            _ => ExpenseCategory::Other,
+        }
+    }
+}
+
 impl std::fmt::Display for ExpenseCategory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -33,13 +53,13 @@ impl std::fmt::Display for ExpenseCategory {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct Expense {
     pub id: Uuid,
     pub user_id: Uuid,
 
-    #[validate(range(min = 0.01))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub amount: Decimal,
 
     pub currency: String,
     pub category: ExpenseCategory,
@@ -51,14 +71,22 @@ pub struct Expense {
     pub date_incurred: NaiveDate,
     pub tax_deductible: bool,
 
+    /// Input tax rate as a fraction (0.21 for 21%)
+    pub tax_rate: Option<Decimal>,
+    /// Tax included in `amount`; reclaimable when the expense is deductible
+    pub tax_amount: Decimal,
+
+    /// Set with `PUT /expenses/{id}/project`
+    pub project_id: Option<Uuid>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateExpense {
-    #[validate(range(min = 0.01))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub amount: Decimal,
 
     pub category: ExpenseCategory,
     pub vendor: Option<String>,
@@ -66,47 +94,127 @@ pub struct CreateExpense {
     pub receipt_image_url: Option<String>,
     pub date_incurred: NaiveDate,
     pub tax_deductible: Option<bool>,
+    pub tax_rate: Option<Decimal>,
+    /// Worked out from `tax_rate` when omitted
+    pub tax_amount: Option<Decimal>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateExpense {
-    pub amount: Option<f64>,
+    pub amount: Option<Decimal>,
     pub category: Option<ExpenseCategory>,
     pub vendor: Option<String>,
     pub description: Option<String>,
     pub receipt_image_url: Option<String>,
     pub date_incurred: Option<NaiveDate>,
     pub tax_deductible: Option<bool>,
+    pub tax_rate: Option<Decimal>,
+    pub tax_amount: Option<Decimal>,
+}
+
+/// Tax portion of a gross expense amount. An explicit `tax_amount` wins (receipts
+/// are often rounded per line); otherwise it is derived from `tax_rate`.
+pub fn resolve_input_tax(
+    amount: Decimal,
+    tax_rate: Option<Decimal>,
+    tax_amount: Option<Decimal>,
+    rounding: CurrencyRounding,
+) -> Result<Decimal, String> {
+    if let Some(rate) = tax_rate {
+        if !(Decimal::ZERO..=Decimal::ONE).contains(&rate) {
+            return Err("Tax rate must be between 0 and 1".to_string());
+        }
+    }
+
+    let tax_amount = match (tax_amount, tax_rate) {
+        (Some(tax_amount), _) => tax_amount,
+        (None, Some(rate)) => rounding.round(amount * rate / (Decimal::ONE + rate)),
+        (None, None) => Decimal::ZERO,
+    };
+
+    if tax_amount < Decimal::ZERO {
+        return Err("Tax amount cannot be negative".to_string());
+    }
+    if tax_amount > amount {
+        return Err("Tax amount cannot exceed the expense amount".to_string());
+    }
+
+    Ok(tax_amount)
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ExpenseListFilter {
     pub category: Option<ExpenseCategory>,
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
     pub tax_deductible: Option<bool>,
     pub search: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// `next_cursor` of the previous page, to page by cursor instead of number
+    pub cursor: Option<String>,
+    /// Older names for `per_page` and the page's start
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ExpenseListFilter {
+    pub fn page_request(&self) -> Result<PageRequest, String> {
+        PageRequest::from_params(self.page, self.per_page, self.cursor.as_deref(), self.limit, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExpenseResponse {
     pub id: Uuid,
-    pub amount: f64,
+    pub amount: Decimal,
     pub currency: String,
     pub category: ExpenseCategory,
     pub vendor: Option<String>,
     pub description: Option<String>,
     pub date_incurred: NaiveDate,
     pub tax_deductible: bool,
+    pub tax_rate: Option<Decimal>,
+    pub tax_amount: Decimal,
+    pub project_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExpenseStats {
     pub total_expenses: f64,
     pub tax_deductible: f64,
     pub by_category: std::collections::HashMap<String, f64>,
     pub monthly_average: f64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::CurrencyRoundingRules;
+    use rust_decimal_macros::dec;
+
+    const CENTS: CurrencyRounding = CurrencyRounding::CENTS;
+
+    #[test]
+    fn input_tax_is_derived_from_gross_amount() {
+        assert_eq!(resolve_input_tax(dec!(121), Some(dec!(0.21)), None, CENTS), Ok(dec!(21)));
+        assert_eq!(resolve_input_tax(dec!(100), None, None, CENTS), Ok(dec!(0)));
+        assert_eq!(resolve_input_tax(dec!(121), Some(dec!(0.21)), Some(dec!(20.99)), CENTS), Ok(dec!(20.99)));
+    }
+
+    #[test]
+    fn input_tax_is_validated() {
+        assert!(resolve_input_tax(dec!(100), Some(dec!(1.5)), None, CENTS).is_err());
+        assert!(resolve_input_tax(dec!(100), None, Some(dec!(-1)), CENTS).is_err());
+        assert!(resolve_input_tax(dec!(100), None, Some(dec!(120)), CENTS).is_err());
+    }
+
+    #[test]
+    fn input_tax_rounds_to_the_expense_currency() {
+        let yen = CurrencyRoundingRules::default().for_currency("JPY");
+        assert_eq!(resolve_input_tax(dec!(1100), Some(dec!(0.1)), None, yen), Ok(dec!(100)));
+        assert_eq!(resolve_input_tax(dec!(1000), Some(dec!(0.08)), None, yen), Ok(dec!(74)));
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Realized foreign exchange gain/loss for a settled foreign-currency invoice payment.
+/// Positive amounts are gains, negative amounts are losses (base currency).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxGainLossEntry {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    pub payment_id: Option<Uuid>,
+    pub currency: String,
+    pub foreign_amount: f64,
+    pub issue_rate: f64,
+    pub settlement_rate: f64,
+    pub gain_loss_amount: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFxGainLoss {
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    pub payment_id: Option<Uuid>,
+    pub currency: String,
+    pub foreign_amount: f64,
+    pub issue_rate: f64,
+    pub settlement_rate: f64,
+}
+
+impl CreateFxGainLoss {
+    /// Gain/loss in base currency, rounded to cents
+    pub fn gain_loss_amount(&self) -> f64 {
+        let raw = self.foreign_amount * (self.settlement_rate - self.issue_rate);
+        (raw * 100.0).round() / 100.0
+    }
+}
+
+/// Where an exchange rate came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FxRateSource {
+    Ecb,
+    ExchangerateHost,
+    Manual,
+    /// Base currency to itself; never stored
+    Identity,
+}
+
+impl FxRateSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FxRateSource::Ecb => "ecb",
+            FxRateSource::ExchangerateHost => "exchangerate_host",
+            FxRateSource::Manual => "manual",
+            FxRateSource::Identity => "identity",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ecb" => Some(FxRateSource::Ecb),
+            "exchangerate_host" => Some(FxRateSource::ExchangerateHost),
+            "manual" => Some(FxRateSource::Manual),
+            _ => None,
+        }
+    }
+}
+
+/// Exchange rate in effect on a day: `rate` units of `base_currency` per 1 unit of `currency`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FxRate {
+    /// None for the identity rate, which isn't stored
+    pub id: Option<Uuid>,
+    pub base_currency: String,
+    pub currency: String,
+    pub rate: f64,
+    pub rate_date: NaiveDate,
+    pub source: FxRateSource,
+}
+
+/// Manual override of the provider rate for one day
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetFxRate {
+    pub currency: String,
+    pub rate: f64,
+    /// Defaults to today
+    pub rate_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FxRateQuery {
+    pub currency: String,
+    /// Defaults to today
+    pub date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FxRateHistoryQuery {
+    pub currency: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Three-letter ISO 4217 code, upper-cased
+pub fn normalize_currency_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    (code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())).then(|| code.to_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn currency_codes_are_normalized() {
+        assert_eq!(normalize_currency_code(" eur "), Some("EUR".to_string()));
+        assert_eq!(normalize_currency_code("EURO"), None);
+        assert_eq!(normalize_currency_code("E1R"), None);
+    }
+
+    fn settled(foreign_amount: f64, issue_rate: f64, settlement_rate: f64) -> CreateFxGainLoss {
+        CreateFxGainLoss {
+            user_id: Uuid::nil(),
+            invoice_id: Uuid::nil(),
+            payment_id: None,
+            currency: "EUR".to_string(),
+            foreign_amount,
+            issue_rate,
+            settlement_rate,
+        }
+    }
+
+    #[test]
+    fn gain_loss_is_in_base_currency() {
+        // Worth more when paid than when issued: a gain
+        assert_eq!(settled(1000.0, 1.08, 1.10).gain_loss_amount(), 20.0);
+        assert_eq!(settled(1000.0, 1.10, 1.08).gain_loss_amount(), -20.0);
+        assert_eq!(settled(1000.0, 1.08, 1.08).gain_loss_amount(), 0.0);
+    }
+
+    #[test]
+    fn gain_loss_is_rounded_to_cents() {
+        assert_eq!(settled(333.33, 1.0, 1.0123).gain_loss_amount(), 4.1);
+        assert_eq!(settled(15_000.0, 16_250.0, 16_100.5).gain_loss_amount(), -2_242_500.0);
+    }
+}
@@ -1,10 +1,15 @@
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use sqlx::{Type, FromRow, Row};
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
+use crate::domain::i18n::Locale;
+use crate::domain::models::{validate_min_cent, validate_rate_range, DepositTerms, InvoiceDeposit, LineTax, NotificationSettings, PaymentMethod, TaxComponent};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq, ToSchema)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum InvoiceStatus {
@@ -15,6 +20,10 @@ pub enum InvoiceStatus {
     Paid,
     Overdue,
     Cancelled,
+    /// Replaced by a corrected invoice
+    Superseded,
+    /// Offer lapsed unpaid past its expiry date
+    Expired,
 }
 
 impl std::fmt::Display for InvoiceStatus {
@@ -27,28 +36,126 @@ impl std::fmt::Display for InvoiceStatus {
             InvoiceStatus::Paid => write!(f, "paid"),
             InvoiceStatus::Overdue => write!(f, "overdue"),
             InvoiceStatus::Cancelled => write!(f, "cancelled"),
+            InvoiceStatus::Superseded => write!(f, "superseded"),
+            InvoiceStatus::Expired => write!(f, "expired"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+impl InvoiceStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(InvoiceStatus::Draft),
+            "sent" => Some(InvoiceStatus::Sent),
+            "viewed" => Some(InvoiceStatus::Viewed),
+            "partial" => Some(InvoiceStatus::Partial),
+            "paid" => Some(InvoiceStatus::Paid),
+            "overdue" => Some(InvoiceStatus::Overdue),
+            "cancelled" => Some(InvoiceStatus::Cancelled),
+            "superseded" => Some(InvoiceStatus::Superseded),
+            "expired" => Some(InvoiceStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// The invoice lifecycle: draft → sent → viewed → partial → paid, with
+    /// unpaid invoices going overdue or, for offers, expired on the way. A
+    /// payment may come in before a draft is sent. Paid, cancelled and
+    /// superseded are final. Credit notes and trashing a client adjust
+    /// statuses by their own rules and aren't covered here.
+    pub fn can_transition_to(&self, next: &InvoiceStatus) -> bool {
+        use InvoiceStatus::*;
+        match (self, next) {
+            (Draft, Sent | Partial | Paid | Cancelled) => true,
+            (Sent, Viewed | Overdue) | (Viewed, Overdue) => true,
+            (Sent | Viewed | Overdue, Partial | Paid | Cancelled | Superseded | Expired) => true,
+            // Each further part payment keeps an invoice partial until it's settled
+            (Partial, Partial | Paid) => true,
+            // A later expiry date reopens a lapsed offer
+            (Expired, Sent | Cancelled) => true,
+            _ => false,
+        }
+    }
+
+    /// Money has been taken or the invoice is closed, so what it bills can't
+    /// change any more; a credit note adjusts it instead
+    pub fn is_finalized(&self) -> bool {
+        matches!(
+            self,
+            InvoiceStatus::Partial | InvoiceStatus::Paid | InvoiceStatus::Cancelled | InvoiceStatus::Superseded
+        )
+    }
+
+    pub fn allows(&self, action: InvoiceAction) -> bool {
+        match action {
+            InvoiceAction::Edit => !self.is_finalized(),
+            // Once a number has gone out, the invoice is cancelled rather than removed
+            InvoiceAction::Delete => *self == InvoiceStatus::Draft,
+            // Sending again keeps the current status, so paid invoices can be resent
+            InvoiceAction::Send => !matches!(
+                self,
+                InvoiceStatus::Cancelled | InvoiceStatus::Superseded | InvoiceStatus::Expired
+            ),
+            InvoiceAction::RecordPayment => {
+                self.can_transition_to(&InvoiceStatus::Partial) || self.can_transition_to(&InvoiceStatus::Paid)
+            }
+            InvoiceAction::Correct => self.can_transition_to(&InvoiceStatus::Superseded),
+            InvoiceAction::Cancel => self.can_transition_to(&InvoiceStatus::Cancelled),
+        }
+    }
+}
+
+/// Changes made through the invoice endpoints, each checked against the
+/// invoice's status with `InvoiceStatus::allows` before it's applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvoiceAction {
+    Edit,
+    Delete,
+    Send,
+    RecordPayment,
+    Correct,
+    Cancel,
+}
+
+impl std::fmt::Display for InvoiceAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvoiceAction::Edit => write!(f, "edited"),
+            InvoiceAction::Delete => write!(f, "deleted"),
+            InvoiceAction::Send => write!(f, "sent"),
+            InvoiceAction::RecordPayment => write!(f, "paid"),
+            InvoiceAction::Correct => write!(f, "corrected"),
+            InvoiceAction::Cancel => write!(f, "cancelled"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct InvoiceItem {
     pub id: Uuid,
 
     #[validate(length(min = 1, max = 1000))]
     pub description: String,
 
-    #[validate(range(min = 0.01))]
-    pub quantity: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub quantity: Decimal,
+
+    #[validate(custom(function = "validate_min_cent"))]
+    pub unit_price: Decimal,
 
-    #[validate(range(min = 0.01))]
-    pub unit_price: f64,
+    #[validate(custom(function = "validate_rate_range"))]
+    pub tax_rate: Decimal,
 
-    #[validate(range(min = 0.0, max = 100.0))]
-    pub tax_rate: f64,
+    pub tax_amount: Decimal,
+    pub total: Decimal,
 
-    pub tax_amount: f64,
-    pub total: f64,
+    // Section heading (e.g. source invoice number on consolidated invoices)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
+    /// Each tax charged when the line has more than one; `tax_rate` is then their combined rate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub taxes: Vec<LineTax>,
 }
 
 impl InvoiceItem {
@@ -72,11 +179,11 @@ pub struct Invoice {
     pub issue_date: NaiveDate,
     pub due_date: NaiveDate,
 
-    pub subtotal: f64,
-    pub tax_amount: f64,
-    pub discount_amount: f64,
-    pub total_amount: f64,
-    pub amount_paid: f64,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+    pub amount_paid: Decimal,
 
     pub items: Vec<InvoiceItem>,
     pub notes: Option<String>,
@@ -87,6 +194,10 @@ pub struct Invoice {
     pub tax_label: Option<String>,  // Snapshot of tax label
     pub tax_id: Option<String>,     // Optional tax ID
 
+    // Currency and exchange rate to the user's base currency at issuance
+    pub currency: String,
+    pub exchange_rate: f64,
+
     pub pdf_url: Option<String>,
     pub receipt_image_url: Option<String>,
 
@@ -106,9 +217,15 @@ pub struct Invoice {
 
     // NEW: Partial Payment Settings
     pub allow_partial_payment: bool,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
     pub partial_payment_count: i32,
 
+    // Upfront deposit
+    pub deposit_amount: Option<Decimal>,
+    pub deposit_percent: Option<Decimal>,
+    pub deposit_due_date: Option<NaiveDate>,
+    pub deposit_paid_at: Option<DateTime<Utc>>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -126,23 +243,25 @@ impl Invoice {
         self.total_amount = self.subtotal + self.tax_amount - self.discount_amount;
     }
 
-    pub fn _balance_due(&self) -> f64 {
+    pub fn _balance_due(&self) -> Decimal {
         self.total_amount - self.amount_paid
     }
 
-    pub fn _is_overdue(&self) -> bool {
-        if self.status == InvoiceStatus::Paid || self.status == InvoiceStatus::Cancelled {
+    pub fn _is_overdue(&self, today: NaiveDate) -> bool {
+        if matches!(
+            self.status,
+            InvoiceStatus::Paid | InvoiceStatus::Cancelled | InvoiceStatus::Superseded | InvoiceStatus::Expired
+        ) {
             return false;
         }
 
-        let today = chrono::Utc::now().naive_utc().date();
         today > self.due_date
     }
 
-    pub fn _update_status(&mut self) {
-        if self._is_overdue() {
+    pub fn _update_status(&mut self, today: NaiveDate) {
+        if self._is_overdue(today) {
             self.status = InvoiceStatus::Overdue;
-        } else if self.amount_paid > 0.0 && self.amount_paid < self.total_amount {
+        } else if self.amount_paid > Decimal::ZERO && self.amount_paid < self.total_amount {
             self.status = InvoiceStatus::Partial;
         } else if self.amount_paid >= self.total_amount {
             self.status = InvoiceStatus::Paid;
@@ -163,29 +282,66 @@ pub struct CreateInvoice {
 
     pub notes: Option<String>,
     pub terms: Option<String>,
-    pub discount_amount: Option<f64>,
+    pub discount_amount: Option<Decimal>,
     pub tax_included: bool,
     pub send_immediately: bool,
     pub tax_label: Option<String>,  // Optional custom tax label
     pub tax_id: Option<String>,     // Optional tax ID
 
+    // Currency (defaults to the user's base currency) and exchange rate at issuance
+    pub currency: Option<String>,
+    pub exchange_rate: Option<f64>,
+
     // Partial Payment Settings
     pub allow_partial_payment: Option<bool>,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
+
+    /// Last day the offer can be accepted or paid ("valid 14 days")
+    #[serde(default)]
+    pub expires_at: Option<NaiveDate>,
+
+    /// One of the client's projects
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+
+    /// Deposit to collect before work begins
+    #[serde(default)]
+    pub deposit: Option<DepositTerms>,
+
+    /// Reverse charged: lines carry no VAT and the PDF notes the client accounts for it
+    #[serde(default)]
+    pub reverse_charge: bool,
+    /// Member state the sale is reported under in the VAT summary
+    #[serde(default)]
+    pub vat_country: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateInvoiceItem {
     #[validate(length(min = 1, max = 1000))]
     pub description: String,
 
-    #[validate(range(min = 0.01))]
-    pub quantity: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub quantity: Decimal,
+
+    #[validate(custom(function = "validate_min_cent"))]
+    pub unit_price: Decimal,
+
+    pub tax_rate: Option<Decimal>,
 
-    #[validate(range(min = 0.01))]
-    pub unit_price: f64,
+    #[serde(default)]
+    pub section: Option<String>,
 
-    pub tax_rate: Option<f64>,
+    /// Several taxes on the line, such as GST and PST; replaces `tax_rate` when given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub taxes: Vec<TaxComponent>,
+}
+
+impl CreateInvoiceItem {
+    /// The line with no tax, as sold under the reverse charge
+    pub fn untaxed(self) -> Self {
+        Self { tax_rate: Some(Decimal::ZERO), taxes: Vec::new(), ..self }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -196,12 +352,77 @@ pub struct UpdateInvoice {
     pub items: Option<Vec<CreateInvoiceItem>>,
     pub notes: Option<String>,
     pub terms: Option<String>,
-    pub discount_amount: Option<f64>,
+    pub discount_amount: Option<Decimal>,
     pub tax_included: Option<bool>,
 
     // Partial Payment Settings
     pub allow_partial_payment: Option<bool>,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
+
+    #[serde(default)]
+    pub expires_at: Option<NaiveDate>,
+
+    /// Replaces the deposit; left out keeps the current one
+    #[serde(default)]
+    pub deposit: Option<DepositTerms>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsolidateInvoices {
+    pub invoice_ids: Vec<Uuid>,
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+}
+
+/// Action applied to every invoice of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceBatchAction {
+    Send,
+    /// Records a payment of each invoice's balance due
+    MarkPaid,
+    /// Moves drafts to the trash
+    Delete,
+    /// Returns the PDFs together as a zip
+    DownloadPdfs,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceBatch {
+    pub action: InvoiceBatchAction,
+    pub invoice_ids: Vec<Uuid>,
+    /// How `mark_paid` payments were made; bank transfer when left out
+    #[serde(default)]
+    pub payment_method: Option<PaymentMethod>,
+}
+
+/// File name for a document about the invoice, e.g. `INV-2026-001.pdf`. Characters
+/// that aren't safe in file names become underscores.
+pub fn invoice_file_name(invoice_number: &str, extension: &str) -> String {
+    let stem: String = invoice_number
+        .trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect();
+    let stem = stem.trim_start_matches('.');
+    format!("{}.{}", if stem.is_empty() { "invoice" } else { stem }, extension)
+}
+
+/// Corrected version of an issued invoice. Fields left out are copied from the original.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrectInvoice {
+    /// Why the original was wrong; kept on the corrected invoice for the audit trail
+    pub reason: String,
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub items: Option<Vec<CreateInvoiceItem>>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub discount_amount: Option<Decimal>,
+    /// Email the corrected invoice straight away (default)
+    pub send: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -211,11 +432,13 @@ pub struct InvoiceListFilter {
     pub date_from: Option<NaiveDate>,
     pub date_to: Option<NaiveDate>,
     pub search: Option<String>,
-    pub limit: Option<i64>,
-    pub offset: Option<i64>,
+    /// Invoices currently carrying this pipeline label
+    pub label_id: Option<Uuid>,
+    /// Restrict to these invoices (batch lookup)
+    pub ids: Option<Vec<Uuid>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceResponse {
     pub id: Uuid,
     pub invoice_number: String,
@@ -224,10 +447,12 @@ pub struct InvoiceResponse {
     pub client_email: Option<String>,
     pub issue_date: NaiveDate,
     pub due_date: NaiveDate,
-    pub total_amount: f64,
-    pub balance_due: f64,
+    pub total_amount: Decimal,
+    pub balance_due: Decimal,
     pub days_until_due: i32,
     pub is_overdue: bool,
+    /// Name of the pipeline label, while it applies to the invoice's status
+    pub label: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -245,12 +470,13 @@ impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceResponse {
             balance_due: row.try_get("balance_due")?,
             days_until_due: row.try_get("days_until_due")?,
             is_overdue: row.try_get("is_overdue")?,
+            label: row.try_get("label")?,
             created_at: row.try_get("created_at")?,
         })
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InvoiceDetailResponse {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -261,14 +487,16 @@ pub struct InvoiceDetailResponse {
     pub client_email: Option<String>,
     pub client_phone: Option<String>,
     pub client_address: Option<serde_json::Value>,
+    /// The client's own locale; None uses the invoicing user's
+    pub client_locale: Option<Locale>,
     pub issue_date: NaiveDate,
     pub due_date: NaiveDate,
-    pub subtotal: f64,
-    pub tax_amount: f64,
-    pub discount_amount: f64,
-    pub total_amount: f64,
-    pub amount_paid: f64,
-    pub balance_due: f64,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+    pub amount_paid: Decimal,
+    pub balance_due: Decimal,
     pub items: Vec<InvoiceItem>,
     pub notes: Option<String>,
     pub terms: Option<String>,
@@ -276,6 +504,8 @@ pub struct InvoiceDetailResponse {
     pub tax_included: bool,
     pub tax_label: Option<String>,
     pub tax_id: Option<String>,
+    pub currency: String,
+    pub exchange_rate: f64,
     pub pdf_url: Option<String>,
     pub receipt_image_url: Option<String>,
     pub sent_at: Option<DateTime<Utc>>,
@@ -289,13 +519,108 @@ pub struct InvoiceDetailResponse {
 
     // Partial Payment Settings
     pub allow_partial_payment: bool,
-    pub min_payment_amount: Option<f64>,
+    pub min_payment_amount: Option<Decimal>,
     pub partial_payment_count: i32,
 
+    /// Deposit to collect before work begins, if the invoice asks for one
+    pub deposit: Option<InvoiceDeposit>,
+
+    /// Sold to a VAT-registered business in another member state: no VAT charged,
+    /// the client accounts for it
+    pub reverse_charge: bool,
+    /// Member state the sale is reported under in the VAT summary
+    pub vat_country: Option<String>,
+    pub client_vat_number: Option<String>,
+
+    // Set when this invoice was cancelled by consolidation
+    pub consolidated_into_id: Option<Uuid>,
+
+    // Corrections: the original this invoice replaces, or the invoice that replaced it
+    pub supersedes_id: Option<Uuid>,
+    pub superseded_by_id: Option<Uuid>,
+    pub correction_reason: Option<String>,
+
+    // Offer expiry: last valid day, and when the invoice was marked expired
+    pub expires_at: Option<NaiveDate>,
+    pub expired_at: Option<DateTime<Utc>>,
+
+    pub project_id: Option<Uuid>,
+
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl InvoiceDetailResponse {
+    /// Whether the offer can no longer be accepted or paid through a guest link.
+    /// Past `expires_at` counts even before the scheduler has marked it.
+    pub fn is_expired(&self, today: NaiveDate) -> bool {
+        match self.status {
+            InvoiceStatus::Expired => true,
+            InvoiceStatus::Sent | InvoiceStatus::Viewed | InvoiceStatus::Overdue => {
+                self.expires_at.is_some_and(|expires_at| today > expires_at)
+            }
+            _ => false,
+        }
+    }
+
+    /// Check a payment against the balance and the invoice's partial payment
+    /// settings. While a deposit is due, a payment has to cover the rest of it;
+    /// paying the deposit alone is allowed even when part payments aren't.
+    pub fn check_payment_amount(&self, amount: Decimal) -> Result<(), String> {
+        match self.deposit.as_ref().filter(|deposit| deposit.is_due()) {
+            Some(deposit) if amount > Decimal::ZERO && amount < deposit.balance_due => {
+                Err(format!("The deposit of {} has to be paid first", deposit.balance_due))
+            }
+            Some(_) => check_payment_amount(amount, self.balance_due, true, None),
+            None => check_payment_amount(amount, self.balance_due, self.allow_partial_payment, self.min_payment_amount),
+        }
+    }
+
+    /// What the client is asked to pay now: the rest of the deposit while it's due, otherwise the balance
+    pub fn amount_due_now(&self) -> Decimal {
+        match self.deposit.as_ref().filter(|deposit| deposit.is_due()) {
+            Some(deposit) => deposit.balance_due.min(self.balance_due),
+            None => self.balance_due,
+        }
+    }
+
+    /// The date payment reminders count from: the deposit's due date while it's
+    /// outstanding, otherwise the invoice's
+    pub fn reminder_due_date(&self) -> NaiveDate {
+        match self.deposit.as_ref().filter(|deposit| deposit.is_due()) {
+            Some(deposit) => deposit.due_date,
+            None => self.due_date,
+        }
+    }
+}
+
+/// A payment must be positive and no more than the balance due. Anything short
+/// of the balance is a partial payment: refused when the invoice doesn't allow
+/// them, or when it's below `min_payment_amount`. Paying off the rest of the
+/// balance is always accepted, however small.
+pub fn check_payment_amount(
+    amount: Decimal,
+    balance_due: Decimal,
+    allow_partial_payment: bool,
+    min_payment_amount: Option<Decimal>,
+) -> Result<(), String> {
+    if amount <= Decimal::ZERO {
+        return Err("Payment amount must be greater than zero".to_string());
+    }
+    if amount > balance_due {
+        return Err(format!("Payment of {} exceeds the balance due of {}", amount, balance_due));
+    }
+    if amount < balance_due {
+        if !allow_partial_payment {
+            return Err(format!("This invoice must be paid in full ({})", balance_due));
+        }
+        if let Some(min_amount) = min_payment_amount.filter(|min_amount| amount < *min_amount) {
+            return Err(format!("Partial payments must be at least {}", min_amount));
+        }
+    }
+    Ok(())
+}
+
 impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDetailResponse {
     fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
         let items_json: serde_json::Value = row.try_get("items")?;
@@ -311,6 +636,7 @@ impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDetailResponse {
             client_email: row.try_get("client_email")?,
             client_phone: row.try_get("client_phone")?,
             client_address: row.try_get("client_address")?,
+            client_locale: row.try_get::<Option<String>, _>("client_locale")?.as_deref().and_then(Locale::parse),
             issue_date: row.try_get("issue_date")?,
             due_date: row.try_get("due_date")?,
             subtotal: row.try_get("subtotal")?,
@@ -326,6 +652,8 @@ impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDetailResponse {
             tax_included: row.try_get("tax_included")?,
             tax_label: row.try_get("tax_label")?,
             tax_id: row.try_get("tax_id")?,
+            currency: row.try_get("currency")?,
+            exchange_rate: row.try_get("exchange_rate")?,
             pdf_url: row.try_get("pdf_url")?,
             receipt_image_url: row.try_get("receipt_image_url")?,
             sent_at: row.try_get("sent_at")?,
@@ -339,6 +667,24 @@ impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDetailResponse {
             allow_partial_payment: row.try_get("allow_partial_payment")?,
             min_payment_amount: row.try_get("min_payment_amount")?,
             partial_payment_count: row.try_get("partial_payment_count")?,
+            reverse_charge: row.try_get("reverse_charge")?,
+            vat_country: row.try_get("vat_country")?,
+            client_vat_number: row.try_get("client_vat_number")?,
+            deposit: InvoiceDeposit::from_columns(
+                row.try_get("deposit_amount")?,
+                row.try_get("deposit_percent")?,
+                row.try_get("deposit_due_date")?,
+                row.try_get("deposit_paid_at")?,
+                row.try_get("amount_paid")?,
+                row.try_get("issue_date")?,
+            ),
+            consolidated_into_id: row.try_get("consolidated_into_id")?,
+            supersedes_id: row.try_get("supersedes_id")?,
+            superseded_by_id: row.try_get("superseded_by_id")?,
+            correction_reason: row.try_get("correction_reason")?,
+            expires_at: row.try_get("expires_at")?,
+            expired_at: row.try_get("expired_at")?,
+            project_id: row.try_get("project_id")?,
             created_at: row.try_get("created_at")?,
             updated_at: row.try_get("updated_at")?,
         })
@@ -362,6 +708,19 @@ pub struct _InvoiceReminder {
     pub add_late_fee: bool,
 }
 
+/// An open invoice on or past its due date, with its owner's reminder settings
+#[derive(Debug, Clone)]
+pub struct ReminderCandidate {
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    /// The deposit's due date while it's outstanding, otherwise the invoice's
+    pub due_date: NaiveDate,
+    /// Reminding about the deposit rather than the balance
+    pub deposit: bool,
+    pub reminder_sent_count: i32,
+    pub settings: NotificationSettings,
+}
+
 // Discussion Models
 #[derive(Debug, Clone, Serialize, Deserialize, Type, PartialEq)]
 #[sqlx(type_name = "varchar", rename_all = "lowercase")]
@@ -371,6 +730,16 @@ pub enum SenderType {
     Buyer,
 }
 
+impl SenderType {
+    /// Who reads this side's messages
+    pub fn other(&self) -> SenderType {
+        match self {
+            SenderType::Seller => SenderType::Buyer,
+            SenderType::Buyer => SenderType::Seller,
+        }
+    }
+}
+
 impl std::fmt::Display for SenderType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -398,6 +767,41 @@ pub struct DiscussionResponse {
     pub created_at: DateTime<Utc>,
 }
 
+/// Shortest gap between two notifications of new messages to the same side
+/// of a discussion; messages in between only count as unread
+pub const DISCUSSION_NOTIFY_THROTTLE_MINUTES: i64 = 15;
+/// Most of a message quoted in its notification
+pub const DISCUSSION_EXCERPT_LENGTH: usize = 200;
+
+/// One side's unread messages in an invoice's discussion
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DiscussionUnread {
+    pub invoice_id: Uuid,
+    /// Messages from the other side since this side last read the discussion
+    pub unread_count: i64,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub last_read_at: Option<DateTime<Utc>>,
+}
+
+/// An invoice with buyer messages the seller hasn't read, for badging the dashboard
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct UnreadDiscussion {
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub client_name: String,
+    pub unread_count: i64,
+    pub last_message_at: DateTime<Utc>,
+}
+
+/// The start of a message for a notification, cut at a character boundary
+pub fn discussion_excerpt(message: &str) -> String {
+    let message = message.trim();
+    match message.char_indices().nth(DISCUSSION_EXCERPT_LENGTH) {
+        Some((end, _)) => format!("{}…", message[..end].trim_end()),
+        None => message.to_string(),
+    }
+}
+
 impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDiscussion {
     fn from_row(row: &sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
         Ok(InvoiceDiscussion {
@@ -409,3 +813,76 @@ impl FromRow<'_, sqlx::postgres::PgRow> for InvoiceDiscussion {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle_moves_forward_only() {
+        assert!(InvoiceStatus::Draft.can_transition_to(&InvoiceStatus::Sent));
+        assert!(InvoiceStatus::Sent.can_transition_to(&InvoiceStatus::Viewed));
+        assert!(InvoiceStatus::Viewed.can_transition_to(&InvoiceStatus::Partial));
+        assert!(InvoiceStatus::Partial.can_transition_to(&InvoiceStatus::Paid));
+        assert!(InvoiceStatus::Expired.can_transition_to(&InvoiceStatus::Sent));
+
+        assert!(!InvoiceStatus::Viewed.can_transition_to(&InvoiceStatus::Sent));
+        assert!(!InvoiceStatus::Paid.can_transition_to(&InvoiceStatus::Partial));
+        assert!(!InvoiceStatus::Partial.can_transition_to(&InvoiceStatus::Cancelled));
+        assert!(!InvoiceStatus::Cancelled.can_transition_to(&InvoiceStatus::Draft));
+    }
+
+    #[test]
+    fn test_finalized_invoices_only_allow_resending() {
+        for status in [InvoiceStatus::Partial, InvoiceStatus::Paid] {
+            assert!(!status.allows(InvoiceAction::Edit));
+            assert!(!status.allows(InvoiceAction::Delete));
+            assert!(!status.allows(InvoiceAction::Cancel));
+            assert!(status.allows(InvoiceAction::Send));
+        }
+        assert!(InvoiceStatus::Partial.allows(InvoiceAction::RecordPayment));
+        assert!(!InvoiceStatus::Paid.allows(InvoiceAction::RecordPayment));
+
+        assert!(InvoiceStatus::Sent.allows(InvoiceAction::Edit));
+        assert!(!InvoiceStatus::Sent.allows(InvoiceAction::Delete));
+        assert!(InvoiceStatus::Sent.allows(InvoiceAction::Cancel));
+        assert!(InvoiceStatus::Draft.allows(InvoiceAction::Delete));
+        assert!(!InvoiceStatus::Draft.allows(InvoiceAction::Correct));
+        assert!(!InvoiceStatus::Expired.allows(InvoiceAction::RecordPayment));
+        assert!(!InvoiceStatus::Superseded.allows(InvoiceAction::Send));
+    }
+
+    #[test]
+    fn test_partial_payment_settings_are_enforced() {
+        let balance = Decimal::new(1000, 0);
+        let min = Some(Decimal::new(200, 0));
+
+        assert!(check_payment_amount(balance, balance, false, min).is_ok());
+        assert!(check_payment_amount(Decimal::new(200, 0), balance, true, min).is_ok());
+        assert!(check_payment_amount(Decimal::new(199, 0), balance, true, min).is_err());
+        assert!(check_payment_amount(Decimal::new(500, 0), balance, false, None).is_err());
+        assert!(check_payment_amount(Decimal::new(1001, 0), balance, true, None).is_err());
+        assert!(check_payment_amount(Decimal::ZERO, balance, true, None).is_err());
+
+        // The last of the balance may be below the minimum
+        assert!(check_payment_amount(Decimal::new(50, 0), Decimal::new(50, 0), true, min).is_ok());
+    }
+
+    #[test]
+    fn test_discussion_excerpt_is_cut_at_a_character() {
+        assert_eq!(discussion_excerpt("  Short question?  "), "Short question?");
+        let long = "é".repeat(DISCUSSION_EXCERPT_LENGTH + 5);
+        let excerpt = discussion_excerpt(&long);
+        assert_eq!(excerpt.chars().count(), DISCUSSION_EXCERPT_LENGTH + 1);
+        assert!(excerpt.ends_with('…'));
+        assert_eq!(SenderType::Buyer.other(), SenderType::Seller);
+    }
+
+    #[test]
+    fn test_invoice_file_name_is_safe() {
+        assert_eq!(invoice_file_name("INV-2026-001", "pdf"), "INV-2026-001.pdf");
+        assert_eq!(invoice_file_name("2026/03 #7", "pdf"), "2026_03__7.pdf");
+        assert_eq!(invoice_file_name("../etc", "pdf"), "_etc.pdf");
+        assert_eq!(invoice_file_name("  ", "pdf"), "invoice.pdf");
+    }
+}
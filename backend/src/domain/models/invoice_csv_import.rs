@@ -0,0 +1,361 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::HashMap;
+use std::str::FromStr;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{CreateInvoiceItem, CsvTable, ImportRowIssue};
+
+pub const MAX_INVOICE_CSV_BYTES: usize = 5 * 1024 * 1024;
+pub const MAX_INVOICE_CSV_ROWS: usize = 10_000;
+/// Invoices are created in the request, so a file is capped well below the row limit
+pub const MAX_IMPORTED_INVOICES: usize = 500;
+
+/// An invoice column in the CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum InvoiceColumn {
+    InvoiceNumber,
+    ClientEmail,
+    ClientName,
+    IssueDate,
+    DueDate,
+    Currency,
+    Notes,
+    Description,
+    Quantity,
+    UnitPrice,
+    TaxRate,
+}
+
+impl InvoiceColumn {
+    /// The column a header names, normalized as `CsvTable` does
+    fn from_header(header: &str) -> Option<Self> {
+        let column = match header {
+            "invoice_number" | "invoice" | "invoice_no" | "invoice_id" | "number" | "reference" => {
+                InvoiceColumn::InvoiceNumber
+            }
+            "client_email" | "customer_email" | "email" => InvoiceColumn::ClientEmail,
+            "client_name" | "client" | "customer" | "customer_name" => InvoiceColumn::ClientName,
+            "issue_date" | "invoice_date" | "date" => InvoiceColumn::IssueDate,
+            "due_date" | "due" => InvoiceColumn::DueDate,
+            "currency" => InvoiceColumn::Currency,
+            "notes" | "note" => InvoiceColumn::Notes,
+            "description" | "item" | "item_description" | "line_item" => InvoiceColumn::Description,
+            "quantity" | "qty" | "hours" => InvoiceColumn::Quantity,
+            "unit_price" | "price" | "rate" => InvoiceColumn::UnitPrice,
+            "tax_rate" | "tax" | "tax_percent" | "vat_rate" => InvoiceColumn::TaxRate,
+            _ => return None,
+        };
+        Some(column)
+    }
+}
+
+/// A valid line item. `row` is the line in the file (the header is row 1).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceCsvItem {
+    pub row: u32,
+    pub item: CreateInvoiceItem,
+}
+
+/// The rows sharing an invoice number, which become one invoice. Invoice
+/// details come from the first of them; the client is matched by email, or
+/// by name when the file has no email.
+#[derive(Debug, Clone)]
+pub struct InvoiceCsvInvoice {
+    /// Invoice number in the file; only used to group rows and in the report
+    pub reference: Option<String>,
+    /// Row the invoice starts on
+    pub row: u32,
+    pub client_email: Option<String>,
+    pub client_name: Option<String>,
+    pub issue_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub currency: Option<String>,
+    pub notes: Option<String>,
+    pub items: Vec<InvoiceCsvItem>,
+}
+
+/// A CSV file checked row by row
+#[derive(Debug, Clone, Default)]
+pub struct ParsedInvoiceCsv {
+    /// Invoices whose rows are all valid, in file order
+    pub invoices: Vec<InvoiceCsvInvoice>,
+    /// Data rows in the file
+    pub total_rows: usize,
+    /// Invoices found in the file, valid or not
+    pub total_invoices: usize,
+    pub errors: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+/// Reads an invoice CSV: a header row, then one line item per row. Rows with
+/// the same invoice number make up one invoice; a row without one is an
+/// invoice of its own. An invoice with an invalid row is left out whole
+/// rather than imported with lines missing.
+pub fn parse_invoice_csv(data: &[u8]) -> Result<ParsedInvoiceCsv, String> {
+    let table = CsvTable::read(data, MAX_INVOICE_CSV_ROWS, InvoiceColumn::from_header)?;
+    if !table.has(InvoiceColumn::ClientEmail) && !table.has(InvoiceColumn::ClientName) {
+        return Err("The CSV needs a client email or client name column".to_string());
+    }
+    for (column, name) in [(InvoiceColumn::Description, "description"), (InvoiceColumn::UnitPrice, "unit price")] {
+        if !table.has(column) {
+            return Err(format!("The CSV needs a {} column", name));
+        }
+    }
+
+    let mut parsed = ParsedInvoiceCsv {
+        total_rows: table.total_rows,
+        errors: table.errors.clone(),
+        ignored_columns: table.ignored_columns.clone(),
+        ..Default::default()
+    };
+    // Invoices in file order; a failed one stays as None so its other rows are skipped too
+    let mut invoices: Vec<Option<InvoiceCsvInvoice>> = Vec::new();
+    let mut by_reference: HashMap<String, usize> = HashMap::new();
+
+    for csv_row in &table.rows {
+        let row = csv_row.row;
+        let value = |column: InvoiceColumn| table.value(csv_row, column);
+
+        let reference = value(InvoiceColumn::InvoiceNumber);
+        let existing = reference.as_ref().and_then(|reference| by_reference.get(reference).copied());
+        let slot = match existing {
+            Some(slot) => slot,
+            None => {
+                invoices.push(match invoice_from_row(row, reference.clone(), &value) {
+                    Ok(invoice) => Some(invoice),
+                    Err((field, message)) => {
+                        parsed.errors.push(ImportRowIssue::new(row, Some(field), message));
+                        None
+                    }
+                });
+                if let Some(reference) = reference.clone() {
+                    by_reference.insert(reference, invoices.len() - 1);
+                }
+                invoices.len() - 1
+            }
+        };
+
+        let Some(invoice) = invoices[slot].as_mut() else {
+            if existing.is_some() {
+                parsed.errors.push(ImportRowIssue::new(row, None, invoice_skipped(reference.as_deref())));
+            }
+            continue;
+        };
+        match item_from_row(&value) {
+            Ok(item) => invoice.items.push(InvoiceCsvItem { row, item }),
+            Err((field, message)) => {
+                parsed.errors.push(ImportRowIssue::new(row, Some(field), message));
+                // Earlier rows of the invoice were fine on their own; say why they're left out
+                for item in &invoice.items {
+                    parsed.errors.push(ImportRowIssue::new(item.row, None, invoice_skipped(reference.as_deref())));
+                }
+                invoices[slot] = None;
+            }
+        }
+    }
+
+    parsed.total_invoices = invoices.len();
+    parsed.invoices = invoices.into_iter().flatten().collect();
+    if parsed.invoices.len() > MAX_IMPORTED_INVOICES {
+        return Err(format!("The CSV can have at most {} invoices", MAX_IMPORTED_INVOICES));
+    }
+    parsed.errors.sort_by_key(|issue| issue.row);
+    Ok(parsed)
+}
+
+fn invoice_skipped(reference: Option<&str>) -> String {
+    format!("Not imported because invoice {} has an invalid row", reference.unwrap_or_default())
+}
+
+/// The invoice a row starts, or the column that's wrong and why
+fn invoice_from_row(
+    row: u32,
+    reference: Option<String>,
+    value: &impl Fn(InvoiceColumn) -> Option<String>,
+) -> Result<InvoiceCsvInvoice, (&'static str, String)> {
+    let client_email = value(InvoiceColumn::ClientEmail);
+    let client_name = value(InvoiceColumn::ClientName);
+    if client_email.is_none() && client_name.is_none() {
+        return Err(("client_email", "Client email or name is required".to_string()));
+    }
+    if client_email.as_ref().is_some_and(|email| !email.validate_email()) {
+        return Err(("client_email", "Invalid email address".to_string()));
+    }
+
+    let issue_date = parse_date(value(InvoiceColumn::IssueDate)).map_err(|e| ("issue_date", e))?;
+    let due_date = parse_date(value(InvoiceColumn::DueDate)).map_err(|e| ("due_date", e))?;
+    if let (Some(issue_date), Some(due_date)) = (issue_date, due_date) {
+        if due_date < issue_date {
+            return Err(("due_date", "Due date can't be before the issue date".to_string()));
+        }
+    }
+
+    let currency = value(InvoiceColumn::Currency).map(|c| c.to_uppercase());
+    if currency.as_ref().is_some_and(|c| c.len() != 3 || !c.chars().all(|ch| ch.is_ascii_alphabetic())) {
+        return Err(("currency", "Currency must be a three-letter code".to_string()));
+    }
+
+    Ok(InvoiceCsvInvoice {
+        reference,
+        row,
+        client_email,
+        client_name,
+        issue_date,
+        due_date,
+        currency,
+        notes: value(InvoiceColumn::Notes),
+        items: Vec::new(),
+    })
+}
+
+/// The line item in one row, or the column that's wrong and why
+fn item_from_row(value: &impl Fn(InvoiceColumn) -> Option<String>) -> Result<CreateInvoiceItem, (&'static str, String)> {
+    let Some(description) = value(InvoiceColumn::Description) else {
+        return Err(("description", "Description is required".to_string()));
+    };
+    if description.chars().count() > 1000 {
+        return Err(("description", "Description must be at most 1000 characters".to_string()));
+    }
+
+    let quantity = match value(InvoiceColumn::Quantity) {
+        Some(quantity) => parse_amount(&quantity)
+            .filter(|q| *q >= Decimal::new(1, 2))
+            .ok_or(("quantity", "Quantity must be a number of at least 0.01".to_string()))?,
+        None => Decimal::ONE,
+    };
+    let unit_price = value(InvoiceColumn::UnitPrice)
+        .and_then(|price| parse_amount(&price))
+        .filter(|price| *price >= Decimal::new(1, 2))
+        .ok_or(("unit_price", "Unit price must be a number of at least 0.01".to_string()))?;
+    // A percentage in the file; line items hold it as a fraction
+    let tax_rate = match value(InvoiceColumn::TaxRate) {
+        Some(rate) => Some(
+            parse_amount(rate.trim_end_matches('%'))
+                .filter(|rate| (Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(rate))
+                .ok_or(("tax_rate", "Tax rate must be a percentage from 0 to 100".to_string()))?
+                / Decimal::ONE_HUNDRED,
+        ),
+        None => None,
+    };
+
+    Ok(CreateInvoiceItem { description, quantity, unit_price, tax_rate, section: None, taxes: Vec::new() })
+}
+
+fn parse_date(value: Option<String>) -> Result<Option<NaiveDate>, String> {
+    value
+        .map(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|_| "Dates must be YYYY-MM-DD".to_string()))
+        .transpose()
+}
+
+/// A plain decimal, allowing thousands separators ("1,250.50") or a decimal
+/// comma ("12,50"). "1,250" could be either, so it's rejected.
+pub(crate) fn parse_amount(value: &str) -> Option<Decimal> {
+    let value = value.trim().replace(' ', "");
+    let value = if value.contains('.') {
+        value.replace(',', "")
+    } else {
+        match value.split_once(',') {
+            Some((whole, fraction)) if (1..=2).contains(&fraction.len()) && !fraction.contains(',') => {
+                format!("{}.{}", whole, fraction)
+            }
+            Some(_) => return None,
+            None => value,
+        }
+    };
+    Decimal::from_str(&value).ok()
+}
+
+/// An invoice the import created, or for a dry run would create
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportedInvoice {
+    /// Invoice number in the file
+    pub reference: Option<String>,
+    pub row: u32,
+    pub client_id: Uuid,
+    pub items: usize,
+    /// The new invoice; not set for dry runs
+    pub invoice_id: Option<Uuid>,
+    pub invoice_number: Option<String>,
+}
+
+/// What an invoice import did, or for a dry run would do
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceImportReport {
+    pub dry_run: bool,
+    pub total_rows: usize,
+    pub total_invoices: usize,
+    pub created: usize,
+    pub failed: usize,
+    pub invoices: Vec<ImportedInvoice>,
+    pub errors: Vec<ImportRowIssue>,
+    pub ignored_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InvoiceImportQuery {
+    /// Validate and report without creating invoices
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_with_the_same_number_make_one_invoice() {
+        let csv = "Invoice Number,Client Email,Issue Date,Description,Qty,Unit Price,Tax Rate,Colour\n\
+                   A-1,ana@example.com,2024-03-01,Design,10,\"1,250.50\",10%,blue\n\
+                   B-7,budi@example.com,2024-03-02,Hosting,,20,,\n\
+                   A-1,,,Support,2,50,,\n";
+        let parsed = parse_invoice_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_rows, 3);
+        assert_eq!(parsed.total_invoices, 2);
+        assert!(parsed.errors.is_empty());
+        let first = &parsed.invoices[0];
+        assert_eq!(first.reference.as_deref(), Some("A-1"));
+        assert_eq!(first.client_email.as_deref(), Some("ana@example.com"));
+        assert_eq!(first.items.iter().map(|i| i.row).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(first.items[0].item.unit_price, Decimal::new(125050, 2));
+        assert_eq!(first.items[0].item.tax_rate, Some(Decimal::new(1, 1)));
+        assert_eq!(parsed.invoices[1].items[0].item.quantity, Decimal::ONE);
+        assert_eq!(parsed.ignored_columns, vec!["Colour".to_string()]);
+    }
+
+    #[test]
+    fn test_an_invalid_row_leaves_its_invoice_out() {
+        let csv = "invoice;client;date;description;price\n\
+                   1;Ana;2024-03-01;Design;100\n\
+                   1;;;Support;free\n\
+                   1;;;Travel;30\n\
+                   ;Budi;01/03/2024;Hosting;20\n\
+                   ;Cahya;;Retainer;500\n";
+        let parsed = parse_invoice_csv(csv.as_bytes()).unwrap();
+
+        assert_eq!(parsed.total_invoices, 3);
+        assert_eq!(parsed.invoices.len(), 1);
+        assert_eq!(parsed.invoices[0].client_name.as_deref(), Some("Cahya"));
+        let errors: Vec<(u32, Option<&str>)> =
+            parsed.errors.iter().map(|e| (e.row, e.field.as_deref())).collect();
+        assert_eq!(errors, vec![(2, None), (3, Some("unit_price")), (4, None), (5, Some("issue_date"))]);
+    }
+
+    #[test]
+    fn test_amounts_with_separators() {
+        assert_eq!(parse_amount("1,250.50"), Some(Decimal::new(125050, 2)));
+        assert_eq!(parse_amount("12,5"), Some(Decimal::new(125, 1)));
+        assert_eq!(parse_amount("1,250"), None);
+    }
+
+    #[test]
+    fn test_file_needs_client_and_item_columns() {
+        assert!(parse_invoice_csv(b"description,unit_price\nDesign,100\n").is_err());
+        assert!(parse_invoice_csv(b"client_email,description\nana@example.com,Design\n").is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::invoice_totals::CurrencyRounding;
+
+/// Whether the client has paid the deposit yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    Due,
+    Paid,
+}
+
+/// Part of an invoice the client pays upfront, before work begins
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceDeposit {
+    pub amount: Decimal,
+    /// Set when the deposit is a percentage of the total; it then follows the total when the invoice is edited
+    pub percent: Option<Decimal>,
+    pub due_date: NaiveDate,
+    pub status: DepositStatus,
+    /// Left to pay toward the deposit
+    pub balance_due: Decimal,
+    /// When payments first covered the deposit
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+impl InvoiceDeposit {
+    /// The deposit stored on an invoice, or None when it doesn't ask for one
+    pub fn from_columns(
+        amount: Option<Decimal>,
+        percent: Option<Decimal>,
+        due_date: Option<NaiveDate>,
+        paid_at: Option<DateTime<Utc>>,
+        amount_paid: Decimal,
+        issue_date: NaiveDate,
+    ) -> Option<Self> {
+        let amount = amount?;
+        let status = if paid_at.is_some() { DepositStatus::Paid } else { DepositStatus::Due };
+        let balance_due = match status {
+            DepositStatus::Due => (amount - amount_paid).max(Decimal::ZERO),
+            DepositStatus::Paid => Decimal::ZERO,
+        };
+        Some(Self {
+            amount,
+            percent,
+            due_date: due_date.unwrap_or(issue_date),
+            status,
+            balance_due,
+            paid_at,
+        })
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.status == DepositStatus::Due
+    }
+
+    /// The terms this deposit was set with, to carry over when the invoice is edited
+    pub fn terms(&self) -> DepositTerms {
+        DepositTerms {
+            amount: self.percent.is_none().then_some(self.amount),
+            percent: self.percent,
+            due_date: Some(self.due_date),
+        }
+    }
+}
+
+/// A deposit asked for on an invoice: a fixed `amount` or a `percent` of the
+/// total. Editing an invoice with neither removes its deposit.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct DepositTerms {
+    #[serde(default)]
+    pub amount: Option<Decimal>,
+    #[serde(default)]
+    pub percent: Option<Decimal>,
+    /// Defaults to the issue date
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+}
+
+impl DepositTerms {
+    pub fn is_empty(&self) -> bool {
+        self.amount.is_none() && self.percent.is_none()
+    }
+
+    /// Checks what can be checked before the invoice's total is known
+    pub fn validate(&self, issue_date: NaiveDate, due_date: NaiveDate) -> Result<(), String> {
+        match (self.amount, self.percent) {
+            (Some(_), Some(_)) => return Err("Give a deposit amount or a percent, not both".to_string()),
+            (Some(amount), None) if amount <= Decimal::ZERO => {
+                return Err("Deposit amount must be greater than zero".to_string());
+            }
+            (None, Some(percent)) if percent <= Decimal::ZERO || percent > Decimal::ONE_HUNDRED => {
+                return Err("Deposit percent must be more than 0 and at most 100".to_string());
+            }
+            _ => {}
+        }
+        if self.due_date.is_some_and(|deposit_due| deposit_due < issue_date || deposit_due > due_date) {
+            return Err("The deposit must fall due between the invoice's issue and due dates".to_string());
+        }
+        Ok(())
+    }
+
+    /// The deposit on an invoice of `total`. A fixed amount above the total is
+    /// capped at it, so the whole invoice is paid upfront.
+    pub fn amount_for(&self, total: Decimal, rounding: CurrencyRounding) -> Option<Decimal> {
+        let amount = match (self.amount, self.percent) {
+            (Some(amount), _) => amount,
+            (None, Some(percent)) => rounding.round(total * percent / Decimal::ONE_HUNDRED),
+            (None, None) => return None,
+        };
+        Some(amount.min(total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_deposit_amount_from_percent_or_capped_fixed_amount() {
+        let percent = DepositTerms { percent: Some(dec!(33)), ..Default::default() };
+        assert_eq!(percent.amount_for(dec!(100.05), CurrencyRounding::CENTS), Some(dec!(33.02)));
+
+        let fixed = DepositTerms { amount: Some(dec!(500)), ..Default::default() };
+        assert_eq!(fixed.amount_for(dec!(1200), CurrencyRounding::CENTS), Some(dec!(500)));
+        assert_eq!(fixed.amount_for(dec!(300), CurrencyRounding::CENTS), Some(dec!(300)));
+
+        assert_eq!(DepositTerms::default().amount_for(dec!(300), CurrencyRounding::CENTS), None);
+    }
+
+    #[test]
+    fn test_deposit_terms_validation() {
+        let terms = |amount, percent, due_date| DepositTerms { amount, percent, due_date };
+        assert!(terms(Some(dec!(100)), None, Some(date(5))).validate(date(1), date(31)).is_ok());
+        assert!(terms(None, Some(dec!(100)), None).validate(date(1), date(31)).is_ok());
+
+        assert!(terms(Some(dec!(100)), Some(dec!(50)), None).validate(date(1), date(31)).is_err());
+        assert!(terms(Some(dec!(0)), None, None).validate(date(1), date(31)).is_err());
+        assert!(terms(None, Some(dec!(120)), None).validate(date(1), date(31)).is_err());
+        assert!(terms(Some(dec!(100)), None, Some(date(20))).validate(date(1), date(15)).is_err());
+    }
+
+    #[test]
+    fn test_deposit_balance_until_paid() {
+        let due = InvoiceDeposit::from_columns(Some(dec!(300)), None, None, None, dec!(120), date(1)).unwrap();
+        assert_eq!(due.status, DepositStatus::Due);
+        assert_eq!(due.balance_due, dec!(180));
+        assert_eq!(due.due_date, date(1));
+
+        let paid = InvoiceDeposit::from_columns(Some(dec!(300)), Some(dec!(30)), Some(date(4)), Some(Utc::now()), dec!(300), date(1))
+            .unwrap();
+        assert_eq!(paid.status, DepositStatus::Paid);
+        assert_eq!(paid.balance_due, dec!(0));
+        assert_eq!(paid.terms().amount, None);
+
+        assert!(InvoiceDeposit::from_columns(None, None, None, None, dec!(0), date(1)).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::{InvoiceItem, InvoiceListFilter, InvoiceStatus};
+
+/// Columns of an invoice export, one row per line item. The invoice and item
+/// columns use the names the invoice import reads, so an export can be
+/// imported again.
+pub const INVOICE_EXPORT_COLUMNS: [&str; 18] = [
+    "invoice_number",
+    "status",
+    "client_name",
+    "client_email",
+    "issue_date",
+    "due_date",
+    "currency",
+    "description",
+    "quantity",
+    "unit_price",
+    "tax_rate",
+    "line_total",
+    "subtotal",
+    "tax_amount",
+    "discount_amount",
+    "total_amount",
+    "amount_paid",
+    "balance_due",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceExportFormat {
+    #[default]
+    Csv,
+    Xlsx,
+}
+
+impl InvoiceExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            InvoiceExportFormat::Csv => "csv",
+            InvoiceExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            InvoiceExportFormat::Csv => "text/csv",
+            InvoiceExportFormat::Xlsx => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        }
+    }
+}
+
+/// The invoice list's filters, without paging, plus the file format
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct InvoiceExportQuery {
+    pub status: Option<String>,
+    pub client_id: Option<Uuid>,
+    pub date_from: Option<NaiveDate>,
+    pub date_to: Option<NaiveDate>,
+    pub search: Option<String>,
+    pub label_id: Option<Uuid>,
+    #[serde(default)]
+    pub format: InvoiceExportFormat,
+}
+
+impl InvoiceExportQuery {
+    pub fn filter(&self) -> InvoiceListFilter {
+        InvoiceListFilter {
+            status: self.status.as_deref().and_then(InvoiceStatus::parse),
+            client_id: self.client_id,
+            date_from: self.date_from,
+            date_to: self.date_to,
+            search: self.search.clone(),
+            label_id: self.label_id,
+            ids: None,
+        }
+    }
+}
+
+/// A value in an exported row
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportCell {
+    Text(String),
+    Number(Decimal),
+}
+
+impl ExportCell {
+    fn text(value: impl Into<String>) -> Self {
+        ExportCell::Text(value.into())
+    }
+}
+
+impl std::fmt::Display for ExportCell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportCell::Text(text) => f.write_str(text),
+            ExportCell::Number(number) => write!(f, "{}", number.normalize()),
+        }
+    }
+}
+
+/// An invoice as it's exported
+#[derive(Debug, Clone)]
+pub struct InvoiceExportRow {
+    pub invoice_number: String,
+    pub status: String,
+    pub client_name: String,
+    pub client_email: Option<String>,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub currency: String,
+    pub items: Vec<InvoiceItem>,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+    pub amount_paid: Decimal,
+}
+
+impl InvoiceExportRow {
+    /// One row per line item, in `INVOICE_EXPORT_COLUMNS` order, with the
+    /// invoice's own columns repeated on each
+    pub fn lines(&self) -> Vec<Vec<ExportCell>> {
+        let invoice = [
+            ExportCell::text(&self.invoice_number),
+            ExportCell::text(&self.status),
+            ExportCell::text(&self.client_name),
+            ExportCell::text(self.client_email.clone().unwrap_or_default()),
+            ExportCell::text(self.issue_date.to_string()),
+            ExportCell::text(self.due_date.to_string()),
+            ExportCell::text(&self.currency),
+        ];
+        let totals = [
+            ExportCell::Number(self.subtotal),
+            ExportCell::Number(self.tax_amount),
+            ExportCell::Number(self.discount_amount),
+            ExportCell::Number(self.total_amount),
+            ExportCell::Number(self.amount_paid),
+            ExportCell::Number(self.total_amount - self.amount_paid),
+        ];
+
+        let item_cells = |item: Option<&InvoiceItem>| match item {
+            Some(item) => vec![
+                ExportCell::text(&item.description),
+                ExportCell::Number(item.quantity),
+                ExportCell::Number(item.unit_price),
+                // As a percentage, the way the import reads it
+                ExportCell::Number(item.tax_rate * Decimal::ONE_HUNDRED),
+                ExportCell::Number(item.total),
+            ],
+            None => vec![ExportCell::text(""); 5],
+        };
+        let line = |item: Option<&InvoiceItem>| {
+            invoice.iter().cloned().chain(item_cells(item)).chain(totals.iter().cloned()).collect()
+        };
+
+        if self.items.is_empty() {
+            return vec![line(None)];
+        }
+        self.items.iter().map(|item| line(Some(item))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(description: &str, total: i64) -> InvoiceItem {
+        InvoiceItem {
+            id: Uuid::new_v4(),
+            description: description.to_string(),
+            quantity: Decimal::ONE,
+            unit_price: Decimal::new(total, 0),
+            tax_rate: Decimal::ZERO,
+            tax_amount: Decimal::ZERO,
+            total: Decimal::new(total, 0),
+            section: None,
+            taxes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_each_line_item_is_a_row() {
+        let row = InvoiceExportRow {
+            invoice_number: "INV-0001".to_string(),
+            status: "sent".to_string(),
+            client_name: "Ana Co".to_string(),
+            client_email: None,
+            issue_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            due_date: NaiveDate::from_ymd_opt(2024, 3, 31).unwrap(),
+            currency: "USD".to_string(),
+            items: vec![item("Design", 100), item("Support", 50)],
+            subtotal: Decimal::new(150, 0),
+            tax_amount: Decimal::ZERO,
+            discount_amount: Decimal::ZERO,
+            total_amount: Decimal::new(150, 0),
+            amount_paid: Decimal::new(5000, 2),
+        };
+
+        let lines = row.lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() == INVOICE_EXPORT_COLUMNS.len()));
+        assert_eq!(lines[1][0], ExportCell::text("INV-0001"));
+        assert_eq!(lines[1][7], ExportCell::text("Support"));
+        // balance_due
+        assert_eq!(lines[1][17].to_string(), "100");
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::InvoiceStatus;
+
+pub const MAX_LABEL_NAME_LENGTH: usize = 50;
+
+/// A user-defined sub-status such as "Awaiting PO", shown alongside the core status
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceLabel {
+    pub id: Uuid,
+    pub name: String,
+    /// Hex color like "#f59e0b"
+    pub color: Option<String>,
+    /// Core statuses the label applies to; empty means any
+    pub statuses: Vec<InvoiceStatus>,
+    /// Print the label next to the invoice number on the PDF
+    pub show_on_pdf: bool,
+    /// Order in pickers and pipeline views
+    pub position: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl InvoiceLabel {
+    pub fn applies_to(&self, status: &InvoiceStatus) -> bool {
+        self.statuses.is_empty() || self.statuses.contains(status)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateInvoiceLabel {
+    pub name: String,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub statuses: Vec<InvoiceStatus>,
+    #[serde(default)]
+    pub show_on_pdf: bool,
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateInvoiceLabel {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub statuses: Option<Vec<InvoiceStatus>>,
+    pub show_on_pdf: Option<bool>,
+    pub position: Option<i32>,
+}
+
+/// Body of `PUT /invoices/{id}/label`; null clears the label
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetInvoiceLabel {
+    pub label_id: Option<Uuid>,
+}
+
+/// Trimmed label name, or why it can't be used
+pub fn normalize_label_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Label name is required".to_string());
+    }
+    if name.chars().count() > MAX_LABEL_NAME_LENGTH {
+        return Err(format!("Label name must be at most {} characters", MAX_LABEL_NAME_LENGTH));
+    }
+    Ok(name.to_string())
+}
+
+/// Lowercased `#rrggbb` color
+pub fn normalize_label_color(color: &str) -> Result<String, String> {
+    let color = color.trim();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid {
+        Ok(color.to_ascii_lowercase())
+    } else {
+        Err(format!("Invalid color {}: expected #rrggbb", color))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names_and_colors() {
+        assert_eq!(normalize_label_name("  Awaiting PO ").unwrap(), "Awaiting PO");
+        assert!(normalize_label_name("   ").is_err());
+        assert!(normalize_label_name(&"x".repeat(51)).is_err());
+
+        assert_eq!(normalize_label_color("#F59E0B").unwrap(), "#f59e0b");
+        assert!(normalize_label_color("f59e0b").is_err());
+        assert!(normalize_label_color("#f59e0").is_err());
+        assert!(normalize_label_color("#zzzzzz").is_err());
+    }
+
+    #[test]
+    fn empty_statuses_apply_to_any_status() {
+        let mut label = InvoiceLabel {
+            id: Uuid::new_v4(),
+            name: "In review".to_string(),
+            color: None,
+            statuses: Vec::new(),
+            show_on_pdf: false,
+            position: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert!(label.applies_to(&InvoiceStatus::Paid));
+
+        label.statuses = vec![InvoiceStatus::Draft, InvoiceStatus::Sent];
+        assert!(label.applies_to(&InvoiceStatus::Sent));
+        assert!(!label.applies_to(&InvoiceStatus::Paid));
+    }
+}
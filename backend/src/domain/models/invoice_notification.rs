@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Email,
+    Whatsapp,
+}
+
+impl NotificationChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationChannel::Email => "email",
+            NotificationChannel::Whatsapp => "whatsapp",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "email" => Some(NotificationChannel::Email),
+            "whatsapp" => Some(NotificationChannel::Whatsapp),
+            _ => None,
+        }
+    }
+}
+
+/// One send attempt of an invoice, with the exact recipients it went to
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceNotification {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub channel: NotificationChannel,
+    pub recipients: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: Option<String>,
+    pub message: Option<String>,
+    pub delivered: bool,
+    pub error: Option<String>,
+    /// The failed attempt this one retried
+    pub resent_from_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of the latest send attempt on one channel
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationDelivery {
+    pub channel: NotificationChannel,
+    /// Pass to `POST /invoices/{id}/notifications/{notification_id}/resend` after a failure
+    pub notification_id: Uuid,
+    pub delivered: bool,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl From<InvoiceNotification> for NotificationDelivery {
+    fn from(notification: InvoiceNotification) -> Self {
+        Self {
+            channel: notification.channel,
+            notification_id: notification.id,
+            delivered: notification.delivered,
+            error: notification.error,
+            attempted_at: notification.created_at,
+        }
+    }
+}
+
+/// A send attempt to record in the log
+#[derive(Debug, Clone)]
+pub struct NewInvoiceNotification {
+    pub channel: NotificationChannel,
+    pub recipients: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: Option<String>,
+    pub message: Option<String>,
+    pub error: Option<String>,
+    pub resent_from_id: Option<Uuid>,
+}
+
+/// Per-send overrides for emailing an invoice
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvoiceSendOptions {
+    /// Send to this address instead of the client's email
+    pub email: Option<String>,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    /// May use the `{{variable}}` placeholders supported in invoice terms
+    pub subject: Option<String>,
+    /// Personal message shown above the standard text; supports the same placeholders
+    pub message: Option<String>,
+    /// Attach a calendar event for the due date to the email
+    #[serde(default)]
+    pub attach_calendar: bool,
+    /// Member sending the invoice; their signature overrides the business default
+    #[serde(skip)]
+    pub sender_id: Option<Uuid>,
+}
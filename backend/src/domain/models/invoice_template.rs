@@ -0,0 +1,305 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::{normalize_currency_code, CreateInvoiceItem, TaxComponent};
+
+pub const MAX_INVOICE_TEMPLATE_NAME_LENGTH: usize = 100;
+
+/// Variables supported in terms/notes templates, e.g. `Payment due within {{due_days}} days`
+pub const TEMPLATE_VARIABLES: [&str; 5] = [
+    "late_fee_rate",
+    "due_days",
+    "due_date",
+    "client_name",
+    "company_name",
+];
+
+/// Values substituted into terms/notes when an invoice is created, so the stored
+/// text reflects the settings and client record at that moment
+#[derive(Debug, Clone)]
+pub struct TemplateVariables {
+    /// Monthly late fee, in percent
+    pub late_fee_rate: Option<f64>,
+    pub due_days: i64,
+    pub due_date: NaiveDate,
+    pub client_name: String,
+    pub company_name: Option<String>,
+}
+
+impl TemplateVariables {
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "late_fee_rate" => Some(format!("{}%", self.late_fee_rate.unwrap_or(0.0))),
+            "due_days" => Some(self.due_days.to_string()),
+            "due_date" => Some(self.due_date.to_string()),
+            "client_name" => Some(self.client_name.clone()),
+            "company_name" => Some(self.company_name.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+
+    /// Replace `{{variable}}` placeholders; unknown placeholders are left untouched
+    pub fn render(&self, template: &str) -> String {
+        render_placeholders(template, |name| self.value(name))
+    }
+}
+
+/// Replace `{{name}}` placeholders with `lookup(name)`; placeholders it doesn't know
+/// (returns None for) are left untouched
+pub fn render_placeholders(template: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after_open = &rest[start + 2..];
+        let end = match after_open.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+
+        output.push_str(&rest[..start]);
+        match lookup(after_open[..end].trim()) {
+            Some(value) => output.push_str(&value),
+            None => output.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after_open[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Named, reusable invoice content. It isn't tied to a client: instantiating it
+/// with one creates a draft, and what the template leaves out comes from the
+/// client's invoice defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceTemplate {
+    pub id: Uuid,
+    pub name: String,
+    /// Lines without a tax rate take the client's default tax
+    pub items: Vec<CreateInvoiceItem>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub discount_amount: Option<Decimal>,
+    pub tax_included: bool,
+    /// None invoices in the client's currency
+    pub currency: Option<String>,
+    /// Days from the issue date to the due date; None uses the client's payment terms
+    pub due_days: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateInvoiceTemplate {
+    pub name: String,
+    pub items: Vec<CreateInvoiceItem>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub discount_amount: Option<Decimal>,
+    #[serde(default)]
+    pub tax_included: bool,
+    pub currency: Option<String>,
+    pub due_days: Option<i32>,
+}
+
+/// Fields left out are unchanged. Empty notes or terms remove them.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateInvoiceTemplate {
+    pub name: Option<String>,
+    pub items: Option<Vec<CreateInvoiceItem>>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub discount_amount: Option<Decimal>,
+    pub tax_included: Option<bool>,
+    pub currency: Option<String>,
+    pub due_days: Option<i32>,
+}
+
+/// Body of `POST /invoice-templates/{id}/invoices`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceFromTemplate {
+    pub client_id: Uuid,
+    /// Defaults to today
+    #[serde(default)]
+    pub issue_date: Option<NaiveDate>,
+    /// Defaults to the issue date plus the template's `due_days`, then the client's payment terms
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+    /// One of the client's active projects
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+}
+
+/// Body of `POST /invoices/{id}/duplicate`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct DuplicateInvoice {
+    /// Defaults to today
+    #[serde(default)]
+    pub issue_date: Option<NaiveDate>,
+    /// Defaults to the issue date plus the original's payment terms
+    #[serde(default)]
+    pub due_date: Option<NaiveDate>,
+}
+
+/// Trimmed template name, or why it can't be used
+pub fn normalize_template_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name is required".to_string());
+    }
+    if name.chars().count() > MAX_INVOICE_TEMPLATE_NAME_LENGTH {
+        return Err(format!("Template name must be at most {} characters", MAX_INVOICE_TEMPLATE_NAME_LENGTH));
+    }
+    Ok(name.to_string())
+}
+
+/// Template lines with trimmed descriptions, or why one can't be invoiced
+pub fn normalize_template_items(items: Vec<CreateInvoiceItem>) -> Result<Vec<CreateInvoiceItem>, String> {
+    if items.is_empty() {
+        return Err("A template needs at least one item".to_string());
+    }
+
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut item)| {
+            let line = index + 1;
+            item.description = item.description.trim().to_string();
+            if item.description.is_empty() {
+                return Err(format!("Item {} needs a description", line));
+            }
+            if item.quantity <= Decimal::ZERO {
+                return Err(format!("Item {} quantity must be positive", line));
+            }
+            if item.unit_price < Decimal::ZERO {
+                return Err(format!("Item {} unit price must not be negative", line));
+            }
+            if item.tax_rate.is_some_and(|rate| rate < Decimal::ZERO) {
+                return Err(format!("Item {} tax rate must not be negative", line));
+            }
+            TaxComponent::validate_all(&item.taxes).map_err(|e| format!("Item {}: {}", line, e))?;
+            item.section = item.section.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+            Ok(item)
+        })
+        .collect()
+}
+
+/// Checks the template's discount, currency and payment terms; the currency is uppercased
+pub fn normalize_template_terms(
+    discount_amount: Option<Decimal>,
+    currency: Option<String>,
+    due_days: Option<i32>,
+) -> Result<(Option<String>, Option<i32>), String> {
+    if discount_amount.is_some_and(|discount| discount < Decimal::ZERO) {
+        return Err("Discount must not be negative".to_string());
+    }
+    let currency = match currency {
+        Some(code) => Some(normalize_currency_code(&code).ok_or_else(|| format!("Invalid currency code '{}'", code))?),
+        None => None,
+    };
+    if due_days.is_some_and(|days| days < 0) {
+        return Err("Due days must not be negative".to_string());
+    }
+    Ok((currency, due_days))
+}
+
+/// Notes or terms as stored; blank text is none
+pub fn normalize_template_text(text: Option<String>) -> Option<String> {
+    text.filter(|t| !t.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn variables() -> TemplateVariables {
+        TemplateVariables {
+            late_fee_rate: Some(1.5),
+            due_days: 14,
+            due_date: NaiveDate::from_ymd_opt(2025, 2, 15).unwrap(),
+            client_name: "Acme Corp".to_string(),
+            company_name: Some("FlashBill Studio".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_known_variables() {
+        let rendered = variables().render(
+            "{{client_name}}: payment due within {{due_days}} days ({{ due_date }}). Late fee {{late_fee_rate}} per month.",
+        );
+        assert_eq!(
+            rendered,
+            "Acme Corp: payment due within 14 days (2025-02-15). Late fee 1.5% per month."
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_unclosed_placeholders_are_kept() {
+        let vars = variables();
+        assert_eq!(vars.render("Hi {{nickname}}, from {{company_name}}"), "Hi {{nickname}}, from FlashBill Studio");
+        assert_eq!(vars.render("Due in {{due_days"), "Due in {{due_days");
+        assert_eq!(vars.render("No variables here"), "No variables here");
+    }
+
+    #[test]
+    fn test_missing_late_fee_renders_zero() {
+        let vars = TemplateVariables { late_fee_rate: None, ..variables() };
+        assert_eq!(vars.render("{{late_fee_rate}}"), "0%");
+    }
+
+    fn item(description: &str, quantity: Decimal, unit_price: Decimal) -> CreateInvoiceItem {
+        CreateInvoiceItem {
+            description: description.to_string(),
+            quantity,
+            unit_price,
+            tax_rate: None,
+            section: None,
+            taxes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_template_name() {
+        assert_eq!(normalize_template_name("  Monthly retainer ").unwrap(), "Monthly retainer");
+        assert!(normalize_template_name("   ").is_err());
+        assert!(normalize_template_name(&"x".repeat(MAX_INVOICE_TEMPLATE_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_template_items() {
+        let items = normalize_template_items(vec![
+            CreateInvoiceItem { section: Some("  ".to_string()), ..item(" Design ", dec!(2), dec!(50)) },
+            item("Hosting", dec!(1), dec!(0)),
+        ])
+        .unwrap();
+        assert_eq!(items[0].description, "Design");
+        assert_eq!(items[0].section, None);
+        assert_eq!(items[1].unit_price, dec!(0));
+
+        assert!(normalize_template_items(vec![]).is_err());
+        assert_eq!(
+            normalize_template_items(vec![item("Design", dec!(1), dec!(50)), item(" ", dec!(1), dec!(50))]).unwrap_err(),
+            "Item 2 needs a description"
+        );
+        assert!(normalize_template_items(vec![item("Design", dec!(0), dec!(50))]).is_err());
+        assert!(normalize_template_items(vec![item("Design", dec!(1), dec!(-1))]).is_err());
+        assert!(normalize_template_items(vec![CreateInvoiceItem { tax_rate: Some(dec!(-0.1)), ..item("Design", dec!(1), dec!(5)) }]).is_err());
+    }
+
+    #[test]
+    fn test_template_terms() {
+        assert_eq!(
+            normalize_template_terms(Some(dec!(10)), Some("eur".to_string()), Some(30)).unwrap(),
+            (Some("EUR".to_string()), Some(30))
+        );
+        assert_eq!(normalize_template_terms(None, None, None).unwrap(), (None, None));
+        assert!(normalize_template_terms(Some(dec!(-1)), None, None).is_err());
+        assert!(normalize_template_terms(None, Some("EURO".to_string()), None).is_err());
+        assert!(normalize_template_terms(None, None, Some(-1)).is_err());
+    }
+}
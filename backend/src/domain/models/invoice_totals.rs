@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+
+/// Round a money amount to cents
+pub fn round_money(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+/// Validator for amounts and quantities, which must be at least 0.01
+pub fn validate_min_cent(value: &Decimal) -> Result<(), validator::ValidationError> {
+    if *value >= Decimal::new(1, 2) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("range"))
+    }
+}
+
+/// Validator for rates between 0 and 100
+pub fn validate_rate_range(value: &Decimal) -> Result<(), validator::ValidationError> {
+    if (Decimal::ZERO..=Decimal::ONE_HUNDRED).contains(value) {
+        Ok(())
+    } else {
+        Err(validator::ValidationError::new("range"))
+    }
+}
+
+/// Where amounts are rounded to the currency's minor unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingPolicy {
+    /// Each line's net and tax are rounded; invoice totals are sums of rounded lines
+    #[default]
+    PerLine,
+    /// Lines keep full precision; only the invoice totals are rounded
+    PerInvoice,
+}
+
+/// How halves (and everything else) are rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// 0.005 becomes 0.01; what customers expect on an invoice
+    HalfUp,
+    /// 0.005 becomes 0.00, 0.015 becomes 0.02 (banker's rounding)
+    HalfEven,
+    /// Always towards zero
+    Truncate,
+}
+
+impl RoundingMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "half_up" => Some(RoundingMode::HalfUp),
+            "half_even" => Some(RoundingMode::HalfEven),
+            "truncate" => Some(RoundingMode::Truncate),
+            _ => None,
+        }
+    }
+
+    fn strategy(self) -> RoundingStrategy {
+        match self {
+            RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+            RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+            RoundingMode::Truncate => RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Money columns are NUMERIC(15,2), so no currency can keep more than two decimals
+const MAX_MONEY_SCALE: u32 = 2;
+
+/// Rounding rule for amounts in one currency
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyRounding {
+    /// Decimal places kept: 2 for USD, 0 for JPY
+    pub scale: u32,
+    pub mode: RoundingMode,
+}
+
+impl CurrencyRounding {
+    /// Whole cents, halves up
+    pub const CENTS: Self = Self { scale: 2, mode: RoundingMode::HalfUp };
+
+    pub fn round(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.scale, self.mode.strategy())
+    }
+}
+
+impl Default for CurrencyRounding {
+    fn default() -> Self {
+        Self::CENTS
+    }
+}
+
+/// ISO 4217 minor units, capped at what the money columns store (three-decimal
+/// currencies such as KWD are kept to two)
+pub fn iso_minor_units(currency: &str) -> u32 {
+    match currency {
+        "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF" | "UGX" | "UYI" | "VND"
+        | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        _ => MAX_MONEY_SCALE,
+    }
+}
+
+/// Per-currency rounding used when invoice totals and tax are calculated. Currencies round
+/// halves up to their ISO 4217 minor unit unless overridden in `CURRENCY_ROUNDING`, a comma
+/// separated list of `CODE:scale[:mode]`, e.g. `CHF:2:half_even,HUF:0`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CurrencyRoundingRules {
+    overrides: HashMap<String, CurrencyRounding>,
+}
+
+impl CurrencyRoundingRules {
+    pub fn from_env() -> Self {
+        let Ok(spec) = std::env::var("CURRENCY_ROUNDING") else {
+            return Self::default();
+        };
+        Self::parse(&spec).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring CURRENCY_ROUNDING: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut overrides = HashMap::new();
+        for rule in spec.split(',').map(str::trim).filter(|rule| !rule.is_empty()) {
+            let mut parts = rule.split(':').map(str::trim);
+            let currency = parts.next().unwrap_or_default().to_ascii_uppercase();
+            if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!("invalid currency in rule '{}'", rule));
+            }
+
+            let scale = parts
+                .next()
+                .and_then(|scale| scale.parse::<u32>().ok())
+                .filter(|scale| *scale <= MAX_MONEY_SCALE)
+                .ok_or_else(|| format!("rule '{}' needs a scale between 0 and {}", rule, MAX_MONEY_SCALE))?;
+            let mode = match parts.next() {
+                Some(mode) => RoundingMode::parse(mode).ok_or_else(|| format!("unknown rounding mode '{}'", mode))?,
+                None => RoundingMode::HalfUp,
+            };
+            if parts.next().is_some() {
+                return Err(format!("too many fields in rule '{}'", rule));
+            }
+
+            overrides.insert(currency, CurrencyRounding { scale, mode });
+        }
+        Ok(Self { overrides })
+    }
+
+    pub fn for_currency(&self, currency: &str) -> CurrencyRounding {
+        let currency = currency.trim().to_ascii_uppercase();
+        self.overrides.get(&currency).copied().unwrap_or(CurrencyRounding {
+            scale: iso_minor_units(&currency),
+            mode: RoundingMode::HalfUp,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LineInput {
+    pub quantity: Decimal,
+    /// Net price, or gross price when tax is included
+    pub unit_price: Decimal,
+    pub tax_rate: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineAmounts {
+    /// Net amount before tax
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub total: Decimal,
+}
+
+impl LineAmounts {
+    pub fn calculate(line: &LineInput, tax_included: bool) -> Self {
+        let gross_or_net = line.quantity * line.unit_price;
+
+        if tax_included {
+            let subtotal = gross_or_net / (Decimal::ONE + line.tax_rate);
+            Self {
+                subtotal,
+                tax_amount: gross_or_net - subtotal,
+                total: gross_or_net,
+            }
+        } else {
+            let tax_amount = gross_or_net * line.tax_rate;
+            Self {
+                subtotal: gross_or_net,
+                tax_amount,
+                total: gross_or_net + tax_amount,
+            }
+        }
+    }
+
+    fn rounded(self, tax_included: bool, rounding: CurrencyRounding) -> Self {
+        if tax_included {
+            // Keep the gross the customer sees; the rounding lands in the net amount
+            let total = rounding.round(self.total);
+            let tax_amount = rounding.round(self.tax_amount);
+            Self { subtotal: total - tax_amount, tax_amount, total }
+        } else {
+            let subtotal = rounding.round(self.subtotal);
+            let tax_amount = rounding.round(self.tax_amount);
+            Self { subtotal, tax_amount, total: subtotal + tax_amount }
+        }
+    }
+}
+
+/// Invoice money math shared by invoice creation and updates
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvoiceTotals {
+    pub lines: Vec<LineAmounts>,
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    /// Discount actually applied (never more than subtotal + tax)
+    pub discount: Decimal,
+    pub total: Decimal,
+}
+
+impl InvoiceTotals {
+    pub fn calculate(
+        lines: &[LineInput],
+        discount: Decimal,
+        tax_included: bool,
+        policy: RoundingPolicy,
+        rounding: CurrencyRounding,
+    ) -> Self {
+        let lines: Vec<LineAmounts> = lines
+            .iter()
+            .map(|line| {
+                let amounts = LineAmounts::calculate(line, tax_included);
+                match policy {
+                    RoundingPolicy::PerLine => amounts.rounded(tax_included, rounding),
+                    RoundingPolicy::PerInvoice => amounts,
+                }
+            })
+            .collect();
+
+        let subtotal = rounding.round(lines.iter().map(|l| l.subtotal).sum());
+        let tax_amount = rounding.round(lines.iter().map(|l| l.tax_amount).sum());
+        let gross = subtotal + tax_amount;
+        let discount = rounding.round(discount.max(Decimal::ZERO)).min(gross);
+
+        Self {
+            lines,
+            subtotal,
+            tax_amount,
+            discount,
+            total: gross - discount,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rust_decimal_macros::dec;
+
+    const CENT: Decimal = dec!(0.01);
+
+    fn line_strategy() -> impl Strategy<Value = LineInput> {
+        (
+            // Whole and fractional quantities (hours, kg)
+            (1i64..=1000, 0i64..=4).prop_map(|(q, quarters)| Decimal::new(q * 100 + quarters * 25, 2)),
+            // Prices in cents up to 100k
+            (0i64..=10_000_000).prop_map(|cents| Decimal::new(cents, 2)),
+            // Common and odd tax rates, 0-30%
+            prop_oneof![
+                Just(dec!(0)),
+                Just(dec!(0.05)),
+                Just(dec!(0.0725)),
+                Just(dec!(0.2)),
+                (0i64..=3000).prop_map(|bp| Decimal::new(bp, 4)),
+            ],
+        )
+            .prop_map(|(quantity, unit_price, tax_rate)| LineInput { quantity, unit_price, tax_rate })
+    }
+
+    fn rounding_strategy() -> impl Strategy<Value = RoundingPolicy> {
+        prop_oneof![Just(RoundingPolicy::PerLine), Just(RoundingPolicy::PerInvoice)]
+    }
+
+    fn is_cents(amount: Decimal) -> bool {
+        amount == amount.round_dp(2)
+    }
+
+    #[test]
+    fn test_simple_exclusive_invoice() {
+        let totals = InvoiceTotals::calculate(
+            &[LineInput { quantity: dec!(2), unit_price: dec!(50), tax_rate: dec!(0.2) }],
+            Decimal::ZERO,
+            false,
+            RoundingPolicy::PerLine,
+            CurrencyRounding::CENTS,
+        );
+        assert_eq!(totals.subtotal, dec!(100));
+        assert_eq!(totals.tax_amount, dec!(20));
+        assert_eq!(totals.total, dec!(120));
+    }
+
+    #[test]
+    fn test_tax_on_thirds_sums_exactly() {
+        // 3 x 0.10 at 7.25%: f64 summed this to 0.32000000000000006
+        let line = LineInput { quantity: dec!(1), unit_price: dec!(0.10), tax_rate: dec!(0.0725) };
+        let totals = InvoiceTotals::calculate(&[line; 3], Decimal::ZERO, false, RoundingPolicy::PerLine, CurrencyRounding::CENTS);
+        assert_eq!(totals.subtotal, dec!(0.30));
+        assert_eq!(totals.tax_amount, dec!(0.03));
+        assert_eq!(totals.total, dec!(0.33));
+    }
+
+    #[test]
+    fn test_currencies_round_to_their_minor_unit() {
+        let rules = CurrencyRoundingRules::default();
+        let line = LineInput { quantity: dec!(3), unit_price: dec!(333.5), tax_rate: dec!(0.1) };
+
+        let yen = InvoiceTotals::calculate(&[line], Decimal::ZERO, false, RoundingPolicy::PerLine, rules.for_currency("jpy"));
+        assert_eq!(yen.subtotal, dec!(1001));
+        assert_eq!(yen.tax_amount, dec!(100));
+        assert_eq!(yen.total, dec!(1101));
+
+        let dinar = InvoiceTotals::calculate(&[line], Decimal::ZERO, false, RoundingPolicy::PerLine, rules.for_currency("KWD"));
+        assert_eq!(dinar.tax_amount, dec!(100.05));
+        assert_eq!(rules.for_currency("EUR"), CurrencyRounding::CENTS);
+    }
+
+    #[test]
+    fn test_rounding_rules_can_be_overridden() {
+        let rules = CurrencyRoundingRules::parse("chf:2:half_even, HUF:0").unwrap();
+        let chf = rules.for_currency("CHF");
+        assert_eq!(chf, CurrencyRounding { scale: 2, mode: RoundingMode::HalfEven });
+        assert_eq!(chf.round(dec!(0.125)), dec!(0.12));
+        assert_eq!(CurrencyRounding::CENTS.round(dec!(0.125)), dec!(0.13));
+        assert_eq!(rules.for_currency("HUF").round(dec!(99.5)), dec!(100));
+
+        assert!(CurrencyRoundingRules::parse("EURO:2").is_err());
+        assert!(CurrencyRoundingRules::parse("EUR").is_err());
+        assert!(CurrencyRoundingRules::parse("EUR:2:ceiling").is_err());
+        // More decimals than the money columns store
+        assert!(CurrencyRoundingRules::parse("KWD:3").is_err());
+        assert_eq!(CurrencyRoundingRules::parse("").unwrap(), CurrencyRoundingRules::default());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_total_is_subtotal_plus_tax_minus_discount(
+            lines in prop::collection::vec(line_strategy(), 0..20),
+            discount in 0i64..=5_000_000,
+            tax_included in any::<bool>(),
+            rounding in rounding_strategy(),
+        ) {
+            let totals = InvoiceTotals::calculate(&lines, Decimal::new(discount, 2), tax_included, rounding, CurrencyRounding::CENTS);
+
+            prop_assert_eq!(totals.subtotal + totals.tax_amount - totals.discount, totals.total);
+            prop_assert!(is_cents(totals.subtotal));
+            prop_assert!(is_cents(totals.tax_amount));
+            prop_assert!(is_cents(totals.discount));
+            prop_assert!(is_cents(totals.total));
+        }
+
+        #[test]
+        fn prop_amounts_are_never_negative(
+            lines in prop::collection::vec(line_strategy(), 0..20),
+            discount in -1_000_000i64..=100_000_000,
+            tax_included in any::<bool>(),
+            rounding in rounding_strategy(),
+        ) {
+            let totals = InvoiceTotals::calculate(&lines, Decimal::new(discount, 2), tax_included, rounding, CurrencyRounding::CENTS);
+
+            prop_assert!(totals.subtotal >= Decimal::ZERO);
+            prop_assert!(totals.tax_amount >= Decimal::ZERO);
+            prop_assert!(totals.discount >= Decimal::ZERO);
+            prop_assert!(totals.total >= Decimal::ZERO);
+            for line in &totals.lines {
+                prop_assert!(line.subtotal >= Decimal::ZERO && line.tax_amount >= Decimal::ZERO && line.total >= Decimal::ZERO);
+            }
+        }
+
+        #[test]
+        fn prop_per_line_rounding_sums_exactly(
+            lines in prop::collection::vec(line_strategy(), 1..20),
+            tax_included in any::<bool>(),
+        ) {
+            let totals = InvoiceTotals::calculate(&lines, Decimal::ZERO, tax_included, RoundingPolicy::PerLine, CurrencyRounding::CENTS);
+
+            let line_subtotals: Decimal = totals.lines.iter().map(|l| l.subtotal).sum();
+            let line_taxes: Decimal = totals.lines.iter().map(|l| l.tax_amount).sum();
+            prop_assert_eq!(line_subtotals, totals.subtotal);
+            prop_assert_eq!(line_taxes, totals.tax_amount);
+            for line in &totals.lines {
+                prop_assert_eq!(line.subtotal + line.tax_amount, line.total);
+            }
+        }
+
+        #[test]
+        fn prop_rounding_policies_agree_within_a_cent_per_line(
+            lines in prop::collection::vec(line_strategy(), 1..20),
+            tax_included in any::<bool>(),
+        ) {
+            let per_line = InvoiceTotals::calculate(&lines, Decimal::ZERO, tax_included, RoundingPolicy::PerLine, CurrencyRounding::CENTS);
+            let per_invoice = InvoiceTotals::calculate(&lines, Decimal::ZERO, tax_included, RoundingPolicy::PerInvoice, CurrencyRounding::CENTS);
+
+            // Each line can drift by a cent (net and tax rounded), plus one for the invoice rounding
+            let tolerance = CENT * Decimal::from(lines.len() + 1);
+            prop_assert!((per_line.total - per_invoice.total).abs() <= tolerance);
+            prop_assert!((per_line.tax_amount - per_invoice.tax_amount).abs() <= tolerance);
+        }
+
+        #[test]
+        fn prop_inclusive_matches_exclusive(
+            line in line_strategy(),
+            rounding in rounding_strategy(),
+        ) {
+            // Pricing the same line tax-inclusive must yield the same net, tax and gross
+            let exclusive = InvoiceTotals::calculate(&[line], Decimal::ZERO, false, rounding, CurrencyRounding::CENTS);
+            let gross_price = line.unit_price * (Decimal::ONE + line.tax_rate);
+            let inclusive = InvoiceTotals::calculate(
+                &[LineInput { unit_price: gross_price, ..line }],
+                Decimal::ZERO,
+                true,
+                rounding,
+                CurrencyRounding::CENTS,
+            );
+
+            // Net and tax are each rounded once, so the gross can drift by a cent from each
+            prop_assert!((exclusive.subtotal - inclusive.subtotal).abs() <= CENT);
+            prop_assert!((exclusive.tax_amount - inclusive.tax_amount).abs() <= CENT);
+            prop_assert!((exclusive.total - inclusive.total).abs() <= CENT * dec!(2));
+        }
+
+        #[test]
+        fn prop_discount_never_exceeds_gross(
+            lines in prop::collection::vec(line_strategy(), 0..5),
+            discount in 0i64..=1_000_000_000,
+            rounding in rounding_strategy(),
+        ) {
+            let requested = Decimal::new(discount, 2);
+            let totals = InvoiceTotals::calculate(&lines, requested, false, rounding, CurrencyRounding::CENTS);
+
+            prop_assert!(totals.discount <= totals.subtotal + totals.tax_amount);
+            prop_assert!(totals.discount <= requested);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{DateTime, Days, Months, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+pub const MAX_LATE_FEE_GRACE_DAYS: i32 = 365;
+pub const MAX_LATE_FEE_APPLICATIONS: i32 = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LateFeeType {
+    /// `amount` in the invoice currency
+    Flat,
+    /// `amount` percent of the open balance when the fee is added
+    Percentage,
+}
+
+impl LateFeeType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LateFeeType::Flat => "flat",
+            LateFeeType::Percentage => "percentage",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "flat" => Some(LateFeeType::Flat),
+            "percentage" => Some(LateFeeType::Percentage),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LateFeeRecurrence {
+    #[default]
+    Once,
+    Weekly,
+    Monthly,
+}
+
+impl LateFeeRecurrence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LateFeeRecurrence::Once => "once",
+            LateFeeRecurrence::Weekly => "weekly",
+            LateFeeRecurrence::Monthly => "monthly",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "once" => Some(LateFeeRecurrence::Once),
+            "weekly" => Some(LateFeeRecurrence::Weekly),
+            "monthly" => Some(LateFeeRecurrence::Monthly),
+            _ => None,
+        }
+    }
+
+    /// When the fee after one charged on `last` is due; None if it doesn't repeat
+    fn next_after(&self, last: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            LateFeeRecurrence::Once => None,
+            LateFeeRecurrence::Weekly => last.checked_add_days(Days::new(7)),
+            LateFeeRecurrence::Monthly => last.checked_add_months(Months::new(1)),
+        }
+    }
+}
+
+/// A user's late fee rule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct LateFeePolicy {
+    pub enabled: bool,
+    pub fee_type: LateFeeType,
+    pub amount: Decimal,
+    /// Days after the due date before the first fee
+    pub grace_days: i32,
+    pub recurrence: LateFeeRecurrence,
+    /// Most fees added to one invoice; unlimited when unset
+    pub max_applications: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LateFeePolicy {
+    /// Whether another fee is due on `today` for an invoice due on `due_date` that
+    /// has `applied` fees so far, the last one charged on `last_applied_on`
+    pub fn fee_due(&self, due_date: NaiveDate, today: NaiveDate, applied: i32, last_applied_on: Option<NaiveDate>) -> bool {
+        if !self.enabled || self.max_applications.is_some_and(|max| applied >= max) {
+            return false;
+        }
+        // The first fee comes the day after the grace period ends
+        let first_due = due_date.checked_add_days(Days::new(self.grace_days.max(0) as u64 + 1));
+        if first_due.is_none_or(|first| today < first) {
+            return false;
+        }
+
+        match last_applied_on {
+            None => applied == 0,
+            Some(last) => self.recurrence.next_after(last).is_some_and(|next| today >= next),
+        }
+    }
+
+    /// The fee for an invoice with `balance` left to pay
+    pub fn fee_amount(&self, balance: Decimal) -> Decimal {
+        match self.fee_type {
+            LateFeeType::Flat => self.amount,
+            LateFeeType::Percentage => (balance * self.amount / Decimal::ONE_HUNDRED).round_dp(2),
+        }
+    }
+
+    /// Line item text saying why the fee was added
+    pub fn describe(&self, balance: Decimal, days_overdue: i64) -> String {
+        match self.fee_type {
+            LateFeeType::Flat => format!("Late fee ({} days overdue)", days_overdue),
+            LateFeeType::Percentage => format!(
+                "Late fee: {}% of {:.2} outstanding ({} days overdue)",
+                self.amount.normalize(),
+                balance,
+                days_overdue
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateLateFeePolicy {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub fee_type: LateFeeType,
+    pub amount: Decimal,
+    #[serde(default)]
+    pub grace_days: i32,
+    #[serde(default)]
+    pub recurrence: LateFeeRecurrence,
+    #[serde(default)]
+    pub max_applications: Option<i32>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl UpdateLateFeePolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.amount <= Decimal::ZERO {
+            return Err("amount must be greater than 0".to_string());
+        }
+        if self.fee_type == LateFeeType::Percentage && self.amount > Decimal::ONE_HUNDRED {
+            return Err("A percentage fee must be at most 100".to_string());
+        }
+        if self.amount.scale() > 2 {
+            return Err("amount can have at most 2 decimal places".to_string());
+        }
+        if !(0..=MAX_LATE_FEE_GRACE_DAYS).contains(&self.grace_days) {
+            return Err(format!("grace_days must be between 0 and {}", MAX_LATE_FEE_GRACE_DAYS));
+        }
+        if self.max_applications.is_some_and(|max| !(1..=MAX_LATE_FEE_APPLICATIONS).contains(&max)) {
+            return Err(format!("max_applications must be between 1 and {}", MAX_LATE_FEE_APPLICATIONS));
+        }
+        Ok(())
+    }
+}
+
+/// An unpaid, past-due invoice whose owner has late fees enabled
+#[derive(Debug, Clone)]
+pub struct LateFeeCandidate {
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub due_date: NaiveDate,
+    /// Total less payments
+    pub balance: Decimal,
+    pub applied: i32,
+    pub last_applied_on: Option<NaiveDate>,
+    pub policy: LateFeePolicy,
+}
+
+/// A fee added to an invoice
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AppliedLateFee {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    /// Line item the fee was added as
+    pub item_id: Uuid,
+    /// 1 for the invoice's first fee, 2 for the next...
+    pub sequence: i32,
+    pub amount: Decimal,
+    pub days_overdue: i32,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn policy(recurrence: LateFeeRecurrence) -> LateFeePolicy {
+        LateFeePolicy {
+            enabled: true,
+            fee_type: LateFeeType::Flat,
+            amount: dec!(25),
+            grace_days: 5,
+            recurrence,
+            max_applications: Some(3),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_first_fee_waits_for_grace_period() {
+        let once = policy(LateFeeRecurrence::Once);
+        let due = date(1);
+        assert!(!once.fee_due(due, date(6), 0, None));
+        assert!(once.fee_due(due, date(7), 0, None));
+        // Charged once and never again
+        assert!(!once.fee_due(due, date(30), 1, Some(date(7))));
+
+        let disabled = LateFeePolicy { enabled: false, ..once };
+        assert!(!disabled.fee_due(due, date(30), 0, None));
+    }
+
+    #[test]
+    fn test_recurring_fees_follow_the_last_one() {
+        let weekly = policy(LateFeeRecurrence::Weekly);
+        let due = date(1);
+        assert!(!weekly.fee_due(due, date(13), 1, Some(date(7))));
+        assert!(weekly.fee_due(due, date(14), 1, Some(date(7))));
+        // Capped by max_applications
+        assert!(!weekly.fee_due(due, date(28), 3, Some(date(21))));
+
+        let monthly = policy(LateFeeRecurrence::Monthly);
+        let feb = NaiveDate::from_ymd_opt(2026, 2, 28).unwrap();
+        assert!(!monthly.fee_due(date(1), date(27), 1, Some(feb)));
+        assert!(monthly.fee_due(date(1), date(28), 1, Some(feb)));
+    }
+
+    #[test]
+    fn test_fee_amount() {
+        let flat = policy(LateFeeRecurrence::Once);
+        assert_eq!(flat.fee_amount(dec!(1000)), dec!(25));
+
+        let percentage = LateFeePolicy { fee_type: LateFeeType::Percentage, amount: dec!(1.5), ..flat };
+        assert_eq!(percentage.fee_amount(dec!(333.33)), dec!(5.00));
+        assert_eq!(percentage.describe(dec!(333.33), 12), "Late fee: 1.5% of 333.33 outstanding (12 days overdue)");
+    }
+
+    #[test]
+    fn test_update_validation() {
+        let update = UpdateLateFeePolicy {
+            enabled: true,
+            fee_type: LateFeeType::Percentage,
+            amount: dec!(150),
+            grace_days: 0,
+            recurrence: LateFeeRecurrence::Monthly,
+            max_applications: None,
+        };
+        assert!(update.validate().is_err());
+        assert!(UpdateLateFeePolicy { amount: dec!(2), ..update.clone() }.validate().is_ok());
+        assert!(UpdateLateFeePolicy { amount: dec!(2), grace_days: -1, ..update.clone() }.validate().is_err());
+        assert!(UpdateLateFeePolicy { amount: dec!(2), max_applications: Some(0), ..update }.validate().is_err());
+    }
+}
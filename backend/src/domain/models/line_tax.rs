@@ -0,0 +1,228 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::domain::models::invoice_totals::CurrencyRounding;
+use crate::domain::models::InvoiceItem;
+
+/// Most taxes a line can carry (e.g. GST, PST and a levy)
+const MAX_LINE_TAXES: usize = 5;
+
+/// One tax charged on a line, e.g. GST 5% next to PST 7%. A compound tax is
+/// charged on the net plus the taxes listed before it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TaxComponent {
+    pub label: String,
+    /// 0 to 1, e.g. 0.05 for 5%
+    pub rate: Decimal,
+    #[serde(default)]
+    pub compound: bool,
+}
+
+/// A tax component and what it came to on one line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LineTax {
+    pub label: String,
+    pub rate: Decimal,
+    #[serde(default)]
+    pub compound: bool,
+    pub tax_amount: Decimal,
+}
+
+impl LineTax {
+    /// The component this was charged from, to carry onto a copy of the line
+    pub fn component(&self) -> TaxComponent {
+        TaxComponent { label: self.label.clone(), rate: self.rate, compound: self.compound }
+    }
+}
+
+/// One tax across an invoice, kept in `tax_calculation.taxes` and printed as its own line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TaxLine {
+    pub label: String,
+    pub rate: Decimal,
+    #[serde(default)]
+    pub compound: bool,
+    /// What the tax was charged on; for a compound tax that includes the taxes before it
+    pub taxable_amount: Decimal,
+    pub tax_amount: Decimal,
+}
+
+impl TaxComponent {
+    /// Checks a line's components: named, 0-100%, and each label listed once
+    pub fn validate_all(components: &[TaxComponent]) -> Result<(), String> {
+        if components.len() > MAX_LINE_TAXES {
+            return Err(format!("A line can have at most {} taxes", MAX_LINE_TAXES));
+        }
+        for (i, component) in components.iter().enumerate() {
+            let label = component.label.trim();
+            if label.is_empty() || label.len() > 100 {
+                return Err("Each tax needs a label of up to 100 characters".to_string());
+            }
+            if component.rate < Decimal::ZERO || component.rate > Decimal::ONE {
+                return Err(format!("{} rate must be between 0 and 1", label));
+            }
+            if components[..i].iter().any(|other| other.label.trim().eq_ignore_ascii_case(label)) {
+                return Err(format!("{} is listed twice on the same line", label));
+            }
+        }
+        Ok(())
+    }
+
+    /// What each component charges as a share of the net: its rate, or for a
+    /// compound tax its rate on the net plus the taxes before it
+    fn effective_rates(components: &[TaxComponent]) -> Vec<Decimal> {
+        let mut charged = Decimal::ZERO;
+        components
+            .iter()
+            .map(|component| {
+                let rate = if component.compound { component.rate * (Decimal::ONE + charged) } else { component.rate };
+                charged += rate;
+                rate
+            })
+            .collect()
+    }
+
+    /// The single rate the components add up to, e.g. 12% for GST 5% + PST 7%
+    pub fn combined_rate(components: &[TaxComponent]) -> Decimal {
+        Self::effective_rates(components).into_iter().sum()
+    }
+
+    /// Splits a line's tax between its components in proportion to what each
+    /// charges. The last one takes the rounding so the parts add up to the line's tax.
+    pub fn split(components: &[TaxComponent], tax_amount: Decimal, rounding: CurrencyRounding) -> Vec<LineTax> {
+        let rates = Self::effective_rates(components);
+        let combined: Decimal = rates.iter().copied().sum();
+        let mut left = tax_amount;
+        components
+            .iter()
+            .zip(rates)
+            .enumerate()
+            .map(|(i, (component, rate))| {
+                let amount = if i + 1 == components.len() {
+                    left
+                } else if combined.is_zero() {
+                    Decimal::ZERO
+                } else {
+                    rounding.round(tax_amount * rate / combined)
+                };
+                left -= amount;
+                LineTax {
+                    label: component.label.trim().to_string(),
+                    rate: component.rate,
+                    compound: component.compound,
+                    tax_amount: amount,
+                }
+            })
+            .collect()
+    }
+}
+
+impl TaxLine {
+    /// Totals per tax across the items. Lines with a single rate count under
+    /// `default_label` (the invoice's tax label); untaxed lines are left out.
+    pub fn breakdown(items: &[InvoiceItem], default_label: Option<&str>) -> Vec<TaxLine> {
+        let mut lines: Vec<TaxLine> = Vec::new();
+        let mut add = |label: &str, rate: Decimal, compound: bool, taxable_amount: Decimal, tax_amount: Decimal| {
+            match lines.iter_mut().find(|line| line.label == label && line.rate == rate && line.compound == compound) {
+                Some(line) => {
+                    line.taxable_amount += taxable_amount;
+                    line.tax_amount += tax_amount;
+                }
+                None => lines.push(TaxLine { label: label.to_string(), rate, compound, taxable_amount, tax_amount }),
+            }
+        };
+
+        for item in items {
+            let net = item.total - item.tax_amount;
+            if item.taxes.is_empty() {
+                if !item.tax_rate.is_zero() {
+                    add(default_label.unwrap_or("Tax"), item.tax_rate, false, net, item.tax_amount);
+                }
+                continue;
+            }
+            let mut charged = Decimal::ZERO;
+            for tax in &item.taxes {
+                let taxable = if tax.compound { net + charged } else { net };
+                add(&tax.label, tax.rate, tax.compound, taxable, tax.tax_amount);
+                charged += tax.tax_amount;
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use uuid::Uuid;
+
+    fn component(label: &str, rate: Decimal, compound: bool) -> TaxComponent {
+        TaxComponent { label: label.to_string(), rate, compound }
+    }
+
+    #[test]
+    fn test_combined_rate_of_stacked_and_compound_taxes() {
+        let gst_pst = [component("GST", dec!(0.05), false), component("PST", dec!(0.07), false)];
+        assert_eq!(TaxComponent::combined_rate(&gst_pst), dec!(0.12));
+
+        // QST charged on the price plus GST: 5% + 9.975% x 1.05
+        let gst_qst = [component("GST", dec!(0.05), false), component("QST", dec!(0.09975), true)];
+        assert_eq!(TaxComponent::combined_rate(&gst_qst), dec!(0.1547375));
+        assert_eq!(TaxComponent::combined_rate(&[]), dec!(0));
+    }
+
+    #[test]
+    fn test_split_adds_up_to_the_line_tax() {
+        let gst_qst = [component("GST", dec!(0.05), false), component("QST", dec!(0.09975), true)];
+        // 100.00 net: GST 5.00, QST 10.47
+        let taxes = TaxComponent::split(&gst_qst, dec!(15.47), CurrencyRounding::CENTS);
+        assert_eq!(taxes[0].tax_amount, dec!(5.00));
+        assert_eq!(taxes[1].tax_amount, dec!(10.47));
+
+        let thirds = [component("A", dec!(0.1), false), component("B", dec!(0.1), false), component("C", dec!(0.1), false)];
+        let taxes = TaxComponent::split(&thirds, dec!(0.10), CurrencyRounding::CENTS);
+        assert_eq!(taxes.iter().map(|tax| tax.tax_amount).sum::<Decimal>(), dec!(0.10));
+    }
+
+    #[test]
+    fn test_components_are_validated() {
+        assert!(TaxComponent::validate_all(&[component("GST", dec!(0.05), false), component("PST", dec!(0.07), false)]).is_ok());
+        assert!(TaxComponent::validate_all(&[component(" ", dec!(0.05), false)]).is_err());
+        assert!(TaxComponent::validate_all(&[component("GST", dec!(5), false)]).is_err());
+        assert!(TaxComponent::validate_all(&[component("GST", dec!(0.05), false), component("gst", dec!(0.05), true)]).is_err());
+    }
+
+    #[test]
+    fn test_breakdown_groups_taxes_across_lines() {
+        let item = |net: Decimal, taxes: Vec<LineTax>, tax_rate: Decimal| {
+            let tax_amount = taxes.iter().map(|tax| tax.tax_amount).sum::<Decimal>() + if taxes.is_empty() { net * tax_rate } else { dec!(0) };
+            InvoiceItem {
+                id: Uuid::new_v4(),
+                description: "Work".to_string(),
+                quantity: dec!(1),
+                unit_price: net,
+                tax_rate,
+                tax_amount,
+                total: net + tax_amount,
+                section: None,
+                taxes,
+            }
+        };
+        let gst_qst = [component("GST", dec!(0.05), false), component("QST", dec!(0.09975), true)];
+        let items = vec![
+            item(dec!(100), TaxComponent::split(&gst_qst, dec!(15.47), CurrencyRounding::CENTS), dec!(0.1547375)),
+            item(dec!(200), TaxComponent::split(&gst_qst[..1], dec!(10), CurrencyRounding::CENTS), dec!(0.05)),
+            item(dec!(50), vec![], dec!(0.1)),
+            item(dec!(80), vec![], dec!(0)),
+        ];
+
+        let lines = TaxLine::breakdown(&items, Some("Sales Tax"));
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].label.as_str(), lines[0].taxable_amount, lines[0].tax_amount), ("GST", dec!(300), dec!(15)));
+        // Compound: charged on the net plus GST
+        assert_eq!((lines[1].label.as_str(), lines[1].taxable_amount, lines[1].tax_amount), ("QST", dec!(105), dec!(10.47)));
+        assert_eq!((lines[2].label.as_str(), lines[2].tax_amount), ("Sales Tax", dec!(5)));
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::{DateTime, Duration, Utc};
+
+/// Wrong passwords in a row before an account is locked
+pub const MAX_FAILED_LOGINS: i32 = 5;
+
+/// The first lockout; each further one in a row doubles it
+pub const BASE_LOCKOUT_MINUTES: i64 = 15;
+
+pub const MAX_LOCKOUT_MINUTES: i64 = 24 * 60;
+
+/// Failed sign-ins from one IP, across accounts, before it is throttled
+pub const MAX_FAILED_LOGINS_PER_IP: i64 = 20;
+
+pub const IP_FAILURE_WINDOW_MINUTES: i64 = 15;
+
+/// Sign-in failure state of an account
+#[derive(Debug, Clone, Default, sqlx::FromRow)]
+pub struct LoginLockout {
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<DateTime<Utc>>,
+    /// Lockouts since the last successful sign-in
+    pub lockout_count: i32,
+}
+
+impl LoginLockout {
+    /// Seconds the account stays locked, if it is
+    pub fn locked_for(&self, now: DateTime<Utc>) -> Option<i64> {
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| (until - now).num_seconds().max(1))
+    }
+
+    /// How long the next lockout lasts: 15 minutes, then 30, 60, ... up to a day
+    pub fn next_lockout(&self) -> Duration {
+        let doublings = self.lockout_count.clamp(0, 16) as u32;
+        Duration::minutes((BASE_LOCKOUT_MINUTES << doublings).min(MAX_LOCKOUT_MINUTES))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockouts_back_off_exponentially_up_to_a_day() {
+        let minutes = |lockout_count| LoginLockout { lockout_count, ..Default::default() }.next_lockout().num_minutes();
+        assert_eq!(minutes(0), 15);
+        assert_eq!(minutes(1), 30);
+        assert_eq!(minutes(3), 120);
+        assert_eq!(minutes(7), MAX_LOCKOUT_MINUTES);
+        assert_eq!(minutes(40), MAX_LOCKOUT_MINUTES);
+    }
+
+    #[test]
+    fn test_lock_expires() {
+        let now = Utc::now();
+        let lockout = LoginLockout { locked_until: Some(now + Duration::seconds(90)), ..Default::default() };
+        assert_eq!(lockout.locked_for(now), Some(90));
+        assert_eq!(lockout.locked_for(now + Duration::seconds(90)), None);
+        assert_eq!(LoginLockout::default().locked_for(now), None);
+    }
+}
@@ -5,10 +5,109 @@ pub mod payment;
 pub mod expense;
 pub mod audit;
 pub mod tax;
+pub mod fx;
+pub mod document_number;
+pub mod automation_issue;
+pub mod invoice_totals;
+pub mod budget;
+pub mod invoice_template;
+pub mod phone;
+pub mod payout;
+pub mod business;
+pub mod invoice_notification;
+pub mod access;
+pub mod campaign;
+pub mod profitability;
+pub mod batch;
+pub mod sync;
+pub mod calendar;
+pub mod invoice_label;
+pub mod email_signature;
+pub mod email_template;
+pub mod custom_report;
+pub mod template_bundle;
+pub mod client_import;
+pub mod credit_note;
+pub mod attachment;
+pub mod late_fee;
+pub mod webhook;
+pub mod csv_table;
+pub mod client_csv_import;
+pub mod invoice_csv_import;
+pub mod invoice_export;
+pub mod pagination;
+pub mod search;
+pub mod trash;
+pub mod portal;
+pub mod bank_reconciliation;
+pub mod receipt;
+pub mod cashflow;
+pub mod account_statement;
+pub mod timeseries;
+pub mod login_lockout;
+pub mod time_entry;
+pub mod project;
+pub mod realtime;
+pub mod client_credit;
+pub mod invoice_deposit;
+pub mod line_tax;
+pub mod eu_vat;
+pub mod e_invoice;
+pub mod payment_qr;
 
 pub use user::*;
 pub use invoice::*;
 pub use client::*;
 pub use payment::*;
 pub use expense::*;
+pub use audit::*;
 pub use tax::*;
+pub use fx::*;
+pub use document_number::*;
+pub use automation_issue::*;
+pub use invoice_totals::*;
+pub use budget::*;
+pub use invoice_template::*;
+pub use phone::*;
+pub use payout::*;
+pub use business::*;
+pub use invoice_notification::*;
+pub use access::*;
+pub use campaign::*;
+pub use profitability::*;
+pub use batch::*;
+pub use sync::*;
+pub use calendar::*;
+pub use invoice_label::*;
+pub use email_signature::*;
+pub use email_template::*;
+pub use custom_report::*;
+pub use template_bundle::*;
+pub use client_import::*;
+pub use credit_note::*;
+pub use attachment::*;
+pub use late_fee::*;
+pub use webhook::*;
+pub use csv_table::*;
+pub use client_csv_import::*;
+pub use invoice_csv_import::*;
+pub use invoice_export::*;
+pub use pagination::*;
+pub use search::*;
+pub use trash::*;
+pub use portal::*;
+pub use bank_reconciliation::*;
+pub use receipt::*;
+pub use cashflow::*;
+pub use account_statement::*;
+pub use timeseries::*;
+pub use login_lockout::*;
+pub use time_entry::*;
+pub use project::*;
+pub use realtime::*;
+pub use client_credit::*;
+pub use invoice_deposit::*;
+pub use line_tax::*;
+pub use eu_vat::*;
+pub use e_invoice::*;
+pub use payment_qr::*;
@@ -0,0 +1,185 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Page size when a list request doesn't ask for one
+pub const DEFAULT_PER_PAGE: i64 = 50;
+/// Largest page a list request can ask for
+pub const MAX_PER_PAGE: i64 = 200;
+
+/// Position in a list ordered newest first: the last row of a page. The next
+/// page starts with the rows created before it, ties broken by ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl PageCursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    /// Opaque to clients; microseconds match the database's timestamp precision
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.created_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(raw: &str) -> Result<Self, String> {
+        let invalid = || "Invalid cursor".to_string();
+        let bytes = URL_SAFE_NO_PAD.decode(raw.trim()).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, id) = text.split_once(':').ok_or_else(invalid)?;
+
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        let created_at = DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Which slice of a list to return: a numbered page, or the rows after a
+/// cursor. Cursors stay cheap however deep the list goes, where large offsets
+/// make the database walk every skipped row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRequest {
+    pub per_page: i64,
+    /// Rows skipped before the page; always zero with a cursor
+    pub offset: i64,
+    pub after: Option<PageCursor>,
+}
+
+impl Default for PageRequest {
+    fn default() -> Self {
+        Self { per_page: DEFAULT_PER_PAGE, offset: 0, after: None }
+    }
+}
+
+impl PageRequest {
+    /// From a list endpoint's `page`, `per_page` and `cursor` parameters.
+    /// `limit` and `offset` are still read, as older names for `per_page`
+    /// and the page's start.
+    pub fn from_params(
+        page: Option<i64>,
+        per_page: Option<i64>,
+        cursor: Option<&str>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> Result<Self, String> {
+        let per_page = per_page.or(limit).unwrap_or(DEFAULT_PER_PAGE);
+        if !(1..=MAX_PER_PAGE).contains(&per_page) {
+            return Err(format!("per_page must be between 1 and {}", MAX_PER_PAGE));
+        }
+
+        if let Some(cursor) = cursor.filter(|c| !c.trim().is_empty()) {
+            if page.is_some() || offset.is_some() {
+                return Err("cursor can't be combined with page or offset".to_string());
+            }
+            return Ok(Self { per_page, offset: 0, after: Some(PageCursor::decode(cursor)?) });
+        }
+
+        let offset = match (page, offset) {
+            (Some(page), _) if page < 1 => return Err("page must be 1 or more".to_string()),
+            (Some(page), _) => (page - 1).saturating_mul(per_page),
+            (None, Some(offset)) if offset < 0 => return Err("offset can't be negative".to_string()),
+            (None, offset) => offset.unwrap_or(0),
+        };
+        Ok(Self { per_page, offset, after: None })
+    }
+
+    /// One row more than the page, to tell whether another page follows
+    pub fn fetch_limit(&self) -> i64 {
+        self.per_page + 1
+    }
+
+    /// The page number, when paging by number rather than cursor
+    pub fn page(&self) -> Option<i64> {
+        match self.after {
+            Some(_) => None,
+            None => Some(self.offset / self.per_page + 1),
+        }
+    }
+}
+
+/// One page of a list endpoint. `total` counts every match of the filters;
+/// `next_cursor` is set while more rows follow and fetches them with `?cursor=`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    /// Null when paging by cursor
+    pub page: Option<i64>,
+    pub per_page: i64,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// `rows` as fetched with `request.fetch_limit()`
+    pub fn new(request: &PageRequest, mut rows: Vec<T>, total: i64, cursor_of: impl Fn(&T) -> PageCursor) -> Self {
+        let has_more = rows.len() as i64 > request.per_page;
+        rows.truncate(request.per_page as usize);
+        let next_cursor = rows.last().filter(|_| has_more).map(|row| cursor_of(row).encode());
+
+        Self {
+            items: rows,
+            total,
+            page: request.page(),
+            per_page: request.per_page,
+            next_cursor,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            page: self.page,
+            per_page: self.per_page,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = PageCursor::new(DateTime::from_timestamp_micros(1_718_000_000_123_456).unwrap(), Uuid::new_v4());
+        assert_eq!(PageCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(PageCursor::decode("not-a-cursor").is_err());
+    }
+
+    #[test]
+    fn resolves_page_and_legacy_params() {
+        let request = PageRequest::from_params(Some(3), Some(20), None, None, None).unwrap();
+        assert_eq!((request.offset, request.page()), (40, Some(3)));
+
+        let legacy = PageRequest::from_params(None, None, None, Some(10), Some(30)).unwrap();
+        assert_eq!((legacy.per_page, legacy.offset, legacy.page()), (10, 30, Some(4)));
+
+        assert_eq!(PageRequest::from_params(None, None, None, None, None).unwrap(), PageRequest::default());
+        assert!(PageRequest::from_params(Some(0), None, None, None, None).is_err());
+        assert!(PageRequest::from_params(None, Some(MAX_PER_PAGE + 1), None, None, None).is_err());
+
+        let cursor = PageCursor::new(Utc::now(), Uuid::new_v4()).encode();
+        assert!(PageRequest::from_params(Some(2), None, Some(&cursor), None, None).is_err());
+        assert_eq!(PageRequest::from_params(None, None, Some(&cursor), None, None).unwrap().page(), None);
+    }
+
+    #[test]
+    fn next_cursor_points_at_the_last_row_when_more_follow() {
+        let request = PageRequest { per_page: 2, offset: 0, after: None };
+        let rows: Vec<PageCursor> = (0..3).map(|_| PageCursor::new(Utc::now(), Uuid::new_v4())).collect();
+
+        let page = Page::new(&request, rows.clone(), 3, |row| *row);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.next_cursor, Some(rows[1].encode()));
+
+        let last = Page::new(&request, rows[..2].to_vec(), 2, |row| *row);
+        assert_eq!(last.next_cursor, None);
+    }
+}
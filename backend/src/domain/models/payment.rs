@@ -1,10 +1,14 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use sqlx::Type;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+use crate::domain::models::{validate_min_cent, PageRequest};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "varchar")]
 pub enum PaymentStatus {
     #[serde(rename = "pending")]
@@ -28,7 +32,7 @@ impl std::fmt::Display for PaymentStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "varchar")]
 pub enum PaymentMethod {
     #[serde(rename = "stripe")]
@@ -58,21 +62,21 @@ impl std::fmt::Display for PaymentMethod {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct Payment {
     pub id: Uuid,
     pub invoice_id: Uuid,
     pub user_id: Uuid,
 
-    #[validate(range(min = 0.01))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub amount: Decimal,
 
     pub currency: String,
     pub payment_method: PaymentMethod,
 
     pub gateway: Option<String>,
     pub gateway_payment_id: Option<String>,
-    pub gateway_fee: f64,
+    pub gateway_fee: Decimal,
 
     pub status: PaymentStatus,
     pub failure_reason: Option<String>,
@@ -84,27 +88,30 @@ pub struct Payment {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreatePayment {
     pub invoice_id: Uuid,
 
-    #[validate(range(min = 0.01))]
-    pub amount: f64,
+    #[validate(custom(function = "validate_min_cent"))]
+    pub amount: Decimal,
 
     pub payment_method: PaymentMethod,
     pub gateway: Option<String>,
     pub gateway_payment_id: Option<String>,
-    pub gateway_fee: Option<f64>,
+    pub gateway_fee: Option<Decimal>,
     pub paid_by: Option<String>,
     pub notes: Option<String>,
+
+    // Settlement exchange rate for foreign-currency invoices (base currency per unit)
+    pub exchange_rate: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaymentResponse {
     pub id: Uuid,
     pub invoice_id: Uuid,
     pub invoice_number: Option<String>,
-    pub amount: f64,
+    pub amount: Decimal,
     pub currency: String,
     pub payment_method: PaymentMethod,
     pub status: PaymentStatus,
@@ -113,17 +120,31 @@ pub struct PaymentResponse {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PaymentListFilter {
     pub status: Option<PaymentStatus>,
     pub payment_method: Option<PaymentMethod>,
     pub date_from: Option<DateTime<Utc>>,
     pub date_to: Option<DateTime<Utc>>,
+    /// Comma-separated payment IDs to fetch in one call
+    pub ids: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// `next_cursor` of the previous page, to page by cursor instead of number
+    pub cursor: Option<String>,
+    /// Older names for `per_page` and the page's start
     pub limit: Option<i64>,
     pub offset: Option<i64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl PaymentListFilter {
+    pub fn page_request(&self) -> Result<PageRequest, String> {
+        PageRequest::from_params(self.page, self.per_page, self.cursor.as_deref(), self.limit, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaymentStats {
     pub total_payments: i64,
     pub total_amount: f64,
@@ -131,8 +152,8 @@ pub struct PaymentStats {
     pub by_method: std::collections::HashMap<String, f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RefundRequest {
-    pub amount: Option<f64>,
+    pub amount: Option<Decimal>,
     pub reason: String,
 }
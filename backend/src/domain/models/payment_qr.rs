@@ -0,0 +1,298 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Longest beneficiary name an EPC QR code carries
+const EPC_MAX_NAME_LENGTH: usize = 70;
+/// Longest unstructured remittance text an EPC QR code carries
+const EPC_MAX_REMITTANCE_LENGTH: usize = 140;
+
+/// What the QR code printed on invoice PDFs holds, chosen in the invoice settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentQrFormat {
+    /// The guest payment link
+    #[default]
+    Link,
+    /// A SEPA credit transfer (EPC QR) to `sepa_account`, on EUR invoices
+    Sepa,
+    /// The seller's QRIS code with the amount filled in, on IDR invoices
+    Qris,
+    /// No QR code
+    Off,
+}
+
+/// The account SEPA QR codes pay into
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct SepaAccount {
+    /// Account holder, up to 70 characters
+    pub name: String,
+    pub iban: String,
+    /// Optional within the EEA
+    #[serde(default)]
+    pub bic: Option<String>,
+}
+
+impl SepaAccount {
+    /// The account with its IBAN and BIC checked and written without spaces
+    pub fn normalize(self) -> Result<Self, String> {
+        let name = self.name.trim().to_string();
+        if name.is_empty() || name.chars().count() > EPC_MAX_NAME_LENGTH {
+            return Err(format!("The account holder name must be 1 to {} characters", EPC_MAX_NAME_LENGTH));
+        }
+        let iban = normalize_iban(&self.iban).ok_or_else(|| format!("'{}' is not a valid IBAN", self.iban))?;
+        let bic = match self.bic.as_deref().map(compact).filter(|bic| !bic.is_empty()) {
+            Some(bic) if is_bic(&bic) => Some(bic),
+            Some(_) => return Err(format!("'{}' is not a valid BIC", self.bic.unwrap_or_default())),
+            None => None,
+        };
+        Ok(Self { name, iban, bic })
+    }
+}
+
+/// A QR code to print on an invoice and the line under it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentQr {
+    pub payload: String,
+    pub caption: &'static str,
+}
+
+impl PaymentQr {
+    /// The code for an invoice with `amount` left to pay. SEPA and QRIS codes
+    /// need the account set up and the invoice in EUR or IDR; otherwise the
+    /// guest payment link is used.
+    pub fn for_invoice(
+        format: PaymentQrFormat,
+        sepa_account: Option<&SepaAccount>,
+        qris_payload: Option<&str>,
+        currency: &str,
+        amount: Decimal,
+        reference: &str,
+        payment_link: Option<&str>,
+    ) -> Option<Self> {
+        if amount <= Decimal::ZERO {
+            return None;
+        }
+        let payload = match format {
+            PaymentQrFormat::Off => return None,
+            PaymentQrFormat::Sepa if currency == "EUR" => {
+                sepa_account.and_then(|account| epc_payload(account, amount, reference))
+            }
+            PaymentQrFormat::Qris if currency == "IDR" => qris_payload.and_then(|qris| qris_with_amount(qris, amount)),
+            _ => None,
+        };
+        match payload {
+            Some(payload) if format == PaymentQrFormat::Sepa => Some(Self { payload, caption: "Scan to pay by bank transfer" }),
+            Some(payload) => Some(Self { payload, caption: "Scan to pay with QRIS" }),
+            None => payment_link.map(|link| Self { payload: link.to_string(), caption: "Scan to pay online" }),
+        }
+    }
+}
+
+/// The EPC069-12 "SEPA credit transfer" QR payload banking apps read, or None
+/// when the amount is outside what it carries
+pub fn epc_payload(account: &SepaAccount, amount: Decimal, reference: &str) -> Option<String> {
+    let amount = amount.round_dp(2);
+    if amount < Decimal::new(1, 2) || amount > Decimal::new(99_999_999_999, 2) {
+        return None;
+    }
+    let remittance: String = reference.chars().take(EPC_MAX_REMITTANCE_LENGTH).collect();
+    let lines = [
+        "BCD",
+        "002",
+        // UTF-8
+        "1",
+        "SCT",
+        account.bic.as_deref().unwrap_or_default(),
+        &account.name,
+        &account.iban,
+        &format!("EUR{:.2}", amount),
+        // Purpose and structured reference, unused
+        "",
+        "",
+        &remittance,
+    ];
+    Some(lines.join("\n"))
+}
+
+/// `iban` without spaces, upper-cased, when its check digits are right
+pub fn normalize_iban(iban: &str) -> Option<String> {
+    let iban = compact(iban);
+    if !(15..=34).contains(&iban.len())
+        || !iban[..2].chars().all(|c| c.is_ascii_uppercase())
+        || !iban[2..4].chars().all(|c| c.is_ascii_digit())
+        || !iban.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return None;
+    }
+    // ISO 13616: the country and check digits move to the end, letters become 10-35
+    let remainder = iban[4..].chars().chain(iban[..4].chars()).fold(0u32, |remainder, c| {
+        let value = c.to_digit(36).unwrap_or_default();
+        let shift = if value >= 10 { 100 } else { 10 };
+        (remainder * shift + value) % 97
+    });
+    (remainder == 1).then_some(iban)
+}
+
+fn is_bic(bic: &str) -> bool {
+    (bic.len() == 8 || bic.len() == 11)
+        && bic[..6].chars().all(|c| c.is_ascii_uppercase())
+        && bic.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn compact(value: &str) -> String {
+    value.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_uppercase()
+}
+
+/// A QRIS code's EMV fields as (tag, value), in order
+fn qris_fields(payload: &str) -> Option<Vec<(&str, &str)>> {
+    let mut fields = Vec::new();
+    let mut rest = payload;
+    while !rest.is_empty() {
+        let tag = rest.get(..2)?;
+        let length: usize = rest.get(2..4)?.parse().ok()?;
+        let value = rest.get(4..4 + length)?;
+        fields.push((tag, value));
+        rest = &rest[4 + length..];
+    }
+    Some(fields)
+}
+
+/// Checks a QRIS code as the acquirer issued it: EMV fields, rupiah (currency
+/// 360) and the CRC closing it
+pub fn validate_qris(payload: &str) -> Result<String, String> {
+    let payload = payload.trim();
+    let invalid = || "qris_payload is not a valid QRIS code".to_string();
+    let fields = qris_fields(payload).ok_or_else(invalid)?;
+    if fields.first() != Some(&("00", "01")) {
+        return Err(invalid());
+    }
+    match fields.last() {
+        Some(&("63", crc)) if crc.eq_ignore_ascii_case(&crc16(&payload[..payload.len() - 4])) => {}
+        _ => return Err("qris_payload has a wrong checksum".to_string()),
+    }
+    if !fields.contains(&("53", "360")) {
+        return Err("qris_payload must be a rupiah (IDR) QRIS code".to_string());
+    }
+    Ok(payload.to_string())
+}
+
+/// The seller's QRIS code made single-use (point of initiation 12) with the
+/// amount to pay, and its CRC recomputed
+pub fn qris_with_amount(payload: &str, amount: Decimal) -> Option<String> {
+    let amount = amount.round_dp(2).normalize().to_string();
+    if amount.len() > 13 {
+        return None;
+    }
+    let mut fields: Vec<(&str, &str)> = qris_fields(payload)?
+        .into_iter()
+        .filter(|(tag, _)| !matches!(*tag, "01" | "54" | "63"))
+        .collect();
+    fields.insert(1, ("01", "12"));
+    let position = fields.iter().position(|(tag, _)| *tag > "54").unwrap_or(fields.len());
+    fields.insert(position, ("54", &amount));
+
+    let mut qris: String = fields.iter().map(|(tag, value)| format!("{}{:02}{}", tag, value.len(), value)).collect();
+    qris.push_str("6304");
+    let crc = crc16(&qris);
+    qris.push_str(&crc);
+    Some(qris)
+}
+
+/// CRC-16/CCITT-FALSE as four upper-case hex digits, the checksum EMV QR codes end with
+fn crc16(data: &str) -> String {
+    let crc = data.bytes().fold(0xFFFFu16, |crc, byte| {
+        (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+            if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 }
+        })
+    });
+    format!("{:04X}", crc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn account() -> SepaAccount {
+        SepaAccount { name: "Berlin GmbH".to_string(), iban: "DE89370400440532013000".to_string(), bic: Some("COBADEFFXXX".to_string()) }
+    }
+
+    fn static_qris() -> String {
+        let body = "00020101021126570011ID.DANA.WWW011893600915302259148102090225914810303UMI\
+                    51440014ID.CO.QRIS.WWW0215ID10200176114730303UMI5204581253033605802ID\
+                    5910Warung Adi6007Jakarta610512345";
+        format!("{}6304{}", body, crc16(&format!("{}6304", body)))
+    }
+
+    #[test]
+    fn test_iban_check_digits() {
+        assert_eq!(normalize_iban("de89 3704 0044 0532 0130 00").as_deref(), Some("DE89370400440532013000"));
+        assert_eq!(normalize_iban("GB82 WEST 1234 5698 7654 32").as_deref(), Some("GB82WEST12345698765432"));
+        assert_eq!(normalize_iban("DE88370400440532013000"), None);
+        assert_eq!(normalize_iban("DE89"), None);
+    }
+
+    #[test]
+    fn test_sepa_account_is_normalized() {
+        let account = SepaAccount { name: " Berlin GmbH ".to_string(), iban: "de89 3704 0044 0532 0130 00".to_string(), bic: Some("cobadeff".to_string()) };
+        let account = account.normalize().unwrap();
+        assert_eq!(account.name, "Berlin GmbH");
+        assert_eq!(account.bic.as_deref(), Some("COBADEFF"));
+
+        let bad_bic = SepaAccount { bic: Some("12345678".to_string()), ..account.clone() };
+        assert!(bad_bic.normalize().is_err());
+        let no_bic = SepaAccount { bic: Some(" ".to_string()), ..account };
+        assert_eq!(no_bic.normalize().unwrap().bic, None);
+    }
+
+    #[test]
+    fn test_epc_payload() {
+        let payload = epc_payload(&account(), dec!(1190), "INV-0001").unwrap();
+        assert_eq!(
+            payload,
+            "BCD\n002\n1\nSCT\nCOBADEFFXXX\nBerlin GmbH\nDE89370400440532013000\nEUR1190.00\n\n\nINV-0001"
+        );
+        assert_eq!(epc_payload(&account(), dec!(1_000_000_000), "INV-0001"), None);
+    }
+
+    #[test]
+    fn test_crc16() {
+        // The CRC-16/CCITT-FALSE check value
+        assert_eq!(crc16("123456789"), "29B1");
+    }
+
+    #[test]
+    fn test_qris_validation() {
+        let qris = static_qris();
+        assert_eq!(validate_qris(&format!(" {} ", qris)).unwrap(), qris);
+        let tampered = qris.replace("Warung Adi", "Warung Ada");
+        assert!(validate_qris(&tampered).unwrap_err().contains("checksum"));
+        assert!(validate_qris("not a qris code").is_err());
+    }
+
+    #[test]
+    fn test_qris_with_amount() {
+        let qris = qris_with_amount(&static_qris(), dec!(150000)).unwrap();
+        assert!(qris.starts_with("000201010212"));
+        assert!(qris.contains("5303360540615000058"));
+        assert!(validate_qris(&qris).is_ok());
+    }
+
+    #[test]
+    fn test_invoice_code_falls_back_to_the_link() {
+        let link = Some("https://pay.example/abc");
+        let qr = |format, currency| PaymentQr::for_invoice(format, Some(&account()), Some(&static_qris()), currency, dec!(10), "INV-1", link);
+
+        assert_eq!(qr(PaymentQrFormat::Link, "EUR").unwrap().payload, "https://pay.example/abc");
+        assert!(qr(PaymentQrFormat::Sepa, "EUR").unwrap().payload.starts_with("BCD\n"));
+        assert!(qr(PaymentQrFormat::Qris, "IDR").unwrap().payload.starts_with("000201"));
+        // Only EUR transfers fit an EPC code, only rupiah a QRIS one
+        assert_eq!(qr(PaymentQrFormat::Sepa, "USD").unwrap().caption, "Scan to pay online");
+        assert_eq!(qr(PaymentQrFormat::Qris, "EUR").unwrap().caption, "Scan to pay online");
+        assert_eq!(qr(PaymentQrFormat::Off, "EUR"), None);
+
+        let paid = PaymentQr::for_invoice(PaymentQrFormat::Link, None, None, "EUR", Decimal::ZERO, "INV-1", link);
+        assert_eq!(paid, None);
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::invoice_totals::round_money;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutStatus {
+    Pending,
+    InTransit,
+    Paid,
+    Failed,
+    Canceled,
+}
+
+impl PayoutStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PayoutStatus::Pending => "pending",
+            PayoutStatus::InTransit => "in_transit",
+            PayoutStatus::Paid => "paid",
+            PayoutStatus::Failed => "failed",
+            PayoutStatus::Canceled => "canceled",
+        }
+    }
+
+    /// Accepts Stripe payout statuses and PayPal's upper-case variants
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "pending" => Some(PayoutStatus::Pending),
+            "in_transit" => Some(PayoutStatus::InTransit),
+            "paid" | "success" | "completed" => Some(PayoutStatus::Paid),
+            "failed" | "denied" | "returned" => Some(PayoutStatus::Failed),
+            "canceled" | "cancelled" => Some(PayoutStatus::Canceled),
+            _ => None,
+        }
+    }
+}
+
+/// A gateway transfer to the bank: a batch of payments minus gateway fees
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Payout {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub gateway: String,
+    pub gateway_payout_id: String,
+    /// Amount that lands in the bank account
+    pub amount: f64,
+    pub currency: String,
+    pub status: PayoutStatus,
+    pub arrival_date: Option<NaiveDate>,
+    /// Gateway payment references in the batch that don't match a recorded payment
+    pub unmatched_references: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One payment included in a payout batch, as reported by the gateway
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutPaymentRef {
+    pub gateway_payment_id: String,
+    /// Fee the gateway charged on this payment; replaces the recorded fee when present
+    pub fee: Option<f64>,
+}
+
+/// Payout as reported by a gateway API, webhook, or settlement report
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordPayout {
+    pub gateway: String,
+    pub gateway_payout_id: String,
+    pub amount: f64,
+    pub currency: Option<String>,
+    pub status: PayoutStatus,
+    pub arrival_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub payments: Vec<PayoutPaymentRef>,
+}
+
+/// A linked payment traced back to its invoice
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct PayoutLine {
+    pub payment_id: Uuid,
+    pub gateway_payment_id: Option<String>,
+    pub invoice_id: Uuid,
+    pub invoice_number: Option<String>,
+    pub client_name: Option<String>,
+    pub gross: f64,
+    pub fee: f64,
+    pub net: f64,
+    pub paid_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutDetail {
+    #[serde(flatten)]
+    pub payout: Payout,
+    pub lines: Vec<PayoutLine>,
+    pub gross: f64,
+    pub fees: f64,
+    pub net: f64,
+    /// Payout amount minus the net of linked payments; non-zero means the
+    /// batch contains items (refunds, adjustments, unmatched payments) not traced here
+    pub difference: f64,
+}
+
+impl PayoutDetail {
+    pub fn new(payout: Payout, lines: Vec<PayoutLine>) -> Self {
+        let gross = round_money(lines.iter().map(|l| l.gross).sum());
+        let fees = round_money(lines.iter().map(|l| l.fee).sum());
+        let net = round_money(gross - fees);
+        let difference = round_money(payout.amount - net);
+
+        Self { payout, lines, gross, fees, net, difference }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PayoutReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub payouts: Vec<PayoutDetail>,
+    /// Total deposited to the bank by the listed payouts
+    pub total_paid_out: f64,
+    pub total_gross: f64,
+    pub total_fees: f64,
+    /// Completed gateway payments in the period not yet included in any payout
+    pub unsettled: Vec<PayoutLine>,
+    pub unsettled_total: f64,
+}
+
+impl PayoutReport {
+    pub fn build(from: NaiveDate, to: NaiveDate, payouts: Vec<PayoutDetail>, unsettled: Vec<PayoutLine>) -> Self {
+        let total_paid_out = round_money(payouts.iter().map(|p| p.payout.amount).sum());
+        let total_gross = round_money(payouts.iter().map(|p| p.gross).sum());
+        let total_fees = round_money(payouts.iter().map(|p| p.fees).sum());
+        let unsettled_total = round_money(unsettled.iter().map(|l| l.net).sum());
+
+        Self {
+            from,
+            to,
+            payouts,
+            total_paid_out,
+            total_gross,
+            total_fees,
+            unsettled,
+            unsettled_total,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(gross: f64, fee: f64) -> PayoutLine {
+        PayoutLine {
+            payment_id: Uuid::new_v4(),
+            gateway_payment_id: Some("ch_1".to_string()),
+            invoice_id: Uuid::new_v4(),
+            invoice_number: Some("INV-1".to_string()),
+            client_name: None,
+            gross,
+            fee,
+            net: gross - fee,
+            paid_at: Utc::now(),
+        }
+    }
+
+    fn payout(amount: f64) -> Payout {
+        Payout {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            gateway: "stripe".to_string(),
+            gateway_payout_id: "po_1".to_string(),
+            amount,
+            currency: "USD".to_string(),
+            status: PayoutStatus::Paid,
+            arrival_date: None,
+            unmatched_references: vec![],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_payout_detail_traces_fees() {
+        let detail = PayoutDetail::new(payout(191.7), vec![line(100.0, 3.2), line(100.0, 3.2)]);
+        assert_eq!(detail.gross, 200.0);
+        assert_eq!(detail.fees, 6.4);
+        assert_eq!(detail.net, 193.6);
+        assert_eq!(detail.difference, -1.9);
+    }
+
+    #[test]
+    fn test_status_parsing() {
+        assert_eq!(PayoutStatus::parse("in_transit"), Some(PayoutStatus::InTransit));
+        assert_eq!(PayoutStatus::parse("SUCCESS"), Some(PayoutStatus::Paid));
+        assert_eq!(PayoutStatus::parse("unknown"), None);
+    }
+}
@@ -0,0 +1,94 @@
+use phonenumber::{country, Mode};
+use std::env;
+
+/// Region used for numbers entered without a country code, e.g. `0812-3456-7890`
+pub const DEFAULT_PHONE_REGION: &str = "ID";
+
+fn default_region() -> Option<country::Id> {
+    env::var("DEFAULT_PHONE_REGION")
+        .ok()
+        .and_then(|region| region.trim().to_uppercase().parse().ok())
+        .or_else(|| DEFAULT_PHONE_REGION.parse().ok())
+}
+
+/// Parse a phone number and return it in E.164 form (`+6281234567890`)
+pub fn normalize_phone(input: &str) -> Result<String, String> {
+    normalize_phone_in(input, default_region())
+}
+
+/// Same as [`normalize_phone`] with an explicit region for national-format numbers
+pub fn normalize_phone_in(input: &str, region: Option<country::Id>) -> Result<String, String> {
+    let trimmed = input.trim();
+    let number = phonenumber::parse(region, trimmed)
+        .map_err(|_| format!("Invalid phone number: {}", trimmed))?;
+
+    if !phonenumber::is_valid(&number) {
+        return Err(format!("Invalid phone number: {}", trimmed));
+    }
+
+    Ok(number.format().mode(Mode::E164).to_string())
+}
+
+/// Normalize when possible; numbers that don't validate are kept as entered
+/// and logged, so existing profiles can still be saved
+pub fn normalize_phone_or_keep(input: &str) -> String {
+    if input.trim().is_empty() {
+        return input.trim().to_string();
+    }
+
+    match normalize_phone(input) {
+        Ok(normalized) => normalized,
+        Err(reason) => {
+            tracing::warn!("{}; storing as entered", reason);
+            input.trim().to_string()
+        }
+    }
+}
+
+/// Normalize an optional phone field, rejecting invalid numbers.
+/// Blank values are passed through untouched.
+pub fn normalize_optional_phone(phone: Option<String>) -> Result<Option<String>, String> {
+    match phone {
+        Some(phone) if !phone.trim().is_empty() => normalize_phone(&phone).map(Some),
+        other => Ok(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indonesia() -> Option<country::Id> {
+        Some(country::Id::ID)
+    }
+
+    #[test]
+    fn test_national_numbers_use_region() {
+        assert_eq!(normalize_phone_in("0812-3456-7890", indonesia()).unwrap(), "+6281234567890");
+        assert_eq!(normalize_phone_in(" 0812 3456 7890 ", indonesia()).unwrap(), "+6281234567890");
+        assert_eq!(
+            normalize_phone_in("(415) 555-2671", Some(country::Id::US)).unwrap(),
+            "+14155552671"
+        );
+    }
+
+    #[test]
+    fn test_international_numbers_ignore_region() {
+        assert_eq!(normalize_phone_in("+62 812 3456 7890", None).unwrap(), "+6281234567890");
+        assert_eq!(normalize_phone_in("+44 20 7946 0958", indonesia()).unwrap(), "+442079460958");
+    }
+
+    #[test]
+    fn test_invalid_numbers_are_rejected() {
+        assert!(normalize_phone_in("12ab", indonesia()).is_err());
+        assert!(normalize_phone_in("+1234567890", indonesia()).is_err());
+        assert!(normalize_phone_in("", indonesia()).is_err());
+    }
+
+    #[test]
+    fn test_lenient_keeps_invalid_input() {
+        assert_eq!(normalize_phone_or_keep(" +1234567890 "), "+1234567890");
+        assert_eq!(normalize_optional_phone(Some("  ".to_string())).unwrap(), Some("  ".to_string()));
+        assert_eq!(normalize_optional_phone(None).unwrap(), None);
+    }
+}
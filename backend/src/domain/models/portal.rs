@@ -0,0 +1,107 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::{InvoiceDetailResponse, InvoiceStatus};
+
+/// How long an emailed sign-in link works
+pub const PORTAL_LINK_TTL_MINUTES: i64 = 30;
+/// Sign-in links sent to one address per hour; further requests are dropped quietly
+pub const PORTAL_LINKS_PER_HOUR: i64 = 5;
+pub const PORTAL_MIN_PASSWORD_LENGTH: usize = 8;
+
+/// A buyer's portal account. It isn't tied to one business: every client record
+/// with the same email, at any business, belongs to it.
+#[derive(Debug, Clone)]
+pub struct ClientAccount {
+    pub id: Uuid,
+    pub email: String,
+    pub password_hash: Option<String>,
+    pub last_sign_in_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PortalLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PortalLinkSignIn {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PortalLogin {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PortalSetPassword {
+    pub password: String,
+}
+
+/// A business that bills the buyer
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortalVendor {
+    pub id: Uuid,
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortalAccount {
+    pub id: Uuid,
+    pub email: String,
+    pub has_password: bool,
+    pub vendors: Vec<PortalVendor>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortalSession {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub account: PortalAccount,
+}
+
+/// An issued invoice as listed in the portal, newest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortalInvoice {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub status: InvoiceStatus,
+    pub vendor_id: Uuid,
+    pub vendor_name: String,
+    pub issue_date: NaiveDate,
+    pub due_date: NaiveDate,
+    pub currency: String,
+    pub total_amount: Decimal,
+    pub balance_due: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PortalInvoiceDetail {
+    pub invoice: InvoiceDetailResponse,
+    pub vendor: PortalVendor,
+}
+
+/// Emails are matched without regard to case or surrounding spaces
+pub fn normalize_portal_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_portal_email() {
+        assert_eq!(normalize_portal_email("  Ana@Acme.CO "), "ana@acme.co");
+        assert_eq!(normalize_portal_email("ana@acme.co"), "ana@acme.co");
+    }
+}
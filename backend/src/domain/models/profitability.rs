@@ -0,0 +1,188 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::models::invoice_totals::round_money;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InvoiceCostKind {
+    Expense,
+    Time,
+}
+
+impl InvoiceCostKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "expense" => Some(InvoiceCostKind::Expense),
+            "time" => Some(InvoiceCostKind::Time),
+            _ => None,
+        }
+    }
+}
+
+/// A direct cost counted against one invoice. Expense costs take the expense's
+/// current amount; time costs are hours at an hourly cost.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceCost {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub kind: InvoiceCostKind,
+    pub expense_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub hours: Option<f64>,
+    pub hourly_cost: Option<f64>,
+    /// Expense date, or the day the time was worked
+    pub date: Option<NaiveDate>,
+    pub amount: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AddInvoiceCost {
+    Expense {
+        expense_id: Uuid,
+    },
+    Time {
+        description: Option<String>,
+        hours: f64,
+        hourly_cost: f64,
+        work_date: Option<NaiveDate>,
+    },
+}
+
+/// Revenue against direct costs, in the base currency. Revenue excludes tax.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct Margin {
+    pub revenue: f64,
+    pub direct_costs: f64,
+    pub margin: f64,
+    /// Margin as a percentage of revenue; None without revenue
+    pub margin_percent: Option<f64>,
+}
+
+impl Margin {
+    pub fn new(revenue: f64, direct_costs: f64) -> Self {
+        let margin = round_money(revenue - direct_costs);
+        let margin_percent = (revenue > 0.0).then(|| ((revenue - direct_costs) / revenue * 10000.0).round() / 100.0);
+
+        Self {
+            revenue: round_money(revenue),
+            direct_costs: round_money(direct_costs),
+            margin,
+            margin_percent,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceProfitability {
+    pub invoice_id: Uuid,
+    pub invoice_number: String,
+    pub currency: String,
+    #[serde(flatten)]
+    pub margin: Margin,
+    pub expense_costs: f64,
+    pub time_costs: f64,
+    pub costs: Vec<InvoiceCost>,
+}
+
+impl InvoiceProfitability {
+    pub fn new(invoice_id: Uuid, invoice_number: String, currency: String, revenue: f64, costs: Vec<InvoiceCost>) -> Self {
+        let sum_of = |kind: InvoiceCostKind| -> f64 {
+            costs.iter().filter(|cost| cost.kind == kind).map(|cost| cost.amount).sum()
+        };
+        let expense_costs = sum_of(InvoiceCostKind::Expense);
+        let time_costs = sum_of(InvoiceCostKind::Time);
+
+        Self {
+            invoice_id,
+            invoice_number,
+            currency,
+            margin: Margin::new(revenue, expense_costs + time_costs),
+            expense_costs: round_money(expense_costs),
+            time_costs: round_money(time_costs),
+            costs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientMargin {
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub invoice_count: i64,
+    #[serde(flatten)]
+    pub margin: Margin,
+}
+
+/// Margin per client over invoices issued in a date range, best margin first
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientMarginReport {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub clients: Vec<ClientMargin>,
+    pub total: Margin,
+}
+
+impl ClientMarginReport {
+    pub fn new(start_date: NaiveDate, end_date: NaiveDate, clients: Vec<ClientMargin>) -> Self {
+        let revenue = clients.iter().map(|client| client.margin.revenue).sum();
+        let direct_costs = clients.iter().map(|client| client.margin.direct_costs).sum();
+
+        Self {
+            start_date,
+            end_date,
+            clients,
+            total: Margin::new(revenue, direct_costs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(kind: InvoiceCostKind, amount: f64) -> InvoiceCost {
+        InvoiceCost {
+            id: Uuid::new_v4(),
+            invoice_id: Uuid::nil(),
+            kind,
+            expense_id: None,
+            description: None,
+            hours: None,
+            hourly_cost: None,
+            date: None,
+            amount,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn profitability_splits_costs_by_kind() {
+        let costs = vec![
+            cost(InvoiceCostKind::Expense, 120.0),
+            cost(InvoiceCostKind::Time, 200.0),
+            cost(InvoiceCostKind::Time, 80.0),
+        ];
+        let report = InvoiceProfitability::new(Uuid::nil(), "INV-1".to_string(), "USD".to_string(), 1000.0, costs);
+
+        assert_eq!(report.expense_costs, 120.0);
+        assert_eq!(report.time_costs, 280.0);
+        assert_eq!(report.margin, Margin::new(1000.0, 400.0));
+        assert_eq!(report.margin.margin, 600.0);
+        assert_eq!(report.margin.margin_percent, Some(60.0));
+    }
+
+    #[test]
+    fn margin_percent_needs_revenue() {
+        let margin = Margin::new(0.0, 50.0);
+        assert_eq!(margin.margin, -50.0);
+        assert_eq!(margin.margin_percent, None);
+
+        let loss = Margin::new(300.0, 400.0);
+        assert_eq!(loss.margin_percent, Some(-33.33));
+    }
+}
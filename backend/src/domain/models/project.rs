@@ -0,0 +1,223 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::invoice_totals::round_money;
+use crate::domain::models::Margin;
+
+pub const MAX_PROJECT_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    Active,
+    Completed,
+    Archived,
+}
+
+impl ProjectStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectStatus::Active => "active",
+            ProjectStatus::Completed => "completed",
+            ProjectStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(ProjectStatus::Active),
+            "completed" => Some(ProjectStatus::Completed),
+            "archived" => Some(ProjectStatus::Archived),
+            _ => None,
+        }
+    }
+}
+
+/// Work for one client that invoices, expenses and time entries are tagged with
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Project {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub name: String,
+    /// Only active projects take new invoices, expenses and time
+    pub status: ProjectStatus,
+    /// What the client agreed to spend, in the base currency
+    pub budget: Option<Decimal>,
+    /// Rate for the project's tracked time; an entry's own rate wins, and the
+    /// client's applies without either
+    pub hourly_rate: Option<Decimal>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateProject {
+    pub client_id: Uuid,
+    pub name: String,
+    pub budget: Option<Decimal>,
+    pub hourly_rate: Option<Decimal>,
+}
+
+/// Fields left out are unchanged. A budget or hourly rate of 0 removes it.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateProject {
+    pub name: Option<String>,
+    pub status: Option<ProjectStatus>,
+    pub budget: Option<Decimal>,
+    pub hourly_rate: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ProjectListFilter {
+    pub client_id: Option<Uuid>,
+    pub status: Option<ProjectStatus>,
+}
+
+/// Body of `PUT /invoices/{id}/project`, `PUT /expenses/{id}/project` and
+/// `PUT /time-entries/{id}/project`; null untags
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetProject {
+    pub project_id: Option<Uuid>,
+}
+
+/// Trimmed project name, or why it can't be used
+pub fn normalize_project_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Project name is required".to_string());
+    }
+    if name.chars().count() > MAX_PROJECT_NAME_LENGTH {
+        return Err(format!("Project name must be at most {} characters", MAX_PROJECT_NAME_LENGTH));
+    }
+    Ok(name.to_string())
+}
+
+/// A budget or rate as stored: positive, or None for 0
+pub fn normalize_project_amount(amount: Option<Decimal>, field: &str) -> Result<Option<Decimal>, String> {
+    match amount {
+        Some(amount) if amount < Decimal::ZERO => Err(format!("{} must not be negative", field)),
+        Some(amount) if amount.is_zero() => Ok(None),
+        amount => Ok(amount),
+    }
+}
+
+/// What a project's numbers are computed from, in the base currency
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectTotals {
+    /// Net revenue of the project's issued invoices
+    pub billed: f64,
+    pub expense_costs: f64,
+    /// Time costs recorded on the project's invoices
+    pub time_costs: f64,
+    pub tracked_hours: f64,
+    pub unbilled_hours: f64,
+    /// Billable, unbilled tracked time at its rate
+    pub unbilled_amount: f64,
+}
+
+/// Billed against costs for a project, and how much of its budget is used.
+/// Budget use counts billed work and tracked time not yet billed.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProjectProfitability {
+    pub project_id: Uuid,
+    pub name: String,
+    pub status: ProjectStatus,
+    #[serde(flatten)]
+    pub margin: Margin,
+    pub expense_costs: f64,
+    pub time_costs: f64,
+    pub tracked_hours: f64,
+    pub unbilled_hours: f64,
+    pub unbilled_amount: f64,
+    pub budget: Option<f64>,
+    pub budget_used: f64,
+    /// Negative once the project is over budget; None without a budget
+    pub remaining_budget: Option<f64>,
+    pub budget_used_percent: Option<f64>,
+}
+
+impl ProjectProfitability {
+    pub fn new(project: &Project, totals: ProjectTotals) -> Self {
+        let budget = project.budget.and_then(|budget| budget.to_f64());
+        let budget_used = totals.billed + totals.unbilled_amount;
+
+        Self {
+            project_id: project.id,
+            name: project.name.clone(),
+            status: project.status,
+            margin: Margin::new(totals.billed, totals.expense_costs + totals.time_costs),
+            expense_costs: round_money(totals.expense_costs),
+            time_costs: round_money(totals.time_costs),
+            tracked_hours: round_money(totals.tracked_hours),
+            unbilled_hours: round_money(totals.unbilled_hours),
+            unbilled_amount: round_money(totals.unbilled_amount),
+            budget,
+            budget_used: round_money(budget_used),
+            remaining_budget: budget.map(|budget| round_money(budget - budget_used)),
+            budget_used_percent: budget.map(|budget| (budget_used / budget * 10000.0).round() / 100.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(budget: Option<i64>) -> Project {
+        Project {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            name: "Website relaunch".to_string(),
+            status: ProjectStatus::Active,
+            budget: budget.map(Decimal::from),
+            hourly_rate: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn budget_use_counts_unbilled_time() {
+        let totals = ProjectTotals {
+            billed: 3000.0,
+            expense_costs: 400.0,
+            time_costs: 600.0,
+            tracked_hours: 40.0,
+            unbilled_hours: 10.0,
+            unbilled_amount: 1000.0,
+        };
+        let report = ProjectProfitability::new(&project(Some(5000)), totals);
+
+        assert_eq!(report.margin, Margin::new(3000.0, 1000.0));
+        assert_eq!(report.budget_used, 4000.0);
+        assert_eq!(report.remaining_budget, Some(1000.0));
+        assert_eq!(report.budget_used_percent, Some(80.0));
+
+        let over = ProjectProfitability::new(
+            &project(Some(2000)),
+            ProjectTotals { billed: 2500.0, ..Default::default() },
+        );
+        assert_eq!(over.remaining_budget, Some(-500.0));
+        assert_eq!(over.budget_used_percent, Some(125.0));
+
+        let unbudgeted = ProjectProfitability::new(&project(None), ProjectTotals::default());
+        assert_eq!(unbudgeted.remaining_budget, None);
+        assert_eq!(unbudgeted.budget_used_percent, None);
+    }
+
+    #[test]
+    fn validates_names_and_amounts() {
+        assert_eq!(normalize_project_name("  Relaunch ").unwrap(), "Relaunch");
+        assert!(normalize_project_name("").is_err());
+        assert!(normalize_project_name(&"x".repeat(MAX_PROJECT_NAME_LENGTH + 1)).is_err());
+
+        assert_eq!(normalize_project_amount(Some(Decimal::ZERO), "Budget").unwrap(), None);
+        assert_eq!(normalize_project_amount(Some(Decimal::from(10)), "Budget").unwrap(), Some(Decimal::from(10)));
+        assert!(normalize_project_amount(Some(Decimal::from(-1)), "Budget").is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How long events are kept for clients reconnecting with `Last-Event-ID`
+pub const REALTIME_REPLAY_MINUTES: i64 = 60;
+/// Most events replayed to a reconnecting client
+pub const MAX_REALTIME_REPLAY: i64 = 500;
+
+/// Events streamed to the dashboard. The database triggers in
+/// `060_add_realtime_events.sql` write them, so the names must match.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum RealtimeEventType {
+    #[serde(rename = "invoice.status_changed")]
+    InvoiceStatusChanged,
+    #[serde(rename = "invoice.viewed")]
+    InvoiceViewed,
+    #[serde(rename = "payment.created")]
+    PaymentCreated,
+    #[serde(rename = "discussion.message")]
+    DiscussionMessage,
+}
+
+impl RealtimeEventType {
+    pub const ALL: [RealtimeEventType; 4] = [
+        RealtimeEventType::InvoiceStatusChanged,
+        RealtimeEventType::InvoiceViewed,
+        RealtimeEventType::PaymentCreated,
+        RealtimeEventType::DiscussionMessage,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RealtimeEventType::InvoiceStatusChanged => "invoice.status_changed",
+            RealtimeEventType::InvoiceViewed => "invoice.viewed",
+            RealtimeEventType::PaymentCreated => "payment.created",
+            RealtimeEventType::DiscussionMessage => "discussion.message",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|event| event.as_str() == value)
+    }
+}
+
+/// One update for a user's dashboard. Sent as an SSE event named after its
+/// type, with the event as JSON data and `id` for resuming.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RealtimeEvent {
+    pub id: i64,
+    #[serde(skip)]
+    pub user_id: Uuid,
+    #[serde(rename = "type")]
+    pub event_type: RealtimeEventType,
+    pub invoice_id: Uuid,
+    /// Fields of the change; see the README for each type
+    pub data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What goes over Redis between replicas: the event with its owner, who is
+/// left out of what clients see
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealtimeEnvelope {
+    pub user_id: Uuid,
+    pub event: RealtimeEvent,
+}
+
+impl RealtimeEnvelope {
+    pub fn new(event: RealtimeEvent) -> Self {
+        Self { user_id: event.user_id, event }
+    }
+
+    pub fn into_event(self) -> RealtimeEvent {
+        RealtimeEvent { user_id: self.user_id, ..self.event }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_types_round_trip_and_hide_the_owner() {
+        for event_type in RealtimeEventType::ALL {
+            assert_eq!(RealtimeEventType::parse(event_type.as_str()), Some(event_type));
+            let json = serde_json::to_value(event_type).unwrap();
+            assert_eq!(json, event_type.as_str());
+        }
+
+        let event = RealtimeEvent {
+            id: 7,
+            user_id: Uuid::new_v4(),
+            event_type: RealtimeEventType::PaymentCreated,
+            invoice_id: Uuid::new_v4(),
+            data: serde_json::json!({ "amount": 100.0 }),
+            created_at: Utc::now(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "payment.created");
+        assert!(json.get("user_id").is_none());
+
+        let sent = serde_json::to_string(&RealtimeEnvelope::new(event.clone())).unwrap();
+        let received = serde_json::from_str::<RealtimeEnvelope>(&sent).unwrap().into_event();
+        assert_eq!(received.user_id, event.user_id);
+        assert_eq!(received.id, 7);
+    }
+}
@@ -0,0 +1,541 @@
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+use crate::domain::models::ExpenseCategory;
+
+pub const MAX_RECEIPT_BYTES: usize = 10 * 1024 * 1024;
+/// Image types the OCR backends read
+pub const RECEIPT_IMAGE_TYPES: &[&str] = &["image/jpeg", "image/png", "image/webp", "image/tiff"];
+/// Receipts dated further back than this are more likely a misread than a real date
+const MAX_RECEIPT_AGE_YEARS: i32 = 10;
+/// The vendor name is printed at the top of the receipt
+const VENDOR_SEARCH_LINES: usize = 5;
+
+/// A line of text as recognized by the OCR backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrLine {
+    pub text: String,
+    /// 0 to 1
+    pub confidence: f64,
+}
+
+/// A value read from the receipt, with how sure the extraction is of it: the OCR
+/// confidence of its line scaled down by how strongly the line indicated it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ExtractedField<T> {
+    pub value: T,
+    pub confidence: f64,
+}
+
+impl<T> ExtractedField<T> {
+    fn new(value: T, line: &OcrLine, certainty: f64) -> Self {
+        Self { value, confidence: round_confidence(line.confidence * certainty) }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ReceiptFields {
+    pub vendor: Option<ExtractedField<String>>,
+    pub date: Option<ExtractedField<NaiveDate>>,
+    /// Total paid, tax included
+    pub amount: Option<ExtractedField<Decimal>>,
+    pub tax_amount: Option<ExtractedField<Decimal>>,
+    /// As a fraction (0.11 for 11%)
+    pub tax_rate: Option<ExtractedField<Decimal>>,
+}
+
+impl ReceiptFields {
+    /// The expense the receipt suggests, for the user to check and complete
+    pub fn draft(&self, receipt_image_url: &str) -> ExpenseDraft {
+        ExpenseDraft {
+            amount: self.amount.as_ref().map(|field| field.value),
+            category: ExpenseCategory::Other,
+            vendor: self.vendor.as_ref().map(|field| field.value.clone()),
+            date_incurred: self.date.as_ref().map(|field| field.value),
+            tax_rate: self.tax_rate.as_ref().map(|field| field.value),
+            tax_amount: self.tax_amount.as_ref().map(|field| field.value),
+            receipt_image_url: receipt_image_url.to_string(),
+        }
+    }
+}
+
+/// Suggested expense; fields the receipt didn't give are left empty
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpenseDraft {
+    pub amount: Option<Decimal>,
+    pub category: ExpenseCategory,
+    pub vendor: Option<String>,
+    pub date_incurred: Option<NaiveDate>,
+    pub tax_rate: Option<Decimal>,
+    pub tax_amount: Option<Decimal>,
+    pub receipt_image_url: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptScanStatus {
+    Completed,
+    /// The OCR backend couldn't read the image; the receipt is still stored
+    Failed,
+}
+
+impl ReceiptScanStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptScanStatus::Completed => "completed",
+            ReceiptScanStatus::Failed => "failed",
+        }
+    }
+
+    pub fn from_stored(value: &str) -> Self {
+        match value {
+            "completed" => ReceiptScanStatus::Completed,
+            _ => ReceiptScanStatus::Failed,
+        }
+    }
+}
+
+/// An uploaded receipt and what OCR read from it
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReceiptScan {
+    pub id: Uuid,
+    pub status: ReceiptScanStatus,
+    /// OCR backend that read the receipt
+    pub backend: String,
+    pub file_name: String,
+    pub receipt_image_url: String,
+    /// Mean OCR confidence over the recognized lines, 0 to 1
+    pub confidence: Option<f64>,
+    pub fields: ReceiptFields,
+    pub draft: ExpenseDraft,
+    pub raw_text: Option<String>,
+    pub error: Option<String>,
+    /// The expense created from the scan, once confirmed
+    pub expense_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+/// Mean confidence of the recognized lines, or None when nothing was read
+pub fn mean_confidence(lines: &[OcrLine]) -> Option<f64> {
+    if lines.is_empty() {
+        return None;
+    }
+    let total: f64 = lines.iter().map(|line| line.confidence).sum();
+    Some(round_confidence(total / lines.len() as f64))
+}
+
+fn round_confidence(confidence: f64) -> f64 {
+    (confidence.clamp(0.0, 1.0) * 10_000.0).round() / 10_000.0
+}
+
+/// Page, block, paragraph and line number of a word in Tesseract's TSV output
+type TesseractLine<'a> = (&'a str, &'a str, &'a str, &'a str);
+
+/// Lines from Tesseract's TSV output: words grouped by the line they are on, with
+/// the line's confidence the mean of its words'
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrLine> {
+    // Words in reading order, with their total confidence, by the line they are on
+    let mut grouped: Vec<(TesseractLine, Vec<&str>, f64)> = Vec::new();
+
+    // level page_num block_num par_num line_num word_num left top width height conf text
+    for row in tsv.lines().skip(1) {
+        let columns: Vec<&str> = row.split('\t').collect();
+        if columns.len() < 12 || columns[0] != "5" {
+            continue;
+        }
+        let text = columns[11].trim();
+        let Ok(confidence) = columns[10].parse::<f64>() else {
+            continue;
+        };
+        if text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+
+        let key = (columns[1], columns[2], columns[3], columns[4]);
+        match grouped.last_mut() {
+            Some((line_key, words, total)) if *line_key == key => {
+                words.push(text);
+                *total += confidence;
+            }
+            _ => grouped.push((key, vec![text], confidence)),
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|(_, words, total)| OcrLine {
+            confidence: round_confidence(total / words.len() as f64 / 100.0),
+            text: words.join(" "),
+        })
+        .collect()
+}
+
+static AMOUNT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d[\d.,]*\d|\d").unwrap());
+static PERCENT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(\d{1,2}(?:[.,]\d{1,2})?)\s*%").unwrap());
+static TAX_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(tax|vat|gst|hst|ppn|pajak|mwst|iva|btw|tva)\b").unwrap());
+static TOTAL_WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(grand total|total due|amount due|balance due|total|jumlah|amount)\b").unwrap()
+});
+static SUBTOTAL_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)(sub\s*-?\s*total|before tax|\bexcl|\b(change|kembali|tunai|cash)\b)").unwrap());
+static DATE_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(date|tanggal|tgl|datum)\b").unwrap());
+static ISO_DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d{4})[-/.](\d{1,2})[-/.](\d{1,2})\b").unwrap());
+static NUMERIC_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(\d{1,2})[-/.](\d{1,2})[-/.](\d{4}|\d{2})\b").unwrap());
+static DAY_MONTH_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(\d{1,2})[\s-]+([a-z]{3,9})\.?[\s-]+(\d{4})\b").unwrap());
+static MONTH_DAY_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b([a-z]{3,9})\.?\s+(\d{1,2}),?\s+(\d{4})\b").unwrap());
+static NOT_VENDOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(\b(receipt|invoice|struk|nota|kwitansi|tel|phone|telp|npwp|cashier|kasir)\b|www\.|https?:|@)").unwrap()
+});
+
+/// An amount as printed on a receipt. Either `,` or `.` can be the decimal
+/// separator; a separator followed by exactly three digits groups thousands,
+/// as in "Rp 45.000" or "1,250".
+pub fn parse_receipt_amount(text: &str) -> Option<Decimal> {
+    let separators: Vec<(usize, char)> = text.char_indices().filter(|(_, c)| *c == '.' || *c == ',').collect();
+    let Some(&(last_index, last_separator)) = separators.last() else {
+        return Decimal::from_str(text).ok();
+    };
+
+    let decimals = &text[last_index + 1..];
+    let (integer_part, fraction) = if decimals.len() == 3 {
+        (text, "")
+    } else if (1..=2).contains(&decimals.len()) {
+        (&text[..last_index], decimals)
+    } else {
+        return None;
+    };
+
+    // What's left can only hold thousands separators, all of the same kind and not
+    // the decimal one, between groups of three digits
+    let mut groups = integer_part.split(['.', ',']);
+    let first = groups.next()?;
+    if first.is_empty() || (first.len() > 3 && integer_part.len() != first.len()) {
+        return None;
+    }
+    if groups.any(|group| group.len() != 3) {
+        return None;
+    }
+    let grouping: Vec<char> = integer_part.chars().filter(|c| *c == '.' || *c == ',').collect();
+    if grouping.windows(2).any(|pair| pair[0] != pair[1]) {
+        return None;
+    }
+    if !fraction.is_empty() && grouping.contains(&last_separator) {
+        return None;
+    }
+
+    let digits: String = integer_part.chars().filter(char::is_ascii_digit).collect();
+    let number = if fraction.is_empty() { digits } else { format!("{}.{}", digits, fraction) };
+    Decimal::from_str(&number).ok()
+}
+
+/// Amounts on a line, in the order printed; dates and percentages aren't amounts
+fn line_amounts(text: &str) -> Vec<Decimal> {
+    let mut text = text.to_string();
+    for pattern in [&*ISO_DATE, &*NUMERIC_DATE, &*PERCENT] {
+        text = pattern.replace_all(&text, " ").into_owned();
+    }
+    AMOUNT
+        .find_iter(&text)
+        .filter_map(|found| parse_receipt_amount(found.as_str()))
+        .filter(|amount| *amount > Decimal::ZERO)
+        .collect()
+}
+
+fn month_number(name: &str) -> Option<u32> {
+    let name = name.to_lowercase();
+    let month = match name.get(..3)? {
+        "jan" => 1,
+        "feb" | "peb" => 2,
+        "mar" | "mrt" => 3,
+        "apr" => 4,
+        "may" | "mei" | "mai" => 5,
+        "jun" => 6,
+        "jul" => 7,
+        "aug" | "agu" | "agt" => 8,
+        "sep" => 9,
+        "oct" | "okt" => 10,
+        "nov" => 11,
+        "dec" | "des" | "dez" => 12,
+        _ => return None,
+    };
+    Some(month)
+}
+
+fn full_year(year: &str) -> Option<i32> {
+    let year: i32 = year.parse().ok()?;
+    Some(if year < 100 { 2000 + year } else { year })
+}
+
+/// The first plausible date on a line. Numeric dates are read day first, unless
+/// only month first makes a date.
+fn line_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let plausible = |date: NaiveDate| {
+        let oldest = today.with_year(today.year() - MAX_RECEIPT_AGE_YEARS).unwrap_or(NaiveDate::MIN);
+        (oldest..=today).contains(&date).then_some(date)
+    };
+    let number = |value: &str| value.parse::<u32>().ok();
+
+    if let Some(captures) = ISO_DATE.captures(text) {
+        let date = NaiveDate::from_ymd_opt(full_year(&captures[1])?, number(&captures[2])?, number(&captures[3])?);
+        if let Some(date) = date.and_then(plausible) {
+            return Some(date);
+        }
+    }
+    if let Some(captures) = NUMERIC_DATE.captures(text) {
+        let (first, second, year) = (number(&captures[1])?, number(&captures[2])?, full_year(&captures[3])?);
+        let date = NaiveDate::from_ymd_opt(year, second, first)
+            .or_else(|| NaiveDate::from_ymd_opt(year, first, second));
+        if let Some(date) = date.and_then(plausible) {
+            return Some(date);
+        }
+    }
+    if let Some(captures) = DAY_MONTH_DATE.captures(text) {
+        let date = NaiveDate::from_ymd_opt(full_year(&captures[3])?, month_number(&captures[2])?, number(&captures[1])?);
+        if let Some(date) = date.and_then(plausible) {
+            return Some(date);
+        }
+    }
+    if let Some(captures) = MONTH_DAY_DATE.captures(text) {
+        let date = NaiveDate::from_ymd_opt(full_year(&captures[3])?, month_number(&captures[1])?, number(&captures[2])?);
+        if let Some(date) = date.and_then(plausible) {
+            return Some(date);
+        }
+    }
+    None
+}
+
+fn is_tax_line(text: &str) -> bool {
+    TAX_WORD.is_match(text) && !SUBTOTAL_WORD.is_match(text) && !text.to_lowercase().contains("incl")
+}
+
+/// The total: the amount on the line labelled as the total, preferring "grand
+/// total" and "amount due" over a plain "total". Without one, the largest amount.
+fn extract_amount(lines: &[OcrLine]) -> Option<ExtractedField<Decimal>> {
+    let mut best: Option<(u8, ExtractedField<Decimal>)> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let Some(label) = TOTAL_WORD.find(&line.text) else {
+            continue;
+        };
+        if SUBTOTAL_WORD.is_match(&line.text) || is_tax_line(&line.text) {
+            continue;
+        }
+        let (rank, certainty) = match label.as_str().to_lowercase().as_str() {
+            "grand total" | "total due" | "amount due" | "balance due" => (3, 0.95),
+            "total" | "jumlah" => (2, 0.85),
+            _ => (1, 0.7),
+        };
+        // Columns can put the amount on the line below its label
+        let found = match line_amounts(&line.text).last() {
+            Some(amount) => Some((*amount, line)),
+            None => lines
+                .get(index + 1)
+                .and_then(|next| line_amounts(&next.text).last().map(|amount| (*amount, next))),
+        };
+        let Some((amount, line)) = found else {
+            continue;
+        };
+        // The last total wins among equals; receipts list totals top to bottom
+        if best.as_ref().is_none_or(|(best_rank, _)| rank >= *best_rank) {
+            best = Some((rank, ExtractedField::new(amount, line, certainty)));
+        }
+    }
+    if let Some((_, field)) = best {
+        return Some(field);
+    }
+
+    lines
+        .iter()
+        .flat_map(|line| line_amounts(&line.text).into_iter().map(move |amount| (amount, line)))
+        .max_by_key(|(amount, _)| *amount)
+        .map(|(amount, line)| ExtractedField::new(amount, line, 0.5))
+}
+
+/// Tax on a line naming it; a percentage on that line is its rate
+fn extract_tax(
+    lines: &[OcrLine],
+    total: Option<Decimal>,
+) -> (Option<ExtractedField<Decimal>>, Option<ExtractedField<Decimal>>) {
+    let mut tax_amount = None;
+    let mut tax_rate = None;
+    for line in lines.iter().filter(|line| is_tax_line(&line.text)) {
+        if tax_rate.is_none() {
+            tax_rate = PERCENT
+                .captures(&line.text)
+                .and_then(|captures| Decimal::from_str(&captures[1].replace(',', ".")).ok())
+                .filter(|percent| *percent > Decimal::ZERO && *percent < Decimal::ONE_HUNDRED)
+                .map(|percent| ExtractedField::new(percent / Decimal::ONE_HUNDRED, line, 0.9));
+        }
+        if tax_amount.is_none() {
+            tax_amount = line_amounts(&line.text)
+                .last()
+                .filter(|amount| total.is_none_or(|total| **amount < total))
+                .map(|amount| ExtractedField::new(*amount, line, 0.85));
+        }
+    }
+    (tax_amount, tax_rate)
+}
+
+/// The receipt's date, preferring a line labelled as the date
+fn extract_date(lines: &[OcrLine], today: NaiveDate) -> Option<ExtractedField<NaiveDate>> {
+    let labelled = lines
+        .iter()
+        .filter(|line| DATE_WORD.is_match(&line.text))
+        .find_map(|line| line_date(&line.text, today).map(|date| ExtractedField::new(date, line, 0.95)));
+    labelled.or_else(|| {
+        lines
+            .iter()
+            .find_map(|line| line_date(&line.text, today).map(|date| ExtractedField::new(date, line, 0.8)))
+    })
+}
+
+/// The first line near the top that reads like a name rather than an address,
+/// a number or a heading
+fn extract_vendor(lines: &[OcrLine], today: NaiveDate) -> Option<ExtractedField<String>> {
+    lines.iter().take(VENDOR_SEARCH_LINES).find_map(|line| {
+        let text = line.text.trim();
+        let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+        let reads_like_name = letters >= 3
+            && letters * 2 > text.chars().filter(|c| !c.is_whitespace()).count()
+            && !NOT_VENDOR.is_match(text)
+            && !TOTAL_WORD.is_match(text)
+            && line_date(text, today).is_none();
+        reads_like_name.then(|| ExtractedField::new(text.to_string(), line, 0.7))
+    })
+}
+
+/// Vendor, date, total and tax read from the recognized lines of a receipt
+pub fn extract_receipt(lines: &[OcrLine], today: NaiveDate) -> ReceiptFields {
+    let amount = extract_amount(lines);
+    let (tax_amount, tax_rate) = extract_tax(lines, amount.as_ref().map(|field| field.value));
+    ReceiptFields {
+        vendor: extract_vendor(lines, today),
+        date: extract_date(lines, today),
+        amount,
+        tax_amount,
+        tax_rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn lines(texts: &[&str]) -> Vec<OcrLine> {
+        texts.iter().map(|text| OcrLine { text: text.to_string(), confidence: 0.9 }).collect()
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 20).unwrap()
+    }
+
+    #[test]
+    fn test_parse_receipt_amount() {
+        assert_eq!(parse_receipt_amount("45.000"), Some(dec!(45000)));
+        assert_eq!(parse_receipt_amount("1,250"), Some(dec!(1250)));
+        assert_eq!(parse_receipt_amount("12.50"), Some(dec!(12.50)));
+        assert_eq!(parse_receipt_amount("1.234,56"), Some(dec!(1234.56)));
+        assert_eq!(parse_receipt_amount("1,234,567.8"), Some(dec!(1234567.8)));
+        assert_eq!(parse_receipt_amount("1250"), Some(dec!(1250)));
+        assert_eq!(parse_receipt_amount("12.03.2026"), None);
+        assert_eq!(parse_receipt_amount("1.2345"), None);
+        assert_eq!(parse_receipt_amount("1,234.567"), None);
+    }
+
+    #[test]
+    fn test_extract_english_receipt() {
+        let fields = extract_receipt(
+            &lines(&[
+                "Blue Bottle Coffee",
+                "123 Market St, San Francisco",
+                "Date: 03/14/2026 09:12",
+                "Latte 2 x 5.50 11.00",
+                "Subtotal 11.00",
+                "Sales Tax 8.5% 0.94",
+                "Total 11.94",
+                "Visa 11.94",
+            ]),
+            today(),
+        );
+
+        assert_eq!(fields.vendor.unwrap().value, "Blue Bottle Coffee");
+        assert_eq!(fields.date.unwrap().value, NaiveDate::from_ymd_opt(2026, 3, 14).unwrap());
+        let amount = fields.amount.unwrap();
+        assert_eq!(amount.value, dec!(11.94));
+        assert!((amount.confidence - 0.765).abs() < 0.001);
+        assert_eq!(fields.tax_amount.unwrap().value, dec!(0.94));
+        assert_eq!(fields.tax_rate.unwrap().value, dec!(0.085));
+    }
+
+    #[test]
+    fn test_extract_indonesian_receipt() {
+        let fields = extract_receipt(
+            &lines(&[
+                "STRUK BELANJA",
+                "Toko Sumber Rejeki",
+                "Tgl 5 Mar 2026",
+                "Kopi Susu 2 20.000",
+                "Sub Total 40.000",
+                "PPN 11% 4.400",
+                "Grand Total Rp 44.400",
+                "Tunai 50.000",
+                "Kembali 5.600",
+            ]),
+            today(),
+        );
+
+        assert_eq!(fields.vendor.unwrap().value, "Toko Sumber Rejeki");
+        assert_eq!(fields.date.unwrap().value, NaiveDate::from_ymd_opt(2026, 3, 5).unwrap());
+        assert_eq!(fields.amount.unwrap().value, dec!(44400));
+        assert_eq!(fields.tax_amount.unwrap().value, dec!(4400));
+        assert_eq!(fields.tax_rate.unwrap().value, dec!(0.11));
+    }
+
+    #[test]
+    fn test_extract_falls_back_to_largest_amount() {
+        let fields = extract_receipt(&lines(&["Harbour Hotel Shop", "Bread 3.20", "Milk 1.10", "4.30"]), today());
+
+        assert_eq!(fields.vendor.unwrap().value, "Harbour Hotel Shop");
+        let amount = fields.amount.unwrap();
+        assert_eq!(amount.value, dec!(4.30));
+        assert!((amount.confidence - 0.45).abs() < 0.001);
+        assert!(fields.date.is_none());
+        assert!(fields.tax_amount.is_none());
+    }
+
+    #[test]
+    fn test_future_dates_are_not_read() {
+        let fields = extract_receipt(&lines(&["Date 2027-01-02", "Printed 2026-03-01"]), today());
+        assert_eq!(fields.date.unwrap().value, NaiveDate::from_ymd_opt(2026, 3, 1).unwrap());
+    }
+
+    #[test]
+    fn test_parse_tesseract_tsv() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+            1\t1\t0\t0\t0\t0\t0\t0\t600\t800\t-1\t\n\
+            5\t1\t1\t1\t1\t1\t10\t10\t50\t20\t96\tCorner\n\
+            5\t1\t1\t1\t1\t2\t70\t10\t50\t20\t90\tShop\n\
+            5\t1\t1\t1\t2\t1\t10\t40\t50\t20\t80\tTotal\n\
+            5\t1\t1\t1\t2\t2\t70\t40\t50\t20\t70\t4.30\n";
+
+        assert_eq!(
+            parse_tesseract_tsv(tsv),
+            vec![
+                OcrLine { text: "Corner Shop".to_string(), confidence: 0.93 },
+                OcrLine { text: "Total 4.30".to_string(), confidence: 0.75 },
+            ]
+        );
+        assert_eq!(mean_confidence(&parse_tesseract_tsv(tsv)), Some(0.84));
+    }
+}
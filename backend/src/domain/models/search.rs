@@ -0,0 +1,191 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::{ExpenseCategory, InvoiceStatus};
+
+/// Longest search text accepted
+pub const MAX_SEARCH_QUERY_CHARS: usize = 200;
+/// Words of a query that are matched; any after these are ignored
+const MAX_SEARCH_TERMS: usize = 8;
+/// Results per group when the request doesn't ask for a number
+pub const DEFAULT_SEARCH_LIMIT: i64 = 5;
+pub const MAX_SEARCH_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchType {
+    Invoices,
+    Clients,
+    Expenses,
+}
+
+impl SearchType {
+    pub const ALL: [SearchType; 3] = [SearchType::Invoices, SearchType::Clients, SearchType::Expenses];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "invoices" | "invoice" => Some(SearchType::Invoices),
+            "clients" | "client" => Some(SearchType::Clients),
+            "expenses" | "expense" => Some(SearchType::Expenses),
+            _ => None,
+        }
+    }
+}
+
+/// Query for `GET /search`
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+    /// Comma-separated groups to search, e.g. `invoices,clients`; all by default
+    pub types: Option<String>,
+    /// Results per group
+    pub limit: Option<i64>,
+}
+
+impl SearchQuery {
+    pub fn types(&self) -> Result<Vec<SearchType>, String> {
+        let Some(raw) = self.types.as_deref().filter(|raw| !raw.trim().is_empty()) else {
+            return Ok(SearchType::ALL.to_vec());
+        };
+
+        let mut types = Vec::new();
+        for part in raw.split(',').filter(|part| !part.trim().is_empty()) {
+            let search_type = SearchType::parse(part)
+                .ok_or_else(|| format!("Unknown search type: {} (expected invoices, clients or expenses)", part.trim()))?;
+            if !types.contains(&search_type) {
+                types.push(search_type);
+            }
+        }
+        Ok(types)
+    }
+
+    pub fn limit(&self) -> Result<i64, String> {
+        let limit = self.limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+        if !(1..=MAX_SEARCH_LIMIT).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_SEARCH_LIMIT));
+        }
+        Ok(limit)
+    }
+}
+
+/// The search text as a tsquery matching every word as a prefix, so results
+/// show up while a word is still being typed: `acme inv-20` becomes
+/// `acme:* & inv:* & 20:*`. Only letters and digits are kept, which is also how
+/// the indexed text is split, and leaves nothing the tsquery syntax would read
+/// as an operator.
+pub fn prefix_tsquery(q: &str) -> Result<String, String> {
+    if q.chars().count() > MAX_SEARCH_QUERY_CHARS {
+        return Err(format!("q can be at most {} characters", MAX_SEARCH_QUERY_CHARS));
+    }
+
+    let terms: Vec<String> = q
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(MAX_SEARCH_TERMS)
+        .map(|word| format!("{}:*", word))
+        .collect();
+    if terms.is_empty() {
+        return Err("q must contain at least one letter or digit".to_string());
+    }
+    Ok(terms.join(" & "))
+}
+
+/// Results of one type. `total` counts every match, of which `items` are the
+/// best ranked.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchGroup<T> {
+    pub total: i64,
+    pub items: Vec<T>,
+}
+
+impl<T> Default for SearchGroup<T> {
+    fn default() -> Self {
+        Self { total: 0, items: Vec::new() }
+    }
+}
+
+/// A matching invoice. `snippet` is the part of the notes, terms or line items
+/// that matched, with matched words between `**`; it's absent when only the
+/// invoice number matched.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceSearchHit {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub status: InvoiceStatus,
+    pub client_name: String,
+    pub total_amount: Decimal,
+    pub issue_date: NaiveDate,
+    pub snippet: Option<String>,
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientSearchHit {
+    pub id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub company_name: Option<String>,
+    /// Matching part of the client's notes
+    pub snippet: Option<String>,
+    pub rank: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExpenseSearchHit {
+    pub id: Uuid,
+    pub vendor: Option<String>,
+    pub category: ExpenseCategory,
+    pub amount: Decimal,
+    pub currency: String,
+    pub date_incurred: NaiveDate,
+    /// Matching part of the description
+    pub snippet: Option<String>,
+    pub rank: f32,
+}
+
+/// Results grouped by type; types that weren't searched are left out
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SearchResults {
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoices: Option<SearchGroup<InvoiceSearchHit>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clients: Option<SearchGroup<ClientSearchHit>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expenses: Option<SearchGroup<ExpenseSearchHit>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_tsquery_keeps_only_words() {
+        assert_eq!(prefix_tsquery("acme").unwrap(), "acme:*");
+        assert_eq!(prefix_tsquery("  Acme  INV-20 ").unwrap(), "Acme:* & INV:* & 20:*");
+        assert_eq!(prefix_tsquery("a & !b | c:* <-> d").unwrap(), "a:* & b:* & c:* & d:*");
+        assert_eq!(prefix_tsquery("kopi susu").unwrap(), "kopi:* & susu:*");
+        assert!(prefix_tsquery(" -- ").is_err());
+        assert!(prefix_tsquery(&"a".repeat(MAX_SEARCH_QUERY_CHARS + 1)).is_err());
+
+        let many = (0..20).map(|i| format!("w{}", i)).collect::<Vec<_>>().join(" ");
+        assert_eq!(prefix_tsquery(&many).unwrap().matches(":*").count(), MAX_SEARCH_TERMS);
+    }
+
+    #[test]
+    fn test_search_types_default_to_all() {
+        let query = SearchQuery { q: "x".to_string(), ..Default::default() };
+        assert_eq!(query.types().unwrap(), SearchType::ALL.to_vec());
+
+        let query = SearchQuery { types: Some("clients, invoices,clients".to_string()), ..query };
+        assert_eq!(query.types().unwrap(), vec![SearchType::Clients, SearchType::Invoices]);
+
+        let query = SearchQuery { types: Some("payments".to_string()), ..query };
+        assert!(query.types().is_err());
+    }
+}
@@ -0,0 +1,397 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{
+    normalize_optional_phone, BusinessAddress, ClientResponse, InvoiceDetailResponse, InvoiceSettings,
+    NotificationSettings, PaymentResponse, TaxSettings, User,
+};
+
+/// Most mutations accepted by one `POST /sync/apply`
+pub const MAX_SYNC_MUTATIONS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncEntityType {
+    Invoice,
+    Client,
+    Payment,
+    /// The account's settings; never deleted
+    Settings,
+}
+
+impl SyncEntityType {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "invoice" => Some(SyncEntityType::Invoice),
+            "client" => Some(SyncEntityType::Client),
+            "payment" => Some(SyncEntityType::Payment),
+            "settings" => Some(SyncEntityType::Settings),
+            _ => None,
+        }
+    }
+}
+
+/// Position in the change feed: the last change delivered, ordered by change time
+/// then entity ID. Clients treat the encoded form as opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncCursor {
+    pub changed_at: DateTime<Utc>,
+    pub entity_id: Uuid,
+}
+
+impl SyncCursor {
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.changed_at.timestamp_micros(), self.entity_id))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, String> {
+        let invalid = || "Invalid sync cursor".to_string();
+        let bytes = URL_SAFE_NO_PAD.decode(cursor.trim()).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, entity_id) = text.split_once(':').ok_or_else(invalid)?;
+
+        let micros = micros.parse::<i64>().map_err(|_| invalid())?;
+        Ok(Self {
+            changed_at: DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?,
+            entity_id: Uuid::parse_str(entity_id).map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// One entry of the change feed. Trashed clients and invoices and hard deletions
+/// are reported as deleted.
+#[derive(Debug, Clone)]
+pub struct SyncChange {
+    pub entity_type: SyncEntityType,
+    pub entity_id: Uuid,
+    pub changed_at: DateTime<Utc>,
+    pub deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncTombstone {
+    pub entity_type: SyncEntityType,
+    pub id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Account settings as the settings endpoints return them
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncSettings {
+    pub email: String,
+    pub company_name: Option<String>,
+    pub business_type: Option<String>,
+    pub phone: Option<String>,
+    pub business_address: Option<BusinessAddress>,
+    pub currency: String,
+    pub tax_settings: Option<TaxSettings>,
+    pub notification_settings: NotificationSettings,
+    pub invoice_settings: InvoiceSettings,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<User> for SyncSettings {
+    fn from(user: User) -> Self {
+        Self {
+            email: user.email,
+            company_name: user.company_name,
+            business_type: user.business_type,
+            phone: user.phone,
+            business_address: user.business_address,
+            currency: user.currency,
+            tax_settings: user.tax_settings,
+            notification_settings: user.notification_settings,
+            invoice_settings: user.invoice_settings.unwrap_or_default(),
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// Everything changed since a cursor. Entities are upserted by ID on the client, so
+/// an entity may be delivered more than once; `deleted` lists what to drop.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncChanges {
+    /// Pass back as `since` on the next sync; None until anything has been delivered
+    pub cursor: Option<String>,
+    /// More changes are waiting; sync again straight away
+    pub has_more: bool,
+    pub invoices: Vec<InvoiceDetailResponse>,
+    pub clients: Vec<ClientResponse>,
+    pub payments: Vec<PaymentResponse>,
+    /// Present when settings changed since the cursor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings: Option<SyncSettings>,
+    pub deleted: Vec<SyncTombstone>,
+}
+
+impl SyncChanges {
+    pub fn is_empty(&self) -> bool {
+        self.invoices.is_empty()
+            && self.clients.is_empty()
+            && self.payments.is_empty()
+            && self.settings.is_none()
+            && self.deleted.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOp {
+    Create,
+    Update,
+    Delete,
+}
+
+/// An edit made offline. `base_version` is the `updated_at` of the copy the edit was
+/// made against; the edit only applies if the server copy is still at that version.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncMutation {
+    /// Client-assigned ID, echoed back in the result
+    pub mutation_id: String,
+    pub entity_type: SyncEntityType,
+    /// For creates, the ID the client generated for the new entity
+    pub entity_id: Uuid,
+    pub op: SyncOp,
+    pub base_version: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ApplySyncMutations {
+    pub mutations: Vec<SyncMutation>,
+}
+
+/// Client fields editable offline. Omitted fields are left unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyncClientFields {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub company_name: Option<String>,
+    pub billing_address: Option<serde_json::Value>,
+    pub payment_terms: Option<i32>,
+    pub notes: Option<String>,
+}
+
+/// Invoice fields editable offline; line items change totals and tax, so they are
+/// edited online only
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SyncInvoiceFields {
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+    pub due_date: Option<NaiveDate>,
+}
+
+/// A mutation that passed validation, ready to apply
+#[derive(Debug, Clone)]
+pub enum SyncWrite {
+    CreateClient(SyncClientFields),
+    UpdateClient(SyncClientFields),
+    DeleteClient,
+    UpdateInvoice(SyncInvoiceFields),
+    DeleteInvoice,
+}
+
+impl SyncMutation {
+    pub fn to_write(&self) -> Result<SyncWrite, String> {
+        if self.op != SyncOp::Create && self.base_version.is_none() {
+            return Err("base_version is required for updates and deletes".to_string());
+        }
+
+        match (self.entity_type, self.op) {
+            (SyncEntityType::Client, SyncOp::Create) => {
+                let fields = self.client_fields()?;
+                if fields.name.is_none() {
+                    return Err("name is required".to_string());
+                }
+                Ok(SyncWrite::CreateClient(fields))
+            }
+            (SyncEntityType::Client, SyncOp::Update) => Ok(SyncWrite::UpdateClient(self.client_fields()?)),
+            (SyncEntityType::Client, SyncOp::Delete) => Ok(SyncWrite::DeleteClient),
+            (SyncEntityType::Invoice, SyncOp::Update) => {
+                let fields: SyncInvoiceFields = self.fields()?;
+                if fields.notes.is_none() && fields.terms.is_none() && fields.due_date.is_none() {
+                    return Err("data must change notes, terms or due_date".to_string());
+                }
+                Ok(SyncWrite::UpdateInvoice(fields))
+            }
+            (SyncEntityType::Invoice, SyncOp::Delete) => Ok(SyncWrite::DeleteInvoice),
+            (SyncEntityType::Invoice, SyncOp::Create) => {
+                Err("invoices are created online so they get a number".to_string())
+            }
+            (SyncEntityType::Payment, _) | (SyncEntityType::Settings, _) => {
+                Err("only clients and invoices can be edited offline".to_string())
+            }
+        }
+    }
+
+    fn fields<T: serde::de::DeserializeOwned + Default>(&self) -> Result<T, String> {
+        if self.data.is_null() {
+            return Ok(T::default());
+        }
+        serde_json::from_value(self.data.clone()).map_err(|e| format!("Invalid data: {}", e))
+    }
+
+    fn client_fields(&self) -> Result<SyncClientFields, String> {
+        let mut fields: SyncClientFields = self.fields()?;
+
+        if let Some(name) = &fields.name {
+            let name = name.trim();
+            if name.is_empty() || name.len() > 255 {
+                return Err("name must be 1 to 255 characters".to_string());
+            }
+            fields.name = Some(name.to_string());
+        }
+        if fields.email.as_ref().is_some_and(|email| !email.validate_email()) {
+            return Err("email is not a valid address".to_string());
+        }
+        if fields.payment_terms.is_some_and(|days| days < 0) {
+            return Err("payment_terms must not be negative".to_string());
+        }
+        fields.phone = normalize_optional_phone(fields.phone)?;
+        Ok(fields)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictReason {
+    /// The server copy changed since `base_version`
+    VersionMismatch,
+    NotFound,
+    /// The entity was deleted on the server
+    Deleted,
+    /// A create reused an ID that already exists
+    AlreadyExists,
+    /// The invoice's status doesn't allow the change, e.g. it's paid or was
+    /// replaced by a correction
+    NotEditable,
+    HasActiveInvoices,
+    HasSubsidiaries,
+}
+
+impl SyncConflictReason {
+    pub fn message(&self) -> &'static str {
+        match self {
+            SyncConflictReason::VersionMismatch => "Changed on the server since base_version",
+            SyncConflictReason::NotFound => "Not found",
+            SyncConflictReason::Deleted => "Deleted on the server",
+            SyncConflictReason::AlreadyExists => "An entity with this ID already exists",
+            SyncConflictReason::NotEditable => "The invoice's status doesn't allow this change",
+            SyncConflictReason::HasActiveInvoices => "Client has active invoices; archive it instead",
+            SyncConflictReason::HasSubsidiaries => "Client has subsidiaries; reassign or delete them first",
+        }
+    }
+}
+
+/// What became of one mutation inside the apply transaction
+#[derive(Debug, Clone)]
+pub enum SyncWriteOutcome {
+    /// Applied; the entity's new version, None once deleted
+    Applied(Option<DateTime<Utc>>),
+    /// Skipped, with the server copy's version if it still exists
+    Conflict(SyncConflictReason, Option<DateTime<Utc>>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum SyncEntity {
+    Invoice(Box<InvoiceDetailResponse>),
+    Client(Box<ClientResponse>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncAppliedMutation {
+    pub mutation_id: String,
+    pub entity_type: SyncEntityType,
+    pub entity_id: Uuid,
+    pub op: SyncOp,
+    /// New version to use as base_version for further edits; None after a delete
+    pub version: Option<DateTime<Utc>>,
+    pub entity: Option<SyncEntity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncConflict {
+    pub mutation_id: String,
+    pub entity_type: SyncEntityType,
+    pub entity_id: Uuid,
+    pub op: SyncOp,
+    pub reason: SyncConflictReason,
+    pub message: String,
+    pub current_version: Option<DateTime<Utc>>,
+    /// The server copy to resolve against, when it still exists
+    pub current: Option<SyncEntity>,
+}
+
+/// Result of applying a batch: everything without a conflict is committed together
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncApplyResult {
+    pub applied: Vec<SyncAppliedMutation>,
+    pub conflicts: Vec<SyncConflict>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = SyncCursor {
+            changed_at: DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap(),
+            entity_id: Uuid::new_v4(),
+        };
+
+        assert_eq!(SyncCursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn rejects_malformed_cursors() {
+        assert!(SyncCursor::decode("").is_err());
+        assert!(SyncCursor::decode("not base64!").is_err());
+        assert!(SyncCursor::decode(&URL_SAFE_NO_PAD.encode("123")).is_err());
+        assert!(SyncCursor::decode(&URL_SAFE_NO_PAD.encode("abc:not-a-uuid")).is_err());
+    }
+
+    fn mutation(entity_type: SyncEntityType, op: SyncOp, data: serde_json::Value) -> SyncMutation {
+        SyncMutation {
+            mutation_id: "m1".to_string(),
+            entity_type,
+            entity_id: Uuid::new_v4(),
+            op,
+            base_version: (op != SyncOp::Create).then(Utc::now),
+            data,
+        }
+    }
+
+    #[test]
+    fn checks_mutations_before_applying() {
+        let create = mutation(SyncEntityType::Client, SyncOp::Create, serde_json::json!({ "name": "  Acme  " }));
+        match create.to_write().unwrap() {
+            SyncWrite::CreateClient(fields) => assert_eq!(fields.name.as_deref(), Some("Acme")),
+            other => panic!("unexpected write: {:?}", other),
+        }
+
+        let nameless = mutation(SyncEntityType::Client, SyncOp::Create, serde_json::json!({ "email": "a@b.co" }));
+        assert!(nameless.to_write().is_err());
+
+        let unknown_field = mutation(SyncEntityType::Client, SyncOp::Update, serde_json::json!({ "balance": 5 }));
+        assert!(unknown_field.to_write().is_err());
+
+        let mut unversioned = mutation(SyncEntityType::Invoice, SyncOp::Delete, serde_json::Value::Null);
+        unversioned.base_version = None;
+        assert!(unversioned.to_write().is_err());
+
+        let empty_update = mutation(SyncEntityType::Invoice, SyncOp::Update, serde_json::json!({}));
+        assert!(empty_update.to_write().is_err());
+        assert!(mutation(SyncEntityType::Invoice, SyncOp::Create, serde_json::json!({})).to_write().is_err());
+        assert!(mutation(SyncEntityType::Payment, SyncOp::Delete, serde_json::Value::Null).to_write().is_err());
+    }
+}
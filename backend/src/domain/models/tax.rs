@@ -1,13 +1,14 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use validator::Validate;
 
 /// Tax Setting Model
 /// Menyimpan tax rate manual per organization
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 pub struct TaxSetting {
     pub id: Uuid,
     pub organization_id: Uuid,
@@ -20,7 +21,7 @@ pub struct TaxSetting {
 }
 
 /// Create Tax Setting Request
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateTaxSetting {
     #[validate(length(min = 1, max = 100, message = "Label is required"))]
     pub label: String,
@@ -32,7 +33,7 @@ pub struct CreateTaxSetting {
 }
 
 /// Update Tax Setting Request
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateTaxSetting {
     #[validate(length(min = 1, max = 100))]
     pub label: Option<String>,
@@ -45,7 +46,7 @@ pub struct UpdateTaxSetting {
 }
 
 /// Tax Calculation Result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaxCalculation {
     pub subtotal: f64,
     pub tax_rate: f64,
@@ -55,7 +56,7 @@ pub struct TaxCalculation {
 }
 
 /// Tax Summary for Report
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaxSummary {
     pub period_start: String,
     pub period_end: String,
@@ -65,7 +66,7 @@ pub struct TaxSummary {
     pub tax_breakdown: Vec<TaxBreakdownItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaxBreakdownItem {
     pub label: String,
     pub rate: f64,
@@ -0,0 +1,277 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::cell::RefCell;
+
+use crate::domain::models::{
+    parse_hex_color, render_placeholders, EmailSignature, InvoiceSettings, MAX_FOOTER_TEXT_LENGTH, TEMPLATE_VARIABLES,
+};
+
+pub const TEMPLATE_BUNDLE_FORMAT: &str = "flashbill.template-bundle";
+pub const TEMPLATE_BUNDLE_VERSION: u32 = 1;
+
+pub const MAX_BUNDLE_NAME_LENGTH: usize = 100;
+pub const MAX_BUNDLE_DESCRIPTION_LENGTH: usize = 500;
+pub const MAX_BUNDLE_TEXT_LENGTH: usize = 5000;
+pub const MAX_TEMPLATE_NAME_LENGTH: usize = 50;
+
+/// A shareable invoice look: PDF template and branding, terms and notes, and the email
+/// signature. Account-specific settings (logo, late fee, SLA targets) stay out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct TemplateBundle {
+    pub format: String,
+    pub version: u32,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    pub invoice: BundleInvoiceTemplate,
+    #[serde(default)]
+    pub email: BundleEmailTemplates,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BundleInvoiceTemplate {
+    pub template: String,
+    #[serde(default)]
+    pub terms: String,
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub paid_stamp: bool,
+    #[serde(default)]
+    pub accent_color: Option<String>,
+    #[serde(default)]
+    pub footer_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BundleEmailTemplates {
+    /// Imported as the business default signature
+    pub signature: Option<EmailSignature>,
+}
+
+impl TemplateBundle {
+    pub fn new(settings: &InvoiceSettings, signature: Option<EmailSignature>, exported_at: DateTime<Utc>) -> Self {
+        Self {
+            format: TEMPLATE_BUNDLE_FORMAT.to_string(),
+            version: TEMPLATE_BUNDLE_VERSION,
+            name: None,
+            description: None,
+            exported_at: Some(exported_at),
+            invoice: BundleInvoiceTemplate {
+                template: settings.template.clone(),
+                terms: settings.terms.clone(),
+                notes: settings.notes.clone(),
+                paid_stamp: settings.paid_stamp,
+                accent_color: settings.accent_color.clone(),
+                footer_text: settings.footer_text.clone(),
+            },
+            email: BundleEmailTemplates { signature },
+        }
+    }
+
+    /// Checks the format and cleans up text from an untrusted bundle: control
+    /// characters are removed, lengths are capped, and terms and notes may only
+    /// use the supported `{{variables}}`
+    pub fn sanitize(self) -> Result<TemplateBundle, String> {
+        if self.format != TEMPLATE_BUNDLE_FORMAT {
+            return Err(format!("Not a template bundle: format must be {}", TEMPLATE_BUNDLE_FORMAT));
+        }
+        if self.version == 0 || self.version > TEMPLATE_BUNDLE_VERSION {
+            return Err(format!(
+                "Unsupported template bundle version {}; this server reads up to version {}",
+                self.version, TEMPLATE_BUNDLE_VERSION
+            ));
+        }
+
+        let template = clean_line(&self.invoice.template);
+        let valid_template = !template.is_empty()
+            && template.len() <= MAX_TEMPLATE_NAME_LENGTH
+            && template.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_template {
+            return Err(format!(
+                "Invalid invoice template name: use up to {} letters, digits, '-' or '_'",
+                MAX_TEMPLATE_NAME_LENGTH
+            ));
+        }
+
+        let accent_color = self.invoice.accent_color.map(|color| clean_line(&color)).filter(|color| !color.is_empty());
+        if accent_color.as_deref().is_some_and(|color| parse_hex_color(color).is_none()) {
+            return Err("Invalid invoice accent colour: use a hex colour like #2563EB".to_string());
+        }
+
+        let signature = self.email.signature.map(EmailSignature::normalize).transpose()?;
+
+        Ok(TemplateBundle {
+            format: self.format,
+            version: TEMPLATE_BUNDLE_VERSION,
+            name: optional_line("name", self.name, MAX_BUNDLE_NAME_LENGTH)?,
+            description: optional_line("description", self.description, MAX_BUNDLE_DESCRIPTION_LENGTH)?,
+            exported_at: self.exported_at,
+            invoice: BundleInvoiceTemplate {
+                template,
+                terms: clean_text("terms", &self.invoice.terms)?,
+                notes: clean_text("notes", &self.invoice.notes)?,
+                paid_stamp: self.invoice.paid_stamp,
+                accent_color,
+                footer_text: optional_line("footer text", self.invoice.footer_text, MAX_FOOTER_TEXT_LENGTH)?,
+            },
+            email: BundleEmailTemplates { signature },
+        })
+    }
+
+    /// `settings` with the bundle's template, terms, notes and paid stamp; the
+    /// accent colour and footer only when the bundle sets them
+    pub fn apply_to(&self, settings: InvoiceSettings) -> InvoiceSettings {
+        InvoiceSettings {
+            template: self.invoice.template.clone(),
+            terms: self.invoice.terms.clone(),
+            notes: self.invoice.notes.clone(),
+            paid_stamp: self.invoice.paid_stamp,
+            accent_color: self.invoice.accent_color.clone().or(settings.accent_color),
+            footer_text: self.invoice.footer_text.clone().or(settings.footer_text),
+            ..settings
+        }
+    }
+}
+
+fn clean_line(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect::<String>().trim().to_string()
+}
+
+fn optional_line(field: &str, text: Option<String>, max: usize) -> Result<Option<String>, String> {
+    let Some(text) = text.map(|text| clean_line(&text)) else {
+        return Ok(None);
+    };
+    if text.chars().count() > max {
+        return Err(format!("Bundle {} must be at most {} characters", field, max));
+    }
+    Ok((!text.is_empty()).then_some(text))
+}
+
+/// Multi-line text with only newlines and tabs kept among control characters
+fn clean_text(field: &str, text: &str) -> Result<String, String> {
+    let text: String = text
+        .replace("\r\n", "\n")
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+        .collect();
+    let text = text.trim().to_string();
+
+    if text.chars().count() > MAX_BUNDLE_TEXT_LENGTH {
+        return Err(format!("Invoice {} must be at most {} characters", field, MAX_BUNDLE_TEXT_LENGTH));
+    }
+
+    let unknown = RefCell::new(Vec::new());
+    render_placeholders(&text, |name| {
+        if !TEMPLATE_VARIABLES.contains(&name) {
+            unknown.borrow_mut().push(name.to_string());
+        }
+        None
+    });
+    let unknown = unknown.into_inner();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Invoice {} uses unknown variables: {}; supported are {}",
+            field,
+            unknown.join(", "),
+            TEMPLATE_VARIABLES.join(", ")
+        ));
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle() -> TemplateBundle {
+        let settings = InvoiceSettings {
+            template: "modern".to_string(),
+            terms: "Payment due within {{due_days}} days".to_string(),
+            notes: "Thank you!".to_string(),
+            paid_stamp: true,
+            accent_color: Some("#2563EB".to_string()),
+            ..Default::default()
+        };
+        TemplateBundle::new(&settings, None, Utc::now())
+    }
+
+    #[test]
+    fn round_trips_and_keeps_account_settings() {
+        let exported = serde_json::to_value(bundle()).unwrap();
+        assert_eq!(exported["format"], TEMPLATE_BUNDLE_FORMAT);
+
+        let imported: TemplateBundle = serde_json::from_value(exported).unwrap();
+        let imported = imported.sanitize().unwrap();
+
+        let mine = InvoiceSettings {
+            template: "classic".to_string(),
+            logo_url: Some("https://cdn.example.com/logo.png".to_string()),
+            late_fee_rate: Some(1.5),
+            footer_text: Some("Acme Ltd, registered in Springfield".to_string()),
+            ..Default::default()
+        };
+        let applied = imported.apply_to(mine);
+        assert_eq!(applied.template, "modern");
+        assert_eq!(applied.terms, "Payment due within {{due_days}} days");
+        assert!(applied.paid_stamp);
+        assert_eq!(applied.logo_url.as_deref(), Some("https://cdn.example.com/logo.png"));
+        assert_eq!(applied.late_fee_rate, Some(1.5));
+        assert_eq!(applied.accent_color.as_deref(), Some("#2563EB"));
+        // The bundle has no footer, so the account's own is kept
+        assert_eq!(applied.footer_text.as_deref(), Some("Acme Ltd, registered in Springfield"));
+    }
+
+    #[test]
+    fn sanitize_strips_control_characters() {
+        let mut bundle = bundle();
+        bundle.name = Some("  Clean\u{0007} look ".to_string());
+        bundle.invoice.notes = "Line one\r\nLine\u{0000} two\t!".to_string();
+
+        let bundle = bundle.sanitize().unwrap();
+        assert_eq!(bundle.name.as_deref(), Some("Clean look"));
+        assert_eq!(bundle.invoice.notes, "Line one\nLine two\t!");
+    }
+
+    #[test]
+    fn sanitize_rejects_bad_bundles() {
+        let mut wrong_format = bundle();
+        wrong_format.format = "something-else".to_string();
+        assert!(wrong_format.sanitize().is_err());
+
+        let mut newer = bundle();
+        newer.version = TEMPLATE_BUNDLE_VERSION + 1;
+        assert!(newer.sanitize().is_err());
+
+        let mut bad_template = bundle();
+        bad_template.invoice.template = "../../etc/passwd".to_string();
+        assert!(bad_template.sanitize().is_err());
+
+        let mut bad_accent = bundle();
+        bad_accent.invoice.accent_color = Some("url(javascript:alert(1))".to_string());
+        assert!(bad_accent.sanitize().is_err());
+
+        let mut unknown_variable = bundle();
+        unknown_variable.invoice.terms = "Pay {{bank_account}} by {{due_date}}".to_string();
+        let err = unknown_variable.sanitize().unwrap_err();
+        assert!(err.contains("bank_account"));
+        assert!(!err.contains("unknown variables: due_date"));
+
+        let mut long_notes = bundle();
+        long_notes.invoice.notes = "x".repeat(MAX_BUNDLE_TEXT_LENGTH + 1);
+        assert!(long_notes.sanitize().is_err());
+
+        let mut bad_signature = bundle();
+        bad_signature.email.signature = Some(EmailSignature {
+            links: vec![crate::domain::models::SignatureLink {
+                label: "Site".to_string(),
+                url: "javascript:alert(1)".to_string(),
+            }],
+            ..Default::default()
+        });
+        assert!(bad_signature.sanitize().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::domain::models::PageRequest;
+
+/// Longest a single entry can run; longer work is split over several entries
+pub const MAX_ENTRY_MINUTES: i64 = 24 * 60;
+pub const MAX_ENTRY_DESCRIPTION_LENGTH: usize = 500;
+
+/// Time worked for a client, either timed with start/stop or entered afterwards
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimeEntry {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    /// One of the client's projects
+    pub project_id: Option<Uuid>,
+    pub description: String,
+    pub started_at: DateTime<Utc>,
+    /// None while the timer is running
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Whole minutes worked; None while the timer is running
+    pub duration_minutes: Option<i64>,
+    /// Overrides the project's and the client's hourly rate
+    pub hourly_rate: Option<Decimal>,
+    /// Non-billable time is tracked but never invoiced
+    pub billable: bool,
+    /// Invoice the entry was billed on
+    pub invoice_id: Option<Uuid>,
+    pub billed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TimeEntry {
+    pub fn is_running(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    pub fn is_billed(&self) -> bool {
+        self.invoice_id.is_some()
+    }
+
+    /// Hours to invoice, to the hundredth
+    pub fn billable_hours(&self) -> Decimal {
+        let minutes = self.duration_minutes.unwrap_or_default();
+        (Decimal::from(minutes) / Decimal::from(60)).round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+    }
+}
+
+/// A manual entry: the start plus either the end or the minutes worked
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTimeEntry {
+    pub client_id: Uuid,
+    /// One of the client's active projects
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    pub description: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_minutes: Option<i64>,
+    pub hourly_rate: Option<Decimal>,
+    pub billable: Option<bool>,
+}
+
+/// Body of `POST /time-entries/start`; the timer starts now
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StartTimer {
+    pub client_id: Uuid,
+    /// One of the client's active projects
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    pub description: String,
+    pub hourly_rate: Option<Decimal>,
+    pub billable: Option<bool>,
+}
+
+/// Fields left out are unchanged. Billed entries can't be edited. Moving an
+/// entry to another client takes it off its project.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UpdateTimeEntry {
+    pub client_id: Option<Uuid>,
+    pub description: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_minutes: Option<i64>,
+    pub hourly_rate: Option<Decimal>,
+    pub billable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeEntryListFilter {
+    pub client_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    /// Entries started on or after this day
+    pub from: Option<NaiveDate>,
+    /// Entries started on or before this day
+    pub to: Option<NaiveDate>,
+    /// true for billed entries only, false for unbilled ones
+    pub billed: Option<bool>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// `next_cursor` of the previous page, to page by cursor instead of number
+    pub cursor: Option<String>,
+}
+
+impl TimeEntryListFilter {
+    pub fn page_request(&self) -> Result<PageRequest, String> {
+        PageRequest::from_params(self.page, self.per_page, self.cursor.as_deref(), None, None)
+    }
+}
+
+/// Body of `POST /invoices/from-time`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InvoiceFromTime {
+    pub client_id: Uuid,
+    /// Entries to bill; by default every stopped, billable, unbilled entry of the client
+    #[serde(default)]
+    pub entry_ids: Option<Vec<Uuid>>,
+    /// Only bill time on this project; the invoice is tagged with it. Without
+    /// one, the invoice is tagged with the project all the entries share.
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// Only bill entries started on or before this day
+    pub until: Option<NaiveDate>,
+    /// Defaults to today
+    pub issue_date: Option<NaiveDate>,
+    /// Defaults to the issue date plus the client's payment terms
+    pub due_date: Option<NaiveDate>,
+    pub notes: Option<String>,
+    pub terms: Option<String>,
+}
+
+/// Trimmed description, or why it can't be used
+pub fn normalize_entry_description(description: &str) -> Result<String, String> {
+    let description = description.trim();
+    if description.is_empty() {
+        return Err("Description is required".to_string());
+    }
+    if description.chars().count() > MAX_ENTRY_DESCRIPTION_LENGTH {
+        return Err(format!("Description must be at most {} characters", MAX_ENTRY_DESCRIPTION_LENGTH));
+    }
+    Ok(description.to_string())
+}
+
+/// End of a manual entry from either its end or its length in minutes. When both
+/// are given they must agree.
+pub fn entry_end(
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    duration_minutes: Option<i64>,
+) -> Result<DateTime<Utc>, String> {
+    let ended_at = match (ended_at, duration_minutes) {
+        (Some(ended_at), Some(minutes)) if (ended_at - started_at).num_minutes() != minutes => {
+            return Err("ended_at and duration_minutes disagree".to_string());
+        }
+        (Some(ended_at), _) => ended_at,
+        (None, Some(minutes)) => started_at + Duration::minutes(minutes),
+        (None, None) => return Err("ended_at or duration_minutes is required".to_string()),
+    };
+    validate_entry_span(started_at, ended_at)?;
+    Ok(ended_at)
+}
+
+/// An entry ends after it starts and lasts at most [`MAX_ENTRY_MINUTES`]
+pub fn validate_entry_span(started_at: DateTime<Utc>, ended_at: DateTime<Utc>) -> Result<(), String> {
+    if ended_at <= started_at {
+        return Err("An entry must end after it starts".to_string());
+    }
+    if (ended_at - started_at).num_minutes() > MAX_ENTRY_MINUTES {
+        return Err(format!("An entry can last at most {} hours", MAX_ENTRY_MINUTES / 60));
+    }
+    Ok(())
+}
+
+/// A rate, if given, is positive
+pub fn validate_hourly_rate(rate: Option<Decimal>) -> Result<(), String> {
+    match rate {
+        Some(rate) if rate <= Decimal::ZERO => Err("Hourly rate must be positive".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 3, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn manual_entries_end_from_either_field() {
+        assert_eq!(entry_end(at(9, 0), Some(at(10, 30)), None).unwrap(), at(10, 30));
+        assert_eq!(entry_end(at(9, 0), None, Some(90)).unwrap(), at(10, 30));
+        assert_eq!(entry_end(at(9, 0), Some(at(10, 30)), Some(90)).unwrap(), at(10, 30));
+
+        assert!(entry_end(at(9, 0), Some(at(10, 30)), Some(60)).is_err());
+        assert!(entry_end(at(9, 0), None, None).is_err());
+        assert!(entry_end(at(9, 0), Some(at(9, 0)), None).is_err());
+        assert!(entry_end(at(9, 0), None, Some(MAX_ENTRY_MINUTES + 1)).is_err());
+    }
+
+    #[test]
+    fn billable_hours_round_to_the_hundredth() {
+        let mut entry = TimeEntry {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            project_id: None,
+            description: "Design review".to_string(),
+            started_at: at(9, 0),
+            ended_at: Some(at(9, 50)),
+            duration_minutes: Some(50),
+            hourly_rate: None,
+            billable: true,
+            invoice_id: None,
+            billed_at: None,
+            created_at: at(9, 0),
+            updated_at: at(9, 50),
+        };
+        assert_eq!(entry.billable_hours(), Decimal::new(83, 2));
+
+        entry.duration_minutes = Some(90);
+        assert_eq!(entry.billable_hours(), Decimal::new(150, 2));
+    }
+
+    #[test]
+    fn validates_descriptions_and_rates() {
+        assert_eq!(normalize_entry_description("  Sprint planning ").unwrap(), "Sprint planning");
+        assert!(normalize_entry_description(" ").is_err());
+        assert!(normalize_entry_description(&"x".repeat(MAX_ENTRY_DESCRIPTION_LENGTH + 1)).is_err());
+
+        assert!(validate_hourly_rate(Some(Decimal::from(120))).is_ok());
+        assert!(validate_hourly_rate(None).is_ok());
+        assert!(validate_hourly_rate(Some(Decimal::ZERO)).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Most buckets one series may return: a year of days
+pub const MAX_TIMESERIES_POINTS: usize = 366;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeseriesMetric {
+    /// Completed payments received in the bucket
+    Revenue,
+    /// Expenses incurred in the bucket
+    Expenses,
+    /// Balance owed by clients at the end of the bucket
+    Outstanding,
+}
+
+impl TimeseriesMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeseriesMetric::Revenue => "revenue",
+            TimeseriesMetric::Expenses => "expenses",
+            TimeseriesMetric::Outstanding => "outstanding",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "revenue" => Some(TimeseriesMetric::Revenue),
+            "expenses" => Some(TimeseriesMetric::Expenses),
+            "outstanding" => Some(TimeseriesMetric::Outstanding),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeseriesInterval {
+    Day,
+    /// Weeks start on Monday
+    Week,
+    #[default]
+    Month,
+}
+
+impl TimeseriesInterval {
+    /// Also the Postgres `date_trunc` field
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeseriesInterval::Day => "day",
+            TimeseriesInterval::Week => "week",
+            TimeseriesInterval::Month => "month",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "day" => Some(TimeseriesInterval::Day),
+            "week" => Some(TimeseriesInterval::Week),
+            "month" => Some(TimeseriesInterval::Month),
+            _ => None,
+        }
+    }
+
+    /// Start of the bucket containing `date`
+    pub fn truncate(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            TimeseriesInterval::Day => date,
+            TimeseriesInterval::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            TimeseriesInterval::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(&self, bucket_start: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            TimeseriesInterval::Day => bucket_start.succ_opt(),
+            TimeseriesInterval::Week => bucket_start.checked_add_signed(Duration::days(7)),
+            TimeseriesInterval::Month => bucket_start.checked_add_months(Months::new(1)),
+        }
+    }
+
+    /// Buckets covering `start_date..=end_date`, as the SQL series generates them
+    pub fn bucket_starts(&self, start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+        let mut starts = Vec::new();
+        let mut bucket = Some(self.truncate(start_date));
+        while let Some(start) = bucket.filter(|start| *start <= end_date) {
+            starts.push(start);
+            bucket = self.next(start);
+        }
+        starts
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+pub struct TimeseriesPoint {
+    pub bucket_start: NaiveDate,
+    /// Last day of the bucket, capped at the series end date
+    pub bucket_end: NaiveDate,
+    pub value: f64,
+    /// Payments, expenses, or invoice/payment/credit movements in the bucket
+    pub count: i64,
+}
+
+/// One metric bucketed over a date range. Every bucket is present, with zero
+/// for empty ones; the first may start before `start_date` but only counts
+/// activity from it.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Timeseries {
+    pub metric: TimeseriesMetric,
+    pub interval: TimeseriesInterval,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub points: Vec<TimeseriesPoint>,
+}
+
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeseriesQuery {
+    /// revenue, expenses or outstanding
+    pub metric: String,
+    /// day, week or month (default)
+    pub interval: Option<String>,
+    #[serde(alias = "start_date")]
+    pub start: NaiveDate,
+    #[serde(alias = "end_date")]
+    pub end: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_bucket_starts_align_to_interval() {
+        assert_eq!(
+            TimeseriesInterval::Month.bucket_starts(date("2025-01-15"), date("2025-03-01")),
+            vec![date("2025-01-01"), date("2025-02-01"), date("2025-03-01")]
+        );
+        // 2025-03-05 is a Wednesday
+        assert_eq!(
+            TimeseriesInterval::Week.bucket_starts(date("2025-03-05"), date("2025-03-17")),
+            vec![date("2025-03-03"), date("2025-03-10"), date("2025-03-17")]
+        );
+        assert_eq!(TimeseriesInterval::Day.bucket_starts(date("2025-03-05"), date("2025-03-05")).len(), 1);
+        assert!(TimeseriesInterval::Day.bucket_starts(date("2025-03-05"), date("2025-03-04")).is_empty());
+    }
+
+    #[test]
+    fn test_a_year_of_days_fits_the_limit() {
+        let days = TimeseriesInterval::Day.bucket_starts(date("2024-01-01"), date("2024-12-31"));
+        assert_eq!(days.len(), MAX_TIMESERIES_POINTS);
+    }
+}
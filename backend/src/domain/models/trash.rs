@@ -0,0 +1,61 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Days deleted invoices and clients stay in the trash when TRASH_RETENTION_DAYS isn't set
+pub const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+
+/// A draft invoice in the trash
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrashedInvoice {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub total_amount: Decimal,
+    pub currency: String,
+    pub deleted_at: DateTime<Utc>,
+    /// When the purge job removes it for good
+    pub purge_at: DateTime<Utc>,
+}
+
+/// A client in the trash
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrashedClient {
+    pub id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub company_name: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    /// When the purge job removes it for good; `None` while it has issued invoices,
+    /// which are kept for the books
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+/// Response for `GET /trash`, most recently deleted first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Trash {
+    pub retention_days: i64,
+    pub invoices: Vec<TrashedInvoice>,
+    pub clients: Vec<TrashedClient>,
+}
+
+/// When something deleted at `deleted_at` is due to be purged
+pub fn purge_due(deleted_at: DateTime<Utc>, retention_days: i64) -> DateTime<Utc> {
+    deleted_at + Duration::days(retention_days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_purge_due_after_retention_period() {
+        let deleted_at = Utc.with_ymd_and_hms(2026, 1, 20, 9, 30, 0).unwrap();
+        assert_eq!(purge_due(deleted_at, 30), Utc.with_ymd_and_hms(2026, 2, 19, 9, 30, 0).unwrap());
+        assert_eq!(purge_due(deleted_at, 1), Utc.with_ymd_and_hms(2026, 1, 21, 9, 30, 0).unwrap());
+    }
+}
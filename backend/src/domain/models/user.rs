@@ -2,11 +2,15 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use sqlx::Type;
 use uuid::Uuid;
 use validator::Validate;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+use crate::domain::i18n::Locale;
+use crate::domain::models::{CreditAutoApply, PaymentQrFormat, SepaAccount};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "varchar")]
 pub enum SubscriptionTier {
     Free,
@@ -20,7 +24,7 @@ impl Default for SubscriptionTier {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type, ToSchema)]
 #[sqlx(type_name = "varchar")]
 pub enum SubscriptionStatus {
     Active,
@@ -35,7 +39,7 @@ impl Default for SubscriptionStatus {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct BusinessAddress {
     pub street: String,
     pub city: String,
@@ -44,13 +48,53 @@ pub struct BusinessAddress {
     pub country: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NotificationSettings {
     pub email_payment_received: bool,
     pub email_invoice_paid: bool,
     pub email_payment_reminder: bool,
     pub push_payment_received: bool,
     pub push_overdue: bool,
+    /// Email each client a statement of the prior month on the 1st
+    #[serde(default)]
+    pub email_monthly_statements: bool,
+    /// Also send payment reminders to the client's WhatsApp number
+    #[serde(default)]
+    pub whatsapp_payment_reminder: bool,
+    /// Days past the due date on which clients are reminded automatically
+    /// (0 = on the due date)
+    #[serde(default = "default_reminder_days")]
+    pub reminder_days: Vec<u32>,
+    /// Email new invoice discussion messages to the other side: the client for
+    /// yours, you for theirs
+    #[serde(default = "default_true")]
+    pub email_discussion_messages: bool,
+    /// Also send them to the other side's WhatsApp number
+    #[serde(default)]
+    pub whatsapp_discussion_messages: bool,
+}
+
+/// Longest reminder schedule a user can configure
+pub const MAX_REMINDER_DAYS: usize = 10;
+
+fn default_reminder_days() -> Vec<u32> {
+    vec![1, 7, 14, 30]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl NotificationSettings {
+    /// Number of scheduled reminders due for an invoice `days_overdue` days past
+    /// its due date. An invoice that has had fewer is reminded once and caught up,
+    /// so a missed run never sends a burst of reminders.
+    pub fn reminders_due(&self, days_overdue: i64) -> i32 {
+        self.reminder_days
+            .iter()
+            .filter(|days| days_overdue >= i64::from(**days))
+            .count() as i32
+    }
 }
 
 impl Default for NotificationSettings {
@@ -61,11 +105,16 @@ impl Default for NotificationSettings {
             email_payment_reminder: true,
             push_payment_received: true,
             push_overdue: true,
+            email_monthly_statements: false,
+            whatsapp_payment_reminder: false,
+            reminder_days: default_reminder_days(),
+            email_discussion_messages: true,
+            whatsapp_discussion_messages: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct TaxSettings {
     pub state_code: String,
     pub tax_rate: f64,
@@ -73,12 +122,55 @@ pub struct TaxSettings {
     pub tax_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 pub struct InvoiceSettings {
     pub template: String,
     pub logo_url: Option<String>,
     pub terms: String,
     pub notes: String,
+    /// Monthly late fee in percent, available to terms as {{late_fee_rate}}
+    #[serde(default)]
+    pub late_fee_rate: Option<f64>,
+    /// Stamp "PAID" across the PDF once an invoice is fully paid
+    #[serde(default)]
+    pub paid_stamp: bool,
+    /// Internal target for a client to first open a sent invoice, in hours
+    #[serde(default)]
+    pub sla_view_hours: Option<f64>,
+    /// Internal target for a sent invoice to be paid, in days
+    #[serde(default)]
+    pub sla_payment_days: Option<f64>,
+    /// PDF accent colour as `#RRGGBB`; the template's own colour when unset
+    #[serde(default)]
+    pub accent_color: Option<String>,
+    /// Printed at the bottom of invoice and report PDFs
+    #[serde(default)]
+    pub footer_text: Option<String>,
+    /// When a client's retainer or other credit is drawn on for their invoices
+    #[serde(default)]
+    pub credit_auto_apply: CreditAutoApply,
+    /// What the QR code on invoice PDFs holds
+    #[serde(default)]
+    pub payment_qr: PaymentQrFormat,
+    /// The account `sepa` QR codes pay into
+    #[serde(default)]
+    pub sepa_account: Option<SepaAccount>,
+    /// The static QRIS code from the seller's acquirer, for `qris` QR codes
+    #[serde(default)]
+    pub qris_payload: Option<String>,
+}
+
+/// Longest PDF footer line
+pub const MAX_FOOTER_TEXT_LENGTH: usize = 200;
+
+/// `#RRGGBB` (or `RRGGBB`) as red, green and blue
+pub fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#').unwrap_or(color.trim());
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
@@ -110,6 +202,8 @@ pub struct User {
     pub business_address: Option<BusinessAddress>,
     pub tax_settings: Option<TaxSettings>,
     pub currency: String,
+    /// Language and formats of invoices, PDFs and emails; a client can override it
+    pub locale: Locale,
 
     // Settings
     pub notification_settings: NotificationSettings,
@@ -143,6 +237,7 @@ pub struct UpdateUser {
     pub tax_settings: Option<TaxSettings>,
     pub notification_settings: Option<NotificationSettings>,
     pub invoice_settings: Option<InvoiceSettings>,
+    pub locale: Option<Locale>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -189,3 +284,48 @@ pub struct AuthResponse {
     pub expires_in: i64,
     pub user: AuthUser,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reminders_due_follow_schedule() {
+        let settings = NotificationSettings::default();
+        assert_eq!(settings.reminders_due(-3), 0);
+        assert_eq!(settings.reminders_due(0), 0);
+        assert_eq!(settings.reminders_due(1), 1);
+        assert_eq!(settings.reminders_due(6), 1);
+        assert_eq!(settings.reminders_due(7), 2);
+        // A long-overdue invoice is caught up in one step
+        assert_eq!(settings.reminders_due(45), 4);
+
+        let on_due_date = NotificationSettings { reminder_days: vec![0], ..Default::default() };
+        assert_eq!(on_due_date.reminders_due(0), 1);
+    }
+
+    #[test]
+    fn test_notification_settings_default_missing_fields() {
+        let settings: NotificationSettings = serde_json::from_value(serde_json::json!({
+            "email_payment_received": true,
+            "email_invoice_paid": true,
+            "email_payment_reminder": false,
+            "push_payment_received": true,
+            "push_overdue": true,
+        }))
+        .unwrap();
+        assert!(!settings.whatsapp_payment_reminder);
+        assert_eq!(settings.reminder_days, vec![1, 7, 14, 30]);
+        assert!(settings.email_discussion_messages);
+        assert!(!settings.whatsapp_discussion_messages);
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#2563EB"), Some((0x25, 0x63, 0xeb)));
+        assert_eq!(parse_hex_color("ff0000"), Some((255, 0, 0)));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("#12345g"), None);
+        assert_eq!(parse_hex_color("#ééé"), None);
+    }
+}
@@ -2,6 +2,9 @@ use async_trait::async_trait;
 use uuid::Uuid;
 use chrono::NaiveDate;
 use serde::{Serialize, Deserialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::domain::models::{CashflowInputs, TimeseriesInterval, TimeseriesMetric, TimeseriesPoint};
 
 #[async_trait]
 pub trait ReportRepository: Send + Sync {
@@ -14,6 +17,7 @@ pub trait ReportRepository: Send + Sync {
         user_id: Uuid,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        filter: &ClientReportFilter,
     ) -> Result<IncomeReport, sqlx::Error>;
 
     /// Get expenses report for date range
@@ -33,34 +37,92 @@ pub trait ReportRepository: Send + Sync {
     ) -> Result<TaxReport, sqlx::Error>;
 
     /// Get aging report (accounts receivable aging)
-    async fn get_aging_report(&self, user_id: Uuid) -> Result<AgingReport, sqlx::Error>;
+    async fn get_aging_report(&self, user_id: Uuid, filter: &ClientReportFilter) -> Result<AgingReport, sqlx::Error>;
+
+    /// Record every user's aging buckets as of `snapshot_date`, replacing any earlier
+    /// snapshot for that day. Returns the number of users snapshotted.
+    async fn snapshot_aging(&self, snapshot_date: NaiveDate) -> Result<u64, sqlx::Error>;
+
+    /// The last snapshot of each month on or after `since`, oldest first
+    async fn get_aging_snapshots(&self, user_id: Uuid, since: NaiveDate) -> Result<Vec<AgingSnapshot>, sqlx::Error>;
+
+    /// Time from sending to first view and to payment for invoices sent in the date
+    /// range, overall and per client, measured against the account's SLA targets
+    async fn get_sla_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<SlaReport, sqlx::Error>;
+
+    /// VAT on invoices issued in the date range per member state and rate, with
+    /// reverse-charged sales on their own rows
+    async fn get_vat_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<VatReport, sqlx::Error>;
+
+    /// Open receivables, per-client payment delay, and invoices and expenses dated
+    /// on or after `since`, for the cash-flow forecast
+    async fn get_cashflow_inputs(&self, user_id: Uuid, since: NaiveDate) -> Result<CashflowInputs, sqlx::Error>;
+
+    /// One point per `interval` bucket between the dates, empty buckets included
+    async fn get_timeseries(
+        &self,
+        user_id: Uuid,
+        metric: TimeseriesMetric,
+        interval: TimeseriesInterval,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<TimeseriesPoint>, sqlx::Error>;
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Restricts a report to one client, optionally including its subsidiaries
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ClientReportFilter {
+    pub client_id: Option<Uuid>,
+    #[serde(default)]
+    pub include_subsidiaries: bool,
+}
+
+impl ClientReportFilter {
+    pub fn is_empty(&self) -> bool {
+        self.client_id.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OverviewStats {
     pub total_revenue: f64,
     pub total_outstanding: f64,
     pub paid_invoices: i64,
     pub overdue_invoices: i64,
     pub total_expenses: f64,
+    pub fx_gain_loss: f64,
     pub net_profit: f64,
+    /// Open invoices past an SLA target; absent until a target is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla_breaches: Option<SlaBreachSummary>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct IncomeReport {
     pub total_income: f64,
     pub by_month: Vec<IncomeByMonth>,
     pub by_client: Vec<IncomeByClient>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct IncomeByMonth {
     pub month: String,
     pub amount: f64,
     pub invoice_count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct IncomeByClient {
     pub client_id: Uuid,
     pub client_name: String,
@@ -68,39 +130,77 @@ pub struct IncomeByClient {
     pub invoice_count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExpensesReport {
     pub total_expenses: f64,
     pub by_category: Vec<ExpensesByCategory>,
     pub by_month: Vec<ExpensesByMonth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExpensesByCategory {
     pub category: String,
     pub amount: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExpensesByMonth {
     pub month: String,
     pub amount: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaxReport {
+    /// Output tax on paid invoices issued in the period
     pub total_tax_collected: f64,
+    /// Reclaimable input tax on deductible expenses incurred in the period
     pub total_tax_deductible: f64,
+    /// Output minus input tax; negative when a refund is due
+    #[serde(default)]
+    pub net_tax_payable: f64,
     pub by_state: Vec<TaxByState>,
+    /// Tax collected per tax and rate, e.g. GST 5% and PST 7% on their own lines
+    #[serde(default)]
+    pub by_tax: Vec<TaxByRate>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TaxByState {
     pub state_code: String,
     pub tax_amount: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaxByRate {
+    pub label: String,
+    /// 0 to 1; missing for invoices issued before per-tax breakdowns were kept
+    pub rate: Option<f64>,
+    pub compound: bool,
+    pub taxable_amount: f64,
+    pub tax_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VatReport {
+    /// Amounts stay in each invoice's currency, so a country can have a row per currency
+    pub by_country: Vec<VatByCountry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VatByCountry {
+    /// Member state the sales are reported under, e.g. FR
+    pub country: String,
+    /// 0 to 1; 0 for zero-rated and reverse-charged sales
+    pub rate: f64,
+    /// Supplies to VAT-registered businesses in another member state, for the EC sales list
+    pub reverse_charge: bool,
+    pub currency: String,
+    pub invoice_count: i64,
+    pub taxable_amount: f64,
+    pub vat_amount: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AgingReport {
     pub current: f64,
     pub one_to_thirty_days: f64,
@@ -108,3 +208,113 @@ pub struct AgingReport {
     pub sixty_one_to_ninety_days: f64,
     pub over_ninety_days: f64,
 }
+
+impl AgingReport {
+    pub fn total(&self) -> f64 {
+        self.current + self.overdue()
+    }
+
+    pub fn overdue(&self) -> f64 {
+        self.one_to_thirty_days + self.thirty_one_to_sixty_days + self.sixty_one_to_ninety_days + self.over_ninety_days
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgingSnapshot {
+    pub snapshot_date: NaiveDate,
+    #[serde(flatten)]
+    pub aging: AgingReport,
+}
+
+/// One point per month of receivables aging; the current month is as of today
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AgingTrend {
+    pub months: u32,
+    pub points: Vec<AgingTrendPoint>,
+    /// Change in the overdue share between the first and last point, in percentage
+    /// points. Negative means collections are improving.
+    pub overdue_share_change: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AgingTrendPoint {
+    pub month: String,
+    pub snapshot_date: NaiveDate,
+    #[serde(flatten)]
+    pub aging: AgingReport,
+    pub total_outstanding: f64,
+    /// Percentage of the outstanding balance that is past due
+    pub overdue_share: f64,
+}
+
+impl AgingTrendPoint {
+    pub fn from_snapshot(snapshot: AgingSnapshot) -> Self {
+        let total_outstanding = snapshot.aging.total();
+        let overdue_share = if total_outstanding > 0.0 {
+            (snapshot.aging.overdue() / total_outstanding * 10000.0).round() / 100.0
+        } else {
+            0.0
+        };
+
+        Self {
+            month: snapshot.snapshot_date.format("%Y-%m").to_string(),
+            snapshot_date: snapshot.snapshot_date,
+            aging: snapshot.aging,
+            total_outstanding,
+            overdue_share,
+        }
+    }
+}
+
+/// Internal response-time targets from the invoice settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SlaTargets {
+    pub view_hours: Option<f64>,
+    pub payment_days: Option<f64>,
+}
+
+impl SlaTargets {
+    pub fn is_empty(&self) -> bool {
+        self.view_hours.is_none() && self.payment_days.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SlaStats {
+    pub sent_invoices: i64,
+    pub viewed_invoices: i64,
+    pub paid_invoices: i64,
+    pub view_hours_p50: Option<f64>,
+    pub view_hours_p90: Option<f64>,
+    pub payment_days_p50: Option<f64>,
+    pub payment_days_p90: Option<f64>,
+    /// Viewed after the target, or still unviewed past it
+    pub view_breaches: i64,
+    /// Paid after the target, or still unpaid past it
+    pub payment_breaches: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ClientSlaStats {
+    pub client_id: Uuid,
+    pub client_name: String,
+    #[serde(flatten)]
+    pub stats: SlaStats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SlaReport {
+    pub targets: SlaTargets,
+    pub overall: SlaStats,
+    pub by_client: Vec<ClientSlaStats>,
+}
+
+/// Open invoices currently past a target, for the dashboard
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SlaBreachSummary {
+    pub targets: SlaTargets,
+    /// Sent and neither viewed nor paid within the view target
+    pub unviewed_invoices: i64,
+    /// Still unpaid past the payment target
+    pub unpaid_invoices: i64,
+}
@@ -0,0 +1,66 @@
+use std::future::Future;
+
+use uuid::Uuid;
+
+/// Header a request ID is read from and echoed back in, and sent on webhooks
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request ID that is kept rather than replaced
+pub const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The client's ID if it is safe to log and store as-is, otherwise a new one.
+/// Letters, digits and `.`, `_`, `:`, `-` are allowed, up to 128 characters.
+pub fn accept_or_generate(supplied: Option<&str>) -> String {
+    supplied
+        .map(str::trim)
+        .filter(|id| is_valid(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+fn is_valid(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b':' | b'-'))
+}
+
+/// Runs `future` with `request_id` as the current request ID, so errors, audit
+/// entries, queued emails and webhook events it produces carry it
+pub async fn scope<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// ID of the request being handled, or of the job that carries one
+pub fn current() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supplied_ids_are_kept_only_when_safe() {
+        assert_eq!(accept_or_generate(Some("req-42.a_b:c")), "req-42.a_b:c");
+        assert_eq!(accept_or_generate(Some("  abc  ")), "abc");
+
+        for unsafe_id in ["", "has space", "line\nbreak", "<script>", &"a".repeat(MAX_REQUEST_ID_LEN + 1)] {
+            let id = accept_or_generate(Some(unsafe_id));
+            assert_ne!(id, unsafe_id);
+            assert!(Uuid::parse_str(&id).is_ok());
+        }
+        assert!(Uuid::parse_str(&accept_or_generate(None)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_current_is_scoped() {
+        assert_eq!(current(), None);
+        let inside = scope("abc".to_string(), async { current() }).await;
+        assert_eq!(inside.as_deref(), Some("abc"));
+        assert_eq!(current(), None);
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::Duration;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{
+    AccountantAccess, AccountantInvite, AccountantLogin, AccountantSession, CreateAccountantAccess,
+    ACCOUNTANT_ACCESS_DEFAULT_DAYS, ACCOUNTANT_ACCESS_MAX_DAYS,
+};
+use crate::domain::services::{AuthService, EmailJobType, EmailQueueService, SharedClock};
+use crate::infrastructure::repositories::{AccountantAccessRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum AccountantError {
+    #[error("Accountant access not found")]
+    NotFound,
+
+    #[error("Invalid or expired access code")]
+    InvalidAccessCode,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Token error: {0}")]
+    Token(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for AccountantError {
+    fn from(err: sqlx::Error) -> Self {
+        AccountantError::DatabaseError(err.to_string())
+    }
+}
+
+/// Time-boxed, revocable read-only access for an external accountant
+pub struct AccountantService {
+    repo: AccountantAccessRepository,
+    user_repo: UserRepository,
+    auth_service: Arc<AuthService>,
+    email_queue: Arc<EmailQueueService>,
+    clock: SharedClock,
+}
+
+impl AccountantService {
+    pub fn new(
+        repo: AccountantAccessRepository,
+        user_repo: UserRepository,
+        auth_service: Arc<AuthService>,
+        email_queue: Arc<EmailQueueService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, user_repo, auth_service, email_queue, clock }
+    }
+
+    /// Grant access and email the accountant their access code
+    pub async fn invite(&self, user_id: Uuid, payload: CreateAccountantAccess) -> Result<AccountantInvite, AccountantError> {
+        let email = payload.email.trim().to_lowercase();
+        if !email.validate_email() {
+            return Err(AccountantError::Validation(format!("Invalid email address: {}", payload.email)));
+        }
+        let days = payload.expires_in_days.unwrap_or(ACCOUNTANT_ACCESS_DEFAULT_DAYS);
+        if !(1..=ACCOUNTANT_ACCESS_MAX_DAYS).contains(&days) {
+            return Err(AccountantError::Validation(format!(
+                "Access must last between 1 and {} days",
+                ACCOUNTANT_ACCESS_MAX_DAYS
+            )));
+        }
+        let name = payload.name.as_deref().map(str::trim).filter(|n| !n.is_empty());
+
+        let business = self.user_repo.find_by_id(user_id).await?.ok_or(AccountantError::NotFound)?;
+        let business_name = business.company_name.unwrap_or(business.email);
+
+        let access_code = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let expires_at = self.clock.now() + Duration::days(days);
+        let access = self.repo.create(user_id, &email, name, &access_code, expires_at).await?;
+
+        // The grant is usable even if the mail doesn't go out; the owner can pass the code on
+        self.email_queue
+            .enqueue_or_log(EmailJobType::SendAccountantInvite {
+                to_email: email.clone(),
+                to_name: name.unwrap_or(&email).to_string(),
+                business_name,
+                access_code: access_code.clone(),
+                expires_on: expires_at.format("%Y-%m-%d").to_string(),
+            })
+            .await;
+
+        Ok(AccountantInvite { access, access_code })
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<AccountantAccess>, AccountantError> {
+        Ok(self.repo.list(user_id).await?)
+    }
+
+    pub async fn revoke(&self, user_id: Uuid, access_id: Uuid) -> Result<AccountantAccess, AccountantError> {
+        self.repo.revoke(user_id, access_id).await?.ok_or(AccountantError::NotFound)
+    }
+
+    /// Exchange an access code for a token limited to the accountant role
+    pub async fn sign_in(&self, payload: AccountantLogin) -> Result<AccountantSession, AccountantError> {
+        let code = payload.access_code.trim();
+        if code.is_empty() {
+            return Err(AccountantError::InvalidAccessCode);
+        }
+
+        let (user_id, access) = self
+            .repo
+            .sign_in(code, self.clock.now())
+            .await?
+            .ok_or(AccountantError::InvalidAccessCode)?;
+        let business = self.user_repo.find_by_id(user_id).await?.ok_or(AccountantError::InvalidAccessCode)?;
+
+        let (access_token, expires_in) = self
+            .auth_service
+            .generate_accountant_token(user_id, &access.email, access.id, access.expires_at)
+            .map_err(|e| AccountantError::Token(e.to_string()))?;
+
+        Ok(AccountantSession {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in,
+            business_id: user_id,
+            business_name: business.company_name.unwrap_or(business.email),
+            access_expires_at: access.expires_at,
+        })
+    }
+
+    /// Whether a grant still allows requests; checked on every accountant request so
+    /// revocation takes effect immediately
+    pub async fn is_active(&self, user_id: Uuid, access_id: Uuid) -> Result<bool, AccountantError> {
+        Ok(self.repo.is_active(user_id, access_id, self.clock.now()).await?)
+    }
+}
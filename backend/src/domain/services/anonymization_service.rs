@@ -0,0 +1,342 @@
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::domain::models::invoice::InvoiceDetailResponse;
+use crate::domain::models::InvoiceDeposit;
+use crate::domain::models::invoice_totals::CurrencyRounding;
+
+/// Produces shareable copies of invoices for support debugging.
+///
+/// Every export uses a fresh random key, so pseudonyms are consistent within one
+/// export (the same client name always maps to the same pseudonym) but can't be
+/// linked across exports. Amounts are scaled by one per-export factor, which keeps
+/// the invoice's arithmetic relationships intact while hiding real figures.
+pub struct InvoiceAnonymizer {
+    key: [u8; 32],
+    amount_factor: Decimal,
+}
+
+impl Default for InvoiceAnonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InvoiceAnonymizer {
+    pub fn new() -> Self {
+        Self::with_key(rand::random())
+    }
+
+    pub fn with_key(key: [u8; 32]) -> Self {
+        // Factor in [0.50, 1.50], derived from the key
+        let factor_cents = u16::from_be_bytes([key[0], key[1]]) % 101;
+        Self {
+            key,
+            amount_factor: Decimal::new(50 + i64::from(factor_cents), 2),
+        }
+    }
+
+    fn digest(&self, kind: &str, value: &str) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.key);
+        hasher.update(kind.as_bytes());
+        hasher.update([0]);
+        hasher.update(value.as_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Stable pseudonym such as `client-3fa85f64`
+    pub fn pseudonym(&self, kind: &str, value: &str) -> String {
+        let digest = self.digest(kind, value);
+        let suffix: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}-{}", kind, suffix)
+    }
+
+    pub fn email(&self, value: &str) -> String {
+        format!("{}@example.invalid", self.pseudonym("email", &value.to_lowercase()))
+    }
+
+    pub fn phone(&self, value: &str) -> String {
+        let digest = self.digest("phone", value);
+        let digits: String = digest[..8].iter().map(|b| char::from(b'0' + b % 10)).collect();
+        format!("+000{}", digits)
+    }
+
+    pub fn uuid(&self, value: Uuid) -> Uuid {
+        let digest = self.digest("uuid", &value.to_string());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        uuid::Builder::from_random_bytes(bytes).into_uuid()
+    }
+
+    pub fn amount(&self, value: Decimal) -> Decimal {
+        CurrencyRounding::CENTS.round(value * self.amount_factor)
+    }
+
+    /// Replace every string in a JSON value (e.g. an address) with a pseudonym
+    fn scramble_strings(&self, kind: &str, value: Value) -> Value {
+        match value {
+            Value::String(s) => Value::String(self.pseudonym(kind, &s)),
+            Value::Array(values) => Value::Array(
+                values.into_iter().map(|v| self.scramble_strings(kind, v)).collect(),
+            ),
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| {
+                        let v = self.scramble_strings(kind, v);
+                        (k, v)
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Scale money fields inside a JSON breakdown, leaving rates and counts alone
+    fn scale_amounts(&self, value: Value) -> Value {
+        match value {
+            Value::Array(values) => {
+                Value::Array(values.into_iter().map(|v| self.scale_amounts(v)).collect())
+            }
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(k, v)| {
+                        let is_money = k.contains("amount") || k.contains("total");
+                        let v = match v {
+                            Value::Number(n) if is_money => n
+                                .as_f64()
+                                .and_then(Decimal::from_f64)
+                                .and_then(|n| serde_json::to_value(self.amount(n)).ok())
+                                .unwrap_or(Value::Null),
+                            other => self.scale_amounts(other),
+                        };
+                        (k, v)
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    /// Anonymized copy of an invoice. Dates, statuses, quantities and tax rates are kept
+    /// since they are usually what a reproduction depends on.
+    pub fn anonymize_invoice(&self, invoice: InvoiceDetailResponse) -> InvoiceDetailResponse {
+        let items = invoice
+            .items
+            .into_iter()
+            .map(|mut item| {
+                item.id = self.uuid(item.id);
+                item.description = self.pseudonym("item", &item.description);
+                item.unit_price = self.amount(item.unit_price);
+                item.tax_amount = self.amount(item.tax_amount);
+                item.total = self.amount(item.total);
+                for tax in &mut item.taxes {
+                    tax.tax_amount = self.amount(tax.tax_amount);
+                }
+                item.section = item.section.map(|s| self.pseudonym("section", &s));
+                item
+            })
+            .collect();
+
+        InvoiceDetailResponse {
+            id: self.uuid(invoice.id),
+            user_id: self.uuid(invoice.user_id),
+            client_id: self.uuid(invoice.client_id),
+            client_name: self.pseudonym("client", &invoice.client_name),
+            client_email: invoice.client_email.map(|e| self.email(&e)),
+            client_phone: invoice.client_phone.map(|p| self.phone(&p)),
+            client_address: invoice.client_address.map(|a| self.scramble_strings("address", a)),
+            subtotal: self.amount(invoice.subtotal),
+            tax_amount: self.amount(invoice.tax_amount),
+            discount_amount: self.amount(invoice.discount_amount),
+            total_amount: self.amount(invoice.total_amount),
+            amount_paid: self.amount(invoice.amount_paid),
+            balance_due: self.amount(invoice.balance_due),
+            items,
+            notes: invoice.notes.map(|n| self.pseudonym("note", &n)),
+            terms: invoice.terms.map(|t| self.pseudonym("terms", &t)),
+            tax_calculation: self.scale_amounts(invoice.tax_calculation),
+            tax_id: invoice.tax_id.map(|t| self.pseudonym("tax-id", &t)),
+            client_vat_number: invoice.client_vat_number.map(|v| self.pseudonym("vat", &v)),
+            // Links and tokens grant access to the real invoice
+            pdf_url: None,
+            receipt_image_url: None,
+            guest_payment_token: None,
+            min_payment_amount: invoice.min_payment_amount.map(|a| self.amount(a)),
+            consolidated_into_id: invoice.consolidated_into_id.map(|id| self.uuid(id)),
+            supersedes_id: invoice.supersedes_id.map(|id| self.uuid(id)),
+            superseded_by_id: invoice.superseded_by_id.map(|id| self.uuid(id)),
+            correction_reason: invoice.correction_reason.map(|r| self.pseudonym("correction", &r)),
+            project_id: invoice.project_id.map(|id| self.uuid(id)),
+            deposit: invoice.deposit.map(|deposit| InvoiceDeposit {
+                amount: self.amount(deposit.amount),
+                balance_due: self.amount(deposit.balance_due),
+                ..deposit
+            }),
+            ..invoice
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::invoice::{InvoiceItem, InvoiceStatus};
+    use crate::domain::models::{DepositStatus, LineTax};
+    use chrono::{NaiveDate, Utc};
+    use rust_decimal_macros::dec;
+
+    fn sample_invoice() -> InvoiceDetailResponse {
+        let item = |description: &str, unit_price: Decimal| InvoiceItem {
+            id: Uuid::new_v4(),
+            description: description.to_string(),
+            quantity: dec!(2),
+            unit_price,
+            tax_rate: dec!(0.1),
+            tax_amount: unit_price * dec!(0.2),
+            total: unit_price * dec!(2.2),
+            section: None,
+            taxes: Vec::new(),
+        };
+
+        InvoiceDetailResponse {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            invoice_number: "INV-2025-0042".to_string(),
+            status: InvoiceStatus::Sent,
+            client_id: Uuid::new_v4(),
+            client_name: "Jane Doe Consulting".to_string(),
+            client_email: Some("jane@doe.com".to_string()),
+            client_phone: Some("+62 812 5555 0101".to_string()),
+            client_address: Some(serde_json::json!({ "street": "1 Main St", "city": "Jakarta" })),
+            client_locale: None,
+            issue_date: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            due_date: NaiveDate::from_ymd_opt(2025, 1, 31).unwrap(),
+            subtotal: dec!(300),
+            tax_amount: dec!(30),
+            discount_amount: Decimal::ZERO,
+            total_amount: dec!(330),
+            amount_paid: dec!(110),
+            balance_due: dec!(220),
+            items: vec![item("Design for Jane Doe", dec!(100)), item("Hosting", dec!(50))],
+            notes: Some("Call Jane at home".to_string()),
+            terms: None,
+            tax_calculation: serde_json::json!({ "tax_rate": 0.1, "tax_amount": 30.0 }),
+            tax_included: false,
+            tax_label: Some("VAT".to_string()),
+            tax_id: Some("01.234.567.8-901.000".to_string()),
+            currency: "USD".to_string(),
+            exchange_rate: 1.0,
+            pdf_url: Some("https://files.example.com/inv.pdf".to_string()),
+            receipt_image_url: None,
+            sent_at: None,
+            viewed_at: None,
+            paid_at: None,
+            reminder_sent_count: 0,
+            last_reminder_sent: None,
+            notification_sent_at: None,
+            whatsapp_sent_at: None,
+            guest_payment_token: Some("guest_secret".to_string()),
+            allow_partial_payment: true,
+            min_payment_amount: Some(dec!(50)),
+            partial_payment_count: 1,
+            deposit: Some(InvoiceDeposit {
+                amount: dec!(99),
+                percent: Some(dec!(30)),
+                due_date: NaiveDate::from_ymd_opt(2025, 1, 10).unwrap(),
+                status: DepositStatus::Paid,
+                balance_due: dec!(17),
+                paid_at: None,
+            }),
+            reverse_charge: false,
+            vat_country: None,
+            client_vat_number: None,
+            consolidated_into_id: None,
+            supersedes_id: None,
+            superseded_by_id: None,
+            correction_reason: None,
+            expires_at: None,
+            expired_at: None,
+            project_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_no_pii_survives() {
+        let original = sample_invoice();
+        let anonymized = InvoiceAnonymizer::new().anonymize_invoice(original.clone());
+        let json = serde_json::to_string(&anonymized).unwrap();
+
+        for secret in ["Jane", "jane@doe.com", "5555", "Main St", "Jakarta", "guest_secret", "files.example.com", "567.8"] {
+            assert!(!json.contains(secret), "{} leaked into {}", secret, json);
+        }
+        assert_ne!(anonymized.id, original.id);
+        assert_ne!(anonymized.client_id, original.client_id);
+        assert_eq!(anonymized.invoice_number, original.invoice_number);
+        assert_eq!(anonymized.due_date, original.due_date);
+        assert_eq!(anonymized.items[0].quantity, original.items[0].quantity);
+    }
+
+    #[test]
+    fn test_scrambling_is_consistent_within_an_export() {
+        let anonymizer = InvoiceAnonymizer::with_key([7; 32]);
+        assert_eq!(anonymizer.pseudonym("client", "Acme"), anonymizer.pseudonym("client", "Acme"));
+        assert_ne!(anonymizer.pseudonym("client", "Acme"), anonymizer.pseudonym("client", "Globex"));
+        assert_eq!(anonymizer.email("Jane@Doe.com"), anonymizer.email("jane@doe.com"));
+
+        let id = Uuid::new_v4();
+        assert_eq!(anonymizer.uuid(id), anonymizer.uuid(id));
+
+        // A different export can't be correlated with this one
+        let other = InvoiceAnonymizer::with_key([8; 32]);
+        assert_ne!(anonymizer.pseudonym("client", "Acme"), other.pseudonym("client", "Acme"));
+    }
+
+    #[test]
+    fn test_amounts_keep_their_relationships() {
+        let anonymized = InvoiceAnonymizer::with_key([3; 32]).anonymize_invoice(sample_invoice());
+
+        let total = anonymized.subtotal + anonymized.tax_amount - anonymized.discount_amount;
+        assert!((total - anonymized.total_amount).abs() <= dec!(0.02));
+        assert!((anonymized.amount_paid + anonymized.balance_due - anonymized.total_amount).abs() <= dec!(0.02));
+        assert_eq!(anonymized.tax_calculation["tax_rate"], 0.1);
+        assert_eq!(anonymized.tax_calculation["tax_amount"], serde_json::to_value(anonymized.tax_amount).unwrap());
+    }
+
+    #[test]
+    fn test_deposit_amounts_are_scaled() {
+        let original = sample_invoice();
+        let anonymizer = InvoiceAnonymizer::with_key([3; 32]);
+        let anonymized = anonymizer.anonymize_invoice(original.clone());
+
+        let (before, after) = (original.deposit.unwrap(), anonymized.deposit.unwrap());
+        assert_ne!(after.amount, before.amount);
+        assert_ne!(after.balance_due, before.balance_due);
+        assert_eq!(after.amount, anonymizer.amount(before.amount));
+        assert_eq!(after.balance_due, anonymizer.amount(before.balance_due));
+        assert_eq!(after.percent, before.percent);
+    }
+
+    #[test]
+    fn test_line_taxes_are_scaled() {
+        let mut original = sample_invoice();
+        // GST, then PST charged on the price plus GST
+        original.items[0].taxes = vec![
+            LineTax { label: "GST".to_string(), rate: dec!(0.05), compound: false, tax_amount: dec!(10) },
+            LineTax { label: "PST".to_string(), rate: dec!(0.07), compound: true, tax_amount: dec!(14.70) },
+        ];
+        let anonymizer = InvoiceAnonymizer::with_key([3; 32]);
+        let anonymized = anonymizer.anonymize_invoice(original.clone());
+
+        for (before, after) in original.items[0].taxes.iter().zip(&anonymized.items[0].taxes) {
+            assert_ne!(after.tax_amount, before.tax_amount);
+            assert_eq!(after.tax_amount, anonymizer.amount(before.tax_amount));
+            assert_eq!((after.rate, after.compound), (before.rate, before.compound));
+        }
+    }
+}
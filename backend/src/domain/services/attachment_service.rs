@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use mime_guess::MimeGuess;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{attachment_name, Attachment, AttachmentParent};
+use crate::domain::services::email_service::AttachmentLink;
+use crate::domain::services::{FileError, FileService, SharedClock};
+use crate::infrastructure::repositories::{AttachmentRepository, NewAttachment};
+
+/// Attachment storage per user when ATTACHMENT_STORAGE_LIMIT isn't set: 100 MB
+const DEFAULT_STORAGE_LIMIT: i64 = 100 * 1024 * 1024;
+const DEFAULT_PUBLIC_URL: &str = "https://app.flashbill.com";
+
+#[derive(Debug, Error)]
+pub enum AttachmentError {
+    #[error("Attachment not found")]
+    NotFound,
+
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("Expense not found")]
+    ExpenseNotFound,
+
+    #[error("File is larger than the {0} byte limit")]
+    FileTooLarge(u64),
+
+    #[error("Attachment storage limit of {limit} bytes reached ({used} bytes used)")]
+    StorageLimitExceeded { used: i64, limit: i64 },
+
+    #[error("Files of type {0} can't be attached")]
+    InvalidFileType(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("File storage error: {0}")]
+    Storage(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for AttachmentError {
+    fn from(err: sqlx::Error) -> Self {
+        AttachmentError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<FileError> for AttachmentError {
+    fn from(err: FileError) -> Self {
+        match err {
+            FileError::NotFound(_) => AttachmentError::NotFound,
+            other => AttachmentError::Storage(other.to_string()),
+        }
+    }
+}
+
+/// The declared content type without parameters, or a guess from the file name
+/// when the client sent none or a generic one
+fn content_type(declared: Option<&str>, file_name: &str) -> String {
+    let declared = declared
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty() && value != "application/octet-stream");
+
+    declared.unwrap_or_else(|| {
+        MimeGuess::from_path(file_name)
+            .first()
+            .map(|m| m.to_string())
+            .unwrap_or_else(|| "application/octet-stream".to_string())
+    })
+}
+
+/// Files attached to invoices and expenses. Files go through FileService, so they
+/// share the per-file size limit (MAX_FILE_SIZE) and encryption of other uploads;
+/// ATTACHMENT_STORAGE_LIMIT caps the bytes each user can keep attached.
+pub struct AttachmentService {
+    repo: AttachmentRepository,
+    files: Arc<FileService>,
+    storage_limit: i64,
+    /// Base of the links put in invoice emails (PUBLIC_API_URL)
+    public_url: String,
+    clock: SharedClock,
+}
+
+impl AttachmentService {
+    pub fn new(repo: AttachmentRepository, files: Arc<FileService>, clock: SharedClock) -> Self {
+        let storage_limit = std::env::var("ATTACHMENT_STORAGE_LIMIT")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|limit| *limit > 0)
+            .unwrap_or(DEFAULT_STORAGE_LIMIT);
+        let public_url = std::env::var("PUBLIC_API_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| DEFAULT_PUBLIC_URL.to_string());
+
+        Self {
+            repo,
+            files,
+            storage_limit,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            clock,
+        }
+    }
+
+    async fn ensure_parent(&self, user_id: Uuid, parent: AttachmentParent) -> Result<(), AttachmentError> {
+        if self.repo.parent_exists(user_id, parent).await? {
+            return Ok(());
+        }
+        Err(match parent {
+            AttachmentParent::Invoice(_) => AttachmentError::InvoiceNotFound,
+            AttachmentParent::Expense(_) => AttachmentError::ExpenseNotFound,
+        })
+    }
+
+    pub async fn upload(
+        &self,
+        user_id: Uuid,
+        parent: AttachmentParent,
+        original_name: &str,
+        declared_type: Option<&str>,
+        data: &[u8],
+    ) -> Result<Attachment, AttachmentError> {
+        let file_name = attachment_name(original_name)
+            .ok_or_else(|| AttachmentError::Validation("File name is required".to_string()))?;
+        if data.is_empty() {
+            return Err(AttachmentError::Validation("File is empty".to_string()));
+        }
+        let max_file_size = self.files.max_file_size();
+        if data.len() as u64 > max_file_size {
+            return Err(AttachmentError::FileTooLarge(max_file_size));
+        }
+        let mime_type = content_type(declared_type, &file_name);
+        self.ensure_parent(user_id, parent).await?;
+
+        // Holds the user's lock until the record is in, so parallel uploads can't
+        // overshoot the limit together
+        let mut tx = self.repo.begin().await?;
+        let used = self.repo.lock_used_bytes(&mut tx, user_id).await?;
+        let file_size = data.len() as i64;
+        if used + file_size > self.storage_limit {
+            return Err(AttachmentError::StorageLimitExceeded { used, limit: self.storage_limit });
+        }
+
+        let stored = self
+            .files
+            .upload_document(user_id, data, &file_name, &mime_type)
+            .await
+            .map_err(|e| match e {
+                FileError::InvalidFileType => AttachmentError::InvalidFileType(mime_type.clone()),
+                other => other.into(),
+            })?;
+        let new_attachment = NewAttachment {
+            file_name: &file_name,
+            stored_name: &stored.file_name,
+            mime_type: &mime_type,
+            file_size,
+        };
+        let saved = match self.repo.insert(&mut tx, user_id, parent, new_attachment, self.clock.now()).await {
+            Ok(attachment) => tx.commit().await.map(|_| attachment),
+            Err(e) => Err(e),
+        };
+
+        saved.map_err(|e| {
+            self.remove_file(user_id, &stored.file_name);
+            e.into()
+        })
+    }
+
+    pub async fn list(&self, user_id: Uuid, parent: AttachmentParent) -> Result<Vec<Attachment>, AttachmentError> {
+        self.ensure_parent(user_id, parent).await?;
+        Ok(self.repo.list(user_id, parent).await?)
+    }
+
+    /// The attachment and its decrypted content
+    pub async fn download(
+        &self,
+        user_id: Uuid,
+        parent: AttachmentParent,
+        attachment_id: Uuid,
+    ) -> Result<(Attachment, Vec<u8>), AttachmentError> {
+        let attachment = self
+            .repo
+            .find(user_id, parent, attachment_id)
+            .await?
+            .ok_or(AttachmentError::NotFound)?;
+        let content = self.files.get_file(attachment.user_id, &attachment.stored_name).await?;
+
+        Ok((attachment, content))
+    }
+
+    /// Attachments shown to guests; only invoice attachments are shared
+    pub async fn list_for_invoice(&self, invoice_id: Uuid) -> Result<Vec<Attachment>, AttachmentError> {
+        Ok(self.repo.list_for_invoice(invoice_id).await?)
+    }
+
+    /// Download through a guest link
+    pub async fn guest_download(&self, invoice_id: Uuid, attachment_id: Uuid) -> Result<(Attachment, Vec<u8>), AttachmentError> {
+        let attachment = self
+            .repo
+            .find_for_invoice(invoice_id, attachment_id)
+            .await?
+            .ok_or(AttachmentError::NotFound)?;
+        let content = self.files.get_file(attachment.user_id, &attachment.stored_name).await?;
+
+        Ok((attachment, content))
+    }
+
+    pub async fn delete(&self, user_id: Uuid, parent: AttachmentParent, attachment_id: Uuid) -> Result<(), AttachmentError> {
+        let attachment = self
+            .repo
+            .delete(user_id, parent, attachment_id)
+            .await?
+            .ok_or(AttachmentError::NotFound)?;
+
+        self.remove_file(user_id, &attachment.stored_name);
+        Ok(())
+    }
+
+    /// Guest download links for an invoice's attachments, for the invoice email
+    pub async fn invoice_links(&self, invoice_id: Uuid, guest_token: &str) -> Result<Vec<AttachmentLink>, AttachmentError> {
+        let attachments = self.list_for_invoice(invoice_id).await?;
+
+        Ok(attachments
+            .into_iter()
+            .map(|attachment| AttachmentLink {
+                url: format!(
+                    "{}/api/v1/guest/invoice/{}/attachments/{}",
+                    self.public_url, guest_token, attachment.id
+                ),
+                name: attachment.file_name,
+            })
+            .collect())
+    }
+
+    /// The record is gone either way; a file left behind only wastes disk
+    fn remove_file(&self, user_id: Uuid, stored_name: &str) {
+        let files = self.files.clone();
+        let stored_name = stored_name.to_string();
+        tokio::spawn(async move {
+            if let Err(e) = files.delete_file(user_id, &stored_name).await {
+                tracing::warn!("Failed to remove attachment file {}: {}", stored_name, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type_prefers_declared_type() {
+        assert_eq!(content_type(Some("application/pdf"), "contract.bin"), "application/pdf");
+        assert_eq!(content_type(Some("Text/CSV; charset=utf-8"), "hours.csv"), "text/csv");
+        assert_eq!(content_type(Some("application/octet-stream"), "hours.csv"), "text/csv");
+        assert_eq!(content_type(None, "receipt.png"), "image/png");
+        assert_eq!(content_type(None, "notes"), "application/octet-stream");
+    }
+}
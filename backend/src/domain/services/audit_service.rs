@@ -0,0 +1,114 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    audit_changes, changes_status, AuditAction, AuditEntityType, AuditListFilter, AuditLog, CreateAuditLog,
+};
+use crate::infrastructure::repositories::AuditLogRepository;
+
+#[derive(Debug, Error)]
+pub enum AuditError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for AuditError {
+    fn from(err: sqlx::Error) -> Self {
+        AuditError::DatabaseError(err.to_string())
+    }
+}
+
+/// Who made a change and from where
+#[derive(Debug, Clone)]
+pub struct AuditOrigin {
+    /// Account the change was made in
+    pub user_id: Uuid,
+    /// Login that made it
+    pub actor_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// A change to record; `before`/`after` are the record as stored on either side
+pub struct AuditEvent {
+    pub action: AuditAction,
+    pub entity_type: AuditEntityType,
+    pub entity_id: Option<Uuid>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    /// Extra context kept next to the diff, e.g. which settings section changed
+    pub context: Option<serde_json::Value>,
+}
+
+/// The audit trail: who created, changed, sent or deleted invoices, clients,
+/// payments, expenses and settings, with a before/after diff of the record
+pub struct AuditService {
+    repo: AuditLogRepository,
+}
+
+impl AuditService {
+    pub fn new(repo: AuditLogRepository) -> Self {
+        Self { repo }
+    }
+
+    /// The record as stored, to diff against after the change
+    pub async fn snapshot(
+        &self,
+        user_id: Uuid,
+        entity_type: AuditEntityType,
+        entity_id: Uuid,
+    ) -> Result<Option<serde_json::Value>, AuditError> {
+        Ok(self.repo.snapshot(user_id, entity_type, entity_id).await?)
+    }
+
+    /// Record the event. Updates that changed the status are recorded as status
+    /// changes; the trail never fails the change itself, so errors are only logged.
+    pub async fn record(&self, origin: &AuditOrigin, event: AuditEvent) {
+        let diff = audit_changes(event.before.as_ref(), event.after.as_ref());
+        let action = match event.action {
+            AuditAction::Update if changes_status(diff.as_ref()) => AuditAction::StatusChange,
+            action => action,
+        };
+        let changes = match (diff, event.context) {
+            (Some(serde_json::Value::Object(mut diff)), Some(serde_json::Value::Object(context))) => {
+                diff.extend(context);
+                Some(serde_json::Value::Object(diff))
+            }
+            (diff, context) => diff.or(context),
+        };
+
+        let entry = CreateAuditLog {
+            user_id: Some(origin.user_id),
+            actor_id: origin.actor_id,
+            action,
+            entity_type: event.entity_type,
+            entity_id: event.entity_id,
+            changes,
+            ip_address: origin.ip_address.clone(),
+            user_agent: origin.user_agent.clone(),
+            request_id: origin.request_id.clone(),
+        };
+        if let Err(e) = self.repo.insert(&entry).await {
+            tracing::error!(
+                user_id = %origin.user_id,
+                action = %entry.action,
+                entity_type = %entry.entity_type,
+                "Failed to record audit log entry: {}",
+                e
+            );
+        }
+    }
+
+    pub async fn list(&self, user_id: Uuid, filter: &AuditListFilter) -> Result<Vec<AuditLog>, AuditError> {
+        if let (Some(from), Some(to)) = (filter.date_from, filter.date_to) {
+            if from > to {
+                return Err(AuditError::Validation("date_from must not be after date_to".to_string()));
+            }
+        }
+        Ok(self.repo.list(user_id, filter).await?)
+    }
+}
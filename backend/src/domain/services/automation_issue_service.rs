@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{AutomationIssue, AutomationIssueFilter, CreateAutomationIssue};
+use crate::domain::services::{EmailJobType, EmailQueueService, Shutdown};
+use crate::infrastructure::repositories::{AutomationIssueRepository, UserRepository};
+
+const DIGEST_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum AutomationIssueError {
+    #[error("Issue not found")]
+    NotFound,
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for AutomationIssueError {
+    fn from(err: sqlx::Error) -> Self {
+        AutomationIssueError::DatabaseError(err.to_string())
+    }
+}
+
+/// Collects failures from background automation so they reach the user
+/// through the issues endpoint and a daily "needs attention" email.
+pub struct AutomationIssueService {
+    repo: Arc<AutomationIssueRepository>,
+    user_repo: UserRepository,
+    email_queue: Arc<EmailQueueService>,
+}
+
+impl AutomationIssueService {
+    pub fn new(
+        repo: Arc<AutomationIssueRepository>,
+        user_repo: UserRepository,
+        email_queue: Arc<EmailQueueService>,
+    ) -> Self {
+        Self {
+            repo,
+            user_repo,
+            email_queue,
+        }
+    }
+
+    /// Record a failure. Never fails the caller; a storage error is only logged.
+    pub async fn record(&self, issue: CreateAutomationIssue) {
+        tracing::warn!(
+            user_id = %issue.user_id,
+            kind = issue.kind.as_str(),
+            "Automation issue: {}",
+            issue.summary
+        );
+
+        if let Err(e) = self.repo.create(issue).await {
+            tracing::error!("Failed to record automation issue: {}", e);
+        }
+    }
+
+    pub async fn list_issues(
+        &self,
+        user_id: Uuid,
+        filter: AutomationIssueFilter,
+    ) -> Result<Vec<AutomationIssue>, AutomationIssueError> {
+        Ok(self.repo.list(user_id, filter.include_resolved).await?)
+    }
+
+    pub async fn resolve_issue(&self, user_id: Uuid, id: Uuid) -> Result<AutomationIssue, AutomationIssueError> {
+        self.repo
+            .resolve(user_id, id)
+            .await?
+            .ok_or(AutomationIssueError::NotFound)
+    }
+
+    /// Email each user a digest of issues raised since their last digest.
+    /// Returns the number of digests sent.
+    pub async fn send_digests(&self) -> Result<usize, AutomationIssueError> {
+        let mut by_user: BTreeMap<Uuid, Vec<AutomationIssue>> = BTreeMap::new();
+        for issue in self.repo.list_undigested().await? {
+            by_user.entry(issue.user_id).or_default().push(issue);
+        }
+
+        let mut sent = 0;
+        for (user_id, issues) in by_user {
+            let user = match self.user_repo.find_by_id(user_id).await? {
+                Some(user) => user,
+                None => continue,
+            };
+
+            let items: Vec<(String, String)> = issues
+                .iter()
+                .map(|issue| (issue.kind.label().to_string(), issue.summary.clone()))
+                .collect();
+            let name = user.company_name.clone().unwrap_or_else(|| user.email.clone());
+            let digest = EmailJobType::SendAttentionDigest { to_email: user.email.clone(), to_name: name, items };
+
+            // Leave issues undigested if the digest can't be queued so the next run retries them
+            if let Err(e) = self.email_queue.enqueue_now(digest).await {
+                tracing::error!(user_id = %user_id, "Failed to queue attention digest: {}", e);
+                continue;
+            }
+
+            let ids: Vec<Uuid> = issues.iter().map(|issue| issue.id).collect();
+            self.repo.mark_digested(&ids).await?;
+            sent += 1;
+        }
+
+        Ok(sent)
+    }
+
+    /// Spawn the daily digest loop
+    pub fn start_daily_digest(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(DIGEST_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.send_digests().await {
+                    Ok(sent) => tracing::info!("Sent {} needs-attention digest(s)", sent),
+                    Err(e) => tracing::error!("Needs-attention digest run failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,191 @@
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    check_csv_upload, parse_bank_statement_csv, BankTransferPayment, ConfirmBankPayment, PaymentStatus,
+    RejectBankPayment, StatementMatchReport, MAX_BANK_STATEMENT_BYTES,
+};
+use crate::domain::services::{EnhancedNotificationService, FxRateService, SharedClock};
+use crate::infrastructure::repositories::{InvoiceRepository, PaymentRepository};
+
+const MAX_REJECT_REASON_LEN: usize = 500;
+const DEFAULT_REJECT_REASON: &str = "Not received";
+
+#[derive(Debug, Error)]
+pub enum BankReconciliationError {
+    #[error("Payment not found")]
+    NotFound,
+
+    #[error("Payment is already {0}")]
+    NotPending(PaymentStatus),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for BankReconciliationError {
+    fn from(err: sqlx::Error) -> Self {
+        BankReconciliationError::DatabaseError(err.to_string())
+    }
+}
+
+/// Bank transfer and ACH payments can't be confirmed by a gateway, so they
+/// stay pending until the seller sees the money on their statement and
+/// confirms it, which is when the invoice's balance goes down. A statement
+/// export can be uploaded to suggest which pending payment each credit is.
+pub struct BankReconciliationService {
+    payment_repo: Arc<PaymentRepository>,
+    invoice_repo: Arc<InvoiceRepository>,
+    notifications: Arc<EnhancedNotificationService>,
+    clock: SharedClock,
+    fx_rates: Option<Arc<FxRateService>>,
+}
+
+impl BankReconciliationService {
+    pub fn new(
+        payment_repo: Arc<PaymentRepository>,
+        invoice_repo: Arc<InvoiceRepository>,
+        notifications: Arc<EnhancedNotificationService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { payment_repo, invoice_repo, notifications, clock, fx_rates: None }
+    }
+
+    /// Book realized FX gains and losses on the transfers it confirms
+    pub fn with_fx_rates(mut self, fx_rates: Arc<FxRateService>) -> Self {
+        self.fx_rates = Some(fx_rates);
+        self
+    }
+
+    /// Bank transfers in a status, pending ones unless asked otherwise
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        status: Option<PaymentStatus>,
+    ) -> Result<Vec<BankTransferPayment>, BankReconciliationError> {
+        let status = status.unwrap_or(PaymentStatus::Pending);
+        Ok(self.payment_repo.list_bank_transfers(user_id, &status).await?)
+    }
+
+    /// The money arrived: complete the payment and apply it to the invoice
+    pub async fn confirm(
+        &self,
+        user_id: Uuid,
+        payment_id: Uuid,
+        confirm: ConfirmBankPayment,
+    ) -> Result<BankTransferPayment, BankReconciliationError> {
+        confirm.validate(self.clock.today()).map_err(BankReconciliationError::Validation)?;
+        let payment = self.pending(user_id, payment_id).await?;
+        // Paid some other way in the meantime
+        if payment.amount > payment.balance_due {
+            return Err(BankReconciliationError::Validation(format!(
+                "The payment is more than the {} the invoice still owes",
+                payment.balance_due
+            )));
+        }
+
+        let settlement_rate = match &self.fx_rates {
+            Some(fx_rates) => {
+                let invoice = self.invoice_repo.get_invoice_by_id(payment.invoice_id).await?;
+                fx_rates.settlement_rate(user_id, &invoice.currency, None).await
+            }
+            None => None,
+        };
+
+        let reference = confirm.reference.trim();
+        let confirmed = self
+            .payment_repo
+            .confirm_bank_transfer(user_id, payment_id, reference, confirm.received_on, settlement_rate)
+            .await?;
+        if !confirmed {
+            return Err(self.not_pending(user_id, payment_id).await);
+        }
+        tracing::info!(user_id = %user_id, payment_id = %payment_id, "Bank transfer confirmed");
+
+        // Best effort: the payment stands even if the confirmation can't be sent
+        match self.invoice_repo.get_invoice_by_id(payment.invoice_id).await {
+            Ok(invoice) => {
+                let (email, phone) = (invoice.client_email.clone(), invoice.client_phone.clone());
+                if let Err(e) = self.notifications.send_payment_confirmation(&invoice, email, phone).await {
+                    tracing::warn!("Payment confirmation for invoice {} failed: {}", invoice.invoice_number, e);
+                }
+            }
+            Err(e) => tracing::warn!("Invoice {} could not be loaded for confirmation: {}", payment.invoice_id, e),
+        }
+
+        self.payment_repo
+            .find_bank_transfer(user_id, payment_id)
+            .await?
+            .ok_or(BankReconciliationError::NotFound)
+    }
+
+    /// The money never arrived or was returned; the invoice is left as it is
+    pub async fn reject(
+        &self,
+        user_id: Uuid,
+        payment_id: Uuid,
+        reject: RejectBankPayment,
+    ) -> Result<BankTransferPayment, BankReconciliationError> {
+        let reason = reject
+            .reason
+            .as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .unwrap_or(DEFAULT_REJECT_REASON);
+        if reason.chars().count() > MAX_REJECT_REASON_LEN {
+            return Err(BankReconciliationError::Validation(format!(
+                "reason can be at most {} characters",
+                MAX_REJECT_REASON_LEN
+            )));
+        }
+        self.pending(user_id, payment_id).await?;
+
+        if !self.payment_repo.reject_bank_transfer(user_id, payment_id, reason).await? {
+            return Err(self.not_pending(user_id, payment_id).await);
+        }
+
+        self.payment_repo
+            .find_bank_transfer(user_id, payment_id)
+            .await?
+            .ok_or(BankReconciliationError::NotFound)
+    }
+
+    /// Match the credits on an uploaded statement against pending bank
+    /// transfers. Only suggests; nothing is confirmed.
+    pub async fn match_statement(
+        &self,
+        user_id: Uuid,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<StatementMatchReport, BankReconciliationError> {
+        check_csv_upload(file_name, data, MAX_BANK_STATEMENT_BYTES).map_err(BankReconciliationError::Validation)?;
+        let statement = parse_bank_statement_csv(data).map_err(BankReconciliationError::Validation)?;
+        let pending = self.payment_repo.list_bank_transfers(user_id, &PaymentStatus::Pending).await?;
+        Ok(StatementMatchReport::new(statement, &pending))
+    }
+
+    async fn pending(&self, user_id: Uuid, payment_id: Uuid) -> Result<BankTransferPayment, BankReconciliationError> {
+        let payment = self
+            .payment_repo
+            .find_bank_transfer(user_id, payment_id)
+            .await?
+            .ok_or(BankReconciliationError::NotFound)?;
+        match payment.status {
+            PaymentStatus::Pending => Ok(payment),
+            status => Err(BankReconciliationError::NotPending(status)),
+        }
+    }
+
+    /// Why a payment that was pending a moment ago couldn't be updated
+    async fn not_pending(&self, user_id: Uuid, payment_id: Uuid) -> BankReconciliationError {
+        match self.payment_repo.find_bank_transfer(user_id, payment_id).await {
+            Ok(Some(payment)) => BankReconciliationError::NotPending(payment.status),
+            Ok(None) => BankReconciliationError::NotFound,
+            Err(e) => e.into(),
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::NaiveDate;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    Budget, BudgetAlert, BudgetLimit, BudgetLine, BudgetLineKind, BudgetPeriod, BudgetReport, BudgetUtilization,
+    CreateBudget, CreateBudgetLimit, UpdateBudget, UpdateBudgetLimit,
+};
+use crate::domain::services::{EmailJobType, EmailQueueService, SharedClock};
+use crate::infrastructure::repositories::{BudgetRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum BudgetError {
+    #[error("Budget not found")]
+    NotFound,
+
+    #[error("A budget for {0} already exists")]
+    AlreadyExists(i32),
+
+    #[error("A {0} limit for {1} already exists")]
+    LimitExists(&'static str, String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for BudgetError {
+    fn from(err: sqlx::Error) -> Self {
+        BudgetError::DatabaseError(err.to_string())
+    }
+}
+
+/// Annual budgets planned against actuals, and spending limits per expense
+/// category that raise an alert (listed in the app and emailed) the first time
+/// spending in a month or quarter reaches 80% and 100% of the limit.
+pub struct BudgetService {
+    repo: BudgetRepository,
+    user_repo: UserRepository,
+    email_queue: Arc<EmailQueueService>,
+    clock: SharedClock,
+}
+
+impl BudgetService {
+    pub fn new(
+        repo: BudgetRepository,
+        user_repo: UserRepository,
+        email_queue: Arc<EmailQueueService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, user_repo, email_queue, clock }
+    }
+
+    fn validate_lines(lines: &[BudgetLine]) -> Result<(), BudgetError> {
+        for line in lines {
+            if !(1..=12).contains(&line.month) {
+                return Err(BudgetError::Validation(format!("Invalid month: {}", line.month)));
+            }
+            if !line.amount.is_finite() || line.amount < 0.0 {
+                return Err(BudgetError::Validation("Budget amounts must be zero or positive".to_string()));
+            }
+            if line.kind == BudgetLineKind::Income && line.category.is_some() {
+                return Err(BudgetError::Validation("Income lines don't take a category".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn create_budget(&self, user_id: Uuid, create: CreateBudget) -> Result<Budget, BudgetError> {
+        if !(2000..=2100).contains(&create.year) {
+            return Err(BudgetError::Validation(format!("Invalid year: {}", create.year)));
+        }
+        Self::validate_lines(&create.lines)?;
+
+        let name = create.name.unwrap_or_else(|| format!("{} Budget", create.year));
+
+        self.repo
+            .create(user_id, create.year, &name, &create.lines)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => BudgetError::AlreadyExists(create.year),
+                other => other.into(),
+            })
+    }
+
+    pub async fn list_budgets(&self, user_id: Uuid) -> Result<Vec<Budget>, BudgetError> {
+        Ok(self.repo.list(user_id).await?)
+    }
+
+    pub async fn get_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<Budget, BudgetError> {
+        self.repo
+            .find_by_id(user_id, budget_id)
+            .await?
+            .ok_or(BudgetError::NotFound)
+    }
+
+    pub async fn update_budget(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid,
+        update: UpdateBudget,
+    ) -> Result<Budget, BudgetError> {
+        if let Some(lines) = &update.lines {
+            Self::validate_lines(lines)?;
+        }
+
+        self.repo
+            .update(user_id, budget_id, update.name.as_deref(), update.lines.as_deref())
+            .await?
+            .ok_or(BudgetError::NotFound)
+    }
+
+    pub async fn delete_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<(), BudgetError> {
+        if self.repo.delete(user_id, budget_id).await? {
+            Ok(())
+        } else {
+            Err(BudgetError::NotFound)
+        }
+    }
+
+    /// Budget vs actuals for every month of the budget year
+    pub async fn get_report(&self, user_id: Uuid, budget_id: Uuid) -> Result<BudgetReport, BudgetError> {
+        let budget = self.get_budget(user_id, budget_id).await?;
+
+        let start = NaiveDate::from_ymd_opt(budget.year, 1, 1)
+            .ok_or_else(|| BudgetError::Validation(format!("Invalid year: {}", budget.year)))?;
+        let end = NaiveDate::from_ymd_opt(budget.year, 12, 31)
+            .ok_or_else(|| BudgetError::Validation(format!("Invalid year: {}", budget.year)))?;
+
+        let actuals = self.repo.get_actuals(user_id, start, end).await?;
+        Ok(BudgetReport::build(&budget, &actuals))
+    }
+
+    fn validate_limit_amount(amount: f64) -> Result<(), BudgetError> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(BudgetError::Validation("Limit amounts must be positive".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn list_limits(&self, user_id: Uuid) -> Result<Vec<BudgetLimit>, BudgetError> {
+        Ok(self.repo.list_limits(user_id).await?)
+    }
+
+    pub async fn create_limit(&self, user_id: Uuid, create: CreateBudgetLimit) -> Result<BudgetLimit, BudgetError> {
+        Self::validate_limit_amount(create.amount)?;
+
+        let limit = self.repo.create_limit(user_id, &create).await.map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                BudgetError::LimitExists(create.period.as_str(), create.category.to_string())
+            }
+            other => other.into(),
+        })?;
+        // Spending already past the new limit is reported right away
+        self.check_alerts(user_id).await?;
+        Ok(limit)
+    }
+
+    /// A changed amount starts the current period's alerts over
+    pub async fn update_limit(
+        &self,
+        user_id: Uuid,
+        limit_id: Uuid,
+        update: UpdateBudgetLimit,
+    ) -> Result<BudgetLimit, BudgetError> {
+        Self::validate_limit_amount(update.amount)?;
+
+        let limit = self
+            .repo
+            .update_limit(user_id, limit_id, update.amount)
+            .await?
+            .ok_or(BudgetError::NotFound)?;
+        let (period_start, _) = limit.period.bounds(self.clock.today());
+        self.repo.clear_alerts(limit.id, period_start).await?;
+        self.check_alerts(user_id).await?;
+        Ok(limit)
+    }
+
+    pub async fn delete_limit(&self, user_id: Uuid, limit_id: Uuid) -> Result<(), BudgetError> {
+        if self.repo.delete_limit(user_id, limit_id).await? {
+            Ok(())
+        } else {
+            Err(BudgetError::NotFound)
+        }
+    }
+
+    /// Spending against each limit in the month or quarter containing `date`
+    /// (today by default)
+    pub async fn utilization(
+        &self,
+        user_id: Uuid,
+        date: Option<NaiveDate>,
+    ) -> Result<Vec<BudgetUtilization>, BudgetError> {
+        let date = date.unwrap_or_else(|| self.clock.today());
+        let limits = self.repo.list_limits(user_id).await?;
+
+        // Spending by category, once per kind of period in use
+        let mut spending: BTreeMap<BudgetPeriod, BTreeMap<String, f64>> = BTreeMap::new();
+        for limit in &limits {
+            if let Entry::Vacant(entry) = spending.entry(limit.period) {
+                let (start, end) = limit.period.bounds(date);
+                entry.insert(self.repo.get_spending(user_id, start, end).await?);
+            }
+        }
+
+        Ok(limits
+            .iter()
+            .map(|limit| {
+                let (start, end) = limit.period.bounds(date);
+                let spent = spending[&limit.period].get(&limit.category.to_string()).copied().unwrap_or(0.0);
+                BudgetUtilization::new(limit, start, end, spent)
+            })
+            .collect())
+    }
+
+    /// Raise alerts for thresholds spending has newly reached this period, and
+    /// email them. Returns the new alerts.
+    pub async fn check_alerts(&self, user_id: Uuid) -> Result<Vec<BudgetAlert>, BudgetError> {
+        let mut new_ids = Vec::new();
+        for usage in self.utilization(user_id, None).await? {
+            for threshold in usage.crossed_thresholds() {
+                if let Some(id) = self
+                    .repo
+                    .record_alert(user_id, usage.limit_id, usage.period_start, threshold, usage.spent, usage.amount)
+                    .await?
+                {
+                    new_ids.push(id);
+                }
+            }
+        }
+        if new_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let alerts = self.repo.find_alerts(user_id, &new_ids).await?;
+        tracing::info!(user_id = %user_id, "Raised {} budget alert(s)", alerts.len());
+        self.email_alerts(user_id, &alerts).await;
+        Ok(alerts)
+    }
+
+    /// Best effort: the alerts stay listed in the app if the email can't be sent
+    async fn email_alerts(&self, user_id: Uuid, alerts: &[BudgetAlert]) {
+        let user = match self.user_repo.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::warn!(user_id = %user_id, "Budget alert email skipped: {}", e);
+                return;
+            }
+        };
+
+        // Only the highest threshold reached per limit is worth a line
+        let mut highest: BTreeMap<Uuid, &BudgetAlert> = BTreeMap::new();
+        for alert in alerts {
+            let entry = highest.entry(alert.limit_id).or_insert(alert);
+            if alert.threshold > entry.threshold {
+                *entry = alert;
+            }
+        }
+        let items: Vec<String> = highest
+            .values()
+            .map(|alert| {
+                format!(
+                    "{}% of your {} {} budget: ${:.2} of ${:.2}",
+                    alert.threshold,
+                    alert.period.as_str(),
+                    alert.category.to_string().replace('_', " "),
+                    alert.spent,
+                    alert.amount
+                )
+            })
+            .collect();
+
+        let name = user.company_name.clone().unwrap_or_else(|| user.email.clone());
+        self.email_queue
+            .enqueue_or_log(EmailJobType::SendBudgetAlert { to_email: user.email, to_name: name, items })
+            .await;
+    }
+
+    /// In-app alerts, newest first
+    pub async fn list_alerts(&self, user_id: Uuid, unread_only: bool) -> Result<Vec<BudgetAlert>, BudgetError> {
+        Ok(self.repo.list_alerts(user_id, unread_only).await?)
+    }
+
+    pub async fn mark_alert_read(&self, user_id: Uuid, alert_id: Uuid) -> Result<(), BudgetError> {
+        if self.repo.mark_alert_read(user_id, alert_id).await? {
+            Ok(())
+        } else {
+            Err(BudgetError::NotFound)
+        }
+    }
+}
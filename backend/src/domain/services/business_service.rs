@@ -0,0 +1,92 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{Business, CreateBusiness, UpdateBusiness};
+use crate::infrastructure::repositories::{BusinessRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum BusinessError {
+    #[error("Business not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for BusinessError {
+    fn from(err: sqlx::Error) -> Self {
+        BusinessError::DatabaseError(err.to_string())
+    }
+}
+
+pub struct BusinessService {
+    repo: BusinessRepository,
+    user_repo: UserRepository,
+}
+
+impl BusinessService {
+    pub fn new(repo: BusinessRepository, user_repo: UserRepository) -> Self {
+        Self { repo, user_repo }
+    }
+
+    fn validate_name(name: &str) -> Result<String, BusinessError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(BusinessError::Validation("Business name is required".to_string()));
+        }
+        if name.len() > 255 {
+            return Err(BusinessError::Validation("Business name must be at most 255 characters".to_string()));
+        }
+        Ok(name.to_string())
+    }
+
+    /// The owner's businesses, starting with the login account itself
+    pub async fn list(&self, owner_id: Uuid) -> Result<Vec<Business>, BusinessError> {
+        let owner = self.user_repo.find_by_id(owner_id).await?.ok_or(BusinessError::NotFound)?;
+
+        let mut businesses = vec![Business {
+            id: owner.id,
+            name: owner.company_name.clone().unwrap_or_else(|| owner.email.clone()),
+            is_primary: true,
+            created_at: owner.created_at,
+        }];
+        businesses.extend(self.repo.list(owner_id).await?);
+        Ok(businesses)
+    }
+
+    pub async fn create(&self, owner_id: Uuid, payload: CreateBusiness) -> Result<Business, BusinessError> {
+        let name = Self::validate_name(&payload.name)?;
+        let owner = self.user_repo.find_by_id(owner_id).await?.ok_or(BusinessError::NotFound)?;
+
+        Ok(self.repo.create(&owner, &name).await?)
+    }
+
+    pub async fn rename(&self, owner_id: Uuid, business_id: Uuid, payload: UpdateBusiness) -> Result<Business, BusinessError> {
+        let name = Self::validate_name(&payload.name)?;
+        if business_id == owner_id {
+            return Err(BusinessError::Validation(
+                "The primary business is renamed through the company name in settings".to_string(),
+            ));
+        }
+
+        self.repo
+            .rename(owner_id, business_id, &name)
+            .await?
+            .ok_or(BusinessError::NotFound)
+    }
+
+    /// Delete a business together with all of its clients, invoices and settings
+    pub async fn delete(&self, owner_id: Uuid, business_id: Uuid) -> Result<(), BusinessError> {
+        if business_id == owner_id {
+            return Err(BusinessError::Validation("The primary business can't be deleted".to_string()));
+        }
+
+        if !self.repo.delete(owner_id, business_id).await? {
+            return Err(BusinessError::NotFound);
+        }
+        Ok(())
+    }
+}
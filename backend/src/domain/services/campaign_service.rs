@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    CampaignMessage, CampaignPreview, CampaignRecipientStatus, CampaignReport, CampaignSummary, CreateCampaign,
+    PreviewCampaign, CAMPAIGN_DEFAULT_INTERVAL_SECS, CAMPAIGN_MAX_INTERVAL_SECS,
+};
+use crate::domain::services::{CampaignEmail, EmailJobType, EmailQueueService, SharedClock, Shutdown};
+use crate::infrastructure::repositories::{CampaignRepository, NewCampaign, UserRepository};
+
+/// How often the sender looks for emails whose slot has come
+const SEND_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Emails claimed per sender run
+const SEND_BATCH: i64 = 50;
+
+#[derive(Debug, Error)]
+pub enum CampaignError {
+    #[error("Campaign not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invalid status: {0}")]
+    InvalidStatus(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for CampaignError {
+    fn from(err: sqlx::Error) -> Self {
+        CampaignError::DatabaseError(err.to_string())
+    }
+}
+
+/// Outstanding-balance email campaigns to a segment of clients, sent on a
+/// throttled schedule with delivery and payment outcomes per recipient
+pub struct CampaignService {
+    repo: CampaignRepository,
+    user_repo: UserRepository,
+    email_queue: Arc<EmailQueueService>,
+    clock: SharedClock,
+}
+
+impl CampaignService {
+    pub fn new(
+        repo: CampaignRepository,
+        user_repo: UserRepository,
+        email_queue: Arc<EmailQueueService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, user_repo, email_queue, clock }
+    }
+
+    fn templates(template: crate::domain::models::CampaignTemplate, subject: Option<String>, body: Option<String>) -> (String, String) {
+        let non_empty = |text: Option<String>| text.filter(|t| !t.trim().is_empty());
+        (
+            non_empty(subject).unwrap_or_else(|| template.subject().to_string()),
+            non_empty(body).unwrap_or_else(|| template.body().to_string()),
+        )
+    }
+
+    async fn company_name(&self, user_id: Uuid) -> Result<String, CampaignError> {
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(CampaignError::NotFound)?;
+        Ok(user.company_name.unwrap_or(user.email))
+    }
+
+    /// The clients a campaign would reach right now, and the email the first one would get
+    pub async fn preview(&self, user_id: Uuid, payload: PreviewCampaign) -> Result<CampaignPreview, CampaignError> {
+        let today = self.clock.today();
+        let recipients = self.repo.audience(user_id, &payload.segment, today).await?;
+        let (subject, body) = Self::templates(payload.template, payload.subject, payload.body);
+
+        let sample = match recipients.first() {
+            Some(first) => {
+                let company_name = self.company_name(user_id).await?;
+                Some(CampaignMessage {
+                    subject: first.render(&subject, &company_name, today),
+                    body: first.render(&body, &company_name, today),
+                })
+            }
+            None => None,
+        };
+
+        Ok(CampaignPreview {
+            total_outstanding: recipients.iter().map(|r| r.outstanding_balance).sum(),
+            recipients,
+            sample,
+        })
+    }
+
+    pub async fn create(&self, user_id: Uuid, payload: CreateCampaign) -> Result<CampaignReport, CampaignError> {
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(CampaignError::Validation("Campaign name is required".to_string()));
+        }
+        let interval = payload.send_interval_seconds.unwrap_or(CAMPAIGN_DEFAULT_INTERVAL_SECS);
+        if !(1..=CAMPAIGN_MAX_INTERVAL_SECS).contains(&interval) {
+            return Err(CampaignError::Validation(format!(
+                "Send interval must be between 1 and {} seconds",
+                CAMPAIGN_MAX_INTERVAL_SECS
+            )));
+        }
+
+        let audience = self.repo.audience(user_id, &payload.segment, self.clock.today()).await?;
+        if audience.is_empty() {
+            return Err(CampaignError::Validation("No clients with an outstanding balance match this segment".to_string()));
+        }
+
+        let (subject, body) = Self::templates(payload.template, payload.subject, payload.body);
+        let now = self.clock.now();
+        let campaign_id = self
+            .repo
+            .create(
+                user_id,
+                NewCampaign {
+                    name,
+                    template: payload.template,
+                    subject_template: &subject,
+                    body_template: &body,
+                    segment: &payload.segment,
+                    send_interval_seconds: interval,
+                    start_at: payload.start_at.filter(|start| *start > now).unwrap_or(now),
+                    audience: &audience,
+                },
+            )
+            .await?;
+
+        self.report(user_id, campaign_id).await
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<crate::domain::models::Campaign>, CampaignError> {
+        Ok(self.repo.list(user_id).await?)
+    }
+
+    pub async fn report(&self, user_id: Uuid, campaign_id: Uuid) -> Result<CampaignReport, CampaignError> {
+        let campaign = self.repo.find(user_id, campaign_id).await?.ok_or(CampaignError::NotFound)?;
+        let recipients = self.repo.recipients(campaign_id).await?;
+
+        Ok(CampaignReport {
+            campaign,
+            summary: CampaignSummary::from_recipients(&recipients),
+            recipients,
+        })
+    }
+
+    pub async fn cancel(&self, user_id: Uuid, campaign_id: Uuid) -> Result<CampaignReport, CampaignError> {
+        let campaign = self.repo.find(user_id, campaign_id).await?.ok_or(CampaignError::NotFound)?;
+        if !self.repo.cancel(user_id, campaign_id).await? {
+            return Err(CampaignError::InvalidStatus(format!(
+                "Campaign is already {}",
+                campaign.status.as_str()
+            )));
+        }
+
+        self.report(user_id, campaign_id).await
+    }
+
+    /// Send every email whose slot has come. Balances are re-read first, so clients
+    /// who paid since the campaign was created are skipped. Returns the number sent.
+    pub async fn send_due(&self) -> Result<usize, CampaignError> {
+        let now = self.clock.now();
+        let today = self.clock.today();
+        let mut sent = 0;
+
+        for due in self.repo.claim_due(now, SEND_BATCH).await? {
+            let member = match self.repo.audience_member(due.user_id, due.client_id, today).await? {
+                Some(member) => member,
+                None => {
+                    self.repo
+                        .record_outcome(due.id, CampaignRecipientStatus::Skipped, None, Some("Balance settled before sending"), now)
+                        .await?;
+                    continue;
+                }
+            };
+
+            let email = CampaignEmail {
+                to_email: member.email.clone(),
+                to_name: member.client_name.clone(),
+                subject: member.render(&due.subject_template, &due.company_name, today),
+                body: member.render(&due.body_template, &due.company_name, today),
+            };
+
+            // Sent inline so the recipient's outcome can be recorded
+            match self.email_queue.send_immediate(EmailJobType::SendCampaignEmail(email)).await {
+                Ok(()) => {
+                    self.repo
+                        .record_outcome(due.id, CampaignRecipientStatus::Sent, Some(member.outstanding_balance), None, now)
+                        .await?;
+                    sent += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(client_id = %due.client_id, "Campaign email failed: {}", e);
+                    self.repo
+                        .record_outcome(due.id, CampaignRecipientStatus::Failed, Some(member.outstanding_balance), Some(&e.to_string()), now)
+                        .await?;
+                }
+            }
+        }
+
+        self.repo.complete_finished(now).await?;
+        Ok(sent)
+    }
+
+    /// Spawn the throttled campaign sender
+    pub fn start_sender(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(SEND_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.send_due().await {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::info!("Sent {} campaign email(s)", sent),
+                    Err(e) => tracing::error!("Campaign send run failed: {}", e),
+                }
+            }
+        });
+    }
+}
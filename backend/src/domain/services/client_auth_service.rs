@@ -0,0 +1,215 @@
+use chrono::Duration;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{
+    normalize_portal_email, ClientAccount, PortalAccount, PortalInvoice, PortalInvoiceDetail, PortalLinkRequest,
+    PortalLinkSignIn, PortalLogin, PortalSession, PortalSetPassword, PORTAL_LINKS_PER_HOUR, PORTAL_LINK_TTL_MINUTES,
+    PORTAL_MIN_PASSWORD_LENGTH,
+};
+use crate::domain::services::{AuthService, EmailJobType, EmailQueueService, InvoiceError, InvoicePdf, InvoiceService, SharedClock};
+use crate::infrastructure::repositories::ClientAccountRepository;
+
+#[derive(Debug, Error)]
+pub enum ClientAuthError {
+    #[error("Not found")]
+    NotFound,
+
+    #[error("Invalid email or password")]
+    InvalidCredentials,
+
+    #[error("This sign-in link is invalid, used or expired")]
+    InvalidLink,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Token error: {0}")]
+    Token(String),
+
+    #[error("Invoice error: {0}")]
+    Invoice(#[from] InvoiceError),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ClientAuthError {
+    fn from(err: sqlx::Error) -> Self {
+        ClientAuthError::DatabaseError(err.to_string())
+    }
+}
+
+/// Buyer accounts for the client portal. A buyer claims their email through an
+/// emailed sign-in link, can then set a password, and sees the issued invoices of
+/// every business that has them as a client.
+pub struct ClientAuthService {
+    repo: ClientAccountRepository,
+    auth_service: Arc<AuthService>,
+    email_queue: Arc<EmailQueueService>,
+    invoice_service: Arc<InvoiceService>,
+    clock: SharedClock,
+}
+
+impl ClientAuthService {
+    pub fn new(
+        repo: ClientAccountRepository,
+        auth_service: Arc<AuthService>,
+        email_queue: Arc<EmailQueueService>,
+        invoice_service: Arc<InvoiceService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, auth_service, email_queue, invoice_service, clock }
+    }
+
+    /// Email a sign-in link if some business bills this address. The outcome isn't
+    /// reported back, so the endpoint can't be used to find out who is a client.
+    pub async fn request_link(&self, payload: PortalLinkRequest) -> Result<(), ClientAuthError> {
+        let email = normalize_portal_email(&payload.email);
+        if !email.validate_email() {
+            return Err(ClientAuthError::Validation(format!("Invalid email address: {}", payload.email)));
+        }
+        if !self.repo.is_client_email(&email).await? {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        if self.repo.count_links_since(&email, now - Duration::hours(1)).await? >= PORTAL_LINKS_PER_HOUR {
+            tracing::warn!("Too many portal sign-in links requested for {}", email);
+            return Ok(());
+        }
+
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let expires_at = now + Duration::minutes(PORTAL_LINK_TTL_MINUTES);
+        self.repo.create_link(&email, &hash_link_token(&token), expires_at).await?;
+
+        self.email_queue
+            .enqueue_or_log(EmailJobType::SendPortalSignInLink {
+                to_email: email,
+                token,
+                valid_minutes: PORTAL_LINK_TTL_MINUTES,
+            })
+            .await;
+        Ok(())
+    }
+
+    /// Use an emailed link, creating the account the first time
+    pub async fn sign_in_with_link(&self, payload: PortalLinkSignIn) -> Result<PortalSession, ClientAuthError> {
+        let token = payload.token.trim();
+        if token.is_empty() {
+            return Err(ClientAuthError::InvalidLink);
+        }
+
+        let email = self
+            .repo
+            .use_link(&hash_link_token(token), self.clock.now())
+            .await?
+            .ok_or(ClientAuthError::InvalidLink)?;
+        let account = self.repo.find_or_create(&email).await?;
+
+        self.start_session(account).await
+    }
+
+    pub async fn sign_in(&self, payload: PortalLogin) -> Result<PortalSession, ClientAuthError> {
+        let email = normalize_portal_email(&payload.email);
+        let account = self.repo.find_by_email(&email).await?.ok_or(ClientAuthError::InvalidCredentials)?;
+        let hash = account.password_hash.as_deref().ok_or(ClientAuthError::InvalidCredentials)?;
+        if !self.auth_service.verify_password(&payload.password, hash).unwrap_or(false) {
+            return Err(ClientAuthError::InvalidCredentials);
+        }
+
+        self.start_session(account).await
+    }
+
+    pub async fn set_password(&self, account_id: Uuid, payload: PortalSetPassword) -> Result<PortalAccount, ClientAuthError> {
+        if payload.password.chars().count() < PORTAL_MIN_PASSWORD_LENGTH {
+            return Err(ClientAuthError::Validation(format!(
+                "Password must be at least {} characters",
+                PORTAL_MIN_PASSWORD_LENGTH
+            )));
+        }
+
+        let hash = self
+            .auth_service
+            .hash_password(&payload.password)
+            .map_err(|e| ClientAuthError::Token(e.to_string()))?;
+        self.repo.set_password(account_id, &hash).await?;
+        self.account(account_id).await
+    }
+
+    pub async fn account(&self, account_id: Uuid) -> Result<PortalAccount, ClientAuthError> {
+        let account = self.repo.find_by_id(account_id).await?.ok_or(ClientAuthError::NotFound)?;
+        self.describe(account).await
+    }
+
+    pub async fn list_invoices(&self, account_id: Uuid) -> Result<Vec<PortalInvoice>, ClientAuthError> {
+        Ok(self.repo.list_invoices(account_id).await?)
+    }
+
+    pub async fn get_invoice(&self, account_id: Uuid, invoice_id: Uuid) -> Result<PortalInvoiceDetail, ClientAuthError> {
+        let vendor = self.repo.invoice_vendor(account_id, invoice_id).await?.ok_or(ClientAuthError::NotFound)?;
+        let invoice = self.invoice_service.get_invoice(vendor.id, invoice_id).await?;
+
+        Ok(PortalInvoiceDetail { invoice, vendor })
+    }
+
+    pub async fn invoice_pdf(&self, account_id: Uuid, invoice_id: Uuid) -> Result<InvoicePdf, ClientAuthError> {
+        let vendor = self.repo.invoice_vendor(account_id, invoice_id).await?.ok_or(ClientAuthError::NotFound)?;
+        Ok(self.invoice_service.get_invoice_pdf(vendor.id, invoice_id, false).await?)
+    }
+
+    /// Check the account may see the invoice before it's paid through the guest checkout
+    pub async fn authorize_invoice(&self, account_id: Uuid, invoice_id: Uuid) -> Result<(), ClientAuthError> {
+        self.repo.invoice_vendor(account_id, invoice_id).await?.ok_or(ClientAuthError::NotFound)?;
+        Ok(())
+    }
+
+    async fn start_session(&self, account: ClientAccount) -> Result<PortalSession, ClientAuthError> {
+        self.repo.record_sign_in(account.id, self.clock.now()).await?;
+        let (access_token, expires_in) = self
+            .auth_service
+            .generate_portal_token(account.id, &account.email)
+            .map_err(|e| ClientAuthError::Token(e.to_string()))?;
+
+        Ok(PortalSession {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in,
+            account: self.describe(account).await?,
+        })
+    }
+
+    async fn describe(&self, account: ClientAccount) -> Result<PortalAccount, ClientAuthError> {
+        let vendors = self.repo.vendors(account.id).await?;
+
+        Ok(PortalAccount {
+            id: account.id,
+            email: account.email,
+            has_password: account.password_hash.is_some(),
+            vendors,
+            created_at: account.created_at,
+        })
+    }
+}
+
+/// Only a hash of a sign-in link's token is stored
+fn hash_link_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_link_tokens_are_stored_hashed() {
+        let hash = hash_link_token("abc123");
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, "abc123");
+        assert_eq!(hash, hash_link_token("abc123"));
+        assert_ne!(hash, hash_link_token("abc124"));
+    }
+}
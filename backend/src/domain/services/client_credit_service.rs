@@ -0,0 +1,111 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{normalize_credit, ClientCreditEntry, ClientCreditKind, ClientCreditLedger, CreateClientCredit};
+use crate::domain::services::SharedClock;
+use crate::infrastructure::repositories::{
+    ClientCreditRepository, ClientRepository, FxRepository, NewClientCredit, RecordClientCredit,
+};
+
+#[derive(Debug, Error)]
+pub enum ClientCreditError {
+    #[error("Client not found")]
+    ClientNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ClientCreditError {
+    fn from(err: sqlx::Error) -> Self {
+        ClientCreditError::DatabaseError(err.to_string())
+    }
+}
+
+/// Retainers and other credit clients hold with the user. Applying it to invoices
+/// goes through `InvoiceService::apply_client_credit`.
+pub struct ClientCreditService {
+    repo: ClientCreditRepository,
+    client_repo: ClientRepository,
+    fx_repo: FxRepository,
+    clock: SharedClock,
+}
+
+impl ClientCreditService {
+    pub fn new(repo: ClientCreditRepository, client_repo: ClientRepository, fx_repo: FxRepository, clock: SharedClock) -> Self {
+        Self { repo, client_repo, fx_repo, clock }
+    }
+
+    pub async fn ledger(&self, user_id: Uuid, client_id: Uuid) -> Result<ClientCreditLedger, ClientCreditError> {
+        self.client_repo.find_by_id(user_id, client_id).await?.ok_or(ClientCreditError::ClientNotFound)?;
+
+        Ok(ClientCreditLedger {
+            client_id,
+            balances: self.repo.balances(user_id, client_id, None).await?,
+            entries: self.repo.list(user_id, client_id).await?,
+        })
+    }
+
+    /// Money received ahead of invoicing, such as a retainer
+    pub async fn deposit(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        deposit: CreateClientCredit,
+    ) -> Result<ClientCreditEntry, ClientCreditError> {
+        self.record(user_id, client_id, ClientCreditKind::Deposit, deposit).await
+    }
+
+    /// Pays unused credit back to the client; can't take more than is available
+    pub async fn refund(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        refund: CreateClientCredit,
+    ) -> Result<ClientCreditEntry, ClientCreditError> {
+        self.record(user_id, client_id, ClientCreditKind::Refund, refund).await
+    }
+
+    async fn record(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        kind: ClientCreditKind,
+        create: CreateClientCredit,
+    ) -> Result<ClientCreditEntry, ClientCreditError> {
+        let client = self
+            .client_repo
+            .find_by_id(user_id, client_id)
+            .await?
+            .filter(|client| client.deleted_at.is_none())
+            .ok_or(ClientCreditError::ClientNotFound)?;
+        let default_currency = match client.default_currency {
+            Some(currency) => currency,
+            None => self.fx_repo.base_currency(user_id).await?,
+        };
+        let currency = normalize_credit(create.amount, create.currency.as_deref(), &default_currency)
+            .map_err(ClientCreditError::Validation)?;
+
+        let amount = if kind == ClientCreditKind::Refund { -create.amount } else { create.amount };
+        let description = create.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+        let new = NewClientCredit {
+            kind,
+            amount,
+            currency: &currency,
+            description,
+            entry_date: create.entry_date.unwrap_or_else(|| self.clock.today()),
+        };
+
+        match self.repo.record(user_id, client_id, new).await? {
+            Some(RecordClientCredit::Recorded(entry)) => Ok(*entry),
+            Some(RecordClientCredit::Insufficient(available)) => Err(ClientCreditError::Validation(format!(
+                "Refund of {:.2} {} exceeds the {:.2} {} of credit available",
+                create.amount, currency, available, currency
+            ))),
+            None => Err(ClientCreditError::ClientNotFound),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::Duration;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    check_csv_upload, parse_client_csv, ClientImportJob, ClientImportJobStatus, ClientImportReport, ImportRowIssue,
+    INLINE_CLIENT_IMPORT_ROWS, MAX_CLIENT_CSV_BYTES,
+};
+use crate::domain::services::{SharedClock, Shutdown};
+use crate::infrastructure::repositories::ClientImportJobRepository;
+
+/// How often the worker looks for queued imports
+const JOB_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Rows created per transaction; progress moves on after each batch
+const BATCH_SIZE: usize = 100;
+/// A running job with no progress for this long has lost its worker
+const STALE_JOB_MINUTES: i64 = 5;
+
+#[derive(Debug, Error)]
+pub enum ClientCsvImportError {
+    #[error("Import job not found")]
+    JobNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ClientCsvImportError {
+    fn from(err: sqlx::Error) -> Self {
+        ClientCsvImportError::DatabaseError(err.to_string())
+    }
+}
+
+pub enum ClientImportOutcome {
+    /// Dry runs and small files, done in the request
+    Completed(ClientImportReport),
+    /// Large files, imported in the background
+    Queued(ClientImportJob),
+}
+
+/// Bulk client imports from CSV files, e.g. when moving from another tool.
+/// Rows are validated one by one and clients whose email already exists are
+/// skipped; small files are imported straight away and large ones by a job.
+pub struct ClientCsvImportService {
+    repo: ClientImportJobRepository,
+    clock: SharedClock,
+}
+
+impl ClientCsvImportService {
+    pub fn new(repo: ClientImportJobRepository, clock: SharedClock) -> Self {
+        Self { repo, clock }
+    }
+
+    pub async fn import(
+        &self,
+        user_id: Uuid,
+        file_name: &str,
+        data: &[u8],
+        dry_run: bool,
+    ) -> Result<ClientImportOutcome, ClientCsvImportError> {
+        check_csv_upload(file_name, data, MAX_CLIENT_CSV_BYTES).map_err(ClientCsvImportError::Validation)?;
+        let parsed = parse_client_csv(data).map_err(ClientCsvImportError::Validation)?;
+        let existing = self.repo.existing_emails(user_id).await?;
+        let (rows, known): (Vec<_>, Vec<_>) = parsed
+            .rows
+            .iter()
+            .cloned()
+            .partition(|row| row.client.email.as_ref().is_none_or(|email| !existing.contains(&email.to_lowercase())));
+        let mut duplicates = parsed.duplicates.clone();
+        duplicates.extend(known.iter().map(|row| ImportRowIssue::duplicate_email(row.row)));
+        duplicates.sort_by_key(|issue| issue.row);
+
+        if !dry_run && rows.len() > INLINE_CLIENT_IMPORT_ROWS {
+            let job = self.repo.create_job(user_id, file_name, &parsed, &rows, &duplicates).await?;
+            tracing::info!(user_id = %user_id, job_id = %job.id, rows = rows.len(), "Client import queued");
+            return Ok(ClientImportOutcome::Queued(job));
+        }
+
+        let created = if dry_run {
+            rows.len()
+        } else {
+            let batch = self.repo.import_batch(user_id, &rows, None, self.clock.now()).await?;
+            duplicates.extend(batch.duplicates);
+            duplicates.sort_by_key(|issue| issue.row);
+            batch.created
+        };
+
+        Ok(ClientImportOutcome::Completed(ClientImportReport {
+            dry_run,
+            total_rows: parsed.total_rows,
+            created,
+            skipped: duplicates.len(),
+            failed: parsed.errors.len(),
+            errors: parsed.errors,
+            duplicates,
+            ignored_columns: parsed.ignored_columns,
+        }))
+    }
+
+    pub async fn get_job(&self, user_id: Uuid, id: Uuid) -> Result<ClientImportJob, ClientCsvImportError> {
+        self.repo.get_job(user_id, id).await?.ok_or(ClientCsvImportError::JobNotFound)
+    }
+
+    /// Spawn the loop that runs queued imports
+    pub fn start_worker(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(JOB_POLL_INTERVAL);
+            while shutdown.tick(&mut interval).await {
+                if let Err(e) = self.run_next_job().await {
+                    tracing::error!("Client import job failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn run_next_job(&self) -> Result<(), ClientCsvImportError> {
+        let now = self.clock.now();
+        let Some(job) = self
+            .repo
+            .claim_job(now, now - Duration::minutes(STALE_JOB_MINUTES))
+            .await?
+        else {
+            return Ok(());
+        };
+
+        for batch in job.rows.chunks(BATCH_SIZE) {
+            if let Err(e) = self.repo.import_batch(job.user_id, batch, Some(job.id), self.clock.now()).await {
+                tracing::error!(job_id = %job.id, "Client import stopped: {}", e);
+                self.repo
+                    .finish_job(job.id, ClientImportJobStatus::Failed, Some(&e.to_string()), self.clock.now())
+                    .await?;
+                return Ok(());
+            }
+        }
+
+        self.repo
+            .finish_job(job.id, ClientImportJobStatus::Completed, None, self.clock.now())
+            .await?;
+        tracing::info!(user_id = %job.user_id, job_id = %job.id, "Client import finished");
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    normalize_optional_phone, parse_inbound_email, Client, ClientImport, ClientImportFilter, ClientImportStatus,
+    CreateClient, InboundAddress, InboundEmail, UpdateClient,
+};
+use crate::domain::services::ClientService;
+use crate::infrastructure::repositories::ClientImportRepository;
+
+#[derive(Debug, Error)]
+pub enum ClientImportError {
+    #[error("Import not found")]
+    NotFound,
+
+    #[error("Import was already confirmed or dismissed")]
+    AlreadyResolved,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ClientImportError {
+    fn from(err: sqlx::Error) -> Self {
+        ClientImportError::DatabaseError(err.to_string())
+    }
+}
+
+/// Turns emails forwarded to a user's inbound address into client imports
+/// that wait in the notification inbox until the user confirms them
+pub struct ClientImportService {
+    repo: ClientImportRepository,
+    clients: Arc<ClientService>,
+    inbound_domain: String,
+}
+
+impl ClientImportService {
+    pub fn new(repo: ClientImportRepository, clients: Arc<ClientService>, inbound_domain: String) -> Self {
+        Self { repo, clients, inbound_domain }
+    }
+
+    /// The user's forwarding address, created on first request
+    pub async fn address(&self, user_id: Uuid) -> Result<InboundAddress, ClientImportError> {
+        let token = match self.repo.find_token(user_id).await? {
+            Some(token) => token,
+            None => return self.rotate_address(user_id).await,
+        };
+        Ok(self.inbound_address(token))
+    }
+
+    /// A new address; mail to the previous one is no longer accepted
+    pub async fn rotate_address(&self, user_id: Uuid) -> Result<InboundAddress, ClientImportError> {
+        let token = Uuid::new_v4().simple().to_string();
+        self.repo.set_token(user_id, &token).await?;
+        Ok(self.inbound_address(token))
+    }
+
+    fn inbound_address(&self, token: String) -> InboundAddress {
+        InboundAddress {
+            address: format!("bills+{}@{}", token, self.inbound_domain),
+            token,
+        }
+    }
+
+    /// Parses a message delivered to the address with `token` and queues the
+    /// contact for confirmation
+    pub async fn receive(&self, token: &str, email: InboundEmail) -> Result<ClientImport, ClientImportError> {
+        let user_id = self
+            .repo
+            .find_user_by_token(token)
+            .await?
+            .ok_or(ClientImportError::NotFound)?;
+
+        let (source, mut contact) = parse_inbound_email(&email).map_err(ClientImportError::Validation)?;
+        // Client numbers must be valid for WhatsApp sends; drop one that isn't
+        contact.phone = normalize_optional_phone(contact.phone).ok().flatten();
+
+        let existing = match &contact.email {
+            Some(address) => self.repo.find_client_by_email(user_id, address).await?,
+            None => None,
+        };
+
+        let subject = email.subject.as_deref().map(|s| s.chars().take(255).collect::<String>());
+        let import = self
+            .repo
+            .create(user_id, source, &email.from, subject.as_deref(), &contact, existing)
+            .await?;
+
+        tracing::info!(
+            user_id = %user_id,
+            import_id = %import.id,
+            source = source.as_str(),
+            "Client import received from forwarded email"
+        );
+        Ok(import)
+    }
+
+    pub async fn list(&self, user_id: Uuid, filter: ClientImportFilter) -> Result<Vec<ClientImport>, ClientImportError> {
+        Ok(self.repo.list(user_id, filter.include_resolved).await?)
+    }
+
+    /// Creates the client, or fills in phone, company and address on the
+    /// existing client with the same email. An existing client keeps its name.
+    pub async fn confirm(&self, user_id: Uuid, id: Uuid) -> Result<Client, ClientImportError> {
+        let import = self.repo.find(user_id, id).await?.ok_or(ClientImportError::NotFound)?;
+        if import.status != ClientImportStatus::Pending {
+            return Err(ClientImportError::AlreadyResolved);
+        }
+
+        let contact = import.contact;
+        let billing_address = contact
+            .billing_address
+            .map(|address| serde_json::to_value(address).unwrap_or_default());
+
+        // Look again: the client may have been added since the email arrived
+        let existing = match &contact.email {
+            Some(address) => self.repo.find_client_by_email(user_id, address).await?,
+            None => None,
+        };
+
+        let client = match existing {
+            Some(client_id) => {
+                let update = UpdateClient {
+                    name: None,
+                    email: None,
+                    phone: contact.phone,
+                    company_name: contact.company_name,
+                    billing_address,
+                    payment_terms: None,
+                    tax_exempt: None,
+                    tax_exempt_certificate: None,
+                    notes: None,
+                    statement_opt_out: None,
+                    billing_contacts: None,
+                    locale: None,
+                    default_tax_id: None,
+                    default_currency: None,
+                    default_discount_percent: None,
+                    hourly_rate: None,
+                    vat_number: None,
+                };
+                self.clients.update_client(user_id, client_id, update).await?
+            }
+            None => {
+                let create = CreateClient {
+                    name: contact.name,
+                    email: contact.email,
+                    phone: contact.phone,
+                    company_name: contact.company_name,
+                    billing_address,
+                    payment_terms: None,
+                    tax_exempt: None,
+                    tax_exempt_certificate: None,
+                    notes: None,
+                    parent_client_id: None,
+                    billing_contacts: None,
+                    locale: None,
+                    default_tax_id: None,
+                    default_currency: None,
+                    default_discount_percent: None,
+                    hourly_rate: None,
+                    vat_number: None,
+                };
+                self.clients.create_client(user_id, create).await?
+            }
+        };
+
+        self.repo
+            .resolve(user_id, id, ClientImportStatus::Confirmed, Some(client.id))
+            .await?
+            .ok_or(ClientImportError::AlreadyResolved)?;
+        Ok(client)
+    }
+
+    pub async fn dismiss(&self, user_id: Uuid, id: Uuid) -> Result<ClientImport, ClientImportError> {
+        match self.repo.resolve(user_id, id, ClientImportStatus::Dismissed, None).await? {
+            Some(import) => Ok(import),
+            None => match self.repo.find(user_id, id).await? {
+                Some(_) => Err(ClientImportError::AlreadyResolved),
+                None => Err(ClientImportError::NotFound),
+            },
+        }
+    }
+}
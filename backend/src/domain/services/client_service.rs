@@ -1,24 +1,38 @@
 use std::sync::Arc;
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use crate::infrastructure::repositories::ClientRepository;
-use crate::domain::models::{Client, ClientResponse, ClientStats, CreateClient, UpdateClient};
+use crate::infrastructure::repositories::{ClientRepository, UserRepository};
+use crate::domain::models::{
+    seller_vat_country, Client, ClientHierarchyStatement, ClientInvoiceDefaults, ClientResponse, ClientStats,
+    CreateClient, Page, PageRequest, UpdateClient, VatCheck, VatNumber, VatStatus,
+};
+use crate::domain::services::{TaxService, VatNumberValidator};
 
 #[derive(Clone)]
 pub struct ClientService {
     client_repo: Arc<ClientRepository>,
+    user_repo: UserRepository,
+    tax_service: Arc<TaxService>,
+    vat_validator: Arc<dyn VatNumberValidator>,
 }
 
 impl ClientService {
-    pub fn new(client_repo: Arc<ClientRepository>) -> Self {
-        Self { client_repo }
+    pub fn new(
+        client_repo: Arc<ClientRepository>,
+        user_repo: UserRepository,
+        tax_service: Arc<TaxService>,
+        vat_validator: Arc<dyn VatNumberValidator>,
+    ) -> Self {
+        Self { client_repo, user_repo, tax_service, vat_validator }
     }
 
     pub async fn create_client(&self, user_id: Uuid, create: CreateClient) -> Result<Client, sqlx::Error> {
         let billing_address = create.billing_address
             .map(|addr| serde_json::to_value(addr).unwrap_or(serde_json::Value::Null));
 
-        self.client_repo.create(
+        let client = self.client_repo.create(
             user_id,
             create.name,
             create.email,
@@ -28,7 +42,23 @@ impl ClientService {
             create.payment_terms,
             create.tax_exempt,
             create.notes,
-        ).await
+            &create.billing_contacts.unwrap_or_default(),
+            create.locale,
+            create.default_tax_id,
+            create.default_currency,
+            create.default_discount_percent,
+            create.hourly_rate,
+        ).await?;
+
+        let client = match create.vat_number.filter(|vat_number| !vat_number.is_empty()) {
+            Some(vat_number) => self.set_vat_number(user_id, client.id, Some(&vat_number)).await?,
+            None => client,
+        };
+
+        match create.parent_client_id {
+            Some(parent_id) => self.client_repo.set_parent(user_id, client.id, Some(parent_id)).await,
+            None => Ok(client),
+        }
     }
 
     pub async fn get_client(&self, user_id: Uuid, client_id: Uuid) -> Result<Option<Client>, sqlx::Error> {
@@ -39,10 +69,15 @@ impl ClientService {
         &self,
         user_id: Uuid,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ClientResponse>, sqlx::Error> {
-        self.client_repo.list(user_id, search, limit, offset).await
+        parent_client_id: Option<Uuid>,
+        include_archived: bool,
+        page: &PageRequest,
+    ) -> Result<Page<ClientResponse>, sqlx::Error> {
+        self.client_repo.list(user_id, search, parent_client_id, include_archived, page).await
+    }
+
+    pub async fn get_clients_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<ClientResponse>, sqlx::Error> {
+        self.client_repo.find_by_ids(user_id, ids).await
     }
 
     pub async fn update_client(
@@ -54,7 +89,7 @@ impl ClientService {
         let billing_address = update.billing_address
             .map(|addr| serde_json::to_value(addr).unwrap_or(serde_json::Value::Null));
 
-        self.client_repo.update(
+        let client = self.client_repo.update(
             user_id,
             client_id,
             update.name,
@@ -65,11 +100,98 @@ impl ClientService {
             update.payment_terms,
             update.tax_exempt,
             update.notes,
-        ).await
+            update.statement_opt_out,
+            update.billing_contacts,
+            update.locale,
+            update.default_tax_id,
+            update.default_currency,
+            update.default_discount_percent,
+            update.hourly_rate,
+        ).await?;
+
+        match update.vat_number {
+            Some(vat_number) if vat_number.is_empty() => self.set_vat_number(user_id, client_id, None).await,
+            Some(vat_number) => self.set_vat_number(user_id, client_id, Some(&vat_number)).await,
+            None => Ok(client),
+        }
+    }
+
+    /// Checks a normalized VAT number against VIES and stores it with the outcome; None
+    /// removes it. When VIES can't answer the number is kept as unverified.
+    pub async fn set_vat_number(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        vat_number: Option<&str>,
+    ) -> Result<Client, sqlx::Error> {
+        let Some(vat_number) = vat_number else {
+            return self.client_repo.set_vat(user_id, client_id, None).await;
+        };
+        let check = match VatNumber::parse(vat_number) {
+            Ok(parsed) => self.vat_validator.check(&parsed).await.unwrap_or_else(|e| {
+                tracing::warn!("⚠️ VAT number {} could not be checked: {}", vat_number, e);
+                VatCheck { status: VatStatus::Unverified, registered_name: None }
+            }),
+            Err(_) => VatCheck { status: VatStatus::Invalid, registered_name: None },
+        };
+        self.client_repo.set_vat(user_id, client_id, Some((vat_number, &check))).await
+    }
+
+    /// Whether `tax_id` is one of the user's active tax settings
+    pub async fn has_tax_setting(&self, user_id: Uuid, tax_id: Uuid) -> bool {
+        self.tax_service.get_tax_setting(user_id, tax_id).await.is_ok_and(|tax| tax.is_active)
+    }
+
+    /// What a new invoice for `client` issued on `issue_date` starts with
+    pub async fn invoice_defaults(
+        &self,
+        user_id: Uuid,
+        client: &Client,
+        issue_date: NaiveDate,
+    ) -> Result<ClientInvoiceDefaults, sqlx::Error> {
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(sqlx::Error::RowNotFound)?;
+        let client_tax = match client.default_tax_id {
+            Some(tax_id) => self.tax_service.get_tax_setting(user_id, tax_id).await.ok(),
+            None => None,
+        };
+        let user_tax = self.tax_service.get_default_tax(user_id).await
+            .map_err(|e| sqlx::Error::Protocol(e.to_string()))?;
+
+        let seller_country = seller_vat_country(&user);
+        Ok(ClientInvoiceDefaults::resolve(
+            client,
+            issue_date,
+            &user.currency,
+            user.locale,
+            seller_country.as_deref(),
+            client_tax,
+            user_tax,
+        ))
+    }
+
+    pub async fn count_active_invoices(&self, user_id: Uuid, client_id: Uuid) -> Result<i64, sqlx::Error> {
+        self.client_repo.count_active_invoices(user_id, client_id).await
+    }
+
+    pub async fn count_subsidiaries(&self, user_id: Uuid, client_id: Uuid) -> Result<i64, sqlx::Error> {
+        self.client_repo.count_subsidiaries(user_id, client_id).await
     }
 
+    /// Soft delete; see [`ClientRepository::soft_delete`]
     pub async fn delete_client(&self, user_id: Uuid, client_id: Uuid) -> Result<(), sqlx::Error> {
-        self.client_repo.delete(user_id, client_id).await
+        self.client_repo.soft_delete(user_id, client_id).await
+    }
+
+    pub async fn set_archived(&self, user_id: Uuid, client_id: Uuid, archived: bool) -> Result<Client, sqlx::Error> {
+        self.client_repo.set_archived(user_id, client_id, archived).await
+    }
+
+    pub async fn restore_client(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, sqlx::Error> {
+        self.client_repo.restore(user_id, client_id).await
+    }
+
+    pub async fn list_deleted_clients(&self, user_id: Uuid) -> Result<Vec<Client>, sqlx::Error> {
+        self.client_repo.list_deleted(user_id).await
     }
 
     pub async fn get_stats(&self, user_id: Uuid) -> Result<ClientStats, sqlx::Error> {
@@ -83,4 +205,46 @@ impl ClientService {
     ) -> Result<Vec<crate::domain::models::InvoiceResponse>, sqlx::Error> {
         self.client_repo.get_client_invoices(user_id, client_id).await
     }
+
+    pub async fn set_parent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        parent_client_id: Option<Uuid>,
+    ) -> Result<Client, sqlx::Error> {
+        self.client_repo.set_parent(user_id, client_id, parent_client_id).await
+    }
+
+    /// The client and all of its subsidiaries
+    pub async fn get_hierarchy_ids(&self, user_id: Uuid, client_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        self.client_repo.get_hierarchy_ids(user_id, client_id).await
+    }
+
+    pub async fn get_hierarchy_statement(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+    ) -> Result<Option<ClientHierarchyStatement>, sqlx::Error> {
+        let client = match self.client_repo.find_by_id(user_id, client_id).await? {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+
+        let client_ids = self.client_repo.get_hierarchy_ids(user_id, client_id).await?;
+        let balances = self.client_repo.get_balances(user_id, &client_ids).await?;
+        let invoices = self.client_repo.get_invoices_for_clients(user_id, &client_ids).await?;
+
+        let total_invoiced: Decimal = balances.iter().map(|b| b.total_invoiced).sum();
+        let total_paid: Decimal = balances.iter().map(|b| b.total_paid).sum();
+
+        Ok(Some(ClientHierarchyStatement {
+            client_id: client.id,
+            name: client.name,
+            total_invoiced,
+            total_paid,
+            outstanding_balance: total_invoiced - total_paid,
+            balances,
+            invoices,
+        }))
+    }
 }
@@ -0,0 +1,276 @@
+use chrono::NaiveDate;
+use csv::Writer;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::ValidateEmail;
+
+use crate::domain::models::{AccountStatement, Client};
+use crate::domain::services::{EmailJobType, EmailQueueService, PdfError, PdfService, StatementPdf};
+use crate::infrastructure::repositories::{ClientCreditRepository, ClientRepository, UserRepository};
+
+/// Longest period one statement covers
+pub const MAX_STATEMENT_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Error)]
+pub enum ClientStatementError {
+    #[error("Client not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Email error: {0}")]
+    Email(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ClientStatementError {
+    fn from(err: sqlx::Error) -> Self {
+        ClientStatementError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<PdfError> for ClientStatementError {
+    fn from(err: PdfError) -> Self {
+        ClientStatementError::Export(err.to_string())
+    }
+}
+
+/// Statements of account: every invoice, payment and applied credit of one client
+/// over a period, as JSON, PDF or CSV, or emailed to the client
+pub struct ClientStatementService {
+    client_repo: ClientRepository,
+    client_credit: ClientCreditRepository,
+    user_repo: UserRepository,
+    pdf_service: PdfService,
+    email_queue: Arc<EmailQueueService>,
+}
+
+impl ClientStatementService {
+    pub fn new(
+        client_repo: ClientRepository,
+        client_credit: ClientCreditRepository,
+        user_repo: UserRepository,
+        pdf_service: PdfService,
+        email_queue: Arc<EmailQueueService>,
+    ) -> Self {
+        Self { client_repo, client_credit, user_repo, pdf_service, email_queue }
+    }
+
+    pub async fn statement(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<AccountStatement, ClientStatementError> {
+        let client = self.client(user_id, client_id).await?;
+        self.build(user_id, &client, start_date, end_date).await
+    }
+
+    pub async fn pdf(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<u8>, ClientStatementError> {
+        let client = self.client(user_id, client_id).await?;
+        let statement = self.build(user_id, &client, start_date, end_date).await?;
+        self.render_pdf(user_id, &client, &statement).await
+    }
+
+    pub async fn csv(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<u8>, ClientStatementError> {
+        let statement = self.statement(user_id, client_id, start_date, end_date).await?;
+        statement_csv(&statement).map_err(|e| ClientStatementError::Export(e.to_string()))
+    }
+
+    /// Emails the statement PDF to `to`, or to the client's own address
+    pub async fn email(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        to: Option<String>,
+    ) -> Result<(String, AccountStatement), ClientStatementError> {
+        let client = self.client(user_id, client_id).await?;
+        let to_email = to
+            .map(|email| email.trim().to_string())
+            .or_else(|| client.email.clone())
+            .filter(|email| !email.is_empty())
+            .ok_or_else(|| ClientStatementError::Validation("Client has no email address; pass `to`".to_string()))?;
+        if !to_email.validate_email() {
+            return Err(ClientStatementError::Validation(format!("Invalid email address: {}", to_email)));
+        }
+
+        let statement = self.build(user_id, &client, start_date, end_date).await?;
+        let pdf = self.render_pdf(user_id, &client, &statement).await?;
+        let seller_name = self
+            .user_repo
+            .find_by_id(user_id)
+            .await?
+            .map(|user| user.company_name.unwrap_or(user.email))
+            .unwrap_or_default();
+
+        self.email_queue
+            .enqueue_now(EmailJobType::SendAccountStatement {
+                to_email: to_email.clone(),
+                to_name: client.name.clone(),
+                seller_name,
+                period_label: statement.period_label(),
+                closing_balance: statement.closing_balance,
+                pdf_bytes: pdf,
+            })
+            .await
+            .map_err(|e| ClientStatementError::Email(e.to_string()))?;
+
+        Ok((to_email, statement))
+    }
+
+    async fn client(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, ClientStatementError> {
+        self.client_repo
+            .find_by_id(user_id, client_id)
+            .await?
+            .ok_or(ClientStatementError::NotFound)
+    }
+
+    async fn build(
+        &self,
+        user_id: Uuid,
+        client: &Client,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<AccountStatement, ClientStatementError> {
+        if end_date < start_date {
+            return Err(ClientStatementError::Validation("end_date must not be before start_date".to_string()));
+        }
+        if (end_date - start_date).num_days() > MAX_STATEMENT_DAYS {
+            return Err(ClientStatementError::Validation("A statement can cover at most five years".to_string()));
+        }
+
+        let entries = self.client_repo.get_statement_entries(user_id, client.id, end_date).await?;
+        let mut statement = AccountStatement::build(
+            client.id,
+            client.name.clone(),
+            client.email.clone(),
+            start_date,
+            end_date,
+            entries,
+        );
+        statement.credit_balances = self.client_credit.balances(user_id, client.id, Some(end_date)).await?;
+        statement.credit_entries = self
+            .client_credit
+            .list(user_id, client.id)
+            .await?
+            .into_iter()
+            .filter(|entry| entry.entry_date >= start_date && entry.entry_date <= end_date)
+            .collect();
+        Ok(statement)
+    }
+
+    async fn render_pdf(
+        &self,
+        user_id: Uuid,
+        client: &Client,
+        statement: &AccountStatement,
+    ) -> Result<Vec<u8>, ClientStatementError> {
+        let user = self.user_repo.find_by_id(user_id).await?;
+        let company_address = user.as_ref().and_then(|u| u.business_address.as_ref()).map(|addr| {
+            format!("{}, {}, {} {}", addr.street, addr.city, addr.state, addr.zip_code)
+        });
+        let client_address = client.billing_address.as_ref().map(|addr| addr.to_string());
+
+        Ok(self.pdf_service.generate_statement_pdf(&StatementPdf {
+            statement,
+            company_name: user.as_ref().and_then(|u| u.company_name.as_deref()),
+            company_address: company_address.as_deref(),
+            client_address: client_address.as_deref(),
+        })?)
+    }
+}
+
+/// Opening balance, one row per transaction, the closing balance, then any credit
+/// the client holds
+pub fn statement_csv(statement: &AccountStatement) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut wtr = Writer::from_writer(vec![]);
+    wtr.write_record(["Date", "Type", "Reference", "Description", "Debit", "Credit", "Balance"])?;
+
+    let start = statement.start_date.to_string();
+    let opening = format!("{:.2}", statement.opening_balance);
+    wtr.write_record([start.as_str(), "opening_balance", "", "Opening balance", "", "", opening.as_str()])?;
+
+    for line in &statement.lines {
+        wtr.write_record([
+            line.date.to_string(),
+            line.kind.as_str().to_string(),
+            line.reference.clone(),
+            line.description.clone(),
+            format!("{:.2}", line.debit),
+            format!("{:.2}", line.credit),
+            format!("{:.2}", line.balance),
+        ])?;
+    }
+
+    let end = statement.end_date.to_string();
+    let closing = format!("{:.2}", statement.closing_balance);
+    wtr.write_record([end.as_str(), "closing_balance", "", "Closing balance", "", "", closing.as_str()])?;
+    for credit in &statement.credit_balances {
+        let description = format!("Credit available ({})", credit.currency);
+        let amount = format!("{:.2}", credit.amount);
+        wtr.write_record([end.as_str(), "credit_balance", "", description.as_str(), "", "", amount.as_str()])?;
+    }
+
+    Ok(wtr.into_inner()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{StatementEntry, StatementEntryKind};
+
+    #[test]
+    fn test_statement_csv_brackets_transactions_with_balances() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 31).unwrap();
+        let entry = |day: u32, kind, reference: &str, debit, credit| StatementEntry {
+            date: NaiveDate::from_ymd_opt(2025, 3, day).unwrap(),
+            kind,
+            reference: reference.to_string(),
+            description: "Invoice, due 2025-04-01".to_string(),
+            invoice_id: Uuid::new_v4(),
+            debit,
+            credit,
+        };
+        let statement = AccountStatement::build(
+            Uuid::new_v4(),
+            "Acme".to_string(),
+            None,
+            start,
+            end,
+            vec![
+                entry(2, StatementEntryKind::Invoice, "INV-1", 100.0, 0.0),
+                entry(9, StatementEntryKind::Payment, "INV-1", 0.0, 40.0),
+            ],
+        );
+
+        let csv = String::from_utf8(statement_csv(&statement).unwrap()).unwrap();
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(rows.len(), 5);
+        assert_eq!(rows[1], "2025-03-01,opening_balance,,Opening balance,,,0.00");
+        assert_eq!(rows[2], "2025-03-02,invoice,INV-1,\"Invoice, due 2025-04-01\",100.00,0.00,100.00");
+        assert_eq!(rows[4], "2025-03-31,closing_balance,,Closing balance,,,60.00");
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use std::sync::Arc;
+
+/// Source of the current time. Services take a clock instead of calling
+/// `Utc::now()` so time-dependent logic can be tested deterministically.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time, used in production
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually controlled clock for tests
+#[cfg(test)]
+pub struct MockClock {
+    now: std::sync::Mutex<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn at(now: DateTime<Utc>) -> Arc<Self> {
+        Arc::new(Self { now: std::sync::Mutex::new(now) })
+    }
+
+    /// Fixed, well-known instant: 2025-01-15 09:00:00 UTC
+    pub fn default_start() -> Arc<Self> {
+        Self::at(
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 15)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap()
+                .and_utc(),
+        )
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += by;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_time_travel() {
+        let clock = MockClock::default_start();
+        let start = clock.now();
+
+        clock.advance(chrono::Duration::days(20));
+        assert_eq!(clock.now() - start, chrono::Duration::days(20));
+        assert_eq!(clock.today(), NaiveDate::from_ymd_opt(2025, 2, 4).unwrap());
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
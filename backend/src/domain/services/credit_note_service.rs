@@ -0,0 +1,252 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use thiserror::Error;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domain::models::{
+    CreateCreditNote, CreditNote, CreditNoteListFilter, CreditNoteStatus, CurrencyRoundingRules, InvoiceItem,
+    InvoiceStatus, InvoiceTotals, LineInput, LineTax, RoundingPolicy, TaxComponent,
+};
+use crate::domain::services::{CreditNotePdf, InvoiceItemPdf, PdfError, PdfService, SharedClock};
+use crate::infrastructure::repositories::{
+    ClientRepository, CreditNoteRepository, InvoiceRepository, IssueCreditNote, NewCreditNote, UserRepository,
+};
+
+#[derive(Debug, Error)]
+pub enum CreditNoteError {
+    #[error("Credit note not found")]
+    NotFound,
+
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Validation error: {0}")]
+    InvalidFields(#[from] validator::ValidationErrors),
+
+    #[error("PDF generation error: {0}")]
+    PdfError(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for CreditNoteError {
+    fn from(err: sqlx::Error) -> Self {
+        CreditNoteError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<PdfError> for CreditNoteError {
+    fn from(err: PdfError) -> Self {
+        CreditNoteError::PdfError(err.to_string())
+    }
+}
+
+/// Credit notes for returned goods or over-billing, each tied to an issued invoice
+pub struct CreditNoteService {
+    repo: CreditNoteRepository,
+    invoice_repo: InvoiceRepository,
+    client_repo: ClientRepository,
+    user_repo: UserRepository,
+    pdf_service: PdfService,
+    rounding_rules: CurrencyRoundingRules,
+    clock: SharedClock,
+}
+
+impl CreditNoteService {
+    pub fn new(
+        repo: CreditNoteRepository,
+        invoice_repo: InvoiceRepository,
+        client_repo: ClientRepository,
+        user_repo: UserRepository,
+        pdf_service: PdfService,
+        clock: SharedClock,
+    ) -> Self {
+        Self {
+            repo,
+            invoice_repo,
+            client_repo,
+            user_repo,
+            pdf_service,
+            rounding_rules: CurrencyRoundingRules::from_env(),
+            clock,
+        }
+    }
+
+    pub async fn create(&self, user_id: Uuid, create: CreateCreditNote) -> Result<CreditNote, CreditNoteError> {
+        create.validate()?;
+
+        let invoice = match self.invoice_repo.get_by_id(user_id, create.invoice_id).await {
+            Ok(invoice) => invoice,
+            Err(sqlx::Error::RowNotFound) => return Err(CreditNoteError::InvoiceNotFound),
+            Err(e) => return Err(e.into()),
+        };
+        match invoice.status {
+            InvoiceStatus::Sent | InvoiceStatus::Viewed | InvoiceStatus::Partial
+            | InvoiceStatus::Paid | InvoiceStatus::Overdue => {}
+            InvoiceStatus::Draft => {
+                return Err(CreditNoteError::Validation("Draft invoices can be edited instead of credited".to_string()));
+            }
+            _ => {
+                return Err(CreditNoteError::Validation(format!("A {} invoice can't be credited", invoice.status)));
+            }
+        }
+
+        // Without explicit lines the whole invoice is credited, discount included
+        let (lines, discount, sections, taxes) = match create.items {
+            Some(items) if items.is_empty() => {
+                return Err(CreditNoteError::Validation("A credit note needs at least one item".to_string()));
+            }
+            Some(items) => {
+                for item in &items {
+                    TaxComponent::validate_all(&item.taxes).map_err(CreditNoteError::Validation)?;
+                }
+                let taxes: Vec<Vec<TaxComponent>> = items.iter().map(|item| item.taxes.clone()).collect();
+                let lines: Vec<(String, LineInput)> = items.into_iter()
+                    .map(|item| (item.description, LineInput {
+                        quantity: item.quantity,
+                        unit_price: item.unit_price,
+                        tax_rate: if item.taxes.is_empty() {
+                            item.tax_rate.unwrap_or_default()
+                        } else {
+                            TaxComponent::combined_rate(&item.taxes)
+                        },
+                    }))
+                    .collect();
+                (lines, Decimal::ZERO, Vec::new(), taxes)
+            }
+            None => {
+                let lines = invoice.items.iter()
+                    .map(|item| (item.description.clone(), LineInput {
+                        quantity: item.quantity,
+                        unit_price: item.unit_price,
+                        tax_rate: item.tax_rate,
+                    }))
+                    .collect();
+                let sections = invoice.items.iter().map(|item| item.section.clone()).collect();
+                let taxes = invoice.items.iter()
+                    .map(|item| item.taxes.iter().map(LineTax::component).collect())
+                    .collect();
+                (lines, invoice.discount_amount, sections, taxes)
+            }
+        };
+
+        let inputs: Vec<LineInput> = lines.iter().map(|(_, line)| *line).collect();
+        let rounding = self.rounding_rules.for_currency(&invoice.currency);
+        let totals = InvoiceTotals::calculate(
+            &inputs,
+            discount,
+            invoice.tax_included,
+            RoundingPolicy::PerLine,
+            rounding,
+        );
+        if totals.total <= Decimal::ZERO {
+            return Err(CreditNoteError::Validation("Credit note total must be greater than zero".to_string()));
+        }
+
+        let items: Vec<InvoiceItem> = lines.into_iter()
+            .zip(&totals.lines)
+            .enumerate()
+            .map(|(i, ((description, line), amounts))| InvoiceItem {
+                id: Uuid::new_v4(),
+                description,
+                quantity: line.quantity,
+                unit_price: line.unit_price,
+                tax_rate: line.tax_rate,
+                tax_amount: amounts.tax_amount,
+                total: amounts.total,
+                section: sections.get(i).cloned().flatten(),
+                taxes: taxes
+                    .get(i)
+                    .map(|components| TaxComponent::split(components, amounts.tax_amount, rounding))
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        let reason = create.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+        let issued = self.repo.issue(
+            user_id,
+            NewCreditNote {
+                invoice_id: invoice.id,
+                reason,
+                issue_date: create.issue_date.unwrap_or_else(|| self.clock.today()),
+                items: &items,
+                subtotal: totals.subtotal,
+                tax_amount: totals.tax_amount,
+                discount_amount: totals.discount,
+                total_amount: totals.total,
+            },
+            self.clock.today(),
+        ).await?;
+
+        match issued {
+            Some(IssueCreditNote::Issued(credit_note)) => Ok(*credit_note),
+            Some(IssueCreditNote::ExceedsInvoice(remaining)) => Err(CreditNoteError::Validation(format!(
+                "Credit of {:.2} exceeds the {:.2} still creditable on invoice {}",
+                totals.total, remaining, invoice.invoice_number
+            ))),
+            None => Err(CreditNoteError::InvoiceNotFound),
+        }
+    }
+
+    pub async fn get(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<CreditNote, CreditNoteError> {
+        self.repo.find_by_id(user_id, credit_note_id).await?.ok_or(CreditNoteError::NotFound)
+    }
+
+    pub async fn list(&self, user_id: Uuid, filter: CreditNoteListFilter) -> Result<Vec<CreditNote>, CreditNoteError> {
+        Ok(self.repo.list(user_id, &filter).await?)
+    }
+
+    /// Withdraw a credit note; the invoice owes its applied credit again
+    pub async fn void(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<CreditNote, CreditNoteError> {
+        if let Some(credit_note) = self.repo.void(user_id, credit_note_id, self.clock.today()).await? {
+            return Ok(credit_note);
+        }
+        match self.repo.find_by_id(user_id, credit_note_id).await? {
+            Some(_) => Err(CreditNoteError::Validation("Credit note is already void".to_string())),
+            None => Err(CreditNoteError::NotFound),
+        }
+    }
+
+    pub async fn pdf(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<Vec<u8>, CreditNoteError> {
+        let credit_note = self.get(user_id, credit_note_id).await?;
+
+        let user = self.user_repo.find_by_id(user_id)
+            .await?
+            .ok_or(CreditNoteError::Validation("User not found".to_string()))?;
+        let client = self.client_repo.find_by_id(user_id, credit_note.client_id).await?;
+
+        let items: Vec<InvoiceItemPdf> = credit_note.items.iter().map(InvoiceItemPdf::from).collect();
+
+        let company_address = user.business_address.as_ref().map(|addr| {
+            format!("{}, {}, {} {}", addr.street, addr.city, addr.state, addr.zip_code)
+        });
+        let client_address = client.as_ref()
+            .and_then(|c| c.billing_address.as_ref())
+            .map(|addr| addr.to_string());
+
+        Ok(self.pdf_service.generate_credit_note_pdf(&CreditNotePdf {
+            credit_note_number: &credit_note.credit_note_number,
+            invoice_number: &credit_note.invoice_number,
+            company_name: user.company_name.as_deref(),
+            company_address: company_address.as_deref(),
+            client_name: &credit_note.client_name,
+            client_email: client.as_ref().and_then(|c| c.email.as_deref()),
+            client_address: client_address.as_deref(),
+            issue_date: &credit_note.issue_date.to_string(),
+            reason: credit_note.reason.as_deref(),
+            items: &items,
+            subtotal: credit_note.subtotal.to_f64().unwrap_or_default(),
+            tax_amount: credit_note.tax_amount.to_f64().unwrap_or_default(),
+            discount: credit_note.discount_amount.to_f64().unwrap_or_default(),
+            total: credit_note.total_amount.to_f64().unwrap_or_default(),
+            refund_amount: credit_note.refund_amount.to_f64().unwrap_or_default(),
+            currency: &credit_note.currency,
+            void: credit_note.status == CreditNoteStatus::Void,
+        })?)
+    }
+}
@@ -0,0 +1,79 @@
+use futures::{stream::BoxStream, StreamExt};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::CustomReportRequest;
+use crate::infrastructure::repositories::CustomReportRepository;
+
+#[derive(Debug, Error)]
+pub enum CustomReportError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("CSV error: {0}")]
+    Csv(String),
+}
+
+impl From<sqlx::Error> for CustomReportError {
+    fn from(err: sqlx::Error) -> Self {
+        CustomReportError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<csv::Error> for CustomReportError {
+    fn from(err: csv::Error) -> Self {
+        CustomReportError::Csv(err.to_string())
+    }
+}
+
+/// Ad-hoc CSV exports built from a whitelist of columns per entity
+pub struct CustomReportService {
+    repo: CustomReportRepository,
+}
+
+impl CustomReportService {
+    pub fn new(repo: CustomReportRepository) -> Self {
+        Self { repo }
+    }
+
+    /// Validates the request up front, then returns the CSV as a stream of chunks:
+    /// the header line followed by one chunk per row
+    pub fn csv_stream(
+        &self,
+        user_id: Uuid,
+        request: &CustomReportRequest,
+    ) -> Result<BoxStream<'static, Result<Vec<u8>, CustomReportError>>, CustomReportError> {
+        let spec = request.validate().map_err(CustomReportError::Validation)?;
+        let header = csv_line(spec.headers());
+
+        let rows = self.repo.stream(user_id, &spec).map(|row| {
+            let row = row?;
+            csv_line(row.iter().map(|value| value.as_deref().unwrap_or("")))
+        });
+        Ok(futures::stream::once(async move { header }).chain(rows).boxed())
+    }
+}
+
+fn csv_line<I, T>(record: I) -> Result<Vec<u8>, CustomReportError>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(record)?;
+    writer.into_inner().map_err(|e| CustomReportError::Csv(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_lines_are_quoted_when_needed() {
+        let line = csv_line(["Acme, Inc.", "say \"hi\"", ""]).unwrap();
+        assert_eq!(String::from_utf8(line).unwrap(), "\"Acme, Inc.\",\"say \"\"hi\"\"\",\n");
+    }
+}
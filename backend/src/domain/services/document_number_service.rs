@@ -0,0 +1,169 @@
+use std::sync::Arc;
+use chrono::Datelike;
+use sqlx::{Postgres, Transaction};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    DocumentNumberFormat, DocumentType, UpdateDocumentNumberFormat, CONTINUOUS_SEQUENCE_YEAR,
+    DEFAULT_DOCUMENT_NUMBER_PADDING,
+};
+use crate::domain::services::SharedClock;
+use crate::infrastructure::repositories::DocumentNumberRepository;
+
+#[derive(Debug, Error)]
+pub enum DocumentNumberError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for DocumentNumberError {
+    fn from(err: sqlx::Error) -> Self {
+        DocumentNumberError::DatabaseError(err.to_string())
+    }
+}
+
+/// Effective prefix, padding and reset policy for one user's document type
+struct ResolvedFormat {
+    prefix: String,
+    padding: i32,
+    yearly_reset: bool,
+}
+
+impl ResolvedFormat {
+    fn sequence_year(&self, year: i32) -> i32 {
+        if self.yearly_reset { year } else { CONTINUOUS_SEQUENCE_YEAR }
+    }
+
+    fn number(&self, year: i32, value: i64) -> String {
+        DocumentNumberFormat::format_number(&self.prefix, self.padding, self.yearly_reset.then_some(year), value)
+    }
+}
+
+/// Issues document numbers from per-user sequences keyed by (user, doc_type, year).
+/// Every document-producing module should take its numbers from here.
+pub struct DocumentNumberService {
+    repo: Arc<DocumentNumberRepository>,
+    clock: SharedClock,
+}
+
+impl DocumentNumberService {
+    pub fn new(repo: Arc<DocumentNumberRepository>, clock: SharedClock) -> Self {
+        Self { repo, clock }
+    }
+
+    fn current_year(&self) -> i32 {
+        self.clock.today().year()
+    }
+
+    async fn resolve_format(&self, user_id: Uuid, doc_type: DocumentType) -> Result<ResolvedFormat, sqlx::Error> {
+        let (prefix, padding, yearly_reset) = self
+            .repo
+            .get_format(user_id, doc_type)
+            .await?
+            .unwrap_or_else(|| (doc_type.default_prefix().to_string(), DEFAULT_DOCUMENT_NUMBER_PADDING, true));
+
+        Ok(ResolvedFormat { prefix, padding, yearly_reset })
+    }
+
+    /// Reserve and return the next number, e.g. `INV-2026-0042`
+    pub async fn next_number(&self, user_id: Uuid, doc_type: DocumentType) -> Result<String, sqlx::Error> {
+        let mut tx = self.repo.begin().await?;
+        let number = self.next_number_in(&mut tx, user_id, doc_type).await?;
+        tx.commit().await?;
+
+        Ok(number)
+    }
+
+    /// Reserve the next number as part of the transaction that stores the document,
+    /// so a rolled back document gives its number back
+    pub async fn next_number_in(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        doc_type: DocumentType,
+    ) -> Result<String, sqlx::Error> {
+        let year = self.current_year();
+        let format = self.resolve_format(user_id, doc_type).await?;
+        let value = self.repo.next_value(tx, user_id, doc_type, format.sequence_year(year)).await?;
+
+        Ok(format.number(year, value))
+    }
+
+    pub async fn get_format(&self, user_id: Uuid, doc_type: DocumentType) -> Result<DocumentNumberFormat, sqlx::Error> {
+        let year = self.current_year();
+        let format = self.resolve_format(user_id, doc_type).await?;
+        let next_number = self.repo.last_value(user_id, doc_type, format.sequence_year(year)).await? + 1;
+
+        Ok(DocumentNumberFormat {
+            doc_type,
+            next_preview: format.number(year, next_number),
+            prefix: format.prefix,
+            padding: format.padding,
+            yearly_reset: format.yearly_reset,
+            year,
+            next_number,
+        })
+    }
+
+    pub async fn list_formats(&self, user_id: Uuid) -> Result<Vec<DocumentNumberFormat>, sqlx::Error> {
+        let mut formats = Vec::with_capacity(DocumentType::ALL.len());
+        for doc_type in DocumentType::ALL {
+            formats.push(self.get_format(user_id, doc_type).await?);
+        }
+        Ok(formats)
+    }
+
+    pub async fn update_format(
+        &self,
+        user_id: Uuid,
+        doc_type: DocumentType,
+        update: UpdateDocumentNumberFormat,
+    ) -> Result<DocumentNumberFormat, DocumentNumberError> {
+        let current = self.get_format(user_id, doc_type).await?;
+
+        let prefix = update.prefix.map(|p| p.trim().to_uppercase()).unwrap_or(current.prefix);
+        if prefix.is_empty()
+            || prefix.len() > 20
+            || !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(DocumentNumberError::Validation(
+                "Prefix must be 1-20 letters, digits, '-' or '_'".to_string(),
+            ));
+        }
+
+        let padding = update.padding.unwrap_or(current.padding);
+        if !(1..=10).contains(&padding) {
+            return Err(DocumentNumberError::Validation(
+                "Padding must be between 1 and 10".to_string(),
+            ));
+        }
+
+        if let Some(next_number) = update.next_number {
+            if next_number < current.next_number {
+                return Err(DocumentNumberError::Validation(format!(
+                    "Next number cannot go below {}",
+                    current.next_number
+                )));
+            }
+        }
+
+        let yearly_reset = update.yearly_reset.unwrap_or(current.yearly_reset);
+        self.repo.upsert_format(user_id, doc_type, &prefix, padding, yearly_reset).await?;
+
+        let format = ResolvedFormat { prefix, padding, yearly_reset };
+        let sequence_year = format.sequence_year(current.year);
+        if yearly_reset != current.yearly_reset {
+            // Carry on from the last number issued instead of starting over at 1
+            self.repo.set_last_value(user_id, doc_type, sequence_year, current.next_number - 1).await?;
+        }
+        if let Some(next_number) = update.next_number {
+            self.repo.set_last_value(user_id, doc_type, sequence_year, next_number - 1).await?;
+        }
+
+        Ok(self.get_format(user_id, doc_type).await?)
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{EInvoice, EInvoiceFormat, InvoiceStatus};
+use crate::domain::services::e_invoice_writer::{cii_invoice, embed_factur_x, ubl_invoice};
+use crate::domain::services::{InvoiceError, InvoiceService};
+use crate::infrastructure::repositories::{ClientRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum EInvoiceError {
+    #[error("{0}")]
+    InvalidStatus(String),
+
+    /// Mandatory EN 16931 fields the invoice, seller or client are missing
+    #[error("Invoice {invoice_number} can't be exported as {format}: {}", problems.join("; "))]
+    NotExportable { invoice_number: String, format: &'static str, problems: Vec<String> },
+
+    #[error("Invoice error: {0}")]
+    Invoice(#[from] InvoiceError),
+
+    #[error("Export error: {0}")]
+    Export(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for EInvoiceError {
+    fn from(err: sqlx::Error) -> Self {
+        EInvoiceError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<lopdf::Error> for EInvoiceError {
+    fn from(err: lopdf::Error) -> Self {
+        EInvoiceError::Export(err.to_string())
+    }
+}
+
+/// A structured e-invoice file
+pub struct EInvoiceFile {
+    pub file_name: String,
+    pub content_type: &'static str,
+    pub content: Vec<u8>,
+}
+
+/// EN 16931 e-invoices: UBL 2.1 XML, or Factur-X, the invoice PDF with its
+/// CII XML attached
+pub struct EInvoiceService {
+    invoice_service: Arc<InvoiceService>,
+    client_repo: ClientRepository,
+    user_repo: UserRepository,
+}
+
+impl EInvoiceService {
+    pub fn new(invoice_service: Arc<InvoiceService>, client_repo: ClientRepository, user_repo: UserRepository) -> Self {
+        Self { invoice_service, client_repo, user_repo }
+    }
+
+    /// Checks the mandatory fields first and lists every one that's missing
+    pub async fn export(&self, user_id: Uuid, invoice_id: Uuid, format: EInvoiceFormat) -> Result<EInvoiceFile, EInvoiceError> {
+        let detail = self.invoice_service.get_invoice(user_id, invoice_id).await?;
+        if matches!(detail.status, InvoiceStatus::Draft | InvoiceStatus::Cancelled | InvoiceStatus::Superseded) {
+            return Err(EInvoiceError::InvalidStatus(format!(
+                "Invoice {} is {} and can't be exported as an e-invoice",
+                detail.invoice_number, detail.status
+            )));
+        }
+
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(InvoiceError::NotFound)?;
+        let client = self.client_repo.find_by_id(user_id, detail.client_id).await?.ok_or(InvoiceError::ClientNotFound)?;
+
+        let invoice = EInvoice::new(&detail, &user, &client);
+        invoice.validate().map_err(|problems| EInvoiceError::NotExportable {
+            invoice_number: detail.invoice_number.clone(),
+            format: format.name(),
+            problems,
+        })?;
+
+        let content = match format {
+            EInvoiceFormat::Ubl => ubl_invoice(&invoice).into_bytes(),
+            EInvoiceFormat::Facturx => {
+                let pdf = self.invoice_service.get_invoice_pdf(user_id, invoice_id, false).await?;
+                embed_factur_x(&pdf.content, &cii_invoice(&invoice), &invoice.number)?
+            }
+        };
+
+        Ok(EInvoiceFile {
+            file_name: format!("{}.{}", detail.invoice_number, format.extension()),
+            content_type: format.content_type(),
+            content,
+        })
+    }
+}
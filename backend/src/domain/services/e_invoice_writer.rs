@@ -0,0 +1,497 @@
+//! Writes an `EInvoice` as UBL 2.1 or as the CII XML Factur-X embeds, and
+//! attaches that XML to the invoice PDF. Elements follow the order of the
+//! schemas' sequences, which validators check.
+
+use lopdf::{dictionary, Document, Object, Stream};
+use rust_decimal::Decimal;
+
+use crate::domain::models::{EInvoice, EInvoiceParty, VatCategory, EN16931_PROFILE};
+use crate::domain::services::xlsx_writer::xml_escape;
+
+/// Name Factur-X readers look for
+pub const FACTUR_X_FILE_NAME: &str = "factur-x.xml";
+
+/// UN/ECE unit code for a piece
+const UNIT_CODE: &str = "C62";
+/// UNTDID 1001: commercial invoice
+const INVOICE_TYPE_CODE: &str = "380";
+
+pub fn ubl_invoice(invoice: &EInvoice) -> String {
+    let currency = &invoice.currency;
+    let money = |element: &str, value: Decimal| {
+        format!("<cbc:{0} currencyID=\"{1}\">{2}</cbc:{0}>", element, currency, amount(value))
+    };
+
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <Invoice xmlns=\"urn:oasis:names:specification:ubl:schema:xsd:Invoice-2\" \
+         xmlns:cac=\"urn:oasis:names:specification:ubl:schema:xsd:CommonAggregateComponents-2\" \
+         xmlns:cbc=\"urn:oasis:names:specification:ubl:schema:xsd:CommonBasicComponents-2\">",
+    );
+    xml.push_str(&element("cbc:CustomizationID", EN16931_PROFILE));
+    xml.push_str(&element("cbc:ID", &invoice.number));
+    xml.push_str(&element("cbc:IssueDate", &invoice.issue_date.to_string()));
+    xml.push_str(&element("cbc:DueDate", &invoice.due_date.to_string()));
+    xml.push_str(&element("cbc:InvoiceTypeCode", INVOICE_TYPE_CODE));
+    if let Some(note) = &invoice.note {
+        xml.push_str(&element("cbc:Note", note));
+    }
+    xml.push_str(&element("cbc:DocumentCurrencyCode", currency));
+
+    xml.push_str(&format!("<cac:AccountingSupplierParty>{}</cac:AccountingSupplierParty>", ubl_party(&invoice.seller)));
+    xml.push_str(&format!("<cac:AccountingCustomerParty>{}</cac:AccountingCustomerParty>", ubl_party(&invoice.buyer)));
+
+    if let Some(terms) = &invoice.payment_terms {
+        xml.push_str(&format!("<cac:PaymentTerms>{}</cac:PaymentTerms>", element("cbc:Note", terms)));
+    }
+    if let Some((category, rate)) = invoice.allowance_category() {
+        xml.push_str(&format!(
+            "<cac:AllowanceCharge><cbc:ChargeIndicator>false</cbc:ChargeIndicator>{}{}{}</cac:AllowanceCharge>",
+            element("cbc:AllowanceChargeReason", "Discount"),
+            money("Amount", invoice.allowance),
+            ubl_tax_category("cac:TaxCategory", category, rate, false),
+        ));
+    }
+
+    xml.push_str(&format!("<cac:TaxTotal>{}", money("TaxAmount", invoice.tax_amount)));
+    for vat in invoice.vat_breakdown() {
+        xml.push_str(&format!(
+            "<cac:TaxSubtotal>{}{}{}</cac:TaxSubtotal>",
+            money("TaxableAmount", vat.taxable_amount),
+            money("TaxAmount", vat.tax_amount),
+            ubl_tax_category("cac:TaxCategory", vat.category, vat.rate, true),
+        ));
+    }
+    xml.push_str("</cac:TaxTotal>");
+
+    xml.push_str("<cac:LegalMonetaryTotal>");
+    xml.push_str(&money("LineExtensionAmount", invoice.line_total()));
+    xml.push_str(&money("TaxExclusiveAmount", invoice.tax_exclusive_total()));
+    xml.push_str(&money("TaxInclusiveAmount", invoice.tax_inclusive_total()));
+    if invoice.allowance > Decimal::ZERO {
+        xml.push_str(&money("AllowanceTotalAmount", invoice.allowance));
+    }
+    if invoice.prepaid > Decimal::ZERO {
+        xml.push_str(&money("PrepaidAmount", invoice.prepaid));
+    }
+    xml.push_str(&money("PayableAmount", invoice.payable()));
+    xml.push_str("</cac:LegalMonetaryTotal>");
+
+    for (index, line) in invoice.lines.iter().enumerate() {
+        xml.push_str(&format!(
+            "<cac:InvoiceLine>{}<cbc:InvoicedQuantity unitCode=\"{}\">{}</cbc:InvoicedQuantity>{}\
+             <cac:Item>{}{}</cac:Item>\
+             <cac:Price>{}<cbc:BaseQuantity unitCode=\"{}\">{}</cbc:BaseQuantity></cac:Price></cac:InvoiceLine>",
+            element("cbc:ID", &(index + 1).to_string()),
+            UNIT_CODE,
+            line.quantity.normalize(),
+            money("LineExtensionAmount", line.net_amount),
+            element("cbc:Name", &line.description),
+            ubl_tax_category("cac:ClassifiedTaxCategory", line.category, line.rate, false),
+            money("PriceAmount", line.price),
+            UNIT_CODE,
+            line.base_quantity.normalize(),
+        ));
+    }
+
+    xml.push_str("</Invoice>\n");
+    xml
+}
+
+fn ubl_party(party: &EInvoiceParty) -> String {
+    let mut xml = String::from("<cac:Party><cac:PostalAddress>");
+    for (name, value) in [
+        ("cbc:StreetName", &party.street),
+        ("cbc:CityName", &party.city),
+        ("cbc:PostalZone", &party.postal_code),
+        ("cbc:CountrySubentity", &party.region),
+    ] {
+        if let Some(value) = value {
+            xml.push_str(&element(name, value));
+        }
+    }
+    if let Some(country) = &party.country {
+        xml.push_str(&format!("<cac:Country>{}</cac:Country>", element("cbc:IdentificationCode", country)));
+    }
+    xml.push_str("</cac:PostalAddress>");
+    if let Some(vat_number) = &party.vat_number {
+        xml.push_str(&format!(
+            "<cac:PartyTaxScheme>{}<cac:TaxScheme><cbc:ID>VAT</cbc:ID></cac:TaxScheme></cac:PartyTaxScheme>",
+            element("cbc:CompanyID", vat_number)
+        ));
+    }
+    xml.push_str(&format!("<cac:PartyLegalEntity>{}</cac:PartyLegalEntity>", element("cbc:RegistrationName", &party.name)));
+    if let Some(email) = &party.email {
+        xml.push_str(&format!("<cac:Contact>{}</cac:Contact>", element("cbc:ElectronicMail", email)));
+    }
+    xml.push_str("</cac:Party>");
+    xml
+}
+
+/// A VAT category; the exemption reason goes only in the breakdown
+fn ubl_tax_category(tag: &str, category: VatCategory, rate: Decimal, with_exemption: bool) -> String {
+    let mut xml = format!("<{}>{}", tag, element("cbc:ID", category.code()));
+    if category.has_rate() {
+        xml.push_str(&element("cbc:Percent", &percent(rate)));
+    }
+    if let Some((code, reason)) = category.exemption().filter(|_| with_exemption) {
+        xml.push_str(&element("cbc:TaxExemptionReasonCode", code));
+        xml.push_str(&element("cbc:TaxExemptionReason", reason));
+    }
+    xml.push_str(&format!("<cac:TaxScheme><cbc:ID>VAT</cbc:ID></cac:TaxScheme></{}>", tag));
+    xml
+}
+
+/// The Cross Industry Invoice (CII) Factur-X carries, in its EN 16931 profile
+pub fn cii_invoice(invoice: &EInvoice) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <rsm:CrossIndustryInvoice xmlns:rsm=\"urn:un:unece:uncefact:data:standard:CrossIndustryInvoice:100\" \
+         xmlns:ram=\"urn:un:unece:uncefact:data:standard:ReusableAggregateBusinessInformationEntity:100\" \
+         xmlns:qdt=\"urn:un:unece:uncefact:data:standard:QualifiedDataType:100\" \
+         xmlns:udt=\"urn:un:unece:uncefact:data:standard:UnqualifiedDataType:100\">",
+    );
+    xml.push_str(&format!(
+        "<rsm:ExchangedDocumentContext><ram:GuidelineSpecifiedDocumentContextParameter>{}\
+         </ram:GuidelineSpecifiedDocumentContextParameter></rsm:ExchangedDocumentContext>",
+        element("ram:ID", EN16931_PROFILE)
+    ));
+
+    xml.push_str("<rsm:ExchangedDocument>");
+    xml.push_str(&element("ram:ID", &invoice.number));
+    xml.push_str(&element("ram:TypeCode", INVOICE_TYPE_CODE));
+    xml.push_str(&format!("<ram:IssueDateTime>{}</ram:IssueDateTime>", cii_date(invoice.issue_date)));
+    if let Some(note) = &invoice.note {
+        xml.push_str(&format!("<ram:IncludedNote>{}</ram:IncludedNote>", element("ram:Content", note)));
+    }
+    xml.push_str("</rsm:ExchangedDocument>");
+
+    xml.push_str("<rsm:SupplyChainTradeTransaction>");
+    for (index, line) in invoice.lines.iter().enumerate() {
+        xml.push_str(&format!(
+            "<ram:IncludedSupplyChainTradeLineItem>\
+             <ram:AssociatedDocumentLineDocument>{}</ram:AssociatedDocumentLineDocument>\
+             <ram:SpecifiedTradeProduct>{}</ram:SpecifiedTradeProduct>\
+             <ram:SpecifiedLineTradeAgreement><ram:NetPriceProductTradePrice>{}\
+             <ram:BasisQuantity unitCode=\"{}\">{}</ram:BasisQuantity></ram:NetPriceProductTradePrice></ram:SpecifiedLineTradeAgreement>\
+             <ram:SpecifiedLineTradeDelivery><ram:BilledQuantity unitCode=\"{}\">{}</ram:BilledQuantity></ram:SpecifiedLineTradeDelivery>\
+             <ram:SpecifiedLineTradeSettlement>{}\
+             <ram:SpecifiedTradeSettlementLineMonetarySummation>{}</ram:SpecifiedTradeSettlementLineMonetarySummation>\
+             </ram:SpecifiedLineTradeSettlement></ram:IncludedSupplyChainTradeLineItem>",
+            element("ram:LineID", &(index + 1).to_string()),
+            element("ram:Name", &line.description),
+            element("ram:ChargeAmount", &amount(line.price)),
+            UNIT_CODE,
+            line.base_quantity.normalize(),
+            UNIT_CODE,
+            line.quantity.normalize(),
+            cii_tax("ram:ApplicableTradeTax", line.category, line.rate),
+            element("ram:LineTotalAmount", &amount(line.net_amount)),
+        ));
+    }
+
+    xml.push_str(&format!(
+        "<ram:ApplicableHeaderTradeAgreement>{}{}</ram:ApplicableHeaderTradeAgreement>",
+        cii_party("ram:SellerTradeParty", &invoice.seller),
+        cii_party("ram:BuyerTradeParty", &invoice.buyer),
+    ));
+    xml.push_str("<ram:ApplicableHeaderTradeDelivery/>");
+
+    xml.push_str("<ram:ApplicableHeaderTradeSettlement>");
+    xml.push_str(&element("ram:InvoiceCurrencyCode", &invoice.currency));
+    for vat in invoice.vat_breakdown() {
+        xml.push_str("<ram:ApplicableTradeTax>");
+        xml.push_str(&element("ram:CalculatedAmount", &amount(vat.tax_amount)));
+        xml.push_str(&element("ram:TypeCode", "VAT"));
+        if let Some((_, reason)) = vat.category.exemption() {
+            xml.push_str(&element("ram:ExemptionReason", reason));
+        }
+        xml.push_str(&element("ram:BasisAmount", &amount(vat.taxable_amount)));
+        xml.push_str(&element("ram:CategoryCode", vat.category.code()));
+        if let Some((code, _)) = vat.category.exemption() {
+            xml.push_str(&element("ram:ExemptionReasonCode", code));
+        }
+        if vat.category.has_rate() {
+            xml.push_str(&element("ram:RateApplicablePercent", &percent(vat.rate)));
+        }
+        xml.push_str("</ram:ApplicableTradeTax>");
+    }
+    if let Some((category, rate)) = invoice.allowance_category() {
+        xml.push_str(&format!(
+            "<ram:SpecifiedTradeAllowanceCharge><ram:ChargeIndicator><udt:Indicator>false</udt:Indicator></ram:ChargeIndicator>\
+             {}{}{}</ram:SpecifiedTradeAllowanceCharge>",
+            element("ram:ActualAmount", &amount(invoice.allowance)),
+            element("ram:Reason", "Discount"),
+            cii_tax("ram:CategoryTradeTax", category, rate),
+        ));
+    }
+    xml.push_str("<ram:SpecifiedTradePaymentTerms>");
+    if let Some(terms) = &invoice.payment_terms {
+        xml.push_str(&element("ram:Description", terms));
+    }
+    xml.push_str(&format!("<ram:DueDateDateTime>{}</ram:DueDateDateTime>", cii_date(invoice.due_date)));
+    xml.push_str("</ram:SpecifiedTradePaymentTerms>");
+
+    xml.push_str("<ram:SpecifiedTradeSettlementHeaderMonetarySummation>");
+    xml.push_str(&element("ram:LineTotalAmount", &amount(invoice.line_total())));
+    if invoice.allowance > Decimal::ZERO {
+        xml.push_str(&element("ram:AllowanceTotalAmount", &amount(invoice.allowance)));
+    }
+    xml.push_str(&element("ram:TaxBasisTotalAmount", &amount(invoice.tax_exclusive_total())));
+    xml.push_str(&format!(
+        "<ram:TaxTotalAmount currencyID=\"{}\">{}</ram:TaxTotalAmount>",
+        xml_escape(&invoice.currency),
+        amount(invoice.tax_amount)
+    ));
+    xml.push_str(&element("ram:GrandTotalAmount", &amount(invoice.tax_inclusive_total())));
+    if invoice.prepaid > Decimal::ZERO {
+        xml.push_str(&element("ram:TotalPrepaidAmount", &amount(invoice.prepaid)));
+    }
+    xml.push_str(&element("ram:DuePayableAmount", &amount(invoice.payable())));
+    xml.push_str("</ram:SpecifiedTradeSettlementHeaderMonetarySummation>");
+    xml.push_str("</ram:ApplicableHeaderTradeSettlement>");
+
+    xml.push_str("</rsm:SupplyChainTradeTransaction></rsm:CrossIndustryInvoice>\n");
+    xml
+}
+
+fn cii_party(tag: &str, party: &EInvoiceParty) -> String {
+    let mut xml = format!("<{}>{}", tag, element("ram:Name", &party.name));
+    if let Some(email) = &party.email {
+        xml.push_str(&format!(
+            "<ram:DefinedTradeContact><ram:EmailURIUniversalCommunication>{}</ram:EmailURIUniversalCommunication></ram:DefinedTradeContact>",
+            element("ram:URIID", email)
+        ));
+    }
+    xml.push_str("<ram:PostalTradeAddress>");
+    for (name, value) in [
+        ("ram:PostcodeCode", &party.postal_code),
+        ("ram:LineOne", &party.street),
+        ("ram:CityName", &party.city),
+        ("ram:CountryID", &party.country),
+        ("ram:CountrySubDivisionName", &party.region),
+    ] {
+        if let Some(value) = value {
+            xml.push_str(&element(name, value));
+        }
+    }
+    xml.push_str("</ram:PostalTradeAddress>");
+    if let Some(vat_number) = &party.vat_number {
+        xml.push_str(&format!(
+            "<ram:SpecifiedTaxRegistration><ram:ID schemeID=\"VA\">{}</ram:ID></ram:SpecifiedTaxRegistration>",
+            xml_escape(vat_number)
+        ));
+    }
+    xml.push_str(&format!("</{}>", tag));
+    xml
+}
+
+fn cii_tax(tag: &str, category: VatCategory, rate: Decimal) -> String {
+    let rate = if category.has_rate() { element("ram:RateApplicablePercent", &percent(rate)) } else { String::new() };
+    format!("<{0}>{1}{2}{3}</{0}>", tag, element("ram:TypeCode", "VAT"), element("ram:CategoryCode", category.code()), rate)
+}
+
+fn cii_date(date: chrono::NaiveDate) -> String {
+    format!("<udt:DateTimeString format=\"102\">{}</udt:DateTimeString>", date.format("%Y%m%d"))
+}
+
+/// Attaches the CII XML to the invoice PDF as Factur-X's `factur-x.xml`, with
+/// the XMP metadata that tells readers which profile it follows
+pub fn embed_factur_x(pdf: &[u8], xml: &str, invoice_number: &str) -> Result<Vec<u8>, lopdf::Error> {
+    let mut doc = Document::load_mem(pdf)?;
+    let modified = chrono::Utc::now().format("D:%Y%m%d%H%M%SZ").to_string();
+
+    let file_id = doc.add_object(Stream::new(
+        dictionary! {
+            "Type" => "EmbeddedFile",
+            "Subtype" => "text/xml",
+            "Params" => dictionary! {
+                "Size" => xml.len() as i64,
+                "ModDate" => Object::string_literal(modified),
+            },
+        },
+        xml.as_bytes().to_vec(),
+    ));
+    let file_spec_id = doc.add_object(dictionary! {
+        "Type" => "Filespec",
+        "F" => Object::string_literal(FACTUR_X_FILE_NAME),
+        "UF" => Object::string_literal(FACTUR_X_FILE_NAME),
+        "Desc" => Object::string_literal("Factur-X invoice"),
+        "AFRelationship" => "Alternative",
+        "EF" => dictionary! { "F" => file_id, "UF" => file_id },
+    });
+    // Readers parse the metadata as plain XML, so it stays uncompressed
+    let metadata_id = doc.add_object(
+        Stream::new(dictionary! { "Type" => "Metadata", "Subtype" => "XML" }, factur_x_xmp(invoice_number).into_bytes())
+            .with_compression(false),
+    );
+
+    let catalog = doc.catalog_mut()?;
+    catalog.set("AF", vec![Object::Reference(file_spec_id)]);
+    catalog.set(
+        "Names",
+        dictionary! {
+            "EmbeddedFiles" => dictionary! {
+                "Names" => vec![Object::string_literal(FACTUR_X_FILE_NAME), Object::Reference(file_spec_id)],
+            },
+        },
+    );
+    catalog.set("Metadata", metadata_id);
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output)?;
+    Ok(output)
+}
+
+fn factur_x_xmp(invoice_number: &str) -> String {
+    format!(
+        r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about="" xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:title><rdf:Alt><rdf:li xml:lang="x-default">Invoice {}</rdf:li></rdf:Alt></dc:title>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:fx="urn:factur-x:pdfa:CrossIndustryDocument:invoice:1p0#">
+<fx:DocumentType>INVOICE</fx:DocumentType>
+<fx:DocumentFileName>{}</fx:DocumentFileName>
+<fx:Version>1.0</fx:Version>
+<fx:ConformanceLevel>EN 16931</fx:ConformanceLevel>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:pdfaExtension="http://www.aiim.org/pdfa/ns/extension/" xmlns:pdfaSchema="http://www.aiim.org/pdfa/ns/schema#" xmlns:pdfaProperty="http://www.aiim.org/pdfa/ns/property#">
+<pdfaExtension:schemas><rdf:Bag><rdf:li rdf:parseType="Resource">
+<pdfaSchema:schema>Factur-X PDFA Extension Schema</pdfaSchema:schema>
+<pdfaSchema:namespaceURI>urn:factur-x:pdfa:CrossIndustryDocument:invoice:1p0#</pdfaSchema:namespaceURI>
+<pdfaSchema:prefix>fx</pdfaSchema:prefix>
+<pdfaSchema:property><rdf:Seq>
+<rdf:li rdf:parseType="Resource"><pdfaProperty:name>DocumentFileName</pdfaProperty:name><pdfaProperty:valueType>Text</pdfaProperty:valueType><pdfaProperty:category>external</pdfaProperty:category><pdfaProperty:description>Name of the embedded XML invoice file</pdfaProperty:description></rdf:li>
+<rdf:li rdf:parseType="Resource"><pdfaProperty:name>DocumentType</pdfaProperty:name><pdfaProperty:valueType>Text</pdfaProperty:valueType><pdfaProperty:category>external</pdfaProperty:category><pdfaProperty:description>INVOICE</pdfaProperty:description></rdf:li>
+<rdf:li rdf:parseType="Resource"><pdfaProperty:name>Version</pdfaProperty:name><pdfaProperty:valueType>Text</pdfaProperty:valueType><pdfaProperty:category>external</pdfaProperty:category><pdfaProperty:description>Version of the Factur-X XML schema</pdfaProperty:description></rdf:li>
+<rdf:li rdf:parseType="Resource"><pdfaProperty:name>ConformanceLevel</pdfaProperty:name><pdfaProperty:valueType>Text</pdfaProperty:valueType><pdfaProperty:category>external</pdfaProperty:category><pdfaProperty:description>Factur-X profile of the XML</pdfaProperty:description></rdf:li>
+</rdf:Seq></pdfaSchema:property>
+</rdf:li></rdf:Bag></pdfaExtension:schemas>
+</rdf:Description>
+</rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+        xml_escape(invoice_number),
+        FACTUR_X_FILE_NAME
+    )
+}
+
+fn element(name: &str, value: &str) -> String {
+    format!("<{0}>{1}</{0}>", name, xml_escape(value))
+}
+
+fn amount(value: Decimal) -> String {
+    format!("{:.2}", value.round_dp(2))
+}
+
+/// A 0-1 rate as the percentage the standards use, e.g. 19 or 5.5
+fn percent(rate: Decimal) -> String {
+    (rate * Decimal::ONE_HUNDRED).normalize().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::EInvoiceLine;
+    use chrono::NaiveDate;
+    use printpdf::{PdfDocument, PdfPage, PdfSaveOptions, Mm};
+    use rust_decimal_macros::dec;
+
+    fn invoice(reverse_charge: bool) -> EInvoice {
+        let (category, rate) = if reverse_charge { (VatCategory::ReverseCharge, dec!(0)) } else { (VatCategory::Standard, dec!(0.19)) };
+        EInvoice {
+            number: "INV-0001".to_string(),
+            issue_date: NaiveDate::from_ymd_opt(2026, 10, 1).unwrap(),
+            due_date: NaiveDate::from_ymd_opt(2026, 10, 31).unwrap(),
+            currency: "EUR".to_string(),
+            note: Some("Thanks & goodbye".to_string()),
+            payment_terms: Some("Net 30".to_string()),
+            seller: EInvoiceParty {
+                name: "Berlin GmbH".to_string(),
+                vat_number: Some("DE136695976".to_string()),
+                country: Some("DE".to_string()),
+                ..EInvoiceParty::default()
+            },
+            buyer: EInvoiceParty {
+                name: "Paris <SARL>".to_string(),
+                vat_number: Some("FR40303265045".to_string()),
+                country: Some("FR".to_string()),
+                ..EInvoiceParty::default()
+            },
+            lines: vec![EInvoiceLine {
+                description: "Consulting".to_string(),
+                quantity: dec!(2),
+                price: dec!(500),
+                base_quantity: Decimal::ONE,
+                net_amount: dec!(1000),
+                tax_amount: dec!(1000) * rate,
+                category,
+                rate,
+                tax_count: 0,
+            }],
+            allowance: Decimal::ZERO,
+            tax_amount: dec!(1000) * rate,
+            prepaid: dec!(100),
+        }
+    }
+
+    #[test]
+    fn test_ubl_invoice() {
+        let xml = ubl_invoice(&invoice(false));
+        assert!(xml.contains("<cbc:CustomizationID>urn:cen.eu:en16931:2017</cbc:CustomizationID>"));
+        assert!(xml.contains("<cbc:ID>INV-0001</cbc:ID><cbc:IssueDate>2026-10-01</cbc:IssueDate>"));
+        assert!(xml.contains("<cbc:Note>Thanks &amp; goodbye</cbc:Note>"));
+        assert!(xml.contains("<cbc:RegistrationName>Paris &lt;SARL&gt;</cbc:RegistrationName>"));
+        assert!(xml.contains("<cac:TaxTotal><cbc:TaxAmount currencyID=\"EUR\">190.00</cbc:TaxAmount>"));
+        assert!(xml.contains("<cbc:ID>S</cbc:ID><cbc:Percent>19</cbc:Percent>"));
+        assert!(xml.contains("<cbc:TaxInclusiveAmount currencyID=\"EUR\">1190.00</cbc:TaxInclusiveAmount>"));
+        assert!(xml.contains("<cbc:PayableAmount currencyID=\"EUR\">1090.00</cbc:PayableAmount>"));
+        assert!(xml.contains("<cbc:InvoicedQuantity unitCode=\"C62\">2</cbc:InvoicedQuantity>"));
+    }
+
+    #[test]
+    fn test_reverse_charge_states_the_exemption() {
+        let ubl = ubl_invoice(&invoice(true));
+        assert!(ubl.contains(
+            "<cbc:ID>AE</cbc:ID><cbc:Percent>0</cbc:Percent><cbc:TaxExemptionReasonCode>VATEX-EU-AE</cbc:TaxExemptionReasonCode>"
+        ));
+
+        let cii = cii_invoice(&invoice(true));
+        assert!(cii.contains("<ram:ExemptionReason>Reverse charge</ram:ExemptionReason>"));
+        assert!(cii.contains("<ram:CategoryCode>AE</ram:CategoryCode><ram:ExemptionReasonCode>VATEX-EU-AE</ram:ExemptionReasonCode>"));
+    }
+
+    #[test]
+    fn test_cii_invoice() {
+        let xml = cii_invoice(&invoice(false));
+        assert!(xml.contains("<ram:IssueDateTime><udt:DateTimeString format=\"102\">20261001</udt:DateTimeString></ram:IssueDateTime>"));
+        assert!(xml.contains("<ram:SpecifiedTaxRegistration><ram:ID schemeID=\"VA\">DE136695976</ram:ID></ram:SpecifiedTaxRegistration>"));
+        assert!(xml.contains("<ram:TaxTotalAmount currencyID=\"EUR\">190.00</ram:TaxTotalAmount>"));
+        assert!(xml.contains("<ram:GrandTotalAmount>1190.00</ram:GrandTotalAmount>"));
+        assert!(xml.contains("<ram:DuePayableAmount>1090.00</ram:DuePayableAmount>"));
+    }
+
+    #[test]
+    fn test_factur_x_attaches_the_xml() {
+        let mut pdf = PdfDocument::new("Invoice INV-0001");
+        pdf.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), Vec::new()));
+        let pdf = pdf.save(&PdfSaveOptions::default(), &mut Vec::new());
+        let xml = cii_invoice(&invoice(false));
+
+        let output = embed_factur_x(&pdf, &xml, "INV-0001").unwrap();
+        let doc = Document::load_mem(&output).unwrap();
+        let catalog = doc.catalog().unwrap();
+        assert!(catalog.get(b"AF").is_ok());
+        assert!(catalog.get(b"Metadata").is_ok());
+
+        let names = catalog.get(b"Names").unwrap().as_dict().unwrap();
+        let files = names.get(b"EmbeddedFiles").unwrap().as_dict().unwrap().get(b"Names").unwrap().as_array().unwrap();
+        assert_eq!(files[0].as_str().unwrap(), FACTUR_X_FILE_NAME.as_bytes());
+        let spec = doc.get_dictionary(files[1].as_reference().unwrap()).unwrap();
+        let file = spec.get(b"EF").unwrap().as_dict().unwrap().get(b"F").unwrap().as_reference().unwrap();
+        let stream = doc.get_object(file).unwrap().as_stream().unwrap();
+        assert_eq!(stream.content, xml.as_bytes());
+    }
+}
@@ -1,11 +1,37 @@
 #![allow(dead_code)]
 
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::Instrument;
 use uuid::Uuid;
 
+use crate::domain::i18n::Locale;
+use crate::domain::models::EmailTemplate;
+use crate::domain::request_id;
 use crate::domain::services::redis_service::RedisService;
-use crate::domain::services::email_service::{EmailService, EmailError};
+use crate::domain::services::email_service::{
+    default_currency, CampaignEmail, ClientStatementEmail, EmailService, EmailError, InvoiceEmail,
+    PaymentConfirmationEmail,
+};
+use crate::domain::services::clock::SharedClock;
+use crate::domain::services::shutdown::Shutdown;
+
+/// Pause between polls when no job was due
+const WORKER_IDLE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+const PENDING_QUEUE: &str = "email_queue:pending";
+const PROCESSING_QUEUE: &str = "email_queue:processing";
+/// Jobs that failed for good, kept for an operator to retry or clear
+const DEAD_LETTER_QUEUE: &str = "email_queue:failed";
+
+/// Attempts after the first before a job is dead-lettered
+pub const MAX_EMAIL_RETRIES: u32 = 5;
+
+/// Wait before the first retry; each further one waits four times as long,
+/// so five retries span about two and a half hours
+pub const EMAIL_RETRY_BASE_SECONDS: i64 = 30;
 
 /// Email job types that can be queued
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,16 +58,30 @@ pub enum EmailJobType {
         invoice_number: String,
         amount: f64,
         payment_method: String,
+        #[serde(default)]
+        business_name: Option<String>,
+        /// The invoicing user's confirmation template
+        #[serde(default)]
+        template: Option<EmailTemplate>,
+        /// The client's locale, else the user's
+        #[serde(default)]
+        locale: Locale,
+        #[serde(default = "default_currency")]
+        currency: String,
     },
     SendPasswordReset {
         to_email: String,
         to_name: String,
         reset_token: String,
+        #[serde(default)]
+        locale: Locale,
     },
     SendVerificationEmail {
         to_email: String,
         to_name: String,
         verification_token: String,
+        #[serde(default)]
+        locale: Locale,
     },
     SendInvoiceWithAttachment {
         to_email: String,
@@ -51,6 +91,103 @@ pub enum EmailJobType {
         amount: f64,
         due_date: String,
     },
+    SendClientStatement {
+        to_email: String,
+        to_name: String,
+        statement: ClientStatementEmail,
+    },
+    SendAccountLocked {
+        to_email: String,
+        to_name: String,
+        locked_minutes: i64,
+    },
+    SendAccountantInvite {
+        to_email: String,
+        to_name: String,
+        business_name: String,
+        access_code: String,
+        expires_on: String,
+    },
+    SendPortalSignInLink {
+        to_email: String,
+        token: String,
+        valid_minutes: i64,
+    },
+    SendAttentionDigest {
+        to_email: String,
+        to_name: String,
+        items: Vec<(String, String)>,
+    },
+    SendBudgetAlert {
+        to_email: String,
+        to_name: String,
+        items: Vec<String>,
+    },
+    SendAccountStatement {
+        to_email: String,
+        to_name: String,
+        seller_name: String,
+        period_label: String,
+        closing_balance: f64,
+        pdf_bytes: Vec<u8>,
+    },
+    SendCampaignEmail(CampaignEmail),
+    SendInvoiceEmail {
+        invoice: Box<InvoiceEmail>,
+        pdf_bytes: Vec<u8>,
+    },
+    /// An HTML email rendered by the caller
+    SendEmail {
+        to_email: String,
+        to_name: String,
+        subject: String,
+        html_body: String,
+    },
+}
+
+impl EmailJobType {
+    /// Names the kind of email in logs and the dead-letter list
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EmailJobType::SendInvoice { .. } => "invoice_link",
+            EmailJobType::SendPaymentReminder { .. } => "payment_reminder",
+            EmailJobType::SendPaymentConfirmation { .. } => "payment_confirmation",
+            EmailJobType::SendPasswordReset { .. } => "password_reset",
+            EmailJobType::SendVerificationEmail { .. } => "verification",
+            EmailJobType::SendInvoiceWithAttachment { .. } => "invoice_with_attachment",
+            EmailJobType::SendClientStatement { .. } => "client_statement",
+            EmailJobType::SendAccountLocked { .. } => "account_locked",
+            EmailJobType::SendAccountantInvite { .. } => "accountant_invite",
+            EmailJobType::SendPortalSignInLink { .. } => "portal_sign_in_link",
+            EmailJobType::SendAttentionDigest { .. } => "attention_digest",
+            EmailJobType::SendBudgetAlert { .. } => "budget_alert",
+            EmailJobType::SendAccountStatement { .. } => "account_statement",
+            EmailJobType::SendCampaignEmail(_) => "campaign",
+            EmailJobType::SendInvoiceEmail { .. } => "invoice",
+            EmailJobType::SendEmail { .. } => "email",
+        }
+    }
+
+    pub fn to_email(&self) -> &str {
+        match self {
+            EmailJobType::SendInvoice { to_email, .. }
+            | EmailJobType::SendPaymentReminder { to_email, .. }
+            | EmailJobType::SendPaymentConfirmation { to_email, .. }
+            | EmailJobType::SendPasswordReset { to_email, .. }
+            | EmailJobType::SendVerificationEmail { to_email, .. }
+            | EmailJobType::SendInvoiceWithAttachment { to_email, .. }
+            | EmailJobType::SendClientStatement { to_email, .. }
+            | EmailJobType::SendAccountLocked { to_email, .. }
+            | EmailJobType::SendAccountantInvite { to_email, .. }
+            | EmailJobType::SendPortalSignInLink { to_email, .. }
+            | EmailJobType::SendAttentionDigest { to_email, .. }
+            | EmailJobType::SendBudgetAlert { to_email, .. }
+            | EmailJobType::SendAccountStatement { to_email, .. }
+            | EmailJobType::SendEmail { to_email, .. } => to_email,
+            EmailJobType::SendCampaignEmail(email) => &email.to_email,
+            EmailJobType::SendInvoiceEmail { invoice, .. } => &invoice.to_email,
+        }
+    }
 }
 
 /// Email job with metadata
@@ -62,42 +199,48 @@ pub struct EmailJob {
     pub max_retries: u32,
     pub created_at: i64,
     pub scheduled_at: i64,
+    /// X-Request-Id of the request that queued the email, for its log lines
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// Why the latest attempt failed
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// When the job was dead-lettered
+    #[serde(default)]
+    pub failed_at: Option<i64>,
 }
 
 impl EmailJob {
-    pub fn new(job_type: EmailJobType) -> Self {
-        let now = chrono::Utc::now().timestamp();
+    pub fn new(job_type: EmailJobType, now: i64) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
             job_type,
             retry_count: 0,
-            max_retries: 3,
+            max_retries: MAX_EMAIL_RETRIES,
             created_at: now,
             scheduled_at: now, // Process immediately
+            request_id: request_id::current(),
+            last_error: None,
+            failed_at: None,
         }
     }
 
-    pub fn with_delay(job_type: EmailJobType, delay_seconds: i64) -> Self {
-        let now = chrono::Utc::now().timestamp();
+    pub fn with_delay(job_type: EmailJobType, now: i64, delay_seconds: i64) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
-            job_type,
-            retry_count: 0,
-            max_retries: 3,
-            created_at: now,
             scheduled_at: now + delay_seconds,
+            ..Self::new(job_type, now)
         }
     }
 
-    pub fn should_process(&self) -> bool {
-        chrono::Utc::now().timestamp() >= self.scheduled_at
+    pub fn should_process(&self, now: i64) -> bool {
+        now >= self.scheduled_at
     }
 
-    pub fn increment_retry(&mut self) {
+    pub fn increment_retry(&mut self, now: i64) {
+        // Exponential backoff: 30s, 2m, 8m, 32m, ~2h
+        let delay = EMAIL_RETRY_BASE_SECONDS * 4_i64.pow(self.retry_count.min(8));
         self.retry_count += 1;
-        // Exponential backoff: 2^retry_count seconds
-        let delay = 2_i64.pow(self.retry_count);
-        self.scheduled_at = chrono::Utc::now().timestamp() + delay;
+        self.scheduled_at = now + delay;
     }
 
     pub fn is_failed(&self) -> bool {
@@ -105,6 +248,36 @@ impl EmailJob {
     }
 }
 
+/// A dead-lettered job as the admin endpoint lists it, without the email's
+/// contents, which can hold sign-in tokens
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct DeadLetter {
+    pub id: String,
+    /// e.g. password_reset, invoice, campaign
+    pub kind: String,
+    pub to_email: String,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub failed_at: Option<i64>,
+    pub request_id: Option<String>,
+}
+
+impl From<&EmailJob> for DeadLetter {
+    fn from(job: &EmailJob) -> Self {
+        Self {
+            id: job.id.clone(),
+            kind: job.job_type.kind().to_string(),
+            to_email: job.job_type.to_email().to_string(),
+            attempts: job.retry_count + 1,
+            last_error: job.last_error.clone(),
+            created_at: job.created_at,
+            failed_at: job.failed_at,
+            request_id: job.request_id.clone(),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum EmailQueueError {
     #[error("Redis error: {0}")]
@@ -117,22 +290,134 @@ pub enum EmailQueueError {
     MaxRetriesExceeded,
 }
 
-/// Email Queue Service - Provides reliable email delivery with retry logic
+/// Where queued jobs are kept: Redis lists when Redis is configured, and lists
+/// in this process when it isn't or doesn't answer. Jobs kept in process are
+/// lost on restart.
+struct JobStore {
+    redis: Option<Arc<RedisService>>,
+    local: Mutex<HashMap<&'static str, VecDeque<String>>>,
+    // Set while Redis refuses jobs, so an outage is logged once rather than on
+    // every job put back to wait
+    redis_down: AtomicBool,
+}
+
+fn redis_error(e: impl std::fmt::Display) -> EmailQueueError {
+    EmailQueueError::RedisError(e.to_string())
+}
+
+impl JobStore {
+    /// Never fails: a job Redis doesn't take is kept in process instead
+    async fn push(&self, queue: &'static str, job_json: &str) {
+        if let Some(redis) = &self.redis {
+            match redis.lpush(queue, job_json).await {
+                Ok(()) => {
+                    if self.redis_down.swap(false, Ordering::Relaxed) {
+                        tracing::info!(queue, "Redis is back, queueing email jobs there again");
+                    }
+                    return;
+                }
+                Err(e) => {
+                    if !self.redis_down.swap(true, Ordering::Relaxed) {
+                        tracing::warn!(queue, "Redis unavailable, keeping email jobs in process: {}", e);
+                    }
+                }
+            }
+        }
+        self.local.lock().unwrap().entry(queue).or_default().push_front(job_json.to_string());
+    }
+
+    /// Jobs kept in process go first, so they are sent while Redis is down
+    async fn pop(&self, queue: &'static str) -> Option<String> {
+        if let Some(job_json) = self.local.lock().unwrap().get_mut(queue).and_then(VecDeque::pop_back) {
+            return Some(job_json);
+        }
+        // An outage is reported by the readiness and health checks; polling just waits it out
+        match &self.redis {
+            Some(redis) => redis.rpop(queue).await.unwrap_or_else(|e| {
+                tracing::debug!(queue, "Email queue poll failed: {}", e);
+                None
+            }),
+            None => None,
+        }
+    }
+
+    fn local_len(&self, queue: &'static str) -> i64 {
+        self.local.lock().unwrap().get(queue).map_or(0, |list| list.len() as i64)
+    }
+
+    async fn len(&self, queue: &'static str) -> Result<i64, EmailQueueError> {
+        let in_redis = match &self.redis {
+            Some(redis) => redis.llen(queue).await.map_err(redis_error)?.unwrap_or(0),
+            None => 0,
+        };
+        Ok(self.local_len(queue) + in_redis)
+    }
+
+    /// Newest first within each store, those kept in process first
+    async fn list(&self, queue: &'static str, limit: usize) -> Result<Vec<String>, EmailQueueError> {
+        let mut jobs: Vec<String> = self
+            .local
+            .lock()
+            .unwrap()
+            .get(queue)
+            .map(|list| list.iter().take(limit).cloned().collect())
+            .unwrap_or_default();
+        if let (Some(redis), true) = (&self.redis, jobs.len() < limit) {
+            jobs.extend(redis.lrange(queue, limit - jobs.len()).await.map_err(redis_error)?);
+        }
+        Ok(jobs)
+    }
+
+    async fn remove(&self, queue: &'static str, job_json: &str) -> Result<bool, EmailQueueError> {
+        {
+            let mut local = self.local.lock().unwrap();
+            if let Some(list) = local.get_mut(queue) {
+                if let Some(index) = list.iter().position(|entry| entry == job_json) {
+                    return Ok(list.remove(index).is_some());
+                }
+            }
+        }
+        match &self.redis {
+            Some(redis) => redis.lrem(queue, job_json).await.map_err(redis_error),
+            None => Ok(false),
+        }
+    }
+
+    async fn clear(&self, queue: &'static str) -> Result<(), EmailQueueError> {
+        self.local.lock().unwrap().remove(queue);
+        match &self.redis {
+            Some(redis) => redis.delete(queue).await.map_err(redis_error),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Every email goes through here. Most are queued and sent by the worker, with
+/// retries and backoff; `send_immediate` is for callers that act on the result.
 pub struct EmailQueueService {
-    redis: Arc<RedisService>,
+    store: JobStore,
     email_service: Arc<EmailService>,
-    queue_key: String,
-    processing_key: String,
+    clock: SharedClock,
+}
+
+impl std::fmt::Debug for EmailQueueService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmailQueueService")
+            .field("in_process", &self.is_in_process())
+            .finish_non_exhaustive()
+    }
 }
 
 impl EmailQueueService {
-    pub fn new(redis: Arc<RedisService>, email_service: Arc<EmailService>) -> Self {
-        Self {
-            redis,
-            email_service,
-            queue_key: "email_queue:pending".to_string(),
-            processing_key: "email_queue:processing".to_string(),
-        }
+    /// Queues in Redis when given, otherwise in this process
+    pub fn new(redis: Option<Arc<RedisService>>, email_service: Arc<EmailService>, clock: SharedClock) -> Self {
+        let store = JobStore { redis, local: Mutex::new(HashMap::new()), redis_down: AtomicBool::new(false) };
+        Self { store, email_service, clock }
+    }
+
+    /// Whether jobs are only ever kept in this process
+    pub fn is_in_process(&self) -> bool {
+        self.store.redis.is_none()
     }
 
     /// Queue an email job for async processing
@@ -140,210 +425,406 @@ impl EmailQueueService {
         let job_json = serde_json::to_string(&job)
             .map_err(|e| EmailQueueError::SerializationError(e.to_string()))?;
 
-        // Add to pending queue
-        self.redis
-            .lpush(&self.queue_key, &job_json)
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?;
+        self.store.push(PENDING_QUEUE, &job_json).await;
 
-        tracing::info!(job_id = %job.id, "Email job queued");
+        tracing::info!(job_id = %job.id, kind = job.job_type.kind(), "Email job queued");
         Ok(())
     }
 
-    /// Queue an email with immediate processing
-    pub async fn send_immediate(&self, job_type: EmailJobType) -> Result<(), EmailQueueError> {
-        let job = EmailJob::new(job_type);
+    /// Send an email now, without queueing or retrying it, for callers that
+    /// record or act on whether it went out
+    pub async fn send_immediate(&self, job_type: EmailJobType) -> Result<(), EmailError> {
+        let job = EmailJob::new(job_type, self.clock.now().timestamp());
         self.process_job(&job).await
     }
 
+    /// Queue an email to be sent as soon as the worker picks it up
+    pub async fn enqueue_now(&self, job_type: EmailJobType) -> Result<(), EmailQueueError> {
+        self.enqueue(EmailJob::new(job_type, self.clock.now().timestamp())).await
+    }
+
+    /// Queue an email for a caller that carries on if queueing fails; the
+    /// failure is logged
+    pub async fn enqueue_or_log(&self, job_type: EmailJobType) {
+        let kind = job_type.kind();
+        if let Err(e) = self.enqueue_now(job_type).await {
+            tracing::error!(kind, "Failed to queue email: {}", e);
+        }
+    }
+
+    /// Spawn the loop that drains the queue. On shutdown the email being sent
+    /// finishes; the rest stay queued for the next start (Redis only).
+    pub fn start_worker(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            while !shutdown.is_triggered() {
+                if self.process_due(&shutdown).await == 0 {
+                    shutdown.sleep(WORKER_IDLE_DELAY).await;
+                }
+            }
+        });
+    }
+
+    /// One pass over the queue: sends the jobs that are due and puts the rest
+    /// back. Returns how many were attempted.
+    async fn process_due(&self, shutdown: &Shutdown) -> usize {
+        // Only what is kept in process while Redis is down
+        let queued = match self.store.len(PENDING_QUEUE).await {
+            Ok(queued) => queued,
+            Err(_) => self.store.local_len(PENDING_QUEUE),
+        };
+        let mut attempted = 0;
+        for _ in 0..queued {
+            if shutdown.is_triggered() {
+                break;
+            }
+            // Failures are logged with the job
+            match self.process_next().await {
+                Ok(None) => {}
+                Ok(Some(_)) | Err(_) => attempted += 1,
+            }
+        }
+        attempted
+    }
+
     /// Process the next job in queue
     pub async fn process_next(&self) -> Result<Option<String>, EmailQueueError> {
-        // Get next job from queue
-        let job_json = match self
-            .redis
-            .rpop(&self.queue_key)
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?
-        {
+        let job_json = match self.store.pop(PENDING_QUEUE).await {
             Some(json) => json,
             None => return Ok(None), // Queue is empty
         };
 
-        let mut job: EmailJob = serde_json::from_str(&job_json)
-            .map_err(|e| EmailQueueError::SerializationError(e.to_string()))?;
+        let job: EmailJob = match serde_json::from_str(&job_json) {
+            Ok(job) => job,
+            Err(e) => {
+                // Kept as-is so an operator can see it; it can't be retried
+                tracing::error!("Unreadable email job dead-lettered: {}", e);
+                self.store.push(DEAD_LETTER_QUEUE, &job_json).await;
+                return Err(EmailQueueError::SerializationError(e.to_string()));
+            }
+        };
 
         // Check if job should be processed (scheduled time)
-        if !job.should_process() {
+        if !job.should_process(self.clock.now().timestamp()) {
             // Re-queue for later
-            self.redis
-                .lpush(&self.queue_key, &job_json)
-                .await
-                .map_err(|e| EmailQueueError::RedisError(e.to_string()))?;
+            self.store.push(PENDING_QUEUE, &job_json).await;
             return Ok(None);
         }
 
-        // Process the job
+        let span = tracing::info_span!(
+            "email_job",
+            job_id = %job.id,
+            kind = job.job_type.kind(),
+            request_id = job.request_id.as_deref().map(tracing::field::display)
+        );
+        self.run_job(job).instrument(span).await
+    }
+
+    /// Send a due job, re-queueing it for a retry or dead-lettering it when it fails
+    async fn run_job(&self, mut job: EmailJob) -> Result<Option<String>, EmailQueueError> {
         match self.process_job(&job).await {
             Ok(_) => {
                 tracing::info!(job_id = %job.id, "Email job processed successfully");
                 Ok(Some(job.id))
             }
             Err(e) => {
-                job.increment_retry();
+                let now = self.clock.now().timestamp();
+                job.last_error = Some(e.to_string());
+                job.increment_retry(now);
 
-                if job.is_failed() {
-                    tracing::error!(job_id = %job.id, error = %e, "Email job failed after max retries");
-                    // Log to failed jobs for manual review
+                // Retrying a refused address or a message that can't be built won't help
+                let permanent = e.is_hard_bounce() || matches!(e, EmailError::MessageBuildError);
+                if permanent || job.is_failed() {
+                    tracing::error!(job_id = %job.id, error = %e, attempts = job.retry_count, "Email job dead-lettered");
+                    job.failed_at = Some(now);
                     let failed_json = serde_json::to_string(&job)
                         .map_err(|e| EmailQueueError::SerializationError(e.to_string()))?;
-                    self.redis
-                        .lpush("email_queue:failed", &failed_json)
-                        .await
-                        .map_err(|e| EmailQueueError::RedisError(e.to_string()))?;
+                    self.store.push(DEAD_LETTER_QUEUE, &failed_json).await;
                     return Err(EmailQueueError::MaxRetriesExceeded);
                 }
 
-                // Re-queue for retry
-                tracing::warn!(job_id = %job.id, retry_count = %job.retry_count, "Email job failed, retrying");
+                tracing::warn!(job_id = %job.id, retry_count = %job.retry_count, error = %e, "Email job failed, retrying");
                 let retry_json = serde_json::to_string(&job)
                     .map_err(|e| EmailQueueError::SerializationError(e.to_string()))?;
-                self.redis
-                    .lpush(&self.queue_key, &retry_json)
-                    .await
-                    .map_err(|e| EmailQueueError::RedisError(e.to_string()))?;
+                self.store.push(PENDING_QUEUE, &retry_json).await;
 
-                Err(e)
+                Err(e.into())
             }
         }
     }
 
     /// Process a specific job
-    async fn process_job(&self, job: &EmailJob) -> Result<(), EmailQueueError> {
+    async fn process_job(&self, job: &EmailJob) -> Result<(), EmailError> {
+        let emails = &self.email_service;
         match &job.job_type {
-            EmailJobType::SendInvoice {
-                to_email,
-                to_name,
-                invoice_number,
-                pdf_url,
-                amount,
-                due_date,
-            } => {
-                self.email_service
-                    .send_invoice(to_email, to_name, invoice_number, pdf_url, *amount, due_date)?;
+            EmailJobType::SendInvoice { to_email, to_name, invoice_number, pdf_url, amount, due_date } => {
+                emails.send_invoice(to_email, to_name, invoice_number, pdf_url, *amount, due_date).await
             }
-            EmailJobType::SendPaymentReminder {
-                to_email,
-                to_name,
-                invoice_number,
-                days_overdue,
-                amount_due,
-                reminder_type,
-            } => {
-                self.email_service.send_payment_reminder(
-                    to_email,
-                    to_name,
-                    invoice_number,
-                    *days_overdue,
-                    *amount_due,
-                    reminder_type,
-                )?;
+            EmailJobType::SendPaymentReminder { to_email, to_name, invoice_number, days_overdue, amount_due, reminder_type } => {
+                emails
+                    .send_payment_reminder(to_email, to_name, invoice_number, *days_overdue, *amount_due, reminder_type)
+                    .await
             }
             EmailJobType::SendPaymentConfirmation {
-                to_email,
-                to_name,
-                invoice_number,
-                amount,
-                payment_method,
+                to_email, to_name, invoice_number, amount, payment_method, business_name, template, locale, currency,
             } => {
-                self.email_service.send_payment_confirmation(
-                    to_email,
-                    to_name,
-                    invoice_number,
-                    *amount,
-                    payment_method,
-                )?;
+                let confirmation = PaymentConfirmationEmail {
+                    to_email: to_email.clone(),
+                    to_name: to_name.clone(),
+                    invoice_number: invoice_number.clone(),
+                    amount: *amount,
+                    payment_method: payment_method.clone(),
+                    business_name: business_name.clone(),
+                    template: template.clone(),
+                    locale: *locale,
+                    currency: currency.clone(),
+                };
+                emails.send_payment_confirmation(&confirmation).await
             }
-            EmailJobType::SendPasswordReset {
-                to_email,
-                to_name,
-                reset_token,
-            } => {
-                self.email_service
-                    .send_password_reset(to_email, to_name, reset_token)?;
+            EmailJobType::SendPasswordReset { to_email, to_name, reset_token, locale } => {
+                emails.send_password_reset(to_email, to_name, reset_token, *locale).await
             }
-            EmailJobType::SendVerificationEmail {
-                to_email,
-                to_name,
-                verification_token,
-            } => {
-                self.email_service
-                    .send_verification_email(to_email, to_name, verification_token)?;
+            EmailJobType::SendVerificationEmail { to_email, to_name, verification_token, locale } => {
+                emails.send_verification_email(to_email, to_name, verification_token, *locale).await
             }
-            EmailJobType::SendInvoiceWithAttachment {
-                to_email,
-                to_name,
-                invoice_number,
-                pdf_bytes,
-                amount,
-                due_date,
-            } => {
-                self.email_service.send_invoice_with_attachment(
-                    to_email,
-                    to_name,
-                    invoice_number,
-                    pdf_bytes.clone(),
-                    *amount,
-                    due_date,
-                )?;
+            EmailJobType::SendInvoiceWithAttachment { to_email, to_name, invoice_number, pdf_bytes, amount, due_date } => {
+                emails
+                    .send_invoice_with_attachment(to_email, to_name, invoice_number, pdf_bytes.clone(), *amount, due_date)
+                    .await
+            }
+            EmailJobType::SendClientStatement { to_email, to_name, statement } => {
+                emails.send_client_statement(to_email, to_name, statement).await
+            }
+            EmailJobType::SendAccountLocked { to_email, to_name, locked_minutes } => {
+                emails.send_account_locked(to_email, to_name, *locked_minutes).await
+            }
+            EmailJobType::SendAccountantInvite { to_email, to_name, business_name, access_code, expires_on } => {
+                emails.send_accountant_invite(to_email, to_name, business_name, access_code, expires_on).await
+            }
+            EmailJobType::SendPortalSignInLink { to_email, token, valid_minutes } => {
+                emails.send_portal_sign_in_link(to_email, token, *valid_minutes).await
+            }
+            EmailJobType::SendAttentionDigest { to_email, to_name, items } => {
+                emails.send_attention_digest(to_email, to_name, items).await
+            }
+            EmailJobType::SendBudgetAlert { to_email, to_name, items } => {
+                emails.send_budget_alert(to_email, to_name, items).await
+            }
+            EmailJobType::SendAccountStatement { to_email, to_name, seller_name, period_label, closing_balance, pdf_bytes } => {
+                emails
+                    .send_account_statement(to_email, to_name, seller_name, period_label, *closing_balance, pdf_bytes.clone())
+                    .await
+            }
+            EmailJobType::SendCampaignEmail(email) => emails.send_campaign_email(email).await,
+            EmailJobType::SendInvoiceEmail { invoice, pdf_bytes } => {
+                emails.send_invoice_email(invoice, pdf_bytes.clone()).await
+            }
+            EmailJobType::SendEmail { to_email, to_name, subject, html_body } => {
+                emails.send_email(to_email, to_name, subject, html_body).await
             }
         }
-
-        Ok(())
     }
 
     /// Get queue statistics
     pub async fn get_stats(&self) -> Result<QueueStats, EmailQueueError> {
-        let pending = self
-            .redis
-            .llen(&self.queue_key)
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?
-            .unwrap_or(0);
-
-        let failed = self
-            .redis
-            .llen("email_queue:failed")
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?
-            .unwrap_or(0);
-
-        let processing = self
-            .redis
-            .llen(&self.processing_key)
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?
-            .unwrap_or(0);
-
         Ok(QueueStats {
-            pending,
-            processing,
-            failed,
+            pending: self.store.len(PENDING_QUEUE).await?,
+            processing: self.store.len(PROCESSING_QUEUE).await?,
+            failed: self.store.len(DEAD_LETTER_QUEUE).await?,
         })
     }
 
-    /// Clear failed jobs
-    pub async fn clear_failed(&self) -> Result<(), EmailQueueError> {
-        self.redis
-            .delete("email_queue:failed")
-            .await
-            .map_err(|e| EmailQueueError::RedisError(e.to_string()))?;
-        Ok(())
+    /// Most recently dead-lettered jobs first
+    pub async fn dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter>, EmailQueueError> {
+        Ok(self
+            .dead_letter_jobs(limit)
+            .await?
+            .iter()
+            .map(|(job, _)| DeadLetter::from(job))
+            .collect())
+    }
+
+    async fn dead_letter_jobs(&self, limit: usize) -> Result<Vec<(EmailJob, String)>, EmailQueueError> {
+        Ok(self
+            .store
+            .list(DEAD_LETTER_QUEUE, limit)
+            .await?
+            .into_iter()
+            .filter_map(|json| serde_json::from_str(&json).ok().map(|job| (job, json)))
+            .collect())
+    }
+
+    /// Queue a dead-lettered job again with a fresh set of retries. False if
+    /// no dead letter has that ID.
+    pub async fn retry_dead_letter(&self, id: &str) -> Result<bool, EmailQueueError> {
+        let total = self.store.len(DEAD_LETTER_QUEUE).await?.max(0) as usize;
+        let Some((mut job, json)) = self.dead_letter_jobs(total).await?.into_iter().find(|(job, _)| job.id == id) else {
+            return Ok(false);
+        };
+        // Only the caller that removes it queues it, so two retries don't send it twice
+        if !self.store.remove(DEAD_LETTER_QUEUE, &json).await? {
+            return Ok(false);
+        }
+
+        job.retry_count = 0;
+        job.scheduled_at = self.clock.now().timestamp();
+        job.failed_at = None;
+        self.enqueue(job).await?;
+        Ok(true)
+    }
+
+    /// Drop every dead-lettered job
+    pub async fn clear_dead_letters(&self) -> Result<(), EmailQueueError> {
+        self.store.clear(DEAD_LETTER_QUEUE).await
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct QueueStats {
     pub pending: i64,
     pub processing: i64,
     pub failed: i64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::clock::{Clock, MockClock};
+    use crate::domain::services::email_service::EmailConfig;
+
+    fn reminder() -> EmailJobType {
+        EmailJobType::SendPaymentReminder {
+            to_email: "client@example.com".to_string(),
+            to_name: "Client".to_string(),
+            invoice_number: "INV-2025-0001".to_string(),
+            days_overdue: 3,
+            amount_due: 100.0,
+            reminder_type: "friendly".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_delayed_job_waits_until_scheduled() {
+        let now = 1_736_931_600; // 2025-01-15 09:00:00 UTC
+        let job = EmailJob::with_delay(reminder(), now, 3600);
+
+        assert!(!job.should_process(now));
+        assert!(!job.should_process(now + 3599));
+        assert!(job.should_process(now + 3600));
+    }
+
+    #[test]
+    fn test_retry_backoff_and_failure() {
+        let now = 1_736_931_600;
+        let mut job = EmailJob::new(reminder(), now);
+        assert!(job.should_process(now));
+
+        let mut at = now;
+        for (retry, delay) in [30, 120, 480, 1920, 7680].into_iter().enumerate() {
+            assert!(!job.is_failed(), "failed before retry {}", retry + 1);
+            job.increment_retry(at);
+            assert_eq!(job.scheduled_at, at + delay);
+            assert!(!job.should_process(at + delay - 1));
+            at += delay;
+        }
+        assert!(job.is_failed());
+    }
+
+    fn in_process_queue() -> (EmailQueueService, Arc<MockClock>) {
+        let clock = MockClock::default_start();
+        let email_service = Arc::new(EmailService::new(EmailConfig {
+            smtp_host: "localhost".to_string(),
+            smtp_port: 2525,
+            username: String::new(),
+            password: String::new(),
+            from_email: "billing@example.com".to_string(),
+            from_name: "Billing".to_string(),
+        }));
+        (EmailQueueService::new(None, email_service, clock.clone()), clock)
+    }
+
+    #[tokio::test]
+    async fn test_bad_addresses_are_dead_lettered_and_can_be_retried() {
+        let (queue, _clock) = in_process_queue();
+        assert!(queue.is_in_process());
+
+        let bad = EmailJobType::SendEmail {
+            to_email: "not an address".to_string(),
+            to_name: "Client".to_string(),
+            subject: "Hello".to_string(),
+            html_body: "<p>Hi</p>".to_string(),
+        };
+        assert!(matches!(queue.send_immediate(bad.clone()).await, Err(EmailError::InvalidEmail)));
+
+        // Retrying can't fix the address, so it skips the backoff
+        queue.enqueue_now(bad).await.unwrap();
+        assert!(matches!(queue.process_next().await, Err(EmailQueueError::MaxRetriesExceeded)));
+        let stats = queue.get_stats().await.unwrap();
+        assert_eq!((stats.pending, stats.failed), (0, 1));
+
+        let dead = queue.dead_letters(10).await.unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].kind, "email");
+        assert_eq!(dead[0].to_email, "not an address");
+        assert_eq!(dead[0].last_error.as_deref(), Some("Invalid email address"));
+        assert!(dead[0].failed_at.is_some());
+
+        assert!(!queue.retry_dead_letter("no-such-job").await.unwrap());
+        assert!(queue.retry_dead_letter(&dead[0].id).await.unwrap());
+        assert!(!queue.retry_dead_letter(&dead[0].id).await.unwrap());
+        let stats = queue.get_stats().await.unwrap();
+        assert_eq!((stats.pending, stats.failed), (1, 0));
+
+        queue.process_next().await.unwrap_err();
+        queue.clear_dead_letters().await.unwrap();
+        assert!(queue.dead_letters(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_jobs_wait_in_process_until_due() {
+        let (queue, clock) = in_process_queue();
+        let job = EmailJob::with_delay(reminder(), clock.now().timestamp(), 60);
+        queue.enqueue(job).await.unwrap();
+
+        assert_eq!(queue.process_next().await.unwrap(), None);
+        assert_eq!(queue.get_stats().await.unwrap().pending, 1);
+
+        clock.advance(chrono::Duration::seconds(60));
+        // Sending is a no-op under TEST_MODE; otherwise it fails for lack of a
+        // mail server and is queued again for a retry
+        match queue.process_next().await {
+            Ok(sent) => assert!(sent.is_some()),
+            Err(_) => assert_eq!(queue.get_stats().await.unwrap().pending, 1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jobs_wait_in_process_while_redis_is_down() {
+        let (queue, clock) = in_process_queue();
+        let redis = Arc::new(RedisService::new("redis://127.0.0.1:1").unwrap());
+        let queue = EmailQueueService::new(Some(redis), queue.email_service, clock.clone());
+        let job = EmailJob::with_delay(reminder(), clock.now().timestamp(), 60);
+        queue.enqueue(job).await.unwrap();
+        assert!(queue.store.redis_down.load(Ordering::Relaxed));
+
+        // Not due yet: put back in process on every poll
+        for _ in 0..3 {
+            assert_eq!(queue.process_next().await.unwrap(), None);
+            assert_eq!(queue.store.local_len(PENDING_QUEUE), 1);
+        }
+        assert!(queue.store.redis_down.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_jobs_carry_the_request_that_queued_them() {
+        let now = 1_736_931_600;
+        let job = request_id::scope("req-1".to_string(), async { EmailJob::new(reminder(), now) }).await;
+        assert_eq!(job.request_id.as_deref(), Some("req-1"));
+        assert_eq!(EmailJob::new(reminder(), now).request_id, None);
+
+        // Jobs queued before the field existed still load
+        let mut queued = serde_json::to_value(&job).unwrap();
+        queued.as_object_mut().unwrap().remove("request_id");
+        let loaded: EmailJob = serde_json::from_value(queued).unwrap();
+        assert_eq!(loaded.request_id, None);
+    }
+}
@@ -3,10 +3,17 @@
 use lettre::{
     message::{Mailbox, MultiPart, SinglePart, header::{ContentType, ContentDisposition}},
     transport::smtp::{authentication::Credentials, client::{Tls, TlsParameters}},
-    Message, SmtpTransport, Transport,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::domain::i18n::{format_money, translate, Locale};
+use crate::domain::models::{EmailSignature, EmailTemplate, EmailTemplateKind};
+use crate::domain::services::email_templates::{EmailTemplates, RenderedEmail};
+
 #[derive(Debug, Error)]
 pub enum EmailError {
     #[error("SMTP error: {0}")]
@@ -17,6 +24,17 @@ pub enum EmailError {
 
     #[error("Failed to build message")]
     MessageBuildError,
+
+    /// The server permanently refused the message (5xx), e.g. an unknown mailbox
+    #[error("Rejected by mail server: {0}")]
+    Rejected(String),
+}
+
+impl EmailError {
+    /// The address itself is bad, so retrying it won't help but another might
+    pub fn is_hard_bounce(&self) -> bool {
+        matches!(self, EmailError::Rejected(_) | EmailError::InvalidEmail)
+    }
 }
 
 #[derive(Debug)]
@@ -29,6 +47,214 @@ pub struct EmailConfig {
     pub from_name: String,
 }
 
+/// One invoice line on a statement email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatementEmailLine {
+    pub invoice_number: String,
+    pub issue_date: String,
+    pub total_amount: f64,
+    pub balance_due: f64,
+}
+
+/// Contents of a monthly client statement email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientStatementEmail {
+    pub seller_name: String,
+    /// e.g. "March 2025"
+    pub period_label: String,
+    pub invoices: Vec<StatementEmailLine>,
+    pub period_invoiced: f64,
+    pub outstanding_balance: f64,
+}
+
+/// Download link for a file attached to an invoice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttachmentLink {
+    pub name: String,
+    pub url: String,
+}
+
+/// An invoice email with its PDF. Copies, subject and a personal message can be set per send.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvoiceEmail {
+    pub to_email: String,
+    pub to_name: String,
+    pub invoice_number: String,
+    pub amount: f64,
+    pub due_date: String,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    #[serde(default)]
+    pub bcc: Vec<String>,
+    /// Replaces the default "Invoice #... - $..." subject
+    pub subject: Option<String>,
+    /// Shown above the standard invoice text
+    pub message: Option<String>,
+    /// iCalendar event for the due date, attached as a .ics file
+    #[serde(default)]
+    pub calendar: Option<String>,
+    /// Sender's contact block, shown below the standard text
+    #[serde(default)]
+    pub signature: Option<EmailSignature>,
+    /// Links to the invoice's attachments, listed below the standard text
+    #[serde(default)]
+    pub attachments: Vec<AttachmentLink>,
+    /// Sender's business, for templates that name it
+    #[serde(default)]
+    pub business_name: Option<String>,
+    /// The sender's own invoice template; the per-send subject still wins
+    #[serde(default)]
+    pub template: Option<EmailTemplate>,
+    /// Language of the standard text and format of the amount
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+/// Jobs queued before amounts carried a currency were all in dollars
+pub(crate) fn default_currency() -> String {
+    "USD".to_string()
+}
+
+impl InvoiceEmail {
+    pub fn new(to_email: &str, to_name: &str, invoice_number: &str, amount: f64, due_date: &str) -> Self {
+        Self {
+            to_email: to_email.to_string(),
+            to_name: to_name.to_string(),
+            invoice_number: invoice_number.to_string(),
+            amount,
+            due_date: due_date.to_string(),
+            cc: Vec::new(),
+            bcc: Vec::new(),
+            subject: None,
+            message: None,
+            calendar: None,
+            signature: None,
+            attachments: Vec::new(),
+            business_name: None,
+            template: None,
+            locale: Locale::default(),
+            currency: default_currency(),
+        }
+    }
+
+    pub fn subject(&self) -> String {
+        match self.subject.as_deref().map(str::trim) {
+            Some(subject) if !subject.is_empty() => subject.to_string(),
+            _ => EmailTemplates::global().subject(EmailTemplateKind::Invoice, &self.template_data(), self.template.as_ref()),
+        }
+    }
+
+    pub fn html_body(&self) -> String {
+        EmailTemplates::global().body(EmailTemplateKind::Invoice, &self.template_data(), self.template.as_ref())
+    }
+
+    fn template_data(&self) -> serde_json::Value {
+        json!({
+            "business_name": self.business_name,
+            "client_name": self.to_name,
+            "invoice_number": self.invoice_number,
+            "amount": format_money(self.locale, self.amount, &self.currency),
+            "due_date": self.due_date,
+            "message": self.message.as_deref().map(str::trim).filter(|message| !message.is_empty()),
+            "attachments": self.attachments,
+            "signature": self.signature.as_ref().map(signature_html).unwrap_or_default(),
+            "locale": self.locale.code(),
+        })
+    }
+}
+
+/// A payment receipt for the client. The business name and template are the
+/// invoicing user's, when known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentConfirmationEmail {
+    pub to_email: String,
+    pub to_name: String,
+    pub invoice_number: String,
+    pub amount: f64,
+    pub payment_method: String,
+    #[serde(default)]
+    pub business_name: Option<String>,
+    #[serde(default)]
+    pub template: Option<EmailTemplate>,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+impl PaymentConfirmationEmail {
+    pub fn render(&self) -> RenderedEmail {
+        let data = json!({
+            "business_name": self.business_name,
+            "client_name": self.to_name,
+            "invoice_number": self.invoice_number,
+            "amount": format_money(self.locale, self.amount, &self.currency),
+            "payment_method": translate(self.locale, &self.payment_method),
+            "locale": self.locale.code(),
+        });
+        EmailTemplates::global().render(EmailTemplateKind::PaymentConfirmation, &data, self.template.as_ref())
+    }
+}
+
+/// A personalized dunning campaign email; the body is plain text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignEmail {
+    pub to_email: String,
+    pub to_name: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl CampaignEmail {
+    pub fn html_body(&self) -> String {
+        format!(
+            r#"
+            <html>
+            <body style="font-family: Arial, sans-serif; padding: 20px;">
+                <p style="white-space: pre-line;">{}</p>
+                <hr>
+                <p style="font-size: 12px; color: #666;">Sent with FlashBill</p>
+            </body>
+            </html>
+            "#,
+            escape_html(&self.body)
+        )
+    }
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// HTML block for an email signature: name, title and phone on separate lines, then links
+pub fn signature_html(signature: &EmailSignature) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(name) = &signature.name {
+        lines.push(format!("<strong>{}</strong>", escape_html(name)));
+    }
+    if let Some(title) = &signature.title {
+        lines.push(escape_html(title));
+    }
+    if let Some(phone) = &signature.phone {
+        lines.push(escape_html(phone));
+    }
+    if !signature.links.is_empty() {
+        let links: Vec<String> = signature
+            .links
+            .iter()
+            .map(|link| format!(r#"<a href="{}">{}</a>"#, escape_html(&link.url), escape_html(&link.label)))
+            .collect();
+        lines.push(links.join(" | "));
+    }
+
+    format!(r#"<p class="signature" style="color: #333;">{}</p>"#, lines.join("<br>"))
+}
+
 #[derive(Debug)]
 pub struct EmailService {
     config: EmailConfig,
@@ -45,7 +271,46 @@ impl EmailService {
         self.skip_queue
     }
 
-    pub fn send_invoice(
+    /// Whether `TEST_MODE` or `SKIP_EMAIL` turns sending into a no-op
+    pub fn sending_disabled() -> bool {
+        std::env::var("TEST_MODE").is_ok() || std::env::var("SKIP_EMAIL").is_ok()
+    }
+
+    fn mailer(&self, timeout: Option<Duration>) -> Result<AsyncSmtpTransport<Tokio1Executor>, EmailError> {
+        let credentials = Credentials::new(self.config.username.clone(), self.config.password.clone());
+        let mut builder = AsyncSmtpTransport::<Tokio1Executor>::relay(&self.config.smtp_host)
+            .map_err(|e| EmailError::SmtpError(e.to_string()))?
+            .port(self.config.smtp_port)
+            .credentials(credentials)
+            .tls(Tls::Required(
+                TlsParameters::new_native(self.config.smtp_host.clone())
+                    .map_err(|e| EmailError::SmtpError(e.to_string()))?
+            ));
+        if timeout.is_some() {
+            builder = builder.timeout(timeout);
+        }
+        Ok(builder.build())
+    }
+
+    async fn deliver(&self, email: Message) -> Result<(), EmailError> {
+        match self.mailer(None)?.send(email).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_permanent() => Err(EmailError::Rejected(e.to_string())),
+            Err(e) => Err(EmailError::SmtpError(e.to_string())),
+        }
+    }
+
+    /// Connects to the SMTP server and checks it answers, without signing in or
+    /// sending anything. Waits up to `timeout` per network operation.
+    pub async fn check_connection(&self, timeout: Duration) -> Result<(), EmailError> {
+        match self.mailer(Some(timeout))?.test_connection().await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(EmailError::SmtpError("server did not accept NOOP".to_string())),
+            Err(e) => Err(EmailError::SmtpError(e.to_string())),
+        }
+    }
+
+    pub async fn send_invoice(
         &self,
         to_email: &str,
         to_name: &str,
@@ -54,31 +319,17 @@ impl EmailService {
         amount: f64,
         due_date: &str,
     ) -> Result<(), EmailError> {
-        let subject = format!("Invoice #{} - ${:.2}", invoice_number, amount);
-
-        let body = format!(
-            r#"
-            <html>
-            <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Invoice #{}</h2>
-                <p>Hello {},</p>
-                <p>You have received an invoice for <strong>${:.2}</strong>.</p>
-                <p><strong>Due Date:</strong> {}</p>
-                <p>You can view and download your invoice using the link below:</p>
-                <p><a href="{}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">View Invoice</a></p>
-                <p>Thank you for your business!</p>
-                <hr>
-                <p style="font-size: 12px; color: #666;">This is an automated message from FlashBill</p>
-            </body>
-            </html>
-            "#,
-            invoice_number, to_name, amount, due_date, pdf_url
-        );
-
-        self.send_email(to_email, to_name, &subject, &body)
+        let data = json!({
+            "client_name": to_name,
+            "invoice_number": invoice_number,
+            "amount": format!("${:.2}", amount),
+            "due_date": due_date,
+            "invoice_url": pdf_url,
+        });
+        self.send_rendered(to_email, to_name, EmailTemplateKind::Invoice, &data).await
     }
 
-    pub fn send_payment_reminder(
+    pub async fn send_payment_reminder(
         &self,
         to_email: &str,
         to_name: &str,
@@ -87,7 +338,7 @@ impl EmailService {
         amount_due: f64,
         reminder_type: &str,
     ) -> Result<(), EmailError> {
-        let subject = match reminder_type {
+        let headline = match reminder_type {
             "friendly" => format!("Friendly reminder: Invoice #{}", invoice_number),
             "urgent" => format!("URGENT: Invoice #{} is overdue", invoice_number),
             "final_notice" => format!("FINAL NOTICE: Invoice #{}", invoice_number),
@@ -101,119 +352,344 @@ impl EmailService {
             _ => "This is a reminder",
         };
 
+        let data = json!({
+            "client_name": to_name,
+            "invoice_number": invoice_number,
+            "amount_due": format!("${:.2}", amount_due),
+            "days_overdue": days_overdue,
+            "headline": headline,
+            "message": format!("{} that this invoice is {} days overdue.", tone, days_overdue),
+            "payment_url": format!("https://app.flashbill.com/invoices/{}", invoice_number),
+        });
+        self.send_rendered(to_email, to_name, EmailTemplateKind::PaymentReminder, &data).await
+    }
+
+    pub async fn send_payment_confirmation(&self, confirmation: &PaymentConfirmationEmail) -> Result<(), EmailError> {
+        let email = confirmation.render();
+        self.send_email(&confirmation.to_email, &confirmation.to_name, &email.subject, &email.html_body).await
+    }
+
+    pub async fn send_password_reset(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        reset_token: &str,
+        locale: Locale,
+    ) -> Result<(), EmailError> {
+        let data = json!({
+            "name": to_name,
+            "reset_token": reset_token,
+            "reset_url": format!("https://app.flashbill.com/reset-password?token={}", reset_token),
+            "locale": locale.code(),
+        });
+        self.send_rendered(to_email, to_name, EmailTemplateKind::PasswordReset, &data).await
+    }
+
+    /// Sent when repeated wrong passwords lock the account
+    pub async fn send_account_locked(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        locked_minutes: i64,
+    ) -> Result<(), EmailError> {
+        let subject = "Your FlashBill account has been locked".to_string();
+
         let body = format!(
             r#"
             <html>
             <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Payment Reminder</h2>
+                <h2>Account Locked</h2>
                 <p>Hello {},</p>
-                <p>{} that invoice <strong>#{}</strong> is <strong>{} days overdue</strong>.</p>
-                <p><strong>Amount Due:</strong> ${:.2}</p>
-                <p>Please make payment as soon as possible to avoid late fees.</p>
-                <p><a href="https://app.flashbill.com/invoices/{}" style="background-color: #F44336; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Pay Now</a></p>
-                <p>If you have already paid, please disregard this email.</p>
+                <p>We locked your account for {} minutes after several failed sign-in attempts.</p>
+                <p>If this was you, wait and try again, or reset your password to unlock your account right away.</p>
+                <p><a href="https://app.flashbill.com/forgot-password" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Reset Password</a></p>
+                <p>If this was not you, we recommend resetting your password.</p>
                 <hr>
-                <p style="font-size: 12px; color: #666;">FlashBill Automated Reminder</p>
+                <p style="font-size: 12px; color: #666;">FlashBill Security</p>
             </body>
             </html>
             "#,
-            to_name, tone, invoice_number, days_overdue, amount_due, invoice_number
+            to_name, locked_minutes
         );
 
-        self.send_email(to_email, to_name, &subject, &body)
+        self.send_email(to_email, to_name, &subject, &body).await
     }
 
-    pub fn send_payment_confirmation(
+    pub async fn send_verification_email(
         &self,
         to_email: &str,
         to_name: &str,
-        invoice_number: &str,
-        amount: f64,
-        payment_method: &str,
+        verification_token: &str,
+        locale: Locale,
     ) -> Result<(), EmailError> {
-        let subject = format!("Payment Received - Invoice #{}", invoice_number);
+        let data = json!({
+            "name": to_name,
+            "verification_token": verification_token,
+            "verify_url": format!("https://app.flashbill.com/verify-email?token={}", verification_token),
+            "locale": locale.code(),
+        });
+        self.send_rendered(to_email, to_name, EmailTemplateKind::Verification, &data).await
+    }
+
+    /// Invitation for an external accountant with the code to sign in with
+    pub async fn send_accountant_invite(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        business_name: &str,
+        access_code: &str,
+        expires_on: &str,
+    ) -> Result<(), EmailError> {
+        let subject = format!("{} has shared their reports with you", business_name);
 
         let body = format!(
             r#"
             <html>
             <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Payment Confirmation</h2>
+                <h2>Accountant access</h2>
                 <p>Hello {},</p>
-                <p>We have received your payment of <strong>${:.2}</strong> for invoice <strong>#{}</strong>.</p>
-                <p><strong>Payment Method:</strong> {}</p>
-                <p>Your invoice has been marked as paid.</p>
-                <p>Thank you for your prompt payment!</p>
+                <p>{} has given you read-only access to their reports, exports and invoice PDFs on FlashBill.</p>
+                <p><a href="https://app.flashbill.com/accountant?code={}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Open Reports</a></p>
+                <p>If the button doesn't work, sign in with this access code: {}</p>
+                <p>Access ends on {} or when it is revoked.</p>
+                <hr>
+                <p style="font-size: 12px; color: #666;">FlashBill Team</p>
+            </body>
+            </html>
+            "#,
+            to_name, business_name, access_code, access_code, expires_on
+        );
+
+        self.send_email(to_email, to_name, &subject, &body).await
+    }
+
+    /// One-time sign-in link for the client portal
+    pub async fn send_portal_sign_in_link(&self, to_email: &str, token: &str, valid_minutes: i64) -> Result<(), EmailError> {
+        let subject = "Your FlashBill sign-in link".to_string();
+
+        let body = format!(
+            r#"
+            <html>
+            <body style="font-family: Arial, sans-serif; padding: 20px;">
+                <h2>Sign in to your invoices</h2>
+                <p>Use the button below to see and pay the invoices sent to {}.</p>
+                <p><a href="https://app.flashbill.com/portal/sign-in?token={}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Sign In</a></p>
+                <p>The link works once and expires in {} minutes. If you didn't ask for it, you can ignore this email.</p>
                 <hr>
-                <p style="font-size: 12px; color: #666;">FlashBill Payment Confirmation</p>
+                <p style="font-size: 12px; color: #666;">FlashBill Team</p>
             </body>
             </html>
             "#,
-            to_name, amount, invoice_number, payment_method
+            to_email, token, valid_minutes
         );
 
-        self.send_email(to_email, to_name, &subject, &body)
+        self.send_email(to_email, to_email, &subject, &body).await
     }
 
-    pub fn send_password_reset(
+    /// Monthly statement sent to a client
+    pub async fn send_client_statement(
         &self,
         to_email: &str,
         to_name: &str,
-        reset_token: &str,
+        statement: &ClientStatementEmail,
     ) -> Result<(), EmailError> {
-        let subject = "Password Reset Request".to_string();
+        let subject = format!("Statement for {} from {}", statement.period_label, statement.seller_name);
+
+        let rows: String = if statement.invoices.is_empty() {
+            r#"<tr><td colspan="4">No invoices issued this period.</td></tr>"#.to_string()
+        } else {
+            statement
+                .invoices
+                .iter()
+                .map(|line| {
+                    format!(
+                        "<tr><td>{}</td><td>{}</td><td>${:.2}</td><td>${:.2}</td></tr>",
+                        line.invoice_number, line.issue_date, line.total_amount, line.balance_due
+                    )
+                })
+                .collect()
+        };
 
         let body = format!(
             r#"
             <html>
             <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Password Reset</h2>
+                <h2>Statement for {}</h2>
                 <p>Hello {},</p>
-                <p>You have requested to reset your password.</p>
-                <p>Your reset token is:</p>
-                <p style="background-color: #f0f0f0; padding: 10px; font-family: monospace; font-size: 16px;">{}</p>
-                <p>This token will expire in 1 hour.</p>
-                <p><a href="https://app.flashbill.com/reset-password?token={}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Reset Password</a></p>
-                <p>If you did not request this, please ignore this email.</p>
+                <p>Here is your statement from <strong>{}</strong>.</p>
+                <table style="border-collapse: collapse;" cellpadding="6">
+                    <tr><th>Invoice</th><th>Issued</th><th>Total</th><th>Balance</th></tr>
+                    {}
+                </table>
+                <p><strong>Invoiced this period:</strong> ${:.2}</p>
+                <p><strong>Total outstanding:</strong> ${:.2}</p>
                 <hr>
-                <p style="font-size: 12px; color: #666;">FlashBill Security</p>
+                <p style="font-size: 12px; color: #666;">FlashBill Monthly Statement. Reply to this email to stop receiving monthly statements.</p>
             </body>
             </html>
             "#,
-            to_name, reset_token, reset_token
+            statement.period_label,
+            to_name,
+            statement.seller_name,
+            rows,
+            statement.period_invoiced,
+            statement.outstanding_balance
         );
 
-        self.send_email(to_email, to_name, &subject, &body)
+        self.send_email(to_email, to_name, &subject, &body).await
     }
 
-    pub fn send_verification_email(
+    /// Daily "needs attention" digest of failed background automation
+    pub async fn send_attention_digest(
         &self,
         to_email: &str,
         to_name: &str,
-        verification_token: &str,
+        items: &[(String, String)],
     ) -> Result<(), EmailError> {
-        let subject = "Verify Your Email Address".to_string();
+        let subject = format!("FlashBill: {} item(s) need your attention", items.len());
+
+        let rows: String = items
+            .iter()
+            .map(|(label, summary)| format!("<li><strong>{}</strong>: {}</li>", label, summary))
+            .collect();
 
         let body = format!(
             r#"
             <html>
             <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Welcome to FlashBill!</h2>
+                <h2>Needs Attention</h2>
                 <p>Hello {},</p>
-                <p>Please verify your email address by clicking the button below:</p>
-                <p><a href="https://app.flashbill.com/verify-email?token={}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Verify Email</a></p>
-                <p>If the button doesn't work, use this token: {}</p>
+                <p>Some automated tasks could not be completed since our last update:</p>
+                <ul>{}</ul>
+                <p><a href="https://app.flashbill.com/notifications/issues" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Review Issues</a></p>
                 <hr>
-                <p style="font-size: 12px; color: #666;">FlashBill Team</p>
+                <p style="font-size: 12px; color: #666;">FlashBill Daily Digest</p>
+            </body>
+            </html>
+            "#,
+            to_name, rows
+        );
+
+        self.send_email(to_email, to_name, &subject, &body).await
+    }
+
+    /// Spending reached 80% or 100% of one or more budget limits
+    pub async fn send_budget_alert(&self, to_email: &str, to_name: &str, items: &[String]) -> Result<(), EmailError> {
+        let subject = if items.len() == 1 {
+            "FlashBill: a budget limit needs your attention".to_string()
+        } else {
+            format!("FlashBill: {} budget limits need your attention", items.len())
+        };
+
+        let rows: String = items.iter().map(|item| format!("<li>{}</li>", item)).collect();
+
+        let body = format!(
+            r#"
+            <html>
+            <body style="font-family: Arial, sans-serif; padding: 20px;">
+                <h2>Budget Alert</h2>
+                <p>Hello {},</p>
+                <p>Your spending has reached:</p>
+                <ul>{}</ul>
+                <p><a href="https://app.flashbill.com/budgets" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">Review Budgets</a></p>
+                <hr>
+                <p style="font-size: 12px; color: #666;">FlashBill Budget Alerts</p>
             </body>
             </html>
             "#,
-            to_name, verification_token, verification_token
+            to_name, rows
         );
 
-        self.send_email(to_email, to_name, &subject, &body)
+        self.send_email(to_email, to_name, &subject, &body).await
+    }
+
+    /// Statement of account sent on request, with the statement PDF attached
+    pub async fn send_account_statement(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        seller_name: &str,
+        period_label: &str,
+        closing_balance: f64,
+        pdf_bytes: Vec<u8>,
+    ) -> Result<(), EmailError> {
+        let subject = format!("Statement for {} from {}", period_label, seller_name);
+
+        let body = format!(
+            r#"
+            <html>
+            <body style="font-family: Arial, sans-serif; padding: 20px;">
+                <h2>Statement for {}</h2>
+                <p>Hello {},</p>
+                <p>Please find attached your statement of account from <strong>{}</strong>.</p>
+                <p><strong>Balance due:</strong> {:.2}</p>
+                <hr>
+                <p style="font-size: 12px; color: #666;">FlashBill Statement of Account</p>
+            </body>
+            </html>
+            "#,
+            period_label, to_name, seller_name, closing_balance
+        );
+
+        self.send_email_with_pdf(to_email, to_name, &subject, &body, "statement.pdf", pdf_bytes).await
+    }
+
+    /// HTML email with one PDF attached
+    pub async fn send_email_with_pdf(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        subject: &str,
+        html_body: &str,
+        pdf_filename: &str,
+        pdf_bytes: Vec<u8>,
+    ) -> Result<(), EmailError> {
+        // Check if we're in test mode - skip actual email sending
+        if Self::sending_disabled() {
+            tracing::info!("TEST_MODE: Skipping email send to {} with {}", to_email, pdf_filename);
+            return Ok(());
+        }
+
+        let from_mailbox: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
+            .parse()
+            .map_err(|_| EmailError::InvalidEmail)?;
+
+        let to_mailbox: Mailbox = format!("{} <{}>", to_name, to_email)
+            .parse()
+            .map_err(|_| EmailError::InvalidEmail)?;
+
+        let email = Message::builder()
+            .from(from_mailbox)
+            .to(to_mailbox)
+            .subject(subject)
+            .multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::html(html_body.to_string()))
+                    .singlepart(
+                        SinglePart::builder()
+                            .header(ContentType::parse("application/pdf").map_err(|_| EmailError::MessageBuildError)?)
+                            .header(ContentDisposition::attachment(pdf_filename))
+                            .body(pdf_bytes),
+                    ),
+            )
+            .map_err(|_| EmailError::MessageBuildError)?;
+
+        self.deliver(email).await
     }
 
-    pub fn send_email(
+    /// Send one of the named emails with its built-in template
+    async fn send_rendered(
+        &self,
+        to_email: &str,
+        to_name: &str,
+        kind: EmailTemplateKind,
+        data: &serde_json::Value,
+    ) -> Result<(), EmailError> {
+        let email = EmailTemplates::global().render(kind, data, None);
+        self.send_email(to_email, to_name, &email.subject, &email.html_body).await
+    }
+
+    pub async fn send_email(
         &self,
         to_email: &str,
         to_name: &str,
@@ -221,7 +697,7 @@ impl EmailService {
         html_body: &str,
     ) -> Result<(), EmailError> {
         // Check if we're in test mode - skip actual email sending
-        if std::env::var("TEST_MODE").is_ok() || std::env::var("SKIP_EMAIL").is_ok() {
+        if Self::sending_disabled() {
             tracing::info!("TEST_MODE: Skipping email send to {}", to_email);
             return Ok(());
         }
@@ -242,26 +718,15 @@ impl EmailService {
             .body(html_body.to_string())
             .map_err(|_| EmailError::MessageBuildError)?;
 
-        let credentials = Credentials::new(self.config.username.clone(), self.config.password.clone());
-
-        let mailer = SmtpTransport::relay(&self.config.smtp_host)
-            .map_err(|e| EmailError::SmtpError(e.to_string()))?
-            .port(self.config.smtp_port)
-            .credentials(credentials)
-            .tls(Tls::Required(
-                TlsParameters::new_native(self.config.smtp_host.clone())
-                    .map_err(|e| EmailError::SmtpError(e.to_string()))?
-            ))
-            .build();
+        self.deliver(email).await
+    }
 
-        match mailer.send(&email) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(EmailError::SmtpError(e.to_string())),
-        }
+    pub async fn send_campaign_email(&self, email: &CampaignEmail) -> Result<(), EmailError> {
+        self.send_email(&email.to_email, &email.to_name, &email.subject, &email.html_body()).await
     }
 
     /// Send invoice with PDF attachment
-    pub fn send_invoice_with_attachment(
+    pub async fn send_invoice_with_attachment(
         &self,
         to_email: &str,
         to_name: &str,
@@ -270,75 +735,155 @@ impl EmailService {
         amount: f64,
         due_date: &str,
     ) -> Result<(), EmailError> {
+        let email = InvoiceEmail::new(to_email, to_name, invoice_number, amount, due_date);
+        self.send_invoice_email(&email, pdf_bytes).await
+    }
+
+    /// Send an invoice PDF, with any CC/BCC recipients, custom subject and personal message
+    pub async fn send_invoice_email(&self, invoice: &InvoiceEmail, pdf_bytes: Vec<u8>) -> Result<(), EmailError> {
         // Check if we're in test mode - skip actual email sending
-        if std::env::var("TEST_MODE").is_ok() || std::env::var("SKIP_EMAIL").is_ok() {
-            tracing::info!("TEST_MODE: Skipping email send to {} for invoice {}", to_email, invoice_number);
+        if Self::sending_disabled() {
+            tracing::info!("TEST_MODE: Skipping email send to {} for invoice {}", invoice.to_email, invoice.invoice_number);
             return Ok(());
         }
 
-        let subject = format!("Invoice #{} - ${:.2}", invoice_number, amount);
-
-        let html_body = format!(
-            r#"
-            <html>
-            <body style="font-family: Arial, sans-serif; padding: 20px;">
-                <h2>Invoice #{}</h2>
-                <p>Hello {},</p>
-                <p>You have received an invoice for <strong>${:.2}</strong>.</p>
-                <p><strong>Due Date:</strong> {}</p>
-                <p>Please find your invoice attached to this email as a PDF.</p>
-                <p>Thank you for your business!</p>
-                <hr>
-                <p style="font-size: 12px; color: #666;">This is an automated message from FlashBill</p>
-            </body>
-            </html>
-            "#,
-            invoice_number, to_name, amount, due_date
-        );
-
         let from_mailbox: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
             .parse()
             .map_err(|_| EmailError::InvalidEmail)?;
 
-        let to_mailbox: Mailbox = format!("{} <{}>", to_name, to_email)
+        let to_mailbox: Mailbox = format!("{} <{}>", invoice.to_name, invoice.to_email)
             .parse()
             .map_err(|_| EmailError::InvalidEmail)?;
 
-        // Create multipart message with HTML body and PDF attachment
-        let pdf_filename = format!("invoice_{}.pdf", invoice_number);
-        let email = Message::builder()
+        let mut builder = Message::builder()
             .from(from_mailbox)
             .to(to_mailbox)
-            .subject(subject)
-            .multipart(
-                MultiPart::mixed()
-                    .singlepart(
-                        SinglePart::html(html_body)
-                    )
-                    .singlepart(
-                        SinglePart::builder()
-                            .header(ContentType::parse("application/pdf").map_err(|_| EmailError::MessageBuildError)?)
-                            .header(ContentDisposition::attachment(&pdf_filename))
-                            .body(pdf_bytes)
-                    )
+            .subject(invoice.subject());
+
+        for address in &invoice.cc {
+            builder = builder.cc(address.parse().map_err(|_| EmailError::InvalidEmail)?);
+        }
+        for address in &invoice.bcc {
+            builder = builder.bcc(address.parse().map_err(|_| EmailError::InvalidEmail)?);
+        }
+
+        // Create multipart message with HTML body and PDF attachment
+        let pdf_filename = format!("invoice_{}.pdf", invoice.invoice_number);
+        let mut parts = MultiPart::mixed()
+            .singlepart(
+                SinglePart::html(invoice.html_body())
             )
+            .singlepart(
+                SinglePart::builder()
+                    .header(ContentType::parse("application/pdf").map_err(|_| EmailError::MessageBuildError)?)
+                    .header(ContentDisposition::attachment(&pdf_filename))
+                    .body(pdf_bytes)
+            );
+        if let Some(calendar) = &invoice.calendar {
+            parts = parts.singlepart(
+                SinglePart::builder()
+                    .header(
+                        ContentType::parse("text/calendar; charset=utf-8; method=PUBLISH")
+                            .map_err(|_| EmailError::MessageBuildError)?,
+                    )
+                    .header(ContentDisposition::attachment(&format!("invoice_{}_due.ics", invoice.invoice_number)))
+                    .body(calendar.clone()),
+            );
+        }
+        let email = builder
+            .multipart(parts)
             .map_err(|_| EmailError::MessageBuildError)?;
 
-        let credentials = Credentials::new(self.config.username.clone(), self.config.password.clone());
+        self.deliver(email).await
+    }
+}
 
-        let mailer = SmtpTransport::relay(&self.config.smtp_host)
-            .map_err(|e| EmailError::SmtpError(e.to_string()))?
-            .port(self.config.smtp_port)
-            .credentials(credentials)
-            .tls(Tls::Required(
-                TlsParameters::new_native(self.config.smtp_host.clone())
-                    .map_err(|e| EmailError::SmtpError(e.to_string()))?
-            ))
-            .build();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        match mailer.send(&email) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(EmailError::SmtpError(e.to_string())),
-        }
+    #[test]
+    fn test_hard_bounce_classification() {
+        assert!(EmailError::Rejected("550 5.1.1 unknown user".to_string()).is_hard_bounce());
+        assert!(EmailError::InvalidEmail.is_hard_bounce());
+        assert!(!EmailError::SmtpError("connection refused".to_string()).is_hard_bounce());
+    }
+
+    #[test]
+    fn test_invoice_email_defaults() {
+        let email = InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30");
+        assert_eq!(email.subject(), "Invoice #INV-001 - $150.00");
+        assert!(!email.html_body().contains("pre-line"));
+    }
+
+    #[test]
+    fn test_invoice_email_custom_subject_and_escaped_message() {
+        let email = InvoiceEmail {
+            subject: Some("April retainer".to_string()),
+            message: Some("Thanks <b>again</b> & see you soon".to_string()),
+            ..InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+        };
+        assert_eq!(email.subject(), "April retainer");
+        let body = email.html_body();
+        assert!(body.contains("Thanks &lt;b&gt;again&lt;/b&gt; &amp; see you soon"));
+        assert!(body.find("Thanks").unwrap() < body.find("You have received").unwrap());
+    }
+
+    #[test]
+    fn test_invoice_email_lists_attachment_links() {
+        let email = InvoiceEmail {
+            attachments: vec![AttachmentLink {
+                name: "Timesheet <March>.xlsx".to_string(),
+                url: "https://app.flashbill.com/api/v1/guest/invoice/guest_x/attachments/1".to_string(),
+            }],
+            ..InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+        };
+        let body = email.html_body();
+        assert!(body.contains(
+            r#"<li><a href="https://app.flashbill.com/api/v1/guest/invoice/guest_x/attachments/1">Timesheet &lt;March&gt;.xlsx</a></li>"#
+        ));
+        assert!(!InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+            .html_body()
+            .contains("Attachments:"));
+    }
+
+    #[test]
+    fn test_invoice_email_uses_the_senders_template_but_a_per_send_subject_wins() {
+        let email = InvoiceEmail {
+            business_name: Some("Acme".to_string()),
+            template: Some(EmailTemplate {
+                subject: Some("{{business_name}}: invoice {{invoice_number}}".to_string()),
+                body: Some("<p>Hi {{client_name}}, {{amount}} is due {{due_date}}.</p>".to_string()),
+            }),
+            ..InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+        };
+        assert_eq!(email.subject(), "Acme: invoice INV-001");
+        assert_eq!(email.html_body(), "<p>Hi Client, $150.00 is due 2025-04-30.</p>");
+
+        let email = InvoiceEmail { subject: Some("April retainer".to_string()), ..email };
+        assert_eq!(email.subject(), "April retainer");
+    }
+
+    #[test]
+    fn test_invoice_email_signature_is_escaped() {
+        let email = InvoiceEmail {
+            signature: Some(EmailSignature {
+                name: Some("Jane <Doe>".to_string()),
+                title: Some("Billing".to_string()),
+                phone: None,
+                links: vec![crate::domain::models::SignatureLink {
+                    label: "Site".to_string(),
+                    url: "https://example.com/?a=1&b=\"2\"".to_string(),
+                }],
+            }),
+            ..InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+        };
+        let body = email.html_body();
+        assert!(body.contains("<strong>Jane &lt;Doe&gt;</strong><br>Billing<br>"));
+        assert!(body.contains(r#"<a href="https://example.com/?a=1&amp;b=&quot;2&quot;">Site</a>"#));
+        assert!(body.find("signature").unwrap() < body.find("automated message").unwrap());
+        assert!(!InvoiceEmail::new("client@example.com", "Client", "INV-001", 150.0, "2025-04-30")
+            .html_body()
+            .contains("signature"));
     }
 }
@@ -0,0 +1,58 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{EmailSignature, EmailSignatureSettings};
+use crate::infrastructure::repositories::EmailSignatureRepository;
+
+#[derive(Debug, Error)]
+pub enum EmailSignatureError {
+    #[error("Signature not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for EmailSignatureError {
+    fn from(err: sqlx::Error) -> Self {
+        EmailSignatureError::DatabaseError(err.to_string())
+    }
+}
+
+/// Business-wide default signature and per-member overrides
+pub struct EmailSignatureService {
+    repo: EmailSignatureRepository,
+}
+
+impl EmailSignatureService {
+    pub fn new(repo: EmailSignatureRepository) -> Self {
+        Self { repo }
+    }
+
+    pub async fn get(&self, user_id: Uuid, member_id: Uuid) -> Result<EmailSignatureSettings, EmailSignatureError> {
+        let default = self.repo.find(user_id, None).await?;
+        let member = self.repo.find(user_id, Some(member_id)).await?;
+        Ok(EmailSignatureSettings::new(default, member))
+    }
+
+    /// Pass a member to set their override, or None for the business default
+    pub async fn set(
+        &self,
+        user_id: Uuid,
+        member_id: Option<Uuid>,
+        signature: EmailSignature,
+    ) -> Result<EmailSignature, EmailSignatureError> {
+        let signature = signature.normalize().map_err(EmailSignatureError::Validation)?;
+        Ok(self.repo.upsert(user_id, member_id, &signature).await?)
+    }
+
+    pub async fn delete(&self, user_id: Uuid, member_id: Option<Uuid>) -> Result<(), EmailSignatureError> {
+        if !self.repo.delete(user_id, member_id).await? {
+            return Err(EmailSignatureError::NotFound);
+        }
+        Ok(())
+    }
+}
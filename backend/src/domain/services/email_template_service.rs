@@ -0,0 +1,141 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::i18n::{format_date, format_money};
+use crate::domain::models::{
+    EmailTemplate, EmailTemplateInfo, EmailTemplateKind, EmailTemplatePreview, PreviewEmailTemplate,
+};
+use crate::domain::services::email_service::signature_html;
+use crate::domain::services::email_templates::{default_body, default_subject, EmailTemplates};
+use crate::infrastructure::repositories::{EmailSignatureRepository, EmailTemplateRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum EmailTemplateError {
+    #[error("Email template not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for EmailTemplateError {
+    fn from(err: sqlx::Error) -> Self {
+        EmailTemplateError::DatabaseError(err.to_string())
+    }
+}
+
+/// Users' own subjects and bodies for the emails they send clients
+pub struct EmailTemplateService {
+    repo: EmailTemplateRepository,
+    users: UserRepository,
+    signatures: EmailSignatureRepository,
+}
+
+impl EmailTemplateService {
+    pub fn new(repo: EmailTemplateRepository, users: UserRepository, signatures: EmailSignatureRepository) -> Self {
+        Self { repo, users, signatures }
+    }
+
+    /// Every named email with its built-in template and the user's override
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<EmailTemplateInfo>, EmailTemplateError> {
+        let mut custom = self.repo.list(user_id).await?;
+        Ok(EmailTemplateKind::ALL
+            .into_iter()
+            .map(|kind| EmailTemplateInfo {
+                kind,
+                customizable: kind.is_customizable(),
+                variables: kind.variables().iter().map(|v| v.to_string()).collect(),
+                default_subject: default_subject(kind).to_string(),
+                default_body: default_body(kind).to_string(),
+                custom: custom.iter().position(|(k, _)| *k == kind).map(|i| custom.swap_remove(i).1),
+            })
+            .collect())
+    }
+
+    pub async fn set(
+        &self,
+        user_id: Uuid,
+        kind: EmailTemplateKind,
+        template: EmailTemplate,
+    ) -> Result<EmailTemplate, EmailTemplateError> {
+        let template = check_customizable(kind)
+            .and_then(|_| template.normalize())
+            .and_then(|template| EmailTemplates::global().validate(kind, &template).map(|_| template))
+            .map_err(EmailTemplateError::Validation)?;
+        Ok(self.repo.upsert(user_id, kind, &template).await?)
+    }
+
+    pub async fn delete(&self, user_id: Uuid, kind: EmailTemplateKind) -> Result<(), EmailTemplateError> {
+        if !self.repo.delete(user_id, kind).await? {
+            return Err(EmailTemplateError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Render with example values, the user's business name, locale and their
+    /// default signature. A subject or body left out of the request comes from the
+    /// saved override, then the built-in template.
+    pub async fn preview(
+        &self,
+        user_id: Uuid,
+        request: PreviewEmailTemplate,
+    ) -> Result<EmailTemplatePreview, EmailTemplateError> {
+        let kind = request.kind;
+        let given = EmailTemplate {
+            subject: request.subject.filter(|subject| !subject.trim().is_empty()),
+            body: request.body.filter(|body| !body.trim().is_empty()),
+        };
+        if given != EmailTemplate::default() {
+            check_customizable(kind).map_err(EmailTemplateError::Validation)?;
+        }
+        let saved = match kind.is_customizable() {
+            true => self.repo.find(user_id, kind).await?.unwrap_or_default(),
+            false => EmailTemplate::default(),
+        };
+        let template = EmailTemplate {
+            subject: given.subject.or(saved.subject),
+            body: given.body.or(saved.body),
+        };
+
+        let user = self.users.find_by_id(user_id).await?;
+        let business_name = user
+            .as_ref()
+            .and_then(|user| user.company_name.clone())
+            .unwrap_or_else(|| "Your Business".to_string());
+        let mut data = kind.sample_data(&business_name);
+        // Example amounts and dates as this user's clients would see them
+        if let Some(user) = &user {
+            data["locale"] = user.locale.code().into();
+            for field in ["amount", "amount_due"] {
+                if data.get(field).is_some() {
+                    data[field] = format_money(user.locale, 1250.0, &user.currency).into();
+                }
+            }
+            if data.get("due_date").is_some() {
+                data["due_date"] = format_date(user.locale, NaiveDate::from_ymd_opt(2025, 5, 31).unwrap_or_default()).into();
+            }
+        }
+        if kind.variables().contains(&"signature") {
+            if let Some(signature) = self.signatures.resolve(user_id, None).await? {
+                data["signature"] = signature_html(&signature).into();
+            }
+        }
+
+        let rendered = EmailTemplates::global()
+            .render_strict(kind, &data, &template)
+            .map_err(EmailTemplateError::Validation)?;
+        Ok(EmailTemplatePreview { subject: rendered.subject, html_body: rendered.html_body })
+    }
+}
+
+fn check_customizable(kind: EmailTemplateKind) -> Result<(), String> {
+    if kind.is_customizable() {
+        Ok(())
+    } else {
+        Err(format!("The {} email can't be customized", kind.as_str()))
+    }
+}
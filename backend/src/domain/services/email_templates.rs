@@ -0,0 +1,240 @@
+use handlebars::{
+    no_escape, Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext, RenderError,
+    RenderErrorReason,
+};
+use serde_json::Value;
+use std::sync::LazyLock;
+
+use crate::domain::i18n::{translate_with, Locale};
+use crate::domain::models::{EmailTemplate, EmailTemplateKind};
+use crate::domain::services::email_service::escape_html;
+
+static TEMPLATES: LazyLock<EmailTemplates> = LazyLock::new(EmailTemplates::new);
+
+/// Subject and HTML body of a rendered email
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html_body: String,
+}
+
+/// Handlebars templates for the named emails. Subjects are plain text; bodies
+/// are HTML with values escaped, except `{{{signature}}}` which is rendered
+/// HTML already. A user's override is tried first and the built-in template
+/// used if it fails, so a broken override never stops an email going out.
+/// `{{t "Hello {}," client_name}}` writes a wording in the data's `locale`.
+pub struct EmailTemplates {
+    html: Handlebars<'static>,
+    text: Handlebars<'static>,
+    // Unknown placeholders are errors, for checking overrides before they are saved
+    strict_html: Handlebars<'static>,
+    strict_text: Handlebars<'static>,
+}
+
+impl EmailTemplates {
+    pub fn global() -> &'static EmailTemplates {
+        &TEMPLATES
+    }
+
+    fn new() -> Self {
+        let registry = |escape: bool, strict: bool| {
+            let mut registry = Handlebars::new();
+            if escape {
+                registry.register_escape_fn(escape_html);
+            } else {
+                registry.register_escape_fn(no_escape);
+            }
+            registry.set_strict_mode(strict);
+            registry.register_helper("t", Box::new(TranslateHelper));
+            registry
+        };
+        let mut templates = Self {
+            html: registry(true, false),
+            text: registry(false, false),
+            strict_html: registry(true, true),
+            strict_text: registry(false, true),
+        };
+        for kind in EmailTemplateKind::ALL {
+            for html in [&mut templates.html, &mut templates.strict_html] {
+                html.register_template_string(kind.as_str(), default_body(kind))
+                    .expect("Built-in email body is valid");
+            }
+            for text in [&mut templates.text, &mut templates.strict_text] {
+                text.register_template_string(kind.as_str(), default_subject(kind))
+                    .expect("Built-in email subject is valid");
+            }
+        }
+        templates
+    }
+
+    pub fn subject(&self, kind: EmailTemplateKind, data: &Value, custom: Option<&EmailTemplate>) -> String {
+        let subject = custom
+            .and_then(|template| template.subject.as_deref())
+            .and_then(|source| self.text.render_template(source, data).map_err(|e| log_fallback(kind, e)).ok())
+            .unwrap_or_else(|| self.text.render(kind.as_str(), data).unwrap_or_default());
+        single_line(&subject)
+    }
+
+    pub fn body(&self, kind: EmailTemplateKind, data: &Value, custom: Option<&EmailTemplate>) -> String {
+        custom
+            .and_then(|template| template.body.as_deref())
+            .and_then(|source| self.html.render_template(source, data).map_err(|e| log_fallback(kind, e)).ok())
+            .unwrap_or_else(|| self.html.render(kind.as_str(), data).unwrap_or_default())
+    }
+
+    pub fn render(&self, kind: EmailTemplateKind, data: &Value, custom: Option<&EmailTemplate>) -> RenderedEmail {
+        RenderedEmail { subject: self.subject(kind, data, custom), html_body: self.body(kind, data, custom) }
+    }
+
+    /// Render strictly, failing on syntax errors and placeholders `data` has no
+    /// value for. Parts `template` leaves out use the built-in ones.
+    pub fn render_strict(
+        &self,
+        kind: EmailTemplateKind,
+        data: &Value,
+        template: &EmailTemplate,
+    ) -> Result<RenderedEmail, String> {
+        let subject = match template.subject.as_deref() {
+            Some(source) => self.strict_text.render_template(source, data),
+            None => self.strict_text.render(kind.as_str(), data),
+        }
+        .map_err(|e| format!("Subject: {}", e))?;
+        let html_body = match template.body.as_deref() {
+            Some(source) => self.strict_html.render_template(source, data),
+            None => self.strict_html.render(kind.as_str(), data),
+        }
+        .map_err(|e| format!("Body: {}", e))?;
+        Ok(RenderedEmail { subject: single_line(&subject), html_body })
+    }
+
+    /// Check an override only uses the kind's variables
+    pub fn validate(&self, kind: EmailTemplateKind, template: &EmailTemplate) -> Result<(), String> {
+        self.render_strict(kind, &kind.sample_data("Example Co"), template).map(|_| ())
+    }
+}
+
+/// Handlebars source of a kind's built-in subject
+pub fn default_subject(kind: EmailTemplateKind) -> &'static str {
+    match kind {
+        EmailTemplateKind::Invoice => r#"{{t "Invoice #{} - {}" invoice_number amount}}"#,
+        EmailTemplateKind::PaymentReminder => "{{#if business_name}}[{{business_name}}] {{/if}}{{headline}}",
+        EmailTemplateKind::PaymentConfirmation => r#"{{t "Payment Received - Invoice #{}" invoice_number}}"#,
+        EmailTemplateKind::PasswordReset => r#"{{t "Password Reset Request"}}"#,
+        EmailTemplateKind::Verification => r#"{{t "Verify Your Email Address"}}"#,
+    }
+}
+
+/// Handlebars source of a kind's built-in HTML body
+pub fn default_body(kind: EmailTemplateKind) -> &'static str {
+    match kind {
+        EmailTemplateKind::Invoice => include_str!("../../templates/email/invoice.html.hbs"),
+        EmailTemplateKind::PaymentReminder => include_str!("../../templates/email/payment_reminder.html.hbs"),
+        EmailTemplateKind::PaymentConfirmation => include_str!("../../templates/email/payment_confirmation.html.hbs"),
+        EmailTemplateKind::PasswordReset => include_str!("../../templates/email/password_reset.html.hbs"),
+        EmailTemplateKind::Verification => include_str!("../../templates/email/verification.html.hbs"),
+    }
+}
+
+/// `{{t "wording {}" arg...}}`: the catalog wording in the root data's `locale`
+/// with each `{}` filled by an argument, escaped as the registry escapes values
+struct TranslateHelper;
+
+impl HelperDef for TranslateHelper {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let text = h
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex("t", 0))?;
+        let mut args = Vec::with_capacity(h.params().len().saturating_sub(1));
+        for param in h.params().iter().skip(1) {
+            if r.strict_mode() && param.is_value_missing() {
+                return Err(RenderError::strict_error(param.relative_path()));
+            }
+            args.push(r.get_escape_fn()(&param.render()));
+        }
+        let locale = ctx.data().get("locale").and_then(Value::as_str).and_then(Locale::parse).unwrap_or_default();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        out.write(&translate_with(locale, text, &args))?;
+        Ok(())
+    }
+}
+
+fn log_fallback(kind: EmailTemplateKind, error: handlebars::RenderError) {
+    tracing::warn!("Custom {} email template failed, using the built-in one: {}", kind.as_str(), error);
+}
+
+/// Values can hold line breaks; a subject header can't
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_built_in_templates_render_every_kind_strictly() {
+        let templates = EmailTemplates::global();
+        for kind in EmailTemplateKind::ALL {
+            let rendered = templates.render_strict(kind, &kind.sample_data("Acme"), &EmailTemplate::default()).unwrap();
+            assert!(!rendered.subject.is_empty());
+            assert!(!rendered.html_body.contains("{{"), "{} left a placeholder", kind.as_str());
+        }
+    }
+
+    #[test]
+    fn test_override_escapes_values_and_keeps_subjects_plain() {
+        let template = EmailTemplate {
+            subject: Some("{{business_name}} invoice {{invoice_number}}".to_string()),
+            body: Some("<p>Dear {{client_name}}, pay {{amount}}</p>{{{signature}}}".to_string()),
+        };
+        let data = json!({
+            "business_name": "Smith & Sons",
+            "client_name": "<Jane>",
+            "invoice_number": "INV-1",
+            "amount": "$5.00",
+            "signature": "<p class=\"signature\">Bob</p>",
+        });
+        let rendered = EmailTemplates::global().render(EmailTemplateKind::Invoice, &data, Some(&template));
+        assert_eq!(rendered.subject, "Smith & Sons invoice INV-1");
+        assert_eq!(rendered.html_body, "<p>Dear &lt;Jane&gt;, pay $5.00</p><p class=\"signature\">Bob</p>");
+    }
+
+    #[test]
+    fn test_unknown_placeholders_are_rejected_and_broken_overrides_fall_back() {
+        let templates = EmailTemplates::global();
+        let typo = EmailTemplate { subject: Some("Invoice {{invoice_numbr}}".to_string()), body: None };
+        assert!(templates.validate(EmailTemplateKind::Invoice, &typo).is_err());
+        let unclosed = EmailTemplate { subject: None, body: Some("{{#if message}}<p>x</p>".to_string()) };
+        assert!(templates.validate(EmailTemplateKind::Invoice, &unclosed).is_err());
+        let fine = EmailTemplate { subject: Some("{{business_name}}: {{invoice_number}}".to_string()), body: None };
+        assert!(templates.validate(EmailTemplateKind::Invoice, &fine).is_ok());
+
+        let data = EmailTemplateKind::Invoice.sample_data("Acme");
+        let rendered = templates.render(EmailTemplateKind::Invoice, &data, Some(&unclosed));
+        assert!(rendered.html_body.contains("You have received an invoice for <strong>$1,250.00</strong>"));
+    }
+
+    #[test]
+    fn test_built_in_templates_follow_the_data_locale() {
+        let mut data = EmailTemplateKind::Invoice.sample_data("Müller & Co");
+        data["locale"] = "de".into();
+        data["client_name"] = "<Jana>".into();
+        data["amount"] = "1.250,00 €".into();
+        let rendered = EmailTemplates::global().render(EmailTemplateKind::Invoice, &data, None);
+        assert_eq!(rendered.subject, "Rechnung Nr. INV-2025-0042 - 1.250,00 €");
+        assert!(rendered.html_body.contains("<p>Hallo &lt;Jana&gt;,</p>"));
+        assert!(rendered.html_body.contains("Sie haben eine Rechnung über <strong>1.250,00 €</strong> erhalten."));
+
+        let custom = EmailTemplate { subject: Some("{{t \"Invoice #{}\" invoice_numbr}}".to_string()), body: None };
+        assert!(EmailTemplates::global().validate(EmailTemplateKind::Invoice, &custom).is_err());
+    }
+}
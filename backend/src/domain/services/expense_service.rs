@@ -2,17 +2,45 @@ use std::sync::Arc;
 use uuid::Uuid;
 use chrono::NaiveDate;
 
+use crate::domain::services::BudgetService;
 use crate::infrastructure::repositories::ExpenseRepository;
-use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, CreateExpense, UpdateExpense};
+use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, Page, PageRequest, CreateExpense, UpdateExpense, CurrencyRounding, CurrencyRoundingRules};
+
+/// Expenses are recorded in a single currency for now
+const EXPENSE_CURRENCY: &str = "USD";
 
 #[derive(Clone)]
 pub struct ExpenseService {
     expense_repo: Arc<ExpenseRepository>,
+    rounding_rules: CurrencyRoundingRules,
+    budgets: Option<Arc<BudgetService>>,
 }
 
 impl ExpenseService {
     pub fn new(expense_repo: Arc<ExpenseRepository>) -> Self {
-        Self { expense_repo }
+        Self { expense_repo, rounding_rules: CurrencyRoundingRules::from_env(), budgets: None }
+    }
+
+    /// Check budget limits after each expense is recorded or changed
+    pub fn with_budget_alerts(mut self, budgets: Arc<BudgetService>) -> Self {
+        self.budgets = Some(budgets);
+        self
+    }
+
+    /// In the background, so recording an expense doesn't wait on the alert email
+    fn check_budgets(&self, user_id: Uuid) {
+        if let Some(budgets) = self.budgets.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = budgets.check_alerts(user_id).await {
+                    tracing::warn!(user_id = %user_id, "Budget alert check failed: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Rounding applied to input tax derived from a rate
+    pub fn rounding(&self) -> CurrencyRounding {
+        self.rounding_rules.for_currency(EXPENSE_CURRENCY)
     }
 
     pub async fn create_expense(
@@ -20,17 +48,21 @@ impl ExpenseService {
         user_id: Uuid,
         create: CreateExpense,
     ) -> Result<Expense, sqlx::Error> {
-        self.expense_repo.create(
+        let expense = self.expense_repo.create(
             user_id,
             create.amount,
-            "USD".to_string(),
+            EXPENSE_CURRENCY.to_string(),
             create.category,
             create.vendor,
             create.description,
             create.receipt_image_url,
             create.date_incurred,
             create.tax_deductible,
-        ).await
+            create.tax_rate,
+            create.tax_amount.unwrap_or_default(),
+        ).await?;
+        self.check_budgets(user_id);
+        Ok(expense)
     }
 
     pub async fn get_expense(
@@ -49,10 +81,9 @@ impl ExpenseService {
         date_to: Option<NaiveDate>,
         tax_deductible: Option<bool>,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ExpenseResponse>, sqlx::Error> {
-        self.expense_repo.list(user_id, category, date_from, date_to, tax_deductible, search, limit, offset).await
+        page: &PageRequest,
+    ) -> Result<Page<ExpenseResponse>, sqlx::Error> {
+        self.expense_repo.list(user_id, category, date_from, date_to, tax_deductible, search, page).await
     }
 
     pub async fn update_expense(
@@ -61,7 +92,7 @@ impl ExpenseService {
         expense_id: Uuid,
         update: UpdateExpense,
     ) -> Result<Expense, sqlx::Error> {
-        self.expense_repo.update(
+        let expense = self.expense_repo.update(
             user_id,
             expense_id,
             update.amount,
@@ -71,7 +102,11 @@ impl ExpenseService {
             update.receipt_image_url,
             update.date_incurred,
             update.tax_deductible,
-        ).await
+            update.tax_rate,
+            update.tax_amount,
+        ).await?;
+        self.check_budgets(user_id);
+        Ok(expense)
     }
 
     pub async fn delete_expense(&self, user_id: Uuid, expense_id: Uuid) -> Result<(), sqlx::Error> {
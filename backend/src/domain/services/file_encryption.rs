@@ -0,0 +1,275 @@
+//! Envelope encryption for uploaded files. Each tenant has its own data key,
+//! stored wrapped under a server master key; files are sealed with the data
+//! key and carry its ID in a small header.
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::domain::services::FileError;
+use crate::infrastructure::repositories::{TenantDataKey, TenantKeyRepository};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+const MAGIC: &[u8; 6] = b"FBENC1";
+const HEADER_LEN: usize = MAGIC.len() + 16 + NONCE_LEN;
+
+/// Bytes an encrypted file adds to its plaintext
+pub const ENCRYPTION_OVERHEAD: u64 = (HEADER_LEN + TAG_LEN) as u64;
+
+/// Master keys by ID. The first key wraps new data keys; the others only
+/// unwrap existing ones, which allows rotating the master key.
+#[derive(Clone)]
+pub struct MasterKeyRing {
+    active: String,
+    keys: HashMap<String, [u8; KEY_LEN]>,
+}
+
+impl std::fmt::Debug for MasterKeyRing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MasterKeyRing").field("active", &self.active).finish_non_exhaustive()
+    }
+}
+
+impl MasterKeyRing {
+    /// `FILE_ENCRYPTION_KEYS`, or None when file encryption isn't configured
+    pub fn from_env() -> Result<Option<Self>, String> {
+        match std::env::var("FILE_ENCRYPTION_KEYS") {
+            Ok(spec) if !spec.trim().is_empty() => Self::parse(&spec).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Parses `id:base64key,id:base64key` with 32-byte keys, active key first
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut active = None;
+        let mut keys = HashMap::new();
+
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, encoded) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("Expected id:base64key, got '{}'", entry.split(':').next().unwrap_or("")))?;
+            let id = id.trim();
+            if id.is_empty() || id.len() > 50 {
+                return Err("Master key IDs must be 1-50 characters".to_string());
+            }
+            let key: [u8; KEY_LEN] = STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| format!("Master key '{}' must be {} bytes, base64 encoded", id, KEY_LEN))?;
+            if keys.insert(id.to_string(), key).is_some() {
+                return Err(format!("Master key '{}' is listed twice", id));
+            }
+            active.get_or_insert_with(|| id.to_string());
+        }
+
+        let active = active.ok_or_else(|| "No master keys given".to_string())?;
+        Ok(Self { active, keys })
+    }
+
+    /// A new random data key for `user_id`, and its wrapped form
+    fn generate_data_key(&self, user_id: Uuid) -> Result<([u8; KEY_LEN], TenantDataKey), FileError> {
+        let key: [u8; KEY_LEN] = Aes256Gcm::generate_key(OsRng).into();
+        let wrapped_key = seal(&self.keys[&self.active], user_id.as_bytes(), &key)?;
+        let record = TenantDataKey {
+            id: Uuid::new_v4(),
+            master_key_id: self.active.clone(),
+            wrapped_key,
+        };
+        Ok((key, record))
+    }
+
+    /// The wrapped key is bound to its tenant, so a copied row won't unwrap for another
+    fn unwrap_data_key(&self, user_id: Uuid, record: &TenantDataKey) -> Result<[u8; KEY_LEN], FileError> {
+        let master = self.keys.get(&record.master_key_id).ok_or_else(|| {
+            FileError::Encryption(format!("Master key '{}' is not configured", record.master_key_id))
+        })?;
+        open(master, user_id.as_bytes(), &record.wrapped_key)?
+            .try_into()
+            .map_err(|_| FileError::Encryption("Stored data key has the wrong length".to_string()))
+    }
+}
+
+/// Encrypts and decrypts tenant files with the tenant's data key
+#[derive(Clone)]
+pub struct FileEncryption {
+    master_keys: MasterKeyRing,
+    repo: TenantKeyRepository,
+}
+
+impl FileEncryption {
+    pub fn new(master_keys: MasterKeyRing, repo: TenantKeyRepository) -> Self {
+        Self { master_keys, repo }
+    }
+
+    /// Seals `plaintext` with the tenant's active data key, creating it on
+    /// first use. Returns the key ID alongside the encrypted file.
+    pub async fn encrypt(&self, user_id: Uuid, plaintext: &[u8]) -> Result<(Uuid, Vec<u8>), FileError> {
+        let record = match self.repo.find_active(user_id).await? {
+            Some(record) => record,
+            None => {
+                let (_, record) = self.master_keys.generate_data_key(user_id)?;
+                self.repo.create_active(user_id, &record).await?
+            }
+        };
+        let key = self.master_keys.unwrap_data_key(user_id, &record)?;
+        Ok((record.id, encrypt_file(&key, record.id, user_id, plaintext)?))
+    }
+
+    pub async fn decrypt(&self, user_id: Uuid, file: &[u8]) -> Result<Vec<u8>, FileError> {
+        let key_id = encrypted_key_id(file).ok_or_else(|| FileError::Encryption("File is not encrypted".to_string()))?;
+        let record = self
+            .repo
+            .find(user_id, key_id)
+            .await?
+            .ok_or_else(|| FileError::Encryption(format!("Data key {} not found for this tenant", key_id)))?;
+        let key = self.master_keys.unwrap_data_key(user_id, &record)?;
+        decrypt_file(&key, user_id, file)
+    }
+}
+
+/// The data key ID in an encrypted file's header; None for plaintext files
+pub fn encrypted_key_id(file: &[u8]) -> Option<Uuid> {
+    let header = file.strip_prefix(MAGIC.as_slice())?;
+    Uuid::from_slice(header.get(..16)?).ok()
+}
+
+fn file_aad(user_id: Uuid, key_id: Uuid) -> [u8; 32] {
+    let mut aad = [0u8; 32];
+    aad[..16].copy_from_slice(user_id.as_bytes());
+    aad[16..].copy_from_slice(key_id.as_bytes());
+    aad
+}
+
+/// `MAGIC || key ID || nonce || ciphertext`, authenticated with the tenant and key ID
+fn encrypt_file(key: &[u8; KEY_LEN], key_id: Uuid, user_id: Uuid, plaintext: &[u8]) -> Result<Vec<u8>, FileError> {
+    let sealed = seal(key, &file_aad(user_id, key_id), plaintext)?;
+    let mut file = Vec::with_capacity(MAGIC.len() + 16 + sealed.len());
+    file.extend_from_slice(MAGIC);
+    file.extend_from_slice(key_id.as_bytes());
+    file.extend_from_slice(&sealed);
+    Ok(file)
+}
+
+fn decrypt_file(key: &[u8; KEY_LEN], user_id: Uuid, file: &[u8]) -> Result<Vec<u8>, FileError> {
+    let key_id = encrypted_key_id(file).ok_or_else(|| FileError::Encryption("File is not encrypted".to_string()))?;
+    open(key, &file_aad(user_id, key_id), &file[MAGIC.len() + 16..])
+}
+
+/// `nonce || ciphertext` under a fresh random nonce
+fn seal(key: &[u8; KEY_LEN], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, FileError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad })
+        .map_err(|_| FileError::Encryption("Encryption failed".to_string()))?;
+
+    let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+fn open(key: &[u8; KEY_LEN], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, FileError> {
+    if sealed.len() < NONCE_LEN + TAG_LEN {
+        return Err(FileError::Encryption("Encrypted data is truncated".to_string()));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| FileError::Encryption("Decryption failed; the file or key doesn't match".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ring() -> MasterKeyRing {
+        let new = STANDARD.encode([7u8; KEY_LEN]);
+        let old = STANDARD.encode([9u8; KEY_LEN]);
+        MasterKeyRing::parse(&format!("2026-10:{}, 2025-01:{}", new, old)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_key_ring() {
+        let ring = ring();
+        assert_eq!(ring.active, "2026-10");
+        assert_eq!(ring.keys.len(), 2);
+        assert!(!format!("{:?}", ring).contains("7, 7"));
+
+        assert!(MasterKeyRing::parse("").is_err());
+        assert!(MasterKeyRing::parse("no-separator").is_err());
+        assert!(MasterKeyRing::parse(&format!("short:{}", STANDARD.encode([1u8; 16]))).is_err());
+        let key = STANDARD.encode([1u8; KEY_LEN]);
+        assert!(MasterKeyRing::parse(&format!("a:{0},a:{0}", key)).is_err());
+    }
+
+    #[test]
+    fn test_data_key_wraps_per_tenant_and_survives_rotation() {
+        let ring = ring();
+        let tenant = Uuid::new_v4();
+        let (key, record) = ring.generate_data_key(tenant).unwrap();
+        assert_eq!(record.master_key_id, "2026-10");
+        assert_ne!(record.wrapped_key[NONCE_LEN..NONCE_LEN + KEY_LEN], key);
+        assert_eq!(ring.unwrap_data_key(tenant, &record).unwrap(), key);
+
+        // Bound to the tenant it was created for
+        assert!(ring.unwrap_data_key(Uuid::new_v4(), &record).is_err());
+
+        // A rotated ring that keeps the old key still unwraps it
+        let rotated = MasterKeyRing::parse(&format!(
+            "2027-01:{},2026-10:{}",
+            STANDARD.encode([3u8; KEY_LEN]),
+            STANDARD.encode([7u8; KEY_LEN])
+        ))
+        .unwrap();
+        assert_eq!(rotated.unwrap_data_key(tenant, &record).unwrap(), key);
+
+        let dropped = MasterKeyRing::parse(&format!("2027-01:{}", STANDARD.encode([3u8; KEY_LEN]))).unwrap();
+        assert!(dropped.unwrap_data_key(tenant, &record).is_err());
+    }
+
+    #[test]
+    fn test_file_round_trip_with_key_id_header() {
+        let key = [5u8; KEY_LEN];
+        let key_id = Uuid::new_v4();
+        let tenant = Uuid::new_v4();
+        let receipt = b"%PDF-1.7 receipt for lunch".to_vec();
+
+        let file = encrypt_file(&key, key_id, tenant, &receipt).unwrap();
+        assert_eq!(file.len() as u64, receipt.len() as u64 + ENCRYPTION_OVERHEAD);
+        assert_eq!(encrypted_key_id(&file), Some(key_id));
+        assert!(!file.windows(4).any(|w| w == b"%PDF"));
+        assert_eq!(decrypt_file(&key, tenant, &file).unwrap(), receipt);
+
+        assert_eq!(encrypted_key_id(&receipt), None);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_other_tenants_and_tampering() {
+        let key = [5u8; KEY_LEN];
+        let tenant = Uuid::new_v4();
+        let file = encrypt_file(&key, Uuid::new_v4(), tenant, b"contract").unwrap();
+
+        assert!(decrypt_file(&key, Uuid::new_v4(), &file).is_err());
+        assert!(decrypt_file(&[6u8; KEY_LEN], tenant, &file).is_err());
+
+        let mut tampered = file.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt_file(&key, tenant, &tampered).is_err());
+
+        // Swapping the key ID in the header breaks authentication too
+        let mut relabeled = file;
+        relabeled[MAGIC.len()..MAGIC.len() + 16].copy_from_slice(Uuid::new_v4().as_bytes());
+        assert!(decrypt_file(&key, tenant, &relabeled).is_err());
+
+        assert!(decrypt_file(&key, tenant, b"FBENC1short").is_err());
+    }
+}
@@ -6,6 +6,24 @@ use tokio::io::AsyncWriteExt;
 use uuid::Uuid;
 use mime_guess::MimeGuess;
 use serde::{Serialize, Deserialize};
+use utoipa::ToSchema;
+
+use crate::domain::services::file_encryption::{encrypted_key_id, FileEncryption, ENCRYPTION_OVERHEAD};
+
+/// Files are downloaded from /api/v1/files/download/{file_name}
+const FILE_URL_PREFIX: &str = "/api/v1/files/download/";
+
+/// Accepted for invoice and expense attachments on top of images and PDFs
+const DOCUMENT_TYPES: &[&str] = &[
+    "text/plain",
+    "text/csv",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+];
 
 #[derive(Debug, thiserror::Error)]
 pub enum FileError {
@@ -19,6 +37,10 @@ pub enum FileError {
     InvalidFileType,
     #[error("File not found: {0}")]
     NotFound(String),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    #[error("Database error: {0}")]
+    Database(String),
 }
 
 impl From<std::io::Error> for FileError {
@@ -27,20 +49,31 @@ impl From<std::io::Error> for FileError {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl From<sqlx::Error> for FileError {
+    fn from(err: sqlx::Error) -> Self {
+        FileError::Database(err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UploadedFile {
     pub file_name: String,
     pub file_path: String,
     pub file_size: u64,
     pub mime_type: String,
     pub file_hash: String,
+    /// Tenant data key the file is encrypted with; None for plaintext files
+    pub key_id: Option<Uuid>,
 }
 
+/// Stores uploads in a directory per tenant, encrypted with the tenant's data
+/// key when file encryption is configured
 #[derive(Clone)]
 pub struct FileService {
     upload_dir: PathBuf,
     max_file_size: u64,
     allowed_types: Vec<String>,
+    encryption: Option<FileEncryption>,
 }
 
 impl FileService {
@@ -63,26 +96,88 @@ impl FileService {
                 "image/heic".to_string(),
                 "image/heif".to_string(),
             ],
+            encryption: None,
         })
     }
 
+    pub fn with_encryption(mut self, encryption: FileEncryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size
+    }
+
+    fn tenant_dir(&self, user_id: Uuid) -> PathBuf {
+        self.upload_dir.join(user_id.to_string())
+    }
+
+    /// The tenant's copy of `file_name`. Files uploaded before per-tenant
+    /// directories sit directly in the upload directory and are still found.
+    fn locate(&self, user_id: Uuid, file_name: &str) -> Result<PathBuf, FileError> {
+        let valid = !file_name.is_empty()
+            && file_name != "."
+            && file_name != ".."
+            && !file_name.contains(['/', '\\', '\0']);
+        if !valid {
+            return Err(FileError::InvalidFileName);
+        }
+
+        [self.tenant_dir(user_id), self.upload_dir.clone()]
+            .into_iter()
+            .map(|dir| dir.join(file_name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| FileError::NotFound(file_name.to_string()))
+    }
+
     /// Upload a file with byte data
     pub async fn upload_file(
         &self,
+        user_id: Uuid,
         file_data: &[u8],
         original_name: &str,
         mime_type: &str,
     ) -> Result<UploadedFile, FileError> {
-        // Validate file size
-        if file_data.len() as u64 > self.max_file_size {
-            return Err(FileError::FileTooLarge(file_data.len() as u64));
-        }
-
         // Validate mime type
         if !self.allowed_types.contains(&mime_type.to_string()) {
             return Err(FileError::InvalidFileType);
         }
 
+        self.store(user_id, file_data, original_name, mime_type).await
+    }
+
+    /// Upload a document: an image, PDF, text, spreadsheet or word processor file
+    pub async fn upload_document(
+        &self,
+        user_id: Uuid,
+        file_data: &[u8],
+        original_name: &str,
+        mime_type: &str,
+    ) -> Result<UploadedFile, FileError> {
+        if !self.allowed_types.iter().any(|t| t == mime_type) && !DOCUMENT_TYPES.contains(&mime_type) {
+            return Err(FileError::InvalidFileType);
+        }
+
+        self.store(user_id, file_data, original_name, mime_type).await
+    }
+
+    async fn store(
+        &self,
+        user_id: Uuid,
+        file_data: &[u8],
+        original_name: &str,
+        mime_type: &str,
+    ) -> Result<UploadedFile, FileError> {
+        // Validate file size
+        if file_data.len() as u64 > self.max_file_size {
+            return Err(FileError::FileTooLarge(file_data.len() as u64));
+        }
+
         // Generate unique file name
         let extension = Path::new(original_name)
             .extension()
@@ -90,11 +185,21 @@ impl FileService {
             .unwrap_or("bin");
 
         let unique_name = format!("{}_{}.{}", Uuid::new_v4().to_string(), chrono::Utc::now().timestamp(), extension);
-        let file_path = self.upload_dir.join(&unique_name);
+        let tenant_dir = self.tenant_dir(user_id);
+        fs::create_dir_all(&tenant_dir).await?;
+        let file_path = tenant_dir.join(&unique_name);
+
+        let (key_id, stored) = match &self.encryption {
+            Some(encryption) => {
+                let (key_id, encrypted) = encryption.encrypt(user_id, file_data).await?;
+                (Some(key_id), encrypted)
+            }
+            None => (None, file_data.to_vec()),
+        };
 
         // Write file
         let mut file = fs::File::create(&file_path).await?;
-        file.write_all(file_data).await?;
+        file.write_all(&stored).await?;
         file.flush().await?;
 
         // Calculate file hash (simple implementation)
@@ -109,12 +214,14 @@ impl FileService {
             file_size: file_data.len() as u64,
             mime_type: mime_type.to_string(),
             file_hash,
+            key_id,
         })
     }
 
     /// Upload a file from bytes with automatic mime type detection
     pub async fn upload_file_auto(
         &self,
+        user_id: Uuid,
         file_data: &[u8],
         original_name: &str,
     ) -> Result<UploadedFile, FileError> {
@@ -124,65 +231,49 @@ impl FileService {
             .map(|m| m.to_string())
             .unwrap_or_else(|| "application/octet-stream".to_string());
 
-        self.upload_file(file_data, original_name, &mime_type).await
+        self.upload_file(user_id, file_data, original_name, &mime_type).await
     }
 
-    /// Get file content
-    pub async fn get_file(&self, file_name: &str) -> Result<Vec<u8>, FileError> {
-        let file_path = self.upload_dir.join(file_name);
+    /// Get file content, decrypted
+    pub async fn get_file(&self, user_id: Uuid, file_name: &str) -> Result<Vec<u8>, FileError> {
+        let file_path = self.locate(user_id, file_name)?;
+        let content = fs::read(&file_path).await?;
 
-        if !file_path.exists() {
-            return Err(FileError::NotFound(file_name.to_string()));
+        if encrypted_key_id(&content).is_none() {
+            return Ok(content);
+        }
+        match &self.encryption {
+            Some(encryption) => encryption.decrypt(user_id, &content).await,
+            None => Err(FileError::Encryption("File encryption is not configured".to_string())),
         }
-
-        let content = fs::read(&file_path).await?;
-        Ok(content)
     }
 
     /// Delete a file
-    pub async fn delete_file(&self, file_name: &str) -> Result<(), FileError> {
-        let file_path = self.upload_dir.join(file_name);
-
-        if !file_path.exists() {
-            return Err(FileError::NotFound(file_name.to_string()));
-        }
-
+    pub async fn delete_file(&self, user_id: Uuid, file_name: &str) -> Result<(), FileError> {
+        let file_path = self.locate(user_id, file_name)?;
         fs::remove_file(&file_path).await?;
         Ok(())
     }
 
     /// Check if file exists
-    pub async fn file_exists(&self, file_name: &str) -> bool {
-        self.upload_dir.join(file_name).exists()
+    pub async fn file_exists(&self, user_id: Uuid, file_name: &str) -> bool {
+        self.locate(user_id, file_name).is_ok()
     }
 
     /// Get file info
-    pub async fn get_file_info(&self, file_name: &str) -> Result<UploadedFile, FileError> {
-        let file_path = self.upload_dir.join(file_name);
-
-        if !file_path.exists() {
-            return Err(FileError::NotFound(file_name.to_string()));
-        }
-
-        let metadata = fs::metadata(&file_path).await?;
-        let mime_type = MimeGuess::from_path(&file_path)
-            .first()
-            .map(|m| m.to_string())
-            .unwrap_or_else(|| "application/octet-stream".to_string());
-
-        Ok(UploadedFile {
-            file_name: file_name.to_string(),
-            file_path: file_path.to_string_lossy().to_string(),
-            file_size: metadata.len(),
-            mime_type,
-            file_hash: "".to_string(), // Would need to re-read file to calculate
-        })
+    pub async fn get_file_info(&self, user_id: Uuid, file_name: &str) -> Result<UploadedFile, FileError> {
+        let file_path = self.locate(user_id, file_name)?;
+        stored_file_info(&file_path).await
     }
 
-    /// List all files
-    pub async fn list_files(&self) -> Result<Vec<UploadedFile>, FileError> {
+    /// List the tenant's files
+    pub async fn list_files(&self, user_id: Uuid) -> Result<Vec<UploadedFile>, FileError> {
         let mut files = Vec::new();
-        let mut entries = fs::read_dir(&self.upload_dir).await?;
+        let tenant_dir = self.tenant_dir(user_id);
+        if !tenant_dir.is_dir() {
+            return Ok(files);
+        }
+        let mut entries = fs::read_dir(&tenant_dir).await?;
 
         while let Some(entry) = entries.next_entry().await? {
             let path = entry.path();
@@ -193,19 +284,7 @@ impl FileService {
                     .to_string();
 
                 if !file_name.is_empty() {
-                    let metadata = entry.metadata().await?;
-                    let mime_type = MimeGuess::from_path(&path)
-                        .first()
-                        .map(|m| m.to_string())
-                        .unwrap_or_else(|| "application/octet-stream".to_string());
-
-                    files.push(UploadedFile {
-                        file_name: file_name.clone(),
-                        file_path: path.to_string_lossy().to_string(),
-                        file_size: metadata.len(),
-                        mime_type,
-                        file_hash: "".to_string(),
-                    });
+                    files.push(stored_file_info(&path).await?);
                 }
             }
         }
@@ -213,8 +292,123 @@ impl FileService {
         Ok(files)
     }
 
+    /// Contents of the file a `get_file_url` URL points at; None for other URLs
+    /// and files that are gone
+    pub async fn get_file_by_url(&self, user_id: Uuid, url: &str) -> Option<Vec<u8>> {
+        let file_name = self.file_name_from_url(url)?;
+        match self.get_file(user_id, file_name).await {
+            Ok(content) => Some(content),
+            Err(e) => {
+                tracing::warn!("Stored file {} is unreadable: {}", file_name, e);
+                None
+            }
+        }
+    }
+
     /// Get the public URL for a file (for API responses)
     pub fn get_file_url(&self, file_name: &str) -> String {
-        format!("/api/v1/files/{}", file_name)
+        format!("{}{}", FILE_URL_PREFIX, file_name)
+    }
+
+    /// The stored file a URL from `get_file_url` refers to
+    pub fn file_name_from_url<'a>(&self, url: &'a str) -> Option<&'a str> {
+        url.strip_prefix(FILE_URL_PREFIX).filter(|name| !name.is_empty())
+    }
+}
+
+/// Metadata from the stored file; only the header is read, so the hash is left empty
+async fn stored_file_info(path: &Path) -> Result<UploadedFile, FileError> {
+    use tokio::io::AsyncReadExt;
+
+    let metadata = fs::metadata(path).await?;
+    let mut header = Vec::with_capacity(32);
+    fs::File::open(path).await?.take(32).read_to_end(&mut header).await?;
+    let key_id = encrypted_key_id(&header);
+    let file_size = match key_id {
+        Some(_) => metadata.len().saturating_sub(ENCRYPTION_OVERHEAD),
+        None => metadata.len(),
+    };
+
+    let mime_type = MimeGuess::from_path(path)
+        .first()
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    Ok(UploadedFile {
+        file_name: path.file_name().and_then(|n| n.to_str()).unwrap_or("").to_string(),
+        file_path: path.to_string_lossy().to_string(),
+        file_size,
+        mime_type,
+        file_hash: "".to_string(),
+        key_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> FileService {
+        let dir = std::env::temp_dir().join(format!("flashbill-files-{}", Uuid::new_v4()));
+        FileService::new(dir.to_str().unwrap(), 1024).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_files_are_kept_per_tenant() {
+        let files = service();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let uploaded = files.upload_file(alice, b"receipt", "lunch.png", "image/png").await.unwrap();
+        assert_eq!(uploaded.key_id, None);
+        assert_eq!(files.get_file(alice, &uploaded.file_name).await.unwrap(), b"receipt");
+
+        assert_eq!(files.list_files(alice).await.unwrap().len(), 1);
+        assert!(files.list_files(bob).await.unwrap().is_empty());
+        assert!(matches!(
+            files.get_file(bob, &uploaded.file_name).await,
+            Err(FileError::NotFound(_))
+        ));
+        assert!(files.delete_file(bob, &uploaded.file_name).await.is_err());
+
+        let url = files.get_file_url(&uploaded.file_name);
+        assert_eq!(files.file_name_from_url(&url), Some(uploaded.file_name.as_str()));
+        assert_eq!(files.file_name_from_url("https://files.example.com/inv.pdf"), None);
+
+        let info = files.get_file_info(alice, &uploaded.file_name).await.unwrap();
+        assert_eq!(info.file_size, 7);
+        files.delete_file(alice, &uploaded.file_name).await.unwrap();
+        assert!(!files.file_exists(alice, &uploaded.file_name).await);
+    }
+
+    #[tokio::test]
+    async fn test_documents_accept_office_files_only() {
+        let files = service();
+        let tenant = Uuid::new_v4();
+
+        assert!(matches!(
+            files.upload_file(tenant, b"a,b", "hours.csv", "text/csv").await,
+            Err(FileError::InvalidFileType)
+        ));
+        let uploaded = files.upload_document(tenant, b"a,b", "hours.csv", "text/csv").await.unwrap();
+        assert!(uploaded.file_name.ends_with(".csv"));
+        files.upload_document(tenant, b"%PDF", "contract.pdf", "application/pdf").await.unwrap();
+
+        assert!(matches!(
+            files.upload_document(tenant, b"MZ", "setup.exe", "application/x-msdownload").await,
+            Err(FileError::InvalidFileType)
+        ));
+        assert!(matches!(
+            files.upload_document(tenant, &[0; 2048], "big.csv", "text/csv").await,
+            Err(FileError::FileTooLarge(2048))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_path_traversal() {
+        let files = service();
+        let tenant = Uuid::new_v4();
+        for name in ["../secrets.env", "..", "a/b.png", "a\\b.png", ""] {
+            assert!(matches!(files.get_file(tenant, name).await, Err(FileError::InvalidFileName)));
+        }
     }
 }
@@ -0,0 +1,438 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{normalize_currency_code, FxRate, FxRateHistoryQuery, FxRateSource, SetFxRate};
+use crate::domain::services::{LazyHttpClient, RedisService, SharedClock};
+use crate::infrastructure::repositories::FxRepository;
+
+/// Provider rates for a past day don't change, so a day is a safe cache lifetime
+const FX_CACHE_TTL: u64 = 24 * 3600;
+/// Longest history range returned in one request
+const MAX_HISTORY_DAYS: i64 = 366;
+
+#[derive(Debug, Error)]
+pub enum FxError {
+    #[error("Exchange rate not found")]
+    NotFound,
+    #[error("No exchange rate available for {0}")]
+    RateUnavailable(String),
+    #[error("Exchange rate provider error: {0}")]
+    Provider(String),
+    #[error("Validation error: {0}")]
+    Validation(String),
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for FxError {
+    fn from(err: sqlx::Error) -> Self {
+        FxError::DatabaseError(err.to_string())
+    }
+}
+
+/// Source of daily exchange rates
+#[async_trait]
+pub trait FxRateProvider: Send + Sync {
+    fn source(&self) -> FxRateSource;
+
+    /// Shared HTTP handle, for warm-up and health reporting
+    fn http_handle(&self) -> LazyHttpClient;
+
+    /// Rates in effect on `date` as units of `base_currency` per 1 unit of each currency.
+    /// Days without a fixing (weekends, holidays) use the last published rates.
+    async fn fetch_rates(&self, base_currency: &str, date: NaiveDate) -> Result<Vec<(String, f64)>, FxError>;
+}
+
+/// Provider selected by `FX_RATE_PROVIDER` (`ecb` or `exchangerate_host`); none when unset
+pub fn provider_from_env() -> Option<Arc<dyn FxRateProvider>> {
+    match std::env::var("FX_RATE_PROVIDER").ok()?.as_str() {
+        "ecb" => Some(Arc::new(EcbProvider::new())),
+        "exchangerate_host" => match std::env::var("EXCHANGERATE_HOST_ACCESS_KEY") {
+            Ok(access_key) => Some(Arc::new(ExchangerateHostProvider::new(access_key))),
+            Err(_) => {
+                tracing::warn!("⚠️ FX_RATE_PROVIDER=exchangerate_host needs EXCHANGERATE_HOST_ACCESS_KEY; exchange rates are manual only");
+                None
+            }
+        },
+        other => {
+            tracing::warn!("⚠️ Unknown FX_RATE_PROVIDER '{}'; exchange rates are manual only", other);
+            None
+        }
+    }
+}
+
+/// European Central Bank reference rates (EUR based, published on TARGET working days)
+pub struct EcbProvider {
+    http_client: LazyHttpClient,
+}
+
+impl EcbProvider {
+    const RECENT_URL: &'static str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist-90d.xml";
+    const FULL_URL: &'static str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-hist.xml";
+
+    pub fn new() -> Self {
+        Self {
+            http_client: LazyHttpClient::new("ecb_fx", std::time::Duration::from_secs(15), true),
+        }
+    }
+
+    async fn download(&self, url: &str) -> Result<String, FxError> {
+        let client = self.http_client.get().map_err(FxError::Provider)?;
+        client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| FxError::Provider(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| FxError::Provider(e.to_string()))
+    }
+}
+
+impl Default for EcbProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl FxRateProvider for EcbProvider {
+    fn source(&self) -> FxRateSource {
+        FxRateSource::Ecb
+    }
+
+    fn http_handle(&self) -> LazyHttpClient {
+        self.http_client.clone()
+    }
+
+    async fn fetch_rates(&self, base_currency: &str, date: NaiveDate) -> Result<Vec<(String, f64)>, FxError> {
+        // The 90-day file covers invoices and payments in practice; older dates need the full history
+        let euro_rates = match parse_ecb_rates(&self.download(Self::RECENT_URL).await?, date) {
+            Some(rates) => rates,
+            None => parse_ecb_rates(&self.download(Self::FULL_URL).await?, date)
+                .ok_or_else(|| FxError::Provider(format!("ECB has no rates on or before {}", date)))?,
+        };
+        rebase_euro_rates(&euro_rates, base_currency)
+            .ok_or_else(|| FxError::RateUnavailable(base_currency.to_string()))
+    }
+}
+
+/// exchangerate.host historical endpoint (needs an access key)
+pub struct ExchangerateHostProvider {
+    http_client: LazyHttpClient,
+    access_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangerateHostResponse {
+    success: bool,
+    #[serde(default)]
+    quotes: HashMap<String, f64>,
+    error: Option<ExchangerateHostError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangerateHostError {
+    info: Option<String>,
+}
+
+impl ExchangerateHostProvider {
+    const URL: &'static str = "https://api.exchangerate.host/historical";
+
+    pub fn new(access_key: String) -> Self {
+        Self {
+            http_client: LazyHttpClient::new("exchangerate_host", std::time::Duration::from_secs(15), true),
+            access_key,
+        }
+    }
+}
+
+#[async_trait]
+impl FxRateProvider for ExchangerateHostProvider {
+    fn source(&self) -> FxRateSource {
+        FxRateSource::ExchangerateHost
+    }
+
+    fn http_handle(&self) -> LazyHttpClient {
+        self.http_client.clone()
+    }
+
+    async fn fetch_rates(&self, base_currency: &str, date: NaiveDate) -> Result<Vec<(String, f64)>, FxError> {
+        let client = self.http_client.get().map_err(FxError::Provider)?;
+        let response: ExchangerateHostResponse = client
+            .get(Self::URL)
+            .query(&[
+                ("access_key", self.access_key.as_str()),
+                ("date", &date.to_string()),
+                ("source", base_currency),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| FxError::Provider(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| FxError::Provider(e.to_string()))?;
+
+        if !response.success {
+            let info = response.error.and_then(|e| e.info).unwrap_or_else(|| "request failed".to_string());
+            return Err(FxError::Provider(info));
+        }
+
+        // Quotes are keyed source+currency ("USDEUR") and give currency per 1 unit of the source
+        Ok(response
+            .quotes
+            .into_iter()
+            .filter(|(_, quote)| *quote > 0.0)
+            .filter_map(|(pair, quote)| pair.strip_prefix(base_currency).map(|currency| (currency.to_string(), 1.0 / quote)))
+            .collect())
+    }
+}
+
+/// Rates from the latest `<Cube time=...>` block on or before `date`, as currency per 1 EUR
+pub fn parse_ecb_rates(xml: &str, date: NaiveDate) -> Option<Vec<(String, f64)>> {
+    let mut best: Option<(NaiveDate, Vec<(String, f64)>)> = None;
+
+    for block in xml.split("<Cube time=").skip(1) {
+        let Some(day) = quoted_value(block).and_then(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()) else {
+            continue;
+        };
+        if day > date || best.as_ref().is_some_and(|(best_day, _)| *best_day >= day) {
+            continue;
+        }
+
+        let rates = block
+            .split("currency=")
+            .skip(1)
+            .filter_map(|entry| {
+                let currency = quoted_value(entry)?;
+                let rate = quoted_value(entry.split_once("rate=")?.1)?.parse::<f64>().ok()?;
+                Some((currency.to_string(), rate))
+            })
+            .collect();
+        best = Some((day, rates));
+    }
+
+    best.map(|(_, rates)| rates)
+}
+
+/// Value of an attribute whose text starts with its opening quote
+fn quoted_value(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    text[1..].split(quote).next()
+}
+
+/// Convert EUR-based quotes (currency per 1 EUR) into `base` per 1 unit of each currency
+pub fn rebase_euro_rates(euro_rates: &[(String, f64)], base: &str) -> Option<Vec<(String, f64)>> {
+    let mut per_euro: HashMap<&str, f64> = euro_rates.iter().map(|(currency, rate)| (currency.as_str(), *rate)).collect();
+    per_euro.insert("EUR", 1.0);
+    let base_per_euro = *per_euro.get(base)?;
+
+    Some(
+        per_euro
+            .iter()
+            .filter(|(currency, rate)| **currency != base && **rate > 0.0)
+            .map(|(currency, rate)| (currency.to_string(), base_per_euro / rate))
+            .collect(),
+    )
+}
+
+/// Resolves exchange rates: the user's manual override for the day, then the
+/// provider rate (Redis, then the Postgres history, then the provider itself).
+pub struct FxRateService {
+    fx_repo: FxRepository,
+    provider: Option<Arc<dyn FxRateProvider>>,
+    redis: Option<Arc<RedisService>>,
+    clock: SharedClock,
+}
+
+impl FxRateService {
+    pub fn new(
+        fx_repo: FxRepository,
+        provider: Option<Arc<dyn FxRateProvider>>,
+        redis: Option<Arc<RedisService>>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { fx_repo, provider, redis, clock }
+    }
+
+    /// HTTP handle of the configured provider, for warm-up and health reporting
+    pub fn http_handle(&self) -> Option<LazyHttpClient> {
+        self.provider.as_ref().map(|provider| provider.http_handle())
+    }
+
+    /// Rate for `currency` into the user's base currency on `date` (today by default)
+    pub async fn rate(&self, user_id: Uuid, currency: &str, date: Option<NaiveDate>) -> Result<FxRate, FxError> {
+        let currency = normalize_currency_code(currency)
+            .ok_or_else(|| FxError::Validation(format!("Invalid currency code '{}'", currency)))?;
+        let base_currency = self.fx_repo.base_currency(user_id).await?;
+        // Nobody publishes future rates; use the latest fixing instead
+        let today = self.clock.today();
+        let date = date.map_or(today, |date| date.min(today));
+
+        if currency == base_currency {
+            return Ok(FxRate {
+                id: None,
+                base_currency,
+                currency,
+                rate: 1.0,
+                rate_date: date,
+                source: FxRateSource::Identity,
+            });
+        }
+
+        if let Some(rate) = self.fx_repo.find_override(user_id, &base_currency, &currency, date).await? {
+            return Ok(rate);
+        }
+
+        self.provider_rate(&base_currency, &currency, date).await
+    }
+
+    /// Best-effort lookup for defaulting stored rates; failures are logged, not returned
+    pub async fn rate_if_available(&self, user_id: Uuid, currency: &str, date: Option<NaiveDate>) -> Option<f64> {
+        match self.rate(user_id, currency, date).await {
+            Ok(rate) => Some(rate.rate),
+            Err(e) => {
+                tracing::warn!("⚠️ No exchange rate for {} on {:?}: {}", currency, date, e);
+                None
+            }
+        }
+    }
+
+    /// Rate a payment on an invoice in `currency` settled at: the one recorded with
+    /// it, else today's. None for invoices in the base currency, which have no
+    /// exchange result, and when no rate can be found.
+    pub async fn settlement_rate(&self, user_id: Uuid, currency: &str, recorded: Option<f64>) -> Option<f64> {
+        match self.fx_repo.base_currency(user_id).await {
+            Ok(base_currency) if normalize_currency_code(currency).as_deref() == Some(base_currency.as_str()) => {
+                return None;
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("⚠️ Base currency of user {} unavailable: {}", user_id, e),
+        }
+
+        match recorded {
+            Some(rate) => Some(rate),
+            None => self.rate_if_available(user_id, currency, None).await,
+        }
+    }
+
+    async fn provider_rate(&self, base_currency: &str, currency: &str, date: NaiveDate) -> Result<FxRate, FxError> {
+        let provider = self.provider.as_ref().ok_or_else(|| FxError::RateUnavailable(currency.to_string()))?;
+        let source = provider.source();
+        let cache_key = format!("fx:{}:{}:{}:{}", source.as_str(), base_currency, currency, date);
+
+        if let Some(redis) = &self.redis {
+            if let Ok(Some(rate)) = redis.get::<FxRate>(&cache_key).await {
+                return Ok(rate);
+            }
+        }
+
+        let rate = match self.fx_repo.find_provider_rate(source, base_currency, currency, date).await? {
+            Some(rate) => rate,
+            None => {
+                let rates = provider.fetch_rates(base_currency, date).await?;
+                self.fx_repo.store_provider_rates(source, base_currency, date, &rates).await?;
+                self.fx_repo
+                    .find_provider_rate(source, base_currency, currency, date)
+                    .await?
+                    .ok_or_else(|| FxError::RateUnavailable(currency.to_string()))?
+            }
+        };
+
+        if let Some(redis) = &self.redis {
+            let _ = redis.set_with_expiration(&cache_key, &rate, FX_CACHE_TTL).await;
+        }
+
+        Ok(rate)
+    }
+
+    pub async fn set_override(&self, user_id: Uuid, set: SetFxRate) -> Result<FxRate, FxError> {
+        let currency = normalize_currency_code(&set.currency)
+            .ok_or_else(|| FxError::Validation(format!("Invalid currency code '{}'", set.currency)))?;
+        if !set.rate.is_finite() || set.rate <= 0.0 {
+            return Err(FxError::Validation("Rate must be greater than 0".to_string()));
+        }
+        let base_currency = self.fx_repo.base_currency(user_id).await?;
+        if currency == base_currency {
+            return Err(FxError::Validation(format!("{} is your base currency", currency)));
+        }
+
+        let rate_date = set.rate_date.unwrap_or_else(|| self.clock.today());
+        Ok(self.fx_repo.upsert_override(user_id, &base_currency, &currency, set.rate, rate_date).await?)
+    }
+
+    pub async fn delete_override(&self, user_id: Uuid, rate_id: Uuid) -> Result<(), FxError> {
+        if self.fx_repo.delete_override(user_id, rate_id).await? {
+            Ok(())
+        } else {
+            Err(FxError::NotFound)
+        }
+    }
+
+    /// Stored daily rates in a range; days that were never looked up are absent
+    pub async fn history(&self, user_id: Uuid, query: FxRateHistoryQuery) -> Result<Vec<FxRate>, FxError> {
+        let currency = normalize_currency_code(&query.currency)
+            .ok_or_else(|| FxError::Validation(format!("Invalid currency code '{}'", query.currency)))?;
+        if query.to < query.from {
+            return Err(FxError::Validation("'to' must not be before 'from'".to_string()));
+        }
+        if (query.to - query.from).num_days() > MAX_HISTORY_DAYS {
+            return Err(FxError::Validation(format!("Range is limited to {} days", MAX_HISTORY_DAYS)));
+        }
+
+        let base_currency = self.fx_repo.base_currency(user_id).await?;
+        Ok(self.fx_repo.history(user_id, &base_currency, &currency, query.from, query.to).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ECB_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+  <Cube>
+    <Cube time='2025-01-10'>
+      <Cube currency='USD' rate='1.0304'/>
+      <Cube currency='GBP' rate='0.83570'/>
+    </Cube>
+    <Cube time='2025-01-09'>
+      <Cube currency='USD' rate='1.0300'/>
+      <Cube currency='GBP' rate='0.83650'/>
+    </Cube>
+  </Cube>
+</gesmes:Envelope>"#;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn ecb_rates_use_latest_fixing_on_or_before_date() {
+        let saturday = parse_ecb_rates(ECB_XML, date("2025-01-11")).unwrap();
+        assert!(saturday.contains(&("USD".to_string(), 1.0304)));
+
+        let thursday = parse_ecb_rates(ECB_XML, date("2025-01-09")).unwrap();
+        assert!(thursday.contains(&("GBP".to_string(), 0.8365)));
+
+        assert!(parse_ecb_rates(ECB_XML, date("2025-01-01")).is_none());
+    }
+
+    #[test]
+    fn euro_rates_are_rebased_to_the_base_currency() {
+        let euro_rates = vec![("USD".to_string(), 1.25), ("GBP".to_string(), 0.8)];
+        let rates: HashMap<String, f64> = rebase_euro_rates(&euro_rates, "USD").unwrap().into_iter().collect();
+
+        assert_eq!(rates["EUR"], 1.25);
+        assert!((rates["GBP"] - 1.5625).abs() < 1e-9);
+        assert!(!rates.contains_key("USD"));
+        assert!(rebase_euro_rates(&euro_rates, "JPY").is_none());
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Serialize;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Lifecycle of an external integration's client
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IntegrationState {
+    /// Credentials are missing; calls fail fast without touching the network
+    NotConfigured,
+    /// Configured, client not built yet
+    Idle,
+    Ready,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrationStatus {
+    pub name: &'static str,
+    pub state: IntegrationState,
+}
+
+/// HTTP client for an external integration, built on first use and then reused.
+/// Clones share the same handle, so the client is built at most once.
+#[derive(Debug, Clone)]
+pub struct LazyHttpClient {
+    name: &'static str,
+    timeout: Duration,
+    configured: bool,
+    client: Arc<OnceLock<Result<reqwest::Client, String>>>,
+}
+
+impl LazyHttpClient {
+    pub fn new(name: &'static str, timeout: Duration, configured: bool) -> Self {
+        Self {
+            name,
+            timeout,
+            configured,
+            client: Arc::new(OnceLock::new()),
+        }
+    }
+
+    pub fn get(&self) -> Result<&reqwest::Client, String> {
+        self.client
+            .get_or_init(|| {
+                let built = reqwest::Client::builder()
+                    .timeout(self.timeout)
+                    .build()
+                    .map_err(|e| e.to_string());
+                match &built {
+                    Ok(_) => tracing::info!("✅ {} client initialized", self.name),
+                    Err(e) => tracing::warn!("⚠️ {} client failed to initialize: {}", self.name, e),
+                }
+                built
+            })
+            .as_ref()
+            .map_err(|e| format!("{} unavailable: {}", self.name, e))
+    }
+
+    /// Build the client ahead of the first request; unconfigured integrations stay idle
+    pub fn warm_up(&self) {
+        if self.configured {
+            let _ = self.get();
+        }
+    }
+
+    pub fn status(&self) -> IntegrationStatus {
+        let state = match self.client.get() {
+            _ if !self.configured => IntegrationState::NotConfigured,
+            None => IntegrationState::Idle,
+            Some(Ok(_)) => IntegrationState::Ready,
+            Some(Err(e)) => IntegrationState::Failed(e.clone()),
+        };
+        IntegrationStatus { name: self.name, state }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_is_built_on_first_use_and_shared() {
+        let client = LazyHttpClient::new("stripe", Duration::from_secs(5), true);
+        let clone = client.clone();
+        assert_eq!(client.status().state, IntegrationState::Idle);
+
+        assert!(clone.get().is_ok());
+        assert_eq!(client.status().state, IntegrationState::Ready);
+    }
+
+    #[test]
+    fn test_unconfigured_integration_skips_warm_up() {
+        let client = LazyHttpClient::new("whatsapp", Duration::from_secs(5), false);
+        client.warm_up();
+        assert!(client.client.get().is_none());
+        assert_eq!(client.status().state, IntegrationState::NotConfigured);
+    }
+}
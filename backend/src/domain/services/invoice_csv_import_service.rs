@@ -0,0 +1,197 @@
+use chrono::Duration;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    check_csv_upload, parse_invoice_csv, Client, CreateInvoice, ImportRowIssue, ImportedInvoice,
+    InvoiceCsvInvoice, InvoiceImportReport, MAX_INVOICE_CSV_BYTES,
+};
+use crate::domain::services::{FxRateService, InvoiceError, InvoiceService, SharedClock};
+use crate::infrastructure::repositories::ClientRepository;
+
+#[derive(Debug, Error)]
+pub enum InvoiceCsvImportError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for InvoiceCsvImportError {
+    fn from(err: sqlx::Error) -> Self {
+        InvoiceCsvImportError::DatabaseError(err.to_string())
+    }
+}
+
+/// Bulk invoice imports from CSV, one line item per row. Invoices go through
+/// the normal create path, so they're numbered, totalled and taxed like any
+/// other and start out as drafts. Clients have to exist already.
+pub struct InvoiceCsvImportService {
+    invoices: Arc<InvoiceService>,
+    clients: ClientRepository,
+    fx_rates: Arc<FxRateService>,
+    clock: SharedClock,
+}
+
+impl InvoiceCsvImportService {
+    pub fn new(
+        invoices: Arc<InvoiceService>,
+        clients: ClientRepository,
+        fx_rates: Arc<FxRateService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { invoices, clients, fx_rates, clock }
+    }
+
+    pub async fn import(
+        &self,
+        user_id: Uuid,
+        file_name: &str,
+        data: &[u8],
+        dry_run: bool,
+    ) -> Result<InvoiceImportReport, InvoiceCsvImportError> {
+        check_csv_upload(file_name, data, MAX_INVOICE_CSV_BYTES).map_err(InvoiceCsvImportError::Validation)?;
+        let parsed = parse_invoice_csv(data).map_err(InvoiceCsvImportError::Validation)?;
+
+        let clients = ClientLookup::new(self.clients.list_active(user_id).await?);
+        let mut errors = parsed.errors;
+        let mut imported = Vec::new();
+
+        for invoice in parsed.invoices {
+            let client = match clients.find(&invoice) {
+                Ok(client) => client,
+                Err((field, message)) => {
+                    errors.push(ImportRowIssue::new(invoice.row, Some(field), message));
+                    continue;
+                }
+            };
+
+            let issue_date = invoice.issue_date.unwrap_or_else(|| self.clock.today());
+            let due_date = invoice
+                .due_date
+                .unwrap_or_else(|| issue_date + Duration::days(client.payment_terms as i64));
+            if due_date < issue_date {
+                errors.push(ImportRowIssue::new(invoice.row, Some("due_date"), "Due date can't be before the issue date"));
+                continue;
+            }
+
+            let mut result = ImportedInvoice {
+                reference: invoice.reference.clone(),
+                row: invoice.row,
+                client_id: client.id,
+                items: invoice.items.len(),
+                invoice_id: None,
+                invoice_number: None,
+            };
+            if dry_run {
+                imported.push(result);
+                continue;
+            }
+
+            // Foreign-currency invoices take the rate on the issue date, as when created by hand
+            let exchange_rate = match &invoice.currency {
+                Some(currency) => self.fx_rates.rate_if_available(user_id, currency, Some(issue_date)).await,
+                None => None,
+            };
+            let create = CreateInvoice {
+                client_id: client.id,
+                issue_date,
+                due_date,
+                items: invoice.items.into_iter().map(|line| line.item).collect(),
+                notes: invoice.notes,
+                terms: None,
+                discount_amount: None,
+                tax_included: false,
+                send_immediately: false,
+                tax_label: None,
+                tax_id: None,
+                currency: invoice.currency,
+                exchange_rate,
+                allow_partial_payment: None,
+                min_payment_amount: None,
+                expires_at: None,
+                project_id: None,
+                deposit: None,
+                reverse_charge: false,
+                vat_country: client.vat_country(),
+            };
+
+            match self.invoices.create_invoice(user_id, create).await {
+                Ok(created) => {
+                    result.invoice_id = Some(created.id);
+                    result.invoice_number = Some(created.invoice_number);
+                    imported.push(result);
+                }
+                Err(e) => {
+                    let message = match e {
+                        InvoiceError::Validation(message) => message,
+                        InvoiceError::ClientNotFound => "Client not found".to_string(),
+                        other => {
+                            tracing::error!(user_id = %user_id, row = invoice.row, "Imported invoice not created: {}", other);
+                            "The invoice could not be created".to_string()
+                        }
+                    };
+                    errors.push(ImportRowIssue::new(invoice.row, None, message));
+                }
+            }
+        }
+
+        errors.sort_by_key(|issue| issue.row);
+        if !dry_run {
+            tracing::info!(user_id = %user_id, created = imported.len(), "Invoices imported from CSV");
+        }
+
+        Ok(InvoiceImportReport {
+            dry_run,
+            total_rows: parsed.total_rows,
+            total_invoices: parsed.total_invoices,
+            created: imported.len(),
+            failed: parsed.total_invoices - imported.len(),
+            invoices: imported,
+            errors,
+            ignored_columns: parsed.ignored_columns,
+        })
+    }
+}
+
+/// The user's active clients, by lower-cased email and name
+struct ClientLookup {
+    clients: Vec<Client>,
+    by_email: HashMap<String, usize>,
+    by_name: HashMap<String, Vec<usize>>,
+}
+
+impl ClientLookup {
+    fn new(clients: Vec<Client>) -> Self {
+        let mut by_email = HashMap::new();
+        let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+        for (index, client) in clients.iter().enumerate() {
+            if let Some(email) = &client.email {
+                by_email.entry(email.to_lowercase()).or_insert(index);
+            }
+            by_name.entry(client.name.to_lowercase()).or_default().push(index);
+        }
+        Self { clients, by_email, by_name }
+    }
+
+    /// The invoice's client: by email when the row has one, otherwise by name
+    fn find(&self, invoice: &InvoiceCsvInvoice) -> Result<&Client, (&'static str, String)> {
+        if let Some(email) = &invoice.client_email {
+            return self
+                .by_email
+                .get(&email.to_lowercase())
+                .map(|index| &self.clients[*index])
+                .ok_or(("client_email", "No client has this email; add or import the client first".to_string()));
+        }
+
+        let name = invoice.client_name.as_deref().unwrap_or_default();
+        match self.by_name.get(&name.to_lowercase()).map(Vec::as_slice) {
+            Some([index]) => Ok(&self.clients[*index]),
+            Some([_, _, ..]) => Err(("client_name", "More than one client has this name; use a client email column".to_string())),
+            _ => Err(("client_name", "No client has this name; add or import the client first".to_string())),
+        }
+    }
+}
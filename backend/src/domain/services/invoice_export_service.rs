@@ -0,0 +1,92 @@
+use futures::{stream::BoxStream, StreamExt};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{ExportCell, InvoiceExportFormat, InvoiceExportQuery, INVOICE_EXPORT_COLUMNS};
+use crate::domain::services::XlsxWriter;
+use crate::infrastructure::repositories::InvoiceRepository;
+
+#[derive(Debug, Error)]
+pub enum InvoiceExportError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Export error: {0}")]
+    Export(String),
+}
+
+impl From<sqlx::Error> for InvoiceExportError {
+    fn from(err: sqlx::Error) -> Self {
+        InvoiceExportError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<csv::Error> for InvoiceExportError {
+    fn from(err: csv::Error) -> Self {
+        InvoiceExportError::Export(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for InvoiceExportError {
+    fn from(err: std::io::Error) -> Self {
+        InvoiceExportError::Export(err.to_string())
+    }
+}
+
+/// Invoice exports as CSV or XLSX, one row per line item. Files are written
+/// as the rows come back from the database rather than built in memory.
+pub struct InvoiceExportService {
+    repo: InvoiceRepository,
+}
+
+impl InvoiceExportService {
+    pub fn new(repo: InvoiceRepository) -> Self {
+        Self { repo }
+    }
+
+    /// The export as a stream of chunks, starting with the header row
+    pub fn stream(&self, user_id: Uuid, query: &InvoiceExportQuery) -> BoxStream<'static, Result<Vec<u8>, InvoiceExportError>> {
+        let rows = self.repo.stream_export(user_id, query.filter());
+        let header: Vec<ExportCell> = INVOICE_EXPORT_COLUMNS.iter().map(|c| ExportCell::Text(c.to_string())).collect();
+
+        match query.format {
+            InvoiceExportFormat::Csv => {
+                let rows = rows.map(|row| {
+                    let mut chunk = Vec::new();
+                    for line in row?.lines() {
+                        chunk.extend(csv_line(&line)?);
+                    }
+                    Ok(chunk)
+                });
+                futures::stream::once(async move { csv_line(&header) }).chain(rows).boxed()
+            }
+            InvoiceExportFormat::Xlsx => {
+                let mut writer = XlsxWriter::new("Invoices");
+                let start = writer.row(&header).map_err(InvoiceExportError::from);
+
+                // The writer goes with the rows; it's finished once they run out
+                let rows = futures::stream::unfold(Some((rows, writer)), |state| async move {
+                    let (mut rows, mut writer) = state?;
+                    match rows.next().await {
+                        Some(Ok(row)) => {
+                            let chunk = row.lines().iter().try_fold(Vec::new(), |mut chunk, line| {
+                                chunk.extend(writer.row(line)?);
+                                Ok(chunk)
+                            });
+                            Some((chunk, Some((rows, writer))))
+                        }
+                        Some(Err(e)) => Some((Err(e.into()), None)),
+                        None => Some((writer.finish().map_err(InvoiceExportError::from), None)),
+                    }
+                });
+                futures::stream::once(async move { start }).chain(rows).boxed()
+            }
+        }
+    }
+}
+
+fn csv_line(cells: &[ExportCell]) -> Result<Vec<u8>, InvoiceExportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(cells.iter().map(|cell| cell.to_string()))?;
+    writer.into_inner().map_err(|e| InvoiceExportError::Export(e.to_string()))
+}
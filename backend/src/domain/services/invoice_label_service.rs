@@ -0,0 +1,149 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    normalize_label_color, normalize_label_name, CreateInvoiceLabel, InvoiceLabel, UpdateInvoiceLabel,
+};
+use crate::infrastructure::repositories::InvoiceLabelRepository;
+
+/// Most labels one account can define
+pub const MAX_INVOICE_LABELS: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum InvoiceLabelError {
+    #[error("Label not found")]
+    NotFound,
+
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("A label named {0} already exists")]
+    AlreadyExists(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for InvoiceLabelError {
+    fn from(err: sqlx::Error) -> Self {
+        InvoiceLabelError::DatabaseError(err.to_string())
+    }
+}
+
+/// User-defined pipeline labels and their assignment to invoices
+pub struct InvoiceLabelService {
+    repo: InvoiceLabelRepository,
+}
+
+impl InvoiceLabelService {
+    pub fn new(repo: InvoiceLabelRepository) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_labels(&self, user_id: Uuid) -> Result<Vec<InvoiceLabel>, InvoiceLabelError> {
+        Ok(self.repo.list(user_id).await?)
+    }
+
+    pub async fn create_label(&self, user_id: Uuid, create: CreateInvoiceLabel) -> Result<InvoiceLabel, InvoiceLabelError> {
+        let name = normalize_label_name(&create.name).map_err(InvoiceLabelError::Validation)?;
+        let color = create
+            .color
+            .as_deref()
+            .map(normalize_label_color)
+            .transpose()
+            .map_err(InvoiceLabelError::Validation)?;
+
+        if self.repo.list(user_id).await?.len() >= MAX_INVOICE_LABELS {
+            return Err(InvoiceLabelError::Validation(format!("At most {} labels are allowed", MAX_INVOICE_LABELS)));
+        }
+
+        self.repo
+            .create(user_id, &name, color.as_deref(), &create.statuses, create.show_on_pdf, create.position)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => InvoiceLabelError::AlreadyExists(name),
+                other => other.into(),
+            })
+    }
+
+    pub async fn update_label(
+        &self,
+        user_id: Uuid,
+        label_id: Uuid,
+        update: UpdateInvoiceLabel,
+    ) -> Result<InvoiceLabel, InvoiceLabelError> {
+        let mut label = self.repo.find_by_id(user_id, label_id).await?.ok_or(InvoiceLabelError::NotFound)?;
+
+        if let Some(name) = update.name {
+            label.name = normalize_label_name(&name).map_err(InvoiceLabelError::Validation)?;
+        }
+        if let Some(color) = update.color {
+            label.color = Some(normalize_label_color(&color).map_err(InvoiceLabelError::Validation)?);
+        }
+        if let Some(statuses) = update.statuses {
+            label.statuses = statuses;
+        }
+        if let Some(show_on_pdf) = update.show_on_pdf {
+            label.show_on_pdf = show_on_pdf;
+        }
+        if let Some(position) = update.position {
+            label.position = position;
+        }
+
+        let name = label.name.clone();
+        self.repo
+            .update(user_id, &label)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => InvoiceLabelError::AlreadyExists(name),
+                other => other.into(),
+            })?
+            .ok_or(InvoiceLabelError::NotFound)
+    }
+
+    pub async fn delete_label(&self, user_id: Uuid, label_id: Uuid) -> Result<(), InvoiceLabelError> {
+        if self.repo.delete(user_id, label_id).await? {
+            Ok(())
+        } else {
+            Err(InvoiceLabelError::NotFound)
+        }
+    }
+
+    /// Put a label on an invoice, or clear it with None. A label limited to certain
+    /// core statuses can only be set while the invoice is in one of them.
+    pub async fn set_invoice_label(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        label_id: Option<Uuid>,
+    ) -> Result<Option<InvoiceLabel>, InvoiceLabelError> {
+        let label = match label_id {
+            Some(label_id) => {
+                let label = self.repo.find_by_id(user_id, label_id).await?.ok_or(InvoiceLabelError::NotFound)?;
+                let status = self
+                    .repo
+                    .invoice_status(user_id, invoice_id)
+                    .await?
+                    .ok_or(InvoiceLabelError::InvoiceNotFound)?;
+                if !label.applies_to(&status) {
+                    return Err(InvoiceLabelError::Validation(format!(
+                        "{} doesn't apply to {} invoices",
+                        label.name, status
+                    )));
+                }
+                Some(label)
+            }
+            None => None,
+        };
+
+        self.repo
+            .set_invoice_label(user_id, invoice_id, label_id)
+            .await?
+            .ok_or(InvoiceLabelError::InvoiceNotFound)?;
+
+        Ok(label)
+    }
+}
@@ -1,12 +1,40 @@
 #![allow(dead_code)]
 
+use crate::domain::i18n::{format_date, format_money, translate, translate_with, Locale};
 use crate::domain::models::*;
-use crate::domain::services::{PdfService, InvoiceItemPdf, EmailService, EnhancedNotificationService, WhatsAppService};
-use crate::infrastructure::repositories::{InvoiceRepository, ClientRepository, UserRepository};
+use crate::domain::services::email_service::{escape_html, signature_html};
+use crate::domain::services::email_templates::EmailTemplates;
+use crate::domain::services::{PdfService, InvoiceItemPdf, PdfTaxLine, PdfWatermark, PdfBranding, EmailError, EmailJobType, EmailQueueService, InvoiceEmail, AttachmentLink, EnhancedNotificationService, WhatsAppService, WhatsAppResponse, AutomationIssueService, AttachmentService, FileService, LateFeeService, FxRateService, MetricsService, Outcome, ReportCache, SharedClock, Shutdown};
+use crate::infrastructure::repositories::{InvoiceRepository, ClientRepository, ClientCreditRepository, UserRepository, EmailSignatureRepository, EmailTemplateRepository};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
+/// Days before the due date that an attached calendar event reminds the client
+const DUE_DATE_REMINDER_DAYS: u32 = 1;
+
+/// Days before an offer's expiry date that the client gets a follow-up
+const EXPIRY_REMINDER_DAYS: i64 = 3;
+
+/// How often the scheduler sends expiry follow-ups and expires lapsed offers
+const EXPIRY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How often the scheduler marks past-due invoices overdue and sends reminders
+const REMINDER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Days after sending that a client who hasn't opened the invoice is nudged
+const UNVIEWED_REMINDER_DAYS: i64 = 3;
+
+/// Invoices sent longer ago than this aren't nudged about being unopened
+const UNVIEWED_REMINDER_WINDOW_DAYS: i64 = 14;
+
+/// Part of every PDF fingerprint; bump when the invoice layout changes so
+/// copies cached with the old layout are rendered again
+const PDF_LAYOUT_VERSION: u32 = 4;
+
 #[derive(Debug, Error)]
 pub enum InvoiceError {
     #[error("Invoice not found")]
@@ -35,6 +63,41 @@ pub enum InvoiceError {
 
     #[error("Notification error: {0}")]
     NotificationError(String),
+
+    /// No channel accepted the invoice; it was left unsent
+    #[error("Invoice could not be delivered: {0}")]
+    DeliveryFailed(String),
+
+    /// The invoice's status doesn't allow the change; see `InvoiceStatus::allows`
+    #[error("{invoice_number} is {from} and can't be {action}{}", transition_hint(.from))]
+    InvalidTransition {
+        invoice_number: String,
+        from: InvoiceStatus,
+        action: InvoiceAction,
+    },
+}
+
+/// What to do instead, for the statuses where there's something
+fn transition_hint(from: &InvoiceStatus) -> &'static str {
+    match from {
+        InvoiceStatus::Partial | InvoiceStatus::Paid => "; issue a credit note instead",
+        InvoiceStatus::Expired => "; set a later expiry date to reopen it",
+        _ => "",
+    }
+}
+
+impl InvoiceError {
+    /// The request was refused, as opposed to failing on our side
+    pub fn is_rejection(&self) -> bool {
+        matches!(
+            self,
+            InvoiceError::NotFound
+                | InvoiceError::ClientNotFound
+                | InvoiceError::InvalidStatus(_)
+                | InvoiceError::Validation(_)
+                | InvoiceError::InvalidTransition { .. }
+        )
+    }
 }
 
 impl From<sqlx::Error> for InvoiceError {
@@ -64,14 +127,71 @@ impl From<anyhow::Error> for InvoiceError {
     }
 }
 
+/// An invoice PDF, and whether it was served from the stored copy
+pub struct InvoicePdf {
+    pub content: Vec<u8>,
+    pub cached: bool,
+}
+
+/// Everything an invoice PDF is drawn from
+#[derive(Debug)]
+struct InvoicePdfContent {
+    invoice_number: String,
+    company_name: Option<String>,
+    company_address: Option<String>,
+    client_name: String,
+    client_email: Option<String>,
+    client_address: Option<String>,
+    issue_date: String,
+    due_date: String,
+    items: Vec<InvoiceItemPdf>,
+    subtotal: f64,
+    tax_amount: f64,
+    discount: f64,
+    total: f64,
+    notes: Option<String>,
+    terms: Option<String>,
+    taxes: Vec<PdfTaxLine>,
+    /// Both parties' VAT numbers and the reverse-charge note, when it applies
+    vat_notes: Vec<String>,
+    payment_qr: Option<PaymentQr>,
+    status_label: Option<String>,
+    watermark: Option<PdfWatermark>,
+    branding: PdfBranding,
+    /// Stands in for the logo's bytes, which the branding only summarises
+    logo_url: Option<String>,
+}
+
+impl InvoicePdfContent {
+    /// Identifies a rendered copy; any change to what the PDF shows changes it
+    fn fingerprint(&self) -> String {
+        use sha2::{Digest, Sha256};
+        hex::encode(Sha256::digest(format!("v{}:{:?}", PDF_LAYOUT_VERSION, self)))
+    }
+}
+
 pub struct InvoiceService {
     invoice_repo: InvoiceRepository,
     client_repo: ClientRepository,
     user_repo: UserRepository,
     pdf_service: PdfService,
-    email_service: Arc<EmailService>,
+    email_queue: Arc<EmailQueueService>,
     notification_service: Arc<EnhancedNotificationService>,
     whatsapp_service: Arc<WhatsAppService>,
+    automation_issues: Arc<AutomationIssueService>,
+    signatures: EmailSignatureRepository,
+    email_templates: EmailTemplateRepository,
+    attachments: Arc<AttachmentService>,
+    files: Arc<FileService>,
+    late_fees: Arc<LateFeeService>,
+    client_credit: ClientCreditRepository,
+    clock: SharedClock,
+    metrics: Option<Arc<MetricsService>>,
+    /// Settlement rates for booking realized FX gains and losses on payments
+    fx_rates: Option<Arc<FxRateService>>,
+    /// Changes made without a signed-in user, such as guest payments and the
+    /// overdue job, make the owner's cached reports stale
+    reports: ReportCache,
 }
 
 impl InvoiceService {
@@ -80,18 +200,65 @@ impl InvoiceService {
         client_repo: ClientRepository,
         user_repo: UserRepository,
         pdf_service: PdfService,
-        email_service: Arc<EmailService>,
+        email_queue: Arc<EmailQueueService>,
         notification_service: Arc<EnhancedNotificationService>,
         whatsapp_service: Arc<WhatsAppService>,
+        automation_issues: Arc<AutomationIssueService>,
+        signatures: EmailSignatureRepository,
+        email_templates: EmailTemplateRepository,
+        attachments: Arc<AttachmentService>,
+        files: Arc<FileService>,
+        late_fees: Arc<LateFeeService>,
+        client_credit: ClientCreditRepository,
+        clock: SharedClock,
     ) -> Self {
         Self {
             invoice_repo,
             client_repo,
             user_repo,
             pdf_service,
-            email_service,
+            email_queue,
             notification_service,
             whatsapp_service,
+            automation_issues,
+            signatures,
+            email_templates,
+            attachments,
+            files,
+            late_fees,
+            client_credit,
+            clock,
+            metrics: None,
+            fx_rates: None,
+            reports: ReportCache::default(),
+        }
+    }
+
+    /// Count invoices created, payments recorded and reminders sent
+    pub fn with_metrics(mut self, metrics: Arc<MetricsService>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Book realized FX gains and losses on foreign-currency payments
+    pub fn with_fx_rates(mut self, fx_rates: Arc<FxRateService>) -> Self {
+        self.fx_rates = Some(fx_rates);
+        self
+    }
+
+    /// Invalidate cached reports on changes no request invalidates them for
+    pub fn with_report_cache(mut self, reports: ReportCache) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    pub fn today(&self) -> chrono::NaiveDate {
+        self.clock.today()
+    }
+
+    fn count(&self, record: impl FnOnce(&MetricsService)) {
+        if let Some(metrics) = &self.metrics {
+            record(metrics);
         }
     }
 
@@ -99,22 +266,99 @@ impl InvoiceService {
         &self,
         user_id: Uuid,
         create: CreateInvoice,
+    ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let result = self.insert_invoice(user_id, create).await;
+        self.count(|metrics| metrics.record_invoice_created(Outcome::of(&result, InvoiceError::is_rejection)));
+        result
+    }
+
+    async fn insert_invoice(
+        &self,
+        user_id: Uuid,
+        mut create: CreateInvoice,
     ) -> Result<InvoiceDetailResponse, InvoiceError> {
         // Validate client exists
-        let client_opt = self.client_repo.find_by_id(user_id, create.client_id).await?;
-        if client_opt.is_none() {
-            return Err(InvoiceError::ClientNotFound);
+        let client = self
+            .client_repo
+            .find_by_id(user_id, create.client_id)
+            .await?
+            .filter(|c| c.deleted_at.is_none())
+            .ok_or(InvoiceError::ClientNotFound)?;
+        if client.archived_at.is_some() {
+            return Err(InvoiceError::Validation("Client is archived; unarchive it to invoice again".to_string()));
+        }
+
+        if create.expires_at.is_some_and(|expires_at| expires_at < create.issue_date) {
+            return Err(InvoiceError::Validation("Expiry date can't be before the issue date".to_string()));
+        }
+        if let Some(deposit) = &create.deposit {
+            deposit.validate(create.issue_date, create.due_date).map_err(InvoiceError::Validation)?;
         }
+        Self::validate_item_taxes(&create.items)?;
+        if create.reverse_charge {
+            Self::remove_vat(&mut create.items);
+            (create.tax_label, create.tax_id) = (None, None);
+        }
+
+        let mut create = self.resolve_templates(user_id, &client, create).await?;
+
+        // Created as a draft; it only becomes sent once a channel accepts it
+        let send_immediately = std::mem::take(&mut create.send_immediately);
 
         // Create invoice via repository
         let invoice = self.invoice_repo.create(user_id, create).await?;
 
+        if let Some(user) = self.user_repo.find_by_id(user_id).await? {
+            let detail = self.invoice_repo.get_by_id(user_id, invoice.id).await?;
+            self.auto_apply_credit(&user, &detail, CreditAutoApply::OnCreate).await?;
+        }
+
+        if send_immediately {
+            self.send_invoice(user_id, invoice.id, InvoiceSendOptions::default())
+                .await
+                .map_err(|e| match e {
+                    InvoiceError::DeliveryFailed(reason) => InvoiceError::DeliveryFailed(format!(
+                        "{} was saved as a draft: {}",
+                        invoice.invoice_number, reason
+                    )),
+                    other => other,
+                })?;
+        }
+
         // Get full details with client info
         let detail = self.invoice_repo.get_by_id(user_id, invoice.id).await?;
 
         Ok(detail)
     }
 
+    /// Fill in default terms/notes from settings and resolve their {{variables}}
+    async fn resolve_templates(
+        &self,
+        user_id: Uuid,
+        client: &Client,
+        mut create: CreateInvoice,
+    ) -> Result<CreateInvoice, InvoiceError> {
+        let user = self.user_repo.find_by_id(user_id).await?;
+        let settings = user.as_ref().and_then(|u| u.invoice_settings.clone()).unwrap_or_default();
+
+        let variables = TemplateVariables {
+            late_fee_rate: settings.late_fee_rate,
+            due_days: (create.due_date - create.issue_date).num_days(),
+            due_date: create.due_date,
+            client_name: client.name.clone(),
+            company_name: user.and_then(|u| u.company_name),
+        };
+
+        let resolve = |text: Option<String>, default: String| {
+            text.or_else(|| Some(default).filter(|d| !d.trim().is_empty()))
+                .map(|t| variables.render(&t))
+        };
+        create.terms = resolve(create.terms, settings.terms);
+        create.notes = resolve(create.notes, settings.notes);
+
+        Ok(create)
+    }
+
     pub async fn get_invoice(
         &self,
         user_id: Uuid,
@@ -131,22 +375,66 @@ impl InvoiceService {
         Ok(self.invoice_repo.list(user_id, filter).await?)
     }
 
+    pub async fn list_invoices_page(
+        &self,
+        user_id: Uuid,
+        filter: InvoiceListFilter,
+        page: &PageRequest,
+    ) -> Result<Page<InvoiceResponse>, InvoiceError> {
+        Ok(self.invoice_repo.list_page(user_id, filter, page).await?)
+    }
+
     pub async fn update_invoice(
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
-        update: UpdateInvoice,
+        mut update: UpdateInvoice,
     ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let existing = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&existing, InvoiceAction::Edit)?;
+        if let Some(expires_at) = update.expires_at {
+            if expires_at < update.issue_date.unwrap_or(existing.issue_date) {
+                return Err(InvoiceError::Validation("Expiry date can't be before the issue date".to_string()));
+            }
+        }
+        let deposit = update.deposit.clone().or_else(|| existing.deposit.as_ref().map(InvoiceDeposit::terms));
+        if let Some(deposit) = deposit {
+            deposit
+                .validate(update.issue_date.unwrap_or(existing.issue_date), update.due_date.unwrap_or(existing.due_date))
+                .map_err(InvoiceError::Validation)?;
+        }
+        if let Some(items) = &update.items {
+            Self::validate_item_taxes(items)?;
+        }
+        if existing.reverse_charge {
+            if let Some(items) = update.items.as_mut() {
+                Self::remove_vat(items);
+            }
+        }
+
         // Validate client exists if being updated
         if let Some(client_id) = update.client_id {
             let client_opt = self.client_repo.find_by_id(user_id, client_id).await?;
-            if client_opt.is_none() {
-                return Err(InvoiceError::ClientNotFound);
+            match client_opt {
+                Some(client) if client.deleted_at.is_none() => {
+                    if client.archived_at.is_some() {
+                        return Err(InvoiceError::Validation("Client is archived".to_string()));
+                    }
+                    // The invoice's VAT treatment was settled for the client it was created for
+                    let seller_country = self.user_repo.find_by_id(user_id).await?.as_ref().and_then(seller_vat_country);
+                    if client.is_reverse_charged_from(seller_country.as_deref()) != existing.reverse_charge {
+                        return Err(InvoiceError::Validation(
+                            "That client's VAT treatment differs (reverse charge); create a new invoice for them".to_string(),
+                        ));
+                    }
+                }
+                _ => return Err(InvoiceError::ClientNotFound),
             }
         }
 
         // Update via repository
         let invoice = self.invoice_repo.update(user_id, invoice_id, update).await?;
+        self.invalidate_pdf(user_id, invoice_id).await;
 
         // Get full details
         let detail = self.invoice_repo.get_by_id(user_id, invoice.id).await?;
@@ -159,7 +447,254 @@ impl InvoiceService {
         user_id: Uuid,
         invoice_id: Uuid,
     ) -> Result<(), InvoiceError> {
-        Ok(self.invoice_repo.delete(user_id, invoice_id).await?)
+        let existing = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&existing, InvoiceAction::Delete)?;
+
+        if !self.invoice_repo.trash_draft(user_id, invoice_id).await? {
+            return Err(self.changed_status(user_id, invoice_id, InvoiceAction::Delete).await);
+        }
+        Ok(())
+    }
+
+    /// Permanently delete a draft that was just created but couldn't be finished,
+    /// bypassing the trash
+    pub async fn discard_draft(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
+        self.invoice_repo.delete_draft(user_id, invoice_id).await?;
+        Ok(())
+    }
+
+    /// Take a deleted draft out of the trash. Drafts whose client is in the
+    /// trash come back with the client instead.
+    pub async fn restore_invoice(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
+        let client_id = self.invoice_repo
+            .find_trashed_client_id(user_id, invoice_id)
+            .await?
+            .ok_or(InvoiceError::NotFound)?;
+        let client = self.client_repo.find_by_id(user_id, client_id).await?;
+        if client.is_some_and(|client| client.deleted_at.is_some()) {
+            return Err(InvoiceError::Validation("The invoice's client is in the trash; restore the client first".to_string()));
+        }
+
+        if !self.invoice_repo.restore(user_id, invoice_id).await? {
+            return Err(InvoiceError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Void an invoice that hasn't been paid. It stays on record with its number;
+    /// invoices with payments are adjusted with a credit note instead.
+    pub async fn cancel_invoice(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
+        let existing = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&existing, InvoiceAction::Cancel)?;
+
+        // A payment or send may have landed since the check
+        if !self.invoice_repo.update_status(user_id, invoice_id, &existing.status, &InvoiceStatus::Cancelled).await? {
+            return Err(self.changed_status(user_id, invoice_id, InvoiceAction::Cancel).await);
+        }
+        self.invalidate_pdf(user_id, invoice_id).await;
+        Ok(())
+    }
+
+    /// Combine several draft invoices for the same client into one invoice with a
+    /// section per source. The originals are cancelled and linked to the new invoice.
+    pub async fn consolidate_invoices(
+        &self,
+        user_id: Uuid,
+        consolidate: ConsolidateInvoices,
+    ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let mut sources: Vec<InvoiceDetailResponse> = Vec::new();
+        for invoice_id in consolidate.invoice_ids {
+            if sources.iter().any(|s| s.id == invoice_id) {
+                continue;
+            }
+
+            let invoice = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+            if invoice.status != InvoiceStatus::Draft {
+                return Err(InvoiceError::InvalidStatus(format!(
+                    "{} is {}, only draft invoices can be consolidated",
+                    invoice.invoice_number, invoice.status
+                )));
+            }
+            sources.push(invoice);
+        }
+
+        if sources.len() < 2 {
+            return Err(InvoiceError::Validation(
+                "At least two invoices are required for consolidation".to_string(),
+            ));
+        }
+
+        let first = &sources[0];
+        if sources.iter().any(|s| s.client_id != first.client_id) {
+            return Err(InvoiceError::Validation(
+                "All invoices must belong to the same client".to_string(),
+            ));
+        }
+        if sources.iter().any(|s| s.currency != first.currency) {
+            return Err(InvoiceError::Validation(
+                "All invoices must use the same currency".to_string(),
+            ));
+        }
+        if sources.iter().any(|s| s.reverse_charge != first.reverse_charge) {
+            return Err(InvoiceError::Validation(
+                "Reverse-charged invoices can't be consolidated with taxed ones".to_string(),
+            ));
+        }
+
+        let source_numbers: Vec<String> = sources.iter().map(|s| s.invoice_number.clone()).collect();
+        let items = sources
+            .iter()
+            .flat_map(|source| {
+                source.items.iter().map(|item| CreateInvoiceItem {
+                    description: item.description.clone(),
+                    quantity: item.quantity,
+                    unit_price: item.unit_price,
+                    tax_rate: Some(item.tax_rate),
+                    section: Some(source.invoice_number.clone()),
+                    taxes: item.taxes.iter().map(LineTax::component).collect(),
+                })
+            })
+            .collect();
+
+        let create = CreateInvoice {
+            client_id: first.client_id,
+            issue_date: consolidate.issue_date.unwrap_or_else(|| self.clock.today()),
+            due_date: consolidate
+                .due_date
+                .or_else(|| sources.iter().map(|s| s.due_date).max())
+                .unwrap_or(first.due_date),
+            items,
+            notes: consolidate
+                .notes
+                .or_else(|| Some(format!("Consolidates invoices {}", source_numbers.join(", ")))),
+            terms: consolidate.terms.or_else(|| first.terms.clone()),
+            discount_amount: Some(sources.iter().map(|s| s.discount_amount).sum()),
+            tax_included: first.tax_included,
+            send_immediately: false,
+            tax_label: None,
+            tax_id: None,
+            currency: Some(first.currency.clone()),
+            exchange_rate: Some(first.exchange_rate),
+            allow_partial_payment: Some(first.allow_partial_payment),
+            min_payment_amount: None,
+            expires_at: None,
+            // Stays on the project only when every source invoice is on it
+            project_id: first.project_id.filter(|project_id| {
+                sources.iter().all(|s| s.project_id == Some(*project_id))
+            }),
+            deposit: None,
+            reverse_charge: first.reverse_charge,
+            vat_country: first.vat_country.clone(),
+        };
+
+        let invoice = self.invoice_repo.create(user_id, create).await?;
+
+        let source_ids: Vec<Uuid> = sources.iter().map(|s| s.id).collect();
+        if !self.invoice_repo.mark_consolidated(user_id, &source_ids, invoice.id).await? {
+            // A source changed while we were building the consolidated invoice
+            self.invoice_repo.delete_draft(user_id, invoice.id).await?;
+            return Err(InvoiceError::InvalidStatus(
+                "Source invoices changed during consolidation".to_string(),
+            ));
+        }
+
+        Ok(self.invoice_repo.get_by_id(user_id, invoice.id).await?)
+    }
+
+    /// Issue a corrected version of a sent invoice. The original stays on record, marked
+    /// superseded and linked to the correction; nothing is edited in place.
+    pub async fn correct_invoice(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        correction: CorrectInvoice,
+    ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let reason = correction.reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(InvoiceError::Validation("A reason for the correction is required".to_string()));
+        }
+
+        let original = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&original, InvoiceAction::Correct)?;
+        if original.amount_paid > Decimal::ZERO {
+            return Err(InvoiceError::InvalidStatus(format!(
+                "{} has payments recorded and can't be corrected",
+                original.invoice_number
+            )));
+        }
+
+        let mut items = correction.items.unwrap_or_else(|| {
+            original
+                .items
+                .iter()
+                .map(|item| CreateInvoiceItem {
+                    description: item.description.clone(),
+                    quantity: item.quantity,
+                    unit_price: item.unit_price,
+                    tax_rate: Some(item.tax_rate),
+                    section: item.section.clone(),
+                    taxes: item.taxes.iter().map(LineTax::component).collect(),
+                })
+                .collect()
+        });
+        if items.is_empty() {
+            return Err(InvoiceError::Validation("A corrected invoice needs at least one item".to_string()));
+        }
+        Self::validate_item_taxes(&items)?;
+        if original.reverse_charge {
+            Self::remove_vat(&mut items);
+        }
+
+        let create = CreateInvoice {
+            client_id: original.client_id,
+            issue_date: correction.issue_date.unwrap_or_else(|| self.clock.today()),
+            due_date: correction.due_date.unwrap_or(original.due_date),
+            items,
+            notes: correction.notes.or_else(|| original.notes.clone()),
+            terms: correction.terms.or_else(|| original.terms.clone()),
+            discount_amount: Some(correction.discount_amount.unwrap_or(original.discount_amount)),
+            tax_included: original.tax_included,
+            send_immediately: false,
+            tax_label: original.tax_label.clone(),
+            tax_id: original.tax_id.clone(),
+            currency: Some(original.currency.clone()),
+            exchange_rate: Some(original.exchange_rate),
+            allow_partial_payment: Some(original.allow_partial_payment),
+            min_payment_amount: original.min_payment_amount,
+            // The corrected invoice carries the original offer's expiry
+            expires_at: original.expires_at,
+            project_id: original.project_id,
+            // Same deposit, falling due on the corrected invoice's issue date
+            deposit: original.deposit.as_ref().map(|deposit| DepositTerms { due_date: None, ..deposit.terms() }),
+            reverse_charge: original.reverse_charge,
+            vat_country: original.vat_country.clone(),
+        };
+        if create.due_date < create.issue_date {
+            return Err(InvoiceError::Validation("Due date must be on or after the issue date".to_string()));
+        }
+
+        let corrected = self.invoice_repo.create(user_id, create).await?;
+
+        if !self.invoice_repo.mark_superseded(user_id, original.id, corrected.id, &reason).await? {
+            // The original was paid or changed while the correction was being issued
+            self.invoice_repo.delete_draft(user_id, corrected.id).await?;
+            return Err(InvoiceError::InvalidStatus(
+                "Original invoice changed while issuing the correction".to_string(),
+            ));
+        }
+
+        if correction.send {
+            let options = InvoiceSendOptions {
+                message: Some(format!(
+                    "This corrected invoice replaces invoice #{}. Reason: {}",
+                    original.invoice_number, reason
+                )),
+                ..Default::default()
+            };
+            self.send_invoice(user_id, corrected.id, options).await?;
+        }
+
+        Ok(self.invoice_repo.get_by_id(user_id, corrected.id).await?)
     }
 
     pub async fn record_payment(
@@ -168,6 +703,60 @@ impl InvoiceService {
         invoice_id: Uuid,
         payment: CreatePayment,
     ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let result = self.apply_payment(user_id, invoice_id, payment).await;
+        self.count(|metrics| metrics.record_payment_recorded(Outcome::of(&result, InvoiceError::is_rejection)));
+        result
+    }
+
+    /// Settles as much of the invoice's balance as the client's credit in its
+    /// currency covers. Applies nothing when there's no credit.
+    pub async fn apply_client_credit(&self, user_id: Uuid, invoice_id: Uuid) -> Result<CreditApplied, InvoiceError> {
+        let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&detail, InvoiceAction::RecordPayment)?;
+
+        let applied = self
+            .client_credit
+            .apply_to_invoice(user_id, invoice_id, self.clock.today())
+            .await?
+            .ok_or(InvoiceError::NotFound)?;
+        if applied.applied > Decimal::ZERO {
+            self.invalidate_pdf(user_id, invoice_id).await;
+        }
+        Ok(applied)
+    }
+
+    /// Applies the client's credit if the user's settings ask for it at `moment`.
+    /// Returns whether any was applied.
+    async fn auto_apply_credit(
+        &self,
+        user: &User,
+        invoice: &InvoiceDetailResponse,
+        moment: CreditAutoApply,
+    ) -> Result<bool, InvoiceError> {
+        let mode = user.invoice_settings.as_ref().map(|settings| settings.credit_auto_apply).unwrap_or_default();
+        if mode != moment || !invoice.status.allows(InvoiceAction::RecordPayment) {
+            return Ok(false);
+        }
+
+        let applied = self.client_credit.apply_to_invoice(user.id, invoice.id, self.clock.today()).await?;
+        let applied = applied.is_some_and(|applied| applied.applied > Decimal::ZERO);
+        if applied {
+            self.invalidate_pdf(user.id, invoice.id).await;
+        }
+        Ok(applied)
+    }
+
+    async fn apply_payment(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        mut payment: CreatePayment,
+    ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let existing = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&existing, InvoiceAction::RecordPayment)?;
+        existing.check_payment_amount(payment.amount).map_err(InvoiceError::Validation)?;
+        payment.exchange_rate = self.settlement_rate(&existing, payment.exchange_rate).await;
+
         // Record payment via repository
         let invoice = self.invoice_repo.record_payment(user_id, invoice_id, payment).await?;
 
@@ -177,72 +766,173 @@ impl InvoiceService {
         Ok(detail)
     }
 
+    /// Applies a completed guest or portal payment, already stored as `payment_id`
+    pub async fn record_guest_payment(
+        &self,
+        invoice_id: Uuid,
+        payment_id: Uuid,
+        mut payment: CreatePayment,
+    ) -> Result<InvoiceDetailResponse, InvoiceError> {
+        let existing = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        payment.exchange_rate = self.settlement_rate(&existing, payment.exchange_rate).await;
+        self.invoice_repo.record_payment_guest(invoice_id, payment_id, payment).await?;
+        self.reports.invalidate_user(existing.user_id).await;
+
+        Ok(self.invoice_repo.get_invoice_by_id(invoice_id).await?)
+    }
+
+    /// Rate a payment on the invoice settled at, when it has an FX result to book
+    async fn settlement_rate(&self, invoice: &InvoiceDetailResponse, recorded: Option<f64>) -> Option<f64> {
+        match &self.fx_rates {
+            Some(fx_rates) => fx_rates.settlement_rate(invoice.user_id, &invoice.currency, recorded).await,
+            None => None,
+        }
+    }
+
     pub async fn send_invoice(
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
-        email: Option<String>,
+        options: InvoiceSendOptions,
     ) -> Result<(), InvoiceError> {
+        let cc = Self::validate_addresses("cc", &options.cc)?;
+        let bcc = Self::validate_addresses("bcc", &options.bcc)?;
+
         // Validate invoice exists and get details
-        let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        let mut detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Self::ensure_allowed(&detail, InvoiceAction::Send)?;
 
         // Get user (company) info
         let user = self.user_repo.find_by_id(user_id)
             .await?
             .ok_or(InvoiceError::Validation("User not found".to_string()))?;
 
+        // Draw on the client's credit first, so what they receive shows what's left to pay
+        if self.auto_apply_credit(&user, &detail, CreditAutoApply::OnSend).await? {
+            detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        }
+
         // Get client info
         let client = self.client_repo.find_by_id(user_id, detail.client_id)
             .await?
             .ok_or(InvoiceError::ClientNotFound)?;
 
-        // Generate PDF
-        let items: Vec<InvoiceItemPdf> = detail.items.iter().map(|item| InvoiceItemPdf {
-            description: item.description.clone(),
-            quantity: item.quantity,
-            unit_price: item.unit_price,
-            total: item.total,
-        }).collect();
+        // An explicit address is used alone. The client's own address falls back to
+        // their billing contacts, in order, when it hard-bounces.
+        let recipients: Vec<(String, String)> = match options.email.clone() {
+            Some(address) => vec![(address, client.name.clone())],
+            None => detail.client_email.iter()
+                .map(|address| (address.clone(), client.name.clone()))
+                .chain(client.billing_contacts.iter().map(|contact| {
+                    (contact.email.clone(), contact.name.clone().unwrap_or_else(|| client.name.clone()))
+                }))
+                .collect(),
+        };
+        if recipients.is_empty() && client.phone.is_none() {
+            return Err(InvoiceError::Validation(
+                "Client has no email address or phone number to send the invoice to".to_string(),
+            ));
+        }
 
-        let company_address = user.business_address.as_ref().map(|addr| {
-            format!("{}, {}, {} {}", addr.street, addr.city, addr.state, addr.zip_code)
-        });
+        // Render before sending anything; a PDF failure leaves the invoice untouched
+        let pdf_bytes = self.emailed_pdf(&detail, &user, &client).await?;
 
-        let client_address = client.billing_address.as_ref().map(|addr| {
-            addr.to_string()
-        });
+        // Subject and message can use the same placeholders as terms and notes
+        let variables = TemplateVariables {
+            late_fee_rate: user.invoice_settings.as_ref().and_then(|settings| settings.late_fee_rate),
+            due_days: (detail.due_date - detail.issue_date).num_days(),
+            due_date: detail.due_date,
+            client_name: client.name.clone(),
+            company_name: user.company_name.clone(),
+        };
+        let subject = options.subject.as_deref().map(|text| variables.render(text));
+        let message = options.message.as_deref().map(|text| variables.render(text));
+        let signature = self.signatures.resolve(user_id, options.sender_id).await?;
+        let template = self.email_templates.find(user_id, EmailTemplateKind::Invoice).await?;
+        let attachments = self.attachment_links(&detail).await?;
+        let locale = client.locale.unwrap_or(user.locale);
 
-        let pdf_bytes = self.pdf_service.generate_invoice_pdf(
-            &detail.invoice_number,
-            user.company_name.as_deref(),
-            company_address.as_deref(),
-            &client.name,
-            client.email.as_deref(),
-            client_address.as_deref(),
-            &detail.issue_date.to_string(),
-            &detail.due_date.to_string(),
-            &items,
-            detail.subtotal,
-            detail.tax_amount,
-            detail.discount_amount,
-            detail.total_amount,
-            detail.notes.as_deref(),
-            detail.terms.as_deref(),
-            detail.tax_label.as_deref(),
-        )?;
+        let mut failures: Vec<String> = Vec::new();
 
         // Send email with PDF attachment
-        let email_sent = if let Some(email) = email.clone().or_else(|| detail.client_email.clone()) {
-            self.email_service.send_invoice_with_attachment(
-                &email,
-                &client.name,
-                &detail.invoice_number,
-                pdf_bytes.clone(),
-                detail.total_amount,
-                &detail.due_date.to_string(),
-            ).is_ok()
-        } else {
+        let email_sent = if recipients.is_empty() {
             false
+        } else {
+            let mut bounced_from: Option<Uuid> = None;
+            let mut errors: Vec<String> = Vec::new();
+            let mut delivered = false;
+
+            for (address, name) in &recipients {
+                let calendar = options
+                    .attach_calendar
+                    .then(|| self.due_date_calendar(&detail, &user, address, name));
+                let invoice_email = InvoiceEmail {
+                    cc: cc.clone(),
+                    bcc: bcc.clone(),
+                    subject: subject.clone(),
+                    message: message.clone(),
+                    calendar,
+                    signature: signature.clone(),
+                    attachments: attachments.clone(),
+                    business_name: user.company_name.clone(),
+                    template: template.clone(),
+                    locale,
+                    currency: detail.currency.clone(),
+                    ..InvoiceEmail::new(
+                        address,
+                        name,
+                        &detail.invoice_number,
+                        detail.total_amount.to_f64().unwrap_or_default(),
+                        &format_date(locale, detail.due_date),
+                    )
+                };
+                let sent_subject = invoice_email.subject();
+                let result = self.email_queue
+                    .send_immediate(EmailJobType::SendInvoiceEmail { invoice: Box::new(invoice_email), pdf_bytes: pdf_bytes.clone() })
+                    .await;
+
+                // A retry after a bounce points at the bounced attempt in the send log
+                let attempt = self.log_notification(user_id, invoice_id, NewInvoiceNotification {
+                    channel: NotificationChannel::Email,
+                    recipients: vec![address.clone()],
+                    cc: cc.clone(),
+                    bcc: bcc.clone(),
+                    subject: Some(sent_subject),
+                    message: message.clone(),
+                    error: result.as_ref().err().map(|e| e.to_string()),
+                    resent_from_id: bounced_from,
+                }).await;
+
+                match result {
+                    Ok(_) => {
+                        delivered = true;
+                        break;
+                    }
+                    Err(e) => {
+                        errors.push(format!("{}: {}", address, e));
+                        // Only a hard bounce means this address is bad; anything else
+                        // would fail for the next contact too
+                        if !e.is_hard_bounce() {
+                            break;
+                        }
+                        bounced_from = attempt.map(|a| a.id);
+                    }
+                }
+            }
+
+            // The seller is only alerted once no address took the invoice
+            if !delivered {
+                failures.push(format!("email to {}", errors.join("; ")));
+                let tried: Vec<&str> = recipients.iter().take(errors.len()).map(|(a, _)| a.as_str()).collect();
+                self.report_issue(
+                    user_id,
+                    AutomationIssueKind::EmailBounce,
+                    invoice_id,
+                    format!("Invoice {} could not be emailed to {}", detail.invoice_number, tried.join(", ")),
+                    errors.join("\n"),
+                ).await;
+            }
+            delivered
         };
 
         // Send WhatsApp notification if phone is available
@@ -255,23 +945,264 @@ impl InvoiceService {
                 &user,
                 payment_link,
             ).await;
+            self.log_notification(user_id, invoice_id, NewInvoiceNotification {
+                channel: NotificationChannel::Whatsapp,
+                recipients: vec![phone.clone()],
+                cc: Vec::new(),
+                bcc: Vec::new(),
+                subject: None,
+                message: None,
+                error: result.as_ref().err().map(|e| e.to_string()),
+                resent_from_id: None,
+            }).await;
+            if let Err(e) = &result {
+                failures.push(format!("WhatsApp to {}: {}", phone, e));
+                self.report_issue(
+                    user_id,
+                    AutomationIssueKind::WhatsappFailed,
+                    invoice_id,
+                    format!("Invoice {} could not be sent via WhatsApp to {}", detail.invoice_number, phone),
+                    e.to_string(),
+                ).await;
+            }
             result.is_ok()
         } else {
             false
         };
 
-        // Update notification tracking in repository
-        self.invoice_repo.send_invoice(user_id, invoice_id, email.clone()).await?;
+        // Only an accepted send moves the invoice to sent
+        if !email_sent && !whatsapp_sent {
+            return Err(InvoiceError::DeliveryFailed(failures.join("; ")));
+        }
+        self.invoice_repo.mark_sent(user_id, invoice_id, email_sent, whatsapp_sent).await?;
+
+        Ok(())
+    }
+
+    /// Send attempts for the invoice, newest first
+    pub async fn list_notifications(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<Vec<InvoiceNotification>, InvoiceError> {
+        // Ensures the invoice belongs to the user
+        self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Ok(self.invoice_repo.list_notifications(user_id, invoice_id).await?)
+    }
 
-        // Update notification sent timestamps
-        if email_sent {
-            let _ = self.invoice_repo.update_notification_sent(invoice_id, false).await;
+    /// Latest attempt on each channel, so a failed send shows up on the invoice
+    pub async fn delivery_status(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<Vec<NotificationDelivery>, InvoiceError> {
+        let latest = self.invoice_repo.latest_notifications(user_id, invoice_id).await?;
+        Ok(latest.into_iter().map(NotificationDelivery::from).collect())
+    }
+
+    /// Pipeline label shown on the invoice, if it applies to the current status
+    pub async fn active_label(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<InvoiceLabel>, InvoiceError> {
+        Ok(self.invoice_repo.active_label(user_id, invoice_id).await?)
+    }
+
+    /// Retry a failed send attempt on the same channel with the same recipients,
+    /// subject and message. Returns the new attempt, which may itself have failed.
+    pub async fn resend_notification(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        notification_id: Uuid,
+    ) -> Result<InvoiceNotification, InvoiceError> {
+        let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        let original = self.invoice_repo
+            .find_notification(user_id, invoice_id, notification_id)
+            .await?
+            .ok_or(InvoiceError::NotFound)?;
+        if original.delivered {
+            return Err(InvoiceError::InvalidStatus("Only failed notifications can be resent".to_string()));
         }
-        if whatsapp_sent {
-            let _ = self.invoice_repo.update_notification_sent(invoice_id, true).await;
+        let latest = self.invoice_repo.latest_notifications(user_id, invoice_id).await?;
+        if latest.iter().any(|n| n.channel == original.channel && n.delivered && n.created_at > original.created_at) {
+            return Err(InvoiceError::InvalidStatus(format!(
+                "A later {} send was already delivered",
+                original.channel.as_str()
+            )));
         }
+        Self::ensure_allowed(&detail, InvoiceAction::Send)?;
+        let recipient = original.recipients.first().cloned()
+            .ok_or(InvoiceError::Validation("Notification has no recipient".to_string()))?;
 
-        Ok(())
+        let user = self.user_repo.find_by_id(user_id)
+            .await?
+            .ok_or(InvoiceError::Validation("User not found".to_string()))?;
+        let client = self.client_repo.find_by_id(user_id, detail.client_id)
+            .await?
+            .ok_or(InvoiceError::ClientNotFound)?;
+
+        let (result, kind, failure) = match original.channel {
+            NotificationChannel::Email => {
+                let pdf_bytes = self.emailed_pdf(&detail, &user, &client).await?;
+                let invoice_email = InvoiceEmail {
+                    cc: original.cc.clone(),
+                    bcc: original.bcc.clone(),
+                    subject: original.subject.clone(),
+                    message: original.message.clone(),
+                    signature: self.signatures.resolve(user_id, None).await?,
+                    attachments: self.attachment_links(&detail).await?,
+                    business_name: user.company_name.clone(),
+                    template: self.email_templates.find(user_id, EmailTemplateKind::Invoice).await?,
+                    locale: client.locale.unwrap_or(user.locale),
+                    currency: detail.currency.clone(),
+                    ..InvoiceEmail::new(
+                        &recipient,
+                        &client.name,
+                        &detail.invoice_number,
+                        detail.total_amount.to_f64().unwrap_or_default(),
+                        &format_date(client.locale.unwrap_or(user.locale), detail.due_date),
+                    )
+                };
+                let result = self.email_queue
+                    .send_immediate(EmailJobType::SendInvoiceEmail { invoice: Box::new(invoice_email), pdf_bytes })
+                    .await
+                    .map_err(|e| e.to_string());
+                (
+                    result,
+                    AutomationIssueKind::EmailBounce,
+                    format!("Invoice {} could not be emailed to {}", detail.invoice_number, recipient),
+                )
+            }
+            NotificationChannel::Whatsapp => {
+                let payment_link = detail.guest_payment_token.clone()
+                    .map(|token| format!("https://yourapp.com/guest/pay/{}", token));
+                let result = self.whatsapp_service
+                    .send_invoice(&recipient, &detail, &user, payment_link)
+                    .await
+                    .map(|_| ())
+                    .map_err(|e| e.to_string());
+                (
+                    result,
+                    AutomationIssueKind::WhatsappFailed,
+                    format!("Invoice {} could not be sent via WhatsApp to {}", detail.invoice_number, recipient),
+                )
+            }
+        };
+
+        let attempt = self.invoice_repo.log_notification(user_id, invoice_id, &NewInvoiceNotification {
+            channel: original.channel,
+            recipients: original.recipients.clone(),
+            cc: original.cc,
+            bcc: original.bcc,
+            subject: original.subject,
+            message: original.message,
+            error: result.as_ref().err().cloned(),
+            resent_from_id: Some(original.id),
+        }).await?;
+
+        // A delivered retry of a failed first send is what sends the invoice
+        match result {
+            Ok(()) => {
+                let whatsapp = original.channel == NotificationChannel::Whatsapp;
+                self.invoice_repo.mark_sent(user_id, invoice_id, !whatsapp, whatsapp).await?;
+            }
+            Err(e) => self.report_issue(user_id, kind, invoice_id, failure, e).await,
+        }
+
+        Ok(attempt)
+    }
+
+    /// Reverse-charged sales carry no VAT, whatever the lines asked for
+    fn remove_vat(items: &mut Vec<CreateInvoiceItem>) {
+        *items = std::mem::take(items).into_iter().map(CreateInvoiceItem::untaxed).collect();
+    }
+
+    fn validate_item_taxes(items: &[CreateInvoiceItem]) -> Result<(), InvoiceError> {
+        items
+            .iter()
+            .try_for_each(|item| TaxComponent::validate_all(&item.taxes))
+            .map_err(InvoiceError::Validation)
+    }
+
+    fn ensure_allowed(invoice: &InvoiceDetailResponse, action: InvoiceAction) -> Result<(), InvoiceError> {
+        if invoice.status.allows(action) {
+            return Ok(());
+        }
+        Err(InvoiceError::InvalidTransition {
+            invoice_number: invoice.invoice_number.clone(),
+            from: invoice.status.clone(),
+            action,
+        })
+    }
+
+    /// The error for a change that lost a race with another status change,
+    /// reporting the status the invoice has now
+    async fn changed_status(&self, user_id: Uuid, invoice_id: Uuid, action: InvoiceAction) -> InvoiceError {
+        match self.invoice_repo.get_by_id(user_id, invoice_id).await {
+            Ok(current) => Self::ensure_allowed(&current, action)
+                .err()
+                .unwrap_or_else(|| InvoiceError::InvalidStatus("The invoice changed while it was being updated; try again".to_string())),
+            Err(err) => err.into(),
+        }
+    }
+
+    fn validate_addresses(field: &str, addresses: &[String]) -> Result<Vec<String>, InvoiceError> {
+        use validator::ValidateEmail;
+
+        let mut valid: Vec<String> = Vec::with_capacity(addresses.len());
+        for address in addresses.iter().map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            if !address.validate_email() {
+                return Err(InvoiceError::Validation(format!("Invalid {} address: {}", field, address)));
+            }
+            if !valid.iter().any(|v| v.eq_ignore_ascii_case(address)) {
+                valid.push(address.to_string());
+            }
+        }
+        if valid.len() > 10 {
+            return Err(InvoiceError::Validation(format!("At most 10 {} addresses are allowed", field)));
+        }
+        Ok(valid)
+    }
+
+    /// The log is best-effort; a failed write never fails the send
+    async fn log_notification(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        notification: NewInvoiceNotification,
+    ) -> Option<InvoiceNotification> {
+        match self.invoice_repo.log_notification(user_id, invoice_id, &notification).await {
+            Ok(logged) => Some(logged),
+            Err(e) => {
+                tracing::warn!("Failed to log {} notification for invoice {}: {}", notification.channel.as_str(), invoice_id, e);
+                None
+            }
+        }
+    }
+
+    /// Due-date event addressed to the recipient. The UID is fixed per invoice, so a
+    /// re-sent invoice updates the event instead of adding another.
+    fn due_date_calendar(&self, detail: &InvoiceDetailResponse, user: &User, to_email: &str, to_name: &str) -> String {
+        let company = user.company_name.clone().unwrap_or_else(|| user.email.clone());
+        let event = CalendarEvent {
+            uid: format!("invoice-{}@flashbill", detail.id),
+            date: detail.due_date,
+            summary: format!(
+                "Invoice #{} due: {} {:.2}",
+                detail.invoice_number, detail.currency, detail.balance_due
+            ),
+            description: Some(format!(
+                "{} invoice #{} for {} {:.2} is due on {}.",
+                company, detail.invoice_number, detail.currency, detail.total_amount, detail.due_date
+            )),
+            url: detail
+                .guest_payment_token
+                .as_ref()
+                .map(|token| format!("https://yourapp.com/guest/pay/{}", token)),
+            organizer: Some(CalendarContact { name: company, email: user.email.clone() }),
+            attendee: Some(CalendarContact { name: to_name.to_string(), email: to_email.to_string() }),
+            reminder_days_before: Some(DUE_DATE_REMINDER_DAYS),
+        };
+
+        Calendar { method: Some("PUBLISH"), events: vec![event], ..Default::default() }.render(self.clock.now())
     }
 
     /// Send invoice via WhatsApp only
@@ -329,16 +1260,53 @@ impl InvoiceService {
 
         // Send email confirmation
         if let Some(email) = client.email.clone() {
-            let _ = self.notification_service.send_payment_confirmation(
+            let result = self.notification_service.send_payment_confirmation(
                 &detail,
-                Some(email),
+                Some(email.clone()),
                 client.phone.clone(),
-            ).await;
+            ).await.unwrap_or_default();
+
+            if let Some(error) = result.email_error {
+                self.report_issue(
+                    user_id,
+                    AutomationIssueKind::EmailBounce,
+                    invoice_id,
+                    format!("Payment confirmation for invoice {} could not be emailed to {}", detail.invoice_number, email),
+                    error,
+                ).await;
+            }
+            if let Some(error) = result.whatsapp_error {
+                self.report_issue(
+                    user_id,
+                    AutomationIssueKind::WhatsappFailed,
+                    invoice_id,
+                    format!("Payment confirmation for invoice {} could not be sent via WhatsApp", detail.invoice_number),
+                    error,
+                ).await;
+            }
         }
 
         Ok(())
     }
 
+    /// Surface a delivery failure in the user's needs-attention digest
+    async fn report_issue(
+        &self,
+        user_id: Uuid,
+        kind: AutomationIssueKind,
+        invoice_id: Uuid,
+        summary: String,
+        detail: String,
+    ) {
+        self.automation_issues.record(CreateAutomationIssue {
+            user_id,
+            kind,
+            invoice_id: Some(invoice_id),
+            summary,
+            detail: Some(detail),
+        }).await;
+    }
+
     /// Mark invoice as viewed (for read receipt tracking)
     pub async fn mark_as_viewed(
         &self,
@@ -355,20 +1323,96 @@ impl InvoiceService {
         Ok(detail)
     }
 
-    /// Send unviewed reminder notifications
+    /// Nudge clients who haven't opened an invoice sent a few days ago.
+    /// Each invoice is nudged at most once. Returns the number of clients reached.
     pub async fn send_unviewed_reminders(&self) -> Result<usize, InvoiceError> {
-        // Get invoices that haven't been viewed within time threshold
-        // This would typically be called by a background job
-        // For now, we'll just return 0 as a placeholder
-        // Real implementation would query for invoices sent > 24 hours ago, not viewed
-        Ok(0)
+        let now = self.clock.now();
+        let claimed = self
+            .invoice_repo
+            .claim_unviewed_reminders(
+                now - chrono::Duration::days(UNVIEWED_REMINDER_WINDOW_DAYS),
+                now - chrono::Duration::days(UNVIEWED_REMINDER_DAYS),
+            )
+            .await?;
+
+        let mut sent = 0;
+        for (user_id, invoice_id) in claimed {
+            match self.send_unviewed_reminder(user_id, invoice_id).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Unviewed reminder for invoice {} failed: {}", invoice_id, e),
+            }
+        }
+        Ok(sent)
+    }
+
+    async fn send_unviewed_reminder(&self, user_id: Uuid, invoice_id: Uuid) -> Result<bool, InvoiceError> {
+        let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        let user = self.user_repo.find_by_id(user_id)
+            .await?
+            .ok_or(InvoiceError::Validation("User not found".to_string()))?;
+        let client = self.client_repo.find_by_id(user_id, detail.client_id)
+            .await?
+            .ok_or(InvoiceError::ClientNotFound)?;
+        let settings = &user.notification_settings;
+        let days_unviewed = detail.sent_at
+            .map(|sent_at| (self.clock.now() - sent_at).num_days())
+            .unwrap_or(UNVIEWED_REMINDER_DAYS);
+
+        let mut delivered = false;
+        if settings.email_payment_reminder {
+            if let Some(email) = client.email.as_deref() {
+                let signature = self.signatures.resolve(user_id, None).await?;
+                let company = user.company_name.clone().unwrap_or_default();
+                let subject = format!("[{}] Invoice {} is waiting for you", company, detail.invoice_number);
+                let payment_link = detail.guest_payment_token.as_ref()
+                    .map(|token| format!(r#"<p><a href="https://yourapp.com/guest/pay/{}">View the invoice</a></p>"#, token))
+                    .unwrap_or_default();
+                let html_body = format!(
+                    r#"
+                    <html>
+                    <body style="font-family: Arial, sans-serif; padding: 20px;">
+                        <h2>{}</h2>
+                        <p>We sent you invoice {} {} day(s) ago and wanted to make sure it reached you.</p>
+                        <p><strong>Amount:</strong> ${:.2}</p>
+                        <p><strong>Due Date:</strong> {}</p>
+                        {}
+                        {}
+                        <hr>
+                        <p style="color: #666;">This is an automated message from {}</p>
+                    </body>
+                    </html>
+                    "#,
+                    subject,
+                    detail.invoice_number,
+                    days_unviewed,
+                    detail.total_amount,
+                    detail.due_date,
+                    payment_link,
+                    signature.as_ref().map(signature_html).unwrap_or_default(),
+                    user.company_name.clone().unwrap_or_else(|| "FlashBill".to_string())
+                );
+                delivered |= self.deliver_reminder_email(user_id, &detail, email, &client.name, &subject, &html_body).await;
+            }
+        }
+        if settings.whatsapp_payment_reminder && self.whatsapp_service.is_enabled() {
+            if let Some(phone) = client.phone.as_deref() {
+                let result = self.whatsapp_service.send_unviewed_reminder(phone, &detail, days_unviewed).await;
+                delivered |= self.check_reminder_whatsapp(user_id, &detail, phone, result).await;
+            }
+        }
+        Ok(delivered)
     }
 
+    /// The invoice PDF. The rendered file is kept through FileService and served
+    /// again while the invoice, seller and client details it shows are unchanged;
+    /// `regenerate` renders a fresh copy regardless.
     pub async fn get_invoice_pdf(
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
-    ) -> Result<Vec<u8>, InvoiceError> {
+        regenerate: bool,
+    ) -> Result<InvoicePdf, InvoiceError> {
         // Get invoice details
         let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
 
@@ -382,14 +1426,124 @@ impl InvoiceService {
             .await?
             .ok_or(InvoiceError::ClientNotFound)?;
 
-        // Generate PDF
-        let items: Vec<InvoiceItemPdf> = detail.items.iter().map(|item| InvoiceItemPdf {
-            description: item.description.clone(),
-            quantity: item.quantity,
-            unit_price: item.unit_price,
-            total: item.total,
-        }).collect();
+        let paid_stamp = user.invoice_settings.as_ref().is_some_and(|settings| settings.paid_stamp);
+        let watermark = PdfWatermark::for_status(&detail.status, paid_stamp);
+        let content = self.pdf_content(&detail, &user, &client, watermark).await?;
+        let fingerprint = content.fingerprint();
+
+        if !regenerate {
+            if let Some(cached) = self.cached_pdf(user_id, invoice_id, &fingerprint).await? {
+                return Ok(InvoicePdf { content: cached, cached: true });
+            }
+        }
+
+        let pdf = self.draw_pdf(&content)?;
+        self.store_pdf(user_id, invoice_id, &detail.invoice_number, &pdf, &fingerprint).await;
+
+        Ok(InvoicePdf { content: pdf, cached: false })
+    }
+
+    /// The stored PDF, if it was rendered from the same content
+    async fn cached_pdf(&self, user_id: Uuid, invoice_id: Uuid, fingerprint: &str) -> Result<Option<Vec<u8>>, InvoiceError> {
+        let Some((pdf_url, stored_fingerprint)) = self.invoice_repo.cached_pdf(user_id, invoice_id).await? else {
+            return Ok(None);
+        };
+        let Some(file_name) = self.files.file_name_from_url(&pdf_url) else {
+            return Ok(None);
+        };
+        if stored_fingerprint != fingerprint {
+            return Ok(None);
+        }
+
+        match self.files.get_file(user_id, file_name).await {
+            Ok(pdf) => Ok(Some(pdf)),
+            Err(e) => {
+                tracing::warn!("Cached PDF for invoice {} is unreadable, regenerating: {}", invoice_id, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Keep a rendered PDF for later requests. Failing to store it only costs a re-render.
+    async fn store_pdf(&self, user_id: Uuid, invoice_id: Uuid, invoice_number: &str, pdf: &[u8], fingerprint: &str) {
+        let stored = match self.files
+            .upload_file(user_id, pdf, &format!("invoice_{}.pdf", invoice_number), "application/pdf")
+            .await
+        {
+            Ok(stored) => stored,
+            Err(e) => {
+                tracing::warn!("Failed to store PDF for invoice {}: {}", invoice_id, e);
+                return;
+            }
+        };
+
+        let pdf_url = self.files.get_file_url(&stored.file_name);
+        match self.invoice_repo.set_cached_pdf(user_id, invoice_id, Some(&pdf_url), Some(fingerprint)).await {
+            Ok(previous) => self.remove_cached_file(user_id, previous.as_deref()).await,
+            Err(e) => {
+                tracing::warn!("Failed to record PDF for invoice {}: {}", invoice_id, e);
+                self.remove_cached_file(user_id, Some(&pdf_url)).await;
+            }
+        }
+    }
+
+    /// Drop the stored PDF so the next request renders the invoice as it is now
+    async fn invalidate_pdf(&self, user_id: Uuid, invoice_id: Uuid) {
+        match self.invoice_repo.set_cached_pdf(user_id, invoice_id, None, None).await {
+            Ok(previous) => self.remove_cached_file(user_id, previous.as_deref()).await,
+            Err(e) => tracing::warn!("Failed to invalidate PDF for invoice {}: {}", invoice_id, e),
+        }
+    }
+
+    /// Delete a file previously stored for `pdf_url`; URLs set elsewhere are left alone
+    async fn remove_cached_file(&self, user_id: Uuid, pdf_url: Option<&str>) {
+        let Some(file_name) = pdf_url.and_then(|url| self.files.file_name_from_url(url)) else {
+            return;
+        };
+        if let Err(e) = self.files.delete_file(user_id, file_name).await {
+            tracing::warn!("Failed to remove cached PDF {}: {}", file_name, e);
+        }
+    }
+
+    /// Guest download links for the invoice's attachments; none without a guest link
+    async fn attachment_links(&self, detail: &InvoiceDetailResponse) -> Result<Vec<AttachmentLink>, InvoiceError> {
+        let Some(token) = detail.guest_payment_token.as_deref() else {
+            return Ok(Vec::new());
+        };
+        self.attachments
+            .invoice_links(detail.id, token)
+            .await
+            .map_err(|e| InvoiceError::DatabaseError(e.to_string()))
+    }
+
+    /// The emailed copy is the issued invoice, so a draft being sent isn't watermarked
+    async fn emailed_pdf(&self, detail: &InvoiceDetailResponse, user: &User, client: &Client) -> Result<Vec<u8>, InvoiceError> {
+        let paid_stamp = user.invoice_settings.as_ref().is_some_and(|settings| settings.paid_stamp);
+        let watermark = match detail.status {
+            InvoiceStatus::Draft => None,
+            ref status => PdfWatermark::for_status(status, paid_stamp),
+        };
+        self.render_pdf(detail, user, client, watermark).await
+    }
 
+    async fn render_pdf(
+        &self,
+        detail: &InvoiceDetailResponse,
+        user: &User,
+        client: &Client,
+        watermark: Option<PdfWatermark>,
+    ) -> Result<Vec<u8>, InvoiceError> {
+        let content = self.pdf_content(detail, user, client, watermark).await?;
+        self.draw_pdf(&content)
+    }
+
+    async fn pdf_content(
+        &self,
+        detail: &InvoiceDetailResponse,
+        user: &User,
+        client: &Client,
+        watermark: Option<PdfWatermark>,
+    ) -> Result<InvoicePdfContent, InvoiceError> {
         let company_address = user.business_address.as_ref().map(|addr| {
             format!("{}, {}, {} {}", addr.street, addr.city, addr.state, addr.zip_code)
         });
@@ -398,33 +1552,94 @@ impl InvoiceService {
             addr.to_string()
         });
 
-        let pdf_bytes = self.pdf_service.generate_invoice_pdf(
+        let status_label = self.invoice_repo.active_label(user.id, detail.id)
+            .await?
+            .filter(|label| label.show_on_pdf)
+            .map(|label| label.name);
+
+        let settings = user.invoice_settings.clone().unwrap_or_default();
+        let logo = match settings.logo_url.as_deref() {
+            Some(url) => self.files.get_file_by_url(user.id, url).await,
+            None => None,
+        };
+
+        let locale = client.locale.unwrap_or(user.locale);
+        let vat_notes = if detail.reverse_charge { reverse_charge_notes(detail, user, locale) } else { Vec::new() };
+        // Drafts get the code too: the emailed copy is drawn before the status changes
+        let payable = !matches!(
+            detail.status,
+            InvoiceStatus::Paid | InvoiceStatus::Cancelled | InvoiceStatus::Superseded | InvoiceStatus::Expired
+        );
+        let payment_link = detail.guest_payment_token.as_ref().map(|token| format!("https://yourapp.com/guest/pay/{}", token));
+        let payment_qr = PaymentQr::for_invoice(
+            settings.payment_qr,
+            settings.sepa_account.as_ref(),
+            settings.qris_payload.as_deref(),
+            &detail.currency,
+            detail.amount_due_now(),
             &detail.invoice_number,
-            user.company_name.as_deref(),
-            company_address.as_deref(),
-            &client.name,
-            client.email.as_deref(),
-            client_address.as_deref(),
-            &detail.issue_date.to_string(),
-            &detail.due_date.to_string(),
-            &items,
-            detail.subtotal,
-            detail.tax_amount,
-            detail.discount_amount,
-            detail.total_amount,
-            detail.notes.as_deref(),
-            detail.terms.as_deref(),
-            detail.tax_label.as_deref(),
-        )?;
+            payment_link.as_deref(),
+        )
+        .filter(|_| payable);
+
+        Ok(InvoicePdfContent {
+            invoice_number: detail.invoice_number.clone(),
+            company_name: user.company_name.clone(),
+            company_address,
+            client_name: client.name.clone(),
+            client_email: client.email.clone(),
+            client_address,
+            issue_date: format_date(locale, detail.issue_date),
+            due_date: format_date(locale, detail.due_date),
+            items: detail.items.iter().map(InvoiceItemPdf::from).collect(),
+            subtotal: detail.subtotal.to_f64().unwrap_or_default(),
+            tax_amount: detail.tax_amount.to_f64().unwrap_or_default(),
+            discount: detail.discount_amount.to_f64().unwrap_or_default(),
+            total: detail.total_amount.to_f64().unwrap_or_default(),
+            notes: detail.notes.clone(),
+            terms: detail.terms.clone(),
+            taxes: pdf_tax_lines(detail),
+            vat_notes,
+            payment_qr,
+            status_label,
+            watermark,
+            branding: PdfBranding { locale, ..PdfBranding::new(&settings, logo) },
+            logo_url: settings.logo_url,
+        })
+    }
 
-        Ok(pdf_bytes)
+    fn draw_pdf(&self, content: &InvoicePdfContent) -> Result<Vec<u8>, InvoiceError> {
+        Ok(self.pdf_service.generate_invoice_pdf(
+            &content.invoice_number,
+            content.company_name.as_deref(),
+            content.company_address.as_deref(),
+            &content.client_name,
+            content.client_email.as_deref(),
+            content.client_address.as_deref(),
+            &content.issue_date,
+            &content.due_date,
+            &content.items,
+            content.subtotal,
+            content.tax_amount,
+            content.discount,
+            content.total,
+            content.notes.as_deref(),
+            content.terms.as_deref(),
+            &content.taxes,
+            &content.vat_notes,
+            content.payment_qr.as_ref(),
+            content.status_label.as_deref(),
+            content.watermark,
+            &content.branding,
+        )?)
     }
 
-    /// Send a payment reminder for an invoice
+    /// Send a payment reminder for an invoice, signed by `sender_id` if they have their own signature
     pub async fn send_reminder(
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
+        sender_id: Option<Uuid>,
     ) -> Result<(), InvoiceError> {
         // Get invoice details
         let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
@@ -440,65 +1655,332 @@ impl InvoiceService {
             .ok_or(InvoiceError::ClientNotFound)?;
 
         // Calculate days overdue
-        let today = chrono::Utc::now().naive_utc().date();
-        let days_overdue = today.signed_duration_since(detail.due_date).num_days();
+        let today = self.clock.today();
+        let days_overdue = today.signed_duration_since(detail.reminder_due_date()).num_days();
 
         if days_overdue < 0 {
             return Err(InvoiceError::Validation("Invoice is not yet due".to_string()));
         }
 
-        // Determine reminder type based on days overdue
-        let (_reminder_type, subject, message) = if days_overdue == 0 {
-            ("friendly", "Friendly Reminder: Invoice Due Today", "Just a friendly reminder that your invoice is due today.")
-        } else if days_overdue <= 7 {
-            ("reminder", "Payment Reminder", "This is a reminder that your invoice is overdue.")
-        } else if days_overdue <= 30 {
-            ("urgent", "Urgent: Payment Overdue", "Your invoice is significantly overdue. Please remit payment immediately.")
-        } else {
-            ("final", "Final Notice", "This is our final notice. Immediate payment is required to avoid further action.")
+        let signature = self.signatures.resolve(user_id, sender_id).await?;
+        let template = self.email_templates.find(user_id, EmailTemplateKind::PaymentReminder).await?;
+        let (subject, html_body) =
+            payment_reminder_email(&detail, &user, days_overdue, signature.as_ref(), template.as_ref());
+
+        let sent = self.send_email_now(
+            &client.email.clone().ok_or(InvoiceError::Validation("Client email required".to_string()))?,
+            &client.name,
+            &subject,
+            &html_body,
+        ).await;
+        let outcome = Outcome::of(&sent, EmailError::is_hard_bounce);
+        self.count(|metrics| metrics.record_reminder_sent("email", outcome));
+        sent.map_err(|e| InvoiceError::EmailError(e.to_string()))?;
+
+        // Update reminder tracking; a manual reminder stands in for the next scheduled one
+        self.invoice_repo
+            .update_reminder_sent_count(user_id, invoice_id, detail.reminder_sent_count + 1)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Spawn the hourly loop that marks past-due invoices overdue, adds late fees
+    /// and reminds clients on the user's reminder schedule
+    pub fn start_reminder_worker(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(REMINDER_CHECK_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.run_reminders().await {
+                    Ok((0, 0, 0, 0)) => {}
+                    Ok((overdue, late_fees, reminded, nudged)) => tracing::info!(
+                        "Marked {} invoice(s) overdue, added {} late fee(s), sent {} payment reminder(s) and {} unviewed reminder(s)",
+                        overdue,
+                        late_fees,
+                        reminded,
+                        nudged
+                    ),
+                    Err(e) => tracing::error!("Invoice reminder run failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Returns the number of invoices marked overdue, late fees added, payment
+    /// reminders sent and unviewed reminders sent
+    pub async fn run_reminders(&self) -> Result<(usize, usize, usize, usize), InvoiceError> {
+        let today = self.clock.today();
+
+        let overdue = self.invoice_repo.mark_overdue(today).await?;
+        for (user_id, invoice_id) in &overdue {
+            tracing::info!(user_id = %user_id, invoice_id = %invoice_id, "Invoice is overdue");
+        }
+        let owners: BTreeSet<Uuid> = overdue.iter().map(|(user_id, _)| *user_id).collect();
+        for user_id in owners {
+            self.reports.invalidate_user(user_id).await;
+        }
+
+        // Fees go on before reminders so the reminder shows the new balance
+        let late_fees = match self.late_fees.apply_late_fees().await {
+            Ok(applied) => applied,
+            Err(e) => {
+                tracing::error!("Late fee run failed: {}", e);
+                0
+            }
+        };
+
+        let reminded = self.send_payment_reminders(today).await?;
+        let nudged = self.send_unviewed_reminders().await?;
+
+        Ok((overdue.len(), late_fees, reminded, nudged))
+    }
+
+    /// Remind clients of invoices that reached the next day on their owner's
+    /// reminder schedule
+    async fn send_payment_reminders(&self, today: chrono::NaiveDate) -> Result<usize, InvoiceError> {
+        let mut sent = 0;
+        for candidate in self.invoice_repo.reminder_candidates(today).await? {
+            let settings = &candidate.settings;
+            if !settings.email_payment_reminder && !settings.whatsapp_payment_reminder {
+                continue;
+            }
+            let days_overdue = (today - candidate.due_date).num_days();
+            let due = settings.reminders_due(days_overdue);
+            if due <= candidate.reminder_sent_count {
+                continue;
+            }
+
+            // Claimed before sending, so an overlapping run never sends it twice
+            let claimed = self.invoice_repo
+                .claim_reminder(candidate.user_id, candidate.invoice_id, candidate.reminder_sent_count, due)
+                .await?;
+            if !claimed {
+                continue;
+            }
+
+            match self.send_scheduled_reminder(&candidate, days_overdue).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Payment reminder for invoice {} failed: {}", candidate.invoice_id, e),
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Email and/or WhatsApp the client, as the user's settings allow. True when
+    /// at least one channel reached them.
+    async fn send_scheduled_reminder(&self, candidate: &ReminderCandidate, days_overdue: i64) -> Result<bool, InvoiceError> {
+        let user_id = candidate.user_id;
+        let detail = self.invoice_repo.get_by_id(user_id, candidate.invoice_id).await?;
+        let user = self.user_repo.find_by_id(user_id)
+            .await?
+            .ok_or(InvoiceError::Validation("User not found".to_string()))?;
+        let client = self.client_repo.find_by_id(user_id, detail.client_id)
+            .await?
+            .ok_or(InvoiceError::ClientNotFound)?;
+
+        let mut delivered = false;
+        if candidate.settings.email_payment_reminder {
+            if let Some(email) = client.email.as_deref() {
+                let signature = self.signatures.resolve(user_id, None).await?;
+                let template = self.email_templates.find(user_id, EmailTemplateKind::PaymentReminder).await?;
+                let (subject, html_body) =
+                    payment_reminder_email(&detail, &user, days_overdue, signature.as_ref(), template.as_ref());
+                delivered |= self.deliver_reminder_email(user_id, &detail, email, &client.name, &subject, &html_body).await;
+            }
+        }
+        if candidate.settings.whatsapp_payment_reminder && self.whatsapp_service.is_enabled() {
+            if let Some(phone) = client.phone.as_deref() {
+                let result = self.whatsapp_service.send_payment_reminder(phone, &detail, days_overdue).await;
+                delivered |= self.check_reminder_whatsapp(user_id, &detail, phone, result).await;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Sends a rendered email right away, bypassing the queue, for callers
+    /// that report whether it went out
+    async fn send_email_now(&self, to_email: &str, to_name: &str, subject: &str, html_body: &str) -> Result<(), EmailError> {
+        self.email_queue
+            .send_immediate(EmailJobType::SendEmail {
+                to_email: to_email.to_string(),
+                to_name: to_name.to_string(),
+                subject: subject.to_string(),
+                html_body: html_body.to_string(),
+            })
+            .await
+    }
+
+    /// Sends an automated reminder email, alerting the seller when it fails
+    async fn deliver_reminder_email(
+        &self,
+        user_id: Uuid,
+        detail: &InvoiceDetailResponse,
+        email: &str,
+        name: &str,
+        subject: &str,
+        html_body: &str,
+    ) -> bool {
+        let sent = self.send_email_now(email, name, subject, html_body).await;
+        let outcome = Outcome::of(&sent, EmailError::is_hard_bounce);
+        self.count(|metrics| metrics.record_reminder_sent("email", outcome));
+        match sent {
+            Ok(()) => true,
+            Err(e) => {
+                self.report_issue(
+                    user_id,
+                    AutomationIssueKind::EmailBounce,
+                    detail.id,
+                    format!("Reminder for invoice {} could not be emailed to {}", detail.invoice_number, email),
+                    e.to_string(),
+                ).await;
+                false
+            }
+        }
+    }
+
+    /// True when an automated WhatsApp reminder went out; alerts the seller otherwise
+    async fn check_reminder_whatsapp(
+        &self,
+        user_id: Uuid,
+        detail: &InvoiceDetailResponse,
+        phone: &str,
+        result: anyhow::Result<WhatsAppResponse>,
+    ) -> bool {
+        let error = match result {
+            Ok(response) if response.success => {
+                self.count(|metrics| metrics.record_reminder_sent("whatsapp", Outcome::Success));
+                return true;
+            }
+            Ok(response) => {
+                self.count(|metrics| metrics.record_reminder_sent("whatsapp", Outcome::Rejected));
+                response.error.unwrap_or_else(|| "Message was not accepted".to_string())
+            }
+            Err(e) => {
+                self.count(|metrics| metrics.record_reminder_sent("whatsapp", Outcome::Failed));
+                e.to_string()
+            }
         };
+        self.report_issue(
+            user_id,
+            AutomationIssueKind::WhatsappFailed,
+            detail.id,
+            format!("Reminder for invoice {} could not be sent via WhatsApp to {}", detail.invoice_number, phone),
+            error,
+        ).await;
+        false
+    }
+
+    /// Spawn the hourly loop that follows up on offers about to expire and
+    /// marks lapsed ones expired
+    pub fn start_expiry_checks(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(EXPIRY_CHECK_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.run_expiry_checks().await {
+                    Ok((0, 0)) => {}
+                    Ok((reminded, expired)) => tracing::info!(
+                        "Sent {} expiry follow-up(s), expired {} invoice(s)",
+                        reminded,
+                        expired
+                    ),
+                    Err(e) => tracing::error!("Invoice expiry check failed: {}", e),
+                }
+            }
+        });
+    }
+
+    /// Returns the number of follow-ups sent and invoices expired
+    pub async fn run_expiry_checks(&self) -> Result<(usize, usize), InvoiceError> {
+        let today = self.clock.today();
 
-        // Send email reminder
-        let subject = format!("[{}] {}", user.company_name.clone().unwrap_or_default(), subject);
+        let due = self
+            .invoice_repo
+            .claim_expiry_reminders(today, today + chrono::Duration::days(EXPIRY_REMINDER_DAYS))
+            .await?;
+        let mut reminded = 0;
+        for (user_id, invoice_id) in due {
+            match self.send_expiry_reminder(user_id, invoice_id).await {
+                Ok(()) => reminded += 1,
+                Err(e) => {
+                    let number = self.invoice_repo.get_by_id(user_id, invoice_id).await
+                        .map(|detail| detail.invoice_number)
+                        .unwrap_or_default();
+                    self.report_issue(
+                        user_id,
+                        AutomationIssueKind::EmailBounce,
+                        invoice_id,
+                        format!("Expiry follow-up for {} could not be emailed", number),
+                        e.to_string(),
+                    ).await;
+                }
+            }
+        }
+
+        let expired = self.invoice_repo.expire_lapsed(today).await?;
+        for (user_id, invoice_id) in &expired {
+            tracing::info!(user_id = %user_id, invoice_id = %invoice_id, "Invoice offer expired");
+        }
+
+        Ok((reminded, expired.len()))
+    }
+
+    /// Remind the client that the offer is only valid until its expiry date
+    async fn send_expiry_reminder(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), InvoiceError> {
+        let detail = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        let expires_at = detail.expires_at
+            .ok_or(InvoiceError::Validation("Invoice has no expiry date".to_string()))?;
+
+        let user = self.user_repo.find_by_id(user_id)
+            .await?
+            .ok_or(InvoiceError::Validation("User not found".to_string()))?;
+        let client = self.client_repo.find_by_id(user_id, detail.client_id)
+            .await?
+            .ok_or(InvoiceError::ClientNotFound)?;
+        let email = client.email.clone()
+            .ok_or(InvoiceError::Validation("Client email required".to_string()))?;
+
+        let signature = self.signatures.resolve(user_id, None).await?;
+        let payment_link = detail.guest_payment_token.as_ref()
+            .map(|token| format!(r#"<p><a href="https://yourapp.com/guest/pay/{}">Review and pay online</a></p>"#, token))
+            .unwrap_or_default();
+
+        let subject = format!(
+            "[{}] Offer {} expires on {}",
+            user.company_name.clone().unwrap_or_default(),
+            detail.invoice_number,
+            expires_at
+        );
         let html_body = format!(
             r#"
             <html>
             <body style="font-family: Arial, sans-serif; padding: 20px;">
                 <h2>{}</h2>
-                <p>{}</p>
+                <p>This is a reminder that our offer is only valid until <strong>{}</strong>. After that date it can no longer be accepted or paid.</p>
                 <p><strong>Invoice #:</strong> {}</p>
-                <p><strong>Amount Due:</strong> ${:.2}</p>
-                <p><strong>Due Date:</strong> {}</p>
-                <p><strong>Days Overdue:</strong> {}</p>
-                <p>Please remit payment at your earliest convenience.</p>
+                <p><strong>Amount:</strong> ${:.2}</p>
+                {}
+                {}
                 <hr>
                 <p style="color: #666;">This is an automated message from {}</p>
             </body>
             </html>
             "#,
             subject,
-            message,
+            expires_at,
             detail.invoice_number,
             detail.total_amount,
-            detail.due_date,
-            days_overdue,
+            payment_link,
+            signature.as_ref().map(signature_html).unwrap_or_default(),
             user.company_name.clone().unwrap_or_else(|| "FlashBill".to_string())
         );
 
-        self.email_service
-            .send_email(
-                &client.email.clone().ok_or(InvoiceError::Validation("Client email required".to_string()))?,
-                &client.name,
-                &subject,
-                &html_body,
-            )
+        self.send_email_now(&email, &client.name, &subject, &html_body)
+            .await
             .map_err(|e| InvoiceError::EmailError(e.to_string()))?;
 
-        // Update reminder tracking
-        self.invoice_repo
-            .update_reminder_sent_count(user_id, invoice_id, detail.reminder_sent_count + 1)
-            .await?;
-
         Ok(())
     }
 
@@ -517,6 +1999,7 @@ impl InvoiceService {
         let discussion = self.invoice_repo
             .add_discussion_message(invoice_id, sender_type, message)
             .await?;
+        self.after_discussion_message(&discussion).await;
 
         Ok(discussion)
     }
@@ -530,6 +2013,7 @@ impl InvoiceService {
         let discussion = self.invoice_repo
             .add_discussion_message_guest(invoice_id, message)
             .await?;
+        self.after_discussion_message(&discussion).await;
 
         Ok(discussion)
     }
@@ -561,4 +2045,261 @@ impl InvoiceService {
 
         Ok(messages)
     }
+
+    /// The client's messages on an invoice the seller hasn't read
+    pub async fn discussion_unread(&self, user_id: Uuid, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        let _invoice = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        Ok(self.invoice_repo.discussion_unread(invoice_id, SenderType::Seller).await?)
+    }
+
+    /// The seller's messages the client hasn't read (guest access)
+    pub async fn discussion_unread_guest(&self, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        let _invoice = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        Ok(self.invoice_repo.discussion_unread(invoice_id, SenderType::Buyer).await?)
+    }
+
+    /// Mark the discussion read by the seller, up to now
+    pub async fn mark_discussion_read(&self, user_id: Uuid, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        let _invoice = self.invoice_repo.get_by_id(user_id, invoice_id).await?;
+        self.invoice_repo.mark_discussion_read(invoice_id, SenderType::Seller, self.clock.now()).await?;
+        Ok(self.invoice_repo.discussion_unread(invoice_id, SenderType::Seller).await?)
+    }
+
+    /// Mark the discussion read by the client, up to now (guest access)
+    pub async fn mark_discussion_read_guest(&self, invoice_id: Uuid) -> Result<DiscussionUnread, InvoiceError> {
+        let _invoice = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        self.invoice_repo.mark_discussion_read(invoice_id, SenderType::Buyer, self.clock.now()).await?;
+        Ok(self.invoice_repo.discussion_unread(invoice_id, SenderType::Buyer).await?)
+    }
+
+    /// The seller's invoices with client messages they haven't read
+    pub async fn unread_discussions(&self, user_id: Uuid) -> Result<Vec<UnreadDiscussion>, InvoiceError> {
+        Ok(self.invoice_repo.unread_discussions(user_id).await?)
+    }
+
+    /// The sender has read everything up to their own message; the other side
+    /// is told about it. Neither fails the message, which is already saved.
+    async fn after_discussion_message(&self, discussion: &InvoiceDiscussion) {
+        if let Err(e) = self.invoice_repo
+            .mark_discussion_read(discussion.invoice_id, discussion.sender_type.clone(), discussion.created_at)
+            .await
+        {
+            tracing::warn!(invoice_id = %discussion.invoice_id, "Failed to update discussion read state: {}", e);
+        }
+        if let Err(e) = self.notify_discussion_message(discussion).await {
+            tracing::warn!(invoice_id = %discussion.invoice_id, "Discussion message notification failed: {}", e);
+        }
+    }
+
+    /// Email and/or WhatsApp the other side about a new message, as the seller's
+    /// settings allow. Messages within the throttle window of the last
+    /// notification to that side only count as unread.
+    async fn notify_discussion_message(&self, discussion: &InvoiceDiscussion) -> Result<(), InvoiceError> {
+        let detail = self.invoice_repo.get_invoice_by_id(discussion.invoice_id).await?;
+        let user = self.user_repo.find_by_id(detail.user_id)
+            .await?
+            .ok_or(InvoiceError::Validation("User not found".to_string()))?;
+        let settings = &user.notification_settings;
+        let whatsapp = settings.whatsapp_discussion_messages && self.whatsapp_service.is_enabled();
+        if !settings.email_discussion_messages && !whatsapp {
+            return Ok(());
+        }
+
+        let seller_name = user.company_name.clone().unwrap_or_else(|| user.email.clone());
+        let recipient = match discussion.sender_type.other() {
+            SenderType::Buyer => DiscussionRecipient {
+                email: detail.client_email.clone(),
+                name: detail.client_name.clone(),
+                phone: detail.client_phone.clone(),
+                from: seller_name,
+                link: detail.guest_payment_token.as_ref().map(|token| format!("https://yourapp.com/guest/pay/{}", token)),
+                locale: detail.client_locale.unwrap_or(user.locale),
+            },
+            SenderType::Seller => DiscussionRecipient {
+                email: Some(user.email.clone()),
+                name: seller_name,
+                phone: user.phone.clone(),
+                from: detail.client_name.clone(),
+                link: Some(format!("https://app.flashbill.com/invoices/{}", detail.id)),
+                locale: user.locale,
+            },
+        };
+        if recipient.email.is_none() && (!whatsapp || recipient.phone.is_none()) {
+            return Ok(());
+        }
+
+        let now = self.clock.now();
+        let throttle_since = now - chrono::Duration::minutes(DISCUSSION_NOTIFY_THROTTLE_MINUTES);
+        let claimed = self.invoice_repo
+            .claim_discussion_notification(discussion.invoice_id, discussion.sender_type.other(), now, throttle_since)
+            .await?;
+        if !claimed {
+            return Ok(());
+        }
+
+        let excerpt = discussion_excerpt(&discussion.message);
+        if settings.email_discussion_messages {
+            if let Some(email) = recipient.email.as_deref() {
+                let (subject, html_body) = discussion_message_email(&detail.invoice_number, &recipient, &excerpt);
+                self.email_queue
+                    .enqueue_or_log(EmailJobType::SendEmail {
+                        to_email: email.to_string(),
+                        to_name: recipient.name.clone(),
+                        subject,
+                        html_body,
+                    })
+                    .await;
+            }
+        }
+        if whatsapp {
+            if let Some(phone) = recipient.phone.clone() {
+                // Sent in the background so posting a message never waits on the provider
+                let whatsapp_service = self.whatsapp_service.clone();
+                let invoice_number = detail.invoice_number.clone();
+                tokio::spawn(async move {
+                    let result = whatsapp_service
+                        .send_discussion_message(&phone, &invoice_number, &recipient.from, &excerpt, recipient.link.as_deref())
+                        .await;
+                    match result {
+                        Ok(response) if response.success => {}
+                        Ok(response) => tracing::warn!("Discussion WhatsApp not delivered: {}", response.error.unwrap_or_default()),
+                        Err(e) => tracing::warn!("Discussion WhatsApp failed: {}", e),
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Who a discussion notification goes to, and how it reads for them
+struct DiscussionRecipient {
+    email: Option<String>,
+    name: String,
+    phone: Option<String>,
+    /// The other side, who wrote the message
+    from: String,
+    link: Option<String>,
+    locale: Locale,
+}
+
+/// Subject and HTML body telling one side of an invoice discussion about a new
+/// message, in the recipient's locale
+fn discussion_message_email(invoice_number: &str, recipient: &DiscussionRecipient, excerpt: &str) -> (String, String) {
+    let locale = recipient.locale;
+    let subject = translate_with(locale, "New message about invoice #{}", &[invoice_number]);
+    let button = recipient
+        .link
+        .as_ref()
+        .map(|link| {
+            format!(
+                r#"<p><a href="{}" style="background-color: #4361EE; color: white; padding: 10px 20px; text-decoration: none; border-radius: 5px;">{}</a></p>"#,
+                escape_html(link),
+                translate(locale, "View Conversation")
+            )
+        })
+        .unwrap_or_default();
+
+    let html_body = format!(
+        r#"
+            <html>
+            <body style="font-family: Arial, sans-serif; padding: 20px;">
+                <h2>{}</h2>
+                <p>{}</p>
+                <blockquote style="border-left: 3px solid #ddd; margin: 0; padding-left: 12px; white-space: pre-wrap;">{}</blockquote>
+                {}
+                <hr>
+                <p style="font-size: 12px; color: #666;">{}</p>
+            </body>
+            </html>
+            "#,
+        escape_html(&subject),
+        escape_html(&translate_with(locale, "{} wrote:", &[&recipient.from])),
+        escape_html(excerpt),
+        button,
+        translate(locale, "FlashBill Team")
+    );
+    (subject, html_body)
+}
+
+/// The tax rows of an invoice PDF: one per tax when the lines carry several
+/// taxes or rates, otherwise the invoice's own tax label
+fn pdf_tax_lines(detail: &InvoiceDetailResponse) -> Vec<PdfTaxLine> {
+    let breakdown = TaxLine::breakdown(&detail.items, detail.tax_label.as_deref());
+    if breakdown.len() <= 1 && detail.items.iter().all(|item| item.taxes.is_empty()) {
+        return detail
+            .tax_label
+            .iter()
+            .filter(|_| detail.tax_amount > Decimal::ZERO)
+            .map(|label| PdfTaxLine { label: label.clone(), amount: detail.tax_amount.to_f64().unwrap_or_default() })
+            .collect();
+    }
+    breakdown
+        .into_iter()
+        .map(|tax| PdfTaxLine {
+            label: format!("{} {}%", tax.label, (tax.rate * Decimal::ONE_HUNDRED).normalize()),
+            amount: tax.tax_amount.to_f64().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// What a reverse-charged invoice must state: both parties' VAT numbers and
+/// that the customer accounts for the VAT
+fn reverse_charge_notes(detail: &InvoiceDetailResponse, user: &User, locale: Locale) -> Vec<String> {
+    let seller_vat_number = user.tax_settings.as_ref().and_then(|tax| tax.tax_id.as_deref());
+    seller_vat_number
+        .map(|vat_number| translate_with(locale, "Supplier VAT number: {}", &[vat_number]))
+        .into_iter()
+        .chain(
+            detail
+                .client_vat_number
+                .as_deref()
+                .map(|vat_number| translate_with(locale, "Customer VAT number: {}", &[vat_number])),
+        )
+        .chain(std::iter::once(translate(locale, REVERSE_CHARGE_NOTE).into_owned()))
+        .collect()
+}
+
+/// Subject and HTML body of a payment reminder, from the user's template if
+/// they have one; the tone sharpens the longer the invoice is overdue. While a
+/// deposit is outstanding the reminder asks for the deposit instead.
+fn payment_reminder_email(
+    detail: &InvoiceDetailResponse,
+    user: &User,
+    days_overdue: i64,
+    signature: Option<&EmailSignature>,
+    template: Option<&EmailTemplate>,
+) -> (String, String) {
+    let locale = detail.client_locale.unwrap_or(user.locale);
+    let deposit_due = detail.deposit.as_ref().is_some_and(InvoiceDeposit::is_due);
+    let (headline, message) = if deposit_due && days_overdue == 0 {
+        ("Deposit Due Today", "Just a friendly reminder that the deposit for your invoice is due today.")
+    } else if deposit_due {
+        ("Deposit Reminder", "This is a reminder that the deposit for your invoice is overdue. Work begins once it's paid.")
+    } else if days_overdue == 0 {
+        ("Friendly Reminder: Invoice Due Today", "Just a friendly reminder that your invoice is due today.")
+    } else if days_overdue <= 7 {
+        ("Payment Reminder", "This is a reminder that your invoice is overdue.")
+    } else if days_overdue <= 30 {
+        ("Urgent: Payment Overdue", "Your invoice is significantly overdue. Please remit payment immediately.")
+    } else {
+        ("Final Notice", "This is our final notice. Immediate payment is required to avoid further action.")
+    };
+    let amount_due = if deposit_due { detail.amount_due_now() } else { detail.total_amount };
+
+    let data = serde_json::json!({
+        "business_name": user.company_name,
+        "client_name": detail.client_name,
+        "invoice_number": detail.invoice_number,
+        "amount_due": format_money(locale, amount_due.to_f64().unwrap_or_default(), &detail.currency),
+        "due_date": format_date(locale, detail.reminder_due_date()),
+        "days_overdue": days_overdue,
+        "headline": translate(locale, headline),
+        "message": translate(locale, message),
+        "payment_url": detail.guest_payment_token.as_ref().map(|token| format!("https://yourapp.com/guest/pay/{}", token)),
+        "signature": signature.map(signature_html).unwrap_or_default(),
+        "locale": locale.code(),
+    });
+    let email = EmailTemplates::global().render(EmailTemplateKind::PaymentReminder, &data, template);
+    (email.subject, email.html_body)
 }
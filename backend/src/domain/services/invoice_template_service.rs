@@ -0,0 +1,143 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    normalize_template_items, normalize_template_name, normalize_template_terms, normalize_template_text,
+    CreateInvoiceTemplate, InvoiceTemplate, UpdateInvoiceTemplate,
+};
+use crate::domain::services::{InvoiceError, SharedClock};
+use crate::infrastructure::repositories::InvoiceTemplateRepository;
+
+#[derive(Debug, Error)]
+pub enum InvoiceTemplateError {
+    #[error("Invoice template not found")]
+    NotFound,
+
+    #[error("A template named {0} already exists")]
+    AlreadyExists(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invoice error: {0}")]
+    Invoice(#[from] InvoiceError),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for InvoiceTemplateError {
+    fn from(err: sqlx::Error) -> Self {
+        InvoiceTemplateError::DatabaseError(err.to_string())
+    }
+}
+
+/// Saved invoice templates
+pub struct InvoiceTemplateService {
+    repo: InvoiceTemplateRepository,
+    clock: SharedClock,
+}
+
+impl InvoiceTemplateService {
+    pub fn new(repo: InvoiceTemplateRepository, clock: SharedClock) -> Self {
+        Self { repo, clock }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.clock.today()
+    }
+
+    pub async fn list_templates(&self, user_id: Uuid) -> Result<Vec<InvoiceTemplate>, InvoiceTemplateError> {
+        Ok(self.repo.list(user_id).await?)
+    }
+
+    pub async fn get_template(&self, user_id: Uuid, template_id: Uuid) -> Result<InvoiceTemplate, InvoiceTemplateError> {
+        self.repo.find_by_id(user_id, template_id).await?.ok_or(InvoiceTemplateError::NotFound)
+    }
+
+    pub async fn create_template(
+        &self,
+        user_id: Uuid,
+        create: CreateInvoiceTemplate,
+    ) -> Result<InvoiceTemplate, InvoiceTemplateError> {
+        let name = normalize_template_name(&create.name).map_err(InvoiceTemplateError::Validation)?;
+        let items = normalize_template_items(create.items).map_err(InvoiceTemplateError::Validation)?;
+        let (currency, due_days) = normalize_template_terms(create.discount_amount, create.currency, create.due_days)
+            .map_err(InvoiceTemplateError::Validation)?;
+
+        let now = self.clock.now();
+        let template = InvoiceTemplate {
+            id: Uuid::new_v4(),
+            name,
+            items,
+            notes: normalize_template_text(create.notes),
+            terms: normalize_template_text(create.terms),
+            discount_amount: create.discount_amount,
+            tax_included: create.tax_included,
+            currency,
+            due_days,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(user_id, &template).await.map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => InvoiceTemplateError::AlreadyExists(template.name.clone()),
+            other => other.into(),
+        })
+    }
+
+    pub async fn update_template(
+        &self,
+        user_id: Uuid,
+        template_id: Uuid,
+        update: UpdateInvoiceTemplate,
+    ) -> Result<InvoiceTemplate, InvoiceTemplateError> {
+        let mut template = self.get_template(user_id, template_id).await?;
+
+        if let Some(name) = update.name {
+            template.name = normalize_template_name(&name).map_err(InvoiceTemplateError::Validation)?;
+        }
+        if let Some(items) = update.items {
+            template.items = normalize_template_items(items).map_err(InvoiceTemplateError::Validation)?;
+        }
+        if update.notes.is_some() {
+            template.notes = normalize_template_text(update.notes);
+        }
+        if update.terms.is_some() {
+            template.terms = normalize_template_text(update.terms);
+        }
+        if let Some(tax_included) = update.tax_included {
+            template.tax_included = tax_included;
+        }
+        let (currency, due_days) = normalize_template_terms(update.discount_amount, update.currency, update.due_days)
+            .map_err(InvoiceTemplateError::Validation)?;
+        if update.discount_amount.is_some() {
+            template.discount_amount = update.discount_amount;
+        }
+        if currency.is_some() {
+            template.currency = currency;
+        }
+        if due_days.is_some() {
+            template.due_days = due_days;
+        }
+
+        template.updated_at = self.clock.now();
+        let name = template.name.clone();
+        self.repo
+            .update(user_id, &template)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => InvoiceTemplateError::AlreadyExists(name),
+                other => other.into(),
+            })?
+            .ok_or(InvoiceTemplateError::NotFound)
+    }
+
+    pub async fn delete_template(&self, user_id: Uuid, template_id: Uuid) -> Result<(), InvoiceTemplateError> {
+        if self.repo.delete(user_id, template_id).await? {
+            Ok(())
+        } else {
+            Err(InvoiceTemplateError::NotFound)
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    AppliedLateFee, AuditAction, AuditEntityType, CreateAuditLog, InvoiceItem, LateFeeCandidate, LateFeePolicy,
+    UpdateLateFeePolicy,
+};
+use crate::domain::request_id;
+use crate::domain::services::{ReportCache, SharedClock};
+use crate::infrastructure::repositories::{LateFeeRepository, NewLateFee};
+
+#[derive(Debug, Error)]
+pub enum LateFeeError {
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for LateFeeError {
+    fn from(err: sqlx::Error) -> Self {
+        LateFeeError::DatabaseError(err.to_string())
+    }
+}
+
+/// Late fees on unpaid invoices, by each user's policy. The overdue job calls
+/// `apply_late_fees`; each fee becomes a line item on the invoice and an
+/// audit log entry saying when and why it was added.
+pub struct LateFeeService {
+    repo: LateFeeRepository,
+    clock: SharedClock,
+    /// Fees change invoice totals, so the owner's cached reports go stale
+    reports: ReportCache,
+}
+
+impl LateFeeService {
+    pub fn new(repo: LateFeeRepository, clock: SharedClock) -> Self {
+        Self { repo, clock, reports: ReportCache::default() }
+    }
+
+    /// Invalidate the owner's cached reports when a fee is added
+    pub fn with_report_cache(mut self, reports: ReportCache) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    pub async fn get_policy(&self, user_id: Uuid) -> Result<Option<LateFeePolicy>, LateFeeError> {
+        Ok(self.repo.get_policy(user_id).await?)
+    }
+
+    pub async fn update_policy(&self, user_id: Uuid, update: UpdateLateFeePolicy) -> Result<LateFeePolicy, LateFeeError> {
+        update.validate().map_err(LateFeeError::Validation)?;
+        Ok(self.repo.upsert_policy(user_id, &update).await?)
+    }
+
+    pub async fn list_for_invoice(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Vec<AppliedLateFee>, LateFeeError> {
+        if !self.repo.invoice_exists(user_id, invoice_id).await? {
+            return Err(LateFeeError::InvoiceNotFound);
+        }
+        Ok(self.repo.list_for_invoice(user_id, invoice_id).await?)
+    }
+
+    /// Add the fees due today. Returns how many were added.
+    pub async fn apply_late_fees(&self) -> Result<usize, LateFeeError> {
+        let today = self.clock.today();
+        let mut applied = 0;
+
+        for candidate in self.repo.candidates(today).await? {
+            let policy = &candidate.policy;
+            if !policy.fee_due(candidate.due_date, today, candidate.applied, candidate.last_applied_on) {
+                continue;
+            }
+
+            match self.apply(&candidate, (today - candidate.due_date).num_days()).await {
+                Ok(true) => applied += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Late fee for invoice {} failed: {}", candidate.invoice_id, e),
+            }
+        }
+        Ok(applied)
+    }
+
+    async fn apply(&self, candidate: &LateFeeCandidate, days_overdue: i64) -> Result<bool, LateFeeError> {
+        let policy = &candidate.policy;
+        let amount = policy.fee_amount(candidate.balance);
+        if amount <= Decimal::ZERO {
+            return Ok(false);
+        }
+
+        let item = InvoiceItem {
+            id: Uuid::new_v4(),
+            description: policy.describe(candidate.balance, days_overdue),
+            quantity: Decimal::ONE,
+            unit_price: amount,
+            tax_rate: Decimal::ZERO,
+            tax_amount: Decimal::ZERO,
+            total: amount,
+            section: None,
+            taxes: Vec::new(),
+        };
+        let sequence = candidate.applied + 1;
+        let audit = CreateAuditLog {
+            user_id: Some(candidate.user_id),
+            actor_id: None,
+            action: AuditAction::LateFee,
+            entity_type: AuditEntityType::Invoice,
+            entity_id: Some(candidate.invoice_id),
+            changes: Some(serde_json::json!({
+                "reason": item.description,
+                "invoice_number": candidate.invoice_number,
+                "item_id": item.id,
+                "amount": amount,
+                "sequence": sequence,
+                "days_overdue": days_overdue,
+                "balance_before": candidate.balance,
+                "policy": {
+                    "fee_type": policy.fee_type,
+                    "amount": policy.amount,
+                    "grace_days": policy.grace_days,
+                    "recurrence": policy.recurrence,
+                    "max_applications": policy.max_applications,
+                },
+            })),
+            ip_address: None,
+            user_agent: None,
+            request_id: request_id::current(),
+        };
+
+        let fee = self.repo.apply(NewLateFee {
+            user_id: candidate.user_id,
+            invoice_id: candidate.invoice_id,
+            sequence,
+            item: &item,
+            days_overdue: days_overdue as i32,
+            applied_at: self.clock.now(),
+            audit: &audit,
+        })
+        .await?;
+
+        if let Some(fee) = &fee {
+            self.reports.invalidate_user(candidate.user_id).await;
+            tracing::info!(
+                user_id = %candidate.user_id,
+                invoice_id = %candidate.invoice_id,
+                amount = %fee.amount,
+                "Late fee #{} added to invoice {}",
+                fee.sequence,
+                candidate.invoice_number
+            );
+        }
+        Ok(fee.is_some())
+    }
+}
@@ -1,163 +1,197 @@
-#![allow(dead_code)]
-
 use prometheus::{
-    register_int_counter, register_int_gauge, register_histogram,
-    IntCounter, IntGauge, Histogram, Encoder, TextEncoder, Registry
+    core::Collector, Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
 };
 use std::sync::Arc;
+use std::time::Duration;
 
-/// Service for collecting and exposing Prometheus metrics
-#[derive(Clone)]
-pub struct MetricsService {
-    registry: Arc<Registry>,
-
-    // Request metrics
-    pub http_requests_total: IntCounter,
-    pub http_requests_duration: Histogram,
-    pub http_requests_active: IntGauge,
-
-    // Business metrics
-    pub invoices_created_total: IntCounter,
-    pub invoices_paid_total: IntCounter,
-    pub invoices_overdue_total: IntGauge,
-    pub expenses_total: IntCounter,
-    pub payments_processed_total: IntCounter,
-
-    // Cache metrics
-    pub cache_hits_total: IntCounter,
-    pub cache_misses_total: IntCounter,
-
-    // Error metrics
-    pub errors_total: IntCounter,
+use crate::domain::services::EmailQueueService;
+
+/// How long a scrape waits on Redis for the email queue depth
+const QUEUE_DEPTH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Route label for requests that matched no route, so unknown paths can't add series
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Outcome label on business counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    /// Refused because of the request: validation, not found, wrong status
+    Rejected,
+    /// Failed on our side: database, mail server, gateway
+    Failed,
 }
 
-impl MetricsService {
-    pub fn new() -> Result<Self, prometheus::Error> {
-        let registry = Arc::new(Registry::new());
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Success => "success",
+            Outcome::Rejected => "rejected",
+            Outcome::Failed => "failed",
+        }
+    }
 
-        // HTTP request metrics
-        let http_requests_total = register_int_counter!(
-            "http_requests_total",
-            "Total number of HTTP requests"
-        )?;
+    /// `Success`, or `Rejected`/`Failed` as `is_rejection` says of the error
+    pub fn of<T, E>(result: &Result<T, E>, is_rejection: impl FnOnce(&E) -> bool) -> Self {
+        match result {
+            Ok(_) => Outcome::Success,
+            Err(e) if is_rejection(e) => Outcome::Rejected,
+            Err(_) => Outcome::Failed,
+        }
+    }
+}
 
-        let http_requests_duration = register_histogram!(
-            "http_request_duration_seconds",
-            "HTTP request duration in seconds",
-            vec![0.001, 0.01, 0.1, 0.5, 1.0, 2.0, 5.0]
-        )?;
+/// Collects Prometheus metrics in its own registry and renders them for scraping
+pub struct MetricsService {
+    registry: Registry,
+
+    // HTTP, labeled by method, matched route and status
+    http_requests_total: IntCounterVec,
+    http_request_duration: HistogramVec,
+    http_requests_active: IntGauge,
+
+    // Read from the pool and the queue at scrape time
+    db_pool_connections: IntGaugeVec,
+    db_pool_max_connections: IntGauge,
+    email_queue_depth: IntGaugeVec,
+    db_pool: Option<sqlx::PgPool>,
+    email_queue: Option<Arc<EmailQueueService>>,
+
+    // Business events, labeled by outcome
+    invoices_created_total: IntCounterVec,
+    payments_recorded_total: IntCounterVec,
+    reminders_sent_total: IntCounterVec,
+}
+
+fn register<C: Collector + Clone + 'static>(registry: &Registry, collector: C) -> Result<C, prometheus::Error> {
+    registry.register(Box::new(collector.clone()))?;
+    Ok(collector)
+}
 
-        let http_requests_active = register_int_gauge!(
+impl MetricsService {
+    pub fn new() -> Result<Self, prometheus::Error> {
+        let registry = Registry::new();
+
+        let http_requests_total = register(&registry, IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by method, route and status"),
+            &["method", "route", "status"],
+        )?)?;
+        let http_request_duration = register(&registry, HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by method, route and status")
+                .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+            &["method", "route", "status"],
+        )?)?;
+        let http_requests_active = register(&registry, IntGauge::new(
             "http_requests_active",
-            "Number of active HTTP requests"
-        )?;
-
-        // Business metrics
-        let invoices_created_total = register_int_counter!(
-            "invoices_created_total",
-            "Total number of invoices created"
-        )?;
-
-        let invoices_paid_total = register_int_counter!(
-            "invoices_paid_total",
-            "Total number of invoices paid"
-        )?;
-
-        let invoices_overdue_total = register_int_gauge!(
-            "invoices_overdue_total",
-            "Number of currently overdue invoices"
-        )?;
-
-        let expenses_total = register_int_counter!(
-            "expenses_total",
-            "Total number of expenses recorded"
-        )?;
-
-        let payments_processed_total = register_int_counter!(
-            "payments_processed_total",
-            "Total number of payments processed"
-        )?;
-
-        // Cache metrics
-        let cache_hits_total = register_int_counter!(
-            "cache_hits_total",
-            "Total number of cache hits"
-        )?;
-
-        let cache_misses_total = register_int_counter!(
-            "cache_misses_total",
-            "Total number of cache misses"
-        )?;
-
-        // Error metrics
-        let errors_total = register_int_counter!(
-            "errors_total",
-            "Total number of errors"
-        )?;
+            "HTTP requests being handled",
+        )?)?;
+
+        let db_pool_connections = register(&registry, IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state (idle, in_use)"),
+            &["state"],
+        )?)?;
+        let db_pool_max_connections = register(&registry, IntGauge::new(
+            "db_pool_max_connections",
+            "Most connections the database pool opens",
+        )?)?;
+        let email_queue_depth = register(&registry, IntGaugeVec::new(
+            Opts::new("email_queue_depth", "Email jobs by queue (pending, processing, failed)"),
+            &["queue"],
+        )?)?;
+
+        let invoices_created_total = register(&registry, IntCounterVec::new(
+            Opts::new("invoices_created_total", "Invoice creations through the API or CSV import, by outcome"),
+            &["outcome"],
+        )?)?;
+        let payments_recorded_total = register(&registry, IntCounterVec::new(
+            Opts::new("payments_recorded_total", "Payments recorded against invoices, by outcome"),
+            &["outcome"],
+        )?)?;
+        let reminders_sent_total = register(&registry, IntCounterVec::new(
+            Opts::new("reminders_sent_total", "Payment reminders by channel (email, whatsapp) and outcome"),
+            &["channel", "outcome"],
+        )?)?;
 
         Ok(Self {
             registry,
             http_requests_total,
-            http_requests_duration,
+            http_request_duration,
             http_requests_active,
+            db_pool_connections,
+            db_pool_max_connections,
+            email_queue_depth,
+            db_pool: None,
+            email_queue: None,
             invoices_created_total,
-            invoices_paid_total,
-            invoices_overdue_total,
-            expenses_total,
-            payments_processed_total,
-            cache_hits_total,
-            cache_misses_total,
-            errors_total,
+            payments_recorded_total,
+            reminders_sent_total,
         })
     }
 
-    /// Get metrics in Prometheus text format
-    pub fn get_metrics(&self) -> Result<String, prometheus::Error> {
-        let encoder = TextEncoder::new();
-        let metric_families = self.registry.gather();
-        let mut buffer = Vec::new();
-        encoder.encode(&metric_families, &mut buffer)?;
-        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    /// Report the pool's connections on each scrape
+    pub fn with_db_pool(mut self, db_pool: sqlx::PgPool) -> Self {
+        self.db_pool = Some(db_pool);
+        self
     }
 
-    /// Record a cache hit
-    pub fn record_cache_hit(&self) {
-        self.cache_hits_total.inc();
+    /// Report the email queue's depth on each scrape
+    pub fn with_email_queue(mut self, email_queue: Arc<EmailQueueService>) -> Self {
+        self.email_queue = Some(email_queue);
+        self
     }
 
-    /// Record a cache miss
-    pub fn record_cache_miss(&self) {
-        self.cache_misses_total.inc();
-    }
+    /// Metrics in the Prometheus text format, with the pool and queue gauges refreshed
+    pub async fn render(&self) -> Result<String, prometheus::Error> {
+        if let Some(pool) = &self.db_pool {
+            let size = pool.size() as i64;
+            let idle = pool.num_idle() as i64;
+            self.db_pool_connections.with_label_values(&["idle"]).set(idle);
+            self.db_pool_connections.with_label_values(&["in_use"]).set((size - idle).max(0));
+            self.db_pool_max_connections.set(pool.options().get_max_connections() as i64);
+        }
+        if let Some(queue) = &self.email_queue {
+            // Left at the last known depth if Redis doesn't answer
+            match tokio::time::timeout(QUEUE_DEPTH_TIMEOUT, queue.get_stats()).await {
+                Ok(Ok(stats)) => {
+                    self.email_queue_depth.with_label_values(&["pending"]).set(stats.pending);
+                    self.email_queue_depth.with_label_values(&["processing"]).set(stats.processing);
+                    self.email_queue_depth.with_label_values(&["failed"]).set(stats.failed);
+                }
+                Ok(Err(e)) => tracing::debug!("Email queue depth unavailable: {}", e),
+                Err(_) => tracing::debug!("Email queue depth timed out"),
+            }
+        }
 
-    /// Record an invoice creation
-    pub fn record_invoice_created(&self) {
-        self.invoices_created_total.inc();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
     }
 
-    /// Record an invoice payment
-    pub fn record_invoice_paid(&self) {
-        self.invoices_paid_total.inc();
+    /// Count a request as in flight until the returned guard is dropped
+    pub fn start_request(&self) -> ActiveRequest {
+        self.http_requests_active.inc();
+        ActiveRequest(self.http_requests_active.clone())
     }
 
-    /// Update overdue invoice count
-    pub fn set_overdue_invoices(&self, count: i64) {
-        self.invoices_overdue_total.set(count);
+    /// `route` is the matched route pattern, e.g. `/api/v1/invoices/{id}`
+    pub fn observe_http_request(&self, method: &str, route: Option<&str>, status: u16, elapsed: Duration) {
+        let status = status.to_string();
+        let labels = [method, route.unwrap_or(UNMATCHED_ROUTE), status.as_str()];
+        self.http_requests_total.with_label_values(&labels).inc();
+        self.http_request_duration.with_label_values(&labels).observe(elapsed.as_secs_f64());
     }
 
-    /// Record an expense
-    pub fn record_expense(&self) {
-        self.expenses_total.inc();
+    pub fn record_invoice_created(&self, outcome: Outcome) {
+        self.invoices_created_total.with_label_values(&[outcome.as_str()]).inc();
     }
 
-    /// Record a payment
-    pub fn record_payment(&self) {
-        self.payments_processed_total.inc();
+    pub fn record_payment_recorded(&self, outcome: Outcome) {
+        self.payments_recorded_total.with_label_values(&[outcome.as_str()]).inc();
     }
 
-    /// Record an error
-    pub fn record_error(&self) {
-        self.errors_total.inc();
+    pub fn record_reminder_sent(&self, channel: &str, outcome: Outcome) {
+        self.reminders_sent_total.with_label_values(&[channel, outcome.as_str()]).inc();
     }
 }
 
@@ -166,3 +200,41 @@ impl Default for MetricsService {
         Self::new().expect("Failed to initialize metrics service")
     }
 }
+
+/// Keeps a request counted in `http_requests_active`, even if it is cancelled
+pub struct ActiveRequest(IntGauge);
+
+impl Drop for ActiveRequest {
+    fn drop(&mut self) {
+        self.0.dec();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_counters_are_labeled_and_services_do_not_share_a_registry() {
+        let metrics = MetricsService::new().unwrap();
+        metrics.record_invoice_created(Outcome::Success);
+        metrics.record_invoice_created(Outcome::of(&Err::<(), _>("bad"), |_| true));
+        metrics.record_reminder_sent("email", Outcome::Failed);
+        metrics.observe_http_request("GET", None, 404, Duration::from_millis(3));
+        {
+            let _active = metrics.start_request();
+            assert!(metrics.render().await.unwrap().contains("http_requests_active 1"));
+        }
+
+        let text = metrics.render().await.unwrap();
+        assert!(text.contains(r#"invoices_created_total{outcome="success"} 1"#));
+        assert!(text.contains(r#"invoices_created_total{outcome="rejected"} 1"#));
+        assert!(text.contains(r#"reminders_sent_total{channel="email",outcome="failed"} 1"#));
+        assert!(text.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#));
+        assert!(text.contains("http_requests_active 0"));
+
+        // A second instance starts from zero instead of failing to register
+        let other = MetricsService::new().unwrap().render().await.unwrap();
+        assert!(!other.contains("invoices_created_total{"));
+    }
+}
@@ -8,6 +8,7 @@ pub mod notification_service;
 pub mod notification_service_new;
 pub mod whatsapp_service;
 pub mod report_service;
+pub mod report_cache;
 pub mod settings_service;
 pub mod client_service;
 pub mod payment_service;
@@ -15,26 +16,126 @@ pub mod expense_service;
 pub mod redis_service;
 pub mod metrics_service;
 pub mod file_service;
+pub mod file_encryption;
 pub mod payment_gateway_service;
 pub mod retry_service;
 pub mod monitoring_service;
+pub mod document_number_service;
+pub mod automation_issue_service;
+pub mod clock;
+pub mod anonymization_service;
+pub mod statement_delivery_service;
+pub mod budget_service;
+pub mod payout_service;
+pub mod business_service;
+pub mod accountant_service;
+pub mod integration;
+pub mod campaign_service;
+pub mod profitability_service;
+pub mod sync_service;
+pub mod invoice_label_service;
+pub mod fx_rate_service;
+pub mod email_signature_service;
+pub mod email_template_service;
+pub mod email_templates;
+pub mod custom_report_service;
+pub mod template_bundle_service;
+pub mod client_import_service;
+pub mod credit_note_service;
+pub mod paypal_webhook_service;
+pub mod guest_token_service;
+pub mod attachment_service;
+pub mod late_fee_service;
+pub mod audit_service;
+pub mod webhook_service;
+pub mod client_csv_import_service;
+pub mod invoice_csv_import_service;
+pub mod invoice_export_service;
+pub mod xlsx_writer;
+pub mod zip_archive;
+pub mod search_service;
+pub mod trash_service;
+pub mod client_auth_service;
+pub mod stripe_checkout_service;
+pub mod bank_reconciliation_service;
+pub mod receipt_scan_service;
+pub mod client_statement_service;
+pub mod time_entry_service;
+pub mod project_service;
+pub mod realtime_service;
+pub mod invoice_template_service;
+pub mod client_credit_service;
+pub mod vat_validation_service;
+pub mod e_invoice_writer;
+pub mod e_invoice_service;
+pub mod shutdown;
 
 pub use auth_service::{AuthService, AuthError};
 pub use redis_service::RedisService;
-pub use email_service::{EmailService, EmailError, EmailConfig};
-pub use email_queue_service::EmailQueueService;
-pub use metrics_service::MetricsService;
+pub use email_service::{EmailService, EmailError, EmailConfig, InvoiceEmail, PaymentConfirmationEmail, CampaignEmail, AttachmentLink};
+pub use email_queue_service::{DeadLetter, EmailJob, EmailJobType, EmailQueueError, EmailQueueService, QueueStats};
+pub use metrics_service::{MetricsService, Outcome};
 pub use monitoring_service::{MonitoringService, HealthStatus};
-pub use invoice_service::{InvoiceService, InvoiceError};
+pub use invoice_service::{InvoiceService, InvoiceError, InvoicePdf};
 pub use tax_service::{TaxService, TaxError};
-pub use pdf_service::{PdfService, InvoiceItemPdf, PdfError};
+pub use pdf_service::{PdfService, InvoiceItemPdf, PdfTaxLine, CreditNotePdf, StatementPdf, PdfError, PdfWatermark, PdfTemplate, PdfBranding};
 pub use report_service::ReportService;
+pub use report_cache::ReportCache;
 pub use settings_service::{SettingsService, SettingsError};
 pub use client_service::ClientService;
 pub use payment_service::PaymentService;
 pub use expense_service::ExpenseService;
 pub use file_service::{FileService, UploadedFile, FileError};
+pub use file_encryption::{FileEncryption, MasterKeyRing};
 pub use payment_gateway_service::PaymentGatewayService;
 pub use notification_service::NotificationService;
 pub use notification_service_new::EnhancedNotificationService;
-pub use whatsapp_service::WhatsAppService;
+pub use whatsapp_service::{WhatsAppService, WhatsAppResponse};
+pub use document_number_service::{DocumentNumberService, DocumentNumberError};
+pub use automation_issue_service::{AutomationIssueService, AutomationIssueError};
+pub use clock::{SharedClock, SystemClock};
+pub use shutdown::Shutdown;
+pub use anonymization_service::InvoiceAnonymizer;
+pub use statement_delivery_service::StatementDeliveryService;
+pub use budget_service::{BudgetService, BudgetError};
+pub use payout_service::{PayoutService, PayoutError};
+pub use paypal_webhook_service::{PayPalWebhookService, PayPalWebhookError};
+pub use guest_token_service::{GuestTokenService, GuestTokenError, GuestLink};
+pub use attachment_service::{AttachmentService, AttachmentError};
+pub use late_fee_service::{LateFeeService, LateFeeError};
+pub use audit_service::{AuditService, AuditError, AuditOrigin, AuditEvent};
+pub use webhook_service::{WebhookService, WebhookError};
+pub use client_csv_import_service::{ClientCsvImportService, ClientCsvImportError, ClientImportOutcome};
+pub use invoice_csv_import_service::{InvoiceCsvImportService, InvoiceCsvImportError};
+pub use invoice_export_service::{InvoiceExportService, InvoiceExportError};
+pub use xlsx_writer::XlsxWriter;
+pub use zip_archive::ZipArchive;
+pub use search_service::{SearchService, SearchError};
+pub use trash_service::{TrashService, TrashError};
+pub use client_auth_service::{ClientAuthService, ClientAuthError};
+pub use stripe_checkout_service::{StripeCheckoutService, StripeCheckoutError};
+pub use bank_reconciliation_service::{BankReconciliationService, BankReconciliationError};
+pub use receipt_scan_service::{ReceiptScanService, ReceiptScanError};
+pub use client_statement_service::{ClientStatementService, ClientStatementError};
+pub use time_entry_service::{TimeEntryService, TimeEntryError, BillableEntry};
+pub use project_service::{ProjectService, ProjectError};
+pub use realtime_service::{RealtimeService, RealtimeError};
+pub use invoice_template_service::{InvoiceTemplateService, InvoiceTemplateError};
+pub use client_credit_service::{ClientCreditService, ClientCreditError};
+pub use vat_validation_service::VatNumberValidator;
+pub use e_invoice_service::{EInvoiceService, EInvoiceError, EInvoiceFile};
+pub use business_service::{BusinessService, BusinessError};
+pub use accountant_service::{AccountantService, AccountantError};
+pub use integration::{LazyHttpClient, IntegrationStatus, IntegrationState};
+pub use campaign_service::{CampaignService, CampaignError};
+pub use profitability_service::{ProfitabilityService, ProfitabilityError};
+pub use sync_service::{SyncService, SyncError};
+pub use invoice_label_service::{InvoiceLabelService, InvoiceLabelError};
+pub use fx_rate_service::{FxRateService, FxError};
+pub use email_signature_service::{EmailSignatureService, EmailSignatureError};
+pub use email_template_service::{EmailTemplateService, EmailTemplateError};
+pub use email_templates::{EmailTemplates, RenderedEmail};
+pub use custom_report_service::{CustomReportService, CustomReportError};
+pub use template_bundle_service::{TemplateBundleService, TemplateBundleError};
+pub use client_import_service::{ClientImportService, ClientImportError};
+pub use credit_note_service::{CreditNoteService, CreditNoteError};
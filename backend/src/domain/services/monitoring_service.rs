@@ -1,10 +1,19 @@
 #![allow(dead_code)]
 
+use serde::Serialize;
+use std::future::Future;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use uuid::Uuid;
-use crate::domain::services::RedisService;
+use crate::domain::services::{EmailService, IntegrationState, IntegrationStatus, LazyHttpClient, RedisService, Shutdown};
+
+/// How often the background health check refreshes the stored status
+const HEALTH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How long a health check waits on each dependency before calling it down
+const DEPENDENCY_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Application health status
 #[derive(Debug, Clone, PartialEq)]
@@ -14,6 +23,62 @@ pub enum HealthStatus {
     Unhealthy(String),
 }
 
+/// Result of checking one dependency
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DependencyState {
+    Up,
+    Down { error: String },
+    /// Not configured, or disabled in this environment
+    Skipped { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyCheck {
+    /// database, redis or smtp
+    pub name: &'static str,
+    #[serde(flatten)]
+    pub state: DependencyState,
+    pub latency_ms: u64,
+}
+
+impl DependencyCheck {
+    fn skipped(name: &'static str, reason: &str) -> Self {
+        Self { name, state: DependencyState::Skipped { reason: reason.to_string() }, latency_ms: 0 }
+    }
+
+    /// Runs `check`, calling the dependency down if it fails or takes too long
+    async fn run<T, E: std::fmt::Display>(name: &'static str, check: impl Future<Output = Result<T, E>>) -> Self {
+        let started = Instant::now();
+        let state = match tokio::time::timeout(DEPENDENCY_CHECK_TIMEOUT, check).await {
+            Ok(Ok(_)) => DependencyState::Up,
+            Ok(Err(e)) => DependencyState::Down { error: e.to_string() },
+            Err(_) => DependencyState::Down {
+                error: format!("no response within {}s", DEPENDENCY_CHECK_TIMEOUT.as_secs()),
+            },
+        };
+        Self { name, state, latency_ms: started.elapsed().as_millis() as u64 }
+    }
+
+    pub fn is_down(&self) -> bool {
+        matches!(self.state, DependencyState::Down { .. })
+    }
+}
+
+/// Health status with the dependency checks it was derived from
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub dependencies: Vec<DependencyCheck>,
+}
+
+impl HealthReport {
+    /// Ready to serve traffic: every configured dependency answered
+    pub fn is_ready(&self) -> bool {
+        !self.dependencies.iter().any(DependencyCheck::is_down)
+    }
+}
+
 /// System metrics
 #[derive(Debug, Clone)]
 pub struct SystemMetrics {
@@ -91,6 +156,18 @@ pub struct MonitoringService {
 
     // Health status
     health_status: Arc<RwLock<HealthStatus>>,
+
+    // Lazily initialized external integrations reported by health checks
+    integrations: std::sync::RwLock<Vec<LazyHttpClient>>,
+
+    // SMTP server checked by health checks, if set
+    email: Option<Arc<EmailService>>,
+}
+
+impl Default for MonitoringService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MonitoringService {
@@ -103,9 +180,26 @@ impl MonitoringService {
             active_requests: Arc::new(RwLock::new(Vec::new())),
             error_log: Arc::new(RwLock::new(Vec::new())),
             health_status: Arc::new(RwLock::new(HealthStatus::Healthy)),
+            integrations: std::sync::RwLock::new(Vec::new()),
+            email: None,
         }
     }
 
+    /// Check the SMTP server emails are sent through in health checks
+    pub fn with_email(mut self, email: Arc<EmailService>) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    /// Track an external integration in health checks
+    pub fn register_integration(&self, client: LazyHttpClient) {
+        self.integrations.write().unwrap().push(client);
+    }
+
+    pub fn integration_statuses(&self) -> Vec<IntegrationStatus> {
+        self.integrations.read().unwrap().iter().map(LazyHttpClient::status).collect()
+    }
+
     /// Record a successful request
     pub fn record_request(&self, duration_ms: f64) {
         self.total_requests.fetch_add(1, Ordering::SeqCst);
@@ -209,23 +303,47 @@ impl MonitoringService {
         db_pool: Option<&sqlx::PgPool>,
         redis: Option<&Arc<RedisService>>,
     ) -> HealthStatus {
-        let mut issues = Vec::new();
+        self.check_health(db_pool, redis).await.status
+    }
 
-        // Check database
-        if let Some(pool) = db_pool {
-            match sqlx::query("SELECT 1").fetch_one(pool).await {
-                Ok(_) => {}
-                Err(e) => issues.push(format!("Database: {}", e)),
+    /// Check Postgres, Redis and SMTP concurrently, each with a short timeout,
+    /// and store the resulting status
+    pub async fn check_health(
+        &self,
+        db_pool: Option<&sqlx::PgPool>,
+        redis: Option<&Arc<RedisService>>,
+    ) -> HealthReport {
+        let database = async {
+            match db_pool {
+                Some(pool) => DependencyCheck::run("database", sqlx::query("SELECT 1").execute(pool)).await,
+                None => DependencyCheck::skipped("database", "not configured"),
             }
-        }
-
-        // Check Redis
-        if let Some(redis_service) = redis {
-            match redis_service.as_ref().exists("health:check").await {
-                Ok(_) => {}
-                Err(e) => issues.push(format!("Redis: {}", e)),
+        };
+        let redis = async {
+            match redis {
+                Some(redis) => DependencyCheck::run("redis", redis.exists("health:check")).await,
+                None => DependencyCheck::skipped("redis", "not configured"),
             }
-        }
+        };
+        let smtp = async {
+            match &self.email {
+                None => DependencyCheck::skipped("smtp", "not configured"),
+                Some(_) if EmailService::sending_disabled() => {
+                    DependencyCheck::skipped("smtp", "sending disabled by TEST_MODE or SKIP_EMAIL")
+                }
+                Some(email) => DependencyCheck::run("smtp", email.check_connection(DEPENDENCY_CHECK_TIMEOUT)).await,
+            }
+        };
+        let (database, redis, smtp) = tokio::join!(database, redis, smtp);
+
+        // Postgres and Redis failures count toward unhealthy; SMTP ones only degrade
+        let mut issues: Vec<String> = [&database, &redis]
+            .into_iter()
+            .filter_map(|check| match &check.state {
+                DependencyState::Down { error } => Some(format!("{}: {}", check.name, error)),
+                _ => None,
+            })
+            .collect();
 
         // Check error rate (if more than 10 errors in last 5 minutes, degraded)
         let error_count = self.get_error_count(5).await;
@@ -233,13 +351,52 @@ impl MonitoringService {
             issues.push(format!("High error rate: {} errors in last 5 minutes", error_count));
         }
 
-        if issues.is_empty() {
+        // Integrations that failed to initialize degrade the service but never take it down
+        let mut integration_issues: Vec<String> = self
+            .integration_statuses()
+            .into_iter()
+            .filter_map(|status| match status.state {
+                IntegrationState::Failed(e) => Some(format!("{}: {}", status.name, e)),
+                _ => None,
+            })
+            .collect();
+        if let DependencyState::Down { error } = &smtp.state {
+            integration_issues.push(format!("{}: {}", smtp.name, error));
+        }
+
+        let status = if issues.is_empty() && integration_issues.is_empty() {
             HealthStatus::Healthy
+        } else if issues.is_empty() {
+            HealthStatus::Degraded(integration_issues.join(", "))
         } else if issues.len() <= 2 {
             HealthStatus::Degraded(issues.join(", "))
         } else {
             HealthStatus::Unhealthy(issues.join(", "))
+        };
+
+        // Store the result so request handling can react without checking again
+        let previous = self.get_health_status().await;
+        if previous != status {
+            match &status {
+                HealthStatus::Healthy => tracing::info!("Health status changed to healthy"),
+                HealthStatus::Degraded(msg) => tracing::warn!("Health status changed to degraded: {}", msg),
+                HealthStatus::Unhealthy(msg) => tracing::error!("Health status changed to unhealthy: {}", msg),
+            }
         }
+        self.set_health_status(status.clone()).await;
+        HealthReport { status, dependencies: vec![database, redis, smtp] }
+    }
+
+    /// Spawn the periodic health check that keeps the stored status current
+    pub fn start_health_checks(self: Arc<Self>, shutdown: &Shutdown, db_pool: sqlx::PgPool, redis: Option<Arc<RedisService>>) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            while shutdown.tick(&mut interval).await {
+                self.perform_health_check(Some(&db_pool), redis.as_ref()).await;
+            }
+        });
     }
 
     /// Get performance summary
@@ -383,6 +540,27 @@ mod tests {
         assert!(matches!(status, HealthStatus::Degraded(_)));
     }
 
+    #[tokio::test]
+    async fn test_dependency_checks_report_failures_and_skips() {
+        let monitor = MonitoringService::new();
+        let report = monitor.check_health(None, None).await;
+        assert!(report.is_ready());
+        assert_eq!(report.status, HealthStatus::Healthy);
+        let names: Vec<_> = report.dependencies.iter().map(|check| check.name).collect();
+        assert_eq!(names, ["database", "redis", "smtp"]);
+        assert!(report.dependencies.iter().all(|check| matches!(check.state, DependencyState::Skipped { .. })));
+
+        let down = DependencyCheck::run("redis", async { Err::<(), _>("connection refused") }).await;
+        assert_eq!(down.state, DependencyState::Down { error: "connection refused".to_string() });
+        let report = HealthReport { status: HealthStatus::Healthy, dependencies: vec![down.clone()] };
+        assert!(!report.is_ready());
+
+        let json = serde_json::to_value(&down).unwrap();
+        assert_eq!(json["name"], "redis");
+        assert_eq!(json["status"], "down");
+        assert_eq!(json["error"], "connection refused");
+    }
+
     #[tokio::test]
     async fn test_request_guard() {
         let monitor = Arc::new(MonitoringService::new());
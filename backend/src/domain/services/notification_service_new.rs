@@ -1,15 +1,31 @@
+use crate::domain::i18n::Locale;
 use crate::domain::models::invoice::InvoiceDetailResponse;
 use crate::domain::models::user::User;
-use crate::domain::services::email_service::EmailService;
+use crate::domain::models::{EmailTemplate, EmailTemplateKind};
+use crate::domain::services::email_queue_service::{EmailJobType, EmailQueueService};
 use crate::domain::services::whatsapp_service::WhatsAppService;
+use crate::infrastructure::repositories::{EmailTemplateRepository, UserRepository};
 use anyhow::Result;
 use chrono::Utc;
+use rust_decimal::prelude::ToPrimitive;
 use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct EnhancedNotificationService {
-    email_service: Arc<EmailService>,
+    email_queue: Arc<EmailQueueService>,
     whatsapp_service: Arc<WhatsAppService>,
+    // The invoicing user's business name and templates, for confirmations
+    users: Option<UserRepository>,
+    email_templates: Option<EmailTemplateRepository>,
+}
+
+impl std::fmt::Debug for EnhancedNotificationService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnhancedNotificationService")
+            .field("email_queue", &self.email_queue)
+            .field("whatsapp_service", &self.whatsapp_service)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -30,15 +46,42 @@ impl NotificationResult {
 
 impl EnhancedNotificationService {
     pub fn new(
-        email_service: Arc<EmailService>,
+        email_queue: Arc<EmailQueueService>,
         whatsapp_service: Arc<WhatsAppService>,
     ) -> Self {
         Self {
-            email_service,
+            email_queue,
             whatsapp_service,
+            users: None,
+            email_templates: None,
         }
     }
 
+    /// Send payment confirmations with the user's business name and own template
+    pub fn with_email_templates(mut self, users: UserRepository, email_templates: EmailTemplateRepository) -> Self {
+        self.users = Some(users);
+        self.email_templates = Some(email_templates);
+        self
+    }
+
+    /// The user's business name, confirmation template and locale. Lookups that fail
+    /// leave the built-in ones rather than hold up the confirmation.
+    async fn confirmation_branding(&self, user_id: uuid::Uuid) -> (Option<String>, Option<EmailTemplate>, Locale) {
+        let user = match &self.users {
+            Some(users) => users.find_by_id(user_id).await.ok().flatten(),
+            None => None,
+        };
+        let locale = user.as_ref().map(|user| user.locale).unwrap_or_default();
+        let business_name = user.and_then(|user| user.company_name);
+        let template = match &self.email_templates {
+            Some(templates) => {
+                templates.find(user_id, EmailTemplateKind::PaymentConfirmation).await.ok().flatten()
+            }
+            None => None,
+        };
+        (business_name, template, locale)
+    }
+
     /// Send invoice notification to buyer (registered)
     pub async fn send_invoice_notification(
         &self,
@@ -55,10 +98,15 @@ impl EnhancedNotificationService {
             let user_name = user.company_name.as_deref().unwrap_or("Unknown");
             let pdf_url = payment_link.clone().unwrap_or_else(|| format!("https://yourapp.com/invoices/{}", invoice.id));
 
-            match self
-                .email_service
-                .send_invoice(&email, user_name, &invoice.invoice_number, &pdf_url, invoice.total_amount, &invoice.due_date.to_string())
-            {
+            let job = EmailJobType::SendInvoice {
+                to_email: email,
+                to_name: user_name.to_string(),
+                invoice_number: invoice.invoice_number.clone(),
+                pdf_url,
+                amount: invoice.total_amount.to_f64().unwrap_or_default(),
+                due_date: invoice.due_date.to_string(),
+            };
+            match self.email_queue.send_immediate(job).await {
                 Ok(_) => {
                     result.email_sent = true;
                     result.email_message_id = Some(uuid::Uuid::new_v4().to_string());
@@ -140,11 +188,21 @@ impl EnhancedNotificationService {
         if let Some(email) = recipient_email {
             let client_name = invoice.client_name.clone();
             let payment_method = "PayPal"; // Default, can be parameterized
+            let (business_name, template, user_locale) = self.confirmation_branding(invoice.user_id).await;
 
-            match self
-                .email_service
-                .send_payment_confirmation(&email, &client_name, &invoice.invoice_number, invoice.total_amount, payment_method)
-            {
+            let job = EmailJobType::SendPaymentConfirmation {
+                to_email: email,
+                to_name: client_name,
+                invoice_number: invoice.invoice_number.clone(),
+                amount: invoice.total_amount.to_f64().unwrap_or_default(),
+                payment_method: payment_method.to_string(),
+                business_name,
+                template,
+                locale: invoice.client_locale.unwrap_or(user_locale),
+                currency: invoice.currency.clone(),
+            };
+            // Queued rather than sent, so it counts as sent once queued
+            match self.email_queue.enqueue_now(job).await {
                 Ok(_) => {
                     result.email_sent = true;
                     result.email_message_id = Some(uuid::Uuid::new_v4().to_string());
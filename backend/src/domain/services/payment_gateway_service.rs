@@ -1,8 +1,33 @@
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::DateTime;
+use hmac::{Hmac, Mac};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::CertificateDer;
 use serde::{Deserialize, Serialize};
+use sha2::Sha256;
 use thiserror::Error;
 
+use crate::domain::models::{PayoutPaymentRef, PayoutStatus, RecordPayout};
+use crate::domain::services::LazyHttpClient;
+
+const STRIPE_API_BASE: &str = "https://api.stripe.com/v1";
+
+/// Stripe reports amounts in the smallest unit; these currencies have no minor unit
+const STRIPE_ZERO_DECIMAL_CURRENCIES: [&str; 16] = [
+    "bif", "clp", "djf", "gnf", "jpy", "kmf", "krw", "mga", "pyg", "rwf", "ugx", "vnd", "vuv", "xaf", "xof", "xpf",
+];
+
+/// Signed webhooks older than this are rejected to prevent replays
+const STRIPE_SIGNATURE_TOLERANCE_SECS: i64 = 300;
+
+/// PayPal only serves webhook signing certificates from its own API hosts
+const PAYPAL_CERT_HOSTS: [&str; 2] = ["api.paypal.com", "api.sandbox.paypal.com"];
+
 #[derive(Debug, Error)]
 pub enum PaymentGatewayError {
     #[error("Stripe error: {0}")]
@@ -57,6 +82,49 @@ pub struct RefundResponse {
     pub status: String,
 }
 
+/// A hosted Stripe Checkout page for a single payment
+#[derive(Debug, Clone)]
+pub struct CreateCheckoutSession {
+    pub amount: f64,
+    pub currency: String,
+    pub description: String,
+    /// Stored as the session's `client_reference_id`
+    pub reference: String,
+    pub customer_email: Option<String>,
+    pub success_url: String,
+    pub cancel_url: String,
+}
+
+/// Stripe's `checkout.session` object, as far as payments need it
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckoutSession {
+    pub id: String,
+    /// Hosted page to send the payer to; only set while the session is open
+    pub url: Option<String>,
+    /// `open`, `complete` or `expired`
+    pub status: Option<String>,
+    /// `paid`, `unpaid` or `no_payment_required`
+    pub payment_status: String,
+    pub payment_intent: Option<String>,
+    pub client_reference_id: Option<String>,
+}
+
+impl CheckoutSession {
+    pub fn is_paid(&self) -> bool {
+        self.payment_status == "paid"
+    }
+}
+
+/// `PAYPAL-TRANSMISSION-*` headers sent with every PayPal webhook delivery
+#[derive(Debug, Clone)]
+pub struct PayPalTransmission {
+    pub id: String,
+    pub time: String,
+    pub signature: String,
+    pub cert_url: String,
+    pub auth_algo: String,
+}
+
 /// Payment Gateway Service - handles Stripe, PayPal, and ACH integrations
 /// This service provides a unified interface for payment gateway operations
 /// In production, you would use official SDKs (stripe, paypal-rs) for full API support
@@ -67,30 +135,43 @@ pub struct PaymentGatewayService {
     paypal_secret: Option<String>,
     ach_enabled: bool,
     ach_provider: Option<String>,
-    http_client: reqwest::Client,
+    http_client: LazyHttpClient,
+    /// PayPal signing certificates (DER) by URL; they rotate rarely
+    paypal_certs: Arc<Mutex<HashMap<String, Vec<u8>>>>,
 }
 
 impl PaymentGatewayService {
-    pub fn new() -> Result<Self, PaymentGatewayError> {
+    /// Reads credentials only; the HTTP client is built on the first gateway call
+    pub fn new() -> Self {
         let stripe_secret_key = std::env::var("STRIPE_SECRET_KEY").ok();
         let paypal_client_id = std::env::var("PAYPAL_CLIENT_ID").ok();
         let paypal_secret = std::env::var("PAYPAL_CLIENT_SECRET").ok();
         let ach_enabled = std::env::var("ACH_ENABLED").unwrap_or_else(|_| "false".to_string()) == "true";
         let ach_provider = std::env::var("ACH_PROVIDER").ok();
 
-        let http_client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?;
+        let configured = stripe_secret_key.is_some()
+            || (paypal_client_id.is_some() && paypal_secret.is_some())
+            || (ach_enabled && ach_provider.is_some());
+        let http_client = LazyHttpClient::new("payment_gateway", std::time::Duration::from_secs(30), configured);
 
-        Ok(Self {
+        Self {
             stripe_secret_key,
             paypal_client_id,
             paypal_secret,
             ach_enabled,
             ach_provider,
             http_client,
-        })
+            paypal_certs: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn http(&self) -> Result<&reqwest::Client, PaymentGatewayError> {
+        self.http_client.get().map_err(PaymentGatewayError::Http)
+    }
+
+    /// Shared handle to the gateway client, for warm-up and health reporting
+    pub fn http_handle(&self) -> LazyHttpClient {
+        self.http_client.clone()
     }
 
     pub async fn create_stripe_payment_intent(
@@ -252,8 +333,456 @@ impl PaymentGatewayService {
     }
 }
 
+impl PaymentGatewayService {
+    /// Fetch a Stripe payout and the charges settled in it, with per-charge fees
+    pub async fn fetch_stripe_payout(&self, payout_id: &str) -> Result<RecordPayout, PaymentGatewayError> {
+        let key = self
+            .stripe_secret_key
+            .as_ref()
+            .ok_or_else(|| PaymentGatewayError::Config("Stripe not configured".to_string()))?;
+
+        let payout: StripePayout = self
+            .http()?
+            .get(format!("{}/payouts/{}", STRIPE_API_BASE, payout_id))
+            .bearer_auth(key)
+            .send()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?;
+
+        let mut payments = Vec::new();
+        let mut starting_after: Option<String> = None;
+        loop {
+            let mut query = vec![
+                ("payout", payout_id.to_string()),
+                ("limit", "100".to_string()),
+                ("expand[]", "data.source".to_string()),
+            ];
+            if let Some(after) = &starting_after {
+                query.push(("starting_after", after.clone()));
+            }
+
+            let page: StripeList<StripeBalanceTransaction> = self
+                .http()?
+                .get(format!("{}/balance_transactions", STRIPE_API_BASE))
+                .bearer_auth(key)
+                .query(&query)
+                .send()
+                .await
+                .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+                .error_for_status()
+                .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?;
+
+            starting_after = page.data.last().map(|txn| txn.id.clone());
+            for txn in page.data {
+                if !matches!(txn.kind.as_str(), "charge" | "payment") {
+                    continue;
+                }
+                // Payments are recorded against the payment intent when there is one
+                let reference = txn.source.as_ref().and_then(|source| {
+                    source.payment_intent.clone().or_else(|| Some(source.id.clone()))
+                });
+                if let Some(gateway_payment_id) = reference {
+                    payments.push(PayoutPaymentRef {
+                        gateway_payment_id,
+                        fee: Some(stripe_amount(txn.fee, &txn.currency)),
+                    });
+                }
+            }
+
+            if !page.has_more || starting_after.is_none() {
+                break;
+            }
+        }
+
+        Ok(RecordPayout {
+            gateway: "stripe".to_string(),
+            gateway_payout_id: payout.id,
+            amount: stripe_amount(payout.amount, &payout.currency),
+            currency: Some(payout.currency.to_uppercase()),
+            status: PayoutStatus::parse(&payout.status).unwrap_or(PayoutStatus::Pending),
+            arrival_date: payout
+                .arrival_date
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|dt| dt.date_naive()),
+            payments,
+        })
+    }
+
+    /// Verify a `Stripe-Signature` header (`t=...,v1=...`) against the raw payload
+    pub fn verify_stripe_signature(
+        payload: &str,
+        header: &str,
+        secret: &str,
+        now: i64,
+    ) -> Result<(), PaymentGatewayError> {
+        let mut timestamp: Option<i64> = None;
+        let mut signatures = Vec::new();
+        for part in header.split(',') {
+            match part.trim().split_once('=') {
+                Some(("t", value)) => timestamp = value.parse().ok(),
+                Some(("v1", value)) => signatures.push(value),
+                _ => {}
+            }
+        }
+
+        let timestamp = timestamp
+            .ok_or_else(|| PaymentGatewayError::Stripe("Missing signature timestamp".to_string()))?;
+        if (now - timestamp).abs() > STRIPE_SIGNATURE_TOLERANCE_SECS {
+            return Err(PaymentGatewayError::Stripe("Signature timestamp outside tolerance".to_string()));
+        }
+
+        let valid = signatures.iter().any(|signature| {
+            let Ok(expected) = hex::decode(signature) else {
+                return false;
+            };
+            let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+                return false;
+            };
+            mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+            mac.verify_slice(&expected).is_ok()
+        });
+
+        if valid {
+            Ok(())
+        } else {
+            Err(PaymentGatewayError::Stripe("Invalid webhook signature".to_string()))
+        }
+    }
+}
+
+impl PaymentGatewayService {
+    fn stripe_key(&self) -> Result<&str, PaymentGatewayError> {
+        self.stripe_secret_key
+            .as_deref()
+            .ok_or_else(|| PaymentGatewayError::Config("Stripe not configured".to_string()))
+    }
+
+    /// Create a Checkout Session; the payer completes the payment on its `url`
+    pub async fn create_stripe_checkout_session(
+        &self,
+        session: CreateCheckoutSession,
+    ) -> Result<CheckoutSession, PaymentGatewayError> {
+        let key = self.stripe_key()?;
+        if session.amount <= 0.0 {
+            return Err(PaymentGatewayError::InvalidAmount);
+        }
+
+        self.http()?
+            .post(format!("{}/checkout/sessions", STRIPE_API_BASE))
+            .bearer_auth(key)
+            .form(&checkout_session_params(&session))
+            .send()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))
+    }
+
+    pub async fn fetch_stripe_checkout_session(&self, session_id: &str) -> Result<CheckoutSession, PaymentGatewayError> {
+        let key = self.stripe_key()?;
+
+        self.http()?
+            .get(format!("{}/checkout/sessions/{}", STRIPE_API_BASE, session_id))
+            .bearer_auth(key)
+            .send()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))
+    }
+
+    /// Close an open session so it can no longer be paid. Stripe refuses once it's complete.
+    pub async fn expire_stripe_checkout_session(&self, session_id: &str) -> Result<CheckoutSession, PaymentGatewayError> {
+        let key = self.stripe_key()?;
+
+        self.http()?
+            .post(format!("{}/checkout/sessions/{}/expire", STRIPE_API_BASE, session_id))
+            .bearer_auth(key)
+            .send()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| PaymentGatewayError::Stripe(e.to_string()))
+    }
+}
+
+impl PaymentGatewayService {
+    /// Verify a PayPal webhook delivery against the signing certificate it points to
+    pub async fn verify_paypal_webhook(
+        &self,
+        transmission: &PayPalTransmission,
+        payload: &str,
+        webhook_id: &str,
+    ) -> Result<(), PaymentGatewayError> {
+        let cert = self.paypal_signing_cert(&transmission.cert_url).await?;
+        Self::verify_paypal_signature(&cert, transmission, payload, webhook_id)
+    }
+
+    async fn paypal_signing_cert(&self, cert_url: &str) -> Result<Vec<u8>, PaymentGatewayError> {
+        let url = reqwest::Url::parse(cert_url)
+            .map_err(|_| PaymentGatewayError::PayPal("Invalid certificate URL".to_string()))?;
+        let trusted = url.scheme() == "https" && url.host_str().is_some_and(|host| PAYPAL_CERT_HOSTS.contains(&host));
+        if !trusted {
+            return Err(PaymentGatewayError::PayPal("Certificate URL is not a PayPal host".to_string()));
+        }
+
+        if let Some(cert) = self.paypal_certs.lock().ok().and_then(|certs| certs.get(cert_url).cloned()) {
+            return Ok(cert);
+        }
+
+        let pem = self
+            .http()?
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| PaymentGatewayError::PayPal(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| PaymentGatewayError::Http(e.to_string()))?;
+        let cert = CertificateDer::from_pem_slice(&pem)
+            .map_err(|e| PaymentGatewayError::PayPal(format!("Invalid signing certificate: {}", e)))?
+            .to_vec();
+
+        if let Ok(mut certs) = self.paypal_certs.lock() {
+            certs.insert(cert_url.to_string(), cert.clone());
+        }
+        Ok(cert)
+    }
+
+    /// Verify a PayPal transmission signature: SHA256withRSA over
+    /// `<transmission id>|<transmission time>|<webhook id>|<crc32 of the body>`
+    pub fn verify_paypal_signature(
+        cert_der: &[u8],
+        transmission: &PayPalTransmission,
+        payload: &str,
+        webhook_id: &str,
+    ) -> Result<(), PaymentGatewayError> {
+        if !transmission.auth_algo.eq_ignore_ascii_case("SHA256withRSA") {
+            return Err(PaymentGatewayError::PayPal(format!(
+                "Unsupported signature algorithm {}",
+                transmission.auth_algo
+            )));
+        }
+
+        let signature = STANDARD
+            .decode(transmission.signature.trim())
+            .map_err(|_| PaymentGatewayError::PayPal("Malformed webhook signature".to_string()))?;
+        let der = CertificateDer::from(cert_der);
+        let cert = webpki::EndEntityCert::try_from(&der)
+            .map_err(|e| PaymentGatewayError::PayPal(format!("Invalid signing certificate: {}", e)))?;
+
+        let message = format!(
+            "{}|{}|{}|{}",
+            transmission.id,
+            transmission.time,
+            webhook_id,
+            crc32fast::hash(payload.as_bytes())
+        );
+        cert.verify_signature(webpki::ring::RSA_PKCS1_2048_8192_SHA256, message.as_bytes(), &signature)
+            .map_err(|_| PaymentGatewayError::PayPal("Invalid webhook signature".to_string()))
+    }
+}
+
+fn stripe_amount(minor: i64, currency: &str) -> f64 {
+    if STRIPE_ZERO_DECIMAL_CURRENCIES.contains(&currency.to_ascii_lowercase().as_str()) {
+        minor as f64
+    } else {
+        minor as f64 / 100.0
+    }
+}
+
+/// Amount in Stripe's smallest currency unit
+fn stripe_minor_units(amount: f64, currency: &str) -> i64 {
+    if STRIPE_ZERO_DECIMAL_CURRENCIES.contains(&currency.to_ascii_lowercase().as_str()) {
+        amount.round() as i64
+    } else {
+        (amount * 100.0).round() as i64
+    }
+}
+
+/// Form fields for a one-line, one-off Checkout Session
+fn checkout_session_params(session: &CreateCheckoutSession) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("mode", "payment".to_string()),
+        ("success_url", session.success_url.clone()),
+        ("cancel_url", session.cancel_url.clone()),
+        ("client_reference_id", session.reference.clone()),
+        ("line_items[0][quantity]", "1".to_string()),
+        ("line_items[0][price_data][currency]", session.currency.to_ascii_lowercase()),
+        (
+            "line_items[0][price_data][unit_amount]",
+            stripe_minor_units(session.amount, &session.currency).to_string(),
+        ),
+        ("line_items[0][price_data][product_data][name]", session.description.clone()),
+        ("metadata[reference]", session.reference.clone()),
+    ];
+    if let Some(email) = &session.customer_email {
+        params.push(("customer_email", email.clone()));
+    }
+    params
+}
+
+#[derive(Debug, Deserialize)]
+struct StripePayout {
+    id: String,
+    amount: i64,
+    currency: String,
+    status: String,
+    arrival_date: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeList<T> {
+    data: Vec<T>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeBalanceTransaction {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    fee: i64,
+    currency: String,
+    source: Option<StripeChargeSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeChargeSource {
+    id: String,
+    payment_intent: Option<String>,
+}
+
 impl Default for PaymentGatewayService {
     fn default() -> Self {
-        Self::new().expect("Failed to initialize payment gateway service")
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(payload: &str, secret: &str, timestamp: i64) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(format!("{}.{}", timestamp, payload).as_bytes());
+        format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn test_stripe_signature_verification() {
+        let payload = r#"{"type":"payout.paid"}"#;
+        let header = sign(payload, "whsec_test", 1_700_000_000);
+
+        assert!(PaymentGatewayService::verify_stripe_signature(payload, &header, "whsec_test", 1_700_000_010).is_ok());
+        assert!(PaymentGatewayService::verify_stripe_signature(payload, &header, "whsec_other", 1_700_000_010).is_err());
+        assert!(PaymentGatewayService::verify_stripe_signature("{}", &header, "whsec_test", 1_700_000_010).is_err());
+        // Replayed outside the tolerance window
+        assert!(PaymentGatewayService::verify_stripe_signature(payload, &header, "whsec_test", 1_700_001_000).is_err());
+    }
+
+    // Self-signed test certificate; the signature below was made with its private key
+    const PAYPAL_TEST_CERT: &str = "-----BEGIN CERTIFICATE-----
+MIIDQTCCAimgAwIBAgIUeTlWnI5xP7xiyjg9GxJ22LlkS50wDQYJKoZIhvcNAQEL
+BQAwLzEtMCsGA1UEAwwkbWVzc2FnZXZlcmlmaWNhdGlvbmNlcnRzLnBheXBhbC50
+ZXN0MCAXDTI2MTAxODAxNDY0NFoYDzIxMjYwOTI0MDE0NjQ0WjAvMS0wKwYDVQQD
+DCRtZXNzYWdldmVyaWZpY2F0aW9uY2VydHMucGF5cGFsLnRlc3QwggEiMA0GCSqG
+SIb3DQEBAQUAA4IBDwAwggEKAoIBAQC41ZBnKRh5clUfJ46VeRDP4EwdDesFF2Ag
+3l+pVfeFcVagT570AfN0DvG+AHgHUsc0CwLvqvvgYILZ8TPkaH9k0BU2J6u/80kx
+nw+sa8Uh3uMk+TtgUDWYo/X4lPar8z6zW2yloxZE+FOw2HSDIHkiw9aDo6v67oaN
+b1pfmIvNe6LEksAL8SBh09uUPLTttRT5yA/l/3J3IWnbVHv0SuEMh+14pzI1hchm
+86MfiD8PM7ja8xZZvGAmuxkfofBPff3il+fhNnnDCcIoj8Mw3Imgq8NNDhiMS/yI
+WuYbHPxkJuSHoIKSYQHf9/fiJ2+3N6j4XQl/LXZf44+QI8rEGoytAgMBAAGjUzBR
+MB0GA1UdDgQWBBRrKnG6f8VAfojz99nMjaCyFvedPDAfBgNVHSMEGDAWgBRrKnG6
+f8VAfojz99nMjaCyFvedPDAPBgNVHRMBAf8EBTADAQH/MA0GCSqGSIb3DQEBCwUA
+A4IBAQC0I8ROhDbaMgjk+hBj3p19fzzbATAshl4S/UOuHSH0+fXd4kUKgE7nFtWW
+++8W2xTeGzZjb7wUpHe4XkkbGoO5Sv7MnmGE6ft1yU9ppJfXuZPL49pwgRo+BQsF
+42c056EVF8xxl742oCX2/ZEhXVNEBsqL5HPA2qQcklFq+IWsTSptCXOF3ICDdC14
+m7iHHK6BbbyQjo73iJZq6BvXHxPDfZVUql3dE4ALIivIOuT6EhXqivdcJaJIia2g
+qG0qDz7bnaMu7erhZ/v8Dw7ds1K3qmZ+fn/1UEHA/kb2Z+DZ5C3A6KQoQboaUyEP
+Dr6HWqknpl968ahCybf9IW+F4Kwz
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_paypal_signature_verification() {
+        let cert = CertificateDer::from_pem_slice(PAYPAL_TEST_CERT.as_bytes()).unwrap();
+        let payload = r#"{"id":"WH-1","event_type":"PAYMENT.CAPTURE.COMPLETED"}"#;
+        let transmission = PayPalTransmission {
+            id: "tx-1".to_string(),
+            time: "2026-10-18T10:00:00Z".to_string(),
+            signature: "dHD250YuWrBqE9kKwOXEmQ8lNKlyBfCotQfFvPm/F8elh+K1qEPafH6AUz9CaBoTYAgGTaZ2Jsz17TRFMP7Q3NlCaoTNxssY8X2G/4Zr2TBO5/RUPF22wUolAWE50r0La+kIXoIHKUMKGNHY52OGCBAxZQJbDvfyrUJsfD5EhdVNp2/PJpj48ChujaPAvH3TvDtGM7gjE0+8RIQLu5UL8hz3TJNrB3aBydVskyauQcl/GKil/zNAf1260HrtkuPHUklNcfHEtIrPAnFb1+E18lRoNEg8Uah3TWPZkHzxGkp3dAZIG/4jKTdxFFyL5kyTg7Oj0NVmGDXvOT9r75KPGg==".to_string(),
+            cert_url: "https://api.sandbox.paypal.com/v1/notifications/certs/CERT-test".to_string(),
+            auth_algo: "SHA256withRSA".to_string(),
+        };
+
+        assert!(PaymentGatewayService::verify_paypal_signature(&cert, &transmission, payload, "WH-ID-1").is_ok());
+        // Signed for a different webhook
+        assert!(PaymentGatewayService::verify_paypal_signature(&cert, &transmission, payload, "WH-ID-2").is_err());
+        // Body altered in transit
+        assert!(PaymentGatewayService::verify_paypal_signature(&cert, &transmission, "{}", "WH-ID-1").is_err());
+
+        let replayed_as = PayPalTransmission { id: "tx-2".to_string(), ..transmission.clone() };
+        assert!(PaymentGatewayService::verify_paypal_signature(&cert, &replayed_as, payload, "WH-ID-1").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_paypal_certs_only_fetched_from_paypal() {
+        let gateway = PaymentGatewayService::new();
+        for url in [
+            "https://attacker.example/cert.pem",
+            "http://api.paypal.com/v1/notifications/certs/CERT-1",
+            "https://api.paypal.com.attacker.example/cert.pem",
+        ] {
+            assert!(gateway.paypal_signing_cert(url).await.is_err(), "{} should be rejected", url);
+        }
+    }
+
+    #[test]
+    fn test_stripe_amounts_respect_zero_decimal_currencies() {
+        assert_eq!(stripe_amount(12345, "usd"), 123.45);
+        assert_eq!(stripe_amount(12345, "JPY"), 12345.0);
+        assert_eq!(stripe_minor_units(123.45, "USD"), 12345);
+        assert_eq!(stripe_minor_units(12345.0, "jpy"), 12345);
+    }
+
+    #[test]
+    fn test_checkout_session_bills_one_line_in_minor_units() {
+        let params = checkout_session_params(&CreateCheckoutSession {
+            amount: 250.5,
+            currency: "EUR".to_string(),
+            description: "Invoice INV-2026-0042".to_string(),
+            reference: "inv_1".to_string(),
+            customer_email: None,
+            success_url: "https://api.example.com/success".to_string(),
+            cancel_url: "https://api.example.com/cancel".to_string(),
+        });
+        let param = |name: &str| params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str());
+
+        assert_eq!(param("mode"), Some("payment"));
+        assert_eq!(param("line_items[0][price_data][currency]"), Some("eur"));
+        assert_eq!(param("line_items[0][price_data][unit_amount]"), Some("25050"));
+        assert_eq!(param("client_reference_id"), Some("inv_1"));
+        assert_eq!(param("customer_email"), None);
     }
 }
@@ -1,12 +1,13 @@
 #![allow(dead_code)]
 
+use rust_decimal::prelude::ToPrimitive;
 use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
-use crate::infrastructure::repositories::{PaymentRepository, InvoiceRepository, ClientRepository, UserRepository};
-use crate::domain::services::EmailService;
-use crate::domain::models::{Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod, CreatePayment, RefundRequest};
+use crate::infrastructure::repositories::{PaymentRepository, InvoiceRepository, ClientRepository, UserRepository, EmailTemplateRepository};
+use crate::domain::services::{EmailJobType, EmailQueueService, FxRateService, MetricsService, Outcome};
+use crate::domain::models::{EmailTemplateKind, Page, PageRequest, Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod, CreatePayment, RefundRequest};
 
 #[derive(Clone)]
 pub struct PaymentService {
@@ -14,7 +15,10 @@ pub struct PaymentService {
     invoice_repo: Arc<InvoiceRepository>,
     client_repo: Arc<ClientRepository>,
     user_repo: Arc<UserRepository>,
-    email_service: Arc<EmailService>,
+    email_queue: Arc<EmailQueueService>,
+    fx_rates: Arc<FxRateService>,
+    metrics: Option<Arc<MetricsService>>,
+    email_templates: Option<EmailTemplateRepository>,
 }
 
 impl PaymentService {
@@ -23,33 +27,67 @@ impl PaymentService {
         invoice_repo: Arc<InvoiceRepository>,
         client_repo: Arc<ClientRepository>,
         user_repo: Arc<UserRepository>,
-        email_service: Arc<EmailService>,
+        email_queue: Arc<EmailQueueService>,
+        fx_rates: Arc<FxRateService>,
     ) -> Self {
         Self {
             payment_repo,
             invoice_repo,
             client_repo,
             user_repo,
-            email_service
+            email_queue,
+            fx_rates,
+            metrics: None,
+            email_templates: None,
         }
     }
 
+    /// Count payments recorded
+    pub fn with_metrics(mut self, metrics: Arc<MetricsService>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Send payment confirmations with the user's own template
+    pub fn with_email_templates(mut self, email_templates: EmailTemplateRepository) -> Self {
+        self.email_templates = Some(email_templates);
+        self
+    }
+
     pub async fn create_payment(
         &self,
         user_id: Uuid,
         create: CreatePayment,
     ) -> Result<Payment, sqlx::Error> {
+        let result = self.insert_payment(user_id, create).await;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_payment_recorded(Outcome::of(&result, |e| matches!(e, sqlx::Error::RowNotFound)));
+        }
+        result
+    }
+
+    async fn insert_payment(
+        &self,
+        user_id: Uuid,
+        create: CreatePayment,
+    ) -> Result<Payment, sqlx::Error> {
+        let invoice = self.invoice_repo.get_by_id(user_id, create.invoice_id).await?;
+
+        // Realized FX gain/loss is booked with the payment when settled at a different
+        // rate than issuance; without an explicit settlement rate, the day's rate is used
+        let settlement_rate = self.fx_rates.settlement_rate(user_id, &invoice.currency, create.exchange_rate).await;
         let payment = self.payment_repo.create(
             user_id,
             create.invoice_id,
             create.amount,
-            "USD".to_string(),
+            invoice.currency.clone(),
             create.payment_method,
             create.gateway,
             create.gateway_payment_id,
             create.gateway_fee,
             create.paid_by,
             create.notes,
+            settlement_rate,
         ).await?;
 
         // Send payment confirmation email (best effort - don't fail the payment if email fails)
@@ -101,13 +139,24 @@ impl PaymentService {
             PaymentMethod::AchDebit => "ACH Debit",
         };
 
-        let _ = self.email_service.send_payment_confirmation(
-            client_email,
-            client_name,
-            &invoice.invoice_number,
-            payment.amount,
-            payment_method_str,
-        );
+        let template = match &self.email_templates {
+            Some(templates) => templates.find(user_id, EmailTemplateKind::PaymentConfirmation).await?,
+            None => None,
+        };
+
+        self.email_queue
+            .enqueue_or_log(EmailJobType::SendPaymentConfirmation {
+                to_email: client_email.to_string(),
+                to_name: client_name.clone(),
+                invoice_number: invoice.invoice_number.clone(),
+                amount: payment.amount.to_f64().unwrap_or_default(),
+                payment_method: payment_method_str.to_string(),
+                business_name: user.company_name.clone(),
+                template,
+                locale: invoice.client_locale.unwrap_or(user.locale),
+                currency: invoice.currency.clone(),
+            })
+            .await;
 
         Ok(())
     }
@@ -127,10 +176,13 @@ impl PaymentService {
         payment_method: Option<PaymentMethod>,
         date_from: Option<DateTime<Utc>>,
         date_to: Option<DateTime<Utc>>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<PaymentResponse>, sqlx::Error> {
-        self.payment_repo.list(user_id, status, payment_method, date_from, date_to, limit, offset).await
+        page: &PageRequest,
+    ) -> Result<Page<PaymentResponse>, sqlx::Error> {
+        self.payment_repo.list(user_id, status, payment_method, date_from, date_to, page).await
+    }
+
+    pub async fn get_payments_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<PaymentResponse>, sqlx::Error> {
+        self.payment_repo.find_by_ids(user_id, ids).await
     }
 
     pub async fn refund_payment(
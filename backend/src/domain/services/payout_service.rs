@@ -0,0 +1,189 @@
+use chrono::NaiveDate;
+use std::collections::HashMap;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{round_money, PayoutDetail, PayoutPaymentRef, PayoutReport, RecordPayout};
+use crate::domain::services::payment_gateway_service::PaymentGatewayError;
+use crate::domain::services::{PaymentGatewayService, SharedClock};
+use crate::infrastructure::repositories::PayoutRepository;
+
+#[derive(Debug, Error)]
+pub enum PayoutError {
+    #[error("Payout not found")]
+    NotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    #[error("{0}")]
+    NotConfigured(String),
+
+    #[error("Gateway error: {0}")]
+    Gateway(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<PaymentGatewayError> for PayoutError {
+    fn from(err: PaymentGatewayError) -> Self {
+        match err {
+            PaymentGatewayError::Config(msg) => PayoutError::NotConfigured(msg),
+            other => PayoutError::Gateway(other.to_string()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for PayoutError {
+    fn from(err: sqlx::Error) -> Self {
+        PayoutError::DatabaseError(err.to_string())
+    }
+}
+
+pub struct PayoutService {
+    repo: PayoutRepository,
+    gateway: Arc<PaymentGatewayService>,
+    stripe_webhook_secret: Option<String>,
+    clock: SharedClock,
+}
+
+impl PayoutService {
+    pub fn new(repo: PayoutRepository, gateway: Arc<PaymentGatewayService>, clock: SharedClock) -> Self {
+        Self {
+            repo,
+            gateway,
+            stripe_webhook_secret: std::env::var("STRIPE_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+            clock,
+        }
+    }
+
+    /// Record a payout reported by a gateway settlement report or API
+    pub async fn record_payout(&self, user_id: Uuid, record: RecordPayout) -> Result<PayoutDetail, PayoutError> {
+        if record.gateway.trim().is_empty() || record.gateway_payout_id.trim().is_empty() {
+            return Err(PayoutError::Validation("gateway and gateway_payout_id are required".to_string()));
+        }
+        if !record.amount.is_finite() {
+            return Err(PayoutError::Validation("Invalid payout amount".to_string()));
+        }
+        if record.payments.iter().any(|p| p.fee.is_some_and(|fee| !fee.is_finite() || fee < 0.0)) {
+            return Err(PayoutError::Validation("Payment fees must be zero or positive".to_string()));
+        }
+
+        let payout = self.repo.upsert(user_id, &record).await?;
+        let lines = self.repo.get_lines(payout.id).await?;
+        Ok(PayoutDetail::new(payout, lines))
+    }
+
+    pub async fn get_payout(&self, user_id: Uuid, payout_id: Uuid) -> Result<PayoutDetail, PayoutError> {
+        let payout = self
+            .repo
+            .find_by_id(user_id, payout_id)
+            .await?
+            .ok_or(PayoutError::NotFound)?;
+        let lines = self.repo.get_lines(payout.id).await?;
+        Ok(PayoutDetail::new(payout, lines))
+    }
+
+    pub async fn list_payouts(
+        &self,
+        user_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<PayoutDetail>, PayoutError> {
+        if from > to {
+            return Err(PayoutError::Validation("from must be on or before to".to_string()));
+        }
+
+        let payouts = self.repo.list(user_id, from, to).await?;
+        let mut details = Vec::with_capacity(payouts.len());
+        for payout in payouts {
+            let lines = self.repo.get_lines(payout.id).await?;
+            details.push(PayoutDetail::new(payout, lines));
+        }
+        Ok(details)
+    }
+
+    /// Payouts in the period traced to invoices, plus gateway payments still awaiting payout
+    pub async fn get_report(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<PayoutReport, PayoutError> {
+        let payouts = self.list_payouts(user_id, from, to).await?;
+        let unsettled = self.repo.list_unsettled(user_id, from, to).await?;
+        Ok(PayoutReport::build(from, to, payouts, unsettled))
+    }
+
+    /// Pull a payout from the Stripe API and link it to this user's payments
+    pub async fn sync_stripe_payout(&self, user_id: Uuid, gateway_payout_id: &str) -> Result<PayoutDetail, PayoutError> {
+        let record = self.gateway.fetch_stripe_payout(gateway_payout_id).await?;
+
+        self.record_payout(user_id, record).await
+    }
+
+    /// Handle a signed Stripe `payout.*` event. The payout is fetched from the API and
+    /// recorded for every user whose payments it contains; returns the number recorded.
+    pub async fn handle_stripe_webhook(&self, payload: &str, signature: &str) -> Result<usize, PayoutError> {
+        let secret = self
+            .stripe_webhook_secret
+            .as_deref()
+            .ok_or_else(|| PayoutError::NotConfigured("Stripe webhooks not configured".to_string()))?;
+
+        PaymentGatewayService::verify_stripe_signature(payload, signature, secret, self.clock.now().timestamp())
+            .map_err(|_| PayoutError::InvalidSignature)?;
+
+        let event: serde_json::Value = serde_json::from_str(payload)
+            .map_err(|e| PayoutError::Validation(format!("Invalid event payload: {}", e)))?;
+
+        let is_payout_event = event["type"].as_str().is_some_and(|t| t.starts_with("payout."));
+        let payout_id = match (is_payout_event, event["data"]["object"]["id"].as_str()) {
+            (true, Some(id)) => id.to_string(),
+            // Other event types are acknowledged and ignored
+            _ => return Ok(0),
+        };
+
+        let record = self.gateway.fetch_stripe_payout(&payout_id).await?;
+
+        let references: Vec<String> = record.payments.iter().map(|p| p.gateway_payment_id.clone()).collect();
+        let owners = self.repo.find_payment_owners(&record.gateway, &references).await?;
+
+        let mut by_user: HashMap<Uuid, Vec<String>> = HashMap::new();
+        for (user_id, reference) in owners {
+            by_user.entry(user_id).or_default().push(reference);
+        }
+
+        let single_owner = by_user.len() == 1;
+        for (user_id, owned) in &by_user {
+            let payments: Vec<_> = record
+                .payments
+                .iter()
+                .filter(|p| owned.contains(&p.gateway_payment_id))
+                .cloned()
+                .collect();
+
+            // A payout shared between accounts is split by the net of each account's payments
+            let amount = if single_owner {
+                record.amount
+            } else {
+                self.share_of_payout(*user_id, &payments).await?
+            };
+
+            let user_record = RecordPayout { amount, payments, ..record.clone() };
+            self.repo.upsert(*user_id, &user_record).await?;
+        }
+
+        Ok(by_user.len())
+    }
+
+    async fn share_of_payout(
+        &self,
+        user_id: Uuid,
+        payments: &[PayoutPaymentRef],
+    ) -> Result<f64, PayoutError> {
+        let references: Vec<String> = payments.iter().map(|p| p.gateway_payment_id.clone()).collect();
+        let gross = self.repo.sum_payments(user_id, "stripe", &references).await?;
+        let fees: f64 = payments.iter().filter_map(|p| p.fee).sum();
+        Ok(round_money(gross - fees))
+    }
+}
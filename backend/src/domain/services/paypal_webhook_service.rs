@@ -0,0 +1,190 @@
+use serde::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::domain::services::payment_gateway_service::{PayPalTransmission, PaymentGatewayError};
+use crate::domain::services::{EnhancedNotificationService, FxRateService, PaymentGatewayService, ReportCache};
+use crate::infrastructure::repositories::{InvoiceRepository, PaymentRepository};
+
+#[derive(Debug, Error)]
+pub enum PayPalWebhookError {
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    #[error("{0}")]
+    NotConfigured(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Gateway error: {0}")]
+    Gateway(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for PayPalWebhookError {
+    fn from(err: sqlx::Error) -> Self {
+        PayPalWebhookError::DatabaseError(err.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PayPalEvent {
+    event_type: String,
+    #[serde(default)]
+    resource: serde_json::Value,
+}
+
+impl PayPalEvent {
+    /// The order paid for by this event, if it's one that settles an invoice
+    fn settled_order_id(&self) -> Option<&str> {
+        match self.event_type.as_str() {
+            "CHECKOUT.ORDER.APPROVED" => self.resource["id"].as_str(),
+            "PAYMENT.CAPTURE.COMPLETED" => self.resource["supplementary_data"]["related_ids"]["order_id"].as_str(),
+            _ => None,
+        }
+    }
+
+    /// Payer's PayPal address; only order events carry it
+    fn payer_email(&self) -> Option<String> {
+        self.resource["payer"]["email_address"].as_str().map(str::to_string)
+    }
+}
+
+/// Reconciles PayPal orders with the pending payments recorded when they were created
+pub struct PayPalWebhookService {
+    gateway: Arc<PaymentGatewayService>,
+    payment_repo: Arc<PaymentRepository>,
+    invoice_repo: Arc<InvoiceRepository>,
+    notifications: Arc<EnhancedNotificationService>,
+    webhook_id: Option<String>,
+    /// Settled payments make the seller's cached reports stale
+    reports: ReportCache,
+    fx_rates: Option<Arc<FxRateService>>,
+}
+
+impl PayPalWebhookService {
+    pub fn new(
+        gateway: Arc<PaymentGatewayService>,
+        payment_repo: Arc<PaymentRepository>,
+        invoice_repo: Arc<InvoiceRepository>,
+        notifications: Arc<EnhancedNotificationService>,
+    ) -> Self {
+        Self {
+            gateway,
+            payment_repo,
+            invoice_repo,
+            notifications,
+            webhook_id: std::env::var("PAYPAL_WEBHOOK_ID").ok().filter(|s| !s.is_empty()),
+            reports: ReportCache::default(),
+            fx_rates: None,
+        }
+    }
+
+    /// Invalidate the seller's cached reports when a payment settles
+    pub fn with_report_cache(mut self, reports: ReportCache) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    /// Book realized FX gains and losses on the payments it settles
+    pub fn with_fx_rates(mut self, fx_rates: Arc<FxRateService>) -> Self {
+        self.fx_rates = Some(fx_rates);
+        self
+    }
+
+    /// Handle a signed PayPal delivery. `CHECKOUT.ORDER.APPROVED` and `PAYMENT.CAPTURE.COMPLETED`
+    /// settle the order's pending payment and confirm it to the payer; returns whether an
+    /// invoice was settled. Other events, and redeliveries, are acknowledged and ignored.
+    pub async fn handle(&self, transmission: &PayPalTransmission, payload: &str) -> Result<bool, PayPalWebhookError> {
+        let webhook_id = self
+            .webhook_id
+            .as_deref()
+            .ok_or_else(|| PayPalWebhookError::NotConfigured("PayPal webhooks not configured".to_string()))?;
+
+        self.gateway
+            .verify_paypal_webhook(transmission, payload, webhook_id)
+            .await
+            .map_err(|err| match err {
+                // PayPal retries failed deliveries, so an unreachable cert host isn't a forgery
+                PaymentGatewayError::Http(msg) => PayPalWebhookError::Gateway(msg),
+                other => {
+                    tracing::warn!("Rejected PayPal webhook {}: {}", transmission.id, other);
+                    PayPalWebhookError::InvalidSignature
+                }
+            })?;
+
+        let event: PayPalEvent = serde_json::from_str(payload)
+            .map_err(|e| PayPalWebhookError::Validation(format!("Invalid event payload: {}", e)))?;
+        let Some(order_id) = event.settled_order_id() else {
+            return Ok(false);
+        };
+
+        let settlement_rate = self.settlement_rate(order_id).await?;
+        let Some(invoice_id) = self.payment_repo.settle_pending_gateway_payment("paypal", order_id, settlement_rate).await? else {
+            return Ok(false);
+        };
+
+        // Best effort: the payment stands even if the confirmation can't be sent
+        match self.invoice_repo.get_invoice_by_id(invoice_id).await {
+            Ok(invoice) => {
+                self.reports.invalidate_user(invoice.user_id).await;
+                let email = event.payer_email().or_else(|| invoice.client_email.clone());
+                let phone = invoice.client_phone.clone();
+                if let Err(e) = self.notifications.send_payment_confirmation(&invoice, email, phone).await {
+                    tracing::warn!("Payment confirmation for invoice {} failed: {}", invoice.invoice_number, e);
+                }
+            }
+            Err(e) => tracing::warn!("Settled invoice {} could not be loaded for confirmation: {}", invoice_id, e),
+        }
+
+        Ok(true)
+    }
+
+    /// Rate the pending payment settles at, for its invoice's realized FX result
+    async fn settlement_rate(&self, gateway_payment_id: &str) -> Result<Option<f64>, sqlx::Error> {
+        let Some(fx_rates) = &self.fx_rates else {
+            return Ok(None);
+        };
+        let references = [gateway_payment_id.to_string()];
+        let Some(invoice_id) = self.payment_repo.find_gateway_payment_invoice("paypal", &references).await? else {
+            return Ok(None);
+        };
+        let invoice = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        Ok(fx_rates.settlement_rate(invoice.user_id, &invoice.currency, None).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(json: serde_json::Value) -> PayPalEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_settling_events_resolve_to_their_order() {
+        let approved = event(serde_json::json!({
+            "event_type": "CHECKOUT.ORDER.APPROVED",
+            "resource": { "id": "ORDER_1", "payer": { "email_address": "payer@example.com" } }
+        }));
+        assert_eq!(approved.settled_order_id(), Some("ORDER_1"));
+        assert_eq!(approved.payer_email().as_deref(), Some("payer@example.com"));
+
+        let captured = event(serde_json::json!({
+            "event_type": "PAYMENT.CAPTURE.COMPLETED",
+            "resource": { "id": "CAPTURE_1", "supplementary_data": { "related_ids": { "order_id": "ORDER_1" } } }
+        }));
+        assert_eq!(captured.settled_order_id(), Some("ORDER_1"));
+        assert_eq!(captured.payer_email(), None);
+
+        let refunded = event(serde_json::json!({
+            "event_type": "PAYMENT.CAPTURE.REFUNDED",
+            "resource": { "id": "REFUND_1" }
+        }));
+        assert_eq!(refunded.settled_order_id(), None);
+    }
+}
@@ -1,14 +1,242 @@
 #![allow(dead_code)]
 
 use printpdf::*;
+use qrcode::{EcLevel, QrCode};
+use rust_decimal::prelude::ToPrimitive;
 use thiserror::Error;
 
+use crate::domain::i18n::{format_number, translate, Locale};
+use crate::domain::models::{parse_hex_color, AccountStatement, InvoiceItem, InvoiceSettings, InvoiceStatus, PaymentQr};
+
 #[derive(Debug, Error)]
 pub enum PdfError {
     #[error("PDF generation failed: {0}")]
     GenerationError(String),
 }
 
+/// Diagonal stamp drawn behind the page content
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfWatermark {
+    Draft,
+    Paid,
+    /// Withdrawn credit notes
+    Void,
+}
+
+impl PdfWatermark {
+    /// Drafts are always marked; the PAID stamp is opt-in via invoice settings
+    pub fn for_status(status: &InvoiceStatus, paid_stamp: bool) -> Option<Self> {
+        match status {
+            InvoiceStatus::Draft => Some(PdfWatermark::Draft),
+            InvoiceStatus::Paid if paid_stamp => Some(PdfWatermark::Paid),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self) -> &'static str {
+        match self {
+            PdfWatermark::Draft => "DRAFT",
+            PdfWatermark::Paid => "PAID",
+            PdfWatermark::Void => "VOID",
+        }
+    }
+
+    /// Light grey for drafts, green for paid, red for void
+    fn color(&self) -> Color {
+        match self {
+            PdfWatermark::Draft => Color::Rgb(Rgb::new(0.85, 0.85, 0.85, None)),
+            PdfWatermark::Paid => Color::Rgb(Rgb::new(0.75, 0.9, 0.75, None)),
+            PdfWatermark::Void => Color::Rgb(Rgb::new(0.95, 0.75, 0.75, None)),
+        }
+    }
+
+    /// Text ops rotated 45 degrees and centred on an A4 page
+    fn ops(&self) -> Vec<Op> {
+        let size = 96.0;
+        let angle: f32 = 45.0;
+        // Approximate Helvetica Bold advance width for capitals
+        let half_width = Mm::from(Pt(self.text().len() as f32 * size * 0.72)).0 / 2.0;
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let x = 105.0 - half_width * cos;
+        let y = 148.5 - half_width * sin;
+
+        vec![
+            Op::SaveGraphicsState,
+            Op::StartTextSection,
+            Op::SetFillColor { col: self.color() },
+            Op::SetFontSizeBuiltinFont {
+                size: Pt(size),
+                font: BuiltinFont::HelveticaBold,
+            },
+            Op::SetTextMatrix {
+                matrix: TextMatrix::TranslateRotate(Mm(x).into(), Mm(y).into(), angle),
+            },
+            Op::WriteTextBuiltinFont {
+                items: vec![TextItem::Text(self.text().to_string())],
+                font: BuiltinFont::HelveticaBold,
+            },
+            Op::EndTextSection,
+            Op::RestoreGraphicsState,
+        ]
+    }
+}
+
+/// Invoice layout, chosen by `template` in the invoice settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PdfTemplate {
+    /// Two-column header with bold section titles
+    #[default]
+    Classic,
+    /// Accent-coloured header band and table heading
+    Modern,
+    /// Light type and hairline rules, colour only on the total
+    Minimal,
+}
+
+impl PdfTemplate {
+    pub const ALL: [PdfTemplate; 3] = [PdfTemplate::Classic, PdfTemplate::Modern, PdfTemplate::Minimal];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PdfTemplate::Classic => "classic",
+            PdfTemplate::Modern => "modern",
+            PdfTemplate::Minimal => "minimal",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|template| template.as_str().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Names saved before templates existed ("default", bundle names) render as classic
+    pub fn for_settings(name: &str) -> Self {
+        Self::parse(name).unwrap_or_default()
+    }
+
+    fn default_accent(&self) -> (u8, u8, u8) {
+        match self {
+            PdfTemplate::Classic => (0, 0, 0),
+            PdfTemplate::Modern => (0x25, 0x63, 0xeb),
+            PdfTemplate::Minimal => (0x37, 0x41, 0x51),
+        }
+    }
+}
+
+/// The seller's look applied to invoice and report PDFs
+#[derive(Clone, Default)]
+pub struct PdfBranding {
+    pub template: PdfTemplate,
+    /// Overrides the template's accent colour
+    pub accent_color: Option<(u8, u8, u8)>,
+    /// Replaces the default footer line
+    pub footer_text: Option<String>,
+    /// PNG or JPEG bytes; a logo that can't be decoded is left out
+    pub logo: Option<Vec<u8>>,
+    /// Language of the invoice labels and format of its numbers
+    pub locale: Locale,
+}
+
+impl PdfBranding {
+    pub fn new(settings: &InvoiceSettings, logo: Option<Vec<u8>>) -> Self {
+        Self {
+            template: PdfTemplate::for_settings(&settings.template),
+            accent_color: settings.accent_color.as_deref().and_then(parse_hex_color),
+            footer_text: settings.footer_text.clone().filter(|text| !text.trim().is_empty()),
+            logo,
+            locale: Locale::default(),
+        }
+    }
+
+    fn accent(&self) -> Color {
+        let (r, g, b) = self.accent_color.unwrap_or_else(|| self.template.default_accent());
+        Color::Rgb(Rgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, None))
+    }
+}
+
+/// The logo is summarised so branding can be part of a cache key without its bytes
+impl std::fmt::Debug for PdfBranding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PdfBranding")
+            .field("template", &self.template)
+            .field("accent_color", &self.accent_color)
+            .field("footer_text", &self.footer_text)
+            .field("logo_bytes", &self.logo.as_ref().map(Vec::len))
+            .field("locale", &self.locale)
+            .finish()
+    }
+}
+
+/// Fonts, colours and positions that differ between templates
+struct InvoiceLayout {
+    heading: BuiltinFont,
+    body: BuiltinFont,
+    /// Text colour inside the header band and table heading
+    on_accent: Color,
+    /// Filled band across the top of the page: (bottom, height) in mm
+    band: Option<(f32, f32)>,
+    /// Filled bar behind the table headings
+    table_bar: bool,
+    /// Hairlines under the table headings and above the total
+    rules: bool,
+    title: &'static str,
+    company_y: f32,
+    address_y: f32,
+    title_y: f32,
+    /// Invoice number, issue and due date, status label
+    meta_y: [f32; 4],
+    bill_to: &'static str,
+}
+
+impl InvoiceLayout {
+    fn for_template(template: PdfTemplate) -> Self {
+        match template {
+            PdfTemplate::Classic => Self {
+                heading: BuiltinFont::HelveticaBold,
+                body: BuiltinFont::Helvetica,
+                on_accent: black(),
+                band: None,
+                table_bar: false,
+                rules: false,
+                title: "INVOICE",
+                company_y: 270.0,
+                address_y: 260.0,
+                title_y: 270.0,
+                meta_y: [260.0, 250.0, 240.0, 230.0],
+                bill_to: "BILL TO:",
+            },
+            PdfTemplate::Modern => Self {
+                heading: BuiltinFont::HelveticaBold,
+                body: BuiltinFont::Helvetica,
+                on_accent: Color::Rgb(Rgb::new(1.0, 1.0, 1.0, None)),
+                band: Some((255.0, 42.0)),
+                table_bar: true,
+                rules: false,
+                title: "INVOICE",
+                company_y: 268.0,
+                address_y: 261.0,
+                title_y: 278.0,
+                meta_y: [268.0, 245.0, 238.0, 231.0],
+                bill_to: "BILL TO",
+            },
+            PdfTemplate::Minimal => Self {
+                heading: BuiltinFont::Helvetica,
+                body: BuiltinFont::Helvetica,
+                on_accent: black(),
+                band: None,
+                table_bar: false,
+                rules: true,
+                title: "Invoice",
+                company_y: 270.0,
+                address_y: 262.0,
+                title_y: 270.0,
+                meta_y: [262.0, 255.0, 248.0, 241.0],
+                bill_to: "Billed to",
+            },
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct PdfService;
 
 impl PdfService {
@@ -16,7 +244,8 @@ impl PdfService {
         Self
     }
 
-    /// Generate a professional invoice PDF with full details
+    /// Generate a professional invoice PDF with full details, laid out with the
+    /// branding's template
     /// NOTE: Tax information is displayed for informational purposes only.
     /// FlashBill does not calculate, verify, or file taxes on your behalf.
     pub fn generate_invoice_pdf(
@@ -36,548 +265,192 @@ impl PdfService {
         total: f64,
         notes: Option<&str>,
         terms: Option<&str>,
-        tax_label: Option<&str>,
+        taxes: &[PdfTaxLine],
+        vat_notes: &[String],
+        payment_qr: Option<&PaymentQr>,
+        status_label: Option<&str>,
+        watermark: Option<PdfWatermark>,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, PdfError> {
-        // Create PDF document
         let mut doc = PdfDocument::new(&format!("Invoice {}", invoice_number));
+        let layout = InvoiceLayout::for_template(branding.template);
+        let accent = branding.accent();
+        let locale = branding.locale;
+        let label = |text: &str| translate(locale, text).into_owned();
+        let number = |value: f64| format_number(locale, value, 2);
+        let (heading, body) = (layout.heading, layout.body);
 
-        // Create operations for the page
-        let mut ops: Vec<Op> = Vec::new();
+        // The watermark goes first so content draws over it, then the coloured backgrounds
+        let mut ops: Vec<Op> = watermark.map(|w| w.ops()).unwrap_or_default();
+        if let Some((bottom, height)) = layout.band {
+            fill_rect(&mut ops, 0.0, bottom, 210.0, height, accent.clone());
+        }
+        if layout.table_bar {
+            fill_rect(&mut ops, 18.0, 178.0, 174.0, 7.0, accent.clone());
+        }
+        if let Some(logo) = &branding.logo {
+            draw_logo(&mut doc, &mut ops, logo);
+        }
 
-        // Start text section
         ops.push(Op::StartTextSection);
 
         // === HEADER ===
-        // Company Name (Bold, 18pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(18.0),
-            font: BuiltinFont::HelveticaBold,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(20.0).into(),
-                y: Mm(270.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(company_name.unwrap_or("FlashBill").to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Company Address (Regular, 9pt)
+        let header_color = if layout.band.is_some() { layout.on_accent.clone() } else { black() };
+        set_color(&mut ops, header_color.clone());
+        set_font(&mut ops, 18.0, heading);
+        write_at(&mut ops, 20.0, layout.company_y, heading, company_name.unwrap_or("FlashBill"));
         if let Some(addr) = company_address {
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(9.0),
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(260.0).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(addr.to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-        }
-
-        // Invoice Title (Bold, 20pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(20.0),
-            font: BuiltinFont::HelveticaBold,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(130.0).into(),
-                y: Mm(270.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("INVOICE".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Invoice Number (Regular, 11pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(11.0),
-            font: BuiltinFont::Helvetica,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(130.0).into(),
-                y: Mm(260.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(format!("Invoice #: {}", invoice_number))],
-            font: BuiltinFont::Helvetica,
-        });
-
-        // Issue Date (Regular, 11pt)
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(130.0).into(),
-                y: Mm(250.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(format!("Issue Date: {}", issue_date))],
-            font: BuiltinFont::Helvetica,
-        });
-
-        // Due Date (Regular, 11pt)
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(130.0).into(),
-                y: Mm(240.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(format!("Due Date: {}", due_date))],
-            font: BuiltinFont::Helvetica,
-        });
+            set_font(&mut ops, 9.0, body);
+            write_at(&mut ops, 20.0, layout.address_y, body, addr);
+        }
 
-        // === BILL TO ===
-        // "BILL TO:" (Bold, 12pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(12.0),
-            font: BuiltinFont::HelveticaBold,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(20.0).into(),
-                y: Mm(240.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("BILL TO:".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Client Name (Regular, 11pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(11.0),
-            font: BuiltinFont::Helvetica,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(20.0).into(),
-                y: Mm(230.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(client_name.to_string())],
-            font: BuiltinFont::Helvetica,
-        });
+        if layout.band.is_none() {
+            set_color(&mut ops, accent.clone());
+        }
+        set_font(&mut ops, 20.0, heading);
+        write_at(&mut ops, 130.0, layout.title_y, heading, &label(layout.title));
 
-        // Client Email (Regular, 9pt)
+        set_color(&mut ops, header_color);
+        set_font(&mut ops, 11.0, body);
+        write_at(&mut ops, 130.0, layout.meta_y[0], body, &label(&format!("Invoice #: {}", invoice_number)));
+        set_color(&mut ops, black());
+        write_at(&mut ops, 130.0, layout.meta_y[1], body, &label(&format!("Issue Date: {}", issue_date)));
+        write_at(&mut ops, 130.0, layout.meta_y[2], body, &label(&format!("Due Date: {}", due_date)));
+        // Pipeline label, only when the user opted to print it
+        if let Some(status) = status_label {
+            write_at(&mut ops, 130.0, layout.meta_y[3], body, &label(&format!("Status: {}", status)));
+        }
+
+        // === BILL TO ===
+        set_font(&mut ops, 12.0, heading);
+        write_at(&mut ops, 20.0, 240.0, heading, &label(layout.bill_to));
+        set_font(&mut ops, 11.0, body);
+        write_at(&mut ops, 20.0, 230.0, body, client_name);
+        set_font(&mut ops, 9.0, body);
         if let Some(email) = client_email {
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(9.0),
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(220.0).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(email.to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-        }
-
-        // Client Address (Regular, 9pt)
+            write_at(&mut ops, 20.0, 220.0, body, email);
+        }
         if let Some(addr) = client_address {
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(9.0),
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(210.0).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(addr.to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-        }
-
-        // === LINE ITEMS HEADER ===
-        let mut y_pos = 180.0;
-
-        // Description header (Bold, 10pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(10.0),
-            font: BuiltinFont::HelveticaBold,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(20.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("Description".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Qty header
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(110.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("Qty".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Unit Price header
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(135.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("Unit Price".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        // Total header
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(165.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("Total".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
+            write_at(&mut ops, 20.0, 210.0, body, addr);
+        }
 
         // === LINE ITEMS ===
-        y_pos -= 8.0;
-        // Set regular font for line items (9pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(9.0),
-            font: BuiltinFont::Helvetica,
-        });
+        let mut y_pos = 180.0;
+        if layout.table_bar {
+            set_color(&mut ops, layout.on_accent.clone());
+        }
+        set_font(&mut ops, 10.0, heading);
+        for (x, header) in [(20.0, "Description"), (110.0, "Qty"), (135.0, "Unit Price"), (165.0, "Total")] {
+            write_at(&mut ops, x, y_pos, heading, &label(header));
+        }
+        set_color(&mut ops, black());
+        let table_rule = y_pos - 2.5;
 
+        y_pos -= 8.0;
+        set_font(&mut ops, 9.0, body);
         for item in items {
-            // Description
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(item.description.clone())],
-                font: BuiltinFont::Helvetica,
-            });
-
-            // Qty
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(110.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(format!("{:.2}", item.quantity))],
-                font: BuiltinFont::Helvetica,
-            });
-
-            // Unit Price
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(135.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(format!("{:.2}", item.unit_price))],
-                font: BuiltinFont::Helvetica,
-            });
-
-            // Total
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(165.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(format!("{:.2}", item.total))],
-                font: BuiltinFont::Helvetica,
-            });
-
+            write_at(&mut ops, 20.0, y_pos, body, &item.description);
+            write_at(&mut ops, 110.0, y_pos, body, &number(item.quantity));
+            write_at(&mut ops, 135.0, y_pos, body, &number(item.unit_price));
+            write_at(&mut ops, 165.0, y_pos, body, &number(item.total));
             y_pos -= 8.0;
         }
 
         // === TOTALS ===
         y_pos -= 10.0;
-
-        // Subtotal (Regular, 10pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(10.0),
-            font: BuiltinFont::Helvetica,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(135.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("Subtotal:".to_string())],
-            font: BuiltinFont::Helvetica,
-        });
-
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(165.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(format!("{:.2}", subtotal))],
-            font: BuiltinFont::Helvetica,
-        });
-
-        y_pos -= 8.0;
-
-        // Tax
-        if tax_amount > 0.0 {
-            // Tax label (if provided)
-            if let Some(label) = tax_label {
-                ops.push(Op::SetTextCursor {
-                    pos: Point {
-                        x: Mm(135.0).into(),
-                        y: Mm(y_pos).into(),
-                    },
-                });
-                ops.push(Op::WriteTextBuiltinFont {
-                    items: vec![TextItem::Text(format!("{}:", label))],
-                    font: BuiltinFont::Helvetica,
-                });
-            } else {
-                ops.push(Op::SetTextCursor {
-                    pos: Point {
-                        x: Mm(135.0).into(),
-                        y: Mm(y_pos).into(),
-                    },
-                });
-                ops.push(Op::WriteTextBuiltinFont {
-                    items: vec![TextItem::Text("Tax:".to_string())],
-                    font: BuiltinFont::Helvetica,
-                });
-            }
-
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(165.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(format!("{:.2}", tax_amount))],
-                font: BuiltinFont::Helvetica,
-            });
-
-            y_pos -= 8.0;
+        set_font(&mut ops, 10.0, body);
+        let mut rows = vec![(label("Subtotal:"), number(subtotal))];
+        if tax_amount > 0.0 && taxes.is_empty() {
+            rows.push((format!("{}:", label("Tax")), number(tax_amount)));
+        }
+        // Tax labels the user set are printed as they wrote them
+        for tax in taxes {
+            rows.push((format!("{}:", tax.label), number(tax.amount)));
         }
-
-        // Discount
         if discount > 0.0 {
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(135.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text("Discount:".to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(165.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(format!("-{:.2}", discount))],
-                font: BuiltinFont::Helvetica,
-            });
-
+            rows.push((label("Discount:"), format!("-{}", number(discount))));
+        }
+        for (row_label, amount) in rows {
+            write_at(&mut ops, 135.0, y_pos, body, &row_label);
+            write_at(&mut ops, 165.0, y_pos, body, &amount);
             y_pos -= 8.0;
         }
 
-        // Total (Bold, 12pt)
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(12.0),
-            font: BuiltinFont::HelveticaBold,
-        });
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(135.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text("TOTAL:".to_string())],
-            font: BuiltinFont::HelveticaBold,
-        });
-
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(165.0).into(),
-                y: Mm(y_pos).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(format!("{:.2}", total))],
-            font: BuiltinFont::HelveticaBold,
-        });
+        let total_rule = y_pos + 5.0;
+        set_color(&mut ops, accent);
+        set_font(&mut ops, 12.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 135.0, y_pos, BuiltinFont::HelveticaBold, &label("TOTAL:"));
+        write_at(&mut ops, 165.0, y_pos, BuiltinFont::HelveticaBold, &number(total));
+        set_color(&mut ops, black());
+
+        // === PAYMENT QR CODE === under the total, kept clear of the footer
+        let qr_code = payment_qr
+            .and_then(|qr| QrCode::with_error_correction_level(&qr.payload, EcLevel::M).ok().map(|code| (code, qr.caption)));
+        let qr_top = (y_pos - 8.0).max(QR_MIN_BOTTOM + QR_SIZE);
+        if let Some((_, caption)) = &qr_code {
+            let caption = label(caption);
+            set_font(&mut ops, 8.0, body);
+            write_at(&mut ops, right_aligned(190.0, &caption, 8.0), qr_top - QR_SIZE - 4.0, body, &caption);
+        }
 
         // === NOTES & TERMS ===
         y_pos -= 15.0;
-
-        if let Some(notes_text) = notes {
-            // Notes header (Bold, 10pt)
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(10.0),
-                font: BuiltinFont::HelveticaBold,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text("Notes:".to_string())],
-                font: BuiltinFont::HelveticaBold,
-            });
-
+        for (title, text) in [("Notes:", notes), ("Terms:", terms)] {
+            let Some(text) = text else { continue };
+            set_font(&mut ops, 10.0, heading);
+            write_at(&mut ops, 20.0, y_pos, heading, &label(title));
             y_pos -= 8.0;
-            // Notes text (Regular, 9pt)
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(9.0),
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(notes_text.to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-
+            set_font(&mut ops, 9.0, body);
+            write_at(&mut ops, 20.0, y_pos, body, text);
             y_pos -= 10.0;
         }
 
-        if let Some(terms_text) = terms {
-            // Terms header (Bold, 10pt)
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(10.0),
-                font: BuiltinFont::HelveticaBold,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text("Terms:".to_string())],
-                font: BuiltinFont::HelveticaBold,
-            });
-
+        // VAT statements the law asks for, such as the reverse-charge note, one per line
+        if !vat_notes.is_empty() {
+            set_font(&mut ops, 10.0, heading);
+            write_at(&mut ops, 20.0, y_pos, heading, &label("VAT:"));
             y_pos -= 8.0;
-            // Terms text (Regular, 9pt)
-            ops.push(Op::SetFontSizeBuiltinFont {
-                size: Pt(9.0),
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(y_pos).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(terms_text.to_string())],
-                font: BuiltinFont::Helvetica,
-            });
-        }
-
-        // End text section
+            set_font(&mut ops, 9.0, body);
+            for line in vat_notes {
+                write_at(&mut ops, 20.0, y_pos, body, line);
+                y_pos -= 6.0;
+            }
+        }
+
         ops.push(Op::EndTextSection);
 
         // === FOOTER ===
-        // Add footer text
         ops.push(Op::StartTextSection);
-        ops.push(Op::SetFontSizeBuiltinFont {
-            size: Pt(8.0),
-            font: BuiltinFont::Helvetica,
-        });
+        set_font(&mut ops, 8.0, BuiltinFont::Helvetica);
 
         // Legal disclaimer about tax (only if tax is present)
         if tax_amount > 0.0 {
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(25.0).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(
-                    "Tax information is for informational purposes only. FlashBill does not".to_string(),
-                )],
-                font: BuiltinFont::Helvetica,
-            });
-            ops.push(Op::SetTextCursor {
-                pos: Point {
-                    x: Mm(20.0).into(),
-                    y: Mm(20.0).into(),
-                },
-            });
-            ops.push(Op::WriteTextBuiltinFont {
-                items: vec![TextItem::Text(
-                    "calculate, verify, or file taxes on your behalf.".to_string(),
-                )],
-                font: BuiltinFont::Helvetica,
-            });
-        }
-
-        // Main footer text
-        ops.push(Op::SetTextCursor {
-            pos: Point {
-                x: Mm(20.0).into(),
-                y: Mm(15.0).into(),
-            },
-        });
-        ops.push(Op::WriteTextBuiltinFont {
-            items: vec![TextItem::Text(
-                "Generated by FlashBill - Thank you for your business!".to_string(),
-            )],
-            font: BuiltinFont::Helvetica,
-        });
+            write_at(
+                &mut ops,
+                20.0,
+                25.0,
+                BuiltinFont::Helvetica,
+                &label("Tax information is for informational purposes only. FlashBill does not"),
+            );
+            write_at(&mut ops, 20.0, 20.0, BuiltinFont::Helvetica, &label("calculate, verify, or file taxes on your behalf."));
+        }
+
+        let footer = match &branding.footer_text {
+            Some(text) => text.clone(),
+            None => label("Generated by FlashBill - Thank you for your business!"),
+        };
+        write_at(&mut ops, 20.0, 15.0, BuiltinFont::Helvetica, &footer);
         ops.push(Op::EndTextSection);
 
+        if layout.rules {
+            for y in [table_rule, total_rule] {
+                draw_rule(&mut ops, 20.0, 190.0, y);
+            }
+        }
+        if let Some((code, _)) = &qr_code {
+            draw_qr_code(&mut ops, code, 190.0 - QR_SIZE, qr_top, QR_SIZE);
+        }
+
         // Create the page with A4 dimensions
         let page = PdfPage::new(Mm(210.0), Mm(297.0), ops);
         doc.pages.push(page);
@@ -589,11 +462,596 @@ impl PdfService {
 
         Ok(output)
     }
+
+    /// Credit note PDF: references the credited invoice and shows how much is
+    /// refunded rather than settled against its balance
+    pub fn generate_credit_note_pdf(&self, note: &CreditNotePdf) -> Result<Vec<u8>, PdfError> {
+        let mut doc = PdfDocument::new(&format!("Credit Note {}", note.credit_note_number));
+
+        let mut ops: Vec<Op> = if note.void { PdfWatermark::Void.ops() } else { Vec::new() };
+        ops.push(Op::StartTextSection);
+
+        // === HEADER ===
+        set_font(&mut ops, 18.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 20.0, 270.0, BuiltinFont::HelveticaBold, note.company_name.unwrap_or("FlashBill"));
+        if let Some(addr) = note.company_address {
+            set_font(&mut ops, 9.0, BuiltinFont::Helvetica);
+            write_at(&mut ops, 20.0, 260.0, BuiltinFont::Helvetica, addr);
+        }
+
+        set_font(&mut ops, 20.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 130.0, 270.0, BuiltinFont::HelveticaBold, "CREDIT NOTE");
+        set_font(&mut ops, 11.0, BuiltinFont::Helvetica);
+        write_at(&mut ops, 130.0, 260.0, BuiltinFont::Helvetica, &format!("Credit Note #: {}", note.credit_note_number));
+        write_at(&mut ops, 130.0, 250.0, BuiltinFont::Helvetica, &format!("Issue Date: {}", note.issue_date));
+        write_at(&mut ops, 130.0, 240.0, BuiltinFont::Helvetica, &format!("Credits Invoice #: {}", note.invoice_number));
+
+        // === CREDIT TO ===
+        set_font(&mut ops, 12.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 20.0, 240.0, BuiltinFont::HelveticaBold, "CREDIT TO:");
+        set_font(&mut ops, 11.0, BuiltinFont::Helvetica);
+        write_at(&mut ops, 20.0, 230.0, BuiltinFont::Helvetica, note.client_name);
+        set_font(&mut ops, 9.0, BuiltinFont::Helvetica);
+        if let Some(email) = note.client_email {
+            write_at(&mut ops, 20.0, 220.0, BuiltinFont::Helvetica, email);
+        }
+        if let Some(addr) = note.client_address {
+            write_at(&mut ops, 20.0, 210.0, BuiltinFont::Helvetica, addr);
+        }
+
+        let mut y_pos = 195.0;
+        if let Some(reason) = note.reason {
+            set_font(&mut ops, 10.0, BuiltinFont::HelveticaBold);
+            write_at(&mut ops, 20.0, y_pos, BuiltinFont::HelveticaBold, "Reason:");
+            set_font(&mut ops, 10.0, BuiltinFont::Helvetica);
+            write_at(&mut ops, 40.0, y_pos, BuiltinFont::Helvetica, reason);
+        }
+
+        // === LINE ITEMS ===
+        y_pos -= 15.0;
+        set_font(&mut ops, 10.0, BuiltinFont::HelveticaBold);
+        for (x, header) in [(20.0, "Description"), (110.0, "Qty"), (135.0, "Unit Price"), (165.0, "Credit")] {
+            write_at(&mut ops, x, y_pos, BuiltinFont::HelveticaBold, header);
+        }
+
+        y_pos -= 8.0;
+        set_font(&mut ops, 9.0, BuiltinFont::Helvetica);
+        for item in note.items {
+            write_at(&mut ops, 20.0, y_pos, BuiltinFont::Helvetica, &item.description);
+            write_at(&mut ops, 110.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", item.quantity));
+            write_at(&mut ops, 135.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", item.unit_price));
+            write_at(&mut ops, 165.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", item.total));
+            y_pos -= 8.0;
+        }
+
+        // === TOTALS ===
+        y_pos -= 10.0;
+        set_font(&mut ops, 10.0, BuiltinFont::Helvetica);
+        let mut rows = vec![("Subtotal:", format!("{:.2}", note.subtotal))];
+        if note.tax_amount > 0.0 {
+            rows.push(("Tax:", format!("{:.2}", note.tax_amount)));
+        }
+        if note.discount > 0.0 {
+            rows.push(("Discount:", format!("-{:.2}", note.discount)));
+        }
+        for (label, amount) in rows {
+            write_at(&mut ops, 135.0, y_pos, BuiltinFont::Helvetica, label);
+            write_at(&mut ops, 165.0, y_pos, BuiltinFont::Helvetica, &amount);
+            y_pos -= 8.0;
+        }
+
+        set_font(&mut ops, 12.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 120.0, y_pos, BuiltinFont::HelveticaBold, "TOTAL CREDIT:");
+        write_at(&mut ops, 165.0, y_pos, BuiltinFont::HelveticaBold, &format!("{:.2} {}", note.total, note.currency));
+
+        // Whatever the invoice balance didn't absorb is paid back to the client
+        if note.refund_amount > 0.0 {
+            y_pos -= 8.0;
+            set_font(&mut ops, 10.0, BuiltinFont::Helvetica);
+            write_at(&mut ops, 120.0, y_pos, BuiltinFont::Helvetica, "To be refunded:");
+            write_at(&mut ops, 165.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2} {}", note.refund_amount, note.currency));
+        }
+
+        // === FOOTER ===
+        set_font(&mut ops, 8.0, BuiltinFont::Helvetica);
+        write_at(
+            &mut ops,
+            20.0,
+            15.0,
+            BuiltinFont::Helvetica,
+            &format!("This credit note reduces the amount due on invoice {}. Generated by FlashBill.", note.invoice_number),
+        );
+        ops.push(Op::EndTextSection);
+
+        doc.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), ops));
+
+        let opts = PdfSaveOptions::default();
+        let mut warnings = Vec::new();
+        Ok(doc.save(&opts, &mut warnings))
+    }
+
+    /// Statement of account: opening balance, each invoice, payment and credit in
+    /// the period with the running balance, and the closing balance. Long
+    /// statements continue on further pages.
+    pub fn generate_statement_pdf(&self, statement: &StatementPdf) -> Result<Vec<u8>, PdfError> {
+        let account = statement.statement;
+        let mut doc = PdfDocument::new(&format!("Statement for {}", account.client_name));
+        let mut ops: Vec<Op> = vec![Op::StartTextSection];
+
+        // === HEADER ===
+        set_font(&mut ops, 18.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 20.0, 270.0, BuiltinFont::HelveticaBold, statement.company_name.unwrap_or("FlashBill"));
+        if let Some(addr) = statement.company_address {
+            set_font(&mut ops, 9.0, BuiltinFont::Helvetica);
+            write_at(&mut ops, 20.0, 260.0, BuiltinFont::Helvetica, addr);
+        }
+
+        set_font(&mut ops, 20.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 130.0, 270.0, BuiltinFont::HelveticaBold, "STATEMENT");
+        set_font(&mut ops, 11.0, BuiltinFont::Helvetica);
+        write_at(&mut ops, 130.0, 260.0, BuiltinFont::Helvetica, &format!("From: {}", account.start_date));
+        write_at(&mut ops, 130.0, 250.0, BuiltinFont::Helvetica, &format!("To: {}", account.end_date));
+
+        // === STATEMENT FOR ===
+        set_font(&mut ops, 12.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 20.0, 240.0, BuiltinFont::HelveticaBold, "STATEMENT FOR:");
+        set_font(&mut ops, 11.0, BuiltinFont::Helvetica);
+        write_at(&mut ops, 20.0, 230.0, BuiltinFont::Helvetica, &account.client_name);
+        set_font(&mut ops, 9.0, BuiltinFont::Helvetica);
+        if let Some(email) = &account.client_email {
+            write_at(&mut ops, 20.0, 220.0, BuiltinFont::Helvetica, email);
+        }
+        if let Some(addr) = statement.client_address {
+            write_at(&mut ops, 20.0, 210.0, BuiltinFont::Helvetica, addr);
+        }
+
+        // === TRANSACTIONS ===
+        let columns = [(20.0, "Date"), (45.0, "Reference"), (80.0, "Description"), (140.0, "Debit"), (160.0, "Credit"), (180.0, "Balance")];
+        let write_headers = |ops: &mut Vec<Op>, y: f32| {
+            set_font(ops, 10.0, BuiltinFont::HelveticaBold);
+            for (x, header) in columns {
+                write_at(ops, x, y, BuiltinFont::HelveticaBold, header);
+            }
+            set_font(ops, 9.0, BuiltinFont::Helvetica);
+        };
+
+        let mut y_pos = 190.0;
+        write_headers(&mut ops, y_pos);
+        y_pos -= 8.0;
+        write_at(&mut ops, 20.0, y_pos, BuiltinFont::Helvetica, &account.start_date.to_string());
+        write_at(&mut ops, 80.0, y_pos, BuiltinFont::Helvetica, "Opening balance");
+        write_at(&mut ops, 180.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", account.opening_balance));
+        y_pos -= 8.0;
+
+        let amount = |value: f64| if value > 0.0 { format!("{:.2}", value) } else { String::new() };
+        for line in &account.lines {
+            if y_pos < 30.0 {
+                ops.push(Op::EndTextSection);
+                doc.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), std::mem::take(&mut ops)));
+                ops.push(Op::StartTextSection);
+                y_pos = 275.0;
+                write_headers(&mut ops, y_pos);
+                y_pos -= 8.0;
+            }
+
+            write_at(&mut ops, 20.0, y_pos, BuiltinFont::Helvetica, &line.date.to_string());
+            write_at(&mut ops, 45.0, y_pos, BuiltinFont::Helvetica, &line.reference);
+            write_at(&mut ops, 80.0, y_pos, BuiltinFont::Helvetica, &line.description);
+            write_at(&mut ops, 140.0, y_pos, BuiltinFont::Helvetica, &amount(line.debit));
+            write_at(&mut ops, 160.0, y_pos, BuiltinFont::Helvetica, &amount(line.credit));
+            write_at(&mut ops, 180.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", line.balance));
+            y_pos -= 8.0;
+        }
+
+        // === TOTALS ===
+        y_pos -= 6.0;
+        set_font(&mut ops, 10.0, BuiltinFont::Helvetica);
+        for (label, value) in [("Invoiced:", account.total_invoiced), ("Received:", account.total_received)] {
+            write_at(&mut ops, 135.0, y_pos, BuiltinFont::Helvetica, label);
+            write_at(&mut ops, 165.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", value));
+            y_pos -= 8.0;
+        }
+        set_font(&mut ops, 12.0, BuiltinFont::HelveticaBold);
+        write_at(&mut ops, 120.0, y_pos, BuiltinFont::HelveticaBold, "BALANCE DUE:");
+        write_at(&mut ops, 165.0, y_pos, BuiltinFont::HelveticaBold, &format!("{:.2}", account.closing_balance));
+        set_font(&mut ops, 10.0, BuiltinFont::Helvetica);
+        for credit in &account.credit_balances {
+            y_pos -= 8.0;
+            write_at(&mut ops, 120.0, y_pos, BuiltinFont::Helvetica, &format!("Credit available ({}):", credit.currency));
+            write_at(&mut ops, 165.0, y_pos, BuiltinFont::Helvetica, &format!("{:.2}", credit.amount));
+        }
+
+        // === FOOTER ===
+        set_font(&mut ops, 8.0, BuiltinFont::Helvetica);
+        write_at(&mut ops, 20.0, 15.0, BuiltinFont::Helvetica, "Please contact us if anything on this statement looks wrong. Generated by FlashBill.");
+        ops.push(Op::EndTextSection);
+
+        doc.pages.push(PdfPage::new(Mm(210.0), Mm(297.0), ops));
+
+        let opts = PdfSaveOptions::default();
+        let mut warnings = Vec::new();
+        Ok(doc.save(&opts, &mut warnings))
+    }
+}
+
+fn set_font(ops: &mut Vec<Op>, size: f32, font: BuiltinFont) {
+    ops.push(Op::SetFontSizeBuiltinFont { size: Pt(size), font });
+}
+
+fn black() -> Color {
+    Color::Rgb(Rgb::new(0.0, 0.0, 0.0, None))
+}
+
+fn set_color(ops: &mut Vec<Op>, col: Color) {
+    ops.push(Op::SetFillColor { col });
 }
 
+/// Filled rectangle; must be drawn outside a text section
+fn fill_rect(ops: &mut Vec<Op>, x: f32, bottom: f32, width: f32, height: f32, col: Color) {
+    let rect = Rect {
+        x: Mm(x).into(),
+        y: Mm(bottom + height).into(),
+        width: Mm(width).into(),
+        height: Mm(height).into(),
+    };
+    ops.push(Op::SaveGraphicsState);
+    ops.push(Op::SetFillColor { col });
+    ops.push(Op::DrawPolygon { polygon: rect.to_polygon() });
+    ops.push(Op::RestoreGraphicsState);
+}
+
+/// Side of the payment QR code in mm
+const QR_SIZE: f32 = 30.0;
+/// Lowest the QR code goes, above its caption and the footer
+const QR_MIN_BOTTOM: f32 = 34.0;
+
+/// Dark modules as black squares, one rectangle per run along a row; must be
+/// drawn outside a text section. The white page is the quiet zone.
+fn draw_qr_code(ops: &mut Vec<Op>, code: &QrCode, x: f32, top: f32, size: f32) {
+    let width = code.width();
+    let module = size / width as f32;
+    ops.push(Op::SaveGraphicsState);
+    ops.push(Op::SetFillColor { col: black() });
+    for row in 0..width {
+        let mut column = 0;
+        while column < width {
+            if code[(column, row)] != qrcode::Color::Dark {
+                column += 1;
+                continue;
+            }
+            let start = column;
+            while column < width && code[(column, row)] == qrcode::Color::Dark {
+                column += 1;
+            }
+            let rect = Rect {
+                x: Mm(x + start as f32 * module).into(),
+                y: Mm(top - row as f32 * module).into(),
+                width: Mm((column - start) as f32 * module).into(),
+                height: Mm(module).into(),
+            };
+            ops.push(Op::DrawPolygon { polygon: rect.to_polygon() });
+        }
+    }
+    ops.push(Op::RestoreGraphicsState);
+}
+
+/// Where text ending at `right` starts, from an average Helvetica advance of half the font size
+fn right_aligned(right: f32, text: &str, size: f32) -> f32 {
+    right - Mm::from(Pt(text.chars().count() as f32 * size * 0.5)).0
+}
+
+/// Thin grey horizontal line; must be drawn outside a text section
+fn draw_rule(ops: &mut Vec<Op>, from_x: f32, to_x: f32, y: f32) {
+    let point = |x: f32| LinePoint {
+        p: Point { x: Mm(x).into(), y: Mm(y).into() },
+        bezier: false,
+    };
+    ops.push(Op::SaveGraphicsState);
+    ops.push(Op::SetOutlineColor { col: Color::Rgb(Rgb::new(0.8, 0.8, 0.8, None)) });
+    ops.push(Op::SetOutlineThickness { pt: Pt(0.5) });
+    ops.push(Op::DrawLine {
+        line: Line { points: vec![point(from_x), point(to_x)], is_closed: false },
+    });
+    ops.push(Op::RestoreGraphicsState);
+}
+
+/// Logo in the top-left corner, scaled to fit 50 x 14 mm
+fn draw_logo(doc: &mut PdfDocument, ops: &mut Vec<Op>, logo: &[u8]) {
+    let mut warnings = Vec::new();
+    let image = match RawImage::decode_from_bytes(logo, &mut warnings) {
+        Ok(image) if image.width > 0 && image.height > 0 => image,
+        Ok(_) => return,
+        Err(e) => {
+            tracing::warn!("Skipping logo that can't be decoded: {}", e);
+            return;
+        }
+    };
+
+    // At a given dpi one pixel is 72/dpi points; pick the dpi that fits the box
+    let (max_width, max_height) = (Pt::from(Mm(50.0)).0, Pt::from(Mm(14.0)).0);
+    let dpi = (image.width as f32 * 72.0 / max_width).max(image.height as f32 * 72.0 / max_height);
+    let height = image.height as f32 * 72.0 / dpi;
+
+    let id = doc.add_image(&image);
+    ops.push(Op::UseXobject {
+        id,
+        transform: XObjectTransform {
+            translate_x: Some(Mm(20.0).into()),
+            translate_y: Some(Pt(Pt::from(Mm(291.0)).0 - height)),
+            dpi: Some(dpi),
+            ..Default::default()
+        },
+    });
+}
+
+fn write_at(ops: &mut Vec<Op>, x: f32, y: f32, font: BuiltinFont, text: &str) {
+    ops.push(Op::SetTextCursor {
+        pos: Point {
+            x: Mm(x).into(),
+            y: Mm(y).into(),
+        },
+    });
+    ops.push(Op::WriteTextBuiltinFont {
+        items: vec![TextItem::Text(text.to_string())],
+        font,
+    });
+}
+
+/// What goes on a credit note PDF
+pub struct CreditNotePdf<'a> {
+    pub credit_note_number: &'a str,
+    pub invoice_number: &'a str,
+    pub company_name: Option<&'a str>,
+    pub company_address: Option<&'a str>,
+    pub client_name: &'a str,
+    pub client_email: Option<&'a str>,
+    pub client_address: Option<&'a str>,
+    pub issue_date: &'a str,
+    pub reason: Option<&'a str>,
+    pub items: &'a [InvoiceItemPdf],
+    pub subtotal: f64,
+    pub tax_amount: f64,
+    pub discount: f64,
+    pub total: f64,
+    /// Part of the credit paid back rather than settled against the invoice
+    pub refund_amount: f64,
+    pub currency: &'a str,
+    pub void: bool,
+}
+
+/// What goes on a statement of account PDF
+pub struct StatementPdf<'a> {
+    pub statement: &'a AccountStatement,
+    pub company_name: Option<&'a str>,
+    pub company_address: Option<&'a str>,
+    pub client_address: Option<&'a str>,
+}
+
+/// A tax row in the invoice totals
+#[derive(Debug)]
+pub struct PdfTaxLine {
+    pub label: String,
+    pub amount: f64,
+}
+
+#[derive(Debug)]
 pub struct InvoiceItemPdf {
     pub description: String,
     pub quantity: f64,
     pub unit_price: f64,
     pub total: f64,
 }
+
+impl From<&InvoiceItem> for InvoiceItemPdf {
+    fn from(item: &InvoiceItem) -> Self {
+        Self {
+            description: item.description.clone(),
+            quantity: item.quantity.to_f64().unwrap_or_default(),
+            unit_price: item.unit_price.to_f64().unwrap_or_default(),
+            total: item.total.to_f64().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermark_follows_status() {
+        assert_eq!(PdfWatermark::for_status(&InvoiceStatus::Draft, false), Some(PdfWatermark::Draft));
+        assert_eq!(PdfWatermark::for_status(&InvoiceStatus::Paid, false), None);
+        assert_eq!(PdfWatermark::for_status(&InvoiceStatus::Paid, true), Some(PdfWatermark::Paid));
+        assert_eq!(PdfWatermark::for_status(&InvoiceStatus::Sent, true), None);
+        assert_eq!(PdfWatermark::for_status(&InvoiceStatus::Partial, true), None);
+    }
+
+    #[test]
+    fn test_credit_note_pdf_renders() {
+        let items = vec![InvoiceItemPdf {
+            description: "Returned widget".to_string(),
+            quantity: 2.0,
+            unit_price: 15.0,
+            total: 30.0,
+        }];
+        let pdf = PdfService::new()
+            .generate_credit_note_pdf(&CreditNotePdf {
+                credit_note_number: "CN-2026-0001",
+                invoice_number: "INV-2026-0001",
+                company_name: Some("Acme"),
+                company_address: None,
+                client_name: "Client",
+                client_email: None,
+                client_address: None,
+                issue_date: "2026-10-18",
+                reason: Some("Damaged in transit"),
+                items: &items,
+                subtotal: 30.0,
+                tax_amount: 0.0,
+                discount: 0.0,
+                total: 30.0,
+                refund_amount: 10.0,
+                currency: "USD",
+                void: true,
+            })
+            .unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn test_statement_pdf_spans_pages() {
+        use crate::domain::models::{StatementEntry, StatementEntryKind};
+
+        let start = chrono::NaiveDate::from_ymd_opt(2026, 1, 1).unwrap();
+        let entries = (0..60)
+            .map(|n| StatementEntry {
+                date: start + chrono::Duration::days(n),
+                kind: StatementEntryKind::Invoice,
+                reference: format!("INV-{:04}", n),
+                description: "Invoice".to_string(),
+                invoice_id: uuid::Uuid::new_v4(),
+                debit: 10.0,
+                credit: 0.0,
+            })
+            .collect();
+        let account = AccountStatement::build(
+            uuid::Uuid::new_v4(),
+            "Client".to_string(),
+            None,
+            start,
+            start + chrono::Duration::days(90),
+            entries,
+        );
+
+        let pdf = PdfService::new()
+            .generate_statement_pdf(&StatementPdf {
+                statement: &account,
+                company_name: Some("Acme"),
+                company_address: None,
+                client_address: None,
+            })
+            .unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+        // 61 lines don't fit on one page
+        assert!(pdf.windows(b"/Count 3".len()).any(|w| w == b"/Count 3"));
+    }
+
+    fn png_logo() -> Vec<u8> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        ::image::DynamicImage::ImageRgb8(::image::RgbImage::from_pixel(40, 10, ::image::Rgb([200, 30, 30])))
+            .write_to(&mut png, ::image::ImageFormat::Png)
+            .unwrap();
+        png.into_inner()
+    }
+
+    fn has_image(pdf: &[u8]) -> bool {
+        pdf.windows(b"/Subtype/Image".len()).any(|w| w == b"/Subtype/Image")
+    }
+
+    fn invoice_pdf(branding: &PdfBranding) -> Vec<u8> {
+        invoice_pdf_with_qr(branding, None)
+    }
+
+    fn invoice_pdf_with_qr(branding: &PdfBranding, payment_qr: Option<&PaymentQr>) -> Vec<u8> {
+        let items = vec![InvoiceItemPdf {
+            description: "Design work".to_string(),
+            quantity: 3.0,
+            unit_price: 100.0,
+            total: 300.0,
+        }];
+        PdfService::new()
+            .generate_invoice_pdf(
+                "INV-2026-0001", Some("Acme"), Some("1 Main St"), "Client", Some("client@example.com"), None,
+                "2026-10-01", "2026-10-31", &items, 300.0, 30.0, 0.0, 330.0, Some("Thanks"), Some("Net 30"),
+                &[], &[], payment_qr, None, None, branding,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_template_names() {
+        assert_eq!(PdfTemplate::parse("Modern"), Some(PdfTemplate::Modern));
+        assert_eq!(PdfTemplate::parse("premium"), None);
+        // Settings saved before templates existed keep working
+        assert_eq!(PdfTemplate::for_settings("default"), PdfTemplate::Classic);
+        for template in PdfTemplate::ALL {
+            assert_eq!(PdfTemplate::parse(template.as_str()), Some(template));
+        }
+    }
+
+    #[test]
+    fn test_every_template_renders_with_branding() {
+        for template in PdfTemplate::ALL {
+            let branding = PdfBranding {
+                template,
+                accent_color: Some((0x0f, 0x76, 0x6e)),
+                footer_text: Some("Acme Ltd - Company no. 123456".to_string()),
+                logo: Some(png_logo()),
+                // Labels with characters outside ASCII
+                locale: Locale::De,
+            };
+            let pdf = invoice_pdf(&branding);
+            assert!(pdf.starts_with(b"%PDF"), "{:?} did not render", template);
+            assert!(has_image(&pdf), "{:?} has no logo", template);
+        }
+    }
+
+    #[test]
+    fn test_unreadable_logo_is_left_out() {
+        let branding = PdfBranding { logo: Some(b"not an image".to_vec()), ..Default::default() };
+        let pdf = invoice_pdf(&branding);
+        assert!(pdf.starts_with(b"%PDF"));
+        assert!(!has_image(&pdf));
+    }
+
+    #[test]
+    fn test_branding_from_settings() {
+        let settings = InvoiceSettings {
+            template: "minimal".to_string(),
+            accent_color: Some("#0F766E".to_string()),
+            footer_text: Some("  ".to_string()),
+            ..Default::default()
+        };
+        let branding = PdfBranding::new(&settings, None);
+        assert_eq!(branding.template, PdfTemplate::Minimal);
+        assert_eq!(branding.accent_color, Some((0x0f, 0x76, 0x6e)));
+        assert_eq!(branding.footer_text, None);
+    }
+
+    #[test]
+    fn test_payment_qr_code_is_drawn() {
+        let branding = PdfBranding::default();
+        let plain = invoice_pdf(&branding);
+        let qr = PaymentQr { payload: "https://yourapp.com/guest/pay/abc".to_string(), caption: "Scan to pay online" };
+        let with_qr = invoice_pdf_with_qr(&branding, Some(&qr));
+        assert!(with_qr.starts_with(b"%PDF"));
+        assert!(with_qr.len() > plain.len());
+
+        // More than a QR code holds is left out rather than failing the PDF
+        let too_long = PaymentQr { payload: "x".repeat(5000), caption: "Scan to pay online" };
+        assert_eq!(invoice_pdf_with_qr(&branding, Some(&too_long)).len(), plain.len());
+    }
+
+    #[test]
+    fn test_qr_code_runs_are_merged() {
+        let code = QrCode::with_error_correction_level("https://yourapp.com/guest/pay/abc", EcLevel::M).unwrap();
+        let dark = code.to_colors().iter().filter(|color| **color == qrcode::Color::Dark).count();
+        let mut ops = Vec::new();
+        draw_qr_code(&mut ops, &code, 160.0, 64.0, QR_SIZE);
+        let rects = ops.iter().filter(|op| matches!(op, Op::DrawPolygon { .. })).count();
+        assert!(rects > 0 && rects < dark);
+        assert!(matches!(ops.first(), Some(Op::SaveGraphicsState)));
+        assert!(matches!(ops.last(), Some(Op::RestoreGraphicsState)));
+    }
+
+    #[test]
+    fn test_watermark_ops_are_self_contained() {
+        let ops = PdfWatermark::Draft.ops();
+        assert!(matches!(ops.first(), Some(Op::SaveGraphicsState)));
+        assert!(matches!(ops.last(), Some(Op::RestoreGraphicsState)));
+        assert!(ops.iter().any(|op| matches!(
+            op,
+            Op::WriteTextBuiltinFont { items, .. } if matches!(items.as_slice(), [TextItem::Text(t)] if t == "DRAFT")
+        )));
+    }
+}
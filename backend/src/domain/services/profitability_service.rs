@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{AddInvoiceCost, ClientMarginReport, InvoiceCost, InvoiceProfitability};
+use crate::infrastructure::repositories::InvoiceCostRepository;
+
+/// Upper bound on hours in a single time cost entry
+const MAX_COST_HOURS: f64 = 1000.0;
+
+#[derive(Debug, Error)]
+pub enum ProfitabilityError {
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("Expense not found")]
+    ExpenseNotFound,
+
+    #[error("Cost not found")]
+    CostNotFound,
+
+    #[error("Expense is already linked to an invoice")]
+    ExpenseAlreadyLinked,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ProfitabilityError {
+    fn from(err: sqlx::Error) -> Self {
+        ProfitabilityError::DatabaseError(err.to_string())
+    }
+}
+
+pub struct ProfitabilityService {
+    repo: InvoiceCostRepository,
+}
+
+impl ProfitabilityService {
+    pub fn new(repo: InvoiceCostRepository) -> Self {
+        Self { repo }
+    }
+
+    pub async fn add_cost(&self, user_id: Uuid, invoice_id: Uuid, cost: AddInvoiceCost) -> Result<InvoiceCost, ProfitabilityError> {
+        if self.repo.invoice_revenue(user_id, invoice_id).await?.is_none() {
+            return Err(ProfitabilityError::InvoiceNotFound);
+        }
+
+        match cost {
+            AddInvoiceCost::Expense { expense_id } => self
+                .repo
+                .add_expense(user_id, invoice_id, expense_id)
+                .await
+                .map_err(|e| match e {
+                    sqlx::Error::Database(db) if db.is_unique_violation() => ProfitabilityError::ExpenseAlreadyLinked,
+                    other => other.into(),
+                })?
+                .ok_or(ProfitabilityError::ExpenseNotFound),
+            AddInvoiceCost::Time { description, hours, hourly_cost, work_date } => {
+                if !hours.is_finite() || hours <= 0.0 || hours > MAX_COST_HOURS {
+                    return Err(ProfitabilityError::Validation(format!(
+                        "Hours must be more than 0 and at most {}",
+                        MAX_COST_HOURS
+                    )));
+                }
+                if !hourly_cost.is_finite() || hourly_cost < 0.0 {
+                    return Err(ProfitabilityError::Validation("Hourly cost must be zero or positive".to_string()));
+                }
+                let description = description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+
+                Ok(self
+                    .repo
+                    .add_time(user_id, invoice_id, description, hours, hourly_cost, work_date)
+                    .await?)
+            }
+        }
+    }
+
+    pub async fn remove_cost(&self, user_id: Uuid, invoice_id: Uuid, cost_id: Uuid) -> Result<(), ProfitabilityError> {
+        if !self.repo.remove(user_id, invoice_id, cost_id).await? {
+            return Err(ProfitabilityError::CostNotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn invoice_profitability(&self, user_id: Uuid, invoice_id: Uuid) -> Result<InvoiceProfitability, ProfitabilityError> {
+        let invoice = self
+            .repo
+            .invoice_revenue(user_id, invoice_id)
+            .await?
+            .ok_or(ProfitabilityError::InvoiceNotFound)?;
+        let costs = self.repo.list(user_id, invoice_id).await?;
+
+        Ok(InvoiceProfitability::new(
+            invoice_id,
+            invoice.invoice_number,
+            invoice.currency,
+            invoice.revenue,
+            costs,
+        ))
+    }
+
+    pub async fn margin_by_client(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<ClientMarginReport, ProfitabilityError> {
+        if start_date > end_date {
+            return Err(ProfitabilityError::Validation("start_date must not be after end_date".to_string()));
+        }
+
+        let clients = self.repo.margin_by_client(user_id, start_date, end_date).await?;
+        Ok(ClientMarginReport::new(start_date, end_date, clients))
+    }
+}
@@ -0,0 +1,232 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    normalize_project_amount, normalize_project_name, CreateProject, Project, ProjectListFilter, ProjectProfitability, ProjectStatus, UpdateProject,
+};
+use crate::domain::services::{InvoiceError, SharedClock};
+use crate::infrastructure::repositories::ProjectRepository;
+
+#[derive(Debug, Error)]
+pub enum ProjectError {
+    #[error("Project not found")]
+    NotFound,
+
+    #[error("Client not found")]
+    ClientNotFound,
+
+    #[error("Invoice not found")]
+    InvoiceNotFound,
+
+    #[error("Expense not found")]
+    ExpenseNotFound,
+
+    #[error("Time entry not found")]
+    TimeEntryNotFound,
+
+    #[error("Time entry is already billed")]
+    AlreadyBilled,
+
+    #[error("A project named {0} already exists for the client")]
+    AlreadyExists(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ProjectError {
+    fn from(err: sqlx::Error) -> Self {
+        ProjectError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<ProjectError> for InvoiceError {
+    fn from(err: ProjectError) -> Self {
+        match err {
+            ProjectError::DatabaseError(e) => InvoiceError::DatabaseError(e),
+            ProjectError::Validation(message) => InvoiceError::Validation(message),
+            other => InvoiceError::Validation(other.to_string()),
+        }
+    }
+}
+
+/// Client projects, what is tagged with them, and how they are doing
+pub struct ProjectService {
+    repo: ProjectRepository,
+    clock: SharedClock,
+}
+
+impl ProjectService {
+    pub fn new(repo: ProjectRepository, clock: SharedClock) -> Self {
+        Self { repo, clock }
+    }
+
+    pub async fn list_projects(&self, user_id: Uuid, filter: ProjectListFilter) -> Result<Vec<Project>, ProjectError> {
+        Ok(self.repo.list(user_id, &filter).await?)
+    }
+
+    pub async fn get_project(&self, user_id: Uuid, project_id: Uuid) -> Result<Project, ProjectError> {
+        self.repo.find_by_id(user_id, project_id).await?.ok_or(ProjectError::NotFound)
+    }
+
+    pub async fn create_project(&self, user_id: Uuid, create: CreateProject) -> Result<Project, ProjectError> {
+        let name = normalize_project_name(&create.name).map_err(ProjectError::Validation)?;
+        let budget = normalize_project_amount(create.budget, "Budget").map_err(ProjectError::Validation)?;
+        let hourly_rate = normalize_project_amount(create.hourly_rate, "Hourly rate").map_err(ProjectError::Validation)?;
+        if !self.repo.client_exists(user_id, create.client_id).await? {
+            return Err(ProjectError::ClientNotFound);
+        }
+
+        let now = self.clock.now();
+        let project = Project {
+            id: Uuid::new_v4(),
+            client_id: create.client_id,
+            name,
+            status: ProjectStatus::Active,
+            budget,
+            hourly_rate,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(user_id, &project).await.map_err(|e| match e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => ProjectError::AlreadyExists(project.name.clone()),
+            other => other.into(),
+        })
+    }
+
+    pub async fn update_project(&self, user_id: Uuid, project_id: Uuid, update: UpdateProject) -> Result<Project, ProjectError> {
+        let mut project = self.get_project(user_id, project_id).await?;
+
+        if let Some(name) = update.name {
+            project.name = normalize_project_name(&name).map_err(ProjectError::Validation)?;
+        }
+        if let Some(status) = update.status {
+            project.status = status;
+        }
+        if update.budget.is_some() {
+            project.budget = normalize_project_amount(update.budget, "Budget").map_err(ProjectError::Validation)?;
+        }
+        if update.hourly_rate.is_some() {
+            project.hourly_rate =
+                normalize_project_amount(update.hourly_rate, "Hourly rate").map_err(ProjectError::Validation)?;
+        }
+
+        project.updated_at = self.clock.now();
+        let name = project.name.clone();
+        self.repo
+            .update(user_id, &project)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => ProjectError::AlreadyExists(name),
+                other => other.into(),
+            })?
+            .ok_or(ProjectError::NotFound)
+    }
+
+    pub async fn delete_project(&self, user_id: Uuid, project_id: Uuid) -> Result<(), ProjectError> {
+        if self.repo.delete(user_id, project_id).await? {
+            Ok(())
+        } else {
+            Err(ProjectError::NotFound)
+        }
+    }
+
+    /// The project new work is tagged with. It must be active and, when the work
+    /// is for a client, that client's.
+    pub async fn taggable_project(
+        &self,
+        user_id: Uuid,
+        project_id: Uuid,
+        client_id: Option<Uuid>,
+    ) -> Result<Project, ProjectError> {
+        let project = self
+            .repo
+            .find_by_id(user_id, project_id)
+            .await?
+            .ok_or_else(|| ProjectError::Validation("Project not found".to_string()))?;
+        if project.status != ProjectStatus::Active {
+            return Err(ProjectError::Validation(format!("{} is {}", project.name, project.status.as_str())));
+        }
+        if client_id.is_some_and(|client_id| client_id != project.client_id) {
+            return Err(ProjectError::Validation(format!("{} is for another client", project.name)));
+        }
+        Ok(project)
+    }
+
+    /// Tag an invoice with a project of its client, or untag it with None
+    pub async fn set_invoice_project(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<Project>, ProjectError> {
+        let client_id = self
+            .repo
+            .invoice_client(user_id, invoice_id)
+            .await?
+            .ok_or(ProjectError::InvoiceNotFound)?;
+        let project = match project_id {
+            Some(project_id) => Some(self.taggable_project(user_id, project_id, Some(client_id)).await?),
+            None => None,
+        };
+
+        if !self.repo.set_invoice_project(user_id, invoice_id, project_id).await? {
+            return Err(ProjectError::InvoiceNotFound);
+        }
+        Ok(project)
+    }
+
+    /// Tag an expense with a project, or untag it with None
+    pub async fn set_expense_project(
+        &self,
+        user_id: Uuid,
+        expense_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<Project>, ProjectError> {
+        let project = match project_id {
+            Some(project_id) => Some(self.taggable_project(user_id, project_id, None).await?),
+            None => None,
+        };
+
+        if !self.repo.set_expense_project(user_id, expense_id, project_id).await? {
+            return Err(ProjectError::ExpenseNotFound);
+        }
+        Ok(project)
+    }
+
+    /// Tag an unbilled time entry with a project of its client, or untag it with None
+    pub async fn set_time_entry_project(
+        &self,
+        user_id: Uuid,
+        entry_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<Option<Project>, ProjectError> {
+        let (client_id, invoice_id) = self
+            .repo
+            .time_entry_client(user_id, entry_id)
+            .await?
+            .ok_or(ProjectError::TimeEntryNotFound)?;
+        if invoice_id.is_some() {
+            return Err(ProjectError::AlreadyBilled);
+        }
+        let project = match project_id {
+            Some(project_id) => Some(self.taggable_project(user_id, project_id, Some(client_id)).await?),
+            None => None,
+        };
+
+        if !self.repo.set_time_entry_project(user_id, entry_id, project_id).await? {
+            // Billed or deleted since we read it
+            return Err(ProjectError::AlreadyBilled);
+        }
+        Ok(project)
+    }
+
+    pub async fn profitability(&self, user_id: Uuid, project_id: Uuid) -> Result<ProjectProfitability, ProjectError> {
+        let project = self.get_project(user_id, project_id).await?;
+        let totals = self.repo.totals(user_id, project_id).await?;
+        Ok(ProjectProfitability::new(&project, totals))
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::Duration;
+use futures::{Stream, StreamExt};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::domain::models::{RealtimeEnvelope, RealtimeEvent, MAX_REALTIME_REPLAY, REALTIME_REPLAY_MINUTES};
+use crate::domain::services::{RedisService, SharedClock, Shutdown};
+use crate::infrastructure::repositories::RealtimeEventRepository;
+
+/// How often new events are picked up from the database
+const PUBLISH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+/// How often events too old to replay are deleted
+const PRUNE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+/// Pause before subscribing to Redis again after the connection dropped
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+const BATCH_SIZE: i64 = 200;
+/// Events buffered for slow streams on this instance; a stream that falls
+/// further behind is closed so the client reconnects and replays
+const LOCAL_CAPACITY: usize = 1024;
+const CHANNEL: &str = "realtime_events";
+
+#[derive(Debug, Error)]
+pub enum RealtimeError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for RealtimeError {
+    fn from(err: sqlx::Error) -> Self {
+        RealtimeError::DatabaseError(err.to_string())
+    }
+}
+
+/// Live updates for the dashboard. Database triggers record invoice status
+/// changes, views, payments and discussion messages; the publisher claims
+/// them and sends them over Redis pub/sub so every replica hands them to its
+/// open streams. Without Redis, or while this instance isn't subscribed,
+/// events only reach streams on the instance that claimed them.
+pub struct RealtimeService {
+    repo: RealtimeEventRepository,
+    redis: Option<Arc<RedisService>>,
+    local: broadcast::Sender<Arc<RealtimeEvent>>,
+    /// Whether the Redis subscription is up, so published events come back
+    subscribed: AtomicBool,
+    shutdown: Shutdown,
+    clock: SharedClock,
+}
+
+impl RealtimeService {
+    pub fn new(
+        repo: RealtimeEventRepository,
+        redis: Option<Arc<RedisService>>,
+        shutdown: Shutdown,
+        clock: SharedClock,
+    ) -> Self {
+        let (local, _) = broadcast::channel(LOCAL_CAPACITY);
+        Self { repo, redis, local, subscribed: AtomicBool::new(false), shutdown, clock }
+    }
+
+    /// Spawn the publisher, the pruning and, with Redis, the subscriber that
+    /// relays other instances' events
+    pub fn start_workers(self: Arc<Self>, shutdown: &Shutdown) {
+        let publisher = self.clone();
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(PUBLISH_INTERVAL);
+            while shutdown.tick(&mut interval).await {
+                if let Err(e) = publisher.publish().await {
+                    tracing::error!("Realtime publish failed: {}", e);
+                }
+            }
+        });
+
+        let pruner = self.clone();
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+            while shutdown.tick(&mut interval).await {
+                let before = pruner.clock.now() - Duration::minutes(REALTIME_REPLAY_MINUTES);
+                if let Err(e) = pruner.repo.prune(before).await {
+                    tracing::error!("Realtime event pruning failed: {}", e);
+                }
+            }
+        });
+
+        if self.redis.is_some() {
+            shutdown.spawn(|shutdown| async move {
+                loop {
+                    self.relay(&shutdown).await;
+                    if !shutdown.sleep(RESUBSCRIBE_DELAY).await {
+                        break;
+                    }
+                }
+            });
+        }
+    }
+
+    /// The user's events as they happen, after replaying those since
+    /// `last_event_id` (within the replay window). Ends on shutdown, or when
+    /// the stream falls too far behind, so the client reconnects and catches up.
+    pub async fn subscribe(
+        &self,
+        user_id: Uuid,
+        last_event_id: Option<i64>,
+    ) -> Result<impl Stream<Item = Arc<RealtimeEvent>> + Send + 'static, RealtimeError> {
+        // Listen before reading the replay so nothing falls in between
+        let live = self.local.subscribe();
+        let replay = match last_event_id {
+            Some(last_id) => {
+                let since = self.clock.now() - Duration::minutes(REALTIME_REPLAY_MINUTES);
+                self.repo.since(user_id, last_id, since, MAX_REALTIME_REPLAY).await?
+            }
+            None => Vec::new(),
+        };
+
+        let subscription = Subscription {
+            user_id,
+            live,
+            replayed: replay.iter().map(|event| event.id).collect(),
+            shutdown: self.shutdown.clone(),
+        };
+        let live = futures::stream::unfold(subscription, |mut subscription| async move {
+            subscription.next().await.map(|event| (event, subscription))
+        });
+
+        Ok(futures::stream::iter(replay.into_iter().map(Arc::new)).chain(live))
+    }
+
+    /// Claim new events and send them to every instance
+    async fn publish(&self) -> Result<(), RealtimeError> {
+        let events = self.repo.claim_unpublished(self.clock.now(), BATCH_SIZE).await?;
+        for event in events {
+            if !self.publish_to_redis(&event).await || !self.subscribed.load(Ordering::Relaxed) {
+                let _ = self.local.send(Arc::new(event));
+            }
+        }
+        Ok(())
+    }
+
+    /// False when the event didn't go out over Redis
+    async fn publish_to_redis(&self, event: &RealtimeEvent) -> bool {
+        let Some(redis) = &self.redis else {
+            return false;
+        };
+        let Ok(message) = serde_json::to_string(&RealtimeEnvelope::new(event.clone())) else {
+            return false;
+        };
+        match redis.publish(CHANNEL, &message).await {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::debug!(event_id = event.id, "Realtime event kept to this instance: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Hand events from Redis to this instance's streams until the
+    /// subscription drops or shutdown begins
+    async fn relay(&self, shutdown: &Shutdown) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let mut messages = match redis.subscribe(CHANNEL).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                tracing::debug!("Realtime subscription unavailable: {}", e);
+                return;
+            }
+        };
+        self.subscribed.store(true, Ordering::Relaxed);
+
+        loop {
+            let message = tokio::select! {
+                _ = shutdown.clone().triggered() => break,
+                message = messages.next() => message,
+            };
+            let Some(message) = message else {
+                tracing::warn!("Realtime subscription dropped");
+                break;
+            };
+            let envelope = message
+                .get_payload::<String>()
+                .ok()
+                .and_then(|payload| serde_json::from_str::<RealtimeEnvelope>(&payload).ok());
+            match envelope {
+                Some(envelope) => {
+                    let _ = self.local.send(Arc::new(envelope.into_event()));
+                }
+                None => tracing::warn!("Dropping malformed realtime message"),
+            }
+        }
+
+        self.subscribed.store(false, Ordering::Relaxed);
+    }
+}
+
+/// One open stream's view of the local broadcast
+struct Subscription {
+    user_id: Uuid,
+    live: broadcast::Receiver<Arc<RealtimeEvent>>,
+    /// Sent from the replay already; the same events may still arrive live
+    replayed: HashSet<i64>,
+    shutdown: Shutdown,
+}
+
+impl Subscription {
+    async fn next(&mut self) -> Option<Arc<RealtimeEvent>> {
+        loop {
+            let received = tokio::select! {
+                _ = self.shutdown.clone().triggered() => return None,
+                received = self.live.recv() => received,
+            };
+            match received {
+                Ok(event) if event.user_id == self.user_id && !self.replayed.contains(&event.id) => {
+                    return Some(event)
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(user_id = %self.user_id, "Realtime stream fell {} events behind", skipped);
+                    return None;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use async_trait::async_trait;
+use mime_guess::MimeGuess;
+use serde::Deserialize;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    extract_receipt, mean_confidence, parse_tesseract_tsv, resolve_input_tax, CreateExpense, Expense, OcrLine,
+    ReceiptFields, ReceiptScan, ReceiptScanStatus, MAX_RECEIPT_BYTES, RECEIPT_IMAGE_TYPES,
+};
+use crate::domain::services::{ExpenseService, FileError, FileService, LazyHttpClient, SharedClock};
+use crate::infrastructure::repositories::{NewReceiptScan, ReceiptScanRepository, ReceiptScanRow};
+
+const OCR_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Error)]
+pub enum ReceiptScanError {
+    #[error("Receipt scan not found")]
+    NotFound,
+
+    #[error("Receipt scanning is not configured")]
+    NotConfigured,
+
+    #[error("Receipt scan was already confirmed")]
+    AlreadyConfirmed,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("OCR error: {0}")]
+    Ocr(String),
+
+    #[error("File storage error: {0}")]
+    Storage(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for ReceiptScanError {
+    fn from(err: sqlx::Error) -> Self {
+        ReceiptScanError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<FileError> for ReceiptScanError {
+    fn from(err: FileError) -> Self {
+        match err {
+            FileError::FileTooLarge(limit) => ReceiptScanError::Validation(format!(
+                "File is larger than the {} byte limit",
+                limit
+            )),
+            FileError::InvalidFileType => ReceiptScanError::Validation("Receipts must be images".to_string()),
+            other => ReceiptScanError::Storage(other.to_string()),
+        }
+    }
+}
+
+/// Reads the text on a receipt image
+#[async_trait]
+pub trait OcrBackend: Send + Sync {
+    /// Recorded with each scan
+    fn name(&self) -> &'static str;
+
+    /// Shared HTTP handle of backends that call out, for warm-up and health reporting
+    fn http_handle(&self) -> Option<LazyHttpClient>;
+
+    /// Lines of text in reading order
+    async fn recognize(&self, image: &[u8], mime_type: &str) -> Result<Vec<OcrLine>, ReceiptScanError>;
+}
+
+/// Backend selected by `OCR_BACKEND` (`tesseract` or `api`); none when unset
+pub fn ocr_backend_from_env() -> Option<Arc<dyn OcrBackend>> {
+    match std::env::var("OCR_BACKEND").ok()?.as_str() {
+        "tesseract" => Some(Arc::new(TesseractOcr::from_env())),
+        "api" => match std::env::var("OCR_API_URL") {
+            Ok(url) => Some(Arc::new(OcrApiBackend::new(url, std::env::var("OCR_API_KEY").ok()))),
+            Err(_) => {
+                tracing::warn!("⚠️ OCR_BACKEND=api needs OCR_API_URL; receipt scanning is disabled");
+                None
+            }
+        },
+        other => {
+            tracing::warn!("⚠️ Unknown OCR_BACKEND '{}'; receipt scanning is disabled", other);
+            None
+        }
+    }
+}
+
+/// Local Tesseract install, run once per receipt
+pub struct TesseractOcr {
+    binary: String,
+    language: String,
+}
+
+impl TesseractOcr {
+    /// `TESSERACT_PATH` (default `tesseract` on the PATH) and `TESSERACT_LANG` (default `eng`;
+    /// `eng+ind` reads both)
+    pub fn from_env() -> Self {
+        Self {
+            binary: std::env::var("TESSERACT_PATH").unwrap_or_else(|_| "tesseract".to_string()),
+            language: std::env::var("TESSERACT_LANG").unwrap_or_else(|_| "eng".to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl OcrBackend for TesseractOcr {
+    fn name(&self) -> &'static str {
+        "tesseract"
+    }
+
+    fn http_handle(&self) -> Option<LazyHttpClient> {
+        None
+    }
+
+    async fn recognize(&self, image: &[u8], _mime_type: &str) -> Result<Vec<OcrLine>, ReceiptScanError> {
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout", "-l", &self.language, "tsv"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| ReceiptScanError::Ocr(format!("Could not run {}: {}", self.binary, e)))?;
+
+        // Write the image while the output is read, so a full pipe can't stall either side
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let image = image.to_vec();
+        let writer = tokio::spawn(async move {
+            let written = stdin.write_all(&image).await;
+            drop(stdin);
+            written
+        });
+
+        let output = tokio::time::timeout(OCR_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| ReceiptScanError::Ocr("Tesseract timed out".to_string()))?
+            .map_err(|e| ReceiptScanError::Ocr(e.to_string()))?;
+        if let Ok(Err(e)) = writer.await {
+            return Err(ReceiptScanError::Ocr(format!("Could not send the image to Tesseract: {}", e)));
+        }
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ReceiptScanError::Ocr(format!("Tesseract failed: {}", stderr.trim())));
+        }
+
+        Ok(parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// External OCR service. The image is POSTed as the request body, and the response
+/// is either `{"lines": [{"text", "confidence"}]}` or `{"text", "confidence"}`, with
+/// confidence from 0 to 1 or 0 to 100.
+pub struct OcrApiBackend {
+    http_client: LazyHttpClient,
+    url: String,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OcrApiResponse {
+    #[serde(default)]
+    lines: Vec<OcrApiLine>,
+    text: Option<String>,
+    confidence: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OcrApiLine {
+    text: String,
+    confidence: Option<f64>,
+}
+
+/// Confidence from 0 to 1, whichever scale the service reports on
+fn normalize_confidence(confidence: Option<f64>) -> f64 {
+    match confidence {
+        Some(confidence) if confidence > 1.0 => (confidence / 100.0).min(1.0),
+        Some(confidence) => confidence.max(0.0),
+        None => 0.0,
+    }
+}
+
+impl OcrApiResponse {
+    fn into_lines(self) -> Vec<OcrLine> {
+        let lines: Vec<OcrLine> = if self.lines.is_empty() {
+            let confidence = normalize_confidence(self.confidence);
+            self.text
+                .unwrap_or_default()
+                .lines()
+                .map(|text| OcrLine { text: text.to_string(), confidence })
+                .collect()
+        } else {
+            self.lines
+                .into_iter()
+                .map(|line| OcrLine { text: line.text, confidence: normalize_confidence(line.confidence) })
+                .collect()
+        };
+        lines.into_iter().filter(|line| !line.text.trim().is_empty()).collect()
+    }
+}
+
+impl OcrApiBackend {
+    pub fn new(url: String, api_key: Option<String>) -> Self {
+        Self {
+            http_client: LazyHttpClient::new("ocr_api", OCR_TIMEOUT, true),
+            url,
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl OcrBackend for OcrApiBackend {
+    fn name(&self) -> &'static str {
+        "api"
+    }
+
+    fn http_handle(&self) -> Option<LazyHttpClient> {
+        Some(self.http_client.clone())
+    }
+
+    async fn recognize(&self, image: &[u8], mime_type: &str) -> Result<Vec<OcrLine>, ReceiptScanError> {
+        let client = self.http_client.get().map_err(ReceiptScanError::Ocr)?;
+        let mut request = client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, mime_type)
+            .body(image.to_vec());
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response: OcrApiResponse = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ReceiptScanError::Ocr(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| ReceiptScanError::Ocr(format!("Unexpected OCR response: {}", e)))?;
+        Ok(response.into_lines())
+    }
+}
+
+/// Receipt photos read into draft expenses. The user checks the draft and
+/// confirms it to create the expense; each scan keeps the OCR text, what was
+/// extracted and how confident the OCR was, for auditing.
+pub struct ReceiptScanService {
+    repo: ReceiptScanRepository,
+    file_service: Arc<FileService>,
+    expense_service: Arc<ExpenseService>,
+    backend: Option<Arc<dyn OcrBackend>>,
+    clock: SharedClock,
+}
+
+impl ReceiptScanService {
+    pub fn new(
+        repo: ReceiptScanRepository,
+        file_service: Arc<FileService>,
+        expense_service: Arc<ExpenseService>,
+        backend: Option<Arc<dyn OcrBackend>>,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, file_service, expense_service, backend, clock }
+    }
+
+    /// HTTP handle of the configured backend, for warm-up and health reporting
+    pub fn http_handle(&self) -> Option<LazyHttpClient> {
+        self.backend.as_ref().and_then(|backend| backend.http_handle())
+    }
+
+    /// Store a receipt image and read it. A receipt OCR can't read is kept as a
+    /// failed scan with an empty draft, to be filled in by hand.
+    pub async fn scan(
+        &self,
+        user_id: Uuid,
+        file_name: &str,
+        declared_type: Option<&str>,
+        data: &[u8],
+    ) -> Result<ReceiptScan, ReceiptScanError> {
+        let backend = self.backend.as_ref().ok_or(ReceiptScanError::NotConfigured)?;
+        if data.is_empty() {
+            return Err(ReceiptScanError::Validation("The receipt is empty".to_string()));
+        }
+        if data.len() > MAX_RECEIPT_BYTES {
+            return Err(ReceiptScanError::Validation(format!(
+                "Receipts can be at most {} bytes",
+                MAX_RECEIPT_BYTES
+            )));
+        }
+        let mime_type = declared_type
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| RECEIPT_IMAGE_TYPES.contains(&value.as_str()))
+            .or_else(|| MimeGuess::from_path(file_name).first().map(|m| m.to_string()))
+            .filter(|value| RECEIPT_IMAGE_TYPES.contains(&value.as_str()))
+            .ok_or_else(|| {
+                ReceiptScanError::Validation(format!("Receipts must be one of: {}", RECEIPT_IMAGE_TYPES.join(", ")))
+            })?;
+
+        let stored = self.file_service.upload_document(user_id, data, file_name, &mime_type).await?;
+        let receipt_image_url = self.file_service.get_file_url(&stored.file_name);
+
+        let (status, lines, error) = match backend.recognize(data, &mime_type).await {
+            Ok(lines) => (ReceiptScanStatus::Completed, lines, None),
+            Err(e) => {
+                tracing::warn!(user_id = %user_id, "Receipt OCR failed: {}", e);
+                (ReceiptScanStatus::Failed, Vec::new(), Some(e.to_string()))
+            }
+        };
+        let fields = extract_receipt(&lines, self.clock.today());
+        let raw_text = (!lines.is_empty())
+            .then(|| lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n"));
+
+        let row = self
+            .repo
+            .create(
+                user_id,
+                NewReceiptScan {
+                    file_name,
+                    receipt_image_url: &receipt_image_url,
+                    backend: backend.name(),
+                    status: status.as_str(),
+                    confidence: mean_confidence(&lines),
+                    raw_text: raw_text.as_deref(),
+                    extracted: serde_json::to_value(&fields).unwrap_or_default(),
+                    error: error.as_deref(),
+                },
+            )
+            .await?;
+        Ok(to_scan(row))
+    }
+
+    pub async fn get(&self, user_id: Uuid, scan_id: Uuid) -> Result<ReceiptScan, ReceiptScanError> {
+        let row = self.repo.find(user_id, scan_id).await?.ok_or(ReceiptScanError::NotFound)?;
+        Ok(to_scan(row))
+    }
+
+    /// Create the expense as the user corrected it from the draft. The receipt
+    /// image is attached unless the user gave another.
+    pub async fn confirm(
+        &self,
+        user_id: Uuid,
+        scan_id: Uuid,
+        mut create: CreateExpense,
+    ) -> Result<Expense, ReceiptScanError> {
+        let scan = self.get(user_id, scan_id).await?;
+        if scan.confirmed_at.is_some() {
+            return Err(ReceiptScanError::AlreadyConfirmed);
+        }
+        let tax_amount = resolve_input_tax(create.amount, create.tax_rate, create.tax_amount, self.expense_service.rounding())
+            .map_err(ReceiptScanError::Validation)?;
+        create.tax_amount = Some(tax_amount);
+        if create.receipt_image_url.is_none() {
+            create.receipt_image_url = Some(scan.receipt_image_url);
+        }
+
+        if !self.repo.claim(user_id, scan_id, self.clock.now()).await? {
+            return Err(ReceiptScanError::AlreadyConfirmed);
+        }
+        let expense = match self.expense_service.create_expense(user_id, create).await {
+            Ok(expense) => expense,
+            Err(e) => {
+                if let Err(release_error) = self.repo.release(scan_id).await {
+                    tracing::warn!("Receipt scan {} could not be released: {}", scan_id, release_error);
+                }
+                return Err(e.into());
+            }
+        };
+        self.repo.link_expense(scan_id, expense.id).await?;
+        tracing::info!(user_id = %user_id, scan_id = %scan_id, expense_id = %expense.id, "Receipt scan confirmed");
+
+        Ok(expense)
+    }
+}
+
+fn to_scan(row: ReceiptScanRow) -> ReceiptScan {
+    let fields: ReceiptFields = serde_json::from_value(row.extracted).unwrap_or_default();
+    ReceiptScan {
+        id: row.id,
+        status: ReceiptScanStatus::from_stored(&row.status),
+        backend: row.backend,
+        draft: fields.draft(&row.receipt_image_url),
+        fields,
+        file_name: row.file_name,
+        receipt_image_url: row.receipt_image_url,
+        confidence: row.confidence,
+        raw_text: row.raw_text,
+        error: row.error,
+        expense_id: row.expense_id,
+        created_at: row.created_at,
+        confirmed_at: row.confirmed_at,
+    }
+}
@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use redis::{Client, RedisError, AsyncCommands, aio::MultiplexedConnection};
+use redis::{Client, RedisError, AsyncCommands, aio::{MultiplexedConnection, PubSubStream}};
 use serde::{Serialize, de::DeserializeOwned};
 
 #[derive(Debug, thiserror::Error)]
@@ -139,4 +139,35 @@ impl RedisService {
         let result: i64 = conn.llen(key).await?;
         Ok(Some(result))
     }
+
+    /// Up to `count` entries from the left of a list
+    pub async fn lrange(&self, key: &str, count: usize) -> Result<Vec<String>, RedisErrorWrapper> {
+        let mut conn = self.get_async_connection().await?;
+        let stop = count.saturating_sub(1) as isize;
+        let result: Vec<String> = conn.lrange(key, 0, stop).await?;
+        Ok(result)
+    }
+
+    /// Remove the first occurrence of `value` from a list; true if there was one
+    pub async fn lrem(&self, key: &str, value: &str) -> Result<bool, RedisErrorWrapper> {
+        let mut conn = self.get_async_connection().await?;
+        let removed: i64 = conn.lrem(key, 1, value).await?;
+        Ok(removed > 0)
+    }
+
+    /// Publish a message to every subscriber of a channel
+    pub async fn publish(&self, channel: &str, message: &str) -> Result<(), RedisErrorWrapper> {
+        let mut conn = self.get_async_connection().await?;
+        conn.publish::<_, _, ()>(channel, message).await?;
+        Ok(())
+    }
+
+    /// Messages published to a channel from now on, on a dedicated connection
+    pub async fn subscribe(&self, channel: &str) -> Result<PubSubStream, RedisErrorWrapper> {
+        let mut pubsub = self.client.get_async_pubsub()
+            .await
+            .map_err(|e| RedisErrorWrapper::Connection(e.to_string()))?;
+        pubsub.subscribe(channel).await?;
+        Ok(pubsub.into_on_message())
+    }
 }
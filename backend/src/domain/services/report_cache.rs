@@ -0,0 +1,71 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::services::RedisService;
+
+/// Outlives any cached report, so a version never restarts under a live entry
+const VERSION_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Cached reports in Redis, keyed under a per-user version. Bumping the version
+/// when invoices, payments or expenses change makes every cached report of that
+/// user stale at once, without scanning for keys; the old entries just expire.
+/// Without Redis nothing is cached.
+#[derive(Clone, Default)]
+pub struct ReportCache {
+    redis: Option<Arc<RedisService>>,
+}
+
+impl ReportCache {
+    pub fn new(redis: Option<Arc<RedisService>>) -> Self {
+        Self { redis }
+    }
+
+    fn version_key(user_id: Uuid) -> String {
+        format!("report_version:{}", user_id)
+    }
+
+    async fn versioned_key(&self, redis: &RedisService, user_id: Uuid, key: &str) -> String {
+        let version = redis
+            .get::<i64>(&Self::version_key(user_id))
+            .await
+            .ok()
+            .flatten()
+            .unwrap_or(0);
+        format!("{}:v{}", key, version)
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, user_id: Uuid, key: &str) -> Option<T> {
+        let redis = self.redis.as_ref()?;
+        let key = self.versioned_key(redis, user_id, key).await;
+        redis.get::<T>(&key).await.ok().flatten()
+    }
+
+    pub async fn set<T: Serialize>(&self, user_id: Uuid, key: &str, data: &T, ttl: u64) {
+        if let Some(redis) = &self.redis {
+            let key = self.versioned_key(redis, user_id, key).await;
+            let _ = redis.set_with_expiration(&key, data, ttl).await;
+        }
+    }
+
+    /// Drops one cached report
+    pub async fn delete(&self, user_id: Uuid, key: &str) {
+        if let Some(redis) = &self.redis {
+            let key = self.versioned_key(redis, user_id, key).await;
+            let _ = redis.delete(&key).await;
+        }
+    }
+
+    /// Makes every cached report of the user stale
+    pub async fn invalidate_user(&self, user_id: Uuid) {
+        if let Some(redis) = &self.redis {
+            let key = Self::version_key(user_id);
+            match redis.increment(&key).await {
+                Ok(_) => {
+                    let _ = redis.expire(&key, VERSION_TTL_SECONDS).await;
+                }
+                Err(e) => tracing::warn!(user_id = %user_id, "Report cache invalidation failed: {}", e),
+            }
+        }
+    }
+}
@@ -1,20 +1,30 @@
 #![allow(dead_code)]
 
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
-use chrono::NaiveDate;
+use chrono::{Datelike, Months, NaiveDate};
 use csv::Writer;
 
 use crate::domain::repositories::report_repository::{
     ReportRepository, OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport,
+    AgingSnapshot, AgingTrend, AgingTrendPoint, ClientReportFilter, SlaReport, VatReport,
 };
-use crate::domain::services::{PdfService, InvoiceItemPdf, RedisService};
+use crate::domain::models::{
+    build_cashflow_forecast, CashflowForecast, Timeseries, TimeseriesInterval, TimeseriesMetric, CASHFLOW_HISTORY_MONTHS,
+};
+use crate::domain::services::{PdfService, PdfBranding, InvoiceItemPdf, RedisService, ReportCache, Shutdown};
+
+pub const DEFAULT_AGING_TREND_MONTHS: u32 = 12;
+pub const MAX_AGING_TREND_MONTHS: u32 = 36;
+
+const AGING_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[derive(Clone)]
 pub struct ReportService<R: ReportRepository> {
     report_repo: Arc<R>,
     pdf_service: Arc<PdfService>,
-    redis: Option<Arc<RedisService>>,
+    cache: ReportCache,
 }
 
 impl<R: ReportRepository> ReportService<R> {
@@ -22,7 +32,7 @@ impl<R: ReportRepository> ReportService<R> {
         Self {
             report_repo,
             pdf_service: Arc::new(PdfService::new()),
-            redis: None,
+            cache: ReportCache::default(),
         }
     }
 
@@ -30,10 +40,15 @@ impl<R: ReportRepository> ReportService<R> {
         Self {
             report_repo,
             pdf_service: Arc::new(PdfService::new()),
-            redis: Some(redis),
+            cache: ReportCache::new(Some(redis)),
         }
     }
 
+    /// Shared with whatever changes report data, to invalidate it
+    pub fn cache(&self) -> ReportCache {
+        self.cache.clone()
+    }
+
     // Helper to get cache key
     fn get_cache_key(&self, prefix: &str, user_id: Uuid, start_date: NaiveDate, end_date: NaiveDate) -> String {
         format!("report:{}:{}:{}:{}", prefix, user_id, start_date, end_date)
@@ -42,39 +57,34 @@ impl<R: ReportRepository> ReportService<R> {
     // Helper to get from cache
     async fn get_cached<T: serde::de::DeserializeOwned + Clone>(
         &self,
+        user_id: Uuid,
         key: &str,
     ) -> Option<T> {
-        if let Some(redis) = &self.redis {
-            if let Ok(Some(data)) = redis.get::<T>(key).await {
-                return Some(data);
-            }
-        }
-        None
+        self.cache.get(user_id, key).await
     }
 
     // Helper to set cache
     async fn set_cache<T: serde::Serialize + Clone>(
         &self,
+        user_id: Uuid,
         key: &str,
         data: &T,
         ttl: u64,
     ) {
-        if let Some(redis) = &self.redis {
-            let _ = redis.set_with_expiration(key, data, ttl).await;
-        }
+        self.cache.set(user_id, key, data, ttl).await;
     }
 
     pub async fn get_overview_stats(&self, user_id: Uuid) -> Result<OverviewStats, sqlx::Error> {
         // Try cache first
         let cache_key = format!("overview_stats:{}", user_id);
-        if let Some(cached) = self.get_cached::<OverviewStats>(&cache_key).await {
+        if let Some(cached) = self.get_cached::<OverviewStats>(user_id, &cache_key).await {
             return Ok(cached);
         }
 
         let result = self.report_repo.get_overview_stats(user_id).await?;
 
         // Cache for 5 minutes
-        self.set_cache(&cache_key, &result, 300).await;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
 
         Ok(result)
     }
@@ -84,14 +94,20 @@ impl<R: ReportRepository> ReportService<R> {
         user_id: Uuid,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        filter: &ClientReportFilter,
     ) -> Result<IncomeReport, sqlx::Error> {
+        // Only unfiltered reports are cached
+        if !filter.is_empty() {
+            return self.report_repo.get_income_report(user_id, start_date, end_date, filter).await;
+        }
+
         let cache_key = self.get_cache_key("income", user_id, start_date, end_date);
-        if let Some(cached) = self.get_cached::<IncomeReport>(&cache_key).await {
+        if let Some(cached) = self.get_cached::<IncomeReport>(user_id, &cache_key).await {
             return Ok(cached);
         }
 
-        let result = self.report_repo.get_income_report(user_id, start_date, end_date).await?;
-        self.set_cache(&cache_key, &result, 300).await;
+        let result = self.report_repo.get_income_report(user_id, start_date, end_date, filter).await?;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
 
         Ok(result)
     }
@@ -103,12 +119,12 @@ impl<R: ReportRepository> ReportService<R> {
         end_date: NaiveDate,
     ) -> Result<ExpensesReport, sqlx::Error> {
         let cache_key = self.get_cache_key("expenses", user_id, start_date, end_date);
-        if let Some(cached) = self.get_cached::<ExpensesReport>(&cache_key).await {
+        if let Some(cached) = self.get_cached::<ExpensesReport>(user_id, &cache_key).await {
             return Ok(cached);
         }
 
         let result = self.report_repo.get_expenses_report(user_id, start_date, end_date).await?;
-        self.set_cache(&cache_key, &result, 300).await;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
 
         Ok(result)
     }
@@ -120,24 +136,138 @@ impl<R: ReportRepository> ReportService<R> {
         end_date: NaiveDate,
     ) -> Result<TaxReport, sqlx::Error> {
         let cache_key = self.get_cache_key("tax", user_id, start_date, end_date);
-        if let Some(cached) = self.get_cached::<TaxReport>(&cache_key).await {
+        if let Some(cached) = self.get_cached::<TaxReport>(user_id, &cache_key).await {
             return Ok(cached);
         }
 
         let result = self.report_repo.get_tax_report(user_id, start_date, end_date).await?;
-        self.set_cache(&cache_key, &result, 300).await;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
 
         Ok(result)
     }
 
-    pub async fn get_aging_report(&self, user_id: Uuid) -> Result<AgingReport, sqlx::Error> {
+    pub async fn get_vat_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<VatReport, sqlx::Error> {
+        let cache_key = self.get_cache_key("vat", user_id, start_date, end_date);
+        if let Some(cached) = self.get_cached::<VatReport>(user_id, &cache_key).await {
+            return Ok(cached);
+        }
+
+        let result = self.report_repo.get_vat_report(user_id, start_date, end_date).await?;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
+
+        Ok(result)
+    }
+
+    pub async fn get_aging_report(&self, user_id: Uuid, filter: &ClientReportFilter) -> Result<AgingReport, sqlx::Error> {
+        if !filter.is_empty() {
+            return self.report_repo.get_aging_report(user_id, filter).await;
+        }
+
         let cache_key = format!("aging:{}", user_id);
-        if let Some(cached) = self.get_cached::<AgingReport>(&cache_key).await {
+        if let Some(cached) = self.get_cached::<AgingReport>(user_id, &cache_key).await {
+            return Ok(cached);
+        }
+
+        let result = self.report_repo.get_aging_report(user_id, filter).await?;
+        self.set_cache(user_id, &cache_key, &result, 300).await;
+
+        Ok(result)
+    }
+
+    pub async fn snapshot_aging(&self, snapshot_date: NaiveDate) -> Result<u64, sqlx::Error> {
+        self.report_repo.snapshot_aging(snapshot_date).await
+    }
+
+    /// Month-end aging over the last `months` months. Earlier months come from the
+    /// nightly snapshots; the current month is the live report as of today.
+    pub async fn get_aging_trend(&self, user_id: Uuid, months: u32, today: NaiveDate) -> Result<AgingTrend, sqlx::Error> {
+        let this_month = today.with_day(1).unwrap_or(today);
+        let since = this_month
+            .checked_sub_months(Months::new(months.saturating_sub(1)))
+            .unwrap_or(this_month);
+
+        let mut points: Vec<AgingTrendPoint> = self
+            .report_repo
+            .get_aging_snapshots(user_id, since)
+            .await?
+            .into_iter()
+            .filter(|snapshot| snapshot.snapshot_date < this_month)
+            .map(AgingTrendPoint::from_snapshot)
+            .collect();
+
+        let live = self.get_aging_report(user_id, &ClientReportFilter::default()).await?;
+        points.push(AgingTrendPoint::from_snapshot(AgingSnapshot { snapshot_date: today, aging: live }));
+
+        let overdue_share_change = match (points.first(), points.last()) {
+            (Some(first), Some(last)) if points.len() > 1 => {
+                Some(((last.overdue_share - first.overdue_share) * 100.0).round() / 100.0)
+            }
+            _ => None,
+        };
+
+        Ok(AgingTrend { months, points, overdue_share_change })
+    }
+
+    /// Not cached: unviewed and unpaid invoices cross their targets as time passes
+    pub async fn get_sla_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<SlaReport, sqlx::Error> {
+        self.report_repo.get_sla_report(user_id, start_date, end_date).await
+    }
+
+    /// Expected cash in and out over the next 30, 60 and 90 days, learned from the
+    /// last year of invoices, payments and expenses
+    pub async fn get_cashflow_forecast(&self, user_id: Uuid, today: NaiveDate) -> Result<CashflowForecast, sqlx::Error> {
+        let cache_key = format!("cashflow_forecast:{}", user_id);
+        if let Some(cached) = self.get_cached::<CashflowForecast>(user_id, &cache_key).await {
+            return Ok(cached);
+        }
+
+        let since = today
+            .checked_sub_months(Months::new(CASHFLOW_HISTORY_MONTHS))
+            .unwrap_or(today);
+        let inputs = self.report_repo.get_cashflow_inputs(user_id, since).await?;
+        let result = build_cashflow_forecast(inputs, today);
+        self.set_cache(user_id, &cache_key, &result, 300).await;
+
+        Ok(result)
+    }
+
+    /// `metric` bucketed by `interval` between the dates, for dashboard charts
+    pub async fn get_timeseries(
+        &self,
+        user_id: Uuid,
+        metric: TimeseriesMetric,
+        interval: TimeseriesInterval,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Timeseries, sqlx::Error> {
+        let cache_key = format!(
+            "report:timeseries:{}:{}:{}:{}:{}",
+            user_id,
+            metric.as_str(),
+            interval.as_str(),
+            start_date,
+            end_date
+        );
+        if let Some(cached) = self.get_cached::<Timeseries>(user_id, &cache_key).await {
             return Ok(cached);
         }
 
-        let result = self.report_repo.get_aging_report(user_id).await?;
-        self.set_cache(&cache_key, &result, 300).await;
+        let points = self
+            .report_repo
+            .get_timeseries(user_id, metric, interval, start_date, end_date)
+            .await?;
+        let result = Timeseries { metric, interval, start_date, end_date, points };
+        self.set_cache(user_id, &cache_key, &result, 300).await;
 
         Ok(result)
     }
@@ -149,14 +279,15 @@ impl<R: ReportRepository> ReportService<R> {
         format: &str,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         // Get the appropriate report data based on report_type
         match report_type {
             "income" => {
-                let report = self.get_income_report(user_id, start_date, end_date).await?;
+                let report = self.get_income_report(user_id, start_date, end_date, &ClientReportFilter::default()).await?;
                 match format {
                     "csv" => self.export_income_csv(&report),
-                    "pdf" => self.export_income_pdf(&report, start_date, end_date),
+                    "pdf" => self.export_income_pdf(&report, start_date, end_date, branding),
                     _ => Err("Unsupported format".into()),
                 }
             }
@@ -164,7 +295,7 @@ impl<R: ReportRepository> ReportService<R> {
                 let report = self.get_expenses_report(user_id, start_date, end_date).await?;
                 match format {
                     "csv" => self.export_expenses_csv(&report),
-                    "pdf" => self.export_expenses_pdf(&report, start_date, end_date),
+                    "pdf" => self.export_expenses_pdf(&report, start_date, end_date, branding),
                     _ => Err("Unsupported format".into()),
                 }
             }
@@ -172,15 +303,15 @@ impl<R: ReportRepository> ReportService<R> {
                 let report = self.get_tax_report(user_id, start_date, end_date).await?;
                 match format {
                     "csv" => self.export_tax_csv(&report),
-                    "pdf" => self.export_tax_pdf(&report, start_date, end_date),
+                    "pdf" => self.export_tax_pdf(&report, start_date, end_date, branding),
                     _ => Err("Unsupported format".into()),
                 }
             }
             "aging" => {
-                let report = self.get_aging_report(user_id).await?;
+                let report = self.get_aging_report(user_id, &ClientReportFilter::default()).await?;
                 match format {
                     "csv" => self.export_aging_csv(&report),
-                    "pdf" => self.export_aging_pdf(&report, start_date, end_date),
+                    "pdf" => self.export_aging_pdf(&report, start_date, end_date, branding),
                     _ => Err("Unsupported format".into()),
                 }
             }
@@ -188,7 +319,7 @@ impl<R: ReportRepository> ReportService<R> {
                 let report = self.get_overview_stats(user_id).await?;
                 match format {
                     "csv" => self.export_overview_csv(&report),
-                    "pdf" => self.export_overview_pdf(&report, start_date, end_date),
+                    "pdf" => self.export_overview_pdf(&report, start_date, end_date, branding),
                     _ => Err("Unsupported format".into()),
                 }
             }
@@ -248,6 +379,7 @@ impl<R: ReportRepository> ReportService<R> {
         let mut wtr = Writer::from_writer(vec![]);
         wtr.write_record(["Total Tax Collected", &report.total_tax_collected.to_string()])?;
         wtr.write_record(["Total Tax Deductible", &report.total_tax_deductible.to_string()])?;
+        wtr.write_record(["Net Tax Payable", &report.net_tax_payable.to_string()])?;
         wtr.write_record([] as [&str; 0])?;
 
         wtr.write_record(["By State"])?;
@@ -255,6 +387,19 @@ impl<R: ReportRepository> ReportService<R> {
         for item in &report.by_state {
             wtr.write_record([&item.state_code, &item.tax_amount.to_string()])?;
         }
+        wtr.write_record([] as [&str; 0])?;
+
+        wtr.write_record(["By Tax"])?;
+        wtr.write_record(["Tax", "Rate", "Compound", "Taxable Amount", "Tax Amount"])?;
+        for item in &report.by_tax {
+            wtr.write_record([
+                item.label.clone(),
+                item.rate.map(|rate| rate.to_string()).unwrap_or_default(),
+                item.compound.to_string(),
+                item.taxable_amount.to_string(),
+                item.tax_amount.to_string(),
+            ])?;
+        }
 
         Ok(wtr.into_inner()?)
     }
@@ -278,6 +423,7 @@ impl<R: ReportRepository> ReportService<R> {
         wtr.write_record(["Paid Invoices", &report.paid_invoices.to_string()])?;
         wtr.write_record(["Overdue Invoices", &report.overdue_invoices.to_string()])?;
         wtr.write_record(["Total Expenses", &report.total_expenses.to_string()])?;
+        wtr.write_record(["FX Gain/Loss", &report.fx_gain_loss.to_string()])?;
         wtr.write_record(["Net Profit", &report.net_profit.to_string()])?;
 
         Ok(wtr.into_inner()?)
@@ -289,6 +435,7 @@ impl<R: ReportRepository> ReportService<R> {
         report: &IncomeReport,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let mut items = vec![
             InvoiceItemPdf {
@@ -333,7 +480,12 @@ impl<R: ReportRepository> ReportService<R> {
             report.total_income,
             Some(&format!("Income report from {} to {}", start_date, end_date)),
             None,
+            &[],
+            &[],
+            None,
+            None,
             None,
+            branding,
         )?;
 
         Ok(pdf)
@@ -344,6 +496,7 @@ impl<R: ReportRepository> ReportService<R> {
         report: &ExpensesReport,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let mut items = vec![
             InvoiceItemPdf {
@@ -388,7 +541,12 @@ impl<R: ReportRepository> ReportService<R> {
             report.total_expenses,
             Some(&format!("Expenses report from {} to {}", start_date, end_date)),
             None,
+            &[],
+            &[],
             None,
+            None,
+            None,
+            branding,
         )?;
 
         Ok(pdf)
@@ -399,6 +557,7 @@ impl<R: ReportRepository> ReportService<R> {
         report: &TaxReport,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let mut items = vec![
             InvoiceItemPdf {
@@ -413,6 +572,12 @@ impl<R: ReportRepository> ReportService<R> {
                 unit_price: report.total_tax_deductible,
                 total: report.total_tax_deductible,
             },
+            InvoiceItemPdf {
+                description: format!("Net Tax Payable: ${:.2}", report.net_tax_payable),
+                quantity: 1.0,
+                unit_price: report.net_tax_payable,
+                total: report.net_tax_payable,
+            },
         ];
 
         for item in &report.by_state {
@@ -424,6 +589,17 @@ impl<R: ReportRepository> ReportService<R> {
             });
         }
 
+        for item in &report.by_tax {
+            let rate = item.rate.map(|rate| format!(" {}%", (rate * 100_000.0).round() / 1000.0)).unwrap_or_default();
+            let compound = if item.compound { " (compound)" } else { "" };
+            items.push(InvoiceItemPdf {
+                description: format!("{}{}{} on ${:.2}", item.label, rate, compound, item.taxable_amount),
+                quantity: 1.0,
+                unit_price: item.tax_amount,
+                total: item.tax_amount,
+            });
+        }
+
         let pdf = self.pdf_service.generate_invoice_pdf(
             &format!("TAX-REPORT-{}-{}", start_date, end_date),
             Some("FlashBill"),
@@ -440,7 +616,12 @@ impl<R: ReportRepository> ReportService<R> {
             report.total_tax_collected,
             Some(&format!("Tax report from {} to {}", start_date, end_date)),
             None,
+            &[],
+            &[],
             None,
+            None,
+            None,
+            branding,
         )?;
 
         Ok(pdf)
@@ -451,6 +632,7 @@ impl<R: ReportRepository> ReportService<R> {
         report: &AgingReport,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let items = vec![
             InvoiceItemPdf {
@@ -504,7 +686,12 @@ impl<R: ReportRepository> ReportService<R> {
             total,
             Some(&format!("Aging report from {} to {}", start_date, end_date)),
             None,
+            &[],
+            &[],
+            None,
             None,
+            None,
+            branding,
         )?;
 
         Ok(pdf)
@@ -515,6 +702,7 @@ impl<R: ReportRepository> ReportService<R> {
         report: &OverviewStats,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        branding: &PdfBranding,
     ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let items = vec![
             InvoiceItemPdf {
@@ -547,6 +735,12 @@ impl<R: ReportRepository> ReportService<R> {
                 unit_price: report.total_expenses,
                 total: report.total_expenses,
             },
+            InvoiceItemPdf {
+                description: "FX Gain/Loss".to_string(),
+                quantity: 1.0,
+                unit_price: report.fx_gain_loss,
+                total: report.fx_gain_loss,
+            },
             InvoiceItemPdf {
                 description: "Net Profit".to_string(),
                 quantity: 1.0,
@@ -571,7 +765,12 @@ impl<R: ReportRepository> ReportService<R> {
             report.net_profit,
             Some(&format!("Overview report from {} to {}", start_date, end_date)),
             None,
+            &[],
+            &[],
+            None,
+            None,
             None,
+            branding,
         )?;
 
         Ok(pdf)
@@ -579,55 +778,52 @@ impl<R: ReportRepository> ReportService<R> {
 
     // Cache Invalidation Methods
     pub async fn invalidate_overview_cache(&self, user_id: Uuid) {
-        if let Some(redis) = &self.redis {
-            let cache_key = format!("overview_stats:{}", user_id);
-            let _ = redis.delete(&cache_key).await;
-        }
+        self.cache.delete(user_id, &format!("overview_stats:{}", user_id)).await;
     }
 
     pub async fn invalidate_income_cache(&self, user_id: Uuid, start_date: NaiveDate, end_date: NaiveDate) {
-        if let Some(redis) = &self.redis {
-            let cache_key = self.get_cache_key("income", user_id, start_date, end_date);
-            let _ = redis.delete(&cache_key).await;
-        }
+        let cache_key = self.get_cache_key("income", user_id, start_date, end_date);
+        self.cache.delete(user_id, &cache_key).await;
     }
 
     pub async fn invalidate_expenses_cache(&self, user_id: Uuid, start_date: NaiveDate, end_date: NaiveDate) {
-        if let Some(redis) = &self.redis {
-            let cache_key = self.get_cache_key("expenses", user_id, start_date, end_date);
-            let _ = redis.delete(&cache_key).await;
-        }
+        let cache_key = self.get_cache_key("expenses", user_id, start_date, end_date);
+        self.cache.delete(user_id, &cache_key).await;
     }
 
     pub async fn invalidate_tax_cache(&self, user_id: Uuid, start_date: NaiveDate, end_date: NaiveDate) {
-        if let Some(redis) = &self.redis {
-            let cache_key = self.get_cache_key("tax", user_id, start_date, end_date);
-            let _ = redis.delete(&cache_key).await;
-        }
+        let cache_key = self.get_cache_key("tax", user_id, start_date, end_date);
+        self.cache.delete(user_id, &cache_key).await;
     }
 
     pub async fn invalidate_aging_cache(&self, user_id: Uuid) {
-        if let Some(redis) = &self.redis {
-            let cache_key = format!("aging:{}", user_id);
-            let _ = redis.delete(&cache_key).await;
-        }
+        self.cache.delete(user_id, &format!("aging:{}", user_id)).await;
+    }
+
+    pub async fn invalidate_cashflow_forecast_cache(&self, user_id: Uuid) {
+        self.cache.delete(user_id, &format!("cashflow_forecast:{}", user_id)).await;
     }
 
     /// Invalidate all report caches for a user
     pub async fn invalidate_all_user_cache(&self, user_id: Uuid) {
-        if let Some(redis) = &self.redis {
-            // Clear all cache patterns for this user
-            let patterns = vec![
-                format!("overview_stats:{}", user_id),
-                format!("aging:{}", user_id),
-                format!("report:income:{}:*", user_id),
-                format!("report:expenses:{}:*", user_id),
-                format!("report:tax:{}:*", user_id),
-            ];
-
-            for pattern in patterns {
-                let _ = redis.clear_pattern(&pattern).await;
+        self.cache.invalidate_user(user_id).await;
+    }
+}
+
+impl<R: ReportRepository + 'static> ReportService<R> {
+    /// Spawn the nightly aging snapshot loop. A run replaces the day's snapshot, so a
+    /// restart on the same day just refreshes it.
+    pub fn start_aging_snapshots(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(AGING_SNAPSHOT_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                let today = chrono::Utc::now().date_naive();
+                match self.snapshot_aging(today).await {
+                    Ok(users) => tracing::info!("Recorded aging snapshots for {} user(s)", users),
+                    Err(e) => tracing::error!("Aging snapshot run failed: {}", e),
+                }
             }
-        }
+        });
     }
 }
@@ -294,12 +294,12 @@ mod tests {
             jitter: false,
         });
 
-        let mut attempts = 0;
+        let attempts = std::sync::atomic::AtomicU32::new(0);
         let result = service.execute(|_| {
-            attempts += 1;
+            let attempt = attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
             async move {
-                if attempts < 3 {
-                    Err("temporary error")
+                if attempt < 3 {
+                    Err("temporary network error")
                 } else {
                     Ok("success")
                 }
@@ -307,7 +307,7 @@ mod tests {
         }).await;
 
         assert_eq!(result.unwrap(), "success");
-        assert_eq!(attempts, 3);
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
     }
 
     #[tokio::test]
@@ -0,0 +1,69 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{prefix_tsquery, SearchQuery, SearchResults, SearchType};
+use crate::infrastructure::repositories::SearchRepository;
+
+#[derive(Debug, Error)]
+pub enum SearchError {
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for SearchError {
+    fn from(err: sqlx::Error) -> Self {
+        SearchError::DatabaseError(err.to_string())
+    }
+}
+
+/// Full-text search across invoices (number, notes, terms and line items),
+/// clients (name, company, email and notes) and expenses (vendor and
+/// description), with results grouped by type
+pub struct SearchService {
+    repo: SearchRepository,
+}
+
+impl SearchService {
+    pub fn new(repo: SearchRepository) -> Self {
+        Self { repo }
+    }
+
+    pub async fn search(&self, user_id: Uuid, query: &SearchQuery) -> Result<SearchResults, SearchError> {
+        let tsquery = prefix_tsquery(&query.q).map_err(SearchError::Validation)?;
+        let types = query.types().map_err(SearchError::Validation)?;
+        let limit = query.limit().map_err(SearchError::Validation)?;
+
+        // The groups are independent, so they're searched at the same time
+        let wanted = |search_type| types.contains(&search_type);
+        let (invoices, clients, expenses) = tokio::try_join!(
+            async {
+                if !wanted(SearchType::Invoices) {
+                    return Ok(None);
+                }
+                self.repo.search_invoices(user_id, &tsquery, limit).await.map(Some)
+            },
+            async {
+                if !wanted(SearchType::Clients) {
+                    return Ok(None);
+                }
+                self.repo.search_clients(user_id, &tsquery, limit).await.map(Some)
+            },
+            async {
+                if !wanted(SearchType::Expenses) {
+                    return Ok(None);
+                }
+                self.repo.search_expenses(user_id, &tsquery, limit).await.map(Some)
+            },
+        )?;
+
+        Ok(SearchResults {
+            query: query.q.trim().to_string(),
+            invoices,
+            clients,
+            expenses,
+        })
+    }
+}
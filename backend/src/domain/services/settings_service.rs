@@ -1,17 +1,38 @@
+use std::sync::Arc;
 use uuid::Uuid;
 use thiserror::Error;
 
-use crate::domain::models::{User, UpdateUser, BusinessAddress, TaxSettings, NotificationSettings, InvoiceSettings};
+use crate::domain::i18n::Locale;
+use crate::domain::models::{User, UpdateUser, BusinessAddress, TaxSettings, NotificationSettings, InvoiceSettings, normalize_phone_or_keep};
+use crate::domain::services::{FileService, FileError, PdfBranding};
 use crate::infrastructure::repositories::UserRepository;
 
+/// Logos are drawn into PDFs, which can embed these formats
+const LOGO_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+
 #[derive(Debug, Error)]
 pub enum SettingsError {
     #[error("User not found")]
     UserNotFound,
+    #[error("Invalid logo: {0}")]
+    InvalidLogo(String),
+    #[error("Storage error: {0}")]
+    Storage(String),
     #[error("Database error: {0}")]
     DatabaseError(String),
 }
 
+impl From<FileError> for SettingsError {
+    fn from(err: FileError) -> Self {
+        match err {
+            FileError::FileTooLarge(_) | FileError::InvalidFileType | FileError::InvalidFileName => {
+                SettingsError::InvalidLogo(err.to_string())
+            }
+            _ => SettingsError::Storage(err.to_string()),
+        }
+    }
+}
+
 impl From<sqlx::Error> for SettingsError {
     fn from(err: sqlx::Error) -> Self {
         match err {
@@ -24,11 +45,12 @@ impl From<sqlx::Error> for SettingsError {
 #[derive(Clone)]
 pub struct SettingsService {
     user_repo: UserRepository,
+    files: Arc<FileService>,
 }
 
 impl SettingsService {
-    pub fn new(user_repo: UserRepository) -> Self {
-        Self { user_repo }
+    pub fn new(user_repo: UserRepository, files: Arc<FileService>) -> Self {
+        Self { user_repo, files }
     }
 
     pub async fn get_business_settings(&self, user_id: Uuid) -> Result<User, SettingsError> {
@@ -43,15 +65,34 @@ impl SettingsService {
         business_type: Option<String>,
         business_address: Option<BusinessAddress>,
         phone: Option<String>,
+        locale: Option<Locale>,
+        vat_number: Option<String>,
     ) -> Result<User, SettingsError> {
+        // The VAT number is kept with the tax settings; "" removes it
+        let tax_settings = match vat_number {
+            Some(vat_number) => {
+                let user = self.get_business_settings(user_id).await?;
+                let mut tax_settings = user.tax_settings.unwrap_or(TaxSettings {
+                    state_code: String::new(),
+                    tax_rate: 0.0,
+                    tax_exempt: false,
+                    tax_id: None,
+                });
+                tax_settings.tax_id = Some(vat_number).filter(|vat_number| !vat_number.is_empty());
+                Some(tax_settings)
+            }
+            None => None,
+        };
+
         let update = UpdateUser {
-            phone,
+            phone: phone.as_deref().map(normalize_phone_or_keep),
             company_name,
             business_type,
             business_address,
-            tax_settings: None,
+            tax_settings,
             notification_settings: None,
             invoice_settings: None,
+            locale,
         };
 
         Ok(self.user_repo.update(user_id, update).await?)
@@ -75,6 +116,7 @@ impl SettingsService {
             tax_settings: Some(tax_settings),
             notification_settings: None,
             invoice_settings: None,
+            locale: None,
         };
 
         Ok(self.user_repo.update(user_id, update).await?)
@@ -98,6 +140,7 @@ impl SettingsService {
             tax_settings: None,
             notification_settings: Some(notification_settings),
             invoice_settings: None,
+            locale: None,
         };
 
         Ok(self.user_repo.update(user_id, update).await?)
@@ -111,10 +154,7 @@ impl SettingsService {
     pub async fn update_invoice_settings(
         &self,
         user_id: Uuid,
-        template: String,
-        logo_url: Option<String>,
-        terms: String,
-        notes: String,
+        invoice_settings: InvoiceSettings,
     ) -> Result<User, SettingsError> {
         let update = UpdateUser {
             phone: None,
@@ -123,14 +163,69 @@ impl SettingsService {
             business_address: None,
             tax_settings: None,
             notification_settings: None,
-            invoice_settings: Some(InvoiceSettings {
-                template,
-                logo_url,
-                terms,
-                notes,
-            }),
+            invoice_settings: Some(invoice_settings),
+            locale: None,
         };
 
         Ok(self.user_repo.update(user_id, update).await?)
     }
+
+    /// Store a PNG or JPEG logo for invoice PDFs, replacing the previous upload
+    pub async fn upload_invoice_logo(
+        &self,
+        user_id: Uuid,
+        file_data: &[u8],
+        file_name: &str,
+        mime_type: &str,
+    ) -> Result<User, SettingsError> {
+        let detected = image::guess_format(file_data).ok().map(|format| format.to_mime_type());
+        if !LOGO_TYPES.contains(&mime_type) || detected != Some(mime_type) {
+            return Err(SettingsError::InvalidLogo("logo must be a PNG or JPEG image".to_string()));
+        }
+
+        let stored = self.files.upload_file(user_id, file_data, file_name, mime_type).await?;
+        let logo_url = self.files.get_file_url(&stored.file_name);
+        match self.set_logo_url(user_id, Some(logo_url.clone())).await {
+            Ok(user) => Ok(user),
+            Err(e) => {
+                self.remove_stored_logo(user_id, Some(&logo_url)).await;
+                Err(e)
+            }
+        }
+    }
+
+    pub async fn remove_invoice_logo(&self, user_id: Uuid) -> Result<User, SettingsError> {
+        self.set_logo_url(user_id, None).await
+    }
+
+    /// Template, accent colour, footer and logo for the user's invoice and report PDFs
+    pub async fn pdf_branding(&self, user_id: Uuid) -> Result<PdfBranding, SettingsError> {
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(SettingsError::UserNotFound)?;
+        let settings = user.invoice_settings.unwrap_or_default();
+        let logo = match settings.logo_url.as_deref() {
+            Some(url) => self.files.get_file_by_url(user_id, url).await,
+            None => None,
+        };
+        Ok(PdfBranding::new(&settings, logo))
+    }
+
+    async fn set_logo_url(&self, user_id: Uuid, logo_url: Option<String>) -> Result<User, SettingsError> {
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(SettingsError::UserNotFound)?;
+        let mut settings = user.invoice_settings.unwrap_or_default();
+        let previous = std::mem::replace(&mut settings.logo_url, logo_url);
+
+        let user = self.update_invoice_settings(user_id, settings).await?;
+        self.remove_stored_logo(user_id, previous.as_deref()).await;
+        Ok(user)
+    }
+
+    /// Logos set as external URLs aren't ours to delete
+    async fn remove_stored_logo(&self, user_id: Uuid, logo_url: Option<&str>) {
+        let Some(file_name) = logo_url.and_then(|url| self.files.file_name_from_url(url)) else {
+            return;
+        };
+        if let Err(e) = self.files.delete_file(user_id, file_name).await {
+            tracing::warn!("Failed to remove replaced logo {}: {}", file_name, e);
+        }
+    }
 }
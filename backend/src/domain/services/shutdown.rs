@@ -0,0 +1,171 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::time::Interval;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+
+/// Default time in-flight requests and jobs get to finish after SIGTERM, under
+/// the 30 seconds orchestrators usually wait before killing the process
+pub const DEFAULT_SHUTDOWN_GRACE_SECONDS: u64 = 25;
+
+/// Coordinates a graceful stop. Background workers are spawned through it and
+/// check it between jobs: once shutdown begins they finish the job in hand and
+/// exit, and `drain` waits for them.
+#[derive(Clone)]
+pub struct Shutdown {
+    token: CancellationToken,
+    workers: TaskTracker,
+    grace: Duration,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_SHUTDOWN_GRACE_SECONDS))
+    }
+}
+
+impl Shutdown {
+    pub fn new(grace: Duration) -> Self {
+        Self { token: CancellationToken::new(), workers: TaskTracker::new(), grace }
+    }
+
+    /// Grace period from `SHUTDOWN_GRACE_SECONDS`
+    pub fn from_env() -> Self {
+        let seconds = std::env::var("SHUTDOWN_GRACE_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_SHUTDOWN_GRACE_SECONDS);
+        Self::new(Duration::from_secs(seconds))
+    }
+
+    pub fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// Spawn a background worker that shutdown waits for. It gets its own
+    /// handle to check between jobs.
+    pub fn spawn<W, F>(&self, worker: W)
+    where
+        W: FnOnce(Shutdown) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.workers.spawn(worker(self.clone()));
+    }
+
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown begins
+    pub async fn triggered(self) {
+        self.token.cancelled_owned().await
+    }
+
+    /// Waits for the next tick; false instead once shutdown begins
+    pub async fn tick(&self, interval: &mut Interval) -> bool {
+        tokio::select! {
+            _ = self.token.cancelled() => false,
+            _ = interval.tick() => !self.is_triggered(),
+        }
+    }
+
+    /// Sleeps for `duration`; false if shutdown began first
+    pub async fn sleep(&self, duration: Duration) -> bool {
+        tokio::select! {
+            _ = self.token.cancelled() => false,
+            _ = tokio::time::sleep(duration) => !self.is_triggered(),
+        }
+    }
+
+    /// Begins shutdown on SIGINT or SIGTERM
+    pub async fn trigger_on_signal(self) {
+        let ctrl_c = async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                tracing::error!("Failed to listen for Ctrl+C: {}", e);
+                std::future::pending::<()>().await;
+            }
+        };
+
+        #[cfg(unix)]
+        let terminate = async {
+            match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(mut signal) => {
+                    signal.recv().await;
+                }
+                Err(e) => {
+                    tracing::error!("Failed to listen for SIGTERM: {}", e);
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
+
+        tokio::select! {
+            _ = ctrl_c => tracing::info!("Received SIGINT, shutting down"),
+            _ = terminate => tracing::info!("Received SIGTERM, shutting down"),
+            _ = self.token.cancelled() => return,
+        }
+        self.trigger();
+    }
+
+    /// Resolves `grace` after shutdown begins
+    pub async fn grace_expired(self) {
+        self.token.cancelled().await;
+        tokio::time::sleep(self.grace).await;
+    }
+
+    /// Waits up to `timeout` for workers to finish their current job. False if
+    /// some were still running.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.trigger();
+        self.workers.close();
+        tokio::time::timeout(timeout, self.workers.wait()).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_workers_finish_the_job_in_hand_then_stop() {
+        let shutdown = Shutdown::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let finished = Arc::new(AtomicUsize::new(0));
+
+        let (worker_runs, worker_finished) = (runs.clone(), finished.clone());
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(60));
+            while shutdown.tick(&mut interval).await {
+                worker_runs.fetch_add(1, Ordering::SeqCst);
+                // A slow job that is under way when shutdown begins
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                worker_finished.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(shutdown.drain(Duration::from_secs(5)).await);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(finished.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_drain_gives_up_after_the_timeout() {
+        let shutdown = Shutdown::default();
+        shutdown.spawn(|_| async { tokio::time::sleep(Duration::from_secs(3600)).await });
+
+        assert!(!shutdown.drain(Duration::from_millis(50)).await);
+        assert!(shutdown.is_triggered());
+        assert!(!shutdown.sleep(Duration::from_secs(1)).await);
+    }
+}
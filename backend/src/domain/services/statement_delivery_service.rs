@@ -0,0 +1,252 @@
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::domain::models::{ClientHierarchyStatement, StatementRecipient};
+use crate::domain::services::clock::SharedClock;
+use crate::domain::services::email_queue_service::EmailJobType;
+use crate::domain::services::email_service::{ClientStatementEmail, StatementEmailLine};
+use crate::domain::services::{ClientService, EmailQueueService, Shutdown};
+use crate::infrastructure::repositories::StatementDeliveryRepository;
+
+/// Checked hourly so a restart on the 1st still delivers that day
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Error)]
+pub enum StatementDeliveryError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for StatementDeliveryError {
+    fn from(err: sqlx::Error) -> Self {
+        StatementDeliveryError::DatabaseError(err.to_string())
+    }
+}
+
+/// First and last day of the month before `today`
+pub fn previous_month(today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let this_month = today.with_day(1).unwrap_or(today);
+    let start = this_month - Months::new(1);
+    (start, this_month.pred_opt().unwrap_or(start))
+}
+
+/// Statement email for one period, built from the client's hierarchy statement.
+/// None when there is nothing to report (no invoices in the period, nothing owed).
+pub fn build_statement_email(
+    statement: &ClientHierarchyStatement,
+    seller_name: &str,
+    period_start: NaiveDate,
+    period_end: NaiveDate,
+) -> Option<ClientStatementEmail> {
+    let invoices: Vec<StatementEmailLine> = statement
+        .invoices
+        .iter()
+        .filter(|invoice| invoice.issue_date >= period_start && invoice.issue_date <= period_end)
+        .map(|invoice| StatementEmailLine {
+            invoice_number: invoice.invoice_number.clone(),
+            issue_date: invoice.issue_date.to_string(),
+            total_amount: invoice.total_amount.to_f64().unwrap_or_default(),
+            balance_due: invoice.balance_due.to_f64().unwrap_or_default(),
+        })
+        .collect();
+
+    if invoices.is_empty() && statement.outstanding_balance <= Decimal::ZERO {
+        return None;
+    }
+
+    Some(ClientStatementEmail {
+        seller_name: seller_name.to_string(),
+        period_label: period_start.format("%B %Y").to_string(),
+        period_invoiced: invoices.iter().map(|line| line.total_amount).sum(),
+        outstanding_balance: statement.outstanding_balance.to_f64().unwrap_or_default(),
+        invoices,
+    })
+}
+
+/// Sends each opted-in client a statement of the prior month on the 1st,
+/// through the email queue.
+pub struct StatementDeliveryService {
+    repo: StatementDeliveryRepository,
+    client_service: Arc<ClientService>,
+    email_queue: Arc<EmailQueueService>,
+    clock: SharedClock,
+}
+
+impl StatementDeliveryService {
+    pub fn new(
+        repo: StatementDeliveryRepository,
+        client_service: Arc<ClientService>,
+        email_queue: Arc<EmailQueueService>,
+        clock: SharedClock,
+    ) -> Self {
+        Self {
+            repo,
+            client_service,
+            email_queue,
+            clock,
+        }
+    }
+
+    /// Deliver last month's statements if today is the 1st. Returns the number handed off.
+    pub async fn deliver_due(&self) -> Result<usize, StatementDeliveryError> {
+        let today = self.clock.today();
+        if today.day() != 1 {
+            return Ok(0);
+        }
+
+        let (period_start, period_end) = previous_month(today);
+        self.deliver_for_period(period_start, period_end).await
+    }
+
+    pub async fn deliver_for_period(
+        &self,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+    ) -> Result<usize, StatementDeliveryError> {
+        let mut delivered = 0;
+
+        for recipient in self.repo.list_due(period_start).await? {
+            let statement = match self
+                .client_service
+                .get_hierarchy_statement(recipient.user_id, recipient.client_id)
+                .await?
+            {
+                Some(statement) => statement,
+                None => continue,
+            };
+
+            let email = match build_statement_email(&statement, &recipient.seller_name, period_start, period_end) {
+                Some(email) => email,
+                None => continue,
+            };
+
+            if !self.repo.record(&recipient, period_start, period_end).await? {
+                continue;
+            }
+
+            if let Err(e) = self.dispatch(&recipient, email).await {
+                tracing::error!(client_id = %recipient.client_id, "Failed to send monthly statement: {}", e);
+                self.repo.release(recipient.client_id, period_start).await?;
+                continue;
+            }
+
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    async fn dispatch(&self, recipient: &StatementRecipient, statement: ClientStatementEmail) -> Result<(), String> {
+        self.email_queue
+            .enqueue_now(EmailJobType::SendClientStatement {
+                to_email: recipient.client_email.clone(),
+                to_name: recipient.client_name.clone(),
+                statement,
+            })
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Spawn the monthly statement schedule
+    pub fn start_monthly_schedule(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(SCHEDULE_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.deliver_due().await {
+                    Ok(0) => {}
+                    Ok(sent) => tracing::info!("Queued {} monthly client statement(s)", sent),
+                    Err(e) => tracing::error!("Monthly statement run failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::{InvoiceResponse, InvoiceStatus};
+    use chrono::Utc;
+    use rust_decimal::prelude::FromPrimitive;
+    use uuid::Uuid;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn invoice(number: &str, issue_date: NaiveDate, total: f64, balance: f64) -> InvoiceResponse {
+        InvoiceResponse {
+            id: Uuid::new_v4(),
+            invoice_number: number.to_string(),
+            status: InvoiceStatus::Sent,
+            client_name: "Acme".to_string(),
+            client_email: Some("billing@acme.test".to_string()),
+            issue_date,
+            due_date: issue_date,
+            total_amount: Decimal::from_f64(total).unwrap(),
+            balance_due: Decimal::from_f64(balance).unwrap(),
+            days_until_due: 0,
+            is_overdue: false,
+            label: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    fn statement(invoices: Vec<InvoiceResponse>, outstanding: f64) -> ClientHierarchyStatement {
+        ClientHierarchyStatement {
+            client_id: Uuid::new_v4(),
+            name: "Acme".to_string(),
+            total_invoiced: invoices.iter().map(|i| i.total_amount).sum(),
+            total_paid: Decimal::ZERO,
+            outstanding_balance: Decimal::from_f64(outstanding).unwrap(),
+            balances: vec![],
+            invoices,
+        }
+    }
+
+    #[test]
+    fn test_previous_month() {
+        assert_eq!(previous_month(date(2025, 3, 1)), (date(2025, 2, 1), date(2025, 2, 28)));
+        assert_eq!(previous_month(date(2024, 3, 1)), (date(2024, 2, 1), date(2024, 2, 29)));
+        assert_eq!(previous_month(date(2025, 1, 1)), (date(2024, 12, 1), date(2024, 12, 31)));
+        assert_eq!(previous_month(date(2025, 7, 15)), (date(2025, 6, 1), date(2025, 6, 30)));
+    }
+
+    #[test]
+    fn test_statement_email_covers_only_the_period() {
+        let (start, end) = previous_month(date(2025, 3, 1));
+        let statement = statement(
+            vec![
+                invoice("INV-1", date(2025, 1, 20), 100.0, 100.0),
+                invoice("INV-2", date(2025, 2, 1), 50.0, 0.0),
+                invoice("INV-3", date(2025, 2, 28), 25.0, 25.0),
+                invoice("INV-4", date(2025, 3, 1), 10.0, 10.0),
+            ],
+            135.0,
+        );
+
+        let email = build_statement_email(&statement, "Seller Co", start, end).unwrap();
+        let numbers: Vec<&str> = email.invoices.iter().map(|l| l.invoice_number.as_str()).collect();
+        assert_eq!(numbers, ["INV-2", "INV-3"]);
+        assert_eq!(email.period_label, "February 2025");
+        assert_eq!(email.period_invoiced, 75.0);
+        assert_eq!(email.outstanding_balance, 135.0);
+    }
+
+    #[test]
+    fn test_nothing_to_report_is_skipped() {
+        let (start, end) = previous_month(date(2025, 3, 1));
+        let settled = statement(vec![invoice("INV-1", date(2025, 1, 20), 100.0, 0.0)], 0.0);
+        assert!(build_statement_email(&settled, "Seller Co", start, end).is_none());
+
+        // An old unpaid balance is still worth a statement
+        let owing = statement(vec![invoice("INV-1", date(2025, 1, 20), 100.0, 100.0)], 100.0);
+        let email = build_statement_email(&owing, "Seller Co", start, end).unwrap();
+        assert!(email.invoices.is_empty());
+    }
+}
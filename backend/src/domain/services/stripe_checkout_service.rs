@@ -0,0 +1,348 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::domain::models::InvoiceDetailResponse;
+use crate::domain::services::payment_gateway_service::{CheckoutSession, CreateCheckoutSession, PaymentGatewayError};
+use crate::domain::services::{EnhancedNotificationService, FxRateService, PaymentGatewayService, ReportCache, SharedClock};
+use crate::infrastructure::repositories::{InvoiceRepository, PaymentRepository};
+
+const STRIPE_GATEWAY: &str = "stripe";
+const DEFAULT_PUBLIC_URL: &str = "https://app.flashbill.com";
+const DEFAULT_RETURN_URL: &str = "https://app.flashbill.com/payments/complete";
+
+#[derive(Debug, Error)]
+pub enum StripeCheckoutError {
+    #[error("Checkout session not found")]
+    NotFound,
+
+    #[error("Invalid webhook signature")]
+    InvalidSignature,
+
+    #[error("{0}")]
+    NotConfigured(String),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Gateway error: {0}")]
+    Gateway(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for StripeCheckoutError {
+    fn from(err: sqlx::Error) -> Self {
+        StripeCheckoutError::DatabaseError(err.to_string())
+    }
+}
+
+impl From<PaymentGatewayError> for StripeCheckoutError {
+    fn from(err: PaymentGatewayError) -> Self {
+        match err {
+            PaymentGatewayError::Config(msg) => StripeCheckoutError::NotConfigured(msg),
+            other => StripeCheckoutError::Gateway(other.to_string()),
+        }
+    }
+}
+
+/// Where a payer is sent back to once they leave the hosted page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckoutOutcome {
+    Paid,
+    /// Completed, but the payment method settles later (e.g. bank debits)
+    Processing,
+    Cancelled,
+}
+
+impl std::fmt::Display for CheckoutOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckoutOutcome::Paid => write!(f, "paid"),
+            CheckoutOutcome::Processing => write!(f, "processing"),
+            CheckoutOutcome::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    data: StripeEventData,
+}
+
+#[derive(Debug, Deserialize)]
+struct StripeEventData {
+    object: serde_json::Value,
+}
+
+#[derive(Debug)]
+enum SessionEvent {
+    Paid(CheckoutSession),
+    Failed(CheckoutSession),
+}
+
+impl StripeEvent {
+    /// The checkout session this event settles or fails, if it's one of those
+    fn session_event(&self) -> Option<SessionEvent> {
+        let session = || serde_json::from_value::<CheckoutSession>(self.data.object.clone()).ok();
+        match self.kind.as_str() {
+            // Delayed payment methods complete the session unpaid and follow up later
+            "checkout.session.completed" => session().filter(CheckoutSession::is_paid).map(SessionEvent::Paid),
+            "checkout.session.async_payment_succeeded" => session().map(SessionEvent::Paid),
+            "checkout.session.async_payment_failed" | "checkout.session.expired" => session().map(SessionEvent::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// Guest and portal card payments through Stripe's hosted Checkout page. Each
+/// session is recorded as a pending payment under the session ID and settled by
+/// whichever arrives first: the payer returning to the success URL or Stripe's
+/// `checkout.session.*` webhook.
+pub struct StripeCheckoutService {
+    gateway: Arc<PaymentGatewayService>,
+    payment_repo: Arc<PaymentRepository>,
+    invoice_repo: Arc<InvoiceRepository>,
+    notifications: Arc<EnhancedNotificationService>,
+    webhook_secret: Option<String>,
+    /// Base of the success and cancel callbacks Stripe redirects to (PUBLIC_API_URL)
+    public_url: String,
+    /// Page the callbacks send the payer on to (CHECKOUT_RETURN_URL)
+    return_url: String,
+    clock: SharedClock,
+    /// Settled payments make the seller's cached reports stale
+    reports: ReportCache,
+    fx_rates: Option<Arc<FxRateService>>,
+}
+
+impl StripeCheckoutService {
+    pub fn new(
+        gateway: Arc<PaymentGatewayService>,
+        payment_repo: Arc<PaymentRepository>,
+        invoice_repo: Arc<InvoiceRepository>,
+        notifications: Arc<EnhancedNotificationService>,
+        clock: SharedClock,
+    ) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        // Stripe signs each endpoint with its own secret
+        let webhook_secret = env("STRIPE_CHECKOUT_WEBHOOK_SECRET").or_else(|| env("STRIPE_WEBHOOK_SECRET"));
+        let public_url = env("PUBLIC_API_URL").unwrap_or_else(|| DEFAULT_PUBLIC_URL.to_string());
+        let return_url = env("CHECKOUT_RETURN_URL").unwrap_or_else(|| DEFAULT_RETURN_URL.to_string());
+
+        Self {
+            gateway,
+            payment_repo,
+            invoice_repo,
+            notifications,
+            webhook_secret,
+            public_url: public_url.trim_end_matches('/').to_string(),
+            return_url,
+            clock,
+            reports: ReportCache::default(),
+            fx_rates: None,
+        }
+    }
+
+    /// Invalidate the seller's cached reports when a payment settles
+    pub fn with_report_cache(mut self, reports: ReportCache) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    /// Book realized FX gains and losses on the payments it settles
+    pub fn with_fx_rates(mut self, fx_rates: Arc<FxRateService>) -> Self {
+        self.fx_rates = Some(fx_rates);
+        self
+    }
+
+    /// Open a hosted payment page for part or all of an invoice
+    pub async fn create_session(
+        &self,
+        invoice: &InvoiceDetailResponse,
+        amount: Decimal,
+        customer_email: Option<String>,
+    ) -> Result<CheckoutSession, PaymentGatewayError> {
+        // Stripe fills in {CHECKOUT_SESSION_ID} when it redirects
+        let callback = |action: &str| {
+            format!("{}/api/v1/checkout/stripe/{}?session_id={{CHECKOUT_SESSION_ID}}", self.public_url, action)
+        };
+
+        self.gateway
+            .create_stripe_checkout_session(CreateCheckoutSession {
+                amount: amount.to_f64().unwrap_or_default(),
+                currency: invoice.currency.clone(),
+                description: format!("Invoice {}", invoice.invoice_number),
+                reference: invoice.id.to_string(),
+                customer_email: customer_email.or_else(|| invoice.client_email.clone()),
+                success_url: callback("success"),
+                cancel_url: callback("cancel"),
+            })
+            .await
+    }
+
+    /// The payer is back from the hosted page. Settles the payment if Stripe
+    /// reports it paid and returns where to send them next.
+    pub async fn complete(&self, session_id: &str) -> Result<String, StripeCheckoutError> {
+        let session = self.gateway.fetch_stripe_checkout_session(session_id).await?;
+        let invoice_number = self.invoice_number(&session).await?;
+
+        let outcome = if session.is_paid() {
+            self.settle(&session, None).await?;
+            CheckoutOutcome::Paid
+        } else {
+            CheckoutOutcome::Processing
+        };
+        Ok(self.return_to(outcome, &invoice_number))
+    }
+
+    /// The payer backed out. The session is expired so it can't be paid later
+    /// and its pending payment is dropped.
+    pub async fn cancel(&self, session_id: &str) -> Result<String, StripeCheckoutError> {
+        let session = self.gateway.fetch_stripe_checkout_session(session_id).await?;
+        let invoice_number = self.invoice_number(&session).await?;
+
+        // Paid in another tab after all
+        if session.is_paid() {
+            self.settle(&session, None).await?;
+            return Ok(self.return_to(CheckoutOutcome::Paid, &invoice_number));
+        }
+        if session.status.as_deref() == Some("open") {
+            self.gateway.expire_stripe_checkout_session(&session.id).await?;
+        }
+
+        self.payment_repo.fail_pending_gateway_payment(STRIPE_GATEWAY, &session.id).await?;
+        Ok(self.return_to(CheckoutOutcome::Cancelled, &invoice_number))
+    }
+
+    /// Handle a signed Stripe delivery. Paid sessions settle their pending payment,
+    /// expired and failed ones drop it; returns whether a payment changed. Other
+    /// events, and redeliveries, are acknowledged and ignored.
+    pub async fn handle_webhook(&self, payload: &str, signature: &str) -> Result<bool, StripeCheckoutError> {
+        let secret = self
+            .webhook_secret
+            .as_deref()
+            .ok_or_else(|| StripeCheckoutError::NotConfigured("Stripe webhooks not configured".to_string()))?;
+
+        PaymentGatewayService::verify_stripe_signature(payload, signature, secret, self.clock.now().timestamp())
+            .map_err(|_| StripeCheckoutError::InvalidSignature)?;
+
+        let event: StripeEvent = serde_json::from_str(payload)
+            .map_err(|e| StripeCheckoutError::Validation(format!("Invalid event payload: {}", e)))?;
+
+        match event.session_event() {
+            Some(SessionEvent::Paid(session)) => {
+                let payer_email = event.data.object["customer_details"]["email"].as_str().map(str::to_string);
+                self.settle(&session, payer_email).await
+            }
+            Some(SessionEvent::Failed(session)) => {
+                Ok(self.payment_repo.fail_pending_gateway_payment(STRIPE_GATEWAY, &session.id).await?)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Settle the session's pending payment once, confirm it to the payer and
+    /// re-key it by payment intent, which is what Stripe payouts report
+    async fn settle(&self, session: &CheckoutSession, payer_email: Option<String>) -> Result<bool, StripeCheckoutError> {
+        let settlement_rate = self.settlement_rate(&session.id).await?;
+        let Some(invoice_id) = self.payment_repo.settle_pending_gateway_payment(STRIPE_GATEWAY, &session.id, settlement_rate).await? else {
+            return Ok(false);
+        };
+        if let Some(payment_intent) = &session.payment_intent {
+            self.payment_repo.update_gateway_payment_id(STRIPE_GATEWAY, &session.id, payment_intent).await?;
+        }
+
+        // Best effort: the payment stands even if the confirmation can't be sent
+        match self.invoice_repo.get_invoice_by_id(invoice_id).await {
+            Ok(invoice) => {
+                self.reports.invalidate_user(invoice.user_id).await;
+                let email = payer_email.or_else(|| invoice.client_email.clone());
+                let phone = invoice.client_phone.clone();
+                if let Err(e) = self.notifications.send_payment_confirmation(&invoice, email, phone).await {
+                    tracing::warn!("Payment confirmation for invoice {} failed: {}", invoice.invoice_number, e);
+                }
+            }
+            Err(e) => tracing::warn!("Settled invoice {} could not be loaded for confirmation: {}", invoice_id, e),
+        }
+
+        Ok(true)
+    }
+
+    /// Rate the pending payment settles at, for its invoice's realized FX result
+    async fn settlement_rate(&self, gateway_payment_id: &str) -> Result<Option<f64>, sqlx::Error> {
+        let Some(fx_rates) = &self.fx_rates else {
+            return Ok(None);
+        };
+        let references = [gateway_payment_id.to_string()];
+        let Some(invoice_id) = self.payment_repo.find_gateway_payment_invoice(STRIPE_GATEWAY, &references).await? else {
+            return Ok(None);
+        };
+        let invoice = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        Ok(fx_rates.settlement_rate(invoice.user_id, &invoice.currency, None).await)
+    }
+
+    /// Only sessions started here are followed up, found by session or payment intent
+    async fn invoice_number(&self, session: &CheckoutSession) -> Result<String, StripeCheckoutError> {
+        let references: Vec<String> = std::iter::once(session.id.clone()).chain(session.payment_intent.clone()).collect();
+        let invoice_id = self
+            .payment_repo
+            .find_gateway_payment_invoice(STRIPE_GATEWAY, &references)
+            .await?
+            .ok_or(StripeCheckoutError::NotFound)?;
+
+        let invoice = self.invoice_repo.get_invoice_by_id(invoice_id).await?;
+        Ok(invoice.invoice_number)
+    }
+
+    fn return_to(&self, outcome: CheckoutOutcome, invoice_number: &str) -> String {
+        let separator = if self.return_url.contains('?') { '&' } else { '?' };
+        format!("{}{}status={}&invoice={}", self.return_url, separator, outcome, invoice_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(json: serde_json::Value) -> StripeEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn session(payment_status: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "cs_test_1",
+            "url": null,
+            "status": "complete",
+            "payment_status": payment_status,
+            "payment_intent": "pi_1",
+            "client_reference_id": "inv_1"
+        })
+    }
+
+    #[test]
+    fn test_only_paid_sessions_settle() {
+        let paid = event(serde_json::json!({ "type": "checkout.session.completed", "data": { "object": session("paid") } }));
+        assert!(matches!(paid.session_event(), Some(SessionEvent::Paid(s)) if s.id == "cs_test_1"));
+
+        // Bank debits complete the session before the money arrives
+        let unpaid = event(serde_json::json!({ "type": "checkout.session.completed", "data": { "object": session("unpaid") } }));
+        assert!(unpaid.session_event().is_none());
+
+        let succeeded = event(serde_json::json!({
+            "type": "checkout.session.async_payment_succeeded",
+            "data": { "object": session("paid") }
+        }));
+        assert!(matches!(succeeded.session_event(), Some(SessionEvent::Paid(_))));
+
+        let expired = event(serde_json::json!({ "type": "checkout.session.expired", "data": { "object": session("unpaid") } }));
+        assert!(matches!(expired.session_event(), Some(SessionEvent::Failed(_))));
+
+        let payout = event(serde_json::json!({ "type": "payout.paid", "data": { "object": { "id": "po_1" } } }));
+        assert!(payout.session_event().is_none());
+    }
+}
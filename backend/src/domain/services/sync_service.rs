@@ -0,0 +1,280 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    ApplySyncMutations, BatchResult, SyncApplyResult, SyncAppliedMutation, SyncChanges, SyncConflict, SyncCursor,
+    SyncEntity, SyncEntityType, SyncSettings, SyncTombstone, SyncWriteOutcome, MAX_SYNC_MUTATIONS,
+};
+use crate::domain::services::{SharedClock, Shutdown};
+use crate::infrastructure::repositories::{
+    ClientRepository, InvoiceRepository, PaymentRepository, SyncRepository, UserRepository,
+};
+
+pub const DEFAULT_SYNC_LIMIT: i64 = 200;
+pub const MAX_SYNC_LIMIT: i64 = 500;
+
+/// Longest a long-poll sync waits for a change
+pub const MAX_SYNC_WAIT_SECS: u64 = 25;
+
+/// Changes younger than this are held back until any transaction that wrote an
+/// earlier timestamp has had time to commit
+const SYNC_SETTLE_SECS: f64 = 2.0;
+
+/// How often a waiting sync looks for new changes
+const SYNC_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Deletions are kept this long; older cursors need a full sync
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+const TOMBSTONE_PRUNE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+
+    #[error("Cursor is older than {0} days; sync again without since")]
+    CursorExpired(i64),
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for SyncError {
+    fn from(err: sqlx::Error) -> Self {
+        SyncError::DatabaseError(err.to_string())
+    }
+}
+
+/// Incremental sync for offline clients: a feed of invoices, clients, payments and
+/// settings changed since a cursor, plus deletions.
+pub struct SyncService {
+    repo: SyncRepository,
+    invoice_repo: InvoiceRepository,
+    client_repo: ClientRepository,
+    payment_repo: PaymentRepository,
+    user_repo: UserRepository,
+    clock: SharedClock,
+}
+
+impl SyncService {
+    pub fn new(
+        repo: SyncRepository,
+        invoice_repo: InvoiceRepository,
+        client_repo: ClientRepository,
+        payment_repo: PaymentRepository,
+        user_repo: UserRepository,
+        clock: SharedClock,
+    ) -> Self {
+        Self { repo, invoice_repo, client_repo, payment_repo, user_repo, clock }
+    }
+
+    /// Changes after `since`, or every live entity without it. With `wait_secs`, an
+    /// incremental sync that finds nothing keeps looking until a change arrives or
+    /// the wait runs out.
+    pub async fn changes(
+        &self,
+        user_id: Uuid,
+        since: Option<&str>,
+        limit: Option<i64>,
+        wait_secs: Option<u64>,
+    ) -> Result<SyncChanges, SyncError> {
+        let limit = limit.unwrap_or(DEFAULT_SYNC_LIMIT);
+        if !(1..=MAX_SYNC_LIMIT).contains(&limit) {
+            return Err(SyncError::Validation(format!("limit must be between 1 and {}", MAX_SYNC_LIMIT)));
+        }
+        let wait_secs = wait_secs.unwrap_or(0);
+        if wait_secs > MAX_SYNC_WAIT_SECS {
+            return Err(SyncError::Validation(format!("wait must be at most {} seconds", MAX_SYNC_WAIT_SECS)));
+        }
+
+        let since = since.map(SyncCursor::decode).transpose().map_err(SyncError::InvalidCursor)?;
+        if let Some(cursor) = since {
+            if cursor.changed_at < self.clock.now() - chrono::Duration::days(TOMBSTONE_RETENTION_DAYS) {
+                return Err(SyncError::CursorExpired(TOMBSTONE_RETENTION_DAYS));
+            }
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(wait_secs);
+        loop {
+            let changes = self.collect(user_id, since, limit).await?;
+            let now = tokio::time::Instant::now();
+            if !changes.is_empty() || since.is_none() || now >= deadline {
+                return Ok(changes);
+            }
+            tokio::time::sleep(SYNC_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    async fn collect(&self, user_id: Uuid, since: Option<SyncCursor>, limit: i64) -> Result<SyncChanges, SyncError> {
+        let mut feed = self.repo.changes(user_id, since, SYNC_SETTLE_SECS, limit + 1).await?;
+        let has_more = feed.len() as i64 > limit;
+        feed.truncate(limit as usize);
+
+        let cursor = feed
+            .last()
+            .map(|change| SyncCursor { changed_at: change.changed_at, entity_id: change.entity_id })
+            .or(since);
+
+        let changed = |entity_type: SyncEntityType| -> Vec<Uuid> {
+            feed.iter()
+                .filter(|change| change.entity_type == entity_type && !change.deleted)
+                .map(|change| change.entity_id)
+                .collect()
+        };
+        let invoice_ids = changed(SyncEntityType::Invoice);
+        let client_ids = changed(SyncEntityType::Client);
+        let payment_ids = changed(SyncEntityType::Payment);
+
+        // An entity deleted between the feed query and these lookups is skipped here
+        // and comes through as a deletion on the next sync
+        let invoices = if invoice_ids.is_empty() {
+            Vec::new()
+        } else {
+            let found = self.invoice_repo.get_by_ids(user_id, &invoice_ids).await?;
+            BatchResult::new(&invoice_ids, found, |invoice| invoice.id).items
+        };
+        let clients = if client_ids.is_empty() {
+            Vec::new()
+        } else {
+            let found = self.client_repo.find_by_ids(user_id, &client_ids).await?;
+            BatchResult::new(&client_ids, found, |client| client.id).items
+        };
+        let payments = if payment_ids.is_empty() {
+            Vec::new()
+        } else {
+            let found = self.payment_repo.find_by_ids(user_id, &payment_ids).await?;
+            BatchResult::new(&payment_ids, found, |payment| payment.id).items
+        };
+
+        let settings = if feed.iter().any(|change| change.entity_type == SyncEntityType::Settings) {
+            self.user_repo.find_by_id(user_id).await?.map(SyncSettings::from)
+        } else {
+            None
+        };
+
+        let deleted = feed
+            .iter()
+            .filter(|change| change.deleted)
+            .map(|change| SyncTombstone {
+                entity_type: change.entity_type,
+                id: change.entity_id,
+                deleted_at: change.changed_at,
+            })
+            .collect();
+
+        Ok(SyncChanges {
+            cursor: cursor.map(|cursor| cursor.encode()),
+            has_more,
+            invoices,
+            clients,
+            payments,
+            settings,
+            deleted,
+        })
+    }
+
+    /// Apply a batch of offline edits. Invalid mutations reject the whole batch; edits
+    /// whose base version is stale come back as conflicts with the server copy, and the
+    /// rest are committed together.
+    pub async fn apply(&self, user_id: Uuid, batch: ApplySyncMutations) -> Result<SyncApplyResult, SyncError> {
+        let mutations = batch.mutations;
+        if mutations.is_empty() {
+            return Err(SyncError::Validation("mutations must not be empty".to_string()));
+        }
+        if mutations.len() > MAX_SYNC_MUTATIONS {
+            return Err(SyncError::Validation(format!("At most {} mutations per batch", MAX_SYNC_MUTATIONS)));
+        }
+
+        let mut seen = HashSet::new();
+        let mut writes = Vec::with_capacity(mutations.len());
+        for mutation in &mutations {
+            if !seen.insert((mutation.entity_type, mutation.entity_id)) {
+                return Err(SyncError::Validation(format!(
+                    "Mutation {}: combine edits to the same entity into one mutation",
+                    mutation.mutation_id
+                )));
+            }
+            let write = mutation
+                .to_write()
+                .map_err(|e| SyncError::Validation(format!("Mutation {}: {}", mutation.mutation_id, e)))?;
+            writes.push((mutation.entity_id, mutation.base_version, write));
+        }
+
+        let outcomes = self.repo.apply(user_id, &writes).await?;
+
+        // Read back the committed copies: the result of each applied edit, and the
+        // server version each conflict has to be resolved against
+        let ids_of = |entity_type: SyncEntityType| -> Vec<Uuid> {
+            mutations
+                .iter()
+                .zip(&outcomes)
+                .filter(|(mutation, outcome)| {
+                    mutation.entity_type == entity_type && !matches!(outcome, SyncWriteOutcome::Applied(None))
+                })
+                .map(|(mutation, _)| mutation.entity_id)
+                .collect()
+        };
+        let mut entities: HashMap<Uuid, SyncEntity> = HashMap::new();
+        let client_ids = ids_of(SyncEntityType::Client);
+        if !client_ids.is_empty() {
+            for client in self.client_repo.find_by_ids(user_id, &client_ids).await? {
+                entities.insert(client.id, SyncEntity::Client(Box::new(client)));
+            }
+        }
+        let invoice_ids = ids_of(SyncEntityType::Invoice);
+        if !invoice_ids.is_empty() {
+            for invoice in self.invoice_repo.get_by_ids(user_id, &invoice_ids).await? {
+                entities.insert(invoice.id, SyncEntity::Invoice(Box::new(invoice)));
+            }
+        }
+
+        let mut result = SyncApplyResult { applied: Vec::new(), conflicts: Vec::new() };
+        for (mutation, outcome) in mutations.into_iter().zip(outcomes) {
+            let entity = entities.remove(&mutation.entity_id);
+            match outcome {
+                SyncWriteOutcome::Applied(version) => result.applied.push(SyncAppliedMutation {
+                    mutation_id: mutation.mutation_id,
+                    entity_type: mutation.entity_type,
+                    entity_id: mutation.entity_id,
+                    op: mutation.op,
+                    version,
+                    entity,
+                }),
+                SyncWriteOutcome::Conflict(reason, current_version) => result.conflicts.push(SyncConflict {
+                    mutation_id: mutation.mutation_id,
+                    entity_type: mutation.entity_type,
+                    entity_id: mutation.entity_id,
+                    op: mutation.op,
+                    reason,
+                    message: reason.message().to_string(),
+                    current_version,
+                    current: entity,
+                }),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Spawn the daily loop that drops deletions past the retention period
+    pub fn start_tombstone_pruning(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(TOMBSTONE_PRUNE_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                let before = self.clock.now() - chrono::Duration::days(TOMBSTONE_RETENTION_DAYS);
+                match self.repo.prune_tombstones(before).await {
+                    Ok(pruned) => tracing::info!("Pruned {} sync tombstone(s)", pruned),
+                    Err(e) => tracing::error!("Sync tombstone pruning failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -2,9 +2,11 @@
 
 use crate::domain::models::{
     TaxSetting, CreateTaxSetting, UpdateTaxSetting, TaxCalculation, TaxSummary,
-    TaxBreakdownItem, validate_tax_rate, validate_tax_id
+    TaxBreakdownItem, TaxLine, validate_tax_rate, validate_tax_id
 };
 use crate::domain::repositories::tax_repository::TaxRepository;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use std::sync::Arc;
 use uuid::Uuid;
 use thiserror::Error;
@@ -251,36 +253,45 @@ impl TaxService {
         _organization_id: Uuid,
         invoices: Vec<crate::domain::models::Invoice>,
     ) -> Result<TaxSummary, TaxError> {
-        let mut subtotal = 0.0;
-        let mut tax_collected = 0.0;
-        let mut total = 0.0;
+        let mut subtotal = Decimal::ZERO;
+        let mut tax_collected = Decimal::ZERO;
+        let mut total = Decimal::ZERO;
 
-        let mut breakdown_map = std::collections::HashMap::new();
+        let mut breakdown: Vec<TaxLine> = Vec::new();
 
         for invoice in invoices {
             subtotal += invoice.subtotal;
             tax_collected += invoice.tax_amount;
             total += invoice.total_amount;
 
-            // Group by tax label
-            if let Some(tax_label) = &invoice.tax_label {
-                let entry = breakdown_map.entry(tax_label.clone())
-                    .or_insert((0.0, 0.0));
-                entry.0 += invoice.subtotal;
-                entry.1 += invoice.tax_amount;
+            // Each tax on its own line, grouped by label and rate
+            for line in TaxLine::breakdown(&invoice.items, invoice.tax_label.as_deref()) {
+                match breakdown.iter_mut().find(|l| l.label == line.label && l.rate == line.rate) {
+                    Some(existing) => {
+                        existing.taxable_amount += line.taxable_amount;
+                        existing.tax_amount += line.tax_amount;
+                    }
+                    None => breakdown.push(line),
+                }
             }
         }
 
-        let tax_breakdown = breakdown_map
+        let tax_breakdown = breakdown
             .into_iter()
-            .map(|(label, (taxable, tax))| TaxBreakdownItem {
-                label,
-                rate: if taxable > 0.0 { tax / taxable } else { 0.0 },
-                taxable_amount: taxable,
-                tax_amount: tax,
+            .map(|line| TaxBreakdownItem {
+                label: line.label,
+                rate: line.rate.to_f64().unwrap_or_default(),
+                taxable_amount: line.taxable_amount.to_f64().unwrap_or_default(),
+                tax_amount: line.tax_amount.to_f64().unwrap_or_default(),
             })
             .collect();
 
+        let (subtotal, tax_collected, total) = (
+            subtotal.to_f64().unwrap_or_default(),
+            tax_collected.to_f64().unwrap_or_default(),
+            total.to_f64().unwrap_or_default(),
+        );
+
         Ok(TaxSummary {
             period_start: "Start".to_string(),  // Will be populated by caller
             period_end: "End".to_string(),
@@ -0,0 +1,72 @@
+use chrono::Utc;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{TemplateBundle, UpdateUser};
+use crate::infrastructure::repositories::{EmailSignatureRepository, UserRepository};
+
+#[derive(Debug, Error)]
+pub enum TemplateBundleError {
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for TemplateBundleError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => TemplateBundleError::UserNotFound,
+            _ => TemplateBundleError::DatabaseError(err.to_string()),
+        }
+    }
+}
+
+/// Exports and imports an account's invoice look as a shareable JSON bundle
+pub struct TemplateBundleService {
+    user_repo: UserRepository,
+    signatures: EmailSignatureRepository,
+}
+
+impl TemplateBundleService {
+    pub fn new(user_repo: UserRepository, signatures: EmailSignatureRepository) -> Self {
+        Self { user_repo, signatures }
+    }
+
+    pub async fn export(&self, user_id: Uuid) -> Result<TemplateBundle, TemplateBundleError> {
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(TemplateBundleError::UserNotFound)?;
+        let signature = self.signatures.find(user_id, None).await?;
+        let mut bundle = TemplateBundle::new(&user.invoice_settings.unwrap_or_default(), signature, Utc::now());
+        bundle.name = user.company_name;
+        Ok(bundle)
+    }
+
+    /// Replaces the template, terms, notes and paid stamp, and the default
+    /// signature when the bundle has one. Returns the sanitized bundle.
+    pub async fn import(&self, user_id: Uuid, bundle: TemplateBundle) -> Result<TemplateBundle, TemplateBundleError> {
+        let bundle = bundle.sanitize().map_err(TemplateBundleError::Validation)?;
+        let user = self.user_repo.find_by_id(user_id).await?.ok_or(TemplateBundleError::UserNotFound)?;
+
+        let update = UpdateUser {
+            phone: None,
+            company_name: None,
+            business_type: None,
+            business_address: None,
+            tax_settings: None,
+            notification_settings: None,
+            invoice_settings: Some(bundle.apply_to(user.invoice_settings.unwrap_or_default())),
+            locale: None,
+        };
+        self.user_repo.update(user_id, update).await?;
+
+        if let Some(signature) = &bundle.email.signature {
+            self.signatures.upsert(user_id, None, signature).await?;
+        }
+
+        Ok(bundle)
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+use rust_decimal::Decimal;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    entry_end, normalize_entry_description, validate_entry_span, validate_hourly_rate, CreateTimeEntry,
+    InvoiceFromTime, Page, StartTimer, TimeEntry, TimeEntryListFilter, UpdateTimeEntry, MAX_ENTRY_MINUTES,
+};
+use crate::domain::services::{InvoiceError, ProjectError, ProjectService, SharedClock};
+use crate::infrastructure::repositories::TimeEntryRepository;
+
+#[derive(Debug, Error)]
+pub enum TimeEntryError {
+    #[error("Time entry not found")]
+    NotFound,
+
+    #[error("Client not found")]
+    ClientNotFound,
+
+    #[error("Time entry is already billed")]
+    AlreadyBilled,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Invoice error: {0}")]
+    Invoice(#[from] InvoiceError),
+
+    #[error("Project error: {0}")]
+    Project(#[from] ProjectError),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for TimeEntryError {
+    fn from(err: sqlx::Error) -> Self {
+        TimeEntryError::DatabaseError(err.to_string())
+    }
+}
+
+/// An entry ready to go on an invoice, at the rate it bills at
+#[derive(Debug, Clone)]
+pub struct BillableEntry {
+    pub entry: TimeEntry,
+    pub hourly_rate: Decimal,
+}
+
+/// Tracked time, by timer or entered by hand, and which of it has been billed
+pub struct TimeEntryService {
+    repo: TimeEntryRepository,
+    projects: Arc<ProjectService>,
+    clock: SharedClock,
+}
+
+impl TimeEntryService {
+    pub fn new(repo: TimeEntryRepository, projects: Arc<ProjectService>, clock: SharedClock) -> Self {
+        Self { repo, projects, clock }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.clock.today()
+    }
+
+    pub async fn list_entries(&self, user_id: Uuid, filter: TimeEntryListFilter) -> Result<Page<TimeEntry>, TimeEntryError> {
+        let page = filter.page_request().map_err(TimeEntryError::Validation)?;
+        Ok(self.repo.list(user_id, &filter, &page).await?)
+    }
+
+    pub async fn create_entry(&self, user_id: Uuid, create: CreateTimeEntry) -> Result<TimeEntry, TimeEntryError> {
+        let description = normalize_entry_description(&create.description).map_err(TimeEntryError::Validation)?;
+        validate_hourly_rate(create.hourly_rate).map_err(TimeEntryError::Validation)?;
+        let ended_at = entry_end(create.started_at, create.ended_at, create.duration_minutes)
+            .map_err(TimeEntryError::Validation)?;
+        self.ensure_client(user_id, create.client_id).await?;
+        if let Some(project_id) = create.project_id {
+            self.projects.taggable_project(user_id, project_id, Some(create.client_id)).await?;
+        }
+
+        let now = self.clock.now();
+        let entry = TimeEntry {
+            id: Uuid::new_v4(),
+            client_id: create.client_id,
+            project_id: create.project_id,
+            description,
+            started_at: create.started_at,
+            ended_at: Some(ended_at),
+            duration_minutes: None,
+            hourly_rate: create.hourly_rate,
+            billable: create.billable.unwrap_or(true),
+            invoice_id: None,
+            billed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        Ok(self.repo.create(user_id, &entry).await?)
+    }
+
+    /// Start a timer now. A timer already running is stopped first; there is
+    /// only ever one per user.
+    pub async fn start_timer(&self, user_id: Uuid, start: StartTimer) -> Result<TimeEntry, TimeEntryError> {
+        let description = normalize_entry_description(&start.description).map_err(TimeEntryError::Validation)?;
+        validate_hourly_rate(start.hourly_rate).map_err(TimeEntryError::Validation)?;
+        self.ensure_client(user_id, start.client_id).await?;
+        if let Some(project_id) = start.project_id {
+            self.projects.taggable_project(user_id, project_id, Some(start.client_id)).await?;
+        }
+
+        if let Some(running) = self.repo.find_running(user_id).await? {
+            self.stop(user_id, running).await?;
+        }
+
+        let now = self.clock.now();
+        let entry = TimeEntry {
+            id: Uuid::new_v4(),
+            client_id: start.client_id,
+            project_id: start.project_id,
+            description,
+            started_at: now,
+            ended_at: None,
+            duration_minutes: None,
+            hourly_rate: start.hourly_rate,
+            billable: start.billable.unwrap_or(true),
+            invoice_id: None,
+            billed_at: None,
+            created_at: now,
+            updated_at: now,
+        };
+        self.repo.create(user_id, &entry).await.map_err(|e| match e {
+            // Another start won the race for the running slot
+            sqlx::Error::Database(db) if db.is_unique_violation() => {
+                TimeEntryError::Validation("A timer is already running".to_string())
+            }
+            other => other.into(),
+        })
+    }
+
+    pub async fn stop_timer(&self, user_id: Uuid, entry_id: Uuid) -> Result<TimeEntry, TimeEntryError> {
+        let entry = self.repo.find_by_id(user_id, entry_id).await?.ok_or(TimeEntryError::NotFound)?;
+        if !entry.is_running() {
+            return Err(TimeEntryError::Validation("The timer isn't running".to_string()));
+        }
+        self.stop(user_id, entry).await
+    }
+
+    pub async fn update_entry(
+        &self,
+        user_id: Uuid,
+        entry_id: Uuid,
+        update: UpdateTimeEntry,
+    ) -> Result<TimeEntry, TimeEntryError> {
+        let mut entry = self.repo.find_by_id(user_id, entry_id).await?.ok_or(TimeEntryError::NotFound)?;
+        if entry.is_billed() {
+            return Err(TimeEntryError::AlreadyBilled);
+        }
+
+        if let Some(client_id) = update.client_id.filter(|client_id| *client_id != entry.client_id) {
+            self.ensure_client(user_id, client_id).await?;
+            entry.client_id = client_id;
+            entry.project_id = None;
+        }
+        if let Some(description) = update.description {
+            entry.description = normalize_entry_description(&description).map_err(TimeEntryError::Validation)?;
+        }
+        if update.hourly_rate.is_some() {
+            validate_hourly_rate(update.hourly_rate).map_err(TimeEntryError::Validation)?;
+            entry.hourly_rate = update.hourly_rate;
+        }
+        if let Some(billable) = update.billable {
+            entry.billable = billable;
+        }
+        if let Some(started_at) = update.started_at {
+            entry.started_at = started_at;
+        }
+
+        if update.ended_at.is_some() || update.duration_minutes.is_some() {
+            let ended_at = entry_end(entry.started_at, update.ended_at, update.duration_minutes)
+                .map_err(TimeEntryError::Validation)?;
+            entry.ended_at = Some(ended_at);
+        } else if let Some(ended_at) = entry.ended_at {
+            validate_entry_span(entry.started_at, ended_at).map_err(TimeEntryError::Validation)?;
+        } else if entry.started_at > self.clock.now() {
+            return Err(TimeEntryError::Validation("A running timer can't start in the future".to_string()));
+        }
+
+        match self.repo.update(user_id, &entry).await? {
+            Some(entry) => Ok(entry),
+            // Billed or deleted since we read it
+            None => Err(self.missing_or_billed(user_id, entry_id).await),
+        }
+    }
+
+    pub async fn delete_entry(&self, user_id: Uuid, entry_id: Uuid) -> Result<(), TimeEntryError> {
+        if self.repo.delete(user_id, entry_id).await? {
+            Ok(())
+        } else {
+            Err(self.missing_or_billed(user_id, entry_id).await)
+        }
+    }
+
+    /// The entries `POST /invoices/from-time` would bill, oldest first. Chosen
+    /// entries must all be billable; by default the client's unbilled time is
+    /// taken, leaving out entries too short to bill. With a project, only its
+    /// time is billed.
+    pub async fn billable_entries(
+        &self,
+        user_id: Uuid,
+        request: &InvoiceFromTime,
+    ) -> Result<Vec<BillableEntry>, TimeEntryError> {
+        let client_rate = self
+            .repo
+            .client_hourly_rate(user_id, request.client_id)
+            .await?
+            .ok_or(TimeEntryError::ClientNotFound)?;
+        if let Some(project_id) = request.project_id {
+            let project = self.projects.get_project(user_id, project_id).await?;
+            if project.client_id != request.client_id {
+                return Err(TimeEntryError::Validation(format!("{} is for another client", project.name)));
+            }
+        }
+
+        let entries = match &request.entry_ids {
+            Some(ids) if ids.is_empty() => {
+                return Err(TimeEntryError::Validation("entry_ids must not be empty".to_string()));
+            }
+            Some(ids) => {
+                let entries = self.repo.find_by_ids(user_id, ids).await?;
+                if entries.len() != ids.len() {
+                    return Err(TimeEntryError::NotFound);
+                }
+                for entry in &entries {
+                    if entry.is_billed() {
+                        return Err(TimeEntryError::AlreadyBilled);
+                    }
+                    let problem = if entry.client_id != request.client_id {
+                        Some("is for another client")
+                    } else if request.project_id.is_some_and(|project_id| entry.project_id != Some(project_id)) {
+                        Some("is for another project")
+                    } else if entry.is_running() {
+                        Some("is still running")
+                    } else if !entry.billable {
+                        Some("isn't billable")
+                    } else if entry.billable_hours().is_zero() {
+                        Some("is too short to bill")
+                    } else {
+                        None
+                    };
+                    if let Some(problem) = problem {
+                        return Err(TimeEntryError::Validation(format!("Time entry {} {}", entry.id, problem)));
+                    }
+                }
+                entries
+            }
+            None => {
+                let started_before = request
+                    .until
+                    .map(|until| (until + Duration::days(1)).and_time(NaiveTime::MIN).and_utc());
+                self.repo
+                    .list_unbilled(user_id, request.client_id, request.project_id, started_before)
+                    .await?
+                    .into_iter()
+                    .filter(|entry| !entry.billable_hours().is_zero())
+                    .collect()
+            }
+        };
+
+        if entries.is_empty() {
+            return Err(TimeEntryError::Validation("No unbilled time to invoice".to_string()));
+        }
+
+        let mut project_ids: Vec<Uuid> = entries.iter().filter_map(|entry| entry.project_id).collect();
+        project_ids.sort();
+        project_ids.dedup();
+        let mut project_rates = HashMap::new();
+        for project_id in project_ids {
+            project_rates.insert(project_id, self.projects.get_project(user_id, project_id).await?.hourly_rate);
+        }
+
+        entries
+            .into_iter()
+            .map(|entry| {
+                let project_rate = entry.project_id.and_then(|project_id| project_rates[&project_id]);
+                let hourly_rate = entry.hourly_rate.or(project_rate).or(client_rate).ok_or_else(|| {
+                    TimeEntryError::Validation("Set an hourly rate on the client, the project or the entry".to_string())
+                })?;
+                Ok(BillableEntry { entry, hourly_rate })
+            })
+            .collect()
+    }
+
+    /// Mark the entries billed on the invoice. Fails with `AlreadyBilled`, marking
+    /// none, if any of them was billed in the meantime.
+    pub async fn mark_billed(&self, user_id: Uuid, entry_ids: &[Uuid], invoice_id: Uuid) -> Result<(), TimeEntryError> {
+        let marked = self.repo.mark_billed(user_id, entry_ids, invoice_id, self.clock.now()).await?;
+        if marked as usize != entry_ids.len() {
+            self.repo.release(user_id, invoice_id).await?;
+            return Err(TimeEntryError::AlreadyBilled);
+        }
+        Ok(())
+    }
+
+    /// Stop a running entry now, or at the longest an entry may run if it was
+    /// left going
+    async fn stop(&self, user_id: Uuid, mut entry: TimeEntry) -> Result<TimeEntry, TimeEntryError> {
+        let latest_end = entry.started_at + Duration::minutes(MAX_ENTRY_MINUTES);
+        let ended_at = self.clock.now().min(latest_end);
+        // A timer stopped the instant it started still ends after it starts
+        entry.ended_at = Some(ended_at.max(entry.started_at + Duration::seconds(1)));
+
+        self.repo.update(user_id, &entry).await?.ok_or(TimeEntryError::NotFound)
+    }
+
+    async fn ensure_client(&self, user_id: Uuid, client_id: Uuid) -> Result<(), TimeEntryError> {
+        self.repo
+            .client_hourly_rate(user_id, client_id)
+            .await?
+            .map(|_| ())
+            .ok_or(TimeEntryError::ClientNotFound)
+    }
+
+    async fn missing_or_billed(&self, user_id: Uuid, entry_id: Uuid) -> TimeEntryError {
+        match self.repo.find_by_id(user_id, entry_id).await {
+            Ok(Some(_)) => TimeEntryError::AlreadyBilled,
+            Ok(None) => TimeEntryError::NotFound,
+            Err(e) => e.into(),
+        }
+    }
+}
+
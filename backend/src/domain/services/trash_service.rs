@@ -0,0 +1,113 @@
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::domain::models::{purge_due, Trash, TrashedClient, TrashedInvoice, DEFAULT_TRASH_RETENTION_DAYS};
+use crate::domain::services::{FileService, SharedClock, Shutdown};
+use crate::infrastructure::repositories::{PurgedTrash, TrashRepository};
+
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Error)]
+pub enum TrashError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for TrashError {
+    fn from(err: sqlx::Error) -> Self {
+        TrashError::DatabaseError(err.to_string())
+    }
+}
+
+/// Deleted draft invoices and clients. They can be restored through their own
+/// endpoints until the purge worker removes them after the retention period.
+pub struct TrashService {
+    repo: TrashRepository,
+    files: Arc<FileService>,
+    retention_days: i64,
+    clock: SharedClock,
+}
+
+impl TrashService {
+    /// Keeps deleted items for TRASH_RETENTION_DAYS (default 30)
+    pub fn new(repo: TrashRepository, files: Arc<FileService>, clock: SharedClock) -> Self {
+        let retention_days = std::env::var("TRASH_RETENTION_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+
+        Self { repo, files, retention_days, clock }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Trash, TrashError> {
+        let (invoices, clients) = tokio::try_join!(self.repo.list_invoices(user_id), self.repo.list_clients(user_id))?;
+
+        Ok(Trash {
+            retention_days: self.retention_days,
+            invoices: invoices
+                .into_iter()
+                .map(|row| TrashedInvoice {
+                    id: row.id,
+                    invoice_number: row.invoice_number,
+                    client_id: row.client_id,
+                    client_name: row.client_name,
+                    total_amount: row.total_amount,
+                    currency: row.currency,
+                    deleted_at: row.deleted_at,
+                    purge_at: purge_due(row.deleted_at, self.retention_days),
+                })
+                .collect(),
+            clients: clients
+                .into_iter()
+                .map(|row| TrashedClient {
+                    id: row.id,
+                    name: row.name,
+                    email: row.email,
+                    company_name: row.company_name,
+                    deleted_at: row.deleted_at,
+                    purge_at: (!row.has_issued_invoices).then(|| purge_due(row.deleted_at, self.retention_days)),
+                })
+                .collect(),
+        })
+    }
+
+    /// Permanently remove everything that has been in the trash longer than the
+    /// retention period, with its stored files
+    pub async fn run_purge(&self) -> Result<PurgedTrash, TrashError> {
+        let before = self.clock.now() - chrono::Duration::days(self.retention_days);
+        let purged = self.repo.purge(before).await?;
+
+        let pdf_files = purged
+            .pdf_urls
+            .iter()
+            .filter_map(|(user_id, url)| self.files.file_name_from_url(url).map(|name| (*user_id, name)));
+        let attachment_files = purged.attachment_files.iter().map(|(user_id, name)| (*user_id, name.as_str()));
+        for (user_id, file_name) in pdf_files.chain(attachment_files) {
+            if let Err(e) = self.files.delete_file(user_id, file_name).await {
+                tracing::warn!("Failed to remove purged file {}: {}", file_name, e);
+            }
+        }
+
+        Ok(purged)
+    }
+
+    pub fn start_purge_worker(self: Arc<Self>, shutdown: &Shutdown) {
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(TRASH_PURGE_INTERVAL);
+
+            while shutdown.tick(&mut interval).await {
+                match self.run_purge().await {
+                    Ok(purged) => tracing::info!(
+                        "Purged {} invoice(s) and {} client(s) from the trash",
+                        purged.invoices,
+                        purged.clients
+                    ),
+                    Err(e) => tracing::error!("Trash purge failed: {}", e),
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::domain::models::{VatCheck, VatNumber, VatStatus};
+use crate::domain::services::LazyHttpClient;
+
+/// Checks EU VAT numbers against a register
+#[async_trait]
+pub trait VatNumberValidator: Send + Sync {
+    /// Shared HTTP handle of validators that call out, for warm-up and health reporting
+    fn http_handle(&self) -> Option<LazyHttpClient>;
+
+    /// Whether the number is registered for intra-EU trade. Errors mean the register
+    /// couldn't answer, not that the number is invalid.
+    async fn check(&self, vat_number: &VatNumber) -> Result<VatCheck, String>;
+}
+
+/// Validator selected by `VAT_VALIDATION`: `vies` (the default) or `format`, which
+/// only checks the number's shape and is the default under `TEST_MODE`
+pub fn validator_from_env() -> Arc<dyn VatNumberValidator> {
+    let default = if std::env::var("TEST_MODE").is_ok() { "format" } else { "vies" };
+    match std::env::var("VAT_VALIDATION").unwrap_or_else(|_| default.to_string()).as_str() {
+        "format" => Arc::new(FormatOnlyValidator),
+        "vies" => Arc::new(ViesValidator::new(std::env::var("VIES_URL").ok())),
+        other => {
+            tracing::warn!("⚠️ Unknown VAT_VALIDATION '{}'; checking VAT numbers against VIES", other);
+            Arc::new(ViesValidator::new(std::env::var("VIES_URL").ok()))
+        }
+    }
+}
+
+/// The European Commission's VIES REST service
+pub struct ViesValidator {
+    http_client: LazyHttpClient,
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ViesResponse {
+    #[serde(default)]
+    valid: bool,
+    name: Option<String>,
+    user_error: Option<String>,
+    #[serde(default)]
+    error_wrappers: Vec<ViesError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ViesError {
+    error: Option<String>,
+}
+
+impl ViesValidator {
+    const URL: &'static str = "https://ec.europa.eu/taxation_customs/vies/rest-api/check-vat-number";
+    /// Answers that say nothing about the number: the register is down or busy
+    const UNAVAILABLE: [&'static str; 5] =
+        ["MS_UNAVAILABLE", "SERVICE_UNAVAILABLE", "TIMEOUT", "MS_MAX_CONCURRENT_REQ", "GLOBAL_MAX_CONCURRENT_REQ"];
+
+    pub fn new(url: Option<String>) -> Self {
+        Self {
+            http_client: LazyHttpClient::new("vies", std::time::Duration::from_secs(15), true),
+            url: url.unwrap_or_else(|| Self::URL.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl VatNumberValidator for ViesValidator {
+    fn http_handle(&self) -> Option<LazyHttpClient> {
+        Some(self.http_client.clone())
+    }
+
+    async fn check(&self, vat_number: &VatNumber) -> Result<VatCheck, String> {
+        let client = self.http_client.get()?;
+        let response: ViesResponse = client
+            .post(&self.url)
+            .json(&serde_json::json!({ "countryCode": vat_number.prefix, "vatNumber": vat_number.number }))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if let Some(error) = response.error_wrappers.iter().find_map(|wrapper| wrapper.error.clone()) {
+            return Err(format!("VIES error: {}", error));
+        }
+        if let Some(error) = response.user_error.as_deref().filter(|error| Self::UNAVAILABLE.contains(error)) {
+            return Err(format!("VIES error: {}", error));
+        }
+
+        // Member states that don't disclose the name answer "---"
+        let registered_name = response.name.map(|name| name.trim().to_string()).filter(|name| !name.is_empty() && name != "---");
+        Ok(VatCheck {
+            status: if response.valid { VatStatus::Valid } else { VatStatus::Invalid },
+            registered_name: if response.valid { registered_name } else { None },
+        })
+    }
+}
+
+/// Accepts any well-formed number without asking VIES; for development and tests
+pub struct FormatOnlyValidator;
+
+#[async_trait]
+impl VatNumberValidator for FormatOnlyValidator {
+    fn http_handle(&self) -> Option<LazyHttpClient> {
+        None
+    }
+
+    async fn check(&self, _vat_number: &VatNumber) -> Result<VatCheck, String> {
+        Ok(VatCheck { status: VatStatus::Valid, registered_name: None })
+    }
+}
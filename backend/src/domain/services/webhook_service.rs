@@ -0,0 +1,398 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    webhook_retry_delay, webhook_signature, without_secrets, CreateWebhookEndpoint, PendingWebhookDelivery,
+    UpdateWebhookEndpoint, WebhookDelivery, WebhookDeliveryFilter, WebhookDeliveryStatus, WebhookEndpoint,
+    WebhookPayload, MAX_WEBHOOK_ENDPOINTS, WEBHOOK_DELIVERY_HEADER, WEBHOOK_EVENT_HEADER, WEBHOOK_SIGNATURE_HEADER,
+};
+use crate::domain::request_id::REQUEST_ID_HEADER;
+use crate::domain::services::{AutomationIssueService, LazyHttpClient, RedisService, SharedClock, Shutdown};
+use crate::infrastructure::repositories::{WebhookAttempt, WebhookRepository};
+
+/// How often new events are turned into deliveries
+const DISPATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// How often due deliveries are picked up from the database, in case a queued
+/// job was lost or Redis is unavailable
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+/// Pause between polls when the queue is empty
+const WORKER_IDLE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// How long an attempt holds its delivery; well past the request timeout
+const ATTEMPT_LEASE_SECONDS: i64 = 120;
+const BATCH_SIZE: i64 = 100;
+/// Most of an endpoint's error response kept in the delivery log
+const MAX_ERROR_LENGTH: usize = 500;
+const QUEUE_KEY: &str = "webhook_queue:pending";
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Webhook endpoint not found")]
+    EndpointNotFound,
+
+    #[error("Validation error: {0}")]
+    Validation(String),
+
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
+
+impl From<sqlx::Error> for WebhookError {
+    fn from(err: sqlx::Error) -> Self {
+        WebhookError::DatabaseError(err.to_string())
+    }
+}
+
+/// A delivery waiting in the Redis queue until `scheduled_at`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WebhookJob {
+    delivery_id: Uuid,
+    scheduled_at: i64,
+}
+
+/// Outbound webhooks. Database triggers record invoice, payment and client
+/// events; the dispatcher turns each into a delivery per subscribed endpoint
+/// and queues it in Redis. Failed attempts are retried with exponential
+/// backoff, and a periodic sweep sends anything due that the queue missed.
+pub struct WebhookService {
+    repo: WebhookRepository,
+    redis: Option<Arc<RedisService>>,
+    http_client: LazyHttpClient,
+    /// Allow plain HTTP and local URLs, for development (WEBHOOK_ALLOW_INSECURE_URLS)
+    allow_insecure_urls: bool,
+    clock: SharedClock,
+    /// Abandoned deliveries are raised to the endpoint's owner
+    automation_issues: Option<Arc<AutomationIssueService>>,
+}
+
+impl WebhookService {
+    pub fn new(repo: WebhookRepository, redis: Option<Arc<RedisService>>, clock: SharedClock) -> Self {
+        let allow_insecure_urls = std::env::var("WEBHOOK_ALLOW_INSECURE_URLS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        Self {
+            repo,
+            redis,
+            http_client: LazyHttpClient::new("webhooks", REQUEST_TIMEOUT, true),
+            allow_insecure_urls,
+            clock,
+            automation_issues: None,
+        }
+    }
+
+    /// Report deliveries that are given up in the owner's needs-attention digest
+    pub fn with_automation_issues(mut self, automation_issues: Arc<AutomationIssueService>) -> Self {
+        self.automation_issues = Some(automation_issues);
+        self
+    }
+
+    /// Shared handle to the delivery client, for warm-up and health reporting
+    pub fn http_handle(&self) -> LazyHttpClient {
+        self.http_client.clone()
+    }
+
+    pub async fn list_endpoints(&self, user_id: Uuid) -> Result<Vec<WebhookEndpoint>, WebhookError> {
+        Ok(self.repo.list_endpoints(user_id).await?)
+    }
+
+    pub async fn get_endpoint(&self, user_id: Uuid, id: Uuid) -> Result<WebhookEndpoint, WebhookError> {
+        self.repo.get_endpoint(user_id, id).await?.ok_or(WebhookError::EndpointNotFound)
+    }
+
+    /// The response carries the signing secret; it isn't shown again
+    pub async fn create_endpoint(
+        &self,
+        user_id: Uuid,
+        endpoint: CreateWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, WebhookError> {
+        endpoint.validate(self.allow_insecure_urls).map_err(WebhookError::Validation)?;
+        if self.repo.count_endpoints(user_id).await? >= MAX_WEBHOOK_ENDPOINTS {
+            return Err(WebhookError::Validation(format!(
+                "At most {} webhook endpoints are allowed",
+                MAX_WEBHOOK_ENDPOINTS
+            )));
+        }
+
+        let secret = generate_secret();
+        let mut created = self.repo.create_endpoint(user_id, &endpoint, &secret).await?;
+        created.secret = Some(secret);
+        Ok(created)
+    }
+
+    pub async fn update_endpoint(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        update: UpdateWebhookEndpoint,
+    ) -> Result<WebhookEndpoint, WebhookError> {
+        update.validate(self.allow_insecure_urls).map_err(WebhookError::Validation)?;
+        self.repo
+            .update_endpoint(user_id, id, &update)
+            .await?
+            .ok_or(WebhookError::EndpointNotFound)
+    }
+
+    /// Replace the signing secret; deliveries from now on use the new one
+    pub async fn rotate_secret(&self, user_id: Uuid, id: Uuid) -> Result<WebhookEndpoint, WebhookError> {
+        let secret = generate_secret();
+        let mut endpoint = self
+            .repo
+            .rotate_secret(user_id, id, &secret)
+            .await?
+            .ok_or(WebhookError::EndpointNotFound)?;
+        endpoint.secret = Some(secret);
+        Ok(endpoint)
+    }
+
+    pub async fn delete_endpoint(&self, user_id: Uuid, id: Uuid) -> Result<(), WebhookError> {
+        if !self.repo.delete_endpoint(user_id, id).await? {
+            return Err(WebhookError::EndpointNotFound);
+        }
+        Ok(())
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        self.get_endpoint(user_id, endpoint_id).await?;
+        Ok(self.repo.list_deliveries(user_id, endpoint_id, filter).await?)
+    }
+
+    /// Spawn the dispatcher, the queue worker and the sweep. On shutdown the
+    /// attempt in progress finishes; the rest are picked up after the restart.
+    pub fn start_workers(self: Arc<Self>, shutdown: &Shutdown) {
+        let dispatcher = self.clone();
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(DISPATCH_INTERVAL);
+            while shutdown.tick(&mut interval).await {
+                if let Err(e) = dispatcher.dispatch().await {
+                    tracing::error!("Webhook dispatch failed: {}", e);
+                }
+            }
+        });
+
+        let sweeper = self.clone();
+        shutdown.spawn(|shutdown| async move {
+            let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+            while shutdown.tick(&mut interval).await {
+                if let Err(e) = sweeper.sweep().await {
+                    tracing::error!("Webhook sweep failed: {}", e);
+                }
+            }
+        });
+
+        if self.redis.is_some() {
+            shutdown.spawn(|shutdown| async move {
+                while !shutdown.is_triggered() {
+                    if !self.process_next().await {
+                        shutdown.sleep(WORKER_IDLE_DELAY).await;
+                    }
+                }
+            });
+        }
+    }
+
+    /// Create deliveries for new events and queue them
+    async fn dispatch(&self) -> Result<(), WebhookError> {
+        let now = self.clock.now();
+        let deliveries = self.repo.fan_out(now, BATCH_SIZE).await?;
+        for delivery_id in deliveries {
+            self.enqueue(delivery_id, now).await;
+        }
+        Ok(())
+    }
+
+    /// Send deliveries that are due but weren't picked up from the queue
+    async fn sweep(&self) -> Result<(), WebhookError> {
+        for delivery_id in self.repo.due_deliveries(self.clock.now(), BATCH_SIZE).await? {
+            if let Some(retry_at) = self.deliver(delivery_id).await? {
+                self.enqueue(delivery_id, retry_at).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue a delivery for `at`. Without Redis the sweep picks it up instead.
+    async fn enqueue(&self, delivery_id: Uuid, at: DateTime<Utc>) {
+        let Some(redis) = &self.redis else {
+            return;
+        };
+        let job = WebhookJob { delivery_id, scheduled_at: at.timestamp() };
+        let Ok(job_json) = serde_json::to_string(&job) else {
+            return;
+        };
+        if let Err(e) = redis.lpush(QUEUE_KEY, &job_json).await {
+            tracing::debug!(delivery_id = %delivery_id, "Webhook delivery left for the sweep: {}", e);
+        }
+    }
+
+    /// Take one job off the queue; false when there was nothing to do
+    async fn process_next(&self) -> bool {
+        let Some(redis) = &self.redis else {
+            return false;
+        };
+        let job_json = match redis.rpop(QUEUE_KEY).await {
+            Ok(Some(json)) => json,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::debug!("Webhook queue unavailable: {}", e);
+                return false;
+            }
+        };
+        let Ok(job) = serde_json::from_str::<WebhookJob>(&job_json) else {
+            tracing::warn!("Dropping malformed webhook job: {}", job_json);
+            return true;
+        };
+
+        // Not due yet: back of the queue
+        if job.scheduled_at > self.clock.now().timestamp() {
+            if let Err(e) = redis.lpush(QUEUE_KEY, &job_json).await {
+                tracing::debug!(delivery_id = %job.delivery_id, "Webhook delivery left for the sweep: {}", e);
+            }
+            return false;
+        }
+
+        match self.deliver(job.delivery_id).await {
+            Ok(Some(retry_at)) => self.enqueue(job.delivery_id, retry_at).await,
+            Ok(None) => {}
+            Err(e) => tracing::error!(delivery_id = %job.delivery_id, "Webhook delivery failed: {}", e),
+        }
+        true
+    }
+
+    /// Make one attempt at the delivery if it's due. Returns when to retry if
+    /// the attempt failed and attempts remain.
+    async fn deliver(&self, delivery_id: Uuid) -> Result<Option<DateTime<Utc>>, WebhookError> {
+        let now = self.clock.now();
+        let Some(delivery) = self
+            .repo
+            .claim(delivery_id, now, now + Duration::seconds(ATTEMPT_LEASE_SECONDS))
+            .await?
+        else {
+            // Already sent, not due yet or the endpoint was disabled
+            return Ok(None);
+        };
+
+        // Logged under the request that caused the event
+        let span = tracing::info_span!(
+            "webhook_delivery",
+            delivery_id = %delivery.id,
+            request_id = delivery.request_id.as_deref().map(tracing::field::display)
+        );
+        self.attempt(&delivery, now).instrument(span).await
+    }
+
+    /// Send a claimed delivery and record how it went
+    async fn attempt(
+        &self,
+        delivery: &PendingWebhookDelivery,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, WebhookError> {
+        let result = self.send(delivery, now).await;
+        let attempts = delivery.attempts + 1;
+        let attempted_at = self.clock.now();
+        let (status, status_code, error, next_attempt_at) = match &result {
+            Ok(code) => (WebhookDeliveryStatus::Delivered, Some(*code), None, None),
+            Err((code, error)) => match webhook_retry_delay(attempts) {
+                Some(delay) => (
+                    WebhookDeliveryStatus::Pending,
+                    *code,
+                    Some(error.as_str()),
+                    Some(attempted_at + Duration::seconds(delay)),
+                ),
+                None => (WebhookDeliveryStatus::Failed, *code, Some(error.as_str()), None),
+            },
+        };
+
+        self.repo
+            .record_attempt(
+                delivery.id,
+                &WebhookAttempt { status, status_code, error, attempted_at, next_attempt_at },
+            )
+            .await?;
+
+        match status {
+            WebhookDeliveryStatus::Delivered => {
+                tracing::debug!(delivery_id = %delivery.id, event = delivery.event_type.as_str(), "Webhook delivered");
+            }
+            WebhookDeliveryStatus::Pending => tracing::warn!(
+                delivery_id = %delivery.id,
+                attempts = attempts,
+                "Webhook delivery to {} failed, retrying: {}",
+                delivery.url,
+                error.unwrap_or_default()
+            ),
+            WebhookDeliveryStatus::Failed => {
+                tracing::error!(
+                    delivery_id = %delivery.id,
+                    attempts = attempts,
+                    "Webhook delivery to {} failed after max attempts: {}",
+                    delivery.url,
+                    error.unwrap_or_default()
+                );
+                let issue = delivery.dead_letter_issue(attempts, error.unwrap_or_default());
+                if let (Some(automation_issues), Some(issue)) = (&self.automation_issues, issue) {
+                    automation_issues.record(issue).await;
+                }
+            }
+        }
+        Ok(next_attempt_at)
+    }
+
+    /// POST the signed event. Ok with the status code on a 2xx response, else
+    /// the status code (if there was a response) and what went wrong.
+    async fn send(&self, delivery: &PendingWebhookDelivery, now: DateTime<Utc>) -> Result<i32, (Option<i32>, String)> {
+        let data = without_secrets(&delivery.data);
+        let payload = WebhookPayload {
+            id: delivery.event_id,
+            event_type: delivery.event_type,
+            created_at: delivery.event_created_at,
+            data: &data,
+        };
+        let body = serde_json::to_vec(&payload).map_err(|e| (None, e.to_string()))?;
+        let timestamp = now.timestamp();
+        let signature = webhook_signature(&delivery.secret, timestamp, &body);
+
+        let client = self.http_client.get().map_err(|e| (None, e))?;
+        let mut request = client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::USER_AGENT, "FlashBill-Webhooks/1.0")
+            .header(WEBHOOK_EVENT_HEADER, delivery.event_type.as_str())
+            .header(WEBHOOK_DELIVERY_HEADER, delivery.id.to_string())
+            .header(WEBHOOK_SIGNATURE_HEADER, format!("t={},v1={}", timestamp, signature));
+        if let Some(request_id) = &delivery.request_id {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| (None, e.to_string()))?;
+
+        let code = i32::from(response.status().as_u16());
+        if response.status().is_success() {
+            return Ok(code);
+        }
+        let text = response.text().await.unwrap_or_default();
+        let mut error = format!("HTTP {}", code);
+        if !text.trim().is_empty() {
+            error.push_str(": ");
+            error.extend(text.trim().chars().take(MAX_ERROR_LENGTH));
+        }
+        Err((Some(code), error))
+    }
+}
+
+/// `whsec_` and 32 random bytes in hex
+fn generate_secret() -> String {
+    format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()))
+}
@@ -1,10 +1,12 @@
 use crate::domain::models::invoice::InvoiceDetailResponse;
 use crate::domain::models::user::User;
+use crate::domain::models::phone::normalize_phone;
 use anyhow::Result;
-use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
 use std::env;
 
+use crate::domain::services::LazyHttpClient;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhatsAppConfig {
     pub api_key: Option<String>,
@@ -26,7 +28,7 @@ impl Default for WhatsAppConfig {
 
 #[derive(Debug, Clone)]
 pub struct WhatsAppService {
-    http_client: HttpClient,
+    http_client: LazyHttpClient,
     config: WhatsAppConfig,
 }
 
@@ -47,15 +49,18 @@ pub struct WhatsAppResponse {
 
 impl WhatsAppService {
     pub fn new(config: WhatsAppConfig) -> Self {
+        let configured = config.enabled && config.api_key.is_some() && config.api_url.is_some();
         Self {
-            http_client: HttpClient::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap_or_else(|_| HttpClient::new()),
+            http_client: LazyHttpClient::new("whatsapp", std::time::Duration::from_secs(30), configured),
             config,
         }
     }
 
+    /// Shared handle to the WhatsApp client, for warm-up and health reporting
+    pub fn http_handle(&self) -> LazyHttpClient {
+        self.http_client.clone()
+    }
+
     pub fn from_env() -> Self {
         Self::new(WhatsAppConfig::default())
     }
@@ -93,6 +98,8 @@ impl WhatsAppService {
 
         let response = self
             .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
             .post(self.config.api_url.as_ref().unwrap())
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
             .header("Content-Type", "application/json")
@@ -143,6 +150,8 @@ impl WhatsAppService {
 
         let response = self
             .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
             .post(self.config.api_url.as_ref().unwrap())
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
             .header("Content-Type", "application/json")
@@ -205,6 +214,81 @@ impl WhatsAppService {
 
         let response = self
             .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
+            .post(self.config.api_url.as_ref().unwrap())
+            .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let resp: serde_json::Value = response.json().await?;
+            Ok(WhatsAppResponse {
+                success: true,
+                message_id: resp.get("message_id").and_then(|v| v.as_str()).map(String::from),
+                error: None,
+            })
+        } else {
+            let error_text = response.text().await?;
+            Ok(WhatsAppResponse {
+                success: false,
+                message_id: None,
+                error: Some(error_text),
+            })
+        }
+    }
+
+    /// Remind the client that an invoice is due or past due
+    pub async fn send_payment_reminder(
+        &self,
+        phone: &str,
+        invoice: &InvoiceDetailResponse,
+        days_overdue: i64,
+    ) -> Result<WhatsAppResponse> {
+        if !self.is_enabled() {
+            return Ok(WhatsAppResponse {
+                success: false,
+                message_id: None,
+                error: Some("WhatsApp not configured".to_string()),
+            });
+        }
+
+        let status = if days_overdue == 0 {
+            "is due today".to_string()
+        } else {
+            format!("is {} day(s) overdue", days_overdue)
+        };
+        // While a deposit is outstanding, that's what the client is asked for
+        let (title, subject) = match invoice.deposit.as_ref().filter(|deposit| deposit.is_due()) {
+            Some(_) => ("Deposit Reminder", "The deposit for invoice"),
+            None => ("Payment Reminder", "Invoice"),
+        };
+        let message = format!(
+            "⏰ *{}*\n\n\
+            Hi! {} *{}* {}.\n\n\
+            Amount Due: ${:.2}\n\
+            Due Date: {}\n\n\
+            Please make payment at your earliest convenience. If you've already paid, thank you and please ignore this message.",
+            title,
+            subject,
+            invoice.invoice_number,
+            status,
+            invoice.amount_due_now(),
+            invoice.reminder_due_date()
+        );
+
+        let payload = WhatsAppMessage {
+            to: self.normalize_phone(phone),
+            message,
+            preview_url: Some(false),
+        };
+
+        let response = self
+            .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
             .post(self.config.api_url.as_ref().unwrap())
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
             .header("Content-Type", "application/json")
@@ -307,8 +391,13 @@ impl WhatsAppService {
         message
     }
 
-    /// Normalize phone number (remove +, spaces, etc.)
+    /// Normalize phone number to the digits-only E.164 form the API expects.
+    /// Numbers that don't parse fall back to stripping non-digits.
     fn normalize_phone(&self, phone: &str) -> String {
+        if let Ok(normalized) = normalize_phone(phone) {
+            return normalized.trim_start_matches('+').to_string();
+        }
+
         phone
             .chars()
             .filter(|c| c.is_ascii_digit())
@@ -349,6 +438,70 @@ impl WhatsAppService {
 
         let response = self
             .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
+            .post(self.config.api_url.as_ref().unwrap())
+            .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            let resp: serde_json::Value = response.json().await?;
+            Ok(WhatsAppResponse {
+                success: true,
+                message_id: resp.get("message_id").and_then(|v| v.as_str()).map(String::from),
+                error: None,
+            })
+        } else {
+            let error_text = response.text().await?;
+            Ok(WhatsAppResponse {
+                success: false,
+                message_id: None,
+                error: Some(error_text),
+            })
+        }
+    }
+
+    /// Tell one side of an invoice discussion about the other's new message
+    pub async fn send_discussion_message(
+        &self,
+        phone: &str,
+        invoice_number: &str,
+        from: &str,
+        excerpt: &str,
+        link: Option<&str>,
+    ) -> Result<WhatsAppResponse> {
+        if !self.is_enabled() {
+            return Ok(WhatsAppResponse {
+                success: false,
+                message_id: None,
+                error: Some("WhatsApp not configured".to_string()),
+            });
+        }
+
+        let mut message = format!(
+            "💬 *New message about invoice {}*\n\n\
+            {} wrote:\n{}",
+            invoice_number,
+            from,
+            excerpt
+        );
+        if let Some(link) = link {
+            message.push_str(&format!("\n\nReply here: {}", link));
+        }
+
+        let payload = WhatsAppMessage {
+            to: self.normalize_phone(phone),
+            message,
+            preview_url: Some(link.is_some()),
+        };
+
+        let response = self
+            .http_client
+            .get()
+            .map_err(anyhow::Error::msg)?
             .post(self.config.api_url.as_ref().unwrap())
             .header("Authorization", format!("Bearer {}", self.config.api_key.as_ref().unwrap()))
             .header("Content-Type", "application/json")
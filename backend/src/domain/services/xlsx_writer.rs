@@ -0,0 +1,274 @@
+//! A minimal XLSX writer for exports: a single worksheet of text and number
+//! cells, written row by row into the zip archive so a large sheet is never
+//! held in memory. The worksheet is deflated as it goes and its checksum and
+//! sizes follow it in a data descriptor.
+
+use crc32fast::Hasher;
+use flate2::{write::DeflateEncoder, Compression};
+use std::io::{self, Write};
+
+use crate::domain::models::ExportCell;
+use crate::domain::services::zip_archive::{
+    put_u32, write_central_directory, write_local_header, ZipEntry, DATA_DESCRIPTOR_SIGNATURE, FLAG_DATA_DESCRIPTOR,
+    METHOD_DEFLATED, METHOD_STORED,
+};
+
+const SHEET_PATH: &str = "xl/worksheets/sheet1.xml";
+const SHEET_START: &[u8] = b"<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+    <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>";
+const SHEET_END: &[u8] = b"</sheetData></worksheet>";
+
+/// Writes the workbook as a stream of chunks: call `row` for each row, then
+/// `finish`. Each call returns the bytes that are ready to send, which may be
+/// none while the compressor is filling its buffer.
+pub struct XlsxWriter {
+    /// Bytes ready to send
+    out: Vec<u8>,
+    /// Bytes already handed out
+    sent: u64,
+    entries: Vec<ZipEntry>,
+    sheet: DeflateEncoder<Vec<u8>>,
+    sheet_crc: Hasher,
+    sheet_size: u64,
+    sheet_compressed_size: u64,
+    sheet_offset: u64,
+    rows: u32,
+}
+
+impl XlsxWriter {
+    /// Starts a workbook with one sheet. The name must be a valid sheet name
+    /// (at most 31 characters, none of `[]:*?/\`).
+    pub fn new(sheet_name: &str) -> Self {
+        let mut writer = Self {
+            out: Vec::new(),
+            sent: 0,
+            entries: Vec::new(),
+            sheet: DeflateEncoder::new(Vec::new(), Compression::default()),
+            sheet_crc: Hasher::new(),
+            sheet_size: 0,
+            sheet_compressed_size: 0,
+            sheet_offset: 0,
+            rows: 0,
+        };
+
+        for (name, content) in package_files(sheet_name) {
+            writer.write_stored(name, content.as_bytes());
+        }
+
+        writer.sheet_offset = writer.position();
+        writer.write_local_header(SHEET_PATH, FLAG_DATA_DESCRIPTOR, METHOD_DEFLATED, 0, 0, 0);
+        writer
+    }
+
+    pub fn row(&mut self, cells: &[ExportCell]) -> io::Result<Vec<u8>> {
+        if self.rows == 0 {
+            self.write_sheet(SHEET_START)?;
+        }
+        self.rows += 1;
+
+        let mut xml = format!("<row r=\"{}\">", self.rows);
+        for (index, cell) in cells.iter().enumerate() {
+            let reference = format!("{}{}", column_name(index), self.rows);
+            match cell {
+                ExportCell::Text(text) if text.is_empty() => {}
+                ExportCell::Text(text) => xml.push_str(&format!(
+                    "<c r=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    reference,
+                    xml_escape(text)
+                )),
+                ExportCell::Number(_) => xml.push_str(&format!("<c r=\"{}\"><v>{}</v></c>", reference, cell)),
+            }
+        }
+        xml.push_str("</row>");
+        self.write_sheet(xml.as_bytes())?;
+
+        Ok(self.take())
+    }
+
+    /// Closes the sheet and writes the zip's central directory
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        if self.rows == 0 {
+            self.write_sheet(SHEET_START)?;
+        }
+        self.write_sheet(SHEET_END)?;
+        let rest = std::mem::replace(&mut self.sheet, DeflateEncoder::new(Vec::new(), Compression::default())).finish()?;
+        self.sheet_compressed_size += rest.len() as u64;
+        self.out.extend_from_slice(&rest);
+
+        let crc = self.sheet_crc.clone().finalize();
+        let compressed_size = zip32(self.sheet_compressed_size)?;
+        let size = zip32(self.sheet_size)?;
+        put_u32(&mut self.out, DATA_DESCRIPTOR_SIGNATURE);
+        put_u32(&mut self.out, crc);
+        put_u32(&mut self.out, compressed_size);
+        put_u32(&mut self.out, size);
+        self.entries.push(ZipEntry {
+            name: SHEET_PATH.to_string(),
+            flags: FLAG_DATA_DESCRIPTOR,
+            method: METHOD_DEFLATED,
+            crc,
+            compressed_size,
+            size,
+            offset: zip32(self.sheet_offset)?,
+        });
+
+        let directory_offset = zip32(self.position())?;
+        write_central_directory(&mut self.out, &self.entries, directory_offset);
+
+        Ok(self.take())
+    }
+
+    fn position(&self) -> u64 {
+        self.sent + self.out.len() as u64
+    }
+
+    fn take(&mut self) -> Vec<u8> {
+        let chunk = std::mem::take(&mut self.out);
+        self.sent += chunk.len() as u64;
+        chunk
+    }
+
+    fn write_sheet(&mut self, data: &[u8]) -> io::Result<()> {
+        self.sheet_crc.update(data);
+        self.sheet_size += data.len() as u64;
+        self.sheet.write_all(data)?;
+
+        let compressed = std::mem::take(self.sheet.get_mut());
+        self.sheet_compressed_size += compressed.len() as u64;
+        self.out.extend_from_slice(&compressed);
+        Ok(())
+    }
+
+    /// A small file written whole, uncompressed
+    fn write_stored(&mut self, name: &'static str, data: &[u8]) {
+        let crc = crc32fast::hash(data);
+        let offset = self.position() as u32;
+        let size = data.len() as u32;
+        self.write_local_header(name, 0, METHOD_STORED, crc, size, size);
+        self.out.extend_from_slice(data);
+        self.entries.push(ZipEntry { name: name.to_string(), flags: 0, method: METHOD_STORED, crc, compressed_size: size, size, offset });
+    }
+
+    fn write_local_header(&mut self, name: &str, flags: u16, method: u16, crc: u32, compressed_size: u32, size: u32) {
+        write_local_header(&mut self.out, name, flags, method, crc, compressed_size, size);
+    }
+}
+
+/// The parts of the package around the worksheet
+fn package_files(sheet_name: &str) -> [(&'static str, String); 4] {
+    [
+        (
+            "[Content_Types].xml",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+             <Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+             <Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+             </Types>"
+                .to_string(),
+        ),
+        (
+            "_rels/.rels",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+             </Relationships>"
+                .to_string(),
+        ),
+        (
+            "xl/workbook.xml",
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+                 <workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+                 xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+                 <sheets><sheet name=\"{}\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>",
+                xml_escape(sheet_name)
+            ),
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+             <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+             </Relationships>"
+                .to_string(),
+        ),
+    ]
+}
+
+/// Spreadsheet column letters: A..Z, AA..ZZ, AAA..
+fn column_name(index: usize) -> String {
+    let mut index = index + 1;
+    let mut name = Vec::new();
+    while index > 0 {
+        let rem = (index - 1) % 26;
+        name.push(b'A' + rem as u8);
+        index = (index - 1) / 26;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Escapes text for XML, dropping control characters XML can't hold
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            ch if ch.is_control() || ch == '\u{FFFE}' || ch == '\u{FFFF}' => {}
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Without zip64, sizes and offsets have to fit in 32 bits
+fn zip32(value: u64) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::other("The export is too large for an XLSX file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::zip_archive::unzip;
+    use std::collections::HashMap;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_workbook_is_a_valid_zip() {
+        let mut writer = XlsxWriter::new("Invoices");
+        let mut data = Vec::new();
+        data.extend(writer.row(&[ExportCell::Text("client".into()), ExportCell::Text("total".into())]).unwrap());
+        for i in 0..2000 {
+            data.extend(
+                writer
+                    .row(&[ExportCell::Text(format!("Ana & Co <{}>", i)), ExportCell::Number(Decimal::new(1250, 2))])
+                    .unwrap(),
+            );
+        }
+        data.extend(writer.row(&[ExportCell::Text(String::new()), ExportCell::Number(Decimal::ONE)]).unwrap());
+        data.extend(writer.finish().unwrap());
+
+        let files: HashMap<String, String> =
+            unzip(&data).into_iter().map(|(name, content)| (name, String::from_utf8(content).unwrap())).collect();
+        assert_eq!(files.len(), 5);
+        assert!(files["xl/workbook.xml"].contains("<sheet name=\"Invoices\""));
+        let sheet = &files[SHEET_PATH];
+        assert!(sheet.contains("<row r=\"1\"><c r=\"A1\" t=\"inlineStr\"><is><t xml:space=\"preserve\">client</t>"));
+        assert!(sheet.contains("Ana &amp; Co &lt;1999&gt;</t></is></c><c r=\"B2001\"><v>12.5</v></c>"));
+        // Empty cells are left out
+        assert!(sheet.contains("<row r=\"2002\"><c r=\"B2002\"><v>1</v></c></row>"));
+        assert!(sheet.ends_with("</sheetData></worksheet>"));
+    }
+
+    #[test]
+    fn test_column_names() {
+        let names: Vec<String> = [0, 25, 26, 27, 701, 702].into_iter().map(column_name).collect();
+        assert_eq!(names, vec!["A", "Z", "AA", "AB", "ZZ", "AAA"]);
+    }
+}
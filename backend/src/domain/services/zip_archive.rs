@@ -0,0 +1,226 @@
+//! Zip archives without zip64: the headers and central directory the XLSX
+//! writer streams, and `ZipArchive` for bundling files already in memory,
+//! such as a batch of invoice PDFs.
+
+use std::io;
+
+pub(crate) const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+pub(crate) const DATA_DESCRIPTOR_SIGNATURE: u32 = 0x0807_4b50;
+pub(crate) const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+pub(crate) const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+/// Zip 2.0: deflate and data descriptors
+const ZIP_VERSION: u16 = 20;
+/// Sizes and checksum follow the entry's data
+pub(crate) const FLAG_DATA_DESCRIPTOR: u16 = 0x0008;
+pub(crate) const METHOD_STORED: u16 = 0;
+pub(crate) const METHOD_DEFLATED: u16 = 8;
+/// 1980-01-01 00:00 in MS-DOS format; entries carry no real timestamp
+const DOS_DATE: u16 = (1 << 5) | 1;
+const DOS_TIME: u16 = 0;
+
+/// An entry already written, for the central directory
+pub(crate) struct ZipEntry {
+    pub name: String,
+    pub flags: u16,
+    pub method: u16,
+    pub crc: u32,
+    pub compressed_size: u32,
+    pub size: u32,
+    pub offset: u32,
+}
+
+pub(crate) fn write_local_header(
+    out: &mut Vec<u8>,
+    name: &str,
+    flags: u16,
+    method: u16,
+    crc: u32,
+    compressed_size: u32,
+    size: u32,
+) {
+    put_u32(out, LOCAL_HEADER_SIGNATURE);
+    put_u16(out, ZIP_VERSION);
+    put_u16(out, flags);
+    put_u16(out, method);
+    put_u16(out, DOS_TIME);
+    put_u16(out, DOS_DATE);
+    put_u32(out, crc);
+    put_u32(out, compressed_size);
+    put_u32(out, size);
+    put_u16(out, name.len() as u16);
+    // Extra field length
+    put_u16(out, 0);
+    out.extend_from_slice(name.as_bytes());
+}
+
+/// Lists the entries and closes the archive; the directory starts at `directory_offset`
+pub(crate) fn write_central_directory(out: &mut Vec<u8>, entries: &[ZipEntry], directory_offset: u32) {
+    let mut directory = Vec::new();
+    for entry in entries {
+        put_u32(&mut directory, CENTRAL_HEADER_SIGNATURE);
+        put_u16(&mut directory, ZIP_VERSION);
+        put_u16(&mut directory, ZIP_VERSION);
+        put_u16(&mut directory, entry.flags);
+        put_u16(&mut directory, entry.method);
+        put_u16(&mut directory, DOS_TIME);
+        put_u16(&mut directory, DOS_DATE);
+        put_u32(&mut directory, entry.crc);
+        put_u32(&mut directory, entry.compressed_size);
+        put_u32(&mut directory, entry.size);
+        put_u16(&mut directory, entry.name.len() as u16);
+        // Extra field, comment, disk number, internal and external attributes
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u16(&mut directory, 0);
+        put_u32(&mut directory, 0);
+        put_u32(&mut directory, entry.offset);
+        directory.extend_from_slice(entry.name.as_bytes());
+    }
+
+    let directory_size = directory.len() as u32;
+    put_u32(&mut directory, END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+    // This disk and the disk the directory starts on
+    put_u16(&mut directory, 0);
+    put_u16(&mut directory, 0);
+    put_u16(&mut directory, entries.len() as u16);
+    put_u16(&mut directory, entries.len() as u16);
+    put_u32(&mut directory, directory_size);
+    put_u32(&mut directory, directory_offset);
+    // Comment length
+    put_u16(&mut directory, 0);
+    out.extend_from_slice(&directory);
+}
+
+pub(crate) fn put_u16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+/// A zip of whole files, stored as they are. Meant for content that is
+/// compressed already, like PDFs, where deflating again gains nothing.
+#[derive(Default)]
+pub struct ZipArchive {
+    out: Vec<u8>,
+    entries: Vec<ZipEntry>,
+}
+
+impl ZipArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a file. A name already in the archive gets a number before its
+    /// extension, so `INV-1.pdf` is followed by `INV-1 (2).pdf`.
+    pub fn add(&mut self, name: &str, data: &[u8]) -> io::Result<()> {
+        let name = self.unique_name(name);
+        let size = zip32(data.len())?;
+        let offset = zip32(self.out.len())?;
+        let crc = crc32fast::hash(data);
+
+        write_local_header(&mut self.out, &name, 0, METHOD_STORED, crc, size, size);
+        self.out.extend_from_slice(data);
+        zip32(self.out.len())?;
+        self.entries.push(ZipEntry { name, flags: 0, method: METHOD_STORED, crc, compressed_size: size, size, offset });
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<Vec<u8>> {
+        let directory_offset = zip32(self.out.len())?;
+        write_central_directory(&mut self.out, &self.entries, directory_offset);
+        Ok(self.out)
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.entries.iter().any(|entry| entry.name == candidate);
+        if !taken(name) {
+            return name.to_string();
+        }
+
+        let (stem, extension) = match name.rfind('.') {
+            Some(dot) if dot > 0 => name.split_at(dot),
+            _ => (name, ""),
+        };
+        (2..)
+            .map(|n| format!("{} ({}){}", stem, n, extension))
+            .find(|candidate| !taken(candidate))
+            .unwrap_or_default()
+    }
+}
+
+/// Without zip64, sizes and offsets have to fit in 32 bits
+fn zip32(value: usize) -> io::Result<u32> {
+    u32::try_from(value).map_err(|_| io::Error::other("The archive is too large for a zip file"))
+}
+
+/// Reads every entry through the central directory, checking its checksum
+#[cfg(test)]
+pub(crate) fn unzip(data: &[u8]) -> std::collections::HashMap<String, Vec<u8>> {
+    use std::io::Read;
+
+    let u16_at = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize;
+
+    let end = data.len() - 22;
+    assert_eq!(u32_at(end), END_OF_CENTRAL_DIRECTORY_SIGNATURE as usize);
+    let count = u16_at(end + 10);
+    let mut at = u32_at(end + 16);
+
+    let mut files = std::collections::HashMap::new();
+    for _ in 0..count {
+        assert_eq!(u32_at(at), CENTRAL_HEADER_SIGNATURE as usize);
+        let method = u16_at(at + 10);
+        let crc = u32_at(at + 16) as u32;
+        let compressed_size = u32_at(at + 20);
+        let name_len = u16_at(at + 28);
+        let offset = u32_at(at + 42);
+        let name = String::from_utf8(data[at + 46..at + 46 + name_len].to_vec()).unwrap();
+        at += 46 + name_len;
+
+        assert_eq!(u32_at(offset), LOCAL_HEADER_SIGNATURE as usize);
+        let start = offset + 30 + u16_at(offset + 26) + u16_at(offset + 28);
+        let raw = &data[start..start + compressed_size];
+        let content = if method == METHOD_DEFLATED as usize {
+            let mut content = Vec::new();
+            flate2::read::DeflateDecoder::new(raw).read_to_end(&mut content).unwrap();
+            content
+        } else {
+            raw.to_vec()
+        };
+        assert_eq!(crc32fast::hash(&content), crc, "{}", name);
+        files.insert(name, content);
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_stores_files_under_unique_names() {
+        let mut archive = ZipArchive::new();
+        archive.add("INV-1.pdf", b"%PDF-1.7 first").unwrap();
+        archive.add("INV-1.pdf", b"%PDF-1.7 second").unwrap();
+        archive.add("INV-1.pdf", b"%PDF-1.7 third").unwrap();
+        archive.add("README", b"").unwrap();
+        let data = archive.finish().unwrap();
+
+        let files = unzip(&data);
+        assert_eq!(files.len(), 4);
+        assert_eq!(files["INV-1.pdf"], b"%PDF-1.7 first");
+        assert_eq!(files["INV-1 (2).pdf"], b"%PDF-1.7 second");
+        assert_eq!(files["INV-1 (3).pdf"], b"%PDF-1.7 third");
+        assert!(files["README"].is_empty());
+    }
+
+    #[test]
+    fn test_empty_archive() {
+        let data = ZipArchive::new().finish().unwrap();
+        assert_eq!(data.len(), 22);
+        assert!(unzip(&data).is_empty());
+    }
+}
@@ -1,16 +1,43 @@
 #![allow(dead_code)]
 
-use sqlx::{postgres::PgPoolOptions, PgPool, Error, migrate::MigrateError};
+use sqlx::{postgres::PgPoolOptions, PgConnection, PgPool, Error, migrate::MigrateError};
 use std::time::Duration;
 
+use crate::domain::request_id;
+
 pub async fn create_pool(database_url: &str) -> Result<PgPool, Error> {
-    PgPoolOptions::new()
+    with_request_ids(PgPoolOptions::new())
         .max_connections(10)
         .acquire_timeout(Duration::from_secs(30))
         .connect(database_url)
         .await
 }
 
+/// Tags each connection with the request ID of whoever acquires it, as the
+/// `app.request_id` setting (empty outside a request), so rows written by
+/// triggers such as webhook events can record it. The statement replaces the
+/// liveness ping otherwise run before handing out an idle connection.
+pub fn with_request_ids(options: PgPoolOptions) -> PgPoolOptions {
+    options
+        .test_before_acquire(false)
+        .after_connect(|conn, _| {
+            let id = request_id::current().unwrap_or_default();
+            Box::pin(async move { set_request_id(conn, id).await })
+        })
+        .before_acquire(|conn, _| {
+            let id = request_id::current().unwrap_or_default();
+            Box::pin(async move { set_request_id(conn, id).await.map(|_| true) })
+        })
+}
+
+async fn set_request_id(conn: &mut PgConnection, id: String) -> Result<(), Error> {
+    sqlx::query("SELECT set_config('app.request_id', $1, false)")
+        .bind(id)
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
 pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
     sqlx::migrate!("./migrations")
         .run(pool)
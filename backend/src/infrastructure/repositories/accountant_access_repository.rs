@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::AccountantAccess;
+
+const ACCESS_COLUMNS: &str = "id, user_id, email, name, expires_at, revoked_at, last_used_at, created_at";
+
+#[derive(Clone)]
+pub struct AccountantAccessRepository {
+    db: PgPool,
+}
+
+impl AccountantAccessRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        name: Option<&str>,
+        access_code: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<AccountantAccess, sqlx::Error> {
+        let row = sqlx::query_as::<_, AccountantAccessRow>(&format!(
+            r#"
+            INSERT INTO accountant_access (id, user_id, email, name, access_code, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            ACCESS_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(email)
+        .bind(name)
+        .bind(access_code)
+        .bind(expires_at)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_access())
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<AccountantAccess>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AccountantAccessRow>(&format!(
+            "SELECT {} FROM accountant_access WHERE user_id = $1 ORDER BY created_at DESC",
+            ACCESS_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AccountantAccessRow::into_access).collect())
+    }
+
+    /// Revoke a grant; tokens already issued for it stop working on their next request
+    pub async fn revoke(&self, user_id: Uuid, access_id: Uuid) -> Result<Option<AccountantAccess>, sqlx::Error> {
+        let row = sqlx::query_as::<_, AccountantAccessRow>(&format!(
+            r#"
+            UPDATE accountant_access SET revoked_at = COALESCE(revoked_at, $3)
+            WHERE user_id = $1 AND id = $2
+            RETURNING {}
+            "#,
+            ACCESS_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(access_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(AccountantAccessRow::into_access))
+    }
+
+    /// Find the grant for an access code and record the sign-in.
+    /// Returns the business account it belongs to alongside the grant.
+    pub async fn sign_in(&self, access_code: &str, now: DateTime<Utc>) -> Result<Option<(Uuid, AccountantAccess)>, sqlx::Error> {
+        let row = sqlx::query_as::<_, AccountantAccessRow>(&format!(
+            r#"
+            UPDATE accountant_access SET last_used_at = $2
+            WHERE access_code = $1 AND revoked_at IS NULL AND expires_at > $2
+            RETURNING {}
+            "#,
+            ACCESS_COLUMNS
+        ))
+        .bind(access_code)
+        .bind(now)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(|row| (row.user_id, row.into_access())))
+    }
+
+    pub async fn is_active(&self, user_id: Uuid, access_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let active: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM accountant_access
+                WHERE user_id = $1 AND id = $2 AND revoked_at IS NULL AND expires_at > $3
+            )
+            "#,
+        )
+        .bind(user_id)
+        .bind(access_id)
+        .bind(now)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(active)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AccountantAccessRow {
+    id: Uuid,
+    user_id: Uuid,
+    email: String,
+    name: Option<String>,
+    expires_at: DateTime<Utc>,
+    revoked_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl AccountantAccessRow {
+    fn into_access(self) -> AccountantAccess {
+        AccountantAccess {
+            id: self.id,
+            email: self.email,
+            name: self.name,
+            expires_at: self.expires_at,
+            revoked_at: self.revoked_at,
+            last_used_at: self.last_used_at,
+            created_at: self.created_at,
+        }
+    }
+}
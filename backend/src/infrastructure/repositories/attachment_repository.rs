@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::models::{Attachment, AttachmentParent};
+
+const SELECT_ATTACHMENTS: &str = r#"
+    SELECT id, user_id, invoice_id, expense_id, original_name, mime_type, file_size, file_name, created_at
+    FROM attachments
+"#;
+
+/// Stored file details for a new attachment
+pub struct NewAttachment<'a> {
+    pub file_name: &'a str,
+    pub stored_name: &'a str,
+    pub mime_type: &'a str,
+    pub file_size: i64,
+}
+
+#[derive(Clone)]
+pub struct AttachmentRepository {
+    db: PgPool,
+}
+
+impl AttachmentRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.db.begin().await
+    }
+
+    /// Whether the invoice or expense exists and belongs to the user
+    pub async fn parent_exists(&self, user_id: Uuid, parent: AttachmentParent) -> Result<bool, sqlx::Error> {
+        let (sql, id) = match parent {
+            AttachmentParent::Invoice(id) => ("SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL)", id),
+            AttachmentParent::Expense(id) => ("SELECT EXISTS(SELECT 1 FROM expenses WHERE id = $1 AND user_id = $2)", id),
+        };
+
+        sqlx::query_scalar(sql).bind(id).bind(user_id).fetch_one(&self.db).await
+    }
+
+    /// Bytes of attachments the user has stored, locking the user so concurrent
+    /// uploads are checked against the limit one at a time
+    pub async fn lock_used_bytes(&self, tx: &mut Transaction<'_, Postgres>, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query_scalar("SELECT COALESCE(SUM(file_size), 0)::int8 FROM attachments WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&mut **tx)
+            .await
+    }
+
+    pub async fn insert(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        parent: AttachmentParent,
+        file: NewAttachment<'_>,
+        now: DateTime<Utc>,
+    ) -> Result<Attachment, sqlx::Error> {
+        let (invoice_id, expense_id) = match parent {
+            AttachmentParent::Invoice(id) => (Some(id), None),
+            AttachmentParent::Expense(id) => (None, Some(id)),
+        };
+
+        let row = sqlx::query_as::<_, AttachmentRow>(
+            r#"
+            INSERT INTO attachments (id, user_id, invoice_id, expense_id, file_name, original_name, mime_type, file_size, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, user_id, invoice_id, expense_id, original_name, mime_type, file_size, file_name, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(invoice_id)
+        .bind(expense_id)
+        .bind(file.stored_name)
+        .bind(file.file_name)
+        .bind(file.mime_type)
+        .bind(file.file_size)
+        .bind(now)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        Ok(row.into_attachment())
+    }
+
+    pub async fn list(&self, user_id: Uuid, parent: AttachmentParent) -> Result<Vec<Attachment>, sqlx::Error> {
+        let (column, id) = Self::parent_column(parent);
+        let rows = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "{} WHERE {} = $1 AND user_id = $2 ORDER BY created_at, id",
+            SELECT_ATTACHMENTS, column
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AttachmentRow::into_attachment).collect())
+    }
+
+    pub async fn find(&self, user_id: Uuid, parent: AttachmentParent, attachment_id: Uuid) -> Result<Option<Attachment>, sqlx::Error> {
+        let (column, id) = Self::parent_column(parent);
+        let row = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "{} WHERE id = $1 AND {} = $2 AND user_id = $3",
+            SELECT_ATTACHMENTS, column
+        ))
+        .bind(attachment_id)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(AttachmentRow::into_attachment))
+    }
+
+    /// Attachments of an invoice the caller already has access to, e.g. through a guest link
+    pub async fn list_for_invoice(&self, invoice_id: Uuid) -> Result<Vec<Attachment>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "{} WHERE invoice_id = $1 ORDER BY created_at, id",
+            SELECT_ATTACHMENTS
+        ))
+        .bind(invoice_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AttachmentRow::into_attachment).collect())
+    }
+
+    pub async fn find_for_invoice(&self, invoice_id: Uuid, attachment_id: Uuid) -> Result<Option<Attachment>, sqlx::Error> {
+        let row = sqlx::query_as::<_, AttachmentRow>(&format!(
+            "{} WHERE id = $1 AND invoice_id = $2",
+            SELECT_ATTACHMENTS
+        ))
+        .bind(attachment_id)
+        .bind(invoice_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(AttachmentRow::into_attachment))
+    }
+
+    /// Delete the record, returning it so the stored file can be removed
+    pub async fn delete(&self, user_id: Uuid, parent: AttachmentParent, attachment_id: Uuid) -> Result<Option<Attachment>, sqlx::Error> {
+        let (column, id) = Self::parent_column(parent);
+        let row = sqlx::query_as::<_, AttachmentRow>(&format!(
+            r#"
+            DELETE FROM attachments
+            WHERE id = $1 AND {} = $2 AND user_id = $3
+            RETURNING id, user_id, invoice_id, expense_id, original_name, mime_type, file_size, file_name, created_at
+            "#,
+            column
+        ))
+        .bind(attachment_id)
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(AttachmentRow::into_attachment))
+    }
+
+    fn parent_column(parent: AttachmentParent) -> (&'static str, Uuid) {
+        match parent {
+            AttachmentParent::Invoice(id) => ("invoice_id", id),
+            AttachmentParent::Expense(id) => ("expense_id", id),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AttachmentRow {
+    id: Uuid,
+    user_id: Uuid,
+    invoice_id: Option<Uuid>,
+    expense_id: Option<Uuid>,
+    original_name: String,
+    mime_type: String,
+    file_size: i64,
+    file_name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl AttachmentRow {
+    fn into_attachment(self) -> Attachment {
+        Attachment {
+            id: self.id,
+            user_id: self.user_id,
+            invoice_id: self.invoice_id,
+            expense_id: self.expense_id,
+            file_name: self.original_name,
+            mime_type: self.mime_type,
+            file_size: self.file_size,
+            stored_name: self.file_name,
+            created_at: self.created_at,
+        }
+    }
+}
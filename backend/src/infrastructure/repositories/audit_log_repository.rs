@@ -0,0 +1,175 @@
+use chrono::{DateTime, Days, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    AuditAction, AuditEntityType, AuditListFilter, AuditLog, CreateAuditLog, DEFAULT_AUDIT_PAGE_SIZE,
+    MAX_AUDIT_PAGE_SIZE,
+};
+
+#[derive(Clone)]
+pub struct AuditLogRepository {
+    db: PgPool,
+}
+
+impl AuditLogRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn insert(&self, entry: &CreateAuditLog) -> Result<Uuid, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let id = insert_audit_log(&mut tx, entry).await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// The record as stored, for diffing before and after a change. Settings are
+    /// the account's own user row.
+    pub async fn snapshot(
+        &self,
+        user_id: Uuid,
+        entity_type: AuditEntityType,
+        entity_id: Uuid,
+    ) -> Result<Option<serde_json::Value>, sqlx::Error> {
+        let sql = match entity_type {
+            AuditEntityType::Invoice => "SELECT to_jsonb(t) FROM invoices t WHERE t.id = $1 AND t.user_id = $2",
+            AuditEntityType::Client => "SELECT to_jsonb(t) FROM clients t WHERE t.id = $1 AND t.user_id = $2",
+            AuditEntityType::Payment => "SELECT to_jsonb(t) FROM payments t WHERE t.id = $1 AND t.user_id = $2",
+            AuditEntityType::Expense => "SELECT to_jsonb(t) FROM expenses t WHERE t.id = $1 AND t.user_id = $2",
+            AuditEntityType::User | AuditEntityType::Settings => {
+                "SELECT to_jsonb(t) FROM users t WHERE t.id = $1 AND t.id = $2"
+            }
+        };
+
+        sqlx::query_scalar(sql)
+            .bind(entity_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Newest first
+    pub async fn list(&self, user_id: Uuid, filter: &AuditListFilter) -> Result<Vec<AuditLog>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, user_id, actor_id, action, entity_type, entity_id, changes,
+                host(ip_address) as ip_address, user_agent, request_id, created_at
+            FROM audit_logs
+            WHERE user_id = "#,
+        );
+        query_builder.push_bind(user_id);
+
+        if let Some(action) = filter.action {
+            query_builder.push(" AND action = ");
+            query_builder.push_bind(action.to_string());
+        }
+
+        if let Some(entity_type) = filter.entity_type {
+            query_builder.push(" AND entity_type = ");
+            query_builder.push_bind(entity_type.to_string());
+        }
+
+        if let Some(entity_id) = filter.entity_id {
+            query_builder.push(" AND entity_id = ");
+            query_builder.push_bind(entity_id);
+        }
+
+        if let Some(actor_id) = filter.actor_id {
+            query_builder.push(" AND actor_id = ");
+            query_builder.push_bind(actor_id);
+        }
+
+        if let Some(request_id) = &filter.request_id {
+            query_builder.push(" AND request_id = ");
+            query_builder.push_bind(request_id.clone());
+        }
+
+        if let Some(date_from) = filter.date_from {
+            query_builder.push(" AND created_at >= ");
+            query_builder.push_bind(date_from.and_hms_opt(0, 0, 0).map(|t| t.and_utc()));
+        }
+
+        // date_to is inclusive
+        if let Some(date_to) = filter.date_to.and_then(|d| d.checked_add_days(Days::new(1))) {
+            query_builder.push(" AND created_at < ");
+            query_builder.push_bind(date_to.and_hms_opt(0, 0, 0).map(|t| t.and_utc()));
+        }
+
+        query_builder.push(" ORDER BY created_at DESC, id");
+
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(filter.limit.unwrap_or(DEFAULT_AUDIT_PAGE_SIZE).clamp(1, MAX_AUDIT_PAGE_SIZE));
+
+        if let Some(offset) = filter.offset {
+            query_builder.push(" OFFSET ");
+            query_builder.push_bind(offset.max(0));
+        }
+
+        let rows = query_builder
+            .build_query_as::<AuditLogRow>()
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows.into_iter().filter_map(AuditLogRow::into_audit_log).collect())
+    }
+}
+
+/// Record an audit entry as part of the change it describes
+pub async fn insert_audit_log(tx: &mut Transaction<'_, Postgres>, entry: &CreateAuditLog) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO audit_logs (id, user_id, actor_id, action, entity_type, entity_id, changes, ip_address, user_agent, request_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8::inet, $9, $10)
+        "#,
+    )
+    .bind(id)
+    .bind(entry.user_id)
+    .bind(entry.actor_id)
+    .bind(entry.action.to_string())
+    .bind(entry.entity_type.to_string())
+    .bind(entry.entity_id)
+    .bind(&entry.changes)
+    .bind(&entry.ip_address)
+    .bind(&entry.user_agent)
+    .bind(&entry.request_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(id)
+}
+
+#[derive(sqlx::FromRow)]
+struct AuditLogRow {
+    id: Uuid,
+    user_id: Option<Uuid>,
+    actor_id: Option<Uuid>,
+    action: String,
+    entity_type: String,
+    entity_id: Option<Uuid>,
+    changes: Option<serde_json::Value>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    request_id: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl AuditLogRow {
+    /// None for actions or entity types this version doesn't know
+    fn into_audit_log(self) -> Option<AuditLog> {
+        Some(AuditLog {
+            id: self.id,
+            user_id: self.user_id,
+            actor_id: self.actor_id,
+            action: AuditAction::parse(&self.action)?,
+            entity_type: AuditEntityType::parse(&self.entity_type)?,
+            entity_id: self.entity_id,
+            changes: self.changes,
+            ip_address: self.ip_address,
+            user_agent: self.user_agent,
+            request_id: self.request_id,
+            created_at: self.created_at.unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,124 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::domain::models::{AutomationIssue, AutomationIssueKind, CreateAutomationIssue};
+
+#[derive(Clone)]
+pub struct AutomationIssueRepository {
+    db: PgPool,
+}
+
+impl AutomationIssueRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, create: CreateAutomationIssue) -> Result<AutomationIssue, sqlx::Error> {
+        let row = sqlx::query_as::<_, AutomationIssueRow>(
+            r#"
+            INSERT INTO automation_issues (id, user_id, kind, invoice_id, summary, detail, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(create.user_id)
+        .bind(create.kind.as_str())
+        .bind(create.invoice_id)
+        .bind(&create.summary)
+        .bind(&create.detail)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_issue())
+    }
+
+    pub async fn list(&self, user_id: Uuid, include_resolved: bool) -> Result<Vec<AutomationIssue>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AutomationIssueRow>(
+            r#"
+            SELECT * FROM automation_issues
+            WHERE user_id = $1 AND ($2 OR resolved_at IS NULL)
+            ORDER BY created_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(user_id)
+        .bind(include_resolved)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AutomationIssueRow::into_issue).collect())
+    }
+
+    pub async fn resolve(&self, user_id: Uuid, id: Uuid) -> Result<Option<AutomationIssue>, sqlx::Error> {
+        let row = sqlx::query_as::<_, AutomationIssueRow>(
+            r#"
+            UPDATE automation_issues
+            SET resolved_at = COALESCE(resolved_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(AutomationIssueRow::into_issue))
+    }
+
+    /// Open issues not yet included in a digest, oldest first
+    pub async fn list_undigested(&self) -> Result<Vec<AutomationIssue>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AutomationIssueRow>(
+            r#"
+            SELECT * FROM automation_issues
+            WHERE digested_at IS NULL AND resolved_at IS NULL
+            ORDER BY user_id, created_at
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AutomationIssueRow::into_issue).collect())
+    }
+
+    pub async fn mark_digested(&self, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE automation_issues SET digested_at = NOW() WHERE id = ANY($1)")
+            .bind(ids)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AutomationIssueRow {
+    id: Uuid,
+    user_id: Uuid,
+    kind: String,
+    invoice_id: Option<Uuid>,
+    summary: String,
+    detail: Option<String>,
+    created_at: DateTime<Utc>,
+    digested_at: Option<DateTime<Utc>>,
+    resolved_at: Option<DateTime<Utc>>,
+}
+
+impl AutomationIssueRow {
+    fn into_issue(self) -> AutomationIssue {
+        AutomationIssue {
+            id: self.id,
+            user_id: self.user_id,
+            kind: AutomationIssueKind::parse(&self.kind).unwrap_or(AutomationIssueKind::EmailBounce),
+            invoice_id: self.invoice_id,
+            summary: self.summary,
+            detail: self.detail,
+            created_at: self.created_at,
+            digested_at: self.digested_at,
+            resolved_at: self.resolved_at,
+        }
+    }
+}
@@ -0,0 +1,501 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    Budget, BudgetActuals, BudgetAlert, BudgetLimit, BudgetLine, BudgetLineKind, BudgetPeriod, CreateBudgetLimit,
+    ExpenseCategory,
+};
+
+#[derive(Clone)]
+pub struct BudgetRepository {
+    db: PgPool,
+}
+
+impl BudgetRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        year: i32,
+        name: &str,
+        lines: &[BudgetLine],
+    ) -> Result<Budget, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as::<_, BudgetRow>(
+            r#"
+            INSERT INTO budgets (id, user_id, year, name, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(year)
+        .bind(name)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::insert_lines(&mut tx, row.id, lines).await?;
+        tx.commit().await?;
+
+        Ok(row.into_budget(lines.to_vec()))
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Budget>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BudgetRow>(
+            "SELECT * FROM budgets WHERE user_id = $1 ORDER BY year DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut budgets = Vec::with_capacity(rows.len());
+        for row in rows {
+            let lines = self.get_lines(row.id).await?;
+            budgets.push(row.into_budget(lines));
+        }
+        Ok(budgets)
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, budget_id: Uuid) -> Result<Option<Budget>, sqlx::Error> {
+        let row = sqlx::query_as::<_, BudgetRow>(
+            "SELECT * FROM budgets WHERE id = $1 AND user_id = $2",
+        )
+        .bind(budget_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let lines = self.get_lines(row.id).await?;
+                Ok(Some(row.into_budget(lines)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid,
+        name: Option<&str>,
+        lines: Option<&[BudgetLine]>,
+    ) -> Result<Option<Budget>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let row = sqlx::query_as::<_, BudgetRow>(
+            r#"
+            UPDATE budgets SET name = COALESCE($3, name), updated_at = $4
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(budget_id)
+        .bind(user_id)
+        .bind(name)
+        .bind(Utc::now())
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let row = match row {
+            Some(row) => row,
+            None => return Ok(None),
+        };
+
+        if let Some(lines) = lines {
+            sqlx::query("DELETE FROM budget_lines WHERE budget_id = $1")
+                .bind(budget_id)
+                .execute(&mut *tx)
+                .await?;
+            Self::insert_lines(&mut tx, budget_id, lines).await?;
+        }
+
+        tx.commit().await?;
+
+        let lines = self.get_lines(budget_id).await?;
+        Ok(Some(row.into_budget(lines)))
+    }
+
+    pub async fn delete(&self, user_id: Uuid, budget_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM budgets WHERE id = $1 AND user_id = $2")
+            .bind(budget_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Payments received and expenses incurred between the two dates, by month
+    pub async fn get_actuals(
+        &self,
+        user_id: Uuid,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BudgetActuals, sqlx::Error> {
+        let income: Vec<(i32, f64)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(MONTH FROM created_at)::int4 as month, SUM(amount)::float8 as amount
+            FROM payments
+            WHERE user_id = $1 AND status = 'completed'
+              AND created_at::date BETWEEN $2 AND $3
+            GROUP BY 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await?;
+
+        let expenses: Vec<(i32, String, f64)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(MONTH FROM date_incurred)::int4 as month, category, SUM(amount)::float8 as amount
+            FROM expenses
+            WHERE user_id = $1 AND date_incurred BETWEEN $2 AND $3
+            GROUP BY 1, 2
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(BudgetActuals {
+            income_by_month: income.into_iter().map(|(m, a)| (m as u32, a)).collect(),
+            expenses: expenses.into_iter().map(|(m, c, a)| ((m as u32, c), a)).collect(),
+        })
+    }
+
+    pub async fn list_limits(&self, user_id: Uuid) -> Result<Vec<BudgetLimit>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BudgetLimitRow>(&format!(
+            "SELECT {} FROM budget_limits WHERE user_id = $1 ORDER BY category, period",
+            LIMIT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BudgetLimitRow::into_limit).collect())
+    }
+
+    pub async fn create_limit(&self, user_id: Uuid, create: &CreateBudgetLimit) -> Result<BudgetLimit, sqlx::Error> {
+        let row = sqlx::query_as::<_, BudgetLimitRow>(&format!(
+            r#"
+            INSERT INTO budget_limits (id, user_id, category, period, amount, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            RETURNING {}
+            "#,
+            LIMIT_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(create.category.to_string())
+        .bind(create.period.as_str())
+        .bind(create.amount)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_limit())
+    }
+
+    pub async fn update_limit(&self, user_id: Uuid, limit_id: Uuid, amount: f64) -> Result<Option<BudgetLimit>, sqlx::Error> {
+        let row = sqlx::query_as::<_, BudgetLimitRow>(&format!(
+            r#"
+            UPDATE budget_limits SET amount = $3, updated_at = $4
+            WHERE id = $1 AND user_id = $2
+            RETURNING {}
+            "#,
+            LIMIT_COLUMNS
+        ))
+        .bind(limit_id)
+        .bind(user_id)
+        .bind(amount)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(BudgetLimitRow::into_limit))
+    }
+
+    pub async fn delete_limit(&self, user_id: Uuid, limit_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM budget_limits WHERE id = $1 AND user_id = $2")
+            .bind(limit_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Expenses incurred between the two dates, by category
+    pub async fn get_spending(
+        &self,
+        user_id: Uuid,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<BTreeMap<String, f64>, sqlx::Error> {
+        let rows: Vec<(String, f64)> = sqlx::query_as(
+            r#"
+            SELECT category, SUM(amount)::float8 as amount
+            FROM expenses
+            WHERE user_id = $1 AND date_incurred BETWEEN $2 AND $3
+            GROUP BY 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Record that spending reached a threshold. None when that was already
+    /// recorded for the limit and period.
+    pub async fn record_alert(
+        &self,
+        user_id: Uuid,
+        limit_id: Uuid,
+        period_start: NaiveDate,
+        threshold: u8,
+        spent: f64,
+        amount: f64,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO budget_alerts (id, user_id, limit_id, period_start, threshold, spent, amount, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (limit_id, period_start, threshold) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(limit_id)
+        .bind(period_start)
+        .bind(i16::from(threshold))
+        .bind(spent)
+        .bind(amount)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Forget alerts of a limit from `period_start` on, so a changed limit is
+    /// measured afresh
+    pub async fn clear_alerts(&self, limit_id: Uuid, period_start: NaiveDate) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM budget_alerts WHERE limit_id = $1 AND period_start >= $2")
+            .bind(limit_id)
+            .bind(period_start)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn list_alerts(&self, user_id: Uuid, unread_only: bool) -> Result<Vec<BudgetAlert>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BudgetAlertRow>(&format!(
+            r#"
+            SELECT {} FROM budget_alerts a JOIN budget_limits l ON l.id = a.limit_id
+            WHERE a.user_id = $1 AND ($2 = FALSE OR a.read_at IS NULL)
+            ORDER BY a.created_at DESC, a.threshold DESC
+            LIMIT 200
+            "#,
+            ALERT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(unread_only)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BudgetAlertRow::into_alert).collect())
+    }
+
+    pub async fn find_alerts(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<BudgetAlert>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BudgetAlertRow>(&format!(
+            r#"
+            SELECT {} FROM budget_alerts a JOIN budget_limits l ON l.id = a.limit_id
+            WHERE a.user_id = $1 AND a.id = ANY($2)
+            ORDER BY l.category, a.threshold
+            "#,
+            ALERT_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BudgetAlertRow::into_alert).collect())
+    }
+
+    pub async fn mark_alert_read(&self, user_id: Uuid, alert_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE budget_alerts SET read_at = COALESCE(read_at, $3) WHERE id = $1 AND user_id = $2",
+        )
+        .bind(alert_id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_lines(&self, budget_id: Uuid) -> Result<Vec<BudgetLine>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BudgetLineRow>(
+            r#"
+            SELECT kind, category, month, amount::float8 as amount
+            FROM budget_lines
+            WHERE budget_id = $1
+            ORDER BY month, kind, category
+            "#,
+        )
+        .bind(budget_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BudgetLineRow::into_line).collect())
+    }
+
+    async fn insert_lines(
+        tx: &mut Transaction<'_, Postgres>,
+        budget_id: Uuid,
+        lines: &[BudgetLine],
+    ) -> Result<(), sqlx::Error> {
+        for line in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO budget_lines (id, budget_id, kind, category, month, amount)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(budget_id)
+            .bind(line.kind.as_str())
+            .bind(line.category.as_ref().map(|c| c.to_string()))
+            .bind(line.month as i16)
+            .bind(line.amount)
+            .execute(&mut **tx)
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct BudgetRow {
+    id: Uuid,
+    user_id: Uuid,
+    year: i32,
+    name: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl BudgetRow {
+    fn into_budget(self, lines: Vec<BudgetLine>) -> Budget {
+        Budget {
+            id: self.id,
+            user_id: self.user_id,
+            year: self.year,
+            name: self.name,
+            lines,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct BudgetLineRow {
+    kind: String,
+    category: Option<String>,
+    month: i16,
+    amount: f64,
+}
+
+impl BudgetLineRow {
+    fn into_line(self) -> BudgetLine {
+        BudgetLine {
+            kind: BudgetLineKind::parse(&self.kind).unwrap_or(BudgetLineKind::Expense),
+            category: self
+                .category
+                .and_then(|c| serde_json::from_value::<ExpenseCategory>(serde_json::Value::String(c)).ok()),
+            month: self.month as u32,
+            amount: self.amount,
+        }
+    }
+}
+
+const LIMIT_COLUMNS: &str = "id, category, period, amount::float8 as amount, created_at, updated_at";
+
+const ALERT_COLUMNS: &str = r#"
+    a.id, a.limit_id, l.category, l.period, a.period_start, a.threshold,
+    a.spent::float8 as spent, a.amount::float8 as amount, a.created_at, a.read_at
+"#;
+
+#[derive(sqlx::FromRow)]
+struct BudgetLimitRow {
+    id: Uuid,
+    category: String,
+    period: String,
+    amount: f64,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl BudgetLimitRow {
+    fn into_limit(self) -> BudgetLimit {
+        BudgetLimit {
+            id: self.id,
+            category: ExpenseCategory::from_stored(&self.category),
+            period: BudgetPeriod::parse(&self.period).unwrap_or(BudgetPeriod::Monthly),
+            amount: self.amount,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct BudgetAlertRow {
+    id: Uuid,
+    limit_id: Uuid,
+    category: String,
+    period: String,
+    period_start: NaiveDate,
+    threshold: i16,
+    spent: f64,
+    amount: f64,
+    created_at: DateTime<Utc>,
+    read_at: Option<DateTime<Utc>>,
+}
+
+impl BudgetAlertRow {
+    fn into_alert(self) -> BudgetAlert {
+        BudgetAlert {
+            id: self.id,
+            limit_id: self.limit_id,
+            category: ExpenseCategory::from_stored(&self.category),
+            period: BudgetPeriod::parse(&self.period).unwrap_or(BudgetPeriod::Monthly),
+            period_start: self.period_start,
+            threshold: self.threshold as u8,
+            spent: self.spent,
+            amount: self.amount,
+            created_at: self.created_at,
+            read_at: self.read_at,
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{business_account_email, Business, User};
+
+#[derive(Clone)]
+pub struct BusinessRepository {
+    db: PgPool,
+}
+
+impl BusinessRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Create the business account and its business row. The account starts with the
+    /// owner's currency, subscription and notification preferences, and no password.
+    pub async fn create(&self, owner: &User, name: &str) -> Result<Business, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let business_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO users (
+                id, email, company_name, password_hash, email_verified,
+                subscription_tier, subscription_status, currency, notification_settings,
+                created_at, updated_at
+            )
+            SELECT $1, $2, $3, NULL, TRUE,
+                   subscription_tier, subscription_status, currency, notification_settings,
+                   $4, $4
+            FROM users WHERE id = $5
+            "#,
+        )
+        .bind(business_id)
+        .bind(business_account_email(&owner.email, business_id))
+        .bind(name)
+        .bind(now)
+        .bind(owner.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let row = sqlx::query_as::<_, BusinessRow>(
+            r#"
+            INSERT INTO businesses (id, owner_id, name, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $4)
+            RETURNING id, name, created_at
+            "#,
+        )
+        .bind(business_id)
+        .bind(owner.id)
+        .bind(name)
+        .bind(now)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(row.into_business())
+    }
+
+    pub async fn list(&self, owner_id: Uuid) -> Result<Vec<Business>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BusinessRow>(
+            "SELECT id, name, created_at FROM businesses WHERE owner_id = $1 ORDER BY created_at",
+        )
+        .bind(owner_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BusinessRow::into_business).collect())
+    }
+
+    pub async fn rename(&self, owner_id: Uuid, business_id: Uuid, name: &str) -> Result<Option<Business>, sqlx::Error> {
+        let row = sqlx::query_as::<_, BusinessRow>(
+            r#"
+            UPDATE businesses SET name = $3, updated_at = $4
+            WHERE id = $1 AND owner_id = $2
+            RETURNING id, name, created_at
+            "#,
+        )
+        .bind(business_id)
+        .bind(owner_id)
+        .bind(name)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(BusinessRow::into_business))
+    }
+
+    /// Delete the business account; its clients, invoices, expenses and settings cascade
+    pub async fn delete(&self, owner_id: Uuid, business_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM users
+            WHERE id = $1 AND EXISTS (SELECT 1 FROM businesses WHERE id = $1 AND owner_id = $2)
+            "#,
+        )
+        .bind(business_id)
+        .bind(owner_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct BusinessRow {
+    id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl BusinessRow {
+    fn into_business(self) -> Business {
+        Business {
+            id: self.id,
+            name: self.name,
+            is_primary: false,
+            created_at: self.created_at,
+        }
+    }
+}
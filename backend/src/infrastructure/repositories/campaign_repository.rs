@@ -0,0 +1,398 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    Campaign, CampaignAudienceMember, CampaignRecipient, CampaignRecipientStatus, CampaignSegment,
+    CampaignStatus, CampaignTemplate,
+};
+
+const CAMPAIGN_COLUMNS: &str = "id, name, template, subject_template, body_template, segment, send_interval_seconds, status, start_at, completed_at, created_at";
+
+/// A recipient claimed for sending, with what's needed to render its email
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DueCampaignRecipient {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub client_id: Uuid,
+    pub subject_template: String,
+    pub body_template: String,
+    pub company_name: String,
+}
+
+/// New campaign with its audience snapshot
+pub struct NewCampaign<'a> {
+    pub name: &'a str,
+    pub template: CampaignTemplate,
+    pub subject_template: &'a str,
+    pub body_template: &'a str,
+    pub segment: &'a CampaignSegment,
+    pub send_interval_seconds: i64,
+    pub start_at: DateTime<Utc>,
+    pub audience: &'a [CampaignAudienceMember],
+}
+
+#[derive(Clone)]
+pub struct CampaignRepository {
+    db: PgPool,
+}
+
+impl CampaignRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Clients with an email address and an open balance that match the segment,
+    /// largest balance first. `today` anchors the days-overdue filters.
+    pub async fn audience(
+        &self,
+        user_id: Uuid,
+        segment: &CampaignSegment,
+        today: NaiveDate,
+    ) -> Result<Vec<CampaignAudienceMember>, sqlx::Error> {
+        // Oldest open invoice due on or before / on or after these dates
+        let due_on_or_before = segment.min_days_overdue.map(|days| today - Duration::days(days));
+        let due_on_or_after = segment.max_days_overdue.map(|days| today - Duration::days(days));
+
+        sqlx::query_as::<_, CampaignAudienceMember>(
+            r#"
+            SELECT
+                c.id as client_id, c.name as client_name, c.email as email,
+                SUM(i.total_amount - i.amount_paid)::float8 as outstanding_balance,
+                COUNT(i.id) as open_invoices,
+                MIN(i.due_date) as oldest_due_date
+            FROM clients c
+            JOIN invoices i ON i.client_id = c.id AND i.user_id = c.user_id
+            WHERE c.user_id = $1
+              AND c.deleted_at IS NULL
+              AND c.email IS NOT NULL AND c.email <> ''
+              AND i.status IN ('sent', 'viewed', 'partial', 'overdue')
+              AND i.total_amount > i.amount_paid
+              AND ($2::uuid[] IS NULL OR c.id = ANY($2))
+            GROUP BY c.id, c.name, c.email
+            HAVING SUM(i.total_amount - i.amount_paid) >= $3
+              AND ($4::date IS NULL OR MIN(i.due_date) <= $4)
+              AND ($5::date IS NULL OR MIN(i.due_date) >= $5)
+            ORDER BY outstanding_balance DESC, c.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(segment.client_ids.as_deref())
+        .bind(segment.min_balance.unwrap_or(0.01))
+        .bind(due_on_or_before)
+        .bind(due_on_or_after)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Current balance for one client, None once nothing is owed
+    pub async fn audience_member(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<Option<CampaignAudienceMember>, sqlx::Error> {
+        let segment = CampaignSegment {
+            client_ids: Some(vec![client_id]),
+            ..Default::default()
+        };
+        Ok(self.audience(user_id, &segment, today).await?.into_iter().next())
+    }
+
+    /// Create the campaign and schedule one email per audience member, spaced by the interval
+    pub async fn create(&self, user_id: Uuid, campaign: NewCampaign<'_>) -> Result<Uuid, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let campaign_id = Uuid::new_v4();
+        let now = Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO dunning_campaigns (
+                id, user_id, name, template, subject_template, body_template, segment,
+                send_interval_seconds, status, start_at, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11)
+            "#,
+        )
+        .bind(campaign_id)
+        .bind(user_id)
+        .bind(campaign.name)
+        .bind(campaign.template.as_str())
+        .bind(campaign.subject_template)
+        .bind(campaign.body_template)
+        .bind(serde_json::to_value(campaign.segment).unwrap_or_default())
+        .bind(campaign.send_interval_seconds as i32)
+        .bind(CampaignStatus::Scheduled.as_str())
+        .bind(campaign.start_at)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        for (slot, member) in campaign.audience.iter().enumerate() {
+            let send_after = campaign.start_at + Duration::seconds(campaign.send_interval_seconds * slot as i64);
+            sqlx::query(
+                r#"
+                INSERT INTO dunning_campaign_recipients (
+                    id, campaign_id, client_id, client_name, email, targeted_balance, status, send_after
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                "#,
+            )
+            .bind(Uuid::new_v4())
+            .bind(campaign_id)
+            .bind(member.client_id)
+            .bind(&member.client_name)
+            .bind(&member.email)
+            .bind(member.outstanding_balance)
+            .bind(CampaignRecipientStatus::Pending.as_str())
+            .bind(send_after)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(campaign_id)
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Campaign>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, CampaignRow>(&format!(
+            "SELECT {} FROM dunning_campaigns WHERE user_id = $1 ORDER BY created_at DESC",
+            CAMPAIGN_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(CampaignRow::into_campaign).collect())
+    }
+
+    pub async fn find(&self, user_id: Uuid, campaign_id: Uuid) -> Result<Option<Campaign>, sqlx::Error> {
+        let row = sqlx::query_as::<_, CampaignRow>(&format!(
+            "SELECT {} FROM dunning_campaigns WHERE user_id = $1 AND id = $2",
+            CAMPAIGN_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(campaign_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(CampaignRow::into_campaign))
+    }
+
+    /// Recipients with the payments each client made since their email went out
+    pub async fn recipients(&self, campaign_id: Uuid) -> Result<Vec<CampaignRecipient>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, CampaignRecipientRow>(
+            r#"
+            SELECT
+                r.id, r.client_id, r.client_name, r.email,
+                r.targeted_balance::float8 as targeted_balance,
+                r.sent_balance::float8 as sent_balance,
+                r.status, r.error, r.send_after, r.sent_at,
+                COALESCE((
+                    SELECT SUM(p.amount)::float8
+                    FROM payments p
+                    JOIN invoices i ON i.id = p.invoice_id
+                    WHERE i.client_id = r.client_id AND p.status = 'completed'
+                      AND r.sent_at IS NOT NULL AND p.created_at >= r.sent_at
+                ), 0.0::float8) as paid_since_sent
+            FROM dunning_campaign_recipients r
+            WHERE r.campaign_id = $1
+            ORDER BY r.send_after, r.client_name
+            "#,
+        )
+        .bind(campaign_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(CampaignRecipientRow::into_recipient).collect())
+    }
+
+    /// Cancel a campaign that hasn't finished; emails not yet sent are skipped
+    pub async fn cancel(&self, user_id: Uuid, campaign_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE dunning_campaigns SET status = 'cancelled', completed_at = $3, updated_at = $3
+            WHERE user_id = $1 AND id = $2 AND status IN ('scheduled', 'sending')
+            "#,
+        )
+        .bind(user_id)
+        .bind(campaign_id)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "UPDATE dunning_campaign_recipients SET status = 'skipped', error = 'Campaign cancelled' WHERE campaign_id = $1 AND status = 'pending'",
+        )
+        .bind(campaign_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Claim recipients whose slot has come, oldest first. Claimed rows move to
+    /// 'sending' so concurrent instances never email the same client twice.
+    pub async fn claim_due(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<DueCampaignRecipient>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let due = sqlx::query_as::<_, DueCampaignRecipient>(
+            r#"
+            UPDATE dunning_campaign_recipients r
+            SET status = 'sending'
+            FROM dunning_campaigns c
+            JOIN users u ON u.id = c.user_id
+            WHERE r.campaign_id = c.id
+              AND r.id IN (
+                  SELECT r2.id FROM dunning_campaign_recipients r2
+                  JOIN dunning_campaigns c2 ON c2.id = r2.campaign_id
+                  WHERE r2.status = 'pending' AND r2.send_after <= $1
+                    AND c2.status IN ('scheduled', 'sending')
+                  ORDER BY r2.send_after
+                  LIMIT $2
+                  FOR UPDATE OF r2 SKIP LOCKED
+              )
+            RETURNING r.id, c.user_id, r.client_id, c.subject_template, c.body_template,
+                      COALESCE(u.company_name, u.email) as company_name
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE dunning_campaigns SET status = 'sending', updated_at = $1
+            WHERE status = 'scheduled'
+              AND id IN (SELECT campaign_id FROM dunning_campaign_recipients WHERE id = ANY($2))
+            "#,
+        )
+        .bind(now)
+        .bind(due.iter().map(|r| r.id).collect::<Vec<_>>())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(due)
+    }
+
+    pub async fn record_outcome(
+        &self,
+        recipient_id: Uuid,
+        status: CampaignRecipientStatus,
+        sent_balance: Option<f64>,
+        error: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE dunning_campaign_recipients
+            SET status = $2, sent_balance = $3, error = $4,
+                sent_at = CASE WHEN $2 = 'sent' THEN $5 ELSE sent_at END
+            WHERE id = $1
+            "#,
+        )
+        .bind(recipient_id)
+        .bind(status.as_str())
+        .bind(sent_balance)
+        .bind(error)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark campaigns with no emails left to send as completed
+    pub async fn complete_finished(&self, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE dunning_campaigns c SET status = 'completed', completed_at = $1, updated_at = $1
+            WHERE c.status IN ('scheduled', 'sending')
+              AND NOT EXISTS (
+                  SELECT 1 FROM dunning_campaign_recipients r
+                  WHERE r.campaign_id = c.id AND r.status IN ('pending', 'sending')
+              )
+            "#,
+        )
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CampaignRow {
+    id: Uuid,
+    name: String,
+    template: String,
+    subject_template: String,
+    body_template: String,
+    segment: serde_json::Value,
+    send_interval_seconds: i32,
+    status: String,
+    start_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl CampaignRow {
+    fn into_campaign(self) -> Campaign {
+        Campaign {
+            id: self.id,
+            name: self.name,
+            template: CampaignTemplate::parse(&self.template).unwrap_or(CampaignTemplate::Friendly),
+            subject_template: self.subject_template,
+            body_template: self.body_template,
+            segment: serde_json::from_value(self.segment).unwrap_or_default(),
+            send_interval_seconds: self.send_interval_seconds,
+            status: CampaignStatus::parse(&self.status).unwrap_or(CampaignStatus::Scheduled),
+            start_at: self.start_at,
+            completed_at: self.completed_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CampaignRecipientRow {
+    id: Uuid,
+    client_id: Uuid,
+    client_name: String,
+    email: String,
+    targeted_balance: f64,
+    sent_balance: Option<f64>,
+    status: String,
+    error: Option<String>,
+    send_after: DateTime<Utc>,
+    sent_at: Option<DateTime<Utc>>,
+    paid_since_sent: f64,
+}
+
+impl CampaignRecipientRow {
+    fn into_recipient(self) -> CampaignRecipient {
+        CampaignRecipient {
+            id: self.id,
+            client_id: self.client_id,
+            client_name: self.client_name,
+            email: self.email,
+            targeted_balance: self.targeted_balance,
+            sent_balance: self.sent_balance,
+            status: CampaignRecipientStatus::parse(&self.status).unwrap_or(CampaignRecipientStatus::Pending),
+            error: self.error,
+            send_after: self.send_after,
+            sent_at: self.sent_at,
+            paid_since_sent: self.paid_since_sent,
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{ClientAccount, InvoiceStatus, PortalInvoice, PortalVendor};
+
+const ACCOUNT_COLUMNS: &str = "id, email, password_hash, last_sign_in_at, created_at";
+
+/// Client records an account sees: live clients, at any business, with its email
+const ACCOUNT_CLIENTS: &str = r#"
+    client_accounts a
+    JOIN clients c ON LOWER(c.email) = LOWER(a.email) AND c.deleted_at IS NULL
+"#;
+
+/// Buyer accounts for the client portal and their sign-in links. Emails are
+/// stored normalized; lookups compare them case-insensitively all the same.
+#[derive(Clone)]
+pub struct ClientAccountRepository {
+    db: PgPool,
+}
+
+impl ClientAccountRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Whether any business has a live client with this email
+    pub async fn is_client_email(&self, email: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM clients WHERE LOWER(email) = LOWER($1) AND deleted_at IS NULL)",
+        )
+        .bind(email)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    pub async fn count_links_since(&self, email: &str, since: DateTime<Utc>) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM client_sign_in_links WHERE LOWER(email) = LOWER($1) AND created_at > $2",
+        )
+        .bind(email)
+        .bind(since)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    pub async fn create_link(&self, email: &str, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO client_sign_in_links (id, email, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Use up a sign-in link, returning the email it was sent to. Used and expired
+    /// links return `None`.
+    pub async fn use_link(&self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            UPDATE client_sign_in_links SET used_at = $2
+            WHERE token_hash = $1 AND used_at IS NULL AND expires_at > $2
+            RETURNING email
+            "#,
+        )
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// The account for an email, created on first sign-in
+    pub async fn find_or_create(&self, email: &str) -> Result<ClientAccount, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientAccountRow>(&format!(
+            r#"
+            INSERT INTO client_accounts (id, email, created_at, updated_at)
+            VALUES ($1, $2, NOW(), NOW())
+            ON CONFLICT ((LOWER(email))) DO UPDATE SET updated_at = client_accounts.updated_at
+            RETURNING {}
+            "#,
+            ACCOUNT_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_account())
+    }
+
+    pub async fn find_by_email(&self, email: &str) -> Result<Option<ClientAccount>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientAccountRow>(&format!(
+            "SELECT {} FROM client_accounts WHERE LOWER(email) = LOWER($1)",
+            ACCOUNT_COLUMNS
+        ))
+        .bind(email)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(ClientAccountRow::into_account))
+    }
+
+    pub async fn find_by_id(&self, account_id: Uuid) -> Result<Option<ClientAccount>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientAccountRow>(&format!(
+            "SELECT {} FROM client_accounts WHERE id = $1",
+            ACCOUNT_COLUMNS
+        ))
+        .bind(account_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(ClientAccountRow::into_account))
+    }
+
+    pub async fn record_sign_in(&self, account_id: Uuid, now: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE client_accounts SET last_sign_in_at = $2 WHERE id = $1")
+            .bind(account_id)
+            .bind(now)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn set_password(&self, account_id: Uuid, password_hash: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE client_accounts SET password_hash = $2, updated_at = NOW() WHERE id = $1")
+            .bind(account_id)
+            .bind(password_hash)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Businesses with a client record for the account's email
+    pub async fn vendors(&self, account_id: Uuid) -> Result<Vec<PortalVendor>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, VendorRow>(&format!(
+            r#"
+            SELECT DISTINCT u.id, COALESCE(u.company_name, u.email) as name, u.email
+            FROM {}
+            JOIN users u ON u.id = c.user_id
+            WHERE a.id = $1
+            ORDER BY name, u.id
+            "#,
+            ACCOUNT_CLIENTS
+        ))
+        .bind(account_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(VendorRow::into_vendor).collect())
+    }
+
+    /// Issued invoices billed to the account's email, from every business
+    pub async fn list_invoices(&self, account_id: Uuid) -> Result<Vec<PortalInvoice>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PortalInvoiceRow>(&format!(
+            r#"
+            SELECT
+                i.id, i.invoice_number, i.status, u.id as vendor_id,
+                COALESCE(u.company_name, u.email) as vendor_name,
+                i.issue_date, i.due_date, COALESCE(i.currency, 'USD') as currency,
+                i.total_amount, (i.total_amount - i.amount_paid) as balance_due
+            FROM {}
+            JOIN invoices i ON i.client_id = c.id AND i.status <> 'draft' AND i.deleted_at IS NULL
+            JOIN users u ON u.id = i.user_id
+            WHERE a.id = $1
+            ORDER BY i.issue_date DESC, i.created_at DESC
+            "#,
+            ACCOUNT_CLIENTS
+        ))
+        .bind(account_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(PortalInvoiceRow::into_invoice).collect())
+    }
+
+    /// The business an invoice belongs to, if the account may see it
+    pub async fn invoice_vendor(&self, account_id: Uuid, invoice_id: Uuid) -> Result<Option<PortalVendor>, sqlx::Error> {
+        let row = sqlx::query_as::<_, VendorRow>(&format!(
+            r#"
+            SELECT u.id, COALESCE(u.company_name, u.email) as name, u.email
+            FROM {}
+            JOIN invoices i ON i.client_id = c.id AND i.status <> 'draft' AND i.deleted_at IS NULL
+            JOIN users u ON u.id = i.user_id
+            WHERE a.id = $1 AND i.id = $2
+            "#,
+            ACCOUNT_CLIENTS
+        ))
+        .bind(account_id)
+        .bind(invoice_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(VendorRow::into_vendor))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ClientAccountRow {
+    id: Uuid,
+    email: String,
+    password_hash: Option<String>,
+    last_sign_in_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl ClientAccountRow {
+    fn into_account(self) -> ClientAccount {
+        ClientAccount {
+            id: self.id,
+            email: self.email,
+            password_hash: self.password_hash,
+            last_sign_in_at: self.last_sign_in_at,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct VendorRow {
+    id: Uuid,
+    name: String,
+    email: String,
+}
+
+impl VendorRow {
+    fn into_vendor(self) -> PortalVendor {
+        PortalVendor { id: self.id, name: self.name, email: self.email }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PortalInvoiceRow {
+    id: Uuid,
+    invoice_number: String,
+    status: InvoiceStatus,
+    vendor_id: Uuid,
+    vendor_name: String,
+    issue_date: NaiveDate,
+    due_date: NaiveDate,
+    currency: String,
+    total_amount: Decimal,
+    balance_due: Decimal,
+}
+
+impl PortalInvoiceRow {
+    fn into_invoice(self) -> PortalInvoice {
+        PortalInvoice {
+            id: self.id,
+            invoice_number: self.invoice_number,
+            status: self.status,
+            vendor_id: self.vendor_id,
+            vendor_name: self.vendor_name,
+            issue_date: self.issue_date,
+            due_date: self.due_date,
+            currency: self.currency,
+            total_amount: self.total_amount,
+            balance_due: self.balance_due,
+        }
+    }
+}
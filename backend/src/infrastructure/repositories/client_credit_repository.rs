@@ -0,0 +1,294 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    credit_to_apply, ClientCreditEntry, ClientCreditKind, CreditApplied, CreditBalance, InvoiceAction, InvoiceStatus,
+};
+
+const ENTRY_SELECT: &str = r#"
+    SELECT e.id, e.client_id, e.kind, e.amount, e.currency, e.invoice_id, i.invoice_number,
+        e.description, e.entry_date, e.created_at
+    FROM client_credit_entries e
+    LEFT JOIN invoices i ON i.id = e.invoice_id
+"#;
+
+/// A deposit or refund ready to be recorded; refunds carry a negative amount
+pub struct NewClientCredit<'a> {
+    pub kind: ClientCreditKind,
+    pub amount: Decimal,
+    pub currency: &'a str,
+    pub description: Option<&'a str>,
+    pub entry_date: NaiveDate,
+}
+
+/// Outcome of recording a deposit or refund
+pub enum RecordClientCredit {
+    Recorded(Box<ClientCreditEntry>),
+    /// A refund larger than the balance; carries what's available
+    Insufficient(Decimal),
+}
+
+#[derive(Clone)]
+pub struct ClientCreditRepository {
+    db: PgPool,
+}
+
+impl ClientCreditRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Record the entry, keeping the balance in its currency from going negative.
+    /// Returns None when the client doesn't exist.
+    pub async fn record(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        new: NewClientCredit<'_>,
+    ) -> Result<Option<RecordClientCredit>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        if !lock_client(&mut tx, user_id, client_id).await? {
+            return Ok(None);
+        }
+
+        let available = available_credit(&mut tx, client_id, new.currency).await?;
+        if available + new.amount < Decimal::ZERO {
+            return Ok(Some(RecordClientCredit::Insufficient(available)));
+        }
+
+        let id = insert_entry(&mut tx, user_id, client_id, None, &new).await?;
+        let entry = fetch(&mut tx, id).await?;
+        tx.commit().await?;
+
+        Ok(entry.map(|entry| RecordClientCredit::Recorded(Box::new(entry))))
+    }
+
+    /// Settle as much of the invoice's balance as the client's credit in its currency
+    /// covers. Nothing is applied to an invoice that no longer takes payments.
+    /// Returns None when the invoice doesn't exist.
+    pub async fn apply_to_invoice(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<Option<CreditApplied>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(invoice) = sqlx::query_as::<_, LockedInvoice>(
+            r#"
+            SELECT id, client_id, invoice_number, status, total_amount,
+                COALESCE(amount_paid, 0) as amount_paid,
+                COALESCE(currency, 'USD') as currency
+            FROM invoices
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        lock_client(&mut tx, user_id, invoice.client_id).await?;
+        let available = available_credit(&mut tx, invoice.client_id, &invoice.currency).await?;
+        let balance_due = invoice.total_amount - invoice.amount_paid;
+        let takes_payment = InvoiceStatus::parse(&invoice.status)
+            .is_some_and(|status| status.allows(InvoiceAction::RecordPayment));
+        let applied = if takes_payment { credit_to_apply(available, balance_due) } else { Decimal::ZERO };
+
+        if applied > Decimal::ZERO {
+            let description = format!("Applied to {}", invoice.invoice_number);
+            let entry = NewClientCredit {
+                kind: ClientCreditKind::Applied,
+                amount: -applied,
+                currency: &invoice.currency,
+                description: Some(&description),
+                entry_date: today,
+            };
+            insert_entry(&mut tx, user_id, invoice.client_id, Some(invoice.id), &entry).await?;
+
+            let amount_paid = invoice.amount_paid + applied;
+            let status = if amount_paid >= invoice.total_amount { InvoiceStatus::Paid } else { InvoiceStatus::Partial };
+            sqlx::query(
+                r#"
+                UPDATE invoices SET
+                    amount_paid = $1,
+                    status = $2,
+                    paid_at = CASE WHEN $2 = 'paid' THEN COALESCE(paid_at, NOW()) ELSE paid_at END,
+                    reminder_sent_count = CASE
+                        WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN 0 ELSE reminder_sent_count END,
+                    deposit_paid_at = CASE
+                        WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN NOW() ELSE deposit_paid_at END,
+                    updated_at = NOW()
+                WHERE id = $3
+                "#,
+            )
+            .bind(amount_paid)
+            .bind(status.to_string())
+            .bind(invoice.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(Some(CreditApplied {
+            invoice_id: invoice.id,
+            currency: invoice.currency,
+            applied,
+            balance_due: balance_due - applied,
+            remaining_credit: available - applied,
+        }))
+    }
+
+    /// Every entry of the client, oldest first
+    pub async fn list(&self, user_id: Uuid, client_id: Uuid) -> Result<Vec<ClientCreditEntry>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ClientCreditRow>(&format!(
+            "{} WHERE e.user_id = $1 AND e.client_id = $2 ORDER BY e.entry_date, e.created_at",
+            ENTRY_SELECT
+        ))
+        .bind(user_id)
+        .bind(client_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(ClientCreditRow::into_entry).collect())
+    }
+
+    /// Credit held at the end of `until` (or now), per currency; currencies with
+    /// nothing left are skipped
+    pub async fn balances(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        until: Option<NaiveDate>,
+    ) -> Result<Vec<CreditBalance>, sqlx::Error> {
+        let rows: Vec<(String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT currency, SUM(amount)
+            FROM client_credit_entries
+            WHERE user_id = $1 AND client_id = $2 AND ($3::date IS NULL OR entry_date <= $3)
+            GROUP BY currency
+            HAVING SUM(amount) <> 0
+            ORDER BY currency
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .bind(until)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(|(currency, amount)| CreditBalance { currency, amount }).collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LockedInvoice {
+    id: Uuid,
+    client_id: Uuid,
+    invoice_number: String,
+    status: String,
+    total_amount: Decimal,
+    amount_paid: Decimal,
+    currency: String,
+}
+
+/// Serializes changes to one client's credit. False when there's no such client.
+async fn lock_client(tx: &mut Transaction<'_, Postgres>, user_id: Uuid, client_id: Uuid) -> Result<bool, sqlx::Error> {
+    let locked: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM clients WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL FOR UPDATE",
+    )
+    .bind(client_id)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await?;
+
+    Ok(locked.is_some())
+}
+
+async fn available_credit(
+    tx: &mut Transaction<'_, Postgres>,
+    client_id: Uuid,
+    currency: &str,
+) -> Result<Decimal, sqlx::Error> {
+    sqlx::query_scalar("SELECT COALESCE(SUM(amount), 0) FROM client_credit_entries WHERE client_id = $1 AND currency = $2")
+        .bind(client_id)
+        .bind(currency)
+        .fetch_one(&mut **tx)
+        .await
+}
+
+async fn insert_entry(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    client_id: Uuid,
+    invoice_id: Option<Uuid>,
+    new: &NewClientCredit<'_>,
+) -> Result<Uuid, sqlx::Error> {
+    let id = Uuid::new_v4();
+    sqlx::query(
+        r#"
+        INSERT INTO client_credit_entries (
+            id, user_id, client_id, invoice_id, kind, amount, currency, description, entry_date
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        "#,
+    )
+    .bind(id)
+    .bind(user_id)
+    .bind(client_id)
+    .bind(invoice_id)
+    .bind(new.kind.as_str())
+    .bind(new.amount)
+    .bind(new.currency)
+    .bind(new.description)
+    .bind(new.entry_date)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(id)
+}
+
+async fn fetch(tx: &mut Transaction<'_, Postgres>, id: Uuid) -> Result<Option<ClientCreditEntry>, sqlx::Error> {
+    let row = sqlx::query_as::<_, ClientCreditRow>(&format!("{} WHERE e.id = $1", ENTRY_SELECT))
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+    Ok(row.and_then(ClientCreditRow::into_entry))
+}
+
+#[derive(sqlx::FromRow)]
+struct ClientCreditRow {
+    id: Uuid,
+    client_id: Uuid,
+    kind: String,
+    amount: Decimal,
+    currency: String,
+    invoice_id: Option<Uuid>,
+    invoice_number: Option<String>,
+    description: Option<String>,
+    entry_date: NaiveDate,
+    created_at: DateTime<Utc>,
+}
+
+impl ClientCreditRow {
+    fn into_entry(self) -> Option<ClientCreditEntry> {
+        Some(ClientCreditEntry {
+            id: self.id,
+            client_id: self.client_id,
+            kind: ClientCreditKind::parse(&self.kind)?,
+            amount: self.amount,
+            currency: self.currency,
+            invoice_id: self.invoice_id,
+            invoice_number: self.invoice_number,
+            description: self.description,
+            entry_date: self.entry_date,
+            created_at: self.created_at,
+        })
+    }
+}
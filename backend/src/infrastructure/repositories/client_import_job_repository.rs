@@ -0,0 +1,291 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    ClientCsvRow, ClientImportJob, ClientImportJobStatus, ImportRowIssue, ParsedClientCsv,
+};
+
+/// A job picked up by the worker, with the rows it still has to create
+pub struct ClaimedClientImportJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub rows: Vec<ClientCsvRow>,
+}
+
+/// What a batch of rows did
+#[derive(Debug, Default)]
+pub struct ClientImportBatch {
+    pub created: usize,
+    /// Rows whose email already belonged to a client
+    pub duplicates: Vec<ImportRowIssue>,
+}
+
+#[derive(Clone)]
+pub struct ClientImportJobRepository {
+    db: PgPool,
+}
+
+impl ClientImportJobRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Lower-cased emails of the user's live clients
+    pub async fn existing_emails(&self, user_id: Uuid) -> Result<HashSet<String>, sqlx::Error> {
+        let emails: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT LOWER(email) FROM clients WHERE user_id = $1 AND email IS NOT NULL AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(emails.into_iter().collect())
+    }
+
+    /// Create the clients in `rows` in one transaction, skipping any whose email
+    /// a live client already has. With a job, its progress moves on in the
+    /// same transaction, so a restarted job carries on where it stopped.
+    pub async fn import_batch(
+        &self,
+        user_id: Uuid,
+        rows: &[ClientCsvRow],
+        job_id: Option<Uuid>,
+        now: DateTime<Utc>,
+    ) -> Result<ClientImportBatch, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        // One import per user at a time, so two can't both create the same email
+        sqlx::query("SELECT id FROM users WHERE id = $1 FOR UPDATE")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut batch = ClientImportBatch::default();
+        for row in rows {
+            if insert_client(&mut tx, user_id, row, now).await? {
+                batch.created += 1;
+            } else {
+                batch.duplicates.push(ImportRowIssue::duplicate_email(row.row));
+            }
+        }
+
+        if let Some(job_id) = job_id {
+            sqlx::query(
+                r#"
+                UPDATE client_import_jobs SET
+                    processed_rows = processed_rows + $2,
+                    created_count = created_count + $3,
+                    skipped_count = skipped_count + $4,
+                    duplicates = duplicates || $5,
+                    updated_at = $6
+                WHERE id = $1
+                "#,
+            )
+            .bind(job_id)
+            .bind(rows.len() as i32)
+            .bind(batch.created as i32)
+            .bind(batch.duplicates.len() as i32)
+            .bind(serde_json::to_value(&batch.duplicates).unwrap_or_default())
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(batch)
+    }
+
+    /// Queue the file's valid rows. Rows already known to be duplicates or
+    /// invalid are recorded on the job up front.
+    pub async fn create_job(
+        &self,
+        user_id: Uuid,
+        file_name: &str,
+        parsed: &ParsedClientCsv,
+        rows: &[ClientCsvRow],
+        duplicates: &[ImportRowIssue],
+    ) -> Result<ClientImportJob, sqlx::Error> {
+        let row = sqlx::query_as::<_, JobRow>(
+            r#"
+            INSERT INTO client_import_jobs (
+                id, user_id, file_name, rows, total_rows, skipped_count, failed_count, errors, duplicates
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, file_name, status, total_rows, processed_rows, created_count, skipped_count,
+                failed_count, errors, duplicates, error, created_at, started_at, finished_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(file_name)
+        .bind(serde_json::to_value(rows).unwrap_or_default())
+        .bind(rows.len() as i32)
+        .bind(duplicates.len() as i32)
+        .bind(parsed.errors.len() as i32)
+        .bind(serde_json::to_value(&parsed.errors).unwrap_or_default())
+        .bind(serde_json::to_value(duplicates).unwrap_or_default())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_job())
+    }
+
+    pub async fn get_job(&self, user_id: Uuid, id: Uuid) -> Result<Option<ClientImportJob>, sqlx::Error> {
+        let row = sqlx::query_as::<_, JobRow>(
+            r#"
+            SELECT id, file_name, status, total_rows, processed_rows, created_count, skipped_count,
+                failed_count, errors, duplicates, error, created_at, started_at, finished_at
+            FROM client_import_jobs
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(JobRow::into_job))
+    }
+
+    /// The oldest queued job, or a running one that hasn't made progress since
+    /// `stale_before` (its worker stopped), with the rows left to create
+    pub async fn claim_job(
+        &self,
+        now: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<Option<ClaimedClientImportJob>, sqlx::Error> {
+        let row: Option<(Uuid, Uuid, Option<serde_json::Value>, i32)> = sqlx::query_as(
+            r#"
+            UPDATE client_import_jobs SET
+                status = 'running',
+                started_at = COALESCE(started_at, $1),
+                updated_at = $1
+            WHERE id = (
+                SELECT id FROM client_import_jobs
+                WHERE status = 'queued' OR (status = 'running' AND updated_at < $2)
+                ORDER BY created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, user_id, rows, processed_rows
+            "#,
+        )
+        .bind(now)
+        .bind(stale_before)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(|(id, user_id, rows, processed)| {
+            let rows: Vec<ClientCsvRow> = rows
+                .and_then(|rows| serde_json::from_value(rows).ok())
+                .unwrap_or_default();
+            ClaimedClientImportJob {
+                id,
+                user_id,
+                rows: rows.into_iter().skip(processed.max(0) as usize).collect(),
+            }
+        }))
+    }
+
+    /// Mark the job completed or failed and drop its stored rows
+    pub async fn finish_job(
+        &self,
+        id: Uuid,
+        status: ClientImportJobStatus,
+        error: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE client_import_jobs SET status = $2, error = $3, rows = NULL, finished_at = $4, updated_at = $4
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(status.as_str())
+        .bind(error)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// Insert the row's client unless a live client already has its email
+async fn insert_client(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    row: &ClientCsvRow,
+    now: DateTime<Utc>,
+) -> Result<bool, sqlx::Error> {
+    let client = &row.client;
+    let result = sqlx::query(
+        r#"
+        INSERT INTO clients (
+            id, user_id, name, email, phone, company_name,
+            billing_address, payment_terms, tax_exempt, notes,
+            created_at, updated_at, billing_contacts
+        )
+        SELECT $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11, '[]'::jsonb
+        WHERE $4::text IS NULL OR NOT EXISTS (
+            SELECT 1 FROM clients
+            WHERE user_id = $2 AND LOWER(email) = LOWER($4) AND deleted_at IS NULL
+        )
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(user_id)
+    .bind(&client.name)
+    .bind(&client.email)
+    .bind(&client.phone)
+    .bind(&client.company_name)
+    .bind(&client.billing_address)
+    .bind(client.payment_terms.unwrap_or(30))
+    .bind(client.tax_exempt.unwrap_or(false))
+    .bind(&client.notes)
+    .bind(now)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    id: Uuid,
+    file_name: String,
+    status: String,
+    total_rows: i32,
+    processed_rows: i32,
+    created_count: i32,
+    skipped_count: i32,
+    failed_count: i32,
+    errors: serde_json::Value,
+    duplicates: serde_json::Value,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    finished_at: Option<DateTime<Utc>>,
+}
+
+impl JobRow {
+    fn into_job(self) -> ClientImportJob {
+        ClientImportJob {
+            id: self.id,
+            file_name: self.file_name,
+            status: ClientImportJobStatus::parse(&self.status).unwrap_or(ClientImportJobStatus::Failed),
+            total_rows: self.total_rows,
+            processed_rows: self.processed_rows,
+            created: self.created_count,
+            skipped: self.skipped_count,
+            failed: self.failed_count,
+            errors: serde_json::from_value(self.errors).unwrap_or_default(),
+            duplicates: serde_json::from_value(self.duplicates).unwrap_or_default(),
+            error: self.error,
+            created_at: self.created_at,
+            started_at: self.started_at,
+            finished_at: self.finished_at,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    ClientImport, ClientImportSource, ClientImportStatus, ContactAddress, ParsedContact,
+};
+
+#[derive(Clone)]
+pub struct ClientImportRepository {
+    db: PgPool,
+}
+
+impl ClientImportRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_token(&self, user_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT token FROM inbound_addresses WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Replaces any previous token, so the old address stops working
+    pub async fn set_token(&self, user_id: Uuid, token: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO inbound_addresses (user_id, token, created_at) VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id) DO UPDATE SET token = EXCLUDED.token, created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(token)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find_user_by_token(&self, token: &str) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT user_id FROM inbound_addresses WHERE token = $1")
+            .bind(token)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// A client (not in the trash) with this email address
+    pub async fn find_client_by_email(&self, user_id: Uuid, email: &str) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT id FROM clients
+            WHERE user_id = $1 AND LOWER(email) = LOWER($2) AND deleted_at IS NULL
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(email)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        source: ClientImportSource,
+        sender: &str,
+        subject: Option<&str>,
+        contact: &ParsedContact,
+        client_id: Option<Uuid>,
+    ) -> Result<ClientImport, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientImportRow>(
+            r#"
+            INSERT INTO client_imports (
+                id, user_id, source, sender, subject, name, email, phone, company_name,
+                billing_address, client_id, status, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'pending', $12)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(source.as_str())
+        .bind(sender)
+        .bind(subject)
+        .bind(&contact.name)
+        .bind(&contact.email)
+        .bind(&contact.phone)
+        .bind(&contact.company_name)
+        .bind(contact.billing_address.as_ref().and_then(|a| serde_json::to_value(a).ok()))
+        .bind(client_id)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_import())
+    }
+
+    pub async fn find(&self, user_id: Uuid, id: Uuid) -> Result<Option<ClientImport>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientImportRow>("SELECT * FROM client_imports WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(ClientImportRow::into_import))
+    }
+
+    pub async fn list(&self, user_id: Uuid, include_resolved: bool) -> Result<Vec<ClientImport>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ClientImportRow>(
+            r#"
+            SELECT * FROM client_imports
+            WHERE user_id = $1 AND ($2 OR status = 'pending')
+            ORDER BY created_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(user_id)
+        .bind(include_resolved)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(ClientImportRow::into_import).collect())
+    }
+
+    /// Moves a pending import to `status`; None if it was already resolved
+    pub async fn resolve(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        status: ClientImportStatus,
+        client_id: Option<Uuid>,
+    ) -> Result<Option<ClientImport>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ClientImportRow>(
+            r#"
+            UPDATE client_imports
+            SET status = $3, client_id = COALESCE($4, client_id), resolved_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(status.as_str())
+        .bind(client_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(ClientImportRow::into_import))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ClientImportRow {
+    id: Uuid,
+    user_id: Uuid,
+    source: String,
+    sender: String,
+    subject: Option<String>,
+    name: String,
+    email: Option<String>,
+    phone: Option<String>,
+    company_name: Option<String>,
+    billing_address: Option<serde_json::Value>,
+    client_id: Option<Uuid>,
+    status: String,
+    created_at: DateTime<Utc>,
+    resolved_at: Option<DateTime<Utc>>,
+}
+
+impl ClientImportRow {
+    fn into_import(self) -> ClientImport {
+        ClientImport {
+            id: self.id,
+            user_id: self.user_id,
+            source: ClientImportSource::parse(&self.source).unwrap_or(ClientImportSource::ForwardedEmail),
+            sender: self.sender,
+            subject: self.subject,
+            contact: ParsedContact {
+                name: self.name,
+                email: self.email,
+                phone: self.phone,
+                company_name: self.company_name,
+                billing_address: self
+                    .billing_address
+                    .and_then(|value| serde_json::from_value::<ContactAddress>(value).ok()),
+            },
+            client_id: self.client_id,
+            status: ClientImportStatus::parse(&self.status).unwrap_or(ClientImportStatus::Pending),
+            created_at: self.created_at,
+            resolved_at: self.resolved_at,
+        }
+    }
+}
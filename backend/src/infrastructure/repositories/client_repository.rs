@@ -1,8 +1,14 @@
-use sqlx::{PgPool, QueryBuilder, Postgres, Row};
+use sqlx::{PgConnection, PgPool, QueryBuilder, Postgres, Row};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 
-use crate::domain::models::{Client, ClientResponse, ClientStats};
+use crate::domain::i18n::Locale;
+use crate::domain::models::{
+    BillingContact, Client, ClientBalance, ClientResponse, ClientStats, InvoiceResponse, Page, PageCursor, PageRequest,
+    StatementEntry, StatementEntryKind, VatCheck, VatStatus,
+};
+use super::pagination::{push_page_after, push_page_window};
 
 #[derive(Clone)]
 pub struct ClientRepository {
@@ -25,14 +31,21 @@ impl ClientRepository {
         payment_terms: Option<i32>,
         tax_exempt: Option<bool>,
         notes: Option<String>,
+        billing_contacts: &[BillingContact],
+        locale: Option<Locale>,
+        default_tax_id: Option<Uuid>,
+        default_currency: Option<String>,
+        default_discount_percent: Option<Decimal>,
+        hourly_rate: Option<Decimal>,
     ) -> Result<Client, sqlx::Error> {
         let client = sqlx::query_as::<_, ClientRow>(
             r#"
             INSERT INTO clients (
                 id, user_id, name, email, phone, company_name,
                 billing_address, payment_terms, tax_exempt, notes,
-                created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                created_at, updated_at, billing_contacts, locale,
+                default_tax_id, default_currency, default_discount_percent, hourly_rate
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
             RETURNING *
             "#,
         )
@@ -48,6 +61,40 @@ impl ClientRepository {
         .bind(&notes)
         .bind(Utc::now())
         .bind(Utc::now())
+        .bind(serde_json::to_value(billing_contacts).unwrap_or_default())
+        .bind(locale.map(Locale::code))
+        .bind(default_tax_id)
+        .bind(&default_currency)
+        .bind(default_discount_percent.filter(|d| !d.is_zero()))
+        .bind(hourly_rate)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(client.to_client())
+    }
+
+    /// Records the client's VAT number and what VIES said about it; None removes it
+    pub async fn set_vat(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        vat: Option<(&str, &VatCheck)>,
+    ) -> Result<Client, sqlx::Error> {
+        let client = sqlx::query_as::<_, ClientRow>(
+            r#"
+            UPDATE clients
+            SET vat_number = $3, vat_status = $4, vat_registered_name = $5,
+                vat_checked_at = CASE WHEN $3::text IS NULL THEN NULL ELSE NOW() END,
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .bind(vat.map(|(vat_number, _)| vat_number))
+        .bind(vat.map(|(_, check)| check.status.as_str()))
+        .bind(vat.and_then(|(_, check)| check.registered_name.as_deref()))
         .fetch_one(&self.db)
         .await?;
 
@@ -66,55 +113,70 @@ impl ClientRepository {
         Ok(client.map(|c| c.to_client()))
     }
 
+    /// One page of the user's clients, newest first
     pub async fn list(
         &self,
         user_id: Uuid,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ClientResponse>, sqlx::Error> {
+        parent_client_id: Option<Uuid>,
+        include_archived: bool,
+        page: &PageRequest,
+    ) -> Result<Page<ClientResponse>, sqlx::Error> {
+        let mut count_builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM clients c WHERE c.user_id = ");
+        count_builder.push_bind(user_id);
+        push_list_filters(&mut count_builder, search.as_deref(), parent_client_id, include_archived);
+        let total: i64 = count_builder.build_query_scalar().fetch_one(&self.db).await?;
+
         let mut query_builder = QueryBuilder::<Postgres>::new(
             r#"
             SELECT
                 c.*,
-                COALESCE(SUM(i.total_amount)::float8, 0.0::float8) as total_invoiced,
-                COALESCE(SUM(i.amount_paid)::float8, 0.0::float8) as total_paid,
-                (COALESCE(SUM(i.total_amount)::float8, 0.0::float8) - COALESCE(SUM(i.amount_paid)::float8, 0.0::float8)) as outstanding_balance,
+                COALESCE(SUM(i.total_amount - i.credited_amount), 0) as total_invoiced,
+                COALESCE(SUM(i.amount_paid - i.credited_amount), 0) as total_paid,
+                (COALESCE(SUM(i.total_amount - i.credited_amount), 0) - COALESCE(SUM(i.amount_paid - i.credited_amount), 0)) as outstanding_balance,
                 0 as average_payment_days,
-                MAX(i.issue_date) as last_invoice_date
+                MAX(i.issue_date)::timestamptz as last_invoice_date
             FROM clients c
-            LEFT JOIN invoices i ON c.id = i.client_id
+            LEFT JOIN invoices i ON c.id = i.client_id AND i.deleted_at IS NULL
             WHERE c.user_id = "#,
         );
 
         query_builder.push_bind(user_id);
+        push_list_filters(&mut query_builder, search.as_deref(), parent_client_id, include_archived);
+        push_page_after(&mut query_builder, page, "c.created_at", "c.id");
 
-        if let Some(s) = search {
-            query_builder.push(" AND (c.name ILIKE ");
-            query_builder.push_bind(format!("%{}%", s));
-            query_builder.push(" OR c.email ILIKE ");
-            query_builder.push_bind(format!("%{}%", s));
-            query_builder.push(")");
-        }
-
-        query_builder.push(" GROUP BY c.id ORDER BY c.created_at DESC");
+        query_builder.push(" GROUP BY c.id");
+        push_page_window(&mut query_builder, page, "c.created_at", "c.id");
 
-        if let Some(l) = limit {
-            query_builder.push(" LIMIT ");
-            query_builder.push_bind(l);
-        }
-
-        if let Some(o) = offset {
-            query_builder.push(" OFFSET ");
-            query_builder.push_bind(o);
-        }
-
-        let clients = query_builder
+        let clients: Vec<ClientResponse> = query_builder
             .build_query_as()
             .fetch_all(&self.db)
             .await?;
 
-        Ok(clients)
+        Ok(Page::new(page, clients, total, |client| PageCursor::new(client.created_at, client.id)))
+    }
+
+    /// Clients with the given IDs, archived ones included; deleted clients are skipped
+    pub async fn find_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<ClientResponse>, sqlx::Error> {
+        sqlx::query_as::<_, ClientResponse>(
+            r#"
+            SELECT
+                c.*,
+                COALESCE(SUM(i.total_amount - i.credited_amount), 0) as total_invoiced,
+                COALESCE(SUM(i.amount_paid - i.credited_amount), 0) as total_paid,
+                (COALESCE(SUM(i.total_amount - i.credited_amount), 0) - COALESCE(SUM(i.amount_paid - i.credited_amount), 0)) as outstanding_balance,
+                0 as average_payment_days,
+                MAX(i.issue_date)::timestamptz as last_invoice_date
+            FROM clients c
+            LEFT JOIN invoices i ON c.id = i.client_id AND i.deleted_at IS NULL
+            WHERE c.user_id = $1 AND c.id = ANY($2) AND c.deleted_at IS NULL
+            GROUP BY c.id
+            "#,
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await
     }
 
     pub async fn update(
@@ -129,6 +191,13 @@ impl ClientRepository {
         payment_terms: Option<i32>,
         tax_exempt: Option<bool>,
         notes: Option<String>,
+        statement_opt_out: Option<bool>,
+        billing_contacts: Option<Vec<BillingContact>>,
+        locale: Option<Locale>,
+        default_tax_id: Option<Uuid>,
+        default_currency: Option<String>,
+        default_discount_percent: Option<Decimal>,
+        hourly_rate: Option<Decimal>,
     ) -> Result<Client, sqlx::Error> {
         let mut query_builder = QueryBuilder::<Postgres>::new(
             "UPDATE clients SET updated_at = "
@@ -175,6 +244,42 @@ impl ClientRepository {
             query_builder.push_bind(notes);
         }
 
+        if let Some(statement_opt_out) = statement_opt_out {
+            query_builder.push(", statement_opt_out = ");
+            query_builder.push_bind(statement_opt_out);
+        }
+
+        if let Some(ref billing_contacts) = billing_contacts {
+            query_builder.push(", billing_contacts = ");
+            query_builder.push_bind(serde_json::to_value(billing_contacts).unwrap_or_default());
+        }
+
+        if let Some(locale) = locale {
+            query_builder.push(", locale = ");
+            query_builder.push_bind(locale.code());
+        }
+
+        if let Some(default_tax_id) = default_tax_id {
+            query_builder.push(", default_tax_id = ");
+            query_builder.push_bind(default_tax_id);
+        }
+
+        if let Some(ref default_currency) = default_currency {
+            query_builder.push(", default_currency = ");
+            query_builder.push_bind(default_currency);
+        }
+
+        // Zero removes the discount
+        if let Some(default_discount_percent) = default_discount_percent {
+            query_builder.push(", default_discount_percent = ");
+            query_builder.push_bind(Some(default_discount_percent).filter(|d| !d.is_zero()));
+        }
+
+        if let Some(hourly_rate) = hourly_rate {
+            query_builder.push(", hourly_rate = ");
+            query_builder.push_bind(hourly_rate);
+        }
+
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(client_id);
         query_builder.push(" AND user_id = ");
@@ -189,35 +294,176 @@ impl ClientRepository {
         Ok(client.to_client())
     }
 
-    pub async fn delete(&self, user_id: Uuid, client_id: Uuid) -> Result<(), sqlx::Error> {
-        let result = sqlx::query(
-            "DELETE FROM clients WHERE id = $1 AND user_id = $2"
+    /// Invoices still awaiting payment; while any exist the client can't be deleted
+    pub async fn count_active_invoices(&self, user_id: Uuid, client_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM invoices
+            WHERE user_id = $1 AND client_id = $2
+              AND status NOT IN ('draft', 'paid', 'cancelled', 'superseded', 'expired')
+            "#,
         )
+        .bind(user_id)
+        .bind(client_id)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    pub async fn count_subsidiaries(&self, user_id: Uuid, client_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM clients WHERE user_id = $1 AND parent_client_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    pub async fn set_archived(&self, user_id: Uuid, client_id: Uuid, archived: bool) -> Result<Client, sqlx::Error> {
+        let now = Utc::now();
+        let client = sqlx::query_as::<_, ClientRow>(
+            r#"
+            UPDATE clients
+            SET archived_at = CASE WHEN $1 THEN COALESCE(archived_at, $2) END, updated_at = $2
+            WHERE id = $3 AND user_id = $4 AND deleted_at IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(archived)
+        .bind(now)
         .bind(client_id)
         .bind(user_id)
-        .execute(&self.db)
+        .fetch_one(&self.db)
         .await?;
 
-        if result.rows_affected() == 0 {
+        Ok(client.to_client())
+    }
+
+    /// Move the client to the trash. Its draft invoices are cancelled and marked with the
+    /// same timestamp so a restore can reopen them; issued invoices and payments stay as they are.
+    pub async fn soft_delete(&self, user_id: Uuid, client_id: Uuid) -> Result<(), sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        if !Self::trash(&mut tx, user_id, client_id, Utc::now()).await? {
             return Err(sqlx::Error::RowNotFound);
         }
 
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Trash a client inside a caller's transaction; false when it isn't live
+    pub async fn trash(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        client_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE clients SET deleted_at = $1, updated_at = $1 WHERE id = $2 AND user_id = $3 AND deleted_at IS NULL"
+        )
+        .bind(now)
+        .bind(client_id)
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE invoices SET status = 'cancelled', trashed_at = $1, updated_at = $1
+            WHERE user_id = $2 AND client_id = $3 AND status = 'draft' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(now)
+        .bind(user_id)
+        .bind(client_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(true)
+    }
+
+    /// Take the client out of the trash and reopen the drafts cancelled with it
+    pub async fn restore(&self, user_id: Uuid, client_id: Uuid) -> Result<Client, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let deleted_at: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT deleted_at FROM clients WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL FOR UPDATE"
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let deleted_at = deleted_at.ok_or(sqlx::Error::RowNotFound)?;
+
+        sqlx::query(
+            r#"
+            UPDATE invoices SET status = 'draft', trashed_at = NULL, updated_at = NOW()
+            WHERE user_id = $1 AND client_id = $2 AND status = 'cancelled' AND trashed_at = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .bind(deleted_at)
+        .execute(&mut *tx)
+        .await?;
+
+        let client = sqlx::query_as::<_, ClientRow>(
+            "UPDATE clients SET deleted_at = NULL, updated_at = NOW() WHERE id = $1 AND user_id = $2 RETURNING *"
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(client.to_client())
+    }
+
+    /// Clients that can be invoiced: not archived or deleted
+    pub async fn list_active(&self, user_id: Uuid) -> Result<Vec<Client>, sqlx::Error> {
+        let clients = sqlx::query_as::<_, ClientRow>(
+            "SELECT * FROM clients WHERE user_id = $1 AND deleted_at IS NULL AND archived_at IS NULL ORDER BY created_at"
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(clients.into_iter().map(|c| c.to_client()).collect())
+    }
+
+    pub async fn list_deleted(&self, user_id: Uuid) -> Result<Vec<Client>, sqlx::Error> {
+        let clients = sqlx::query_as::<_, ClientRow>(
+            "SELECT * FROM clients WHERE user_id = $1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC"
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(clients.into_iter().map(|c| c.to_client()).collect())
+    }
+
     pub async fn get_stats(&self, user_id: Uuid) -> Result<ClientStats, sqlx::Error> {
         let row = sqlx::query(
             r#"
             SELECT
                 COUNT(DISTINCT c.id) as total_clients,
-                COUNT(DISTINCT CASE WHEN i.status != 'cancelled' THEN c.id END) as active_clients,
-                COALESCE(SUM(i.total_amount)::float8, 0.0::float8) as total_invoiced,
-                COALESCE(SUM(i.amount_paid)::float8, 0.0::float8) as total_paid,
-                (COALESCE(SUM(i.total_amount)::float8, 0.0::float8) - COALESCE(SUM(i.amount_paid)::float8, 0.0::float8)) as outstanding_balance,
-                0.0::float8 as avg_payment_days
+                COUNT(DISTINCT CASE WHEN i.status NOT IN ('cancelled', 'superseded', 'expired') THEN c.id END) as active_clients,
+                COALESCE(SUM(i.total_amount - i.credited_amount), 0) as total_invoiced,
+                COALESCE(SUM(i.amount_paid - i.credited_amount), 0) as total_paid,
+                (COALESCE(SUM(i.total_amount - i.credited_amount), 0) - COALESCE(SUM(i.amount_paid - i.credited_amount), 0)) as outstanding_balance,
+                0.0::float8 as avg_payment_days,
+                (SELECT COALESCE(SUM(e.amount), 0)
+                 FROM client_credit_entries e
+                 JOIN clients cc ON cc.id = e.client_id
+                 WHERE e.user_id = $1 AND cc.deleted_at IS NULL) as credit_balance
             FROM clients c
-            LEFT JOIN invoices i ON c.id = i.client_id
-            WHERE c.user_id = $1
+            LEFT JOIN invoices i ON c.id = i.client_id AND i.deleted_at IS NULL
+            WHERE c.user_id = $1 AND c.deleted_at IS NULL
             "#,
         )
         .bind(user_id)
@@ -226,10 +472,11 @@ impl ClientRepository {
 
         let total_clients: i64 = row.get("total_clients");
         let active_clients: i64 = row.get("active_clients");
-        let total_invoiced: f64 = row.get("total_invoiced");
-        let total_paid: f64 = row.get("total_paid");
-        let outstanding_balance: f64 = row.get("outstanding_balance");
+        let total_invoiced: Decimal = row.get("total_invoiced");
+        let total_paid: Decimal = row.get("total_paid");
+        let outstanding_balance: Decimal = row.get("outstanding_balance");
         let avg_payment_days: f64 = row.get("avg_payment_days");
+        let credit_balance: Decimal = row.get("credit_balance");
 
         Ok(ClientStats {
             total_clients,
@@ -238,6 +485,7 @@ impl ClientRepository {
             total_paid,
             outstanding_balance,
             avg_payment_days,
+            credit_balance,
         })
     }
 
@@ -254,10 +502,12 @@ impl ClientRepository {
                 (i.total_amount - i.amount_paid) as balance_due,
                 i.created_at,
                 0 as days_until_due,
-                (i.due_date < CURRENT_DATE AND i.status != 'paid' AND i.status != 'cancelled') as is_overdue
+                (i.due_date < CURRENT_DATE AND i.status NOT IN ('paid', 'cancelled', 'superseded', 'expired')) as is_overdue,
+                l.name as label
             FROM invoices i
             JOIN clients c ON i.client_id = c.id
-            WHERE i.user_id = $1 AND i.client_id = $2
+            LEFT JOIN invoice_labels l ON l.id = i.label_id AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
+            WHERE i.user_id = $1 AND i.client_id = $2 AND i.deleted_at IS NULL
             ORDER BY i.created_at DESC
             "#,
         )
@@ -268,6 +518,205 @@ impl ClientRepository {
 
         Ok(invoices)
     }
+
+    pub async fn set_parent(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        parent_client_id: Option<Uuid>,
+    ) -> Result<Client, sqlx::Error> {
+        let client = sqlx::query_as::<_, ClientRow>(
+            r#"
+            UPDATE clients
+            SET parent_client_id = $1, updated_at = $2
+            WHERE id = $3 AND user_id = $4
+            RETURNING *
+            "#,
+        )
+        .bind(parent_client_id)
+        .bind(Utc::now())
+        .bind(client_id)
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(client.to_client())
+    }
+
+    /// IDs of the client and all of its subsidiaries (any depth)
+    pub async fn get_hierarchy_ids(&self, user_id: Uuid, client_id: Uuid) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE hierarchy AS (
+                SELECT id FROM clients WHERE id = $1 AND user_id = $2
+                UNION
+                SELECT c.id FROM clients c
+                JOIN hierarchy h ON c.parent_client_id = h.id
+                WHERE c.user_id = $2 AND c.deleted_at IS NULL
+            )
+            SELECT id FROM hierarchy
+            "#,
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    pub async fn get_balances(&self, user_id: Uuid, client_ids: &[Uuid]) -> Result<Vec<ClientBalance>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                c.id, c.name, c.parent_client_id,
+                COALESCE(SUM(i.total_amount - i.credited_amount), 0) as total_invoiced,
+                COALESCE(SUM(i.amount_paid - i.credited_amount), 0) as total_paid
+            FROM clients c
+            LEFT JOIN invoices i ON c.id = i.client_id AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+            WHERE c.user_id = $1 AND c.id = ANY($2)
+            GROUP BY c.id, c.name, c.parent_client_id
+            ORDER BY c.name
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let total_invoiced: Decimal = row.get("total_invoiced");
+                let total_paid: Decimal = row.get("total_paid");
+                ClientBalance {
+                    client_id: row.get("id"),
+                    name: row.get("name"),
+                    parent_client_id: row.get("parent_client_id"),
+                    total_invoiced,
+                    total_paid,
+                    outstanding_balance: total_invoiced - total_paid,
+                }
+            })
+            .collect())
+    }
+
+    /// Invoices, completed payments, applied credit notes and credit drawn from the
+    /// client's balance, dated on or before `until`, for its statement of account
+    pub async fn get_statement_entries(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        until: NaiveDate,
+    ) -> Result<Vec<StatementEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 'invoice' AS kind, i.issue_date AS date, i.invoice_number AS reference,
+                   'Invoice, due ' || i.due_date::text AS description,
+                   i.id AS invoice_id, i.total_amount::float8 AS debit, 0::float8 AS credit
+            FROM invoices i
+            WHERE i.user_id = $1 AND i.client_id = $2 AND i.issue_date <= $3
+              AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+              AND i.deleted_at IS NULL
+            UNION ALL
+            SELECT 'payment', COALESCE(p.received_on, p.created_at::date), i.invoice_number,
+                   'Payment received' || COALESCE(' (' || REPLACE(p.payment_method, '_', ' ') || ')', ''),
+                   i.id, 0::float8, p.amount::float8
+            FROM payments p
+            JOIN invoices i ON i.id = p.invoice_id
+            WHERE p.user_id = $1 AND i.client_id = $2 AND p.status = 'completed'
+              AND COALESCE(p.received_on, p.created_at::date) <= $3
+            UNION ALL
+            SELECT 'credit_note', n.issue_date, n.credit_note_number,
+                   'Credit against ' || i.invoice_number,
+                   i.id, 0::float8, n.applied_amount::float8
+            FROM credit_notes n
+            JOIN invoices i ON i.id = n.invoice_id
+            WHERE n.user_id = $1 AND n.client_id = $2 AND n.status = 'issued'
+              AND n.applied_amount > 0 AND n.issue_date <= $3
+            UNION ALL
+            SELECT 'client_credit', e.entry_date, i.invoice_number,
+                   'Credit applied from account balance',
+                   i.id, 0::float8, (-e.amount)::float8
+            FROM client_credit_entries e
+            JOIN invoices i ON i.id = e.invoice_id
+            WHERE e.user_id = $1 AND e.client_id = $2 AND e.kind = 'applied'
+              AND e.entry_date <= $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .bind(until)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(StatementEntry {
+                    kind: StatementEntryKind::parse(row.get("kind"))?,
+                    date: row.get("date"),
+                    reference: row.get("reference"),
+                    description: row.get("description"),
+                    invoice_id: row.get("invoice_id"),
+                    debit: row.get("debit"),
+                    credit: row.get("credit"),
+                })
+            })
+            .collect())
+    }
+
+    pub async fn get_invoices_for_clients(
+        &self,
+        user_id: Uuid,
+        client_ids: &[Uuid],
+    ) -> Result<Vec<InvoiceResponse>, sqlx::Error> {
+        sqlx::query_as::<_, InvoiceResponse>(
+            r#"
+            SELECT
+                i.id, i.invoice_number, i.status, c.name as client_name, c.email as client_email,
+                i.issue_date, i.due_date, i.total_amount,
+                (i.total_amount - i.amount_paid) as balance_due,
+                i.created_at,
+                (i.due_date - CURRENT_DATE)::int4 as days_until_due,
+                (i.due_date < CURRENT_DATE AND i.status NOT IN ('paid', 'cancelled', 'superseded', 'expired')) as is_overdue,
+                l.name as label
+            FROM invoices i
+            JOIN clients c ON i.client_id = c.id
+            LEFT JOIN invoice_labels l ON l.id = i.label_id AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
+            WHERE i.user_id = $1 AND i.client_id = ANY($2) AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+            ORDER BY i.issue_date, i.invoice_number
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_ids)
+        .fetch_all(&self.db)
+        .await
+    }
+}
+
+fn push_list_filters(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    search: Option<&str>,
+    parent_client_id: Option<Uuid>,
+    include_archived: bool,
+) {
+    query_builder.push(" AND c.deleted_at IS NULL");
+
+    if !include_archived {
+        query_builder.push(" AND c.archived_at IS NULL");
+    }
+
+    if let Some(s) = search {
+        query_builder.push(" AND (c.name ILIKE ");
+        query_builder.push_bind(format!("%{}%", s));
+        query_builder.push(" OR c.email ILIKE ");
+        query_builder.push_bind(format!("%{}%", s));
+        query_builder.push(")");
+    }
+
+    if let Some(parent_id) = parent_client_id {
+        query_builder.push(" AND c.parent_client_id = ");
+        query_builder.push_bind(parent_id);
+    }
 }
 
 #[derive(sqlx::FromRow)]
@@ -283,9 +732,23 @@ struct ClientRow {
     tax_exempt: bool,
     tax_exempt_certificate: Option<String>,
     notes: Option<String>,
-    total_invoiced: f64,
-    total_paid: f64,
+    total_invoiced: Decimal,
+    total_paid: Decimal,
     average_payment_days: Option<i32>,
+    parent_client_id: Option<Uuid>,
+    statement_opt_out: bool,
+    billing_contacts: serde_json::Value,
+    locale: Option<String>,
+    default_tax_id: Option<Uuid>,
+    default_currency: Option<String>,
+    default_discount_percent: Option<Decimal>,
+    hourly_rate: Option<Decimal>,
+    vat_number: Option<String>,
+    vat_status: Option<String>,
+    vat_registered_name: Option<String>,
+    vat_checked_at: Option<DateTime<Utc>>,
+    archived_at: Option<DateTime<Utc>>,
+    deleted_at: Option<DateTime<Utc>>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -307,6 +770,20 @@ impl ClientRow {
             total_invoiced: self.total_invoiced,
             total_paid: self.total_paid,
             average_payment_days: self.average_payment_days,
+            parent_client_id: self.parent_client_id,
+            statement_opt_out: self.statement_opt_out,
+            billing_contacts: serde_json::from_value(self.billing_contacts).unwrap_or_default(),
+            locale: self.locale.as_deref().and_then(Locale::parse),
+            default_tax_id: self.default_tax_id,
+            default_currency: self.default_currency,
+            default_discount_percent: self.default_discount_percent,
+            hourly_rate: self.hourly_rate,
+            vat_number: self.vat_number,
+            vat_status: self.vat_status.as_deref().and_then(VatStatus::parse),
+            vat_registered_name: self.vat_registered_name,
+            vat_checked_at: self.vat_checked_at,
+            archived_at: self.archived_at,
+            deleted_at: self.deleted_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
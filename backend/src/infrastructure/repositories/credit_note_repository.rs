@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    credit_applied_to_balance, creditable_amount, status_after_credit, CreditNote,
+    CreditNoteListFilter, CreditNoteStatus, DocumentType, InvoiceItem, InvoiceStatus,
+};
+use crate::domain::services::DocumentNumberService;
+
+const CREDIT_NOTE_SELECT: &str = r#"
+    SELECT
+        cn.id, cn.user_id, cn.invoice_id, i.invoice_number, cn.client_id, c.name as client_name,
+        cn.credit_note_number, cn.status, cn.reason, cn.issue_date, cn.items,
+        cn.subtotal, cn.tax_amount, cn.discount_amount, cn.total_amount,
+        cn.applied_amount, cn.currency, cn.voided_at,
+        cn.created_at, cn.updated_at
+    FROM credit_notes cn
+    JOIN invoices i ON i.id = cn.invoice_id
+    JOIN clients c ON c.id = cn.client_id
+"#;
+
+/// Credit note ready to be issued; amounts are already rounded
+pub struct NewCreditNote<'a> {
+    pub invoice_id: Uuid,
+    pub reason: Option<&'a str>,
+    pub issue_date: NaiveDate,
+    pub items: &'a [InvoiceItem],
+    pub subtotal: Decimal,
+    pub tax_amount: Decimal,
+    pub discount_amount: Decimal,
+    pub total_amount: Decimal,
+}
+
+/// Outcome of issuing a credit note against an invoice
+pub enum IssueCreditNote {
+    Issued(Box<CreditNote>),
+    /// The invoice can't take this much more credit; carries what's left
+    ExceedsInvoice(Decimal),
+}
+
+#[derive(Clone)]
+pub struct CreditNoteRepository {
+    db: PgPool,
+    document_numbers: Arc<DocumentNumberService>,
+}
+
+impl CreditNoteRepository {
+    pub fn new(db: PgPool, document_numbers: Arc<DocumentNumberService>) -> Self {
+        Self { db, document_numbers }
+    }
+
+    /// Issue the credit note and settle it against the invoice in one transaction.
+    /// The number is only taken once the credit fits, so rejected credits leave no gaps.
+    /// Returns None when the invoice doesn't exist.
+    pub async fn issue(
+        &self,
+        user_id: Uuid,
+        new: NewCreditNote<'_>,
+        today: NaiveDate,
+    ) -> Result<Option<IssueCreditNote>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let Some(invoice) = lock_invoice(&mut tx, user_id, new.invoice_id).await? else {
+            return Ok(None);
+        };
+
+        let remaining = creditable_amount(invoice.total_amount, invoice.credited_amount);
+        if new.total_amount > remaining {
+            return Ok(Some(IssueCreditNote::ExceedsInvoice(remaining)));
+        }
+
+        let credit_note_number = self
+            .document_numbers
+            .next_number_in(&mut tx, user_id, DocumentType::CreditNote)
+            .await?;
+        let applied = credit_applied_to_balance(invoice.total_amount - invoice.amount_paid, new.total_amount);
+        settle_invoice(
+            &mut tx,
+            &invoice,
+            invoice.amount_paid + applied,
+            invoice.credited_amount + new.total_amount,
+            today,
+        )
+        .await?;
+
+        let id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO credit_notes (
+                id, user_id, invoice_id, client_id, credit_note_number, status, reason,
+                issue_date, items, subtotal, tax_amount, discount_amount, total_amount,
+                applied_amount, currency
+            ) VALUES ($1, $2, $3, $4, $5, 'issued', $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(new.invoice_id)
+        .bind(invoice.client_id)
+        .bind(&credit_note_number)
+        .bind(new.reason)
+        .bind(new.issue_date)
+        .bind(serde_json::to_value(new.items).unwrap_or(serde_json::Value::Array(vec![])))
+        .bind(new.subtotal)
+        .bind(new.tax_amount)
+        .bind(new.discount_amount)
+        .bind(new.total_amount)
+        .bind(applied)
+        .bind(&invoice.currency)
+        .execute(&mut *tx)
+        .await?;
+
+        let credit_note = fetch(&mut tx, user_id, id).await?;
+        tx.commit().await?;
+
+        Ok(credit_note.map(|credit_note| IssueCreditNote::Issued(Box::new(credit_note))))
+    }
+
+    /// Void an issued credit note, taking its credit back off the invoice.
+    /// Returns None when there's no issued credit note with this ID.
+    pub async fn void(
+        &self,
+        user_id: Uuid,
+        credit_note_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<Option<CreditNote>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let claimed: Option<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE credit_notes SET status = 'void', voided_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'issued'
+            RETURNING invoice_id, total_amount, applied_amount
+            "#,
+        )
+        .bind(credit_note_id)
+        .bind(user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((invoice_id, total_amount, applied_amount)) = claimed else {
+            return Ok(None);
+        };
+
+        if let Some(invoice) = lock_invoice(&mut tx, user_id, invoice_id).await? {
+            settle_invoice(
+                &mut tx,
+                &invoice,
+                (invoice.amount_paid - applied_amount).max(Decimal::ZERO),
+                (invoice.credited_amount - total_amount).max(Decimal::ZERO),
+                today,
+            )
+            .await?;
+        }
+
+        let credit_note = fetch(&mut tx, user_id, credit_note_id).await?;
+        tx.commit().await?;
+
+        Ok(credit_note)
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, credit_note_id: Uuid) -> Result<Option<CreditNote>, sqlx::Error> {
+        let row = sqlx::query_as::<_, CreditNoteRow>(&format!("{} WHERE cn.id = $1 AND cn.user_id = $2", CREDIT_NOTE_SELECT))
+            .bind(credit_note_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(CreditNoteRow::into_credit_note))
+    }
+
+    pub async fn list(&self, user_id: Uuid, filter: &CreditNoteListFilter) -> Result<Vec<CreditNote>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Postgres>::new(CREDIT_NOTE_SELECT);
+        query_builder.push(" WHERE cn.user_id = ");
+        query_builder.push_bind(user_id);
+
+        if let Some(invoice_id) = filter.invoice_id {
+            query_builder.push(" AND cn.invoice_id = ");
+            query_builder.push_bind(invoice_id);
+        }
+
+        if let Some(client_id) = filter.client_id {
+            query_builder.push(" AND cn.client_id = ");
+            query_builder.push_bind(client_id);
+        }
+
+        query_builder.push(" ORDER BY cn.issue_date DESC, cn.created_at DESC");
+
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(filter.limit.unwrap_or(50));
+
+        if let Some(offset) = filter.offset {
+            query_builder.push(" OFFSET ");
+            query_builder.push_bind(offset);
+        }
+
+        let rows = query_builder
+            .build_query_as::<CreditNoteRow>()
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows.into_iter().map(CreditNoteRow::into_credit_note).collect())
+    }
+}
+
+/// Invoice balance fields, locked for the rest of the transaction
+#[derive(sqlx::FromRow)]
+struct LockedInvoice {
+    id: Uuid,
+    client_id: Uuid,
+    status: String,
+    due_date: NaiveDate,
+    total_amount: Decimal,
+    amount_paid: Decimal,
+    credited_amount: Decimal,
+    currency: String,
+}
+
+async fn lock_invoice(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    invoice_id: Uuid,
+) -> Result<Option<LockedInvoice>, sqlx::Error> {
+    sqlx::query_as::<_, LockedInvoice>(
+        r#"
+        SELECT id, client_id, status, due_date,
+            total_amount,
+            COALESCE(amount_paid, 0) as amount_paid,
+            credited_amount,
+            COALESCE(currency, 'USD') as currency
+        FROM invoices
+        WHERE id = $1 AND user_id = $2
+        FOR UPDATE
+        "#,
+    )
+    .bind(invoice_id)
+    .bind(user_id)
+    .fetch_optional(&mut **tx)
+    .await
+}
+
+async fn settle_invoice(
+    tx: &mut Transaction<'_, Postgres>,
+    invoice: &LockedInvoice,
+    amount_paid: Decimal,
+    credited_amount: Decimal,
+    today: NaiveDate,
+) -> Result<(), sqlx::Error> {
+    let current = match invoice.status.as_str() {
+        "sent" => InvoiceStatus::Sent,
+        "viewed" => InvoiceStatus::Viewed,
+        "overdue" => InvoiceStatus::Overdue,
+        "partial" => InvoiceStatus::Partial,
+        "paid" => InvoiceStatus::Paid,
+        _ => InvoiceStatus::Cancelled,
+    };
+    let status = status_after_credit(
+        current,
+        invoice.total_amount,
+        amount_paid,
+        credited_amount,
+        invoice.due_date < today,
+    );
+
+    sqlx::query(
+        r#"
+        UPDATE invoices SET
+            amount_paid = $1,
+            credited_amount = $2,
+            status = $3,
+            paid_at = CASE
+                WHEN $3 = 'paid' THEN COALESCE(paid_at, NOW())
+                WHEN $3 = 'cancelled' THEN paid_at
+                ELSE NULL
+            END,
+            updated_at = NOW()
+        WHERE id = $4
+        "#,
+    )
+    .bind(amount_paid)
+    .bind(credited_amount)
+    .bind(status.to_string())
+    .bind(invoice.id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+async fn fetch(
+    tx: &mut Transaction<'_, Postgres>,
+    user_id: Uuid,
+    credit_note_id: Uuid,
+) -> Result<Option<CreditNote>, sqlx::Error> {
+    let row = sqlx::query_as::<_, CreditNoteRow>(&format!("{} WHERE cn.id = $1 AND cn.user_id = $2", CREDIT_NOTE_SELECT))
+        .bind(credit_note_id)
+        .bind(user_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+    Ok(row.map(CreditNoteRow::into_credit_note))
+}
+
+#[derive(sqlx::FromRow)]
+struct CreditNoteRow {
+    id: Uuid,
+    user_id: Uuid,
+    invoice_id: Uuid,
+    invoice_number: String,
+    client_id: Uuid,
+    client_name: String,
+    credit_note_number: String,
+    status: String,
+    reason: Option<String>,
+    issue_date: NaiveDate,
+    items: serde_json::Value,
+    subtotal: Decimal,
+    tax_amount: Decimal,
+    discount_amount: Decimal,
+    total_amount: Decimal,
+    applied_amount: Decimal,
+    currency: String,
+    voided_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl CreditNoteRow {
+    fn into_credit_note(self) -> CreditNote {
+        let status = match self.status.as_str() {
+            "void" => CreditNoteStatus::Void,
+            _ => CreditNoteStatus::Issued,
+        };
+
+        CreditNote {
+            id: self.id,
+            user_id: self.user_id,
+            invoice_id: self.invoice_id,
+            invoice_number: self.invoice_number,
+            client_id: self.client_id,
+            client_name: self.client_name,
+            credit_note_number: self.credit_note_number,
+            status,
+            reason: self.reason,
+            issue_date: self.issue_date,
+            items: serde_json::from_value(self.items).unwrap_or_default(),
+            subtotal: self.subtotal,
+            tax_amount: self.tax_amount,
+            discount_amount: self.discount_amount,
+            refund_amount: self.total_amount - self.applied_amount,
+            total_amount: self.total_amount,
+            applied_amount: self.applied_amount,
+            currency: self.currency,
+            voided_at: self.voided_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
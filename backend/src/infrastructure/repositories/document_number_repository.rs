@@ -0,0 +1,160 @@
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::domain::models::DocumentType;
+
+#[derive(Clone)]
+pub struct DocumentNumberRepository {
+    db: PgPool,
+}
+
+impl DocumentNumberRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.db.begin().await
+    }
+
+    /// Advance the (user, doc_type, year) sequence inside the caller's transaction
+    /// and return the new value. The row stays locked until the transaction ends,
+    /// so concurrent documents are numbered one after another.
+    pub async fn next_value(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        doc_type: DocumentType,
+        year: i32,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_sequences (user_id, doc_type, year, last_value, updated_at)
+            VALUES ($1, $2, $3, 0, NOW())
+            ON CONFLICT (user_id, doc_type, year) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(year)
+        .execute(&mut **tx)
+        .await?;
+
+        let last_value: i64 = sqlx::query_scalar(
+            r#"
+            SELECT last_value FROM document_sequences
+            WHERE user_id = $1 AND doc_type = $2 AND year = $3
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(year)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE document_sequences SET last_value = $4, updated_at = NOW()
+            WHERE user_id = $1 AND doc_type = $2 AND year = $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(year)
+        .bind(last_value + 1)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(last_value + 1)
+    }
+
+    pub async fn last_value(&self, user_id: Uuid, doc_type: DocumentType, year: i32) -> Result<i64, sqlx::Error> {
+        let value: Option<i64> = sqlx::query_scalar(
+            "SELECT last_value FROM document_sequences WHERE user_id = $1 AND doc_type = $2 AND year = $3"
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(year)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(value.unwrap_or(0))
+    }
+
+    pub async fn set_last_value(
+        &self,
+        user_id: Uuid,
+        doc_type: DocumentType,
+        year: i32,
+        last_value: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_sequences (user_id, doc_type, year, last_value, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (user_id, doc_type, year)
+            DO UPDATE SET last_value = GREATEST(document_sequences.last_value, EXCLUDED.last_value), updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(year)
+        .bind(last_value)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Custom (prefix, padding, yearly_reset) for a document type, if configured
+    pub async fn get_format(&self, user_id: Uuid, doc_type: DocumentType) -> Result<Option<(String, i32, bool)>, sqlx::Error> {
+        let row: Option<DocumentNumberFormatRow> = sqlx::query_as(
+            "SELECT prefix, padding, yearly_reset FROM document_number_formats WHERE user_id = $1 AND doc_type = $2"
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(|r| (r.prefix, r.padding, r.yearly_reset)))
+    }
+
+    pub async fn upsert_format(
+        &self,
+        user_id: Uuid,
+        doc_type: DocumentType,
+        prefix: &str,
+        padding: i32,
+        yearly_reset: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_number_formats (user_id, doc_type, prefix, padding, yearly_reset, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW())
+            ON CONFLICT (user_id, doc_type)
+            DO UPDATE SET
+                prefix = EXCLUDED.prefix,
+                padding = EXCLUDED.padding,
+                yearly_reset = EXCLUDED.yearly_reset,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(doc_type.as_str())
+        .bind(prefix)
+        .bind(padding)
+        .bind(yearly_reset)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DocumentNumberFormatRow {
+    prefix: String,
+    padding: i32,
+    yearly_reset: bool,
+}
@@ -0,0 +1,117 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{EmailSignature, SignatureLink};
+
+/// Signatures are keyed by business account; `member_id` None is the business default
+#[derive(Clone)]
+pub struct EmailSignatureRepository {
+    db: PgPool,
+}
+
+impl EmailSignatureRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn find(&self, user_id: Uuid, member_id: Option<Uuid>) -> Result<Option<EmailSignature>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EmailSignatureRow>(
+            r#"
+            SELECT name, title, phone, links FROM email_signatures
+            WHERE user_id = $1 AND member_id IS NOT DISTINCT FROM $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(member_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EmailSignatureRow::into_signature))
+    }
+
+    /// The member's override if they have one, otherwise the business default
+    pub async fn resolve(&self, user_id: Uuid, member_id: Option<Uuid>) -> Result<Option<EmailSignature>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EmailSignatureRow>(
+            r#"
+            SELECT name, title, phone, links FROM email_signatures
+            WHERE user_id = $1 AND (member_id IS NULL OR member_id = $2)
+            ORDER BY member_id IS NULL
+            LIMIT 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(member_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EmailSignatureRow::into_signature))
+    }
+
+    pub async fn upsert(
+        &self,
+        user_id: Uuid,
+        member_id: Option<Uuid>,
+        signature: &EmailSignature,
+    ) -> Result<EmailSignature, sqlx::Error> {
+        let conflict = if member_id.is_some() {
+            "(user_id, member_id) WHERE member_id IS NOT NULL"
+        } else {
+            "(user_id) WHERE member_id IS NULL"
+        };
+        let row = sqlx::query_as::<_, EmailSignatureRow>(&format!(
+            r#"
+            INSERT INTO email_signatures (id, user_id, member_id, name, title, phone, links, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+            ON CONFLICT {}
+            DO UPDATE SET name = EXCLUDED.name, title = EXCLUDED.title, phone = EXCLUDED.phone,
+                links = EXCLUDED.links, updated_at = EXCLUDED.updated_at
+            RETURNING name, title, phone, links
+            "#,
+            conflict
+        ))
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(member_id)
+        .bind(&signature.name)
+        .bind(&signature.title)
+        .bind(&signature.phone)
+        .bind(serde_json::to_value(&signature.links).unwrap_or_default())
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_signature())
+    }
+
+    pub async fn delete(&self, user_id: Uuid, member_id: Option<Uuid>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM email_signatures WHERE user_id = $1 AND member_id IS NOT DISTINCT FROM $2",
+        )
+        .bind(user_id)
+        .bind(member_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EmailSignatureRow {
+    name: Option<String>,
+    title: Option<String>,
+    phone: Option<String>,
+    links: serde_json::Value,
+}
+
+impl EmailSignatureRow {
+    fn into_signature(self) -> EmailSignature {
+        EmailSignature {
+            name: self.name,
+            title: self.title,
+            phone: self.phone,
+            links: serde_json::from_value::<Vec<SignatureLink>>(self.links).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{EmailTemplate, EmailTemplateKind};
+
+/// Users' overrides of the built-in email templates, one per kind
+#[derive(Clone)]
+pub struct EmailTemplateRepository {
+    db: PgPool,
+}
+
+impl EmailTemplateRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<(EmailTemplateKind, EmailTemplate)>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, EmailTemplateRow>(
+            "SELECT kind, subject, body FROM email_templates WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        // Kinds this version doesn't know are skipped rather than failing the list
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((EmailTemplateKind::parse(&row.kind)?, row.into_template())))
+            .collect())
+    }
+
+    pub async fn find(&self, user_id: Uuid, kind: EmailTemplateKind) -> Result<Option<EmailTemplate>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EmailTemplateRow>(
+            "SELECT kind, subject, body FROM email_templates WHERE user_id = $1 AND kind = $2",
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EmailTemplateRow::into_template))
+    }
+
+    pub async fn upsert(
+        &self,
+        user_id: Uuid,
+        kind: EmailTemplateKind,
+        template: &EmailTemplate,
+    ) -> Result<EmailTemplate, sqlx::Error> {
+        let row = sqlx::query_as::<_, EmailTemplateRow>(
+            r#"
+            INSERT INTO email_templates (id, user_id, kind, subject, body, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $6)
+            ON CONFLICT (user_id, kind)
+            DO UPDATE SET subject = EXCLUDED.subject, body = EXCLUDED.body, updated_at = EXCLUDED.updated_at
+            RETURNING kind, subject, body
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(&template.subject)
+        .bind(&template.body)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_template())
+    }
+
+    pub async fn delete(&self, user_id: Uuid, kind: EmailTemplateKind) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM email_templates WHERE user_id = $1 AND kind = $2")
+            .bind(user_id)
+            .bind(kind.as_str())
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EmailTemplateRow {
+    kind: String,
+    subject: Option<String>,
+    body: Option<String>,
+}
+
+impl EmailTemplateRow {
+    fn into_template(self) -> EmailTemplate {
+        EmailTemplate { subject: self.subject, body: self.body }
+    }
+}
@@ -1,8 +1,10 @@
 use sqlx::{PgPool, QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 
-use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, ExpenseCategory};
+use crate::domain::models::{Expense, ExpenseResponse, ExpenseStats, ExpenseCategory, Page, PageCursor, PageRequest};
+use super::pagination::{push_page_after, push_page_window};
 
 #[derive(Clone)]
 pub struct ExpenseRepository {
@@ -17,7 +19,7 @@ impl ExpenseRepository {
     pub async fn create(
         &self,
         user_id: Uuid,
-        amount: f64,
+        amount: Decimal,
         currency: String,
         category: ExpenseCategory,
         vendor: Option<String>,
@@ -25,14 +27,16 @@ impl ExpenseRepository {
         receipt_image_url: Option<String>,
         date_incurred: NaiveDate,
         tax_deductible: Option<bool>,
+        tax_rate: Option<Decimal>,
+        tax_amount: Decimal,
     ) -> Result<Expense, sqlx::Error> {
         let expense = sqlx::query_as::<_, ExpenseRow>(
             r#"
             INSERT INTO expenses (
                 id, user_id, amount, currency, category, vendor,
                 description, receipt_image_url, date_incurred, tax_deductible,
-                created_at, updated_at
-            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                tax_rate, tax_amount, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING *
             "#,
         )
@@ -46,6 +50,8 @@ impl ExpenseRepository {
         .bind(&receipt_image_url)
         .bind(date_incurred)
         .bind(tax_deductible.unwrap_or(true))
+        .bind(tax_rate)
+        .bind(tax_amount)
         .bind(Utc::now())
         .bind(Utc::now())
         .fetch_one(&self.db)
@@ -66,6 +72,7 @@ impl ExpenseRepository {
         Ok(expense.map(|e| e.to_expense()))
     }
 
+    /// One page of the user's expenses, newest first
     pub async fn list(
         &self,
         user_id: Uuid,
@@ -74,9 +81,13 @@ impl ExpenseRepository {
         date_to: Option<NaiveDate>,
         tax_deductible: Option<bool>,
         search: Option<String>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<ExpenseResponse>, sqlx::Error> {
+        page: &PageRequest,
+    ) -> Result<Page<ExpenseResponse>, sqlx::Error> {
+        let mut count_builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM expenses WHERE user_id = ");
+        count_builder.push_bind(user_id);
+        push_list_filters(&mut count_builder, category.as_ref(), date_from, date_to, tax_deductible, search.as_deref());
+        let total: i64 = count_builder.build_query_scalar().fetch_one(&self.db).await?;
+
         let mut query_builder = QueryBuilder::<Postgres>::new(
             r#"
             SELECT * FROM expenses
@@ -84,66 +95,32 @@ impl ExpenseRepository {
         );
 
         query_builder.push_bind(user_id);
-
-        if let Some(c) = category {
-            query_builder.push(" AND category = ");
-            query_builder.push_bind(c.to_string());
-        }
-
-        if let Some(df) = date_from {
-            query_builder.push(" AND date_incurred >= ");
-            query_builder.push_bind(df);
-        }
-
-        if let Some(dt) = date_to {
-            query_builder.push(" AND date_incurred <= ");
-            query_builder.push_bind(dt);
-        }
-
-        if let Some(td) = tax_deductible {
-            query_builder.push(" AND tax_deductible = ");
-            query_builder.push_bind(td);
-        }
-
-        if let Some(s) = search {
-            query_builder.push(" AND (vendor ILIKE ");
-            query_builder.push_bind(format!("%{}%", s));
-            query_builder.push(" OR description ILIKE ");
-            query_builder.push_bind(format!("%{}%", s));
-            query_builder.push(")");
-        }
-
-        query_builder.push(" ORDER BY created_at DESC");
-
-        if let Some(l) = limit {
-            query_builder.push(" LIMIT ");
-            query_builder.push_bind(l);
-        }
-
-        if let Some(o) = offset {
-            query_builder.push(" OFFSET ");
-            query_builder.push_bind(o);
-        }
+        push_list_filters(&mut query_builder, category.as_ref(), date_from, date_to, tax_deductible, search.as_deref());
+        push_page_after(&mut query_builder, page, "created_at", "id");
+        push_page_window(&mut query_builder, page, "created_at", "id");
 
         let expenses = query_builder
             .build_query_as::<ExpenseRow>()
             .fetch_all(&self.db)
             .await?;
 
-        Ok(expenses.into_iter().map(|e: ExpenseRow| e.to_expense_response()).collect())
+        let expenses = expenses.into_iter().map(|e: ExpenseRow| e.to_expense_response()).collect();
+        Ok(Page::new(page, expenses, total, |expense| PageCursor::new(expense.created_at, expense.id)))
     }
 
     pub async fn update(
         &self,
         user_id: Uuid,
         expense_id: Uuid,
-        amount: Option<f64>,
+        amount: Option<Decimal>,
         category: Option<ExpenseCategory>,
         vendor: Option<String>,
         description: Option<String>,
         receipt_image_url: Option<String>,
         date_incurred: Option<NaiveDate>,
         tax_deductible: Option<bool>,
+        tax_rate: Option<Decimal>,
+        tax_amount: Option<Decimal>,
     ) -> Result<Expense, sqlx::Error> {
         let mut query_builder = QueryBuilder::<Postgres>::new(
             "UPDATE expenses SET updated_at = "
@@ -185,6 +162,16 @@ impl ExpenseRepository {
             query_builder.push_bind(td);
         }
 
+        if let Some(tr) = tax_rate {
+            query_builder.push(", tax_rate = ");
+            query_builder.push_bind(tr);
+        }
+
+        if let Some(ta) = tax_amount {
+            query_builder.push(", tax_amount = ");
+            query_builder.push_bind(ta);
+        }
+
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(expense_id);
         query_builder.push(" AND user_id = ");
@@ -257,11 +244,48 @@ impl ExpenseRepository {
     }
 }
 
+fn push_list_filters(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    category: Option<&ExpenseCategory>,
+    date_from: Option<NaiveDate>,
+    date_to: Option<NaiveDate>,
+    tax_deductible: Option<bool>,
+    search: Option<&str>,
+) {
+    if let Some(c) = category {
+        query_builder.push(" AND category = ");
+        query_builder.push_bind(c.to_string());
+    }
+
+    if let Some(df) = date_from {
+        query_builder.push(" AND date_incurred >= ");
+        query_builder.push_bind(df);
+    }
+
+    if let Some(dt) = date_to {
+        query_builder.push(" AND date_incurred <= ");
+        query_builder.push_bind(dt);
+    }
+
+    if let Some(td) = tax_deductible {
+        query_builder.push(" AND tax_deductible = ");
+        query_builder.push_bind(td);
+    }
+
+    if let Some(s) = search {
+        query_builder.push(" AND (vendor ILIKE ");
+        query_builder.push_bind(format!("%{}%", s));
+        query_builder.push(" OR description ILIKE ");
+        query_builder.push_bind(format!("%{}%", s));
+        query_builder.push(")");
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct ExpenseRow {
     id: Uuid,
     user_id: Uuid,
-    amount: f64,
+    amount: Decimal,
     currency: String,
     category: String,
     vendor: Option<String>,
@@ -269,23 +293,16 @@ struct ExpenseRow {
     receipt_image_url: Option<String>,
     date_incurred: NaiveDate,
     tax_deductible: bool,
+    tax_rate: Option<Decimal>,
+    tax_amount: Decimal,
+    project_id: Option<Uuid>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
 
 impl ExpenseRow {
     fn to_expense(self) -> Expense {
-        let category = match self.category.as_str() {
-            "supplies" => ExpenseCategory::Supplies,
-            "office_supplies" => ExpenseCategory::OfficeSupplies,
-            "travel" => ExpenseCategory::Travel,
-            "equipment" => ExpenseCategory::Equipment,
-            "software" => ExpenseCategory::Software,
-            "marketing" => ExpenseCategory::Marketing,
-            "utilities" => ExpenseCategory::Utilities,
-            "other" => ExpenseCategory::Other,
-            _ => ExpenseCategory::Other,
-        };
+        let category = ExpenseCategory::from_stored(&self.category);
 
         Expense {
             id: self.id,
@@ -298,23 +315,16 @@ impl ExpenseRow {
             receipt_image_url: self.receipt_image_url,
             date_incurred: self.date_incurred,
             tax_deductible: self.tax_deductible,
+            tax_rate: self.tax_rate,
+            tax_amount: self.tax_amount,
+            project_id: self.project_id,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
     }
 
     fn to_expense_response(self) -> ExpenseResponse {
-        let category = match self.category.as_str() {
-            "supplies" => ExpenseCategory::Supplies,
-            "office_supplies" => ExpenseCategory::OfficeSupplies,
-            "travel" => ExpenseCategory::Travel,
-            "equipment" => ExpenseCategory::Equipment,
-            "software" => ExpenseCategory::Software,
-            "marketing" => ExpenseCategory::Marketing,
-            "utilities" => ExpenseCategory::Utilities,
-            "other" => ExpenseCategory::Other,
-            _ => ExpenseCategory::Other,
-        };
+        let category = ExpenseCategory::from_stored(&self.category);
 
         ExpenseResponse {
             id: self.id,
@@ -325,6 +335,9 @@ impl ExpenseRow {
             description: self.description,
             date_incurred: self.date_incurred,
             tax_deductible: self.tax_deductible,
+            tax_rate: self.tax_rate,
+            tax_amount: self.tax_amount,
+            project_id: self.project_id,
             created_at: self.created_at,
         }
     }
@@ -0,0 +1,294 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use uuid::Uuid;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::domain::models::{CreateFxGainLoss, FxGainLossEntry, FxRate, FxRateSource};
+
+#[derive(Clone)]
+pub struct FxRepository {
+    db: PgPool,
+}
+
+impl FxRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, create: CreateFxGainLoss) -> Result<FxGainLossEntry, sqlx::Error> {
+        insert_gain_loss(&self.db, create).await
+    }
+
+    /// Currency the user reports in
+    pub async fn base_currency(&self, user_id: Uuid) -> Result<String, sqlx::Error> {
+        sqlx::query_scalar("SELECT COALESCE(currency, 'USD') FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await
+    }
+
+    pub async fn find_override(
+        &self,
+        user_id: Uuid,
+        base_currency: &str,
+        currency: &str,
+        rate_date: NaiveDate,
+    ) -> Result<Option<FxRate>, sqlx::Error> {
+        let row = sqlx::query_as::<_, FxRateRow>(
+            r#"
+            SELECT id, base_currency, currency, rate::float8 as rate, rate_date, source
+            FROM fx_rates
+            WHERE user_id = $1 AND base_currency = $2 AND currency = $3 AND rate_date = $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(base_currency)
+        .bind(currency)
+        .bind(rate_date)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(FxRateRow::into_rate))
+    }
+
+    pub async fn find_provider_rate(
+        &self,
+        source: FxRateSource,
+        base_currency: &str,
+        currency: &str,
+        rate_date: NaiveDate,
+    ) -> Result<Option<FxRate>, sqlx::Error> {
+        let row = sqlx::query_as::<_, FxRateRow>(
+            r#"
+            SELECT id, base_currency, currency, rate::float8 as rate, rate_date, source
+            FROM fx_rates
+            WHERE user_id IS NULL AND source = $1 AND base_currency = $2 AND currency = $3 AND rate_date = $4
+            "#,
+        )
+        .bind(source.as_str())
+        .bind(base_currency)
+        .bind(currency)
+        .bind(rate_date)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(FxRateRow::into_rate))
+    }
+
+    /// Record a provider's rates for a day, replacing any earlier fetch
+    pub async fn store_provider_rates(
+        &self,
+        source: FxRateSource,
+        base_currency: &str,
+        rate_date: NaiveDate,
+        rates: &[(String, f64)],
+    ) -> Result<(), sqlx::Error> {
+        let (currencies, values): (Vec<String>, Vec<f64>) = rates.iter().cloned().unzip();
+
+        sqlx::query(
+            r#"
+            INSERT INTO fx_rates (base_currency, currency, rate, rate_date, source)
+            SELECT $1, r.currency, r.rate, $2, $3
+            FROM UNNEST($4::varchar[], $5::float8[]) AS r(currency, rate)
+            ON CONFLICT (source, base_currency, currency, rate_date) WHERE user_id IS NULL
+            DO UPDATE SET rate = EXCLUDED.rate
+            "#,
+        )
+        .bind(base_currency)
+        .bind(rate_date)
+        .bind(source.as_str())
+        .bind(&currencies)
+        .bind(&values)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_override(
+        &self,
+        user_id: Uuid,
+        base_currency: &str,
+        currency: &str,
+        rate: f64,
+        rate_date: NaiveDate,
+    ) -> Result<FxRate, sqlx::Error> {
+        let row = sqlx::query_as::<_, FxRateRow>(
+            r#"
+            INSERT INTO fx_rates (user_id, base_currency, currency, rate, rate_date, source)
+            VALUES ($1, $2, $3, $4, $5, 'manual')
+            ON CONFLICT (user_id, base_currency, currency, rate_date) WHERE user_id IS NOT NULL
+            DO UPDATE SET rate = EXCLUDED.rate
+            RETURNING id, base_currency, currency, rate::float8 as rate, rate_date, source
+            "#,
+        )
+        .bind(user_id)
+        .bind(base_currency)
+        .bind(currency)
+        .bind(rate)
+        .bind(rate_date)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_rate())
+    }
+
+    pub async fn delete_override(&self, user_id: Uuid, rate_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM fx_rates WHERE id = $1 AND user_id = $2")
+            .bind(rate_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stored rates per day, the user's override taking precedence over provider rates
+    pub async fn history(
+        &self,
+        user_id: Uuid,
+        base_currency: &str,
+        currency: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<FxRate>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, FxRateRow>(
+            r#"
+            SELECT DISTINCT ON (rate_date)
+                id, base_currency, currency, rate::float8 as rate, rate_date, source
+            FROM fx_rates
+            WHERE (user_id = $1 OR user_id IS NULL)
+              AND base_currency = $2 AND currency = $3
+              AND rate_date BETWEEN $4 AND $5
+            ORDER BY rate_date, user_id IS NULL, updated_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(base_currency)
+        .bind(currency)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(FxRateRow::into_rate).collect())
+    }
+}
+
+async fn insert_gain_loss(db: impl PgExecutor<'_>, create: CreateFxGainLoss) -> Result<FxGainLossEntry, sqlx::Error> {
+    let gain_loss_amount = create.gain_loss_amount();
+
+    let entry = sqlx::query_as::<_, FxGainLossRow>(
+        r#"
+        INSERT INTO fx_gain_loss_entries (
+            id, user_id, invoice_id, payment_id, currency, foreign_amount,
+            issue_rate, settlement_rate, gain_loss_amount, created_at
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        RETURNING id, user_id, invoice_id, payment_id, currency, foreign_amount::float8 as foreign_amount,
+            issue_rate::float8 as issue_rate, settlement_rate::float8 as settlement_rate,
+            gain_loss_amount::float8 as gain_loss_amount, created_at
+        "#,
+    )
+    .bind(Uuid::new_v4())
+    .bind(create.user_id)
+    .bind(create.invoice_id)
+    .bind(create.payment_id)
+    .bind(&create.currency)
+    .bind(create.foreign_amount)
+    .bind(create.issue_rate)
+    .bind(create.settlement_rate)
+    .bind(gain_loss_amount)
+    .bind(Utc::now())
+    .fetch_one(db)
+    .await?;
+
+    Ok(entry.into_entry())
+}
+
+/// Book the realized gain or loss of a payment on its invoice, settled at
+/// `settlement_rate`, in the transaction that records the payment. Nothing is
+/// booked when the rate hasn't moved since the invoice was issued.
+pub(crate) async fn book_realized_gain_loss(
+    conn: &mut PgConnection,
+    invoice_id: Uuid,
+    payment_id: Option<Uuid>,
+    amount: Decimal,
+    settlement_rate: f64,
+) -> Result<Option<FxGainLossEntry>, sqlx::Error> {
+    let (user_id, currency, issue_rate): (Uuid, String, f64) = sqlx::query_as(
+        "SELECT user_id, currency, COALESCE(exchange_rate, 1)::float8 FROM invoices WHERE id = $1",
+    )
+    .bind(invoice_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let entry = CreateFxGainLoss {
+        user_id,
+        invoice_id,
+        payment_id,
+        currency,
+        foreign_amount: amount.to_f64().unwrap_or_default(),
+        issue_rate,
+        settlement_rate,
+    };
+    if entry.gain_loss_amount() == 0.0 {
+        return Ok(None);
+    }
+
+    insert_gain_loss(conn, entry).await.map(Some)
+}
+
+#[derive(sqlx::FromRow)]
+struct FxRateRow {
+    id: Uuid,
+    base_currency: String,
+    currency: String,
+    rate: f64,
+    rate_date: NaiveDate,
+    source: String,
+}
+
+impl FxRateRow {
+    fn into_rate(self) -> FxRate {
+        FxRate {
+            id: Some(self.id),
+            base_currency: self.base_currency,
+            currency: self.currency,
+            rate: self.rate,
+            rate_date: self.rate_date,
+            source: FxRateSource::parse(&self.source).unwrap_or(FxRateSource::Manual),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct FxGainLossRow {
+    id: Uuid,
+    user_id: Uuid,
+    invoice_id: Uuid,
+    payment_id: Option<Uuid>,
+    currency: String,
+    foreign_amount: f64,
+    issue_rate: f64,
+    settlement_rate: f64,
+    gain_loss_amount: f64,
+    created_at: DateTime<Utc>,
+}
+
+impl FxGainLossRow {
+    fn into_entry(self) -> FxGainLossEntry {
+        FxGainLossEntry {
+            id: self.id,
+            user_id: self.user_id,
+            invoice_id: self.invoice_id,
+            payment_id: self.payment_id,
+            currency: self.currency,
+            foreign_amount: self.foreign_amount,
+            issue_rate: self.issue_rate,
+            settlement_rate: self.settlement_rate,
+            gain_loss_amount: self.gain_loss_amount,
+            created_at: self.created_at,
+        }
+    }
+}
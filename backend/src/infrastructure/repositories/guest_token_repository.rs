@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct GuestTokenRepository {
+    db: PgPool,
+}
+
+impl GuestTokenRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+        self.db.begin().await
+    }
+
+    /// Record an issued token and make it the invoice's current guest link
+    pub async fn store(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        token_id: Uuid,
+        invoice_id: Uuid,
+        token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO guest_tokens (id, invoice_id, expires_at) VALUES ($1, $2, $3)")
+            .bind(token_id)
+            .bind(invoice_id)
+            .bind(expires_at)
+            .execute(&mut **tx)
+            .await?;
+
+        sqlx::query("UPDATE invoices SET guest_payment_token = $1, updated_at = NOW() WHERE id = $2")
+            .bind(token)
+            .bind(invoice_id)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lock the user's invoice for the rest of the transaction; false when it isn't theirs
+    pub async fn lock_invoice(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let found: Option<Uuid> = sqlx::query_scalar("SELECT id FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL FOR UPDATE")
+            .bind(invoice_id)
+            .bind(user_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(found.is_some())
+    }
+
+    /// Returns the number of tokens revoked
+    pub async fn revoke_for_invoice(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        invoice_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("UPDATE guest_tokens SET revoked_at = $2 WHERE invoice_id = $1 AND revoked_at IS NULL")
+            .bind(invoice_id)
+            .bind(now)
+            .execute(&mut **tx)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Whether the token was issued for this invoice and hasn't been revoked
+    pub async fn is_active(&self, token_id: Uuid, invoice_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM guest_tokens WHERE id = $1 AND invoice_id = $2 AND revoked_at IS NULL)",
+        )
+        .bind(token_id)
+        .bind(invoice_id)
+        .fetch_one(&self.db)
+        .await
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Response recorded for a completed request
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredResponse {
+    pub status_code: u16,
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Outcome of claiming an idempotency key for a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdempotencyClaim {
+    /// First use of the key; the caller runs the request and records its response
+    Claimed,
+    /// Another request with this key is still running
+    InFlight,
+    /// The key was already used with a different request body
+    Mismatch,
+    Completed(StoredResponse),
+}
+
+#[derive(Clone)]
+pub struct IdempotencyRepository {
+    db: PgPool,
+}
+
+impl IdempotencyRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Claim the key for a request, taking over keys created before
+    /// `expired_before` and in-flight claims locked before `stale_before`
+    pub async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        request_hash: &str,
+        now: DateTime<Utc>,
+        expired_before: DateTime<Utc>,
+        stale_before: DateTime<Utc>,
+    ) -> Result<IdempotencyClaim, sqlx::Error> {
+        let claimed: Option<(String,)> = sqlx::query_as(
+            r#"
+            INSERT INTO idempotency_keys (scope, key, request_hash, locked_at, created_at)
+            VALUES ($1, $2, $3, $4, $4)
+            ON CONFLICT (scope, key) DO UPDATE SET
+                request_hash = EXCLUDED.request_hash,
+                status_code = NULL,
+                content_type = NULL,
+                response_body = NULL,
+                locked_at = EXCLUDED.locked_at,
+                created_at = EXCLUDED.created_at
+            WHERE idempotency_keys.created_at < $5
+                OR (idempotency_keys.status_code IS NULL AND idempotency_keys.locked_at < $6)
+            RETURNING scope
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(request_hash)
+        .bind(now)
+        .bind(expired_before)
+        .bind(stale_before)
+        .fetch_optional(&self.db)
+        .await?;
+
+        if claimed.is_some() {
+            return Ok(IdempotencyClaim::Claimed);
+        }
+
+        let row = sqlx::query_as::<_, IdempotencyKeyRow>(
+            r#"
+            SELECT request_hash, status_code, content_type, response_body
+            FROM idempotency_keys
+            WHERE scope = $1 AND key = $2
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .fetch_optional(&self.db)
+        .await?;
+
+        // A row that vanished between the two queries was released by its request
+        Ok(row.map_or(IdempotencyClaim::InFlight, |row| row.into_claim(request_hash)))
+    }
+
+    pub async fn complete(&self, scope: &str, key: &str, response: &StoredResponse) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status_code = $3, content_type = $4, response_body = $5
+            WHERE scope = $1 AND key = $2
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(i32::from(response.status_code))
+        .bind(response.content_type.as_deref())
+        .bind(&response.body)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Give up an in-flight claim so the request can be retried with the same key
+    pub async fn release(&self, scope: &str, key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE scope = $1 AND key = $2 AND status_code IS NULL")
+            .bind(scope)
+            .bind(key)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the number of keys removed
+    pub async fn purge_expired(&self, expired_before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE created_at < $1")
+            .bind(expired_before)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct IdempotencyKeyRow {
+    request_hash: String,
+    status_code: Option<i32>,
+    content_type: Option<String>,
+    response_body: Option<Vec<u8>>,
+}
+
+impl IdempotencyKeyRow {
+    fn into_claim(self, request_hash: &str) -> IdempotencyClaim {
+        if self.request_hash != request_hash {
+            return IdempotencyClaim::Mismatch;
+        }
+
+        match self.status_code.and_then(|code| u16::try_from(code).ok()) {
+            Some(status_code) => IdempotencyClaim::Completed(StoredResponse {
+                status_code,
+                content_type: self.content_type,
+                body: self.response_body.unwrap_or_default(),
+            }),
+            None => IdempotencyClaim::InFlight,
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{ClientMargin, InvoiceCost, InvoiceCostKind, Margin};
+
+/// Cost amount: the linked expense's current amount, or hours at the hourly cost
+const COST_AMOUNT: &str = "CASE WHEN c.kind = 'expense' THEN e.amount ELSE c.hours * c.hourly_cost END";
+
+/// Net revenue of an invoice in the base currency
+pub(crate) const INVOICE_REVENUE: &str = "(i.total_amount - COALESCE(i.tax_amount, 0)) * COALESCE(i.exchange_rate, 1)";
+
+/// Invoice a profitability report is computed for
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct InvoiceRevenue {
+    pub invoice_number: String,
+    pub currency: String,
+    pub revenue: f64,
+}
+
+#[derive(Clone)]
+pub struct InvoiceCostRepository {
+    db: PgPool,
+}
+
+impl InvoiceCostRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn invoice_revenue(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<InvoiceRevenue>, sqlx::Error> {
+        sqlx::query_as::<_, InvoiceRevenue>(&format!(
+            r#"
+            SELECT i.invoice_number, COALESCE(i.currency, 'USD') as currency, ({})::float8 as revenue
+            FROM invoices i
+            WHERE i.id = $1 AND i.user_id = $2 AND i.deleted_at IS NULL
+            "#,
+            INVOICE_REVENUE
+        ))
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Link one of the user's expenses. Returns None when the expense doesn't exist.
+    pub async fn add_expense(&self, user_id: Uuid, invoice_id: Uuid, expense_id: Uuid) -> Result<Option<InvoiceCost>, sqlx::Error> {
+        let cost_id = Uuid::new_v4();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO invoice_costs (id, user_id, invoice_id, kind, expense_id, created_at)
+            SELECT $1, $2, $3, 'expense', e.id, $5
+            FROM expenses e
+            WHERE e.id = $4 AND e.user_id = $2
+            "#,
+        )
+        .bind(cost_id)
+        .bind(user_id)
+        .bind(invoice_id)
+        .bind(expense_id)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.find(user_id, cost_id).await
+    }
+
+    pub async fn add_time(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        description: Option<&str>,
+        hours: f64,
+        hourly_cost: f64,
+        work_date: Option<NaiveDate>,
+    ) -> Result<InvoiceCost, sqlx::Error> {
+        let cost_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO invoice_costs (id, user_id, invoice_id, kind, description, hours, hourly_cost, work_date, created_at)
+            VALUES ($1, $2, $3, 'time', $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(cost_id)
+        .bind(user_id)
+        .bind(invoice_id)
+        .bind(description)
+        .bind(hours)
+        .bind(hourly_cost)
+        .bind(work_date)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        self.find(user_id, cost_id).await?.ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn find(&self, user_id: Uuid, cost_id: Uuid) -> Result<Option<InvoiceCost>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceCostRow>(&format!(
+            "{} WHERE c.id = $1 AND c.user_id = $2",
+            Self::select_costs()
+        ))
+        .bind(cost_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceCostRow::into_cost))
+    }
+
+    pub async fn list(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Vec<InvoiceCost>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceCostRow>(&format!(
+            "{} WHERE c.invoice_id = $1 AND c.user_id = $2 ORDER BY c.created_at",
+            Self::select_costs()
+        ))
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(InvoiceCostRow::into_cost).collect())
+    }
+
+    pub async fn remove(&self, user_id: Uuid, invoice_id: Uuid, cost_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM invoice_costs WHERE id = $1 AND invoice_id = $2 AND user_id = $3")
+            .bind(cost_id)
+            .bind(invoice_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revenue and direct costs per client for invoices issued in the range.
+    /// Drafts, cancelled and superseded invoices are left out.
+    pub async fn margin_by_client(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<ClientMargin>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ClientMarginRow>(&format!(
+            r#"
+            WITH costs AS (
+                SELECT c.invoice_id, SUM({cost}) as amount
+                FROM invoice_costs c
+                LEFT JOIN expenses e ON e.id = c.expense_id
+                WHERE c.user_id = $1
+                GROUP BY c.invoice_id
+            )
+            SELECT
+                i.client_id, cl.name as client_name, COUNT(*) as invoice_count,
+                COALESCE(SUM({revenue}), 0)::float8 as revenue,
+                COALESCE(SUM(costs.amount), 0)::float8 as direct_costs
+            FROM invoices i
+            JOIN clients cl ON cl.id = i.client_id
+            LEFT JOIN costs ON costs.invoice_id = i.id
+            WHERE i.user_id = $1
+              AND i.issue_date BETWEEN $2 AND $3
+              AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+            GROUP BY i.client_id, cl.name
+            ORDER BY COALESCE(SUM({revenue}), 0) - COALESCE(SUM(costs.amount), 0) DESC, cl.name
+            "#,
+            cost = COST_AMOUNT,
+            revenue = INVOICE_REVENUE,
+        ))
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(ClientMarginRow::into_client_margin).collect())
+    }
+
+    fn select_costs() -> String {
+        format!(
+            r#"
+            SELECT
+                c.id, c.invoice_id, c.kind, c.expense_id,
+                COALESCE(c.description, e.description, e.vendor) as description,
+                c.hours::float8 as hours, c.hourly_cost::float8 as hourly_cost,
+                COALESCE(c.work_date, e.date_incurred) as date,
+                COALESCE({}, 0)::float8 as amount,
+                c.created_at
+            FROM invoice_costs c
+            LEFT JOIN expenses e ON e.id = c.expense_id
+            "#,
+            COST_AMOUNT
+        )
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct InvoiceCostRow {
+    id: Uuid,
+    invoice_id: Uuid,
+    kind: String,
+    expense_id: Option<Uuid>,
+    description: Option<String>,
+    hours: Option<f64>,
+    hourly_cost: Option<f64>,
+    date: Option<NaiveDate>,
+    amount: f64,
+    created_at: DateTime<Utc>,
+}
+
+impl InvoiceCostRow {
+    fn into_cost(self) -> InvoiceCost {
+        InvoiceCost {
+            id: self.id,
+            invoice_id: self.invoice_id,
+            kind: InvoiceCostKind::parse(&self.kind).unwrap_or(InvoiceCostKind::Time),
+            expense_id: self.expense_id,
+            description: self.description,
+            hours: self.hours,
+            hourly_cost: self.hourly_cost,
+            date: self.date,
+            amount: self.amount,
+            created_at: self.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ClientMarginRow {
+    client_id: Uuid,
+    client_name: String,
+    invoice_count: i64,
+    revenue: f64,
+    direct_costs: f64,
+}
+
+impl ClientMarginRow {
+    fn into_client_margin(self) -> ClientMargin {
+        ClientMargin {
+            client_id: self.client_id,
+            client_name: self.client_name,
+            invoice_count: self.invoice_count,
+            margin: Margin::new(self.revenue, self.direct_costs),
+        }
+    }
+}
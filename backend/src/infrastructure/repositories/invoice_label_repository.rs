@@ -0,0 +1,165 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{InvoiceLabel, InvoiceStatus};
+
+#[derive(Clone)]
+pub struct InvoiceLabelRepository {
+    db: PgPool,
+}
+
+impl InvoiceLabelRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<InvoiceLabel>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceLabelRow>(
+            "SELECT * FROM invoice_labels WHERE user_id = $1 ORDER BY position, LOWER(name)",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(InvoiceLabelRow::into_label).collect())
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, label_id: Uuid) -> Result<Option<InvoiceLabel>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceLabelRow>(
+            "SELECT * FROM invoice_labels WHERE id = $1 AND user_id = $2",
+        )
+        .bind(label_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceLabelRow::into_label))
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        color: Option<&str>,
+        statuses: &[InvoiceStatus],
+        show_on_pdf: bool,
+        position: i32,
+    ) -> Result<InvoiceLabel, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceLabelRow>(
+            r#"
+            INSERT INTO invoice_labels (id, user_id, name, color, statuses, show_on_pdf, position, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(name)
+        .bind(color)
+        .bind(status_names(statuses))
+        .bind(show_on_pdf)
+        .bind(position)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_label())
+    }
+
+    /// Saves a label the service has already merged with the update
+    pub async fn update(&self, user_id: Uuid, label: &InvoiceLabel) -> Result<Option<InvoiceLabel>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceLabelRow>(
+            r#"
+            UPDATE invoice_labels SET
+                name = $3, color = $4, statuses = $5, show_on_pdf = $6, position = $7, updated_at = $8
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(label.id)
+        .bind(user_id)
+        .bind(&label.name)
+        .bind(&label.color)
+        .bind(status_names(&label.statuses))
+        .bind(label.show_on_pdf)
+        .bind(label.position)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceLabelRow::into_label))
+    }
+
+    /// Invoices carrying the label lose it
+    pub async fn delete(&self, user_id: Uuid, label_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM invoice_labels WHERE id = $1 AND user_id = $2")
+            .bind(label_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns the invoice's core status, or None when the invoice doesn't exist
+    pub async fn set_invoice_label(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        label_id: Option<Uuid>,
+    ) -> Result<Option<InvoiceStatus>, sqlx::Error> {
+        sqlx::query_scalar::<_, InvoiceStatus>(
+            r#"
+            UPDATE invoices SET label_id = $3, updated_at = $4
+            WHERE id = $1 AND user_id = $2
+            RETURNING status
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(label_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    pub async fn invoice_status(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<InvoiceStatus>, sqlx::Error> {
+        sqlx::query_scalar::<_, InvoiceStatus>("SELECT status FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL")
+            .bind(invoice_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+}
+
+fn status_names(statuses: &[InvoiceStatus]) -> Vec<String> {
+    statuses.iter().map(|status| status.to_string()).collect()
+}
+
+#[derive(sqlx::FromRow)]
+pub(super) struct InvoiceLabelRow {
+    id: Uuid,
+    name: String,
+    color: Option<String>,
+    statuses: Vec<String>,
+    show_on_pdf: bool,
+    position: i32,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl InvoiceLabelRow {
+    pub(super) fn into_label(self) -> InvoiceLabel {
+        InvoiceLabel {
+            id: self.id,
+            name: self.name,
+            color: self.color,
+            statuses: self.statuses.iter().filter_map(|status| InvoiceStatus::parse(status)).collect(),
+            show_on_pdf: self.show_on_pdf,
+            position: self.position,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -1,70 +1,132 @@
 #![allow(dead_code)]
 
+use futures::{stream::BoxStream, StreamExt};
 use sqlx::{PgPool, Postgres, QueryBuilder, Row};
 use uuid::Uuid;
-use chrono::{DateTime, Utc, NaiveDate, Datelike};
+use chrono::{DateTime, Utc, NaiveDate};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
 use std::sync::Arc;
 
+use crate::domain::i18n::Locale;
 use crate::domain::models::{
     Invoice, InvoiceItem, InvoiceStatus, InvoiceResponse, InvoiceDetailResponse,
     CreateInvoice, UpdateInvoice, InvoiceListFilter, CreatePayment,
-    InvoiceDiscussion, SenderType, DiscussionResponse
+    InvoiceDiscussion, SenderType, DiscussionResponse, DiscussionUnread, UnreadDiscussion,
+    InvoiceNotification, NewInvoiceNotification, NotificationChannel, InvoiceLabel,
+    NotificationSettings, ReminderCandidate, InvoiceExportRow, Page, PageCursor, PageRequest,
+    DepositTerms, InvoiceDeposit,
 };
-use crate::domain::services::TaxService;
+use crate::domain::models::{CurrencyRoundingRules, DocumentType, InvoiceTotals, LineInput, RoundingPolicy, TaxComponent, TaxLine};
+use crate::domain::services::{TaxService, DocumentNumberService, GuestTokenService};
+use super::fx_repository::book_realized_gain_loss;
+use super::invoice_label_repository::InvoiceLabelRow;
+use super::pagination::{push_page_after, push_page_window};
+
+/// Invoices buffered between the export query and the response body
+const EXPORT_ROW_BUFFER: usize = 256;
 
 #[derive(Clone)]
 pub struct InvoiceRepository {
     db: PgPool,
     tax_service: Arc<TaxService>,
+    document_numbers: Arc<DocumentNumberService>,
+    guest_tokens: Arc<GuestTokenService>,
+    rounding_rules: CurrencyRoundingRules,
 }
 
 impl InvoiceRepository {
-    pub fn new(db: PgPool, tax_service: Arc<TaxService>) -> Self {
-        Self { db, tax_service }
+    pub fn new(
+        db: PgPool,
+        tax_service: Arc<TaxService>,
+        document_numbers: Arc<DocumentNumberService>,
+        guest_tokens: Arc<GuestTokenService>,
+    ) -> Self {
+        Self {
+            db,
+            tax_service,
+            document_numbers,
+            guest_tokens,
+            rounding_rules: CurrencyRoundingRules::from_env(),
+        }
     }
 
     pub async fn create(&self, user_id: Uuid, create: CreateInvoice) -> Result<Invoice, sqlx::Error> {
-        // Fetch default tax setting for the organization
+        // Fetch default tax setting for the organization; reverse-charged invoices charge no tax
         let default_tax = self.tax_service.get_default_tax(user_id).await
-            .map_err(|_| sqlx::Error::RowNotFound)?;
+            .map_err(|_| sqlx::Error::RowNotFound)?
+            .filter(|_| !create.reverse_charge);
 
-        // Calculate items first (needed for both retry and final insert)
-        let mut items = Vec::new();
-        let mut subtotal = 0.0;
-        let mut tax_amount = 0.0;
-
-        for item in create.items {
-            // Use item tax rate if provided, otherwise use default tax rate
-            let tax_rate = item.tax_rate.unwrap_or_else(|| {
-                default_tax.as_ref().map(|t| t.rate).unwrap_or(0.0)
-            });
+        // Currency defaults to the user's base currency (rate 1.0)
+        let base_currency: String = sqlx::query_scalar(
+            "SELECT COALESCE(currency, 'USD') FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+        let currency = create.currency.clone()
+            .map(|c| c.to_uppercase())
+            .unwrap_or(base_currency.clone());
+        let exchange_rate = if currency == base_currency {
+            1.0
+        } else {
+            create.exchange_rate.unwrap_or(1.0)
+        };
 
-            let item_subtotal = item.quantity * item.unit_price;
-            let item_tax = item_subtotal * tax_rate;
-            let item_total = item_subtotal + item_tax;
+        // Calculate items and totals before taking a number
+        let lines: Vec<LineInput> = create.items.iter()
+            .map(|item| LineInput {
+                quantity: item.quantity,
+                unit_price: item.unit_price,
+                // Several taxes charge their combined rate; otherwise the item's rate, else the default tax rate
+                tax_rate: if item.taxes.is_empty() {
+                    item.tax_rate.unwrap_or_else(|| {
+                        default_tax.as_ref().and_then(|t| Decimal::from_f64(t.rate)).unwrap_or_default()
+                    })
+                } else {
+                    TaxComponent::combined_rate(&item.taxes)
+                },
+            })
+            .collect();
+        let rounding = self.rounding_rules.for_currency(&currency);
+        let totals = InvoiceTotals::calculate(
+            &lines,
+            create.discount_amount.unwrap_or_default(),
+            create.tax_included,
+            RoundingPolicy::PerLine,
+            rounding,
+        );
 
-            items.push(InvoiceItem {
+        let items: Vec<InvoiceItem> = create.items.into_iter()
+            .zip(lines.iter().zip(&totals.lines))
+            .map(|(item, (line, amounts))| InvoiceItem {
                 id: Uuid::new_v4(),
                 description: item.description,
                 quantity: item.quantity,
                 unit_price: item.unit_price,
-                tax_rate,
-                tax_amount: item_tax,
-                total: item_total,
-            });
+                tax_rate: line.tax_rate,
+                tax_amount: amounts.tax_amount,
+                total: amounts.total,
+                section: item.section,
+                taxes: TaxComponent::split(&item.taxes, amounts.tax_amount, rounding),
+            })
+            .collect();
 
-            subtotal += item_subtotal;
-            tax_amount += item_tax;
-        }
+        let subtotal = totals.subtotal;
+        let tax_amount = totals.tax_amount;
+        let discount = totals.discount;
+        let total_amount = totals.total;
 
-        let discount = create.discount_amount.unwrap_or(0.0);
-        let total_amount = subtotal + tax_amount - discount;
+        // Tax label and ID as given (e.g. the client's default tax), else from the default tax
+        let tax_label = create.tax_label.clone().or_else(|| default_tax.as_ref().map(|t| t.label.clone()));
+        let tax_id = create.tax_id.clone().or_else(|| default_tax.as_ref().map(|t| t.id.to_string()));
 
         let tax_calculation = serde_json::json!({
             "subtotal": subtotal,
             "tax_amount": tax_amount,
             "discount": discount,
-            "total": total_amount
+            "total": total_amount,
+            "taxes": TaxLine::breakdown(&items, tax_label.as_deref()),
         });
 
         let status = if create.send_immediately {
@@ -79,127 +141,39 @@ impl InvoiceRepository {
             None
         };
 
-        // Get tax label and ID from default tax
-        let tax_label = default_tax.as_ref().map(|t| t.label.clone());
-        let tax_id = default_tax.as_ref().map(|t| t.id.to_string());
-
         let items_json = serde_json::to_value(&items).unwrap_or(serde_json::Value::Array(vec![]));
         let tax_calculation_json = serde_json::to_value(&tax_calculation).unwrap_or(serde_json::Value::Null);
 
-        // Generate guest payment token (for guest checkout)
-        // Format: guest_{invoice_id}_{random_hash}
-        // We'll generate this after creating the invoice to get the invoice_id
-        // For now, use a placeholder that will be updated
+        // The guest link is signed once the invoice has an id
         let guest_payment_token: Option<String> = None;
 
         // Partial Payment Settings (default: allow partial, no minimum)
         let allow_partial_payment = create.allow_partial_payment.unwrap_or(true);
         let min_payment_amount = create.min_payment_amount;
 
-        // Retry logic for duplicate invoice numbers
-        let max_retries = 5;
-        for attempt in 0..max_retries {
-            // Generate random values BEFORE async operations to avoid Send issues
-            let random_suffix: u32 = rand::random();
+        let deposit = create.deposit.unwrap_or_default();
+        let deposit_amount = deposit.amount_for(total_amount, self.rounding_rules.for_currency(&currency));
+        let deposit_due_date = deposit_amount.map(|_| deposit.due_date.unwrap_or(create.issue_date));
 
-            let count = sqlx::query_scalar::<_, i64>(
-                "SELECT COUNT(*) FROM invoices WHERE user_id = $1 AND created_at >= date_trunc('year', CURRENT_DATE)"
-            )
-            .bind(user_id)
-            .fetch_one(&self.db)
-            .await?;
+        let mut tx = self.db.begin().await?;
 
-            let year = chrono::Local::now().year();
-            let invoice_number = format!("INV-{}-{:04}-{:03}", year, count + 1, random_suffix % 1000);
+        // The invoice sequence stays locked until commit, so concurrent creates
+        // are numbered one after another and a failed insert frees its number
+        let invoice_number = loop {
+            let number = self.document_numbers.next_number_in(&mut tx, user_id, DocumentType::Invoice).await?;
 
-            let result = sqlx::query_as::<_, InvoiceInsertRow>(
-                r#"
-                INSERT INTO invoices (
-                    id, user_id, client_id, invoice_number, status,
-                    issue_date, due_date, subtotal, tax_amount, discount_amount,
-                    total_amount, amount_paid, items, notes, terms,
-                    tax_calculation, tax_included, tax_label, tax_id,
-                    sent_at, viewed_at, paid_at, reminder_sent_count, last_reminder_sent,
-                    notification_sent_at, whatsapp_sent_at, guest_payment_token,
-                    allow_partial_payment, min_payment_amount, partial_payment_count,
-                    created_at, updated_at
-                ) VALUES (
-                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32
-                )
-                RETURNING *
-                "#,
+            // Skip numbers already used, e.g. by imported invoices
+            let taken: bool = sqlx::query_scalar(
+                "SELECT EXISTS(SELECT 1 FROM invoices WHERE user_id = $1 AND invoice_number = $2)"
             )
-            .bind(Uuid::new_v4())
             .bind(user_id)
-            .bind(create.client_id)
-            .bind(&invoice_number)
-            .bind(&status.to_string())
-            .bind(create.issue_date)
-            .bind(create.due_date)
-            .bind(subtotal)
-            .bind(tax_amount)
-            .bind(discount)
-            .bind(total_amount)
-            .bind(0.0) // amount_paid
-            .bind(&items_json)
-            .bind(&create.notes)
-            .bind(&create.terms)
-            .bind(&tax_calculation_json)
-            .bind(create.tax_included)
-            .bind(&tax_label)
-            .bind(&tax_id)
-            .bind(sent_at)
-            .bind(None::<chrono::DateTime<chrono::Utc>>) // viewed_at
-            .bind(None::<chrono::DateTime<chrono::Utc>>) // paid_at
-            .bind(0) // reminder_sent_count
-            .bind(None::<chrono::DateTime<chrono::Utc>>) // last_reminder_sent
-            .bind(None::<chrono::DateTime<chrono::Utc>>) // notification_sent_at
-            .bind(None::<chrono::DateTime<chrono::Utc>>) // whatsapp_sent_at
-            .bind(&guest_payment_token)
-            .bind(allow_partial_payment) // allow_partial_payment
-            .bind(min_payment_amount) // min_payment_amount
-            .bind(0) // partial_payment_count
-            .bind(Utc::now())
-            .bind(Utc::now())
-            .fetch_one(&self.db)
-            .await;
-
-            match result {
-                Ok(invoice) => {
-                    // Generate and update guest payment token after successful insert
-                    let invoice_id = invoice.id;
-                    let guest_token = format!("guest_{}_{}", invoice_id, rand::random::<u32>() % 1000000);
-
-                    sqlx::query(
-                        "UPDATE invoices SET guest_payment_token = $1 WHERE id = $2"
-                    )
-                    .bind(&guest_token)
-                    .bind(invoice_id)
-                    .execute(&self.db)
-                    .await?;
-
-                    // Return with the updated token
-                    let mut updated_invoice = invoice.to_invoice();
-                    updated_invoice.guest_payment_token = Some(guest_token);
-                    return Ok(updated_invoice);
-                }
-                Err(sqlx::Error::Database(db_err)) => {
-                    if db_err.is_unique_violation() && attempt < max_retries - 1 {
-                        // Wait a tiny bit and retry
-                        tokio::time::sleep(tokio::time::Duration::from_millis(10 * (attempt as u64 + 1))).await;
-                        continue;
-                    } else {
-                        return Err(sqlx::Error::Database(db_err));
-                    }
-                }
-                Err(e) => return Err(e),
+            .bind(&number)
+            .fetch_one(&mut *tx)
+            .await?;
+            if !taken {
+                break number;
             }
-        }
-
-        // Fallback: use timestamp-based invoice number with microseconds and random
-        let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S%.6f");
-        let random_suffix: u32 = rand::random();
-        let invoice_number = format!("INV-{}-{:05}", timestamp, random_suffix % 100000);
+        };
 
         let invoice = sqlx::query_as::<_, InvoiceInsertRow>(
             r#"
@@ -211,9 +185,11 @@ impl InvoiceRepository {
                 sent_at, viewed_at, paid_at, reminder_sent_count, last_reminder_sent,
                 notification_sent_at, whatsapp_sent_at, guest_payment_token,
                 allow_partial_payment, min_payment_amount, partial_payment_count,
-                created_at, updated_at
+                created_at, updated_at, currency, exchange_rate, expires_at, project_id,
+                deposit_amount, deposit_percent, deposit_due_date, reverse_charge, vat_country
             ) VALUES (
-                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32
+                $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36,
+                $37, $38, $39, $40, $41
             )
             RETURNING *
             "#,
@@ -229,7 +205,7 @@ impl InvoiceRepository {
         .bind(tax_amount)
         .bind(discount)
         .bind(total_amount)
-        .bind(0.0) // amount_paid
+        .bind(Decimal::ZERO) // amount_paid
         .bind(&items_json)
         .bind(&create.notes)
         .bind(&create.terms)
@@ -250,25 +226,26 @@ impl InvoiceRepository {
         .bind(0) // partial_payment_count
         .bind(Utc::now())
         .bind(Utc::now())
-        .fetch_one(&self.db)
+        .bind(&currency)
+        .bind(exchange_rate)
+        .bind(create.expires_at)
+        .bind(create.project_id)
+        .bind(deposit_amount)
+        .bind(deposit_amount.and(deposit.percent))
+        .bind(deposit_due_date)
+        .bind(create.reverse_charge)
+        .bind(&create.vat_country)
+        .fetch_one(&mut *tx)
         .await?;
 
-        // Generate and update guest payment token after successful insert
-        let invoice_id = invoice.id;
-        let guest_token = format!("guest_{}_{}", invoice_id, rand::random::<u32>() % 1000000);
+        // Signed guest checkout link, which needs the new invoice's id
+        let guest_link = self.guest_tokens.issue_in(&mut tx, invoice.id).await?;
 
-        sqlx::query(
-            "UPDATE invoices SET guest_payment_token = $1 WHERE id = $2"
-        )
-        .bind(&guest_token)
-        .bind(invoice_id)
-        .execute(&self.db)
-        .await?;
+        tx.commit().await?;
 
-        // Return with the updated token
-        let mut updated_invoice = invoice.to_invoice();
-        updated_invoice.guest_payment_token = Some(guest_token);
-        Ok(updated_invoice)
+        let mut created = invoice.to_invoice();
+        created.guest_payment_token = Some(guest_link.token);
+        Ok(created)
     }
 
     /// Get invoice by ID (for guest access - no user_id check)
@@ -280,18 +257,25 @@ impl InvoiceRepository {
                 i.issue_date, i.due_date, i.subtotal, i.tax_amount, i.discount_amount,
                 i.total_amount, i.amount_paid, i.items, i.notes, i.terms,
                 i.tax_calculation, i.tax_included, i.tax_label, i.tax_id,
+                i.currency, i.exchange_rate::float8 AS exchange_rate,
                 i.pdf_url, i.receipt_image_url,
                 i.sent_at, i.viewed_at, i.paid_at, i.reminder_sent_count, i.last_reminder_sent,
                 i.notification_sent_at, i.whatsapp_sent_at, i.guest_payment_token,
                 i.allow_partial_payment, i.min_payment_amount, i.partial_payment_count,
+                i.consolidated_into_id, i.supersedes_id, i.superseded_by_id, i.correction_reason,
+                i.expires_at, i.expired_at, i.project_id,
+                i.deposit_amount, i.deposit_percent, i.deposit_due_date, i.deposit_paid_at,
+                i.reverse_charge, i.vat_country,
                 i.created_at, i.updated_at,
                 c.name as client_name,
                 c.email as client_email,
                 c.phone as client_phone,
-                c.billing_address as client_address
+                c.billing_address as client_address,
+                c.locale as client_locale,
+                c.vat_number as client_vat_number
             FROM invoices i
             JOIN clients c ON i.client_id = c.id
-            WHERE i.id = $1
+            WHERE i.id = $1 AND i.deleted_at IS NULL
             "#,
         )
         .bind(invoice_id)
@@ -310,9 +294,11 @@ impl InvoiceRepository {
                     "paid" => InvoiceStatus::Paid,
                     "overdue" => InvoiceStatus::Overdue,
                     "cancelled" => InvoiceStatus::Cancelled,
+                    "superseded" => InvoiceStatus::Superseded,
+                    "expired" => InvoiceStatus::Expired,
                     _ => InvoiceStatus::Draft,
                 };
-                let balance_due: f64 = r.try_get::<f64, _>("total_amount")? - r.try_get::<f64, _>("amount_paid")?;
+                let balance_due: Decimal = r.try_get::<Decimal, _>("total_amount")? - r.try_get::<Decimal, _>("amount_paid")?;
 
                 Ok(InvoiceDetailResponse {
                     id: r.try_get("id")?,
@@ -324,6 +310,7 @@ impl InvoiceRepository {
                     client_email: r.try_get("client_email")?,
                     client_phone: r.try_get("client_phone")?,
                     client_address: r.try_get("client_address")?,
+                    client_locale: r.try_get::<Option<String>, _>("client_locale")?.as_deref().and_then(Locale::parse),
                     issue_date: r.try_get("issue_date")?,
                     due_date: r.try_get("due_date")?,
                     subtotal: r.try_get("subtotal")?,
@@ -339,6 +326,8 @@ impl InvoiceRepository {
                     tax_included: r.try_get("tax_included")?,
                     tax_label: r.try_get("tax_label")?,
                     tax_id: r.try_get("tax_id")?,
+                    currency: r.try_get("currency")?,
+                    exchange_rate: r.try_get("exchange_rate")?,
                     pdf_url: r.try_get("pdf_url")?,
                     receipt_image_url: r.try_get("receipt_image_url")?,
                     sent_at: r.try_get("sent_at")?,
@@ -352,6 +341,24 @@ impl InvoiceRepository {
                     allow_partial_payment: r.try_get("allow_partial_payment")?,
                     min_payment_amount: r.try_get("min_payment_amount")?,
                     partial_payment_count: r.try_get("partial_payment_count")?,
+                    reverse_charge: r.try_get("reverse_charge")?,
+                    vat_country: r.try_get("vat_country")?,
+                    client_vat_number: r.try_get("client_vat_number")?,
+                    deposit: InvoiceDeposit::from_columns(
+                        r.try_get("deposit_amount")?,
+                        r.try_get("deposit_percent")?,
+                        r.try_get("deposit_due_date")?,
+                        r.try_get("deposit_paid_at")?,
+                        r.try_get("amount_paid")?,
+                        r.try_get("issue_date")?,
+                    ),
+                    consolidated_into_id: r.try_get("consolidated_into_id")?,
+                    supersedes_id: r.try_get("supersedes_id")?,
+                    superseded_by_id: r.try_get("superseded_by_id")?,
+                    correction_reason: r.try_get("correction_reason")?,
+                    expires_at: r.try_get("expires_at")?,
+                    expired_at: r.try_get("expired_at")?,
+                    project_id: r.try_get("project_id")?,
                     created_at: r.try_get("created_at")?,
                     updated_at: r.try_get("updated_at")?,
                 })
@@ -368,18 +375,25 @@ impl InvoiceRepository {
                 i.issue_date, i.due_date, i.subtotal, i.tax_amount, i.discount_amount,
                 i.total_amount, i.amount_paid, i.items, i.notes, i.terms,
                 i.tax_calculation, i.tax_included, i.tax_label, i.tax_id,
+                i.currency, i.exchange_rate::float8 AS exchange_rate,
                 i.pdf_url, i.receipt_image_url,
                 i.sent_at, i.viewed_at, i.paid_at, i.reminder_sent_count, i.last_reminder_sent,
                 i.notification_sent_at, i.whatsapp_sent_at, i.guest_payment_token,
                 i.allow_partial_payment, i.min_payment_amount, i.partial_payment_count,
+                i.consolidated_into_id, i.supersedes_id, i.superseded_by_id, i.correction_reason,
+                i.expires_at, i.expired_at, i.project_id,
+                i.deposit_amount, i.deposit_percent, i.deposit_due_date, i.deposit_paid_at,
+                i.reverse_charge, i.vat_country,
                 i.created_at, i.updated_at,
                 c.name as client_name,
                 c.email as client_email,
                 c.phone as client_phone,
-                c.billing_address as client_address
+                c.billing_address as client_address,
+                c.locale as client_locale,
+                c.vat_number as client_vat_number
             FROM invoices i
             JOIN clients c ON i.client_id = c.id
-            WHERE i.id = $1 AND i.user_id = $2
+            WHERE i.id = $1 AND i.user_id = $2 AND i.deleted_at IS NULL
             "#,
         )
         .bind(invoice_id)
@@ -399,9 +413,11 @@ impl InvoiceRepository {
                     "paid" => InvoiceStatus::Paid,
                     "overdue" => InvoiceStatus::Overdue,
                     "cancelled" => InvoiceStatus::Cancelled,
+                    "superseded" => InvoiceStatus::Superseded,
+                    "expired" => InvoiceStatus::Expired,
                     _ => InvoiceStatus::Draft,
                 };
-                let balance_due: f64 = r.try_get::<f64, _>("total_amount")? - r.try_get::<f64, _>("amount_paid")?;
+                let balance_due: Decimal = r.try_get::<Decimal, _>("total_amount")? - r.try_get::<Decimal, _>("amount_paid")?;
 
                 Ok(InvoiceDetailResponse {
                     id: r.try_get("id")?,
@@ -413,6 +429,7 @@ impl InvoiceRepository {
                     client_email: r.try_get("client_email")?,
                     client_phone: r.try_get("client_phone")?,
                     client_address: r.try_get("client_address")?,
+                    client_locale: r.try_get::<Option<String>, _>("client_locale")?.as_deref().and_then(Locale::parse),
                     issue_date: r.try_get("issue_date")?,
                     due_date: r.try_get("due_date")?,
                     subtotal: r.try_get("subtotal")?,
@@ -428,6 +445,8 @@ impl InvoiceRepository {
                     tax_included: r.try_get("tax_included")?,
                     tax_label: r.try_get("tax_label")?,
                     tax_id: r.try_get("tax_id")?,
+                    currency: r.try_get("currency")?,
+                    exchange_rate: r.try_get("exchange_rate")?,
                     pdf_url: r.try_get("pdf_url")?,
                     receipt_image_url: r.try_get("receipt_image_url")?,
                     sent_at: r.try_get("sent_at")?,
@@ -441,6 +460,24 @@ impl InvoiceRepository {
                     allow_partial_payment: r.try_get("allow_partial_payment")?,
                     min_payment_amount: r.try_get("min_payment_amount")?,
                     partial_payment_count: r.try_get("partial_payment_count")?,
+                    reverse_charge: r.try_get("reverse_charge")?,
+                    vat_country: r.try_get("vat_country")?,
+                    client_vat_number: r.try_get("client_vat_number")?,
+                    deposit: InvoiceDeposit::from_columns(
+                        r.try_get("deposit_amount")?,
+                        r.try_get("deposit_percent")?,
+                        r.try_get("deposit_due_date")?,
+                        r.try_get("deposit_paid_at")?,
+                        r.try_get("amount_paid")?,
+                        r.try_get("issue_date")?,
+                    ),
+                    consolidated_into_id: r.try_get("consolidated_into_id")?,
+                    supersedes_id: r.try_get("supersedes_id")?,
+                    superseded_by_id: r.try_get("superseded_by_id")?,
+                    correction_reason: r.try_get("correction_reason")?,
+                    expires_at: r.try_get("expires_at")?,
+                    expired_at: r.try_get("expired_at")?,
+                    project_id: r.try_get("project_id")?,
                     created_at: r.try_get("created_at")?,
                     updated_at: r.try_get("updated_at")?,
                 })
@@ -449,10 +486,90 @@ impl InvoiceRepository {
         }
     }
 
+    /// Full invoices for a set of IDs; IDs that don't exist or belong to another user are skipped
+    pub async fn get_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<InvoiceDetailResponse>, sqlx::Error> {
+        sqlx::query_as::<_, InvoiceDetailResponse>(
+            r#"
+            SELECT
+                i.id, i.user_id, i.client_id, i.invoice_number, COALESCE(i.status, 'draft') as status,
+                i.issue_date, i.due_date,
+                i.subtotal,
+                COALESCE(i.tax_amount, 0) as tax_amount,
+                COALESCE(i.discount_amount, 0) as discount_amount,
+                i.total_amount,
+                COALESCE(i.amount_paid, 0) as amount_paid,
+                (i.total_amount - COALESCE(i.amount_paid, 0)) as balance_due,
+                i.items, i.notes, i.terms,
+                COALESCE(i.tax_calculation, '{}'::jsonb) as tax_calculation,
+                COALESCE(i.tax_included, FALSE) as tax_included,
+                i.tax_label, i.tax_id,
+                COALESCE(i.currency, 'USD') as currency,
+                COALESCE(i.exchange_rate, 1)::float8 as exchange_rate,
+                i.pdf_url, i.receipt_image_url,
+                i.sent_at, i.viewed_at, i.paid_at,
+                COALESCE(i.reminder_sent_count, 0) as reminder_sent_count, i.last_reminder_sent,
+                i.notification_sent_at, i.whatsapp_sent_at, i.guest_payment_token,
+                COALESCE(i.allow_partial_payment, TRUE) as allow_partial_payment,
+                i.min_payment_amount,
+                COALESCE(i.partial_payment_count, 0) as partial_payment_count,
+                i.consolidated_into_id, i.supersedes_id, i.superseded_by_id, i.correction_reason,
+                i.expires_at, i.expired_at, i.project_id,
+                i.deposit_amount, i.deposit_percent, i.deposit_due_date, i.deposit_paid_at,
+                i.reverse_charge, i.vat_country,
+                i.created_at, i.updated_at,
+                c.name as client_name,
+                c.email as client_email,
+                c.phone as client_phone,
+                c.billing_address as client_address,
+                c.locale as client_locale,
+                c.vat_number as client_vat_number
+            FROM invoices i
+            JOIN clients c ON i.client_id = c.id
+            WHERE i.user_id = $1 AND i.id = ANY($2) AND i.deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await
+    }
+
     pub async fn list(
         &self,
         user_id: Uuid,
         filter: InvoiceListFilter,
+    ) -> Result<Vec<InvoiceResponse>, sqlx::Error> {
+        self.fetch_list(user_id, &filter, None).await
+    }
+
+    /// One page of the invoices matching the filters, newest first
+    pub async fn list_page(
+        &self,
+        user_id: Uuid,
+        filter: InvoiceListFilter,
+        page: &PageRequest,
+    ) -> Result<Page<InvoiceResponse>, sqlx::Error> {
+        let mut count_builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT COUNT(*)
+            FROM invoices i
+            JOIN clients c ON i.client_id = c.id
+            LEFT JOIN invoice_labels l ON l.id = i.label_id AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
+            WHERE i.user_id = "#,
+        );
+        count_builder.push_bind(user_id);
+        push_list_filters(&mut count_builder, &filter);
+        let total: i64 = count_builder.build_query_scalar().fetch_one(&self.db).await?;
+
+        let rows = self.fetch_list(user_id, &filter, Some(page)).await?;
+        Ok(Page::new(page, rows, total, |invoice| PageCursor::new(invoice.created_at, invoice.id)))
+    }
+
+    async fn fetch_list(
+        &self,
+        user_id: Uuid,
+        filter: &InvoiceListFilter,
+        page: Option<&PageRequest>,
     ) -> Result<Vec<InvoiceResponse>, sqlx::Error> {
         let mut query_builder = QueryBuilder::<Postgres>::new(
             r#"
@@ -462,52 +579,25 @@ impl InvoiceRepository {
                 (i.total_amount - i.amount_paid) as balance_due,
                 i.created_at,
                 (i.due_date - CURRENT_DATE)::int4 as days_until_due,
-                (i.due_date < CURRENT_DATE AND i.status != 'paid' AND i.status != 'cancelled') as is_overdue
+                (i.due_date < CURRENT_DATE AND i.status NOT IN ('paid', 'cancelled', 'superseded', 'expired')) as is_overdue,
+                l.name as label
             FROM invoices i
             JOIN clients c ON i.client_id = c.id
+            LEFT JOIN invoice_labels l ON l.id = i.label_id AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
             WHERE i.user_id = "#,
         );
 
         query_builder.push_bind(user_id);
+        push_list_filters(&mut query_builder, filter);
 
-        if let Some(status) = filter.status {
-            query_builder.push(" AND i.status = ");
-            query_builder.push_bind(status.to_string());
-        }
-
-        if let Some(client_id) = filter.client_id {
-            query_builder.push(" AND i.client_id = ");
-            query_builder.push_bind(client_id);
-        }
-
-        if let Some(date_from) = filter.date_from {
-            query_builder.push(" AND i.issue_date >= ");
-            query_builder.push_bind(date_from);
-        }
-
-        if let Some(date_to) = filter.date_to {
-            query_builder.push(" AND i.issue_date <= ");
-            query_builder.push_bind(date_to);
-        }
-
-        if let Some(search) = filter.search {
-            query_builder.push(" AND (c.name ILIKE ");
-            query_builder.push_bind(format!("%{}%", search));
-            query_builder.push(" OR i.invoice_number ILIKE ");
-            query_builder.push_bind(format!("%{}%", search));
-            query_builder.push(")");
-        }
-
-        query_builder.push(" ORDER BY i.created_at DESC");
-
-        if let Some(limit) = filter.limit {
-            query_builder.push(" LIMIT ");
-            query_builder.push_bind(limit);
-        }
-
-        if let Some(offset) = filter.offset {
-            query_builder.push(" OFFSET ");
-            query_builder.push_bind(offset);
+        match page {
+            Some(page) => {
+                push_page_after(&mut query_builder, page, "i.created_at", "i.id");
+                push_page_window(&mut query_builder, page, "i.created_at", "i.id");
+            }
+            None => {
+                query_builder.push(" ORDER BY i.created_at DESC");
+            }
         }
 
         let query = query_builder.build();
@@ -526,6 +616,7 @@ impl InvoiceRepository {
                 balance_due: row.try_get("balance_due")?,
                 days_until_due: row.try_get("days_until_due")?,
                 is_overdue: row.try_get("is_overdue")?,
+                label: row.try_get("label")?,
                 created_at: row.try_get("created_at")?,
             })
         }).collect::<Result<Vec<_>, sqlx::Error>>()?;
@@ -533,6 +624,65 @@ impl InvoiceRepository {
         Ok(invoices)
     }
 
+    /// Streams the invoices matching the list filters (paging aside), newest
+    /// first, with their line items
+    pub fn stream_export(
+        &self,
+        user_id: Uuid,
+        filter: InvoiceListFilter,
+    ) -> BoxStream<'static, Result<InvoiceExportRow, sqlx::Error>> {
+        let db = self.db.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(EXPORT_ROW_BUFFER);
+
+        tokio::spawn(async move {
+            let mut query_builder = QueryBuilder::<Postgres>::new(
+                r#"
+                SELECT
+                    i.invoice_number, i.status, c.name as client_name, c.email as client_email,
+                    i.issue_date, i.due_date, COALESCE(i.currency, 'USD') as currency, i.items,
+                    i.subtotal, COALESCE(i.tax_amount, 0) as tax_amount,
+                    COALESCE(i.discount_amount, 0) as discount_amount,
+                    i.total_amount, COALESCE(i.amount_paid, 0) as amount_paid
+                FROM invoices i
+                JOIN clients c ON i.client_id = c.id
+                LEFT JOIN invoice_labels l ON l.id = i.label_id AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
+                WHERE i.user_id = "#,
+            );
+            query_builder.push_bind(user_id);
+            push_list_filters(&mut query_builder, &filter);
+            query_builder.push(" ORDER BY i.created_at DESC");
+
+            let mut rows = query_builder.build().fetch(&db);
+            while let Some(row) = rows.next().await {
+                let row = row.and_then(|row| {
+                    let items: serde_json::Value = row.try_get("items")?;
+                    Ok(InvoiceExportRow {
+                        invoice_number: row.try_get("invoice_number")?,
+                        status: row.try_get("status")?,
+                        client_name: row.try_get("client_name")?,
+                        client_email: row.try_get("client_email")?,
+                        issue_date: row.try_get("issue_date")?,
+                        due_date: row.try_get("due_date")?,
+                        currency: row.try_get("currency")?,
+                        items: serde_json::from_value(items).unwrap_or_default(),
+                        subtotal: row.try_get("subtotal")?,
+                        tax_amount: row.try_get("tax_amount")?,
+                        discount_amount: row.try_get("discount_amount")?,
+                        total_amount: row.try_get("total_amount")?,
+                        amount_paid: row.try_get("amount_paid")?,
+                    })
+                });
+                let failed = row.is_err();
+                // Stop when the client goes away or the query fails
+                if tx.send(row).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|row| (row, rx)) }).boxed()
+    }
+
     pub async fn update(
         &self,
         user_id: Uuid,
@@ -542,53 +692,88 @@ impl InvoiceRepository {
         // Get existing invoice
         let existing = self.get_invoice_internal(user_id, invoice_id).await?;
 
+        let tax_included = update.tax_included.unwrap_or(existing.tax_included);
+        let discount_requested = update.discount_amount.unwrap_or(existing.discount_amount);
+
         // Calculate new values if items changed
         let mut items = existing.items;
         let mut subtotal = existing.subtotal;
         let mut tax_amount = existing.tax_amount;
+        let mut discount = discount_requested;
+        let mut total_amount = subtotal + tax_amount - discount;
 
         if let Some(new_items) = update.items {
-            items = Vec::new();
-            subtotal = 0.0;
-            tax_amount = 0.0;
-
-            for item in new_items {
-                let tax_rate = item.tax_rate.unwrap_or(0.0);
-                let item_subtotal = item.quantity * item.unit_price;
-                let item_tax = item_subtotal * tax_rate;
-                let item_total = item_subtotal + item_tax;
-
-                items.push(InvoiceItem {
+            let lines: Vec<LineInput> = new_items.iter()
+                .map(|item| LineInput {
+                    quantity: item.quantity,
+                    unit_price: item.unit_price,
+                    tax_rate: if item.taxes.is_empty() {
+                        item.tax_rate.unwrap_or_default()
+                    } else {
+                        TaxComponent::combined_rate(&item.taxes)
+                    },
+                })
+                .collect();
+            let rounding = self.rounding_rules.for_currency(&existing.currency);
+            let totals = InvoiceTotals::calculate(
+                &lines,
+                discount_requested,
+                tax_included,
+                RoundingPolicy::PerLine,
+                rounding,
+            );
+
+            items = new_items.into_iter()
+                .zip(lines.iter().zip(&totals.lines))
+                .map(|(item, (line, amounts))| InvoiceItem {
                     id: Uuid::new_v4(),
                     description: item.description,
                     quantity: item.quantity,
                     unit_price: item.unit_price,
-                    tax_rate,
-                    tax_amount: item_tax,
-                    total: item_total,
-                });
+                    tax_rate: line.tax_rate,
+                    tax_amount: amounts.tax_amount,
+                    total: amounts.total,
+                    section: item.section,
+                    taxes: TaxComponent::split(&item.taxes, amounts.tax_amount, rounding),
+                })
+                .collect();
 
-                subtotal += item_subtotal;
-                tax_amount += item_tax;
-            }
+            subtotal = totals.subtotal;
+            tax_amount = totals.tax_amount;
+            discount = totals.discount;
+            total_amount = totals.total;
         }
 
-        let discount = update.discount_amount.unwrap_or(existing.discount_amount);
-        let total_amount = subtotal + tax_amount - discount;
-
         // Update fields
         let client_id = update.client_id.unwrap_or(existing.client_id);
         let issue_date = update.issue_date.unwrap_or(existing.issue_date);
         let due_date = update.due_date.unwrap_or(existing.due_date);
         let notes = update.notes.unwrap_or(existing.notes.unwrap_or_default());
         let terms = update.terms.unwrap_or(existing.terms.unwrap_or_default());
-        let tax_included = update.tax_included.unwrap_or(existing.tax_included);
         let items_json = serde_json::to_value(&items).unwrap_or(serde_json::Value::Array(vec![]));
+        let tax_calculation = serde_json::json!({
+            "subtotal": subtotal,
+            "tax_amount": tax_amount,
+            "discount": discount,
+            "total": total_amount,
+            "taxes": TaxLine::breakdown(&items, existing.tax_label.as_deref()),
+        });
 
         // Partial payment settings
         let allow_partial_payment = update.allow_partial_payment.unwrap_or(existing.allow_partial_payment);
         let min_payment_amount = update.min_payment_amount;
 
+        // A deposit left out of the update is kept, following the new total if it's a percentage
+        let deposit = update.deposit.unwrap_or_else(|| DepositTerms {
+            amount: existing.deposit_percent.is_none().then_some(existing.deposit_amount).flatten(),
+            percent: existing.deposit_percent,
+            due_date: existing.deposit_due_date,
+        });
+        let deposit_amount = deposit.amount_for(total_amount, self.rounding_rules.for_currency(&existing.currency));
+        let deposit_due_date = deposit_amount.map(|_| deposit.due_date.unwrap_or(issue_date));
+
+        // A new expiry date re-arms the follow-up, and reopens an expired offer
+        // when it moves to today or later
         let invoice = sqlx::query_as::<_, InvoiceInsertRow>(
             r#"
             UPDATE invoices SET
@@ -596,7 +781,23 @@ impl InvoiceRepository {
                 notes = $5, terms = $6, discount_amount = $7, tax_included = $8,
                 subtotal = $9, tax_amount = $10, total_amount = $11,
                 allow_partial_payment = $12, min_payment_amount = $13,
-                updated_at = $14
+                updated_at = $14,
+                deposit_amount = $18, deposit_percent = $19, deposit_due_date = $20,
+                tax_calculation = $21,
+                deposit_paid_at = CASE
+                    WHEN $18 IS NULL THEN NULL
+                    WHEN deposit_paid_at IS NULL AND $18 <= COALESCE(amount_paid, 0) THEN $14
+                    ELSE deposit_paid_at END,
+                expires_at = COALESCE($17, expires_at),
+                expiry_reminder_sent_at = CASE
+                    WHEN $17 IS DISTINCT FROM expires_at AND $17 IS NOT NULL THEN NULL
+                    ELSE expiry_reminder_sent_at END,
+                status = CASE
+                    WHEN status = 'expired' AND $17 >= CURRENT_DATE THEN 'sent'
+                    ELSE status END,
+                expired_at = CASE
+                    WHEN status = 'expired' AND $17 >= CURRENT_DATE THEN NULL
+                    ELSE expired_at END
             WHERE id = $15 AND user_id = $16
             RETURNING *
             "#,
@@ -617,26 +818,355 @@ impl InvoiceRepository {
         .bind(Utc::now())
         .bind(invoice_id)
         .bind(user_id)
+        .bind(update.expires_at)
+        .bind(deposit_amount)
+        .bind(deposit_amount.and(deposit.percent))
+        .bind(deposit_due_date)
+        .bind(&tax_calculation)
         .fetch_one(&self.db)
         .await?;
 
         Ok(invoice.to_invoice())
     }
 
-    pub async fn delete(&self, user_id: Uuid, invoice_id: Uuid) -> Result<(), sqlx::Error> {
+    /// URL and fingerprint of the stored PDF, if one was recorded
+    pub async fn cached_pdf(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<(String, String)>, sqlx::Error> {
+        sqlx::query_as::<_, (String, String)>(
+            r#"
+            SELECT pdf_url, pdf_fingerprint FROM invoices
+            WHERE id = $1 AND user_id = $2 AND pdf_url IS NOT NULL AND pdf_fingerprint IS NOT NULL
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Record (or with None, clear) the stored PDF. Returns the URL it replaces.
+    pub async fn set_cached_pdf(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        pdf_url: Option<&str>,
+        fingerprint: Option<&str>,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let previous: Option<Option<String>> = sqlx::query_scalar(
+            r#"
+            UPDATE invoices i SET pdf_url = $3, pdf_fingerprint = $4
+            FROM (SELECT id, pdf_url FROM invoices WHERE id = $1 AND user_id = $2 FOR UPDATE) old
+            WHERE i.id = old.id
+            RETURNING old.pdf_url
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(pdf_url)
+        .bind(fingerprint)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(previous.flatten())
+    }
+
+    /// Only drafts are deleted. Returns false if the invoice isn't a draft (any more).
+    pub async fn delete_draft(&self, user_id: Uuid, invoice_id: Uuid) -> Result<bool, sqlx::Error> {
         let result = sqlx::query(
-            "DELETE FROM invoices WHERE id = $1 AND user_id = $2"
+            "DELETE FROM invoices WHERE id = $1 AND user_id = $2 AND status = 'draft'"
         )
         .bind(invoice_id)
         .bind(user_id)
         .execute(&self.db)
         .await?;
 
-        if result.rows_affected() == 0 {
-            return Err(sqlx::Error::RowNotFound);
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move a draft to the trash. Returns false if it isn't a draft (any more).
+    pub async fn trash_draft(&self, user_id: Uuid, invoice_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE invoices SET deleted_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'draft' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Client of an invoice in the trash
+    pub async fn find_trashed_client_id(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT client_id FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL"
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Take an invoice out of the trash. Returns false if it isn't in the trash.
+    pub async fn restore(&self, user_id: Uuid, invoice_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE invoices SET deleted_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NOT NULL
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move the invoice from `from` to `to`. Returns false, changing nothing, if
+    /// its status is no longer `from`.
+    pub async fn update_status(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        from: &InvoiceStatus,
+        to: &InvoiceStatus,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE invoices SET status = $1, updated_at = NOW() WHERE id = $2 AND user_id = $3 AND status = $4"
+        )
+        .bind(to.to_string())
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(from.to_string())
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel the source drafts of a consolidated invoice and link them to it.
+    /// Returns false (and changes nothing) if any source is no longer a draft.
+    pub async fn mark_consolidated(
+        &self,
+        user_id: Uuid,
+        source_ids: &[Uuid],
+        consolidated_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE invoices
+            SET status = 'cancelled', consolidated_into_id = $1, updated_at = $2
+            WHERE user_id = $3 AND id = ANY($4) AND status = 'draft' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(consolidated_id)
+        .bind(Utc::now())
+        .bind(user_id)
+        .bind(source_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != source_ids.len() as u64 {
+            tx.rollback().await?;
+            return Ok(false);
         }
 
-        Ok(())
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Mark an issued, unpaid invoice as superseded by its corrected version and link
+    /// both ways. Returns false if the original was paid or changed status meanwhile.
+    pub async fn mark_superseded(
+        &self,
+        user_id: Uuid,
+        original_id: Uuid,
+        corrected_id: Uuid,
+        reason: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE invoices
+            SET status = 'superseded', superseded_by_id = $1, updated_at = $2
+            WHERE user_id = $3 AND id = $4
+              AND status IN ('sent', 'viewed', 'overdue') AND amount_paid = 0
+            "#,
+        )
+        .bind(corrected_id)
+        .bind(now)
+        .bind(user_id)
+        .bind(original_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() != 1 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "UPDATE invoices SET supersedes_id = $1, correction_reason = $2, updated_at = $3 WHERE user_id = $4 AND id = $5",
+        )
+        .bind(original_id)
+        .bind(reason)
+        .bind(now)
+        .bind(user_id)
+        .bind(corrected_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Claims unpaid offers expiring between `from` and `until` whose follow-up
+    /// hasn't gone out, returning (user_id, invoice_id). Claiming first means a
+    /// follow-up is sent at most once, even with several instances running.
+    pub async fn claim_expiry_reminders(
+        &self,
+        from: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE invoices SET expiry_reminder_sent_at = NOW()
+            WHERE expires_at BETWEEN $1 AND $2
+              AND expiry_reminder_sent_at IS NULL
+              AND status IN ('sent', 'viewed', 'overdue') AND amount_paid = 0
+            RETURNING user_id, id
+            "#,
+        )
+        .bind(from)
+        .bind(until)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Marks unpaid offers whose last valid day is before `today` as expired,
+    /// returning (user_id, invoice_id) for each
+    pub async fn expire_lapsed(&self, today: NaiveDate) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE invoices SET status = 'expired', expired_at = NOW(), updated_at = NOW()
+            WHERE expires_at < $1
+              AND status IN ('sent', 'viewed', 'overdue') AND amount_paid = 0
+            RETURNING user_id, id
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Marks sent invoices whose due date is before `today` as overdue,
+    /// returning (user_id, invoice_id) for each
+    pub async fn mark_overdue(&self, today: NaiveDate) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE invoices SET status = 'overdue', updated_at = NOW()
+            WHERE due_date < $1 AND status IN ('sent', 'viewed')
+            RETURNING user_id, id
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Unpaid invoices due on or before `today`, for the reminder schedule. While
+    /// a deposit is outstanding its due date counts instead of the invoice's.
+    pub async fn reminder_candidates(&self, today: NaiveDate) -> Result<Vec<ReminderCandidate>, sqlx::Error> {
+        let rows: Vec<(Uuid, Uuid, NaiveDate, bool, i32, Option<serde_json::Value>)> = sqlx::query_as(
+            r#"
+            SELECT user_id, id, reminder_date, deposit_due, reminder_sent_count, notification_settings
+            FROM (
+                SELECT i.user_id, i.id, COALESCE(i.reminder_sent_count, 0) AS reminder_sent_count,
+                       u.notification_settings,
+                       i.deposit_amount IS NOT NULL AND i.deposit_paid_at IS NULL AS deposit_due,
+                       CASE WHEN i.deposit_amount IS NOT NULL AND i.deposit_paid_at IS NULL
+                            THEN COALESCE(i.deposit_due_date, i.issue_date)
+                            ELSE i.due_date END AS reminder_date
+                FROM invoices i
+                JOIN users u ON u.id = i.user_id
+                WHERE i.status IN ('sent', 'viewed', 'partial', 'overdue')
+                  AND i.total_amount > COALESCE(i.amount_paid, 0)
+            ) open_invoices
+            WHERE reminder_date <= $1
+            ORDER BY reminder_date
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, invoice_id, due_date, deposit, reminder_sent_count, settings)| ReminderCandidate {
+                user_id,
+                invoice_id,
+                due_date,
+                deposit,
+                reminder_sent_count,
+                settings: settings
+                    .and_then(|value| serde_json::from_value::<NotificationSettings>(value).ok())
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Records that the invoice has had `count` reminders, unless another run got
+    /// there first (its count is no longer `seen`). True when this call claimed it.
+    pub async fn claim_reminder(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        seen: i32,
+        count: i32,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE invoices SET reminder_sent_count = $4, last_reminder_sent = NOW(), updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND COALESCE(reminder_sent_count, 0) = $3
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(seen)
+        .bind(count)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Marks invoices sent between `sent_after` and `sent_before` that the client
+    /// still hasn't opened, returning (user_id, invoice_id) for each. Each invoice
+    /// is only returned once.
+    pub async fn claim_unviewed_reminders(
+        &self,
+        sent_after: DateTime<Utc>,
+        sent_before: DateTime<Utc>,
+    ) -> Result<Vec<(Uuid, Uuid)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE invoices SET unviewed_reminder_sent_at = NOW()
+            WHERE status = 'sent' AND sent_at BETWEEN $1 AND $2 AND viewed_at IS NULL
+              AND unviewed_reminder_sent_at IS NULL
+            RETURNING user_id, id
+            "#,
+        )
+        .bind(sent_after)
+        .bind(sent_before)
+        .fetch_all(&self.db)
+        .await
     }
 
     pub async fn record_payment(
@@ -648,17 +1178,7 @@ impl InvoiceRepository {
         // Get invoice
         let invoice = self.get_invoice_internal(user_id, invoice_id).await?;
 
-        // Validate partial payment settings
-        if !invoice.allow_partial_payment {
-            return Err(sqlx::Error::RowNotFound);
-        }
-
-        // Check minimum payment amount
-        if let Some(min_amount) = invoice.min_payment_amount {
-            if payment.amount < min_amount {
-                return Err(sqlx::Error::RowNotFound);
-            }
-        }
+        // Partial payment settings are checked by the caller against the balance
 
         // Update amount paid
         let new_amount_paid = invoice.amount_paid + payment.amount;
@@ -676,19 +1196,25 @@ impl InvoiceRepository {
         if new_amount_paid >= invoice.total_amount {
             status = InvoiceStatus::Paid;
             paid_at = Some(Utc::now());
-        } else if new_amount_paid > 0.0 {
-            if status != InvoiceStatus::Partial {
-                partial_payment_count += 1;
-            }
+        } else if new_amount_paid > Decimal::ZERO {
+            // Every payment that leaves a balance counts
+            partial_payment_count += 1;
             status = InvoiceStatus::Partial;
         }
 
-        // Update in database
+        // Update in database; the payment and its FX result are recorded with it
+        let mut tx = self.db.begin().await?;
         let updated = sqlx::query_as::<_, InvoiceInsertRow>(
             r#"
             UPDATE invoices SET
                 amount_paid = $1, status = $2, paid_at = $3,
-                partial_payment_count = $4, updated_at = $5
+                partial_payment_count = $4, updated_at = $5,
+                reminder_sent_count = CASE
+                    WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN 0
+                    ELSE reminder_sent_count END,
+                deposit_paid_at = CASE
+                    WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN $5
+                    ELSE deposit_paid_at END
             WHERE id = $6 AND user_id = $7
             RETURNING *
             "#,
@@ -700,10 +1226,11 @@ impl InvoiceRepository {
         .bind(Utc::now())
         .bind(invoice_id)
         .bind(user_id)
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
         // Create payment record
+        let payment_id = Uuid::new_v4();
         sqlx::query(
             r#"
             INSERT INTO payments (
@@ -712,7 +1239,7 @@ impl InvoiceRepository {
             ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
         )
-        .bind(Uuid::new_v4())
+        .bind(payment_id)
         .bind(invoice_id)
         .bind(user_id)
         .bind(payment.amount)
@@ -721,21 +1248,28 @@ impl InvoiceRepository {
         .bind("completed")
         .bind(Utc::now())
         .bind(Utc::now())
-        .execute(&self.db)
+        .execute(&mut *tx)
         .await?;
 
+        if let Some(settlement_rate) = payment.exchange_rate {
+            book_realized_gain_loss(&mut tx, invoice_id, Some(payment_id), payment.amount, settlement_rate).await?;
+        }
+        tx.commit().await?;
+
         Ok(updated.to_invoice())
     }
 
-    /// Record payment for guest checkout (no user_id check)
+    /// Record payment for guest checkout (no user_id check). The payment itself
+    /// is already stored as `payment_id`.
     pub async fn record_payment_guest(
         &self,
         invoice_id: Uuid,
+        payment_id: Uuid,
         payment: CreatePayment,
     ) -> Result<Invoice, sqlx::Error> {
         // Get user_id from invoice
         let user_id: Uuid = sqlx::query_scalar(
-            "SELECT user_id FROM invoices WHERE id = $1"
+            "SELECT user_id FROM invoices WHERE id = $1 AND deleted_at IS NULL"
         )
         .bind(invoice_id)
         .fetch_one(&self.db)
@@ -744,17 +1278,7 @@ impl InvoiceRepository {
         // Get invoice
         let invoice = self.get_invoice_internal(user_id, invoice_id).await?;
 
-        // Validate partial payment settings
-        if !invoice.allow_partial_payment {
-            return Err(sqlx::Error::RowNotFound);
-        }
-
-        // Check minimum payment amount
-        if let Some(min_amount) = invoice.min_payment_amount {
-            if payment.amount < min_amount {
-                return Err(sqlx::Error::RowNotFound);
-            }
-        }
+        // Partial payment settings are checked by the caller against the balance
 
         // Update amount paid
         let new_amount_paid = invoice.amount_paid + payment.amount;
@@ -772,19 +1296,25 @@ impl InvoiceRepository {
         if new_amount_paid >= invoice.total_amount {
             status = InvoiceStatus::Paid;
             paid_at = Some(Utc::now());
-        } else if new_amount_paid > 0.0 {
-            if status != InvoiceStatus::Partial {
-                partial_payment_count += 1;
-            }
+        } else if new_amount_paid > Decimal::ZERO {
+            // Every payment that leaves a balance counts
+            partial_payment_count += 1;
             status = InvoiceStatus::Partial;
         }
 
-        // Update in database
+        // Update in database; the payment and its FX result are recorded with it
+        let mut tx = self.db.begin().await?;
         let updated = sqlx::query_as::<_, InvoiceInsertRow>(
             r#"
             UPDATE invoices SET
                 amount_paid = $1, status = $2, paid_at = $3,
-                partial_payment_count = $4, updated_at = $5
+                partial_payment_count = $4, updated_at = $5,
+                reminder_sent_count = CASE
+                    WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN 0
+                    ELSE reminder_sent_count END,
+                deposit_paid_at = CASE
+                    WHEN deposit_paid_at IS NULL AND deposit_amount <= $1 THEN $5
+                    ELSE deposit_paid_at END
             WHERE id = $6
             RETURNING *
             "#,
@@ -795,57 +1325,52 @@ impl InvoiceRepository {
         .bind(partial_payment_count)
         .bind(Utc::now())
         .bind(invoice_id)
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if let Some(settlement_rate) = payment.exchange_rate {
+            book_realized_gain_loss(&mut tx, invoice_id, Some(payment_id), payment.amount, settlement_rate).await?;
+        }
+        tx.commit().await?;
+
         Ok(updated.to_invoice())
     }
 
-    pub async fn send_invoice(
+    /// Record an accepted send in one statement. A draft becomes sent; later statuses
+    /// (viewed, partial, paid, ...) are kept when an invoice is sent again.
+    pub async fn mark_sent(
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
-        email: Option<String>,
+        email_sent: bool,
+        whatsapp_sent: bool,
     ) -> Result<(), sqlx::Error> {
-        // Get client email
-        let _client_email = match email {
-            Some(e) => e,
-            None => {
-                sqlx::query_scalar::<_, String>(
-                    "SELECT email FROM clients WHERE id = (SELECT client_id FROM invoices WHERE id = $1 AND user_id = $2)"
-                )
-                .bind(invoice_id)
-                .bind(user_id)
-                .fetch_one(&self.db)
-                .await?
-            }
-        };
-
-        // Update status
         sqlx::query(
             r#"
             UPDATE invoices SET
-                status = 'sent', sent_at = $1, updated_at = $2
-            WHERE id = $3 AND user_id = $4
+                status = CASE WHEN status = 'draft' THEN 'sent' ELSE status END,
+                sent_at = $1,
+                notification_sent_at = CASE WHEN $2 THEN $1 ELSE notification_sent_at END,
+                whatsapp_sent_at = CASE WHEN $3 THEN $1 ELSE whatsapp_sent_at END,
+                updated_at = $1
+            WHERE id = $4 AND user_id = $5
             "#,
         )
         .bind(Utc::now())
-        .bind(Utc::now())
+        .bind(email_sent)
+        .bind(whatsapp_sent)
         .bind(invoice_id)
         .bind(user_id)
         .execute(&self.db)
         .await?;
 
-        // Note: Actual email sending happens in service layer
-        // This just updates the database
-
         Ok(())
     }
 
     // Internal helper
     async fn get_invoice_internal(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Invoice, sqlx::Error> {
         let row = sqlx::query_as::<_, InvoiceInsertRow>(
-            "SELECT * FROM invoices WHERE id = $1 AND user_id = $2"
+            "SELECT * FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL"
         )
         .bind(invoice_id)
         .bind(user_id)
@@ -895,11 +1420,11 @@ struct InvoiceRow {
     status: String,
     issue_date: NaiveDate,
     due_date: NaiveDate,
-    subtotal: f64,
-    tax_amount: f64,
-    discount_amount: f64,
-    total_amount: f64,
-    amount_paid: f64,
+    subtotal: Decimal,
+    tax_amount: Decimal,
+    discount_amount: Decimal,
+    total_amount: Decimal,
+    amount_paid: Decimal,
     items: serde_json::Value,
     notes: Option<String>,
     terms: Option<String>,
@@ -907,6 +1432,8 @@ struct InvoiceRow {
     tax_included: bool,
     tax_label: Option<String>,
     tax_id: Option<String>,
+    currency: String,
+    exchange_rate: Decimal,
     pdf_url: Option<String>,
     receipt_image_url: Option<String>,
     sent_at: Option<DateTime<Utc>>,
@@ -918,13 +1445,26 @@ struct InvoiceRow {
     whatsapp_sent_at: Option<DateTime<Utc>>,
     guest_payment_token: Option<String>,
     allow_partial_payment: bool,
-    min_payment_amount: Option<f64>,
+    min_payment_amount: Option<Decimal>,
     partial_payment_count: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     // Additional columns in invoices table
     payment_method: Option<String>,
     payment_reference: Option<String>,
+    consolidated_into_id: Option<Uuid>,
+    supersedes_id: Option<Uuid>,
+    superseded_by_id: Option<Uuid>,
+    correction_reason: Option<String>,
+    expires_at: Option<NaiveDate>,
+    expired_at: Option<DateTime<Utc>>,
+    project_id: Option<Uuid>,
+    deposit_amount: Option<Decimal>,
+    deposit_percent: Option<Decimal>,
+    deposit_due_date: Option<NaiveDate>,
+    deposit_paid_at: Option<DateTime<Utc>>,
+    reverse_charge: bool,
+    vat_country: Option<String>,
     // For detail query
     client_name: Option<String>,
     client_email: Option<String>,
@@ -945,11 +1485,11 @@ struct InvoiceInsertRow {
     status: String,
     issue_date: NaiveDate,
     due_date: NaiveDate,
-    subtotal: f64,
-    tax_amount: f64,
-    discount_amount: f64,
-    total_amount: f64,
-    amount_paid: f64,
+    subtotal: Decimal,
+    tax_amount: Decimal,
+    discount_amount: Decimal,
+    total_amount: Decimal,
+    amount_paid: Decimal,
     items: serde_json::Value,
     notes: Option<String>,
     terms: Option<String>,
@@ -957,6 +1497,8 @@ struct InvoiceInsertRow {
     tax_included: bool,
     tax_label: Option<String>,
     tax_id: Option<String>,
+    currency: String,
+    exchange_rate: Decimal,
     pdf_url: Option<String>,
     receipt_image_url: Option<String>,
     sent_at: Option<DateTime<Utc>>,
@@ -968,12 +1510,16 @@ struct InvoiceInsertRow {
     whatsapp_sent_at: Option<DateTime<Utc>>,
     guest_payment_token: Option<String>,
     allow_partial_payment: bool,
-    min_payment_amount: Option<f64>,
+    min_payment_amount: Option<Decimal>,
     partial_payment_count: i32,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
     payment_method: Option<String>,
     payment_reference: Option<String>,
+    deposit_amount: Option<Decimal>,
+    deposit_percent: Option<Decimal>,
+    deposit_due_date: Option<NaiveDate>,
+    deposit_paid_at: Option<DateTime<Utc>>,
 }
 
 impl InvoiceRow {
@@ -988,6 +1534,8 @@ impl InvoiceRow {
             "paid" => InvoiceStatus::Paid,
             "overdue" => InvoiceStatus::Overdue,
             "cancelled" => InvoiceStatus::Cancelled,
+            "superseded" => InvoiceStatus::Superseded,
+            "expired" => InvoiceStatus::Expired,
             _ => InvoiceStatus::Draft,
         };
 
@@ -1011,6 +1559,8 @@ impl InvoiceRow {
             tax_included: self.tax_included,
             tax_label: self.tax_label,
             tax_id: self.tax_id,
+            currency: self.currency,
+            exchange_rate: self.exchange_rate.to_f64().unwrap_or(1.0),
             pdf_url: self.pdf_url,
             receipt_image_url: self.receipt_image_url,
             sent_at: self.sent_at,
@@ -1024,6 +1574,10 @@ impl InvoiceRow {
             allow_partial_payment: self.allow_partial_payment,
             min_payment_amount: self.min_payment_amount,
             partial_payment_count: self.partial_payment_count,
+            deposit_amount: self.deposit_amount,
+            deposit_percent: self.deposit_percent,
+            deposit_due_date: self.deposit_due_date,
+            deposit_paid_at: self.deposit_paid_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -1040,6 +1594,8 @@ impl InvoiceRow {
             "paid" => InvoiceStatus::Paid,
             "overdue" => InvoiceStatus::Overdue,
             "cancelled" => InvoiceStatus::Cancelled,
+            "superseded" => InvoiceStatus::Superseded,
+            "expired" => InvoiceStatus::Expired,
             _ => InvoiceStatus::Draft,
         };
 
@@ -1055,6 +1611,7 @@ impl InvoiceRow {
             client_email: self.client_email,
             client_phone: self.client_phone,
             client_address: self.client_address,
+            client_locale: None,
             issue_date: self.issue_date,
             due_date: self.due_date,
             subtotal: self.subtotal,
@@ -1070,6 +1627,8 @@ impl InvoiceRow {
             tax_included: self.tax_included,
             tax_label: self.tax_label,
             tax_id: self.tax_id,
+            currency: self.currency,
+            exchange_rate: self.exchange_rate.to_f64().unwrap_or(1.0),
             pdf_url: self.pdf_url,
             receipt_image_url: self.receipt_image_url,
             sent_at: self.sent_at,
@@ -1083,6 +1642,24 @@ impl InvoiceRow {
             allow_partial_payment: self.allow_partial_payment,
             min_payment_amount: self.min_payment_amount,
             partial_payment_count: self.partial_payment_count,
+            reverse_charge: self.reverse_charge,
+            vat_country: self.vat_country,
+            client_vat_number: None,
+            deposit: InvoiceDeposit::from_columns(
+                self.deposit_amount,
+                self.deposit_percent,
+                self.deposit_due_date,
+                self.deposit_paid_at,
+                self.amount_paid,
+                self.issue_date,
+            ),
+            consolidated_into_id: self.consolidated_into_id,
+            supersedes_id: self.supersedes_id,
+            superseded_by_id: self.superseded_by_id,
+            correction_reason: self.correction_reason,
+            expires_at: self.expires_at,
+            expired_at: self.expired_at,
+            project_id: self.project_id,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -1101,6 +1678,8 @@ impl InvoiceInsertRow {
             "paid" => InvoiceStatus::Paid,
             "overdue" => InvoiceStatus::Overdue,
             "cancelled" => InvoiceStatus::Cancelled,
+            "superseded" => InvoiceStatus::Superseded,
+            "expired" => InvoiceStatus::Expired,
             _ => InvoiceStatus::Draft,
         };
 
@@ -1124,6 +1703,8 @@ impl InvoiceInsertRow {
             tax_included: self.tax_included,
             tax_label: self.tax_label,
             tax_id: self.tax_id,
+            currency: self.currency,
+            exchange_rate: self.exchange_rate.to_f64().unwrap_or(1.0),
             pdf_url: self.pdf_url,
             receipt_image_url: self.receipt_image_url,
             sent_at: self.sent_at,
@@ -1137,6 +1718,10 @@ impl InvoiceInsertRow {
             allow_partial_payment: self.allow_partial_payment,
             min_payment_amount: self.min_payment_amount,
             partial_payment_count: self.partial_payment_count,
+            deposit_amount: self.deposit_amount,
+            deposit_percent: self.deposit_percent,
+            deposit_due_date: self.deposit_due_date,
+            deposit_paid_at: self.deposit_paid_at,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -1172,12 +1757,15 @@ impl InvoiceDiscussionRow {
 }
 
 impl InvoiceRepository {
-    /// Mark invoice as viewed by buyer
+    /// Mark invoice as viewed by buyer. Keeps the first view time, and only a sent
+    /// invoice moves to viewed.
     pub async fn mark_as_viewed(&self, invoice_id: Uuid) -> Result<Invoice, sqlx::Error> {
-        let result = sqlx::query_as::<_, InvoiceRow>(
+        let result = sqlx::query_as::<_, InvoiceInsertRow>(
             r#"
             UPDATE invoices
-            SET viewed_at = $1, status = 'viewed', updated_at = $1
+            SET viewed_at = COALESCE(viewed_at, $1),
+                status = CASE WHEN status = 'sent' THEN 'viewed' ELSE status END,
+                updated_at = $1
             WHERE id = $2
             RETURNING *
             "#,
@@ -1216,6 +1804,134 @@ impl InvoiceRepository {
         Ok(())
     }
 
+    /// Record a send attempt with its exact recipients
+    pub async fn log_notification(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        notification: &NewInvoiceNotification,
+    ) -> Result<InvoiceNotification, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceNotificationRow>(
+            r#"
+            INSERT INTO invoice_notifications (
+                id, user_id, invoice_id, channel, recipients, cc, bcc, subject, message, status, error,
+                resent_from_id, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id, invoice_id, channel, recipients, cc, bcc, subject, message, status, error,
+                resent_from_id, created_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(invoice_id)
+        .bind(notification.channel.as_str())
+        .bind(&notification.recipients)
+        .bind(&notification.cc)
+        .bind(&notification.bcc)
+        .bind(&notification.subject)
+        .bind(&notification.message)
+        .bind(if notification.error.is_none() { "sent" } else { "failed" })
+        .bind(&notification.error)
+        .bind(notification.resent_from_id)
+        .bind(Utc::now())
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_notification())
+    }
+
+    pub async fn list_notifications(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<Vec<InvoiceNotification>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceNotificationRow>(
+            r#"
+            SELECT id, invoice_id, channel, recipients, cc, bcc, subject, message, status, error,
+                resent_from_id, created_at
+            FROM invoice_notifications
+            WHERE user_id = $1 AND invoice_id = $2
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(invoice_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(InvoiceNotificationRow::into_notification).collect())
+    }
+
+    pub async fn find_notification(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        notification_id: Uuid,
+    ) -> Result<Option<InvoiceNotification>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceNotificationRow>(
+            r#"
+            SELECT id, invoice_id, channel, recipients, cc, bcc, subject, message, status, error,
+                resent_from_id, created_at
+            FROM invoice_notifications
+            WHERE id = $1 AND user_id = $2 AND invoice_id = $3
+            "#,
+        )
+        .bind(notification_id)
+        .bind(user_id)
+        .bind(invoice_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceNotificationRow::into_notification))
+    }
+
+    /// The most recent send attempt on each channel
+    pub async fn latest_notifications(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<Vec<InvoiceNotification>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceNotificationRow>(
+            r#"
+            SELECT DISTINCT ON (channel)
+                id, invoice_id, channel, recipients, cc, bcc, subject, message, status, error,
+                resent_from_id, created_at
+            FROM invoice_notifications
+            WHERE user_id = $1 AND invoice_id = $2
+            ORDER BY channel, created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(invoice_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(InvoiceNotificationRow::into_notification).collect())
+    }
+
+    /// The invoice's pipeline label, if it applies to the current status
+    pub async fn active_label(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> Result<Option<InvoiceLabel>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceLabelRow>(
+            r#"
+            SELECT l.id, l.name, l.color, l.statuses, l.show_on_pdf, l.position, l.created_at, l.updated_at
+            FROM invoices i
+            JOIN invoice_labels l ON l.id = i.label_id
+            WHERE i.id = $1 AND i.user_id = $2
+              AND (cardinality(l.statuses) = 0 OR i.status = ANY(l.statuses))
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceLabelRow::into_label))
+    }
+
     /// Add a discussion message to an invoice
     pub async fn add_discussion_message(
         &self,
@@ -1275,7 +1991,7 @@ impl InvoiceRepository {
     ) -> Result<Vec<DiscussionResponse>, sqlx::Error> {
         // Verify invoice exists first
         let invoice_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1)"
+            "SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1 AND deleted_at IS NULL)"
         )
         .bind(invoice_id)
         .fetch_one(&self.db)
@@ -1296,7 +2012,7 @@ impl InvoiceRepository {
     ) -> Result<InvoiceDiscussion, sqlx::Error> {
         // Verify invoice exists first
         let invoice_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1)"
+            "SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1 AND deleted_at IS NULL)"
         )
         .bind(invoice_id)
         .fetch_one(&self.db)
@@ -1309,4 +2025,200 @@ impl InvoiceRepository {
         // Guests are always buyers
         self.add_discussion_message(invoice_id, SenderType::Buyer, message).await
     }
+
+    /// `reader`'s unread messages in an invoice's discussion: the other side's
+    /// messages since `reader` last read it
+    pub async fn discussion_unread(
+        &self,
+        invoice_id: Uuid,
+        reader: SenderType,
+    ) -> Result<DiscussionUnread, sqlx::Error> {
+        let (unread_count, last_message_at, last_read_at) =
+            sqlx::query_as::<_, (i64, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>(
+                r#"
+                SELECT
+                    COUNT(d.id) FILTER (
+                        WHERE d.sender_type = $3 AND d.created_at > COALESCE(p.last_read_at, '-infinity')
+                    ),
+                    MAX(d.created_at),
+                    MAX(p.last_read_at)
+                FROM (SELECT $1::uuid AS invoice_id) i
+                LEFT JOIN invoice_discussions d ON d.invoice_id = i.invoice_id
+                LEFT JOIN invoice_discussion_participants p
+                    ON p.invoice_id = i.invoice_id AND p.participant = $2
+                "#,
+            )
+            .bind(invoice_id)
+            .bind(reader.to_string())
+            .bind(reader.other().to_string())
+            .fetch_one(&self.db)
+            .await?;
+
+        Ok(DiscussionUnread { invoice_id, unread_count, last_message_at, last_read_at })
+    }
+
+    /// Record that `reader` has read the discussion up to `read_at`; an earlier
+    /// time never moves it back
+    pub async fn mark_discussion_read(
+        &self,
+        invoice_id: Uuid,
+        reader: SenderType,
+        read_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO invoice_discussion_participants (invoice_id, participant, last_read_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (invoice_id, participant) DO UPDATE
+            SET last_read_at = GREATEST(invoice_discussion_participants.last_read_at, EXCLUDED.last_read_at)
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(reader.to_string())
+        .bind(read_at)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Claim the next notification to `recipient` of new messages, unless one
+    /// went out after `throttle_since`; false when it's too soon
+    pub async fn claim_discussion_notification(
+        &self,
+        invoice_id: Uuid,
+        recipient: SenderType,
+        now: DateTime<Utc>,
+        throttle_since: DateTime<Utc>,
+    ) -> Result<bool, sqlx::Error> {
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO invoice_discussion_participants (invoice_id, participant, last_notified_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (invoice_id, participant) DO UPDATE
+            SET last_notified_at = EXCLUDED.last_notified_at
+            WHERE invoice_discussion_participants.last_notified_at IS NULL
+               OR invoice_discussion_participants.last_notified_at <= $4
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(recipient.to_string())
+        .bind(now)
+        .bind(throttle_since)
+        .execute(&self.db)
+        .await?;
+
+        Ok(claimed.rows_affected() > 0)
+    }
+
+    /// The user's invoices with buyer messages they haven't read, latest first
+    pub async fn unread_discussions(&self, user_id: Uuid) -> Result<Vec<UnreadDiscussion>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, (Uuid, String, String, i64, DateTime<Utc>)>(
+            r#"
+            SELECT i.id, i.invoice_number, c.name, COUNT(d.id), MAX(d.created_at)
+            FROM invoices i
+            JOIN clients c ON c.id = i.client_id
+            JOIN invoice_discussions d ON d.invoice_id = i.id AND d.sender_type = 'buyer'
+            LEFT JOIN invoice_discussion_participants p ON p.invoice_id = i.id AND p.participant = 'seller'
+            WHERE i.user_id = $1 AND i.deleted_at IS NULL
+              AND d.created_at > COALESCE(p.last_read_at, '-infinity')
+            GROUP BY i.id, i.invoice_number, c.name
+            ORDER BY MAX(d.created_at) DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(invoice_id, invoice_number, client_name, unread_count, last_message_at)| UnreadDiscussion {
+                invoice_id,
+                invoice_number,
+                client_name,
+                unread_count,
+                last_message_at,
+            })
+            .collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct InvoiceNotificationRow {
+    id: Uuid,
+    invoice_id: Uuid,
+    channel: String,
+    recipients: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: Option<String>,
+    message: Option<String>,
+    status: String,
+    error: Option<String>,
+    resent_from_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl InvoiceNotificationRow {
+    fn into_notification(self) -> InvoiceNotification {
+        InvoiceNotification {
+            id: self.id,
+            invoice_id: self.invoice_id,
+            channel: NotificationChannel::parse(&self.channel).unwrap_or(NotificationChannel::Email),
+            recipients: self.recipients,
+            cc: self.cc,
+            bcc: self.bcc,
+            subject: self.subject,
+            message: self.message,
+            delivered: self.status == "sent",
+            error: self.error,
+            resent_from_id: self.resent_from_id,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// The invoice list's filters, for a query over `invoices i`, `clients c` and
+/// the applicable label `l`. Invoices in the trash are always left out.
+fn push_list_filters(query_builder: &mut QueryBuilder<'_, Postgres>, filter: &InvoiceListFilter) {
+    query_builder.push(" AND i.deleted_at IS NULL");
+
+    if let Some(status) = &filter.status {
+        query_builder.push(" AND i.status = ");
+        query_builder.push_bind(status.to_string());
+    }
+
+    if let Some(client_id) = filter.client_id {
+        query_builder.push(" AND i.client_id = ");
+        query_builder.push_bind(client_id);
+    }
+
+    if let Some(date_from) = filter.date_from {
+        query_builder.push(" AND i.issue_date >= ");
+        query_builder.push_bind(date_from);
+    }
+
+    if let Some(date_to) = filter.date_to {
+        query_builder.push(" AND i.issue_date <= ");
+        query_builder.push_bind(date_to);
+    }
+
+    if let Some(search) = &filter.search {
+        query_builder.push(" AND (c.name ILIKE ");
+        query_builder.push_bind(format!("%{}%", search));
+        query_builder.push(" OR i.invoice_number ILIKE ");
+        query_builder.push_bind(format!("%{}%", search));
+        query_builder.push(")");
+    }
+
+    if let Some(label_id) = filter.label_id {
+        query_builder.push(" AND l.id = ");
+        query_builder.push_bind(label_id);
+    }
+
+    if let Some(ids) = &filter.ids {
+        query_builder.push(" AND i.id = ANY(");
+        query_builder.push_bind(ids.clone());
+        query_builder.push(")");
+    }
 }
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::InvoiceTemplate;
+
+#[derive(Clone)]
+pub struct InvoiceTemplateRepository {
+    db: PgPool,
+}
+
+impl InvoiceTemplateRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<InvoiceTemplate>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceTemplateRow>(
+            "SELECT * FROM invoice_templates WHERE user_id = $1 ORDER BY LOWER(name), id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(InvoiceTemplateRow::into_template).collect())
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, template_id: Uuid) -> Result<Option<InvoiceTemplate>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceTemplateRow>("SELECT * FROM invoice_templates WHERE id = $1 AND user_id = $2")
+            .bind(template_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(InvoiceTemplateRow::into_template))
+    }
+
+    pub async fn create(&self, user_id: Uuid, template: &InvoiceTemplate) -> Result<InvoiceTemplate, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceTemplateRow>(
+            r#"
+            INSERT INTO invoice_templates (
+                id, user_id, name, items, notes, terms, discount_amount, tax_included, currency, due_days,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $11)
+            RETURNING *
+            "#,
+        )
+        .bind(template.id)
+        .bind(user_id)
+        .bind(&template.name)
+        .bind(items_json(template))
+        .bind(&template.notes)
+        .bind(&template.terms)
+        .bind(template.discount_amount)
+        .bind(template.tax_included)
+        .bind(&template.currency)
+        .bind(template.due_days)
+        .bind(template.created_at)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_template())
+    }
+
+    pub async fn update(&self, user_id: Uuid, template: &InvoiceTemplate) -> Result<Option<InvoiceTemplate>, sqlx::Error> {
+        let row = sqlx::query_as::<_, InvoiceTemplateRow>(
+            r#"
+            UPDATE invoice_templates
+            SET name = $3, items = $4, notes = $5, terms = $6, discount_amount = $7, tax_included = $8,
+                currency = $9, due_days = $10, updated_at = $11
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(template.id)
+        .bind(user_id)
+        .bind(&template.name)
+        .bind(items_json(template))
+        .bind(&template.notes)
+        .bind(&template.terms)
+        .bind(template.discount_amount)
+        .bind(template.tax_included)
+        .bind(&template.currency)
+        .bind(template.due_days)
+        .bind(template.updated_at)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(InvoiceTemplateRow::into_template))
+    }
+
+    pub async fn delete(&self, user_id: Uuid, template_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM invoice_templates WHERE id = $1 AND user_id = $2")
+            .bind(template_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn items_json(template: &InvoiceTemplate) -> serde_json::Value {
+    serde_json::to_value(&template.items).unwrap_or(serde_json::Value::Array(vec![]))
+}
+
+#[derive(sqlx::FromRow)]
+struct InvoiceTemplateRow {
+    id: Uuid,
+    #[allow(dead_code)]
+    user_id: Uuid,
+    name: String,
+    items: serde_json::Value,
+    notes: Option<String>,
+    terms: Option<String>,
+    discount_amount: Option<Decimal>,
+    tax_included: bool,
+    currency: Option<String>,
+    due_days: Option<i32>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl InvoiceTemplateRow {
+    fn into_template(self) -> InvoiceTemplate {
+        InvoiceTemplate {
+            id: self.id,
+            name: self.name,
+            items: serde_json::from_value(self.items).unwrap_or_default(),
+            notes: self.notes,
+            terms: self.terms,
+            discount_amount: self.discount_amount,
+            tax_included: self.tax_included,
+            currency: self.currency,
+            due_days: self.due_days,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
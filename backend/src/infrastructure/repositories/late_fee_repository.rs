@@ -0,0 +1,278 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    AppliedLateFee, CreateAuditLog, InvoiceItem, LateFeeCandidate, LateFeePolicy, LateFeeRecurrence,
+    LateFeeType, UpdateLateFeePolicy,
+};
+use crate::infrastructure::repositories::insert_audit_log;
+
+/// A fee ready to be added to an invoice
+pub struct NewLateFee<'a> {
+    pub user_id: Uuid,
+    pub invoice_id: Uuid,
+    pub sequence: i32,
+    pub item: &'a InvoiceItem,
+    pub days_overdue: i32,
+    pub applied_at: DateTime<Utc>,
+    pub audit: &'a CreateAuditLog,
+}
+
+#[derive(Clone)]
+pub struct LateFeeRepository {
+    db: PgPool,
+}
+
+impl LateFeeRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_policy(&self, user_id: Uuid) -> Result<Option<LateFeePolicy>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PolicyRow>(
+            r#"
+            SELECT enabled, fee_type, amount, grace_days, recurrence, max_applications, updated_at
+            FROM late_fee_policies
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(PolicyRow::into_policy))
+    }
+
+    pub async fn upsert_policy(&self, user_id: Uuid, update: &UpdateLateFeePolicy) -> Result<LateFeePolicy, sqlx::Error> {
+        let row = sqlx::query_as::<_, PolicyRow>(
+            r#"
+            INSERT INTO late_fee_policies (user_id, enabled, fee_type, amount, grace_days, recurrence, max_applications)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id) DO UPDATE SET
+                enabled = EXCLUDED.enabled,
+                fee_type = EXCLUDED.fee_type,
+                amount = EXCLUDED.amount,
+                grace_days = EXCLUDED.grace_days,
+                recurrence = EXCLUDED.recurrence,
+                max_applications = EXCLUDED.max_applications,
+                updated_at = NOW()
+            RETURNING enabled, fee_type, amount, grace_days, recurrence, max_applications, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(update.enabled)
+        .bind(update.fee_type.as_str())
+        .bind(update.amount)
+        .bind(update.grace_days)
+        .bind(update.recurrence.as_str())
+        .bind(update.max_applications)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_policy())
+    }
+
+    /// Unpaid invoices past their due date whose owner has late fees enabled,
+    /// with the fees charged on each so far
+    pub async fn candidates(&self, today: NaiveDate) -> Result<Vec<LateFeeCandidate>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, CandidateRow>(
+            r#"
+            SELECT
+                i.user_id, i.id as invoice_id, i.invoice_number, i.due_date,
+                i.total_amount - COALESCE(i.amount_paid, 0) as balance,
+                COUNT(f.id)::int4 as applied,
+                (MAX(f.applied_at) AT TIME ZONE 'UTC')::date as last_applied_on,
+                p.enabled, p.fee_type, p.amount, p.grace_days, p.recurrence, p.max_applications, p.updated_at
+            FROM invoices i
+            JOIN late_fee_policies p ON p.user_id = i.user_id AND p.enabled
+            LEFT JOIN invoice_late_fees f ON f.invoice_id = i.id
+            WHERE i.due_date < $1
+              AND i.status IN ('overdue', 'partial')
+              AND i.total_amount > COALESCE(i.amount_paid, 0)
+            GROUP BY i.id, p.user_id
+            ORDER BY i.due_date
+            "#,
+        )
+        .bind(today)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(CandidateRow::into_candidate).collect())
+    }
+
+    /// Append the fee to the invoice as a line item and record it with its audit
+    /// entry, all in one transaction. Returns None when the invoice was paid in the
+    /// meantime or another run already charged this fee.
+    pub async fn apply(&self, fee: NewLateFee<'_>) -> Result<Option<AppliedLateFee>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let open: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM invoices
+            WHERE id = $1 AND user_id = $2
+              AND status IN ('overdue', 'partial')
+              AND total_amount > COALESCE(amount_paid, 0)
+            FOR UPDATE
+            "#,
+        )
+        .bind(fee.invoice_id)
+        .bind(fee.user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        if open.is_none() {
+            return Ok(None);
+        }
+
+        let applied = sqlx::query_as::<_, AppliedRow>(
+            r#"
+            INSERT INTO invoice_late_fees (id, user_id, invoice_id, item_id, sequence, amount, days_overdue, applied_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (invoice_id, sequence) DO NOTHING
+            RETURNING id, invoice_id, item_id, sequence, amount, days_overdue, applied_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(fee.user_id)
+        .bind(fee.invoice_id)
+        .bind(fee.item.id)
+        .bind(fee.sequence)
+        .bind(fee.item.total)
+        .bind(fee.days_overdue)
+        .bind(fee.applied_at)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(applied) = applied else {
+            return Ok(None);
+        };
+
+        let item_json = serde_json::to_value(fee.item).unwrap_or(serde_json::Value::Null);
+        sqlx::query(
+            r#"
+            UPDATE invoices SET
+                items = COALESCE(items, '[]'::jsonb) || jsonb_build_array($1::jsonb),
+                subtotal = subtotal + $2,
+                total_amount = total_amount + $2,
+                tax_calculation = COALESCE(tax_calculation, '{}'::jsonb)
+                    || jsonb_build_object('subtotal', subtotal + $2, 'total', total_amount + $2),
+                updated_at = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(item_json)
+        .bind(fee.item.total)
+        .bind(fee.applied_at)
+        .bind(fee.invoice_id)
+        .execute(&mut *tx)
+        .await?;
+
+        insert_audit_log(&mut tx, fee.audit).await?;
+        tx.commit().await?;
+
+        Ok(Some(applied.into_applied()))
+    }
+
+    pub async fn invoice_exists(&self, user_id: Uuid, invoice_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM invoices WHERE id = $1 AND user_id = $2)")
+            .bind(invoice_id)
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await
+    }
+
+    pub async fn list_for_invoice(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Vec<AppliedLateFee>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, AppliedRow>(
+            r#"
+            SELECT id, invoice_id, item_id, sequence, amount, days_overdue, applied_at
+            FROM invoice_late_fees
+            WHERE invoice_id = $1 AND user_id = $2
+            ORDER BY sequence
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(AppliedRow::into_applied).collect())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PolicyRow {
+    enabled: bool,
+    fee_type: String,
+    amount: Decimal,
+    grace_days: i32,
+    recurrence: String,
+    max_applications: Option<i32>,
+    updated_at: DateTime<Utc>,
+}
+
+impl PolicyRow {
+    fn into_policy(self) -> LateFeePolicy {
+        LateFeePolicy {
+            enabled: self.enabled,
+            fee_type: LateFeeType::parse(&self.fee_type).unwrap_or(LateFeeType::Flat),
+            amount: self.amount,
+            grace_days: self.grace_days,
+            recurrence: LateFeeRecurrence::parse(&self.recurrence).unwrap_or_default(),
+            max_applications: self.max_applications,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct CandidateRow {
+    user_id: Uuid,
+    invoice_id: Uuid,
+    invoice_number: String,
+    due_date: NaiveDate,
+    balance: Decimal,
+    applied: i32,
+    last_applied_on: Option<NaiveDate>,
+    #[sqlx(flatten)]
+    policy: PolicyRow,
+}
+
+impl CandidateRow {
+    fn into_candidate(self) -> LateFeeCandidate {
+        LateFeeCandidate {
+            user_id: self.user_id,
+            invoice_id: self.invoice_id,
+            invoice_number: self.invoice_number,
+            due_date: self.due_date,
+            balance: self.balance,
+            applied: self.applied,
+            last_applied_on: self.last_applied_on,
+            policy: self.policy.into_policy(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct AppliedRow {
+    id: Uuid,
+    invoice_id: Uuid,
+    item_id: Uuid,
+    sequence: i32,
+    amount: Decimal,
+    days_overdue: i32,
+    applied_at: DateTime<Utc>,
+}
+
+impl AppliedRow {
+    fn into_applied(self) -> AppliedLateFee {
+        AppliedLateFee {
+            id: self.id,
+            invoice_id: self.invoice_id,
+            item_id: self.item_id,
+            sequence: self.sequence,
+            amount: self.amount,
+            days_overdue: self.days_overdue,
+            applied_at: self.applied_at,
+        }
+    }
+}
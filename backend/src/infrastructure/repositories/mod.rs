@@ -5,6 +5,41 @@ pub mod report_repository;
 pub mod payment_repository;
 pub mod expense_repository;
 pub mod tax_repository_impl;
+pub mod fx_repository;
+pub mod document_number_repository;
+pub mod automation_issue_repository;
+pub mod statement_delivery_repository;
+pub mod budget_repository;
+pub mod payout_repository;
+pub mod business_repository;
+pub mod accountant_access_repository;
+pub mod campaign_repository;
+pub mod invoice_cost_repository;
+pub mod sync_repository;
+pub mod invoice_label_repository;
+pub mod email_signature_repository;
+pub mod email_template_repository;
+pub mod custom_report_repository;
+pub mod tenant_key_repository;
+pub mod client_import_repository;
+pub mod credit_note_repository;
+pub mod idempotency_repository;
+pub mod guest_token_repository;
+pub mod attachment_repository;
+pub mod audit_log_repository;
+pub mod late_fee_repository;
+pub mod webhook_repository;
+pub mod client_import_job_repository;
+pub mod pagination;
+pub mod search_repository;
+pub mod trash_repository;
+pub mod client_account_repository;
+pub mod receipt_scan_repository;
+pub mod time_entry_repository;
+pub mod project_repository;
+pub mod realtime_event_repository;
+pub mod invoice_template_repository;
+pub mod client_credit_repository;
 
 pub use invoice_repository::*;
 pub use user_repository::*;
@@ -13,3 +48,38 @@ pub use report_repository::*;
 pub use payment_repository::*;
 pub use expense_repository::*;
 pub use tax_repository_impl::*;
+pub use fx_repository::*;
+pub use document_number_repository::*;
+pub use automation_issue_repository::*;
+pub use statement_delivery_repository::*;
+pub use budget_repository::*;
+pub use payout_repository::*;
+pub use business_repository::*;
+pub use accountant_access_repository::*;
+pub use campaign_repository::*;
+pub use invoice_cost_repository::*;
+pub use sync_repository::*;
+pub use invoice_label_repository::*;
+pub use email_signature_repository::*;
+pub use email_template_repository::*;
+pub use custom_report_repository::*;
+pub use tenant_key_repository::*;
+pub use client_import_repository::*;
+pub use credit_note_repository::*;
+pub use idempotency_repository::*;
+pub use guest_token_repository::*;
+pub use attachment_repository::*;
+pub use audit_log_repository::*;
+pub use late_fee_repository::*;
+pub use webhook_repository::*;
+pub use client_import_job_repository::*;
+pub use pagination::*;
+pub use search_repository::*;
+pub use trash_repository::*;
+pub use client_account_repository::*;
+pub use receipt_scan_repository::*;
+pub use time_entry_repository::*;
+pub use project_repository::*;
+pub use realtime_event_repository::*;
+pub use invoice_template_repository::*;
+pub use client_credit_repository::*;
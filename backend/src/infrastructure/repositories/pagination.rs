@@ -0,0 +1,26 @@
+use sqlx::{Postgres, QueryBuilder};
+
+use crate::domain::models::PageRequest;
+
+/// Keeps a cursor page to the rows after its cursor. Goes with the WHERE
+/// clause; `created_at` and `id` are the list's columns, table alias included.
+pub fn push_page_after(query_builder: &mut QueryBuilder<'_, Postgres>, page: &PageRequest, created_at: &str, id: &str) {
+    if let Some(after) = page.after {
+        query_builder.push(format!(" AND ({}, {}) < (", created_at, id));
+        query_builder.push_bind(after.created_at);
+        query_builder.push(", ");
+        query_builder.push_bind(after.id);
+        query_builder.push(")");
+    }
+}
+
+/// Newest first, with the ID breaking ties so cursors land on a single row,
+/// then the page's window plus one row to tell whether another page follows
+pub fn push_page_window(query_builder: &mut QueryBuilder<'_, Postgres>, page: &PageRequest, created_at: &str, id: &str) {
+    query_builder.push(format!(" ORDER BY {} DESC, {} DESC LIMIT ", created_at, id));
+    query_builder.push_bind(page.fetch_limit());
+    if page.offset > 0 {
+        query_builder.push(" OFFSET ");
+        query_builder.push_bind(page.offset);
+    }
+}
@@ -1,8 +1,11 @@
 use sqlx::{PgPool, QueryBuilder, Postgres, Row};
 use uuid::Uuid;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 
-use crate::domain::models::{Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod};
+use crate::domain::models::{BankTransferPayment, Page, PageCursor, PageRequest, Payment, PaymentResponse, PaymentStats, PaymentStatus, PaymentMethod};
+use super::fx_repository::book_realized_gain_loss;
+use super::pagination::{push_page_after, push_page_window};
 
 #[derive(Clone)]
 pub struct PaymentRepository {
@@ -18,15 +21,18 @@ impl PaymentRepository {
         &self,
         user_id: Uuid,
         invoice_id: Uuid,
-        amount: f64,
+        amount: Decimal,
         currency: String,
         payment_method: PaymentMethod,
         gateway: Option<String>,
         gateway_payment_id: Option<String>,
-        gateway_fee: Option<f64>,
+        gateway_fee: Option<Decimal>,
         paid_by: Option<String>,
         notes: Option<String>,
+        settlement_rate: Option<f64>,
     ) -> Result<Payment, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
         let payment = sqlx::query_as::<_, PaymentRow>(
             r#"
             INSERT INTO payments (
@@ -45,26 +51,34 @@ impl PaymentRepository {
         .bind(payment_method.to_string())
         .bind(&gateway)
         .bind(&gateway_payment_id)
-        .bind(gateway_fee.unwrap_or(0.0))
+        .bind(gateway_fee.unwrap_or_default())
         .bind("completed") // status
         .bind(&paid_by)
         .bind(notes)
         .bind(Utc::now())
         .bind(Utc::now())
-        .fetch_one(&self.db)
+        .fetch_one(&mut *tx)
         .await?;
 
+        // Booked with the payment, so neither is recorded without the other
+        if let Some(settlement_rate) = settlement_rate {
+            book_realized_gain_loss(&mut tx, invoice_id, Some(payment.id), amount, settlement_rate).await?;
+        }
+        tx.commit().await?;
+
         Ok(payment.to_payment())
     }
 
-    /// Create payment using CreatePayment struct (for guest checkout)
+    /// Create payment using CreatePayment struct (for guest checkout). Gateway payments
+    /// awaiting confirmation are stored as pending and settled by the gateway's webhook.
     pub async fn create_payment(
         &self,
         create: crate::domain::models::payment::CreatePayment,
+        status: PaymentStatus,
     ) -> Result<Payment, sqlx::Error> {
-        // Get user_id from invoice
-        let user_id: Uuid = sqlx::query_scalar(
-            "SELECT user_id FROM invoices WHERE id = $1"
+        // The payment is the invoice owner's, in the invoice's currency
+        let (user_id, currency): (Uuid, String) = sqlx::query_as(
+            "SELECT user_id, currency FROM invoices WHERE id = $1"
         )
         .bind(create.invoice_id)
         .fetch_one(&self.db)
@@ -84,12 +98,12 @@ impl PaymentRepository {
         .bind(create.invoice_id)
         .bind(user_id)
         .bind(create.amount)
-        .bind("USD") // default currency
+        .bind(currency)
         .bind(create.payment_method.to_string())
         .bind(&create.gateway)
         .bind(&create.gateway_payment_id)
-        .bind(create.gateway_fee.unwrap_or(0.0))
-        .bind("completed") // status
+        .bind(create.gateway_fee.unwrap_or_default())
+        .bind(status.to_string())
         .bind(&create.paid_by)
         .bind(&create.notes)
         .bind(Utc::now())
@@ -100,6 +114,159 @@ impl PaymentRepository {
         Ok(payment.to_payment())
     }
 
+    /// Complete a pending gateway payment and add it to its invoice's amount paid.
+    /// Returns the invoice ID, or None when no such payment is still pending, so
+    /// redelivered gateway events settle an invoice only once.
+    pub async fn settle_pending_gateway_payment(
+        &self,
+        gateway: &str,
+        gateway_payment_id: &str,
+        settlement_rate: Option<f64>,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let claimed: Option<(Uuid, Uuid, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE payments SET status = 'completed', updated_at = NOW()
+            WHERE gateway = $1 AND gateway_payment_id = $2 AND status = 'pending'
+            RETURNING id, invoice_id, amount
+            "#,
+        )
+        .bind(gateway)
+        .bind(gateway_payment_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((payment_id, invoice_id, amount)) = claimed else {
+            return Ok(None);
+        };
+
+        apply_payment_to_invoice(&mut tx, payment_id, invoice_id, amount, settlement_rate).await?;
+        tx.commit().await?;
+        Ok(Some(invoice_id))
+    }
+
+    /// The invoice a gateway payment was recorded against, under any of its references
+    pub async fn find_gateway_payment_invoice(
+        &self,
+        gateway: &str,
+        references: &[String],
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT invoice_id FROM payments WHERE gateway = $1 AND gateway_payment_id = ANY($2) LIMIT 1")
+            .bind(gateway)
+            .bind(references)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Mark a pending gateway payment failed; returns whether one was still pending
+    pub async fn fail_pending_gateway_payment(&self, gateway: &str, gateway_payment_id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE payments SET status = 'failed', updated_at = NOW()
+            WHERE gateway = $1 AND gateway_payment_id = $2 AND status = 'pending'
+            "#,
+        )
+        .bind(gateway)
+        .bind(gateway_payment_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Point a payment at the gateway's final reference, e.g. from a checkout
+    /// session to the payment intent that payouts report
+    pub async fn update_gateway_payment_id(&self, gateway: &str, from: &str, to: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE payments SET gateway_payment_id = $3, updated_at = NOW() WHERE gateway = $1 AND gateway_payment_id = $2")
+            .bind(gateway)
+            .bind(from)
+            .bind(to)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The user's bank transfer and ACH payments in a status, oldest first
+    pub async fn list_bank_transfers(&self, user_id: Uuid, status: &PaymentStatus) -> Result<Vec<BankTransferPayment>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, BankTransferRow>(&format!(
+            "{} AND p.status = $2 ORDER BY p.created_at, p.id",
+            BANK_TRANSFER_SELECT
+        ))
+        .bind(user_id)
+        .bind(status.to_string())
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(BankTransferRow::into_payment).collect())
+    }
+
+    pub async fn find_bank_transfer(&self, user_id: Uuid, payment_id: Uuid) -> Result<Option<BankTransferPayment>, sqlx::Error> {
+        let row = sqlx::query_as::<_, BankTransferRow>(&format!("{} AND p.id = $2", BANK_TRANSFER_SELECT))
+            .bind(user_id)
+            .bind(payment_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(BankTransferRow::into_payment))
+    }
+
+    /// Complete a pending bank transfer with the statement's reference and add it
+    /// to the invoice's amount paid. Returns false when it's no longer pending.
+    pub async fn confirm_bank_transfer(
+        &self,
+        user_id: Uuid,
+        payment_id: Uuid,
+        bank_reference: &str,
+        received_on: NaiveDate,
+        settlement_rate: Option<f64>,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let claimed: Option<(Uuid, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE payments SET
+                status = 'completed', bank_reference = $3, received_on = $4,
+                reconciled_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+                AND payment_method IN ('bank_transfer', 'ach_debit')
+            RETURNING invoice_id, amount
+            "#,
+        )
+        .bind(payment_id)
+        .bind(user_id)
+        .bind(bank_reference)
+        .bind(received_on)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((invoice_id, amount)) = claimed else {
+            return Ok(false);
+        };
+
+        apply_payment_to_invoice(&mut tx, payment_id, invoice_id, amount, settlement_rate).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Mark a pending bank transfer failed; returns false when it's no longer pending
+    pub async fn reject_bank_transfer(&self, user_id: Uuid, payment_id: Uuid, reason: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE payments SET
+                status = 'failed', failure_reason = $3, reconciled_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+                AND payment_method IN ('bank_transfer', 'ach_debit')
+            "#,
+        )
+        .bind(payment_id)
+        .bind(user_id)
+        .bind(reason)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
     pub async fn find_by_id(&self, user_id: Uuid, payment_id: Uuid) -> Result<Option<PaymentResponse>, sqlx::Error> {
         let payment = sqlx::query_as::<_, PaymentResponseRow>(
             r#"
@@ -119,6 +286,7 @@ impl PaymentRepository {
         Ok(payment.map(|p| p.to_payment_response()))
     }
 
+    /// One page of the user's payments, newest first
     pub async fn list(
         &self,
         user_id: Uuid,
@@ -126,9 +294,13 @@ impl PaymentRepository {
         payment_method: Option<PaymentMethod>,
         date_from: Option<DateTime<Utc>>,
         date_to: Option<DateTime<Utc>>,
-        limit: Option<i64>,
-        offset: Option<i64>,
-    ) -> Result<Vec<PaymentResponse>, sqlx::Error> {
+        page: &PageRequest,
+    ) -> Result<Page<PaymentResponse>, sqlx::Error> {
+        let mut count_builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM payments p WHERE p.user_id = ");
+        count_builder.push_bind(user_id);
+        push_list_filters(&mut count_builder, status.as_ref(), payment_method.as_ref(), date_from, date_to);
+        let total: i64 = count_builder.build_query_scalar().fetch_one(&self.db).await?;
+
         let mut query_builder = QueryBuilder::<Postgres>::new(
             r#"
             SELECT
@@ -140,52 +312,43 @@ impl PaymentRepository {
         );
 
         query_builder.push_bind(user_id);
-
-        if let Some(s) = status {
-            query_builder.push(" AND p.status = ");
-            query_builder.push_bind(s.to_string());
-        }
-
-        if let Some(pm) = payment_method {
-            query_builder.push(" AND p.payment_method = ");
-            query_builder.push_bind(pm.to_string());
-        }
-
-        if let Some(df) = date_from {
-            query_builder.push(" AND p.created_at >= ");
-            query_builder.push_bind(df);
-        }
-
-        if let Some(dt) = date_to {
-            query_builder.push(" AND p.created_at <= ");
-            query_builder.push_bind(dt);
-        }
-
-        query_builder.push(" ORDER BY p.created_at DESC");
-
-        if let Some(l) = limit {
-            query_builder.push(" LIMIT ");
-            query_builder.push_bind(l);
-        }
-
-        if let Some(o) = offset {
-            query_builder.push(" OFFSET ");
-            query_builder.push_bind(o);
-        }
+        push_list_filters(&mut query_builder, status.as_ref(), payment_method.as_ref(), date_from, date_to);
+        push_page_after(&mut query_builder, page, "p.created_at", "p.id");
+        push_page_window(&mut query_builder, page, "p.created_at", "p.id");
 
         let payments = query_builder
             .build_query_as()
             .fetch_all(&self.db)
             .await?;
 
-        Ok(payments.into_iter().map(|p: PaymentResponseRow| p.to_payment_response()).collect())
+        let payments = payments.into_iter().map(|p: PaymentResponseRow| p.to_payment_response()).collect();
+        Ok(Page::new(page, payments, total, |payment| PageCursor::new(payment.created_at, payment.id)))
+    }
+
+    pub async fn find_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<PaymentResponse>, sqlx::Error> {
+        let payments = sqlx::query_as::<_, PaymentResponseRow>(
+            r#"
+            SELECT
+                p.*,
+                i.invoice_number
+            FROM payments p
+            LEFT JOIN invoices i ON p.invoice_id = i.id
+            WHERE p.user_id = $1 AND p.id = ANY($2)
+            "#,
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(payments.into_iter().map(|p| p.to_payment_response()).collect())
     }
 
     pub async fn refund(
         &self,
         user_id: Uuid,
         payment_id: Uuid,
-        amount: Option<f64>,
+        amount: Option<Decimal>,
         reason: String,
     ) -> Result<Payment, sqlx::Error> {
         // Get original payment
@@ -218,7 +381,7 @@ impl PaymentRepository {
         .bind(original.payment_method)
         .bind(&original.gateway)
         .bind(&original.gateway_payment_id)
-        .bind(0.0)
+        .bind(Decimal::ZERO)
         .bind("refunded")
         .bind(&original.paid_by)
         .bind(Some(format!("Refund: {}", reason)))
@@ -266,17 +429,104 @@ impl PaymentRepository {
     }
 }
 
+/// Add a completed payment to its invoice, settling it once nothing is left to pay,
+/// and book its realized FX gain or loss when it settled at `settlement_rate`
+async fn apply_payment_to_invoice(
+    tx: &mut sqlx::Transaction<'_, Postgres>,
+    payment_id: Uuid,
+    invoice_id: Uuid,
+    amount: Decimal,
+    settlement_rate: Option<f64>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        r#"
+        UPDATE invoices SET
+            amount_paid = COALESCE(amount_paid, 0) + $1,
+            partial_payment_count = COALESCE(partial_payment_count, 0)
+                + CASE WHEN COALESCE(amount_paid, 0) + $1 < total_amount THEN 1 ELSE 0 END,
+            status = CASE
+                WHEN COALESCE(amount_paid, 0) + $1 >= total_amount THEN 'paid'
+                ELSE 'partial'
+            END,
+            paid_at = CASE
+                WHEN COALESCE(amount_paid, 0) + $1 >= total_amount THEN COALESCE(paid_at, NOW())
+                ELSE paid_at
+            END,
+            reminder_sent_count = CASE
+                WHEN deposit_paid_at IS NULL AND deposit_amount <= COALESCE(amount_paid, 0) + $1 THEN 0
+                ELSE reminder_sent_count
+            END,
+            deposit_paid_at = CASE
+                WHEN deposit_paid_at IS NULL AND deposit_amount <= COALESCE(amount_paid, 0) + $1 THEN NOW()
+                ELSE deposit_paid_at
+            END,
+            updated_at = NOW()
+        WHERE id = $2
+        "#,
+    )
+    .bind(amount)
+    .bind(invoice_id)
+    .execute(&mut **tx)
+    .await?;
+
+    if let Some(settlement_rate) = settlement_rate {
+        book_realized_gain_loss(tx, invoice_id, Some(payment_id), amount, settlement_rate).await?;
+    }
+
+    Ok(())
+}
+
+const BANK_TRANSFER_SELECT: &str = r#"
+    SELECT
+        p.id, p.invoice_id, i.invoice_number, c.name AS client_name, p.amount, p.currency,
+        p.payment_method, p.status, p.gateway_payment_id, p.paid_by, p.bank_reference,
+        p.received_on, p.failure_reason, i.total_amount - COALESCE(i.amount_paid, 0) AS balance_due,
+        p.created_at, p.reconciled_at
+    FROM payments p
+    JOIN invoices i ON p.invoice_id = i.id
+    LEFT JOIN clients c ON i.client_id = c.id
+    WHERE p.user_id = $1 AND p.payment_method IN ('bank_transfer', 'ach_debit') AND i.deleted_at IS NULL
+"#;
+
+fn push_list_filters(
+    query_builder: &mut QueryBuilder<'_, Postgres>,
+    status: Option<&PaymentStatus>,
+    payment_method: Option<&PaymentMethod>,
+    date_from: Option<DateTime<Utc>>,
+    date_to: Option<DateTime<Utc>>,
+) {
+    if let Some(s) = status {
+        query_builder.push(" AND p.status = ");
+        query_builder.push_bind(s.to_string());
+    }
+
+    if let Some(pm) = payment_method {
+        query_builder.push(" AND p.payment_method = ");
+        query_builder.push_bind(pm.to_string());
+    }
+
+    if let Some(df) = date_from {
+        query_builder.push(" AND p.created_at >= ");
+        query_builder.push_bind(df);
+    }
+
+    if let Some(dt) = date_to {
+        query_builder.push(" AND p.created_at <= ");
+        query_builder.push_bind(dt);
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct PaymentRow {
     id: Uuid,
     invoice_id: Uuid,
     user_id: Uuid,
-    amount: f64,
+    amount: Decimal,
     currency: String,
     payment_method: String,
     gateway: Option<String>,
     gateway_payment_id: Option<String>,
-    gateway_fee: f64,
+    gateway_fee: Decimal,
     status: String,
     failure_reason: Option<String>,
     paid_by: Option<String>,
@@ -292,6 +542,7 @@ impl PaymentRow {
             "check" => PaymentMethod::Check,
             "cash" => PaymentMethod::Cash,
             "bank_transfer" => PaymentMethod::BankTransfer,
+            "ach_debit" => PaymentMethod::AchDebit,
             _ => PaymentMethod::Stripe,
         };
 
@@ -328,7 +579,7 @@ struct PaymentResponseRow {
     id: Uuid,
     invoice_id: Uuid,
     invoice_number: Option<String>,
-    amount: f64,
+    amount: Decimal,
     currency: String,
     payment_method: String,
     status: String,
@@ -344,6 +595,7 @@ impl PaymentResponseRow {
             "check" => PaymentMethod::Check,
             "cash" => PaymentMethod::Cash,
             "bank_transfer" => PaymentMethod::BankTransfer,
+            "ach_debit" => PaymentMethod::AchDebit,
             _ => PaymentMethod::Stripe,
         };
 
@@ -369,3 +621,58 @@ impl PaymentResponseRow {
         }
     }
 }
+
+#[derive(sqlx::FromRow)]
+struct BankTransferRow {
+    id: Uuid,
+    invoice_id: Uuid,
+    invoice_number: String,
+    client_name: Option<String>,
+    amount: Decimal,
+    currency: Option<String>,
+    payment_method: String,
+    status: String,
+    gateway_payment_id: Option<String>,
+    paid_by: Option<String>,
+    bank_reference: Option<String>,
+    received_on: Option<NaiveDate>,
+    failure_reason: Option<String>,
+    balance_due: Decimal,
+    created_at: DateTime<Utc>,
+    reconciled_at: Option<DateTime<Utc>>,
+}
+
+impl BankTransferRow {
+    fn into_payment(self) -> BankTransferPayment {
+        let payment_method = match self.payment_method.as_str() {
+            "ach_debit" => PaymentMethod::AchDebit,
+            _ => PaymentMethod::BankTransfer,
+        };
+
+        let status = match self.status.as_str() {
+            "completed" => PaymentStatus::Completed,
+            "failed" => PaymentStatus::Failed,
+            "refunded" => PaymentStatus::Refunded,
+            _ => PaymentStatus::Pending,
+        };
+
+        BankTransferPayment {
+            id: self.id,
+            invoice_id: self.invoice_id,
+            invoice_number: self.invoice_number,
+            client_name: self.client_name,
+            amount: self.amount,
+            currency: self.currency.unwrap_or_else(|| "USD".to_string()),
+            payment_method,
+            status,
+            reference: self.gateway_payment_id,
+            paid_by: self.paid_by,
+            bank_reference: self.bank_reference,
+            received_on: self.received_on,
+            failure_reason: self.failure_reason,
+            balance_due: self.balance_due,
+            created_at: self.created_at,
+            reconciled_at: self.reconciled_at,
+        }
+    }
+}
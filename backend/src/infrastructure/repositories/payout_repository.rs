@@ -0,0 +1,244 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{Payout, PayoutLine, PayoutStatus, RecordPayout};
+
+const LINE_COLUMNS: &str = r#"
+    p.id as payment_id, p.gateway_payment_id, p.invoice_id, i.invoice_number, c.name as client_name,
+    p.amount::float8 as gross, COALESCE(p.gateway_fee, 0)::float8 as fee,
+    (p.amount - COALESCE(p.gateway_fee, 0))::float8 as net, p.created_at as paid_at
+"#;
+
+#[derive(Clone)]
+pub struct PayoutRepository {
+    db: PgPool,
+}
+
+impl PayoutRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Insert or refresh a payout and link the referenced payments to it.
+    /// Payments are matched on gateway and gateway payment id, scoped to the user.
+    pub async fn upsert(&self, user_id: Uuid, record: &RecordPayout) -> Result<Payout, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let references: Vec<String> = record.payments.iter().map(|p| p.gateway_payment_id.clone()).collect();
+        let matched: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT gateway_payment_id FROM payments
+            WHERE user_id = $1 AND gateway = $2 AND gateway_payment_id = ANY($3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&record.gateway)
+        .bind(&references)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let unmatched: Vec<String> = references
+            .iter()
+            .filter(|reference| !matched.contains(reference))
+            .cloned()
+            .collect();
+
+        let row = sqlx::query_as::<_, PayoutRow>(
+            r#"
+            INSERT INTO payouts (
+                id, user_id, gateway, gateway_payout_id, amount, currency, status,
+                arrival_date, unmatched_references, created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+            ON CONFLICT (user_id, gateway, gateway_payout_id) DO UPDATE SET
+                amount = EXCLUDED.amount,
+                currency = EXCLUDED.currency,
+                status = EXCLUDED.status,
+                arrival_date = EXCLUDED.arrival_date,
+                unmatched_references = EXCLUDED.unmatched_references,
+                updated_at = EXCLUDED.updated_at
+            RETURNING id, user_id, gateway, gateway_payout_id, amount::float8 as amount, currency, status,
+                      arrival_date, unmatched_references, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(&record.gateway)
+        .bind(&record.gateway_payout_id)
+        .bind(record.amount)
+        .bind(record.currency.as_deref().unwrap_or("USD"))
+        .bind(record.status.as_str())
+        .bind(record.arrival_date)
+        .bind(&unmatched)
+        .bind(Utc::now())
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for payment in &record.payments {
+            sqlx::query(
+                r#"
+                UPDATE payments
+                SET payout_id = $1, gateway_fee = COALESCE($2, gateway_fee), updated_at = NOW()
+                WHERE user_id = $3 AND gateway = $4 AND gateway_payment_id = $5
+                "#,
+            )
+            .bind(row.id)
+            .bind(payment.fee)
+            .bind(user_id)
+            .bind(&record.gateway)
+            .bind(&payment.gateway_payment_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(row.into_payout())
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, payout_id: Uuid) -> Result<Option<Payout>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PayoutRow>(
+            r#"
+            SELECT id, user_id, gateway, gateway_payout_id, amount::float8 as amount, currency, status,
+                   arrival_date, unmatched_references, created_at, updated_at
+            FROM payouts
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(payout_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(PayoutRow::into_payout))
+    }
+
+    /// Payouts arriving between the two dates; payouts without an arrival date are included
+    pub async fn list(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<Payout>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, PayoutRow>(
+            r#"
+            SELECT id, user_id, gateway, gateway_payout_id, amount::float8 as amount, currency, status,
+                   arrival_date, unmatched_references, created_at, updated_at
+            FROM payouts
+            WHERE user_id = $1
+              AND (arrival_date BETWEEN $2 AND $3 OR (arrival_date IS NULL AND created_at::date BETWEEN $2 AND $3))
+            ORDER BY COALESCE(arrival_date, created_at::date) DESC, created_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(PayoutRow::into_payout).collect())
+    }
+
+    pub async fn get_lines(&self, payout_id: Uuid) -> Result<Vec<PayoutLine>, sqlx::Error> {
+        sqlx::query_as::<_, PayoutLine>(&format!(
+            r#"
+            SELECT {LINE_COLUMNS}
+            FROM payments p
+            LEFT JOIN invoices i ON i.id = p.invoice_id
+            LEFT JOIN clients c ON c.id = i.client_id
+            WHERE p.payout_id = $1
+            ORDER BY p.created_at
+            "#
+        ))
+        .bind(payout_id)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Completed gateway payments in the period that no payout has picked up yet
+    pub async fn list_unsettled(&self, user_id: Uuid, from: NaiveDate, to: NaiveDate) -> Result<Vec<PayoutLine>, sqlx::Error> {
+        sqlx::query_as::<_, PayoutLine>(&format!(
+            r#"
+            SELECT {LINE_COLUMNS}
+            FROM payments p
+            LEFT JOIN invoices i ON i.id = p.invoice_id
+            LEFT JOIN clients c ON c.id = i.client_id
+            WHERE p.user_id = $1
+              AND p.status = 'completed'
+              AND p.gateway IS NOT NULL
+              AND p.payout_id IS NULL
+              AND p.created_at::date BETWEEN $2 AND $3
+            ORDER BY p.created_at
+            "#
+        ))
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    pub async fn sum_payments(
+        &self,
+        user_id: Uuid,
+        gateway: &str,
+        gateway_payment_ids: &[String],
+    ) -> Result<f64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)::float8 FROM payments
+            WHERE user_id = $1 AND gateway = $2 AND gateway_payment_id = ANY($3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(gateway)
+        .bind(gateway_payment_ids)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Owners of the given gateway payments, used to route webhook payouts to users
+    pub async fn find_payment_owners(
+        &self,
+        gateway: &str,
+        gateway_payment_ids: &[String],
+    ) -> Result<Vec<(Uuid, String)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT user_id, gateway_payment_id FROM payments
+            WHERE gateway = $1 AND gateway_payment_id = ANY($2)
+            "#,
+        )
+        .bind(gateway)
+        .bind(gateway_payment_ids)
+        .fetch_all(&self.db)
+        .await
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PayoutRow {
+    id: Uuid,
+    user_id: Uuid,
+    gateway: String,
+    gateway_payout_id: String,
+    amount: f64,
+    currency: String,
+    status: String,
+    arrival_date: Option<NaiveDate>,
+    unmatched_references: Vec<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl PayoutRow {
+    fn into_payout(self) -> Payout {
+        Payout {
+            id: self.id,
+            user_id: self.user_id,
+            gateway: self.gateway,
+            gateway_payout_id: self.gateway_payout_id,
+            amount: self.amount,
+            currency: self.currency,
+            status: PayoutStatus::parse(&self.status).unwrap_or(PayoutStatus::Pending),
+            arrival_date: self.arrival_date,
+            unmatched_references: self.unmatched_references,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
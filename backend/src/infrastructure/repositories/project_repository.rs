@@ -0,0 +1,277 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::domain::models::{Project, ProjectListFilter, ProjectStatus, ProjectTotals};
+use super::invoice_cost_repository::INVOICE_REVENUE;
+
+/// Invoices that count as billed; drafts and voided invoices don't
+const ISSUED_INVOICE: &str = "i.deleted_at IS NULL AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')";
+
+/// Hours of a stopped entry as billed: whole minutes, to the hundredth
+const ENTRY_HOURS: &str = "ROUND(FLOOR(EXTRACT(EPOCH FROM (t.ended_at - t.started_at)) / 60) / 60, 2)";
+
+#[derive(Clone)]
+pub struct ProjectRepository {
+    db: PgPool,
+}
+
+impl ProjectRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list(&self, user_id: Uuid, filter: &ProjectListFilter) -> Result<Vec<Project>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Postgres>::new("SELECT * FROM projects WHERE user_id = ");
+        query_builder.push_bind(user_id);
+        if let Some(client_id) = filter.client_id {
+            query_builder.push(" AND client_id = ");
+            query_builder.push_bind(client_id);
+        }
+        if let Some(status) = filter.status {
+            query_builder.push(" AND status = ");
+            query_builder.push_bind(status.as_str());
+        }
+        query_builder.push(" ORDER BY LOWER(name), id");
+
+        let rows: Vec<ProjectRow> = query_builder.build_query_as().fetch_all(&self.db).await?;
+        Ok(rows.into_iter().map(ProjectRow::into_project).collect())
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, project_id: Uuid) -> Result<Option<Project>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ProjectRow>("SELECT * FROM projects WHERE id = $1 AND user_id = $2")
+            .bind(project_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(ProjectRow::into_project))
+    }
+
+    pub async fn create(&self, user_id: Uuid, project: &Project) -> Result<Project, sqlx::Error> {
+        let row = sqlx::query_as::<_, ProjectRow>(
+            r#"
+            INSERT INTO projects (id, user_id, client_id, name, status, budget, hourly_rate, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(project.id)
+        .bind(user_id)
+        .bind(project.client_id)
+        .bind(&project.name)
+        .bind(project.status.as_str())
+        .bind(project.budget)
+        .bind(project.hourly_rate)
+        .bind(project.created_at)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_project())
+    }
+
+    pub async fn update(&self, user_id: Uuid, project: &Project) -> Result<Option<Project>, sqlx::Error> {
+        let row = sqlx::query_as::<_, ProjectRow>(
+            r#"
+            UPDATE projects SET name = $3, status = $4, budget = $5, hourly_rate = $6, updated_at = $7
+            WHERE id = $1 AND user_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(project.id)
+        .bind(user_id)
+        .bind(&project.name)
+        .bind(project.status.as_str())
+        .bind(project.budget)
+        .bind(project.hourly_rate)
+        .bind(project.updated_at)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(ProjectRow::into_project))
+    }
+
+    /// Deletes the project; what was tagged with it is untagged
+    pub async fn delete(&self, user_id: Uuid, project_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM projects WHERE id = $1 AND user_id = $2")
+            .bind(project_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Whether the client is the user's and not in the trash
+    pub async fn client_exists(&self, user_id: Uuid, client_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM clients WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL)")
+            .bind(client_id)
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await
+    }
+
+    /// Client of one of the user's invoices
+    pub async fn invoice_client(&self, user_id: Uuid, invoice_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT client_id FROM invoices WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL")
+            .bind(invoice_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    pub async fn set_invoice_project(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE invoices SET project_id = $3, updated_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(project_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn set_expense_project(
+        &self,
+        user_id: Uuid,
+        expense_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE expenses SET project_id = $3, updated_at = NOW() WHERE id = $1 AND user_id = $2")
+            .bind(expense_id)
+            .bind(user_id)
+            .bind(project_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Client of one of the user's time entries, and the invoice it was billed on
+    pub async fn time_entry_client(
+        &self,
+        user_id: Uuid,
+        entry_id: Uuid,
+    ) -> Result<Option<(Uuid, Option<Uuid>)>, sqlx::Error> {
+        sqlx::query_as("SELECT client_id, invoice_id FROM time_entries WHERE id = $1 AND user_id = $2")
+            .bind(entry_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+
+    /// Only unbilled entries move
+    pub async fn set_time_entry_project(
+        &self,
+        user_id: Uuid,
+        entry_id: Uuid,
+        project_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE time_entries SET project_id = $3, updated_at = NOW() WHERE id = $1 AND user_id = $2 AND invoice_id IS NULL",
+        )
+        .bind(entry_id)
+        .bind(user_id)
+        .bind(project_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Billed revenue, costs and tracked time of a project, in the base currency.
+    /// An expense tagged with the project and also costed on one of its invoices
+    /// counts once.
+    pub async fn totals(&self, user_id: Uuid, project_id: Uuid) -> Result<ProjectTotals, sqlx::Error> {
+        let row = sqlx::query_as::<_, ProjectTotalsRow>(&format!(
+            r#"
+            SELECT
+                (SELECT COALESCE(SUM({revenue}), 0)::float8
+                 FROM invoices i
+                 WHERE i.user_id = $1 AND i.project_id = $2 AND {issued}) as billed,
+                (SELECT COALESCE(SUM(e.amount), 0)::float8
+                 FROM expenses e
+                 WHERE e.user_id = $1 AND (e.project_id = $2 OR e.id IN (
+                     SELECT c.expense_id FROM invoice_costs c
+                     JOIN invoices i ON i.id = c.invoice_id
+                     WHERE c.kind = 'expense' AND i.user_id = $1 AND i.project_id = $2 AND {issued}
+                 ))) as expense_costs,
+                (SELECT COALESCE(SUM(c.hours * c.hourly_cost), 0)::float8
+                 FROM invoice_costs c
+                 JOIN invoices i ON i.id = c.invoice_id
+                 WHERE c.kind = 'time' AND i.user_id = $1 AND i.project_id = $2 AND {issued}) as time_costs,
+                COALESCE(SUM({hours}), 0)::float8 as tracked_hours,
+                COALESCE(SUM({hours}) FILTER (WHERE t.billable AND t.invoice_id IS NULL), 0)::float8 as unbilled_hours,
+                COALESCE(SUM({hours} * COALESCE(t.hourly_rate, p.hourly_rate, cl.hourly_rate, 0))
+                    FILTER (WHERE t.billable AND t.invoice_id IS NULL), 0)::float8 as unbilled_amount
+            FROM projects p
+            JOIN clients cl ON cl.id = p.client_id
+            LEFT JOIN time_entries t ON t.project_id = p.id AND t.ended_at IS NOT NULL
+            WHERE p.id = $2 AND p.user_id = $1
+            "#,
+            revenue = INVOICE_REVENUE,
+            issued = ISSUED_INVOICE,
+            hours = ENTRY_HOURS,
+        ))
+        .bind(user_id)
+        .bind(project_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(ProjectTotals {
+            billed: row.billed,
+            expense_costs: row.expense_costs,
+            time_costs: row.time_costs,
+            tracked_hours: row.tracked_hours,
+            unbilled_hours: row.unbilled_hours,
+            unbilled_amount: row.unbilled_amount,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ProjectTotalsRow {
+    billed: f64,
+    expense_costs: f64,
+    time_costs: f64,
+    tracked_hours: f64,
+    unbilled_hours: f64,
+    unbilled_amount: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ProjectRow {
+    id: Uuid,
+    #[allow(dead_code)]
+    user_id: Uuid,
+    client_id: Uuid,
+    name: String,
+    status: String,
+    budget: Option<Decimal>,
+    hourly_rate: Option<Decimal>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ProjectRow {
+    fn into_project(self) -> Project {
+        Project {
+            id: self.id,
+            client_id: self.client_id,
+            name: self.name,
+            status: ProjectStatus::parse(&self.status).unwrap_or(ProjectStatus::Active),
+            budget: self.budget,
+            hourly_rate: self.hourly_rate,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{RealtimeEvent, RealtimeEventType};
+
+#[derive(Clone)]
+pub struct RealtimeEventRepository {
+    db: PgPool,
+}
+
+impl RealtimeEventRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Mark the oldest unpublished events as published and return them.
+    /// Locked events are being published by another instance.
+    pub async fn claim_unpublished(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<RealtimeEvent>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RealtimeEventRow>(
+            r#"
+            UPDATE realtime_events SET published_at = $1
+            WHERE id IN (
+                SELECT id FROM realtime_events
+                WHERE published_at IS NULL
+                ORDER BY id
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut events: Vec<RealtimeEvent> = rows.into_iter().filter_map(RealtimeEventRow::into_event).collect();
+        events.sort_by_key(|event| event.id);
+        Ok(events)
+    }
+
+    /// The user's published events after `last_id` and since `since`, oldest first
+    pub async fn since(
+        &self,
+        user_id: Uuid,
+        last_id: i64,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RealtimeEvent>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, RealtimeEventRow>(
+            r#"
+            SELECT * FROM realtime_events
+            WHERE user_id = $1 AND id > $2 AND created_at >= $3 AND published_at IS NOT NULL
+            ORDER BY id
+            LIMIT $4
+            "#,
+        )
+        .bind(user_id)
+        .bind(last_id)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(RealtimeEventRow::into_event).collect())
+    }
+
+    /// Delete published events created before `before`
+    pub async fn prune(&self, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM realtime_events WHERE created_at < $1 AND published_at IS NOT NULL")
+            .bind(before)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RealtimeEventRow {
+    id: i64,
+    user_id: Uuid,
+    event_type: String,
+    invoice_id: Uuid,
+    data: serde_json::Value,
+    created_at: DateTime<Utc>,
+    #[allow(dead_code)]
+    published_at: Option<DateTime<Utc>>,
+}
+
+impl RealtimeEventRow {
+    /// None for event types this version doesn't know
+    fn into_event(self) -> Option<RealtimeEvent> {
+        Some(RealtimeEvent {
+            id: self.id,
+            user_id: self.user_id,
+            event_type: RealtimeEventType::parse(&self.event_type)?,
+            invoice_id: self.invoice_id,
+            data: self.data,
+            created_at: self.created_at,
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+const SCAN_COLUMNS: &str = r#"
+    id, file_name, receipt_image_url, backend, status, confidence, raw_text,
+    extracted, error, expense_id, created_at, confirmed_at
+"#;
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct ReceiptScanRow {
+    pub id: Uuid,
+    pub file_name: String,
+    pub receipt_image_url: String,
+    pub backend: String,
+    pub status: String,
+    pub confidence: Option<f64>,
+    pub raw_text: Option<String>,
+    pub extracted: serde_json::Value,
+    pub error: Option<String>,
+    pub expense_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+}
+
+pub struct NewReceiptScan<'a> {
+    pub file_name: &'a str,
+    pub receipt_image_url: &'a str,
+    pub backend: &'a str,
+    pub status: &'a str,
+    pub confidence: Option<f64>,
+    pub raw_text: Option<&'a str>,
+    pub extracted: serde_json::Value,
+    pub error: Option<&'a str>,
+}
+
+/// Scanned receipts, kept with what OCR read from them for auditing
+#[derive(Clone)]
+pub struct ReceiptScanRepository {
+    db: PgPool,
+}
+
+impl ReceiptScanRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn create(&self, user_id: Uuid, scan: NewReceiptScan<'_>) -> Result<ReceiptScanRow, sqlx::Error> {
+        sqlx::query_as::<_, ReceiptScanRow>(&format!(
+            r#"
+            INSERT INTO receipt_scans
+                (id, user_id, file_name, receipt_image_url, backend, status, confidence, raw_text, extracted, error, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
+            RETURNING {}
+            "#,
+            SCAN_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(scan.file_name)
+        .bind(scan.receipt_image_url)
+        .bind(scan.backend)
+        .bind(scan.status)
+        .bind(scan.confidence)
+        .bind(scan.raw_text)
+        .bind(scan.extracted)
+        .bind(scan.error)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    pub async fn find(&self, user_id: Uuid, scan_id: Uuid) -> Result<Option<ReceiptScanRow>, sqlx::Error> {
+        sqlx::query_as::<_, ReceiptScanRow>(&format!(
+            "SELECT {} FROM receipt_scans WHERE id = $1 AND user_id = $2",
+            SCAN_COLUMNS
+        ))
+        .bind(scan_id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Mark a scan confirmed before its expense is created. False when it was
+    /// confirmed already, so one receipt can't become two expenses.
+    pub async fn claim(&self, user_id: Uuid, scan_id: Uuid, now: DateTime<Utc>) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE receipt_scans SET confirmed_at = $3 WHERE id = $1 AND user_id = $2 AND confirmed_at IS NULL",
+        )
+        .bind(scan_id)
+        .bind(user_id)
+        .bind(now)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Give a claim back when its expense couldn't be created
+    pub async fn release(&self, scan_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE receipt_scans SET confirmed_at = NULL WHERE id = $1 AND expense_id IS NULL")
+            .bind(scan_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn link_expense(&self, scan_id: Uuid, expense_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE receipt_scans SET expense_id = $2 WHERE id = $1")
+            .bind(scan_id)
+            .bind(expense_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -5,7 +5,12 @@ use chrono::NaiveDate;
 
 use crate::domain::repositories::report_repository::{
     ReportRepository, OverviewStats, IncomeReport, ExpensesReport, TaxReport, AgingReport,
-    IncomeByMonth, IncomeByClient, TaxByState,
+    AgingSnapshot, IncomeByMonth, IncomeByClient, TaxByState, TaxByRate, VatReport, VatByCountry, ClientReportFilter,
+    SlaTargets, SlaStats, ClientSlaStats, SlaReport, SlaBreachSummary,
+};
+use crate::domain::models::{
+    CashflowHistoryEntry, CashflowInputs, ClientPaymentDelay, OpenReceivable, TimeseriesInterval, TimeseriesMetric,
+    TimeseriesPoint,
 };
 
 #[derive(Clone)]
@@ -17,14 +22,126 @@ impl ReportRepositoryImpl {
     pub fn new(db: PgPool) -> Self {
         Self { db }
     }
+
+    /// Client IDs a filtered report is restricted to (None = all clients)
+    async fn scoped_client_ids(
+        &self,
+        user_id: Uuid,
+        filter: &ClientReportFilter,
+    ) -> Result<Option<Vec<Uuid>>, sqlx::Error> {
+        let client_id = match filter.client_id {
+            Some(id) => id,
+            None => return Ok(None),
+        };
+
+        if !filter.include_subsidiaries {
+            return Ok(Some(vec![client_id]));
+        }
+
+        let ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            WITH RECURSIVE hierarchy AS (
+                SELECT id FROM clients WHERE id = $1 AND user_id = $2
+                UNION
+                SELECT c.id FROM clients c
+                JOIN hierarchy h ON c.parent_client_id = h.id
+                WHERE c.user_id = $2
+            )
+            SELECT id FROM hierarchy
+            "#,
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(Some(ids))
+    }
+
+    /// SLA targets stored in the user's invoice settings
+    async fn sla_targets(&self, user_id: Uuid) -> Result<SlaTargets, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                (invoice_settings->>'sla_view_hours')::float8 AS view_hours,
+                (invoice_settings->>'sla_payment_days')::float8 AS payment_days
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row
+            .map(|row| SlaTargets {
+                view_hours: row.get("view_hours"),
+                payment_days: row.get("payment_days"),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Open invoices past the targets right now
+    async fn sla_breaches(&self, user_id: Uuid, targets: SlaTargets) -> Result<SlaBreachSummary, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (
+                    WHERE viewed_at IS NULL AND NOW() - sent_at > make_interval(secs => $2::float8 * 3600)
+                ) AS unviewed_invoices,
+                COUNT(*) FILTER (
+                    WHERE NOW() - sent_at > make_interval(secs => $3::float8 * 86400)
+                ) AS unpaid_invoices
+            FROM invoices
+            WHERE user_id = $1 AND sent_at IS NOT NULL
+              AND status NOT IN ('draft', 'paid', 'cancelled', 'superseded', 'expired')
+            "#,
+        )
+        .bind(user_id)
+        .bind(targets.view_hours)
+        .bind(targets.payment_days)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(SlaBreachSummary {
+            targets,
+            unviewed_invoices: row.get("unviewed_invoices"),
+            unpaid_invoices: row.get("unpaid_invoices"),
+        })
+    }
+}
+
+fn history_entry_from_row(row: &sqlx::postgres::PgRow) -> CashflowHistoryEntry {
+    CashflowHistoryEntry {
+        series: row.get("series"),
+        label: row.get("label"),
+        client_id: row.get("client_id"),
+        date: row.get("date"),
+        amount: row.get("amount"),
+        terms_days: row.get("terms_days"),
+    }
+}
+
+fn sla_stats_from_row(row: &sqlx::postgres::PgRow) -> SlaStats {
+    SlaStats {
+        sent_invoices: row.get("sent_invoices"),
+        viewed_invoices: row.get("viewed_invoices"),
+        paid_invoices: row.get("paid_invoices"),
+        view_hours_p50: row.get("view_hours_p50"),
+        view_hours_p90: row.get("view_hours_p90"),
+        payment_days_p50: row.get("payment_days_p50"),
+        payment_days_p90: row.get("payment_days_p90"),
+        view_breaches: row.get("view_breaches"),
+        payment_breaches: row.get("payment_breaches"),
+    }
 }
 
 #[async_trait]
 impl ReportRepository for ReportRepositoryImpl {
     async fn get_overview_stats(&self, user_id: Uuid) -> Result<OverviewStats, sqlx::Error> {
-        // Total revenue (paid invoices)
+        // Total revenue (paid invoices, net of credit notes)
         let total_revenue: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status = 'paid'"
+            "SELECT COALESCE(SUM(total_amount - credited_amount)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status = 'paid'"
         )
         .bind(user_id)
         .fetch_one(&self.db)
@@ -57,8 +174,23 @@ impl ReportRepository for ReportRepositoryImpl {
         // Total expenses (mock - would need expenses table)
         let total_expenses: f64 = 0.0;
 
+        // Realized FX gain/loss on foreign-currency payments
+        let fx_gain_loss: f64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(gain_loss_amount)::float8, 0.0::float8) FROM fx_gain_loss_entries WHERE user_id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await?;
+
         // Net profit
-        let net_profit = total_revenue - total_expenses;
+        let net_profit = total_revenue - total_expenses + fx_gain_loss;
+
+        let targets = self.sla_targets(user_id).await?;
+        let sla_breaches = if targets.is_empty() {
+            None
+        } else {
+            Some(self.sla_breaches(user_id, targets).await?)
+        };
 
         Ok(OverviewStats {
             total_revenue,
@@ -66,7 +198,9 @@ impl ReportRepository for ReportRepositoryImpl {
             paid_invoices,
             overdue_invoices,
             total_expenses,
+            fx_gain_loss,
             net_profit,
+            sla_breaches,
         })
     }
 
@@ -75,14 +209,18 @@ impl ReportRepository for ReportRepositoryImpl {
         user_id: Uuid,
         start_date: NaiveDate,
         end_date: NaiveDate,
+        filter: &ClientReportFilter,
     ) -> Result<IncomeReport, sqlx::Error> {
-        // Total income
+        let client_ids = self.scoped_client_ids(user_id, filter).await?;
+
+        // Total income, net of credit notes against the paid invoices
         let total_income: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status = 'paid' AND issue_date BETWEEN $2 AND $3"
+            "SELECT COALESCE(SUM(total_amount - credited_amount)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status = 'paid' AND issue_date BETWEEN $2 AND $3 AND ($4::uuid[] IS NULL OR client_id = ANY($4))"
         )
         .bind(user_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
@@ -91,10 +229,11 @@ impl ReportRepository for ReportRepositoryImpl {
             r#"
             SELECT
                 TO_CHAR(issue_date, 'YYYY-MM') as month,
-                SUM(total_amount)::float8 as amount,
+                SUM(total_amount - credited_amount)::float8 as amount,
                 COUNT(*) as invoice_count
             FROM invoices
             WHERE user_id = $1 AND status = 'paid' AND issue_date BETWEEN $2 AND $3
+              AND ($4::uuid[] IS NULL OR client_id = ANY($4))
             GROUP BY TO_CHAR(issue_date, 'YYYY-MM')
             ORDER BY month
             "#
@@ -102,6 +241,7 @@ impl ReportRepository for ReportRepositoryImpl {
         .bind(user_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(&client_ids)
         .fetch_all(&self.db)
         .await?;
 
@@ -120,11 +260,12 @@ impl ReportRepository for ReportRepositoryImpl {
             SELECT
                 c.id as client_id,
                 c.name as client_name,
-                SUM(i.total_amount)::float8 as total_amount,
+                SUM(i.total_amount - i.credited_amount)::float8 as total_amount,
                 COUNT(*) as invoice_count
             FROM invoices i
             JOIN clients c ON i.client_id = c.id
             WHERE i.user_id = $1 AND i.status = 'paid' AND i.issue_date BETWEEN $2 AND $3
+              AND ($4::uuid[] IS NULL OR i.client_id = ANY($4))
             GROUP BY c.id, c.name
             ORDER BY total_amount DESC
             "#
@@ -132,6 +273,7 @@ impl ReportRepository for ReportRepositoryImpl {
         .bind(user_id)
         .bind(start_date)
         .bind(end_date)
+        .bind(&client_ids)
         .fetch_all(&self.db)
         .await?;
 
@@ -183,8 +325,15 @@ impl ReportRepository for ReportRepositoryImpl {
         .fetch_one(&self.db)
         .await?;
 
-        // Tax deductible (mock - would need expense tax tracking)
-        let total_tax_deductible: f64 = 0.0;
+        // Input tax paid on deductible expenses
+        let total_tax_deductible: f64 = sqlx::query_scalar(
+            "SELECT COALESCE(SUM(tax_amount)::float8, 0.0::float8) FROM expenses WHERE user_id = $1 AND tax_deductible AND date_incurred BETWEEN $2 AND $3"
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_one(&self.db)
+        .await?;
 
         // By state (from client addresses)
         let by_state_rows = sqlx::query(
@@ -213,61 +362,172 @@ impl ReportRepository for ReportRepositoryImpl {
             })
             .collect();
 
+        // By tax and rate, from the breakdown kept with each invoice; older invoices
+        // without one count under their tax label
+        let by_tax: Vec<TaxByRate> = sqlx::query_as::<_, (String, Option<f64>, bool, f64, f64)>(
+            r#"
+            SELECT label, rate, compound, SUM(taxable_amount)::float8, SUM(tax_amount)::float8
+            FROM (
+                SELECT t->>'label' AS label,
+                       (t->>'rate')::numeric::float8 AS rate,
+                       COALESCE((t->>'compound')::boolean, false) AS compound,
+                       (t->>'taxable_amount')::numeric AS taxable_amount,
+                       (t->>'tax_amount')::numeric AS tax_amount
+                FROM invoices i
+                CROSS JOIN LATERAL jsonb_array_elements(i.tax_calculation->'taxes') t
+                WHERE i.user_id = $1 AND i.status = 'paid' AND i.issue_date BETWEEN $2 AND $3
+                  AND jsonb_typeof(i.tax_calculation->'taxes') = 'array'
+                UNION ALL
+                SELECT COALESCE(i.tax_label, 'Tax'), NULL, false, i.subtotal, i.tax_amount
+                FROM invoices i
+                WHERE i.user_id = $1 AND i.status = 'paid' AND i.issue_date BETWEEN $2 AND $3
+                  AND i.tax_amount > 0
+                  AND jsonb_typeof(i.tax_calculation->'taxes') IS DISTINCT FROM 'array'
+            ) taxes
+            GROUP BY label, rate, compound
+            ORDER BY label, rate
+            "#
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|(label, rate, compound, taxable_amount, tax_amount)| TaxByRate { label, rate, compound, taxable_amount, tax_amount })
+        .collect();
+
         Ok(TaxReport {
             total_tax_collected,
             total_tax_deductible,
+            net_tax_payable: ((total_tax_collected - total_tax_deductible) * 100.0).round() / 100.0,
             by_state,
+            by_tax,
         })
     }
 
-    async fn get_aging_report(&self, user_id: Uuid) -> Result<AgingReport, sqlx::Error> {
+    async fn get_vat_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<VatReport, sqlx::Error> {
+        // Taxed amounts come from each invoice's breakdown and the rest of its subtotal
+        // is zero-rated; older invoices without a breakdown count at their overall rate
+        let by_country: Vec<VatByCountry> = sqlx::query_as::<_, (String, f64, bool, String, i64, f64, f64)>(
+            r#"
+            WITH issued AS (
+                SELECT id, vat_country, reverse_charge, currency, subtotal, tax_amount, tax_calculation->'taxes' AS taxes
+                FROM invoices
+                WHERE user_id = $1 AND vat_country IS NOT NULL AND issue_date BETWEEN $2 AND $3
+                  AND status IN ('sent', 'viewed', 'partial', 'paid', 'overdue')
+            )
+            SELECT country, rate::float8, reverse_charge, currency, COUNT(DISTINCT invoice_id),
+                   SUM(taxable_amount)::float8, SUM(vat_amount)::float8
+            FROM (
+                SELECT i.id AS invoice_id, i.vat_country AS country, (t->>'rate')::numeric AS rate,
+                       i.reverse_charge, i.currency,
+                       (t->>'taxable_amount')::numeric AS taxable_amount,
+                       (t->>'tax_amount')::numeric AS vat_amount
+                FROM issued i
+                CROSS JOIN LATERAL jsonb_array_elements(i.taxes) t
+                WHERE jsonb_typeof(i.taxes) = 'array'
+                UNION ALL
+                SELECT i.id, i.vat_country, 0, i.reverse_charge, i.currency,
+                       i.subtotal - COALESCE((
+                           SELECT SUM((t->>'taxable_amount')::numeric)
+                           FROM jsonb_array_elements(i.taxes) t
+                           WHERE NOT COALESCE((t->>'compound')::boolean, false)
+                       ), 0),
+                       0
+                FROM issued i
+                WHERE jsonb_typeof(i.taxes) = 'array'
+                UNION ALL
+                SELECT i.id, i.vat_country,
+                       CASE WHEN i.subtotal > 0 THEN ROUND(i.tax_amount / i.subtotal, 4) ELSE 0 END,
+                       i.reverse_charge, i.currency, i.subtotal, i.tax_amount
+                FROM issued i
+                WHERE jsonb_typeof(i.taxes) IS DISTINCT FROM 'array'
+            ) vat
+            WHERE taxable_amount > 0 OR vat_amount > 0
+            GROUP BY country, rate, reverse_charge, currency
+            ORDER BY country, reverse_charge, rate DESC, currency
+            "#
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .map(|(country, rate, reverse_charge, currency, invoice_count, taxable_amount, vat_amount)| VatByCountry {
+            country,
+            rate,
+            reverse_charge,
+            currency,
+            invoice_count,
+            taxable_amount,
+            vat_amount,
+        })
+        .collect();
+
+        Ok(VatReport { by_country })
+    }
+
+    async fn get_aging_report(&self, user_id: Uuid, filter: &ClientReportFilter) -> Result<AgingReport, sqlx::Error> {
         let today = chrono::Utc::now().naive_utc().date();
+        let client_ids = self.scoped_client_ids(user_id, filter).await?;
 
         // Current (not yet due)
         let current: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('sent', 'partial') AND due_date >= $2"
+            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('sent', 'partial') AND due_date >= $2 AND ($3::uuid[] IS NULL OR client_id = ANY($3))"
         )
         .bind(user_id)
         .bind(today)
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
         // 1-30 days overdue
         let one_to_thirty_days: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3"
+            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3 AND ($4::uuid[] IS NULL OR client_id = ANY($4))"
         )
         .bind(user_id)
         .bind(today)
         .bind(today - chrono::Duration::days(30))
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
         // 31-60 days overdue
         let thirty_one_to_sixty_days: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3"
+            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3 AND ($4::uuid[] IS NULL OR client_id = ANY($4))"
         )
         .bind(user_id)
         .bind(today - chrono::Duration::days(30))
         .bind(today - chrono::Duration::days(60))
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
         // 61-90 days overdue
         let sixty_one_to_ninety_days: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3"
+            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3 AND ($4::uuid[] IS NULL OR client_id = ANY($4))"
         )
         .bind(user_id)
         .bind(today - chrono::Duration::days(60))
         .bind(today - chrono::Duration::days(90))
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
         // Over 90 days overdue
         let over_ninety_days: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2"
+            "SELECT COALESCE(SUM(total_amount - amount_paid)::float8, 0.0::float8) FROM invoices WHERE user_id = $1 AND status IN ('overdue', 'partial') AND due_date < $2 AND ($3::uuid[] IS NULL OR client_id = ANY($3))"
         )
         .bind(user_id)
         .bind(today - chrono::Duration::days(90))
+        .bind(&client_ids)
         .fetch_one(&self.db)
         .await?;
 
@@ -279,4 +539,372 @@ impl ReportRepository for ReportRepositoryImpl {
             over_ninety_days,
         })
     }
+
+    async fn snapshot_aging(&self, snapshot_date: NaiveDate) -> Result<u64, sqlx::Error> {
+        // Same buckets as get_aging_report, for every user with invoices
+        let result = sqlx::query(
+            r#"
+            INSERT INTO aging_snapshots (
+                user_id, snapshot_date, current_amount, one_to_thirty_days,
+                thirty_one_to_sixty_days, sixty_one_to_ninety_days, over_ninety_days
+            )
+            SELECT
+                user_id,
+                $1,
+                COALESCE(SUM(total_amount - amount_paid) FILTER (
+                    WHERE status IN ('sent', 'partial') AND due_date >= $1), 0),
+                COALESCE(SUM(total_amount - amount_paid) FILTER (
+                    WHERE status IN ('overdue', 'partial') AND due_date < $1 AND due_date >= $2), 0),
+                COALESCE(SUM(total_amount - amount_paid) FILTER (
+                    WHERE status IN ('overdue', 'partial') AND due_date < $2 AND due_date >= $3), 0),
+                COALESCE(SUM(total_amount - amount_paid) FILTER (
+                    WHERE status IN ('overdue', 'partial') AND due_date < $3 AND due_date >= $4), 0),
+                COALESCE(SUM(total_amount - amount_paid) FILTER (
+                    WHERE status IN ('overdue', 'partial') AND due_date < $4), 0)
+            FROM invoices
+            GROUP BY user_id
+            ON CONFLICT (user_id, snapshot_date) DO UPDATE SET
+                current_amount = EXCLUDED.current_amount,
+                one_to_thirty_days = EXCLUDED.one_to_thirty_days,
+                thirty_one_to_sixty_days = EXCLUDED.thirty_one_to_sixty_days,
+                sixty_one_to_ninety_days = EXCLUDED.sixty_one_to_ninety_days,
+                over_ninety_days = EXCLUDED.over_ninety_days,
+                created_at = NOW()
+            "#,
+        )
+        .bind(snapshot_date)
+        .bind(snapshot_date - chrono::Duration::days(30))
+        .bind(snapshot_date - chrono::Duration::days(60))
+        .bind(snapshot_date - chrono::Duration::days(90))
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn get_aging_snapshots(&self, user_id: Uuid, since: NaiveDate) -> Result<Vec<AgingSnapshot>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM (
+                SELECT DISTINCT ON (date_trunc('month', snapshot_date))
+                    snapshot_date,
+                    current_amount::float8 AS current_amount,
+                    one_to_thirty_days::float8 AS one_to_thirty_days,
+                    thirty_one_to_sixty_days::float8 AS thirty_one_to_sixty_days,
+                    sixty_one_to_ninety_days::float8 AS sixty_one_to_ninety_days,
+                    over_ninety_days::float8 AS over_ninety_days
+                FROM aging_snapshots
+                WHERE user_id = $1 AND snapshot_date >= $2
+                ORDER BY date_trunc('month', snapshot_date), snapshot_date DESC
+            ) monthly
+            ORDER BY snapshot_date
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| AgingSnapshot {
+                snapshot_date: row.get("snapshot_date"),
+                aging: AgingReport {
+                    current: row.get("current_amount"),
+                    one_to_thirty_days: row.get("one_to_thirty_days"),
+                    thirty_one_to_sixty_days: row.get("thirty_one_to_sixty_days"),
+                    sixty_one_to_ninety_days: row.get("sixty_one_to_ninety_days"),
+                    over_ninety_days: row.get("over_ninety_days"),
+                },
+            })
+            .collect())
+    }
+
+    async fn get_sla_report(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<SlaReport, sqlx::Error> {
+        let targets = self.sla_targets(user_id).await?;
+
+        // Resent invoices restart the clock, so a view before the latest send counts as
+        // immediate. Invoices paid without a recorded view were evidently seen.
+        let rows = sqlx::query(
+            r#"
+            WITH sent AS (
+                SELECT
+                    i.client_id,
+                    i.viewed_at IS NOT NULL AS viewed,
+                    i.paid_at IS NOT NULL AS paid,
+                    (GREATEST(EXTRACT(EPOCH FROM i.viewed_at - i.sent_at), 0) / 3600)::float8 AS view_hours,
+                    (GREATEST(EXTRACT(EPOCH FROM i.paid_at - i.sent_at), 0) / 86400)::float8 AS payment_days,
+                    (EXTRACT(EPOCH FROM NOW() - i.sent_at))::float8 AS age_secs
+                FROM invoices i
+                WHERE i.user_id = $1 AND i.sent_at IS NOT NULL
+                  AND i.sent_at::date BETWEEN $2 AND $3
+                  AND i.status NOT IN ('cancelled', 'superseded', 'expired')
+            )
+            SELECT
+                GROUPING(s.client_id) = 1 AS overall,
+                s.client_id,
+                c.name AS client_name,
+                COUNT(*) AS sent_invoices,
+                COUNT(*) FILTER (WHERE s.viewed) AS viewed_invoices,
+                COUNT(*) FILTER (WHERE s.paid) AS paid_invoices,
+                ROUND((percentile_cont(0.5) WITHIN GROUP (ORDER BY s.view_hours))::numeric, 2)::float8 AS view_hours_p50,
+                ROUND((percentile_cont(0.9) WITHIN GROUP (ORDER BY s.view_hours))::numeric, 2)::float8 AS view_hours_p90,
+                ROUND((percentile_cont(0.5) WITHIN GROUP (ORDER BY s.payment_days))::numeric, 2)::float8 AS payment_days_p50,
+                ROUND((percentile_cont(0.9) WITHIN GROUP (ORDER BY s.payment_days))::numeric, 2)::float8 AS payment_days_p90,
+                COUNT(*) FILTER (
+                    WHERE s.view_hours > $4::float8
+                       OR (NOT s.viewed AND NOT s.paid AND s.age_secs / 3600 > $4::float8)
+                ) AS view_breaches,
+                COUNT(*) FILTER (
+                    WHERE s.payment_days > $5::float8
+                       OR (NOT s.paid AND s.age_secs / 86400 > $5::float8)
+                ) AS payment_breaches
+            FROM sent s
+            JOIN clients c ON c.id = s.client_id
+            GROUP BY GROUPING SETS ((s.client_id, c.name), ())
+            ORDER BY overall DESC, sent_invoices DESC, client_name
+            "#,
+        )
+        .bind(user_id)
+        .bind(start_date)
+        .bind(end_date)
+        .bind(targets.view_hours)
+        .bind(targets.payment_days)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut overall = SlaStats {
+            sent_invoices: 0,
+            viewed_invoices: 0,
+            paid_invoices: 0,
+            view_hours_p50: None,
+            view_hours_p90: None,
+            payment_days_p50: None,
+            payment_days_p90: None,
+            view_breaches: 0,
+            payment_breaches: 0,
+        };
+        let mut by_client = Vec::new();
+        for row in &rows {
+            if row.get::<bool, _>("overall") {
+                overall = sla_stats_from_row(row);
+            } else {
+                by_client.push(ClientSlaStats {
+                    client_id: row.get("client_id"),
+                    client_name: row.get("client_name"),
+                    stats: sla_stats_from_row(row),
+                });
+            }
+        }
+
+        Ok(SlaReport { targets, overall, by_client })
+    }
+
+    async fn get_cashflow_inputs(&self, user_id: Uuid, since: NaiveDate) -> Result<CashflowInputs, sqlx::Error> {
+        let open_invoices = sqlx::query(
+            r#"
+            SELECT id, invoice_number, client_id, due_date, (total_amount - amount_paid)::float8 AS balance
+            FROM invoices
+            WHERE user_id = $1 AND status IN ('sent', 'viewed', 'partial', 'overdue')
+              AND total_amount > amount_paid
+              AND trashed_at IS NULL AND deleted_at IS NULL
+            ORDER BY due_date
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?
+        .iter()
+        .map(|row| OpenReceivable {
+            invoice_id: row.get("id"),
+            invoice_number: row.get("invoice_number"),
+            client_id: row.get("client_id"),
+            due_date: row.get("due_date"),
+            balance: row.get("balance"),
+        })
+        .collect();
+
+        let payment_delays = sqlx::query(
+            r#"
+            SELECT client_id, AVG(paid_at::date - due_date)::float8 AS avg_delay_days, COUNT(*) AS paid_invoices
+            FROM invoices
+            WHERE user_id = $1 AND status = 'paid' AND paid_at IS NOT NULL AND paid_at::date >= $2
+            GROUP BY client_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?
+        .iter()
+        .map(|row| ClientPaymentDelay {
+            client_id: row.get("client_id"),
+            avg_delay_days: row.get("avg_delay_days"),
+            paid_invoices: row.get("paid_invoices"),
+        })
+        .collect();
+
+        // Recurring invoices bill the same client the same amount each time
+        let invoice_history = sqlx::query(
+            r#"
+            SELECT
+                i.client_id::text || ':' || i.total_amount::text AS series,
+                c.name AS label,
+                i.client_id,
+                i.issue_date AS date,
+                i.total_amount::float8 AS amount,
+                (i.due_date - i.issue_date)::int8 AS terms_days
+            FROM invoices i
+            JOIN clients c ON c.id = i.client_id
+            WHERE i.user_id = $1 AND i.issue_date >= $2
+              AND i.status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+              AND i.trashed_at IS NULL AND i.deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?
+        .iter()
+        .map(history_entry_from_row)
+        .collect();
+
+        // Recurring expenses come from the same vendor in the same category; the
+        // amount may vary, as with utilities
+        let expense_history = sqlx::query(
+            r#"
+            SELECT
+                LOWER(TRIM(vendor)) || ':' || category AS series,
+                TRIM(vendor) AS label,
+                NULL::uuid AS client_id,
+                date_incurred AS date,
+                amount::float8 AS amount,
+                0::int8 AS terms_days
+            FROM expenses
+            WHERE user_id = $1 AND date_incurred >= $2 AND TRIM(COALESCE(vendor, '')) <> ''
+            "#,
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&self.db)
+        .await?
+        .iter()
+        .map(history_entry_from_row)
+        .collect();
+
+        Ok(CashflowInputs { open_invoices, payment_delays, invoice_history, expense_history })
+    }
+
+    async fn get_timeseries(
+        &self,
+        user_id: Uuid,
+        metric: TimeseriesMetric,
+        interval: TimeseriesInterval,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<TimeseriesPoint>, sqlx::Error> {
+        let query = match metric {
+            TimeseriesMetric::Revenue => TIMESERIES_REVENUE_SQL,
+            TimeseriesMetric::Expenses => TIMESERIES_EXPENSES_SQL,
+            TimeseriesMetric::Outstanding => TIMESERIES_OUTSTANDING_SQL,
+        };
+
+        let rows = sqlx::query(query)
+            .bind(user_id)
+            .bind(start_date)
+            .bind(end_date)
+            .bind(interval.as_str())
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TimeseriesPoint {
+                bucket_start: row.get("bucket_start"),
+                bucket_end: row.get("bucket_end"),
+                value: row.get("value"),
+                count: row.get("count"),
+            })
+            .collect())
+    }
 }
+
+// Time-series queries take $1 user, $2 start, $3 end and $4 the `date_trunc`
+// field. Each builds its buckets with generate_series so empty ones still appear.
+
+const TIMESERIES_REVENUE_SQL: &str = r#"
+    WITH buckets AS (
+        SELECT b::date AS bucket_start, (b + ('1 ' || $4)::interval)::date - 1 AS bucket_end
+        FROM generate_series(date_trunc($4, $2::timestamp), $3::timestamp, ('1 ' || $4)::interval) b
+    )
+    SELECT k.bucket_start, LEAST(k.bucket_end, $3) AS bucket_end,
+           COALESCE(SUM(p.amount), 0)::float8 AS value, COUNT(p.id) AS count
+    FROM buckets k
+    LEFT JOIN payments p
+      ON p.user_id = $1 AND p.status = 'completed'
+     AND COALESCE(p.received_on, p.created_at::date) BETWEEN GREATEST(k.bucket_start, $2) AND LEAST(k.bucket_end, $3)
+    GROUP BY k.bucket_start, k.bucket_end
+    ORDER BY k.bucket_start
+"#;
+
+const TIMESERIES_EXPENSES_SQL: &str = r#"
+    WITH buckets AS (
+        SELECT b::date AS bucket_start, (b + ('1 ' || $4)::interval)::date - 1 AS bucket_end
+        FROM generate_series(date_trunc($4, $2::timestamp), $3::timestamp, ('1 ' || $4)::interval) b
+    )
+    SELECT k.bucket_start, LEAST(k.bucket_end, $3) AS bucket_end,
+           COALESCE(SUM(e.amount), 0)::float8 AS value, COUNT(e.id) AS count
+    FROM buckets k
+    LEFT JOIN expenses e
+      ON e.user_id = $1
+     AND e.date_incurred BETWEEN GREATEST(k.bucket_start, $2) AND LEAST(k.bucket_end, $3)
+    GROUP BY k.bucket_start, k.bucket_end
+    ORDER BY k.bucket_start
+"#;
+
+/// Invoices raise the balance, payments and applied credit lower it. Everything
+/// before the first bucket forms the opening balance; a running sum carries it
+/// through the buckets.
+const TIMESERIES_OUTSTANDING_SQL: &str = r#"
+    WITH buckets AS (
+        SELECT b::date AS bucket_start, (b + ('1 ' || $4)::interval)::date - 1 AS bucket_end
+        FROM generate_series(date_trunc($4, $2::timestamp), $3::timestamp, ('1 ' || $4)::interval) b
+    ),
+    billed AS (
+        SELECT id, issue_date, total_amount
+        FROM invoices
+        WHERE user_id = $1 AND issue_date <= $3
+          AND status NOT IN ('draft', 'cancelled', 'superseded', 'expired')
+          AND deleted_at IS NULL
+    ),
+    movements AS (
+        SELECT issue_date AS date, total_amount AS amount FROM billed
+        UNION ALL
+        SELECT COALESCE(p.received_on, p.created_at::date), -p.amount
+        FROM payments p
+        JOIN billed i ON i.id = p.invoice_id
+        WHERE p.user_id = $1 AND p.status = 'completed' AND COALESCE(p.received_on, p.created_at::date) <= $3
+        UNION ALL
+        SELECT n.issue_date, -n.applied_amount
+        FROM credit_notes n
+        JOIN billed i ON i.id = n.invoice_id
+        WHERE n.user_id = $1 AND n.status = 'issued' AND n.applied_amount > 0 AND n.issue_date <= $3
+    ),
+    per_bucket AS (
+        SELECT k.bucket_start, k.bucket_end, COALESCE(SUM(m.amount), 0) AS delta, COUNT(m.amount) AS count
+        FROM buckets k
+        LEFT JOIN movements m ON m.date BETWEEN k.bucket_start AND LEAST(k.bucket_end, $3)
+        GROUP BY k.bucket_start, k.bucket_end
+    )
+    SELECT bucket_start, LEAST(bucket_end, $3) AS bucket_end,
+           ((SELECT COALESCE(SUM(amount), 0) FROM movements WHERE date < (SELECT MIN(bucket_start) FROM buckets))
+             + SUM(delta) OVER (ORDER BY bucket_start))::float8 AS value,
+           count
+    FROM per_bucket
+    ORDER BY bucket_start
+"#;
@@ -0,0 +1,219 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::{
+    ClientSearchHit, ExpenseCategory, ExpenseSearchHit, InvoiceSearchHit, InvoiceStatus, SearchGroup,
+};
+
+/// `ts_headline` options for snippets: matched words between `**`, up to two
+/// short fragments
+const SNIPPET_OPTIONS: &str = r#"StartSel="**", StopSel="**", MaxWords=18, MinWords=6, MaxFragments=2, FragmentDelimiter=" … ""#;
+
+/// Full-text search over the `search_vector` columns. Each method takes a
+/// tsquery built by `prefix_tsquery` and returns the best ranked matches with
+/// the number of matches overall. Snippets are only built for the returned rows.
+#[derive(Clone)]
+pub struct SearchRepository {
+    db: PgPool,
+}
+
+impl SearchRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn search_invoices(
+        &self,
+        user_id: Uuid,
+        tsquery: &str,
+        limit: i64,
+    ) -> Result<SearchGroup<InvoiceSearchHit>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, InvoiceHitRow>(
+            r#"
+            SELECT
+                m.id, m.invoice_number, m.status, m.client_name, m.total_amount, m.issue_date, m.rank, m.total,
+                CASE WHEN to_tsvector('simple', search_words(m.body)) @@ to_tsquery('simple', $2)
+                    THEN ts_headline('simple', m.body, to_tsquery('simple', $2), $4)
+                END as snippet
+            FROM (
+                SELECT
+                    i.id, i.invoice_number, i.status, c.name as client_name, i.total_amount, i.issue_date,
+                    concat_ws(' ', i.notes, i.terms, (
+                        SELECT string_agg(item->>'description', ' ') FROM jsonb_array_elements(i.items) item
+                    )) as body,
+                    ts_rank(i.search_vector, to_tsquery('simple', $2)) as rank,
+                    COUNT(*) OVER () as total
+                FROM invoices i
+                JOIN clients c ON c.id = i.client_id
+                WHERE i.user_id = $1 AND i.deleted_at IS NULL AND i.search_vector @@ to_tsquery('simple', $2)
+                ORDER BY rank DESC, i.created_at DESC
+                LIMIT $3
+            ) m
+            ORDER BY m.rank DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(tsquery)
+        .bind(limit)
+        .bind(SNIPPET_OPTIONS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let items = rows
+            .into_iter()
+            .map(|row| InvoiceSearchHit {
+                id: row.id,
+                invoice_number: row.invoice_number,
+                status: row.status,
+                client_name: row.client_name,
+                total_amount: row.total_amount,
+                issue_date: row.issue_date,
+                snippet: row.snippet,
+                rank: row.rank,
+            })
+            .collect();
+
+        Ok(SearchGroup { total, items })
+    }
+
+    /// Deleted clients are left out; archived ones are found
+    pub async fn search_clients(
+        &self,
+        user_id: Uuid,
+        tsquery: &str,
+        limit: i64,
+    ) -> Result<SearchGroup<ClientSearchHit>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ClientHitRow>(
+            r#"
+            SELECT
+                m.id, m.name, m.email, m.company_name, m.rank, m.total,
+                CASE WHEN to_tsvector('simple', search_words(m.notes)) @@ to_tsquery('simple', $2)
+                    THEN ts_headline('simple', m.notes, to_tsquery('simple', $2), $4)
+                END as snippet
+            FROM (
+                SELECT
+                    c.id, c.name, c.email, c.company_name, c.notes,
+                    ts_rank(c.search_vector, to_tsquery('simple', $2)) as rank,
+                    COUNT(*) OVER () as total
+                FROM clients c
+                WHERE c.user_id = $1 AND c.deleted_at IS NULL AND c.search_vector @@ to_tsquery('simple', $2)
+                ORDER BY rank DESC, c.created_at DESC
+                LIMIT $3
+            ) m
+            ORDER BY m.rank DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(tsquery)
+        .bind(limit)
+        .bind(SNIPPET_OPTIONS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let items = rows
+            .into_iter()
+            .map(|row| ClientSearchHit {
+                id: row.id,
+                name: row.name,
+                email: row.email,
+                company_name: row.company_name,
+                snippet: row.snippet,
+                rank: row.rank,
+            })
+            .collect();
+
+        Ok(SearchGroup { total, items })
+    }
+
+    pub async fn search_expenses(
+        &self,
+        user_id: Uuid,
+        tsquery: &str,
+        limit: i64,
+    ) -> Result<SearchGroup<ExpenseSearchHit>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, ExpenseHitRow>(
+            r#"
+            SELECT
+                m.id, m.vendor, m.category, m.amount, m.currency, m.date_incurred, m.rank, m.total,
+                CASE WHEN to_tsvector('simple', search_words(m.description)) @@ to_tsquery('simple', $2)
+                    THEN ts_headline('simple', m.description, to_tsquery('simple', $2), $4)
+                END as snippet
+            FROM (
+                SELECT
+                    e.id, e.vendor, e.category, e.amount, e.currency, e.date_incurred, e.description,
+                    ts_rank(e.search_vector, to_tsquery('simple', $2)) as rank,
+                    COUNT(*) OVER () as total
+                FROM expenses e
+                WHERE e.user_id = $1 AND e.search_vector @@ to_tsquery('simple', $2)
+                ORDER BY rank DESC, e.created_at DESC
+                LIMIT $3
+            ) m
+            ORDER BY m.rank DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(tsquery)
+        .bind(limit)
+        .bind(SNIPPET_OPTIONS)
+        .fetch_all(&self.db)
+        .await?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let items = rows
+            .into_iter()
+            .map(|row| ExpenseSearchHit {
+                id: row.id,
+                vendor: row.vendor,
+                category: ExpenseCategory::from_stored(&row.category),
+                amount: row.amount,
+                currency: row.currency,
+                date_incurred: row.date_incurred,
+                snippet: row.snippet,
+                rank: row.rank,
+            })
+            .collect();
+
+        Ok(SearchGroup { total, items })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct InvoiceHitRow {
+    id: Uuid,
+    invoice_number: String,
+    status: InvoiceStatus,
+    client_name: String,
+    total_amount: Decimal,
+    issue_date: NaiveDate,
+    snippet: Option<String>,
+    rank: f32,
+    total: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ClientHitRow {
+    id: Uuid,
+    name: String,
+    email: Option<String>,
+    company_name: Option<String>,
+    snippet: Option<String>,
+    rank: f32,
+    total: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct ExpenseHitRow {
+    id: Uuid,
+    vendor: Option<String>,
+    category: String,
+    amount: Decimal,
+    currency: String,
+    date_incurred: NaiveDate,
+    snippet: Option<String>,
+    rank: f32,
+    total: i64,
+}
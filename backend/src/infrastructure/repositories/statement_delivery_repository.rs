@@ -0,0 +1,80 @@
+use chrono::{NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::domain::models::StatementRecipient;
+
+#[derive(Clone)]
+pub struct StatementDeliveryRepository {
+    db: PgPool,
+}
+
+impl StatementDeliveryRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Clients of users with monthly statements enabled that haven't opted out
+    /// and haven't been sent the statement starting at `period_start`
+    pub async fn list_due(&self, period_start: NaiveDate) -> Result<Vec<StatementRecipient>, sqlx::Error> {
+        sqlx::query_as::<_, StatementRecipient>(
+            r#"
+            SELECT
+                c.user_id, c.id as client_id, c.name as client_name, c.email as client_email,
+                COALESCE(u.company_name, u.email) as seller_name
+            FROM clients c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.email IS NOT NULL AND c.email <> ''
+              AND NOT c.statement_opt_out
+              AND c.deleted_at IS NULL
+              AND COALESCE((u.notification_settings->>'email_monthly_statements')::boolean, FALSE)
+              AND NOT EXISTS (
+                  SELECT 1 FROM statement_deliveries sd
+                  WHERE sd.client_id = c.id AND sd.period_start = $1
+              )
+            ORDER BY c.user_id, c.name
+            "#,
+        )
+        .bind(period_start)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Claim a client's statement for a period. Returns false when another run
+    /// already claimed it, so concurrent instances never send it twice.
+    pub async fn record(
+        &self,
+        recipient: &StatementRecipient,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO statement_deliveries (id, user_id, client_id, period_start, period_end, sent_to, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (client_id, period_start) DO NOTHING
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(recipient.user_id)
+        .bind(recipient.client_id)
+        .bind(period_start)
+        .bind(period_end)
+        .bind(&recipient.client_email)
+        .bind(Utc::now())
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Release a claim when the statement could not be handed off for delivery
+    pub async fn release(&self, client_id: Uuid, period_start: NaiveDate) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM statement_deliveries WHERE client_id = $1 AND period_start = $2")
+            .bind(client_id)
+            .bind(period_start)
+            .execute(&self.db)
+            .await?;
+        Ok(())
+    }
+}
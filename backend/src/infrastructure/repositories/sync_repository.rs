@@ -0,0 +1,353 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    InvoiceAction, InvoiceStatus, SyncChange, SyncClientFields, SyncConflictReason, SyncCursor, SyncEntityType, SyncInvoiceFields, SyncWrite,
+    SyncWriteOutcome,
+};
+use crate::infrastructure::repositories::ClientRepository;
+
+/// Server copy of an entity a mutation targets, locked for the rest of the transaction
+#[derive(sqlx::FromRow)]
+struct LockedRow {
+    user_id: Uuid,
+    updated_at: Option<DateTime<Utc>>,
+    /// Clients: in the trash
+    deleted: bool,
+    /// Invoices: the status, which decides what can be changed
+    status: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct SyncRepository {
+    db: PgPool,
+}
+
+impl SyncRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// Changes after the cursor in feed order, leaving out the last `settle_seconds` so
+    /// rows from transactions still in flight aren't skipped past. Without a cursor only
+    /// live entities are listed.
+    pub async fn changes(
+        &self,
+        user_id: Uuid,
+        since: Option<SyncCursor>,
+        settle_seconds: f64,
+        limit: i64,
+    ) -> Result<Vec<SyncChange>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, SyncChangeRow>(
+            r#"
+            SELECT entity_type, entity_id, changed_at, deleted
+            FROM (
+                SELECT 'invoice' as entity_type, id as entity_id,
+                       COALESCE(updated_at, created_at) as changed_at, deleted_at IS NOT NULL as deleted
+                FROM invoices WHERE user_id = $1
+                UNION ALL
+                SELECT 'client', id, COALESCE(updated_at, created_at), deleted_at IS NOT NULL
+                FROM clients WHERE user_id = $1
+                UNION ALL
+                SELECT 'payment', id, COALESCE(updated_at, created_at), FALSE
+                FROM payments WHERE user_id = $1
+                UNION ALL
+                SELECT entity_type, entity_id, deleted_at, TRUE
+                FROM sync_tombstones WHERE user_id = $1
+                UNION ALL
+                SELECT 'settings', id, updated_at, FALSE
+                FROM users WHERE id = $1
+            ) changes
+            WHERE changed_at <= NOW() - make_interval(secs => $2)
+              AND ($3::timestamptz IS NULL OR (changed_at, entity_id) > ($3, $4))
+              AND ($3::timestamptz IS NOT NULL OR NOT deleted)
+            ORDER BY changed_at, entity_id
+            LIMIT $5
+            "#,
+        )
+        .bind(user_id)
+        .bind(settle_seconds)
+        .bind(since.map(|cursor| cursor.changed_at))
+        .bind(since.map(|cursor| cursor.entity_id))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().filter_map(SyncChangeRow::into_change).collect())
+    }
+
+    /// Apply offline writes in one transaction. Each write is checked against the locked
+    /// server copy; a conflicting write is skipped and the rest still commit.
+    pub async fn apply(
+        &self,
+        user_id: Uuid,
+        writes: &[(Uuid, Option<DateTime<Utc>>, SyncWrite)],
+    ) -> Result<Vec<SyncWriteOutcome>, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+        let mut outcomes = Vec::with_capacity(writes.len());
+
+        for (entity_id, base_version, write) in writes {
+            let outcome = match write {
+                SyncWrite::CreateClient(fields) => Self::create_client(&mut tx, user_id, *entity_id, fields).await?,
+                SyncWrite::UpdateClient(_) | SyncWrite::DeleteClient => {
+                    let locked = Self::lock_client(&mut tx, *entity_id).await?;
+                    match Self::check(locked, user_id, *base_version, None) {
+                        Err(conflict) => conflict,
+                        Ok(()) => match write {
+                            SyncWrite::UpdateClient(fields) => Self::update_client(&mut tx, user_id, *entity_id, fields).await?,
+                            _ => Self::delete_client(&mut tx, user_id, *entity_id).await?,
+                        },
+                    }
+                }
+                SyncWrite::UpdateInvoice(_) | SyncWrite::DeleteInvoice => {
+                    let locked = Self::lock_invoice(&mut tx, *entity_id).await?;
+                    let action = match write {
+                        SyncWrite::UpdateInvoice(_) => InvoiceAction::Edit,
+                        _ => InvoiceAction::Delete,
+                    };
+                    match Self::check(locked, user_id, *base_version, Some(action)) {
+                        Err(conflict) => conflict,
+                        Ok(()) => match write {
+                            SyncWrite::UpdateInvoice(fields) => Self::update_invoice(&mut tx, user_id, *entity_id, fields).await?,
+                            _ => Self::delete_invoice(&mut tx, user_id, *entity_id).await?,
+                        },
+                    }
+                }
+            };
+            outcomes.push(outcome);
+        }
+
+        tx.commit().await?;
+        Ok(outcomes)
+    }
+
+    /// Whether a write may go ahead against the locked server copy. Invoice writes
+    /// also have to be allowed by the invoice's status.
+    fn check(
+        locked: Option<LockedRow>,
+        user_id: Uuid,
+        base_version: Option<DateTime<Utc>>,
+        action: Option<InvoiceAction>,
+    ) -> Result<(), SyncWriteOutcome> {
+        let row = match locked {
+            Some(row) if row.user_id == user_id => row,
+            _ => return Err(SyncWriteOutcome::Conflict(SyncConflictReason::NotFound, None)),
+        };
+        if row.deleted {
+            return Err(SyncWriteOutcome::Conflict(SyncConflictReason::Deleted, None));
+        }
+        if row.updated_at != base_version {
+            return Err(SyncWriteOutcome::Conflict(SyncConflictReason::VersionMismatch, row.updated_at));
+        }
+        let status = row.status.as_deref().and_then(InvoiceStatus::parse);
+        if let (Some(action), Some(status)) = (action, status) {
+            if !status.allows(action) {
+                return Err(SyncWriteOutcome::Conflict(SyncConflictReason::NotEditable, row.updated_at));
+            }
+        }
+        Ok(())
+    }
+
+    async fn lock_client(conn: &mut PgConnection, client_id: Uuid) -> Result<Option<LockedRow>, sqlx::Error> {
+        sqlx::query_as::<_, LockedRow>(
+            r#"
+            SELECT user_id, updated_at, deleted_at IS NOT NULL as deleted, NULL::varchar as status
+            FROM clients WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(client_id)
+        .fetch_optional(&mut *conn)
+        .await
+    }
+
+    async fn lock_invoice(conn: &mut PgConnection, invoice_id: Uuid) -> Result<Option<LockedRow>, sqlx::Error> {
+        sqlx::query_as::<_, LockedRow>(
+            r#"
+            SELECT user_id, updated_at, deleted_at IS NOT NULL as deleted, status
+            FROM invoices WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(invoice_id)
+        .fetch_optional(&mut *conn)
+        .await
+    }
+
+    async fn create_client(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        client_id: Uuid,
+        fields: &SyncClientFields,
+    ) -> Result<SyncWriteOutcome, sqlx::Error> {
+        let now = Utc::now();
+        let created: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            INSERT INTO clients (
+                id, user_id, name, email, phone, company_name,
+                billing_address, payment_terms, tax_exempt, notes,
+                created_at, updated_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, FALSE, $9, $10, $10)
+            ON CONFLICT (id) DO NOTHING
+            RETURNING updated_at
+            "#,
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .bind(&fields.name)
+        .bind(&fields.email)
+        .bind(&fields.phone)
+        .bind(&fields.company_name)
+        .bind(&fields.billing_address)
+        .bind(fields.payment_terms.unwrap_or(30))
+        .bind(&fields.notes)
+        .bind(now)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        match created {
+            Some(version) => Ok(SyncWriteOutcome::Applied(Some(version))),
+            None => {
+                // Only reveal the existing version when the client is the caller's own
+                let existing = Self::lock_client(conn, client_id).await?;
+                let version = existing.filter(|row| row.user_id == user_id).and_then(|row| row.updated_at);
+                Ok(SyncWriteOutcome::Conflict(SyncConflictReason::AlreadyExists, version))
+            }
+        }
+    }
+
+    async fn update_client(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        client_id: Uuid,
+        fields: &SyncClientFields,
+    ) -> Result<SyncWriteOutcome, sqlx::Error> {
+        let version: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            UPDATE clients SET
+                name = COALESCE($3, name),
+                email = COALESCE($4, email),
+                phone = COALESCE($5, phone),
+                company_name = COALESCE($6, company_name),
+                billing_address = COALESCE($7, billing_address),
+                payment_terms = COALESCE($8, payment_terms),
+                notes = COALESCE($9, notes),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING updated_at
+            "#,
+        )
+        .bind(client_id)
+        .bind(user_id)
+        .bind(&fields.name)
+        .bind(&fields.email)
+        .bind(&fields.phone)
+        .bind(&fields.company_name)
+        .bind(&fields.billing_address)
+        .bind(fields.payment_terms)
+        .bind(&fields.notes)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(SyncWriteOutcome::Applied(version))
+    }
+
+    /// Same rules as deleting through the clients API: no outstanding invoices or subsidiaries
+    async fn delete_client(conn: &mut PgConnection, user_id: Uuid, client_id: Uuid) -> Result<SyncWriteOutcome, sqlx::Error> {
+        let (active_invoices, subsidiaries): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM invoices
+                 WHERE user_id = $1 AND client_id = $2
+                   AND status NOT IN ('draft', 'paid', 'cancelled', 'superseded', 'expired')),
+                (SELECT COUNT(*) FROM clients
+                 WHERE user_id = $1 AND parent_client_id = $2 AND deleted_at IS NULL)
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        if active_invoices > 0 || subsidiaries > 0 {
+            let reason = if active_invoices > 0 {
+                SyncConflictReason::HasActiveInvoices
+            } else {
+                SyncConflictReason::HasSubsidiaries
+            };
+            let version = Self::lock_client(conn, client_id).await?.and_then(|row| row.updated_at);
+            return Ok(SyncWriteOutcome::Conflict(reason, version));
+        }
+
+        ClientRepository::trash(conn, user_id, client_id, Utc::now()).await?;
+        Ok(SyncWriteOutcome::Applied(None))
+    }
+
+    async fn update_invoice(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        invoice_id: Uuid,
+        fields: &SyncInvoiceFields,
+    ) -> Result<SyncWriteOutcome, sqlx::Error> {
+        let version: Option<DateTime<Utc>> = sqlx::query_scalar(
+            r#"
+            UPDATE invoices SET
+                notes = COALESCE($3, notes),
+                terms = COALESCE($4, terms),
+                due_date = COALESCE($5, due_date),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING updated_at
+            "#,
+        )
+        .bind(invoice_id)
+        .bind(user_id)
+        .bind(&fields.notes)
+        .bind(&fields.terms)
+        .bind(fields.due_date)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(SyncWriteOutcome::Applied(version))
+    }
+
+    /// Moves the draft to the trash, like deleting through the invoices API
+    async fn delete_invoice(conn: &mut PgConnection, user_id: Uuid, invoice_id: Uuid) -> Result<SyncWriteOutcome, sqlx::Error> {
+        sqlx::query("UPDATE invoices SET deleted_at = NOW(), updated_at = NOW() WHERE id = $1 AND user_id = $2")
+            .bind(invoice_id)
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(SyncWriteOutcome::Applied(None))
+    }
+
+    pub async fn prune_tombstones(&self, before: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM sync_tombstones WHERE deleted_at < $1")
+            .bind(before)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SyncChangeRow {
+    entity_type: String,
+    entity_id: Uuid,
+    changed_at: DateTime<Utc>,
+    deleted: bool,
+}
+
+impl SyncChangeRow {
+    fn into_change(self) -> Option<SyncChange> {
+        Some(SyncChange {
+            entity_type: SyncEntityType::parse(&self.entity_type)?,
+            entity_id: self.entity_id,
+            changed_at: self.changed_at,
+            deleted: self.deleted,
+        })
+    }
+}
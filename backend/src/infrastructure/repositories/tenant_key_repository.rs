@@ -0,0 +1,73 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A tenant's data key as stored: wrapped under the master key `master_key_id`
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TenantDataKey {
+    pub id: Uuid,
+    pub master_key_id: String,
+    pub wrapped_key: Vec<u8>,
+}
+
+#[derive(Clone)]
+pub struct TenantKeyRepository {
+    db: PgPool,
+}
+
+impl TenantKeyRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn find_active(&self, user_id: Uuid) -> Result<Option<TenantDataKey>, sqlx::Error> {
+        sqlx::query_as::<_, TenantDataKey>(
+            r#"
+            SELECT id, master_key_id, wrapped_key FROM tenant_data_keys
+            WHERE user_id = $1 AND retired_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Retired keys included, so older files stay readable
+    pub async fn find(&self, user_id: Uuid, key_id: Uuid) -> Result<Option<TenantDataKey>, sqlx::Error> {
+        sqlx::query_as::<_, TenantDataKey>(
+            r#"
+            SELECT id, master_key_id, wrapped_key FROM tenant_data_keys
+            WHERE user_id = $1 AND id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(key_id)
+        .fetch_optional(&self.db)
+        .await
+    }
+
+    /// Stores `key` as the tenant's active key, unless a concurrent upload
+    /// created one first; either way the active key is returned
+    pub async fn create_active(&self, user_id: Uuid, key: &TenantDataKey) -> Result<TenantDataKey, sqlx::Error> {
+        let inserted = sqlx::query_as::<_, TenantDataKey>(
+            r#"
+            INSERT INTO tenant_data_keys (id, user_id, master_key_id, wrapped_key, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id) WHERE retired_at IS NULL DO NOTHING
+            RETURNING id, master_key_id, wrapped_key
+            "#,
+        )
+        .bind(key.id)
+        .bind(user_id)
+        .bind(&key.master_key_id)
+        .bind(&key.wrapped_key)
+        .bind(Utc::now())
+        .fetch_optional(&self.db)
+        .await?;
+
+        match inserted {
+            Some(key) => Ok(key),
+            None => self.find_active(user_id).await?.ok_or(sqlx::Error::RowNotFound),
+        }
+    }
+}
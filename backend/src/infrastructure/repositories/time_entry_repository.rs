@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::domain::models::{Page, PageCursor, PageRequest, TimeEntry, TimeEntryListFilter};
+use super::pagination::{push_page_after, push_page_window};
+
+#[derive(Clone)]
+pub struct TimeEntryRepository {
+    db: PgPool,
+}
+
+impl TimeEntryRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    /// One page of the user's entries, latest start first
+    pub async fn list(
+        &self,
+        user_id: Uuid,
+        filter: &TimeEntryListFilter,
+        page: &PageRequest,
+    ) -> Result<Page<TimeEntry>, sqlx::Error> {
+        let mut count_builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM time_entries WHERE user_id = ");
+        count_builder.push_bind(user_id);
+        push_list_filters(&mut count_builder, filter);
+        let total: i64 = count_builder.build_query_scalar().fetch_one(&self.db).await?;
+
+        let mut query_builder = QueryBuilder::<Postgres>::new("SELECT * FROM time_entries WHERE user_id = ");
+        query_builder.push_bind(user_id);
+        push_list_filters(&mut query_builder, filter);
+        push_page_after(&mut query_builder, page, "started_at", "id");
+        push_page_window(&mut query_builder, page, "started_at", "id");
+
+        let rows: Vec<TimeEntryRow> = query_builder.build_query_as().fetch_all(&self.db).await?;
+        let entries = rows.into_iter().map(TimeEntryRow::into_entry).collect();
+
+        Ok(Page::new(page, entries, total, |entry: &TimeEntry| PageCursor::new(entry.started_at, entry.id)))
+    }
+
+    pub async fn find_by_id(&self, user_id: Uuid, entry_id: Uuid) -> Result<Option<TimeEntry>, sqlx::Error> {
+        let row = sqlx::query_as::<_, TimeEntryRow>("SELECT * FROM time_entries WHERE id = $1 AND user_id = $2")
+            .bind(entry_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(TimeEntryRow::into_entry))
+    }
+
+    /// The user's running timer, if any
+    pub async fn find_running(&self, user_id: Uuid) -> Result<Option<TimeEntry>, sqlx::Error> {
+        let row = sqlx::query_as::<_, TimeEntryRow>("SELECT * FROM time_entries WHERE user_id = $1 AND ended_at IS NULL")
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await?;
+
+        Ok(row.map(TimeEntryRow::into_entry))
+    }
+
+    /// Stopped, billable, unbilled entries of a client, oldest first; with a
+    /// project, only that project's
+    pub async fn list_unbilled(
+        &self,
+        user_id: Uuid,
+        client_id: Uuid,
+        project_id: Option<Uuid>,
+        started_before: Option<DateTime<Utc>>,
+    ) -> Result<Vec<TimeEntry>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, TimeEntryRow>(
+            r#"
+            SELECT * FROM time_entries
+            WHERE user_id = $1 AND client_id = $2
+              AND ended_at IS NOT NULL AND billable AND invoice_id IS NULL
+              AND ($3::timestamptz IS NULL OR started_at < $3)
+              AND ($4::uuid IS NULL OR project_id = $4)
+            ORDER BY started_at, id
+            "#,
+        )
+        .bind(user_id)
+        .bind(client_id)
+        .bind(started_before)
+        .bind(project_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(TimeEntryRow::into_entry).collect())
+    }
+
+    pub async fn find_by_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<TimeEntry>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, TimeEntryRow>(
+            "SELECT * FROM time_entries WHERE user_id = $1 AND id = ANY($2) ORDER BY started_at, id",
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(TimeEntryRow::into_entry).collect())
+    }
+
+    pub async fn create(&self, user_id: Uuid, entry: &TimeEntry) -> Result<TimeEntry, sqlx::Error> {
+        let row = sqlx::query_as::<_, TimeEntryRow>(
+            r#"
+            INSERT INTO time_entries (
+                id, user_id, client_id, description, started_at, ended_at, hourly_rate, billable,
+                created_at, updated_at, project_id
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $9, $10)
+            RETURNING *
+            "#,
+        )
+        .bind(entry.id)
+        .bind(user_id)
+        .bind(entry.client_id)
+        .bind(&entry.description)
+        .bind(entry.started_at)
+        .bind(entry.ended_at)
+        .bind(entry.hourly_rate)
+        .bind(entry.billable)
+        .bind(entry.created_at)
+        .bind(entry.project_id)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_entry())
+    }
+
+    /// Saves an entry the service has already merged with the update; billed
+    /// entries are left alone
+    pub async fn update(&self, user_id: Uuid, entry: &TimeEntry) -> Result<Option<TimeEntry>, sqlx::Error> {
+        let row = sqlx::query_as::<_, TimeEntryRow>(
+            r#"
+            UPDATE time_entries SET
+                client_id = $3, description = $4, started_at = $5, ended_at = $6, hourly_rate = $7,
+                billable = $8, updated_at = $9, project_id = $10
+            WHERE id = $1 AND user_id = $2 AND invoice_id IS NULL
+            RETURNING *
+            "#,
+        )
+        .bind(entry.id)
+        .bind(user_id)
+        .bind(entry.client_id)
+        .bind(&entry.description)
+        .bind(entry.started_at)
+        .bind(entry.ended_at)
+        .bind(entry.hourly_rate)
+        .bind(entry.billable)
+        .bind(Utc::now())
+        .bind(entry.project_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(TimeEntryRow::into_entry))
+    }
+
+    /// Deletes an unbilled entry; false if there was none
+    pub async fn delete(&self, user_id: Uuid, entry_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM time_entries WHERE id = $1 AND user_id = $2 AND invoice_id IS NULL")
+            .bind(entry_id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Attaches the entries to an invoice, skipping any billed in the meantime.
+    /// Returns how many were marked.
+    pub async fn mark_billed(
+        &self,
+        user_id: Uuid,
+        ids: &[Uuid],
+        invoice_id: Uuid,
+        billed_at: DateTime<Utc>,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE time_entries SET invoice_id = $3, billed_at = $4, updated_at = $4
+            WHERE user_id = $1 AND id = ANY($2) AND invoice_id IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .bind(ids)
+        .bind(invoice_id)
+        .bind(billed_at)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Returns an invoice's entries to unbilled
+    pub async fn release(&self, user_id: Uuid, invoice_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE time_entries SET invoice_id = NULL, billed_at = NULL, updated_at = NOW() WHERE user_id = $1 AND invoice_id = $2",
+        )
+        .bind(user_id)
+        .bind(invoice_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// The client's hourly rate: None if the client isn't the user's (or is in the
+    /// trash), Some(None) if it has no rate
+    pub async fn client_hourly_rate(&self, user_id: Uuid, client_id: Uuid) -> Result<Option<Option<Decimal>>, sqlx::Error> {
+        sqlx::query_scalar("SELECT hourly_rate FROM clients WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL")
+            .bind(client_id)
+            .bind(user_id)
+            .fetch_optional(&self.db)
+            .await
+    }
+}
+
+fn push_list_filters(query_builder: &mut QueryBuilder<'_, Postgres>, filter: &TimeEntryListFilter) {
+    if let Some(client_id) = filter.client_id {
+        query_builder.push(" AND client_id = ");
+        query_builder.push_bind(client_id);
+    }
+    if let Some(project_id) = filter.project_id {
+        query_builder.push(" AND project_id = ");
+        query_builder.push_bind(project_id);
+    }
+    if let Some(from) = filter.from {
+        query_builder.push(" AND started_at >= ");
+        query_builder.push_bind(from.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    if let Some(to) = filter.to {
+        query_builder.push(" AND started_at < ");
+        query_builder.push_bind((to + chrono::Duration::days(1)).and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    match filter.billed {
+        Some(true) => {
+            query_builder.push(" AND invoice_id IS NOT NULL");
+        }
+        Some(false) => {
+            query_builder.push(" AND invoice_id IS NULL");
+        }
+        None => {}
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TimeEntryRow {
+    id: Uuid,
+    #[allow(dead_code)]
+    user_id: Uuid,
+    client_id: Uuid,
+    project_id: Option<Uuid>,
+    description: String,
+    started_at: DateTime<Utc>,
+    ended_at: Option<DateTime<Utc>>,
+    hourly_rate: Option<Decimal>,
+    billable: bool,
+    invoice_id: Option<Uuid>,
+    billed_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TimeEntryRow {
+    fn into_entry(self) -> TimeEntry {
+        TimeEntry {
+            id: self.id,
+            client_id: self.client_id,
+            project_id: self.project_id,
+            description: self.description,
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            duration_minutes: self.ended_at.map(|ended_at| (ended_at - self.started_at).num_minutes()),
+            hourly_rate: self.hourly_rate,
+            billable: self.billable,
+            invoice_id: self.invoice_id,
+            billed_at: self.billed_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
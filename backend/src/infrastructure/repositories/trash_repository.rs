@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Invoices that keep a trashed client from being purged: anything issued. Drafts,
+/// trashed or not, and the drafts cancelled along with the client go with it.
+const HAS_ISSUED_INVOICES: &str = r#"
+    EXISTS (
+        SELECT 1 FROM invoices i
+        WHERE i.client_id = c.id AND i.status <> 'draft' AND i.trashed_at IS NULL
+    )
+"#;
+
+/// A trashed invoice as listed; `TrashService` adds when it will be purged
+#[derive(Debug, sqlx::FromRow)]
+pub struct TrashedInvoiceRow {
+    pub id: Uuid,
+    pub invoice_number: String,
+    pub client_id: Uuid,
+    pub client_name: String,
+    pub total_amount: Decimal,
+    pub currency: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+pub struct TrashedClientRow {
+    pub id: Uuid,
+    pub name: String,
+    pub email: Option<String>,
+    pub company_name: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    pub has_issued_invoices: bool,
+}
+
+/// What a purge removed. The stored files are left for the caller to delete.
+#[derive(Debug, Default)]
+pub struct PurgedTrash {
+    pub invoices: u64,
+    pub clients: u64,
+    /// Cached PDFs of the purged invoices, by user
+    pub pdf_urls: Vec<(Uuid, String)>,
+    /// Stored attachment files of the purged invoices, by user
+    pub attachment_files: Vec<(Uuid, String)>,
+}
+
+/// Trashed invoices (deleted drafts) and clients, and their permanent removal
+#[derive(Clone)]
+pub struct TrashRepository {
+    db: PgPool,
+}
+
+impl TrashRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_invoices(&self, user_id: Uuid) -> Result<Vec<TrashedInvoiceRow>, sqlx::Error> {
+        sqlx::query_as::<_, TrashedInvoiceRow>(
+            r#"
+            SELECT
+                i.id, i.invoice_number, i.client_id, c.name as client_name, i.total_amount,
+                COALESCE(i.currency, 'USD') as currency, i.deleted_at
+            FROM invoices i
+            JOIN clients c ON c.id = i.client_id
+            WHERE i.user_id = $1 AND i.deleted_at IS NOT NULL
+            ORDER BY i.deleted_at DESC, i.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    pub async fn list_clients(&self, user_id: Uuid) -> Result<Vec<TrashedClientRow>, sqlx::Error> {
+        sqlx::query_as::<_, TrashedClientRow>(&format!(
+            r#"
+            SELECT c.id, c.name, c.email, c.company_name, c.deleted_at, {} as has_issued_invoices
+            FROM clients c
+            WHERE c.user_id = $1 AND c.deleted_at IS NOT NULL
+            ORDER BY c.deleted_at DESC, c.id
+            "#,
+            HAS_ISSUED_INVOICES
+        ))
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Permanently delete invoices trashed before `before`, and clients trashed before
+    /// then that have no issued invoices, along with their drafts. Clients are locked
+    /// first so a restore can't interleave.
+    pub async fn purge(&self, before: DateTime<Utc>) -> Result<PurgedTrash, sqlx::Error> {
+        let mut tx = self.db.begin().await?;
+
+        let client_ids: Vec<Uuid> = sqlx::query_scalar(&format!(
+            "SELECT c.id FROM clients c WHERE c.deleted_at < $1 AND NOT {} FOR UPDATE",
+            HAS_ISSUED_INVOICES
+        ))
+        .bind(before)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let attachment_files: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT a.user_id, a.file_name
+            FROM attachments a
+            JOIN invoices i ON i.id = a.invoice_id
+            WHERE (i.deleted_at < $1 AND i.status = 'draft') OR i.client_id = ANY($2)
+            "#,
+        )
+        .bind(before)
+        .bind(&client_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let purged: Vec<(Uuid, Option<String>)> = sqlx::query_as(
+            r#"
+            DELETE FROM invoices
+            WHERE (deleted_at < $1 AND status = 'draft') OR client_id = ANY($2)
+            RETURNING user_id, pdf_url
+            "#,
+        )
+        .bind(before)
+        .bind(&client_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let clients = sqlx::query("DELETE FROM clients WHERE id = ANY($1)")
+            .bind(&client_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+
+        Ok(PurgedTrash {
+            invoices: purged.len() as u64,
+            clients,
+            pdf_urls: purged
+                .into_iter()
+                .filter_map(|(user_id, pdf_url)| pdf_url.map(|url| (user_id, url)))
+                .collect(),
+            attachment_files,
+        })
+    }
+}
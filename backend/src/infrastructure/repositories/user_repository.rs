@@ -2,7 +2,8 @@ use sqlx::{PgPool, Postgres, QueryBuilder};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 
-use crate::domain::models::{User, UpdateUser, SubscriptionTier, SubscriptionStatus, InvoiceSettings};
+use crate::domain::i18n::Locale;
+use crate::domain::models::{User, UpdateUser, SubscriptionTier, SubscriptionStatus, InvoiceSettings, LoginLockout};
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -59,9 +60,10 @@ impl UserRepository {
         Ok(user.to_user())
     }
 
+    /// Find a login account by email; business accounts can't sign in and are skipped
     pub async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error> {
         let user = sqlx::query_as::<_, UserRow>(
-            "SELECT * FROM users WHERE email = $1"
+            "SELECT * FROM users u WHERE u.email = $1 AND NOT EXISTS (SELECT 1 FROM businesses b WHERE b.id = u.id)"
         )
         .bind(email)
         .fetch_optional(&self.db)
@@ -130,6 +132,11 @@ impl UserRepository {
             query_builder.push_bind(json);
         }
 
+        if let Some(locale) = update.locale {
+            query_builder.push(", locale = ");
+            query_builder.push_bind(locale.code());
+        }
+
         query_builder.push(" WHERE id = ");
         query_builder.push_bind(user_id);
         query_builder.push(" RETURNING *");
@@ -142,6 +149,15 @@ impl UserRepository {
         Ok(user.to_user())
     }
 
+    /// Whether `business_id` is an additional business owned by `owner_id`
+    pub async fn owns_business(&self, owner_id: Uuid, business_id: Uuid) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM businesses WHERE id = $1 AND owner_id = $2)")
+            .bind(business_id)
+            .bind(owner_id)
+            .fetch_one(&self.db)
+            .await
+    }
+
     pub async fn update_password_hash(&self, user_id: Uuid, password_hash: &str) -> Result<(), sqlx::Error> {
         sqlx::query(
             "UPDATE users SET password_hash = $1, updated_at = $2 WHERE id = $3"
@@ -220,6 +236,96 @@ impl UserRepository {
 
         Ok(())
     }
+
+    pub async fn get_login_lockout(&self, user_id: Uuid) -> Result<LoginLockout, sqlx::Error> {
+        sqlx::query_as::<_, LoginLockout>(
+            "SELECT failed_login_attempts, locked_until, lockout_count FROM users WHERE id = $1"
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Count a wrong password; returns the failures in a row
+    pub async fn increment_failed_logins(&self, user_id: Uuid) -> Result<i32, sqlx::Error> {
+        sqlx::query_scalar(
+            "UPDATE users SET failed_login_attempts = failed_login_attempts + 1 WHERE id = $1 RETURNING failed_login_attempts"
+        )
+        .bind(user_id)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Lock until `until` and start counting failures afresh
+    pub async fn lock_account(&self, user_id: Uuid, until: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE users SET locked_until = $1, failed_login_attempts = 0, lockout_count = lockout_count + 1 WHERE id = $2"
+        )
+        .bind(until)
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// After a successful sign-in or a password reset
+    pub async fn clear_login_lockout(&self, user_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE users SET failed_login_attempts = 0, locked_until = NULL, lockout_count = 0 WHERE id = $1"
+        )
+        .bind(user_id)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_login_attempt(
+        &self,
+        email: &str,
+        user_id: Option<Uuid>,
+        ip_address: Option<&str>,
+        succeeded: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO login_attempts (id, email, user_id, ip_address, succeeded) VALUES ($1, $2, $3, $4::inet, $5)"
+        )
+        .bind(Uuid::new_v4())
+        .bind(email)
+        .bind(user_id)
+        .bind(ip_address)
+        .bind(succeeded)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Failed sign-ins from the IP since `since`, and when the oldest of them was
+    pub async fn failed_logins_from_ip(
+        &self,
+        ip_address: &str,
+        since: DateTime<Utc>,
+    ) -> Result<(i64, Option<DateTime<Utc>>), sqlx::Error> {
+        sqlx::query_as(
+            "SELECT COUNT(*), MIN(attempted_at) FROM login_attempts WHERE ip_address = $1::inet AND NOT succeeded AND attempted_at >= $2"
+        )
+        .bind(ip_address)
+        .bind(since)
+        .fetch_one(&self.db)
+        .await
+    }
+
+    /// Sign-in history is kept for 30 days
+    pub async fn prune_login_attempts(&self, email: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM login_attempts WHERE email = $1 AND attempted_at < NOW() - INTERVAL '30 days'")
+            .bind(email)
+            .execute(&self.db)
+            .await?;
+
+        Ok(())
+    }
 }
 
 #[derive(sqlx::FromRow)]
@@ -242,6 +348,7 @@ struct UserRow {
     business_address: Option<serde_json::Value>,
     tax_settings: Option<serde_json::Value>,
     currency: String,
+    locale: String,
     notification_settings: serde_json::Value,
     invoice_settings: Option<serde_json::Value>,
     created_at: DateTime<Utc>,
@@ -297,6 +404,7 @@ impl UserRow {
             business_address,
             tax_settings,
             currency: self.currency,
+            locale: Locale::parse(&self.locale).unwrap_or_default(),
             notification_settings,
             invoice_settings,
             created_at: self.created_at,
@@ -0,0 +1,411 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use crate::domain::models::{
+    CreateWebhookEndpoint, PendingWebhookDelivery, UpdateWebhookEndpoint, WebhookDelivery, WebhookDeliveryFilter,
+    WebhookDeliveryStatus, WebhookEndpoint, WebhookEventType, DEFAULT_WEBHOOK_DELIVERY_PAGE_SIZE,
+    MAX_WEBHOOK_DELIVERY_PAGE_SIZE,
+};
+
+/// Outcome of sending a delivery
+pub struct WebhookAttempt<'a> {
+    pub status: WebhookDeliveryStatus,
+    pub status_code: Option<i32>,
+    pub error: Option<&'a str>,
+    pub attempted_at: DateTime<Utc>,
+    /// Next try while the delivery is still pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct WebhookRepository {
+    db: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(db: PgPool) -> Self {
+        Self { db }
+    }
+
+    pub async fn list_endpoints(&self, user_id: Uuid) -> Result<Vec<WebhookEndpoint>, sqlx::Error> {
+        let rows = sqlx::query_as::<_, EndpointRow>(
+            r#"
+            SELECT id, url, events, description, enabled, created_at, updated_at
+            FROM webhook_endpoints
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows.into_iter().map(EndpointRow::into_endpoint).collect())
+    }
+
+    pub async fn get_endpoint(&self, user_id: Uuid, id: Uuid) -> Result<Option<WebhookEndpoint>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EndpointRow>(
+            r#"
+            SELECT id, url, events, description, enabled, created_at, updated_at
+            FROM webhook_endpoints
+            WHERE id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EndpointRow::into_endpoint))
+    }
+
+    pub async fn count_endpoints(&self, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM webhook_endpoints WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db)
+            .await
+    }
+
+    pub async fn create_endpoint(
+        &self,
+        user_id: Uuid,
+        endpoint: &CreateWebhookEndpoint,
+        secret: &str,
+    ) -> Result<WebhookEndpoint, sqlx::Error> {
+        let row = sqlx::query_as::<_, EndpointRow>(
+            r#"
+            INSERT INTO webhook_endpoints (id, user_id, url, secret, events, description, enabled)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, url, events, description, enabled, created_at, updated_at
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(&endpoint.url)
+        .bind(secret)
+        .bind(event_names(&endpoint.events))
+        .bind(&endpoint.description)
+        .bind(endpoint.enabled)
+        .fetch_one(&self.db)
+        .await?;
+
+        Ok(row.into_endpoint())
+    }
+
+    pub async fn update_endpoint(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+        update: &UpdateWebhookEndpoint,
+    ) -> Result<Option<WebhookEndpoint>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EndpointRow>(
+            r#"
+            UPDATE webhook_endpoints SET
+                url = COALESCE($3, url),
+                events = COALESCE($4, events),
+                description = COALESCE($5, description),
+                enabled = COALESCE($6, enabled),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, url, events, description, enabled, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(&update.url)
+        .bind(update.events.as_deref().map(event_names))
+        .bind(&update.description)
+        .bind(update.enabled)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EndpointRow::into_endpoint))
+    }
+
+    pub async fn rotate_secret(&self, user_id: Uuid, id: Uuid, secret: &str) -> Result<Option<WebhookEndpoint>, sqlx::Error> {
+        let row = sqlx::query_as::<_, EndpointRow>(
+            r#"
+            UPDATE webhook_endpoints SET secret = $3, updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, url, events, description, enabled, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(secret)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.map(EndpointRow::into_endpoint))
+    }
+
+    /// Deliveries go with the endpoint
+    pub async fn delete_endpoint(&self, user_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM webhook_endpoints WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.db)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Turn up to `limit` new events into one delivery per subscribed endpoint,
+    /// due at `now`. Returns the new deliveries.
+    pub async fn fan_out(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<Uuid>, sqlx::Error> {
+        // Locked events are being fanned out by another instance
+        sqlx::query_scalar(
+            r#"
+            WITH events AS (
+                SELECT id, user_id, event_type
+                FROM webhook_events
+                WHERE dispatched_at IS NULL
+                ORDER BY created_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            ), dispatched AS (
+                UPDATE webhook_events e SET dispatched_at = $1
+                FROM events
+                WHERE e.id = events.id
+            )
+            INSERT INTO webhook_deliveries (id, endpoint_id, user_id, event_id, event_type, next_attempt_at)
+            SELECT gen_random_uuid(), w.id, events.user_id, events.id, events.event_type, $1
+            FROM events
+            JOIN webhook_endpoints w
+                ON w.user_id = events.user_id AND w.enabled AND events.event_type = ANY(w.events)
+            ON CONFLICT (endpoint_id, event_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Pending deliveries to enabled endpoints that are due by `now`
+    pub async fn due_deliveries(&self, now: DateTime<Utc>, limit: i64) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT d.id
+            FROM webhook_deliveries d
+            JOIN webhook_endpoints w ON w.id = d.endpoint_id
+            WHERE d.status = 'pending' AND d.next_attempt_at <= $1 AND w.enabled
+            ORDER BY d.next_attempt_at
+            LIMIT $2
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await
+    }
+
+    /// Take the delivery for one attempt if it's due, holding it until
+    /// `lease_until` so the queue and the sweep don't both send it. None when
+    /// it isn't due, is already settled or its endpoint is disabled.
+    pub async fn claim(
+        &self,
+        id: Uuid,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+    ) -> Result<Option<PendingWebhookDelivery>, sqlx::Error> {
+        let row = sqlx::query_as::<_, PendingRow>(
+            r#"
+            UPDATE webhook_deliveries d SET next_attempt_at = $3, updated_at = NOW()
+            FROM webhook_endpoints w, webhook_events e
+            WHERE d.id = $1
+                AND d.status = 'pending'
+                AND d.next_attempt_at <= $2
+                AND w.id = d.endpoint_id
+                AND w.enabled
+                AND e.id = d.event_id
+            RETURNING d.id, w.user_id, d.attempts, w.url, w.secret, e.id AS event_id, e.event_type,
+                e.created_at AS event_created_at, e.data, e.request_id
+            "#,
+        )
+        .bind(id)
+        .bind(now)
+        .bind(lease_until)
+        .fetch_optional(&self.db)
+        .await?;
+
+        Ok(row.and_then(PendingRow::into_pending))
+    }
+
+    pub async fn record_attempt(&self, id: Uuid, attempt: &WebhookAttempt<'_>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries SET
+                status = $2,
+                attempts = attempts + 1,
+                last_status_code = $3,
+                last_error = $4,
+                next_attempt_at = $5,
+                delivered_at = CASE WHEN $2 = 'delivered' THEN $6 ELSE delivered_at END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(attempt.status.as_str())
+        .bind(attempt.status_code)
+        .bind(attempt.error)
+        .bind(attempt.next_attempt_at)
+        .bind(attempt.attempted_at)
+        .execute(&self.db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Newest first
+    pub async fn list_deliveries(
+        &self,
+        user_id: Uuid,
+        endpoint_id: Uuid,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        let mut query_builder = QueryBuilder::<Postgres>::new(
+            r#"
+            SELECT id, endpoint_id, event_id, event_type, status, attempts, next_attempt_at,
+                last_status_code, last_error, delivered_at, created_at
+            FROM webhook_deliveries
+            WHERE user_id = "#,
+        );
+        query_builder.push_bind(user_id);
+        query_builder.push(" AND endpoint_id = ");
+        query_builder.push_bind(endpoint_id);
+
+        if let Some(status) = filter.status {
+            query_builder.push(" AND status = ");
+            query_builder.push_bind(status.as_str());
+        }
+
+        if let Some(event_type) = filter.event_type {
+            query_builder.push(" AND event_type = ");
+            query_builder.push_bind(event_type.as_str());
+        }
+
+        query_builder.push(" ORDER BY created_at DESC, id");
+
+        query_builder.push(" LIMIT ");
+        query_builder.push_bind(
+            filter
+                .limit
+                .unwrap_or(DEFAULT_WEBHOOK_DELIVERY_PAGE_SIZE)
+                .clamp(1, MAX_WEBHOOK_DELIVERY_PAGE_SIZE),
+        );
+
+        if let Some(offset) = filter.offset {
+            query_builder.push(" OFFSET ");
+            query_builder.push_bind(offset.max(0));
+        }
+
+        let rows = query_builder
+            .build_query_as::<DeliveryRow>()
+            .fetch_all(&self.db)
+            .await?;
+
+        Ok(rows.into_iter().filter_map(DeliveryRow::into_delivery).collect())
+    }
+}
+
+fn event_names(events: &[WebhookEventType]) -> Vec<String> {
+    let mut names: Vec<String> = events.iter().map(|event| event.as_str().to_string()).collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[derive(sqlx::FromRow)]
+struct EndpointRow {
+    id: Uuid,
+    url: String,
+    events: Vec<String>,
+    description: Option<String>,
+    enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl EndpointRow {
+    fn into_endpoint(self) -> WebhookEndpoint {
+        WebhookEndpoint {
+            id: self.id,
+            url: self.url,
+            events: self.events.iter().filter_map(|event| WebhookEventType::parse(event)).collect(),
+            description: self.description,
+            enabled: self.enabled,
+            secret: None,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingRow {
+    id: Uuid,
+    user_id: Uuid,
+    attempts: i32,
+    url: String,
+    secret: String,
+    event_id: Uuid,
+    event_type: String,
+    event_created_at: DateTime<Utc>,
+    data: serde_json::Value,
+    request_id: Option<String>,
+}
+
+impl PendingRow {
+    fn into_pending(self) -> Option<PendingWebhookDelivery> {
+        Some(PendingWebhookDelivery {
+            id: self.id,
+            user_id: self.user_id,
+            attempts: self.attempts,
+            url: self.url,
+            secret: self.secret,
+            event_id: self.event_id,
+            event_type: WebhookEventType::parse(&self.event_type)?,
+            event_created_at: self.event_created_at,
+            data: self.data,
+            request_id: self.request_id,
+        })
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DeliveryRow {
+    id: Uuid,
+    endpoint_id: Uuid,
+    event_id: Uuid,
+    event_type: String,
+    status: String,
+    attempts: i32,
+    next_attempt_at: Option<DateTime<Utc>>,
+    last_status_code: Option<i32>,
+    last_error: Option<String>,
+    delivered_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl DeliveryRow {
+    /// None for event types or statuses this version doesn't know
+    fn into_delivery(self) -> Option<WebhookDelivery> {
+        Some(WebhookDelivery {
+            id: self.id,
+            endpoint_id: self.endpoint_id,
+            event_id: self.event_id,
+            event_type: WebhookEventType::parse(&self.event_type)?,
+            status: WebhookDeliveryStatus::parse(&self.status)?,
+            attempts: self.attempts,
+            next_attempt_at: self.next_attempt_at,
+            last_status_code: self.last_status_code,
+            last_error: self.last_error,
+            delivered_at: self.delivered_at,
+            created_at: self.created_at,
+        })
+    }
+}
@@ -0,0 +1,7 @@
+//! FlashBill API library: the server binary and the benchmarks are built on it
+
+pub mod api;
+pub mod application;
+pub mod config;
+pub mod domain;
+pub mod infrastructure;
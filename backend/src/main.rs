@@ -1,11 +1,6 @@
-mod api;
-mod domain;
-mod infrastructure;
-mod config;
-mod application;
 
 use axum::{
-    http::{Method, HeaderValue},
+    http::{Method, HeaderName, HeaderValue},
     routing::get,
     Extension, Router,
 };
@@ -20,11 +15,27 @@ use tower_http::{
 use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::api::routes::{auth, invoices, reports, settings, clients, payments, expenses, metrics, files, tax, paypal, guest};
-use crate::domain::services::{InvoiceService, AuthService, EmailService, EmailConfig, PdfService, ReportService, SettingsService, ClientService, PaymentService, ExpenseService, RedisService, MetricsService, FileService, NotificationService, TaxService, PaymentGatewayService, MonitoringService, EmailQueueService, EnhancedNotificationService, WhatsAppService};
-use crate::application::use_cases::*;
-use crate::infrastructure::repositories::{InvoiceRepository, ClientRepository, UserRepository, ReportRepositoryImpl, PaymentRepository, ExpenseRepository, TaxRepositoryImpl};
-use crate::domain::repositories::tax_repository::TaxRepository;
+use flashbill_api::api::routes::{auth, invoices, reports, settings, clients, payments, expenses, metrics, files, tax, paypal, guest, document_numbers, notifications, rate_limits, support, budgets, payouts, businesses, accountants, campaigns, profitability, sync, invoice_labels, fx, email_signatures, email_templates, template_bundles, client_imports, credit_notes, attachments, late_fees, audit_logs, webhook_endpoints, invoice_transfers, search, trash, portal, stripe_checkout, bank_transfers, receipts, time_entries, projects, invoice_templates, events, admin, client_credit};
+use flashbill_api::api::openapi;
+use flashbill_api::api::middleware::rate_limit::{RateLimitMiddleware, rate_limit_middleware};
+use flashbill_api::api::middleware::scrape_auth::ScrapeAuthConfig;
+use flashbill_api::api::middleware::client_ip::TrustedProxies;
+use flashbill_api::api::middleware::sparse_fields::sparse_fields_middleware;
+use flashbill_api::api::middleware::locale::locale_middleware;
+use flashbill_api::api::middleware::load_shedding::load_shedding_middleware;
+use flashbill_api::api::middleware::idempotency::{IdempotencyMiddleware, idempotency_middleware};
+use flashbill_api::api::middleware::audit::audit_middleware;
+use flashbill_api::api::middleware::report_cache::report_cache_middleware;
+use flashbill_api::api::middleware::error_envelope::error_envelope_middleware;
+use flashbill_api::api::middleware::request_id::request_id_middleware;
+use flashbill_api::api::middleware::metrics::metrics_middleware;
+use flashbill_api::domain::request_id::REQUEST_ID_HEADER;
+use flashbill_api::infrastructure::database::connection::with_request_ids;
+use flashbill_api::domain::services::{InvoiceService, AuthService, EmailService, EmailConfig, PdfService, ReportService, SettingsService, ClientService, PaymentService, ExpenseService, RedisService, MetricsService, FileService, NotificationService, TaxService, PaymentGatewayService, MonitoringService, EmailQueueService, EnhancedNotificationService, WhatsAppService, DocumentNumberService, AutomationIssueService, StatementDeliveryService, BudgetService, PayoutService, BusinessService, AccountantService, CampaignService, ProfitabilityService, SyncService, InvoiceLabelService, FxRateService, EmailSignatureService, EmailTemplateService, CustomReportService, TemplateBundleService, ClientImportService, CreditNoteService, PayPalWebhookService, GuestTokenService, AttachmentService, LateFeeService, AuditService, WebhookService, ClientCsvImportService, InvoiceCsvImportService, InvoiceExportService, EInvoiceService, SearchService, TrashService, ClientAuthService, StripeCheckoutService, BankReconciliationService, FileEncryption, MasterKeyRing, fx_rate_service, vat_validation_service, LazyHttpClient, SystemClock, Shutdown, ReceiptScanService, receipt_scan_service, ClientStatementService, TimeEntryService, ProjectService, RealtimeService, InvoiceTemplateService, ClientCreditService};
+use flashbill_api::domain::models::MAX_FAILED_LOGINS_PER_IP;
+use flashbill_api::application::use_cases::*;
+use flashbill_api::infrastructure::repositories::{InvoiceRepository, ClientRepository, ClientCreditRepository, UserRepository, ReportRepositoryImpl, PaymentRepository, ExpenseRepository, TaxRepositoryImpl, FxRepository, DocumentNumberRepository, AutomationIssueRepository, StatementDeliveryRepository, BudgetRepository, PayoutRepository, BusinessRepository, AccountantAccessRepository, CampaignRepository, InvoiceCostRepository, SyncRepository, InvoiceLabelRepository, EmailSignatureRepository, EmailTemplateRepository, CustomReportRepository, TenantKeyRepository, ClientImportRepository, CreditNoteRepository, IdempotencyRepository, GuestTokenRepository, AttachmentRepository, LateFeeRepository, AuditLogRepository, WebhookRepository, ClientImportJobRepository, SearchRepository, TrashRepository, ClientAccountRepository, ReceiptScanRepository, TimeEntryRepository, ProjectRepository, RealtimeEventRepository, InvoiceTemplateRepository};
+use flashbill_api::domain::repositories::tax_repository::TaxRepository;
 
 #[tokio::main]
 async fn main() {
@@ -42,7 +53,7 @@ async fn main() {
 
     // Validate environment variables and print configuration summary
     tracing::info!("🔍 Validating configuration...");
-    crate::config::Config::validate_and_print();
+    flashbill_api::config::Config::validate_and_print();
     tracing::info!("✅ Configuration validated successfully\n");
 
     // Load configuration
@@ -65,8 +76,8 @@ async fn main() {
         }
     };
 
-    // Initialize database connection
-    let db_pool = sqlx::postgres::PgPoolOptions::new()
+    // Initialize database connection; connections carry the request ID for triggers
+    let db_pool = with_request_ids(sqlx::postgres::PgPoolOptions::new())
         .max_connections(10)
         .connect(&database_url)
         .await
@@ -78,6 +89,14 @@ async fn main() {
         .await
         .expect("Failed to run migrations");
 
+    // Wall-clock time for all time-dependent services (tests inject a mock clock)
+    let clock = SystemClock::shared();
+
+    // SIGTERM/SIGINT stop the server and the background workers below, which are
+    // drained before the pool is closed
+    let shutdown = Shutdown::from_env();
+    tokio::spawn(shutdown.clone().trigger_on_signal());
+
     // Initialize repositories (Infrastructure layer)
     let tax_repo_impl = TaxRepositoryImpl::new(db_pool.clone());
     let tax_repo: Arc<dyn TaxRepository + Send + Sync> = Arc::new(tax_repo_impl);
@@ -86,34 +105,41 @@ async fn main() {
     let tax_service = Arc::new(TaxService::new(tax_repo.clone()));
     tracing::info!("✅ Tax service initialized");
 
-    // Initialize payment gateway service (PayPal and Stripe)
-    let payment_gateway_service = match PaymentGatewayService::new() {
-        Ok(service) => {
-            let gateways = service.get_available_gateways();
-            if gateways.is_empty() {
-                tracing::warn!("⚠️ Payment gateways not configured. Set STRIPE_SECRET_KEY and/or PAYPAL_CLIENT_ID/PAYPAL_CLIENT_SECRET");
-            } else {
-                tracing::info!("✅ Payment gateway service initialized: {:?}", gateways);
-            }
-            Arc::new(service)
-        }
-        Err(e) => {
-            tracing::warn!("⚠️ Payment gateway service initialization failed: {}", e);
-            Arc::new(PaymentGatewayService::default())
-        }
-    };
+    // Initialize payment gateway service (PayPal and Stripe); its client is built on first use
+    let payment_gateway_service = Arc::new(PaymentGatewayService::new());
+    tracing::info!("Payment gateways configured: {:?}", payment_gateway_service.get_available_gateways());
+
+    // Document numbering shared by all document-producing modules
+    let document_number_service = Arc::new(DocumentNumberService::new(
+        Arc::new(DocumentNumberRepository::new(db_pool.clone())),
+        clock.clone(),
+    ));
+
+    // Signed, revocable guest checkout links
+    let guest_token_service = Arc::new(GuestTokenService::new(
+        Arc::new(GuestTokenRepository::new(db_pool.clone())),
+        &jwt_secret,
+        clock.clone(),
+    ));
 
     // Initialize invoice repository with tax service
-    let invoice_repo = InvoiceRepository::new(db_pool.clone(), tax_service.clone());
+    let invoice_repo = InvoiceRepository::new(db_pool.clone(), tax_service.clone(), document_number_service.clone(), guest_token_service.clone());
     let client_repo = ClientRepository::new(db_pool.clone());
     let user_repo = UserRepository::new(db_pool.clone());
     let report_repo = ReportRepositoryImpl::new(db_pool.clone());
     let payment_repo = PaymentRepository::new(db_pool.clone());
     let expense_repo = ExpenseRepository::new(db_pool.clone());
+    let fx_repo = FxRepository::new(db_pool.clone());
+    let email_signature_repo = EmailSignatureRepository::new(db_pool.clone());
+    let email_template_repo = EmailTemplateRepository::new(db_pool.clone());
 
     // Initialize services (Domain layer)
-    let metrics_service = Arc::new(MetricsService::new().expect("Failed to initialize metrics service"));
-    tracing::info!("✅ Metrics service initialized");
+
+    let scrape_auth = Arc::new(ScrapeAuthConfig::from_env());
+    if scrape_auth.is_public() {
+        tracing::warn!("⚠️  /metrics is public; set METRICS_TOKEN or METRICS_ALLOWED_IPS to protect it");
+    }
+    let trusted_proxies = Arc::new(TrustedProxies::from_env());
 
     // Initialize file service
     let file_upload_dir = std::env::var("FILE_UPLOAD_DIR")
@@ -126,13 +152,29 @@ async fn main() {
     let file_service = match FileService::new(&file_upload_dir, max_file_size) {
         Ok(service) => {
             tracing::info!("✅ File service initialized (upload dir: {})", file_upload_dir);
-            Arc::new(service)
+            service
         }
         Err(e) => {
             tracing::error!("❌ Failed to initialize file service: {}", e);
             panic!("File service initialization failed");
         }
     };
+    // Per-tenant envelope encryption of uploads
+    let file_service = match MasterKeyRing::from_env() {
+        Ok(Some(master_keys)) => {
+            tracing::info!("✅ File encryption enabled");
+            let encryption = FileEncryption::new(master_keys, TenantKeyRepository::new(db_pool.clone()));
+            Arc::new(file_service.with_encryption(encryption))
+        }
+        Ok(None) => {
+            tracing::warn!("⚠️  FILE_ENCRYPTION_KEYS not set, uploaded files are stored unencrypted");
+            Arc::new(file_service)
+        }
+        Err(e) => {
+            tracing::error!("❌ Invalid FILE_ENCRYPTION_KEYS: {}", e);
+            panic!("File encryption configuration failed");
+        }
+    };
 
     let pdf_service = PdfService::new();
     let email_service = Arc::new(EmailService::new(EmailConfig {
@@ -147,78 +189,328 @@ async fn main() {
         from_name: std::env::var("FROM_NAME").unwrap_or_else(|_| "FlashBill".to_string()),
     }));
 
+    // Every email goes through the queue: sent off the request path with retries,
+    // kept in Redis when available and in this process otherwise
+    let email_queue_service = Arc::new(EmailQueueService::new(redis_service.clone(), email_service.clone(), clock.clone()));
+    email_queue_service.clone().start_worker(&shutdown);
+    if email_queue_service.is_in_process() {
+        tracing::warn!("⚠️ Email queue kept in process (Redis not available); queued emails are lost on restart");
+    } else {
+        tracing::info!("✅ Email queue service initialized");
+    }
+
     let _notification_service = Arc::new(NotificationService::new().expect("Failed to initialize notification service"));
     tracing::info!("✅ Notification service initialized");
 
-    // Initialize WhatsApp service
+    // Initialize WhatsApp service; its client is built on first use
     let whatsapp_service = Arc::new(WhatsAppService::from_env());
-    tracing::info!("✅ WhatsApp service initialized");
+    tracing::info!("WhatsApp enabled: {}", whatsapp_service.is_enabled());
 
     // Initialize enhanced notification service
     let enhanced_notification_service = Arc::new(EnhancedNotificationService::new(
-        email_service.clone(),
+        email_queue_service.clone(),
         whatsapp_service.clone(),
-    ));
+    ).with_email_templates(user_repo.clone(), email_template_repo.clone()));
     tracing::info!("✅ Enhanced notification service initialized");
 
+    // Failed background automation, surfaced via /notifications/issues and a daily digest
+    let automation_issue_service = Arc::new(AutomationIssueService::new(
+        Arc::new(AutomationIssueRepository::new(db_pool.clone())),
+        user_repo.clone(),
+        email_queue_service.clone(),
+    ));
+    automation_issue_service.clone().start_daily_digest(&shutdown);
+    tracing::info!("✅ Automation issue digest scheduled");
+
+    // Exchange rates: optional provider (FX_RATE_PROVIDER), cached in Redis and Postgres
+    let fx_rate_service = Arc::new(FxRateService::new(
+        fx_repo.clone(),
+        fx_rate_service::provider_from_env(),
+        redis_service.clone(),
+        clock.clone(),
+    ));
+    // EU VAT numbers: VIES, or format checks only (VAT_VALIDATION)
+    let vat_validator = vat_validation_service::validator_from_env();
+
+    // Outbound webhooks: signed event deliveries to user endpoints, retried through the Redis queue
+    let webhook_service = Arc::new(WebhookService::new(
+        WebhookRepository::new(db_pool.clone()),
+        redis_service.clone(),
+        clock.clone(),
+    ).with_automation_issues(automation_issue_service.clone()));
+    webhook_service.clone().start_workers(&shutdown);
+
+    // Live dashboard updates over SSE, fanned out to every replica through Redis pub/sub
+    let realtime_service = Arc::new(RealtimeService::new(
+        RealtimeEventRepository::new(db_pool.clone()),
+        redis_service.clone(),
+        shutdown.clone(),
+        clock.clone(),
+    ));
+    realtime_service.clone().start_workers(&shutdown);
+
     // Initialize monitoring service
-    let monitoring_service = Arc::new(MonitoringService::new());
+    let monitoring_service = Arc::new(MonitoringService::new().with_email(email_service.clone()));
+    let mut integrations = vec![
+        payment_gateway_service.http_handle(),
+        whatsapp_service.http_handle(),
+        webhook_service.http_handle(),
+    ];
+    integrations.extend(fx_rate_service.http_handle());
+    integrations.extend(vat_validator.http_handle());
+    // Receipt OCR: local Tesseract or an external service, by OCR_BACKEND
+    let ocr_backend = receipt_scan_service::ocr_backend_from_env();
+    integrations.extend(ocr_backend.as_ref().and_then(|backend| backend.http_handle()));
+    for integration in &integrations {
+        monitoring_service.register_integration(integration.clone());
+    }
+    // Warm up configured integrations in the background so startup doesn't wait on them
+    tokio::task::spawn_blocking(move || integrations.iter().for_each(LazyHttpClient::warm_up));
+    // Keeps the health status current for load shedding
+    monitoring_service.clone().start_health_checks(&shutdown, db_pool.clone(), redis_service.clone());
     tracing::info!("✅ Monitoring service initialized");
 
-    // Initialize email queue service (if Redis available)
-    let _email_queue_service = if let Some(redis) = &redis_service {
-        let queue = Arc::new(EmailQueueService::new(redis.clone(), email_service.clone()));
-        tracing::info!("✅ Email queue service initialized");
-        Some(queue)
-    } else {
-        tracing::warn!("⚠️ Email queue disabled (Redis not available)");
-        None
-    };
+    // Prometheus metrics: HTTP, database pool, email queue and business events
+    let metrics_service = Arc::new(
+        MetricsService::new()
+            .expect("Failed to initialize metrics service")
+            .with_db_pool(db_pool.clone())
+            .with_email_queue(email_queue_service.clone()),
+    );
+    tracing::info!("✅ Metrics service initialized");
 
     // Initialize services with repositories and other services
     // Clone invoice_repo before moving it into invoice_service
     let invoice_repo_for_payment = invoice_repo.clone();
     let invoice_repo_for_tax = invoice_repo.clone();
+    let invoice_repo_for_sync = invoice_repo.clone();
+    let invoice_repo_for_credit_notes = invoice_repo.clone();
+    let invoice_repo_for_exports = invoice_repo.clone();
+    // Contracts, receipts and timesheets on invoices and expenses, linked from invoice emails
+    let attachment_service = Arc::new(AttachmentService::new(
+        AttachmentRepository::new(db_pool.clone()),
+        file_service.clone(),
+        clock.clone(),
+    ));
+    let report_service = match &redis_service {
+        Some(redis) => Arc::new(ReportService::with_redis(Arc::new(report_repo), redis.clone())),
+        None => Arc::new(ReportService::new(Arc::new(report_repo))),
+    };
+    report_service.clone().start_aging_snapshots(&shutdown);
+    // Writes to invoices, payments and expenses invalidate cached reports through this
+    let report_cache = report_service.cache();
+    // Late fees by each user's policy, added by the overdue job below
+    let late_fee_service = Arc::new(
+        LateFeeService::new(LateFeeRepository::new(db_pool.clone()), clock.clone())
+            .with_report_cache(report_cache.clone()),
+    );
     let invoice_service = Arc::new(InvoiceService::new(
         invoice_repo,
         client_repo.clone(),
         user_repo.clone(),
         pdf_service,
-        email_service.clone(),
+        email_queue_service.clone(),
         enhanced_notification_service.clone(),
         whatsapp_service.clone(),
+        automation_issue_service.clone(),
+        email_signature_repo.clone(),
+        email_template_repo.clone(),
+        attachment_service.clone(),
+        file_service.clone(),
+        late_fee_service.clone(),
+        ClientCreditRepository::new(db_pool.clone()),
+        clock.clone(),
+    )
+    .with_metrics(metrics_service.clone())
+    .with_fx_rates(fx_rate_service.clone())
+    .with_report_cache(report_cache.clone()));
+    // Follow up on offers about to expire and expire lapsed ones
+    invoice_service.clone().start_expiry_checks(&shutdown);
+    // Mark past-due invoices overdue, add late fees and remind clients on each user's schedule
+    invoice_service.clone().start_reminder_worker(&shutdown);
+    // Failed sign-ins from one IP before it is throttled; 0 turns the throttle off
+    let login_ip_limit = std::env::var("LOGIN_MAX_FAILURES_PER_IP")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(MAX_FAILED_LOGINS_PER_IP);
+    let auth_service = Arc::new(
+        AuthService::new(user_repo.clone(), email_queue_service.clone(), jwt_secret, clock.clone())
+            .with_ip_failure_limit(login_ip_limit),
+    );
+    let settings_service = Arc::new(SettingsService::new(user_repo.clone(), file_service.clone()));
+    let client_service = Arc::new(ClientService::new(
+        Arc::new(client_repo.clone()),
+        user_repo.clone(),
+        tax_service.clone(),
+        vat_validator.clone(),
     ));
-    let auth_service = Arc::new(AuthService::new(user_repo.clone(), email_service.clone(), jwt_secret));
-    let report_service = match &redis_service {
-        Some(redis) => Arc::new(ReportService::with_redis(Arc::new(report_repo), redis.clone())),
-        None => Arc::new(ReportService::new(Arc::new(report_repo))),
-    };
-    let settings_service = Arc::new(SettingsService::new(user_repo.clone()));
-    let client_service = Arc::new(ClientService::new(Arc::new(client_repo.clone())));
+    // Clients onboarded from emails forwarded to bills+{token}@<domain>
+    let client_import_service = Arc::new(ClientImportService::new(
+        ClientImportRepository::new(db_pool.clone()),
+        client_service.clone(),
+        std::env::var("INBOUND_EMAIL_DOMAIN").unwrap_or_else(|_| "in.flashbill.com".to_string()),
+    ));
+    let inbound_email_secret = std::env::var("INBOUND_EMAIL_SECRET").ok().filter(|s| !s.trim().is_empty());
+    // Bulk client imports from CSV; large files run in the background
+    let client_csv_import_service = Arc::new(ClientCsvImportService::new(
+        ClientImportJobRepository::new(db_pool.clone()),
+        clock.clone(),
+    ));
+    client_csv_import_service.clone().start_worker(&shutdown);
+    // Bulk invoice imports from CSV and streamed CSV/XLSX exports
+    let invoice_csv_import_service = Arc::new(InvoiceCsvImportService::new(
+        invoice_service.clone(),
+        client_repo.clone(),
+        fx_rate_service.clone(),
+        clock.clone(),
+    ));
+    let invoice_export_service = Arc::new(InvoiceExportService::new(invoice_repo_for_exports));
+    // UBL 2.1 and Factur-X e-invoices of single invoices
+    let e_invoice_service = Arc::new(EInvoiceService::new(invoice_service.clone(), client_repo.clone(), user_repo.clone()));
+
+    // Budgets, and spending limits that alert at 80% and 100% as expenses are recorded
+    let budget_service = Arc::new(BudgetService::new(
+        BudgetRepository::new(db_pool.clone()),
+        user_repo.clone(),
+        email_queue_service.clone(),
+        clock.clone(),
+    ));
+    let profitability_service = Arc::new(ProfitabilityService::new(InvoiceCostRepository::new(db_pool.clone())));
+    let invoice_label_service = Arc::new(InvoiceLabelService::new(InvoiceLabelRepository::new(db_pool.clone())));
+    let project_service = Arc::new(ProjectService::new(ProjectRepository::new(db_pool.clone()), clock.clone()));
+    let time_entry_service = Arc::new(TimeEntryService::new(
+        TimeEntryRepository::new(db_pool.clone()),
+        project_service.clone(),
+        clock.clone(),
+    ));
+    let invoice_template_service = Arc::new(InvoiceTemplateService::new(
+        InvoiceTemplateRepository::new(db_pool.clone()),
+        clock.clone(),
+    ));
+    let template_bundle_service = Arc::new(TemplateBundleService::new(user_repo.clone(), email_signature_repo.clone()));
+    let email_template_service = Arc::new(EmailTemplateService::new(
+        email_template_repo.clone(),
+        user_repo.clone(),
+        email_signature_repo.clone(),
+    ));
+    let email_signature_service = Arc::new(EmailSignatureService::new(email_signature_repo));
+    let custom_report_service = Arc::new(CustomReportService::new(CustomReportRepository::new(db_pool.clone())));
+
+    // Gateway payouts linked back to the payments they settle
+    let payout_service = Arc::new(PayoutService::new(
+        PayoutRepository::new(db_pool.clone()),
+        payment_gateway_service.clone(),
+        clock.clone(),
+    ));
+
+    // Additional businesses under one login, selected per request
+    let business_service = Arc::new(BusinessService::new(
+        BusinessRepository::new(db_pool.clone()),
+        user_repo.clone(),
+    ));
+
+    // Read-only accountant access, checked by the AuthUser extractor on each request
+    let accountant_service = Arc::new(AccountantService::new(
+        AccountantAccessRepository::new(db_pool.clone()),
+        user_repo.clone(),
+        auth_service.clone(),
+        email_queue_service.clone(),
+        clock.clone(),
+    ));
+
+    // Monthly client statements, delivered on the 1st
+    let statement_delivery_service = Arc::new(StatementDeliveryService::new(
+        StatementDeliveryRepository::new(db_pool.clone()),
+        client_service.clone(),
+        email_queue_service.clone(),
+        clock.clone(),
+    ));
+    statement_delivery_service.start_monthly_schedule(&shutdown);
+
+    // Dunning campaigns, sent on a throttled schedule
+    let campaign_service = Arc::new(CampaignService::new(
+        CampaignRepository::new(db_pool.clone()),
+        user_repo.clone(),
+        email_queue_service.clone(),
+        clock.clone(),
+    ));
+    campaign_service.clone().start_sender(&shutdown);
+
+    // Change feed for offline clients; deletions are pruned after the cursor retention period
+    let sync_service = Arc::new(SyncService::new(
+        SyncRepository::new(db_pool.clone()),
+        invoice_repo_for_sync,
+        client_repo.clone(),
+        payment_repo.clone(),
+        user_repo.clone(),
+        clock.clone(),
+    ));
+    sync_service.clone().start_tombstone_pruning(&shutdown);
+    tracing::info!("✅ Monthly statement delivery scheduled");
     let payment_service = Arc::new(PaymentService::new(
         Arc::new(payment_repo.clone()),
         Arc::new(invoice_repo_for_payment),
         Arc::new(client_repo.clone()),
         Arc::new(user_repo.clone()),
-        email_service.clone(),
+        email_queue_service.clone(),
+        fx_rate_service.clone(),
+    )
+    .with_metrics(metrics_service.clone())
+    .with_email_templates(email_template_repo.clone()));
+    let expense_service = Arc::new(
+        ExpenseService::new(Arc::new(expense_repo.clone())).with_budget_alerts(budget_service.clone()),
+    );
+    let credit_note_service = Arc::new(CreditNoteService::new(
+        CreditNoteRepository::new(db_pool.clone(), document_number_service.clone()),
+        invoice_repo_for_credit_notes,
+        client_repo.clone(),
+        user_repo.clone(),
+        PdfService::new(),
+        clock.clone(),
     ));
-    let expense_service = Arc::new(ExpenseService::new(Arc::new(expense_repo.clone())));
 
     // Initialize application use cases (Application layer - glue code)
-    let create_invoice_uc = Arc::new(CreateInvoiceUseCase::new(invoice_service.clone()));
+    let create_invoice_uc = Arc::new(CreateInvoiceUseCase::new(
+        invoice_service.clone(),
+        client_service.clone(),
+        fx_rate_service.clone(),
+        project_service.clone(),
+    ));
+    let create_invoice_from_time_uc = Arc::new(CreateInvoiceFromTimeUseCase::new(
+        create_invoice_uc.clone(),
+        invoice_service.clone(),
+        time_entry_service.clone(),
+    ));
+    let create_invoice_from_template_uc = Arc::new(CreateInvoiceFromTemplateUseCase::new(
+        create_invoice_uc.clone(),
+        invoice_template_service.clone(),
+    ));
+    let duplicate_invoice_uc = Arc::new(DuplicateInvoiceUseCase::new(
+        create_invoice_uc.clone(),
+        invoice_service.clone(),
+        project_service.clone(),
+    ));
     let get_invoice_uc = Arc::new(GetInvoiceUseCase::new(invoice_service.clone()));
     let list_invoices_uc = Arc::new(ListInvoicesUseCase::new(invoice_service.clone()));
     let update_invoice_uc = Arc::new(UpdateInvoiceUseCase::new(invoice_service.clone()));
     let delete_invoice_uc = Arc::new(DeleteInvoiceUseCase::new(invoice_service.clone()));
+    let restore_invoice_uc = Arc::new(RestoreInvoiceUseCase::new(invoice_service.clone()));
+    let cancel_invoice_uc = Arc::new(CancelInvoiceUseCase::new(invoice_service.clone()));
+    let consolidate_invoices_uc = Arc::new(ConsolidateInvoicesUseCase::new(invoice_service.clone()));
+    let correct_invoice_uc = Arc::new(CorrectInvoiceUseCase::new(invoice_service.clone()));
     let record_payment_uc = Arc::new(RecordPaymentUseCase::new(invoice_service.clone()));
     let send_invoice_uc = Arc::new(SendInvoiceUseCase::new(invoice_service.clone()));
     let get_pdf_uc = Arc::new(GetInvoicePdfUseCase::new(invoice_service.clone()));
     let send_reminder_uc = Arc::new(SendReminderUseCase::new(invoice_service.clone()));
     let send_invoice_whatsapp_uc = Arc::new(SendInvoiceWhatsappUseCase::new(invoice_service.clone()));
     let mark_invoice_viewed_uc = Arc::new(MarkInvoiceViewedUseCase::new(invoice_service.clone()));
+    let regenerate_guest_link_uc = Arc::new(RegenerateGuestLinkUseCase::new(guest_token_service.clone()));
+    let invoice_batch_uc = Arc::new(InvoiceBatchUseCase::new(invoice_service.clone()));
     let send_payment_confirmation_uc = Arc::new(SendPaymentConfirmationUseCase::new(invoice_service.clone()));
     let add_discussion_message_uc = Arc::new(AddDiscussionMessageUseCase::new(invoice_service.clone()));
     let get_discussion_messages_uc = Arc::new(GetDiscussionMessagesUseCase::new(invoice_service.clone()));
+    let get_invoice_notifications_uc = Arc::new(GetInvoiceNotificationsUseCase::new(invoice_service.clone()));
+    let resend_invoice_notification_uc = Arc::new(ResendInvoiceNotificationUseCase::new(invoice_service.clone()));
 
     let register_uc = Arc::new(RegisterUserUseCase::new(auth_service.clone()));
     let login_uc = Arc::new(LoginUserUseCase::new(auth_service.clone()));
@@ -235,7 +527,12 @@ async fn main() {
     let get_expenses_report_uc = Arc::new(GetExpensesReportUseCase::new(report_service.clone()));
     let get_tax_report_uc = Arc::new(GetTaxReportUseCase::new(report_service.clone()));
     let get_aging_report_uc = Arc::new(GetAgingReportUseCase::new(report_service.clone()));
-    let export_report_uc = Arc::new(ExportReportUseCase::new(report_service.clone()));
+    let get_aging_trend_uc = Arc::new(GetAgingTrendUseCase::new(report_service.clone()));
+    let get_vat_report_uc = Arc::new(GetVatReportUseCase::new(report_service.clone()));
+    let get_sla_report_uc = Arc::new(GetSlaReportUseCase::new(report_service.clone()));
+    let get_cashflow_forecast_uc = Arc::new(GetCashflowForecastUseCase::new(report_service.clone()));
+    let get_timeseries_uc = Arc::new(GetTimeseriesUseCase::new(report_service.clone()));
+    let export_report_uc = Arc::new(ExportReportUseCase::new(report_service.clone(), settings_service.clone()));
 
     // Settings use cases
     let get_business_settings_uc = Arc::new(GetBusinessSettingsUseCase::new(settings_service.clone()));
@@ -253,6 +550,24 @@ async fn main() {
     let delete_client_uc = Arc::new(DeleteClientUseCase::new(client_service.clone()));
     let get_client_invoices_uc = Arc::new(GetClientInvoicesUseCase::new(client_service.clone()));
     let get_client_stats_uc = Arc::new(GetClientStatsUseCase::new(client_service.clone()));
+    let set_client_parent_uc = Arc::new(SetClientParentUseCase::new(client_service.clone()));
+    let client_credit_service = Arc::new(ClientCreditService::new(
+        ClientCreditRepository::new(db_pool.clone()),
+        client_repo.clone(),
+        fx_repo.clone(),
+        clock.clone(),
+    ));
+    let client_statement_service = Arc::new(ClientStatementService::new(
+        client_repo.clone(),
+        ClientCreditRepository::new(db_pool.clone()),
+        user_repo.clone(),
+        PdfService::new(),
+        email_queue_service.clone(),
+    ));
+    let get_client_statement_uc = Arc::new(GetClientStatementUseCase::new(client_service.clone(), client_statement_service.clone()));
+    let archive_client_uc = Arc::new(ArchiveClientUseCase::new(client_service.clone()));
+    let restore_client_uc = Arc::new(RestoreClientUseCase::new(client_service.clone()));
+    let list_deleted_clients_uc = Arc::new(ListDeletedClientsUseCase::new(client_service.clone()));
 
     // Payment use cases
     let create_payment_uc = Arc::new(CreatePaymentUseCase::new(payment_service.clone()));
@@ -270,6 +585,13 @@ async fn main() {
     let delete_expense_uc = Arc::new(DeleteExpenseUseCase::new(expense_service.clone()));
     let get_expense_stats_uc = Arc::new(GetExpenseStatsUseCase::new(expense_service.clone()));
 
+    // Credit note use cases
+    let create_credit_note_uc = Arc::new(CreateCreditNoteUseCase::new(credit_note_service.clone()));
+    let get_credit_note_uc = Arc::new(GetCreditNoteUseCase::new(credit_note_service.clone()));
+    let list_credit_notes_uc = Arc::new(ListCreditNotesUseCase::new(credit_note_service.clone()));
+    let void_credit_note_uc = Arc::new(VoidCreditNoteUseCase::new(credit_note_service.clone()));
+    let get_credit_note_pdf_uc = Arc::new(GetCreditNotePdfUseCase::new(credit_note_service));
+
     // Tax use cases
     let create_tax_setting_uc = Arc::new(CreateTaxSettingUseCase::new(tax_service.clone()));
     let get_org_tax_settings_uc = Arc::new(GetOrganizationTaxSettingsUseCase::new(tax_service.clone()));
@@ -293,9 +615,37 @@ async fn main() {
         invoice_repo: Arc::new(invoice_repo_for_tax),
     };
 
+    // Stripe Checkout sessions for card payments, settled on return or by webhook
+    let stripe_checkout_service = Arc::new(StripeCheckoutService::new(
+        payment_gateway_service.clone(),
+        Arc::new(payment_repo.clone()),
+        Arc::new(InvoiceRepository::new(db_pool.clone(), tax_service.clone(), document_number_service.clone(), guest_token_service.clone())),
+        enhanced_notification_service.clone(),
+        clock.clone(),
+    )
+    .with_report_cache(report_cache.clone())
+    .with_fx_rates(fx_rate_service.clone()));
+
+    // Bank transfers and ACH debits wait for the seller to confirm them against the statement
+    let bank_reconciliation_service = Arc::new(BankReconciliationService::new(
+        Arc::new(payment_repo.clone()),
+        Arc::new(InvoiceRepository::new(db_pool.clone(), tax_service.clone(), document_number_service.clone(), guest_token_service.clone())),
+        enhanced_notification_service.clone(),
+        clock.clone(),
+    ).with_fx_rates(fx_rate_service.clone()));
+
+    // Receipt photos read into draft expenses the user confirms
+    let receipt_scan_service = Arc::new(ReceiptScanService::new(
+        ReceiptScanRepository::new(db_pool.clone()),
+        file_service.clone(),
+        expense_service.clone(),
+        ocr_backend,
+        clock.clone(),
+    ));
+
     // Guest state for guest checkout routes
     // Need to create a separate invoice_repo reference for guest state
-    let guest_invoice_repo = InvoiceRepository::new(db_pool.clone(), tax_service.clone());
+    let guest_invoice_repo = InvoiceRepository::new(db_pool.clone(), tax_service.clone(), document_number_service.clone(), guest_token_service.clone());
     let guest_state = guest::GuestState {
         invoice_repo: Arc::new(guest_invoice_repo),
         payment_repo: Arc::new(payment_repo.clone()),
@@ -303,12 +653,66 @@ async fn main() {
         invoice_service: invoice_service.clone(),
         payment_gateway: payment_gateway_service.clone(),
         notification_service: enhanced_notification_service.clone(),
+        guest_tokens: guest_token_service.clone(),
+        attachments: attachment_service.clone(),
+        stripe_checkout: stripe_checkout_service.clone(),
+        clock: clock.clone(),
     };
 
+    // Client portal: buyer accounts across every business that bills them
+    let portal_state = portal::PortalState {
+        client_auth: Arc::new(ClientAuthService::new(
+            ClientAccountRepository::new(db_pool.clone()),
+            auth_service.clone(),
+            email_queue_service.clone(),
+            invoice_service.clone(),
+            clock.clone(),
+        )),
+        guest: guest_state.clone(),
+    };
+
+    // PayPal orders are settled by webhook against their pending payments
+    let paypal_invoice_repo = Arc::new(InvoiceRepository::new(db_pool.clone(), tax_service.clone(), document_number_service.clone(), guest_token_service.clone()));
+    let paypal_webhook_service = Arc::new(PayPalWebhookService::new(
+        payment_gateway_service.clone(),
+        Arc::new(payment_repo.clone()),
+        paypal_invoice_repo.clone(),
+        enhanced_notification_service.clone(),
+    )
+    .with_report_cache(report_cache.clone())
+    .with_fx_rates(fx_rate_service.clone()));
+
+    // Rate limiting: X-RateLimit-* headers on every response, 429 over the limit unless RATE_LIMIT_ENFORCE=false
+    let rate_limiter = RateLimitMiddleware::new(redis_service.clone(), clock.clone());
+
+    // Idempotency-Key replays for invoice, payment and guest payment creation
+    let idempotency = IdempotencyMiddleware::new(Arc::new(IdempotencyRepository::new(db_pool.clone())), clock.clone());
+    idempotency.clone().start_purge_worker(&shutdown);
+
+    // Audit trail of changes to invoices, clients, payments, expenses and settings
+    let audit_service = Arc::new(AuditService::new(AuditLogRepository::new(db_pool.clone())));
+
+    // Full-text search across invoices, clients and expenses
+    let search_service = Arc::new(SearchService::new(SearchRepository::new(db_pool.clone())));
+
+    // Deleted drafts and clients, purged daily once past the retention period
+    let trash_service = Arc::new(TrashService::new(
+        TrashRepository::new(db_pool.clone()),
+        file_service.clone(),
+        clock.clone(),
+    ));
+    trash_service.clone().start_purge_worker(&shutdown);
+
     // Create main router with security layers
     let app = Router::new()
         .route("/health", get(health_check))
-        .route("/ready", get(ready_check))
+        .merge(metrics::readiness_router(
+            monitoring_service.clone(),
+            redis_service.clone(),
+            Some(db_pool.clone()),
+        ))
+        // OpenAPI spec at /api/v1/openapi.json, Swagger UI at /api/v1/docs
+        .merge(openapi::create_router())
         .nest("/api/v1", Router::new()
             .nest("/auth", auth::create_router(
                 register_uc,
@@ -326,6 +730,10 @@ async fn main() {
                 list_invoices_uc,
                 update_invoice_uc,
                 delete_invoice_uc,
+                restore_invoice_uc,
+                cancel_invoice_uc,
+                consolidate_invoices_uc,
+                correct_invoice_uc,
                 record_payment_uc,
                 send_invoice_uc,
                 get_pdf_uc,
@@ -335,15 +743,35 @@ async fn main() {
                 send_payment_confirmation_uc,
                 add_discussion_message_uc,
                 get_discussion_messages_uc,
-            ))
+                get_invoice_notifications_uc,
+                resend_invoice_notification_uc,
+                regenerate_guest_link_uc,
+                invoice_batch_uc,
+            )
+            .merge(profitability::create_invoice_router(profitability_service.clone()))
+            .merge(invoice_labels::create_invoice_router(invoice_label_service.clone()))
+            .merge(time_entries::create_invoice_router(create_invoice_from_time_uc))
+            .merge(invoice_templates::create_invoice_router(duplicate_invoice_uc))
+            .merge(projects::create_invoice_router(project_service.clone()))
+            .merge(attachments::create_invoice_router(attachment_service.clone()))
+            .merge(late_fees::create_invoice_router(late_fee_service.clone()))
+            .merge(client_credit::create_invoice_router(invoice_service.clone()))
+            .merge(invoice_transfers::create_invoice_router(invoice_csv_import_service, invoice_export_service, e_invoice_service)))
             .nest("/reports", reports::create_router(
                 get_overview_stats_uc,
                 get_income_report_uc,
                 get_expenses_report_uc,
                 get_tax_report_uc,
                 get_aging_report_uc,
+                get_aging_trend_uc,
                 export_report_uc,
-            ))
+            )
+            .merge(reports::create_vat_router(get_vat_report_uc))
+            .merge(reports::create_sla_router(get_sla_report_uc))
+            .merge(reports::create_cashflow_router(get_cashflow_forecast_uc))
+            .merge(reports::create_timeseries_router(get_timeseries_uc))
+            .merge(reports::create_custom_router(custom_report_service))
+            .merge(profitability::create_report_router(profitability_service)))
             .nest("/settings", settings::create_router(
                 get_business_settings_uc,
                 update_business_settings_uc,
@@ -351,6 +779,7 @@ async fn main() {
                 update_notification_settings_uc,
                 get_invoice_settings_uc,
                 update_invoice_settings_uc,
+                document_number_service.clone(),
             ))
             .nest("/clients", clients::create_router(
                 create_client_uc,
@@ -360,7 +789,16 @@ async fn main() {
                 delete_client_uc,
                 get_client_invoices_uc,
                 get_client_stats_uc,
-            ))
+                set_client_parent_uc,
+                get_client_statement_uc,
+                archive_client_uc,
+                restore_client_uc,
+                list_deleted_clients_uc,
+            )
+                .merge(client_imports::create_csv_router(client_csv_import_service))
+                .merge(clients::create_statement_router(client_statement_service))
+                .merge(client_credit::create_router(client_credit_service)))
+            .nest("/payments/bank-transfers", bank_transfers::create_router(bank_reconciliation_service))
             .nest("/payments", payments::create_router(
                 create_payment_uc,
                 get_payment_uc,
@@ -369,7 +807,18 @@ async fn main() {
                 get_payment_stats_uc,
                 get_payment_methods_uc,
             ))
-            .nest("/paypal", paypal::create_paypal_router(payment_gateway_service.clone()))
+            .nest("/credit-notes", credit_notes::create_router(
+                create_credit_note_uc,
+                get_credit_note_uc,
+                list_credit_notes_uc,
+                void_credit_note_uc,
+                get_credit_note_pdf_uc,
+            ))
+            .nest("/paypal", paypal::create_paypal_router(
+                payment_gateway_service.clone(),
+                Arc::new(payment_repo.clone()),
+                paypal_invoice_repo,
+            ))
             .nest("/expenses", expenses::create_router(
                 create_expense_uc,
                 get_expense_uc,
@@ -377,11 +826,48 @@ async fn main() {
                 update_expense_uc,
                 delete_expense_uc,
                 get_expense_stats_uc,
-            ))
+            )
+            .merge(attachments::create_expense_router(attachment_service.clone()))
+            .merge(receipts::create_router(receipt_scan_service))
+            .merge(projects::create_expense_router(project_service.clone())))
             .nest("/files", files::create_router(file_service.clone()))
             .nest("/settings/tax", tax::create_settings_router(tax_state.clone()))
+            .nest("/settings/rate-limits", rate_limits::create_router(rate_limiter.clone()))
+            .nest("/settings/document-numbers", document_numbers::create_router(document_number_service.clone()))
+            .nest("/settings/email-signature", email_signatures::create_router(email_signature_service))
+            .nest("/settings/email-templates", email_templates::create_router(email_template_service))
+            .nest("/settings/template-bundle", template_bundles::create_router(template_bundle_service))
+            .nest("/settings/late-fees", late_fees::create_router(late_fee_service))
+            .nest("/audit-logs", audit_logs::create_router(audit_service.clone()))
+            .nest("/search", search::create_router(search_service))
+            .nest("/trash", trash::create_router(trash_service))
+            .nest("/webhook-endpoints", webhook_endpoints::create_router(webhook_service))
             .nest("/tax", tax::create_operations_router(tax_state))
-            .nest("/guest", guest::create_guest_router(guest_state))
+            .nest("/guest", guest::create_guest_router(guest_state.clone()))
+            .nest("/guest/v1", guest::create_guest_router(guest_state))
+            .nest("/checkout/stripe", stripe_checkout::create_router(stripe_checkout_service.clone()))
+            .nest("/portal", portal::create_router(portal_state))
+            .nest("/notifications", notifications::create_router(automation_issue_service.clone())
+                .merge(client_imports::create_inbox_router(client_import_service.clone())))
+            .nest("/settings/inbound-address", client_imports::create_settings_router(client_import_service.clone()))
+            .nest("/inbound", client_imports::create_inbound_router(client_import_service, inbound_email_secret))
+            .nest("/support", support::create_router(invoice_service.clone()))
+            .nest("/budgets", budgets::create_router(budget_service))
+            .nest("/invoice-labels", invoice_labels::create_router(invoice_label_service))
+            .nest("/time-entries", time_entries::create_router(time_entry_service)
+                .merge(projects::create_time_entry_router(project_service.clone())))
+            .nest("/projects", projects::create_router(project_service))
+            .nest("/invoice-templates", invoice_templates::create_router(invoice_template_service, create_invoice_from_template_uc))
+            .nest("/events", events::create_router(realtime_service))
+            .nest("/fx", fx::create_router(fx_rate_service))
+            .nest("/payouts", payouts::create_router(payout_service.clone()))
+            .nest("/businesses", businesses::create_router(business_service))
+            .nest("/accountants", accountants::create_router(accountant_service.clone()))
+            .nest("/campaigns", campaigns::create_router(campaign_service))
+            .nest("/sync", sync::create_router(sync_service))
+            .nest("/webhooks", payouts::create_webhook_router(payout_service)
+                .merge(paypal::create_webhook_router(paypal_webhook_service))
+                .merge(stripe_checkout::create_webhook_router(stripe_checkout_service)))
         )
         // Metrics endpoint (public, no auth required)
         .nest("/metrics", metrics::create_router(
@@ -389,8 +875,26 @@ async fn main() {
             monitoring_service.clone(),
             redis_service.clone(),
             Some(db_pool.clone()),
+            scrape_auth.clone(),
         ))
+        // Email queue dead letters; needs the metrics credentials
+        .nest("/admin", admin::create_router(email_queue_service.clone(), scrape_auth))
+        // Sparse responses: ?fields=... or ?view=compact on GET endpoints
+        .layer(axum::middleware::from_fn(sparse_fields_middleware))
+        // Inside idempotency so replays aren't recorded twice
+        .layer(axum::middleware::from_fn_with_state(audit_service, audit_middleware))
+        .layer(axum::middleware::from_fn_with_state(report_cache, report_cache_middleware))
+        // Runs inside the auth extension so keys are scoped to the calling user
+        .layer(axum::middleware::from_fn_with_state(idempotency, idempotency_middleware))
+        .layer(axum::middleware::from_fn_with_state(rate_limiter, rate_limit_middleware))
         .layer(Extension(auth_service))  // Add auth service to extensions for AuthUser extractor
+        // Who may set X-Forwarded-For, for rate limits, sign-in throttling and the audit trail
+        .layer(Extension(trusted_proxies))
+        .layer(Extension(accountant_service))
+        // Reject reports, exports and PDFs with 503 while unhealthy
+        .layer(axum::middleware::from_fn_with_state(monitoring_service.clone(), load_shedding_middleware))
+        // Accept-Language for translated error messages
+        .layer(axum::middleware::from_fn(locale_middleware))
         // Security: CORS configuration
         .layer(
             CorsLayer::new()
@@ -406,7 +910,9 @@ async fn main() {
                     axum::http::header::AUTHORIZATION,
                     axum::http::header::ACCEPT,
                     axum::http::header::ORIGIN,
+                    HeaderName::from_static(REQUEST_ID_HEADER),
                 ])
+                .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)])
                 .allow_credentials(true)
                 .max_age(Duration::from_secs(3600)),
         )
@@ -414,6 +920,8 @@ async fn main() {
         .layer(RequestBodyLimitLayer::new(10 * 1024 * 1024))
         // Security: Request timeout (30 seconds)
         .layer(RequestBodyTimeoutLayer::new(Duration::from_secs(30)))
+        // Errors from rejections, unmatched routes and the limits above get the error envelope
+        .layer(axum::middleware::from_fn(error_envelope_middleware))
         // Security: Add security headers to responses
         .layer(SetResponseHeaderLayer::overriding(
             axum::http::header::X_CONTENT_TYPE_OPTIONS,
@@ -435,8 +943,12 @@ async fn main() {
             axum::http::header::REFERRER_POLICY,
             HeaderValue::from_static("strict-origin-when-cross-origin"),
         ))
+        // Latency and status by matched route; outside the limits above so their rejections count too
+        .layer(axum::middleware::from_fn_with_state(metrics_service, metrics_middleware))
         // Logging/Tracing
-        .layer(TraceLayer::new_for_http());
+        .layer(TraceLayer::new_for_http())
+        // Outermost, so every log line and error body carries the X-Request-Id
+        .layer(axum::middleware::from_fn(request_id_middleware));
 
     // Start server
     let port = std::env::var("PORT").unwrap_or_else(|_| "3000".to_string());
@@ -448,15 +960,32 @@ async fn main() {
         .await
         .expect("Failed to bind port");
 
-    axum::serve(listener, app)
-        .await
-        .expect("Server failed");
+    // Peer addresses are needed for the metrics IP allowlist
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown.clone().triggered());
+
+    // On shutdown no new connections are accepted; in-flight requests and the
+    // jobs workers have in hand get the grace period to finish
+    let serving = async {
+        tokio::select! {
+            result = server => result.expect("Server failed"),
+            _ = shutdown.clone().grace_expired() => {
+                tracing::warn!("Requests still in flight after {:?}, dropping them", shutdown.grace());
+            }
+        }
+    };
+    let draining = async {
+        shutdown.clone().triggered().await;
+        if !shutdown.drain(shutdown.grace()).await {
+            tracing::warn!("Background jobs still running after {:?}, abandoning them", shutdown.grace());
+        }
+    };
+    tokio::join!(serving, draining);
+
+    db_pool.close().await;
+    tracing::info!("FlashBill API server stopped");
 }
 
 async fn health_check() -> &'static str {
     "OK"
 }
-
-async fn ready_check() -> &'static str {
-    "READY"
-}
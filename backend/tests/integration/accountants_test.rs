@@ -0,0 +1,86 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("accountant_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Ledger Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_accountant_access_is_read_only_and_revocable() {
+    let owner = setup_authenticated_client().await;
+
+    let resp = owner.create_client("Audit Client", "audit.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = owner.create_invoice(&client_id, 300.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = owner.invite_accountant("not-an-email", None).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = owner.invite_accountant("books@example.com", Some(14)).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let invite: Value = resp.json().await.unwrap();
+    let access_id = invite["id"].as_str().unwrap().to_string();
+    let access_code = invite["access_code"].as_str().unwrap().to_string();
+
+    let resp = owner.accountant_sign_in("wrong-code").await.unwrap();
+    assert_eq!(resp.status(), 401);
+
+    let resp = owner.accountant_sign_in(&access_code).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let session: Value = resp.json().await.unwrap();
+    assert_eq!(session["business_name"], "Ledger Co");
+    let mut accountant = ApiTestClient::new(get_api_base_url());
+    accountant.set_token(session["access_token"].as_str().unwrap().to_string());
+
+    // Reports, exports, the invoice list and PDFs are readable
+    let resp = accountant.get_overview_stats().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = accountant.get_income_report("2024-01-01", "2030-12-31").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = accountant.list_invoices().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = accountant.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // Nothing else: no clients, no edits, no sending, no managing access
+    let resp = accountant.list_clients().await.unwrap();
+    assert_eq!(resp.status(), 403);
+    let resp = accountant.create_client("Sneaky", "sneaky@example.com").await.unwrap();
+    assert_eq!(resp.status(), 403);
+    let resp = accountant.send_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 403);
+    let resp = accountant.invite_accountant("another@example.com", None).await.unwrap();
+    assert_eq!(resp.status(), 403);
+
+    let resp = owner.list_accountants().await.unwrap();
+    let grants: Value = resp.json().await.unwrap();
+    let grants = grants.as_array().unwrap();
+    assert_eq!(grants.len(), 1);
+    assert!(grants[0].get("access_code").is_none());
+    assert!(grants[0]["last_used_at"].is_string());
+
+    // Revoking cuts off the token already issued
+    let resp = owner.revoke_accountant(&access_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = accountant.get_overview_stats().await.unwrap();
+    assert_eq!(resp.status(), 401);
+    let resp = owner.accountant_sign_in(&access_code).await.unwrap();
+    assert_eq!(resp.status(), 401);
+}
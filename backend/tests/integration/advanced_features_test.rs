@@ -129,6 +129,62 @@ async fn test_push_notification() {
     assert!(resp.status() == 200 || resp.status() == 202 || resp.status() == 500);
 }
 
+#[tokio::test]
+async fn test_automation_issues_endpoint() {
+    let client = setup_authenticated_client().await;
+
+    // A fresh account has nothing needing attention
+    let resp = client.list_automation_issues(false).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let issues: Value = resp.json().await.unwrap();
+    assert!(issues.as_array().unwrap().is_empty());
+
+    let resp = client.list_automation_issues(true).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // Resolving an unknown issue is a 404
+    let resp = client.resolve_automation_issue(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    // Requires authentication
+    let anonymous = ApiTestClient::new(get_api_base_url());
+    let resp = anonymous.list_automation_issues(false).await.unwrap();
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn test_anonymized_invoice_export() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Pat Private", "pat.private@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_anonymized_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    let text = body.to_string();
+    assert!(!text.contains("Pat Private"));
+    assert!(!text.contains("pat.private@example.com"));
+    assert!(!text.contains(&invoice_id));
+    assert!(body["invoice"]["guest_payment_token"].is_null());
+    assert_eq!(body["invoice"]["invoice_number"], created["invoice_number"]);
+
+    // Only the owner can export, and unknown invoices are a 404
+    let resp = client.get_anonymized_invoice(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+    let anonymous = ApiTestClient::new(get_api_base_url());
+    let resp = anonymous.get_anonymized_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 401);
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
 #[tokio::test]
 async fn test_rate_limiting() {
     let client = setup_authenticated_client().await;
@@ -152,6 +208,84 @@ async fn test_rate_limiting() {
     assert!(success_count > 0 || rate_limited_count > 0);
 }
 
+#[tokio::test]
+async fn test_rate_limit_headers_and_quota() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.list_clients().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let headers = resp.headers();
+    let limit: u32 = headers["x-ratelimit-limit"].to_str().unwrap().parse().unwrap();
+    let remaining: u32 = headers["x-ratelimit-remaining"].to_str().unwrap().parse().unwrap();
+    let reset: i64 = headers["x-ratelimit-reset"].to_str().unwrap().parse().unwrap();
+    assert!(remaining < limit);
+    assert!(reset > 0);
+
+    // Quota endpoint shows the caller's buckets
+    let resp = client.get_rate_limits().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().contains_key("x-ratelimit-remaining"));
+    let status: Value = resp.json().await.unwrap();
+    assert!(status["caller"].as_str().unwrap().starts_with("user:"));
+    let buckets = status["buckets"].as_array().unwrap();
+    assert_eq!(buckets.len(), 2);
+    for bucket in buckets {
+        assert!(bucket["used"].as_u64().unwrap() >= 2);
+        assert_eq!(
+            bucket["remaining"].as_u64().unwrap(),
+            bucket["limit"].as_u64().unwrap().saturating_sub(bucket["used"].as_u64().unwrap())
+        );
+    }
+
+    // Unauthenticated responses carry the headers too
+    let resp = reqwest::get(format!("{}/health", get_api_base_url())).await.unwrap();
+    assert!(resp.headers().contains_key("x-ratelimit-limit"));
+}
+
+#[tokio::test]
+async fn test_sign_in_is_limited_more_strictly_than_reads() {
+    let client = setup_authenticated_client().await;
+    let resp = client.get_rate_limits().await.unwrap();
+    let status: Value = resp.json().await.unwrap();
+    assert_eq!(status["category"], "read");
+    let read_limit: u32 = resp_limit(&client.list_clients().await.unwrap());
+
+    let anonymous = ApiTestClient::new(get_api_base_url());
+    let mut resp = anonymous.login("nobody@example.com", "wrong-password").await.unwrap();
+    assert!(resp_limit(&resp) < read_limit);
+
+    if status["enforced"] == true {
+        // Enough attempts from this IP end in 429 with a wait
+        for _ in 0..100 {
+            if resp.status() == 429 {
+                break;
+            }
+            resp = anonymous.login("nobody@example.com", "wrong-password").await.unwrap();
+        }
+        assert_eq!(resp.status(), 429);
+        let retry_after: i64 = resp.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+        assert!(retry_after > 0);
+    }
+}
+
+fn resp_limit(resp: &reqwest::Response) -> u32 {
+    resp.headers()["x-ratelimit-limit"].to_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_openapi_spec_is_served() {
+    let resp = reqwest::get(format!("{}/api/v1/openapi.json", get_api_base_url())).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let spec: Value = resp.json().await.unwrap();
+    assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+    assert!(spec["paths"]["/api/v1/invoices/{id}"]["get"].is_object());
+    assert!(spec["components"]["schemas"]["ErrorResponse"].is_object());
+    assert_eq!(spec["components"]["securitySchemes"]["bearer_auth"]["scheme"], "bearer");
+
+    let resp = reqwest::get(format!("{}/api/v1/docs/", get_api_base_url())).await.unwrap();
+    assert_eq!(resp.status(), 200);
+}
+
 #[tokio::test]
 async fn test_security_headers() {
     // Make a raw request to check headers
@@ -197,3 +331,44 @@ async fn test_request_timeout() {
     let resp = client.health_check().await.unwrap();
     assert_eq!(resp, "OK");
 }
+
+#[tokio::test]
+async fn test_readiness_checks_each_dependency() {
+    let client = ApiTestClient::new(get_api_base_url());
+
+    let resp = client.ready_check().await.unwrap();
+    let status = resp.status();
+    let body: Value = resp.json().await.unwrap();
+    let dependencies = body["dependencies"].as_array().unwrap();
+    let names: Vec<_> = dependencies.iter().map(|d| d["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["database", "redis", "smtp"]);
+    assert_eq!(dependencies[0]["status"], "up");
+
+    // 503 exactly when something is down, and the body says what
+    let down: Vec<_> = dependencies.iter().filter(|d| d["status"] == "down").collect();
+    assert_eq!(body["ready"], down.is_empty());
+    if down.is_empty() {
+        assert_eq!(status, 200);
+    } else {
+        assert_eq!(status, 503);
+        assert!(down.iter().all(|d| d["error"].is_string()));
+        assert!(body["error"].as_str().unwrap().contains(down[0]["name"].as_str().unwrap()));
+    }
+}
+
+#[tokio::test]
+async fn test_email_dead_letters_need_admin_credentials() {
+    let anonymous = ApiTestClient::new(get_api_base_url());
+
+    // Closed (403) when no metrics credentials are configured, 401 otherwise
+    let resp = anonymous.get_email_queue_dead_letters(None).await.unwrap();
+    assert!(matches!(resp.status().as_u16(), 401 | 403));
+
+    // A user's token isn't enough
+    let email = format!("dead_letters_{}@example.com", crate::integration::utils::get_unique_id());
+    anonymous.register(&email, "testpassword123", None).await.unwrap();
+    let login: Value = anonymous.login(&email, "testpassword123").await.unwrap().json().await.unwrap();
+    let token = login["access_token"].as_str().unwrap();
+    let resp = anonymous.get_email_queue_dead_letters(Some(token)).await.unwrap();
+    assert!(matches!(resp.status().as_u16(), 401 | 403));
+}
@@ -0,0 +1,126 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("attachments_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Attachment Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_invoice(client: &ApiTestClient) -> String {
+    let resp = client.create_client("Attachment Client", "attachments@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 400.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    invoice["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_invoice_attachments() {
+    let client = setup_authenticated_client().await;
+    let invoice_id = create_invoice(&client).await;
+
+    let resp = client
+        .upload_attachment("invoices", &invoice_id, "timesheet.csv", "text/csv", b"date,hours\n2026-10-01,8\n")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let attachment: Value = resp.json().await.unwrap();
+    let attachment_id = attachment["id"].as_str().unwrap().to_string();
+    assert_eq!(attachment["file_name"], "timesheet.csv");
+    assert_eq!(attachment["mime_type"], "text/csv");
+    assert_eq!(attachment["file_size"], 24);
+    assert_eq!(attachment["invoice_id"], invoice_id.as_str());
+    assert!(attachment.get("stored_name").is_none());
+
+    let resp = client.list_attachments("invoices", &invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let list: Value = resp.json().await.unwrap();
+    assert_eq!(list.as_array().unwrap().len(), 1);
+
+    let resp = client.download_attachment("invoices", &invoice_id, &attachment_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/csv");
+    assert_eq!(resp.headers()["content-disposition"], "attachment; filename=\"timesheet.csv\"");
+    assert_eq!(resp.bytes().await.unwrap().as_ref(), b"date,hours\n2026-10-01,8\n");
+
+    // The client can see and download it through the guest link
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    let guest_token = invoice["guest_payment_token"].as_str().unwrap().to_string();
+    let guest: Value = client.get_guest_invoice(&guest_token).await.unwrap().json().await.unwrap();
+    assert_eq!(guest["attachments"][0]["id"], attachment_id.as_str());
+    let resp = client.get_guest_attachment(&guest_token, &attachment_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.bytes().await.unwrap().as_ref(), b"date,hours\n2026-10-01,8\n");
+
+    // Executables aren't accepted
+    let resp = client
+        .upload_attachment("invoices", &invoice_id, "setup.exe", "application/x-msdownload", b"MZ")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Other users can't see or add to the invoice's attachments
+    let other = setup_authenticated_client().await;
+    assert_eq!(other.list_attachments("invoices", &invoice_id).await.unwrap().status(), 404);
+    assert_eq!(other.download_attachment("invoices", &invoice_id, &attachment_id).await.unwrap().status(), 404);
+    let resp = other
+        .upload_attachment("invoices", &invoice_id, "contract.pdf", "application/pdf", b"%PDF-1.4")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+    assert_eq!(other.delete_attachment("invoices", &invoice_id, &attachment_id).await.unwrap().status(), 404);
+
+    let resp = client.delete_attachment("invoices", &invoice_id, &attachment_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    assert_eq!(client.download_attachment("invoices", &invoice_id, &attachment_id).await.unwrap().status(), 404);
+    assert_eq!(client.get_guest_attachment(&guest_token, &attachment_id).await.unwrap().status(), 404);
+    let list: Value = client.list_attachments("invoices", &invoice_id).await.unwrap().json().await.unwrap();
+    assert!(list.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_expense_receipt_attachment() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_expense(42.5, "travel", "Taxi Co").await.unwrap();
+    let expense: Value = resp.json().await.unwrap();
+    let expense_id = expense["id"].as_str().unwrap().to_string();
+
+    // The type is guessed from the name when the client doesn't know it
+    let resp = client
+        .upload_attachment("expenses", &expense_id, "receipt.png", "application/octet-stream", b"\x89PNG")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let attachment: Value = resp.json().await.unwrap();
+    assert_eq!(attachment["mime_type"], "image/png");
+    assert_eq!(attachment["expense_id"], expense_id.as_str());
+    assert!(attachment["invoice_id"].is_null());
+    let attachment_id = attachment["id"].as_str().unwrap().to_string();
+
+    let resp = client.download_attachment("expenses", &expense_id, &attachment_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.bytes().await.unwrap().as_ref(), b"\x89PNG");
+
+    // An expense attachment isn't reachable under an invoice
+    let invoice_id = create_invoice(&client).await;
+    assert_eq!(client.download_attachment("invoices", &invoice_id, &attachment_id).await.unwrap().status(), 404);
+
+    let resp = client.upload_attachment("expenses", &expense_id, "empty.pdf", "application/pdf", b"").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
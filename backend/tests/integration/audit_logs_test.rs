@@ -0,0 +1,118 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+/// Client logged in as a fresh user, with that user's id
+async fn setup_authenticated_client() -> (ApiTestClient, String) {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("audit_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Audit Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+    let user_id = data["user"]["id"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    (authed_client, user_id)
+}
+
+#[tokio::test]
+async fn test_invoice_changes_are_audited() {
+    let (client, user_id) = setup_authenticated_client().await;
+
+    let resp = client.create_client("Audit Client", "audit@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 300.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.update_invoice(&invoice_id, "Updated for the audit trail").await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .list_audit_logs(&format!("entity_type=invoice&entity_id={}", invoice_id))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert_eq!(logs.len(), 2);
+
+    // Newest first
+    let update = &logs[0];
+    assert_eq!(update["action"], "update");
+    assert_eq!(update["actor_id"], user_id.as_str());
+    assert_eq!(update["changes"]["after"]["notes"], "Updated for the audit trail");
+    assert!(update["changes"]["after"].get("updated_at").is_none());
+    assert!(update["ip_address"].is_string());
+
+    let create = &logs[1];
+    assert_eq!(create["action"], "create");
+    assert!(create["changes"]["before"].is_null());
+    assert_eq!(create["changes"]["after"]["id"], invoice_id.as_str());
+    assert!(create["changes"]["after"].get("guest_payment_token").is_none());
+
+    let resp = client.list_audit_logs("entity_type=client").await.unwrap();
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["entity_id"], client_id.as_str());
+}
+
+#[tokio::test]
+async fn test_settings_changes_and_filters() {
+    let (client, user_id) = setup_authenticated_client().await;
+
+    let resp = client.update_invoice_settings("modern", "Net 15", "Thanks!").await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.list_audit_logs(&format!("entity_type=settings&actor_id={}", user_id)).await.unwrap();
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["changes"]["section"], "invoice");
+    assert_eq!(logs[0]["changes"]["after"]["invoice_settings"]["template"], "modern");
+    assert!(logs[0]["changes"]["after"].get("password_hash").is_none());
+
+    // Reads aren't recorded, and other actors' changes are filtered out
+    let resp = client.list_audit_logs(&format!("actor_id={}", uuid::Uuid::new_v4())).await.unwrap();
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert!(logs.is_empty());
+
+    let resp = client.list_audit_logs("date_from=2030-01-01").await.unwrap();
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert!(logs.is_empty());
+
+    let resp = client.list_audit_logs("date_from=2026-02-01&date_to=2026-01-01").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_entries_and_errors_carry_the_request_id() {
+    let (client, _) = setup_authenticated_client().await;
+
+    let request_id = format!("audit-test-{}", crate::integration::utils::get_unique_id());
+    let resp = client
+        .create_client_with_request_id("Traced Client", "traced@example.com", &request_id)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.headers()["x-request-id"], request_id.as_str());
+
+    let resp = client.list_audit_logs(&format!("request_id={}", request_id)).await.unwrap();
+    let logs: Vec<Value> = resp.json().await.unwrap();
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0]["entity_type"], "client");
+    assert_eq!(logs[0]["request_id"], request_id.as_str());
+
+    // Without one, an ID is generated and quoted in the error body
+    let resp = client.get_client(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+    let generated = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["request_id"], generated.as_str());
+}
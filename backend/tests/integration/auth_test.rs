@@ -82,3 +82,31 @@ async fn test_register_validation() {
     let resp = client.register("test@example.com", "short", None).await.unwrap();
     assert_eq!(resp.status(), 400, "Short password should return 400");
 }
+
+#[tokio::test]
+async fn test_repeated_wrong_passwords_lock_the_account() {
+    let client = ApiTestClient::new(get_api_base_url());
+    let email = format!("lockout_{}@example.com", crate::integration::utils::get_unique_id());
+    let password = "testpassword123";
+    let resp = client.register(&email, password, None).await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    for _ in 0..4 {
+        let resp = client.login(&email, "wrongpassword").await.unwrap();
+        assert_eq!(resp.status(), 401);
+    }
+
+    // The fifth wrong password locks the account for 15 minutes
+    let resp = client.login(&email, "wrongpassword").await.unwrap();
+    assert_eq!(resp.status(), 423);
+    let retry_after: i64 = resp.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert_eq!(retry_after, 15 * 60);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "ACCOUNT_LOCKED");
+
+    // Even the right password is refused until the lock expires
+    let resp = client.login(&email, password).await.unwrap();
+    assert_eq!(resp.status(), 423);
+    let retry_after: i64 = resp.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!(retry_after > 0 && retry_after <= 15 * 60);
+}
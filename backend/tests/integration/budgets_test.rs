@@ -0,0 +1,210 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("budget_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Budget Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_budget_crud() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_budget(json!({
+            "year": 2025,
+            "lines": [
+                { "kind": "income", "month": 1, "amount": 5000.0 },
+                { "kind": "expense", "category": "software", "month": 1, "amount": 100.0 }
+            ]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let budget: Value = resp.json().await.unwrap();
+    let budget_id = budget["id"].as_str().unwrap().to_string();
+    assert_eq!(budget["name"], "2025 Budget");
+    assert_eq!(budget["lines"].as_array().unwrap().len(), 2);
+
+    // One budget per year
+    let resp = client.create_budget(json!({ "year": 2025 })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .update_budget(&budget_id, json!({
+            "name": "Lean 2025",
+            "lines": [{ "kind": "expense", "category": "travel", "month": 3, "amount": 250.0 }]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["name"], "Lean 2025");
+    assert_eq!(updated["lines"].as_array().unwrap().len(), 1);
+    assert_eq!(updated["lines"][0]["category"], "travel");
+
+    let resp = client.list_budgets().await.unwrap();
+    let budgets: Value = resp.json().await.unwrap();
+    assert_eq!(budgets.as_array().unwrap().len(), 1);
+
+    let resp = client.delete_budget(&budget_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.get_budget_report(&budget_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_budget_validation() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_budget(json!({ "year": 2025, "lines": [{ "kind": "expense", "category": "software", "month": 13, "amount": 10.0 }] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .create_budget(json!({ "year": 2025, "lines": [{ "kind": "expense", "category": "software", "month": 1, "amount": -5.0 }] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_budget_report_variance() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_budget(json!({
+            "year": 2025,
+            "lines": [
+                { "kind": "income", "month": 1, "amount": 1000.0 },
+                { "kind": "expense", "category": "software", "month": 1, "amount": 100.0 }
+            ]
+        }))
+        .await
+        .unwrap();
+    let budget: Value = resp.json().await.unwrap();
+    let budget_id = budget["id"].as_str().unwrap().to_string();
+
+    // Test expenses are dated 2025-01-01
+    client.create_expense(150.0, "software", "Editor License").await.unwrap();
+
+    let resp = client.get_budget_report(&budget_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+
+    assert_eq!(report["months"].as_array().unwrap().len(), 12);
+    let january = &report["months"][0];
+    assert_eq!(january["expenses"]["planned"], 100.0);
+    assert_eq!(january["expenses"]["actual"], 150.0);
+    assert_eq!(january["expenses"]["variance"], 50.0);
+    assert_eq!(january["expenses"]["variance_pct"], 50.0);
+    assert_eq!(january["income"]["actual"], 0.0);
+    assert_eq!(january["income"]["variance_pct"], -100.0);
+
+    let software = report["categories"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["category"] == "software")
+        .unwrap();
+    assert_eq!(software["expenses"]["actual"], 150.0);
+
+    client.delete_budget(&budget_id).await.unwrap();
+}
+
+/// Alerts are checked in the background after an expense is saved
+async fn wait_for_alerts(client: &ApiTestClient, count: usize) -> Vec<Value> {
+    for _ in 0..20 {
+        let resp = client.list_budget_alerts(false).await.unwrap();
+        let alerts: Value = resp.json().await.unwrap();
+        let alerts = alerts.as_array().unwrap().clone();
+        if alerts.len() >= count {
+            return alerts;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("expected {} budget alert(s)", count);
+}
+
+#[tokio::test]
+async fn test_budget_limits_alert_at_thresholds() {
+    let client = setup_authenticated_client().await;
+    let today = chrono::Utc::now().date_naive().to_string();
+    let travel = |amount: f64| json!({ "amount": amount, "category": "travel", "date_incurred": today });
+
+    let resp = client
+        .create_budget_limit(json!({ "category": "travel", "period": "monthly", "amount": 500.0 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let limit: Value = resp.json().await.unwrap();
+    let limit_id = limit["id"].as_str().unwrap().to_string();
+
+    // One limit per category and period, and only positive amounts
+    let resp = client
+        .create_budget_limit(json!({ "category": "travel", "period": "monthly", "amount": 900.0 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client
+        .create_budget_limit(json!({ "category": "software", "period": "quarterly", "amount": 0.0 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    assert_eq!(client.create_expense_with(travel(300.0)).await.unwrap().status(), 201);
+    let resp = client.get_budget_utilization().await.unwrap();
+    let utilization: Value = resp.json().await.unwrap();
+    assert_eq!(utilization[0]["spent"], 300.0);
+    assert_eq!(utilization[0]["utilization_pct"], 60.0);
+    assert_eq!(utilization[0]["status"], "on_track");
+
+    assert_eq!(client.create_expense_with(travel(150.0)).await.unwrap().status(), 201);
+    let alerts = wait_for_alerts(&client, 1).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0]["threshold"], 80);
+    assert_eq!(alerts[0]["category"], "travel");
+
+    assert_eq!(client.create_expense_with(travel(100.0)).await.unwrap().status(), 201);
+    let alerts = wait_for_alerts(&client, 2).await;
+    assert_eq!(alerts[0]["threshold"], 100);
+    assert_eq!(alerts[0]["spent"], 550.0);
+
+    let resp = client.mark_budget_alert_read(alerts[1]["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.list_budget_alerts(true).await.unwrap();
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread.as_array().unwrap().len(), 1);
+
+    // Raising the limit starts the period's alerts over
+    let resp = client.update_budget_limit(&limit_id, json!({ "amount": 1000.0 })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.list_budget_alerts(false).await.unwrap();
+    let alerts: Value = resp.json().await.unwrap();
+    assert!(alerts.as_array().unwrap().is_empty());
+    let resp = client.get_budget_utilization().await.unwrap();
+    let utilization: Value = resp.json().await.unwrap();
+    assert_eq!(utilization[0]["status"], "on_track");
+    assert_eq!(utilization[0]["remaining"], 450.0);
+
+    let resp = client.delete_budget_limit(&limit_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.get_budget_utilization().await.unwrap();
+    let utilization: Value = resp.json().await.unwrap();
+    assert!(utilization.as_array().unwrap().is_empty());
+}
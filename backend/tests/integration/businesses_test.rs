@@ -0,0 +1,94 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("business_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Primary Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_business_scopes_data() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_business("Side Studio").await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let business: Value = resp.json().await.unwrap();
+    let business_id = business["id"].as_str().unwrap().to_string();
+    assert_eq!(business["is_primary"], false);
+
+    let resp = client.list_businesses().await.unwrap();
+    let businesses: Value = resp.json().await.unwrap();
+    let businesses = businesses.as_array().unwrap();
+    assert_eq!(businesses.len(), 2);
+    assert_eq!(businesses[0]["is_primary"], true);
+    assert_eq!(businesses[0]["name"], "Primary Co");
+
+    let side = client.with_business(&business_id);
+    let resp = side.create_client("Side Client", "side@example.com").await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let side_clients: Value = side.list_clients().await.unwrap().json().await.unwrap();
+    let primary_clients: Value = client.list_clients().await.unwrap().json().await.unwrap();
+    assert_eq!(side_clients["items"].as_array().unwrap().len(), 1);
+    assert_eq!(primary_clients["items"].as_array().unwrap().len(), 0);
+
+    // Branding is kept per business
+    let resp = side.update_business_settings("Side Studio LLC", "").await.unwrap();
+    assert!(resp.status().is_success());
+    let side_settings: Value = side.get_business_settings().await.unwrap().json().await.unwrap();
+    let primary_settings: Value = client.get_business_settings().await.unwrap().json().await.unwrap();
+    assert_eq!(side_settings["company_name"], "Side Studio LLC");
+    assert_eq!(primary_settings["company_name"], "Primary Co");
+
+    // The profile always belongs to the login
+    let me: Value = side.get_current_user().await.unwrap().json().await.unwrap();
+    assert_eq!(me["company_name"], "Primary Co");
+}
+
+#[tokio::test]
+async fn test_unknown_business_is_forbidden() {
+    let client = setup_authenticated_client().await;
+    let other = setup_authenticated_client().await;
+
+    let resp = other.create_business("Not Yours").await.unwrap();
+    let business: Value = resp.json().await.unwrap();
+    let foreign_id = business["id"].as_str().unwrap();
+
+    let resp = client.with_business(foreign_id).list_clients().await.unwrap();
+    assert_eq!(resp.status(), 403);
+
+    let resp = client.with_business("not-a-uuid").list_clients().await.unwrap();
+    assert_eq!(resp.status(), 403);
+}
+
+#[tokio::test]
+async fn test_delete_business() {
+    let client = setup_authenticated_client().await;
+
+    let businesses: Value = client.list_businesses().await.unwrap().json().await.unwrap();
+    let primary_id = businesses[0]["id"].as_str().unwrap().to_string();
+    let resp = client.delete_business(&primary_id).await.unwrap();
+    assert!(resp.status().is_client_error());
+
+    let business: Value = client.create_business("Short Lived").await.unwrap().json().await.unwrap();
+    let business_id = business["id"].as_str().unwrap();
+
+    let resp = client.delete_business(business_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+
+    let resp = client.with_business(business_id).list_clients().await.unwrap();
+    assert_eq!(resp.status(), 403);
+}
@@ -0,0 +1,99 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("campaign_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Dunning Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_campaign_preview_schedule_and_cancel() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Slow Payer", "slow.payer@example.com").await.unwrap();
+    let owing: Value = resp.json().await.unwrap();
+    let owing_id = owing["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&owing_id, 400.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let resp = client.send_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // A client with only a draft owes nothing yet
+    let resp = client.create_client("Draft Only", "draft.only@example.com").await.unwrap();
+    let settled: Value = resp.json().await.unwrap();
+    let settled_id = settled["id"].as_str().unwrap().to_string();
+    client.create_invoice(&settled_id, 100.0).await.unwrap();
+
+    let resp = client
+        .preview_campaign(json!({
+            "segment": { "client_ids": [owing_id, settled_id] },
+            "template": "friendly",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let preview: Value = resp.json().await.unwrap();
+    let recipients = preview["recipients"].as_array().unwrap();
+    assert_eq!(recipients.len(), 1);
+    assert_eq!(recipients[0]["client_id"], owing_id.as_str());
+    assert_eq!(recipients[0]["open_invoices"], 1);
+    let sample_body = preview["sample"]["body"].as_str().unwrap();
+    assert!(sample_body.starts_with("Hi Slow Payer,"));
+    assert!(sample_body.contains("Dunning Co"));
+
+    let resp = client
+        .create_campaign(json!({
+            "name": "Q1 follow-up",
+            "segment": { "client_ids": [settled_id] },
+            "template": "firm",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Scheduled well ahead so the sender leaves it alone
+    let resp = client
+        .create_campaign(json!({
+            "name": "Q1 follow-up",
+            "segment": { "client_ids": [owing_id] },
+            "template": "firm",
+            "send_interval_seconds": 120,
+            "start_at": "2099-01-01T09:00:00Z",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let report: Value = resp.json().await.unwrap();
+    let campaign_id = report["id"].as_str().unwrap().to_string();
+    assert_eq!(report["status"], "scheduled");
+    assert_eq!(report["summary"]["recipients"], 1);
+    assert_eq!(report["summary"]["pending"], 1);
+    assert_eq!(report["recipients"][0]["status"], "pending");
+    assert_eq!(report["recipients"][0]["send_after"], "2099-01-01T09:00:00Z");
+
+    let resp = client.cancel_campaign(&campaign_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["status"], "cancelled");
+    assert_eq!(report["summary"]["skipped"], 1);
+
+    let resp = client.cancel_campaign(&campaign_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_campaign(&campaign_id).await.unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["recipients"][0]["error"], "Campaign cancelled");
+}
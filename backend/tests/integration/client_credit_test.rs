@@ -0,0 +1,166 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("credit_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Credit Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient, name: &str) -> String {
+    let resp = client.create_client(name, "retainer.client@test.com").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+async fn create_invoice(client: &ApiTestClient, client_id: &str, amount: f64) -> Value {
+    let resp = client.create_invoice(client_id, amount).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let data: Value = resp.json().await.unwrap();
+    let invoice_id = data["id"].as_str().unwrap();
+    client.get_invoice(invoice_id).await.unwrap().json().await.unwrap()
+}
+
+fn as_f64(value: &Value) -> f64 {
+    value.as_f64().unwrap_or_else(|| value.as_str().unwrap().parse().unwrap())
+}
+
+#[tokio::test]
+async fn test_client_credit_deposit_applied_when_sent() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Retainer Client").await;
+    let invoice = create_invoice(&client, &client_id, 300.0).await;
+    let invoice_id = invoice["id"].as_str().unwrap();
+    let currency = invoice["currency"].as_str().unwrap();
+
+    let resp = client
+        .deposit_client_credit(&client_id, json!({ "amount": 500, "currency": currency, "description": "Q1 retainer" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let entry: Value = resp.json().await.unwrap();
+    assert_eq!(entry["kind"], "deposit");
+
+    // The default applies credit when the invoice is sent, not when it's created
+    let draft: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&draft["amount_paid"]), 0.0);
+
+    let resp = client.send_invoice(invoice_id).await.unwrap();
+    assert!(resp.status().is_success());
+    let sent: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(sent["status"], "paid");
+    assert_eq!(as_f64(&sent["amount_paid"]), 300.0);
+
+    let ledger: Value = client.get_client_credit(&client_id).await.unwrap().json().await.unwrap();
+    let balances = ledger["balances"].as_array().unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(as_f64(&balances[0]["amount"]), 200.0);
+    let entries = ledger["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["kind"], "applied");
+    assert_eq!(as_f64(&entries[1]["amount"]), -300.0);
+    assert_eq!(entries[1]["invoice_id"], invoice_id);
+
+    let stats: Value = client.get_client_stats().await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&stats["credit_balance"]), 200.0);
+
+    // The statement lists the credit drawn and what's left
+    let today = chrono::Utc::now().naive_utc().date();
+    let query = format!("start_date={}&end_date={}", today - chrono::Duration::days(7), today);
+    let statement: Value = client.get_client_account_statement(&client_id, &query).await.unwrap().json().await.unwrap();
+    let lines = statement["lines"].as_array().unwrap();
+    assert!(lines.iter().any(|line| line["kind"] == "client_credit" && as_f64(&line["credit"]) == 300.0));
+    assert_eq!(as_f64(&statement["closing_balance"]), 0.0);
+    assert_eq!(as_f64(&statement["credit_balances"][0]["amount"]), 200.0);
+
+    // A refund can't take more than is left
+    let resp = client
+        .refund_client_credit(&client_id, json!({ "amount": 250, "currency": currency }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .refund_client_credit(&client_id, json!({ "amount": 200, "currency": currency }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let ledger: Value = client.get_client_credit(&client_id).await.unwrap().json().await.unwrap();
+    assert!(ledger["balances"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_client_credit_apply_modes() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Prepaid Client").await;
+    let first = create_invoice(&client, &client_id, 100.0).await;
+    let currency = first["currency"].as_str().unwrap().to_string();
+
+    let resp = client
+        .deposit_client_credit(&client_id, json!({ "amount": 0, "currency": currency }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    client
+        .deposit_client_credit(&client_id, json!({ "amount": 150, "currency": currency }))
+        .await
+        .unwrap();
+
+    // Applying by hand settles what the credit covers
+    let resp = client.apply_client_credit(first["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let applied: Value = resp.json().await.unwrap();
+    assert_eq!(as_f64(&applied["applied"]), 100.0);
+    assert_eq!(as_f64(&applied["remaining_credit"]), 50.0);
+
+    // Applied on creation, the rest of the credit leaves the invoice partly paid
+    let resp = client
+        .update_invoice_settings_with(json!({
+            "template": "default",
+            "terms": "Net 30",
+            "notes": "",
+            "credit_auto_apply": "on_create",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let second = create_invoice(&client, &client_id, 80.0).await;
+    assert_eq!(second["status"], "partial");
+    assert_eq!(as_f64(&second["amount_paid"]), 50.0);
+
+    // With nothing left, applying does nothing
+    let resp = client.apply_client_credit(second["id"].as_str().unwrap()).await.unwrap();
+    let applied: Value = resp.json().await.unwrap();
+    assert_eq!(as_f64(&applied["applied"]), 0.0);
+    assert_eq!(as_f64(&applied["balance_due"]), 30.0);
+
+    // Manual never applies on its own
+    client
+        .update_invoice_settings_with(json!({
+            "template": "default",
+            "terms": "Net 30",
+            "notes": "",
+            "credit_auto_apply": "manual",
+        }))
+        .await
+        .unwrap();
+    client
+        .deposit_client_credit(&client_id, json!({ "amount": 40, "currency": currency }))
+        .await
+        .unwrap();
+    let third = create_invoice(&client, &client_id, 20.0).await;
+    client.send_invoice(third["id"].as_str().unwrap()).await.unwrap();
+    let third: Value = client.get_invoice(third["id"].as_str().unwrap()).await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&third["amount_paid"]), 0.0);
+}
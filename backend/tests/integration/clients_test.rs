@@ -1,5 +1,5 @@
 use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
-use serde_json::Value;
+use serde_json::{json, Value};
 
 async fn setup_authenticated_client() -> ApiTestClient {
     let base_url = get_api_base_url();
@@ -41,8 +41,8 @@ async fn test_client_crud() {
     let resp = client.list_clients().await.unwrap();
     assert_eq!(resp.status(), 200);
     let list: Value = resp.json().await.unwrap();
-    assert!(list.is_array());
-    assert!(list.as_array().unwrap().len() > 0);
+    assert!(list["items"].is_array());
+    assert!(list["items"].as_array().unwrap().len() > 0);
 
     // 4. Update client
     let resp = client.update_client(&client_id, "Jane Doe", "jane@example.com").await.unwrap();
@@ -100,3 +100,541 @@ async fn test_client_not_found() {
     let resp = client.get_client("00000000-0000-0000-0000-000000000000").await.unwrap();
     assert_eq!(resp.status(), 404, "Non-existent client should return 404");
 }
+
+#[tokio::test]
+async fn test_client_hierarchy_statement() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Parent Holdings", "parent@test.com").await.unwrap();
+    let parent: Value = resp.json().await.unwrap();
+    let parent_id = parent["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_client("Subsidiary Ltd", "subsidiary@test.com").await.unwrap();
+    let child: Value = resp.json().await.unwrap();
+    let child_id = child["id"].as_str().unwrap().to_string();
+
+    // Attach subsidiary to parent
+    let resp = client.set_client_parent(&child_id, Some(&parent_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["parent_client_id"], parent_id);
+
+    // Cycles are rejected
+    let resp = client.set_client_parent(&parent_id, Some(&child_id)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.set_client_parent(&parent_id, Some(&parent_id)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Sent invoices to parent and subsidiary roll up into the parent statement
+    let resp = client.create_invoice(&parent_id, 100.0).await.unwrap();
+    let parent_invoice: Value = resp.json().await.unwrap();
+    let parent_invoice_id = parent_invoice["id"].as_str().unwrap().to_string();
+    client.send_invoice(&parent_invoice_id).await.unwrap();
+
+    let resp = client.create_invoice(&child_id, 200.0).await.unwrap();
+    let child_invoice: Value = resp.json().await.unwrap();
+    let child_invoice_id = child_invoice["id"].as_str().unwrap().to_string();
+    client.send_invoice(&child_invoice_id).await.unwrap();
+
+    let resp = client.get_client_statement(&parent_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let statement: Value = resp.json().await.unwrap();
+    assert_eq!(statement["outstanding_balance"], 300.0);
+    assert_eq!(statement["balances"].as_array().unwrap().len(), 2);
+    assert_eq!(statement["invoices"].as_array().unwrap().len(), 2);
+
+    // The subsidiary's own statement only covers its invoices
+    let resp = client.get_client_statement(&child_id).await.unwrap();
+    let statement: Value = resp.json().await.unwrap();
+    assert_eq!(statement["outstanding_balance"], 200.0);
+
+    // Detach
+    let resp = client.set_client_parent(&child_id, None).await.unwrap();
+    let detached: Value = resp.json().await.unwrap();
+    assert!(detached["parent_client_id"].is_null());
+
+    // Cleanup
+    client.delete_invoice(&parent_invoice_id).await.unwrap();
+    client.delete_invoice(&child_invoice_id).await.unwrap();
+    client.delete_client(&child_id).await.unwrap();
+    client.delete_client(&parent_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_account_statement() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Statement Account", "account@test.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 1000.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    client.send_invoice(&invoice_id).await.unwrap();
+    let resp = client.record_payment(&invoice_id, 400.0).await.unwrap();
+    assert!(resp.status().is_success());
+
+    let today = chrono::Utc::now().date_naive();
+    let period = format!("start_date={}&end_date={}", today - chrono::Duration::days(30), today);
+    let resp = client.get_client_account_statement(&client_id, &period).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let statement: Value = resp.json().await.unwrap();
+    assert_eq!(statement["opening_balance"], 0.0);
+    let lines = statement["lines"].as_array().unwrap();
+    let kinds: Vec<&str> = lines.iter().map(|line| line["kind"].as_str().unwrap()).collect();
+    assert_eq!(kinds, vec!["invoice", "payment"]);
+    assert_eq!(lines[0]["reference"], invoice["invoice_number"]);
+    assert_eq!(lines[0]["balance"], 1000.0);
+    assert_eq!(lines[1]["credit"], 400.0);
+    assert_eq!(statement["total_received"], 400.0);
+    assert_eq!(statement["closing_balance"], 600.0);
+
+    // A later period opens with what is still owed
+    let later = format!(
+        "start_date={}&end_date={}",
+        today + chrono::Duration::days(1),
+        today + chrono::Duration::days(10)
+    );
+    let resp = client.get_client_account_statement(&client_id, &later).await.unwrap();
+    let statement: Value = resp.json().await.unwrap();
+    assert_eq!(statement["opening_balance"], 600.0);
+    assert!(statement["lines"].as_array().unwrap().is_empty());
+    assert_eq!(statement["closing_balance"], 600.0);
+
+    // PDF and CSV exports
+    let resp = client.get_client_account_statement(&client_id, &format!("{}&format=pdf", period)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+    assert!(resp.bytes().await.unwrap().starts_with(b"%PDF"));
+
+    let resp = client.get_client_account_statement(&client_id, &format!("{}&format=csv", period)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/csv");
+    let csv = resp.text().await.unwrap();
+    assert!(csv.starts_with("Date,Type,Reference,Description,Debit,Credit,Balance"));
+    assert!(csv.trim_end().ends_with("Closing balance,,,600.00"));
+
+    // Emailed to the client's address unless another is given
+    let body = serde_json::json!({ "start_date": today - chrono::Duration::days(30), "end_date": today });
+    let resp = client.email_client_statement(&client_id, body).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let emailed: Value = resp.json().await.unwrap();
+    assert_eq!(emailed["sent_to"], "account@test.com");
+    assert_eq!(emailed["closing_balance"], 600.0);
+
+    let body = serde_json::json!({ "start_date": today, "end_date": today, "to": "not-an-email" });
+    let resp = client.email_client_statement(&client_id, body).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Both dates are needed, in order
+    let resp = client.get_client_account_statement(&client_id, &format!("start_date={}", today)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let reversed = format!("start_date={}&end_date={}", today, today - chrono::Duration::days(1));
+    let resp = client.get_client_account_statement(&client_id, &reversed).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.get_client_account_statement(&client_id, &format!("{}&format=xml", period)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_monthly_statement_settings() {
+    let client = setup_authenticated_client().await;
+
+    // Monthly statements are off until the account enables them
+    let resp = client.get_notification_settings().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["email_monthly_statements"], false);
+
+    let resp = client.enable_monthly_statements(true).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["email_monthly_statements"], true);
+
+    // Clients receive them unless opted out
+    let resp = client.create_client("Statement Client", "statements@example.com").await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_client(&client_id).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    assert_eq!(data["statement_opt_out"], false);
+
+    let resp = client.set_client_statement_opt_out(&client_id, true).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let data: Value = resp.json().await.unwrap();
+    assert_eq!(data["statement_opt_out"], true);
+    assert_eq!(data["name"], "Statement Client");
+
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_client_locale_overrides_the_users() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Berlin GmbH", "buchhaltung@example.com").await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+    assert!(created["locale"].is_null());
+
+    let resp = client.set_client_locale(&client_id, "de").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let data: Value = resp.json().await.unwrap();
+    assert_eq!(data["locale"], "de");
+    assert_eq!(data["name"], "Berlin GmbH");
+
+    // The invoice PDF is drawn with the German labels
+    let resp = client.create_invoice(&client_id, 1250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let resp = client.get_invoice_pdf(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.bytes().await.unwrap().starts_with(b"%PDF"));
+}
+
+#[tokio::test]
+async fn test_client_invoice_defaults_prefill_new_invoices() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_tax_setting("Sales Tax", 0.07, true).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.create_tax_setting("VAT", 0.19, false).await.unwrap();
+    let vat: Value = resp.json().await.unwrap();
+
+    let resp = client.create_client("Acme Europe", "ap@acme.example").await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = client.update_client_with(&client_id, json!({
+        "payment_terms": 14,
+        "default_tax_id": vat["id"],
+        "default_currency": "eur",
+        "default_discount_percent": 10,
+    })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["default_currency"], "EUR");
+    assert_eq!(updated["default_tax_id"], vat["id"]);
+
+    let resp = client.get_client_invoice_defaults(&client_id, "issue_date=2025-03-01").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let defaults: Value = resp.json().await.unwrap();
+    assert_eq!(defaults["due_date"], "2025-03-15");
+    assert_eq!(defaults["currency"], "EUR");
+    assert_eq!(defaults["tax_label"], "VAT");
+    assert_eq!(defaults["tax_rate"], 0.19);
+    assert_eq!(defaults["locale"], "en");
+
+    // Left out of the invoice, the client's defaults apply
+    let resp = client.create_invoice_with(json!({
+        "client_id": client_id,
+        "issue_date": "2025-03-01",
+        "items": [{"description": "Consulting", "quantity": 2, "unit_price": 100}],
+        "tax_included": false,
+        "send_immediately": false,
+    })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice: Value = client.get_invoice(created["id"].as_str().unwrap()).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["due_date"], "2025-03-15");
+    assert_eq!(invoice["currency"], "EUR");
+    assert_eq!(invoice["tax_label"], "VAT");
+    assert_eq!(invoice["discount_amount"], 20.0);
+    assert_eq!(invoice["tax_amount"], 38.0);
+
+    // Anything sent with the invoice wins
+    let resp = client.create_invoice_with(json!({
+        "client_id": client_id,
+        "issue_date": "2025-03-01",
+        "due_date": "2025-04-30",
+        "items": [{"description": "Consulting", "quantity": 2, "unit_price": 100, "tax_rate": 0.0}],
+        "discount_amount": 0,
+        "currency": "USD",
+        "tax_included": false,
+        "send_immediately": false,
+    })).await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice: Value = client.get_invoice(created["id"].as_str().unwrap()).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["due_date"], "2025-04-30");
+    assert_eq!(invoice["currency"], "USD");
+    assert_eq!(invoice["discount_amount"], 0.0);
+    assert_eq!(invoice["tax_amount"], 0.0);
+
+    // Defaults are checked when saved
+    let resp = client.update_client_with(&client_id, json!({"default_currency": "EURO"})).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.update_client_with(&client_id, json!({"default_discount_percent": 120})).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.update_client_with(&client_id, json!({"default_tax_id": uuid::Uuid::new_v4()})).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_client_phone_normalization() {
+    let client = setup_authenticated_client().await;
+
+    // National format is stored as E.164 using the default region
+    let resp = client
+        .create_client_with_phone("Budi Santoso", "budi@example.com", "0812-3456-7890")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["phone"], "+6281234567890");
+
+    let resp = client.update_client_phone(&client_id, "+44 20 7946 0958").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["phone"], "+442079460958");
+
+    // Invalid numbers are rejected on create and update
+    let resp = client
+        .create_client_with_phone("Bad Phone", "bad@example.com", "12ab")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.update_client_phone(&client_id, "+1234567890").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_client(&client_id).await.unwrap();
+    let fetched: Value = resp.json().await.unwrap();
+    assert_eq!(fetched["phone"], "+442079460958");
+}
+
+#[tokio::test]
+async fn test_client_billing_contacts() {
+    let client = setup_authenticated_client().await;
+
+    // Duplicates and the client's own address are dropped
+    let resp = client
+        .create_client_with(serde_json::json!({
+            "name": "Acme",
+            "email": "owner@acme.com",
+            "billing_contacts": [
+                {"name": "Accounts", "email": " AP@acme.com "},
+                {"email": "ap@acme.com"},
+                {"email": "owner@acme.com"},
+            ],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+    let contacts = created["billing_contacts"].as_array().unwrap();
+    assert_eq!(contacts.len(), 1);
+    assert_eq!(contacts[0]["email"], "AP@acme.com");
+    assert_eq!(contacts[0]["name"], "Accounts");
+
+    let resp = client
+        .update_client_with(&client_id, serde_json::json!({"billing_contacts": [{"email": "not-an-email"}]}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .update_client_with(&client_id, serde_json::json!({"billing_contacts": []}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["billing_contacts"], serde_json::json!([]));
+}
+
+#[tokio::test]
+async fn test_client_archive_delete_and_restore() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Lifecycle Client", "lifecycle@example.com").await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let client_id = created["id"].as_str().unwrap().to_string();
+
+    // Archived clients drop out of the list and can't be invoiced
+    let resp = client.archive_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let archived: Value = resp.json().await.unwrap();
+    assert!(archived["archived_at"].is_string());
+
+    let clients: Value = client.list_clients().await.unwrap().json().await.unwrap();
+    assert!(clients["items"].as_array().unwrap().iter().all(|c| c["id"] != client_id.as_str()));
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.unarchive_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // An outstanding invoice blocks deletion
+    let invoice: Value = client.create_invoice(&client_id, 100.0).await.unwrap().json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    client.send_invoice(&invoice_id).await.unwrap();
+
+    let sent: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    if sent["status"] != "draft" {
+        let resp = client.delete_client(&client_id).await.unwrap();
+        assert_eq!(resp.status(), 400);
+        client.record_payment(&invoice_id, 100.0).await.unwrap();
+    }
+
+    // A draft is cancelled along with the client and reopened on restore
+    let draft: Value = client.create_invoice(&client_id, 50.0).await.unwrap().json().await.unwrap();
+    let draft_id = draft["id"].as_str().unwrap().to_string();
+
+    let resp = client.delete_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+
+    let resp = client.get_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+    let trashed: Value = client.get_invoice(&draft_id).await.unwrap().json().await.unwrap();
+    assert_eq!(trashed["status"], "cancelled");
+
+    let trash: Value = client.list_deleted_clients().await.unwrap().json().await.unwrap();
+    assert!(trash.as_array().unwrap().iter().any(|c| c["id"] == client_id.as_str()));
+
+    let resp = client.restore_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let restored: Value = resp.json().await.unwrap();
+    assert!(restored["deleted_at"].is_null());
+
+    let reopened: Value = client.get_invoice(&draft_id).await.unwrap().json().await.unwrap();
+    assert_eq!(reopened["status"], "draft");
+
+    let resp = client.get_client(&client_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_client_onboarded_from_forwarded_vcard() {
+    let client = setup_authenticated_client().await;
+
+    let address: Value = client.get_inbound_address().await.unwrap().json().await.unwrap();
+    let token = address["token"].as_str().unwrap().to_string();
+    assert!(address["address"].as_str().unwrap().starts_with(&format!("bills+{}@", token)));
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let vcard = format!(
+        "BEGIN:VCARD\r\nVERSION:3.0\r\nFN:Dewi Lestari\r\nORG:Lestari Studio\r\nEMAIL:dewi_{}@example.com\r\nEND:VCARD\r\n",
+        unique_id
+    );
+    let resp = client
+        .deliver_inbound_email(&token, serde_json::json!({
+            "from": "owner@example.com",
+            "subject": "Fwd: contact",
+            "text": "See attached",
+            "attachments": [{ "filename": "dewi.vcf", "content_type": "text/vcard", "content": vcard }]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 202);
+    let ack: Value = resp.json().await.unwrap();
+    let import_id = ack["import_id"].as_str().unwrap().to_string();
+
+    // Nothing is created until the user confirms the import
+    let imports: Value = client.list_client_imports().await.unwrap().json().await.unwrap();
+    let pending = imports.as_array().unwrap().iter().find(|i| i["id"] == import_id.as_str()).unwrap();
+    assert_eq!(pending["status"], "pending");
+    assert_eq!(pending["contact"]["company_name"], "Lestari Studio");
+
+    let resp = client.confirm_client_import(&import_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let created: Value = resp.json().await.unwrap();
+    assert_eq!(created["name"], "Dewi Lestari");
+
+    let resp = client.confirm_client_import(&import_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Unknown addresses are rejected
+    let resp = client
+        .deliver_inbound_email("unknown-token", serde_json::json!({ "from": "x@example.com", "text": "hi" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_clients_imported_from_csv() {
+    let client = setup_authenticated_client().await;
+    client.create_client("Existing", "existing@example.com").await.unwrap();
+
+    let csv = "Name,Email,Company,City,Payment Terms\n\
+               Ana,ana@example.com,Ana Co,Jakarta,14\n\
+               Budi,not-an-email,,,\n\
+               Old friend,EXISTING@example.com,,,\n\
+               Ana twin,ana@example.com,,,\n\
+               ,,Cahya Ltd,Bandung,\n";
+
+    // Dry run reports without creating anything
+    let resp = client.import_clients_csv("clients.csv", csv.as_bytes(), "dry_run=true").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["total_rows"], 5);
+    assert_eq!(report["created"], 2);
+    assert_eq!(report["skipped"], 2);
+    assert_eq!(report["failed"], 1);
+    assert_eq!(report["errors"][0]["row"], 3);
+    assert_eq!(report["errors"][0]["field"], "email");
+
+    let resp = client.list_clients().await.unwrap();
+    let clients: Value = resp.json().await.unwrap();
+    assert_eq!(clients["items"].as_array().unwrap().len(), 1);
+
+    let resp = client.import_clients_csv("clients.csv", csv.as_bytes(), "").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["dry_run"], false);
+    assert_eq!(report["created"], 2);
+
+    let resp = client.list_clients().await.unwrap();
+    let clients: Value = resp.json().await.unwrap();
+    let clients = clients["items"].as_array().unwrap();
+    assert_eq!(clients.len(), 3);
+    let ana_id = clients.iter().find(|c| c["name"] == "Ana").unwrap()["id"].as_str().unwrap().to_string();
+    let resp = client.get_client(&ana_id).await.unwrap();
+    let ana: Value = resp.json().await.unwrap();
+    assert_eq!(ana["payment_terms"], 14);
+    assert_eq!(ana["billing_address"]["city"], "Jakarta");
+
+    // Importing the file again only creates the client without an email
+    let resp = client.import_clients_csv("clients.csv", csv.as_bytes(), "").await.unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["created"], 1);
+    assert_eq!(report["duplicates"].as_array().unwrap().len(), 3);
+
+    // Excel files are turned away with a hint
+    let resp = client.import_clients_csv("clients.xlsx", b"PK\x03\x04rest", "").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_large_csv_imported_in_background() {
+    let client = setup_authenticated_client().await;
+
+    let mut csv = String::from("name,email\n");
+    for i in 0..250 {
+        csv.push_str(&format!("Client {},bulk{}@example.com\n", i, i));
+    }
+
+    let resp = client.import_clients_csv("bulk.csv", csv.as_bytes(), "").await.unwrap();
+    assert_eq!(resp.status(), 202);
+    let job: Value = resp.json().await.unwrap();
+    let job_id = job["id"].as_str().unwrap().to_string();
+    assert_eq!(job["total_rows"], 250);
+
+    let mut job = job;
+    for _ in 0..40 {
+        let resp = client.get_client_import_job(&job_id).await.unwrap();
+        assert_eq!(resp.status(), 200);
+        job = resp.json().await.unwrap();
+        if job["status"] == "completed" || job["status"] == "failed" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    assert_eq!(job["status"], "completed");
+    assert_eq!(job["processed_rows"], 250);
+    assert_eq!(job["created"], 250);
+}
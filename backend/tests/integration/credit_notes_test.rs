@@ -0,0 +1,142 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("credit_note_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Test Company")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_sent_invoice(client: &ApiTestClient, client_id: &str, amount: f64) -> String {
+    let today = chrono::Utc::now().naive_utc().date();
+    let resp = client
+        .create_invoice_with(serde_json::json!({
+            "client_id": client_id,
+            "issue_date": today,
+            "due_date": today + chrono::Duration::days(30),
+            "items": [{"description": "Widgets", "quantity": 3, "unit_price": amount / 3.0, "tax_rate": 0.0}],
+            "tax_included": false,
+            "send_immediately": true,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    invoice["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_credit_note_lifecycle() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Credit Client", "credit@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let invoice_id = create_sent_invoice(&client, &client_id, 300.0).await;
+
+    // One returned widget settles part of the open balance
+    let resp = client
+        .create_credit_note_with(serde_json::json!({
+            "invoice_id": invoice_id,
+            "reason": "Returned damaged widget",
+            "items": [{"description": "Widgets", "quantity": 1, "unit_price": 100.0, "tax_rate": 0.0}],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let credit_note: Value = resp.json().await.unwrap();
+    let credit_note_id = credit_note["id"].as_str().unwrap().to_string();
+    assert!(credit_note["credit_note_number"].as_str().unwrap().starts_with("CN-"));
+    assert_eq!(credit_note["status"], "issued");
+    assert_eq!(credit_note["total_amount"], 100.0);
+    assert_eq!(credit_note["applied_amount"], 100.0);
+    assert_eq!(credit_note["refund_amount"], 0.0);
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["balance_due"], 200.0);
+    assert_eq!(invoice["status"], "partial");
+
+    // Client balances are net of the credit
+    let stats: Value = client.get_client_stats().await.unwrap().json().await.unwrap();
+    assert_eq!(stats["total_invoiced"], 200.0);
+    assert_eq!(stats["total_paid"], 0.0);
+    assert_eq!(stats["outstanding_balance"], 200.0);
+
+    // Can't credit more than what's left on the invoice
+    let resp = client
+        .create_credit_note_with(serde_json::json!({
+            "invoice_id": invoice_id,
+            "items": [{"description": "Widgets", "quantity": 3, "unit_price": 100.0, "tax_rate": 0.0}],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.list_credit_notes(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let list: Value = resp.json().await.unwrap();
+    assert_eq!(list.as_array().unwrap().len(), 1);
+
+    let resp = client.get_credit_note_pdf(&credit_note_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+
+    // Voiding puts the balance back
+    let resp = client.void_credit_note(&credit_note_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let voided: Value = resp.json().await.unwrap();
+    assert_eq!(voided["status"], "void");
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["balance_due"], 300.0);
+    assert_eq!(invoice["status"], "sent");
+
+    let resp = client.void_credit_note(&credit_note_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_full_credit_cancels_invoice() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Full Credit Client", "fullcredit@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let invoice_id = create_sent_invoice(&client, &client_id, 150.0).await;
+
+    // Paid in full, then credited in full: the whole amount is owed back
+    client.record_payment(&invoice_id, 150.0).await.unwrap();
+    let resp = client
+        .create_credit_note_with(serde_json::json!({"invoice_id": invoice_id}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let credit_note: Value = resp.json().await.unwrap();
+    assert_eq!(credit_note["total_amount"], 150.0);
+    assert_eq!(credit_note["applied_amount"], 0.0);
+    assert_eq!(credit_note["refund_amount"], 150.0);
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["status"], "cancelled");
+
+    // Drafts are edited, not credited
+    let resp = client.create_invoice(&client_id, 50.0).await.unwrap();
+    let draft: Value = resp.json().await.unwrap();
+    let resp = client
+        .create_credit_note_with(serde_json::json!({"invoice_id": draft["id"]}))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
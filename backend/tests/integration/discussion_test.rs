@@ -180,3 +180,73 @@ async fn test_discussion_empty_messages() {
     client.delete_invoice(&invoice_id).await.unwrap();
     client.delete_client(&client_id).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_discussion_unread_tracking() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Unread Client", "unread@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 120.0).await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let guest_token = invoice["guest_payment_token"].as_str().unwrap().to_string();
+
+    let resp = client.get_discussion_unread(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 0);
+    assert!(unread["last_message_at"].is_null());
+
+    // The buyer's messages are unread for the seller, not for the buyer
+    client.add_guest_discussion_message(&guest_token, "Is VAT included?").await.unwrap();
+    client.add_guest_discussion_message(&guest_token, "And can I pay in two parts?").await.unwrap();
+    let resp = client.get_discussion_unread(&invoice_id).await.unwrap();
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 2);
+    let resp = client.get_guest_discussion_unread(&guest_token).await.unwrap();
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 0);
+
+    let resp = client.list_unread_discussions().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let conversations: Value = resp.json().await.unwrap();
+    let conversations = conversations.as_array().unwrap();
+    assert_eq!(conversations.len(), 1);
+    assert_eq!(conversations[0]["invoice_id"], invoice_id);
+    assert_eq!(conversations[0]["client_name"], "Unread Client");
+    assert_eq!(conversations[0]["unread_count"], 2);
+
+    // Replying reads the conversation for the seller and leaves the reply unread for the buyer
+    client.add_discussion_message(&invoice_id, "Yes to both").await.unwrap();
+    let resp = client.get_discussion_unread(&invoice_id).await.unwrap();
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 0);
+    let resp = client.get_guest_discussion_unread(&guest_token).await.unwrap();
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 1);
+
+    let resp = client.mark_guest_discussion_read(&guest_token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 0);
+    assert!(unread["last_read_at"].is_string());
+
+    client.add_guest_discussion_message(&guest_token, "Thanks!").await.unwrap();
+    let resp = client.mark_discussion_read(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let unread: Value = resp.json().await.unwrap();
+    assert_eq!(unread["unread_count"], 0);
+    let resp = client.list_unread_discussions().await.unwrap();
+    let conversations: Value = resp.json().await.unwrap();
+    assert!(conversations.as_array().unwrap().is_empty());
+
+    let resp = client.get_guest_discussion_unread("invalid_token").await.unwrap();
+    assert!(resp.status().is_client_error());
+    let resp = client.get_discussion_unread("00000000-0000-0000-0000-000000000000").await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
@@ -0,0 +1,110 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("e_invoice_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Berlin GmbH")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+
+    let resp = authed_client.set_business_country("DE").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    authed_client
+}
+
+async fn create_invoice(client: &ApiTestClient, send: bool) -> String {
+    let resp = client
+        .create_client_with(json!({
+            "name": "Munich AG",
+            "email": "accounts@example.com",
+            "billing_address": { "street": "Marienplatz 1", "city": "Munich", "zip_code": "80331", "country": "DE" },
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let customer: Value = resp.json().await.unwrap();
+
+    let resp = client
+        .create_invoice_with_items(customer["id"].as_str().unwrap(), json!([
+            { "description": "Consulting", "quantity": 2, "unit_price": 500, "tax_rate": 0.19 },
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+    if send {
+        client.send_invoice(&invoice_id).await.unwrap();
+    }
+    invoice_id
+}
+
+#[tokio::test]
+async fn test_ubl_export() {
+    let client = setup_authenticated_client().await;
+    let resp = client.set_business_vat_number("de 136 695 976").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["vat_number"], "DE136695976");
+
+    let invoice_id = create_invoice(&client, true).await;
+    let resp = client.export_e_invoice(&invoice_id, "ubl").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/xml");
+    let xml = resp.text().await.unwrap();
+    assert!(xml.contains("<cbc:CompanyID>DE136695976</cbc:CompanyID>"));
+    assert!(xml.contains(">190.00</cbc:TaxAmount>"));
+    assert!(xml.contains("<cbc:IdentificationCode>DE</cbc:IdentificationCode>"));
+}
+
+#[tokio::test]
+async fn test_factur_x_export() {
+    let client = setup_authenticated_client().await;
+    client.set_business_vat_number("DE136695976").await.unwrap();
+
+    let invoice_id = create_invoice(&client, true).await;
+    let resp = client.export_e_invoice(&invoice_id, "facturx").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+    assert!(resp.headers()["content-disposition"].to_str().unwrap().ends_with(".pdf\""));
+    let pdf = resp.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    assert!(pdf.windows(b"factur-x.xml".len()).any(|window| window == b"factur-x.xml"));
+}
+
+#[tokio::test]
+async fn test_missing_mandatory_fields_are_listed() {
+    let client = setup_authenticated_client().await;
+
+    // No VAT number of our own yet
+    let invoice_id = create_invoice(&client, true).await;
+    let resp = client.export_e_invoice(&invoice_id, "ubl").await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let body: Value = resp.json().await.unwrap();
+    let missing = body["error"]["details"]["missing"].as_array().unwrap();
+    assert_eq!(missing.len(), 1);
+    assert!(missing[0].as_str().unwrap().contains("VAT number"));
+}
+
+#[tokio::test]
+async fn test_drafts_are_not_exported() {
+    let client = setup_authenticated_client().await;
+    client.set_business_vat_number("DE136695976").await.unwrap();
+
+    let invoice_id = create_invoice(&client, false).await;
+    let resp = client.export_e_invoice(&invoice_id, "ubl").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.export_e_invoice(&invoice_id, "pdf").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
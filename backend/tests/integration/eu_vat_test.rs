@@ -0,0 +1,141 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("eu_vat_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Berlin GmbH")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+
+    // Sells from Germany
+    let resp = authed_client.set_business_country("DE").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    authed_client
+}
+
+async fn create_client(client: &ApiTestClient, name: &str, vat_number: Option<&str>) -> Value {
+    let resp = client
+        .create_client_with(json!({ "name": name, "email": "accounts@example.com", "vat_number": vat_number }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    resp.json().await.unwrap()
+}
+
+async fn create_sent_invoice(client: &ApiTestClient, client_id: &str) -> Value {
+    let resp = client
+        .create_invoice_with_items(client_id, json!([
+            { "description": "Consulting", "quantity": 2, "unit_price": 500, "tax_rate": 0.19 },
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap();
+    client.send_invoice(invoice_id).await.unwrap();
+    client.get_invoice(invoice_id).await.unwrap().json().await.unwrap()
+}
+
+fn as_f64(value: &Value) -> f64 {
+    value.as_f64().unwrap_or_else(|| value.as_str().unwrap().parse().unwrap())
+}
+
+#[tokio::test]
+async fn test_vat_number_is_normalized_and_checked() {
+    let client = setup_authenticated_client().await;
+
+    let created = create_client(&client, "Paris SARL", Some("fr 40 303 265 045")).await;
+    assert_eq!(created["vat_number"], "FR40303265045");
+    assert_eq!(created["vat_status"], "valid");
+    assert!(created["vat_checked_at"].is_string());
+
+    let resp = client.check_client_vat(created["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .create_client_with(json!({ "name": "London Ltd", "email": "ap@example.com", "vat_number": "GB123456789" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // A client without a number has nothing to check
+    let plain = create_client(&client, "No VAT Co", None).await;
+    let resp = client.check_client_vat(plain["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_cross_border_b2b_invoice_is_reverse_charged() {
+    let client = setup_authenticated_client().await;
+    let paris = create_client(&client, "Paris SARL", Some("FR40303265045")).await;
+
+    let invoice = create_sent_invoice(&client, paris["id"].as_str().unwrap()).await;
+    assert_eq!(invoice["reverse_charge"], true);
+    assert_eq!(invoice["vat_country"], "FR");
+    assert_eq!(invoice["client_vat_number"], "FR40303265045");
+    assert_eq!(as_f64(&invoice["subtotal"]), 1000.0);
+    assert_eq!(as_f64(&invoice["tax_amount"]), 0.0);
+    assert_eq!(as_f64(&invoice["total_amount"]), 1000.0);
+
+    let resp = client.get_invoice_pdf(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // Moving it to a domestic client would change how it's taxed
+    let munich = create_client(&client, "Munich AG", Some("DE129273398")).await;
+    let resp = client
+        .update_invoice_with(invoice["id"].as_str().unwrap(), json!({ "client_id": munich["id"] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_domestic_client_keeps_vat() {
+    let client = setup_authenticated_client().await;
+    let munich = create_client(&client, "Munich AG", Some("DE129273398")).await;
+
+    let invoice = create_sent_invoice(&client, munich["id"].as_str().unwrap()).await;
+    assert_eq!(invoice["reverse_charge"], false);
+    assert_eq!(invoice["vat_country"], "DE");
+    assert_eq!(as_f64(&invoice["tax_amount"]), 190.0);
+}
+
+#[tokio::test]
+async fn test_vat_report_groups_by_country_and_rate() {
+    let client = setup_authenticated_client().await;
+    let paris = create_client(&client, "Paris SARL", Some("FR40303265045")).await;
+    let munich = create_client(&client, "Munich AG", Some("DE129273398")).await;
+    create_sent_invoice(&client, paris["id"].as_str().unwrap()).await;
+    create_sent_invoice(&client, munich["id"].as_str().unwrap()).await;
+    create_sent_invoice(&client, munich["id"].as_str().unwrap()).await;
+
+    let today = chrono::Utc::now().naive_utc().date().to_string();
+    let resp = client.get_vat_report(&today, &today).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    let rows = report["by_country"].as_array().unwrap();
+    assert_eq!(rows.len(), 2);
+
+    let germany = rows.iter().find(|row| row["country"] == "DE").unwrap();
+    assert_eq!(germany["reverse_charge"], false);
+    assert_eq!(as_f64(&germany["rate"]), 0.19);
+    assert_eq!(germany["invoice_count"], 2);
+    assert_eq!(as_f64(&germany["taxable_amount"]), 2000.0);
+    assert_eq!(as_f64(&germany["vat_amount"]), 380.0);
+
+    let france = rows.iter().find(|row| row["country"] == "FR").unwrap();
+    assert_eq!(france["reverse_charge"], true);
+    assert_eq!(as_f64(&france["rate"]), 0.0);
+    assert_eq!(as_f64(&france["taxable_amount"]), 1000.0);
+    assert_eq!(as_f64(&france["vat_amount"]), 0.0);
+}
@@ -0,0 +1,106 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+use std::time::Duration;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("events_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Events Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+/// Reads SSE events (id, name, data) until `count` have arrived or `wait` passes
+async fn read_events(resp: &mut reqwest::Response, count: usize, wait: Duration) -> Vec<(String, String, Value)> {
+    let mut events = Vec::new();
+    let mut buffer = String::new();
+    let deadline = tokio::time::Instant::now() + wait;
+    while events.len() < count {
+        let chunk = match tokio::time::timeout_at(deadline, resp.chunk()).await {
+            Ok(Ok(Some(chunk))) => chunk,
+            _ => break,
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(end) = buffer.find("\n\n") {
+            let block: String = buffer.drain(..end + 2).collect();
+            let (mut id, mut name, mut data) = (String::new(), String::new(), String::new());
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("id:") {
+                    id = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("event:") {
+                    name = value.trim().to_string();
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data.push_str(value.trim());
+                }
+            }
+            if !data.is_empty() {
+                events.push((id, name, serde_json::from_str(&data).unwrap()));
+            }
+        }
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_event_stream_delivers_and_replays_updates() {
+    let client = setup_authenticated_client().await;
+    let other = setup_authenticated_client().await;
+
+    let resp = client.open_event_stream(None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers()["content-type"].to_str().unwrap().starts_with("text/event-stream"));
+    let mut stream = resp;
+    let mut other_stream = other.open_event_stream(None).await.unwrap();
+
+    let resp = client.create_client("Events Client", "events.client@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let resp = client.create_invoice(client_data["id"].as_str().unwrap(), 100.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    client.send_invoice(&invoice_id).await.unwrap();
+    let resp = client.add_discussion_message(&invoice_id, "Any questions?").await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let events = read_events(&mut stream, 2, Duration::from_secs(15)).await;
+    assert_eq!(events.len(), 2);
+    let (first_id, name, data) = &events[0];
+    assert_eq!(name, "invoice.status_changed");
+    assert_eq!(data["type"], "invoice.status_changed");
+    assert_eq!(data["invoice_id"], invoice_id);
+    assert_eq!(data["data"]["status"], "sent");
+    assert_eq!(data["data"]["previous_status"], "draft");
+    assert!(data.get("user_id").is_none());
+    let (second_id, name, data) = &events[1];
+    assert_eq!(name, "discussion.message");
+    assert_eq!(data["data"]["message"], "Any questions?");
+    assert_eq!(data["data"]["sender_type"], "seller");
+
+    // Other users' streams stay quiet
+    let events = read_events(&mut other_stream, 1, Duration::from_secs(2)).await;
+    assert!(events.is_empty());
+
+    // Reconnecting with the last id received replays what came after it
+    let mut stream = client.open_event_stream(Some(first_id)).await.unwrap();
+    let events = read_events(&mut stream, 1, Duration::from_secs(5)).await;
+    assert_eq!(events.len(), 1);
+    assert_eq!(&events[0].0, second_id);
+    assert_eq!(events[0].1, "discussion.message");
+
+    let resp = reqwest::Client::new()
+        .get(format!("{}/api/v1/events/stream", client.get_base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+}
@@ -42,8 +42,8 @@ async fn test_expense_crud() {
     let resp = client.list_expenses().await.unwrap();
     assert_eq!(resp.status(), 200);
     let list: Value = resp.json().await.unwrap();
-    assert!(list.is_array());
-    assert!(list.as_array().unwrap().len() > 0);
+    assert!(list["items"].is_array());
+    assert!(list["items"].as_array().unwrap().len() > 0);
 
     // 4. Update expense
     let resp = client.update_expense(&expense_id, "Updated description").await.unwrap();
@@ -78,10 +78,11 @@ async fn test_expense_with_filters() {
     // List all expenses
     let resp = client.list_expenses().await.unwrap();
     let all_expenses: Value = resp.json().await.unwrap();
-    assert_eq!(all_expenses.as_array().unwrap().len(), 3);
+    assert_eq!(all_expenses["items"].as_array().unwrap().len(), 3);
+    assert_eq!(all_expenses["total"], 3);
 
     // Cleanup - delete all
-    for expense in all_expenses.as_array().unwrap() {
+    for expense in all_expenses["items"].as_array().unwrap() {
         let id = expense["id"].as_str().unwrap();
         client.delete_expense(id).await.unwrap();
     }
@@ -120,6 +121,74 @@ async fn test_expense_tax_deductible() {
     client.delete_expense(expense2["id"].as_str().unwrap()).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_expense_input_tax() {
+    let client = setup_authenticated_client().await;
+
+    // Tax is taken out of the gross amount when only a rate is given
+    let resp = client
+        .create_expense_with(serde_json::json!({
+            "amount": 121.0,
+            "category": "software",
+            "vendor": "EU SaaS",
+            "date_incurred": "2025-03-10",
+            "tax_rate": 0.21,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let expense: Value = resp.json().await.unwrap();
+    let expense_id = expense["id"].as_str().unwrap().to_string();
+    assert_eq!(expense["tax_amount"], 21.0);
+
+    // Input tax on non-deductible expenses isn't reclaimable
+    let resp = client
+        .create_expense_with(serde_json::json!({
+            "amount": 110.0,
+            "category": "other",
+            "vendor": "Restaurant",
+            "date_incurred": "2025-03-12",
+            "tax_deductible": false,
+            "tax_amount": 10.0,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client
+        .create_expense_with(serde_json::json!({
+            "amount": 100.0,
+            "category": "other",
+            "date_incurred": "2025-03-12",
+            "tax_rate": 21.0,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .create_expense_with(serde_json::json!({
+            "amount": 100.0,
+            "category": "other",
+            "date_incurred": "2025-03-12",
+            "tax_amount": 150.0,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // The tax follows the amount while a rate is on file
+    let resp = client.update_expense_with(&expense_id, serde_json::json!({ "amount": 242.0 })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["tax_amount"], 42.0);
+
+    let resp = client.get_tax_report("2025-03-01", "2025-03-31").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["total_tax_deductible"], 42.0);
+    assert_eq!(report["net_tax_payable"], -42.0);
+}
+
 #[tokio::test]
 async fn test_expense_validation() {
     let client = setup_authenticated_client().await;
@@ -138,3 +207,57 @@ async fn test_expense_validation() {
     // 422 is correct for JSON deserialization/validation errors
     assert!(resp.status() == 400 || resp.status() == 422, "Missing required fields should return 400 or 422, got {}", resp.status());
 }
+
+#[tokio::test]
+async fn test_receipt_scan_drafts_an_expense() {
+    let client = setup_authenticated_client().await;
+    // PNG signature and an empty IHDR chunk: enough to be stored as an image
+    let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x00\x00\x01\x00\x00\x00\x01\x08\x02\x00\x00\x00";
+
+    let resp = client.get_receipt_scan(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client.scan_receipt("receipt.png", "image/png", png).await.unwrap();
+    if resp.status() == 400 {
+        // No OCR_BACKEND on this server
+        let body: Value = resp.json().await.unwrap();
+        assert!(body["error"]["message"].as_str().unwrap().contains("not configured"));
+        return;
+    }
+    assert_eq!(resp.status(), 201);
+    let scan: Value = resp.json().await.unwrap();
+    let scan_id = scan["id"].as_str().unwrap().to_string();
+    let receipt_url = scan["receipt_image_url"].as_str().unwrap().to_string();
+    assert_eq!(scan["draft"]["receipt_image_url"], receipt_url.as_str());
+    assert_eq!(scan["draft"]["category"], "other");
+    assert!(scan["confirmed_at"].is_null());
+
+    // Only images are read
+    let resp = client.scan_receipt("receipt.txt", "text/plain", b"Total 12.50").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_receipt_scan(&scan_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let payload = serde_json::json!({
+        "amount": 44.40,
+        "category": "office_supplies",
+        "vendor": "Corner Shop",
+        "date_incurred": "2026-03-05",
+        "tax_rate": 0.11
+    });
+    let resp = client.confirm_receipt_scan(&scan_id, payload.clone()).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let expense: Value = resp.json().await.unwrap();
+    assert_eq!(expense["receipt_image_url"], receipt_url.as_str());
+    assert_eq!(expense["tax_amount"], 4.4);
+
+    // One receipt, one expense
+    let resp = client.confirm_receipt_scan(&scan_id, payload).await.unwrap();
+    assert_eq!(resp.status(), 409);
+
+    let resp = client.get_receipt_scan(&scan_id).await.unwrap();
+    let confirmed: Value = resp.json().await.unwrap();
+    assert_eq!(confirmed["expense_id"], expense["id"]);
+    assert!(confirmed["confirmed_at"].is_string());
+}
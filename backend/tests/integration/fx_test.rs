@@ -0,0 +1,193 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("fx_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("FX Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_fx_rate_overrides() {
+    let client = setup_authenticated_client().await;
+    let today = chrono::Utc::now().naive_utc().date();
+
+    let resp = client.set_fx_rate(json!({ "currency": "eur", "rate": 1.1, "rate_date": today })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let rate: Value = resp.json().await.unwrap();
+    let rate_id = rate["id"].as_str().unwrap().to_string();
+    assert_eq!(rate["currency"], "EUR");
+    assert_eq!(rate["base_currency"], "USD");
+    assert_eq!(rate["source"], "manual");
+
+    // Setting the same day again replaces the override
+    let resp = client.set_fx_rate(json!({ "currency": "EUR", "rate": 1.12 })).await.unwrap();
+    let replaced: Value = resp.json().await.unwrap();
+    assert_eq!(replaced["id"], rate_id.as_str());
+
+    let resp = client.get_with_query("/fx/rates", &format!("currency=EUR&date={}", today)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let effective: Value = resp.json().await.unwrap();
+    assert_eq!(effective["rate"], 1.12);
+    assert_eq!(effective["source"], "manual");
+
+    let resp = client.get_with_query("/fx/rates", "currency=USD").await.unwrap();
+    let identity: Value = resp.json().await.unwrap();
+    assert_eq!(identity["rate"], 1.0);
+    assert_eq!(identity["source"], "identity");
+
+    let resp = client.set_fx_rate(json!({ "currency": "USD", "rate": 1.0 })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.set_fx_rate(json!({ "currency": "EUR", "rate": 0.0 })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.set_fx_rate(json!({ "currency": "EURO", "rate": 1.1 })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let from = today - chrono::Duration::days(7);
+    let resp = client
+        .get_with_query("/fx/rates/history", &format!("currency=EUR&from={}&to={}", from, today))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let history: Value = resp.json().await.unwrap();
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["rate"], 1.12);
+
+    let resp = client.delete_fx_rate(&rate_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.delete_fx_rate(&rate_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_foreign_invoice_uses_rate_for_issue_date() {
+    let client = setup_authenticated_client().await;
+    let today = chrono::Utc::now().naive_utc().date();
+
+    let resp = client.create_client("FX Client", "fx@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.set_fx_rate(json!({ "currency": "GBP", "rate": 1.27, "rate_date": today })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .create_invoice_with(json!({
+            "client_id": client_id,
+            "issue_date": today,
+            "due_date": today + chrono::Duration::days(30),
+            "currency": "GBP",
+            "items": [{ "description": "Consulting", "quantity": 1, "unit_price": 100.0, "tax_rate": 0.0 }],
+            "tax_included": false,
+            "send_immediately": false,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+
+    let resp = client.get_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["currency"], "GBP");
+    assert_eq!(detail["exchange_rate"], 1.27);
+}
+
+#[tokio::test]
+async fn test_paying_foreign_invoice_books_realized_gain() {
+    let client = setup_authenticated_client().await;
+    let today = chrono::Utc::now().naive_utc().date();
+
+    let resp = client.create_client("FX Payer", "fx-payer@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    client.set_fx_rate(json!({ "currency": "GBP", "rate": 1.27, "rate_date": today })).await.unwrap();
+    let mut invoice_ids = Vec::new();
+    for currency in ["GBP", "USD"] {
+        let resp = client
+            .create_invoice_with(json!({
+                "client_id": client_id,
+                "issue_date": today,
+                "due_date": today + chrono::Duration::days(30),
+                "currency": currency,
+                "items": [{ "description": "Consulting", "quantity": 1, "unit_price": 100.0, "tax_rate": 0.0 }],
+                "tax_included": false,
+                "send_immediately": false,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+        let invoice: Value = resp.json().await.unwrap();
+        invoice_ids.push(invoice["id"].as_str().unwrap().to_string());
+    }
+
+    // The pound strengthened before the client paid
+    client.set_fx_rate(json!({ "currency": "GBP", "rate": 1.30, "rate_date": today })).await.unwrap();
+    for invoice_id in &invoice_ids {
+        client.send_invoice(invoice_id).await.unwrap();
+        let resp = client.record_payment(invoice_id, 100.0).await.unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+
+    // 100 GBP * (1.30 - 1.27); the invoice in the base currency has no FX result
+    let resp = client.get_overview_stats().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let overview: Value = resp.json().await.unwrap();
+    assert_eq!(overview["fx_gain_loss"], 3.0);
+}
+
+#[tokio::test]
+async fn test_guest_payment_is_in_the_invoice_currency() {
+    let client = setup_authenticated_client().await;
+    let today = chrono::Utc::now().naive_utc().date();
+
+    let resp = client.create_client("FX Guest", "fx-guest@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+
+    client.set_fx_rate(json!({ "currency": "EUR", "rate": 1.1, "rate_date": today })).await.unwrap();
+    let resp = client
+        .create_invoice_with(json!({
+            "client_id": client_data["id"],
+            "issue_date": today,
+            "due_date": today + chrono::Duration::days(30),
+            "currency": "EUR",
+            "items": [{ "description": "Consulting", "quantity": 1, "unit_price": 250.0, "tax_rate": 0.0 }],
+            "tax_included": false,
+            "send_immediately": false,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap();
+    let detail: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    let token = detail["guest_payment_token"].as_str().unwrap();
+
+    let resp = client
+        .process_guest_payment_with(token, json!({
+            "amount": 250.0,
+            "payment_method": "bank_transfer",
+            "customer_name": "Marie Dupont",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let pending: Value = client.list_bank_transfers(None).await.unwrap().json().await.unwrap();
+    let payment = pending.as_array().unwrap().iter().find(|p| p["invoice_id"] == invoice_id).unwrap();
+    assert_eq!(payment["currency"], "EUR");
+    assert_eq!(payment["amount"], 250.0);
+}
@@ -0,0 +1,245 @@
+//! Contract tests for the guest API (v1).
+//!
+//! The public payment page can't be force-upgraded, so these tests pin the JSON
+//! shapes and status codes it depends on. Extra fields are allowed; a failure here
+//! means a breaking change that belongs under `/guest/v2` instead.
+
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    String,
+    Number,
+    Bool,
+    Array,
+    Object,
+    /// String or null
+    OptString,
+    /// Number or null
+    OptNumber,
+}
+
+impl Kind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Kind::String => value.is_string(),
+            Kind::Number => value.is_number(),
+            Kind::Bool => value.is_boolean(),
+            Kind::Array => value.is_array(),
+            Kind::Object => value.is_object(),
+            Kind::OptString => value.is_string() || value.is_null(),
+            Kind::OptNumber => value.is_number() || value.is_null(),
+        }
+    }
+}
+
+/// Fields the guest invoice page reads, as JSON pointers
+const GUEST_INVOICE_V1: &[(&str, Kind)] = &[
+    ("/invoice", Kind::Object),
+    ("/invoice/id", Kind::String),
+    ("/invoice/invoice_number", Kind::String),
+    ("/invoice/status", Kind::String),
+    ("/invoice/client_name", Kind::String),
+    ("/invoice/client_email", Kind::OptString),
+    ("/invoice/issue_date", Kind::String),
+    ("/invoice/due_date", Kind::String),
+    ("/invoice/subtotal", Kind::Number),
+    ("/invoice/tax_amount", Kind::Number),
+    ("/invoice/discount_amount", Kind::Number),
+    ("/invoice/total_amount", Kind::Number),
+    ("/invoice/amount_paid", Kind::Number),
+    ("/invoice/balance_due", Kind::Number),
+    ("/invoice/items", Kind::Array),
+    ("/invoice/items/0/description", Kind::String),
+    ("/invoice/items/0/quantity", Kind::Number),
+    ("/invoice/items/0/unit_price", Kind::Number),
+    ("/invoice/items/0/total", Kind::Number),
+    ("/invoice/notes", Kind::OptString),
+    ("/invoice/terms", Kind::OptString),
+    ("/invoice/tax_included", Kind::Bool),
+    ("/invoice/tax_label", Kind::OptString),
+    ("/invoice/allow_partial_payment", Kind::Bool),
+    ("/invoice/min_payment_amount", Kind::OptNumber),
+    ("/seller", Kind::Object),
+    ("/seller/company_name", Kind::OptString),
+    ("/seller/email", Kind::OptString),
+    ("/seller/phone", Kind::OptString),
+    ("/payment_methods", Kind::Array),
+    ("/guest_payment_link", Kind::String),
+];
+
+const DISCUSSION_MESSAGE_V1: &[(&str, Kind)] = &[
+    ("/id", Kind::String),
+    ("/invoice_id", Kind::String),
+    ("/sender_type", Kind::String),
+    ("/message", Kind::String),
+    ("/created_at", Kind::String),
+];
+
+const PAYMENT_HISTORY_V1: &[(&str, Kind)] = &[
+    ("/payments", Kind::Array),
+    ("/total_count", Kind::Number),
+];
+
+const ERROR_V1: &[(&str, Kind)] = &[
+    ("/error", Kind::Object),
+    ("/error/code", Kind::String),
+    ("/error/message", Kind::String),
+    ("/error/timestamp", Kind::String),
+];
+
+fn assert_shape(body: &Value, contract: &[(&str, Kind)]) {
+    for (pointer, kind) in contract {
+        match body.pointer(pointer) {
+            Some(value) => assert!(
+                kind.matches(value),
+                "{} should be {:?}, got {}",
+                pointer,
+                kind,
+                value
+            ),
+            None => panic!("{} missing from guest response: {}", pointer, body),
+        }
+    }
+}
+
+async fn assert_error(resp: reqwest::Response, status: u16, code: &str) {
+    assert_eq!(resp.status(), status);
+    let body: Value = resp.json().await.unwrap();
+    assert_shape(&body, ERROR_V1);
+    assert_eq!(body["error"]["code"], code);
+}
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("guest_contract_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Contract Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+/// Create a client and invoice, returning (client_id, invoice_id, guest_token)
+async fn create_guest_invoice(client: &ApiTestClient) -> (String, String, String) {
+    let resp = client.create_client("Guest Contract Client", "guest-contract@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let guest_token = invoice["guest_payment_token"].as_str().unwrap().to_string();
+
+    (client_id, invoice_id, guest_token)
+}
+
+#[tokio::test]
+async fn test_guest_invoice_contract() {
+    let client = setup_authenticated_client().await;
+    let (client_id, invoice_id, guest_token) = create_guest_invoice(&client).await;
+
+    let resp = client.get_guest_invoice(&guest_token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_shape(&body, GUEST_INVOICE_V1);
+    assert_eq!(body["invoice"]["id"], invoice_id.as_str());
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_guest_v1_prefix_matches_unversioned() {
+    let client = setup_authenticated_client().await;
+    let (client_id, invoice_id, guest_token) = create_guest_invoice(&client).await;
+
+    let resp = client.get_guest_invoice(&guest_token).await.unwrap();
+    let unversioned: Value = resp.json().await.unwrap();
+
+    let resp = client
+        .get_guest_versioned("v1", &format!("invoice/{}", guest_token))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let versioned: Value = resp.json().await.unwrap();
+    assert_shape(&versioned, GUEST_INVOICE_V1);
+    assert_eq!(versioned, unversioned);
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_guest_discussion_contract() {
+    let client = setup_authenticated_client().await;
+    let (client_id, invoice_id, guest_token) = create_guest_invoice(&client).await;
+
+    let resp = client.add_guest_discussion_message(&guest_token, "Is this the final amount?").await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let message: Value = resp.json().await.unwrap();
+    assert_shape(&message, DISCUSSION_MESSAGE_V1);
+    assert_eq!(message["sender_type"], "buyer");
+
+    let resp = client.get_guest_discussion_messages(&guest_token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    let messages = body["messages"].as_array().expect("messages should be an array");
+    assert_eq!(messages.len(), 1);
+    assert_shape(&messages[0], DISCUSSION_MESSAGE_V1);
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_guest_view_and_history_contract() {
+    let client = setup_authenticated_client().await;
+    let (client_id, invoice_id, guest_token) = create_guest_invoice(&client).await;
+
+    let resp = client.mark_guest_invoice_viewed(&guest_token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.get_guest_payment_history("guest-contract@test.com").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_shape(&body, PAYMENT_HISTORY_V1);
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_guest_error_contract() {
+    let client = ApiTestClient::new(get_api_base_url());
+
+    // Token without an invoice id segment
+    let resp = client.get_guest_invoice("invalidtoken").await.unwrap();
+    assert_error(resp, 400, "BAD_REQUEST").await;
+
+    // Invoice id segment that isn't a UUID
+    let resp = client.get_guest_invoice("guest_not-a-uuid_hash").await.unwrap();
+    assert_error(resp, 400, "BAD_REQUEST").await;
+
+    // Well-formed token for an invoice that doesn't exist
+    let resp = client
+        .get_guest_invoice("guest_00000000-0000-0000-0000-000000000000_hash")
+        .await
+        .unwrap();
+    assert_error(resp, 404, "NOT_FOUND").await;
+
+    let resp = client.mark_guest_invoice_viewed("invalidtoken").await.unwrap();
+    assert_error(resp, 400, "BAD_REQUEST").await;
+}
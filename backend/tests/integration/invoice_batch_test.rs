@@ -0,0 +1,120 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("batch_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Batch Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_invoice_id(client: &ApiTestClient, client_id: &str, amount: f64) -> String {
+    let resp = client.create_invoice(client_id, amount).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+async fn invoice_status(client: &ApiTestClient, invoice_id: &str) -> Value {
+    let resp = client.get_invoice(invoice_id).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["status"].clone()
+}
+
+/// File names listed in a zip's central directory
+fn zip_entry_names(data: &[u8]) -> Vec<String> {
+    let u16_at = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let u32_at = |at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]) as usize;
+
+    let end = data.len() - 22;
+    let mut at = u32_at(end + 16);
+    (0..u16_at(end + 10))
+        .map(|_| {
+            let name_len = u16_at(at + 28);
+            let name = String::from_utf8(data[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            at += 46 + name_len + u16_at(at + 30) + u16_at(at + 32);
+            name
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_invoice_batch_actions() {
+    let client = setup_authenticated_client().await;
+    let resp = client.create_client("Batch Client", "batch.client@test.com").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let client_id = data["id"].as_str().unwrap().to_string();
+
+    let first = create_invoice_id(&client, &client_id, 100.0).await;
+    let second = create_invoice_id(&client, &client_id, 250.0).await;
+    let missing = uuid::Uuid::new_v4().to_string();
+
+    let resp = client
+        .batch_invoices(json!({ "action": "send", "invoice_ids": [first, second, first, missing] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["succeeded"], 2);
+    assert_eq!(report["failed"], 1);
+    let results = report["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[2]["id"], missing);
+    assert_eq!(results[2]["success"], false);
+    assert!(results[2]["error"].as_str().unwrap().contains("not found"));
+    assert_eq!(invoice_status(&client, &first).await, "sent");
+
+    // A payment of the balance due is recorded; an invoice already paid fails on its own
+    let resp = client.record_payment(&second, 250.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = client
+        .batch_invoices(json!({ "action": "mark_paid", "invoice_ids": [first, second], "payment_method": "cash" }))
+        .await
+        .unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["succeeded"], 1);
+    assert_eq!(report["results"][0]["success"], true);
+    assert_eq!(report["results"][1]["success"], false);
+    let resp = client.get_invoice(&first).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    assert_eq!(invoice["status"], "paid");
+    assert_eq!(invoice["balance_due"], 0.0);
+
+    let resp = client.batch_invoices(json!({ "action": "download_pdfs", "invoice_ids": [first, second, missing] })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/zip");
+    assert_eq!(resp.headers()["x-batch-failed"], "1");
+    let archive = resp.bytes().await.unwrap();
+    let names = zip_entry_names(&archive);
+    assert_eq!(names.len(), 3);
+    assert!(names.iter().filter(|name| name.ends_with(".pdf")).count() == 2);
+    assert!(names.contains(&"errors.txt".to_string()));
+
+    let resp = client.batch_invoices(json!({ "action": "download_pdfs", "invoice_ids": [missing] })).await.unwrap();
+    assert_eq!(resp.status(), 422);
+
+    // Only drafts can be deleted
+    let draft = create_invoice_id(&client, &client_id, 80.0).await;
+    let resp = client.batch_invoices(json!({ "action": "delete", "invoice_ids": [draft, first] })).await.unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["succeeded"], 1);
+    assert_eq!(report["results"][1]["success"], false);
+    let resp = client.get_invoice(&draft).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client.batch_invoices(json!({ "action": "send", "invoice_ids": [] })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.batch_invoices(json!({ "action": "archive", "invoice_ids": [first] })).await.unwrap();
+    assert!(resp.status().is_client_error());
+}
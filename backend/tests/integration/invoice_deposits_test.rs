@@ -0,0 +1,129 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("deposit_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Deposit Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient) -> String {
+    let resp = client.create_client("Upfront Client", "upfront.client@test.com").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+async fn get_invoice(client: &ApiTestClient, invoice_id: &str) -> Value {
+    client.get_invoice(invoice_id).await.unwrap().json().await.unwrap()
+}
+
+fn as_f64(value: &Value) -> f64 {
+    value.as_f64().unwrap_or_else(|| value.as_str().unwrap().parse().unwrap())
+}
+
+#[tokio::test]
+async fn test_deposit_collected_before_balance() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client).await;
+
+    let resp = client
+        .create_invoice_with_deposit(&client_id, 1000.0, json!({ "percent": 30 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap();
+
+    let invoice = get_invoice(&client, invoice_id).await;
+    assert_eq!(as_f64(&invoice["deposit"]["amount"]), 300.0);
+    assert_eq!(as_f64(&invoice["deposit"]["balance_due"]), 300.0);
+    assert_eq!(invoice["deposit"]["status"], "due");
+    assert_eq!(invoice["deposit"]["due_date"], invoice["issue_date"]);
+
+    client.send_invoice(invoice_id).await.unwrap();
+
+    // Nothing short of the deposit is accepted while it's due
+    let resp = client.record_payment(invoice_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let token = get_invoice(&client, invoice_id).await["guest_payment_token"].as_str().unwrap().to_string();
+    let guest: Value = client.get_guest_invoice(&token).await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&guest["amount_due_now"]), 300.0);
+
+    let resp = client.record_payment(invoice_id, 300.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice = get_invoice(&client, invoice_id).await;
+    assert_eq!(invoice["status"], "partial");
+    assert_eq!(invoice["deposit"]["status"], "paid");
+    assert!(!invoice["deposit"]["paid_at"].is_null());
+
+    // Once the deposit is in, the rest can be paid in parts
+    let guest: Value = client.get_guest_invoice(&token).await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&guest["amount_due_now"]), 700.0);
+    let resp = client.record_payment(invoice_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+}
+
+#[tokio::test]
+async fn test_deposit_terms_on_create_and_edit() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client).await;
+
+    let resp = client
+        .create_invoice_with_deposit(&client_id, 500.0, json!({ "amount": 100, "percent": 10 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .create_invoice_with_deposit(&client_id, 500.0, json!({ "percent": 150 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .create_invoice_with_deposit(&client_id, 500.0, json!({ "percent": 20 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap();
+
+    // A percentage follows the total when the items change
+    let resp = client
+        .update_invoice_with(invoice_id, json!({
+            "items": [{ "description": "Bigger job", "quantity": 1, "unit_price": 800, "tax_rate": 0 }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let invoice = get_invoice(&client, invoice_id).await;
+    assert_eq!(as_f64(&invoice["deposit"]["amount"]), 160.0);
+
+    // A fixed amount can't ask for more than the invoice
+    let resp = client
+        .update_invoice_with(invoice_id, json!({ "deposit": { "amount": 5000 } }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let invoice = get_invoice(&client, invoice_id).await;
+    assert_eq!(as_f64(&invoice["deposit"]["amount"]), 800.0);
+    assert!(invoice["deposit"]["percent"].is_null());
+
+    // Empty terms remove the deposit
+    let resp = client.update_invoice_with(invoice_id, json!({ "deposit": {} })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let invoice = get_invoice(&client, invoice_id).await;
+    assert!(invoice["deposit"].is_null());
+}
@@ -0,0 +1,138 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("label_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Label Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_invoice_label_crud() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_invoice_label(json!({ "name": "  In review ", "color": "#3366FF", "statuses": ["draft"] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let label: Value = resp.json().await.unwrap();
+    let label_id = label["id"].as_str().unwrap().to_string();
+    assert_eq!(label["name"], "In review");
+    assert_eq!(label["color"], "#3366ff");
+    assert_eq!(label["statuses"], json!(["draft"]));
+    assert_eq!(label["show_on_pdf"], false);
+
+    // Names are unique per user, ignoring case
+    let resp = client.create_invoice_label(json!({ "name": "in REVIEW" })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_invoice_label(json!({ "name": "Bad", "color": "blue" })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_invoice_label(json!({ "name": "Bad", "statuses": ["pending"] })).await.unwrap();
+    assert_eq!(resp.status(), 422);
+
+    let resp = client
+        .update_invoice_label(&label_id, json!({ "name": "Awaiting PO", "show_on_pdf": true }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["name"], "Awaiting PO");
+    assert_eq!(updated["show_on_pdf"], true);
+
+    let resp = client.list_invoice_labels().await.unwrap();
+    let labels: Value = resp.json().await.unwrap();
+    assert_eq!(labels.as_array().unwrap().len(), 1);
+
+    let resp = client.delete_invoice_label(&label_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.delete_invoice_label(&label_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_invoice_label_follows_status() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Label Client", "label@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 50.0).await.unwrap();
+    let other: Value = resp.json().await.unwrap();
+    let other_id = other["id"].as_str().unwrap().to_string();
+
+    let resp = client
+        .create_invoice_label(json!({ "name": "Awaiting PO", "statuses": ["draft"], "show_on_pdf": true }))
+        .await
+        .unwrap();
+    let label: Value = resp.json().await.unwrap();
+    let label_id = label["id"].as_str().unwrap().to_string();
+    let resp = client
+        .create_invoice_label(json!({ "name": "Chasing", "statuses": ["overdue"] }))
+        .await
+        .unwrap();
+    let overdue_label: Value = resp.json().await.unwrap();
+
+    // A label only goes on invoices in one of its statuses
+    let resp = client
+        .set_invoice_label(&invoice_id, overdue_label["id"].as_str())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.set_invoice_label(&invoice_id, Some(&label_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let assigned: Value = resp.json().await.unwrap();
+    assert_eq!(assigned["name"], "Awaiting PO");
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["label"]["id"], label_id.as_str());
+
+    let resp = client.get_with_query("/invoices", &format!("label_id={}", label_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let list: Value = resp.json().await.unwrap();
+    let ids: Vec<&str> = list["items"].as_array().unwrap().iter().filter_map(|inv| inv["id"].as_str()).collect();
+    assert_eq!(ids, vec![invoice_id.as_str()]);
+    assert_eq!(list["items"][0]["label"], "Awaiting PO");
+    assert!(!ids.contains(&other_id.as_str()));
+
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body = resp.bytes().await.unwrap();
+    assert_eq!(body.get(0..4).unwrap_or_default(), b"%PDF");
+
+    // Once the invoice moves past draft the label no longer applies
+    let resp = client.send_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert!(detail.get("label").is_none());
+    let resp = client.get_with_query("/invoices", &format!("label_id={}", label_id)).await.unwrap();
+    let list: Value = resp.json().await.unwrap();
+    assert!(list["items"].as_array().unwrap().is_empty());
+
+    // Clearing the label
+    let resp = client.set_invoice_label(&other_id, Some(&label_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.set_invoice_label(&other_id, None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let cleared: Value = resp.json().await.unwrap();
+    assert!(cleared.is_null());
+}
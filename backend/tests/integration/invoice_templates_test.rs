@@ -0,0 +1,168 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("template_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Template Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient, name: &str) -> String {
+    let resp = client.create_client(name, &format!("{}@test.com", name.to_lowercase().replace(' ', "."))).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+async fn get_invoice(client: &ApiTestClient, invoice_id: &str) -> Value {
+    let resp = client.get_invoice(invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_invoice_templates() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Template Client").await;
+
+    let resp = client
+        .create_invoice_template(json!({
+            "name": " Monthly maintenance ",
+            "items": [
+                { "description": "Hosting", "quantity": 1, "unit_price": 40, "tax_rate": 0, "section": "Infrastructure" },
+                { "description": " Support hours ", "quantity": 3, "unit_price": 20, "tax_rate": 0 }
+            ],
+            "notes": "Thanks for your business",
+            "due_days": 10
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let template: Value = resp.json().await.unwrap();
+    let template_id = template["id"].as_str().unwrap().to_string();
+    assert_eq!(template["name"], "Monthly maintenance");
+    assert_eq!(template["items"][1]["description"], "Support hours");
+    assert_eq!(template["due_days"], 10);
+
+    // Names are unique per user, and a template needs valid lines
+    let item = json!({ "description": "Hosting", "quantity": 1, "unit_price": 40 });
+    let resp = client.create_invoice_template(json!({ "name": "monthly MAINTENANCE", "items": [item] })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_invoice_template(json!({ "name": "Empty", "items": [] })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .create_invoice_template(json!({ "name": "Negative", "items": [{ "description": "Hosting", "quantity": -1, "unit_price": 40 }] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.list_invoice_templates().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let templates: Value = resp.json().await.unwrap();
+    assert_eq!(templates.as_array().unwrap().len(), 1);
+
+    let resp = client
+        .create_invoice_from_template(&template_id, json!({ "client_id": client_id, "issue_date": "2026-03-02" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    assert_eq!(created["status"], "draft");
+    assert_eq!(created["total_amount"], 100.0);
+    let invoice = get_invoice(&client, created["id"].as_str().unwrap()).await;
+    assert_eq!(invoice["client_id"], client_id);
+    assert_eq!(invoice["issue_date"], "2026-03-02");
+    assert_eq!(invoice["due_date"], "2026-03-12");
+    assert_eq!(invoice["notes"], "Thanks for your business");
+    assert_eq!(invoice["items"].as_array().unwrap().len(), 2);
+    assert_eq!(invoice["items"][0]["section"], "Infrastructure");
+
+    // Without its own payment terms the template takes the client's
+    let resp = client.update_client_with(&client_id, json!({ "payment_terms": 14 })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.create_invoice_template(json!({ "name": "One-off", "items": [item] })).await.unwrap();
+    let one_off: Value = resp.json().await.unwrap();
+    let resp = client
+        .create_invoice_from_template(
+            one_off["id"].as_str().unwrap(),
+            json!({ "client_id": client_id, "issue_date": "2026-03-02" }),
+        )
+        .await
+        .unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice = get_invoice(&client, created["id"].as_str().unwrap()).await;
+    assert_eq!(invoice["due_date"], "2026-03-16");
+
+    let resp = client
+        .update_invoice_template(&template_id, json!({ "name": "Maintenance", "notes": "" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["name"], "Maintenance");
+    assert!(updated["notes"].is_null());
+    assert_eq!(updated["items"].as_array().unwrap().len(), 2);
+
+    let resp = client
+        .create_invoice_from_template(&template_id, json!({ "client_id": uuid::Uuid::new_v4() }))
+        .await
+        .unwrap();
+    assert!(resp.status().is_client_error());
+
+    let resp = client.delete_invoice_template(&template_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client
+        .create_invoice_from_template(&template_id, json!({ "client_id": client_id }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_duplicate_invoice() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Repeat Client").await;
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let original: Value = resp.json().await.unwrap();
+    let original_id = original["id"].as_str().unwrap().to_string();
+    let original = get_invoice(&client, &original_id).await;
+
+    let resp = client.duplicate_invoice(&original_id, json!({})).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let copy: Value = resp.json().await.unwrap();
+    assert_eq!(copy["status"], "draft");
+    assert_ne!(copy["invoice_number"], original["invoice_number"]);
+    assert_eq!(copy["total_amount"], original["total_amount"]);
+
+    let copy = get_invoice(&client, copy["id"].as_str().unwrap()).await;
+    assert_ne!(copy["id"], original["id"]);
+    assert_eq!(copy["client_id"], original["client_id"]);
+    assert_eq!(copy["items"][0]["description"], original["items"][0]["description"]);
+    assert_eq!(copy["notes"], original["notes"]);
+    assert_eq!(copy["terms"], original["terms"]);
+    assert_eq!(copy["issue_date"], original["issue_date"]);
+    assert_eq!(copy["due_date"], original["due_date"]);
+
+    // Payment terms carry over from a new issue date
+    let resp = client.duplicate_invoice(&original_id, json!({ "issue_date": "2026-05-01" })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let copy: Value = resp.json().await.unwrap();
+    let copy = get_invoice(&client, copy["id"].as_str().unwrap()).await;
+    assert_eq!(copy["issue_date"], "2026-05-01");
+    assert_eq!(copy["due_date"], "2026-05-31");
+
+    let resp = client.duplicate_invoice(&uuid::Uuid::new_v4().to_string(), json!({})).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
@@ -46,7 +46,7 @@ async fn test_invoice_crud() {
     let resp = client.list_invoices().await.unwrap();
     assert_eq!(resp.status(), 200);
     let list: Value = resp.json().await.unwrap();
-    assert!(list.is_array());
+    assert!(list["items"].is_array());
 
     // 4. Update invoice
     let resp = client.update_invoice(&invoice_id, "Updated notes").await.unwrap();
@@ -71,9 +71,9 @@ async fn test_invoice_crud() {
     let resp = client.record_payment(&invoice_id, 250.0).await.unwrap();
     assert_eq!(resp.status(), 201);
 
-    // 8. Delete invoice
+    // 8. A sent, part-paid invoice stays on record
     let resp = client.delete_invoice(&invoice_id).await.unwrap();
-    assert_eq!(resp.status(), 204);
+    assert_eq!(resp.status(), 409);
 
     // Cleanup
     client.delete_client(&client_id).await.unwrap();
@@ -218,3 +218,851 @@ async fn test_invoice_reminder() {
     client.delete_invoice(&invoice_id).await.unwrap();
     client.delete_client(&client_id).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_consolidate_draft_invoices() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Consolidation Client", "consolidate@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    // Two drafts for the same client
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let first: Value = resp.json().await.unwrap();
+    let first_id = first["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let second: Value = resp.json().await.unwrap();
+    let second_id = second["id"].as_str().unwrap().to_string();
+
+    // A single draft cannot be consolidated
+    let resp = client.consolidate_invoices(&[&first_id]).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.consolidate_invoices(&[&first_id, &second_id]).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let consolidated: Value = resp.json().await.unwrap();
+    let consolidated_id = consolidated["id"].as_str().unwrap().to_string();
+    assert_eq!(consolidated["total_amount"], 350.0);
+    assert_eq!(consolidated["status"], "draft");
+
+    // One section per source invoice
+    let resp = client.get_invoice(&consolidated_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    let items = detail["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["section"], first["invoice_number"]);
+    assert_eq!(items[1]["section"], second["invoice_number"]);
+
+    // Originals are cancelled and point at the consolidated invoice
+    for source_id in [&first_id, &second_id] {
+        let resp = client.get_invoice(source_id).await.unwrap();
+        let source: Value = resp.json().await.unwrap();
+        assert_eq!(source["status"], "cancelled");
+        assert_eq!(source["consolidated_into_id"], consolidated_id);
+    }
+
+    // Cancelled invoices cannot be consolidated again
+    let resp = client.consolidate_invoices(&[&first_id, &second_id]).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Cleanup
+    client.delete_invoice(&consolidated_id).await.unwrap();
+    client.delete_invoice(&first_id).await.unwrap();
+    client.delete_invoice(&second_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_invoice_terms_template_variables() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .update_invoice_settings_with_late_fee(
+            "Payment due within {{due_days}} days. Late fee: {{late_fee_rate}} per month.",
+            "Thank you, {{client_name}}!",
+            1.5,
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["late_fee_rate"], 1.5);
+    assert!(settings["variables"].as_array().unwrap().iter().any(|v| v == "client_name"));
+
+    let resp = client.create_client("Template Client", "template@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    // No terms or notes given, so the templated defaults from settings apply
+    let today = chrono::Utc::now().naive_utc().date();
+    let due_date = today + chrono::Duration::days(14);
+    let request = client.clone();
+    let resp = request.get_http_client().post(format!("{}/api/v1/invoices", get_api_base_url()))
+        .header("Authorization", format!("Bearer {}", request.get_auth_token().unwrap()))
+        .json(&serde_json::json!({
+            "client_id": client_id,
+            "issue_date": today,
+            "due_date": due_date,
+            "items": [{ "description": "Retainer", "quantity": 1, "unit_price": 300.0, "tax_rate": 0.0 }],
+            "tax_included": false,
+            "send_immediately": false
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["terms"], "Payment due within 14 days. Late fee: 1.5% per month.");
+    assert_eq!(detail["notes"], "Thank you, Template Client!");
+
+    // Later settings changes don't rewrite issued invoices
+    client.update_invoice_settings_with_late_fee("Net {{due_days}}", "", 3.0).await.unwrap();
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["terms"], "Payment due within 14 days. Late fee: 1.5% per month.");
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_invoice_pdf_watermarks() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.set_paid_stamp(true).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["paid_stamp"], true);
+
+    let resp = client.create_client("Stamp Client", "stamp@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    // Draft PDF carries the DRAFT watermark
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body = resp.bytes().await.unwrap();
+    assert_eq!(body.get(0..4).unwrap_or_default(), b"%PDF");
+
+    // Fully paid invoices get the PAID stamp
+    let resp = client.record_payment(&invoice_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["status"], "paid");
+
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body = resp.bytes().await.unwrap();
+    assert_eq!(body.get(0..4).unwrap_or_default(), b"%PDF");
+
+    let resp = client.set_paid_stamp(false).await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["paid_stamp"], false);
+}
+
+#[tokio::test]
+async fn test_invoice_pdf_is_cached_until_invoice_changes() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Cached PDF Client", "cached.pdf@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    // First request renders and stores the PDF
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["x-pdf-cache"], "miss");
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+    let first = resp.bytes().await.unwrap();
+    assert_eq!(first.get(0..4).unwrap_or_default(), b"%PDF");
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    let pdf_url = detail["pdf_url"].as_str().unwrap().to_string();
+    assert!(pdf_url.starts_with("/api/v1/files/download/"));
+
+    // Unchanged invoice is served from the stored copy
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.headers()["x-pdf-cache"], "hit");
+    assert_eq!(resp.bytes().await.unwrap(), first);
+
+    // Forced regeneration replaces the stored copy
+    let resp = client.regenerate_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["x-pdf-cache"], "miss");
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_ne!(detail["pdf_url"].as_str().unwrap(), pdf_url);
+
+    // Editing the invoice drops the stored copy
+    let resp = client.update_invoice(&invoice_id, "Updated notes").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert!(detail["pdf_url"].is_null());
+
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.headers()["x-pdf-cache"], "miss");
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.headers()["x-pdf-cache"], "hit");
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&client_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_send_invoice_with_cc_bcc_and_message() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Copy Client", "copy.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    // Malformed copy addresses are rejected before anything is sent
+    let resp = client
+        .send_invoice_with(&invoice_id, serde_json::json!({ "cc": ["not-an-email"] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .send_invoice_with(&invoice_id, serde_json::json!({
+            "cc": ["accounts@example.com", "ACCOUNTS@example.com"],
+            "bcc": ["me@example.com"],
+            "subject": "Invoice for {{client_name}}",
+            "message": "Thanks for the quick turnaround, {{client_name}}!"
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.get_invoice_notifications(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let log: Value = resp.json().await.unwrap();
+    let email = log
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["channel"] == "email")
+        .expect("email send should be logged");
+
+    assert_eq!(email["recipients"], serde_json::json!(["copy.client@example.com"]));
+    assert_eq!(email["cc"], serde_json::json!(["accounts@example.com"]));
+    assert_eq!(email["bcc"], serde_json::json!(["me@example.com"]));
+    assert_eq!(email["subject"], "Invoice for Copy Client");
+    assert_eq!(email["message"], "Thanks for the quick turnaround, Copy Client!");
+}
+
+#[tokio::test]
+async fn test_send_invoice_with_calendar_attachment() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Calendar Client", "calendar.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 320.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client
+        .send_invoice_with(&invoice_id, serde_json::json!({ "attach_calendar": true }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let sent: Value = resp.json().await.unwrap();
+    assert_eq!(sent["status"], "sent");
+}
+
+#[tokio::test]
+async fn test_correct_sent_invoice_supersedes_original() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Correction Client", "correction.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let original_id = invoice["id"].as_str().unwrap().to_string();
+
+    // Drafts are edited in place, not corrected
+    let resp = client
+        .correct_invoice(&original_id, serde_json::json!({ "reason": "Wrong amount" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    let resp = client.send_invoice(&original_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // A reason is required for the audit trail
+    let resp = client
+        .correct_invoice(&original_id, serde_json::json!({ "reason": "  " }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .correct_invoice(&original_id, serde_json::json!({
+            "reason": "Wrong amount",
+            "items": [{ "description": "Consulting", "quantity": 1.0, "unit_price": 120.0 }],
+            "send": false
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let corrected: Value = resp.json().await.unwrap();
+    let corrected_id = corrected["id"].as_str().unwrap().to_string();
+    assert_ne!(corrected["invoice_number"], invoice["invoice_number"]);
+
+    let resp = client.get_invoice(&original_id).await.unwrap();
+    let original: Value = resp.json().await.unwrap();
+    assert_eq!(original["status"], "superseded");
+    assert_eq!(original["superseded_by_id"], corrected_id.as_str());
+
+    let resp = client.get_invoice(&corrected_id).await.unwrap();
+    let corrected: Value = resp.json().await.unwrap();
+    assert_eq!(corrected["supersedes_id"], original_id.as_str());
+    assert_eq!(corrected["correction_reason"], "Wrong amount");
+
+    // The superseded original can't be corrected, edited or paid again
+    let resp = client
+        .correct_invoice(&original_id, serde_json::json!({ "reason": "Again" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client.update_invoice(&original_id, "Edited").await.unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client.record_payment(&original_id, 50.0).await.unwrap();
+    assert_eq!(resp.status(), 409);
+}
+
+#[tokio::test]
+async fn test_invoice_lifecycle_is_enforced() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Lifecycle Client", "lifecycle.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    // Paid invoices can't be edited, deleted or cancelled; they take a credit note
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let paid_id = invoice["id"].as_str().unwrap().to_string();
+    let resp = client.record_payment(&paid_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client.update_invoice(&paid_id, "Changed after payment").await.unwrap();
+    assert_eq!(resp.status(), 409);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "INVALID_STATUS_TRANSITION");
+    assert!(body["error"]["message"].as_str().unwrap().contains("credit note"), "{}", body);
+    assert_eq!(body["error"]["details"]["status"], "paid");
+    assert_eq!(client.delete_invoice(&paid_id).await.unwrap().status(), 409);
+    assert_eq!(client.cancel_invoice(&paid_id).await.unwrap().status(), 409);
+    assert_eq!(client.record_payment(&paid_id, 10.0).await.unwrap().status(), 409);
+
+    let resp = client.get_invoice(&paid_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["status"], "paid");
+    assert_eq!(detail["notes"], "Test invoice");
+
+    // A sent invoice is cancelled rather than deleted, and then stays closed
+    let resp = client.create_invoice(&client_id, 200.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let sent_id = invoice["id"].as_str().unwrap().to_string();
+    assert_eq!(client.send_invoice(&sent_id).await.unwrap().status(), 200);
+    assert_eq!(client.delete_invoice(&sent_id).await.unwrap().status(), 409);
+
+    let resp = client.cancel_invoice(&sent_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let cancelled: Value = resp.json().await.unwrap();
+    assert_eq!(cancelled["status"], "cancelled");
+
+    assert_eq!(client.send_invoice(&sent_id).await.unwrap().status(), 409);
+    assert_eq!(client.record_payment(&sent_id, 50.0).await.unwrap().status(), 409);
+    assert_eq!(client.cancel_invoice(&sent_id).await.unwrap().status(), 409);
+
+    // Drafts can still be deleted outright
+    let resp = client.create_invoice(&client_id, 50.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let draft_id = invoice["id"].as_str().unwrap().to_string();
+    assert_eq!(client.delete_invoice(&draft_id).await.unwrap().status(), 204);
+}
+
+#[tokio::test]
+async fn test_expired_offer_cannot_be_paid_by_guest() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Offer Client", "offer.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let today = chrono::Utc::now().date_naive();
+    let offer = |issue_date: chrono::NaiveDate, expires_at: chrono::NaiveDate| {
+        serde_json::json!({
+            "client_id": client_id,
+            "issue_date": issue_date,
+            "due_date": issue_date + chrono::Duration::days(30),
+            "items": [{ "description": "Proposal", "quantity": 1, "unit_price": 300.0, "tax_rate": 0.0 }],
+            "tax_included": false,
+            "send_immediately": false,
+            "expires_at": expires_at
+        })
+    };
+
+    // An offer can't lapse before it was issued
+    let resp = client.create_invoice_with(offer(today, today - chrono::Duration::days(1))).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Issued three weeks ago, valid for 14 days
+    let issued = today - chrono::Duration::days(21);
+    let resp = client.create_invoice_with(offer(issued, issued + chrono::Duration::days(14))).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+
+    let resp = client.send_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["expires_at"], (issued + chrono::Duration::days(14)).to_string());
+    let guest_token = invoice["guest_payment_token"].as_str().unwrap().to_string();
+
+    // The stale link still shows the offer, but offers no way to pay it
+    let resp = client.get_guest_invoice(&guest_token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert!(body["payment_methods"].as_array().unwrap().is_empty());
+
+    let resp = client.process_guest_payment(&guest_token, 300.0).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_regenerating_guest_link_revokes_old_one() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Guest Link Client", "guestlink@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 120.0).await.unwrap();
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    let old_token = invoice["guest_payment_token"].as_str().unwrap().to_string();
+    assert_eq!(client.get_guest_invoice(&old_token).await.unwrap().status(), 200);
+
+    // Knowing the invoice id isn't enough to build a link
+    let forged = format!("guest_{}_{}", invoice_id, "0".repeat(32));
+    assert_eq!(client.get_guest_invoice(&forged).await.unwrap().status(), 404);
+
+    let resp = client.regenerate_guest_link(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let link: Value = resp.json().await.unwrap();
+    let new_token = link["token"].as_str().unwrap().to_string();
+    assert_ne!(new_token, old_token);
+    assert!(link["expires_at"].is_string());
+
+    assert_eq!(client.get_guest_invoice(&old_token).await.unwrap().status(), 404);
+    assert_eq!(client.get_guest_invoice(&new_token).await.unwrap().status(), 200);
+
+    let invoice: Value = client.get_invoice(&invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(invoice["guest_payment_token"], new_token);
+
+    // Only the owner can reissue the link
+    let other = setup_authenticated_client().await;
+    assert_eq!(other.regenerate_guest_link(&invoice_id).await.unwrap().status(), 404);
+}
+
+#[tokio::test]
+async fn test_sparse_invoice_responses() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Sparse Client", "sparse@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_with_query("/invoices", "fields=id,status").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let list: Value = resp.json().await.unwrap();
+    let first = list["items"][0].as_object().unwrap();
+    assert_eq!(first.len(), 2);
+    assert!(first.contains_key("id") && first.contains_key("status"));
+
+    let resp = client.get_with_query(&format!("/invoices/{}", invoice_id), "view=compact").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let compact: Value = resp.json().await.unwrap();
+    let mut keys: Vec<&String> = compact.as_object().unwrap().keys().collect();
+    keys.sort();
+    assert_eq!(keys, ["balance_due", "client_name", "due_date", "id", "invoice_number", "status"]);
+
+    let resp = client.get_with_query(&format!("/invoices/{}", invoice_id), "view=full").await.unwrap();
+    let full: Value = resp.json().await.unwrap();
+    assert!(full["items"].is_array());
+
+    let resp = client.get_with_query("/invoices", "view=tiny").await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.get_with_query("/invoices", "fields=id;status").await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.get_with_query("/reports/aging", "view=compact").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_batch_get_invoices_and_clients() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Batch Client", "batch@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let mut invoice_ids = Vec::new();
+    for amount in [100.0, 200.0] {
+        let resp = client.create_invoice(&client_id, amount).await.unwrap();
+        let invoice: Value = resp.json().await.unwrap();
+        invoice_ids.push(invoice["id"].as_str().unwrap().to_string());
+    }
+    let missing = uuid::Uuid::new_v4().to_string();
+
+    let query = format!("ids={},{},{}", invoice_ids[1], missing, invoice_ids[0]);
+    let resp = client.get_with_query("/invoices", &query).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let batch: Value = resp.json().await.unwrap();
+    let items = batch["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["id"], invoice_ids[1].as_str());
+    assert_eq!(items[1]["id"], invoice_ids[0].as_str());
+    assert_eq!(batch["not_found"], serde_json::json!([missing]));
+
+    // Field selection applies to each batch item
+    let resp = client.get_with_query("/invoices", &format!("{}&fields=id", query)).await.unwrap();
+    let batch: Value = resp.json().await.unwrap();
+    assert_eq!(batch["items"][0].as_object().unwrap().len(), 1);
+
+    let resp = client.get_with_query("/clients", &format!("ids={}", client_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let batch: Value = resp.json().await.unwrap();
+    assert_eq!(batch["items"][0]["id"], client_id.as_str());
+    assert!(batch["not_found"].as_array().unwrap().is_empty());
+
+    let resp = client.get_with_query("/payments", &format!("ids={}", missing)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let batch: Value = resp.json().await.unwrap();
+    assert!(batch["items"].as_array().unwrap().is_empty());
+    assert_eq!(batch["not_found"], serde_json::json!([missing]));
+
+    let resp = client.get_with_query("/invoices", "ids=not-a-uuid").await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let too_many: Vec<String> = (0..101).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    let resp = client.get_with_query("/clients", &format!("ids={}", too_many.join(","))).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+
+#[tokio::test]
+async fn test_resend_failed_notification() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Resend Client", "resend.client@example.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 180.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.send_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // The invoice shows how the latest email attempt went
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    let email = detail["delivery"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["channel"] == "email")
+        .expect("email delivery should be shown")
+        .clone();
+    let notification_id = email["notification_id"].as_str().unwrap();
+
+    let resp = client.resend_invoice_notification(&invoice_id, notification_id).await.unwrap();
+    if email["delivered"] == true {
+        // Delivered sends aren't retried
+        assert_eq!(resp.status(), 400);
+    } else {
+        assert!(email["error"].is_string());
+        assert_eq!(resp.status(), 200);
+        let attempt: Value = resp.json().await.unwrap();
+        assert_eq!(attempt["channel"], "email");
+        assert_eq!(attempt["resent_from_id"], notification_id);
+        assert_eq!(attempt["recipients"], serde_json::json!(["resend.client@example.com"]));
+
+        let resp = client.get_invoice(&invoice_id).await.unwrap();
+        let detail: Value = resp.json().await.unwrap();
+        let latest = detail["delivery"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["channel"] == "email")
+            .unwrap()
+            .clone();
+        assert_eq!(latest["notification_id"], attempt["id"]);
+    }
+
+    let resp = client
+        .resend_invoice_notification(&invoice_id, "00000000-0000-0000-0000-000000000000")
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_send_without_recipient_leaves_invoice_unsent() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client_with(serde_json::json!({ "name": "No Contact Ltd" })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 80.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.send_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["status"], "draft");
+    assert!(detail["sent_at"].is_null());
+
+    // Giving an address for this send delivers it and only then marks it sent
+    let resp = client
+        .send_invoice_with(&invoice_id, serde_json::json!({ "email": "accounts@nocontact.test" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["status"], "sent");
+}
+
+#[tokio::test]
+async fn test_error_messages_follow_accept_language() {
+    let client = setup_authenticated_client().await;
+    let missing = format!("/invoices/{}", uuid::Uuid::new_v4());
+
+    let resp = client.get_with_language(&missing, "id-ID,id;q=0.9,en;q=0.8").await.unwrap();
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers()["content-language"], "id");
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["code"], "NOT_FOUND");
+    assert_eq!(body["error"]["message"], "Tidak ditemukan");
+
+    let resp = client.get_with_language(&missing, "es-MX").await.unwrap();
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["message"], "No encontrado");
+
+    // Unsupported languages fall back to English
+    let resp = client.get_with_language(&missing, "fr-FR").await.unwrap();
+    assert_eq!(resp.headers()["content-language"], "en");
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["message"], "Not found");
+}
+
+#[tokio::test]
+async fn test_invoice_creation_is_idempotent() {
+    let client = setup_authenticated_client().await;
+    let resp = client.create_client("Retry Client", "retry@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let key = uuid::Uuid::new_v4().to_string();
+
+    let resp = client.create_invoice_with_idempotency_key(&client_id, 120.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    assert!(resp.headers().get("idempotent-replayed").is_none());
+    let first: Value = resp.json().await.unwrap();
+
+    // A retry gets the original invoice back instead of a second one
+    let resp = client.create_invoice_with_idempotency_key(&client_id, 120.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.headers()["idempotent-replayed"], "true");
+    let replayed: Value = resp.json().await.unwrap();
+    assert_eq!(replayed["id"], first["id"]);
+    assert_eq!(replayed["invoice_number"], first["invoice_number"]);
+
+    let resp = client.get_client_invoices(&client_id).await.unwrap();
+    let invoices: Value = resp.json().await.unwrap();
+    assert_eq!(invoices.as_array().unwrap().len(), 1);
+
+    // Reusing the key for a different invoice is rejected
+    let resp = client.create_invoice_with_idempotency_key(&client_id, 99.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_invoices_imported_from_csv() {
+    let client = setup_authenticated_client().await;
+    let resp = client.create_client("Ana Co", "ana@example.com").await.unwrap();
+    let ana: Value = resp.json().await.unwrap();
+    client.create_client("Budi", "budi@example.com").await.unwrap();
+
+    let csv = "Invoice Number,Client Email,Client Name,Issue Date,Due Date,Description,Quantity,Unit Price,Tax Rate\n\
+               A-1,ana@example.com,,2024-03-01,2024-03-31,Design,10,100,10\n\
+               A-1,,,,,Support,2,50,\n\
+               B-1,,Budi,2024-03-02,,Hosting,1,20,\n\
+               C-1,nobody@example.com,,2024-03-03,,Audit,1,500,\n\
+               D-1,ana@example.com,,2024-03-04,,Travel,1,free,\n";
+
+    // Dry run reports without creating anything
+    let resp = client.import_invoices_csv("invoices.csv", csv.as_bytes(), "dry_run=true").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["total_rows"], 5);
+    assert_eq!(report["total_invoices"], 4);
+    assert_eq!(report["created"], 2);
+    assert_eq!(report["failed"], 2);
+    let errors: Vec<(i64, &str)> = report["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| (e["row"].as_i64().unwrap(), e["field"].as_str().unwrap_or_default()))
+        .collect();
+    assert_eq!(errors, vec![(5, "client_email"), (6, "unit_price")]);
+
+    let resp = client.list_invoices().await.unwrap();
+    let invoices: Value = resp.json().await.unwrap();
+    assert_eq!(invoices["total"], 0);
+
+    let resp = client.import_invoices_csv("invoices.csv", csv.as_bytes(), "").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["created"], 2);
+    assert_eq!(report["invoices"][0]["reference"], "A-1");
+    assert_eq!(report["invoices"][0]["client_id"], ana["id"]);
+    assert_eq!(report["invoices"][0]["items"], 2);
+
+    let invoice_id = report["invoices"][0]["invoice_id"].as_str().unwrap();
+    let resp = client.get_invoice(invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    assert_eq!(invoice["status"], "draft");
+    assert_eq!(invoice["items"].as_array().unwrap().len(), 2);
+    assert_eq!(invoice["subtotal"], 1100.0);
+
+    // Budi's invoice takes the client's payment terms for its due date
+    let invoice_id = report["invoices"][1]["invoice_id"].as_str().unwrap();
+    let resp = client.get_invoice(invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    assert_eq!(invoice["due_date"], "2024-04-01");
+}
+
+#[tokio::test]
+async fn test_invoices_exported_with_list_filters() {
+    let client = setup_authenticated_client().await;
+    let resp = client.create_client("Export Client", "export@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+    let resp = client.create_client("Other Client", "other@example.com").await.unwrap();
+    let other: Value = resp.json().await.unwrap();
+
+    client.create_invoice(&client_id, 100.0).await.unwrap();
+    client.create_invoice(&client_id, 250.0).await.unwrap();
+    client.create_invoice(other["id"].as_str().unwrap(), 75.0).await.unwrap();
+
+    let resp = client.export_invoices(&format!("client_id={}", client_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "text/csv");
+    let csv = resp.text().await.unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(lines[0].starts_with("invoice_number,status,client_name,client_email"));
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1..].iter().all(|line| line.contains("Export Client")));
+
+    // The export can be imported again
+    let resp = client.import_invoices_csv("export.csv", csv.as_bytes(), "dry_run=true").await.unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["created"], 2);
+
+    let resp = client.export_invoices("format=xlsx").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()["content-type"],
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    );
+    assert!(resp.headers()["content-disposition"].to_str().unwrap().ends_with(".xlsx\""));
+    let file = resp.bytes().await.unwrap();
+    assert!(file.starts_with(b"PK\x03\x04"));
+
+    let resp = client.export_invoices("format=pdf").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_invoice_list_pages_by_number_and_cursor() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Paging Client", "paging@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    for amount in [100.0, 200.0, 300.0] {
+        client.create_invoice(&client_id, amount).await.unwrap();
+    }
+
+    let resp = client.get_with_query("/invoices", "per_page=2").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let first: Value = resp.json().await.unwrap();
+    assert_eq!(first["items"].as_array().unwrap().len(), 2);
+    assert_eq!(first["total"], 3);
+    assert_eq!(first["page"], 1);
+    assert_eq!(first["per_page"], 2);
+    let cursor = first["next_cursor"].as_str().unwrap();
+
+    let resp = client.get_with_query("/invoices", &format!("per_page=2&cursor={}", cursor)).await.unwrap();
+    let next: Value = resp.json().await.unwrap();
+    let rest = next["items"].as_array().unwrap();
+    assert_eq!(rest.len(), 1);
+    assert!(first["items"].as_array().unwrap().iter().all(|invoice| invoice["id"] != rest[0]["id"]));
+    assert!(next["page"].is_null());
+    assert!(next["next_cursor"].is_null());
+
+    // The second numbered page holds the same invoice
+    let resp = client.get_with_query("/invoices", "page=2&per_page=2").await.unwrap();
+    let second: Value = resp.json().await.unwrap();
+    assert_eq!(second["items"][0]["id"], rest[0]["id"]);
+    assert_eq!(second["page"], 2);
+
+    let resp = client.get_with_query("/invoices", "cursor=not-a-cursor").await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.get_with_query("/invoices", "per_page=1000").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
@@ -0,0 +1,146 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("line_tax_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Maple Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient) -> String {
+    let resp = client.create_client("Quebec Client", "quebec.client@test.com").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+fn as_f64(value: &Value) -> f64 {
+    value.as_f64().unwrap_or_else(|| value.as_str().unwrap().parse().unwrap())
+}
+
+#[tokio::test]
+async fn test_line_with_stacked_and_compound_taxes() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client).await;
+
+    let resp = client
+        .create_invoice_with_items(&client_id, json!([
+            {
+                "description": "Consulting",
+                "quantity": 1,
+                "unit_price": 100,
+                "taxes": [
+                    { "label": "GST", "rate": 0.05 },
+                    { "label": "QST", "rate": 0.09975, "compound": true },
+                ],
+            },
+            {
+                "description": "Hardware",
+                "quantity": 2,
+                "unit_price": 100,
+                "taxes": [
+                    { "label": "GST", "rate": 0.05 },
+                    { "label": "PST", "rate": 0.07 },
+                ],
+            },
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap();
+
+    let invoice: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    // 100 x (5% + 9.975% on 105) = 15.47; 200 x 12% = 24
+    assert_eq!(as_f64(&invoice["subtotal"]), 300.0);
+    assert_eq!(as_f64(&invoice["tax_amount"]), 39.47);
+    assert_eq!(as_f64(&invoice["total_amount"]), 339.47);
+
+    let first_line = invoice["items"][0]["taxes"].as_array().unwrap();
+    assert_eq!(first_line.len(), 2);
+    assert_eq!(as_f64(&first_line[0]["tax_amount"]), 5.0);
+    assert_eq!(as_f64(&first_line[1]["tax_amount"]), 10.47);
+    assert_eq!(first_line[1]["compound"], true);
+
+    let taxes = invoice["tax_calculation"]["taxes"].as_array().unwrap();
+    let find = |label: &str| taxes.iter().find(|tax| tax["label"] == label).unwrap().clone();
+    assert_eq!(taxes.len(), 3);
+    assert_eq!(as_f64(&find("GST")["tax_amount"]), 15.0);
+    assert_eq!(as_f64(&find("GST")["taxable_amount"]), 300.0);
+    assert_eq!(as_f64(&find("QST")["taxable_amount"]), 105.0);
+    assert_eq!(as_f64(&find("PST")["tax_amount"]), 14.0);
+
+    let resp = client.get_invoice_pdf(invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // The tax report lists each tax on its own line
+    client.send_invoice(invoice_id).await.unwrap();
+    let resp = client.record_payment(invoice_id, 339.47).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let today = chrono::Utc::now().naive_utc().date().to_string();
+    let report: Value = client.get_tax_report(&today, &today).await.unwrap().json().await.unwrap();
+    let by_tax = report["by_tax"].as_array().unwrap();
+    assert_eq!(by_tax.len(), 3);
+    let qst = by_tax.iter().find(|tax| tax["label"] == "QST").unwrap();
+    assert_eq!(as_f64(&qst["tax_amount"]), 10.47);
+    assert_eq!(qst["compound"], true);
+}
+
+#[tokio::test]
+async fn test_line_taxes_are_validated_and_follow_edits() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client).await;
+
+    let resp = client
+        .create_invoice_with_items(&client_id, json!([{
+            "description": "Consulting",
+            "quantity": 1,
+            "unit_price": 100,
+            "taxes": [{ "label": "GST", "rate": 5 }],
+        }]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .create_invoice_with_items(&client_id, json!([{
+            "description": "Consulting",
+            "quantity": 1,
+            "unit_price": 100,
+            "tax_rate": 0.1,
+        }]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap();
+
+    // Editing the lines rebuilds the breakdown
+    let resp = client
+        .update_invoice_with(invoice_id, json!({
+            "items": [{
+                "description": "Consulting",
+                "quantity": 1,
+                "unit_price": 100,
+                "taxes": [{ "label": "GST", "rate": 0.05 }, { "label": "PST", "rate": 0.07 }],
+            }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let invoice: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(as_f64(&invoice["tax_amount"]), 12.0);
+    assert_eq!(as_f64(&invoice["items"][0]["tax_rate"]), 0.12);
+    assert_eq!(invoice["tax_calculation"]["taxes"].as_array().unwrap().len(), 2);
+}
@@ -30,3 +30,31 @@ pub mod settings_test;
 pub mod advanced_features_test;
 pub mod tax_test;
 pub mod discussion_test;
+pub mod guest_contract_test;
+pub mod budgets_test;
+pub mod payouts_test;
+pub mod businesses_test;
+pub mod accountants_test;
+pub mod campaigns_test;
+pub mod profitability_test;
+pub mod sync_test;
+pub mod invoice_labels_test;
+pub mod fx_test;
+pub mod credit_notes_test;
+pub mod attachments_test;
+pub mod audit_logs_test;
+pub mod webhook_endpoints_test;
+pub mod search_test;
+pub mod trash_test;
+pub mod portal_test;
+pub mod time_entries_test;
+pub mod projects_test;
+pub mod events_test;
+pub mod invoice_templates_test;
+pub mod invoice_batch_test;
+pub mod client_credit_test;
+pub mod invoice_deposits_test;
+pub mod line_taxes_test;
+pub mod eu_vat_test;
+pub mod e_invoice_test;
+pub mod payment_qr_test;
@@ -0,0 +1,108 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("payment_qr_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Berlin GmbH")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+fn invoice_settings(extra: Value) -> Value {
+    let mut settings = json!({ "template": "classic", "terms": "Net 30", "notes": "" });
+    settings.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    settings
+}
+
+async fn pdf_size(client: &ApiTestClient, invoice_id: &str) -> usize {
+    let resp = client.get_invoice_pdf(invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.bytes().await.unwrap().len()
+}
+
+#[tokio::test]
+async fn test_payment_qr_settings_are_checked() {
+    let client = setup_authenticated_client().await;
+
+    // SEPA codes need an account, with a valid IBAN
+    let resp = client.update_invoice_settings_with(invoice_settings(json!({ "payment_qr": "sepa" }))).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .update_invoice_settings_with(invoice_settings(json!({
+            "payment_qr": "sepa",
+            "sepa_account": { "name": "Berlin GmbH", "iban": "DE88370400440532013000" },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .update_invoice_settings_with(invoice_settings(json!({
+            "payment_qr": "sepa",
+            "sepa_account": { "name": "Berlin GmbH", "iban": "de89 3704 0044 0532 0130 00", "bic": "cobadeffxxx" },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["payment_qr"], "sepa");
+    assert_eq!(settings["sepa_account"]["iban"], "DE89370400440532013000");
+    assert_eq!(settings["sepa_account"]["bic"], "COBADEFFXXX");
+
+    let resp = client
+        .update_invoice_settings_with(invoice_settings(json!({ "payment_qr": "qris", "qris_payload": "00020101021163041234" })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_invoice_pdf_carries_the_payment_qr_code() {
+    let client = setup_authenticated_client().await;
+    let resp = client
+        .create_client_with(json!({ "name": "Paris SARL", "email": "accounts@example.com", "default_currency": "EUR" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let customer: Value = resp.json().await.unwrap();
+    let resp = client
+        .create_invoice_with_items(customer["id"].as_str().unwrap(), json!([
+            { "description": "Consulting", "quantity": 2, "unit_price": 500, "tax_rate": 0.0 },
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap();
+
+    let resp = client.update_invoice_settings_with(invoice_settings(json!({ "payment_qr": "off" }))).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let without_code = pdf_size(&client, invoice_id).await;
+
+    // The stored copy is redrawn once the setting changes
+    let resp = client.update_invoice_settings_with(invoice_settings(json!({ "payment_qr": "link" }))).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let with_link = pdf_size(&client, invoice_id).await;
+    assert!(with_link > without_code);
+
+    let resp = client
+        .update_invoice_settings_with(invoice_settings(json!({
+            "payment_qr": "sepa",
+            "sepa_account": { "name": "Berlin GmbH", "iban": "DE89370400440532013000" },
+        })))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(pdf_size(&client, invoice_id).await > without_code);
+}
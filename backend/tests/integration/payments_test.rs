@@ -50,8 +50,8 @@ async fn test_payment_crud() {
     let resp = client.list_payments().await.unwrap();
     assert_eq!(resp.status(), 200);
     let list: Value = resp.json().await.unwrap();
-    assert!(list.is_array());
-    assert!(list.as_array().unwrap().len() > 0);
+    assert!(list["items"].is_array());
+    assert!(list["items"].as_array().unwrap().len() > 0);
 
     // 4. Get payment stats
     let resp = client.get_payment_stats().await.unwrap();
@@ -162,3 +162,233 @@ async fn test_payment_with_different_methods() {
     client.delete_invoice(&invoice_id).await.unwrap();
     client.delete_client(&client_id).await.unwrap();
 }
+
+#[tokio::test]
+async fn test_payment_creation_is_idempotent() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Retry Payment Client", "retry-payment@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 300.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    let key = uuid::Uuid::new_v4().to_string();
+
+    let resp = client.create_payment_with_idempotency_key(&invoice_id, 100.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let first: Value = resp.json().await.unwrap();
+
+    let resp = client.create_payment_with_idempotency_key(&invoice_id, 100.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    assert_eq!(resp.headers()["idempotent-replayed"], "true");
+    let replayed: Value = resp.json().await.unwrap();
+    assert_eq!(replayed["id"], first["id"]);
+
+    // Only one payment was recorded
+    let resp = client.list_payments().await.unwrap();
+    let payments: Value = resp.json().await.unwrap();
+    let recorded = payments["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|payment| payment["invoice_id"] == invoice_id.as_str())
+        .count();
+    assert_eq!(recorded, 1);
+
+    // Failed requests don't consume the key
+    let key = uuid::Uuid::new_v4().to_string();
+    let missing = uuid::Uuid::new_v4().to_string();
+    let resp = client.create_payment_with_idempotency_key(&missing, 50.0, &key).await.unwrap();
+    assert!(resp.status().is_client_error());
+    let resp = client.create_payment_with_idempotency_key(&invoice_id, 50.0, &key).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    assert!(resp.headers().get("idempotent-replayed").is_none());
+}
+
+#[tokio::test]
+async fn test_partial_payment_settings_are_enforced() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Installments Client", "installments@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let today = chrono::Utc::now().date_naive();
+    let invoice_with = |amount: f64, settings: Value| {
+        let mut body = serde_json::json!({
+            "client_id": client_id,
+            "issue_date": today,
+            "due_date": today + chrono::Duration::days(30),
+            "items": [{ "description": "Retainer", "quantity": 1, "unit_price": amount, "tax_rate": 0.0 }],
+            "tax_included": false,
+            "send_immediately": false
+        });
+        body.as_object_mut().unwrap().extend(settings.as_object().unwrap().clone());
+        body
+    };
+
+    // Paid in full only
+    let resp = client.create_invoice_with(invoice_with(400.0, serde_json::json!({ "allow_partial_payment": false }))).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let full_only = invoice["id"].as_str().unwrap().to_string();
+
+    assert_eq!(client.record_payment(&full_only, 100.0).await.unwrap().status(), 400);
+    let detail: Value = client.get_invoice(&full_only).await.unwrap().json().await.unwrap();
+    let guest_token = detail["guest_payment_token"].as_str().unwrap().to_string();
+    let resp = client.process_guest_payment(&guest_token, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let body: Value = resp.json().await.unwrap();
+    assert!(body["error"]["message"].as_str().unwrap().contains("paid in full"));
+
+    assert_eq!(client.record_payment(&full_only, 400.0).await.unwrap().status(), 201);
+    let detail: Value = client.get_invoice(&full_only).await.unwrap().json().await.unwrap();
+    assert_eq!(detail["status"], "paid");
+    assert_eq!(detail["partial_payment_count"], 0);
+
+    // Installments of at least 150
+    let resp = client.create_invoice_with(invoice_with(500.0, serde_json::json!({ "min_payment_amount": 150.0 }))).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let installments = invoice["id"].as_str().unwrap().to_string();
+
+    assert_eq!(client.record_payment(&installments, 100.0).await.unwrap().status(), 400);
+    assert_eq!(client.record_payment(&installments, 150.0).await.unwrap().status(), 201);
+    assert_eq!(client.record_payment(&installments, 250.0).await.unwrap().status(), 201);
+    assert_eq!(client.record_payment(&installments, 150.0).await.unwrap().status(), 400);
+
+    let detail: Value = client.get_invoice(&installments).await.unwrap().json().await.unwrap();
+    assert_eq!(detail["status"], "partial");
+    assert_eq!(detail["balance_due"], 100.0);
+    assert_eq!(detail["partial_payment_count"], 2);
+
+    // The rest of the balance may be below the minimum
+    assert_eq!(client.record_payment(&installments, 100.0).await.unwrap().status(), 201);
+    let detail: Value = client.get_invoice(&installments).await.unwrap().json().await.unwrap();
+    assert_eq!(detail["status"], "paid");
+    assert_eq!(detail["partial_payment_count"], 2);
+}
+
+#[tokio::test]
+async fn test_stripe_checkout_rejects_unverified_callbacks() {
+    let client = setup_authenticated_client().await;
+    let payload = r#"{"type":"checkout.session.completed","data":{"object":{"id":"cs_test_forged","payment_status":"paid"}}}"#;
+
+    // Webhooks without a signature are rejected
+    let resp = client.post_stripe_checkout_webhook(payload, None).await.unwrap();
+    assert_eq!(resp.status(), 401);
+
+    // Return callbacks need the session Stripe sends back
+    let resp = client
+        .get_http_client()
+        .get(format!("{}/api/v1/checkout/stripe/success", get_api_base_url()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_bank_transfers_are_reconciled_by_the_seller() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Transfer Client", "transfers@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let today = chrono::Utc::now().date_naive();
+    let mut invoices = Vec::new();
+    for amount in [640.0, 210.0] {
+        let resp = client
+            .create_invoice_with(serde_json::json!({
+                "client_id": client_data["id"],
+                "issue_date": today,
+                "due_date": today + chrono::Duration::days(30),
+                "items": [{ "description": "Fit-out", "quantity": 1, "unit_price": amount, "tax_rate": 0.0 }],
+                "tax_included": false,
+                "send_immediately": false
+            }))
+            .await
+            .unwrap();
+        let invoice: Value = resp.json().await.unwrap();
+        let detail: Value = client.get_invoice(invoice["id"].as_str().unwrap()).await.unwrap().json().await.unwrap();
+        invoices.push((
+            detail["id"].as_str().unwrap().to_string(),
+            detail["invoice_number"].as_str().unwrap().to_string(),
+            detail["guest_payment_token"].as_str().unwrap().to_string(),
+        ));
+    }
+
+    // The payer says they've sent the money
+    for ((_, _, token), amount) in invoices.iter().zip([640.0, 210.0]) {
+        let resp = client
+            .process_guest_payment_with(token, serde_json::json!({
+                "amount": amount,
+                "payment_method": "bank_transfer",
+                "customer_name": "Sari Wijaya",
+            }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+    let (invoice_id, invoice_number, _) = &invoices[0];
+    let detail: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(detail["amount_paid"], 0.0);
+
+    let pending: Value = client.list_bank_transfers(None).await.unwrap().json().await.unwrap();
+    let pending = pending.as_array().unwrap();
+    assert_eq!(pending.len(), 2);
+    let payment = pending.iter().find(|p| p["invoice_id"] == invoice_id.as_str()).unwrap();
+    let payment_id = payment["id"].as_str().unwrap().to_string();
+    assert_eq!(payment["status"], "pending");
+
+    // The statement line is matched to the right transfer
+    let statement = format!(
+        "Date,Description,Amount\n{},TRF SARI WIJAYA {},640.00\n{},Office rent,-1200.00\n",
+        today, invoice_number.to_lowercase(), today
+    );
+    let resp = client.upload_bank_statement("statement.csv", statement.as_bytes()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["credits"], 1);
+    assert_eq!(report["matched"], 1);
+    let best = &report["lines"][0]["suggestions"][0];
+    assert_eq!(best["payment_id"], payment_id.as_str());
+    assert!(best["reasons"].as_array().unwrap().contains(&Value::from("invoice_number")));
+
+    // Confirming needs the bank's reference
+    let resp = client.reconcile_bank_transfer(&payment_id, "confirm", serde_json::json!({ "reference": " ", "received_on": today })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .reconcile_bank_transfer(&payment_id, "confirm", serde_json::json!({ "reference": "FT2603031234", "received_on": today }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let confirmed: Value = resp.json().await.unwrap();
+    assert_eq!(confirmed["status"], "completed");
+    assert_eq!(confirmed["bank_reference"], "FT2603031234");
+    assert_eq!(confirmed["balance_due"], 0.0);
+
+    let detail: Value = client.get_invoice(invoice_id).await.unwrap().json().await.unwrap();
+    assert_eq!(detail["status"], "paid");
+    assert_eq!(detail["amount_paid"], 640.0);
+
+    let resp = client
+        .reconcile_bank_transfer(&payment_id, "confirm", serde_json::json!({ "reference": "FT2603031234", "received_on": today }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+
+    // The other transfer never arrived
+    let other: Value = client.list_bank_transfers(None).await.unwrap().json().await.unwrap();
+    let other_id = other[0]["id"].as_str().unwrap().to_string();
+    let resp = client.reconcile_bank_transfer(&other_id, "reject", serde_json::json!({})).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let rejected: Value = resp.json().await.unwrap();
+    assert_eq!(rejected["status"], "failed");
+    assert_eq!(rejected["failure_reason"], "Not received");
+    assert_eq!(rejected["balance_due"], 210.0);
+
+    let pending: Value = client.list_bank_transfers(None).await.unwrap().json().await.unwrap();
+    assert!(pending.as_array().unwrap().is_empty());
+    let completed: Value = client.list_bank_transfers(Some("completed")).await.unwrap().json().await.unwrap();
+    assert_eq!(completed.as_array().unwrap().len(), 1);
+}
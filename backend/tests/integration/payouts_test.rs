@@ -0,0 +1,137 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("payout_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Payout Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_invoice(client: &ApiTestClient, amount: f64) -> String {
+    let resp = client.create_client("Payout Client", "payout@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, amount).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    invoice["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_payout_links_payments_to_invoices() {
+    let client = setup_authenticated_client().await;
+    let unique_id = crate::integration::utils::get_unique_id();
+    let first_ref = format!("PAY-{}-1", unique_id);
+    let second_ref = format!("PAY-{}-2", unique_id);
+    let pending_ref = format!("PAY-{}-3", unique_id);
+
+    let invoice_a = create_invoice(&client, 100.0).await;
+    let invoice_b = create_invoice(&client, 50.0).await;
+    let invoice_c = create_invoice(&client, 30.0).await;
+
+    for (invoice_id, amount, reference) in [
+        (&invoice_a, 100.0, &first_ref),
+        (&invoice_b, 50.0, &second_ref),
+        (&invoice_c, 30.0, &pending_ref),
+    ] {
+        let resp = client
+            .create_gateway_payment(invoice_id, amount, "paypal", reference, 1.0)
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+
+    let today = chrono::Utc::now().date_naive();
+    let resp = client
+        .record_payout(serde_json::json!({
+            "gateway": "paypal",
+            "gateway_payout_id": format!("TRANSFER-{}", unique_id),
+            "amount": 145.1,
+            "currency": "USD",
+            "status": "paid",
+            "arrival_date": today,
+            "payments": [
+                { "gateway_payment_id": first_ref, "fee": 3.2 },
+                { "gateway_payment_id": second_ref },
+                { "gateway_payment_id": "UNKNOWN-REF" }
+            ]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let payout: Value = resp.json().await.unwrap();
+    let payout_id = payout["id"].as_str().unwrap().to_string();
+
+    // Fees reported by the gateway replace the recorded ones
+    assert_eq!(payout["lines"].as_array().unwrap().len(), 2);
+    assert_eq!(payout["gross"], 150.0);
+    assert_eq!(payout["fees"], 4.2);
+    assert_eq!(payout["net"], 145.8);
+    assert_eq!(payout["difference"], -0.7);
+    assert_eq!(payout["unmatched_references"], serde_json::json!(["UNKNOWN-REF"]));
+    let invoices: Vec<&str> = payout["lines"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|line| line["invoice_id"].as_str().unwrap())
+        .collect();
+    assert!(invoices.contains(&invoice_a.as_str()));
+    assert!(invoices.contains(&invoice_b.as_str()));
+
+    let resp = client.get_payout(&payout_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // The report traces the deposit and lists payments still awaiting payout
+    let date = today.to_string();
+    let resp = client.get_payout_report(&date, &date).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["payouts"].as_array().unwrap().len(), 1);
+    assert_eq!(report["total_paid_out"], 145.1);
+    assert_eq!(report["total_fees"], 4.2);
+    let unsettled = report["unsettled"].as_array().unwrap();
+    assert_eq!(unsettled.len(), 1);
+    assert_eq!(unsettled[0]["gateway_payment_id"], pending_ref.as_str());
+    assert_eq!(report["unsettled_total"], 29.0);
+}
+
+#[tokio::test]
+async fn test_payout_errors() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_payout(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client
+        .record_payout(serde_json::json!({
+            "gateway": "",
+            "gateway_payout_id": "",
+            "amount": 10.0,
+            "status": "paid"
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_payout_report("2025-02-01", "2025-01-01").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Webhooks without a signature are rejected
+    let resp = client
+        .post_stripe_payout_webhook(r#"{"type":"payout.paid","data":{"object":{"id":"po_1"}}}"#, None)
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 401);
+}
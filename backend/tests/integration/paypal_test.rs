@@ -160,3 +160,63 @@ async fn test_paypal_refund_with_partial_amount() {
     let refund: serde_json::Value = refund_resp.json().await.unwrap();
     assert_eq!(refund["amount"], 150.00);
 }
+
+#[tokio::test]
+async fn test_paypal_webhook_rejects_unverified_deliveries() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("PayPal Client", "paypal_client@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let today = chrono::Utc::now().naive_utc().date();
+    let resp = client
+        .create_invoice_with(json!({
+            "client_id": client_data["id"],
+            "issue_date": today,
+            "due_date": today + chrono::Duration::days(30),
+            "items": [{"description": "Consulting", "quantity": 1, "unit_price": 120.0, "tax_rate": 0.0}],
+            "tax_included": false,
+            "send_immediately": true,
+        }))
+        .await
+        .unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_http_client().post(format!("{}/api/v1/paypal/create-order", get_api_base_url()))
+        .header("Authorization", format!("Bearer {}", client.get_auth_token().unwrap()))
+        .json(&json!({ "amount": 120.0, "currency": "USD", "invoice_id": invoice_id }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let order: Value = resp.json().await.unwrap();
+
+    let payload = json!({
+        "event_type": "CHECKOUT.ORDER.APPROVED",
+        "resource": { "id": order["order_id"] }
+    })
+    .to_string();
+
+    // No transmission headers at all
+    let resp = client.post_paypal_webhook(&payload, &[]).await.unwrap();
+    assert_eq!(resp.status(), 401);
+
+    // Signed with a certificate that isn't PayPal's
+    let resp = client
+        .post_paypal_webhook(&payload, &[
+            ("PAYPAL-TRANSMISSION-ID", "forged-1"),
+            ("PAYPAL-TRANSMISSION-TIME", "2026-10-18T10:00:00Z"),
+            ("PAYPAL-TRANSMISSION-SIG", "Zm9yZ2Vk"),
+            ("PAYPAL-CERT-URL", "https://attacker.example/cert.pem"),
+            ("PAYPAL-AUTH-ALGO", "SHA256withRSA"),
+        ])
+        .await
+        .unwrap();
+    assert!(resp.status().is_client_error());
+
+    // The order is still awaiting PayPal's confirmation
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    assert_ne!(invoice["status"], "paid");
+    assert_eq!(invoice["amount_paid"].as_f64().unwrap_or(0.0), 0.0);
+}
@@ -0,0 +1,140 @@
+use crate::integration::{
+    test_client::ApiTestClient,
+    utils::{create_test_pool, get_api_base_url, get_unique_id},
+};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+async fn setup_seller(company: &str) -> ApiTestClient {
+    let client = ApiTestClient::new(get_api_base_url());
+
+    let email = format!("portal_seller_{}@example.com", get_unique_id());
+    let password = "testpassword123";
+
+    client.register(&email, password, Some(company)).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+/// Bill the buyer and return the invoice ID; sent unless `draft`
+async fn bill(seller: &ApiTestClient, buyer_email: &str, amount: f64, draft: bool) -> String {
+    let resp = seller.create_client("Portal Buyer", buyer_email).await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap();
+
+    let resp = seller.create_invoice(client_id, amount).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    if !draft {
+        assert!(seller.send_invoice(&invoice_id).await.unwrap().status().is_success());
+    }
+    invoice_id
+}
+
+/// Sign-in links are only emailed, so the test stores one with a token it knows
+async fn plant_sign_in_link(email: &str) -> String {
+    let token = format!("portaltest{}", get_unique_id().replace('_', ""));
+    let pool = create_test_pool().await;
+    sqlx::query(
+        r#"
+        INSERT INTO client_sign_in_links (id, email, token_hash, expires_at, created_at)
+        VALUES ($1, $2, $3, NOW() + INTERVAL '10 minutes', NOW())
+        "#,
+    )
+    .bind(uuid::Uuid::new_v4())
+    .bind(email)
+    .bind(hex::encode(Sha256::digest(token.as_bytes())))
+    .execute(&pool)
+    .await
+    .unwrap();
+    token
+}
+
+#[tokio::test]
+async fn test_buyer_sees_invoices_from_every_seller() {
+    let buyer_email = format!("portal_buyer_{}@example.com", get_unique_id());
+    let acme = setup_seller("Acme Studio").await;
+    let globex = setup_seller("Globex Supply").await;
+
+    let acme_invoice = bill(&acme, &buyer_email, 300.0, false).await;
+    let acme_draft = bill(&acme, &buyer_email, 50.0, true).await;
+    let globex_invoice = bill(&globex, &buyer_email.to_uppercase(), 120.0, false).await;
+    let someone_else = bill(&globex, &format!("other_{}", buyer_email), 75.0, false).await;
+
+    // Requests are accepted whether or not the email is billed anywhere
+    let anon = ApiTestClient::new(get_api_base_url());
+    assert_eq!(anon.request_portal_link(&buyer_email).await.unwrap().status(), 202);
+    assert_eq!(anon.request_portal_link("nobody_billed@example.com").await.unwrap().status(), 202);
+    assert_eq!(anon.request_portal_link("not-an-email").await.unwrap().status(), 400);
+    assert_eq!(anon.portal_sign_in_with_link("made-up-token").await.unwrap().status(), 401);
+
+    // The first link creates the account
+    let token = plant_sign_in_link(&buyer_email).await;
+    let resp = anon.portal_sign_in_with_link(&token).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let session: Value = resp.json().await.unwrap();
+    assert_eq!(session["account"]["email"], buyer_email.as_str());
+    assert_eq!(session["account"]["has_password"], false);
+    assert_eq!(session["account"]["vendors"].as_array().unwrap().len(), 2);
+    assert_eq!(anon.portal_sign_in_with_link(&token).await.unwrap().status(), 401);
+
+    let mut buyer = anon.clone();
+    buyer.set_token(session["access_token"].as_str().unwrap().to_string());
+
+    // Issued invoices from both sellers; not drafts or other buyers' invoices
+    let resp = buyer.get_portal("/invoices").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let invoices: Value = resp.json().await.unwrap();
+    let ids: Vec<&str> = invoices.as_array().unwrap().iter().map(|i| i["id"].as_str().unwrap()).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&acme_invoice.as_str()) && ids.contains(&globex_invoice.as_str()));
+
+    let resp = buyer.get_portal(&format!("/invoices/{}", acme_invoice)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["vendor"]["name"], "Acme Studio");
+    assert_eq!(detail["invoice"]["id"], acme_invoice.as_str());
+    for hidden in [&acme_draft, &someone_else] {
+        assert_eq!(buyer.get_portal(&format!("/invoices/{}", hidden)).await.unwrap().status(), 404);
+    }
+
+    let resp = buyer.get_portal(&format!("/invoices/{}/pdf", globex_invoice)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-type"], "application/pdf");
+
+    // Portal and seller tokens each only work on their own side
+    assert_eq!(buyer.list_invoices().await.unwrap().status(), 403);
+    assert_eq!(acme.get_portal("/invoices").await.unwrap().status(), 403);
+}
+
+#[tokio::test]
+async fn test_buyer_can_set_a_password() {
+    let buyer_email = format!("portal_pw_{}@example.com", get_unique_id());
+    let seller = setup_seller("Initech").await;
+    bill(&seller, &buyer_email, 90.0, false).await;
+
+    let anon = ApiTestClient::new(get_api_base_url());
+    assert_eq!(anon.portal_sign_in(&buyer_email, "whatever123").await.unwrap().status(), 401);
+
+    let token = plant_sign_in_link(&buyer_email).await;
+    let session: Value = anon.portal_sign_in_with_link(&token).await.unwrap().json().await.unwrap();
+    let mut buyer = anon.clone();
+    buyer.set_token(session["access_token"].as_str().unwrap().to_string());
+
+    assert_eq!(buyer.set_portal_password("short").await.unwrap().status(), 400);
+    let resp = buyer.set_portal_password("correct horse battery").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let account: Value = resp.json().await.unwrap();
+    assert_eq!(account["has_password"], true);
+
+    assert_eq!(anon.portal_sign_in(&buyer_email, "wrong password").await.unwrap().status(), 401);
+    let resp = anon.portal_sign_in(&buyer_email.to_uppercase(), "correct horse battery").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let session: Value = resp.json().await.unwrap();
+    assert_eq!(session["account"]["id"], account["id"]);
+}
@@ -0,0 +1,102 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("profitability_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Margin Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_invoice_profitability_with_linked_costs() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Margin Client", "margin@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 1000.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    client.send_invoice(&invoice_id).await.unwrap();
+
+    let resp = client.create_expense(150.0, "supplies", "Paper Co").await.unwrap();
+    let expense: Value = resp.json().await.unwrap();
+    let expense_id = expense["id"].as_str().unwrap().to_string();
+
+    let resp = client
+        .add_invoice_cost(&invoice_id, json!({ "kind": "expense", "expense_id": expense_id }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let cost: Value = resp.json().await.unwrap();
+    assert_eq!(cost["amount"], 150.0);
+
+    let resp = client
+        .add_invoice_cost(
+            &invoice_id,
+            json!({ "kind": "time", "description": "Design work", "hours": 4, "hourly_cost": 50 }),
+        )
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let time_cost: Value = resp.json().await.unwrap();
+    assert_eq!(time_cost["amount"], 200.0);
+
+    let resp = client.get_invoice_profitability(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["revenue"], 1000.0);
+    assert_eq!(report["expense_costs"], 150.0);
+    assert_eq!(report["time_costs"], 200.0);
+    assert_eq!(report["direct_costs"], 350.0);
+    assert_eq!(report["margin"], 650.0);
+    assert_eq!(report["margin_percent"], 65.0);
+    assert_eq!(report["costs"].as_array().unwrap().len(), 2);
+
+    // An expense counts against one invoice only
+    let resp = client.create_invoice(&client_id, 500.0).await.unwrap();
+    let other: Value = resp.json().await.unwrap();
+    let resp = client
+        .add_invoice_cost(other["id"].as_str().unwrap(), json!({ "kind": "expense", "expense_id": expense_id }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .add_invoice_cost(&invoice_id, json!({ "kind": "time", "hours": 0, "hourly_cost": 50 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .remove_invoice_cost(&invoice_id, time_cost["id"].as_str().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 204);
+
+    let today = chrono::Utc::now().naive_utc().date().to_string();
+    let resp = client.get_margin_by_client(&today, &today).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let margins: Value = resp.json().await.unwrap();
+    // The draft second invoice is left out
+    let clients = margins["clients"].as_array().unwrap();
+    assert_eq!(clients.len(), 1);
+    assert_eq!(clients[0]["client_id"], client_id.as_str());
+    assert_eq!(clients[0]["invoice_count"], 1);
+    assert_eq!(clients[0]["direct_costs"], 150.0);
+    assert_eq!(clients[0]["margin"], 850.0);
+    assert_eq!(margins["total"]["margin"], 850.0);
+}
@@ -0,0 +1,175 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("project_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Project Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient, name: &str) -> String {
+    let resp = client.create_client(name, &format!("{}@test.com", name.to_lowercase().replace(' ', "."))).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_project_management_and_tagging() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Project Client").await;
+    let other_client_id = create_client_id(&client, "Other Client").await;
+
+    let resp = client
+        .create_project(json!({ "client_id": client_id, "name": " Website ", "budget": 5000, "hourly_rate": 120 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let project: Value = resp.json().await.unwrap();
+    let project_id = project["id"].as_str().unwrap().to_string();
+    assert_eq!(project["name"], "Website");
+    assert_eq!(project["status"], "active");
+    assert_eq!(project["budget"], 5000.0);
+
+    // Names are unique per client, amounts can't be negative, and the client must be the user's
+    let resp = client.create_project(json!({ "client_id": client_id, "name": "website" })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_project(json!({ "client_id": client_id, "name": "App", "budget": -1 })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_project(json!({ "client_id": uuid::Uuid::new_v4(), "name": "App" })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_project(json!({ "client_id": other_client_id, "name": "Website" })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client.list_projects(&format!("client_id={}", client_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let projects: Value = resp.json().await.unwrap();
+    assert_eq!(projects.as_array().unwrap().len(), 1);
+
+    // Invoices and time only go on their own client's projects
+    let resp = client.create_project_invoice(&other_client_id, 100.0, &project_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.create_project_invoice(&client_id, 100.0, &project_id).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["project_id"], project_id);
+
+    let resp = client.create_invoice(&other_client_id, 100.0).await.unwrap();
+    let other: Value = resp.json().await.unwrap();
+    let resp = client.set_project("invoices", other["id"].as_str().unwrap(), Some(&project_id)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .start_timer(json!({ "client_id": other_client_id, "description": "Wrong", "project_id": project_id }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Untagging, then a project that is no longer active takes no new work
+    let resp = client.set_project("invoices", &invoice_id, None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = client.update_project(&project_id, json!({ "status": "completed", "budget": 0 })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["status"], "completed");
+    assert!(updated["budget"].is_null());
+    let resp = client.set_project("invoices", &invoice_id, Some(&project_id)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.list_projects("status=active").await.unwrap();
+    let projects: Value = resp.json().await.unwrap();
+    assert_eq!(projects.as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_project_profitability_and_budget() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Budget Client").await;
+
+    let resp = client
+        .create_project(json!({ "client_id": client_id, "name": "Relaunch", "budget": 2000, "hourly_rate": 100 }))
+        .await
+        .unwrap();
+    let project: Value = resp.json().await.unwrap();
+    let project_id = project["id"].as_str().unwrap().to_string();
+
+    // Issued invoices count as billed, drafts don't
+    let resp = client.create_project_invoice(&client_id, 1000.0, &project_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    client.send_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    let resp = client.create_project_invoice(&client_id, 300.0, &project_id).await.unwrap();
+    assert_eq!(resp.status(), 201);
+
+    let resp = client.create_expense(150.0, "supplies", "Paper Co").await.unwrap();
+    let expense: Value = resp.json().await.unwrap();
+    let expense_id = expense["id"].as_str().unwrap().to_string();
+    let resp = client.set_project("expenses", &expense_id, Some(&project_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // Tracked time bills at the project's rate
+    let resp = client
+        .create_time_entry(json!({
+            "client_id": client_id,
+            "project_id": project_id,
+            "description": "Build",
+            "started_at": "2025-03-03T09:00:00Z",
+            "duration_minutes": 120,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let entry: Value = resp.json().await.unwrap();
+    assert_eq!(entry["project_id"], project_id);
+
+    let resp = client.get_project_profitability(&project_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["revenue"], 1000.0);
+    assert_eq!(report["expense_costs"], 150.0);
+    assert_eq!(report["margin"], 850.0);
+    assert_eq!(report["tracked_hours"], 2.0);
+    assert_eq!(report["unbilled_amount"], 200.0);
+    assert_eq!(report["budget_used"], 1200.0);
+    assert_eq!(report["remaining_budget"], 800.0);
+    assert_eq!(report["budget_used_percent"], 60.0);
+
+    let resp = client
+        .create_invoice_from_time(json!({ "client_id": client_id, "project_id": project_id }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let from_time_id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["subtotal"], 200.0);
+    let resp = client.get_invoice(&from_time_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert_eq!(detail["project_id"], project_id);
+
+    let resp = client.get_project_profitability(&project_id).await.unwrap();
+    let report: Value = resp.json().await.unwrap();
+    assert_eq!(report["unbilled_hours"], 0.0);
+    assert_eq!(report["budget_used"], 1000.0);
+
+    // Deleting the project untags its work
+    let resp = client.delete_project(&project_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.get_invoice(&from_time_id).await.unwrap();
+    let detail: Value = resp.json().await.unwrap();
+    assert!(detail["project_id"].is_null());
+    let resp = client.get_project_profitability(&project_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
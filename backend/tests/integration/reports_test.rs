@@ -34,7 +34,7 @@ async fn setup_authenticated_client_with_data() -> ApiTestClient {
     // Payments
     let resp = authed_client.list_invoices().await.unwrap();
     let invoices: Value = resp.json().await.unwrap();
-    if let Some(invoice) = invoices.as_array().and_then(|arr| arr.first()) {
+    if let Some(invoice) = invoices["items"].as_array().and_then(|arr| arr.first()) {
         let invoice_id = invoice["id"].as_str().unwrap();
         authed_client.record_payment(invoice_id, 1000.0).await.unwrap();
     }
@@ -59,6 +59,39 @@ async fn test_overview_stats() {
     assert!(stats["net_profit"].is_number());
 }
 
+#[tokio::test]
+async fn test_overview_reflects_new_payment() {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+    let email = format!("report_cache_test_{}@example.com", crate::integration::utils::get_unique_id());
+    client.register(&email, "testpassword123", Some("Report Cache Company")).await.unwrap();
+    let resp = client.login(&email, "testpassword123").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let mut client = client.clone();
+    client.set_token(data["access_token"].as_str().unwrap().to_string());
+
+    let resp = client.create_client("Report Cache Client", "report-cache@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let resp = client.create_invoice(client_data["id"].as_str().unwrap(), 500.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap();
+    client.send_invoice(invoice_id).await.unwrap();
+
+    // Cache the overview, then pay: the next read must not be the cached one
+    let resp = client.get_overview_stats().await.unwrap();
+    let stats: Value = resp.json().await.unwrap();
+    assert_eq!(stats["paid_invoices"], 0);
+
+    let total = invoice["total_amount"].as_f64().unwrap();
+    let resp = client.record_payment(invoice_id, total).await.unwrap();
+    assert!(resp.status().is_success());
+
+    let resp = client.get_overview_stats().await.unwrap();
+    let stats: Value = resp.json().await.unwrap();
+    assert_eq!(stats["paid_invoices"], 1);
+    assert_eq!(stats["total_revenue"].as_f64().unwrap(), total);
+}
+
 #[tokio::test]
 async fn test_income_report() {
     let client = setup_authenticated_client_with_data().await;
@@ -95,6 +128,7 @@ async fn test_tax_report() {
 
     assert!(report["total_tax_collected"].is_number());
     assert!(report["total_tax_deductible"].is_number());
+    assert!(report["net_tax_payable"].is_number());
     assert!(report["by_state"].is_array());
 }
 
@@ -113,6 +147,152 @@ async fn test_aging_report() {
     assert!(report["over_ninety_days"].is_number());
 }
 
+#[tokio::test]
+async fn test_aging_trend() {
+    let client = setup_authenticated_client_with_data().await;
+
+    let resp = client.get_aging_trend(None).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let trend: Value = resp.json().await.unwrap();
+
+    assert_eq!(trend["months"], 12);
+    let points = trend["points"].as_array().unwrap();
+    assert!(!points.is_empty() && points.len() <= 12);
+
+    // The current month is always the live aging report
+    let latest = points.last().unwrap();
+    let resp = client.get_aging_report().await.unwrap();
+    let aging: Value = resp.json().await.unwrap();
+    assert_eq!(latest["current"], aging["current"]);
+    assert_eq!(latest["over_ninety_days"], aging["over_ninety_days"]);
+    assert!(latest["total_outstanding"].is_number());
+    assert!(latest["overdue_share"].is_number());
+
+    let resp = client.get_aging_trend(Some(0)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.get_aging_trend(Some(120)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_cashflow_forecast() {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+    let email = format!("cashflow_test_{}@example.com", crate::integration::utils::get_unique_id());
+    client.register(&email, "testpassword123", Some("Cashflow Company")).await.unwrap();
+    let resp = client.login(&email, "testpassword123").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let mut client = client.clone();
+    client.set_token(data["access_token"].as_str().unwrap().to_string());
+
+    // One sent invoice due in 30 days, and rent paid monthly for the last three months
+    let resp = client.create_client("Cashflow Client", "cashflow@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let resp = client.create_invoice(client_data["id"].as_str().unwrap(), 1000.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let resp = client.send_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let today = chrono::Utc::now().date_naive();
+    for months in 1..=3 {
+        let resp = client
+            .create_expense_with(serde_json::json!({
+                "amount": 800.0,
+                "category": "other",
+                "vendor": "Landlord",
+                "description": "Office rent",
+                "date_incurred": today - chrono::Months::new(months),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+
+    let resp = client.get_cashflow_forecast().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let forecast: Value = resp.json().await.unwrap();
+    assert_eq!(forecast["as_of"], today.to_string());
+
+    let horizons = forecast["horizons"].as_array().unwrap();
+    let days: Vec<i64> = horizons.iter().map(|h| h["days"].as_i64().unwrap()).collect();
+    assert_eq!(days, vec![30, 60, 90]);
+    assert_eq!(horizons[0]["open_invoices"], 1000.0);
+    assert_eq!(horizons[2]["recurring_expenses"], 2400.0);
+    assert_eq!(horizons[2]["net"], -1400.0);
+
+    let items = forecast["items"].as_array().unwrap();
+    let open = items.iter().find(|item| item["source"] == "open_invoice").unwrap();
+    assert_eq!(open["direction"], "in");
+    assert_eq!(open["invoice_id"], invoice["id"]);
+    let rent: Vec<&Value> = items.iter().filter(|item| item["source"] == "recurring_expense").collect();
+    assert_eq!(rent.len(), 3);
+    assert!(rent.iter().all(|item| item["label"] == "Landlord" && item["direction"] == "out"));
+}
+
+#[tokio::test]
+async fn test_timeseries() {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+    let email = format!("timeseries_test_{}@example.com", crate::integration::utils::get_unique_id());
+    client.register(&email, "testpassword123", Some("Timeseries Company")).await.unwrap();
+    let resp = client.login(&email, "testpassword123").await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let mut client = client.clone();
+    client.set_token(data["access_token"].as_str().unwrap().to_string());
+
+    let today = chrono::Utc::now().date_naive();
+    for months in 1..=3 {
+        let resp = client
+            .create_expense_with(serde_json::json!({
+                "amount": 250.0,
+                "category": "other",
+                "description": "Hosting",
+                "date_incurred": today - chrono::Months::new(months),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+    let resp = client.create_client("Timeseries Client", "timeseries@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let resp = client.create_invoice(client_data["id"].as_str().unwrap(), 600.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let resp = client.send_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    // Monthly expenses: one per past month, none yet this month
+    let start = today - chrono::Months::new(3);
+    let resp = client
+        .get_timeseries(&format!("metric=expenses&interval=month&start={}&end={}", start, today))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let series: Value = resp.json().await.unwrap();
+    assert_eq!(series["metric"], "expenses");
+    let values: Vec<f64> = series["points"].as_array().unwrap().iter().map(|p| p["value"].as_f64().unwrap()).collect();
+    assert_eq!(values, vec![250.0, 250.0, 250.0, 0.0]);
+    assert_eq!(series["points"][3]["bucket_end"], today.to_string());
+
+    // Outstanding carries the sent invoice from its issue date on
+    let resp = client
+        .get_timeseries(&format!("metric=outstanding&interval=day&start={}&end={}", today - chrono::Days::new(6), today))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let series: Value = resp.json().await.unwrap();
+    let points = series["points"].as_array().unwrap();
+    assert_eq!(points.len(), 7);
+    assert_eq!(points[6]["value"], 600.0);
+
+    let resp = client.get_timeseries(&format!("metric=profit&start={}&end={}", start, today)).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .get_timeseries(&format!("metric=revenue&interval=day&start={}&end={}", today - chrono::Days::new(400), today))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
 #[tokio::test]
 async fn test_export_report() {
     let client = setup_authenticated_client_with_data().await;
@@ -125,3 +305,114 @@ async fn test_export_report() {
     assert!(export["download_url"].is_string());
     assert!(export["expires_at"].is_string());
 }
+
+#[tokio::test]
+async fn test_sla_report() {
+    let client = setup_authenticated_client_with_data().await;
+
+    let resp = client.create_client("SLA Client", "sla@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 750.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let resp = client.send_invoice(invoice["id"].as_str().unwrap()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .update_invoice_settings_with(serde_json::json!({
+            "template": "default",
+            "terms": "Net 30",
+            "notes": "",
+            "sla_view_hours": 0.0001,
+            "sla_payment_days": 14,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["sla_view_hours"], 0.0001);
+    assert_eq!(settings["sla_payment_days"], 14.0);
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    let today = chrono::Utc::now().date_naive();
+    let query = format!(
+        "start_date={}&end_date={}",
+        today - chrono::Duration::days(1),
+        today + chrono::Duration::days(1)
+    );
+    let resp = client.get_with_query("/reports/sla", &query).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let report: Value = resp.json().await.unwrap();
+
+    assert_eq!(report["targets"]["view_hours"], 0.0001);
+    assert!(report["overall"]["sent_invoices"].as_i64().unwrap() >= 1);
+    assert!(report["overall"]["view_breaches"].as_i64().unwrap() >= 1);
+    assert_eq!(report["overall"]["payment_breaches"], 0);
+    let sla_client = report["by_client"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|row| row["client_id"] == client_id.as_str())
+        .expect("sent client should be listed");
+    assert_eq!(sla_client["client_name"], "SLA Client");
+    assert_eq!(sla_client["sent_invoices"], 1);
+    assert_eq!(sla_client["viewed_invoices"], 0);
+    assert!(sla_client["view_hours_p50"].is_null());
+
+    // The dashboard highlights the unviewed invoice
+    let resp = client.get_overview_stats().await.unwrap();
+    let stats: Value = resp.json().await.unwrap();
+    assert!(stats["sla_breaches"]["unviewed_invoices"].as_i64().unwrap() >= 1);
+
+    let resp = client
+        .update_invoice_settings_with(serde_json::json!({
+            "template": "default",
+            "terms": "Net 30",
+            "notes": "",
+            "sla_payment_days": 0,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_with_query("/reports/sla", "start_date=bad&end_date=2025-12-31").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_custom_report_csv() {
+    let client = setup_authenticated_client_with_data().await;
+
+    let resp = client
+        .custom_report(serde_json::json!({
+            "entity": "expenses",
+            "columns": ["category", "count", "amount"],
+            "group_by": "category",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers()["content-type"].to_str().unwrap().starts_with("text/csv"));
+    let csv = resp.text().await.unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines, vec!["Category,Count,Amount", "office_supplies,1,500.00", "travel,1,300.00"]);
+
+    let resp = client
+        .custom_report(serde_json::json!({
+            "entity": "invoices",
+            "columns": ["client_name", "total_amount"],
+            "filters": [{ "column": "total_amount", "op": "gt", "value": 1500 }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let csv = resp.text().await.unwrap();
+    assert_eq!(csv.lines().collect::<Vec<_>>(), vec!["Client,Total", "Report Client,2000.00"]);
+
+    // Columns outside the whitelist are rejected before anything is streamed
+    let resp = client
+        .custom_report(serde_json::json!({ "entity": "invoices", "columns": ["user_id"] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
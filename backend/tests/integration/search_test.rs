@@ -0,0 +1,85 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("search_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Search Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn search(client: &ApiTestClient, query: &str) -> Value {
+    let resp = client.get_with_query("/search", query).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_search_groups_matches_by_type() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Kopi Nusantara", "orders@kopi-nusantara.test").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 450.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+    let invoice_number = invoice["invoice_number"].as_str().unwrap().to_string();
+    client.update_invoice(&invoice_id, "Monthly roasting workshop in Bandung").await.unwrap();
+
+    client.create_expense(120.0, "travel", "Nusantara Airlines").await.unwrap();
+
+    // Prefixes of the client's name and the expense's vendor
+    let results = search(&client, "q=nusan").await;
+    assert_eq!(results["query"], "nusan");
+    assert_eq!(results["invoices"]["total"], 0);
+    assert_eq!(results["clients"]["total"], 1);
+    assert_eq!(results["clients"]["items"][0]["id"], client_id.as_str());
+    assert_eq!(results["expenses"]["total"], 1);
+    assert_eq!(results["expenses"]["items"][0]["vendor"], "Nusantara Airlines");
+
+    // Every word has to match; the snippet marks the words in the notes
+    let results = search(&client, "q=roast%20bandung").await;
+    assert_eq!(results["invoices"]["total"], 1);
+    let hit = &results["invoices"]["items"][0];
+    assert_eq!(hit["id"], invoice_id.as_str());
+    assert_eq!(hit["client_name"], "Kopi Nusantara");
+    let snippet = hit["snippet"].as_str().unwrap();
+    assert!(snippet.contains("**roasting**") && snippet.contains("**Bandung**"), "{}", snippet);
+    assert_eq!(search(&client, "q=roast%20jakarta").await["invoices"]["total"], 0);
+
+    // The invoice number, punctuation and all
+    let results = search(&client, &format!("q={}&types=invoices", invoice_number)).await;
+    assert_eq!(results["invoices"]["items"][0]["id"], invoice_id.as_str());
+    assert!(results["invoices"]["items"][0]["snippet"].is_null());
+    assert!(results.get("clients").is_none());
+    assert!(results.get("expenses").is_none());
+
+    // Another account doesn't see any of it
+    let other = setup_authenticated_client().await;
+    let results = search(&other, "q=nusan").await;
+    assert_eq!(results["clients"]["total"], 0);
+    assert_eq!(results["expenses"]["total"], 0);
+}
+
+#[tokio::test]
+async fn test_search_rejects_bad_queries() {
+    let client = setup_authenticated_client().await;
+
+    for query in ["q=", "q=%20--%20", "q=acme&types=payments", "q=acme&limit=0"] {
+        let resp = client.get_with_query("/search", query).await.unwrap();
+        assert_eq!(resp.status(), 400, "{}", query);
+    }
+}
@@ -88,6 +88,51 @@ async fn test_notification_settings() {
     assert_eq!(verify["email_payment_received"], true);
 }
 
+#[tokio::test]
+async fn test_reminder_schedule_settings() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_notification_settings().await.unwrap();
+    let initial: Value = resp.json().await.unwrap();
+    assert_eq!(initial["reminder_days"], serde_json::json!([1, 7, 14, 30]));
+    assert_eq!(initial["whatsapp_payment_reminder"], false);
+
+    // The schedule is stored sorted, without repeats
+    let resp = client
+        .update_notification_settings_with(serde_json::json!({
+            "email_payment_received": true,
+            "email_invoice_paid": true,
+            "email_payment_reminder": true,
+            "push_payment_received": true,
+            "push_overdue": true,
+            "whatsapp_payment_reminder": true,
+            "reminder_days": [14, 0, 3, 14],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["reminder_days"], serde_json::json!([0, 3, 14]));
+    assert_eq!(updated["whatsapp_payment_reminder"], true);
+
+    let resp = client
+        .update_notification_settings_with(serde_json::json!({
+            "email_payment_received": true,
+            "email_invoice_paid": true,
+            "email_payment_reminder": true,
+            "push_payment_received": true,
+            "push_overdue": true,
+            "reminder_days": [400],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_notification_settings().await.unwrap();
+    let verify: Value = resp.json().await.unwrap();
+    assert_eq!(verify["reminder_days"], serde_json::json!([0, 3, 14]));
+}
+
 #[tokio::test]
 async fn test_invoice_settings() {
     let client = setup_authenticated_client().await;
@@ -161,3 +206,426 @@ async fn test_settings_persistence() {
     assert_eq!(tax["tax_settings"]["state_code"], "TX");
     assert_eq!(invoice["terms"], "Net 30");
 }
+
+#[tokio::test]
+async fn test_document_number_sequences() {
+    let client = setup_authenticated_client().await;
+    let year = chrono::Utc::now().format("%Y").to_string();
+
+    // Every document type has its own sequence with a default prefix
+    let resp = client.get_document_number_formats().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let formats: Value = resp.json().await.unwrap();
+    let formats = formats.as_array().unwrap();
+    assert_eq!(formats.len(), 4);
+    let quote = formats.iter().find(|f| f["doc_type"] == "quote").unwrap();
+    assert_eq!(quote["next_preview"], format!("QUO-{}-0001", year));
+
+    // Customize the invoice sequence
+    let resp = client
+        .update_document_number_format("invoice", serde_json::json!({ "prefix": "acme", "next_number": 5 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let format: Value = resp.json().await.unwrap();
+    assert_eq!(format["prefix"], "ACME");
+    assert_eq!(format["next_preview"], format!("ACME-{}-0005", year));
+
+    // New invoices draw from the customized sequence
+    let resp = client.create_client("Numbering Client", "numbering@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 100.0).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let invoice: Value = resp.json().await.unwrap();
+    assert_eq!(invoice["invoice_number"], format!("ACME-{}-0005", year));
+
+    // Sequences never move backwards
+    let resp = client
+        .update_document_number_format("invoice", serde_json::json!({ "next_number": 2 }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .update_document_number_format("receipt", serde_json::json!({ "prefix": "RC" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_invoice_numbering_in_invoice_settings() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_invoice_settings().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["numbering"]["prefix"], "INV");
+    assert_eq!(settings["numbering"]["yearly_reset"], true);
+
+    // Continuous numbering drops the year from the number
+    let resp = client
+        .put_invoice_settings(serde_json::json!({
+            "template": "modern",
+            "terms": "Net 30",
+            "notes": "",
+            "numbering": { "prefix": "fb", "padding": 5, "yearly_reset": false, "next_number": 7 }
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["terms"], "Net 30");
+    assert_eq!(settings["numbering"]["yearly_reset"], false);
+    assert_eq!(settings["numbering"]["next_preview"], "FB-00007");
+
+    let resp = client.create_client("Sequence Client", "sequence@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+
+    // Concurrent creates are numbered one after another without gaps
+    let creates = (0..4).map(|_| client.create_invoice(&client_id, 50.0));
+    let mut numbers: Vec<String> = Vec::new();
+    for resp in futures::future::join_all(creates).await {
+        let resp = resp.unwrap();
+        assert_eq!(resp.status(), 201);
+        let invoice: Value = resp.json().await.unwrap();
+        numbers.push(invoice["invoice_number"].as_str().unwrap().to_string());
+    }
+    numbers.sort();
+    assert_eq!(numbers, ["FB-00007", "FB-00008", "FB-00009", "FB-00010"]);
+
+    // Leaving numbering out keeps it as is
+    let resp = client.update_invoice_settings("classic", "Net 15", "").await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["numbering"]["next_preview"], "FB-00011");
+}
+
+#[tokio::test]
+async fn test_email_signature_default_and_member_override() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_email_signature().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert!(settings["effective"].is_null());
+
+    let resp = client
+        .set_email_signature(false, serde_json::json!({
+            "name": "  Test Company Billing ",
+            "phone": "+1 555 0100",
+            "links": [{ "label": "Website", "url": "https://example.com" }],
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let default: Value = resp.json().await.unwrap();
+    assert_eq!(default["name"], "Test Company Billing");
+
+    let resp = client
+        .set_email_signature(true, serde_json::json!({ "name": "Jane Doe", "title": "Account Manager" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client.get_email_signature().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["default"]["name"], "Test Company Billing");
+    assert_eq!(settings["member"]["title"], "Account Manager");
+    assert_eq!(settings["effective"]["name"], "Jane Doe");
+
+    // Without an override the business default applies again
+    let resp = client.delete_email_signature(true).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.get_email_signature().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert!(settings["member"].is_null());
+    assert_eq!(settings["effective"]["name"], "Test Company Billing");
+
+    let resp = client.delete_email_signature(true).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client
+        .set_email_signature(false, serde_json::json!({ "links": [{ "label": "Site", "url": "ftp://example.com" }] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.set_email_signature(false, serde_json::json!({ "name": " " })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_email_template_override_and_preview() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_email_templates().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let templates: Vec<Value> = resp.json().await.unwrap();
+    let invoice = templates.iter().find(|t| t["kind"] == "invoice").unwrap();
+    assert!(invoice["customizable"].as_bool().unwrap());
+    assert!(invoice["custom"].is_null());
+    assert!(invoice["variables"].as_array().unwrap().iter().any(|v| v == "client_name"));
+
+    // Unsaved templates can be tried out
+    let resp = client
+        .preview_email_template(serde_json::json!({
+            "kind": "invoice",
+            "subject": "{{business_name}} invoice {{invoice_number}}",
+            "body": "<p>Dear {{client_name}}</p>",
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let preview: Value = resp.json().await.unwrap();
+    assert!(preview["subject"].as_str().unwrap().ends_with("invoice INV-2025-0042"));
+    assert_eq!(preview["html_body"], "<p>Dear Jane Client</p>");
+
+    let resp = client
+        .set_email_template("payment_reminder", serde_json::json!({ "subject": " Reminder: {{invoice_number}} " }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let saved: Value = resp.json().await.unwrap();
+    assert_eq!(saved["subject"], "Reminder: {{invoice_number}}");
+    assert!(saved["body"].is_null());
+
+    // The saved subject is used when the preview leaves it out
+    let resp = client.preview_email_template(serde_json::json!({ "kind": "payment_reminder" })).await.unwrap();
+    let preview: Value = resp.json().await.unwrap();
+    assert_eq!(preview["subject"], "Reminder: INV-2025-0042");
+    assert!(preview["html_body"].as_str().unwrap().contains("Days Overdue"));
+
+    // Unknown placeholders, account emails and unknown kinds are refused
+    let resp = client
+        .set_email_template("invoice", serde_json::json!({ "subject": "{{invoice_numbr}}" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .preview_email_template(serde_json::json!({ "kind": "invoice", "body": "{{#if message}}" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .set_email_template("password_reset", serde_json::json!({ "subject": "Reset" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.set_email_template("statement", serde_json::json!({ "subject": "x" })).await.unwrap();
+    assert_eq!(resp.status(), 404);
+
+    let resp = client.delete_email_template("payment_reminder").await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.delete_email_template("payment_reminder").await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_locale_setting_translates_email_previews() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_business_settings().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["locale"], "en");
+
+    let resp = client.set_locale("de").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["locale"], "de");
+    assert_eq!(settings["company_name"], "Test Company");
+
+    let resp = client.preview_email_template(serde_json::json!({ "kind": "invoice" })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let preview: Value = resp.json().await.unwrap();
+    assert_eq!(preview["subject"], "Rechnung Nr. INV-2025-0042 - 1.250,00 $");
+    let body = preview["html_body"].as_str().unwrap();
+    assert!(body.contains("Hallo Jane Client,"));
+    assert!(body.contains("31.05.2025"));
+
+    // Unsupported languages are refused rather than silently ignored
+    let resp = client.set_locale("fr").await.unwrap();
+    assert!(resp.status().is_client_error());
+    let resp = client.get_business_settings().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["locale"], "de");
+}
+
+#[tokio::test]
+async fn test_template_bundle_export_and_import() {
+    let source = setup_authenticated_client().await;
+    let resp = source.update_invoice_settings("premium", "Net {{due_days}}", "Thanks!").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let resp = source
+        .set_email_signature(false, serde_json::json!({ "name": "Billing Team" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = source.export_template_bundle().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers()["content-disposition"].to_str().unwrap().starts_with("attachment"));
+    let bundle: Value = resp.json().await.unwrap();
+    assert_eq!(bundle["format"], "flashbill.template-bundle");
+    assert_eq!(bundle["invoice"]["template"], "premium");
+    assert_eq!(bundle["email"]["signature"]["name"], "Billing Team");
+
+    // Another account picks up the look but keeps its own account settings
+    let target = setup_authenticated_client().await;
+    let resp = target.import_template_bundle(bundle.clone()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = target.get_invoice_settings().await.unwrap();
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["template"], "premium");
+    assert_eq!(settings["terms"], "Net {{due_days}}");
+    assert_eq!(settings["notes"], "Thanks!");
+
+    let resp = target.get_email_signature().await.unwrap();
+    let signatures: Value = resp.json().await.unwrap();
+    assert_eq!(signatures["default"]["name"], "Billing Team");
+
+    let mut unknown_variable = bundle.clone();
+    unknown_variable["invoice"]["terms"] = serde_json::json!("Pay to {{bank_account}}");
+    let resp = target.import_template_bundle(unknown_variable).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let mut wrong_format = bundle;
+    wrong_format["format"] = serde_json::json!("something-else");
+    let resp = target.import_template_bundle(wrong_format).await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+fn has_image(pdf: &[u8]) -> bool {
+    pdf.windows(b"/Subtype/Image".len()).any(|w| w == b"/Subtype/Image")
+}
+
+#[tokio::test]
+async fn test_pdf_template_and_branding() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .put_invoice_settings(serde_json::json!({
+            "template": "modern",
+            "terms": "Net 30",
+            "notes": "",
+            "accent_color": "#0F766E",
+            "footer_text": "Acme Ltd - Company no. 123456"
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert_eq!(settings["template"], "modern");
+    assert_eq!(settings["accent_color"], "#0F766E");
+    assert_eq!(settings["footer_text"], "Acme Ltd - Company no. 123456");
+    assert_eq!(settings["templates"], serde_json::json!(["classic", "modern", "minimal"]));
+
+    let resp = client
+        .put_invoice_settings(serde_json::json!({ "template": "modern", "terms": "", "notes": "", "accent_color": "teal" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Logos must be images the PDF can embed
+    let resp = client.upload_invoice_logo("logo.txt", "text/plain", b"not a logo").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgb8(image::RgbImage::from_pixel(60, 20, image::Rgb([15, 118, 110])))
+        .write_to(&mut png, image::ImageFormat::Png)
+        .unwrap();
+    let resp = client.upload_invoice_logo("logo.png", "image/png", png.get_ref()).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert!(settings["logo_url"].as_str().unwrap().starts_with("/api/v1/files/download/"));
+    assert_eq!(settings["accent_color"], "#0F766E");
+
+    let resp = client.create_client("Branded Client", "branded@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 120.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let pdf = resp.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    assert!(has_image(&pdf));
+
+    // Report exports use the same template and logo
+    let resp = client.export_report("income", "pdf", "2026-01-01", "2026-12-31").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let pdf = resp.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF"));
+    assert!(has_image(&pdf));
+
+    // Removing the logo takes it off the next render
+    let resp = client.remove_invoice_logo().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let settings: Value = resp.json().await.unwrap();
+    assert!(settings["logo_url"].is_null());
+
+    let resp = client.get_invoice_pdf(&invoice_id).await.unwrap();
+    assert_eq!(resp.headers()["x-pdf-cache"], "miss");
+    let pdf = resp.bytes().await.unwrap();
+    assert!(!has_image(&pdf));
+}
+
+#[tokio::test]
+async fn test_late_fee_policy() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.get_late_fee_policy().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let policy: Value = resp.json().await.unwrap();
+    assert!(policy.is_null());
+
+    let resp = client
+        .put_late_fee_policy(serde_json::json!({
+            "fee_type": "percentage",
+            "amount": 1.5,
+            "grace_days": 7,
+            "recurrence": "monthly",
+            "max_applications": 3
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let policy: Value = resp.json().await.unwrap();
+    assert_eq!(policy["enabled"], true);
+    assert_eq!(policy["fee_type"], "percentage");
+    assert_eq!(policy["grace_days"], 7);
+    assert_eq!(policy["recurrence"], "monthly");
+    assert_eq!(policy["max_applications"], 3);
+
+    let resp = client.get_late_fee_policy().await.unwrap();
+    let policy: Value = resp.json().await.unwrap();
+    assert_eq!(policy["fee_type"], "percentage");
+
+    // Percentages above 100 and negative grace periods are rejected
+    for body in [
+        serde_json::json!({ "fee_type": "percentage", "amount": 150 }),
+        serde_json::json!({ "fee_type": "flat", "amount": 25, "grace_days": -1 }),
+        serde_json::json!({ "fee_type": "flat", "amount": 0 }),
+    ] {
+        let resp = client.put_late_fee_policy(body).await.unwrap();
+        assert_eq!(resp.status(), 400);
+    }
+
+    let resp = client.create_client("Late Fee Client", "latefee@test.com").await.unwrap();
+    let client_data: Value = resp.json().await.unwrap();
+    let client_id = client_data["id"].as_str().unwrap().to_string();
+    let resp = client.create_invoice(&client_id, 200.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.get_invoice_late_fees(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let fees: Value = resp.json().await.unwrap();
+    assert_eq!(fees, serde_json::json!([]));
+
+    let resp = client.get_invoice_late_fees(&uuid::Uuid::new_v4().to_string()).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
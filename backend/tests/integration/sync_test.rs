@@ -0,0 +1,222 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("sync_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Offline Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+/// Changes show up in the feed once they are a couple of seconds old
+async fn wait_for_settle() {
+    tokio::time::sleep(Duration::from_secs(3)).await;
+}
+
+fn ids(changes: &Value, key: &str) -> Vec<String> {
+    changes[key]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entity| entity["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_incremental_sync_with_deletions() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Synced Client", "synced@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_client("Short Lived", "short@example.com").await.unwrap();
+    let short_lived: Value = resp.json().await.unwrap();
+    let short_lived_id = short_lived["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    wait_for_settle().await;
+
+    // Full sync: every live entity plus settings
+    let resp = client.get_with_query("/sync", "limit=500").await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let full: Value = resp.json().await.unwrap();
+    assert_eq!(full["has_more"], false);
+    assert!(ids(&full, "invoices").contains(&invoice_id));
+    assert!(ids(&full, "clients").contains(&client_id));
+    assert!(ids(&full, "clients").contains(&short_lived_id));
+    assert_eq!(full["settings"]["company_name"], "Offline Co");
+    assert_eq!(full["deleted"].as_array().unwrap().len(), 0);
+    let cursor = full["cursor"].as_str().unwrap().to_string();
+
+    // Nothing new: same cursor, empty delta
+    let resp = client.get_with_query("/sync", &format!("since={}", cursor)).await.unwrap();
+    let unchanged: Value = resp.json().await.unwrap();
+    assert_eq!(unchanged["cursor"], cursor.as_str());
+    assert!(ids(&unchanged, "invoices").is_empty());
+    assert!(unchanged.get("settings").is_none());
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    client.delete_client(&short_lived_id).await.unwrap();
+    wait_for_settle().await;
+
+    let resp = client.get_with_query("/sync", &format!("since={}", cursor)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let delta: Value = resp.json().await.unwrap();
+    let deleted: Vec<(String, String)> = delta["deleted"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tombstone| {
+            (
+                tombstone["entity_type"].as_str().unwrap().to_string(),
+                tombstone["id"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert!(deleted.contains(&("invoice".to_string(), invoice_id.clone())));
+    assert!(deleted.contains(&("client".to_string(), short_lived_id.clone())));
+    assert!(!ids(&delta, "invoices").contains(&invoice_id));
+    assert!(!ids(&delta, "clients").contains(&client_id));
+    assert_ne!(delta["cursor"], cursor.as_str());
+}
+
+#[tokio::test]
+async fn test_sync_pages_and_rejects_bad_input() {
+    let client = setup_authenticated_client().await;
+
+    for i in 0..3 {
+        client
+            .create_client(&format!("Paged Client {}", i), &format!("paged{}@example.com", i))
+            .await
+            .unwrap();
+    }
+    wait_for_settle().await;
+
+    // Settings plus three clients, two at a time
+    let resp = client.get_with_query("/sync", "limit=2").await.unwrap();
+    let first: Value = resp.json().await.unwrap();
+    assert_eq!(first["has_more"], true);
+    let cursor = first["cursor"].as_str().unwrap().to_string();
+
+    let resp = client.get_with_query("/sync", &format!("since={}&limit=2", cursor)).await.unwrap();
+    let second: Value = resp.json().await.unwrap();
+    assert_eq!(second["has_more"], false);
+    let first_ids = ids(&first, "clients");
+    let second_ids = ids(&second, "clients");
+    assert_eq!(first_ids.len() + second_ids.len(), 3);
+    assert!(second_ids.iter().all(|id| !first_ids.contains(id)));
+
+    let resp = client.get_with_query("/sync", "since=not-a-cursor").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_with_query("/sync", "limit=0").await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.get_with_query("/sync", "wait=120").await.unwrap();
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_apply_offline_edits_with_conflicts() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Server Client", "server@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+    wait_for_settle().await;
+
+    let resp = client.get_with_query("/sync", "").await.unwrap();
+    let full: Value = resp.json().await.unwrap();
+    let synced = full["clients"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|c| c["id"] == client_id.as_str())
+        .unwrap()
+        .clone();
+    let base_version = synced["updated_at"].clone();
+
+    let new_client_id = uuid::Uuid::new_v4().to_string();
+    let resp = client
+        .apply_sync(json!({
+            "mutations": [
+                {
+                    "mutation_id": "edit-1",
+                    "entity_type": "client",
+                    "entity_id": client_id,
+                    "op": "update",
+                    "base_version": base_version,
+                    "data": { "name": "Renamed Offline" }
+                },
+                {
+                    "mutation_id": "create-1",
+                    "entity_type": "client",
+                    "entity_id": new_client_id,
+                    "op": "create",
+                    "data": { "name": "Created Offline", "email": "offline@example.com" }
+                }
+            ]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let result: Value = resp.json().await.unwrap();
+    assert_eq!(result["applied"].as_array().unwrap().len(), 2);
+    assert_eq!(result["conflicts"].as_array().unwrap().len(), 0);
+    assert_eq!(result["applied"][0]["entity"]["name"], "Renamed Offline");
+    assert_eq!(result["applied"][1]["entity"]["id"], new_client_id.as_str());
+
+    // A second device still holding the old version gets a conflict with the server copy
+    let resp = client
+        .apply_sync(json!({
+            "mutations": [{
+                "mutation_id": "stale-1",
+                "entity_type": "client",
+                "entity_id": client_id,
+                "op": "update",
+                "base_version": base_version,
+                "data": { "notes": "From the other phone" }
+            }]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let result: Value = resp.json().await.unwrap();
+    assert_eq!(result["applied"].as_array().unwrap().len(), 0);
+    let conflict = &result["conflicts"][0];
+    assert_eq!(conflict["mutation_id"], "stale-1");
+    assert_eq!(conflict["reason"], "version_mismatch");
+    assert_eq!(conflict["current"]["name"], "Renamed Offline");
+    assert_ne!(conflict["current_version"], base_version);
+
+    // Invalid mutations reject the whole batch
+    let resp = client
+        .apply_sync(json!({
+            "mutations": [{
+                "mutation_id": "bad-1",
+                "entity_type": "invoice",
+                "entity_id": uuid::Uuid::new_v4(),
+                "op": "create",
+                "data": {}
+            }]
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+}
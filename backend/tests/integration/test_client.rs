@@ -50,11 +50,10 @@ impl ApiTestClient {
         response.text().await
     }
 
-    pub async fn ready_check(&self) -> Result<String, reqwest::Error> {
-        let response = self.client.get(&format!("{}/ready", self.base_url))
+    pub async fn ready_check(&self) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.get(&format!("{}/ready", self.base_url))
             .send()
-            .await?;
-        response.text().await
+            .await
     }
 
     // Auth endpoints
@@ -116,6 +115,19 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn create_client_with_request_id(&self, name: &str, email: &str, request_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients", self.base_url))
+            .header("X-Request-Id", request_id)
+            .json(&serde_json::json!({
+                "name": name,
+                "email": email,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn list_clients(&self) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.get(&format!("{}/api/v1/clients", self.base_url));
         if let Some(auth) = self.get_auth_header() {
@@ -160,6 +172,41 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn set_client_parent(&self, client_id: &str, parent_client_id: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/clients/{}/parent", self.base_url, client_id))
+            .json(&serde_json::json!({
+                "parent_client_id": parent_client_id,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_client_statement(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/{}/statement", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_client_account_statement(&self, client_id: &str, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/{}/statement?{}", self.base_url, client_id, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn email_client_statement(&self, client_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/statement/email", self.base_url, client_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn get_client_stats(&self) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.get(&format!("{}/api/v1/clients/stats", self.base_url));
         if let Some(auth) = self.get_auth_header() {
@@ -169,28 +216,42 @@ impl ApiTestClient {
     }
 
     // Invoice endpoints
-    pub async fn create_invoice(&self, client_id: &str, amount: f64) -> Result<reqwest::Response, reqwest::Error> {
+    fn invoice_payload(client_id: &str, amount: f64) -> serde_json::Value {
         let today = chrono::Utc::now().naive_utc().date();
         let due_date = today + chrono::Duration::days(30);
 
+        serde_json::json!({
+            "client_id": client_id,
+            "issue_date": today,
+            "due_date": due_date,
+            "items": [
+                {
+                    "description": "Test Service",
+                    "quantity": 1,
+                    "unit_price": amount,
+                    "tax_rate": 0.0
+                }
+            ],
+            "notes": "Test invoice",
+            "terms": "Payment due in 30 days",
+            "tax_included": false,
+            "send_immediately": false
+        })
+    }
+
+    pub async fn create_invoice(&self, client_id: &str, amount: f64) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.post(&format!("{}/api/v1/invoices", self.base_url))
-            .json(&serde_json::json!({
-                "client_id": client_id,
-                "issue_date": today,
-                "due_date": due_date,
-                "items": [
-                    {
-                        "description": "Test Service",
-                        "quantity": 1,
-                        "unit_price": amount,
-                        "tax_rate": 0.0
-                    }
-                ],
-                "notes": "Test invoice",
-                "terms": "Payment due in 30 days",
-                "tax_included": false,
-                "send_immediately": false
-            }));
+            .json(&Self::invoice_payload(client_id, amount));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_invoice_with_idempotency_key(&self, client_id: &str, amount: f64, key: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices", self.base_url))
+            .header("Idempotency-Key", key)
+            .json(&Self::invoice_payload(client_id, amount));
         if let Some(auth) = self.get_auth_header() {
             request = request.header("Authorization", auth);
         }
@@ -232,6 +293,17 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn consolidate_invoices(&self, invoice_ids: &[&str]) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/consolidate", self.base_url))
+            .json(&serde_json::json!({
+                "invoice_ids": invoice_ids,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn send_invoice(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.post(&format!("{}/api/v1/invoices/{}/send", self.base_url, invoice_id))
             .json(&serde_json::json!({}));
@@ -241,6 +313,38 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn cancel_invoice(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/cancel", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn restore_invoice(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/restore", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_trash(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/trash", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn regenerate_guest_link(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/guest-link", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn get_invoice_pdf(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.get(&format!("{}/api/v1/invoices/{}/pdf", self.base_url, invoice_id));
         if let Some(auth) = self.get_auth_header() {
@@ -249,6 +353,14 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn regenerate_invoice_pdf(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/pdf?regenerate=true", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn send_reminder(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.post(&format!("{}/api/v1/invoices/{}/remind", self.base_url, invoice_id))
             .json(&serde_json::json!({}));
@@ -284,6 +396,20 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn create_payment_with_idempotency_key(&self, invoice_id: &str, amount: f64, key: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/payments", self.base_url))
+            .header("Idempotency-Key", key)
+            .json(&serde_json::json!({
+                "invoice_id": invoice_id,
+                "amount": amount,
+                "payment_method": "stripe",
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn list_payments(&self) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.get(&format!("{}/api/v1/payments", self.base_url));
         if let Some(auth) = self.get_auth_header() {
@@ -445,6 +571,17 @@ impl ApiTestClient {
     }
 
     // Settings endpoints
+    pub async fn set_locale(&self, locale: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/business", self.base_url))
+            .json(&serde_json::json!({
+                "locale": locale,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     pub async fn get_business_settings(&self) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.get(&format!("{}/api/v1/settings/business", self.base_url));
         if let Some(auth) = self.get_auth_header() {
@@ -530,6 +667,166 @@ impl ApiTestClient {
         request.send().await
     }
 
+    pub async fn upload_invoice_logo(&self, file_name: &str, content_type: &str, data: &[u8]) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, content_type, data);
+        let mut request = self.client.post(format!("{}/api/v1/settings/invoice/logo", self.base_url))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn remove_invoice_logo(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/settings/invoice/logo", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_late_fee_policy(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/late-fees", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn put_late_fee_policy(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/late-fees", self.base_url))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_invoice_late_fees(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/late-fees", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_audit_logs(&self, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/audit-logs?{}", self.base_url, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Upload a CSV to `/clients/import`; `query` is e.g. "dry_run=true"
+    pub async fn import_clients_csv(&self, file_name: &str, data: &[u8], query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, "text/csv", data);
+        let mut request = self.client.post(format!("{}/api/v1/clients/import?{}", self.base_url, query))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_client_import_job(&self, id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/import/{}", self.base_url, id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Upload a CSV to `/invoices/import`; `query` is e.g. "dry_run=true"
+    pub async fn import_invoices_csv(&self, file_name: &str, data: &[u8], query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, "text/csv", data);
+        let mut request = self.client.post(format!("{}/api/v1/invoices/import?{}", self.base_url, query))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// `query` carries the list filters and `format`
+    pub async fn export_invoices(&self, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/export?{}", self.base_url, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_webhook_endpoint(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/webhook-endpoints", self.base_url))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_webhook_endpoints(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/webhook-endpoints", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_webhook_endpoint(&self, id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/webhook-endpoints/{}", self.base_url, id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_webhook_endpoint(&self, id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/webhook-endpoints/{}", self.base_url, id))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_webhook_endpoint(&self, id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/webhook-endpoints/{}", self.base_url, id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn rotate_webhook_secret(&self, id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/webhook-endpoints/{}/rotate-secret", self.base_url, id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_webhook_deliveries(&self, id: &str, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/webhook-endpoints/{}/deliveries?{}", self.base_url, id, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn put_invoice_settings(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/invoice", self.base_url))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     // Metrics endpoint
     pub async fn get_metrics(&self) -> Result<reqwest::Response, reqwest::Error> {
         self.client.get(&format!("{}/metrics", self.base_url))
@@ -537,6 +834,15 @@ impl ApiTestClient {
             .await
     }
 
+    // Admin endpoints; they take the metrics token, not a user's
+    pub async fn get_email_queue_dead_letters(&self, scrape_token: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/admin/email-queue/dead-letters", self.base_url));
+        if let Some(token) = scrape_token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await
+    }
+
     // Payment Gateway endpoints
     pub async fn create_stripe_payment_intent(&self, amount: f64, currency: &str) -> Result<reqwest::Response, reqwest::Error> {
         let mut request = self.client.post(&format!("{}/api/v1/payments/stripe/intent", self.base_url))
@@ -620,6 +926,42 @@ impl ApiTestClient {
             .await
     }
 
+    pub async fn get_guest_discussion_unread(&self, token: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.get(format!("{}/api/v1/guest/discussion/{}/unread", self.base_url, token))
+            .send()
+            .await
+    }
+
+    pub async fn mark_guest_discussion_read(&self, token: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.post(format!("{}/api/v1/guest/discussion/{}/read", self.base_url, token))
+            .send()
+            .await
+    }
+
+    pub async fn get_discussion_unread(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/discussion/unread", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn mark_discussion_read(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/discussion/read", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_unread_discussions(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/discussions/unread", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
     // Guest checkout endpoints
     pub async fn get_guest_invoice(&self, token: &str) -> Result<reqwest::Response, reqwest::Error> {
         self.client.get(&format!("{}/api/v1/guest/invoice/{}", self.base_url, token))
@@ -631,12 +973,20 @@ impl ApiTestClient {
         self.client.post(&format!("{}/api/v1/guest/pay/{}", self.base_url, token))
             .json(&serde_json::json!({
                 "amount": amount,
-                "payment_method": "PayPal",
+                "payment_method": "paypal",
+                "customer_name": "Guest Payer",
             }))
             .send()
             .await
     }
 
+    pub async fn process_guest_payment_with(&self, token: &str, payload: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.post(format!("{}/api/v1/guest/pay/{}", self.base_url, token))
+            .json(&payload)
+            .send()
+            .await
+    }
+
     pub async fn get_guest_payment_history(&self, email: &str) -> Result<reqwest::Response, reqwest::Error> {
         self.client.post(&format!("{}/api/v1/guest/history", self.base_url))
             .json(&serde_json::json!({
@@ -659,4 +1009,1332 @@ impl ApiTestClient {
             .send()
             .await
     }
+
+    // Document numbering endpoints
+    pub async fn get_document_number_formats(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/document-numbers", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_document_number_format(&self, doc_type: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/document-numbers/{}", self.base_url, doc_type))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Automation issue endpoints
+    pub async fn list_automation_issues(&self, include_resolved: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/notifications/issues?include_resolved={}", self.base_url, include_resolved));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn resolve_automation_issue(&self, issue_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/notifications/issues/{}/resolve", self.base_url, issue_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_rate_limits(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/rate-limits", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// GET a guest endpoint under an explicit API version, e.g. `("v1", "invoice/<token>")`
+    pub async fn get_guest_versioned(&self, version: &str, path: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.get(format!("{}/api/v1/guest/{}/{}", self.base_url, version, path))
+            .send()
+            .await
+    }
+
+    // Support endpoints
+    pub async fn get_anonymized_invoice(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/support/invoices/{}/anonymized", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_client_statement_opt_out(&self, client_id: &str, opt_out: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/clients/{}", self.base_url, client_id))
+            .json(&serde_json::json!({
+                "statement_opt_out": opt_out,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_client_locale(&self, client_id: &str, locale: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/clients/{}", self.base_url, client_id))
+            .json(&serde_json::json!({
+                "locale": locale,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_client_invoice_defaults(&self, client_id: &str, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/{}/invoice-defaults?{}", self.base_url, client_id, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_tax_setting(&self, label: &str, rate: f64, is_default: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/settings/tax", self.base_url))
+            .json(&serde_json::json!({
+                "label": label,
+                "rate": rate,
+                "is_default": is_default,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn enable_monthly_statements(&self, enabled: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/notifications", self.base_url))
+            .json(&serde_json::json!({
+                "email_payment_received": true,
+                "email_invoice_paid": true,
+                "email_payment_reminder": true,
+                "push_payment_received": true,
+                "push_overdue": true,
+                "email_monthly_statements": enabled,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Budget endpoints
+    pub async fn create_budget(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/budgets", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_budgets(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/budgets", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_budget(&self, budget_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/budgets/{}", self.base_url, budget_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_budget(&self, budget_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/budgets/{}", self.base_url, budget_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_budget_report(&self, budget_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/budgets/{}/report", self.base_url, budget_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_budget_limit(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/budgets/limits", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_budget_limit(&self, limit_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/budgets/limits/{}", self.base_url, limit_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_budget_limit(&self, limit_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/budgets/limits/{}", self.base_url, limit_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_budget_utilization(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/budgets/utilization", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_budget_alerts(&self, unread: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/budgets/alerts?unread={}", self.base_url, unread));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn mark_budget_alert_read(&self, alert_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/budgets/alerts/{}/read", self.base_url, alert_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_invoice_settings_with_late_fee(&self, terms: &str, notes: &str, late_fee_rate: f64) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/invoice", self.base_url))
+            .json(&serde_json::json!({
+                "template": "default",
+                "terms": terms,
+                "notes": notes,
+                "late_fee_rate": late_fee_rate,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_client_with_phone(&self, name: &str, email: &str, phone: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients", self.base_url))
+            .json(&serde_json::json!({
+                "name": name,
+                "email": email,
+                "phone": phone,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_client_phone(&self, client_id: &str, phone: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/clients/{}", self.base_url, client_id))
+            .json(&serde_json::json!({
+                "phone": phone,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_paid_stamp(&self, enabled: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/invoice", self.base_url))
+            .json(&serde_json::json!({
+                "template": "default",
+                "terms": "",
+                "notes": "",
+                "paid_stamp": enabled,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_gateway_payment(&self, invoice_id: &str, amount: f64, gateway: &str, gateway_payment_id: &str, fee: f64) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/payments", self.base_url))
+            .json(&serde_json::json!({
+                "invoice_id": invoice_id,
+                "amount": amount,
+                "payment_method": gateway,
+                "gateway": gateway,
+                "gateway_payment_id": gateway_payment_id,
+                "gateway_fee": fee,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn record_payout(&self, payout: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/payouts", self.base_url))
+            .json(&payout);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_payout(&self, payout_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/payouts/{}", self.base_url, payout_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_payout_report(&self, start_date: &str, end_date: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/payouts/report", self.base_url))
+            .query(&[("start_date", start_date), ("end_date", end_date)]);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn post_stripe_payout_webhook(&self, payload: &str, signature: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/webhooks/stripe/payouts", self.base_url))
+            .header("Content-Type", "application/json")
+            .body(payload.to_string());
+        if let Some(signature) = signature {
+            request = request.header("Stripe-Signature", signature);
+        }
+        request.send().await
+    }
+
+    pub async fn post_stripe_checkout_webhook(&self, payload: &str, signature: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/webhooks/stripe/checkout", self.base_url))
+            .header("Content-Type", "application/json")
+            .body(payload.to_string());
+        if let Some(signature) = signature {
+            request = request.header("Stripe-Signature", signature);
+        }
+        request.send().await
+    }
+
+    /// A copy of this client that sends every request on behalf of the given business
+    pub fn with_business(&self, business_id: &str) -> Self {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-Business-Id", business_id.parse().expect("Invalid business id header"));
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .default_headers(headers)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            base_url: self.base_url.clone(),
+            client,
+            auth_token: self.auth_token.clone(),
+        }
+    }
+
+    // Businesses
+    pub async fn create_business(&self, name: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/businesses", self.base_url))
+            .json(&serde_json::json!({ "name": name }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_businesses(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/businesses", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_business(&self, business_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/businesses/{}", self.base_url, business_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Client archive and trash
+    pub async fn archive_client(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/archive", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn unarchive_client(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/clients/{}/archive", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn restore_client(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/restore", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_deleted_clients(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/trash", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn send_invoice_with(&self, invoice_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/send", self.base_url, invoice_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_invoice_notifications(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/notifications", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn correct_invoice(&self, invoice_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/correct", self.base_url, invoice_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn invite_accountant(&self, email: &str, expires_in_days: Option<i64>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/accountants", self.base_url))
+            .json(&serde_json::json!({
+                "email": email,
+                "name": "Books & Co",
+                "expires_in_days": expires_in_days,
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_accountants(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/accountants", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn revoke_accountant(&self, access_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/accountants/{}", self.base_url, access_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn accountant_sign_in(&self, access_code: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.post(format!("{}/api/v1/accountants/sign-in", self.base_url))
+            .json(&serde_json::json!({ "access_code": access_code }))
+            .send()
+            .await
+    }
+
+    pub async fn preview_campaign(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/campaigns/preview", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_campaign(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/campaigns", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_campaign(&self, campaign_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/campaigns/{}", self.base_url, campaign_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn cancel_campaign(&self, campaign_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/campaigns/{}/cancel", self.base_url, campaign_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_aging_trend(&self, months: Option<u32>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut url = format!("{}/api/v1/reports/aging/trend", self.base_url);
+        if let Some(months) = months {
+            url = format!("{}?months={}", url, months);
+        }
+        let mut request = self.client.get(url);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_cashflow_forecast(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/reports/cashflow-forecast", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_timeseries(&self, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/reports/timeseries?{}", self.base_url, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn add_invoice_cost(&self, invoice_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/costs", self.base_url, invoice_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn remove_invoice_cost(&self, invoice_id: &str, cost_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/invoices/{}/costs/{}", self.base_url, invoice_id, cost_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_invoice_profitability(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/profitability", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_margin_by_client(&self, start_date: &str, end_date: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!(
+            "{}/api/v1/reports/margin-by-client?start_date={}&end_date={}",
+            self.base_url, start_date, end_date
+        ));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_with_query(&self, path: &str, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1{}?{}", self.base_url, path, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn request_portal_link(&self, email: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/api/v1/portal/sign-in/link", self.base_url))
+            .json(&serde_json::json!({ "email": email }))
+            .send()
+            .await
+    }
+
+    pub async fn portal_sign_in_with_link(&self, token: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/api/v1/portal/sign-in/link/verify", self.base_url))
+            .json(&serde_json::json!({ "token": token }))
+            .send()
+            .await
+    }
+
+    pub async fn portal_sign_in(&self, email: &str, password: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/api/v1/portal/sign-in", self.base_url))
+            .json(&serde_json::json!({ "email": email, "password": password }))
+            .send()
+            .await
+    }
+
+    pub async fn set_portal_password(&self, password: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .put(format!("{}/api/v1/portal/password", self.base_url))
+            .json(&serde_json::json!({ "password": password }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// GET a path under /api/v1/portal
+    pub async fn get_portal(&self, path: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/portal{}", self.base_url, path));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn apply_sync(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/sync/apply", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_invoice_settings_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/invoice", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn resend_invoice_notification(&self, invoice_id: &str, notification_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!(
+            "{}/api/v1/invoices/{}/notifications/{}/resend",
+            self.base_url, invoice_id, notification_id
+        ));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_invoice_label(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoice-labels", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_invoice_labels(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoice-labels", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_invoice_label(&self, label_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/invoice-labels/{}", self.base_url, label_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_invoice_label(&self, label_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/invoice-labels/{}", self.base_url, label_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_invoice_label(&self, invoice_id: &str, label_id: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/invoices/{}/label", self.base_url, invoice_id))
+            .json(&serde_json::json!({ "label_id": label_id }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_expense_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/expenses", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_expense_with(&self, expense_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/expenses/{}", self.base_url, expense_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_invoice_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_fx_rate(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/fx/rates", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_fx_rate(&self, rate_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/fx/rates/{}", self.base_url, rate_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_client_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_email_signature(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/email-signature", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Sets the caller's own signature when `member` is true, otherwise the business default
+    pub async fn set_email_signature(&self, member: bool, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let path = if member { "/member" } else { "" };
+        let mut request = self.client
+            .put(format!("{}/api/v1/settings/email-signature{}", self.base_url, path))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_email_signature(&self, member: bool) -> Result<reqwest::Response, reqwest::Error> {
+        let path = if member { "/member" } else { "" };
+        let mut request = self.client.delete(format!("{}/api/v1/settings/email-signature{}", self.base_url, path));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_email_templates(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/email-templates", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_email_template(&self, kind: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .put(format!("{}/api/v1/settings/email-templates/{}", self.base_url, kind))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_email_template(&self, kind: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/settings/email-templates/{}", self.base_url, kind));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn preview_email_template(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .post(format!("{}/api/v1/settings/email-templates/preview", self.base_url))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn custom_report(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/reports/custom", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_with_language(&self, path: &str, accept_language: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .get(format!("{}/api/v1{}", self.base_url, path))
+            .header("Accept-Language", accept_language);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn export_template_bundle(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/template-bundle", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn import_template_bundle(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .post(format!("{}/api/v1/settings/template-bundle", self.base_url))
+            .json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_inbound_address(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/settings/inbound-address", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Posts as the mail provider would; no Authorization header
+    pub async fn deliver_inbound_email(&self, token: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        self.client
+            .post(format!("{}/api/v1/inbound/u/{}", self.base_url, token))
+            .json(&body)
+            .send()
+            .await
+    }
+
+    pub async fn list_client_imports(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/notifications/client-imports", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn confirm_client_import(&self, import_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client
+            .post(format!("{}/api/v1/notifications/client-imports/{}/confirm", self.base_url, import_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_client_with(&self, client_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/clients/{}", self.base_url, client_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_notification_settings_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/notifications", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Credit note endpoints
+    pub async fn create_credit_note_with(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/credit-notes", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_credit_notes(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/credit-notes?invoice_id={}", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn void_credit_note(&self, credit_note_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/credit-notes/{}/void", self.base_url, credit_note_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_credit_note_pdf(&self, credit_note_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/credit-notes/{}/pdf", self.base_url, credit_note_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Deliver a PayPal webhook with the given `PAYPAL-*` transmission headers
+    pub async fn post_paypal_webhook(&self, payload: &str, headers: &[(&str, &str)]) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/webhooks/paypal", self.base_url))
+            .header("Content-Type", "application/json")
+            .body(payload.to_string());
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        request.send().await
+    }
+
+    pub async fn list_bank_transfers(&self, status: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/payments/bank-transfers", self.base_url));
+        if let Some(status) = status {
+            request = request.query(&[("status", status)]);
+        }
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// POST `/payments/bank-transfers/{id}/{action}`, where action is "confirm" or "reject"
+    pub async fn reconcile_bank_transfer(&self, payment_id: &str, action: &str, payload: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/payments/bank-transfers/{}/{}", self.base_url, payment_id, action))
+            .json(&payload);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn upload_bank_statement(&self, file_name: &str, data: &[u8]) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, "text/csv", data);
+        let mut request = self.client.post(format!("{}/api/v1/payments/bank-transfers/statement", self.base_url))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn scan_receipt(&self, file_name: &str, content_type: &str, data: &[u8]) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, content_type, data);
+        let mut request = self.client.post(format!("{}/api/v1/expenses/receipts", self.base_url))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_receipt_scan(&self, scan_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/expenses/receipts/{}", self.base_url, scan_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn confirm_receipt_scan(&self, scan_id: &str, payload: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/expenses/receipts/{}/confirm", self.base_url, scan_id))
+            .json(&payload);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// Upload a file to `/{parent}/{id}/attachments`, where parent is "invoices" or "expenses"
+    pub async fn upload_attachment(&self, parent: &str, id: &str, file_name: &str, content_type: &str, data: &[u8]) -> Result<reqwest::Response, reqwest::Error> {
+        let (multipart_type, body) = multipart_file(file_name, content_type, data);
+        let mut request = self.client.post(format!("{}/api/v1/{}/{}/attachments", self.base_url, parent, id))
+            .header("Content-Type", multipart_type)
+            .body(body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_attachments(&self, parent: &str, id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/{}/{}/attachments", self.base_url, parent, id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn download_attachment(&self, parent: &str, id: &str, attachment_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/{}/{}/attachments/{}", self.base_url, parent, id, attachment_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_attachment(&self, parent: &str, id: &str, attachment_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/{}/{}/attachments/{}", self.base_url, parent, id, attachment_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_guest_attachment(&self, token: &str, attachment_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        self.client.get(format!("{}/api/v1/guest/invoice/{}/attachments/{}", self.base_url, token, attachment_id))
+            .send()
+            .await
+    }
+
+    pub async fn create_time_entry(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/time-entries", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_time_entries(&self, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/time-entries?{}", self.base_url, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn start_timer(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/time-entries/start", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn stop_timer(&self, entry_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/time-entries/{}/stop", self.base_url, entry_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_time_entry(&self, entry_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/time-entries/{}", self.base_url, entry_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_time_entry(&self, entry_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/time-entries/{}", self.base_url, entry_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_invoice_from_time(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/from-time", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_project(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/projects", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_projects(&self, query: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/projects?{}", self.base_url, query));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_project(&self, project_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/projects/{}", self.base_url, project_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_project(&self, project_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/projects/{}", self.base_url, project_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_project_profitability(&self, project_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/projects/{}/profitability", self.base_url, project_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_project_invoice(&self, client_id: &str, amount: f64, project_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut payload = Self::invoice_payload(client_id, amount);
+        payload["project_id"] = serde_json::json!(project_id);
+        let mut request = self.client.post(format!("{}/api/v1/invoices", self.base_url)).json(&payload);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    /// `kind` is `invoices`, `expenses` or `time-entries`
+    pub async fn set_project(&self, kind: &str, id: &str, project_id: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/{}/{}/project", self.base_url, kind, id))
+            .json(&serde_json::json!({ "project_id": project_id }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Invoice templates
+    pub async fn create_invoice_template(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoice-templates", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn list_invoice_templates(&self) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoice-templates", self.base_url));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_invoice_template(&self, template_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/invoice-templates/{}", self.base_url, template_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn delete_invoice_template(&self, template_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.delete(format!("{}/api/v1/invoice-templates/{}", self.base_url, template_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn create_invoice_from_template(&self, template_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoice-templates/{}/invoices", self.base_url, template_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn duplicate_invoice(&self, invoice_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/duplicate", self.base_url, invoice_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn batch_invoices(&self, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/batch", self.base_url)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Client credit
+    pub async fn get_client_credit(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/clients/{}/credit", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn deposit_client_credit(&self, client_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/credit/deposits", self.base_url, client_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn refund_client_credit(&self, client_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/credit/refunds", self.base_url, client_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn apply_client_credit(&self, invoice_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/invoices/{}/apply-credit", self.base_url, invoice_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Invoice deposits
+    pub async fn create_invoice_with_deposit(&self, client_id: &str, amount: f64, deposit: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut payload = Self::invoice_payload(client_id, amount);
+        payload["deposit"] = deposit;
+        let mut request = self.client.post(format!("{}/api/v1/invoices", self.base_url)).json(&payload);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn update_invoice_with(&self, invoice_id: &str, body: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/invoices/{}", self.base_url, invoice_id)).json(&body);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Line taxes
+    pub async fn create_invoice_with_items(&self, client_id: &str, items: serde_json::Value) -> Result<reqwest::Response, reqwest::Error> {
+        let mut payload = Self::invoice_payload(client_id, 0.0);
+        payload["items"] = items;
+        let mut request = self.client.post(format!("{}/api/v1/invoices", self.base_url)).json(&payload);
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // EU VAT
+    pub async fn set_business_country(&self, country: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/business", self.base_url))
+            .json(&serde_json::json!({
+                "address": {
+                    "street": "1 Main Street",
+                    "city": "Berlin",
+                    "state": "",
+                    "zip_code": "10115",
+                    "country": country,
+                },
+            }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn check_client_vat(&self, client_id: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.post(format!("{}/api/v1/clients/{}/vat-check", self.base_url, client_id));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn get_vat_report(&self, start_date: &str, end_date: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/reports/vat?start_date={}&end_date={}", self.base_url, start_date, end_date));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn set_business_vat_number(&self, vat_number: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.put(format!("{}/api/v1/settings/business", self.base_url))
+            .json(&serde_json::json!({ "vat_number": vat_number }));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    pub async fn export_e_invoice(&self, invoice_id: &str, format: &str) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/invoices/{}/export?format={}", self.base_url, invoice_id, format));
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+
+    // Real-time events
+    pub async fn open_event_stream(&self, last_event_id: Option<&str>) -> Result<reqwest::Response, reqwest::Error> {
+        let mut request = self.client.get(format!("{}/api/v1/events/stream", self.base_url));
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("Last-Event-ID", last_event_id);
+        }
+        if let Some(auth) = self.get_auth_header() {
+            request = request.header("Authorization", auth);
+        }
+        request.send().await
+    }
+}
+
+/// A multipart/form-data body with one `file` field, and its Content-Type
+fn multipart_file(file_name: &str, content_type: &str, data: &[u8]) -> (String, Vec<u8>) {
+    let boundary = "flashbill-test-boundary";
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        boundary, file_name, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
 }
@@ -0,0 +1,181 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::{json, Value};
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("time_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Time Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn create_client_id(client: &ApiTestClient, name: &str) -> String {
+    let resp = client.create_client(name, &format!("{}@test.com", name.to_lowercase().replace(' ', "."))).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    data["id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_time_entry_tracking() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Time Client").await;
+
+    let resp = client
+        .create_time_entry(json!({
+            "client_id": client_id,
+            "description": "  Kickoff call ",
+            "started_at": "2025-03-03T09:00:00Z",
+            "duration_minutes": 45,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let manual: Value = resp.json().await.unwrap();
+    let manual_id = manual["id"].as_str().unwrap().to_string();
+    assert_eq!(manual["description"], "Kickoff call");
+    assert_eq!(manual["ended_at"], "2025-03-03T09:45:00Z");
+    assert_eq!(manual["duration_minutes"], 45);
+    assert_eq!(manual["billable"], true);
+
+    // A manual entry needs an end, and a client of the user's
+    let resp = client
+        .create_time_entry(json!({ "client_id": client_id, "description": "Open", "started_at": "2025-03-03T09:00:00Z" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .create_time_entry(json!({
+            "client_id": uuid::Uuid::new_v4(),
+            "description": "Elsewhere",
+            "started_at": "2025-03-03T09:00:00Z",
+            "duration_minutes": 30,
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    // Starting a timer stops the one already running
+    let resp = client.start_timer(json!({ "client_id": client_id, "description": "Wireframes" })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let first: Value = resp.json().await.unwrap();
+    let first_id = first["id"].as_str().unwrap().to_string();
+    assert!(first["ended_at"].is_null());
+
+    let resp = client.start_timer(json!({ "client_id": client_id, "description": "Copy edits" })).await.unwrap();
+    assert_eq!(resp.status(), 201);
+    let second: Value = resp.json().await.unwrap();
+    let second_id = second["id"].as_str().unwrap().to_string();
+
+    let resp = client.stop_timer(&first_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client.stop_timer(&second_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let stopped: Value = resp.json().await.unwrap();
+    assert!(!stopped["ended_at"].is_null());
+
+    let resp = client
+        .update_time_entry(&manual_id, json!({ "duration_minutes": 60, "billable": false }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["ended_at"], "2025-03-03T10:00:00Z");
+    assert_eq!(updated["billable"], false);
+
+    let resp = client.list_time_entries(&format!("client_id={}&billed=false", client_id)).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let page: Value = resp.json().await.unwrap();
+    assert_eq!(page["total"], 3);
+    // Latest start first
+    assert_eq!(page["items"][0]["id"], second_id);
+    assert_eq!(page["items"][2]["id"], manual_id);
+
+    let resp = client.list_time_entries("from=2025-03-03&to=2025-03-03").await.unwrap();
+    let page: Value = resp.json().await.unwrap();
+    assert_eq!(page["total"], 1);
+
+    let resp = client.delete_time_entry(&manual_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.delete_time_entry(&manual_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_invoice_from_time_bills_unbilled_entries() {
+    let client = setup_authenticated_client().await;
+    let client_id = create_client_id(&client, "Hourly Client").await;
+
+    for (description, started_at, minutes, rate, billable) in [
+        ("Design", "2025-03-03T09:00:00Z", 90, None, true),
+        ("Workshop", "2025-03-04T13:00:00Z", 30, Some(150.0), true),
+        ("Internal notes", "2025-03-04T15:00:00Z", 20, None, false),
+    ] {
+        let resp = client
+            .create_time_entry(json!({
+                "client_id": client_id,
+                "description": description,
+                "started_at": started_at,
+                "duration_minutes": minutes,
+                "hourly_rate": rate,
+                "billable": billable,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 201);
+    }
+
+    // Without a client rate the entry at no rate can't be priced
+    let resp = client.create_invoice_from_time(json!({ "client_id": client_id })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client.update_client_with(&client_id, json!({ "hourly_rate": 100 })).await.unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let resp = client
+        .create_invoice_from_time(json!({ "client_id": client_id, "issue_date": "2025-03-31" }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let created: Value = resp.json().await.unwrap();
+    let invoice_id = created["id"].as_str().unwrap().to_string();
+    assert_eq!(created["status"], "draft");
+    // 1.5h at the client's 100 and 0.5h at the entry's 150
+    assert_eq!(created["subtotal"], 225.0);
+
+    let resp = client.get_invoice(&invoice_id).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let items = invoice["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["description"], "Design (2025-03-03)");
+    assert_eq!(items[0]["quantity"], 1.5);
+    assert_eq!(items[1]["unit_price"], 150.0);
+
+    let resp = client.list_time_entries("billed=true").await.unwrap();
+    let page: Value = resp.json().await.unwrap();
+    assert_eq!(page["total"], 2);
+    let billed_id = page["items"][0]["id"].as_str().unwrap().to_string();
+    assert_eq!(page["items"][0]["invoice_id"], invoice_id);
+
+    // Billed time is not billed twice, nor changed
+    let resp = client.create_invoice_from_time(json!({ "client_id": client_id })).await.unwrap();
+    assert_eq!(resp.status(), 400);
+    let resp = client
+        .create_invoice_from_time(json!({ "client_id": client_id, "entry_ids": [billed_id] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client.update_time_entry(&billed_id, json!({ "description": "Changed" })).await.unwrap();
+    assert_eq!(resp.status(), 409);
+    let resp = client.delete_time_entry(&billed_id).await.unwrap();
+    assert_eq!(resp.status(), 409);
+}
@@ -0,0 +1,111 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("trash_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Trash Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+async fn trash(client: &ApiTestClient) -> Value {
+    let resp = client.list_trash().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    resp.json().await.unwrap()
+}
+
+async fn listed_invoice_ids(client: &ApiTestClient) -> Vec<String> {
+    let resp = client.list_invoices().await.unwrap();
+    let page: Value = resp.json().await.unwrap();
+    page["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|invoice| invoice["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_deleted_draft_goes_to_trash_and_restores() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Trash Client", "trash@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 250.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    let resp = client.delete_invoice(&invoice_id).await.unwrap();
+    assert!(resp.status().is_success());
+
+    // Gone from lookups and the list, but in the trash
+    assert_eq!(client.get_invoice(&invoice_id).await.unwrap().status(), 404);
+    assert!(!listed_invoice_ids(&client).await.contains(&invoice_id));
+    let listed = trash(&client).await;
+    assert_eq!(listed["retention_days"], 30);
+    assert_eq!(listed["invoices"][0]["id"], invoice_id.as_str());
+    assert_eq!(listed["invoices"][0]["client_name"], "Trash Client");
+    assert!(listed["invoices"][0]["purge_at"].is_string());
+
+    // Deleting it again finds nothing
+    assert_eq!(client.delete_invoice(&invoice_id).await.unwrap().status(), 404);
+
+    let resp = client.restore_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let restored: Value = resp.json().await.unwrap();
+    assert_eq!(restored["status"], "draft");
+    assert!(listed_invoice_ids(&client).await.contains(&invoice_id));
+    assert_eq!(trash(&client).await["invoices"].as_array().unwrap().len(), 0);
+
+    // Only trashed invoices can be restored
+    assert_eq!(client.restore_invoice(&invoice_id).await.unwrap().status(), 404);
+
+    // Another account can't see or restore it
+    client.delete_invoice(&invoice_id).await.unwrap();
+    let other = setup_authenticated_client().await;
+    assert_eq!(trash(&other).await["invoices"].as_array().unwrap().len(), 0);
+    assert_eq!(other.restore_invoice(&invoice_id).await.unwrap().status(), 404);
+}
+
+#[tokio::test]
+async fn test_trashed_client_is_restored_before_its_drafts() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client.create_client("Paused Client", "paused@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_invoice(&client_id, 80.0).await.unwrap();
+    let invoice: Value = resp.json().await.unwrap();
+    let invoice_id = invoice["id"].as_str().unwrap().to_string();
+
+    client.delete_invoice(&invoice_id).await.unwrap();
+    let resp = client.delete_client(&client_id).await.unwrap();
+    assert!(resp.status().is_success());
+
+    let listed = trash(&client).await;
+    assert_eq!(listed["clients"][0]["id"], client_id.as_str());
+    assert!(listed["clients"][0]["purge_at"].is_string());
+
+    let resp = client.restore_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 400);
+
+    assert_eq!(client.restore_client(&client_id).await.unwrap().status(), 200);
+    let resp = client.restore_invoice(&invoice_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let restored: Value = resp.json().await.unwrap();
+    assert_eq!(restored["status"], "draft");
+}
@@ -0,0 +1,125 @@
+use crate::integration::{test_client::ApiTestClient, utils::get_api_base_url};
+use serde_json::Value;
+
+async fn setup_authenticated_client() -> ApiTestClient {
+    let base_url = get_api_base_url();
+    let client = ApiTestClient::new(base_url);
+
+    let unique_id = crate::integration::utils::get_unique_id();
+    let email = format!("webhook_test_{}@example.com", unique_id);
+    let password = "testpassword123";
+
+    client.register(&email, password, Some("Webhook Co")).await.unwrap();
+    let resp = client.login(&email, password).await.unwrap();
+    let data: Value = resp.json().await.unwrap();
+    let token = data["access_token"].as_str().unwrap().to_string();
+
+    let mut authed_client = client.clone();
+    authed_client.set_token(token);
+    authed_client
+}
+
+#[tokio::test]
+async fn test_webhook_endpoint_lifecycle() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_webhook_endpoint(serde_json::json!({
+            "url": "https://hooks.example.com/flashbill",
+            "events": ["invoice.paid", "client.created"],
+            "description": "Bookkeeping sync"
+        }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 201);
+    let endpoint: Value = resp.json().await.unwrap();
+    let endpoint_id = endpoint["id"].as_str().unwrap().to_string();
+    let secret = endpoint["secret"].as_str().unwrap().to_string();
+    assert!(secret.starts_with("whsec_"));
+    assert_eq!(endpoint["enabled"], true);
+
+    // The secret is only shown on create and rotate
+    let resp = client.list_webhook_endpoints().await.unwrap();
+    let endpoints: Vec<Value> = resp.json().await.unwrap();
+    assert_eq!(endpoints.len(), 1);
+    assert!(endpoints[0].get("secret").is_none());
+
+    let resp = client.rotate_webhook_secret(&endpoint_id).await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let rotated: Value = resp.json().await.unwrap();
+    assert_ne!(rotated["secret"].as_str().unwrap(), secret);
+
+    let resp = client
+        .update_webhook_endpoint(&endpoint_id, serde_json::json!({ "enabled": false }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    let updated: Value = resp.json().await.unwrap();
+    assert_eq!(updated["enabled"], false);
+    assert_eq!(updated["description"], "Bookkeeping sync");
+
+    let resp = client.delete_webhook_endpoint(&endpoint_id).await.unwrap();
+    assert_eq!(resp.status(), 204);
+    let resp = client.get_webhook_endpoint(&endpoint_id).await.unwrap();
+    assert_eq!(resp.status(), 404);
+}
+
+#[tokio::test]
+async fn test_webhook_endpoint_validation() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_webhook_endpoint(serde_json::json!({ "url": "https://hooks.example.com/flashbill", "events": [] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .create_webhook_endpoint(serde_json::json!({ "url": "not a url", "events": ["invoice.paid"] }))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 400);
+
+    let resp = client
+        .create_webhook_endpoint(serde_json::json!({ "url": "https://hooks.example.com/flashbill", "events": ["invoice.deleted"] }))
+        .await
+        .unwrap();
+    assert!(resp.status().is_client_error());
+}
+
+#[tokio::test]
+async fn test_subscribed_events_are_logged_as_deliveries() {
+    let client = setup_authenticated_client().await;
+
+    let resp = client
+        .create_webhook_endpoint(serde_json::json!({
+            "url": "https://hooks.example.com/flashbill",
+            "events": ["client.created"]
+        }))
+        .await
+        .unwrap();
+    let endpoint: Value = resp.json().await.unwrap();
+    let endpoint_id = endpoint["id"].as_str().unwrap().to_string();
+
+    let resp = client.create_client("Webhook Client", "webhook@example.com").await.unwrap();
+    let customer: Value = resp.json().await.unwrap();
+    let client_id = customer["id"].as_str().unwrap().to_string();
+
+    // Not subscribed
+    client.create_invoice(&client_id, 100.0).await.unwrap();
+
+    // The dispatcher picks new events up every couple of seconds
+    let mut deliveries = Vec::new();
+    for _ in 0..20 {
+        let resp = client.list_webhook_deliveries(&endpoint_id, "").await.unwrap();
+        assert_eq!(resp.status(), 200);
+        deliveries = resp.json::<Vec<Value>>().await.unwrap();
+        if !deliveries.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!(deliveries[0]["event_type"], "client.created");
+}
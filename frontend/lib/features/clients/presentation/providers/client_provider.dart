@@ -113,7 +113,7 @@ class ClientNotifier extends StateNotifier<ClientState> {
 
     try {
       final response = await _apiClient.getClients(search: search);
-      final List<Client> clients = (response.data as List)
+      final List<Client> clients = (response.data['items'] as List)
           .map((e) => Client.fromJson(e))
           .toList();
 
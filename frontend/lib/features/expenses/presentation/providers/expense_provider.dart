@@ -112,7 +112,7 @@ class ExpenseNotifier extends StateNotifier<ExpenseState> {
 
     try {
       final response = await _apiClient.getExpenses(category: category, search: search);
-      final List<Expense> expenses = (response.data as List)
+      final List<Expense> expenses = (response.data['items'] as List)
           .map((e) => Expense.fromJson(e))
           .toList();
 
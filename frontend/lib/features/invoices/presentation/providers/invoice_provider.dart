@@ -277,7 +277,7 @@ class InvoiceNotifier extends StateNotifier<InvoiceState> {
 
     try {
       final response = await _apiClient.getInvoices(status: status, search: search);
-      final List<dynamic> data = response.data;
+      final List<dynamic> data = response.data['items'];
 
       final invoices = data.map((json) => Invoice.fromJson(json)).toList();
 
@@ -107,7 +107,7 @@ class PaymentNotifier extends StateNotifier<PaymentState> {
 
     try {
       final response = await _apiClient.getPayments(status: status, paymentMethod: paymentMethod);
-      final List<Payment> payments = (response.data as List)
+      final List<Payment> payments = (response.data['items'] as List)
           .map((e) => Payment.fromJson(e))
           .toList();
 